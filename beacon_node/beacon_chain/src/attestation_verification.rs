@@ -366,6 +366,18 @@ pub enum AttestationSlashInfo<'a, T: BeaconChainTypes, TErr> {
     SignatureValid(IndexedAttestation<T::EthSpec>, TErr),
 }
 
+impl<'a, T: BeaconChainTypes, TErr> AttestationSlashInfo<'a, T, TErr> {
+    /// Returns the error, without supplying the attestation to the slasher.
+    fn into_error(self) -> TErr {
+        match self {
+            AttestationSlashInfo::SignatureNotChecked(_, e)
+            | AttestationSlashInfo::SignatureNotCheckedIndexed(_, e)
+            | AttestationSlashInfo::SignatureInvalid(e)
+            | AttestationSlashInfo::SignatureValid(_, e) => e,
+        }
+    }
+}
+
 /// After processing an attestation normally, optionally process it further for the slasher.
 ///
 /// This maps an `AttestationSlashInfo` error back into a regular `Error`, performing signature
@@ -415,12 +427,7 @@ fn process_slash_info<T: BeaconChainTypes>(
 
         err
     } else {
-        match slash_info {
-            SignatureNotChecked(_, e)
-            | SignatureNotCheckedIndexed(_, e)
-            | SignatureInvalid(e)
-            | SignatureValid(_, e) => e,
-        }
+        slash_info.into_error()
     }
 }
 
@@ -478,6 +485,15 @@ impl<'a, T: BeaconChainTypes> IndexedAggregatedAttestation<'a, T> {
             return Err(Error::AttestationSupersetKnown(attestation_data_root));
         }
 
+        // The op pool merges the aggregates it receives, so it may hold a superset of this
+        // aggregate even if none of the aggregates observed above are. Every attestation in the
+        // op pool has already been verified, so this aggregate adds nothing and its signature
+        // need not be checked.
+        if chain.op_pool.contains_attestation_superset(attestation) {
+            metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_OP_POOL_SUBSETS);
+            return Err(Error::AttestationSupersetKnown(attestation_data_root));
+        }
+
         let aggregator_index = signed_aggregate.message.aggregator_index;
 
         // Ensure there has been no other observed aggregate for the given `aggregator_index`.
@@ -1074,6 +1090,37 @@ pub fn verify_propagation_slot_range<S: SlotClock, E: EthSpec>(
     Ok(())
 }
 
+/// Runs the checks of `VerifiedUnaggregatedAttestation::verify` without any side-effects: the
+/// attester is not observed and nothing is supplied to the slasher.
+///
+/// A genuine copy of an `attestation` verified this way is still processed normally.
+pub fn verify_unaggregated_attestation_dry_run<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    attestation: &Attestation<T::EthSpec>,
+    subnet_id: Option<SubnetId>,
+) -> Result<(), Error> {
+    let indexed = IndexedUnaggregatedAttestation::verify_slashable(attestation, subnet_id, chain)
+        .map_err(AttestationSlashInfo::into_error)?;
+    verify_attestation_signature(chain, &indexed.indexed_attestation)
+}
+
+/// Runs the checks of `VerifiedAggregatedAttestation::verify` without any side-effects: the
+/// aggregate and its aggregator are not observed and nothing is supplied to the slasher.
+///
+/// A genuine copy of a `signed_aggregate` verified this way is still processed normally.
+pub fn verify_aggregated_attestation_dry_run<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    signed_aggregate: &SignedAggregateAndProof<T::EthSpec>,
+) -> Result<(), Error> {
+    let indexed = IndexedAggregatedAttestation::verify_slashable(signed_aggregate, chain)
+        .map_err(AttestationSlashInfo::into_error)?;
+    if verify_signed_aggregate_signatures(chain, signed_aggregate, &indexed.indexed_attestation)? {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
 /// Verifies that the signature of the `indexed_attestation` is valid.
 pub fn verify_attestation_signature<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
@@ -1,5 +1,5 @@
 use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
-use eth2::lighthouse::StandardBlockReward;
+use eth2::types::StandardBlockReward;
 use operation_pool::RewardCache;
 use safe_arith::SafeArith;
 use slog::error;
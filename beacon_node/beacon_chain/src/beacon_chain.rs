@@ -18,19 +18,23 @@ use crate::block_verification::{
 use crate::block_verification_types::{
     AsBlock, AvailableExecutedBlock, BlockImportData, ExecutedBlock, RpcBlock,
 };
+use crate::builder_circuit_breaker;
 pub use crate::canonical_head::{CanonicalHead, CanonicalHeadRwLock};
 use crate::chain_config::ChainConfig;
 use crate::data_availability_checker::{
     Availability, AvailabilityCheckError, AvailableBlock, DataAvailabilityChecker,
 };
 use crate::early_attester_cache::EarlyAttesterCache;
+use crate::epoch_timings::EpochTimingsCache;
 use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::eth1_finalization_cache::{Eth1FinalizationCache, Eth1FinalizationData};
 use crate::events::ServerSentEventHandler;
 use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
+use crate::fetch_blobs;
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::head_tracker::HeadTracker;
+use crate::head_vote_accuracy::HeadVoteAccuracyTracker;
 use crate::historical_blocks::HistoricalBlockError;
 use crate::light_client_finality_update_verification::{
     Error as LightClientFinalityUpdateError, VerifiedLightClientFinalityUpdate,
@@ -57,9 +61,11 @@ use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{BlockProductionPreState, SnapshotCache};
+use crate::sync_committee_pubkey_cache::{SyncCommitteePubkeyCache, SyncCommitteePubkeys};
 use crate::sync_committee_verification::{
     Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
 };
+use crate::sync_duties_cache::{PeriodDuties, SyncDutiesCache};
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_monitor::{
     get_slot_delay_ms, timestamp_now, ValidatorMonitor,
@@ -70,22 +76,30 @@ use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
-use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes, SyncDuty};
+use eth2::lighthouse::{
+    BlockProductionDryRun, BlockProductionTimings, ProposerReOrgDecision,
+    SyncCommitteeParticipation,
+};
+use eth2::types::{
+    EventKind, SseBlobSidecar, SseBlock, SseExecutionOptimisticStatus,
+    SseExtendedPayloadAttributes, SyncDuty,
+};
 use execution_layer::{
-    BlockProposalContents, BuilderParams, ChainHealth, ExecutionLayer, FailedCondition,
-    PayloadAttributes, PayloadStatus,
+    BlockProposalContents, BuilderParams, ChainHealth, ExecutionLayer, PayloadAttributes,
+    PayloadStatus,
 };
 use fork_choice::{
-    AttestationFromBlock, ExecutionStatus, ForkChoice, ForkchoiceUpdateParameters,
+    AttestationFromBlock, ExecutionStatusChange, ForkChoice, ForkchoiceUpdateParameters,
     InvalidationOperation, PayloadVerificationStatus, ResetPayloadStatuses,
 };
 use futures::channel::mpsc::Sender;
+use graffiti_template::{expand_graffiti, GraffitiVariables};
 use itertools::process_results;
 use itertools::Itertools;
 use kzg::Kzg;
 use operation_pool::{AttestationRef, OperationPool, PersistedOperationPool, ReceivedPreCapella};
 use parking_lot::{Mutex, RwLock};
-use proto_array::{DoNotReOrg, ProposerHeadError};
+use proto_array::{DoNotReOrg, ProposerHeadError, ReOrgThreshold};
 use safe_arith::SafeArith;
 use slasher::Slasher;
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -156,6 +170,10 @@ const EARLY_ATTESTER_CACHE_HISTORIC_SLOTS: u64 = 4;
 /// If the head block is older than this value, don't bother preparing beacon proposers.
 const PREPARE_PROPOSER_HISTORIC_EPOCHS: u64 = 4;
 
+/// The maximum number of times proposer preparation is restarted because the head changed whilst
+/// the payload attributes were being computed.
+const PREPARE_PROPOSER_MAX_ATTEMPTS: usize = 3;
+
 /// If the head is more than `MAX_PER_SLOT_FORK_CHOICE_DISTANCE` slots behind the wall-clock slot, DO NOT
 /// run the per-slot tasks (primarily fork choice).
 ///
@@ -226,6 +244,10 @@ pub enum ChainSegmentResult<T: EthSpec> {
 }
 
 /// Configure the signature verification of produced blocks.
+///
+/// Blocks produced with `NoVerification` can't be a real proposal, so the local payload of such a
+/// blinded block is not kept in the execution layer's payload cache.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ProduceBlockVerification {
     VerifyRandao,
     NoVerification,
@@ -244,6 +266,14 @@ pub struct PrePayloadAttributes {
     pub parent_beacon_block_root: Hash256,
 }
 
+/// The result of a single attempt at preparing a beacon proposer.
+enum ProposerPreparation {
+    /// Preparation finished, or was not required.
+    Complete,
+    /// The head changed after the payload attributes were computed.
+    HeadChanged,
+}
+
 /// Information about a state/block at a specific slot.
 #[derive(Debug, Clone, Copy)]
 pub struct FinalizationAndCanonicity {
@@ -456,12 +486,22 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub beacon_proposer_cache: Mutex<BeaconProposerCache>,
     /// Caches a map of `validator_index -> validator_pubkey`.
     pub(crate) validator_pubkey_cache: TimeoutRwLock<ValidatorPubkeyCache<T>>,
+    /// Caches the decompressed pubkeys of the current and next sync committees.
+    pub(crate) sync_committee_pubkey_cache: SyncCommitteePubkeyCache,
+    /// Caches the sync committee duties of each member of recent sync committees.
+    pub sync_duties_cache: SyncDutiesCache,
     /// A cache used when producing attestations.
     pub(crate) attester_cache: Arc<AttesterCache>,
     /// A cache used when producing attestations whilst the head block is still being imported.
     pub early_attester_cache: EarlyAttesterCache<T::EthSpec>,
     /// A cache used to keep track of various block timings.
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
+    /// A cache of block arrival and attestation inclusion statistics for recent epochs.
+    pub epoch_timings_cache: EpochTimingsCache,
+    /// Tracks how many observed attestations voted for the block that became canonical.
+    pub head_vote_accuracy: HeadVoteAccuracyTracker,
+    /// The inputs and outcome of the most recent proposer re-org decision.
+    pub last_re_org_decision: Mutex<Option<ProposerReOrgDecision>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
@@ -469,8 +509,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub shutdown_sender: Sender<ShutdownReason>,
     /// Logging to CLI, etc.
     pub(crate) log: Logger,
-    /// Arbitrary bytes included in the blocks.
-    pub(crate) graffiti: Graffiti,
+    /// Arbitrary bytes included in blocks when the validator doesn't supply any graffiti.
+    ///
+    /// May be updated at runtime via `Self::set_graffiti`.
+    pub(crate) graffiti: RwLock<Graffiti>,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
     /// Provides monitoring of a set of explicitly defined validators.
@@ -551,6 +593,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Persists fork choice, the op pool, the data availability checker and the eth1 cache.
+    ///
+    /// This is done when the chain is dropped, and may be done earlier during shutdown so that
+    /// it is not delayed by the teardown of other services.
+    pub fn persist_to_disk(&self) -> Result<(), Error> {
+        self.persist_head_and_fork_choice()?;
+        self.persist_op_pool()?;
+        self.persist_data_availability_checker()?;
+        self.persist_eth1_cache()
+    }
+
     /// Persists the head tracker and fork choice.
     ///
     /// We do it atomically even though no guarantees need to be made about blocks from
@@ -655,6 +708,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Returns the graffiti used for blocks produced without graffiti from the validator.
+    pub fn graffiti(&self) -> Graffiti {
+        *self.graffiti.read()
+    }
+
+    /// Updates the graffiti used for blocks produced without graffiti from the validator.
+    ///
+    /// The new graffiti applies to the next block produced, it is not persisted across restarts.
+    pub fn set_graffiti(&self, graffiti: Graffiti) {
+        *self.graffiti.write() = graffiti;
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -953,7 +1018,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// ## Errors
     ///
     /// May return a database error.
-    fn block_root_at_slot_skips_none(&self, request_slot: Slot) -> Result<Option<Hash256>, Error> {
+    pub(crate) fn block_root_at_slot_skips_none(
+        &self,
+        request_slot: Slot,
+    ) -> Result<Option<Hash256>, Error> {
         if request_slot == self.spec.genesis_slot {
             return Ok(Some(self.genesis_block_root));
         } else if request_slot > self.slot()? {
@@ -1189,6 +1257,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns the index of each blob of the block at `block_root` and `slot` which has been
+    /// observed on gossip, mapped to the time it was first seen in milliseconds since the Unix
+    /// epoch.
+    ///
+    /// Observations are pruned on finalization.
+    pub fn blob_first_seen_times(&self, block_root: Hash256, slot: Slot) -> HashMap<u64, u64> {
+        self.observed_blob_sidecars
+            .read()
+            .first_seen_times(block_root, slot)
+    }
+
     pub fn get_blinded_block(
         &self,
         block_root: &Hash256,
@@ -1209,6 +1288,57 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(self.store.get_state(state_root, slot)?)
     }
 
+    /// Returns the state with root `base_state_root` advanced through skipped slots to `slot`,
+    /// along with the root of the advanced state.
+    ///
+    /// Advanced states are cached in the store so that repeated requests for the same skipped
+    /// slot do not repeat the per-slot processing. Returns `None` if the base state is unknown.
+    ///
+    /// ## Errors
+    ///
+    /// May return a database error, or an error if the base state is later than `slot`.
+    pub fn get_skip_slot_state(
+        &self,
+        base_state_root: Hash256,
+        slot: Slot,
+    ) -> Result<Option<(Hash256, BeaconState<T::EthSpec>)>, Error> {
+        if let Some(cached) = self.store.get_cached_skip_slot_state(base_state_root, slot) {
+            return Ok(Some(cached));
+        }
+
+        let head_snapshot = self.head_snapshot();
+        let mut state = if head_snapshot.beacon_state_root() == base_state_root {
+            head_snapshot
+                .beacon_state
+                .clone_with(CloneConfig::committee_caches_only())
+        } else {
+            drop(head_snapshot);
+            match self.get_state(&base_state_root, None)? {
+                Some(state) => state,
+                None => return Ok(None),
+            }
+        };
+
+        if state.slot() > slot {
+            return Err(Error::CannotAdvanceStateBackwards {
+                state_slot: state.slot(),
+                requested_slot: slot,
+            });
+        }
+
+        let mut state_root_opt = Some(base_state_root);
+        while state.slot() < slot {
+            per_slot_processing(&mut state, state_root_opt.take(), &self.spec)?;
+            metrics::inc_counter(&metrics::SKIP_SLOT_STATE_SLOTS_PROCESSED);
+        }
+
+        let state_root = state.update_tree_hash_cache()?;
+        self.store
+            .cache_skip_slot_state(base_state_root, slot, state_root, &state);
+
+        Ok(Some((state_root, state)))
+    }
+
     /// Return the sync committee at `slot + 1` from the canonical chain.
     ///
     /// This is useful when dealing with sync committee messages, because messages are signed
@@ -1253,6 +1383,38 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Return the decompressed pubkeys of the sync committee at `slot + 1` from the canonical
+    /// chain.
+    ///
+    /// See `Self::sync_committee_at_next_slot`.
+    pub fn sync_committee_pubkeys_at_next_slot(
+        &self,
+        slot: Slot,
+    ) -> Result<Arc<SyncCommitteePubkeys>, Error> {
+        let epoch = slot.safe_add(1)?.epoch(T::EthSpec::slots_per_epoch());
+        self.sync_committee_pubkeys_at_epoch(epoch)
+    }
+
+    /// Return the decompressed pubkeys of the sync committee at `epoch` from the canonical chain.
+    ///
+    /// The pubkeys are decompressed once per sync committee period and cached thereafter.
+    pub fn sync_committee_pubkeys_at_epoch(
+        &self,
+        epoch: Epoch,
+    ) -> Result<Arc<SyncCommitteePubkeys>, Error> {
+        let period = epoch.sync_committee_period(&self.spec)?;
+        if let Some(pubkeys) = self.sync_committee_pubkey_cache.get(period) {
+            return Ok(pubkeys);
+        }
+
+        let committee = self.sync_committee_at_epoch(epoch)?;
+        Ok(self.sync_committee_pubkey_cache.get_or_insert_with(
+            period,
+            &committee,
+            |pubkey_bytes| pubkey_bytes.decompress().ok(),
+        ))
+    }
+
     /// Load a state suitable for determining the sync committee for the given period.
     ///
     /// Specifically, the state at the start of the *previous* sync committee period.
@@ -1509,49 +1671,6 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
-    /// Returns the attestation duties for the given validator indices using the shuffling cache.
-    ///
-    /// An error may be returned if `head_block_root` is a finalized block, this function is only
-    /// designed for operations at the head of the chain.
-    ///
-    /// The returned `Vec` will have the same length as `validator_indices`, any
-    /// non-existing/inactive validators will have `None` values.
-    ///
-    /// ## Notes
-    ///
-    /// This function will try to use the shuffling cache to return the value. If the value is not
-    /// in the shuffling cache, it will be added. Care should be taken not to wash out the
-    /// shuffling cache with historical/useless values.
-    pub fn validator_attestation_duties(
-        &self,
-        validator_indices: &[u64],
-        epoch: Epoch,
-        head_block_root: Hash256,
-    ) -> Result<(Vec<Option<AttestationDuty>>, Hash256, ExecutionStatus), Error> {
-        let execution_status = self
-            .canonical_head
-            .fork_choice_read_lock()
-            .get_block_execution_status(&head_block_root)
-            .ok_or(Error::AttestationHeadNotInForkChoice(head_block_root))?;
-
-        let (duties, dependent_root) = self.with_committee_cache(
-            head_block_root,
-            epoch,
-            |committee_cache, dependent_root| {
-                let duties = validator_indices
-                    .iter()
-                    .map(|validator_index| {
-                        let validator_index = *validator_index as usize;
-                        committee_cache.get_attestation_duties(validator_index)
-                    })
-                    .collect();
-
-                Ok((duties, dependent_root))
-            },
-        )?;
-        Ok((duties, dependent_root, execution_status))
-    }
-
     /// Returns an aggregated `Attestation`, if any, that has a matching `attestation.data`.
     ///
     /// The attestation will be obtained from `self.naive_aggregation_pool`.
@@ -2053,14 +2172,72 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_ATTESTATION_TIMES);
 
+        let indexed_attestation = verified.indexed_attestation();
         self.canonical_head
             .fork_choice_write_lock()
             .on_attestation(
                 self.slot()?,
-                verified.indexed_attestation(),
+                indexed_attestation,
                 AttestationFromBlock::False,
-            )
-            .map_err(Into::into)
+            )?;
+
+        self.head_vote_accuracy.register_attestation::<T::EthSpec>(
+            &indexed_attestation.data,
+            indexed_attestation.attesting_indices.len() as u64,
+        );
+        Ok(())
+    }
+
+    /// Evaluates the head votes of sufficiently old epochs against the canonical chain.
+    ///
+    /// See `HeadVoteAccuracyTracker::update`.
+    pub fn update_head_vote_accuracy(&self) -> Result<(), Error> {
+        let current_epoch = self.epoch()?;
+        let head = self.canonical_head.cached_head();
+
+        self.head_vote_accuracy.update::<T::EthSpec>(
+            current_epoch,
+            |slot| Self::canonical_block_root_at_slot(&head, slot),
+            &self.log,
+        );
+        Ok(())
+    }
+
+    /// Discards sync committee participation from previous periods and updates the participation
+    /// metrics from the canonical chain.
+    ///
+    /// See `ValidatorMonitor::update_sync_committee_participation`.
+    pub fn update_sync_committee_participation(&self) -> Result<(), Error> {
+        let current_period = self.epoch()?.sync_committee_period(&self.spec)?;
+        let head = self.canonical_head.cached_head();
+
+        self.validator_monitor
+            .read()
+            .update_sync_committee_participation(current_period, |slot| {
+                Self::canonical_block_root_at_slot(&head, slot)
+            });
+        Ok(())
+    }
+
+    /// Returns the sync committee participation in the canonical blocks of the current sync
+    /// committee period.
+    pub fn sync_committee_participation(&self) -> SyncCommitteeParticipation {
+        let head = self.canonical_head.cached_head();
+
+        self.validator_monitor
+            .read()
+            .sync_committee_participation(|slot| Self::canonical_block_root_at_slot(&head, slot))
+    }
+
+    /// Returns the root of the block at or before `slot` on the chain of `head`, or `None` if it
+    /// is no longer available from the head state.
+    fn canonical_block_root_at_slot(head: &CachedHead<T::EthSpec>, slot: Slot) -> Option<Hash256> {
+        let head_state = &head.snapshot.beacon_state;
+        if slot >= head_state.slot() {
+            Some(head.head_block_root())
+        } else {
+            head_state.get_block_root(slot).ok().copied()
+        }
     }
 
     /// Accepts an `VerifiedUnaggregatedAttestation` and attempts to apply it to the "naive
@@ -2490,17 +2667,118 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
-    /// Attempt to obtain sync committee duties from the head.
+    /// Attempt to obtain sync committee duties from the sync duties cache, or otherwise from the
+    /// head.
     pub fn sync_committee_duties_from_head(
         &self,
         epoch: Epoch,
         validator_indices: &[u64],
     ) -> Result<Vec<Option<SyncDuty>>, Error> {
-        self.with_head(move |head| {
-            head.beacon_state
-                .get_sync_committee_duties(epoch, validator_indices, &self.spec)
-                .map_err(Error::SyncDutiesError)
-        })
+        let duties = self.with_head(|head| self.cached_sync_duties(epoch, &head.beacon_state))?;
+        self.sync_duties_for_validators(&duties, validator_indices)
+    }
+
+    /// Obtain sync committee duties from `state`, which must know the sync committee of `epoch`.
+    ///
+    /// The duties of the whole committee are added to the sync duties cache.
+    pub fn sync_committee_duties_from_state(
+        &self,
+        epoch: Epoch,
+        validator_indices: &[u64],
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Vec<Option<SyncDuty>>, Error> {
+        let duties = self.cached_sync_duties(epoch, state)?;
+        self.sync_duties_for_validators(&duties, validator_indices)
+    }
+
+    /// Returns the duties of the sync committee of `epoch` on the chain of `state` from the sync
+    /// duties cache, building them from `state` and adding them to the cache on a miss.
+    fn cached_sync_duties(
+        &self,
+        epoch: Epoch,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Arc<PeriodDuties>, Error> {
+        let sync_committee = state
+            .get_built_sync_committee(epoch, &self.spec)
+            .map_err(Error::SyncDutiesError)?;
+        let period = epoch.sync_committee_period(&self.spec)?;
+        let dependent_root = self.sync_committee_dependent_root(period, state)?;
+        if let Some(duties) = self.sync_duties_cache.get(period, dependent_root) {
+            return Ok(duties);
+        }
+
+        let current_period = self.epoch()?.sync_committee_period(&self.spec)?;
+        let pubkey_cache = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or(Error::ValidatorPubkeyCacheLockTimeout)?;
+
+        Ok(self.sync_duties_cache.insert(
+            period,
+            dependent_root,
+            current_period,
+            sync_committee,
+            |pubkey| pubkey_cache.get_index(pubkey),
+        ))
+    }
+
+    /// Returns the root of the block which determines the sync committee of `period` on the chain
+    /// of `state`.
+    ///
+    /// The committee of a period is computed when entering the preceding period, so it depends on
+    /// the block at the last slot before that period, or on the genesis block for the first two
+    /// periods.
+    fn sync_committee_dependent_root(
+        &self,
+        period: u64,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Hash256, Error> {
+        let decision_epoch = self
+            .spec
+            .epochs_per_sync_committee_period
+            .safe_mul(period.saturating_sub(1))?;
+        let decision_slot = decision_epoch.start_slot(T::EthSpec::slots_per_epoch());
+        if decision_slot == self.spec.genesis_slot {
+            return Ok(self.genesis_block_root);
+        }
+
+        let dependent_slot = decision_slot - 1;
+        match state.get_block_root(dependent_slot) {
+            Ok(root) => Ok(*root),
+            // The state's block roots no longer reach back to the dependent slot, which is then at
+            // least a whole period behind the state and on the canonical chain.
+            Err(BeaconStateError::SlotOutOfBounds) => self
+                .block_root_at_slot(dependent_slot, WhenSlotSkipped::Prev)?
+                .ok_or(Error::InvalidSlot(dependent_slot)),
+            Err(e) => Err(Error::SyncDutiesError(e)),
+        }
+    }
+
+    /// Selects the duties of `validator_indices` from the duties of a sync committee.
+    ///
+    /// Returns an error if any of the validators is unknown.
+    fn sync_duties_for_validators(
+        &self,
+        duties: &PeriodDuties,
+        validator_indices: &[u64],
+    ) -> Result<Vec<Option<SyncDuty>>, Error> {
+        let num_validators = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or(Error::ValidatorPubkeyCacheLockTimeout)?
+            .len();
+
+        validator_indices
+            .iter()
+            .map(|&validator_index| {
+                if validator_index as usize >= num_validators {
+                    return Err(Error::SyncDutiesError(BeaconStateError::UnknownValidator(
+                        validator_index as usize,
+                    )));
+                }
+                Ok(duties.get(&validator_index).cloned())
+            })
+            .collect()
     }
 
     /// A convenience method for spawning a blocking task. It maps an `Option` and
@@ -2928,6 +3206,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Increment the Prometheus counter for block processing requests.
         metrics::inc_counter(&metrics::BLOCK_PROCESSING_REQUESTS);
 
+        // Allow the import to complete if a shutdown is requested part way through.
+        let _in_flight = self.task_executor.graceful_shutdown().track();
+
         let block_slot = unverified_block.block().slot();
 
         // A small closure to group the verification and import errors.
@@ -3070,12 +3351,71 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: AvailabilityPendingExecutedBlock<T::EthSpec>,
     ) -> Result<AvailabilityProcessingStatus, BlockError<T::EthSpec>> {
         let slot = block.block.slot();
+        let signed_block = block.block.clone();
         let availability = self
             .data_availability_checker
             .put_pending_executed_block(block)?;
+
+        // If some blobs are still missing, try to fetch them from the execution layer's mempool
+        // rather than waiting for them to arrive via gossip or RPC.
+        if let Availability::MissingComponents(block_root) = availability {
+            self.spawn_fetch_blobs_from_execution_layer(block_root, signed_block);
+        }
+
         self.process_availability(slot, availability).await
     }
 
+    /// Spawns a task which fetches the missing blobs of `block` from the execution layer and adds
+    /// them to the data availability checker, importing the block if it becomes available.
+    ///
+    /// The fetch runs alongside gossip and RPC, so the block is never held up waiting on the
+    /// execution layer.
+    fn spawn_fetch_blobs_from_execution_layer(
+        self: &Arc<Self>,
+        block_root: Hash256,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    ) {
+        let chain = self.clone();
+        self.task_executor.spawn(
+            async move {
+                let slot = block.slot();
+                let availability =
+                    match fetch_blobs::fetch_blobs_from_execution_layer(&chain, block_root, block)
+                        .await
+                    {
+                        Ok(Some(availability)) => availability,
+                        Ok(None) => return,
+                        Err(e) => {
+                            warn!(
+                                chain.log,
+                                "Failed to fetch blobs from execution layer";
+                                "error" => ?e,
+                                "block_root" => ?block_root,
+                                "slot" => slot,
+                            );
+                            return;
+                        }
+                    };
+                debug!(
+                    chain.log,
+                    "Fetched blobs from execution layer";
+                    "block_root" => ?block_root,
+                    "slot" => slot,
+                );
+                if let Err(e) = chain.process_availability(slot, availability).await {
+                    warn!(
+                        chain.log,
+                        "Failed to import block with blobs from execution layer";
+                        "error" => ?e,
+                        "block_root" => ?block_root,
+                        "slot" => slot,
+                    );
+                }
+            },
+            "fetch_blobs_from_el",
+        );
+    }
+
     /// Checks if the provided blob can make any cached blocks available, and imports immediately
     /// if so, otherwise caches the blob in the data availability checker.
     async fn check_gossip_blob_availability_and_import(
@@ -3165,6 +3505,24 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "payload_verification_handle",
             )
             .await??;
+
+        // If the block revealed a new unrealized justified checkpoint, load its balances in the
+        // background so that fork choice doesn't need to read a state when it becomes justified.
+        // Even checking the checkpoint requires the fork choice lock, so it's left to the task.
+        let chain = self.clone();
+        self.task_executor.spawn_blocking(
+            move || {
+                if let Err(e) = chain.prime_justified_balances() {
+                    warn!(
+                        chain.log,
+                        "Failed to cache justified balances";
+                        "error" => ?e
+                    );
+                }
+            },
+            "prime_justified_balances",
+        );
+
         Ok(AvailabilityProcessingStatus::Imported(block_root))
     }
 
@@ -3328,6 +3686,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         );
         self.import_block_update_validator_monitor(
             block,
+            block_root,
             &state,
             &mut consensus_context,
             current_slot,
@@ -3402,10 +3761,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
         drop(txn_lock);
 
+        // Importing a valid block may have validated its optimistic ancestors.
+        let execution_status_changes = fork_choice.take_execution_status_changes();
+
         // The fork choice write-lock is dropped *after* the on-disk database has been updated.
         // This prevents inconsistency between the two at the expense of concurrency.
         drop(fork_choice);
 
+        self.register_execution_status_changes(execution_status_changes);
+
         // We're declaring the block "imported" at this point, since fork choice and the DB know
         // about it.
         let block_time_imported = timestamp_now();
@@ -3542,6 +3906,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     fn import_block_update_validator_monitor(
         &self,
         block: BeaconBlockRef<T::EthSpec>,
+        block_root: Hash256,
         state: &BeaconState<T::EthSpec>,
         ctxt: &mut ConsensusContext<T::EthSpec>,
         current_slot: Slot,
@@ -3582,6 +3947,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         block.parent_root(),
                         participant_pubkeys,
                     );
+
+                    match duty_epoch.sync_committee_period(&self.spec) {
+                        Ok(period) => validator_monitor.register_sync_aggregate_participation(
+                            block.slot(),
+                            block_root,
+                            period,
+                            &sync_committee,
+                            sync_aggregate,
+                        ),
+                        Err(e) => {
+                            warn!(
+                                self.log,
+                                "Unable to compute sync committee period";
+                                "epoch" => duty_epoch,
+                                "purpose" => "validator monitor",
+                                "error" => ?e,
+                            );
+                        }
+                    }
                 }
                 Err(e) => {
                     warn!(
@@ -3724,6 +4108,40 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Registers the gossip arrival delay of `block`, the inclusion distances of its attestations
+    /// and its number of blobs with the `epoch_timings_cache`.
+    fn import_block_update_epoch_timings(
+        &self,
+        block: BeaconBlockRef<T::EthSpec>,
+        block_root: Hash256,
+        current_slot: Slot,
+    ) {
+        let arrival_delay = self
+            .slot_clock
+            .start_of(block.slot())
+            .and_then(|slot_start| {
+                self.block_times_cache
+                    .read()
+                    .get_block_delays(block_root, slot_start)
+                    .observed
+            });
+
+        self.epoch_timings_cache.register_block::<T::EthSpec>(
+            block.slot(),
+            current_slot,
+            arrival_delay,
+            block
+                .body()
+                .attestations()
+                .iter()
+                .map(|attestation| attestation.data.slot),
+            block
+                .body()
+                .blob_kzg_commitments()
+                .map_or(0, |commitments| commitments.len()),
+        );
+    }
+
     fn import_block_update_metrics_and_events(
         &self,
         block: BeaconBlockRef<T::EthSpec>,
@@ -3749,6 +4167,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        self.import_block_update_epoch_timings(block, block_root, current_slot);
+
         let block_delay_total =
             get_slot_delay_ms(block_time_imported, block.slot(), &self.slot_clock);
 
@@ -4085,6 +4505,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok((state, state_root_opt))
     }
 
+    /// Load a copy of the state to produce a dry-run block upon at `slot`.
+    ///
+    /// Unlike `load_state_for_block_production`, this never considers re-orging the head, doesn't
+    /// read from the snapshot cache and records no block production metrics. The head state is
+    /// cloned instead, so loading it has no effect on a real proposal at `slot`.
+    fn load_state_for_block_production_dry_run(
+        &self,
+        slot: Slot,
+    ) -> Result<(BeaconState<T::EthSpec>, Option<Hash256>), BlockProductionError> {
+        let head = self.head_snapshot();
+        if head.beacon_block.slot() < slot {
+            Ok((
+                head.beacon_state.clone_with(CloneConfig::all()),
+                Some(head.beacon_block.state_root()),
+            ))
+        } else {
+            let state = self
+                .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
+                .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
+            Ok((state, None))
+        }
+    }
+
     /// Fetch the beacon state to use for producing a block if a 1-slot proposer re-org is viable.
     ///
     /// This function will return `None` if proposer re-orgs are disabled.
@@ -4105,16 +4548,69 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return None;
         }
 
+        let mut decision = ProposerReOrgDecision {
+            slot,
+            head_root: canonical_head,
+            parent_root: None,
+            head_weight: None,
+            parent_weight: None,
+            re_org_weight_threshold: None,
+            re_org_margin_weight: None,
+            participation_percent: None,
+            min_participation_percent: self.config.re_org_participation_threshold,
+            attempted: false,
+            reason: None,
+        };
+        let result = self.get_state_for_re_org_or_failure_reason(
+            slot,
+            head_slot,
+            canonical_head,
+            re_org_threshold,
+            &mut decision,
+        );
+
+        decision.attempted = result.is_ok();
+        decision.reason = result.as_ref().err().cloned();
+        debug!(
+            self.log,
+            "Proposer re-org decision";
+            "slot" => slot,
+            "head" => ?canonical_head,
+            "parent" => ?decision.parent_root,
+            "head_weight" => ?decision.head_weight,
+            "parent_weight" => ?decision.parent_weight,
+            "threshold_weight" => ?decision.re_org_weight_threshold,
+            "margin_weight" => ?decision.re_org_margin_weight,
+            "participation" => ?decision.participation_percent,
+            "min_participation" => decision.min_participation_percent,
+            "reason" => decision.reason.as_deref().unwrap_or("none"),
+        );
+        *self.last_re_org_decision.lock() = Some(decision);
+
+        result.ok()
+    }
+
+    /// Perform the checks for `get_state_for_re_org`, recording their inputs in `decision`.
+    ///
+    /// Returns the reason for not re-orging as a string if any check fails.
+    fn get_state_for_re_org_or_failure_reason(
+        &self,
+        slot: Slot,
+        head_slot: Slot,
+        canonical_head: Hash256,
+        re_org_threshold: ReOrgThreshold,
+        decision: &mut ProposerReOrgDecision,
+    ) -> Result<BlockProductionPreState<T::EthSpec>, String> {
         let slot_delay = self
             .slot_clock
             .seconds_from_current_slot_start()
-            .or_else(|| {
+            .ok_or_else(|| {
                 warn!(
                     self.log,
                     "Not attempting re-org";
                     "error" => "unable to read slot clock"
                 );
-                None
+                "unable to read slot clock".to_string()
             })?;
 
         // Attempt a proposer re-org if:
@@ -4122,24 +4618,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // 1. It seems we have time to propagate and still receive the proposer boost.
         // 2. The current head block was seen late.
         // 3. The `get_proposer_head` conditions from fork choice pass.
+        // 4. Participation in the previous epoch was high enough.
         let proposing_on_time = slot_delay < self.config.re_org_cutoff(self.spec.seconds_per_slot);
         if !proposing_on_time {
-            debug!(
-                self.log,
-                "Not attempting re-org";
-                "reason" => "not proposing on time",
-            );
-            return None;
+            return Err("not proposing on time".to_string());
         }
 
         let head_late = self.block_observed_after_attestation_deadline(canonical_head, head_slot);
         if !head_late {
-            debug!(
-                self.log,
-                "Not attempting re-org";
-                "reason" => "head not late"
-            );
-            return None;
+            return Err("head not late".to_string());
         }
 
         // Is the current head weak and appropriate for re-orging?
@@ -4152,29 +4639,36 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 slot,
                 canonical_head,
                 re_org_threshold,
+                self.config.re_org_margin,
                 &self.config.re_org_disallowed_offsets,
                 self.config.re_org_max_epochs_since_finalization,
             )
             .map_err(|e| match e {
-                ProposerHeadError::DoNotReOrg(reason) => {
-                    debug!(
-                        self.log,
-                        "Not attempting re-org";
-                        "reason" => %reason,
-                    );
-                }
+                ProposerHeadError::DoNotReOrg(reason) => reason.to_string(),
                 ProposerHeadError::Error(e) => {
                     warn!(
                         self.log,
                         "Not attempting re-org";
                         "error" => ?e,
                     );
+                    format!("fork choice error: {e:?}")
                 }
-            })
-            .ok()?;
+            })?;
         drop(proposer_head_timer);
         let re_org_parent_block = proposer_head.parent_node.root;
 
+        decision.parent_root = Some(re_org_parent_block);
+        decision.head_weight = Some(proposer_head.head_node.weight);
+        decision.parent_weight = Some(proposer_head.parent_node.weight);
+        decision.re_org_weight_threshold = Some(proposer_head.re_org_weight_threshold);
+        decision.re_org_margin_weight = Some(proposer_head.re_org_margin_weight);
+
+        // Only re-org if enough of the network was online during the previous epoch. Otherwise
+        // the re-org block may fail to gather enough attestations to become canonical.
+        decision.participation_percent = self
+            .check_re_org_participation(canonical_head)
+            .map_err(|reason| reason.to_string())?;
+
         // Only attempt a re-org if we hit the snapshot cache.
         let pre_state = self
             .snapshot_cache
@@ -4182,15 +4676,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .and_then(|snapshot_cache| {
                 snapshot_cache.get_state_for_block_production(re_org_parent_block)
             })
-            .or_else(|| {
-                debug!(
-                    self.log,
-                    "Not attempting re-org";
-                    "reason" => "missed snapshot cache",
-                    "parent_block" => ?re_org_parent_block,
-                );
-                None
-            })?;
+            .ok_or_else(|| "missed snapshot cache".to_string())?;
 
         info!(
             self.log,
@@ -4201,7 +4687,48 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "threshold_weight" => proposer_head.re_org_weight_threshold
         );
 
-        Some(pre_state)
+        Ok(pre_state)
+    }
+
+    /// Check that participation during the epoch prior to the head's epoch meets the configured
+    /// minimum for proposer re-orgs.
+    ///
+    /// Returns the participation percentage, or `None` if the check is disabled or the head is in
+    /// the genesis epoch (which has no prior epoch).
+    fn check_re_org_participation(
+        &self,
+        head_block_root: Hash256,
+    ) -> Result<Option<u64>, DoNotReOrg> {
+        let min_participation_percent = self.config.re_org_participation_threshold;
+        if min_participation_percent == 0 {
+            return Ok(None);
+        }
+
+        let cached_head = self.canonical_head.cached_head();
+        if cached_head.head_block_root() != head_block_root {
+            return Err(DoNotReOrg::ParticipationUnknown);
+        }
+        let participation_percent = previous_epoch_target_participation(
+            &cached_head.snapshot.beacon_state,
+        )
+        .map_err(|e| {
+            debug!(
+                self.log,
+                "Unable to compute re-org participation";
+                "error" => ?e,
+            );
+            DoNotReOrg::ParticipationUnknown
+        })?;
+
+        match participation_percent {
+            Some(participation_percent) if participation_percent < min_participation_percent => {
+                Err(DoNotReOrg::ParticipationTooLow {
+                    participation_percent,
+                    min_participation_percent,
+                })
+            }
+            _ => Ok(participation_percent),
+        }
     }
 
     /// Get the proposer index and `prev_randao` value for a proposal at slot `proposal_slot`.
@@ -4316,12 +4843,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }))
     }
 
+    /// Compute the withdrawals for a block at `proposal_slot` built upon
+    /// `forkchoice_update_params.head_root`.
+    ///
+    /// `cached_head` should be the same head that `forkchoice_update_params` were read from.
     pub fn get_expected_withdrawals(
         &self,
+        cached_head: &CachedHead<T::EthSpec>,
         forkchoice_update_params: &ForkchoiceUpdateParameters,
         proposal_slot: Slot,
     ) -> Result<Withdrawals<T::EthSpec>, Error> {
-        let cached_head = self.canonical_head.cached_head();
         let head_state = &cached_head.snapshot.beacon_state;
 
         let parent_block_root = forkchoice_update_params.head_root;
@@ -4432,6 +4963,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .get_preliminary_proposer_head(
                 head_block_root,
                 re_org_threshold,
+                self.config.re_org_margin,
                 &self.config.re_org_disallowed_offsets,
                 self.config.re_org_max_epochs_since_finalization,
             )
@@ -4500,17 +5032,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // If the current slot is already equal to the proposal slot (or we are in the tail end of
         // the prior slot), then check the actual weight of the head against the re-org threshold.
-        let head_weak = if fork_choice_slot == re_org_block_slot {
-            info.head_node.weight < info.re_org_weight_threshold
-        } else {
-            true
-        };
-        if !head_weak {
-            return Err(DoNotReOrg::HeadNotWeak {
-                head_weight: info.head_node.weight,
-                re_org_weight_threshold: info.re_org_weight_threshold,
-            }
-            .into());
+        if fork_choice_slot == re_org_block_slot {
+            info.check_head_weak()?;
         }
 
         // Check that the head block arrived late and is vulnerable to a re-org. This check is only
@@ -4524,6 +5047,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Err(DoNotReOrg::HeadNotLate.into());
         }
 
+        // Apply the same participation gate as `get_state_for_re_org`, so that we don't suppress
+        // the update for a head that we will not re-org.
+        self.check_re_org_participation(head_block_root)?;
+
         let parent_head_hash = info.parent_node.execution_status.block_hash();
         let forkchoice_update_params = ForkchoiceUpdateParameters {
             head_root: info.parent_node.root,
@@ -4577,6 +5104,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         validator_graffiti: Option<Graffiti>,
         verification: ProduceBlockVerification,
     ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+        let cache_local_payload = verification == ProduceBlockVerification::VerifyRandao;
+
         // Part 1/3 (blocking)
         //
         // Perform the state advance and block-packing functions.
@@ -4591,6 +5120,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         produce_at_slot,
                         randao_reveal,
                         validator_graffiti,
+                        cache_local_payload,
+                        false,
                     )
                 },
                 "produce_partial_beacon_block",
@@ -4625,6 +5156,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         partial_beacon_block,
                         block_contents,
                         verification,
+                        false,
                     )
                 },
                 "complete_partial_beacon_block",
@@ -4634,6 +5166,137 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(BlockProductionError::TokioJoin)?
     }
 
+    /// Run the block production pipeline for `slot` without returning a block to sign, timing
+    /// each of its stages.
+    ///
+    /// The block is produced with an empty RANDAO reveal which is not verified, so a dry run
+    /// doesn't require the keys of the proposer. A dry run doesn't affect a later proposal at the
+    /// same slot: the pre-state is a clone of the head state which is discarded afterwards, no
+    /// proposer re-org is considered, the payload and its ID aren't cached by the execution layer
+    /// and no block production metrics are recorded.
+    pub async fn produce_block_dry_run<Payload: AbstractExecPayload<T::EthSpec> + 'static>(
+        self: &Arc<Self>,
+        slot: Slot,
+    ) -> Result<BlockProductionDryRun, BlockProductionError> {
+        let total_timer = Instant::now();
+
+        let chain = self.clone();
+        let state_load_timer = Instant::now();
+        let (mut state, state_root_opt) = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || chain.load_state_for_block_production_dry_run(slot),
+                "produce_block_dry_run_load_state",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+        let state_load_time = state_load_timer.elapsed();
+
+        // Advance the state here so that `produce_partial_beacon_block` only packs the block,
+        // allowing the two to be timed separately.
+        let chain = self.clone();
+        let state_advance_timer = Instant::now();
+        let state = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    complete_state_advance(&mut state, state_root_opt, slot, &chain.spec)
+                        .map(|()| state)
+                },
+                "produce_block_dry_run_state_advance",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+        let state_advance_time = state_advance_timer.elapsed();
+
+        let chain = self.clone();
+        let block_packing_timer = Instant::now();
+        let mut partial_beacon_block = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    chain.produce_partial_beacon_block::<Payload>(
+                        state,
+                        None,
+                        slot,
+                        Signature::empty(),
+                        None,
+                        false,
+                        true,
+                    )
+                },
+                "produce_block_dry_run_partial_beacon_block",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+        let block_packing_time = block_packing_timer.elapsed();
+
+        let payload_wait_timer = Instant::now();
+        let prepare_payload_handle = partial_beacon_block.prepare_payload_handle.take();
+        let block_contents = if let Some(prepare_payload_handle) = prepare_payload_handle {
+            Some(
+                prepare_payload_handle
+                    .await
+                    .map_err(BlockProductionError::TokioJoin)?
+                    .ok_or(BlockProductionError::ShuttingDown)??,
+            )
+        } else {
+            None
+        };
+        let payload_wait_time = payload_wait_timer.elapsed();
+        let payload_value = block_contents
+            .as_ref()
+            .map_or_else(Uint256::zero, |contents| *contents.block_value());
+
+        let chain = self.clone();
+        let state_root_timer = Instant::now();
+        let (block, _, _) = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    chain.complete_partial_beacon_block(
+                        partial_beacon_block,
+                        block_contents,
+                        ProduceBlockVerification::NoVerification,
+                        true,
+                    )
+                },
+                "produce_block_dry_run_complete_beacon_block",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+        let state_root_time = state_root_timer.elapsed();
+
+        Ok(BlockProductionDryRun {
+            slot: block.slot(),
+            proposer_index: block.proposer_index(),
+            parent_root: block.parent_root(),
+            state_root: block.state_root(),
+            attestations: block.body().attestations().len() as u64,
+            payload_value,
+            blobs: block
+                .body()
+                .blob_kzg_commitments()
+                .map_or(0, |commitments| commitments.len() as u64),
+            timings: BlockProductionTimings {
+                state_load_ms: state_load_time.as_millis() as u64,
+                state_advance_ms: state_advance_time.as_millis() as u64,
+                block_packing_ms: block_packing_time.as_millis() as u64,
+                payload_wait_ms: payload_wait_time.as_millis() as u64,
+                state_root_ms: state_root_time.as_millis() as u64,
+                total_ms: total_timer.elapsed().as_millis() as u64,
+            },
+        })
+    }
+
+    /// Advance `state` to `produce_at_slot` and pack a block upon it.
+    ///
+    /// Block production metrics are not recorded for a `dry_run`.
+    #[allow(clippy::too_many_arguments)]
     fn produce_partial_beacon_block<Payload: AbstractExecPayload<T::EthSpec> + 'static>(
         self: &Arc<Self>,
         mut state: BeaconState<T::EthSpec>,
@@ -4641,7 +5304,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         produce_at_slot: Slot,
         randao_reveal: Signature,
         validator_graffiti: Option<Graffiti>,
+        cache_local_payload: bool,
+        dry_run: bool,
     ) -> Result<PartialBeaconBlock<T::EthSpec, Payload>, BlockProductionError> {
+        let start_timer = |histogram: &metrics::Result<metrics::Histogram>| {
+            (!dry_run)
+                .then(|| metrics::start_timer(histogram))
+                .flatten()
+        };
+
         let eth1_chain = self
             .eth1_chain
             .as_ref()
@@ -4655,7 +5326,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
-        let slot_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
+        let slot_timer = start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
 
         // Ensure the state has performed a complete transition into the required slot.
         complete_state_advance(&mut state, state_root_opt, produce_at_slot, &self.spec)?;
@@ -4701,6 +5372,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     parent_root,
                     proposer_index,
                     builder_params,
+                    cache_local_payload,
                 )?;
                 Some(prepare_payload_handle)
             }
@@ -4718,8 +5390,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // Iterate through the naive aggregation pool and ensure all the attestations from there
         // are included in the operation pool.
-        let unagg_import_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_UNAGGREGATED_TIMES);
+        let unagg_import_timer = start_timer(&metrics::BLOCK_PRODUCTION_UNAGGREGATED_TIMES);
         for attestation in self.naive_aggregation_pool.read().iter() {
             let import = |attestation: &Attestation<T::EthSpec>| {
                 let attesting_indices = get_attesting_indices_from_state(&state, attestation)?;
@@ -4738,13 +5409,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         drop(unagg_import_timer);
 
         // Override the beacon node's graffiti with graffiti from the validator, if present.
+        //
+        // Graffiti from the validator is used verbatim, any template it contained has already
+        // been expanded by the validator client.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
-            None => self.graffiti,
+            None => expand_graffiti(
+                self.graffiti(),
+                &GraffitiVariables {
+                    slot: produce_at_slot,
+                    epoch: produce_at_slot.epoch(T::EthSpec::slots_per_epoch()),
+                    validator_index: None,
+                },
+            ),
         };
 
-        let attestation_packing_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES);
+        let attestation_packing_timer = start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES);
 
         let mut prev_filter_cache = HashMap::new();
         let prev_attestation_filter = |att: &AttestationRef<T::EthSpec>| {
@@ -4883,7 +5563,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         partial_beacon_block: PartialBeaconBlock<T::EthSpec, Payload>,
         block_contents: Option<BlockProposalContents<T::EthSpec, Payload>>,
         verification: ProduceBlockVerification,
+        dry_run: bool,
     ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+        let start_timer = |histogram: &metrics::Result<metrics::Histogram>| {
+            (!dry_run)
+                .then(|| metrics::start_timer(histogram))
+                .flatten()
+        };
+
         let PartialBeaconBlock {
             mut state,
             slot,
@@ -5060,13 +5747,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "slot" => block.slot(),
         );
 
-        metrics::observe(&metrics::BLOCK_SIZE, block_size as f64);
+        if !dry_run {
+            metrics::observe(&metrics::BLOCK_SIZE, block_size as f64);
+        }
 
         if block_size > self.config.max_network_size {
             return Err(BlockProductionError::BlockTooLarge(block_size));
         }
 
-        let process_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
+        let process_timer = start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
         let signature_strategy = match verification {
             ProduceBlockVerification::VerifyRandao => BlockSignatureStrategy::VerifyRandao,
             ProduceBlockVerification::NoVerification => BlockSignatureStrategy::NoVerification,
@@ -5086,7 +5775,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         )?;
         drop(process_timer);
 
-        let state_root_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
+        let state_root_timer = start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
         let state_root = state.update_tree_hash_cache()?;
         drop(state_root_timer);
 
@@ -5094,7 +5783,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         *block.state_root_mut() = state_root;
 
         let blobs_verification_timer =
-            metrics::start_timer(&metrics::BLOCK_PRODUCTION_BLOBS_VERIFICATION_TIMES);
+            start_timer(&metrics::BLOCK_PRODUCTION_BLOBS_VERIFICATION_TIMES);
         let maybe_sidecar_list = match (blobs_opt, proofs_opt) {
             (Some(blobs_or_blobs_roots), Some(proofs)) => {
                 let expected_kzg_commitments =
@@ -5144,7 +5833,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         drop(blobs_verification_timer);
 
-        metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
+        if !dry_run {
+            metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
+        }
 
         trace!(
             self.log,
@@ -5157,6 +5848,39 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok((block, state, maybe_sidecar_list))
     }
 
+    /// Emits an `execution_optimistic_status` event for each of the `changes` taken from fork
+    /// choice with `take_execution_status_changes`.
+    fn register_execution_status_changes(
+        &self,
+        (changes, dropped): (Vec<ExecutionStatusChange>, usize),
+    ) {
+        if dropped > 0 {
+            debug!(
+                self.log,
+                "Execution status changes not reported";
+                "reported" => changes.len(),
+                "dropped" => dropped,
+            );
+        }
+
+        if let Some(event_handler) = self
+            .event_handler
+            .as_ref()
+            .filter(|handler| handler.has_execution_optimistic_status_subscribers())
+        {
+            for change in changes {
+                event_handler.register(EventKind::ExecutionOptimisticStatus(
+                    SseExecutionOptimisticStatus {
+                        block_root: change.block_root,
+                        slot: change.slot,
+                        old_status: change.old_status.into(),
+                        new_status: change.new_status.into(),
+                    },
+                ));
+            }
+        }
+    }
+
     /// This method must be called whenever an execution engine indicates that a payload is
     /// invalid.
     ///
@@ -5180,17 +5904,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // on the core executor is bad.
         let chain = self.clone();
         let inner_op = op.clone();
-        let fork_choice_result = self
+        let (fork_choice_result, execution_status_changes) = self
             .spawn_blocking_handle(
                 move || {
-                    chain
-                        .canonical_head
-                        .fork_choice_write_lock()
-                        .on_invalid_execution_payload(&inner_op)
+                    let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+                    let result = fork_choice.on_invalid_execution_payload(&inner_op);
+                    (result, fork_choice.take_execution_status_changes())
                 },
                 "invalid_payload_fork_choice_update",
             )
             .await?;
+        self.register_execution_status_changes(execution_status_changes);
 
         // Update fork choice.
         if let Err(e) = fork_choice_result {
@@ -5293,6 +6017,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(());
         }
 
+        for _ in 0..PREPARE_PROPOSER_MAX_ATTEMPTS {
+            match self
+                .prepare_beacon_proposer_on_head(current_slot, &execution_layer)
+                .await?
+            {
+                ProposerPreparation::Complete => return Ok(()),
+                ProposerPreparation::HeadChanged => continue,
+            }
+        }
+
+        warn!(
+            self.log,
+            "Abandoned proposer preparation";
+            "reason" => "head changed repeatedly",
+            "prepare_slot" => prepare_slot,
+        );
+        Ok(())
+    }
+
+    /// Run a single attempt of `prepare_beacon_proposer`.
+    ///
+    /// The payload attributes are derived from a single read of the canonical head. If the head
+    /// has changed by the time the `forkchoiceUpdated` call is due, nothing is sent and
+    /// `ProposerPreparation::HeadChanged` is returned so that the attributes can be recomputed.
+    async fn prepare_beacon_proposer_on_head(
+        self: &Arc<Self>,
+        current_slot: Slot,
+        execution_layer: &ExecutionLayer<T::EthSpec>,
+    ) -> Result<ProposerPreparation, Error> {
+        let prepare_slot = current_slot + 1;
+
         // Load the cached head and its forkchoice update parameters.
         //
         // Use a blocking task since blocking the core executor on the canonical head read lock can
@@ -5328,19 +6083,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         fcu_params.head_root,
                         &cached_head,
                     )?;
-                    Ok::<_, Error>(Some((fcu_params, pre_payload_attributes)))
+                    Ok::<_, Error>(Some((cached_head, fcu_params, pre_payload_attributes)))
                 },
                 "prepare_beacon_proposer_head_read",
             )
             .await??;
 
-        let (forkchoice_update_params, pre_payload_attributes) =
-            if let Some((fcu, Some(pre_payload))) = maybe_prep_data {
-                (fcu, pre_payload)
+        let (cached_head, forkchoice_update_params, pre_payload_attributes) =
+            if let Some((cached_head, fcu, Some(pre_payload))) = maybe_prep_data {
+                (cached_head, fcu, pre_payload)
             } else {
                 // Appropriate log messages have already been logged above and in
                 // `get_pre_payload_attributes`.
-                return Ok(());
+                return Ok(ProposerPreparation::Complete);
             };
 
         // If the execution layer doesn't have any proposer data for this validator then we assume
@@ -5351,7 +6106,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .has_proposer_preparation_data(proposer)
                 .await
         {
-            return Ok(());
+            return Ok(ProposerPreparation::Complete);
         }
 
         // Fetch payload attributes from the execution layer's cache, or compute them from scratch
@@ -5371,7 +6126,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     let chain = self.clone();
                     self.spawn_blocking_handle(
                         move || {
-                            chain.get_expected_withdrawals(&forkchoice_update_params, prepare_slot)
+                            chain.get_expected_withdrawals(
+                                &cached_head,
+                                &forkchoice_update_params,
+                                prepare_slot,
+                            )
                         },
                         "prepare_beacon_proposer_withdrawals",
                     )
@@ -5449,7 +6208,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     "prepare_slot" => prepare_slot,
                     "validator" => proposer,
                 );
-                return Ok(());
+                return Ok(ProposerPreparation::Complete);
             };
 
         // If we are close enough to the proposal slot, send an fcU, which will have payload
@@ -5457,6 +6216,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         if self.config.always_prepare_payload
             || till_prepare_slot <= self.config.prepare_payload_lookahead
         {
+            // The attributes were derived from the head read above. If the head has since changed
+            // (e.g. due to a re-org) they may describe the wrong parent, so recompute them rather
+            // than sending them alongside a different head.
+            let chain = self.clone();
+            let latest_fcu_params = self
+                .spawn_blocking_handle(
+                    move || {
+                        let canonical_fcu_params = chain
+                            .canonical_head
+                            .cached_head()
+                            .forkchoice_update_parameters();
+                        chain.overridden_forkchoice_update_params(canonical_fcu_params)
+                    },
+                    "prepare_beacon_proposer_head_recheck",
+                )
+                .await??;
+            if latest_fcu_params.head_root != head_root {
+                debug!(
+                    self.log,
+                    "Head changed during proposer preparation";
+                    "prepare_slot" => prepare_slot,
+                    "prepared_head" => ?head_root,
+                    "new_head" => ?latest_fcu_params.head_root,
+                );
+                return Ok(ProposerPreparation::HeadChanged);
+            }
+
             debug!(
                 self.log,
                 "Sending forkchoiceUpdate for proposer prep";
@@ -5472,7 +6258,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .await?;
         }
 
-        Ok(())
+        Ok(ProposerPreparation::Complete)
     }
 
     pub async fn update_execution_engine_forkchoice(
@@ -5608,17 +6394,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 PayloadStatus::Valid => {
                     // Ensure that fork choice knows that the block is no longer optimistic.
                     let chain = self.clone();
-                    let fork_choice_update_result = self
+                    let (fork_choice_update_result, execution_status_changes) = self
                         .spawn_blocking_handle(
                             move || {
-                                chain
-                                    .canonical_head
-                                    .fork_choice_write_lock()
-                                    .on_valid_execution_payload(head_block_root)
+                                let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+                                let result =
+                                    fork_choice.on_valid_execution_payload(head_block_root);
+                                (result, fork_choice.take_execution_status_changes())
                             },
                             "update_execution_engine_valid_payload",
                         )
                         .await?;
+                    self.register_execution_status_changes(execution_status_changes);
                     if let Err(e) = fork_choice_update_result {
                         error!(
                             self.log,
@@ -5882,6 +6669,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // sync anyway).
             self.naive_aggregation_pool.write().prune(slot);
             self.block_times_cache.write().prune(slot);
+            self.epoch_timings_cache
+                .prune(slot.epoch(T::EthSpec::slots_per_epoch()));
 
             // Don't run heavy-weight tasks during sync.
             if self.best_slot() + MAX_PER_SLOT_FORK_CHOICE_DISTANCE < slot {
@@ -5891,6 +6680,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // Run fork choice and signal to any waiting task that it has completed.
             self.recompute_head_at_current_slot().await;
 
+            if let Err(e) = self.update_head_vote_accuracy() {
+                debug!(
+                    self.log,
+                    "Failed to update head vote accuracy";
+                    "error" => ?e
+                );
+            }
+
+            if let Err(e) = self.update_sync_committee_participation() {
+                debug!(
+                    self.log,
+                    "Failed to update sync committee participation";
+                    "error" => ?e
+                );
+            }
+
             // Send the notification regardless of fork choice success, this is a "best effort"
             // notification and we don't want block production to hit the timeout in case of error.
             // Use a blocking task to avoid blocking the core executor whilst waiting for locks
@@ -6108,6 +6913,66 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Builds the caches required to serve validator duties for the head, so that the first duties
+    /// requests after a checkpoint sync don't have to wait for them.
+    ///
+    /// The committee caches for the current and next epochs of the head are added to the
+    /// `shuffling_cache`, and the proposers for the current epoch are added to the
+    /// `beacon_proposer_cache`. The validator pubkey cache is always complete once the chain has
+    /// been built, so it is only reported.
+    pub fn preheat_caches(&self) -> Result<(), Error> {
+        let num_pubkeys = self
+            .validator_pubkey_cache
+            .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+            .ok_or(Error::ValidatorPubkeyCacheLockTimeout)?
+            .len();
+        info!(
+            self.log,
+            "Validator pubkey cache ready";
+            "validators" => num_pubkeys,
+        );
+
+        let head_block_root = self.canonical_head.cached_head().head_block_root();
+        let head_block = self
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block(&head_block_root)
+            .ok_or(Error::MissingBeaconBlock(head_block_root))?;
+
+        for shuffling_id in [
+            &head_block.current_epoch_shuffling_id,
+            &head_block.next_epoch_shuffling_id,
+        ] {
+            self.with_committee_cache(
+                head_block_root,
+                shuffling_id.shuffling_epoch,
+                |_, _| Ok(()),
+            )?;
+            info!(
+                self.log,
+                "Committee cache ready";
+                "epoch" => shuffling_id.shuffling_epoch,
+            );
+        }
+
+        let proposer_epoch = head_block.current_epoch_shuffling_id.shuffling_epoch;
+        let (proposers, dependent_root, _, fork) =
+            compute_proposer_duties_from_head(proposer_epoch, self)?;
+        self.beacon_proposer_cache.lock().insert(
+            proposer_epoch,
+            dependent_root,
+            proposers,
+            fork,
+        )?;
+        info!(
+            self.log,
+            "Proposer cache ready";
+            "epoch" => proposer_epoch,
+        );
+
+        Ok(())
+    }
+
     /// Dumps the entire canonical chain, from the head to genesis to a vector for analysis.
     ///
     /// This could be a very expensive operation and should only be done in testing/analysis
@@ -6219,43 +7084,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(ChainHealth::Healthy);
         }
 
-        let current_slot = self.slot()?;
-
-        // Check slots at the head of the chain.
-        let prev_slot = current_slot.saturating_sub(Slot::new(1));
-        let head_skips = prev_slot.saturating_sub(cached_head.head_slot());
-        let head_skips_check = head_skips.as_usize() <= self.config.builder_fallback_skips;
-
-        // Check if finalization is advancing.
-        let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
-        let epochs_since_finalization =
-            current_epoch.saturating_sub(cached_head.finalized_checkpoint().epoch);
-        let finalization_check = epochs_since_finalization.as_usize()
-            <= self.config.builder_fallback_epochs_since_finalization;
-
-        // Check skip slots in the last `SLOTS_PER_EPOCH`.
-        let start_slot = current_slot.saturating_sub(T::EthSpec::slots_per_epoch());
-        let mut epoch_skips = 0;
-        for slot in start_slot.as_u64()..current_slot.as_u64() {
-            if self
-                .block_root_at_slot_skips_none(Slot::new(slot))?
-                .is_none()
-            {
-                epoch_skips += 1;
-            }
-        }
-        let epoch_skips_check = epoch_skips <= self.config.builder_fallback_skips_per_epoch;
-
-        if !head_skips_check {
-            Ok(ChainHealth::Unhealthy(FailedCondition::Skips))
-        } else if !finalization_check {
-            Ok(ChainHealth::Unhealthy(
-                FailedCondition::EpochsSinceFinalization,
-            ))
-        } else if !epoch_skips_check {
-            Ok(ChainHealth::Unhealthy(FailedCondition::SkipsPerEpoch))
-        } else {
-            Ok(ChainHealth::Healthy)
+        // Check the conditions of the builder circuit breaker.
+        let status = self.builder_circuit_breaker_status()?;
+        match builder_circuit_breaker::failed_condition(&status) {
+            Some(condition) => Ok(ChainHealth::Unhealthy(condition)),
+            None => Ok(ChainHealth::Healthy),
         }
     }
 
@@ -6395,18 +7228,36 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn data_availability_boundary(&self) -> Option<Epoch> {
         self.data_availability_checker.data_availability_boundary()
     }
+
+    /// Returns the root and slot of every block in fork choice prior to `epoch`.
+    ///
+    /// Fork choice holds every block that descends from finalization, so these are the blocks
+    /// whose blobs can't be found by iterating the finalized chain.
+    pub fn fork_choice_blocks_before(&self, epoch: Epoch) -> Vec<(Hash256, Slot)> {
+        let start_slot = epoch.start_slot(T::EthSpec::slots_per_epoch());
+        self.canonical_head
+            .fork_choice_read_lock()
+            .proto_array()
+            .core_proto_array()
+            .nodes
+            .iter()
+            .filter(|node| node.slot < start_slot)
+            .map(|node| (node.root, node.slot))
+            .collect()
+    }
+
+    /// Prune blobs older than `data_availability_boundary` in the background, including those of
+    /// blocks which are not yet finalized.
+    pub fn prune_blobs(&self, data_availability_boundary: Epoch) {
+        let unfinalized_blocks = self.fork_choice_blocks_before(data_availability_boundary);
+        self.store_migrator
+            .process_prune_blobs(data_availability_boundary, unfinalized_blocks);
+    }
 }
 
 impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
     fn drop(&mut self) {
-        let drop = || -> Result<(), Error> {
-            self.persist_head_and_fork_choice()?;
-            self.persist_op_pool()?;
-            self.persist_data_availability_checker()?;
-            self.persist_eth1_cache()
-        };
-
-        if let Err(e) = drop() {
+        if let Err(e) = self.persist_to_disk() {
             error!(
                 self.log,
                 "Failed to persist on BeaconChain drop";
@@ -6447,3 +7298,32 @@ impl<T: EthSpec> ChainSegmentResult<T> {
         }
     }
 }
+
+/// Compute the percentage of the total active balance that attested to the correct target during
+/// the epoch prior to `state`'s current epoch.
+///
+/// As in justification processing, the target attesting balance is taken from the progressive
+/// balances cache and compared against the cached total active balance, so no validator is
+/// visited.
+///
+/// Returns `None` if `state` is in the genesis epoch, which has no prior epoch. Returns an error
+/// if either cache is uninitialized, which is always the case for phase0 states.
+fn previous_epoch_target_participation<E: EthSpec>(
+    state: &BeaconState<E>,
+) -> Result<Option<u64>, BeaconStateError> {
+    if state.previous_epoch() == state.current_epoch() {
+        return Ok(None);
+    }
+
+    let target_balance = state
+        .progressive_balances_cache()
+        .previous_epoch_target_attesting_balance()?;
+    let active_balance = state.get_total_active_balance()?;
+
+    if active_balance == 0 {
+        return Ok(Some(0));
+    }
+    Ok(Some(
+        target_balance.safe_mul(100)?.safe_div(active_balance)?,
+    ))
+}
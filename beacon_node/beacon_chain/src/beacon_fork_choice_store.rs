@@ -109,20 +109,34 @@ impl BalancesCache {
         // of a single epoch, so even if the block on the epoch boundary itself is skipped we can
         // still update its cache entry from any subsequent state in that epoch.
         if self.position(epoch_boundary_root, epoch).is_none() {
-            let item = CacheItem {
-                block_root: epoch_boundary_root,
-                epoch,
-                balances: JustifiedBalances::from_justified_state(state)?.effective_balances,
-            };
+            let balances = JustifiedBalances::from_justified_state(state)?.effective_balances;
+            self.insert(epoch_boundary_root, epoch, balances);
+        }
 
-            if self.items.len() == MAX_BALANCE_CACHE_SIZE {
-                self.items.remove(0);
-            }
+        Ok(())
+    }
 
-            self.items.push(item);
+    /// Adds the `balances` of the checkpoint at `block_root` and `epoch`, evicting the oldest entry
+    /// if the cache is full.
+    pub fn insert(&mut self, block_root: Hash256, epoch: Epoch, balances: Vec<u64>) {
+        if self.position(block_root, epoch).is_some() {
+            return;
         }
 
-        Ok(())
+        if self.items.len() == MAX_BALANCE_CACHE_SIZE {
+            self.items.remove(0);
+        }
+
+        self.items.push(CacheItem {
+            block_root,
+            epoch,
+            balances,
+        });
+    }
+
+    /// Returns `true` if the cache contains balances for the given `block_root` and `epoch`.
+    pub fn contains(&self, block_root: Hash256, epoch: Epoch) -> bool {
+        self.position(block_root, epoch).is_some()
     }
 
     fn position(&self, block_root: Hash256, epoch: Epoch) -> Option<usize> {
@@ -156,6 +170,9 @@ pub struct BeaconForkChoiceStore<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<
     unrealized_finalized_checkpoint: Checkpoint,
     proposer_boost_root: Hash256,
     equivocating_indices: BTreeSet<u64>,
+    /// The number of times a state has been loaded to find the justified balances.
+    #[derivative(PartialEq = "ignore")]
+    justified_state_loads: u64,
     _phantom: PhantomData<E>,
 }
 
@@ -205,6 +222,7 @@ where
             unrealized_finalized_checkpoint: finalized_checkpoint,
             proposer_boost_root: Hash256::zero(),
             equivocating_indices: BTreeSet::new(),
+            justified_state_loads: 0,
             _phantom: PhantomData,
         })
     }
@@ -243,9 +261,55 @@ where
             unrealized_finalized_checkpoint: persisted.unrealized_finalized_checkpoint,
             proposer_boost_root: persisted.proposer_boost_root,
             equivocating_indices: persisted.equivocating_indices,
+            justified_state_loads: 0,
             _phantom: PhantomData,
         })
     }
+
+    /// Loads the effective balances of the state at `checkpoint` from the database.
+    ///
+    /// This reads a state, so callers should prefer the balances cache where possible.
+    pub fn load_checkpoint_balances(
+        store: &HotColdDB<E, Hot, Cold>,
+        checkpoint: Checkpoint,
+    ) -> Result<JustifiedBalances, Error> {
+        let block = store
+            .get_blinded_block(&checkpoint.root)
+            .map_err(Error::FailedToReadBlock)?
+            .ok_or(Error::MissingBlock(checkpoint.root))?
+            .deconstruct()
+            .0;
+
+        let max_slot = checkpoint.epoch.start_slot(E::slots_per_epoch());
+        let (_, state) = store
+            .get_advanced_hot_state(checkpoint.root, max_slot, block.state_root())
+            .map_err(Error::FailedToReadState)?
+            .ok_or_else(|| Error::MissingState(block.state_root()))?;
+
+        JustifiedBalances::from_justified_state(&state)
+    }
+
+    /// Returns `true` if the balances for `checkpoint` are cached, or if it's the current
+    /// justified checkpoint, in which case its balances are already loaded.
+    pub fn has_balances_for(&self, checkpoint: Checkpoint) -> bool {
+        checkpoint == self.justified_checkpoint
+            || self
+                .balances_cache
+                .contains(checkpoint.root, checkpoint.epoch)
+    }
+
+    /// Adds the `balances` of `checkpoint` to the balances cache, so that they can be used when
+    /// the checkpoint becomes justified without loading its state.
+    pub fn cache_checkpoint_balances(&mut self, checkpoint: Checkpoint, balances: Vec<u64>) {
+        self.balances_cache
+            .insert(checkpoint.root, checkpoint.epoch, balances);
+    }
+
+    /// Returns the number of times a state has been loaded to find the justified balances since
+    /// `self` was created.
+    pub fn justified_state_loads(&self) -> u64 {
+        self.justified_state_loads
+    }
 }
 
 impl<E, Hot, Cold> ForkChoiceStore<E> for BeaconForkChoiceStore<E, Hot, Cold>
@@ -313,29 +377,9 @@ where
             self.justified_balances = JustifiedBalances::from_effective_balances(balances)?;
         } else {
             metrics::inc_counter(&metrics::BALANCES_CACHE_MISSES);
-            let justified_block = self
-                .store
-                .get_blinded_block(&self.justified_checkpoint.root)
-                .map_err(Error::FailedToReadBlock)?
-                .ok_or(Error::MissingBlock(self.justified_checkpoint.root))?
-                .deconstruct()
-                .0;
-
-            let max_slot = self
-                .justified_checkpoint
-                .epoch
-                .start_slot(E::slots_per_epoch());
-            let (_, state) = self
-                .store
-                .get_advanced_hot_state(
-                    self.justified_checkpoint.root,
-                    max_slot,
-                    justified_block.state_root(),
-                )
-                .map_err(Error::FailedToReadState)?
-                .ok_or_else(|| Error::MissingState(justified_block.state_root()))?;
-
-            self.justified_balances = JustifiedBalances::from_justified_state(&state)?;
+            self.justified_state_loads += 1;
+            self.justified_balances =
+                Self::load_checkpoint_balances(&self.store, self.justified_checkpoint)?;
         }
 
         Ok(())
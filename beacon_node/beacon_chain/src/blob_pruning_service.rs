@@ -0,0 +1,79 @@
+//! Prunes blobs which are older than the data availability boundary on a fixed schedule.
+//!
+//! The data availability boundary is computed from the current slot rather than the finalized
+//! checkpoint, so that blobs continue to be pruned during periods of non-finality.
+
+use crate::{BeaconChain, BeaconChainTypes};
+use slog::{debug, error};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::EthSpec;
+
+/// The routine is expected to run 1/2 through the epoch, away from the other per-epoch services.
+pub const EPOCH_DELAY_FACTOR: u32 = 2;
+
+/// Spawns a routine which prunes blobs older than the data availability boundary.
+///
+/// This routine will run once every `blob_prune_interval_epochs`, at
+/// `epoch_duration / EPOCH_DELAY_FACTOR` after the start of the epoch. Blobs are only pruned once
+/// `epochs_per_blob_prune` epochs of them have accumulated.
+///
+/// The service will not be started if Deneb is not scheduled or blob pruning is disabled.
+pub fn start_blob_pruning_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    if chain.spec.deneb_fork_epoch.is_some() && chain.store.get_config().prune_blobs {
+        executor.spawn(
+            async move { blob_pruning_service(chain).await },
+            "blob_pruning_service",
+        );
+    } else {
+        debug!(
+            chain.log,
+            "Blob pruning disabled, not starting blob pruning service"
+        );
+    }
+}
+
+async fn blob_pruning_service<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) {
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let epoch_duration = chain.slot_clock.slot_duration() * slots_per_epoch as u32;
+    let interval_epochs = chain.store.get_config().blob_prune_interval_epochs;
+    loop {
+        match chain.slot_clock.duration_to_next_epoch(slots_per_epoch) {
+            Some(duration) => {
+                let additional_delay = epoch_duration / EPOCH_DELAY_FACTOR;
+                sleep(duration + additional_delay).await;
+
+                let Some(current_epoch) = chain
+                    .slot_clock
+                    .now()
+                    .map(|slot| slot.epoch(slots_per_epoch))
+                else {
+                    continue;
+                };
+
+                if current_epoch.as_u64() % interval_epochs != 0 {
+                    continue;
+                }
+
+                if let Some(data_availability_boundary) = chain.data_availability_boundary() {
+                    debug!(
+                        chain.log,
+                        "Blob pruning service firing";
+                        "data_availability_boundary" => data_availability_boundary,
+                    );
+                    chain.prune_blobs(data_availability_boundary);
+                }
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(chain.slot_clock.slot_duration()).await;
+            }
+        }
+    }
+}
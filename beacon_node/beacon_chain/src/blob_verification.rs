@@ -9,6 +9,7 @@ use crate::beacon_chain::{
 use crate::block_verification::cheap_state_advance_to_obtain_committees;
 use crate::data_availability_checker::AvailabilityCheckError;
 use crate::kzg_utils::{validate_blob, validate_blobs};
+use crate::validator_monitor::timestamp_now;
 use crate::{metrics, BeaconChainError};
 use kzg::{Kzg, KzgCommitment};
 use slog::{debug, warn};
@@ -29,7 +30,8 @@ pub enum GossipBlobError<T: EthSpec> {
     ///
     /// ## Peer scoring
     ///
-    /// Assuming the local clock is correct, the peer has sent an invalid message.
+    /// Assuming the local clock is correct, the peer has sent an invalid message. Blob sidecars
+    /// that are less than a slot early may instead be queued until their slot.
     FutureSlot {
         message_slot: Slot,
         latest_permissible_slot: Slot,
@@ -158,6 +160,12 @@ impl<T: BeaconChainTypes> GossipVerifiedBlob<T> {
         let blob_index = blob.message.index;
         validate_blob_sidecar_for_gossip(blob, blob_index, chain)
     }
+    /// Construct a `GossipVerifiedBlob` for a blob identical to one which has already passed
+    /// gossip verification and has been taken from the availability cache.
+    pub(crate) fn from_cached(blob: SignedBlobSidecar<T::EthSpec>) -> Self {
+        Self { blob }
+    }
+
     /// Construct a `GossipVerifiedBlob` that is assumed to be valid.
     ///
     /// This should ONLY be used for testing.
@@ -431,7 +439,7 @@ pub fn validate_blob_sidecar_for_gossip<T: BeaconChainTypes>(
     if chain
         .observed_blob_sidecars
         .write()
-        .observe_sidecar(&signed_blob_sidecar.message)
+        .observe_sidecar(&signed_blob_sidecar.message, timestamp_now())
         .map_err(|e| GossipBlobError::BeaconChainError(e.into()))?
     {
         return Err(GossipBlobError::RepeatBlob {
@@ -701,12 +701,27 @@ impl<T: BeaconChainTypes> IntoGossipVerifiedBlockContents<T> for SignedBlockCont
     ) -> Result<GossipVerifiedBlockContents<T>, BlockContentsError<T::EthSpec>> {
         let (block, blobs) = self.deconstruct();
         let gossip_verified_block = GossipVerifiedBlock::new(Arc::new(block), chain)?;
+
         let gossip_verified_blobs = blobs
             .map(|blobs| {
-                Ok::<_, GossipBlobError<T::EthSpec>>(VariableList::from(
+                // Blobs which have already been received (e.g. on gossip ahead of the block) are
+                // verified and held by the availability checker until the block is imported. Take
+                // them from the cache so that they're imported with the rest of the submitted blobs,
+                // rather than rejecting them as repeats.
+                let cached_blobs = chain
+                    .data_availability_checker
+                    .take_cached_blobs(gossip_verified_block.block_root, &blobs)
+                    .map_err(BlockError::from)?;
+                Ok::<_, BlockContentsError<T::EthSpec>>(VariableList::from(
                     blobs
                         .into_iter()
-                        .map(|blob| GossipVerifiedBlob::new(blob, chain))
+                        .map(|blob| {
+                            if cached_blobs.contains(&blob.message) {
+                                Ok(GossipVerifiedBlob::from_cached(blob))
+                            } else {
+                                GossipVerifiedBlob::new(blob, chain)
+                            }
+                        })
                         .collect::<Result<Vec<_>, GossipBlobError<T::EthSpec>>>()?,
                 ))
             })
@@ -1,12 +1,16 @@
 use crate::beacon_chain::{CanonicalHead, BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, OP_POOL_DB_KEY};
 use crate::data_availability_checker::DataAvailabilityChecker;
+use crate::epoch_timings::EpochTimingsCache;
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::eth1_finalization_cache::Eth1FinalizationCache;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::head_tracker::HeadTracker;
+use crate::head_vote_accuracy::HeadVoteAccuracyTracker;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
+use crate::observed_aggregates::ObservedAggregates;
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_proposer_preparation::load_proposer_preparations;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
 use crate::timeout_rw_lock::TimeoutRwLock;
@@ -26,7 +30,7 @@ use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::per_slot_processing;
 use std::marker::PhantomData;
@@ -34,9 +38,10 @@ use std::sync::Arc;
 use std::time::Duration;
 use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
 use task_executor::{ShutdownReason, TaskExecutor};
+use types::consts::deneb::MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS;
 use types::{
-    BeaconBlock, BeaconState, ChainSpec, Checkpoint, Epoch, EthSpec, Graffiti, Hash256,
-    PublicKeyBytes, Signature, SignedBeaconBlock, Slot,
+    BeaconBlock, BeaconState, BlobSidecarList, ChainSpec, Checkpoint, Epoch, EthSpec, Graffiti,
+    Hash256, PublicKeyBytes, Signature, SignedBeaconBlock, Slot,
 };
 
 /// An empty struct used to "witness" all the `BeaconChainTypes` traits. It has no user-facing
@@ -171,6 +176,18 @@ where
         self
     }
 
+    /// Sets the margin below the proposer re-org threshold required to re-org.
+    pub fn proposer_re_org_margin(mut self, margin: ReOrgThreshold) -> Self {
+        self.chain_config.re_org_margin = margin;
+        self
+    }
+
+    /// Sets the minimum previous epoch participation required for proposer re-orgs.
+    pub fn proposer_re_org_participation_threshold(mut self, percent: u64) -> Self {
+        self.chain_config.re_org_participation_threshold = percent;
+        self
+    }
+
     /// Sets the proposer re-org max epochs since finalization.
     pub fn proposer_re_org_max_epochs_since_finalization(
         mut self,
@@ -422,10 +439,13 @@ where
     }
 
     /// Start the chain from a weak subjectivity state.
+    ///
+    /// The `weak_subj_blobs` must be provided if the checkpoint block has blob KZG commitments.
     pub fn weak_subjectivity_state(
         mut self,
         mut weak_subj_state: BeaconState<TEthSpec>,
         weak_subj_block: SignedBeaconBlock<TEthSpec>,
+        weak_subj_blobs: Option<BlobSidecarList<TEthSpec>>,
         genesis_state: BeaconState<TEthSpec>,
     ) -> Result<Self, String> {
         let store = self
@@ -437,6 +457,27 @@ where
             .as_ref()
             .ok_or("weak_subjectivity_state requires a log")?;
 
+        // Validate the checkpoint block's `state_root` against the state, prior to any slot
+        // processing. If the state is at the block's slot we can compare the roots directly,
+        // otherwise the state's latest block header will have been filled in with the block's
+        // post-state root by `process_slot`.
+        let expected_block_state_root = if weak_subj_state.slot() == weak_subj_block.slot() {
+            weak_subj_state
+                .update_tree_hash_cache()
+                .map_err(|e| format!("Error computing checkpoint state root: {e:?}"))?
+        } else {
+            weak_subj_state.latest_block_header().state_root
+        };
+        if weak_subj_block.state_root() != expected_block_state_root {
+            return Err(format!(
+                "Snapshot block's state root does not match state, expected: {:?}, got: {:?}",
+                expected_block_state_root,
+                weak_subj_block.state_root()
+            ));
+        }
+
+        let weak_subj_blobs = verify_weak_subjectivity_blobs(&weak_subj_block, weak_subj_blobs)?;
+
         // Ensure the state is advanced to an epoch boundary.
         let slots_per_epoch = TEthSpec::slots_per_epoch();
         if weak_subj_state.slot() % slots_per_epoch != 0 {
@@ -513,6 +554,11 @@ where
         store
             .put_block(&weak_subj_block_root, weak_subj_block.clone())
             .map_err(|e| format!("Failed to store weak subjectivity block: {:?}", e))?;
+        if let Some(blobs) = weak_subj_blobs {
+            store
+                .put_blobs(&weak_subj_block_root, blobs)
+                .map_err(|e| format!("Failed to store weak subjectivity blobs: {:?}", e))?;
+        }
 
         // Stage the database's metadata fields for atomic storage when `build` is called.
         // This prevents the database from restarting in an inconsistent state if the anchor
@@ -829,15 +875,21 @@ where
         let head_for_snapshot_cache = head_snapshot.clone();
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
+        let epoch_timings_retention_epochs = self.chain_config.epoch_timings_retention_epochs;
+        let head_vote_accuracy_warn_threshold = self.chain_config.head_vote_accuracy_warn_threshold;
+        let observed_attestations_max_bytes = self.chain_config.observed_attestations_max_bytes;
 
         // Calculate the weak subjectivity point in which to backfill blocks to.
         let genesis_backfill_slot = if self.chain_config.genesis_backfill {
             Slot::new(0)
         } else {
-            let backfill_epoch_range = (self.spec.min_validator_withdrawability_delay
-                + self.spec.churn_limit_quotient)
-                .as_u64()
-                / 2;
+            let backfill_epoch_range = if self.chain_config.backfill_to_data_availability_boundary {
+                MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS.as_u64()
+            } else {
+                (self.spec.min_validator_withdrawability_delay + self.spec.churn_limit_quotient)
+                    .as_u64()
+                    / 2
+            };
             match slot_clock.now() {
                 Some(current_slot) => {
                     let genesis_backfill_epoch = current_slot
@@ -853,6 +905,40 @@ where
             }
         };
 
+        // Restore the proposer preparations and builder registrations sent by validator clients
+        // before the restart, so that the fee recipients of local validators are known before they
+        // are sent again.
+        if let Some(execution_layer) = self.execution_layer.as_ref() {
+            match load_proposer_preparations(
+                &store,
+                current_slot.epoch(TEthSpec::slots_per_epoch()),
+            ) {
+                Ok(preparations) if !preparations.is_empty() => {
+                    let count = preparations.len();
+                    if execution_layer.restore_proposer_preparation(preparations) {
+                        info!(
+                            log,
+                            "Restored proposer preparations";
+                            "count" => count,
+                        );
+                    } else {
+                        warn!(
+                            log,
+                            "Unable to restore proposer preparations";
+                            "reason" => "proposer preparation data is locked",
+                            "count" => count,
+                        );
+                    }
+                }
+                Ok(_) => (),
+                Err(e) => warn!(
+                    log,
+                    "Unable to load proposer preparations";
+                    "error" => ?e,
+                ),
+            }
+        }
+
         let beacon_chain = BeaconChain {
             spec: self.spec.clone(),
             config: self.chain_config,
@@ -868,7 +954,9 @@ where
             // TODO: allow for persisting and loading the pool from disk.
             naive_sync_aggregation_pool: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
-            observed_attestations: <_>::default(),
+            observed_attestations: RwLock::new(ObservedAggregates::new(
+                observed_attestations_max_bytes,
+            )),
             // TODO: allow for persisting and loading the pool from disk.
             observed_sync_contributions: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
@@ -913,15 +1001,20 @@ where
             eth1_finalization_cache: TimeoutRwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache: <_>::default(),
             block_times_cache: <_>::default(),
+            epoch_timings_cache: EpochTimingsCache::new(epoch_timings_retention_epochs),
+            head_vote_accuracy: HeadVoteAccuracyTracker::new(head_vote_accuracy_warn_threshold),
+            last_re_org_decision: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
+            sync_committee_pubkey_cache: <_>::default(),
+            sync_duties_cache: <_>::default(),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
             shutdown_sender: self
                 .shutdown_sender
                 .ok_or("Cannot build without a shutdown sender.")?,
             log: log.clone(),
-            graffiti: self.graffiti,
+            graffiti: RwLock::new(self.graffiti),
             slasher: self.slasher.clone(),
             validator_monitor: RwLock::new(validator_monitor),
             genesis_backfill_slot,
@@ -994,9 +1087,7 @@ where
 
         // Prune blobs older than the blob data availability boundary in the background.
         if let Some(data_availability_boundary) = beacon_chain.data_availability_boundary() {
-            beacon_chain
-                .store_migrator
-                .process_prune_blobs(data_availability_boundary);
+            beacon_chain.prune_blobs(data_availability_boundary);
         }
 
         Ok(beacon_chain)
@@ -1077,6 +1168,75 @@ fn genesis_block<T: EthSpec>(
     ))
 }
 
+/// Check that `blobs` are consistent with the checkpoint `block`.
+///
+/// Returns the blobs to be stored alongside the block, or `None` if the block has no blobs.
+fn verify_weak_subjectivity_blobs<E: EthSpec>(
+    block: &SignedBeaconBlock<E>,
+    blobs: Option<BlobSidecarList<E>>,
+) -> Result<Option<BlobSidecarList<E>>, String> {
+    let block_root = block.canonical_root();
+    let Ok(commitments) = block.message().body().blob_kzg_commitments() else {
+        // Pre-Deneb blocks never have blobs.
+        if blobs.as_ref().map_or(false, |blobs| !blobs.is_empty()) {
+            return Err(format!(
+                "Snapshot blobs provided for pre-Deneb block {block_root:?}"
+            ));
+        }
+        return Ok(None);
+    };
+
+    let blobs = match blobs {
+        Some(blobs) => blobs,
+        None if commitments.is_empty() => return Ok(None),
+        None => {
+            return Err(format!(
+                "Snapshot block {block_root:?} has {} blob commitments but no blobs were provided",
+                commitments.len()
+            ))
+        }
+    };
+
+    if blobs.len() != commitments.len() {
+        return Err(format!(
+            "Snapshot blobs do not match block, expected {} blobs, got: {}",
+            commitments.len(),
+            blobs.len()
+        ));
+    }
+
+    for (i, (blob, commitment)) in blobs.iter().zip(commitments.iter()).enumerate() {
+        if blob.block_root != block_root {
+            return Err(format!(
+                "Snapshot blob {} has the wrong block root, expected: {:?}, got: {:?}",
+                blob.index, block_root, blob.block_root
+            ));
+        }
+        if blob.index != i as u64 {
+            return Err(format!(
+                "Snapshot blobs are not in order, expected index: {}, got: {}",
+                i, blob.index
+            ));
+        }
+        if blob.slot != block.slot() {
+            return Err(format!(
+                "Snapshot blob {} has the wrong slot, expected: {}, got: {}",
+                blob.index,
+                block.slot(),
+                blob.slot
+            ));
+        }
+        if blob.kzg_commitment != *commitment {
+            return Err(format!(
+                "Snapshot blob {} does not match the block's KZG commitment",
+                blob.index
+            ));
+        }
+    }
+
+    Ok((!blobs.is_empty()).then_some(blobs))
+}
+
 // Helper function to return more useful errors when reading from the database.
 fn descriptive_db_error(item: &str, error: &StoreError) -> String {
     let additional_info = if let StoreError::SszDecodeError(_) = error {
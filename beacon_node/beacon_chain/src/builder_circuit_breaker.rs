@@ -0,0 +1,323 @@
+//! The builder circuit breaker decides whether connected builders may be used for payload
+//! construction, based on the recent health of the chain.
+//!
+//! Each condition compares an observation of the chain against a configurable threshold, and can
+//! be disabled individually. If any enabled condition fails the breaker trips, and block
+//! production only uses the local execution engine.
+use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes, ChainConfig};
+use eth2::lighthouse::{BuilderCircuitBreakerCondition, BuilderCircuitBreakerStatus};
+use execution_layer::FailedCondition;
+use std::collections::HashMap;
+use types::{Epoch, EthSpec, Slot};
+
+/// The thresholds of the circuit breaker conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuilderCircuitBreaker {
+    pub max_skips: u64,
+    pub max_skips_per_epoch: u64,
+    /// The number of recent slots in which skips and missed proposals are counted.
+    pub skips_window: u64,
+    pub max_epochs_since_finalization: u64,
+    /// The missed proposals condition is only checked if this is set.
+    pub max_missed_proposals: Option<u64>,
+    pub disabled_conditions: Vec<FailedCondition>,
+    /// Disables all conditions.
+    pub disable_checks: bool,
+}
+
+/// The observations of the chain which the circuit breaker conditions are checked against.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ChainHealthObservations {
+    /// Skip slots in a row at the head of the chain, excluding the current slot.
+    pub head_skips: u64,
+    /// Skip slots in the recent window, excluding the current slot.
+    pub skips_in_window: u64,
+    pub epochs_since_finalization: u64,
+    /// Skip slots in the recent window whose proposer is a validator of this node.
+    pub missed_proposals: u64,
+}
+
+impl ChainHealthObservations {
+    fn value(&self, condition: FailedCondition) -> u64 {
+        match condition {
+            FailedCondition::Skips => self.head_skips,
+            FailedCondition::SkipsPerEpoch => self.skips_in_window,
+            FailedCondition::EpochsSinceFinalization => self.epochs_since_finalization,
+            FailedCondition::MissedProposals => self.missed_proposals,
+        }
+    }
+}
+
+impl BuilderCircuitBreaker {
+    pub fn from_config(config: &ChainConfig, slots_per_epoch: u64) -> Self {
+        Self {
+            max_skips: config.builder_fallback_skips as u64,
+            max_skips_per_epoch: config.builder_fallback_skips_per_epoch as u64,
+            skips_window: config
+                .builder_fallback_skips_window
+                .map_or(slots_per_epoch, |window| window as u64),
+            max_epochs_since_finalization: config.builder_fallback_epochs_since_finalization as u64,
+            max_missed_proposals: config
+                .builder_fallback_missed_proposals
+                .map(|max| max as u64),
+            disabled_conditions: config.builder_fallback_disabled_conditions.clone(),
+            disable_checks: config.builder_fallback_disable_checks,
+        }
+    }
+
+    /// Returns `true` if `condition` can trip the breaker.
+    pub fn is_enabled(&self, condition: FailedCondition) -> bool {
+        !self.disable_checks
+            && !self.disabled_conditions.contains(&condition)
+            && (condition != FailedCondition::MissedProposals
+                || self.max_missed_proposals.is_some())
+    }
+
+    fn threshold(&self, condition: FailedCondition) -> u64 {
+        match condition {
+            FailedCondition::Skips => self.max_skips,
+            FailedCondition::SkipsPerEpoch => self.max_skips_per_epoch,
+            FailedCondition::EpochsSinceFinalization => self.max_epochs_since_finalization,
+            FailedCondition::MissedProposals => self.max_missed_proposals.unwrap_or(0),
+        }
+    }
+
+    /// Checks every condition against `observations`.
+    pub fn evaluate(
+        &self,
+        slot: Slot,
+        observations: &ChainHealthObservations,
+    ) -> BuilderCircuitBreakerStatus {
+        let conditions = FailedCondition::ALL
+            .into_iter()
+            .map(|condition| {
+                let enabled = self.is_enabled(condition);
+                let value = observations.value(condition);
+                let threshold = self.threshold(condition);
+                BuilderCircuitBreakerCondition {
+                    name: condition.as_str().to_string(),
+                    enabled,
+                    value,
+                    threshold,
+                    tripped: enabled && value > threshold,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        BuilderCircuitBreakerStatus {
+            slot,
+            tripped: conditions.iter().any(|condition| condition.tripped),
+            conditions,
+        }
+    }
+}
+
+/// Returns the first condition that tripped the breaker, if any.
+pub fn failed_condition(status: &BuilderCircuitBreakerStatus) -> Option<FailedCondition> {
+    status
+        .conditions
+        .iter()
+        .find(|condition| condition.tripped)
+        .and_then(|condition| condition.name.parse().ok())
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Evaluates the builder circuit breaker for a block proposal in the current slot, and updates
+    /// the circuit breaker metrics.
+    pub fn builder_circuit_breaker_status(
+        &self,
+    ) -> Result<BuilderCircuitBreakerStatus, BeaconChainError> {
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+        let breaker = BuilderCircuitBreaker::from_config(&self.config, slots_per_epoch);
+        let current_slot = self.slot()?;
+        let (head_slot, finalized_epoch) = {
+            let cached_head = self.canonical_head.cached_head();
+            (
+                cached_head.head_slot(),
+                cached_head.finalized_checkpoint().epoch,
+            )
+        };
+
+        // Since we are likely proposing in the current slot, don't count it as a skip.
+        let prev_slot = current_slot.saturating_sub(Slot::new(1));
+        let head_skips = prev_slot.saturating_sub(head_slot).as_u64();
+
+        let current_epoch = current_slot.epoch(slots_per_epoch);
+        let epochs_since_finalization = current_epoch.saturating_sub(finalized_epoch).as_u64();
+
+        let start_slot = current_slot.saturating_sub(breaker.skips_window);
+        let mut skipped_slots = vec![];
+        for slot in start_slot.as_u64()..current_slot.as_u64() {
+            let slot = Slot::new(slot);
+            if self.block_root_at_slot_skips_none(slot)?.is_none() {
+                skipped_slots.push(slot);
+            }
+        }
+
+        let missed_proposals = if breaker.is_enabled(FailedCondition::MissedProposals) {
+            self.count_missed_local_proposals(&skipped_slots)?
+        } else {
+            0
+        };
+
+        let observations = ChainHealthObservations {
+            head_skips,
+            skips_in_window: skipped_slots.len() as u64,
+            epochs_since_finalization,
+            missed_proposals,
+        };
+        let status = breaker.evaluate(current_slot, &observations);
+
+        metrics::set_gauge(
+            &metrics::BUILDER_CIRCUIT_BREAKER_TRIPPED,
+            status.tripped as i64,
+        );
+        for condition in &status.conditions {
+            metrics::set_gauge_vec(
+                &metrics::BUILDER_CIRCUIT_BREAKER_CONDITION_TRIPPED,
+                &[&condition.name],
+                condition.tripped as i64,
+            );
+        }
+
+        Ok(status)
+    }
+
+    /// Counts the `skipped_slots` whose proposer is a validator of this node, i.e. one for which a
+    /// validator client has sent proposer preparation data.
+    ///
+    /// Slots whose proposer is unknown are not counted.
+    fn count_missed_local_proposals(
+        &self,
+        skipped_slots: &[Slot],
+    ) -> Result<u64, BeaconChainError> {
+        let Some(execution_layer) = self.execution_layer.as_ref() else {
+            return Ok(0);
+        };
+        let slots_per_epoch = T::EthSpec::slots_per_epoch();
+
+        let mut proposers_by_epoch = HashMap::new();
+        let mut missed_proposals = 0;
+        for slot in skipped_slots {
+            let epoch = slot.epoch(slots_per_epoch);
+            if !proposers_by_epoch.contains_key(&epoch) {
+                proposers_by_epoch.insert(epoch, self.proposers_at_epoch(epoch)?);
+            }
+            let proposer = proposers_by_epoch
+                .get(&epoch)
+                .and_then(Option::as_ref)
+                .and_then(|proposers| proposers.get(slot.as_usize() % slots_per_epoch as usize));
+
+            if let Some(&proposer) = proposer {
+                if execution_layer.has_proposer_preparation_data_blocking(proposer as u64) {
+                    missed_proposals += 1;
+                }
+            }
+        }
+
+        Ok(missed_proposals)
+    }
+
+    /// Returns the proposers of every slot in `epoch`.
+    ///
+    /// Proposers are computed from the head for the current and later epochs, and read from the
+    /// proposer cache for earlier epochs, returning `None` if they are not cached.
+    fn proposers_at_epoch(&self, epoch: Epoch) -> Result<Option<Vec<usize>>, BeaconChainError> {
+        let (head_epoch, decision_root) = {
+            let cached_head = self.canonical_head.cached_head();
+            let head_state = &cached_head.snapshot.beacon_state;
+            let decision_root = if epoch == Epoch::new(0) {
+                Some(self.genesis_block_root)
+            } else {
+                let decision_slot = epoch
+                    .start_slot(T::EthSpec::slots_per_epoch())
+                    .saturating_sub(Slot::new(1));
+                head_state.get_block_root(decision_slot).ok().copied()
+            };
+            (head_state.current_epoch(), decision_root)
+        };
+
+        if epoch >= head_epoch {
+            compute_proposer_duties_from_head(epoch, self).map(|(proposers, ..)| Some(proposers))
+        } else {
+            Ok(decision_root.and_then(|decision_root| {
+                self.beacon_proposer_cache
+                    .lock()
+                    .get_epoch::<T::EthSpec>(decision_root, epoch)
+                    .map(|proposers| proposers.to_vec())
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> BuilderCircuitBreaker {
+        BuilderCircuitBreaker::from_config(&ChainConfig::default(), 32)
+    }
+
+    fn tripped(status: &BuilderCircuitBreakerStatus) -> Vec<&str> {
+        status
+            .conditions
+            .iter()
+            .filter(|condition| condition.tripped)
+            .map(|condition| condition.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn each_condition_trips_individually() {
+        let breaker = BuilderCircuitBreaker {
+            max_missed_proposals: Some(1),
+            ..breaker()
+        };
+        let healthy = ChainHealthObservations::default();
+        assert!(!breaker.evaluate(Slot::new(1), &healthy).tripped);
+
+        for condition in FailedCondition::ALL {
+            let mut observations = healthy;
+            match condition {
+                FailedCondition::Skips => observations.head_skips = 4,
+                FailedCondition::SkipsPerEpoch => observations.skips_in_window = 9,
+                FailedCondition::EpochsSinceFinalization => {
+                    observations.epochs_since_finalization = 4
+                }
+                FailedCondition::MissedProposals => observations.missed_proposals = 2,
+            }
+            let status = breaker.evaluate(Slot::new(1), &observations);
+            assert!(status.tripped);
+            assert_eq!(tripped(&status), vec![condition.as_str()]);
+            assert_eq!(failed_condition(&status), Some(condition));
+        }
+    }
+
+    #[test]
+    fn disabled_conditions_do_not_trip() {
+        let observations = ChainHealthObservations {
+            head_skips: 4,
+            skips_in_window: 9,
+            epochs_since_finalization: 0,
+            missed_proposals: 2,
+        };
+
+        // The missed proposals condition is disabled unless a threshold is set.
+        let status = BuilderCircuitBreaker {
+            disabled_conditions: vec![FailedCondition::Skips],
+            ..breaker()
+        }
+        .evaluate(Slot::new(1), &observations);
+        assert_eq!(tripped(&status), vec!["skips_per_epoch"]);
+
+        let status = BuilderCircuitBreaker {
+            max_missed_proposals: Some(1),
+            disable_checks: true,
+            ..breaker()
+        }
+        .evaluate(Slot::new(1), &observations);
+        assert!(!status.tripped);
+        assert!(status.conditions.iter().all(|condition| !condition.enabled));
+    }
+}
@@ -43,9 +43,12 @@ use crate::{
     events::ServerSentEventHandler,
     metrics,
     validator_monitor::{get_slot_delay_ms, timestamp_now},
-    BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconSnapshot,
+    BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconForkChoiceStore,
+    BeaconSnapshot,
+};
+use eth2::types::{
+    EventKind, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead, SseProposerDutiesReorg,
 };
-use eth2::types::{EventKind, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead};
 use fork_choice::{
     ExecutionStatus, ForkChoiceStore, ForkChoiceView, ForkchoiceUpdateParameters, ProtoBlock,
     ResetPayloadStatuses,
@@ -55,7 +58,7 @@ use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::{iter::StateRootsIterator, KeyValueStoreOp, StoreItem};
 use task_executor::{JoinHandle, ShutdownReason};
 use types::*;
@@ -228,6 +231,29 @@ impl<E: EthSpec> CachedHead<E> {
     }
 }
 
+/// Describes a single run of `BeaconChain::recompute_head_at_slot`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadRecompute {
+    /// The slot at which fork choice was run.
+    pub slot: Slot,
+    /// The time at which the recompute started, as a duration since the UNIX epoch.
+    pub started_at: Duration,
+    /// The time taken to recompute the head, including updating the execution layer.
+    pub elapsed: Duration,
+    /// The head block root prior to the recompute.
+    pub old_head_block_root: Hash256,
+    /// The head block root after the recompute.
+    pub new_head_block_root: Hash256,
+    /// True if the recompute was requested manually rather than by block import or a timer.
+    pub manual: bool,
+}
+
+impl HeadRecompute {
+    pub fn head_changed(&self) -> bool {
+        self.old_head_block_root != self.new_head_block_root
+    }
+}
+
 /// Represents the "canonical head" of the beacon chain.
 ///
 /// The `cached_head` is elected by the `fork_choice` algorithm contained in this struct.
@@ -249,6 +275,10 @@ pub struct CanonicalHead<T: BeaconChainTypes> {
     ///
     /// This lock **should not be made public**, it should only be used inside this module.
     recompute_head_lock: Mutex<()>,
+    /// The most recent run of `BeaconChain::recompute_head_at_slot`.
+    last_recompute: Mutex<Option<HeadRecompute>>,
+    /// A lock used to prevent concurrent runs of `BeaconChain::prime_justified_balances`.
+    prime_balances_lock: Mutex<()>,
 }
 
 impl<T: BeaconChainTypes> CanonicalHead<T> {
@@ -272,9 +302,16 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             fork_choice: CanonicalHeadRwLock::new(fork_choice),
             cached_head: CanonicalHeadRwLock::new(cached_head),
             recompute_head_lock: Mutex::new(()),
+            last_recompute: Mutex::new(None),
+            prime_balances_lock: Mutex::new(()),
         }
     }
 
+    /// Returns the most recent run of `BeaconChain::recompute_head_at_slot`, if any.
+    pub fn last_recompute(&self) -> Option<HeadRecompute> {
+        *self.last_recompute.lock()
+    }
+
     /// Load a persisted version of `BeaconForkChoice` from the `store` and restore `self` to that
     /// state.
     ///
@@ -471,6 +508,53 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .clone_with(CloneConfig::committee_caches_only())
     }
 
+    /// Loads the balances of the unrealized justified checkpoint into the fork choice balances
+    /// cache, if they're not already present.
+    ///
+    /// The unrealized justified checkpoint is pulled up to the justified checkpoint at the start of
+    /// the next epoch, during `recompute_head`. Loading its balances ahead of time avoids reading a
+    /// state from the database whilst holding the fork choice write-lock. The cache is keyed by
+    /// checkpoint, so the balances are never used if a different checkpoint ends up justified.
+    ///
+    /// The state is read without holding any fork choice lock, so this function may block for some
+    /// time and should not be called from an async context.
+    pub fn prime_justified_balances(&self) -> Result<(), Error> {
+        // Only allow one run at a time, so that the same state isn't loaded twice and blocking
+        // threads don't pile up behind a slow load. The run in progress checks the checkpoint
+        // again before it returns, so it also loads the balances of any newer checkpoint.
+        let Some(_guard) = self.canonical_head.prime_balances_lock.try_lock() else {
+            return Ok(());
+        };
+
+        loop {
+            let checkpoint = {
+                let fork_choice = self.canonical_head.fork_choice_read_lock();
+                let checkpoint = fork_choice.unrealized_justified_checkpoint();
+                if fork_choice.fc_store().has_balances_for(checkpoint) {
+                    return Ok(());
+                }
+                checkpoint
+            };
+
+            let balances = BeaconForkChoiceStore::<T::EthSpec, T::HotStore, T::ColdStore>::load_checkpoint_balances(
+                &self.store,
+                checkpoint,
+            )?;
+
+            self.canonical_head
+                .fork_choice_write_lock()
+                .fc_store_mut()
+                .cache_checkpoint_balances(checkpoint, balances.effective_balances);
+
+            debug!(
+                self.log,
+                "Cached justified balances";
+                "epoch" => checkpoint.epoch,
+                "root" => ?checkpoint.root,
+            );
+        }
+    }
+
     /// Execute the fork choice algorithm and enthrone the result as the canonical head.
     ///
     /// This method replaces the old `BeaconChain::fork_choice` method.
@@ -498,6 +582,49 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// situation can be rectified. We avoid returning an error here so that calling functions
     /// can't abort block import because an error is returned here.
     pub async fn recompute_head_at_slot(self: &Arc<Self>, current_slot: Slot) {
+        self.recompute_head_at_slot_and_record(current_slot, false)
+            .await;
+    }
+
+    /// Recompute the head at the current slot on request of the user, e.g. via the HTTP API.
+    ///
+    /// Returns a description of the recompute, which is also available from
+    /// `CanonicalHead::last_recompute`.
+    pub async fn recompute_head_manually(self: &Arc<Self>) -> Result<HeadRecompute, Error> {
+        let current_slot = self.slot()?;
+        Ok(self
+            .recompute_head_at_slot_and_record(current_slot, true)
+            .await)
+    }
+
+    /// Run `recompute_head_at_slot` and record the outcome in `CanonicalHead::last_recompute`.
+    ///
+    /// The old and new head are read outside of the `recompute_head_lock`, so a concurrent
+    /// recompute may be attributed to this one.
+    async fn recompute_head_at_slot_and_record(
+        self: &Arc<Self>,
+        current_slot: Slot,
+        manual: bool,
+    ) -> HeadRecompute {
+        let started_at = timestamp_now();
+        let timer = Instant::now();
+        let old_head_block_root = self.canonical_head.cached_head().head_block_root();
+
+        self.recompute_head_at_slot_inner(current_slot).await;
+
+        let recompute = HeadRecompute {
+            slot: current_slot,
+            started_at,
+            elapsed: timer.elapsed(),
+            old_head_block_root,
+            new_head_block_root: self.canonical_head.cached_head().head_block_root(),
+            manual,
+        };
+        *self.canonical_head.last_recompute.lock() = Some(recompute);
+        recompute
+    }
+
+    async fn recompute_head_at_slot_inner(self: &Arc<Self>, current_slot: Slot) {
         metrics::inc_counter(&metrics::FORK_CHOICE_REQUESTS);
         let _timer = metrics::start_timer(&metrics::FORK_CHOICE_TIMES);
 
@@ -950,6 +1077,47 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // Register a server-sent-event for each epoch with proposer duties which have changed.
+        if let Some(event_handler) = self
+            .event_handler
+            .as_ref()
+            .filter(|handler| handler.has_proposer_duties_reorg_subscribers())
+        {
+            let current_slot = self.slot()?;
+            let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
+            for epoch in [current_epoch, current_epoch + 1] {
+                let decision_slot = epoch
+                    .start_slot(T::EthSpec::slots_per_epoch())
+                    .saturating_sub(1_u64);
+                // Until the decision slot has passed, a new head is expected to change the
+                // dependent root.
+                if decision_slot >= current_slot {
+                    continue;
+                }
+                let old_root = proposer_duties_dependent_root(old_snapshot, decision_slot);
+                let new_root = proposer_duties_dependent_root(new_snapshot, decision_slot);
+                match (old_root, new_root) {
+                    (Ok(old_root), Ok(new_root)) if old_root != new_root => {
+                        event_handler.register(EventKind::ProposerDutiesReorg(
+                            SseProposerDutiesReorg {
+                                epoch,
+                                dependent_root: new_root,
+                            },
+                        ));
+                    }
+                    (Ok(_), Ok(_)) => {}
+                    (Err(e), _) | (_, Err(e)) => {
+                        debug!(
+                            self.log,
+                            "Unable to find proposer duties dependent root";
+                            "error" => ?e,
+                            "epoch" => epoch,
+                        );
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -1058,12 +1226,6 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.head_tracker.clone(),
         )?;
 
-        // Prune blobs in the background.
-        if let Some(data_availability_boundary) = self.data_availability_boundary() {
-            self.store_migrator
-                .process_prune_blobs(data_availability_boundary);
-        }
-
         // Take a write-lock on the canonical head and signal for it to prune.
         self.canonical_head.fork_choice_write_lock().prune()?;
 
@@ -1087,6 +1249,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     }
 }
 
+/// Returns the root of the block at `decision_slot` in the chain of `snapshot`, which is the
+/// dependent root of the proposer duties for the epoch following `decision_slot`.
+fn proposer_duties_dependent_root<E: EthSpec>(
+    snapshot: &BeaconSnapshot<E>,
+    decision_slot: Slot,
+) -> Result<Hash256, BeaconStateError> {
+    if snapshot.beacon_block.slot() <= decision_slot {
+        Ok(snapshot.beacon_block_root)
+    } else {
+        snapshot.beacon_state.get_block_root(decision_slot).copied()
+    }
+}
+
 /// Check to see if the `finalized_proto_block` has an invalid execution payload. If so, shut down
 /// Lighthouse.
 ///
@@ -1,3 +1,4 @@
+pub use execution_layer::FailedCondition;
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
@@ -5,6 +6,9 @@ use types::{Checkpoint, Epoch, ProgressiveBalancesMode};
 
 pub const DEFAULT_RE_ORG_THRESHOLD: ReOrgThreshold = ReOrgThreshold(20);
 pub const DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION: Epoch = Epoch::new(2);
+/// Default minimum percentage of stake attesting to the correct target in the previous epoch.
+pub const DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD: u64 = 80;
+pub const DEFAULT_RE_ORG_MARGIN: ReOrgThreshold = ReOrgThreshold(0);
 /// Default to 1/12th of the slot, which is 1 second on mainnet.
 pub const DEFAULT_RE_ORG_CUTOFF_DENOMINATOR: u32 = 12;
 pub const DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT: u64 = 250;
@@ -33,6 +37,12 @@ pub struct ChainConfig {
     pub max_network_size: usize,
     /// Maximum percentage of committee weight at which to attempt re-orging the canonical head.
     pub re_org_threshold: Option<ReOrgThreshold>,
+    /// Percentage of committee weight by which the head's weight must fall below
+    /// `re_org_threshold` in order to attempt a re-org.
+    pub re_org_margin: ReOrgThreshold,
+    /// Minimum percentage of active stake attesting to the correct target in the previous epoch
+    /// required to attempt a re-org. A value of 0 disables the check.
+    pub re_org_participation_threshold: u64,
     /// Maximum number of epochs since finalization for attempting a proposer re-org.
     pub re_org_max_epochs_since_finalization: Epoch,
     /// Maximum delay after the start of the slot at which to propose a reorging block.
@@ -54,6 +64,14 @@ pub struct ChainConfig {
     /// Number of epochs since finalization before the BN refuses to use connected builders during
     /// payload construction.
     pub builder_fallback_epochs_since_finalization: usize,
+    /// Number of recent slots in which skips are counted for `builder_fallback_skips_per_epoch` and
+    /// `builder_fallback_missed_proposals`. Defaults to `SLOTS_PER_EPOCH` if `None`.
+    pub builder_fallback_skips_window: Option<usize>,
+    /// Number of proposals missed by this node's validators in the recent window of slots before
+    /// the BN refuses to use connected builders. The check is disabled if `None`.
+    pub builder_fallback_missed_proposals: Option<usize>,
+    /// Chain health conditions which are not checked when deciding whether to use the builder API.
+    pub builder_fallback_disabled_conditions: Vec<FailedCondition>,
     /// Whether any chain health checks should be considered when deciding whether to use the builder API.
     pub builder_fallback_disable_checks: bool,
     /// When set to `true`, forget any valid/invalid/optimistic statuses in fork choice during start
@@ -75,6 +93,9 @@ pub struct ChainConfig {
     /// If using a weak-subjectivity sync, whether we should download blocks all the way back to
     /// genesis.
     pub genesis_backfill: bool,
+    /// Only backfill blocks within the data availability window, rather than the weak subjectivity
+    /// period. Ignored if `genesis_backfill` is set.
+    pub backfill_to_data_availability_boundary: bool,
     /// Whether to send payload attributes every slot, regardless of connected proposers.
     ///
     /// This is useful for block builders and testing.
@@ -83,6 +104,13 @@ pub struct ChainConfig {
     pub progressive_balances_mode: ProgressiveBalancesMode,
     /// Number of epochs between each migration of data from the hot database to the freezer.
     pub epochs_per_migration: u64,
+    /// Number of recent epochs for which block and attestation timing statistics are retained.
+    pub epoch_timings_retention_epochs: u64,
+    /// Percentage of observed head votes which must be for the canonical block, below which a
+    /// warning is logged.
+    pub head_vote_accuracy_warn_threshold: u64,
+    /// Maximum approximate memory used by the cache of observed aggregate attestations, in bytes.
+    pub observed_attestations_max_bytes: usize,
 }
 
 impl Default for ChainConfig {
@@ -94,6 +122,8 @@ impl Default for ChainConfig {
             enable_lock_timeouts: true,
             max_network_size: 10 * 1_048_576, // 10M
             re_org_threshold: Some(DEFAULT_RE_ORG_THRESHOLD),
+            re_org_margin: DEFAULT_RE_ORG_MARGIN,
+            re_org_participation_threshold: DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD,
             re_org_max_epochs_since_finalization: DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
             re_org_cutoff_millis: None,
             re_org_disallowed_offsets: DisallowedReOrgOffsets::default(),
@@ -102,6 +132,9 @@ impl Default for ChainConfig {
             builder_fallback_skips: 3,
             builder_fallback_skips_per_epoch: 8,
             builder_fallback_epochs_since_finalization: 3,
+            builder_fallback_skips_window: None,
+            builder_fallback_missed_proposals: None,
+            builder_fallback_disabled_conditions: vec![],
             builder_fallback_disable_checks: false,
             always_reset_payload_statuses: false,
             paranoid_block_proposal: false,
@@ -111,9 +144,15 @@ impl Default for ChainConfig {
             optimistic_finalized_sync: true,
             shuffling_cache_size: crate::shuffling_cache::DEFAULT_CACHE_SIZE,
             genesis_backfill: false,
+            backfill_to_data_availability_boundary: false,
             always_prepare_payload: false,
             progressive_balances_mode: ProgressiveBalancesMode::Checked,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
+            epoch_timings_retention_epochs: crate::epoch_timings::DEFAULT_RETENTION_EPOCHS,
+            head_vote_accuracy_warn_threshold:
+                crate::head_vote_accuracy::DEFAULT_WARN_THRESHOLD_PERCENT,
+            observed_attestations_max_bytes:
+                crate::observed_aggregates::DEFAULT_OBSERVED_ATTESTATIONS_MAX_BYTES,
         }
     }
 }
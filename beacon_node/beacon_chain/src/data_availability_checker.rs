@@ -1,4 +1,6 @@
-use crate::blob_verification::{verify_kzg_for_blob, verify_kzg_for_blob_list, GossipVerifiedBlob};
+use crate::blob_verification::{
+    verify_kzg_for_blob, verify_kzg_for_blob_list, GossipVerifiedBlob, KzgVerifiedBlob,
+};
 use crate::block_verification_types::{
     AvailabilityPendingExecutedBlock, AvailableExecutedBlock, RpcBlock,
 };
@@ -8,7 +10,7 @@ pub use crate::data_availability_checker::availability_view::{
 pub use crate::data_availability_checker::child_components::ChildComponents;
 use crate::data_availability_checker::overflow_lru_cache::OverflowLRUCache;
 use crate::data_availability_checker::processing_cache::ProcessingCache;
-use crate::{BeaconChain, BeaconChainTypes, BeaconStore};
+use crate::{metrics, BeaconChain, BeaconChainTypes, BeaconStore};
 use kzg::Kzg;
 use parking_lot::RwLock;
 pub use processing_cache::ProcessingComponents;
@@ -22,7 +24,9 @@ use task_executor::TaskExecutor;
 use types::beacon_block_body::{KzgCommitmentOpts, KzgCommitments};
 use types::blob_sidecar::{BlobIdentifier, BlobSidecar, FixedBlobSidecarList};
 use types::consts::deneb::MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS;
-use types::{BlobSidecarList, ChainSpec, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+use types::{
+    BlobSidecarList, ChainSpec, Epoch, EthSpec, Hash256, SignedBeaconBlock, SignedBlobSidecar, Slot,
+};
 
 mod availability_view;
 mod child_components;
@@ -190,6 +194,29 @@ impl<T: BeaconChainTypes> DataAvailabilityChecker<T> {
         self.availability_cache.peek_blob(blob_id)
     }
 
+    /// Take the blobs for `block_root` which are identical to one of `blobs` from the
+    /// availability cache.
+    ///
+    /// Blobs that arrive before their block (e.g. on gossip) are verified and cached until the
+    /// block is executed. A caller about to import the same blobs alongside the block claims the
+    /// cached copies, so that the blobs are imported once as a single set. Cached blobs which
+    /// differ from all of `blobs` are left in the cache.
+    pub fn take_cached_blobs(
+        &self,
+        block_root: Hash256,
+        blobs: &[SignedBlobSidecar<T::EthSpec>],
+    ) -> Result<Vec<Arc<BlobSidecar<T::EthSpec>>>, AvailabilityCheckError> {
+        let taken_blobs = self
+            .availability_cache
+            .take_blobs(block_root, |cached_blob| {
+                blobs.iter().any(|blob| *blob.message == *cached_blob)
+            })?;
+        Ok(taken_blobs
+            .into_iter()
+            .map(KzgVerifiedBlob::to_blob)
+            .collect())
+    }
+
     /// Put a list of blobs received via RPC into the availability cache. This performs KZG
     /// verification on the blobs in the list.
     pub fn put_rpc_blobs(
@@ -200,7 +227,8 @@ impl<T: BeaconChainTypes> DataAvailabilityChecker<T> {
         let mut verified_blobs = vec![];
         if let Some(kzg) = self.kzg.as_ref() {
             for blob in blobs.iter().flatten() {
-                verified_blobs.push(verify_kzg_for_blob(blob.clone(), kzg)?)
+                verified_blobs
+                    .push(verify_kzg_for_blob(blob.clone(), kzg).map_err(count_check_failure)?)
             }
         } else {
             return Err(AvailabilityCheckError::KzgNotInitialized);
@@ -221,7 +249,7 @@ impl<T: BeaconChainTypes> DataAvailabilityChecker<T> {
     ) -> Result<Availability<T::EthSpec>, AvailabilityCheckError> {
         // Verify the KZG commitments.
         let kzg_verified_blob = if let Some(kzg) = self.kzg.as_ref() {
-            verify_kzg_for_blob(gossip_blob.to_blob(), kzg)?
+            verify_kzg_for_blob(gossip_blob.to_blob(), kzg).map_err(count_check_failure)?
         } else {
             return Err(AvailabilityCheckError::KzgNotInitialized);
         };
@@ -265,7 +293,7 @@ impl<T: BeaconChainTypes> DataAvailabilityChecker<T> {
                         .kzg
                         .as_ref()
                         .ok_or(AvailabilityCheckError::KzgNotInitialized)?;
-                    verify_kzg_for_blob_list(&blob_list, kzg)?;
+                    verify_kzg_for_blob_list(&blob_list, kzg).map_err(count_check_failure)?;
                     Some(blob_list)
                 } else {
                     None
@@ -608,3 +636,9 @@ impl Into<Vec<BlobIdentifier>> for MissingBlobs {
         }
     }
 }
+
+/// Records a failed data availability check, passing the error through.
+fn count_check_failure(e: AvailabilityCheckError) -> AvailabilityCheckError {
+    metrics::inc_counter(&metrics::DATA_AVAILABILITY_CHECK_FAILURES);
+    e
+}
@@ -411,6 +411,45 @@ impl<T: BeaconChainTypes> OverflowLRUCache<T> {
         }
     }
 
+    /// Removes and returns the blobs cached for `block_root` for which `take` returns `true`,
+    /// leaving any other components in the cache.
+    pub fn take_blobs<F>(
+        &self,
+        block_root: Hash256,
+        mut take: F,
+    ) -> Result<Vec<KzgVerifiedBlob<T::EthSpec>>, AvailabilityCheckError>
+    where
+        F: FnMut(&BlobSidecar<T::EthSpec>) -> bool,
+    {
+        let mut write_lock = self.critical.write();
+        let Some(mut pending_components) =
+            write_lock.pop_pending_components(block_root, &self.overflow_store)?
+        else {
+            return Ok(vec![]);
+        };
+
+        let mut taken_blobs = vec![];
+        for blob_opt in pending_components.verified_blobs.iter_mut() {
+            if blob_opt.as_ref().map_or(false, |blob| take(blob.as_blob())) {
+                taken_blobs.extend(blob_opt.take());
+            }
+        }
+
+        if pending_components.executed_block.is_some()
+            || pending_components
+                .verified_blobs
+                .iter()
+                .any(Option::is_some)
+        {
+            write_lock.put_pending_components(
+                block_root,
+                pending_components,
+                &self.overflow_store,
+            )?;
+        }
+        Ok(taken_blobs)
+    }
+
     pub fn put_kzg_verified_blobs(
         &self,
         block_root: Hash256,
@@ -1099,6 +1138,77 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn overflow_cache_test_take_blobs() {
+        type E = MinimalEthSpec;
+        type T = DiskHarnessType<E>;
+        let capacity = 4;
+        let (harness, cache) = setup_harness_and_cache::<E, T>(capacity).await;
+
+        let kzg = harness
+            .chain
+            .kzg
+            .as_ref()
+            .cloned()
+            .expect("kzg should exist");
+
+        // the mock execution layer includes a random number of blobs in each payload
+        let mut attempts = 0;
+        let (pending_block, blobs) = loop {
+            let (pending_block, blobs) = availability_pending_block(&harness).await;
+            if blobs.len() > 1 {
+                break (pending_block, blobs);
+            }
+            attempts += 1;
+            assert!(attempts < 32, "no block with multiple blobs");
+        };
+        let root = pending_block.import_data.block_root;
+
+        let kzg_verified_blobs = blobs
+            .into_iter()
+            .map(|gossip_blob| {
+                verify_kzg_for_blob(gossip_blob.to_blob(), kzg.as_ref()).expect("kzg should verify")
+            })
+            .collect::<Vec<_>>();
+        let num_blobs = kzg_verified_blobs.len();
+        cache
+            .put_kzg_verified_blobs(root, kzg_verified_blobs)
+            .expect("should put blobs");
+
+        let taken = cache
+            .take_blobs(root, |blob| blob.index == 0)
+            .expect("should take blob");
+        assert_eq!(taken.len(), 1, "should take only the matching blob");
+        assert_eq!(taken[0].blob_index(), 0);
+        assert!(
+            cache
+                .peek_blob(&BlobIdentifier {
+                    block_root: root,
+                    index: 0
+                })
+                .expect("should peek")
+                .is_none(),
+            "taken blob should be removed from the cache"
+        );
+        assert!(
+            cache
+                .peek_blob(&BlobIdentifier {
+                    block_root: root,
+                    index: 1
+                })
+                .expect("should peek")
+                .is_some(),
+            "other blobs should remain in the cache"
+        );
+
+        let taken = cache.take_blobs(root, |_| true).expect("should take blobs");
+        assert_eq!(taken.len(), num_blobs - 1, "should take remaining blobs");
+        assert!(
+            cache.critical.read().in_memory.is_empty(),
+            "empty pending components should be removed"
+        );
+    }
+
     #[tokio::test]
     async fn overflow_cache_test_overflow() {
         type E = MinimalEthSpec;
@@ -1,10 +1,12 @@
 use crate::data_availability_checker::AvailableBlock;
+use crate::reportable_cache::{CacheCounters, ReportableCache};
 use crate::{
     attester_cache::{CommitteeLengths, Error},
     metrics,
 };
 use parking_lot::RwLock;
 use proto_array::Block as ProtoBlock;
+use ssz::Encode;
 use std::sync::Arc;
 use types::blob_sidecar::BlobSidecarList;
 use types::*;
@@ -38,6 +40,7 @@ pub struct CacheItem<E: EthSpec> {
 #[derive(Default)]
 pub struct EarlyAttesterCache<E: EthSpec> {
     item: RwLock<Option<CacheItem<E>>>,
+    counters: CacheCounters,
 }
 
 impl<E: EthSpec> EarlyAttesterCache<E> {
@@ -102,15 +105,18 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
         let item = if let Some(item) = lock.as_ref() {
             item
         } else {
+            self.counters.record(false);
             return Ok(None);
         };
 
         let request_epoch = request_slot.epoch(E::slots_per_epoch());
         if request_epoch != item.epoch {
+            self.counters.record(false);
             return Ok(None);
         }
 
         if request_slot < item.block.slot() {
+            self.counters.record(false);
             return Ok(None);
         }
 
@@ -118,6 +124,7 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
             .committee_lengths
             .get_committee_count_per_slot::<E>(spec)?;
         if request_index >= committee_count as u64 {
+            self.counters.record(false);
             return Ok(None);
         }
 
@@ -139,6 +146,7 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
         };
 
         metrics::inc_counter(&metrics::BEACON_EARLY_ATTESTER_CACHE_HITS);
+        self.counters.record(true);
 
         Ok(Some(attestation))
     }
@@ -178,3 +186,28 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
             .map(|item| item.proto_block.clone())
     }
 }
+
+/// Lookups are counted by `EarlyAttesterCache::try_attest`.
+impl<E: EthSpec> ReportableCache for EarlyAttesterCache<E> {
+    fn num_entries(&self) -> usize {
+        usize::from(self.item.read().is_some())
+    }
+
+    fn byte_estimate(&self) -> usize {
+        self.item.read().as_ref().map_or(0, |item| {
+            item.block.ssz_bytes_len() + item.blobs.as_ref().map_or(0, Encode::ssz_bytes_len)
+        })
+    }
+
+    fn hits(&self) -> Option<u64> {
+        Some(self.counters.hits())
+    }
+
+    fn misses(&self) -> Option<u64> {
+        Some(self.counters.misses())
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(1)
+    }
+}
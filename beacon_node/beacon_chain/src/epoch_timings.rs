@@ -0,0 +1,211 @@
+//! Provides the `EpochTimingsCache`, which aggregates timing statistics for the blocks imported in
+//! recent epochs.
+//!
+//! For each epoch the cache records the delay between the start of each block's slot and the time
+//! the block arrived via gossip, the inclusion distance of every attestation packed into those
+//! blocks and the number of blobs imported with them. Only the most recent epochs are retained, so
+//! that the cache remains small regardless of how long the node has been running.
+
+use crate::metrics;
+use eth2::lighthouse::{BlockArrivalDelays, EpochTimings};
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use types::{Epoch, EthSpec, Slot};
+
+/// The default number of epochs for which timings are retained.
+pub const DEFAULT_RETENTION_EPOCHS: u64 = 64;
+
+#[derive(Default)]
+struct EpochData {
+    arrival_delays: Vec<Duration>,
+    inclusion_distances: BTreeMap<u64, u64>,
+    blobs: u64,
+}
+
+impl EpochData {
+    fn timings(&self, epoch: Epoch) -> EpochTimings {
+        let block_arrival_delays = if self.arrival_delays.is_empty() {
+            None
+        } else {
+            let mut delays = self
+                .arrival_delays
+                .iter()
+                .map(|delay| delay.as_millis() as u64)
+                .collect::<Vec<_>>();
+            delays.sort_unstable();
+            let blocks = delays.len() as u64;
+            Some(BlockArrivalDelays {
+                blocks,
+                min_ms: delays[0],
+                mean_ms: delays.iter().sum::<u64>() / blocks,
+                median_ms: delays[delays.len() / 2],
+                max_ms: delays[delays.len() - 1],
+            })
+        };
+
+        EpochTimings {
+            epoch,
+            block_arrival_delays,
+            inclusion_distances: self.inclusion_distances.clone(),
+            blobs: self.blobs,
+        }
+    }
+}
+
+pub struct EpochTimingsCache {
+    retention_epochs: u64,
+    epochs: RwLock<HashMap<Epoch, EpochData>>,
+}
+
+impl EpochTimingsCache {
+    pub fn new(retention_epochs: u64) -> Self {
+        Self {
+            retention_epochs,
+            epochs: <_>::default(),
+        }
+    }
+
+    /// Records the timings of a block imported at `current_slot`.
+    ///
+    /// `arrival_delay` is the time between the start of `block_slot` and the block being observed
+    /// on gossip, if it was. `attestation_slots` are the slots of the attestations included in the
+    /// block, and `num_blobs` the number of blobs imported with it. Blocks from epochs outside the
+    /// retention period (e.g. during sync) are ignored.
+    pub fn register_block<E: EthSpec>(
+        &self,
+        block_slot: Slot,
+        current_slot: Slot,
+        arrival_delay: Option<Duration>,
+        attestation_slots: impl Iterator<Item = Slot>,
+        num_blobs: usize,
+    ) {
+        let epoch = block_slot.epoch(E::slots_per_epoch());
+        let current_epoch = current_slot.epoch(E::slots_per_epoch());
+        if !self.is_retained(epoch, current_epoch) {
+            return;
+        }
+
+        let mut epochs = self.epochs.write();
+        let data = epochs.entry(epoch).or_default();
+        data.blobs += num_blobs as u64;
+
+        if let Some(delay) = arrival_delay {
+            metrics::observe_duration(&metrics::EPOCH_TIMINGS_BLOCK_ARRIVAL_DELAY, delay);
+            data.arrival_delays.push(delay);
+        }
+
+        for attestation_slot in attestation_slots {
+            let distance = block_slot.saturating_sub(attestation_slot).as_u64();
+            metrics::observe(
+                &metrics::EPOCH_TIMINGS_ATTESTATION_INCLUSION_DISTANCE,
+                distance as f64,
+            );
+            *data.inclusion_distances.entry(distance).or_default() += 1;
+        }
+
+        let previous_epoch_blobs = current_epoch
+            .as_u64()
+            .checked_sub(1)
+            .and_then(|previous_epoch| epochs.get(&Epoch::new(previous_epoch)))
+            .map_or(0, |data| data.blobs);
+        metrics::set_gauge(
+            &metrics::EPOCH_TIMINGS_PREVIOUS_EPOCH_BLOBS,
+            previous_epoch_blobs as i64,
+        );
+    }
+
+    /// Returns the timings for `epoch`, if any blocks from it have been registered.
+    pub fn get(&self, epoch: Epoch) -> Option<EpochTimings> {
+        self.epochs
+            .read()
+            .get(&epoch)
+            .map(|data| data.timings(epoch))
+    }
+
+    /// Removes the timings of all epochs that fall outside the retention period.
+    pub fn prune(&self, current_epoch: Epoch) {
+        self.epochs
+            .write()
+            .retain(|epoch, _| self.is_retained(*epoch, current_epoch));
+    }
+
+    fn is_retained(&self, epoch: Epoch, current_epoch: Epoch) -> bool {
+        epoch + self.retention_epochs > current_epoch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    fn slot(epoch: u64, slot_in_epoch: u64) -> Slot {
+        Epoch::new(epoch).start_slot(E::slots_per_epoch()) + slot_in_epoch
+    }
+
+    #[test]
+    fn aggregates_per_epoch() {
+        let cache = EpochTimingsCache::new(DEFAULT_RETENTION_EPOCHS);
+        let current_slot = slot(1, 7);
+
+        for (i, millis) in [400, 100, 300].into_iter().enumerate() {
+            let block_slot = slot(1, i as u64 + 1);
+            cache.register_block::<E>(
+                block_slot,
+                current_slot,
+                Some(Duration::from_millis(millis)),
+                vec![block_slot - 1, block_slot - 2].into_iter(),
+                i,
+            );
+        }
+        // A block that was not observed on gossip contributes only its attestations.
+        cache.register_block::<E>(
+            slot(1, 5),
+            current_slot,
+            None,
+            vec![slot(1, 4)].into_iter(),
+            0,
+        );
+
+        let timings = cache.get(Epoch::new(1)).unwrap();
+        assert_eq!(
+            timings.block_arrival_delays,
+            Some(BlockArrivalDelays {
+                blocks: 3,
+                min_ms: 100,
+                mean_ms: 266,
+                median_ms: 300,
+                max_ms: 400,
+            })
+        );
+        assert_eq!(
+            timings.inclusion_distances,
+            BTreeMap::from([(1, 4), (2, 3)])
+        );
+        assert_eq!(timings.blobs, 3);
+        assert!(cache.get(Epoch::new(0)).is_none());
+    }
+
+    #[test]
+    fn drops_epochs_outside_retention() {
+        let cache = EpochTimingsCache::new(2);
+        let delay = Some(Duration::from_millis(100));
+
+        // Too old to be retained at all.
+        cache.register_block::<E>(slot(0, 1), slot(2, 0), delay, std::iter::empty(), 0);
+        assert!(cache.get(Epoch::new(0)).is_none());
+
+        cache.register_block::<E>(slot(1, 1), slot(2, 0), delay, std::iter::empty(), 0);
+        cache.register_block::<E>(slot(2, 1), slot(2, 1), delay, std::iter::empty(), 0);
+
+        cache.prune(Epoch::new(2));
+        assert!(cache.get(Epoch::new(1)).is_some());
+
+        cache.prune(Epoch::new(3));
+        assert!(cache.get(Epoch::new(1)).is_none());
+        assert!(cache.get(Epoch::new(2)).is_some());
+    }
+}
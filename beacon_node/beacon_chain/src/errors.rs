@@ -11,6 +11,7 @@ use crate::observed_aggregates::Error as ObservedAttestationsError;
 use crate::observed_attesters::Error as ObservedAttestersError;
 use crate::observed_blob_sidecars::Error as ObservedBlobSidecarsError;
 use crate::observed_block_producers::Error as ObservedBlockProducersError;
+use eth2::lighthouse::CacheName;
 use execution_layer::PayloadStatus;
 use fork_choice::ExecutionStatus;
 use futures::channel::mpsc::TrySendError;
@@ -80,6 +81,10 @@ pub enum BeaconChainError {
         requested_slot: Slot,
         max_task_runtime: Duration,
     },
+    CannotAdvanceStateBackwards {
+        state_slot: Slot,
+        requested_slot: Slot,
+    },
     MissingFinalizedStateRoot(Slot),
     /// Returned when an internal check fails, indicating corrupt data.
     InvariantViolated(String),
@@ -89,6 +94,11 @@ pub enum BeaconChainError {
     AttestationCacheLockTimeout,
     ValidatorPubkeyCacheLockTimeout,
     SnapshotCacheLockTimeout,
+    CacheNotResizable(CacheName),
+    InvalidCacheCapacity {
+        name: CacheName,
+        capacity: usize,
+    },
     IncorrectStateForAttestation(RelativeEpochError),
     InvalidValidatorPubkeyBytes(bls::Error),
     ValidatorPubkeyCacheIncomplete(usize),
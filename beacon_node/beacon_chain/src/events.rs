@@ -1,4 +1,5 @@
 pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+use proto_array::MAX_PENDING_EXECUTION_STATUS_CHANGES;
 use slog::{trace, Logger};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
@@ -17,7 +18,9 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     contribution_tx: Sender<EventKind<T>>,
     payload_attributes_tx: Sender<EventKind<T>>,
     late_head: Sender<EventKind<T>>,
+    proposer_duties_reorg_tx: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
+    execution_optimistic_status_tx: Sender<EventKind<T>>,
     log: Logger,
 }
 
@@ -40,7 +43,12 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (contribution_tx, _) = broadcast::channel(capacity);
         let (payload_attributes_tx, _) = broadcast::channel(capacity);
         let (late_head, _) = broadcast::channel(capacity);
+        let (proposer_duties_reorg_tx, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        // A single payload verification may change the status of many blocks at once, so ensure
+        // that every change from one verification can be buffered.
+        let (execution_optimistic_status_tx, _) =
+            broadcast::channel(capacity.max(MAX_PENDING_EXECUTION_STATUS_CHANGES));
 
         Self {
             attestation_tx,
@@ -53,7 +61,9 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             contribution_tx,
             payload_attributes_tx,
             late_head,
+            proposer_duties_reorg_tx,
             block_reward_tx,
+            execution_optimistic_status_tx,
             log,
         }
     }
@@ -108,10 +118,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .late_head
                 .send(kind)
                 .map(|count| log_count("late head", count)),
+            EventKind::ProposerDutiesReorg(_) => self
+                .proposer_duties_reorg_tx
+                .send(kind)
+                .map(|count| log_count("proposer duties reorg", count)),
             EventKind::BlockReward(_) => self
                 .block_reward_tx
                 .send(kind)
                 .map(|count| log_count("block reward", count)),
+            EventKind::ExecutionOptimisticStatus(_) => self
+                .execution_optimistic_status_tx
+                .send(kind)
+                .map(|count| log_count("execution optimistic status", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
@@ -158,10 +176,18 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.late_head.subscribe()
     }
 
+    pub fn subscribe_proposer_duties_reorg(&self) -> Receiver<EventKind<T>> {
+        self.proposer_duties_reorg_tx.subscribe()
+    }
+
     pub fn subscribe_block_reward(&self) -> Receiver<EventKind<T>> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_execution_optimistic_status(&self) -> Receiver<EventKind<T>> {
+        self.execution_optimistic_status_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -202,7 +228,15 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.late_head.receiver_count() > 0
     }
 
+    pub fn has_proposer_duties_reorg_subscribers(&self) -> bool {
+        self.proposer_duties_reorg_tx.receiver_count() > 0
+    }
+
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
+
+    pub fn has_execution_optimistic_status_subscribers(&self) -> bool {
+        self.execution_optimistic_status_tx.receiver_count() > 0
+    }
 }
@@ -388,6 +388,9 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
 
 /// Gets an execution payload for inclusion in a block.
 ///
+/// The local payload of a blinded block is only kept in the execution layer's payload cache, for
+/// unblinding once the block is signed, if `cache_local_payload` is `true`.
+///
 /// ## Errors
 ///
 /// Will return an error when using a pre-merge fork `state`. Ensure to only run this function
@@ -407,6 +410,7 @@ pub fn get_execution_payload<
     parent_block_root: Hash256,
     proposer_index: u64,
     builder_params: BuilderParams,
+    cache_local_payload: bool,
 ) -> Result<PreparePayloadHandle<T::EthSpec, Payload>, BlockProductionError> {
     // Compute all required values from the `state` now to avoid needing to pass it into a spawned
     // task.
@@ -450,6 +454,7 @@ pub fn get_execution_payload<
                     builder_params,
                     withdrawals,
                     parent_beacon_block_root,
+                    cache_local_payload,
                 )
                 .await
             },
@@ -485,6 +490,7 @@ pub async fn prepare_execution_payload<T, Payload>(
     builder_params: BuilderParams,
     withdrawals: Option<Vec<Withdrawal>>,
     parent_beacon_block_root: Option<Hash256>,
+    cache_local_payload: bool,
 ) -> Result<BlockProposalContents<T::EthSpec, Payload>, BlockProductionError>
 where
     T: BeaconChainTypes,
@@ -565,6 +571,7 @@ where
             builder_params,
             fork,
             &chain.spec,
+            cache_local_payload,
         )
         .await
         .map_err(BlockProductionError::GetPayloadFailed)?;
@@ -0,0 +1,131 @@
+//! Provides functions for fetching the blobs of a block from the execution layer's mempool.
+//!
+//! A block frequently arrives via gossip before its blob sidecars. The blobs' transactions have
+//! usually already been seen by our execution node, so rather than waiting for gossip or RPC we
+//! can request them via `engine_getBlobsV1`. Blobs obtained this way are KZG-verified and placed
+//! in the availability cache, but they are never published to the network.
+use crate::data_availability_checker::{Availability, AvailabilityCheckError};
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use execution_layer::Error as ExecutionLayerError;
+use state_processing::per_block_processing::deneb::kzg_commitment_to_versioned_hash;
+use std::sync::Arc;
+use types::blob_sidecar::{BlobIdentifier, BlobSidecar, FixedBlobSidecarList};
+use types::{Hash256, SignedBeaconBlock};
+
+#[derive(Debug)]
+pub enum FetchBlobsError {
+    RequestFailed(ExecutionLayerError),
+    /// The execution layer returned a different number of entries than was requested.
+    InconsistentResponseLength {
+        requested: usize,
+        received: usize,
+    },
+    /// The blobs from the execution layer could not be added to the availability cache, e.g.
+    /// because a KZG proof was invalid.
+    AvailabilityCheck(AvailabilityCheckError),
+}
+
+/// Request the blobs of `block` that are missing from the availability cache from the execution
+/// layer and, if any are returned, add them to the availability cache.
+///
+/// Returns `Ok(None)` if no blobs were requested or none were returned by the execution layer.
+/// Otherwise, returns the availability of the block after the blobs were added.
+pub async fn fetch_blobs_from_execution_layer<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    block_root: Hash256,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+) -> Result<Option<Availability<T::EthSpec>>, FetchBlobsError> {
+    let Some(execution_layer) = chain.execution_layer.as_ref() else {
+        return Ok(None);
+    };
+    let Ok(commitments) = block.message().body().blob_kzg_commitments() else {
+        return Ok(None);
+    };
+
+    // Only request the blobs that haven't already arrived via gossip or RPC.
+    let missing_indices = (0..commitments.len())
+        .filter(|&index| {
+            let blob_id = BlobIdentifier {
+                block_root,
+                index: index as u64,
+            };
+            !matches!(
+                chain.data_availability_checker.get_blob(&blob_id),
+                Ok(Some(_))
+            )
+        })
+        .collect::<Vec<_>>();
+    if missing_indices.is_empty() {
+        return Ok(None);
+    }
+
+    let versioned_hashes = missing_indices
+        .iter()
+        .filter_map(|&index| commitments.get(index))
+        .map(kzg_commitment_to_versioned_hash)
+        .collect::<Vec<_>>();
+    metrics::inc_counter_by(
+        &metrics::BLOBS_FROM_EL_EXPECTED,
+        versioned_hashes.len() as u64,
+    );
+
+    let response = match execution_layer.get_blobs(versioned_hashes).await {
+        Ok(response) => response,
+        Err(ExecutionLayerError::GetBlobsNotSupported) => return Ok(None),
+        Err(e) => return Err(FetchBlobsError::RequestFailed(e)),
+    };
+    if response.len() != missing_indices.len() {
+        return Err(FetchBlobsError::InconsistentResponseLength {
+            requested: missing_indices.len(),
+            received: response.len(),
+        });
+    }
+
+    let mut blobs = FixedBlobSidecarList::default();
+    let mut num_received = 0;
+    for (index, blob_and_proof) in missing_indices.into_iter().zip(response) {
+        let (Some(blob_and_proof), Some(kzg_commitment)) = (blob_and_proof, commitments.get(index))
+        else {
+            continue;
+        };
+        let Some(entry) = blobs.get_mut(index) else {
+            continue;
+        };
+        *entry = Some(Arc::new(BlobSidecar {
+            block_root,
+            index: index as u64,
+            slot: block.slot(),
+            block_parent_root: block.parent_root(),
+            proposer_index: block.message().proposer_index(),
+            blob: blob_and_proof.blob,
+            kzg_commitment: *kzg_commitment,
+            kzg_proof: blob_and_proof.proof,
+        }));
+        num_received += 1;
+    }
+    metrics::inc_counter_by(&metrics::BLOBS_FROM_EL_RECEIVED, num_received);
+
+    if num_received == 0 {
+        return Ok(None);
+    }
+
+    chain
+        .data_availability_checker
+        .notify_rpc_blobs(block.slot(), block_root, &blobs);
+
+    // The blobs are KZG-verified before they are added to the cache. A single invalid proof
+    // causes the whole response to be discarded.
+    chain
+        .data_availability_checker
+        .put_rpc_blobs(block_root, blobs)
+        .map(Some)
+        .map_err(|e| {
+            if matches!(
+                e,
+                AvailabilityCheckError::KzgVerificationFailed | AvailabilityCheckError::Kzg(_)
+            ) {
+                metrics::inc_counter(&metrics::BLOBS_FROM_EL_INVALID);
+            }
+            FetchBlobsError::AvailabilityCheck(e)
+        })
+}
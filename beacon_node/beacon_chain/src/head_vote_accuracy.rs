@@ -0,0 +1,221 @@
+//! Provides the `HeadVoteAccuracyTracker`, which measures how often the attestations observed by
+//! this node voted for the block that ended up canonical at their slot.
+//!
+//! The attesting weight of each attestation applied to fork choice is aggregated per epoch by
+//! `(slot, beacon_block_root)`, so memory use depends on the number of distinct votes rather than
+//! the number of validators. Once an epoch is old enough that re-orgs of it are unlikely, its votes
+//! are compared against the canonical chain and discarded.
+//!
+//! Aggregates overlap with the unaggregated attestations they were built from, so the accuracy is
+//! a ratio of observed votes rather than of individual validators.
+
+use crate::metrics;
+use parking_lot::Mutex;
+use slog::{warn, Logger};
+use std::collections::{BTreeMap, HashMap};
+use types::{AttestationData, Epoch, EthSpec, Hash256, Slot};
+
+/// The default accuracy, as a percentage, below which a warning is logged.
+pub const DEFAULT_WARN_THRESHOLD_PERCENT: u64 = 90;
+/// The number of epochs after which the votes of an epoch are evaluated.
+pub const EVALUATION_DELAY_EPOCHS: u64 = 2;
+/// The number of consecutive low-accuracy epochs after which a warning is logged.
+const CONSECUTIVE_LOW_EPOCHS_TO_WARN: u64 = 2;
+
+#[derive(Default)]
+struct Inner {
+    /// Attesting weight for each `(slot, beacon_block_root)` voted for, by epoch.
+    epochs: BTreeMap<Epoch, HashMap<(Slot, Hash256), u64>>,
+    /// The latest epoch to have been evaluated, and its accuracy if it had any votes.
+    latest: Option<(Epoch, Option<f64>)>,
+    consecutive_low_epochs: u64,
+}
+
+pub struct HeadVoteAccuracyTracker {
+    warn_threshold_percent: u64,
+    inner: Mutex<Inner>,
+}
+
+impl HeadVoteAccuracyTracker {
+    pub fn new(warn_threshold_percent: u64) -> Self {
+        Self {
+            warn_threshold_percent,
+            inner: <_>::default(),
+        }
+    }
+
+    /// Records a vote by `weight` validators for the head described by `data`.
+    ///
+    /// Votes from epochs which have already been evaluated are ignored.
+    pub fn register_attestation<E: EthSpec>(&self, data: &AttestationData, weight: u64) {
+        let epoch = data.slot.epoch(E::slots_per_epoch());
+        let mut inner = self.inner.lock();
+        if inner
+            .latest
+            .map_or(false, |(latest_epoch, _)| epoch <= latest_epoch)
+        {
+            return;
+        }
+        *inner
+            .epochs
+            .entry(epoch)
+            .or_default()
+            .entry((data.slot, data.beacon_block_root))
+            .or_default() += weight;
+    }
+
+    /// Evaluates the votes of every epoch at least `EVALUATION_DELAY_EPOCHS` before
+    /// `current_epoch`, updating the accuracy gauge and warning if the accuracy has been below the
+    /// threshold for several consecutive epochs.
+    ///
+    /// `canonical_block_root` returns the root of the canonical block at or before a slot, or
+    /// `None` if it is unknown, in which case votes for that slot are not counted.
+    pub fn update<E: EthSpec>(
+        &self,
+        current_epoch: Epoch,
+        canonical_block_root: impl Fn(Slot) -> Option<Hash256>,
+        log: &Logger,
+    ) {
+        let mut inner = self.inner.lock();
+        let inner = &mut *inner;
+
+        while let Some(entry) = inner.epochs.first_entry() {
+            if *entry.key() + EVALUATION_DELAY_EPOCHS > current_epoch {
+                break;
+            }
+            let (epoch, votes) = entry.remove_entry();
+
+            let (mut correct, mut total) = (0, 0);
+            for ((slot, block_root), weight) in votes {
+                if let Some(canonical_root) = canonical_block_root(slot) {
+                    total += weight;
+                    if block_root == canonical_root {
+                        correct += weight;
+                    }
+                }
+            }
+
+            if total == 0 {
+                inner.latest = Some((epoch, None));
+                continue;
+            }
+            let accuracy = correct as f64 / total as f64;
+            metrics::set_float_gauge(&metrics::HEAD_VOTE_ACCURACY, accuracy);
+            inner.latest = Some((epoch, Some(accuracy)));
+
+            if correct * 100 < total * self.warn_threshold_percent {
+                inner.consecutive_low_epochs += 1;
+            } else {
+                inner.consecutive_low_epochs = 0;
+            }
+            if inner.consecutive_low_epochs >= CONSECUTIVE_LOW_EPOCHS_TO_WARN {
+                warn!(
+                    log,
+                    "Low head vote accuracy";
+                    "info" => "many attestations are voting for blocks that are later re-orged, \
+                               this may indicate network or peering issues",
+                    "epoch" => epoch,
+                    "accuracy" => format!("{:.3}", accuracy),
+                    "threshold_percent" => self.warn_threshold_percent,
+                    "consecutive_epochs" => inner.consecutive_low_epochs,
+                );
+            }
+        }
+    }
+
+    /// Returns the accuracy of the most recently evaluated epoch which had any votes.
+    pub fn latest(&self) -> Option<f64> {
+        self.inner.lock().latest.and_then(|(_, accuracy)| accuracy)
+    }
+
+    /// Returns the number of epochs with votes awaiting evaluation.
+    pub fn pending_epochs(&self) -> usize {
+        self.inner.lock().epochs.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Checkpoint, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn data(slot: u64, root: u64) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(slot),
+            index: 0,
+            beacon_block_root: Hash256::from_low_u64_be(root),
+            source: Checkpoint::default(),
+            target: Checkpoint::default(),
+        }
+    }
+
+    /// The canonical root at every slot is the slot number.
+    fn canonical(slot: Slot) -> Option<Hash256> {
+        Some(Hash256::from_low_u64_be(slot.as_u64()))
+    }
+
+    #[test]
+    fn weighs_votes_against_canonical_chain() {
+        let log = logging::test_logger();
+        let tracker = HeadVoteAccuracyTracker::new(DEFAULT_WARN_THRESHOLD_PERCENT);
+
+        tracker.register_attestation::<E>(&data(1, 1), 3);
+        tracker.register_attestation::<E>(&data(2, 2), 4);
+        tracker.register_attestation::<E>(&data(2, 99), 1);
+
+        // Too recent to be evaluated.
+        tracker.update::<E>(Epoch::new(1), canonical, &log);
+        assert_eq!(tracker.latest(), None);
+        assert_eq!(tracker.pending_epochs(), 1);
+
+        tracker.update::<E>(Epoch::new(2), canonical, &log);
+        assert_eq!(tracker.latest(), Some(7.0 / 8.0));
+        assert_eq!(tracker.pending_epochs(), 0);
+
+        // Late votes for the evaluated epoch are ignored.
+        tracker.register_attestation::<E>(&data(3, 99), 1);
+        assert_eq!(tracker.pending_epochs(), 0);
+    }
+
+    #[test]
+    fn votes_for_unknown_slots_are_not_counted() {
+        let log = logging::test_logger();
+        let tracker = HeadVoteAccuracyTracker::new(DEFAULT_WARN_THRESHOLD_PERCENT);
+
+        tracker.register_attestation::<E>(&data(1, 99), 1);
+        tracker.update::<E>(Epoch::new(2), |_| None, &log);
+        assert_eq!(tracker.latest(), None);
+
+        let slot = E::slots_per_epoch();
+        tracker.register_attestation::<E>(&data(slot, slot), 1);
+        tracker.update::<E>(Epoch::new(3), canonical, &log);
+        assert_eq!(tracker.latest(), Some(1.0));
+    }
+
+    #[test]
+    fn counts_consecutive_low_epochs() {
+        let log = logging::test_logger();
+        let tracker = HeadVoteAccuracyTracker::new(DEFAULT_WARN_THRESHOLD_PERCENT);
+        let slots_per_epoch = E::slots_per_epoch();
+
+        // Epochs 0 and 1 are low, epoch 2 recovers.
+        for (epoch, accurate) in [(0, false), (1, false), (2, true)] {
+            let slot = epoch * slots_per_epoch + 1;
+            tracker.register_attestation::<E>(&data(slot, slot), 1);
+            if !accurate {
+                tracker.register_attestation::<E>(&data(slot, 99), 1);
+            }
+        }
+
+        tracker.update::<E>(Epoch::new(2), canonical, &log);
+        assert_eq!(tracker.inner.lock().consecutive_low_epochs, 1);
+        tracker.update::<E>(Epoch::new(3), canonical, &log);
+        assert_eq!(tracker.inner.lock().consecutive_low_epochs, 2);
+        assert_eq!(tracker.latest(), Some(0.5));
+        tracker.update::<E>(Epoch::new(4), canonical, &log);
+        assert_eq!(tracker.inner.lock().consecutive_low_epochs, 0);
+        assert_eq!(tracker.latest(), Some(1.0));
+    }
+}
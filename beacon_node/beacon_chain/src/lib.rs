@@ -7,25 +7,30 @@ mod beacon_chain;
 mod beacon_fork_choice_store;
 pub mod beacon_proposer_cache;
 mod beacon_snapshot;
+pub mod blob_pruning_service;
 pub mod blob_verification;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
 pub mod block_verification_types;
 pub mod builder;
+pub mod builder_circuit_breaker;
 pub mod canonical_head;
 pub mod capella_readiness;
 pub mod chain_config;
 pub mod data_availability_checker;
 mod early_attester_cache;
+pub mod epoch_timings;
 mod errors;
 pub mod eth1_chain;
 mod eth1_finalization_cache;
 pub mod events;
 pub mod execution_payload;
+pub mod fetch_blobs;
 pub mod fork_choice_signal;
 pub mod fork_revert;
 mod head_tracker;
+pub mod head_vote_accuracy;
 pub mod historical_blocks;
 pub mod kzg_utils;
 pub mod light_client_finality_update_verification;
@@ -42,14 +47,19 @@ pub mod observed_operations;
 pub mod otb_verification_service;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+pub mod persisted_proposer_preparation;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
+pub mod reportable_cache;
 pub mod schema_change;
 pub mod shuffling_cache;
 mod snapshot_cache;
 pub mod state_advance_timer;
+pub mod sync_committee_participation;
+pub mod sync_committee_pubkey_cache;
 pub mod sync_committee_rewards;
 pub mod sync_committee_verification;
+pub mod sync_duties_cache;
 pub mod test_utils;
 mod timeout_rw_lock;
 pub mod validator_monitor;
@@ -75,7 +85,7 @@ pub use block_verification::{
 };
 pub use block_verification_types::AvailabilityPendingExecutedBlock;
 pub use block_verification_types::ExecutedBlock;
-pub use canonical_head::{CachedHead, CanonicalHead, CanonicalHeadRwLock};
+pub use canonical_head::{CachedHead, CanonicalHead, CanonicalHeadRwLock, HeadRecompute};
 pub use eth1_chain::{Eth1Chain, Eth1ChainBackend};
 pub use events::ServerSentEventHandler;
 pub use execution_layer::EngineState;
@@ -266,6 +266,14 @@ lazy_static! {
         "Time spent on the signature verification of batch unaggregate attestation processing"
     );
 
+    /*
+     * Sync duties cache
+     */
+    pub static ref SYNC_DUTIES_CACHE_HITS: Result<IntCounter> =
+        try_create_int_counter("beacon_sync_duties_cache_hits_total", "Count of times the sync duties cache fulfils request");
+    pub static ref SYNC_DUTIES_CACHE_MISSES: Result<IntCounter> =
+        try_create_int_counter("beacon_sync_duties_cache_misses_total", "Count of times the sync duties cache does not fulfil request");
+
     /*
      * Shuffling cache
      */
@@ -389,6 +397,14 @@ lazy_static! {
     pub static ref PERSIST_DATA_AVAILABILITY_CHECKER: Result<Histogram> =
         try_create_histogram("beacon_persist_data_availability_checker", "Time taken to persist the data availability checker");
 
+    /*
+     * Skip-slot states
+     */
+    pub static ref SKIP_SLOT_STATE_SLOTS_PROCESSED: Result<IntCounter> = try_create_int_counter(
+        "beacon_skip_slot_state_slots_processed_total",
+        "Count of slots processed whilst advancing states through skipped slots for API requests"
+    );
+
     /*
      * Eth1
      */
@@ -473,6 +489,15 @@ lazy_static! {
         "beacon_attn_observation_epoch_aggregators",
         "Count of aggregators that have been seen by the beacon chain in the previous epoch"
     );
+    pub static ref OBSERVED_AGGREGATES_EVICTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_observed_aggregates_evictions_total",
+        "Count of roots evicted from the observed aggregates cache because it exceeded its memory cap",
+        &["type"]
+    );
+    pub static ref OBSERVED_AGGREGATE_ATTESTATIONS_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "beacon_observed_aggregate_attestations_bytes",
+        "Approximate memory used by the observed aggregate attestations cache"
+    );
 
     /*
      * Sync Committee Observation Metrics
@@ -791,6 +816,11 @@ lazy_static! {
         "Number of times a validator's sync committee message has been seen in a sync aggregate",
         &["src", "validator"]
     );
+    pub static ref VALIDATOR_MONITOR_SYNC_COMMITTEE_HITS: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "validator_monitor_sync_committee_hits",
+        "Number of canonical blocks in the current sync committee period in which the validator's sync committee bit was set",
+        &["validator"]
+    );
     pub static ref VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_DELAY_SLOTS: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "validator_monitor_attestation_in_block_delay_slots",
         "The excess slots (beyond the minimum delay) between the attestation slot and the block slot.",
@@ -863,6 +893,41 @@ lazy_static! {
             "gossip_beacon_block_skipped_slots",
             "For each gossip blocks, the number of skip slots between it and its parent"
         );
+
+    /*
+     * Epoch timing metrics
+     */
+    pub static ref EPOCH_TIMINGS_BLOCK_ARRIVAL_DELAY: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_epoch_timings_block_arrival_delay_seconds",
+        "Duration between the start of each imported block's slot and its arrival via gossip",
+        // [0.1, 0.2, 0.5, 1, 2, 5, 10, 20, 50]
+        decimal_buckets(-1,2)
+    );
+    pub static ref EPOCH_TIMINGS_ATTESTATION_INCLUSION_DISTANCE: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_epoch_timings_attestation_inclusion_distance_slots",
+        "Number of slots between each attestation in an imported block and the block itself",
+        Ok(vec![1_f64, 2_f64, 3_f64, 4_f64, 8_f64, 16_f64, 32_f64])
+    );
+    pub static ref EPOCH_TIMINGS_PREVIOUS_EPOCH_BLOBS: Result<IntGauge> = try_create_int_gauge(
+        "beacon_epoch_timings_previous_epoch_blobs",
+        "Number of blobs imported with the blocks of the previous epoch"
+    );
+
+    /*
+     * Head vote accuracy
+     */
+    pub static ref HEAD_VOTE_ACCURACY: Result<Gauge> = try_create_float_gauge(
+        "beacon_health_head_vote_accuracy",
+        "Fraction of observed attestation votes in the latest evaluated epoch which were for the canonical block"
+    );
+
+    /*
+     * Sync committee participation
+     */
+    pub static ref SYNC_COMMITTEE_PARTICIPATION_RATE: Result<Gauge> = try_create_float_gauge(
+        "sync_committee_participation_rate",
+        "Fraction of sync committee bits set in the canonical blocks of the current sync committee period"
+    );
 }
 
 // Fourth lazy-static block is used to account for macro recursion limit.
@@ -975,6 +1040,20 @@ lazy_static! {
         "Time spent verifying the signature set during backfill sync, including setup"
     );
 
+    /*
+     * Builder circuit breaker
+     */
+    pub static ref BUILDER_CIRCUIT_BREAKER_TRIPPED: Result<IntGauge> = try_create_int_gauge(
+        "beacon_builder_circuit_breaker_tripped",
+        "Set to 1 if the builder circuit breaker was tripped when chain health was last checked"
+    );
+    pub static ref BUILDER_CIRCUIT_BREAKER_CONDITION_TRIPPED: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "beacon_builder_circuit_breaker_condition_tripped",
+            "Set to 1 for each chain health condition that failed when chain health was last checked",
+            &["condition"]
+        );
+
     /*
      * Pre-finalization block cache.
      */
@@ -989,6 +1068,22 @@ lazy_static! {
             "Number of block roots subject to single block lookups"
         );
 
+    /*
+     * Pruning of abandoned forks
+     */
+    pub static ref PRUNING_ABANDONED_REMAINING: Result<IntGauge> = try_create_int_gauge(
+        "beacon_pruning_abandoned_remaining",
+        "Number of blocks and states of abandoned forks still to be deleted"
+    );
+    pub static ref PRUNING_ABANDONED_DELETED: Result<IntCounter> = try_create_int_counter(
+        "beacon_pruning_abandoned_deleted_total",
+        "Number of blocks and states of abandoned forks deleted"
+    );
+    pub static ref PRUNING_ABANDONED_CHUNK_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_pruning_abandoned_chunk_seconds",
+        "Time taken to delete a chunk of the blocks and states of abandoned forks"
+    );
+
     /*
      * Blob sidecar Verification
      */
@@ -1004,6 +1099,22 @@ lazy_static! {
         "beacon_blobs_sidecar_gossip_verification_seconds",
         "Full runtime of blob sidecars gossip verification"
     );
+    pub static ref BLOBS_FROM_EL_EXPECTED: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_from_el_expected_total",
+        "Number of missing blobs requested from the execution layer's mempool"
+    );
+    pub static ref BLOBS_FROM_EL_RECEIVED: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_from_el_received_total",
+        "Number of blobs returned by the execution layer's mempool"
+    );
+    pub static ref BLOBS_FROM_EL_INVALID: Result<IntCounter> = try_create_int_counter(
+        "beacon_blobs_from_el_invalid_total",
+        "Number of engine_getBlobsV1 responses rejected due to invalid KZG proofs"
+    );
+    pub static ref DATA_AVAILABILITY_CHECK_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "beacon_data_availability_check_failures_total",
+        "Number of blocks or blobs which failed the data availability check"
+    );
 }
 
 // Fifth lazy-static block is used to account for macro recursion limit.
@@ -1033,6 +1144,10 @@ lazy_static! {
         "beacon_aggregated_attestation_subsets_total",
         "Count of new aggregated attestations that are subsets of already known aggregates"
     );
+    pub static ref AGGREGATED_ATTESTATION_OP_POOL_SUBSETS: Result<IntCounter> = try_create_int_counter(
+        "beacon_aggregated_attestation_op_pool_subsets_total",
+        "Count of new aggregated attestations that are subsets of an attestation in the op pool"
+    );
 
     /*
     * Kzg related metrics
@@ -1225,6 +1340,11 @@ fn scrape_attestation_observation<T: BeaconChainTypes>(slot_now: Slot, chain: &B
     {
         set_gauge_by_usize(&ATTN_OBSERVATION_PREV_EPOCH_AGGREGATORS, count);
     }
+
+    set_gauge_by_usize(
+        &OBSERVED_AGGREGATE_ATTESTATIONS_BYTES,
+        chain.observed_attestations.read().total_bytes(),
+    );
 }
 
 fn scrape_sync_committee_observation<T: BeaconChainTypes>(slot_now: Slot, chain: &BeaconChain<T>) {
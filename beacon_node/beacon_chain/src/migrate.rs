@@ -1,9 +1,11 @@
 use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::errors::BeaconChainError;
 use crate::head_tracker::{HeadTracker, SszHeadTracker};
+use crate::metrics;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
+use std::cmp;
 use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::sync::{mpsc, Arc};
@@ -11,7 +13,7 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::{migrate_database, HotColdDBError};
 use store::iter::RootsIterator;
-use store::{Error, ItemStore, StoreItem, StoreOp};
+use store::{AbandonedForks, AbandonedState, Error, ItemStore, StoreItem, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
     BeaconState, BeaconStateError, BeaconStateHash, Checkpoint, Epoch, EthSpec, Hash256,
@@ -28,6 +30,9 @@ const COMPACTION_FINALITY_DISTANCE: u64 = 1024;
 /// Default number of epochs to wait between finalization migrations.
 pub const DEFAULT_EPOCHS_PER_MIGRATION: u64 = 1;
 
+/// Default number of blocks and states of abandoned forks to delete per database write.
+pub const DEFAULT_PRUNING_CHUNK_SIZE: usize = 256;
+
 /// The background migrator runs a thread to perform pruning and migrate state from the hot
 /// to the cold database.
 pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
@@ -38,6 +43,8 @@ pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>
     tx_thread: Option<Mutex<(mpsc::Sender<Notification>, thread::JoinHandle<()>)>>,
     /// Genesis block root, for persisting the `PersistedBeaconChain`.
     genesis_block_root: Hash256,
+    pruning_chunk_size: usize,
+    max_pruning_chunks: Option<usize>,
     log: Logger,
 }
 
@@ -48,6 +55,14 @@ pub struct MigratorConfig {
     ///
     /// If set to 0 or 1, then run every finalization.
     pub epochs_per_migration: u64,
+    /// The maximum number of blocks and states of abandoned forks to delete per database write.
+    ///
+    /// Other database writes (e.g. block imports) can proceed between chunks.
+    pub pruning_chunk_size: usize,
+    /// Stop deleting abandoned forks after this many chunks, as if the node had been stopped.
+    ///
+    /// The remaining blocks and states are deleted by the next migration. Only for testing.
+    pub max_pruning_chunks: Option<usize>,
 }
 
 impl Default for MigratorConfig {
@@ -55,6 +70,8 @@ impl Default for MigratorConfig {
         Self {
             blocking: false,
             epochs_per_migration: DEFAULT_EPOCHS_PER_MIGRATION,
+            pruning_chunk_size: DEFAULT_PRUNING_CHUNK_SIZE,
+            max_pruning_chunks: None,
         }
     }
 }
@@ -69,6 +86,16 @@ impl MigratorConfig {
         self.epochs_per_migration = epochs_per_migration;
         self
     }
+
+    pub fn pruning_chunk_size(mut self, pruning_chunk_size: usize) -> Self {
+        self.pruning_chunk_size = pruning_chunk_size;
+        self
+    }
+
+    pub fn max_pruning_chunks(mut self, max_pruning_chunks: usize) -> Self {
+        self.max_pruning_chunks = Some(max_pruning_chunks);
+        self
+    }
 }
 
 /// Record of when the last migration ran.
@@ -117,7 +144,9 @@ pub enum PruningError {
 pub enum Notification {
     Finalization(FinalizationNotification),
     Reconstruction,
-    PruneBlobs(Epoch),
+    /// Prune blobs older than the data availability boundary, including those of the given
+    /// unfinalized blocks.
+    PruneBlobs(Epoch, Vec<(Hash256, Slot)>),
 }
 
 pub struct FinalizationNotification {
@@ -126,6 +155,8 @@ pub struct FinalizationNotification {
     head_tracker: Arc<HeadTracker>,
     prev_migration: Arc<Mutex<PrevMigration>>,
     genesis_block_root: Hash256,
+    pruning_chunk_size: usize,
+    max_pruning_chunks: Option<usize>,
 }
 
 impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Hot, Cold> {
@@ -151,6 +182,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             tx_thread,
             prev_migration,
             genesis_block_root,
+            pruning_chunk_size: config.pruning_chunk_size,
+            max_pruning_chunks: config.max_pruning_chunks,
             log,
         }
     }
@@ -172,6 +205,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker,
             prev_migration: self.prev_migration.clone(),
             genesis_block_root: self.genesis_block_root,
+            pruning_chunk_size: self.pruning_chunk_size,
+            max_pruning_chunks: self.max_pruning_chunks,
         };
 
         // Send to background thread if configured, otherwise run in foreground.
@@ -192,11 +227,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         }
     }
 
-    pub fn process_prune_blobs(&self, data_availability_boundary: Epoch) {
-        if let Some(Notification::PruneBlobs(data_availability_boundary)) =
-            self.send_background_notification(Notification::PruneBlobs(data_availability_boundary))
+    pub fn process_prune_blobs(
+        &self,
+        data_availability_boundary: Epoch,
+        unfinalized_blocks: Vec<(Hash256, Slot)>,
+    ) {
+        if let Some(Notification::PruneBlobs(data_availability_boundary, unfinalized_blocks)) = self
+            .send_background_notification(Notification::PruneBlobs(
+                data_availability_boundary,
+                unfinalized_blocks,
+            ))
         {
-            Self::run_prune_blobs(self.db.clone(), data_availability_boundary, &self.log);
+            Self::run_prune_blobs(
+                self.db.clone(),
+                data_availability_boundary,
+                &unfinalized_blocks,
+                &self.log,
+            );
         }
     }
 
@@ -213,9 +260,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     pub fn run_prune_blobs(
         db: Arc<HotColdDB<E, Hot, Cold>>,
         data_availability_boundary: Epoch,
+        unfinalized_blocks: &[(Hash256, Slot)],
         log: &Logger,
     ) {
-        if let Err(e) = db.try_prune_blobs(false, data_availability_boundary) {
+        if let Err(e) = db.try_prune_blobs(false, data_availability_boundary, unfinalized_blocks) {
             error!(
                 log,
                 "Blob pruning failed";
@@ -288,6 +336,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
         debug!(log, "Database consolidation started");
 
+        // Finish deleting the abandoned forks found by a previous run, which may have been
+        // interrupted by a restart.
+        match Self::resume_pruning(&db, &notif, log) {
+            Ok(true) => (),
+            Ok(false) => {
+                debug!(
+                    log,
+                    "Database consolidation deferred until pruning completes"
+                );
+                return;
+            }
+            Err(e) => {
+                warn!(log, "Block pruning failed"; "error" => ?e);
+                return;
+            }
+        }
+
         let finalized_state_root = notif.finalized_state_root;
         let finalized_block_root = notif.finalized_checkpoint.root;
 
@@ -311,6 +376,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             &finalized_state,
             notif.finalized_checkpoint,
             notif.genesis_block_root,
+            notif.pruning_chunk_size,
+            notif.max_pruning_chunks,
             log,
         ) {
             Ok(PruningOutcome::Successful {
@@ -396,7 +463,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 match notif {
                     Notification::Reconstruction => reconstruction_notif = Some(notif),
                     Notification::Finalization(fin) => finalization_notif = Some(fin),
-                    Notification::PruneBlobs(dab) => prune_blobs_notif = Some(dab),
+                    Notification::PruneBlobs(dab, blocks) => {
+                        prune_blobs_notif = Some((dab, blocks))
+                    }
                 }
                 // Read the rest of the messages in the channel, taking the best of each type.
                 for notif in rx.try_iter() {
@@ -413,8 +482,13 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                                 finalization_notif = Some(fin);
                             }
                         }
-                        Notification::PruneBlobs(dab) => {
-                            prune_blobs_notif = std::cmp::max(prune_blobs_notif, Some(dab));
+                        Notification::PruneBlobs(dab, blocks) => {
+                            if prune_blobs_notif
+                                .as_ref()
+                                .map_or(true, |(current, _)| dab >= *current)
+                            {
+                                prune_blobs_notif = Some((dab, blocks));
+                            }
                         }
                     }
                 }
@@ -425,8 +499,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                     if let Some(fin) = finalization_notif {
                         Self::run_migration(db.clone(), fin, &log);
                     }
-                    if let Some(dab) = prune_blobs_notif {
-                        Self::run_prune_blobs(db.clone(), dab, &log);
+                    if let Some((dab, blocks)) = prune_blobs_notif {
+                        Self::run_prune_blobs(db.clone(), dab, &blocks, &log);
                     }
                 }
             }
@@ -445,6 +519,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         new_finalized_state: &BeaconState<E>,
         new_finalized_checkpoint: Checkpoint,
         genesis_block_root: Hash256,
+        pruning_chunk_size: usize,
+        max_pruning_chunks: Option<usize>,
         log: &Logger,
     ) -> Result<PruningOutcome, BeaconChainError> {
         let old_finalized_checkpoint =
@@ -668,22 +744,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker_lock.remove(&head_hash);
         }
 
-        let mut batch: Vec<StoreOp<E>> = abandoned_blocks
-            .into_iter()
-            .map(Into::into)
-            .flat_map(|block_root: Hash256| {
-                [
-                    StoreOp::DeleteBlock(block_root),
-                    StoreOp::DeleteExecutionPayload(block_root),
-                    StoreOp::DeleteBlobs(block_root),
-                ]
-            })
-            .chain(
-                abandoned_states
-                    .into_iter()
-                    .map(|(slot, state_hash)| StoreOp::DeleteState(state_hash.into(), Some(slot))),
-            )
-            .collect();
+        let abandoned_forks = AbandonedForks {
+            block_roots: abandoned_blocks.into_iter().map(Into::into).collect(),
+            states: abandoned_states
+                .into_iter()
+                .map(|(slot, state_hash)| AbandonedState {
+                    slot,
+                    state_root: state_hash.into(),
+                })
+                .collect(),
+        };
 
         // Persist the head in case the process is killed or crashes here. This prevents
         // the head tracker reverting after our mutation above.
@@ -693,16 +763,37 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             ssz_head_tracker: SszHeadTracker::from_map(&head_tracker_lock),
         };
         drop(head_tracker_lock);
-        batch.push(StoreOp::KeyValueOp(
+        let mut batch = vec![StoreOp::KeyValueOp(
             persisted_head.as_kv_store_op(BEACON_CHAIN_DB_KEY),
-        ));
+        )];
 
         // Persist the new finalized checkpoint as the pruning checkpoint.
         batch.push(StoreOp::KeyValueOp(
             store.pruning_checkpoint_store_op(new_finalized_checkpoint),
         ));
 
+        // Persist the abandoned forks alongside the head tracker they were removed from, so that
+        // their deletion can be resumed if it is interrupted.
+        if !abandoned_forks.is_empty() {
+            batch.extend(
+                store
+                    .abandoned_forks_store_ops(&abandoned_forks)
+                    .into_iter()
+                    .map(StoreOp::KeyValueOp),
+            );
+        }
+
         store.do_atomically_with_block_and_blobs_cache(batch)?;
+
+        Self::delete_abandoned_forks(
+            &store,
+            &head_tracker,
+            &abandoned_forks,
+            0,
+            pruning_chunk_size,
+            max_pruning_chunks,
+            log,
+        )?;
         debug!(log, "Database pruning complete");
 
         Ok(PruningOutcome::Successful {
@@ -710,8 +801,127 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         })
     }
 
+    /// Deletes the remaining blocks and states of abandoned forks from a previous run, if any.
+    ///
+    /// Returns `false` if they could not all be deleted.
+    fn resume_pruning(
+        store: &HotColdDB<E, Hot, Cold>,
+        notif: &FinalizationNotification,
+        log: &Logger,
+    ) -> Result<bool, Error> {
+        let Some((abandoned_forks, deleted)) = store.load_abandoned_forks()? else {
+            return Ok(true);
+        };
+
+        info!(
+            log,
+            "Resuming pruning of abandoned forks";
+            "deleted" => deleted,
+            "remaining" => (abandoned_forks.len() as u64).saturating_sub(deleted),
+        );
+        Self::delete_abandoned_forks(
+            store,
+            &notif.head_tracker,
+            &abandoned_forks,
+            deleted as usize,
+            notif.pruning_chunk_size,
+            notif.max_pruning_chunks,
+            log,
+        )
+    }
+
+    /// Deletes the blocks and states of `abandoned_forks`, starting from the `deleted`th, in
+    /// chunks of `chunk_size`.
+    ///
+    /// Each chunk is written atomically along with the number of blocks and states deleted so
+    /// far, and the block cache lock is released between chunks so that block imports are not
+    /// delayed by a large prune.
+    ///
+    /// The abandoned forks were computed from the head tracker, and conflict with the finalized
+    /// checkpoint, so neither fork choice nor the head can build upon them. As a safeguard against
+    /// deleting a live chain, blocks which are heads of the head tracker are never deleted.
+    ///
+    /// Returns `false` if pruning stopped early because `max_chunks` chunks were deleted.
+    fn delete_abandoned_forks(
+        store: &HotColdDB<E, Hot, Cold>,
+        head_tracker: &HeadTracker,
+        abandoned_forks: &AbandonedForks,
+        mut deleted: usize,
+        chunk_size: usize,
+        max_chunks: Option<usize>,
+        log: &Logger,
+    ) -> Result<bool, Error> {
+        let total = abandoned_forks.len();
+        let chunk_size = cmp::max(chunk_size, 1);
+        let mut chunks = 0;
+
+        metrics::set_gauge(
+            &metrics::PRUNING_ABANDONED_REMAINING,
+            total.saturating_sub(deleted) as i64,
+        );
+
+        while deleted < total {
+            if max_chunks.map_or(false, |max_chunks| chunks >= max_chunks) {
+                debug!(
+                    log,
+                    "Stopped pruning abandoned forks";
+                    "deleted" => deleted,
+                    "remaining" => total - deleted,
+                );
+                return Ok(false);
+            }
+
+            let _timer = metrics::start_timer(&metrics::PRUNING_ABANDONED_CHUNK_TIMES);
+            let end = cmp::min(deleted + chunk_size, total);
+            let mut batch = vec![];
+            for index in deleted..end {
+                if let Some(&block_root) = abandoned_forks.block_roots.get(index) {
+                    if head_tracker.contains_head(block_root) {
+                        warn!(
+                            log,
+                            "Not pruning abandoned block which is a head";
+                            "block_root" => ?block_root,
+                        );
+                        continue;
+                    }
+                    batch.extend([
+                        StoreOp::DeleteBlock(block_root),
+                        StoreOp::DeleteExecutionPayload(block_root),
+                        StoreOp::DeleteBlobs(block_root),
+                    ]);
+                } else if let Some(state) = abandoned_forks
+                    .states
+                    .get(index - abandoned_forks.block_roots.len())
+                {
+                    batch.push(StoreOp::DeleteState(state.state_root, Some(state.slot)));
+                }
+            }
+
+            if end == total {
+                batch.extend(
+                    store
+                        .abandoned_forks_delete_ops()
+                        .into_iter()
+                        .map(StoreOp::KeyValueOp),
+                );
+            } else {
+                batch.push(StoreOp::KeyValueOp(
+                    store.pruning_cursor_store_op(end as u64),
+                ));
+            }
+            store.do_atomically_with_block_and_blobs_cache(batch)?;
+
+            metrics::inc_counter_by(&metrics::PRUNING_ABANDONED_DELETED, (end - deleted) as u64);
+            metrics::set_gauge(&metrics::PRUNING_ABANDONED_REMAINING, (total - end) as i64);
+            deleted = end;
+            chunks += 1;
+        }
+
+        Ok(true)
+    }
+
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
-    /// was last compacted.
+    /// was last compacted, or more than `compact_interval_epochs` if it is configured.
     pub fn run_compaction(
         db: Arc<HotColdDB<E, Hot, Cold>>,
         old_finalized_epoch: Epoch,
@@ -733,24 +943,31 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             .as_ref()
             .map_or(0, Duration::as_secs);
 
-        if seconds_since_last_compaction > MAX_COMPACTION_PERIOD_SECONDS
-            || (new_finalized_epoch - old_finalized_epoch > COMPACTION_FINALITY_DISTANCE
-                && seconds_since_last_compaction > MIN_COMPACTION_PERIOD_SECONDS)
+        let compaction_due = if let Some(interval_epochs) = db.get_config().compact_interval_epochs
         {
+            let seconds_per_epoch = db.get_chain_spec().seconds_per_slot * E::slots_per_epoch();
+            seconds_since_last_compaction >= interval_epochs.saturating_mul(seconds_per_epoch)
+        } else {
+            seconds_since_last_compaction > MAX_COMPACTION_PERIOD_SECONDS
+                || (new_finalized_epoch - old_finalized_epoch > COMPACTION_FINALITY_DISTANCE
+                    && seconds_since_last_compaction > MIN_COMPACTION_PERIOD_SECONDS)
+        };
+
+        if compaction_due {
             info!(
                 log,
                 "Starting database compaction";
                 "old_finalized_epoch" => old_finalized_epoch,
                 "new_finalized_epoch" => new_finalized_epoch,
             );
-            db.compact()?;
+            let summary = db.compact()?;
 
-            let finish_time = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or(start_time);
-            db.store_compaction_timestamp(finish_time)?;
-
-            info!(log, "Database compaction complete");
+            info!(
+                log,
+                "Database compaction complete";
+                "duration" => ?summary.duration,
+                "reclaimed_bytes" => summary.reclaimed_bytes,
+            );
         }
         Ok(())
     }
@@ -1,10 +1,17 @@
 //! Provides an `ObservedAggregates` struct which allows us to reject aggregated attestations or
 //! sync committee contributions if we've already seen them.
+//!
+//! The memory used by the observed aggregates is approximately accounted for, and capped by
+//! evicting the roots which have been observed the fewest times. See
+//! `ObservedAggregates::evict_least_observed`.
 
+use crate::metrics;
 use crate::sync_committee_verification::SyncCommitteeData;
 use ssz_types::{BitList, BitVector};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::mem;
 use tree_hash::TreeHash;
 use types::consts::altair::{
     SYNC_COMMITTEE_SUBNET_COUNT, TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE,
@@ -23,11 +30,26 @@ pub type ObservedAggregateAttestations<E> = ObservedAggregates<
     BitList<<E as types::EthSpec>::MaxValidatorsPerCommittee>,
 >;
 
+/// The default maximum memory used by observed aggregate attestations, 128 MiB.
+pub const DEFAULT_OBSERVED_ATTESTATIONS_MAX_BYTES: usize = 128 * 1024 * 1024;
+
+/// When the maximum memory is exceeded, roots are evicted until this percentage of it is used.
+///
+/// Evicting a little more than necessary avoids searching for eviction candidates on every
+/// subsequent observation.
+const EVICTION_TARGET_PERCENT: usize = 90;
+
 /// A trait use to associate capacity constants with the type being stored in `ObservedAggregates`.
 pub trait Consts {
     /// The default capacity of items stored per slot, in a single `SlotHashSet`.
     const DEFAULT_PER_SLOT_CAPACITY: usize;
 
+    /// The default maximum number of bytes used by the observed items, across all slots.
+    const DEFAULT_MAX_BYTES: usize;
+
+    /// A name for the type of item, used in metrics.
+    const NAME: &'static str;
+
     /// The maximum number of slots
     fn max_slot_capacity() -> usize;
 
@@ -41,6 +63,10 @@ impl<T: EthSpec> Consts for Attestation<T> {
     /// much.
     const DEFAULT_PER_SLOT_CAPACITY: usize = 128;
 
+    const DEFAULT_MAX_BYTES: usize = DEFAULT_OBSERVED_ATTESTATIONS_MAX_BYTES;
+
+    const NAME: &'static str = "attestation";
+
     /// We need to keep attestations for each slot of the current epoch.
     fn max_slot_capacity() -> usize {
         T::slots_per_epoch() as usize
@@ -68,6 +94,12 @@ impl<T: EthSpec> Consts for SyncCommitteeContribution<T> {
     const DEFAULT_PER_SLOT_CAPACITY: usize =
         (SYNC_COMMITTEE_SUBNET_COUNT * TARGET_AGGREGATORS_PER_SYNC_SUBCOMMITTEE) as usize;
 
+    /// Contributions are only kept for a single slot and are bounded by the sync committee size,
+    /// so there is no need to cap their memory.
+    const DEFAULT_MAX_BYTES: usize = usize::MAX;
+
+    const NAME: &'static str = "sync_contribution";
+
     /// We only need to keep contributions related to the current slot.
     fn max_slot_capacity() -> usize {
         1
@@ -97,6 +129,9 @@ pub trait SubsetItem {
     /// comparison with incoming aggregates.
     fn get_item(&self) -> Self::Item;
 
+    /// Returns the approximate number of bytes used by `item` when it is stored.
+    fn item_size(item: &Self::Item) -> usize;
+
     /// Returns a unique value that keys the object to the item that is being stored
     /// in `ObservedAggregates`.
     fn root(&self) -> Hash256;
@@ -117,6 +152,10 @@ impl<T: EthSpec> SubsetItem for Attestation<T> {
         self.aggregation_bits.clone()
     }
 
+    fn item_size(item: &Self::Item) -> usize {
+        mem::size_of::<Self::Item>() + (item.len() + 7) / 8
+    }
+
     /// Returns the hash tree root of the attestation data.
     fn root(&self) -> Hash256 {
         self.data.tree_hash_root()
@@ -138,6 +177,10 @@ impl<T: EthSpec> SubsetItem for SyncCommitteeContribution<T> {
         self.aggregation_bits.clone()
     }
 
+    fn item_size(_item: &Self::Item) -> usize {
+        mem::size_of::<Self::Item>()
+    }
+
     /// Returns the hash tree root of the root, slot and subcommittee index
     /// of the sync contribution.
     fn root(&self) -> Hash256 {
@@ -175,13 +218,29 @@ pub enum Error {
     },
 }
 
-/// A `HashMap` that contains entries related to some `Slot`.
-struct SlotHashSet<I> {
+/// The items observed for a single root.
+struct ObservedRoot<I> {
     /// Contains a vector of maximally-sized aggregation bitfields/bitvectors
     /// such that no bitfield/bitvector is a subset of any other in the list.
-    map: HashMap<Hash256, Vec<I>>,
+    items: Vec<I>,
+    /// The number of times an item with this root has been observed, including subsets.
+    observations: u64,
+    /// The approximate number of bytes used by this root and its items.
+    bytes: usize,
+}
+
+impl<I> ObservedRoot<I> {
+    /// The approximate number of bytes used by a root in a `SlotHashSet`, excluding its items.
+    const OVERHEAD_BYTES: usize = mem::size_of::<Hash256>() + mem::size_of::<Self>();
+}
+
+/// A `HashMap` that contains entries related to some `Slot`.
+struct SlotHashSet<I> {
+    map: HashMap<Hash256, ObservedRoot<I>>,
     slot: Slot,
     max_capacity: usize,
+    /// The approximate number of bytes used by the entries of `map`.
+    bytes: usize,
 }
 
 impl<I> SlotHashSet<I> {
@@ -190,6 +249,7 @@ impl<I> SlotHashSet<I> {
             slot,
             map: HashMap::with_capacity(initial_capacity),
             max_capacity,
+            bytes: 0,
         }
     }
 
@@ -206,8 +266,9 @@ impl<I> SlotHashSet<I> {
             });
         }
 
-        if let Some(aggregates) = self.map.get_mut(&root) {
-            for existing in aggregates {
+        if let Some(observed) = self.map.get_mut(&root) {
+            observed.observations = observed.observations.saturating_add(1);
+            for existing in &mut observed.items {
                 // Check if `item` is a subset of any of the observed aggregates
                 if item.is_subset(existing) {
                     return Ok(ObserveOutcome::Subset);
@@ -215,7 +276,11 @@ impl<I> SlotHashSet<I> {
                 // If true, we replace the new item with its existing subset. This allows us
                 // to hold fewer items in the list.
                 } else if item.is_superset(existing) {
-                    *existing = item.get_item();
+                    let new_item = item.get_item();
+                    let (old_size, new_size) = (S::item_size(existing), S::item_size(&new_item));
+                    observed.bytes = observed.bytes - old_size + new_size;
+                    self.bytes = self.bytes - old_size + new_size;
+                    *existing = new_item;
                     return Ok(ObserveOutcome::New);
                 }
             }
@@ -234,7 +299,24 @@ impl<I> SlotHashSet<I> {
         }
 
         let item = item.get_item();
-        self.map.entry(root).or_default().push(item);
+        let item_size = S::item_size(&item);
+        match self.map.entry(root) {
+            Entry::Occupied(mut entry) => {
+                let observed = entry.get_mut();
+                observed.items.push(item);
+                observed.bytes += item_size;
+                self.bytes += item_size;
+            }
+            Entry::Vacant(entry) => {
+                let bytes = ObservedRoot::<I>::OVERHEAD_BYTES + item_size;
+                entry.insert(ObservedRoot {
+                    items: vec![item],
+                    observations: 1,
+                    bytes,
+                });
+                self.bytes += bytes;
+            }
+        }
         Ok(ObserveOutcome::New)
     }
 
@@ -252,10 +334,16 @@ impl<I> SlotHashSet<I> {
             });
         }
 
-        Ok(self
-            .map
-            .get(&root)
-            .map_or(false, |agg| agg.iter().any(|val| item.is_subset(val))))
+        Ok(self.map.get(&root).map_or(false, |observed| {
+            observed.items.iter().any(|val| item.is_subset(val))
+        }))
+    }
+
+    /// Removes `root` and its items, returning the number of bytes freed.
+    fn remove(&mut self, root: &Hash256) -> usize {
+        let bytes = self.map.remove(root).map_or(0, |observed| observed.bytes);
+        self.bytes -= bytes;
+        bytes
     }
 
     /// The number of observed items in `self`.
@@ -269,19 +357,34 @@ impl<I> SlotHashSet<I> {
 pub struct ObservedAggregates<T: SlotData + Consts, E: EthSpec, I> {
     lowest_permissible_slot: Slot,
     sets: Vec<SlotHashSet<I>>,
+    /// The maximum approximate number of bytes used by the items in `sets`.
+    max_bytes: usize,
     _phantom_spec: PhantomData<E>,
     _phantom_tree_hash: PhantomData<T>,
 }
 
 impl<T: SlotData + Consts, E: EthSpec, I> Default for ObservedAggregates<T, E, I> {
     fn default() -> Self {
+        Self::new(T::DEFAULT_MAX_BYTES)
+    }
+}
+
+impl<T: SlotData + Consts, E: EthSpec, I> ObservedAggregates<T, E, I> {
+    /// Creates an empty cache which uses approximately at most `max_bytes` of memory.
+    pub fn new(max_bytes: usize) -> Self {
         Self {
             lowest_permissible_slot: Slot::new(0),
             sets: vec![],
+            max_bytes,
             _phantom_spec: PhantomData,
             _phantom_tree_hash: PhantomData,
         }
     }
+
+    /// The approximate number of bytes used by the observed items.
+    pub fn total_bytes(&self) -> usize {
+        self.sets.iter().map(|set| set.bytes).sum()
+    }
 }
 
 impl<T: SlotData + Consts + SubsetItem<Item = I>, E: EthSpec, I> ObservedAggregates<T, E, I> {
@@ -296,10 +399,62 @@ impl<T: SlotData + Consts + SubsetItem<Item = I>, E: EthSpec, I> ObservedAggrega
         let index = self.get_set_index(item.get_slot())?;
         let root = root_opt.unwrap_or_else(|| item.root());
 
-        self.sets
+        let outcome = self
+            .sets
             .get_mut(index)
             .ok_or(Error::InvalidSetIndex(index))
-            .and_then(|set| set.observe_item(item, root))
+            .and_then(|set| set.observe_item(item, root))?;
+
+        if outcome == ObserveOutcome::New && self.total_bytes() > self.max_bytes {
+            self.evict_least_observed(item.get_slot(), root);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Evicts roots until at most `EVICTION_TARGET_PERCENT` of `self.max_bytes` is used.
+    ///
+    /// The roots observed the fewest times are evicted first, then those of the earliest slots.
+    /// Aggregates of honest validators share a few roots which are observed many times, whereas
+    /// a flood of aggregates with distinct roots is evicted quickly. The root that was just
+    /// observed, at `keep_slot`, is never evicted.
+    ///
+    /// Evicting a root forgets that its aggregates were seen, so an aggregate which was already
+    /// verified will be considered new and verified again if it is received again. This costs some
+    /// redundant verification and forwarding of duplicates on gossip (where libp2p also removes
+    /// duplicate messages), but never causes an invalid aggregate to be accepted.
+    fn evict_least_observed(&mut self, keep_slot: Slot, keep_root: Hash256) {
+        let target_bytes = self.max_bytes - self.max_bytes / 100 * (100 - EVICTION_TARGET_PERCENT);
+
+        let mut candidates = self
+            .sets
+            .iter()
+            .flat_map(|set| {
+                set.map
+                    .iter()
+                    .map(move |(root, observed)| (observed.observations, set.slot, *root))
+            })
+            .filter(|(_, slot, root)| (*slot, *root) != (keep_slot, keep_root))
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        let mut total_bytes = self.total_bytes();
+        let mut num_evicted = 0;
+        for (_, slot, root) in candidates {
+            if total_bytes <= target_bytes {
+                break;
+            }
+            if let Some(set) = self.sets.iter_mut().find(|set| set.slot == slot) {
+                total_bytes -= set.remove(&root);
+                num_evicted += 1;
+            }
+        }
+
+        metrics::inc_counter_vec_by(
+            &metrics::OBSERVED_AGGREGATES_EVICTIONS,
+            &[T::NAME],
+            num_evicted,
+        );
     }
 
     /// Check if `item` is a non-strict subset of any of the already observed aggregates for
@@ -607,4 +762,98 @@ mod tests {
         ObservedAggregateAttestations,
         get_attestation
     );
+
+    mod eviction {
+        use super::*;
+
+        /// Returns an attestation with a single bit set, so that every root uses the same memory.
+        fn attestation(slot: u64, beacon_block_root: u64, bit: usize) -> Attestation<E> {
+            let mut a = get_attestation(Slot::new(slot), beacon_block_root);
+            a.aggregation_bits = BitList::with_capacity(16).unwrap();
+            a.aggregation_bits.set(bit, true).unwrap();
+            a
+        }
+
+        fn is_known(store: &mut ObservedAggregateAttestations<E>, a: &Attestation<E>) -> bool {
+            store.is_known_subset(a, a.root()).unwrap()
+        }
+
+        #[test]
+        fn evicts_least_observed_roots_first() {
+            // Observe each root the given number of times, at the given slot.
+            let observations = [(0, 4), (1, 1), (0, 3), (0, 1)];
+            let attestations = observations
+                .iter()
+                .enumerate()
+                .map(|(root, &(slot, _))| attestation(slot, root as u64, 0))
+                .collect::<Vec<_>>();
+
+            let mut store = ObservedAggregateAttestations::<E>::default();
+            for a in &attestations {
+                store.observe_item(a, None).unwrap();
+            }
+            let root_bytes = store.total_bytes() / attestations.len();
+
+            // Allow a little less than five roots, so that observing a fifth evicts one.
+            let mut store = ObservedAggregateAttestations::<E>::new(root_bytes * 5 - 1);
+            for (a, &(_, count)) in attestations.iter().zip(&observations) {
+                assert_eq!(store.observe_item(a, None), Ok(ObserveOutcome::New));
+                for _ in 1..count {
+                    assert_eq!(store.observe_item(a, None), Ok(ObserveOutcome::Subset));
+                }
+            }
+            assert_eq!(store.total_bytes(), root_bytes * 4);
+
+            // Roots 1 and 3 have been observed the fewest times, and root 3 has the earlier slot.
+            let new = attestation(1, 4, 0);
+            assert_eq!(store.observe_item(&new, None), Ok(ObserveOutcome::New));
+            assert!(!is_known(&mut store, &attestations[3]));
+            assert!(is_known(&mut store, &attestations[1]));
+            assert!(is_known(&mut store, &new));
+            assert_eq!(store.total_bytes(), root_bytes * 4);
+
+            // Once `new` has been observed again, root 1 has the fewest observations. The root
+            // which was just observed is never evicted.
+            assert_eq!(store.observe_item(&new, None), Ok(ObserveOutcome::Subset));
+            let newer = attestation(1, 5, 0);
+            assert_eq!(store.observe_item(&newer, None), Ok(ObserveOutcome::New));
+            assert!(!is_known(&mut store, &attestations[1]));
+            assert!(is_known(&mut store, &new));
+            assert!(is_known(&mut store, &newer));
+            assert_eq!(store.total_bytes(), root_bytes * 4);
+
+            // Duplicates of the surviving roots are still detected.
+            for a in [&attestations[0], &attestations[2], &new, &newer] {
+                assert!(is_known(&mut store, a));
+                assert_eq!(store.observe_item(a, None), Ok(ObserveOutcome::Subset));
+            }
+
+            // An evicted root is treated as new, and its aggregate would be verified again.
+            assert_eq!(
+                store.observe_item(&attestations[3], None),
+                Ok(ObserveOutcome::New)
+            );
+        }
+
+        #[test]
+        fn accounts_for_superset_replacement() {
+            let mut store = ObservedAggregateAttestations::<E>::default();
+            let a = attestation(0, 0, 0);
+            let mut b = a.clone();
+            b.aggregation_bits = BitList::with_capacity(16).unwrap();
+            b.aggregation_bits.set(1, true).unwrap();
+            let mut both = a.clone();
+            both.aggregation_bits.set(1, true).unwrap();
+
+            store.observe_item(&a, None).unwrap();
+            let one_item = store.total_bytes();
+            store.observe_item(&b, None).unwrap();
+            let two_items = store.total_bytes();
+            assert!(two_items > one_item);
+
+            // `both` replaces the first of the two items, leaving the memory used unchanged.
+            assert_eq!(store.observe_item(&both, None), Ok(ObserveOutcome::New));
+            assert_eq!(store.total_bytes(), two_items);
+        }
+    }
 }
@@ -3,9 +3,10 @@
 //! Only `BlobSidecar`s that have completed proposer signature verification can be added
 //! to this cache to reduce DoS risks.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Duration;
 use types::{BlobSidecar, EthSpec, Hash256, Slot};
 
 #[derive(Debug, PartialEq)]
@@ -29,8 +30,11 @@ pub enum Error {
 /// like checking the proposer signature.
 pub struct ObservedBlobSidecars<T: EthSpec> {
     finalized_slot: Slot,
-    /// Stores all received blob indices for a given `(Root, Slot)` tuple.
-    items: HashMap<(Hash256, Slot), HashSet<u64>>,
+    /// Stores all received blob indices for a given `(Root, Slot)` tuple, along with the time at
+    /// which each was first seen in milliseconds since the Unix epoch.
+    ///
+    /// Indices are checked against `T::max_blobs_per_block()`, which bounds the size of each map.
+    items: HashMap<(Hash256, Slot), HashMap<u64, u64>>,
     _phantom: PhantomData<T>,
 }
 
@@ -49,17 +53,27 @@ impl<T: EthSpec> ObservedBlobSidecars<T> {
     /// Observe the `blob_sidecar` at (`blob_sidecar.block_root, blob_sidecar.slot`).
     /// This will update `self` so future calls to it indicate that this `blob_sidecar` is known.
     ///
+    /// The `seen_timestamp` is recorded as the time the sidecar was first seen, unless it has
+    /// already been observed.
+    ///
     /// The supplied `blob_sidecar` **MUST** have completed proposer signature verification.
-    pub fn observe_sidecar(&mut self, blob_sidecar: &Arc<BlobSidecar<T>>) -> Result<bool, Error> {
+    pub fn observe_sidecar(
+        &mut self,
+        blob_sidecar: &Arc<BlobSidecar<T>>,
+        seen_timestamp: Duration,
+    ) -> Result<bool, Error> {
         self.sanitize_blob_sidecar(blob_sidecar)?;
 
-        let did_not_exist = self
+        let indices = self
             .items
             .entry((blob_sidecar.block_root, blob_sidecar.slot))
-            .or_insert_with(|| HashSet::with_capacity(T::max_blobs_per_block()))
-            .insert(blob_sidecar.index);
+            .or_insert_with(|| HashMap::with_capacity(T::max_blobs_per_block()));
+        let already_known = indices.contains_key(&blob_sidecar.index);
+        if !already_known {
+            indices.insert(blob_sidecar.index, seen_timestamp.as_millis() as u64);
+        }
 
-        Ok(!did_not_exist)
+        Ok(already_known)
     }
 
     /// Returns `true` if the `blob_sidecar` has already been observed in the cache within the prune window.
@@ -68,10 +82,19 @@ impl<T: EthSpec> ObservedBlobSidecars<T> {
         let is_known = self
             .items
             .get(&(blob_sidecar.block_root, blob_sidecar.slot))
-            .map_or(false, |set| set.contains(&blob_sidecar.index));
+            .map_or(false, |indices| indices.contains_key(&blob_sidecar.index));
         Ok(is_known)
     }
 
+    /// Returns the index of each observed blob of the block at `block_root` and `slot`, mapped to
+    /// the time it was first seen in milliseconds since the Unix epoch.
+    pub fn first_seen_times(&self, block_root: Hash256, slot: Slot) -> HashMap<u64, u64> {
+        self.items
+            .get(&(block_root, slot))
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn sanitize_blob_sidecar(&self, blob_sidecar: &Arc<BlobSidecar<T>>) -> Result<(), Error> {
         if blob_sidecar.index >= T::max_blobs_per_block() as u64 {
             return Err(Error::InvalidBlobIndex(blob_sidecar.index));
@@ -125,7 +148,7 @@ mod tests {
         let sidecar_a = get_blob_sidecar(0, block_root_a, 0);
 
         assert_eq!(
-            cache.observe_sidecar(&sidecar_a),
+            cache.observe_sidecar(&sidecar_a, Duration::ZERO),
             Ok(false),
             "can observe proposer, indicates proposer unobserved"
         );
@@ -188,7 +211,7 @@ mod tests {
         let block_b = get_blob_sidecar(E::slots_per_epoch(), Hash256::random(), 0);
 
         assert_eq!(
-            cache.observe_sidecar(&block_b),
+            cache.observe_sidecar(&block_b, Duration::ZERO),
             Err(Error::FinalizedBlob {
                 slot: E::slots_per_epoch().into(),
                 finalized_slot: E::slots_per_epoch().into(),
@@ -209,7 +232,7 @@ mod tests {
         let block_b = get_blob_sidecar(three_epochs, block_root_b, 0);
 
         assert_eq!(
-            cache.observe_sidecar(&block_b),
+            cache.observe_sidecar(&block_b, Duration::ZERO),
             Ok(false),
             "can insert non-finalized block"
         );
@@ -265,7 +288,7 @@ mod tests {
         );
 
         assert_eq!(
-            cache.observe_sidecar(&sidecar_a),
+            cache.observe_sidecar(&sidecar_a, Duration::ZERO),
             Ok(false),
             "can observe proposer, indicates proposer unobserved"
         );
@@ -277,7 +300,7 @@ mod tests {
         );
 
         assert_eq!(
-            cache.observe_sidecar(&sidecar_a),
+            cache.observe_sidecar(&sidecar_a, Duration::ZERO),
             Ok(true),
             "observing again indicates true"
         );
@@ -305,7 +328,7 @@ mod tests {
             "no observation for new slot"
         );
         assert_eq!(
-            cache.observe_sidecar(&sidecar_b),
+            cache.observe_sidecar(&sidecar_b, Duration::ZERO),
             Ok(false),
             "can observe proposer for new slot, indicates proposer unobserved"
         );
@@ -315,7 +338,7 @@ mod tests {
             "observed block in slot 1 is indicated as true"
         );
         assert_eq!(
-            cache.observe_sidecar(&sidecar_b),
+            cache.observe_sidecar(&sidecar_b, Duration::ZERO),
             Ok(true),
             "observing slot 1 again indicates true"
         );
@@ -350,7 +373,7 @@ mod tests {
             "no observation for new index"
         );
         assert_eq!(
-            cache.observe_sidecar(&sidecar_c),
+            cache.observe_sidecar(&sidecar_c, Duration::ZERO),
             Ok(false),
             "can observe new index, indicates sidecar unobserved for new index"
         );
@@ -360,7 +383,7 @@ mod tests {
             "observed new sidecar is indicated as true"
         );
         assert_eq!(
-            cache.observe_sidecar(&sidecar_c),
+            cache.observe_sidecar(&sidecar_c, Duration::ZERO),
             Ok(true),
             "observing new sidecar again indicates true"
         );
@@ -381,9 +404,37 @@ mod tests {
         let invalid_index = E::max_blobs_per_block() as u64;
         let sidecar_d = get_blob_sidecar(0, block_root_a, invalid_index);
         assert_eq!(
-            cache.observe_sidecar(&sidecar_d),
+            cache.observe_sidecar(&sidecar_d, Duration::ZERO),
             Err(Error::InvalidBlobIndex(invalid_index)),
             "cannot add an index > MaxBlobsPerBlock"
         );
     }
+
+    #[test]
+    fn first_seen_times() {
+        let mut cache = ObservedBlobSidecars::default();
+
+        let block_root = Hash256::random();
+        let sidecar_a = get_blob_sidecar(0, block_root, 0);
+        let sidecar_b = get_blob_sidecar(0, block_root, 2);
+
+        assert!(cache.first_seen_times(block_root, Slot::new(0)).is_empty());
+
+        cache
+            .observe_sidecar(&sidecar_a, Duration::from_millis(1_000))
+            .unwrap();
+        cache
+            .observe_sidecar(&sidecar_b, Duration::from_millis(1_500))
+            .unwrap();
+        // Observing a sidecar again doesn't update the time it was first seen.
+        cache
+            .observe_sidecar(&sidecar_a, Duration::from_millis(2_000))
+            .unwrap();
+
+        assert_eq!(
+            cache.first_seen_times(block_root, Slot::new(0)),
+            HashMap::from([(0, 1_000), (2, 1_500)])
+        );
+        assert!(cache.first_seen_times(block_root, Slot::new(1)).is_empty());
+    }
 }
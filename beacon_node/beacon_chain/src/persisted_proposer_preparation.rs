@@ -0,0 +1,124 @@
+//! Persists the proposer preparations and builder registrations received from validator clients,
+//! so that the fee recipients of local validators are known immediately after a restart rather
+//! than only once the validator client next sends them.
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use execution_layer::PROPOSER_PREPARATION_LOOKAHEAD;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::sync::Arc;
+use store::{
+    get_key_for_col, DBColumn, Error as StoreError, HotColdDB, ItemStore, KeyValueStore,
+    KeyValueStoreOp, StoreItem,
+};
+use types::{
+    Address, Epoch, EthSpec, Hash256, ProposerPreparationData, SignedValidatorRegistrationData,
+};
+
+/// The preparation of a single validator, keyed by its validator index.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct PersistedProposerPreparation {
+    /// The epoch in which the preparation was last received.
+    pub update_epoch: Epoch,
+    pub fee_recipient: Address,
+    /// The latest builder registration of the validator, if any.
+    pub registration: Option<SignedValidatorRegistrationData>,
+}
+
+impl StoreItem for PersistedProposerPreparation {
+    fn db_column() -> DBColumn {
+        DBColumn::ProposerPreparation
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
+
+fn db_key(validator_index: u64) -> Hash256 {
+    Hash256::from_low_u64_be(validator_index)
+}
+
+/// Loads the persisted proposer preparations which were updated within the last
+/// `PROPOSER_PREPARATION_LOOKAHEAD` epochs of `current_epoch`, deleting any older ones.
+pub fn load_proposer_preparations<E, Hot, Cold>(
+    store: &Arc<HotColdDB<E, Hot, Cold>>,
+    current_epoch: Epoch,
+) -> Result<
+    Vec<(
+        Epoch,
+        ProposerPreparationData,
+        Option<SignedValidatorRegistrationData>,
+    )>,
+    StoreError,
+>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let retain_epoch = current_epoch.saturating_sub(PROPOSER_PREPARATION_LOOKAHEAD);
+    let column = DBColumn::ProposerPreparation;
+
+    let mut preparations = vec![];
+    let mut stale_keys = vec![];
+    for result in store.hot_db.iter_column(column) {
+        let (key, bytes) = result?;
+        let preparation = PersistedProposerPreparation::from_store_bytes(&bytes)?;
+
+        if preparation.update_epoch < retain_epoch {
+            stale_keys.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                column.as_str(),
+                key.as_bytes(),
+            )));
+        } else {
+            preparations.push((
+                preparation.update_epoch,
+                ProposerPreparationData {
+                    validator_index: key.to_low_u64_be(),
+                    fee_recipient: preparation.fee_recipient,
+                },
+                preparation.registration,
+            ));
+        }
+    }
+
+    store.hot_db.do_atomically(stale_keys)?;
+
+    Ok(preparations)
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Persists proposer preparations received from validator clients, along with the latest
+    /// builder registration held by the execution layer for each validator.
+    pub fn persist_proposer_preparations(
+        &self,
+        update_epoch: Epoch,
+        preparations: &[ProposerPreparationData],
+    ) -> Result<(), BeaconChainError> {
+        let execution_layer = self
+            .execution_layer
+            .as_ref()
+            .ok_or(BeaconChainError::ExecutionLayerMissing)?;
+
+        let batch = preparations
+            .iter()
+            .map(|preparation_data| {
+                let validator_index = preparation_data.validator_index;
+                PersistedProposerPreparation {
+                    update_epoch,
+                    fee_recipient: preparation_data.fee_recipient,
+                    registration: execution_layer.builder_registration(validator_index),
+                }
+                .as_kv_store_op(db_key(validator_index))
+            })
+            .collect();
+
+        self.store.hot_db.do_atomically(batch)?;
+
+        Ok(())
+    }
+}
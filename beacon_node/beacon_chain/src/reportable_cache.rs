@@ -0,0 +1,183 @@
+//! Provides a common interface for inspecting and resizing the major caches of the `BeaconChain`
+//! at runtime, as served by the `/lighthouse/caches` HTTP API endpoints.
+
+use crate::beacon_chain::{
+    ATTESTATION_CACHE_LOCK_TIMEOUT, BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT,
+    VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
+};
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{CacheInfo, CacheName};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use store::StateCache;
+use types::EthSpec;
+
+/// A cache whose contents can be reported and, optionally, whose capacity can be adjusted.
+pub trait ReportableCache {
+    fn num_entries(&self) -> usize;
+
+    /// An approximation of the memory used by the entries, in bytes.
+    fn byte_estimate(&self) -> usize;
+
+    /// The number of lookups which found an entry, or `None` if lookups are not counted.
+    fn hits(&self) -> Option<u64> {
+        None
+    }
+
+    /// The number of lookups which did not find an entry, or `None` if lookups are not counted.
+    fn misses(&self) -> Option<u64> {
+        None
+    }
+
+    /// The maximum number of entries, or `None` if the cache is unbounded.
+    fn capacity(&self) -> Option<usize>;
+
+    fn is_resizable(&self) -> bool {
+        false
+    }
+
+    /// Sets the maximum number of entries, evicting entries immediately if the cache holds more
+    /// than `capacity`.
+    ///
+    /// Only called if `Self::is_resizable` returns `true`.
+    fn resize(&mut self, _capacity: usize) {}
+
+    fn info(&self, name: CacheName) -> CacheInfo {
+        CacheInfo {
+            name,
+            entries: self.num_entries(),
+            byte_estimate: self.byte_estimate(),
+            hits: self.hits(),
+            misses: self.misses(),
+            capacity: self.capacity(),
+            resizable: self.is_resizable(),
+        }
+    }
+}
+
+/// Counts the hits and misses of the lookups of a cache.
+#[derive(Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl CacheCounters {
+    pub fn record(&self, hit: bool) {
+        if hit {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl<E: EthSpec> ReportableCache for StateCache<E> {
+    fn num_entries(&self) -> usize {
+        self.len()
+    }
+
+    fn byte_estimate(&self) -> usize {
+        StateCache::byte_estimate(self)
+    }
+
+    fn hits(&self) -> Option<u64> {
+        Some(StateCache::hits(self))
+    }
+
+    fn misses(&self) -> Option<u64> {
+        Some(StateCache::misses(self))
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(StateCache::capacity(self))
+    }
+
+    fn is_resizable(&self) -> bool {
+        true
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        StateCache::resize(self, capacity)
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the contents and configuration of each of the caches in `CacheName::ALL`.
+    pub fn cache_info(&self) -> Result<Vec<CacheInfo>, BeaconChainError> {
+        CacheName::ALL
+            .into_iter()
+            .map(|name| self.with_cache(name, |cache| cache.info(name)))
+            .collect()
+    }
+
+    /// Sets the capacity of each cache in `capacities`, evicting entries from caches which shrink.
+    ///
+    /// No cache is resized unless all of the caches are resizable and all capacities are non-zero.
+    pub fn resize_caches(
+        &self,
+        capacities: &BTreeMap<CacheName, usize>,
+    ) -> Result<(), BeaconChainError> {
+        for (&name, &capacity) in capacities {
+            if !self.with_cache(name, |cache| cache.is_resizable())? {
+                return Err(BeaconChainError::CacheNotResizable(name));
+            }
+            if capacity == 0 {
+                return Err(BeaconChainError::InvalidCacheCapacity { name, capacity });
+            }
+        }
+
+        for (&name, &capacity) in capacities {
+            match name {
+                CacheName::StateCache => self.store.state_cache().resize(capacity),
+                CacheName::SnapshotCache => self
+                    .snapshot_cache
+                    .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                    .ok_or(BeaconChainError::SnapshotCacheLockTimeout)?
+                    .resize(capacity),
+                CacheName::ShufflingCache => self
+                    .shuffling_cache
+                    .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                    .ok_or(BeaconChainError::AttestationCacheLockTimeout)?
+                    .resize(capacity),
+                CacheName::PubkeyCache | CacheName::EarlyAttesterCache => {
+                    return Err(BeaconChainError::CacheNotResizable(name))
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn with_cache<R>(
+        &self,
+        name: CacheName,
+        f: impl FnOnce(&dyn ReportableCache) -> R,
+    ) -> Result<R, BeaconChainError> {
+        let result = match name {
+            CacheName::StateCache => f(&*self.store.state_cache()),
+            CacheName::SnapshotCache => f(&*self
+                .snapshot_cache
+                .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                .ok_or(BeaconChainError::SnapshotCacheLockTimeout)?),
+            CacheName::ShufflingCache => f(&*self
+                .shuffling_cache
+                .try_read_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                .ok_or(BeaconChainError::AttestationCacheLockTimeout)?),
+            CacheName::PubkeyCache => f(&*self
+                .validator_pubkey_cache
+                .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+                .ok_or(BeaconChainError::ValidatorPubkeyCacheLockTimeout)?),
+            CacheName::EarlyAttesterCache => f(&self.early_attester_cache),
+        };
+        Ok(result)
+    }
+}
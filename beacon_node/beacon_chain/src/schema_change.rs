@@ -16,6 +16,124 @@ use store::hot_cold_store::{HotColdDB, HotColdDBError};
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
 use store::{Error as StoreError, StoreItem};
 
+/// A single-version step of a schema migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationStep {
+    pub from: SchemaVersion,
+    pub to: SchemaVersion,
+    /// A summary of the changes made to the database by this step.
+    pub description: &'static str,
+}
+
+/// Describe the changes made by migrating from `from` to `to`, which must be adjacent versions.
+fn describe_step(from: SchemaVersion, to: SchemaVersion) -> Option<&'static str> {
+    let description = match (from, to) {
+        (SchemaVersion(11), SchemaVersion(12)) => {
+            "store richer metadata in the attestation op pool"
+        }
+        (SchemaVersion(12), SchemaVersion(11)) => {
+            "drop richer metadata and attestations from the op pool"
+        }
+        (SchemaVersion(12), SchemaVersion(13)) => "upgrade the eth1 deposit cache",
+        (SchemaVersion(13), SchemaVersion(12)) => {
+            "downgrade the eth1 deposit cache, reinitializing it if it can't be converted"
+        }
+        (SchemaVersion(13), SchemaVersion(14)) => "add BLS to execution changes to the op pool",
+        (SchemaVersion(14), SchemaVersion(13)) => {
+            "drop BLS to execution changes from the op pool, refused after Capella"
+        }
+        (SchemaVersion(14), SchemaVersion(15)) => {
+            "track BLS to execution changes received before Capella"
+        }
+        (SchemaVersion(15), SchemaVersion(14)) => {
+            "forget which BLS to execution changes were received before Capella"
+        }
+        (SchemaVersion(15), SchemaVersion(16)) | (SchemaVersion(16), SchemaVersion(15)) => {
+            "drop the fork choice balances cache"
+        }
+        (SchemaVersion(16), SchemaVersion(17)) => {
+            "remove the unused best justified checkpoint from fork choice"
+        }
+        (SchemaVersion(17), SchemaVersion(16)) => {
+            "add a placeholder best justified checkpoint to fork choice"
+        }
+        (SchemaVersion(17), SchemaVersion(18)) => "no changes, prepares the database for Deneb",
+        (SchemaVersion(18), SchemaVersion(17)) => {
+            "delete the blob info metadata, refused after Deneb"
+        }
+        _ => return None,
+    };
+    Some(description)
+}
+
+/// Return the steps required to migrate the database from `from` to `to`, one version at a time.
+///
+/// An error is returned if any of the steps is unsupported.
+pub fn plan_migration(
+    from: SchemaVersion,
+    to: SchemaVersion,
+) -> Result<Vec<MigrationStep>, StoreError> {
+    let unsupported = || {
+        StoreError::from(HotColdDBError::UnsupportedSchemaVersion {
+            target_version: to,
+            current_version: from,
+        })
+    };
+
+    if from == to {
+        return if to == CURRENT_SCHEMA_VERSION {
+            Ok(vec![])
+        } else {
+            Err(unsupported())
+        };
+    }
+
+    let versions: Vec<u64> = if from.as_u64() < to.as_u64() {
+        (from.as_u64()..=to.as_u64()).collect()
+    } else {
+        (to.as_u64()..=from.as_u64()).rev().collect()
+    };
+
+    versions
+        .windows(2)
+        .map(|pair| {
+            let (step_from, step_to) = (SchemaVersion(pair[0]), SchemaVersion(pair[1]));
+            let description = describe_step(step_from, step_to).ok_or_else(unsupported)?;
+            Ok(MigrationStep {
+                from: step_from,
+                to: step_to,
+                description,
+            })
+        })
+        .collect()
+}
+
+/// Plan a migration from `from` to `to` and check that each of its steps can be applied to `db`,
+/// without modifying the database.
+///
+/// Downgrades which would discard data that the older schema can't represent are refused with
+/// `StoreError::UnableToDowngrade`.
+pub fn check_migration<T: BeaconChainTypes>(
+    db: &Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    from: SchemaVersion,
+    to: SchemaVersion,
+    log: &Logger,
+) -> Result<Vec<MigrationStep>, StoreError> {
+    let steps = plan_migration(from, to)?;
+    for step in &steps {
+        match (step.from, step.to) {
+            (SchemaVersion(14), SchemaVersion(13)) => {
+                migration_schema_v14::check_downgrade_from_v14::<T>(db, log)?
+            }
+            (SchemaVersion(18), SchemaVersion(17)) => {
+                migration_schema_v18::check_downgrade_from_v18::<T>(db, log)?
+            }
+            _ => (),
+        }
+    }
+    Ok(steps)
+}
+
 /// Migrate the database from one schema version to another, applying all requisite mutations.
 #[allow(clippy::only_used_in_recursion)] // spec is not used but likely to be used in future
 pub fn migrate_schema<T: BeaconChainTypes>(
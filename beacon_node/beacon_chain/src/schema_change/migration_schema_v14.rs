@@ -69,15 +69,17 @@ pub fn upgrade_to_v14<T: BeaconChainTypes>(
     Ok(vec![v14.as_kv_store_op(OP_POOL_DB_KEY)])
 }
 
-pub fn downgrade_from_v14<T: BeaconChainTypes>(
-    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
-    log: Logger,
-) -> Result<Vec<KeyValueStoreOp>, Error> {
-    // We cannot downgrade from V14 once the Capella fork has been reached because there will
-    // be HistoricalSummaries stored in the database instead of HistoricalRoots and prior versions
-    // of Lighthouse can't handle that.
+/// Check that the database can be downgraded to v13.
+///
+/// We cannot downgrade from V14 once the Capella fork has been reached because there will
+/// be HistoricalSummaries stored in the database instead of HistoricalRoots and prior versions
+/// of Lighthouse can't handle that.
+pub fn check_downgrade_from_v14<T: BeaconChainTypes>(
+    db: &Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: &Logger,
+) -> Result<(), Error> {
     if let Some(capella_fork_epoch) = db.get_chain_spec().capella_fork_epoch {
-        let current_epoch = get_slot_clock::<T>(&db, &log)?
+        let current_epoch = get_slot_clock::<T>(db, log)?
             .and_then(|clock| clock.now())
             .map(|slot| slot.epoch(T::EthSpec::slots_per_epoch()))
             .ok_or(Error::SlotClockUnavailableForMigration)?;
@@ -89,9 +91,21 @@ pub fn downgrade_from_v14<T: BeaconChainTypes>(
                 "current_epoch" => current_epoch,
                 "capella_fork_epoch" => capella_fork_epoch,
             );
-            return Err(Error::UnableToDowngrade);
+            return Err(Error::UnableToDowngrade(format!(
+                "Capella activated at epoch {}, historical summaries in the database can't be \
+                 represented by v13",
+                capella_fork_epoch
+            )));
         }
     }
+    Ok(())
+}
+
+pub fn downgrade_from_v14<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    check_downgrade_from_v14::<T>(&db, &log)?;
 
     // Load a V14 op pool and transform it to V12.
     let PersistedOperationPoolV14::<T::EthSpec> {
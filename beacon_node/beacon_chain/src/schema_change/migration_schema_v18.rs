@@ -79,14 +79,16 @@ pub fn upgrade_to_v18<T: BeaconChainTypes>(
     Ok(vec![])
 }
 
-pub fn downgrade_from_v18<T: BeaconChainTypes>(
-    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
-    log: Logger,
-) -> Result<Vec<KeyValueStoreOp>, Error> {
-    // We cannot downgrade from V18 once the Deneb fork has been activated, because there will
-    // be blobs and blob metadata in the database that aren't understood by the V17 schema.
+/// Check that the database can be downgraded to v17.
+///
+/// We cannot downgrade from V18 once the Deneb fork has been activated, because there will
+/// be blobs and blob metadata in the database that aren't understood by the V17 schema.
+pub fn check_downgrade_from_v18<T: BeaconChainTypes>(
+    db: &Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: &Logger,
+) -> Result<(), Error> {
     if let Some(deneb_fork_epoch) = db.get_chain_spec().deneb_fork_epoch {
-        let current_epoch = get_current_epoch::<T>(&db, &log)?;
+        let current_epoch = get_current_epoch::<T>(db, log)?;
         if current_epoch >= deneb_fork_epoch {
             error!(
                 log,
@@ -94,15 +96,29 @@ pub fn downgrade_from_v18<T: BeaconChainTypes>(
                 "current_epoch" => current_epoch,
                 "deneb_fork_epoch" => deneb_fork_epoch,
             );
-            return Err(Error::UnableToDowngrade);
-        } else {
-            info!(
-                log,
-                "Downgrading to v17 schema";
-                "info" => "you will need to upgrade before Deneb",
-                "epochs_until_deneb" => deneb_fork_epoch - current_epoch
-            );
+            return Err(Error::UnableToDowngrade(format!(
+                "Deneb activated at epoch {}, blobs in the database can't be represented by v17",
+                deneb_fork_epoch
+            )));
         }
+    }
+    Ok(())
+}
+
+pub fn downgrade_from_v18<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    check_downgrade_from_v18::<T>(&db, &log)?;
+
+    if let Some(deneb_fork_epoch) = db.get_chain_spec().deneb_fork_epoch {
+        let current_epoch = get_current_epoch::<T>(&db, &log)?;
+        info!(
+            log,
+            "Downgrading to v17 schema";
+            "info" => "you will need to upgrade before Deneb",
+            "epochs_until_deneb" => deneb_fork_epoch - current_epoch
+        );
     } else {
         info!(
             log,
@@ -10,6 +10,7 @@ use types::{
     RelativeEpoch,
 };
 
+use crate::reportable_cache::{CacheCounters, ReportableCache};
 use crate::{metrics, BeaconChainError};
 
 /// The size of the cache that stores committee caches for quicker verification.
@@ -61,6 +62,7 @@ pub struct ShufflingCache {
     cache: HashMap<AttestationShufflingId, CacheItem>,
     cache_size: usize,
     head_shuffling_ids: BlockShufflingIds,
+    counters: CacheCounters,
     logger: Logger,
 }
 
@@ -70,6 +72,7 @@ impl ShufflingCache {
             cache: HashMap::new(),
             cache_size,
             head_shuffling_ids,
+            counters: CacheCounters::default(),
             logger,
         }
     }
@@ -79,6 +82,7 @@ impl ShufflingCache {
             // The cache contained the committee cache, return it.
             item @ Some(CacheItem::Committee(_)) => {
                 metrics::inc_counter(&metrics::SHUFFLING_CACHE_HITS);
+                self.counters.record(true);
                 item.cloned()
             }
             // The cache contains a promise for the committee cache. Check to see if the promise has
@@ -89,6 +93,7 @@ impl ShufflingCache {
                 Ok(Some(committee)) => {
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_HITS);
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_HITS);
+                    self.counters.record(true);
                     let ready = CacheItem::Committee(committee);
                     self.insert_cache_item(key.clone(), ready.clone());
                     Some(ready)
@@ -98,6 +103,7 @@ impl ShufflingCache {
                 Ok(None) => {
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_HITS);
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_HITS);
+                    self.counters.record(true);
                     item.cloned()
                 }
                 // The sender has been dropped without sending a committee. There was most likely an
@@ -112,6 +118,7 @@ impl ShufflingCache {
                 Err(oneshot_broadcast::Error::SenderDropped) => {
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_FAILS);
                     metrics::inc_counter(&metrics::SHUFFLING_CACHE_MISSES);
+                    self.counters.record(false);
                     self.cache.remove(key);
                     None
                 }
@@ -119,6 +126,7 @@ impl ShufflingCache {
             // The cache does not have this committee and it's not already promised to be computed.
             None => {
                 metrics::inc_counter(&metrics::SHUFFLING_CACHE_MISSES);
+                self.counters.record(false);
                 None
             }
         }
@@ -149,17 +157,16 @@ impl ShufflingCache {
 
     /// Prunes the cache first before inserting a new cache item.
     fn insert_cache_item(&mut self, key: AttestationShufflingId, cache_item: CacheItem) {
-        self.prune_cache();
+        self.prune_cache(self.cache_size.saturating_sub(1));
         self.cache.insert(key, cache_item);
     }
 
-    /// Prunes the `cache` to keep the size below the `cache_size` limit, based on the following
+    /// Prunes the `cache` to keep the size at or below `target_cache_size`, based on the following
     /// preferences:
     /// - Entries from more recent epochs are preferred over older ones.
     /// - Entries with shuffling ids matching the head's previous, current, and future epochs must
     ///   not be pruned.
-    fn prune_cache(&mut self) {
-        let target_cache_size = self.cache_size.saturating_sub(1);
+    fn prune_cache(&mut self, target_cache_size: usize) {
         if let Some(prune_count) = self.cache.len().checked_sub(target_cache_size) {
             let shuffling_ids_to_prune = self
                 .cache
@@ -216,6 +223,50 @@ impl ShufflingCache {
     }
 }
 
+impl ReportableCache for ShufflingCache {
+    fn num_entries(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Counts the shuffling and shuffling positions of each committee, ignoring promises.
+    fn byte_estimate(&self) -> usize {
+        self.cache
+            .iter()
+            .map(|(key, item)| {
+                let committee_size = match item {
+                    CacheItem::Committee(committee) => {
+                        committee.active_validator_count() * std::mem::size_of::<usize>() * 2
+                    }
+                    CacheItem::Promise(_) => 0,
+                };
+                std::mem::size_of_val(key) + committee_size
+            })
+            .sum()
+    }
+
+    fn hits(&self) -> Option<u64> {
+        Some(self.counters.hits())
+    }
+
+    fn misses(&self) -> Option<u64> {
+        Some(self.counters.misses())
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.cache_size)
+    }
+
+    fn is_resizable(&self) -> bool {
+        true
+    }
+
+    /// The shufflings of the head are retained even if this leaves the cache above `capacity`.
+    fn resize(&mut self, capacity: usize) {
+        self.cache_size = capacity;
+        self.prune_cache(capacity);
+    }
+}
+
 /// A helper trait to allow lazy-cloning of the committee cache when inserting into the cache.
 pub trait ToArcCommitteeCache {
     fn to_arc_committee_cache(&self) -> Arc<CommitteeCache>;
@@ -581,4 +632,48 @@ mod test {
             "should limit cache size"
         );
     }
+
+    #[test]
+    fn should_evict_on_resize() {
+        let mut cache = new_shuffling_cache();
+        let committee_cache = Arc::new(CommitteeCache::default());
+        let shuffling_ids = (0..TEST_CACHE_SIZE as u64)
+            .map(shuffling_id)
+            .collect::<Vec<_>>();
+        for shuffling_id in &shuffling_ids {
+            cache.insert_committee_cache(shuffling_id.clone(), &committee_cache);
+        }
+        assert_eq!(cache.capacity(), Some(TEST_CACHE_SIZE));
+        assert_eq!(cache.num_entries(), TEST_CACHE_SIZE);
+
+        cache.resize(2);
+
+        assert_eq!(cache.capacity(), Some(2));
+        assert_eq!(cache.num_entries(), 2, "should evict immediately");
+        for shuffling_id in &shuffling_ids[..TEST_CACHE_SIZE - 2] {
+            assert!(
+                !cache.contains(shuffling_id),
+                "should evict the oldest shuffling ids"
+            );
+        }
+        for shuffling_id in &shuffling_ids[TEST_CACHE_SIZE - 2..] {
+            assert!(cache.contains(shuffling_id));
+        }
+
+        // Subsequent inserts respect the new capacity.
+        cache.insert_committee_cache(shuffling_id(TEST_CACHE_SIZE as u64), &committee_cache);
+        assert_eq!(cache.num_entries(), 2);
+    }
+
+    #[test]
+    fn should_count_hits_and_misses() {
+        let mut cache = new_shuffling_cache();
+        let id_a = shuffling_id(1);
+        cache.insert_committee_cache(id_a.clone(), &Arc::new(CommitteeCache::default()));
+
+        assert!(cache.get(&id_a).is_some());
+        assert!(cache.get(&shuffling_id(2)).is_none());
+        assert_eq!(cache.hits(), Some(1));
+        assert_eq!(cache.misses(), Some(1));
+    }
 }
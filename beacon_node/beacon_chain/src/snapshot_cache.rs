@@ -1,5 +1,7 @@
+use crate::reportable_cache::{CacheCounters, ReportableCache};
 use crate::BeaconSnapshot;
 use itertools::process_results;
+use ssz::Encode;
 use std::cmp;
 use std::sync::Arc;
 use std::time::Duration;
@@ -155,6 +157,8 @@ pub struct SnapshotCache<T: EthSpec> {
     max_len: usize,
     head_block_root: Hash256,
     snapshots: Vec<CacheItem<T>>,
+    /// Counts the lookups for block processing and production.
+    counters: CacheCounters,
 }
 
 impl<T: EthSpec> SnapshotCache<T> {
@@ -166,6 +170,7 @@ impl<T: EthSpec> SnapshotCache<T> {
             max_len: cmp::max(max_len, 1),
             head_block_root: head.beacon_block_root,
             snapshots: vec![CacheItem::new_without_pre_state(head)],
+            counters: CacheCounters::default(),
         }
     }
 
@@ -252,23 +257,26 @@ impl<T: EthSpec> SnapshotCache<T> {
         block_delay: Option<Duration>,
         spec: &ChainSpec,
     ) -> Option<(PreProcessingSnapshot<T>, bool)> {
-        self.snapshots
+        let position = self
+            .snapshots
             .iter()
-            .position(|snapshot| snapshot.beacon_block_root == block_root)
-            .map(|i| {
-                if let Some(cache) = self.snapshots.get(i) {
-                    // Avoid cloning the block during sync (when the `block_delay` is `None`).
-                    if let Some(delay) = block_delay {
-                        if delay >= minimum_block_delay_for_clone(spec.seconds_per_slot)
-                            && delay <= Duration::from_secs(spec.seconds_per_slot) * 4
-                            || block_slot > cache.beacon_block.slot() + 1
-                        {
-                            return (cache.clone_as_pre_state(), true);
-                        }
+            .position(|snapshot| snapshot.beacon_block_root == block_root);
+        self.counters.record(position.is_some());
+
+        position.map(|i| {
+            if let Some(cache) = self.snapshots.get(i) {
+                // Avoid cloning the block during sync (when the `block_delay` is `None`).
+                if let Some(delay) = block_delay {
+                    if delay >= minimum_block_delay_for_clone(spec.seconds_per_slot)
+                        && delay <= Duration::from_secs(spec.seconds_per_slot) * 4
+                        || block_slot > cache.beacon_block.slot() + 1
+                    {
+                        return (cache.clone_as_pre_state(), true);
                     }
                 }
-                (self.snapshots.remove(i).into_pre_state(), false)
-            })
+            }
+            (self.snapshots.remove(i).into_pre_state(), false)
+        })
     }
 
     /// If available, obtains a clone of a `BeaconState` that should be used for block production.
@@ -283,22 +291,25 @@ impl<T: EthSpec> SnapshotCache<T> {
         &self,
         block_root: Hash256,
     ) -> Option<BlockProductionPreState<T>> {
-        self.snapshots
+        let snapshot = self
+            .snapshots
             .iter()
-            .find(|snapshot| snapshot.beacon_block_root == block_root)
-            .map(|snapshot| {
-                if let Some(pre_state) = &snapshot.pre_state {
-                    BlockProductionPreState {
-                        pre_state: pre_state.clone_with(CloneConfig::all()),
-                        state_root: None,
-                    }
-                } else {
-                    BlockProductionPreState {
-                        pre_state: snapshot.beacon_state.clone_with(CloneConfig::all()),
-                        state_root: Some(snapshot.beacon_block.state_root()),
-                    }
+            .find(|snapshot| snapshot.beacon_block_root == block_root);
+        self.counters.record(snapshot.is_some());
+
+        snapshot.map(|snapshot| {
+            if let Some(pre_state) = &snapshot.pre_state {
+                BlockProductionPreState {
+                    pre_state: pre_state.clone_with(CloneConfig::all()),
+                    state_root: None,
                 }
-            })
+            } else {
+                BlockProductionPreState {
+                    pre_state: snapshot.beacon_state.clone_with(CloneConfig::all()),
+                    state_root: Some(snapshot.beacon_block.state_root()),
+                }
+            }
+        })
     }
 
     /// If there is a snapshot with `block_root`, clone it and return the clone.
@@ -362,6 +373,62 @@ impl<T: EthSpec> SnapshotCache<T> {
     }
 }
 
+impl<T: EthSpec> ReportableCache for SnapshotCache<T> {
+    fn num_entries(&self) -> usize {
+        self.len()
+    }
+
+    fn byte_estimate(&self) -> usize {
+        self.snapshots
+            .iter()
+            .map(|snapshot| {
+                snapshot.beacon_block.ssz_bytes_len()
+                    + snapshot.beacon_state.ssz_bytes_len()
+                    + snapshot
+                        .pre_state
+                        .as_ref()
+                        .map_or(0, |pre_state| pre_state.ssz_bytes_len())
+            })
+            .sum()
+    }
+
+    fn hits(&self) -> Option<u64> {
+        Some(self.counters.hits())
+    }
+
+    fn misses(&self) -> Option<u64> {
+        Some(self.counters.misses())
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.max_len)
+    }
+
+    fn is_resizable(&self) -> bool {
+        true
+    }
+
+    /// Ejects snapshots in the same order as `Self::insert`, never ejecting the head.
+    fn resize(&mut self, capacity: usize) {
+        self.max_len = cmp::max(capacity, 1);
+        while self.snapshots.len() > self.max_len {
+            let eject = self
+                .snapshots
+                .iter()
+                .enumerate()
+                .filter(|(_, snapshot)| snapshot.beacon_block_root != self.head_block_root)
+                .min_by_key(|(_, snapshot)| snapshot.beacon_state.slot())
+                .map(|(i, _)| i);
+            match eject {
+                Some(i) => {
+                    self.snapshots.remove(i);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
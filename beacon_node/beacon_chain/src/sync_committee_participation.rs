@@ -0,0 +1,231 @@
+//! Provides `SyncCommitteeParticipation`, which records the sync committee bits set in the
+//! `SyncAggregate` of each block imported during the current sync committee period.
+//!
+//! A record is kept for every imported block, so that participation can be computed for whichever
+//! blocks end up canonical. All records are discarded once a later period begins.
+//!
+//! The `SyncAggregate` of a block contains the signatures of the previous slot, however
+//! participation is reported against the slot of the block which included it.
+
+use eth2::lighthouse::{
+    SyncCommitteeEpochParticipation, SyncCommitteeParticipation as Summary,
+    SyncCommitteeValidatorParticipation,
+};
+use std::collections::BTreeMap;
+use types::{Epoch, EthSpec, Hash256, Slot};
+
+/// The sync committee participation in the `SyncAggregate` of a single block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncAggregateRecord {
+    pub block_root: Hash256,
+    /// The number of bits set.
+    pub participants: u64,
+    /// The number of bits in the aggregate.
+    pub committee_size: u64,
+    /// The validator index and bit of each committee position held by a monitored validator.
+    pub monitored: Vec<(u64, bool)>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncCommitteeParticipation {
+    period: Option<u64>,
+    records: BTreeMap<Slot, Vec<SyncAggregateRecord>>,
+}
+
+#[derive(Default)]
+struct EpochTotals {
+    blocks: u64,
+    expected_participants: u64,
+    participants: u64,
+    /// `(expected, hits)` by validator index.
+    validators: BTreeMap<u64, (u64, u64)>,
+}
+
+impl SyncCommitteeParticipation {
+    /// Records the participation in the block at `slot`, which is in the sync committee `period`.
+    ///
+    /// Blocks from earlier periods are ignored, whilst a block from a later period discards all
+    /// existing records.
+    pub fn register(&mut self, period: u64, slot: Slot, record: SyncAggregateRecord) {
+        match self.period {
+            Some(current) if period < current => return,
+            Some(current) if period == current => (),
+            _ => {
+                self.period = Some(period);
+                self.records.clear();
+            }
+        }
+
+        let records = self.records.entry(slot).or_default();
+        records.retain(|existing| existing.block_root != record.block_root);
+        records.push(record);
+    }
+
+    /// Discards all records if they are from a period before `current_period`.
+    pub fn prune(&mut self, current_period: u64) {
+        if self.period.map_or(false, |period| period < current_period) {
+            self.period = None;
+            self.records.clear();
+        }
+    }
+
+    /// Returns the participation in the canonical blocks of the period, by epoch.
+    ///
+    /// `canonical_block_root` returns the root of the canonical block at or before a slot, or
+    /// `None` if it is unknown. Only the records of blocks which are canonical at their own slot
+    /// are counted.
+    pub fn summarize<E: EthSpec>(
+        &self,
+        canonical_block_root: impl Fn(Slot) -> Option<Hash256>,
+    ) -> Summary {
+        let mut epochs = BTreeMap::<Epoch, EpochTotals>::new();
+
+        for (slot, records) in &self.records {
+            let Some(canonical_root) = canonical_block_root(*slot) else {
+                continue;
+            };
+            let Some(record) = records
+                .iter()
+                .find(|record| record.block_root == canonical_root)
+            else {
+                continue;
+            };
+
+            let totals = epochs.entry(slot.epoch(E::slots_per_epoch())).or_default();
+            totals.blocks += 1;
+            totals.expected_participants += record.committee_size;
+            totals.participants += record.participants;
+            for (index, bit) in &record.monitored {
+                let (expected, hits) = totals.validators.entry(*index).or_default();
+                *expected += 1;
+                if *bit {
+                    *hits += 1;
+                }
+            }
+        }
+
+        Summary {
+            period: self.period,
+            epochs: epochs
+                .into_iter()
+                .map(|(epoch, totals)| SyncCommitteeEpochParticipation {
+                    epoch,
+                    blocks: totals.blocks,
+                    expected_participants: totals.expected_participants,
+                    participants: totals.participants,
+                    participation_rate: participation_rate(
+                        totals.participants,
+                        totals.expected_participants,
+                    ),
+                    validators: totals
+                        .validators
+                        .into_iter()
+                        .map(
+                            |(index, (expected, hits))| SyncCommitteeValidatorParticipation {
+                                index,
+                                expected,
+                                hits,
+                            },
+                        )
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns `participants / expected_participants`, or `0.0` if nothing was expected.
+pub fn participation_rate(participants: u64, expected_participants: u64) -> f64 {
+    if expected_participants == 0 {
+        0.0
+    } else {
+        participants as f64 / expected_participants as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn record(root: u64, participants: u64, monitored: Vec<(u64, bool)>) -> SyncAggregateRecord {
+        SyncAggregateRecord {
+            block_root: Hash256::from_low_u64_be(root),
+            participants,
+            committee_size: 512,
+            monitored,
+        }
+    }
+
+    fn canonical(roots: &[(u64, u64)]) -> impl Fn(Slot) -> Option<Hash256> + '_ {
+        move |slot| {
+            roots
+                .iter()
+                .find(|(s, _)| Slot::new(*s) == slot)
+                .map(|(_, root)| Hash256::from_low_u64_be(*root))
+        }
+    }
+
+    #[test]
+    fn only_canonical_blocks_are_counted() {
+        let mut participation = SyncCommitteeParticipation::default();
+        participation.register(0, Slot::new(1), record(1, 512, vec![(7, true)]));
+        participation.register(0, Slot::new(2), record(2, 256, vec![(7, false)]));
+        participation.register(0, Slot::new(2), record(3, 384, vec![(7, true), (7, true)]));
+        participation.register(0, Slot::new(33), record(4, 128, vec![]));
+
+        let summary = participation.summarize::<E>(canonical(&[(1, 1), (2, 3), (33, 4)]));
+        assert_eq!(summary.period, Some(0));
+        assert_eq!(summary.epochs.len(), 2);
+
+        let first = &summary.epochs[0];
+        assert_eq!(first.epoch, Epoch::new(0));
+        assert_eq!(first.blocks, 2);
+        assert_eq!(first.expected_participants, 1024);
+        assert_eq!(first.participants, 896);
+        assert_eq!(first.participation_rate, 0.875);
+        assert_eq!(
+            first.validators,
+            vec![SyncCommitteeValidatorParticipation {
+                index: 7,
+                expected: 3,
+                hits: 3
+            }]
+        );
+
+        let second = &summary.epochs[1];
+        assert_eq!(second.epoch, Epoch::new(1));
+        assert_eq!(second.participation_rate, 0.25);
+        assert!(second.validators.is_empty());
+    }
+
+    #[test]
+    fn records_roll_off_with_the_period() {
+        let mut participation = SyncCommitteeParticipation::default();
+        participation.register(1, Slot::new(1), record(1, 512, vec![]));
+
+        // Blocks from an earlier period are ignored.
+        participation.register(0, Slot::new(2), record(2, 512, vec![]));
+        let summary = participation.summarize::<E>(canonical(&[(1, 1), (2, 2)]));
+        assert_eq!(summary.epochs[0].blocks, 1);
+
+        // A block from a later period replaces the existing records.
+        participation.register(2, Slot::new(3), record(3, 512, vec![]));
+        let summary = participation.summarize::<E>(canonical(&[(1, 1), (3, 3)]));
+        assert_eq!(summary.period, Some(2));
+        assert_eq!(summary.epochs[0].blocks, 1);
+
+        participation.prune(2);
+        assert_eq!(participation.summarize::<E>(|_| None).period, Some(2));
+        participation.prune(3);
+        assert_eq!(
+            participation.summarize::<E>(canonical(&[(3, 3)])),
+            Summary {
+                period: None,
+                epochs: vec![]
+            }
+        );
+    }
+}
@@ -0,0 +1,177 @@
+//! Provides the `SyncCommitteePubkeyCache`, a cache of the decompressed public keys of recent sync
+//! committees.
+//!
+//! Every sync committee message and contribution is verified against the pubkeys of the same
+//! (at most 512) validators for an entire sync committee period. Rather than decompressing those
+//! pubkeys each time they're needed, the whole committee is decompressed once when its period is
+//! first requested and kept until it's no longer the current or next period.
+//!
+//! The cache is keyed by pubkey bytes rather than committee position, so it's always safe to use
+//! regardless of which chain the committee was read from. Pubkeys that are absent from the cache
+//! should be obtained from the `ValidatorPubkeyCache` instead.
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::{EthSpec, PublicKey, PublicKeyBytes, SyncCommittee};
+
+/// The maximum number of sync committee periods to keep in the cache (the current and next).
+const MAX_CACHED_PERIODS: usize = 2;
+
+/// The decompressed pubkeys of a single sync committee.
+pub type SyncCommitteePubkeys = HashMap<PublicKeyBytes, PublicKey>;
+
+#[derive(Default)]
+pub struct SyncCommitteePubkeyCache {
+    periods: RwLock<Vec<(u64, Arc<SyncCommitteePubkeys>)>>,
+}
+
+impl SyncCommitteePubkeyCache {
+    /// Returns the cached pubkeys for the sync committee of `period`, if any.
+    pub fn get(&self, period: u64) -> Option<Arc<SyncCommitteePubkeys>> {
+        self.periods
+            .read()
+            .iter()
+            .find(|(cached_period, _)| *cached_period == period)
+            .map(|(_, pubkeys)| pubkeys.clone())
+    }
+
+    /// Returns the cached pubkeys for the sync committee of `period`, decompressing the pubkeys of
+    /// `committee` with `decompress` if the period is not yet cached.
+    ///
+    /// Pubkeys which `decompress` fails to decompress are omitted from the cache. When a new
+    /// period is inserted, the oldest periods are pruned so that at most `MAX_CACHED_PERIODS`
+    /// remain.
+    pub fn get_or_insert_with<E, F>(
+        &self,
+        period: u64,
+        committee: &SyncCommittee<E>,
+        mut decompress: F,
+    ) -> Arc<SyncCommitteePubkeys>
+    where
+        E: EthSpec,
+        F: FnMut(&PublicKeyBytes) -> Option<PublicKey>,
+    {
+        // Hold the write lock whilst building so that concurrent callers don't each decompress
+        // the same committee.
+        let mut periods = self.periods.write();
+
+        if let Some((_, pubkeys)) = periods
+            .iter()
+            .find(|(cached_period, _)| *cached_period == period)
+        {
+            return pubkeys.clone();
+        }
+
+        let pubkeys = Arc::new(
+            committee
+                .pubkeys
+                .iter()
+                .filter_map(|pubkey_bytes| {
+                    decompress(pubkey_bytes).map(|pubkey| (*pubkey_bytes, pubkey))
+                })
+                .collect::<SyncCommitteePubkeys>(),
+        );
+
+        periods.push((period, pubkeys.clone()));
+        periods.sort_unstable_by_key(|(cached_period, _)| *cached_period);
+        while periods.len() > MAX_CACHED_PERIODS {
+            periods.remove(0);
+        }
+
+        pubkeys
+    }
+
+    /// Returns the periods that are currently cached, in ascending order.
+    pub fn cached_periods(&self) -> Vec<u64> {
+        self.periods
+            .read()
+            .iter()
+            .map(|(period, _)| *period)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz_types::FixedVector;
+    use std::cell::Cell;
+    use types::test_utils::generate_deterministic_keypair;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    /// Returns a committee of distinct pubkeys, which are unique to `period`.
+    fn committee(period: u64) -> SyncCommittee<E> {
+        let pubkeys = (0..E::sync_committee_size())
+            .map(|i| {
+                let mut bytes = [0; 48];
+                bytes[0..8].copy_from_slice(&period.to_le_bytes());
+                bytes[8..16].copy_from_slice(&(i as u64).to_le_bytes());
+                PublicKeyBytes::deserialize(&bytes).unwrap()
+            })
+            .collect::<Vec<_>>();
+        SyncCommittee {
+            pubkeys: FixedVector::new(pubkeys).unwrap(),
+            aggregate_pubkey: PublicKeyBytes::empty(),
+        }
+    }
+
+    #[test]
+    fn decompresses_each_committee_once_per_period() {
+        let cache = SyncCommitteePubkeyCache::default();
+        let pubkey = generate_deterministic_keypair(0).pk;
+        let decompressions = Cell::new(0);
+        let counting_decompress = |_: &PublicKeyBytes| {
+            decompressions.set(decompressions.get() + 1);
+            Some(pubkey.clone())
+        };
+
+        for period in 0..4 {
+            let committee = committee(period);
+            decompressions.set(0);
+
+            // Simulate a large volume of messages and contributions within the period.
+            for _ in 0..10_000 {
+                let pubkeys = cache.get(period).unwrap_or_else(|| {
+                    cache.get_or_insert_with(period, &committee, counting_decompress)
+                });
+                assert_eq!(pubkeys.len(), E::sync_committee_size());
+            }
+
+            assert!(decompressions.get() <= E::sync_committee_size());
+        }
+    }
+
+    #[test]
+    fn bounded_to_two_periods() {
+        let cache = SyncCommitteePubkeyCache::default();
+        let pubkey = generate_deterministic_keypair(0).pk;
+        let decompress = |_: &PublicKeyBytes| Some(pubkey.clone());
+
+        cache.get_or_insert_with(1, &committee(1), decompress);
+        cache.get_or_insert_with(2, &committee(2), decompress);
+        assert_eq!(cache.cached_periods(), vec![1, 2]);
+
+        cache.get_or_insert_with(3, &committee(3), decompress);
+        assert_eq!(cache.cached_periods(), vec![2, 3]);
+        assert!(cache.get(1).is_none());
+
+        // A period older than those cached is returned but not retained.
+        let pubkeys = cache.get_or_insert_with(0, &committee(0), decompress);
+        assert_eq!(pubkeys.len(), E::sync_committee_size());
+        assert_eq!(cache.cached_periods(), vec![2, 3]);
+    }
+
+    #[test]
+    fn invalid_pubkeys_are_omitted() {
+        let cache = SyncCommitteePubkeyCache::default();
+        let committee = committee(0);
+
+        let pubkeys =
+            cache.get_or_insert_with(0, &committee, |pubkey_bytes| pubkey_bytes.decompress().ok());
+
+        assert!(pubkeys.is_empty());
+    }
+}
@@ -1,14 +1,22 @@
 use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
 
-use eth2::lighthouse::SyncCommitteeReward;
+use eth2::types::SyncCommitteeReward;
 use safe_arith::SafeArith;
 use slog::error;
-use state_processing::per_block_processing::altair::sync_committee::compute_sync_aggregate_rewards;
+use state_processing::per_block_processing::altair::sync_committee::{
+    sync_aggregate_balance_changes, SyncAggregateBalanceChange,
+};
 use std::collections::HashMap;
 use store::RelativeEpoch;
 use types::{AbstractExecPayload, BeaconBlockRef, BeaconState};
 
 impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Computes the reward (or penalty, if negative) of each sync committee member for the sync
+    /// aggregate in `block`, as applied by `process_sync_aggregate`.
+    ///
+    /// The `state` should be the pre-state of `block`, advanced to its slot. Members which appear
+    /// in the committee more than once have their rewards summed. Rewards received by the proposer
+    /// for including the aggregate are not counted.
     pub fn compute_sync_committee_rewards<Payload: AbstractExecPayload<T::EthSpec>>(
         &self,
         block: BeaconBlockRef<'_, T::EthSpec, Payload>,
@@ -24,64 +32,53 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let sync_aggregate = block.body().sync_aggregate()?;
 
-        let sync_committee = state.current_sync_committee()?.clone();
-
-        let sync_committee_indices = state.get_sync_committee_indices(&sync_committee)?;
-
-        let (participant_reward_value, proposer_reward_per_bit) =
-            compute_sync_aggregate_rewards(state, spec).map_err(|e| {
-                error!(
-                    self.log, "Error calculating sync aggregate rewards";
-                    "error" => ?e
-                );
-                BeaconChainError::SyncCommitteeRewardsSyncError
-            })?;
+        let balance_changes =
+            sync_aggregate_balance_changes(state, sync_aggregate, block.proposer_index(), spec)
+                .map_err(|e| {
+                    error!(
+                        self.log, "Error calculating sync aggregate rewards";
+                        "error" => ?e
+                    );
+                    BeaconChainError::SyncCommitteeRewardsSyncError
+                })?;
 
+        // Replay the changes against the members' balances, so that penalties are capped at the
+        // balance in the same way as during block processing.
         let mut balances = HashMap::<usize, u64>::new();
-
-        let mut total_proposer_rewards = 0;
-        let proposer_index = state.get_beacon_proposer_index(block.slot(), spec)?;
-
-        // Apply rewards to participant balances. Keep track of proposer rewards
-        for (validator_index, participant_bit) in sync_committee_indices
-            .iter()
-            .zip(sync_aggregate.sync_committee_bits.iter())
-        {
-            let participant_balance = balances
-                .entry(*validator_index)
-                .or_insert_with(|| state.balances()[*validator_index]);
-
-            if participant_bit {
-                participant_balance.safe_add_assign(participant_reward_value)?;
-
-                balances
-                    .entry(proposer_index)
-                    .or_insert_with(|| state.balances()[proposer_index])
-                    .safe_add_assign(proposer_reward_per_bit)?;
-
-                total_proposer_rewards.safe_add_assign(proposer_reward_per_bit)?;
-            } else {
-                *participant_balance = participant_balance.saturating_sub(participant_reward_value);
+        for change in balance_changes {
+            match change {
+                SyncAggregateBalanceChange::ParticipantReward {
+                    validator_index,
+                    reward,
+                } => {
+                    balances
+                        .entry(validator_index)
+                        .or_insert_with(|| state.balances()[validator_index])
+                        .safe_add_assign(reward)?;
+                }
+                SyncAggregateBalanceChange::NonParticipantPenalty {
+                    validator_index,
+                    penalty,
+                } => {
+                    let balance = balances
+                        .entry(validator_index)
+                        .or_insert_with(|| state.balances()[validator_index]);
+                    *balance = balance.saturating_sub(penalty);
+                }
+                // Proposer rewards are reported by the block rewards endpoint.
+                SyncAggregateBalanceChange::ProposerReward { .. } => (),
             }
         }
 
-        Ok(balances
-            .iter()
-            .filter_map(|(i, new_balance)| {
-                let reward = if *i != proposer_index {
-                    *new_balance as i64 - state.balances()[*i] as i64
-                } else if sync_committee_indices.contains(i) {
-                    *new_balance as i64
-                        - state.balances()[*i] as i64
-                        - total_proposer_rewards as i64
-                } else {
-                    return None;
-                };
-                Some(SyncCommitteeReward {
-                    validator_index: *i as u64,
-                    reward,
-                })
+        let mut rewards = balances
+            .into_iter()
+            .map(|(validator_index, new_balance)| SyncCommitteeReward {
+                validator_index: validator_index as u64,
+                reward: new_balance as i64 - state.balances()[validator_index] as i64,
             })
-            .collect())
+            .collect::<Vec<_>>();
+        rewards.sort_unstable_by_key(|reward| reward.validator_index);
+
+        Ok(rewards)
     }
 }
@@ -27,11 +27,13 @@
 //! ```
 
 use crate::observed_attesters::SlotSubcommitteeIndex;
+use crate::sync_committee_pubkey_cache::SyncCommitteePubkeys;
+use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
     beacon_chain::VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT, metrics,
     observed_aggregates::ObserveOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
-use bls::{verify_signature_sets, PublicKeyBytes};
+use bls::{verify_signature_sets, PublicKey, PublicKeyBytes};
 use derivative::Derivative;
 use safe_arith::ArithError;
 use slot_clock::SlotClock;
@@ -619,6 +621,13 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
     signed_aggregate: &SignedContributionAndProof<T::EthSpec>,
     participant_pubkeys: &[PublicKeyBytes],
 ) -> Result<bool, Error> {
+    // Prefer the decompressed pubkeys of the sync committee, falling back to the general pubkey
+    // cache if they're unavailable. This is obtained prior to locking the pubkey cache since it
+    // may require loading a state.
+    let committee_pubkeys = chain
+        .sync_committee_pubkeys_at_next_slot(signed_aggregate.message.contribution.slot)
+        .ok();
+
     let pubkey_cache = chain
         .validator_pubkey_cache
         .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
@@ -651,10 +660,8 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         )
         .map_err(BeaconChainError::SignatureSetError)?,
         sync_committee_contribution_signature_set_from_pubkeys::<T::EthSpec, _>(
-            |validator_index| {
-                pubkey_cache
-                    .get_pubkey_from_pubkey_bytes(validator_index)
-                    .map(Cow::Borrowed)
+            |pubkey_bytes| {
+                get_sync_committee_pubkey(committee_pubkeys.as_deref(), &pubkey_cache, pubkey_bytes)
             },
             participant_pubkeys,
             &signed_aggregate.message.contribution.signature,
@@ -683,15 +690,18 @@ pub fn verify_sync_committee_message<T: BeaconChainTypes>(
     let signature_setup_timer =
         metrics::start_timer(&metrics::SYNC_MESSAGE_PROCESSING_SIGNATURE_SETUP_TIMES);
 
+    let committee_pubkeys = chain
+        .sync_committee_pubkeys_at_next_slot(sync_message.get_slot())
+        .ok();
+
     let pubkey_cache = chain
         .validator_pubkey_cache
         .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
         .ok_or(BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
 
-    let pubkey = pubkey_cache
-        .get_pubkey_from_pubkey_bytes(pubkey_bytes)
-        .map(Cow::Borrowed)
-        .ok_or(Error::UnknownValidatorPubkey(*pubkey_bytes))?;
+    let pubkey =
+        get_sync_committee_pubkey(committee_pubkeys.as_deref(), &pubkey_cache, pubkey_bytes)
+            .ok_or(Error::UnknownValidatorPubkey(*pubkey_bytes))?;
 
     let next_slot_epoch = (sync_message.get_slot() + 1).epoch(T::EthSpec::slots_per_epoch());
     let fork = chain.spec.fork_at_epoch(next_slot_epoch);
@@ -719,3 +729,16 @@ pub fn verify_sync_committee_message<T: BeaconChainTypes>(
         Err(Error::InvalidSignature)
     }
 }
+
+/// Returns the decompressed pubkey for `pubkey_bytes` from `committee_pubkeys`, or from the
+/// `pubkey_cache` if it's not present there.
+fn get_sync_committee_pubkey<'a, T: BeaconChainTypes>(
+    committee_pubkeys: Option<&'a SyncCommitteePubkeys>,
+    pubkey_cache: &'a ValidatorPubkeyCache<T>,
+    pubkey_bytes: &PublicKeyBytes,
+) -> Option<Cow<'a, PublicKey>> {
+    committee_pubkeys
+        .and_then(|pubkeys| pubkeys.get(pubkey_bytes))
+        .or_else(|| pubkey_cache.get_pubkey_from_pubkey_bytes(pubkey_bytes))
+        .map(Cow::Borrowed)
+}
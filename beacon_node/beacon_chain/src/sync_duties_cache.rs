@@ -0,0 +1,154 @@
+//! The `SyncDutiesCache` stores the sync committee duties of every member of a sync committee, by
+//! sync committee period and dependent root.
+//!
+//! A sync committee is fixed for its entire period, so the duties of a period are built once from
+//! the first state which knows its committee and requests for any set of validators are served
+//! from them. The dependent root is the root of the block which determined the committee, so that
+//! chains which disagree on the committee of a period never share its duties. Periods are dropped
+//! once they are more than one period behind the current period.
+
+use crate::metrics;
+use parking_lot::RwLock;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use types::{EthSpec, Hash256, PublicKeyBytes, SyncCommittee, SyncDuty};
+
+/// The duties of the members of a sync committee, by validator index.
+pub type PeriodDuties = HashMap<u64, SyncDuty>;
+
+#[derive(Default)]
+pub struct SyncDutiesCache {
+    periods: RwLock<BTreeMap<(u64, Hash256), Arc<PeriodDuties>>>,
+    /// The number of times the duties of a period have been built.
+    builds: AtomicU64,
+}
+
+impl SyncDutiesCache {
+    /// Returns the duties for `period` with the given `dependent_root`, if they are cached.
+    pub fn get(&self, period: u64, dependent_root: Hash256) -> Option<Arc<PeriodDuties>> {
+        let duties = self.periods.read().get(&(period, dependent_root)).cloned();
+        if duties.is_some() {
+            metrics::inc_counter(&metrics::SYNC_DUTIES_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::SYNC_DUTIES_CACHE_MISSES);
+        }
+        duties
+    }
+
+    /// Builds and stores the duties for `period` with the given `dependent_root` from its
+    /// `sync_committee`, dropping any periods before the one preceding `current_period`.
+    ///
+    /// `validator_index` returns the index of a member of the committee.
+    pub fn insert<E: EthSpec>(
+        &self,
+        period: u64,
+        dependent_root: Hash256,
+        current_period: u64,
+        sync_committee: &SyncCommittee<E>,
+        validator_index: impl Fn(&PublicKeyBytes) -> Option<usize>,
+    ) -> Arc<PeriodDuties> {
+        let mut duties = PeriodDuties::new();
+        for pubkey in sync_committee.pubkeys.iter() {
+            let Some(index) = validator_index(pubkey) else {
+                continue;
+            };
+            let index = index as u64;
+            if duties.contains_key(&index) {
+                continue;
+            }
+            if let Some(duty) = SyncDuty::from_sync_committee(index, *pubkey, sync_committee) {
+                duties.insert(index, duty);
+            }
+        }
+        let duties = Arc::new(duties);
+        self.builds.fetch_add(1, Ordering::Relaxed);
+
+        let mut periods = self.periods.write();
+        periods.retain(|(cached_period, _), _| cached_period + 1 >= current_period);
+        periods.insert((period, dependent_root), duties.clone());
+        duties
+    }
+
+    /// Returns the number of times the duties of a period have been built.
+    pub fn builds(&self) -> u64 {
+        self.builds.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{FixedVector, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn pubkey(i: u8) -> PublicKeyBytes {
+        let mut bytes = [0; 48];
+        bytes[0] = i;
+        PublicKeyBytes::deserialize(&bytes).unwrap()
+    }
+
+    /// A committee in which validator `i` holds positions `i` and `i + 16`.
+    fn committee() -> SyncCommittee<E> {
+        let pubkeys = (0..E::sync_committee_size())
+            .map(|i| pubkey((i % 16) as u8))
+            .collect::<Vec<_>>();
+        SyncCommittee {
+            pubkeys: FixedVector::new(pubkeys).unwrap(),
+            aggregate_pubkey: PublicKeyBytes::empty(),
+        }
+    }
+
+    fn index(pubkey: &PublicKeyBytes) -> Option<usize> {
+        Some(pubkey.as_serialized()[0] as usize)
+    }
+
+    #[test]
+    fn builds_duties_for_every_member() {
+        let cache = SyncDutiesCache::default();
+        let root = Hash256::repeat_byte(1);
+        let duties = cache.insert(0, root, 0, &committee(), index);
+
+        assert_eq!(duties.len(), 16);
+        assert_eq!(duties[&3].validator_sync_committee_indices, vec![3, 19]);
+        assert_eq!(duties[&3].pubkey, pubkey(3));
+        assert_eq!(cache.builds(), 1);
+        assert!(cache.get(0, root).is_some());
+        assert!(cache.get(1, root).is_none());
+    }
+
+    #[test]
+    fn dependent_roots_are_cached_separately() {
+        let cache = SyncDutiesCache::default();
+        let root_a = Hash256::repeat_byte(1);
+        let root_b = Hash256::repeat_byte(2);
+        let duties_a = cache.insert(2, root_a, 1, &committee(), index);
+        assert!(cache.get(2, root_b).is_none());
+
+        // A committee with no known members.
+        let duties_b = cache.insert(2, root_b, 1, &committee(), |_| None);
+        assert!(Arc::ptr_eq(&cache.get(2, root_a).unwrap(), &duties_a));
+        assert!(Arc::ptr_eq(&cache.get(2, root_b).unwrap(), &duties_b));
+        assert!(duties_b.is_empty());
+        assert_eq!(cache.builds(), 2);
+    }
+
+    #[test]
+    fn old_periods_are_dropped() {
+        let cache = SyncDutiesCache::default();
+        let root = Hash256::repeat_byte(1);
+        let other_root = Hash256::repeat_byte(2);
+        cache.insert(1, root, 1, &committee(), index);
+        cache.insert(1, other_root, 1, &committee(), index);
+        cache.insert(2, root, 1, &committee(), index);
+        cache.insert(3, root, 2, &committee(), index);
+        assert!(cache.get(1, root).is_some());
+
+        cache.insert(4, root, 3, &committee(), index);
+        assert!(cache.get(1, root).is_none());
+        assert!(cache.get(1, other_root).is_none());
+        assert!(cache.get(2, root).is_some());
+        assert_eq!(cache.builds(), 5);
+    }
+}
@@ -452,14 +452,21 @@ where
     }
 
     pub fn mock_execution_layer(self) -> Self {
-        self.mock_execution_layer_with_config(None)
+        self.mock_execution_layer_with_config(None, None, false)
     }
 
-    pub fn mock_execution_layer_with_config(mut self, builder_threshold: Option<u128>) -> Self {
+    pub fn mock_execution_layer_with_config(
+        mut self,
+        builder_threshold: Option<u128>,
+        builder_extra_data_prefix: Option<Vec<u8>>,
+        strict_fee_recipient: bool,
+    ) -> Self {
         let mock = mock_execution_layer_from_parts::<E>(
             self.spec.as_ref().expect("cannot build without spec"),
             self.runtime.task_executor.clone(),
             builder_threshold,
+            builder_extra_data_prefix,
+            strict_fee_recipient,
         );
         self.execution_layer = Some(mock.el.clone());
         self.mock_execution_layer = Some(mock);
@@ -563,6 +570,8 @@ pub fn mock_execution_layer_from_parts<T: EthSpec>(
     spec: &ChainSpec,
     task_executor: TaskExecutor,
     builder_threshold: Option<u128>,
+    builder_extra_data_prefix: Option<Vec<u8>>,
+    strict_fee_recipient: bool,
 ) -> MockExecutionLayer<T> {
     let shanghai_time = spec.capella_fork_epoch.map(|epoch| {
         HARNESS_GENESIS_TIME + spec.seconds_per_slot * T::slots_per_epoch() * epoch.as_u64()
@@ -583,6 +592,8 @@ pub fn mock_execution_layer_from_parts<T: EthSpec>(
         shanghai_time,
         cancun_time,
         builder_threshold,
+        builder_extra_data_prefix,
+        strict_fee_recipient,
         Some(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap()),
         spec.clone(),
         Some(kzg),
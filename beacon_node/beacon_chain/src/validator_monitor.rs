@@ -3,7 +3,11 @@
 //! This component should not affect consensus.
 
 use crate::metrics;
-use parking_lot::RwLock;
+use crate::sync_committee_participation::{
+    participation_rate, SyncAggregateRecord, SyncCommitteeParticipation,
+};
+use eth2::lighthouse::SyncCommitteeParticipation as SyncCommitteeParticipationSummary;
+use parking_lot::{Mutex, RwLock};
 use slog::{crit, debug, info, Logger};
 use slot_clock::SlotClock;
 use state_processing::per_epoch_processing::{
@@ -19,7 +23,8 @@ use store::AbstractExecPayload;
 use types::{
     AttesterSlashing, BeaconBlockRef, BeaconState, ChainSpec, Epoch, EthSpec, Hash256,
     IndexedAttestation, ProposerSlashing, PublicKeyBytes, SignedAggregateAndProof,
-    SignedContributionAndProof, Slot, SyncCommitteeMessage, VoluntaryExit,
+    SignedContributionAndProof, Slot, SyncAggregate, SyncCommittee, SyncCommitteeMessage,
+    VoluntaryExit,
 };
 
 /// Used for Prometheus labels.
@@ -343,6 +348,8 @@ pub struct ValidatorMonitor<T> {
     /// large validator counts causing infeasibly high cardinailty for
     /// Prometheus and high log volumes.
     individual_tracking_threshold: usize,
+    /// Sync committee participation in the blocks of the current sync committee period.
+    sync_committee_participation: Mutex<SyncCommitteeParticipation>,
     log: Logger,
     _phantom: PhantomData<T>,
 }
@@ -359,6 +366,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             indices: <_>::default(),
             auto_register,
             individual_tracking_threshold,
+            sync_committee_participation: <_>::default(),
             log,
             _phantom: PhantomData,
         };
@@ -1407,6 +1415,105 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         }
     }
 
+    /// Register the participation of `sync_committee` in the `sync_aggregate` of a *valid* block.
+    ///
+    /// `period` is the sync committee period of the block.
+    pub fn register_sync_aggregate_participation(
+        &self,
+        slot: Slot,
+        block_root: Hash256,
+        period: u64,
+        sync_committee: &SyncCommittee<T>,
+        sync_aggregate: &SyncAggregate<T>,
+    ) {
+        let monitored = sync_committee
+            .pubkeys
+            .iter()
+            .zip(sync_aggregate.sync_committee_bits.iter())
+            .filter_map(|(pubkey, bit)| {
+                self.validators
+                    .get(pubkey)
+                    .and_then(|validator| validator.index)
+                    .map(|index| (index, bit))
+            })
+            .collect();
+
+        self.sync_committee_participation.lock().register(
+            period,
+            slot,
+            SyncAggregateRecord {
+                block_root,
+                participants: sync_aggregate.num_set_bits() as u64,
+                committee_size: T::sync_committee_size() as u64,
+                monitored,
+            },
+        );
+    }
+
+    /// Returns the sync committee participation in the canonical blocks of the current sync
+    /// committee period.
+    ///
+    /// See `SyncCommitteeParticipation::summarize`.
+    pub fn sync_committee_participation(
+        &self,
+        canonical_block_root: impl Fn(Slot) -> Option<Hash256>,
+    ) -> SyncCommitteeParticipationSummary {
+        self.sync_committee_participation
+            .lock()
+            .summarize::<T>(canonical_block_root)
+    }
+
+    /// Discards sync committee participation from before `current_period` and updates the
+    /// participation metrics from the canonical blocks of the remaining period.
+    pub fn update_sync_committee_participation(
+        &self,
+        current_period: u64,
+        canonical_block_root: impl Fn(Slot) -> Option<Hash256>,
+    ) {
+        let summary = {
+            let mut participation = self.sync_committee_participation.lock();
+            participation.prune(current_period);
+            participation.summarize::<T>(canonical_block_root)
+        };
+
+        let (participants, expected_participants) =
+            summary
+                .epochs
+                .iter()
+                .fold((0, 0), |(participants, expected), epoch| {
+                    (
+                        participants + epoch.participants,
+                        expected + epoch.expected_participants,
+                    )
+                });
+        metrics::set_float_gauge(
+            &metrics::SYNC_COMMITTEE_PARTICIPATION_RATE,
+            participation_rate(participants, expected_participants),
+        );
+
+        let mut hits = HashMap::<u64, u64>::new();
+        for validator in summary.epochs.iter().flat_map(|epoch| &epoch.validators) {
+            *hits.entry(validator.index).or_default() += validator.hits;
+        }
+        metrics::set_gauge_vec(
+            &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_HITS,
+            &[TOTAL_LABEL],
+            hits.values().sum::<u64>() as i64,
+        );
+        if self.individual_tracking() {
+            for validator in self.validators.values() {
+                let Some(index) = validator.index else {
+                    continue;
+                };
+                metrics::set_gauge_vec(
+                    &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_HITS,
+                    &[&validator.id],
+                    hits.get(&index).copied().unwrap_or(0) as i64,
+                );
+            }
+        }
+    }
+
     /// Register an exit from the gossip network.
     pub fn register_gossip_voluntary_exit(&self, exit: &VoluntaryExit) {
         self.register_voluntary_exit("gossip", exit)
@@ -1,9 +1,11 @@
 use crate::errors::BeaconChainError;
+use crate::reportable_cache::ReportableCache;
 use crate::{BeaconChainTypes, BeaconStore};
 use ssz::{Decode, Encode};
 use std::collections::HashMap;
 use std::convert::TryInto;
 use std::marker::PhantomData;
+use std::mem;
 use store::{DBColumn, Error as StoreError, StoreItem, StoreOp};
 use types::{BeaconState, Hash256, PublicKey, PublicKeyBytes};
 
@@ -163,6 +165,25 @@ impl<T: BeaconChainTypes> ValidatorPubkeyCache<T> {
     }
 }
 
+/// The cache holds every known validator, so it is unbounded. Lookups are not counted since
+/// they are on the hot path of signature verification.
+impl<T: BeaconChainTypes> ReportableCache for ValidatorPubkeyCache<T> {
+    fn num_entries(&self) -> usize {
+        self.len()
+    }
+
+    fn byte_estimate(&self) -> usize {
+        self.len()
+            * (mem::size_of::<PublicKey>()
+                + 2 * mem::size_of::<PublicKeyBytes>()
+                + mem::size_of::<usize>())
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+}
+
 /// Wrapper for a public key stored in the database.
 ///
 /// Keyed by the validator index as `Hash256::from_low_u64_be(index)`.
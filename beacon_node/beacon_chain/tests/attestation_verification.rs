@@ -164,6 +164,15 @@ fn get_valid_unaggregated_attestation<T: BeaconChainTypes>(
 fn get_valid_aggregated_attestation<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     aggregate: Attestation<T::EthSpec>,
+) -> (SignedAggregateAndProof<T::EthSpec>, usize, SecretKey) {
+    get_valid_aggregated_attestation_from_nth_aggregator(chain, aggregate, 0)
+}
+
+/// Returns `aggregate` signed by the `n`th aggregator of its committee.
+fn get_valid_aggregated_attestation_from_nth_aggregator<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    aggregate: Attestation<T::EthSpec>,
+    n: usize,
 ) -> (SignedAggregateAndProof<T::EthSpec>, usize, SecretKey) {
     let head = chain.head_snapshot();
     let state = &head.beacon_state;
@@ -177,7 +186,7 @@ fn get_valid_aggregated_attestation<T: BeaconChainTypes>(
     let (aggregator_index, aggregator_sk) = committee
         .committee
         .iter()
-        .find_map(|&val_index| {
+        .filter_map(|&val_index| {
             let aggregator_sk = generate_deterministic_keypair(val_index).sk;
 
             let proof = SelectionProof::new::<T::EthSpec>(
@@ -194,6 +203,7 @@ fn get_valid_aggregated_attestation<T: BeaconChainTypes>(
                 None
             }
         })
+        .nth(n)
         .expect("should find aggregator for committee");
 
     let signed_aggregate = SignedAggregateAndProof::from_aggregate(
@@ -1003,6 +1013,97 @@ async fn unaggregated_gossip_verification() {
         );
 }
 
+/// Ensures that aggregates are ignored before signature verification when the op pool holds an
+/// attestation which includes all of their signers, and are accepted otherwise.
+#[tokio::test]
+async fn aggregate_subset_of_op_pool_attestation() {
+    let mut spec = test_spec::<E>();
+    // Make every member of a committee an aggregator, so that several aggregates can be accepted.
+    spec.target_aggregators_per_committee = VALIDATOR_COUNT as u64;
+
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize * 3 - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let chain = &harness.chain;
+    let head = chain.head_snapshot();
+    let current_slot = chain.slot().unwrap();
+    let unsigned = chain
+        .produce_unaggregated_attestation(current_slot, 0)
+        .unwrap();
+    let committee = head
+        .beacon_state
+        .get_beacon_committee(current_slot, 0)
+        .unwrap()
+        .committee
+        .to_vec();
+
+    // Returns an attestation signed by the members at the given committee positions.
+    let attestation_of = |positions: &[usize]| {
+        let mut attestation = unsigned.clone();
+        for &position in positions {
+            attestation
+                .sign(
+                    &generate_deterministic_keypair(committee[position]).sk,
+                    position,
+                    &head.beacon_state.fork(),
+                    chain.genesis_validators_root,
+                    &chain.spec,
+                )
+                .unwrap();
+        }
+        attestation
+    };
+
+    let pooled = attestation_of(&[0, 1, 2, 3]);
+    let mut attesting_indices = (0..4)
+        .map(|position| committee[position] as u64)
+        .collect::<Vec<_>>();
+    attesting_indices.sort_unstable();
+    chain
+        .op_pool
+        .insert_attestation(pooled, attesting_indices)
+        .unwrap();
+
+    let verify = |positions: &[usize], aggregator: usize| {
+        let (aggregate, _, _) = get_valid_aggregated_attestation_from_nth_aggregator(
+            chain,
+            attestation_of(positions),
+            aggregator,
+        );
+        chain
+            .verify_aggregated_attestation_for_gossip(&aggregate)
+            .map(|_| ())
+    };
+
+    // Subset.
+    assert!(matches!(
+        verify(&[1, 2], 0).err().unwrap(),
+        AttnError::AttestationSupersetKnown(_)
+    ));
+    // Equal.
+    assert!(matches!(
+        verify(&[0, 1, 2, 3], 0).err().unwrap(),
+        AttnError::AttestationSupersetKnown(_)
+    ));
+    // Overlapping, but not a subset.
+    assert!(verify(&[3, 4], 0).is_ok());
+    // Disjoint.
+    assert!(verify(&[5, 6], 1).is_ok());
+}
+
 /// Ensures that an attestation that skips epochs can still be processed.
 ///
 /// This also checks that we can do a state lookup if we don't get a hit from the shuffling cache.
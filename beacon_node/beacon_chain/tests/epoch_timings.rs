@@ -0,0 +1,96 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+use eth2::lighthouse::BlockArrivalDelays;
+use slot_clock::SlotClock;
+use std::time::Duration;
+use types::{Epoch, EthSpec, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 24;
+
+/// Produces the blocks of the first epoch with the slot clock running ahead of each block's slot
+/// by an increasing offset, then checks that the recorded arrival delays match those offsets.
+#[tokio::test]
+async fn epoch_timings_reflect_block_arrival_offsets() {
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    let mut state = harness.get_current_state();
+    let mut offsets_ms = vec![];
+    for i in 1..E::slots_per_epoch() {
+        let slot = harness.get_current_slot();
+        let offset = Duration::from_millis(250 * i);
+        offsets_ms.push(offset.as_millis() as u64);
+
+        let slot_start = harness.chain.slot_clock.start_of(slot).unwrap();
+        harness
+            .chain
+            .slot_clock
+            .set_current_time(slot_start + offset);
+
+        let (block_contents, new_state) = harness.make_block(state, slot).await;
+        let block_root = block_contents.0.canonical_root();
+
+        // Record the block as observed now, as the gossip handler does.
+        harness.chain.block_times_cache.write().set_time_observed(
+            block_root,
+            slot,
+            harness.chain.slot_clock.now_duration().unwrap(),
+            None,
+            None,
+        );
+        harness
+            .process_block(slot, block_root, block_contents)
+            .await
+            .unwrap();
+
+        state = new_state;
+        harness.advance_slot();
+    }
+
+    let timings = harness
+        .chain
+        .epoch_timings_cache
+        .get(Epoch::new(0))
+        .unwrap();
+    assert_eq!(
+        timings.block_arrival_delays,
+        Some(BlockArrivalDelays {
+            blocks: offsets_ms.len() as u64,
+            min_ms: offsets_ms[0],
+            mean_ms: offsets_ms.iter().sum::<u64>() / offsets_ms.len() as u64,
+            median_ms: offsets_ms[offsets_ms.len() / 2],
+            max_ms: *offsets_ms.last().unwrap(),
+        })
+    );
+    assert!(timings.inclusion_distances.is_empty());
+
+    // Blocks in the next epoch pack the attestations to their parents, which are included at
+    // the earliest opportunity. None of them were observed on gossip.
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let timings = harness
+        .chain
+        .epoch_timings_cache
+        .get(Epoch::new(1))
+        .unwrap();
+    assert_eq!(timings.block_arrival_delays, None);
+    assert!(!timings.inclusion_distances.is_empty());
+    assert_eq!(
+        timings.inclusion_distances.keys().collect::<Vec<_>>(),
+        vec![&1]
+    );
+}
@@ -1,13 +1,16 @@
 use beacon_chain::blob_verification::GossipVerifiedBlob;
 use beacon_chain::test_utils::BeaconChainHarness;
 use bls::Signature;
-use eth2::types::{EventKind, SseBlobSidecar};
+use eth2::types::{EventKind, SseBlobSidecar, SseProposerDutiesReorg};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::TryRecvError;
 use types::blob_sidecar::FixedBlobSidecarList;
-use types::{BlobSidecar, EthSpec, ForkName, MinimalEthSpec, SignedBlobSidecar};
+use types::{
+    BlobSidecar, Epoch, EthSpec, ForkName, Hash256, MinimalEthSpec, SignedBlobSidecar, Slot,
+};
 
 type E = MinimalEthSpec;
 
@@ -97,3 +100,64 @@ async fn blob_sidecar_event_on_process_rpc_blobs() {
     }
     assert_eq!(sse_blobs, expected_sse_blobs);
 }
+
+/// Verifies that a single proposer duties reorg event is emitted for the epoch whose dependent root
+/// is changed by a re-org, and none for ordinary changes of head.
+#[tokio::test]
+async fn proposer_duties_reorg_event_on_re_org() {
+    let validator_count = 32;
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(validator_count)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    let all_validators = (0..validator_count).collect::<Vec<_>>();
+
+    // Build the chain up to the second last slot of epoch 1.
+    let fork_slot = Slot::new(2 * E::slots_per_epoch() - 2);
+    let slots = (1..=fork_slot.as_u64()).map(Slot::new).collect::<Vec<_>>();
+    let (state, state_root) = harness.get_current_state_and_root();
+    let (_, _, fork_root, fork_state) = harness
+        .add_attested_blocks_at_slots(state, state_root, &slots, &all_validators)
+        .await;
+
+    let event_handler = harness.chain.event_handler.as_ref().unwrap();
+    let mut event_receiver = event_handler.subscribe_proposer_duties_reorg();
+
+    // A block at the decision slot of epoch 2 is an ordinary change of head.
+    let (orphan_root, _, _) = harness
+        .add_block_at_slot(fork_slot + 1, fork_state.clone())
+        .await
+        .unwrap();
+    assert_eq!(harness.head_block_root(), orphan_root.into());
+    assert!(matches!(
+        event_receiver.try_recv(),
+        Err(TryRecvError::Empty)
+    ));
+
+    // A block in epoch 2 which skips the decision slot re-orgs it out.
+    let (new_head_root, _, mut new_head_state) = harness
+        .add_block_at_slot(fork_slot + 2, fork_state)
+        .await
+        .unwrap();
+    assert_eq!(harness.head_block_root(), new_head_root.into());
+    assert_eq!(
+        event_receiver.try_recv().unwrap(),
+        EventKind::ProposerDutiesReorg(SseProposerDutiesReorg {
+            epoch: Epoch::new(2),
+            dependent_root: Hash256::from(fork_root),
+        })
+    );
+
+    // Extending the new head leaves the dependent roots unchanged.
+    let state_root = new_head_state.update_tree_hash_cache().unwrap();
+    harness
+        .add_attested_block_at_slot(fork_slot + 3, new_head_state, state_root, &all_validators)
+        .await
+        .unwrap();
+    assert!(matches!(
+        event_receiver.try_recv(),
+        Err(TryRecvError::Empty)
+    ));
+}
@@ -0,0 +1,194 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::block_verification_types::RpcBlock;
+use beacon_chain::data_availability_checker::AvailabilityCheckError;
+use beacon_chain::fetch_blobs::{fetch_blobs_from_execution_layer, FetchBlobsError};
+use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+use beacon_chain::{AvailabilityProcessingStatus, NotifyExecutionLayer};
+use state_processing::per_block_processing::deneb::kzg_commitment_to_versioned_hash;
+use std::sync::Arc;
+use std::time::Duration;
+use types::blob_sidecar::BlobIdentifier;
+use types::{EthSpec, ForkName, Hash256, MinimalEthSpec, SignedBeaconBlock, VersionedHash};
+
+type E = MinimalEthSpec;
+
+fn get_harness() -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec)
+        .deterministic_keypairs(8)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    harness
+}
+
+/// Extends the chain until a block with at least `min_blobs` blobs is produced, then returns that
+/// block without importing it.
+async fn make_block_with_blobs(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    min_blobs: usize,
+) -> (Hash256, Arc<SignedBeaconBlock<E>>) {
+    loop {
+        let slot = harness.get_current_slot();
+        let state = harness.get_current_state();
+        let ((block, blobs), _) = harness.make_block(state, slot).await;
+        let block_root = block.canonical_root();
+        let num_blobs = blobs.as_ref().map_or(0, |blobs| blobs.len());
+        if num_blobs >= min_blobs {
+            return (block_root, Arc::new(block));
+        }
+        harness
+            .process_block(slot, block_root, (block, blobs))
+            .await
+            .unwrap();
+        harness.advance_slot();
+    }
+}
+
+fn versioned_hashes(block: &SignedBeaconBlock<E>) -> Vec<VersionedHash> {
+    block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .unwrap()
+        .iter()
+        .map(kzg_commitment_to_versioned_hash)
+        .collect()
+}
+
+/// Processes `block` without any blobs, as if it had arrived via gossip before its blobs.
+async fn process_block_without_blobs(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    block_root: Hash256,
+    block: Arc<SignedBeaconBlock<E>>,
+) -> AvailabilityProcessingStatus {
+    harness
+        .chain
+        .process_block(
+            block_root,
+            RpcBlock::new_without_blobs(Some(block_root), block),
+            NotifyExecutionLayer::Yes,
+            || Ok(()),
+        )
+        .await
+        .unwrap()
+}
+
+/// Waits for the fetch from the execution layer spawned during block processing to import the
+/// block.
+async fn wait_for_import(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    block_root: Hash256,
+) {
+    for _ in 0..100 {
+        if harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&block_root)
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("block was not imported with blobs from the execution layer");
+}
+
+fn cached_blob_indices(
+    harness: &BeaconChainHarness<EphemeralHarnessType<E>>,
+    block_root: Hash256,
+    num_blobs: usize,
+) -> Vec<u64> {
+    (0..num_blobs as u64)
+        .filter(|&index| {
+            harness
+                .chain
+                .data_availability_checker
+                .get_blob(&BlobIdentifier { block_root, index })
+                .unwrap()
+                .is_some()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn block_imported_with_blobs_from_execution_layer() {
+    let harness = get_harness();
+    let (block_root, block) = make_block_with_blobs(&harness, 1).await;
+    let num_blobs = versioned_hashes(&block).len();
+
+    // The block is not held up waiting for the execution layer.
+    let status = process_block_without_blobs(&harness, block_root, block.clone()).await;
+    assert_eq!(
+        status,
+        AvailabilityProcessingStatus::MissingComponents(block.slot(), block_root)
+    );
+
+    wait_for_import(&harness, block_root).await;
+    assert_eq!(
+        harness.chain.get_blobs(&block_root).unwrap().len(),
+        num_blobs
+    );
+}
+
+#[tokio::test]
+async fn partial_response_from_execution_layer_is_cached() {
+    let harness = get_harness();
+    let (block_root, block) = make_block_with_blobs(&harness, 2).await;
+    let hashes = versioned_hashes(&block);
+
+    // The execution layer has seen every blob except the first.
+    harness
+        .execution_block_generator()
+        .blob_pool
+        .remove(&hashes[0]);
+
+    let status = process_block_without_blobs(&harness, block_root, block.clone()).await;
+    assert_eq!(
+        status,
+        AvailabilityProcessingStatus::MissingComponents(block.slot(), block_root)
+    );
+
+    // Whether or not the spawned fetch has finished, fetching again leaves the same blobs cached.
+    fetch_blobs_from_execution_layer(&harness.chain, block_root, block)
+        .await
+        .unwrap();
+    assert_eq!(
+        cached_blob_indices(&harness, block_root, hashes.len()),
+        (1..hashes.len() as u64).collect::<Vec<_>>()
+    );
+}
+
+#[tokio::test]
+async fn invalid_proof_from_execution_layer_is_rejected() {
+    let harness = get_harness();
+    let (block_root, block) = make_block_with_blobs(&harness, 2).await;
+    let hashes = versioned_hashes(&block);
+
+    // Swap the proofs of the first two blobs so that neither verifies.
+    {
+        let mut generator = harness.execution_block_generator();
+        let proof_0 = generator.blob_pool[&hashes[0]].proof;
+        let proof_1 = generator.blob_pool[&hashes[1]].proof;
+        generator.blob_pool.get_mut(&hashes[0]).unwrap().proof = proof_1;
+        generator.blob_pool.get_mut(&hashes[1]).unwrap().proof = proof_0;
+    }
+
+    let status = process_block_without_blobs(&harness, block_root, block.clone()).await;
+    assert_eq!(
+        status,
+        AvailabilityProcessingStatus::MissingComponents(block.slot(), block_root)
+    );
+
+    let result = fetch_blobs_from_execution_layer(&harness.chain, block_root, block).await;
+    assert!(matches!(
+        result,
+        Err(FetchBlobsError::AvailabilityCheck(
+            AvailabilityCheckError::KzgVerificationFailed | AvailabilityCheckError::Kzg(_)
+        ))
+    ));
+    assert!(cached_blob_indices(&harness, block_root, hashes.len()).is_empty());
+}
@@ -0,0 +1,109 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::test_utils::BeaconChainHarness;
+use types::{MinimalEthSpec, Slot};
+
+type E = MinimalEthSpec;
+
+const HONEST_VALIDATOR_COUNT: usize = 32;
+const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+
+/// Builds a fork in epoch 1 which some validators vote for before it is re-orged out, and checks
+/// that the accuracy drops for that epoch and recovers once the chain is healthy again.
+#[tokio::test]
+async fn head_vote_accuracy_reflects_re_orgs() {
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let all_validators: Vec<usize> = (0..VALIDATOR_COUNT).collect();
+
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    let slots = |start: u64, end: u64| (start..end).map(Slot::new).collect::<Vec<_>>();
+
+    // Epoch 0 and the first slot of epoch 1 are built by all validators.
+    let (state, state_root) = harness.get_current_state_and_root();
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &slots(1, harness.epoch_start_slot(1) + 1),
+            &all_validators,
+        )
+        .await;
+
+    // The rest of epoch 1 contains a fork voted for by the adversarial validators, which loses to
+    // the chain built by the honest validators.
+    let epoch_1_slots = slots(harness.epoch_start_slot(1) + 1, harness.epoch_start_slot(2));
+    let state_root = state.update_tree_hash_cache().unwrap();
+    harness
+        .add_attested_blocks_at_slots(
+            state.clone(),
+            state_root,
+            &epoch_1_slots,
+            &adversarial_validators,
+        )
+        .await;
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(state, state_root, &epoch_1_slots, &honest_validators)
+        .await;
+
+    // Epoch 2 is built by all validators.
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &slots(harness.epoch_start_slot(2), harness.epoch_start_slot(3)),
+            &all_validators,
+        )
+        .await;
+    assert_eq!(harness.chain.epoch().unwrap(), 2);
+
+    // Epoch 0 is evaluated with every vote correct.
+    harness.chain.update_head_vote_accuracy().unwrap();
+    assert_eq!(harness.chain.head_vote_accuracy.latest(), Some(1.0));
+
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &slots(harness.epoch_start_slot(3), harness.epoch_start_slot(4)),
+            &all_validators,
+        )
+        .await;
+    assert_eq!(harness.chain.epoch().unwrap(), 3);
+
+    // Epoch 1 is evaluated, and the votes for the re-orged fork count against it.
+    harness.chain.update_head_vote_accuracy().unwrap();
+    let accuracy = harness.chain.head_vote_accuracy.latest().unwrap();
+    assert!(
+        accuracy > 0.5 && accuracy < 0.9,
+        "unexpected accuracy {accuracy}"
+    );
+
+    let state_root = state.update_tree_hash_cache().unwrap();
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &slots(harness.epoch_start_slot(4), harness.epoch_start_slot(4) + 1),
+            &all_validators,
+        )
+        .await;
+    assert_eq!(harness.chain.epoch().unwrap(), 4);
+
+    // Epoch 2 is evaluated and the accuracy recovers.
+    harness.chain.update_head_vote_accuracy().unwrap();
+    assert_eq!(harness.chain.head_vote_accuracy.latest(), Some(1.0));
+    assert_eq!(
+        harness.chain.head_vote_accuracy.pending_epochs(),
+        2,
+        "epochs 3 and 4 should still be awaiting evaluation"
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::test_utils::BeaconChainHarness;
+use state_processing::state_advance::complete_state_advance;
+use std::time::Duration;
+use types::{Checkpoint, Epoch, EthSpec, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 32;
+
+/// Checks that the balances of a newly justified checkpoint are loaded after the block which
+/// justifies it is imported, rather than when `recompute_head` pulls it up to the justified
+/// checkpoint.
+///
+/// The checkpoint is at an epoch without any blocks, so its balances aren't cached whilst
+/// importing blocks.
+#[tokio::test]
+async fn justified_balances_are_loaded_before_justification() {
+    let harness = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    let slots_per_epoch = E::slots_per_epoch();
+    let skipped_epoch = Epoch::new(4);
+
+    harness
+        .extend_to_slot(skipped_epoch.start_slot(slots_per_epoch) - 1)
+        .await;
+    let head = harness.chain.head_snapshot();
+    let head_root = head.beacon_block_root;
+
+    // Skip every slot of `skipped_epoch`, whilst all validators attest to the head.
+    let mut state = head.beacon_state.clone();
+    complete_state_advance(
+        &mut state,
+        Some(head.beacon_state_root()),
+        skipped_epoch.start_slot(slots_per_epoch),
+        &harness.spec,
+    )
+    .unwrap();
+    state.build_caches(&harness.spec).unwrap();
+    for slot in skipped_epoch.slot_iter(slots_per_epoch) {
+        harness.set_current_slot(slot);
+        let attestations = harness.make_attestations(
+            &harness.get_all_validators(),
+            &state,
+            head.beacon_state_root(),
+            head_root.into(),
+            slot,
+        );
+        harness.process_attestations(attestations);
+    }
+
+    // Include the attestations in the first block of the next epoch, which justifies
+    // `skipped_epoch` in its unrealized checkpoints.
+    harness.set_current_slot((skipped_epoch + 1).start_slot(slots_per_epoch));
+    harness.extend_slots(1).await;
+    let checkpoint = Checkpoint {
+        epoch: skipped_epoch,
+        root: head_root,
+    };
+    assert_eq!(
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .unrealized_justified_checkpoint(),
+        checkpoint
+    );
+
+    // Wait for the balances to be loaded in the background.
+    let balances_cached = || {
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .fc_store()
+            .has_balances_for(checkpoint)
+    };
+    for _ in 0..100 {
+        if balances_cached() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(balances_cached());
+
+    // The checkpoint becomes justified at the start of the next epoch, without loading a state.
+    harness.set_current_slot((skipped_epoch + 2).start_slot(slots_per_epoch));
+    let justified_state_loads = || {
+        harness
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .fc_store()
+            .justified_state_loads()
+    };
+    let justified_state_loads_before = justified_state_loads();
+    harness.chain.recompute_head_at_current_slot().await;
+
+    let fork_choice = harness.chain.canonical_head.fork_choice_read_lock();
+    assert_eq!(fork_choice.justified_checkpoint(), checkpoint);
+    drop(fork_choice);
+    assert_eq!(justified_state_loads(), justified_state_loads_before);
+
+    // Balances are cached by checkpoint, so they're never used for a checkpoint with the same root
+    // at a different epoch.
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .fc_store()
+        .has_balances_for(Checkpoint {
+            epoch: skipped_epoch + 1,
+            root: head_root,
+        }));
+}
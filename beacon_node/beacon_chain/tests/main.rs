@@ -2,11 +2,16 @@ mod attestation_production;
 mod attestation_verification;
 mod block_verification;
 mod capella;
+mod epoch_timings;
 mod events;
+mod fetch_blobs;
+mod head_vote_accuracy;
+mod justified_balances;
 mod merge;
 mod op_verification;
 mod payload_invalidation;
 mod rewards;
 mod store_tests;
+mod sync_committee_participation;
 mod sync_committee_verification;
 mod tests;
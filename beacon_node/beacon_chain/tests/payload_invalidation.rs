@@ -12,9 +12,13 @@ use beacon_chain::{
     INVALID_FINALIZED_MERGE_TRANSITION_BLOCK_SHUTDOWN_REASON,
     INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON,
 };
+use eth2::types::{BlockExecutionStatus, EventKind};
 use execution_layer::{
-    json_structures::{JsonForkchoiceStateV1, JsonPayloadAttributes, JsonPayloadAttributesV1},
-    test_utils::ExecutionBlockGenerator,
+    json_structures::{
+        JsonForkchoiceStateV1, JsonPayloadAttributes, JsonPayloadAttributesV1, JsonPayloadStatusV1,
+        JsonPayloadStatusV1Status,
+    },
+    test_utils::{ExecutionBlockGenerator, PayloadScript, ScriptRule, SlotRange},
     ExecutionLayer, ForkchoiceState, PayloadAttributes,
 };
 use fork_choice::{Error as ForkChoiceError, InvalidationOperation, PayloadVerificationStatus};
@@ -25,6 +29,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use task_executor::ShutdownReason;
+use tokio::sync::broadcast::Receiver;
 use tree_hash::TreeHash;
 use types::*;
 
@@ -968,6 +973,132 @@ async fn manually_validate_parent() {
     assert!(rig.execution_status(child).is_strictly_optimistic());
 }
 
+/// Returns the next `execution_optimistic_status` event as `(block_root, old_status, new_status)`.
+fn next_status_change(
+    events: &mut Receiver<EventKind<E>>,
+) -> (Hash256, BlockExecutionStatus, BlockExecutionStatus) {
+    match events.try_recv().unwrap() {
+        EventKind::ExecutionOptimisticStatus(event) => {
+            (event.block_root, event.old_status, event.new_status)
+        }
+        other => panic!("unexpected event {:?}", other),
+    }
+}
+
+/// Optimistically imported blocks emit one event each when the mock EL later declares them
+/// `VALID` or `INVALID`.
+#[tokio::test]
+async fn execution_optimistic_status_events() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+
+    let mut events = rig
+        .harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_execution_optimistic_status();
+
+    // A `VALID` block validates its optimistic ancestors, starting with its parent.
+    let optimistic = rig.build_blocks(2, Payload::Syncing).await;
+    assert!(events.try_recv().is_err());
+    let valid_root = rig.import_block(Payload::Valid).await;
+    for root in optimistic.iter().rev() {
+        assert_eq!(
+            next_status_change(&mut events),
+            (
+                *root,
+                BlockExecutionStatus::Optimistic,
+                BlockExecutionStatus::Valid
+            )
+        );
+    }
+    assert!(events.try_recv().is_err());
+
+    // An `INVALID` block invalidates its optimistic ancestors back to the latest valid hash,
+    // starting with its parent.
+    let optimistic = rig.build_blocks(2, Payload::Syncing).await;
+    rig.import_block(Payload::Invalid {
+        latest_valid_hash: Some(rig.block_hash(valid_root)),
+    })
+    .await;
+    for root in optimistic.iter().rev() {
+        assert_eq!(
+            next_status_change(&mut events),
+            (
+                *root,
+                BlockExecutionStatus::Optimistic,
+                BlockExecutionStatus::Invalid
+            )
+        );
+        assert!(rig.execution_status(*root).is_invalid());
+    }
+    assert!(events.try_recv().is_err());
+}
+
+/// Drive the chain through `SYNCING` and then `VALID` responses using a script, rather than by
+/// setting the responses of the mock EL before each import.
+#[tokio::test]
+async fn scripted_syncing_then_valid() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+
+    let syncing = JsonPayloadStatusV1 {
+        status: JsonPayloadStatusV1Status::Syncing,
+        latest_valid_hash: None,
+        validation_error: None,
+    };
+    let script = PayloadScript {
+        genesis_time: Some(rig.harness.get_current_state().genesis_time()),
+        seconds_per_slot: Some(rig.harness.spec.seconds_per_slot),
+        rules: vec![ScriptRule {
+            slots: Some(SlotRange {
+                start: Slot::new(2),
+                end: Slot::new(3),
+            }),
+            new_payload: vec![syncing.clone()],
+            forkchoice_updated: vec![syncing],
+            ..ScriptRule::default()
+        }],
+    };
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer.server.set_script(script).unwrap();
+
+    // Blocks in slots 2 and 3 are imported optimistically.
+    let mut roots = vec![];
+    for slot in [2, 3] {
+        let slot = Slot::new(slot);
+        let state = rig.harness.get_current_state();
+        let ((block, blobs), _) = rig.harness.make_block(state, slot).await;
+        let root = rig
+            .harness
+            .process_block(slot, block.canonical_root(), (block, blobs))
+            .await
+            .unwrap();
+        assert!(rig.execution_status(root.into()).is_strictly_optimistic());
+        roots.push(root.into());
+    }
+
+    // The block in slot 4 is not matched by the script, so it is verified by the mock EL. Its
+    // `VALID` status validates its optimistic ancestors.
+    let slot = Slot::new(4);
+    let state = rig.harness.get_current_state();
+    let ((block, blobs), _) = rig.harness.make_block(state, slot).await;
+    let root = rig
+        .harness
+        .process_block(slot, block.canonical_root(), (block, blobs))
+        .await
+        .unwrap();
+    roots.push(root.into());
+
+    for root in roots {
+        assert!(rig.execution_status(root).is_valid_and_post_bellatrix());
+    }
+}
+
 #[tokio::test]
 async fn payload_preparation() {
     let mut rig = InvalidPayloadRig::new();
@@ -1026,6 +1157,101 @@ async fn payload_preparation() {
     assert_eq!(rig.previous_payload_attributes(), payload_attributes);
 }
 
+/// Check that the payload attributes sent alongside a `forkchoiceUpdated` always build upon the
+/// head in that same message, even if the head is re-orged between proposer preparation and the
+/// `forkchoiceUpdated` call.
+#[tokio::test]
+async fn payload_preparation_after_re_org() {
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let mut rig = InvalidPayloadRig::new_with_spec(spec);
+    let block_a = rig.import_block(Payload::Valid).await;
+    let block_b = rig.import_block(Payload::Syncing).await;
+    assert_eq!(rig.harness.head_block_root(), block_b);
+
+    let el = rig.execution_layer();
+    let current_slot = rig.harness.chain.slot().unwrap();
+    let next_slot = current_slot + 1;
+    let lookahead = rig.harness.chain.config.prepare_payload_lookahead;
+
+    // Provide preparation data to the EL for all validators.
+    let preparation_data = (0..VALIDATOR_COUNT as u64)
+        .map(|validator_index| ProposerPreparationData {
+            validator_index,
+            fee_recipient: Address::repeat_byte(99),
+        })
+        .collect::<Vec<_>>();
+    el.update_proposer_preparation(Epoch::new(0), &preparation_data)
+        .await;
+
+    // Record all the `forkchoiceUpdated` calls which carry payload attributes.
+    let forkchoice_updates = Arc::new(parking_lot::Mutex::new(vec![]));
+    let forkchoice_updates_inner = forkchoice_updates.clone();
+    rig.harness
+        .mock_execution_layer
+        .as_ref()
+        .unwrap()
+        .server
+        .ctx
+        .hook
+        .lock()
+        .set_forkchoice_updated_hook(Box::new(move |state, payload_attributes| {
+            if let Some(payload_attributes) = payload_attributes {
+                forkchoice_updates_inner.lock().push((
+                    ForkchoiceState::from(state),
+                    PayloadAttributes::from(payload_attributes),
+                ));
+            }
+            None
+        }));
+
+    // Prepare the proposer upon B, too early in the slot to send a `forkchoiceUpdated`.
+    rig.harness
+        .advance_to_slot_lookahead(next_slot, lookahead + Duration::from_secs(2));
+    rig.harness
+        .chain
+        .prepare_beacon_proposer(current_slot)
+        .await
+        .unwrap();
+    assert!(forkchoice_updates.lock().is_empty());
+    let prepared_attributes = el.payload_attributes(next_slot, block_b).await.unwrap();
+    assert_eq!(
+        prepared_attributes.parent_beacon_block_root().unwrap(),
+        block_b
+    );
+
+    // Re-org B out of the chain.
+    rig.invalidate_manually(block_b).await;
+    rig.recompute_head().await;
+    assert_eq!(rig.harness.head_block_root(), block_a);
+
+    // Prepare the proposer again, this time sending a `forkchoiceUpdated`.
+    rig.harness.advance_to_slot_lookahead(next_slot, lookahead);
+    rig.harness
+        .chain
+        .prepare_beacon_proposer(current_slot)
+        .await
+        .unwrap();
+
+    let forkchoice_updates = forkchoice_updates.lock();
+    let (forkchoice_state, payload_attributes) = forkchoice_updates
+        .last()
+        .expect("forkchoiceUpdated with payload attributes was sent");
+    assert_eq!(forkchoice_state.head_block_hash, rig.block_hash(block_a));
+    assert_eq!(
+        payload_attributes.parent_beacon_block_root().unwrap(),
+        block_a
+    );
+
+    // No update paired B's attributes with A's payload, or vice versa.
+    for (forkchoice_state, payload_attributes) in forkchoice_updates.iter() {
+        let parent_beacon_block_root = payload_attributes.parent_beacon_block_root().unwrap();
+        assert_eq!(
+            forkchoice_state.head_block_hash,
+            rig.block_hash(parent_beacon_block_root)
+        );
+    }
+}
+
 #[tokio::test]
 async fn invalid_parent() {
     let mut rig = InvalidPayloadRig::new();
@@ -1,9 +1,10 @@
 #![cfg(not(debug_assertions))]
 
 use beacon_chain::attestation_verification::Error as AttnError;
+use beacon_chain::beacon_proposer_cache::compute_proposer_duties_from_head;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::builder::BeaconChainBuilder;
-use beacon_chain::schema_change::migrate_schema;
+use beacon_chain::schema_change::{check_migration, migrate_schema};
 use beacon_chain::test_utils::{
     mock_execution_layer_from_parts, test_spec, AttestationStrategy, BeaconChainHarness,
     BlockStrategy, DiskHarnessType,
@@ -21,16 +22,20 @@ use logging::test_logger;
 use maplit::hashset;
 use rand::Rng;
 use slot_clock::{SlotClock, TestingSlotClock};
+use ssz::{Decode, Encode};
 use state_processing::{state_advance::complete_state_advance, BlockReplayer};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use std::time::Duration;
-use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+use store::metadata::{
+    RestorePointMigration, SchemaVersion, CURRENT_SCHEMA_VERSION, RESTORE_POINT_MIGRATION_KEY,
+};
 use store::{
+    hot_cold_store::HotColdDBError,
     iter::{BlockRootsIterator, StateRootsIterator},
-    HotColdDB, LevelDB, StoreConfig,
+    DBColumn, Error as StoreError, HotColdDB, KeyValueStore, LevelDB, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -1144,6 +1149,248 @@ async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
     assert!(!rig.chain.knows_head(&stray_head));
 }
 
+#[tokio::test]
+async fn resumes_interrupted_pruning_of_abandoned_forks() {
+    const HONEST_VALIDATOR_COUNT: usize = 32;
+    const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+    const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+    const NUM_FORKS: u64 = 4;
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    // Delete a single block or state per migration, so that pruning is interrupted.
+    let rig = TestHarness::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .logger(store.logger().clone())
+        .fresh_disk_store(store.clone())
+        .mock_execution_layer()
+        .initial_mutator(Box::new(|builder| {
+            builder.store_migrator_config(
+                MigratorConfig::default()
+                    .blocking()
+                    .pruning_chunk_size(1)
+                    .max_pruning_chunks(1),
+            )
+        }))
+        .build();
+    rig.advance_slot();
+    let slots_per_epoch = rig.slots_per_epoch();
+    let (state, state_root) = rig.get_current_state_and_root();
+
+    let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
+    let (canonical_chain_blocks_pre_finalization, _, _, mut state) = rig
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &canonical_chain_slots,
+            &honest_validators,
+        )
+        .await;
+    let canonical_chain_slot: u64 = rig.get_current_slot().into();
+
+    // Build several forks from the canonical head, each starting at a different slot.
+    let mut stray_blocks = vec![];
+    let mut stray_states = vec![];
+    let mut stray_heads = vec![];
+    for fork in 0..NUM_FORKS {
+        let stray_slots: Vec<Slot> = (canonical_chain_slot + 1 + fork..rig.epoch_start_slot(2))
+            .map(Slot::new)
+            .collect();
+        let (current_state, current_state_root) = rig.get_current_state_and_root();
+        let (blocks, states, head, _) = rig
+            .add_attested_blocks_at_slots(
+                current_state,
+                current_state_root,
+                &stray_slots,
+                &adversarial_validators,
+            )
+            .await;
+        stray_blocks.extend(blocks.into_values());
+        stray_states.extend(states.into_values());
+        stray_heads.push(head);
+    }
+    check_all_blocks_exist(&rig, stray_blocks.iter());
+    for head in &stray_heads {
+        assert!(rig.chain.knows_head(head));
+    }
+
+    // Finalize the canonical chain past the forks.
+    let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
+        ..=(canonical_chain_slot + slots_per_epoch * 5))
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let (canonical_chain_blocks_post_finalization, _, _, _) = rig
+        .add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
+        .await;
+    assert!(store.get_split_slot() > rig.epoch_start_slot(1));
+
+    // The forks are no longer heads, but pruning stopped before deleting all of them.
+    for head in &stray_heads {
+        assert!(!rig.chain.knows_head(head));
+    }
+    let (abandoned_forks, deleted) = store
+        .load_abandoned_forks()
+        .unwrap()
+        .expect("pruning should be unfinished");
+    assert!(deleted < abandoned_forks.len() as u64);
+    assert!(stray_blocks
+        .iter()
+        .any(|block_hash| rig.block_exists(*block_hash)));
+
+    // Restart, and finalize another epoch.
+    rig.chain.persist_head_and_fork_choice().unwrap();
+    let latest_slot = rig.chain.slot().unwrap();
+    let original_chain = rig.chain;
+
+    let resumed = BeaconChainHarness::<DiskHarnessType<E>>::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .logger(store.logger().clone())
+        .resumed_disk_store(store.clone())
+        .testing_slot_clock(original_chain.slot_clock.clone())
+        .execution_layer(original_chain.execution_layer.clone())
+        .build();
+    resumed.chain.slot_clock.set_slot(latest_slot.as_u64() + 1);
+    resumed
+        .extend_chain(
+            slots_per_epoch as usize * 2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Pruning has completed and the canonical chain is intact.
+    assert!(store.load_abandoned_forks().unwrap().is_none());
+    check_no_blocks_exist(&resumed, stray_blocks.iter());
+    for state_hash in &stray_states {
+        assert!(
+            !resumed.hot_state_exists(*state_hash),
+            "stray state {state_hash:?} should have been pruned",
+        );
+    }
+    check_all_blocks_exist(
+        &resumed,
+        canonical_chain_blocks_pre_finalization
+            .values()
+            .chain(canonical_chain_blocks_post_finalization.values()),
+    );
+    assert_eq!(
+        resumed.chain.head_snapshot().beacon_block.slot(),
+        resumed.get_current_slot()
+    );
+}
+
+/// Check that every block in the hot database is present in the block root index.
+fn check_block_root_index(store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>) {
+    for block_root in store.hot_db.iter_column_keys(DBColumn::BeaconBlock) {
+        let block_root = block_root.unwrap();
+        assert!(
+            store.block_may_exist(&block_root),
+            "block {block_root:?} is stored but missing from the index"
+        );
+    }
+}
+
+#[tokio::test]
+async fn block_root_index_has_no_false_negatives() {
+    const HONEST_VALIDATOR_COUNT: usize = 32;
+    const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+    const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let rig = get_harness(store.clone(), VALIDATOR_COUNT);
+    let slots_per_epoch = rig.slots_per_epoch();
+    let (state, state_root) = rig.get_current_state_and_root();
+
+    // Import a canonical chain and a stray fork.
+    let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
+    let (canonical_chain_blocks_pre_finalization, _, _, mut state) = rig
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &canonical_chain_slots,
+            &honest_validators,
+        )
+        .await;
+    let canonical_chain_slot: u64 = rig.get_current_slot().into();
+
+    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..rig.epoch_start_slot(2))
+        .map(Slot::new)
+        .collect();
+    let (current_state, current_state_root) = rig.get_current_state_and_root();
+    let (stray_blocks, _, _, _) = rig
+        .add_attested_blocks_at_slots(
+            current_state,
+            current_state_root,
+            &stray_slots,
+            &adversarial_validators,
+        )
+        .await;
+
+    check_block_root_index(&store);
+    check_all_blocks_exist(&rig, canonical_chain_blocks_pre_finalization.values());
+    check_all_blocks_exist(&rig, stray_blocks.values());
+
+    // Finalize the canonical chain, migrating it to the freezer and pruning the stray fork.
+    let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
+        ..=(canonical_chain_slot + slots_per_epoch * 5))
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let (canonical_chain_blocks_post_finalization, _, _, _) = rig
+        .add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
+        .await;
+    assert!(store.get_split_slot() > rig.epoch_start_slot(1));
+
+    check_block_root_index(&store);
+    check_all_blocks_exist(&rig, canonical_chain_blocks_pre_finalization.values());
+    check_all_blocks_exist(&rig, canonical_chain_blocks_post_finalization.values());
+    for &block_hash in stray_blocks.values() {
+        assert!(
+            !rig.block_exists(block_hash),
+            "abandoned block {block_hash:?} should have been pruned",
+        );
+        assert!(store.get_block_state(&block_hash.into()).unwrap().is_none());
+    }
+
+    // The states of finalized blocks are loaded from the freezer.
+    for block_hash in canonical_chain_blocks_pre_finalization.values() {
+        let block_root = (*block_hash).into();
+        let block = store.get_blinded_block(&block_root).unwrap().unwrap();
+        assert!(block.slot() < store.get_split_slot());
+        let state = store
+            .get_block_state(&block_root)
+            .unwrap()
+            .expect("finalized state should be in the freezer");
+        assert_eq!(state.slot(), block.slot());
+        assert_eq!(state.canonical_root(), block.state_root());
+    }
+
+    // Re-open the store and check that the rebuilt index is also complete.
+    drop(rig);
+    drop(store);
+    let store = get_store(&db_path);
+
+    check_block_root_index(&store);
+    for block_hash in canonical_chain_blocks_pre_finalization
+        .values()
+        .chain(canonical_chain_blocks_post_finalization.values())
+    {
+        let block_root = (*block_hash).into();
+        assert!(store.block_exists(&block_root).unwrap());
+        assert!(store.get_blinded_block(&block_root).unwrap().is_some());
+    }
+    for &block_hash in stray_blocks.values() {
+        assert!(!store.block_exists(&block_hash.into()).unwrap());
+    }
+}
+
 #[tokio::test]
 async fn pruning_does_not_touch_abandoned_block_shared_with_canonical_chain() {
     const HONEST_VALIDATOR_COUNT: usize = 32;
@@ -2096,6 +2343,218 @@ async fn weak_subjectivity_sync_unaligned_unadvanced_checkpoint() {
     weak_subjectivity_sync_test(slots, checkpoint_slot).await
 }
 
+#[tokio::test]
+async fn weak_subjectivity_sync_rejects_mismatched_block() {
+    let num_initial_slots = E::slots_per_epoch() * 11;
+    let checkpoint_slot = Slot::new(E::slots_per_epoch() * 9);
+    let slots = (1..num_initial_slots).map(Slot::new).collect::<Vec<_>>();
+
+    let temp1 = tempdir().unwrap();
+    let full_store = get_store(&temp1);
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+    let all_validators = (0..LOW_VALIDATOR_COUNT).collect::<Vec<_>>();
+
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    harness
+        .add_attested_blocks_at_slots(
+            genesis_state.clone(),
+            genesis_state_root,
+            &slots,
+            &all_validators,
+        )
+        .await;
+
+    // Pair the checkpoint state with a block from the previous slot.
+    let wrong_block_root = harness
+        .chain
+        .block_root_at_slot(checkpoint_slot - 1, WhenSlotSkipped::Prev)
+        .unwrap()
+        .unwrap();
+    let wrong_block = harness
+        .chain
+        .store
+        .get_full_block(&wrong_block_root)
+        .unwrap()
+        .unwrap();
+    let wss_state_root = harness
+        .chain
+        .state_root_at_slot(checkpoint_slot)
+        .unwrap()
+        .unwrap();
+    let wss_state = full_store
+        .get_state(&wss_state_root, Some(checkpoint_slot))
+        .unwrap()
+        .unwrap();
+
+    let temp2 = tempdir().unwrap();
+    let store = get_store(&temp2);
+    let result = BeaconChainBuilder::<DiskHarnessType<E>>::new(MinimalEthSpec)
+        .store(store)
+        .custom_spec(test_spec::<E>())
+        .task_executor(harness.chain.task_executor.clone())
+        .logger(test_logger())
+        .weak_subjectivity_state(wss_state, wrong_block, None, genesis_state);
+
+    match result {
+        Err(e) => assert!(e.contains("does not match"), "unexpected error: {e}"),
+        Ok(_) => panic!("mismatched checkpoint block should be rejected"),
+    }
+}
+
+#[tokio::test]
+async fn weak_subjectivity_sync_from_ssz_files_with_blobs() {
+    let num_initial_slots = E::slots_per_epoch() * 4;
+    let slots = (1..num_initial_slots).map(Slot::new).collect::<Vec<_>>();
+
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let temp1 = tempdir().unwrap();
+    let full_store = get_store_generic(&temp1, StoreConfig::default(), spec.clone());
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+    let all_validators = (0..LOW_VALIDATOR_COUNT).collect::<Vec<_>>();
+
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    harness
+        .add_attested_blocks_at_slots(
+            genesis_state.clone(),
+            genesis_state_root,
+            &slots,
+            &all_validators,
+        )
+        .await;
+
+    // Use the most recent block with blobs as an unadvanced checkpoint.
+    let (wss_block_root, wss_blobs) = slots
+        .iter()
+        .rev()
+        .find_map(|&slot| {
+            let block_root = harness
+                .chain
+                .block_root_at_slot(slot, WhenSlotSkipped::None)
+                .unwrap()?;
+            let blobs = full_store.get_blobs(&block_root).unwrap()?;
+            (!blobs.is_empty()).then_some((block_root, blobs))
+        })
+        .expect("some block should have blobs");
+    let wss_block = full_store.get_full_block(&wss_block_root).unwrap().unwrap();
+    let wss_state = full_store
+        .get_state(&wss_block.state_root(), Some(wss_block.slot()))
+        .unwrap()
+        .unwrap();
+
+    // Write the checkpoint files as they would be provided to `--checkpoint-state`,
+    // `--checkpoint-block` and `--checkpoint-blobs`.
+    let files_dir = tempdir().unwrap();
+    let state_path = files_dir.path().join("state.ssz");
+    let block_path = files_dir.path().join("block.ssz");
+    let blobs_path = files_dir.path().join("blobs.ssz");
+    std::fs::write(&state_path, wss_state.as_ssz_bytes()).unwrap();
+    std::fs::write(&block_path, wss_block.as_ssz_bytes()).unwrap();
+    std::fs::write(&blobs_path, wss_blobs.as_ssz_bytes()).unwrap();
+
+    let anchor_state =
+        BeaconState::<E>::from_ssz_bytes(&std::fs::read(&state_path).unwrap(), &spec).unwrap();
+    let anchor_block =
+        SignedBeaconBlock::<E>::from_ssz_bytes(&std::fs::read(&block_path).unwrap(), &spec)
+            .unwrap();
+    let anchor_blobs =
+        BlobSidecarList::<E>::from_ssz_bytes(&std::fs::read(&blobs_path).unwrap()).unwrap();
+    assert_eq!(anchor_blobs, wss_blobs);
+
+    let builder_from_files = |blobs: Option<BlobSidecarList<E>>| {
+        let temp = tempdir().unwrap();
+        let store = get_store_generic(&temp, StoreConfig::default(), spec.clone());
+        let builder = BeaconChainBuilder::<DiskHarnessType<E>>::new(MinimalEthSpec)
+            .store(store.clone())
+            .custom_spec(spec.clone())
+            .task_executor(harness.chain.task_executor.clone())
+            .logger(test_logger())
+            .weak_subjectivity_state(
+                anchor_state.clone(),
+                anchor_block.clone(),
+                blobs,
+                genesis_state.clone(),
+            );
+        (temp, store, builder)
+    };
+    let assert_rejected =
+        |blobs: Option<BlobSidecarList<E>>, expected: &str| match builder_from_files(blobs).2 {
+            Err(e) => assert!(e.contains(expected), "unexpected error: {e}"),
+            Ok(_) => panic!("invalid checkpoint blobs should be rejected"),
+        };
+
+    // Blobs are required when the block has commitments.
+    assert_rejected(None, "no blobs were provided");
+
+    // A missing blob.
+    let mut missing_blob = Vec::from(anchor_blobs.clone());
+    missing_blob.pop();
+    assert_rejected(Some(missing_blob.into()), "expected");
+
+    // A blob for a different block.
+    let mut wrong_block_root = Vec::from(anchor_blobs.clone());
+    let mut sidecar = (*wrong_block_root[0]).clone();
+    sidecar.block_root = Hash256::repeat_byte(0xff);
+    wrong_block_root[0] = Arc::new(sidecar);
+    assert_rejected(Some(wrong_block_root.into()), "wrong block root");
+
+    // A blob that doesn't match the block's commitment.
+    let mut wrong_commitment = Vec::from(anchor_blobs.clone());
+    let mut sidecar = (*wrong_commitment[0]).clone();
+    sidecar.kzg_commitment = KzgCommitment::empty_for_testing();
+    wrong_commitment[0] = Arc::new(sidecar);
+    assert_rejected(
+        Some(wrong_commitment.into()),
+        "does not match the block's KZG commitment",
+    );
+
+    // Boot a chain from the valid files.
+    let (shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+    let log = test_logger();
+    let trusted_setup: TrustedSetup =
+        serde_json::from_reader(get_trusted_setup::<<E as EthSpec>::Kzg>())
+            .map_err(|e| println!("Unable to read trusted setup file: {}", e))
+            .unwrap();
+    let mock = mock_execution_layer_from_parts(
+        &harness.spec,
+        harness.runtime.task_executor.clone(),
+        None,
+        None,
+        false,
+    );
+    let slot_clock = TestingSlotClock::new(
+        Slot::new(0),
+        Duration::from_secs(harness.chain.genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+    slot_clock.set_slot(harness.get_current_slot().as_u64());
+
+    let (_temp2, store, builder) = builder_from_files(Some(anchor_blobs.clone()));
+    let beacon_chain = builder
+        .unwrap()
+        .store_migrator_config(MigratorConfig::default().blocking())
+        .dummy_eth1_backend()
+        .expect("should build dummy backend")
+        .slot_clock(slot_clock)
+        .shutdown_sender(shutdown_tx)
+        .chain_config(ChainConfig::default())
+        .event_handler(Some(ServerSentEventHandler::new_with_capacity(
+            log.clone(),
+            1,
+        )))
+        .execution_layer(Some(mock.el))
+        .monitor_validators(true, vec![], DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD, log)
+        .trusted_setup(trusted_setup)
+        .build()
+        .expect("should build");
+
+    assert_eq!(
+        beacon_chain.head_snapshot().beacon_block_root,
+        wss_block_root
+    );
+    assert_eq!(beacon_chain.get_blobs(&wss_block_root).unwrap(), wss_blobs);
+    assert_eq!(store.get_blobs(&wss_block_root).unwrap(), Some(wss_blobs));
+}
+
 async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
     // Build an initial chain on one harness, representing a synced node with full history.
     let num_final_blocks = E::slots_per_epoch() * 2;
@@ -2133,6 +2592,7 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
         .get_full_block(&wss_block_root)
         .unwrap()
         .unwrap();
+    let wss_blobs_opt = harness.chain.store.get_blobs(&wss_block_root).unwrap();
     let wss_state = full_store
         .get_state(&wss_state_root, Some(checkpoint_slot))
         .unwrap()
@@ -2159,8 +2619,13 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
             .map_err(|e| println!("Unable to read trusted setup file: {}", e))
             .unwrap();
 
-    let mock =
-        mock_execution_layer_from_parts(&harness.spec, harness.runtime.task_executor.clone(), None);
+    let mock = mock_execution_layer_from_parts(
+        &harness.spec,
+        harness.runtime.task_executor.clone(),
+        None,
+        None,
+        false,
+    );
 
     // Initialise a new beacon chain from the finalized checkpoint.
     // The slot clock must be set to a time ahead of the checkpoint state.
@@ -2175,7 +2640,12 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
         .custom_spec(test_spec::<E>())
         .task_executor(harness.chain.task_executor.clone())
         .logger(log.clone())
-        .weak_subjectivity_state(wss_state, wss_block.clone(), genesis_state)
+        .weak_subjectivity_state(
+            wss_state,
+            wss_block.clone(),
+            wss_blobs_opt.clone(),
+            genesis_state,
+        )
         .unwrap()
         .store_migrator_config(MigratorConfig::default().blocking())
         .dummy_eth1_backend()
@@ -2195,6 +2665,31 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
 
     let beacon_chain = Arc::new(beacon_chain);
 
+    // Pre-heating the caches, as the client does after a checkpoint sync, builds the committees for
+    // the anchor epochs and the proposers of the anchor epoch.
+    beacon_chain.preheat_caches().unwrap();
+    let anchor_snapshot = beacon_chain.head_snapshot();
+    let anchor_block = beacon_chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&anchor_snapshot.beacon_block_root)
+        .unwrap();
+    let shuffling_cache = beacon_chain
+        .shuffling_cache
+        .try_read_for(Duration::from_secs(1))
+        .unwrap();
+    assert!(shuffling_cache.contains(&anchor_block.current_epoch_shuffling_id));
+    assert!(shuffling_cache.contains(&anchor_block.next_epoch_shuffling_id));
+    drop(shuffling_cache);
+    let anchor_epoch = anchor_block.current_epoch_shuffling_id.shuffling_epoch;
+    let (_, proposer_dependent_root, _, _) =
+        compute_proposer_duties_from_head(anchor_epoch, &beacon_chain).unwrap();
+    assert!(beacon_chain
+        .beacon_proposer_cache
+        .lock()
+        .get_epoch::<E>(proposer_dependent_root, anchor_epoch)
+        .is_some());
+
     // Apply blocks forward to reach head.
     let chain_dump = harness.chain.chain_dump().unwrap();
     let new_blocks = chain_dump
@@ -2755,7 +3250,140 @@ async fn revert_minority_fork_on_resume() {
     assert_eq!(heads.len(), 1);
 }
 
-// This test checks whether the schema downgrade from the latest version to some minimum supported
+// Check that compaction reports the space it reclaimed and records its timestamp, and that blocks
+// and states can be read while it runs.
+#[tokio::test]
+async fn compaction_keeps_store_readable() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain_dump = harness.chain.chain_dump().unwrap();
+    let compaction = {
+        let store = store.clone();
+        std::thread::spawn(move || store.compact())
+    };
+    for snapshot in &chain_dump {
+        assert!(store
+            .get_blinded_block(&snapshot.beacon_block_root)
+            .unwrap()
+            .is_some());
+        assert!(store
+            .get_state(
+                &snapshot.beacon_state_root(),
+                Some(snapshot.beacon_block.slot())
+            )
+            .unwrap()
+            .is_some());
+    }
+    let summary = compaction.join().unwrap().unwrap();
+
+    assert!(summary.reclaimed_bytes.is_some());
+    assert!(store.load_compaction_timestamp().unwrap().is_some());
+    check_chain_dump(&harness, num_blocks_produced + 1);
+}
+
+// Check that the restore point spacing can be changed and changed back, with frozen states at
+// every slot remaining loadable.
+#[tokio::test]
+async fn migrate_restore_points_round_trip() {
+    let num_blocks_produced = E::slots_per_epoch() * 20;
+    let db_path = tempdir().unwrap();
+    let store_config = |slots_per_restore_point| StoreConfig {
+        slots_per_restore_point,
+        slots_per_restore_point_set_explicitly: true,
+        ..StoreConfig::default()
+    };
+
+    let (chain_dump, split_slot) = {
+        let store = get_store_generic(&db_path, store_config(32), test_spec::<E>());
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+        harness
+            .extend_chain(
+                num_blocks_produced as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        (harness.chain.chain_dump().unwrap(), store.get_split_slot())
+    };
+    assert!(split_slot > 128);
+
+    let check_frozen_states = |store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>| {
+        for snapshot in chain_dump
+            .iter()
+            .filter(|snapshot| snapshot.beacon_block.slot() < split_slot)
+        {
+            let slot = snapshot.beacon_block.slot();
+            let state = store
+                .load_cold_state_by_slot(slot)
+                .unwrap()
+                .unwrap_or_else(|| panic!("state at slot {} should be available", slot));
+            assert_eq!(
+                state.canonical_root(),
+                snapshot.beacon_state_root(),
+                "{}",
+                slot
+            );
+        }
+    };
+
+    // A migration started with different parameters is refused.
+    {
+        let store = get_store_generic(&db_path, store_config(32), test_spec::<E>());
+        let migration = RestorePointMigration {
+            old_slots_per_restore_point: 32,
+            new_slots_per_restore_point: 16,
+            next_slot: Slot::new(0),
+        };
+        store
+            .hot_db
+            .put(&RESTORE_POINT_MIGRATION_KEY, &migration)
+            .unwrap();
+        assert!(matches!(
+            store.migrate_restore_points(64, 1),
+            Err(StoreError::HotColdDBError(
+                HotColdDBError::RestorePointMigrationMismatch { .. }
+            ))
+        ));
+        store
+            .hot_db
+            .key_delete(
+                DBColumn::BeaconMeta.as_str(),
+                RESTORE_POINT_MIGRATION_KEY.as_bytes(),
+            )
+            .unwrap();
+    }
+
+    // 32 -> 64. The new restore points are a subset of the old ones.
+    {
+        let store = get_store_generic(&db_path, store_config(32), test_spec::<E>());
+        store.migrate_restore_points(64, 1).unwrap();
+        assert_eq!(store.load_restore_point_migration().unwrap(), None);
+    }
+
+    // 64 -> 32, regenerating the restore points between the existing ones.
+    {
+        let store = get_store_generic(&db_path, store_config(64), test_spec::<E>());
+        check_frozen_states(&store);
+        store.migrate_restore_points(32, 1).unwrap();
+    }
+
+    let store = get_store_generic(&db_path, store_config(32), test_spec::<E>());
+    assert_eq!(store.get_split_slot(), split_slot);
+    check_frozen_states(&store);
+}
+
+// This test checks whether the schema downgrade from the latest version to some minimum supported
 // version is correct. This is the easiest schema test to write without historic versions of
 // Lighthouse on-hand, but has the disadvantage that the min version needs to be adjusted manually
 // as old downgrades are deprecated.
@@ -2846,6 +3474,106 @@ async fn schema_downgrade_to_min_version() {
     .expect_err("should not downgrade below minimum version");
 }
 
+// Check that the last two schema migrations can be reversed and re-applied one version at a time,
+// and that blocks and states remain readable at every version along the way.
+#[tokio::test]
+async fn schema_round_trip_last_two_versions() {
+    let num_blocks_produced = E::slots_per_epoch() * 4;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let spec = &harness.chain.spec.clone();
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain_dump = harness.chain.chain_dump().unwrap();
+    let slot_clock = harness.chain.slot_clock.clone();
+    let deneb_active = harness.spec.deneb_fork_epoch.is_some();
+
+    drop(store);
+    drop(harness);
+
+    let store = get_store(&db_path);
+    let log = store.logger().clone();
+    let check_blocks_and_states = |version: SchemaVersion| {
+        for snapshot in &chain_dump {
+            let block_root = snapshot.beacon_block_root;
+            let state_root = snapshot.beacon_state_root();
+            let slot = snapshot.beacon_block.slot();
+            assert!(
+                store.get_blinded_block(&block_root).unwrap().is_some(),
+                "block {:?} missing at {:?}",
+                block_root,
+                version
+            );
+            assert!(
+                store.get_state(&state_root, Some(slot)).unwrap().is_some(),
+                "state {:?} missing at {:?}",
+                state_root,
+                version
+            );
+        }
+    };
+
+    let current = CURRENT_SCHEMA_VERSION;
+    let previous = SchemaVersion(current.as_u64() - 1);
+    let two_before = SchemaVersion(current.as_u64() - 2);
+
+    if deneb_active {
+        // Once Deneb is active the blobs in the database can't be represented by v17, so the
+        // downgrade must be refused before any step is applied.
+        assert!(matches!(
+            check_migration::<DiskHarnessType<E>>(&store, current, two_before, &log),
+            Err(StoreError::UnableToDowngrade(_))
+        ));
+        migrate_schema::<DiskHarnessType<E>>(store.clone(), 0, current, previous, log, spec)
+            .expect_err("should not downgrade after Deneb");
+        check_blocks_and_states(current);
+        return;
+    }
+
+    let steps = check_migration::<DiskHarnessType<E>>(&store, current, two_before, &log)
+        .expect("downgrade by two versions should be possible");
+    assert_eq!(
+        steps
+            .iter()
+            .map(|step| (step.from, step.to))
+            .collect::<Vec<_>>(),
+        vec![(current, previous), (previous, two_before)]
+    );
+
+    check_blocks_and_states(current);
+    for (from, to) in [
+        (current, previous),
+        (previous, two_before),
+        (two_before, previous),
+        (previous, current),
+    ] {
+        migrate_schema::<DiskHarnessType<E>>(store.clone(), 0, from, to, log.clone(), spec)
+            .unwrap_or_else(|e| panic!("migration {:?} -> {:?} failed: {:?}", from, to, e));
+        check_blocks_and_states(to);
+    }
+
+    // The chain resumes from the round-tripped database.
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .logger(log)
+        .testing_slot_clock(slot_clock)
+        .resumed_disk_store(store.clone())
+        .mock_execution_layer()
+        .build();
+
+    check_finalization(&harness, num_blocks_produced);
+    check_chain_dump(&harness, num_blocks_produced + 1);
+}
+
 /// Check that blob pruning prunes blobs older than the data availability boundary.
 #[tokio::test]
 async fn deneb_prune_blobs_happy_case() {
@@ -2879,9 +3607,7 @@ async fn deneb_prune_blobs_happy_case() {
 
     // Trigger blob pruning of blobs older than epoch 2.
     let data_availability_boundary = Epoch::new(2);
-    store
-        .try_prune_blobs(true, data_availability_boundary)
-        .unwrap();
+    prune_blobs(&harness, data_availability_boundary);
 
     // Check oldest blob slot is updated accordingly and prior blobs have been deleted.
     let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
@@ -2893,7 +3619,8 @@ async fn deneb_prune_blobs_happy_case() {
     check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
 }
 
-/// Check that blob pruning does not prune without finalization.
+/// Check that blob pruning prunes blobs older than the data availability boundary even if they
+/// are newer than finalization.
 #[tokio::test]
 async fn deneb_prune_blobs_no_finalization() {
     let db_path = tempdir().unwrap();
@@ -2940,17 +3667,242 @@ async fn deneb_prune_blobs_no_finalization() {
     );
     check_blob_existence(&harness, Slot::new(0), harness.head_slot(), true);
 
-    // Attempt blob pruning of blobs older than epoch 4, which is newer than finalization.
+    // Prune blobs older than epoch 4, which is newer than finalization.
     let data_availability_boundary = Epoch::new(4);
+    prune_blobs(&harness, data_availability_boundary);
+
+    // Check oldest blob slot is updated to the DAB rather than finalization, and that the
+    // unfinalized blobs prior to the DAB have been deleted.
+    let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
+    assert_eq!(
+        oldest_blob_slot,
+        data_availability_boundary.start_slot(E::slots_per_epoch())
+    );
+    assert!(oldest_blob_slot > finalized_slot);
+    check_blob_existence(&harness, Slot::new(0), oldest_blob_slot - 1, false);
+    check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
+}
+
+/// Check that blobs older than the data availability boundary are pruned from every fork during a
+/// long period of non-finality, while newer blobs are retained.
+#[tokio::test]
+async fn deneb_prune_blobs_long_non_finality() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    if store.get_chain_spec().deneb_fork_epoch != Some(Epoch::new(0)) {
+        // Requires blobs from genesis.
+        return;
+    }
+
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let slots_per_epoch = E::slots_per_epoch();
+
+    // Only a minority of validators attest, so the chain never finalizes.
+    let minority_validators: Vec<usize> = (0..LOW_VALIDATOR_COUNT / 4).collect();
+
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    let first_epoch_slots: Vec<Slot> = (1..=slots_per_epoch).map(Slot::new).collect();
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(
+            genesis_state,
+            genesis_state_root,
+            &first_epoch_slots,
+            &minority_validators,
+        )
+        .await;
+
+    // Build a stray fork for an epoch.
+    let stray_slots: Vec<Slot> = (slots_per_epoch + 1..=slots_per_epoch * 2)
+        .map(Slot::new)
+        .collect();
+    let (head_state, head_state_root) = harness.get_current_state_and_root();
+    let (stray_blocks, _, _, _) = harness
+        .add_attested_blocks_at_slots(
+            head_state,
+            head_state_root,
+            &stray_slots,
+            &minority_validators,
+        )
+        .await;
+
+    // Extend the other fork for several more epochs.
+    let canonical_slots: Vec<Slot> = (slots_per_epoch + 1..=slots_per_epoch * 8)
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    harness
+        .add_attested_blocks_at_slots(state, state_root, &canonical_slots, &minority_validators)
+        .await;
+
+    assert_eq!(harness.get_current_state().finalized_checkpoint().epoch, 0);
+    assert_eq!(store.get_split_slot(), 0);
+    assert_eq!(store.get_blob_info().oldest_blob_slot, Some(Slot::new(0)));
+    assert!(stray_blocks
+        .values()
+        .any(|block_root| store.blobs_exist(&(*block_root).into()).unwrap()));
+    check_blob_existence(&harness, Slot::new(0), harness.head_slot(), true);
+
+    // Prune blobs older than epoch 4, as if the current epoch were far enough ahead.
+    let data_availability_boundary = Epoch::new(4);
+    prune_blobs(&harness, data_availability_boundary);
+
+    let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
+    assert_eq!(
+        oldest_blob_slot,
+        data_availability_boundary.start_slot(slots_per_epoch)
+    );
+    check_blob_existence(&harness, Slot::new(0), oldest_blob_slot - 1, false);
+    check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
+    for block_root in stray_blocks.values() {
+        assert!(!store.blobs_exist(&(*block_root).into()).unwrap());
+    }
+}
+
+/// Check that the data availability boundary computed from the current slot drives blob pruning
+/// when the chain hasn't finalized for longer than the data availability window.
+#[tokio::test]
+async fn deneb_prune_blobs_past_data_availability_window_without_finality() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    if store.get_chain_spec().deneb_fork_epoch != Some(Epoch::new(0)) {
+        // Requires blobs from genesis.
+        return;
+    }
+
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let slots_per_epoch = E::slots_per_epoch();
+
+    // Only a minority of validators attest, so the chain never finalizes.
+    let minority_validators: Vec<usize> = (0..LOW_VALIDATOR_COUNT / 4).collect();
+    let slots: Vec<Slot> = (1..=slots_per_epoch * 8).map(Slot::new).collect();
+    let (state, state_root) = harness.get_current_state_and_root();
+    harness
+        .add_attested_blocks_at_slots(state, state_root, &slots, &minority_validators)
+        .await;
+    assert_eq!(harness.get_current_state().finalized_checkpoint().epoch, 0);
+    check_blob_existence(&harness, Slot::new(0), harness.head_slot(), true);
+
+    // Advance the clock until the first 4 epochs of blobs fall outside the data availability
+    // window, without producing any more blocks.
+    let current_epoch = types::consts::deneb::MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS + 4;
+    harness
+        .chain
+        .slot_clock
+        .set_slot(current_epoch.start_slot(slots_per_epoch).as_u64());
+    let data_availability_boundary = harness.chain.data_availability_boundary().unwrap();
+    assert_eq!(data_availability_boundary, Epoch::new(4));
+
+    // This is what the blob pruning service runs.
+    harness.chain.prune_blobs(data_availability_boundary);
+
+    assert_eq!(harness.get_current_state().finalized_checkpoint().epoch, 0);
+    let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
+    assert_eq!(
+        oldest_blob_slot,
+        data_availability_boundary.start_slot(slots_per_epoch)
+    );
+    check_blob_existence(&harness, Slot::new(0), oldest_blob_slot - 1, false);
+    check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
+}
+
+/// Check that the oldest blob slot isn't advanced past the blobs of hot blocks which aren't pruned
+/// because they're missing from fork choice.
+#[tokio::test]
+async fn deneb_prune_blobs_retains_blocks_outside_fork_choice() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    if store.get_chain_spec().deneb_fork_epoch != Some(Epoch::new(0)) {
+        // Requires blobs from genesis.
+        return;
+    }
+
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let slots_per_epoch = E::slots_per_epoch();
+    let minority_validators: Vec<usize> = (0..LOW_VALIDATOR_COUNT / 4).collect();
+
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    let first_epoch_slots: Vec<Slot> = (1..=slots_per_epoch).map(Slot::new).collect();
+    let (_, _, _, mut state) = harness
+        .add_attested_blocks_at_slots(
+            genesis_state,
+            genesis_state_root,
+            &first_epoch_slots,
+            &minority_validators,
+        )
+        .await;
+
+    // Build a stray fork for an epoch, then extend the other fork for several more epochs.
+    let stray_slots: Vec<Slot> = (slots_per_epoch + 1..=slots_per_epoch * 2)
+        .map(Slot::new)
+        .collect();
+    let (head_state, head_state_root) = harness.get_current_state_and_root();
+    let (stray_blocks, _, _, _) = harness
+        .add_attested_blocks_at_slots(
+            head_state,
+            head_state_root,
+            &stray_slots,
+            &minority_validators,
+        )
+        .await;
+    let canonical_slots: Vec<Slot> = (slots_per_epoch + 1..=slots_per_epoch * 8)
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    harness
+        .add_attested_blocks_at_slots(state, state_root, &canonical_slots, &minority_validators)
+        .await;
+
+    let oldest_stray_slot_with_blobs = stray_blocks
+        .iter()
+        .filter(|(_, block_root)| store.blobs_exist(&(**block_root).into()).unwrap())
+        .map(|(slot, _)| *slot)
+        .min()
+        .expect("stray fork should have blobs");
+
+    // Prune as if the stray fork were unknown to fork choice.
+    let data_availability_boundary = Epoch::new(4);
+    let stray_block_roots = stray_blocks
+        .values()
+        .map(|block_root| (*block_root).into())
+        .collect::<HashSet<Hash256>>();
+    let unfinalized_blocks = harness
+        .chain
+        .fork_choice_blocks_before(data_availability_boundary)
+        .into_iter()
+        .filter(|(block_root, _)| !stray_block_roots.contains(block_root))
+        .collect::<Vec<_>>();
     store
-        .try_prune_blobs(true, data_availability_boundary)
+        .try_prune_blobs(true, data_availability_boundary, &unfinalized_blocks)
         .unwrap();
 
-    // Check oldest blob slot is only updated to finalization, and NOT to the DAB.
-    let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
-    assert_eq!(oldest_blob_slot, finalized_slot);
-    check_blob_existence(&harness, Slot::new(0), finalized_slot - 1, false);
-    check_blob_existence(&harness, finalized_slot, harness.head_slot(), true);
+    // The canonical blobs are deleted, but the oldest blob slot is held back by the stray fork.
+    assert_eq!(
+        store.get_blob_info().oldest_blob_slot,
+        Some(oldest_stray_slot_with_blobs)
+    );
+    check_blob_existence(
+        &harness,
+        Slot::new(0),
+        data_availability_boundary.start_slot(slots_per_epoch) - 1,
+        false,
+    );
+    assert!(stray_blocks
+        .iter()
+        .filter(|(slot, _)| **slot >= oldest_stray_slot_with_blobs)
+        .any(|(_, block_root)| store.blobs_exist(&(*block_root).into()).unwrap()));
+
+    // Once fork choice knows about the stray fork, its blobs are pruned too.
+    prune_blobs(&harness, data_availability_boundary);
+    assert_eq!(
+        store.get_blob_info().oldest_blob_slot,
+        Some(data_availability_boundary.start_slot(slots_per_epoch))
+    );
+    for block_root in stray_blocks.values() {
+        assert!(!store.blobs_exist(&(*block_root).into()).unwrap());
+    }
 }
 
 /// Check that blob pruning does not fail trying to prune across the fork boundary.
@@ -2997,9 +3949,7 @@ async fn deneb_prune_blobs_fork_boundary() {
     // No pruning should occur.
     assert!(deneb_fork_epoch < finalized_epoch);
     for data_availability_boundary in [Epoch::new(0), Epoch::new(3), deneb_fork_epoch] {
-        store
-            .try_prune_blobs(true, data_availability_boundary)
-            .unwrap();
+        prune_blobs(&harness, data_availability_boundary);
 
         // Check oldest blob slot is not updated.
         assert_eq!(
@@ -3012,7 +3962,7 @@ async fn deneb_prune_blobs_fork_boundary() {
 
     // Prune one epoch past the fork.
     let pruned_slot = (deneb_fork_epoch + 1).start_slot(E::slots_per_epoch());
-    store.try_prune_blobs(true, deneb_fork_epoch + 1).unwrap();
+    prune_blobs(&harness, deneb_fork_epoch + 1);
     assert_eq!(store.get_blob_info().oldest_blob_slot, Some(pruned_slot));
     check_blob_existence(&harness, Slot::new(0), pruned_slot - 1, false);
     check_blob_existence(&harness, pruned_slot, harness.head_slot(), true);
@@ -3077,9 +4027,7 @@ async fn deneb_prune_blobs_margin_test(margin: u64) {
         effective_data_availability_boundary > 0,
         "must be > 0 because epoch 0 won't get pruned alone"
     );
-    store
-        .try_prune_blobs(true, data_availability_boundary)
-        .unwrap();
+    prune_blobs(&harness, data_availability_boundary);
 
     // Check oldest blob slot is updated accordingly and prior blobs have been deleted.
     let oldest_blob_slot = store.get_blob_info().oldest_blob_slot.unwrap();
@@ -3091,6 +4039,19 @@ async fn deneb_prune_blobs_margin_test(margin: u64) {
     check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
 }
 
+/// Force blob pruning up to `data_availability_boundary`, including the blobs of the unfinalized
+/// blocks in fork choice.
+fn prune_blobs(harness: &TestHarness, data_availability_boundary: Epoch) {
+    let unfinalized_blocks = harness
+        .chain
+        .fork_choice_blocks_before(data_availability_boundary);
+    harness
+        .chain
+        .store
+        .try_prune_blobs(true, data_availability_boundary, &unfinalized_blocks)
+        .unwrap();
+}
+
 /// Check that there are blob sidecars (or not) at every slot in the range.
 fn check_blob_existence(
     harness: &TestHarness,
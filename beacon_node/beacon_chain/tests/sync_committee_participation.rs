@@ -0,0 +1,104 @@
+#![cfg(not(debug_assertions))]
+
+use beacon_chain::test_utils::{
+    AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+    SyncCommitteeStrategy,
+};
+use types::{Epoch, EthSpec, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 32;
+
+async fn extend_with_sync(harness: &BeaconChainHarness<EphemeralHarnessType<E>>, slots: u64) {
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            slots as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+        )
+        .await;
+}
+
+/// Builds a chain in which every sync committee member signs every block, and checks that the
+/// participation is complete and rolls off at the end of the period.
+#[tokio::test]
+async fn sync_committee_participation_with_all_validators() {
+    let mut spec = E::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec.clone())
+        .deterministic_keypairs(VALIDATOR_COUNT)
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Monitor every validator once their indices are known from the first block.
+    extend_with_sync(&harness, 1).await;
+    for index in 0..VALIDATOR_COUNT as u64 {
+        harness
+            .chain
+            .validator_monitor
+            .write()
+            .auto_register_local_validator(index);
+    }
+    extend_with_sync(&harness, 3 * E::slots_per_epoch() - 2).await;
+
+    let participation = harness.chain.sync_committee_participation();
+    assert_eq!(participation.period, Some(0));
+    assert_eq!(participation.epochs.len(), 3);
+
+    // The first block can't include any signatures, since there are none for the genesis block.
+    let first = &participation.epochs[0];
+    assert_eq!(first.blocks, E::slots_per_epoch() - 1);
+    assert_eq!(
+        first.participants,
+        first.expected_participants - E::sync_committee_size() as u64
+    );
+
+    for epoch in &participation.epochs[1..] {
+        assert_eq!(epoch.blocks, E::slots_per_epoch());
+        assert_eq!(
+            epoch.expected_participants,
+            E::slots_per_epoch() * E::sync_committee_size() as u64
+        );
+        assert_eq!(epoch.participation_rate, 1.0);
+
+        // Every committee position is held by a monitored validator, and every bit was set.
+        assert_eq!(
+            epoch
+                .validators
+                .iter()
+                .map(|validator| validator.expected)
+                .sum::<u64>(),
+            E::slots_per_epoch() * E::sync_committee_size() as u64
+        );
+        for validator in &epoch.validators {
+            assert!(validator.expected > 0);
+            assert_eq!(validator.hits, validator.expected);
+        }
+    }
+
+    // Once the next period begins, only its blocks are reported.
+    let period_start_slot =
+        harness.epoch_start_slot(spec.epochs_per_sync_committee_period.as_u64());
+    let head_slot = harness.chain.head_snapshot().beacon_block.slot().as_u64();
+    extend_with_sync(
+        &harness,
+        period_start_slot + E::slots_per_epoch() - 1 - head_slot,
+    )
+    .await;
+    harness.chain.update_sync_committee_participation().unwrap();
+
+    let participation = harness.chain.sync_committee_participation();
+    assert_eq!(participation.period, Some(1));
+    assert_eq!(participation.epochs.len(), 1);
+    assert_eq!(
+        participation.epochs[0].epoch,
+        spec.epochs_per_sync_committee_period
+    );
+    assert_eq!(participation.epochs[0].blocks, E::slots_per_epoch());
+    assert!(participation.epochs[0].participants > 0);
+}
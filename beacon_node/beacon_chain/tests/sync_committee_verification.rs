@@ -813,3 +813,75 @@ async fn unaggregated_gossip_verification() {
         if received == subnet_id && !expected.contains(&subnet_id)
     );
 }
+
+/// Tests that the contribution served to aggregators merges all of the individual sync committee
+/// messages in the naive aggregation pool, even if no aggregate has been received.
+#[tokio::test]
+async fn contribution_from_individual_messages() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let state = harness.get_current_state();
+
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            Hash256::zero(),
+            &[Slot::new(1), Slot::new(2)],
+            (0..VALIDATOR_COUNT).collect::<Vec<_>>().as_slice(),
+        )
+        .await;
+
+    let current_slot = harness.chain.slot().expect("should get slot");
+    let head_root = harness.chain.head_snapshot().beacon_block_root;
+    let head_state = harness.chain.head_beacon_state_cloned();
+    let subnet_id = SyncSubnetId::new(0);
+
+    let subcommittee_messages = harness
+        .make_sync_committee_messages(
+            &head_state,
+            head_root,
+            current_slot,
+            RelativeSyncCommittee::Current,
+        )
+        .remove(0);
+
+    // Only use validators with a single position in the subcommittee, so that each message sets a
+    // single bit.
+    let messages = subcommittee_messages
+        .iter()
+        .filter(|(message, _)| {
+            subcommittee_messages
+                .iter()
+                .filter(|(other, _)| other.validator_index == message.validator_index)
+                .count()
+                == 1
+        })
+        .take(5)
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 5);
+
+    for (message, _) in &messages {
+        let verified = harness
+            .chain
+            .verify_sync_committee_message_for_gossip(message.clone(), subnet_id)
+            .expect("valid sync message should be verified");
+        harness
+            .chain
+            .add_to_naive_sync_aggregation_pool(verified)
+            .unwrap();
+    }
+
+    let contribution = harness
+        .chain
+        .get_aggregated_sync_committee_contribution(&SyncContributionData {
+            slot: current_slot,
+            beacon_block_root: head_root,
+            subcommittee_index: subnet_id.into(),
+        })
+        .unwrap()
+        .expect("contribution should exist");
+
+    assert_eq!(contribution.aggregation_bits.num_set_bits(), 5);
+    for (_, position) in messages {
+        assert!(contribution.aggregation_bits.get(*position).unwrap());
+    }
+}
@@ -11,10 +11,12 @@ use beacon_chain::{
 use lazy_static::lazy_static;
 use operation_pool::PersistedOperationPool;
 use state_processing::{
-    per_slot_processing, per_slot_processing::Error as SlotProcessingError, EpochProcessingError,
+    per_slot_processing, per_slot_processing::Error as SlotProcessingError,
+    state_advance::complete_state_advance, EpochProcessingError,
 };
 use types::{
-    BeaconState, BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Slot,
+    BeaconState, BeaconStateError, BlindedPayload, EthSpec, ForkName, FullPayload, Hash256,
+    Keypair, MinimalEthSpec, RelativeEpoch, Slot,
 };
 
 // Should ideally be divisible by 3.
@@ -873,3 +875,81 @@ async fn block_roots_skip_slot_behaviour() {
         "WhenSlotSkipped::Prev should return None on a future slot"
     );
 }
+
+/// A block production dry run must not leave behind anything which affects a real proposal at the
+/// same slot.
+#[tokio::test]
+async fn produce_block_dry_run_does_not_affect_proposal() {
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(ForkName::Capella.make_genesis_spec(MinimalEthSpec::default_spec()))
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness
+        .extend_chain(
+            MinimalEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+    let slot = harness.get_current_slot();
+    let head_root = harness.head_block_root();
+    let last_re_org_decision_slot = || {
+        harness
+            .chain
+            .last_re_org_decision
+            .lock()
+            .as_ref()
+            .map(|d| d.slot)
+    };
+    let payload_ids = || harness.execution_block_generator().payload_ids.len();
+    let re_org_decision_slot_before = last_re_org_decision_slot();
+    let payload_ids_before = payload_ids();
+
+    for blinded in [false, true] {
+        let dry_run = if blinded {
+            harness
+                .chain
+                .produce_block_dry_run::<BlindedPayload<MinimalEthSpec>>(slot)
+                .await
+        } else {
+            harness
+                .chain
+                .produce_block_dry_run::<FullPayload<MinimalEthSpec>>(slot)
+                .await
+        }
+        .unwrap();
+        assert_eq!(dry_run.slot, slot);
+        assert_eq!(dry_run.parent_root, head_root);
+    }
+
+    // The dry runs made no proposer re-org decision, but did request payloads from the EL.
+    assert_eq!(last_re_org_decision_slot(), re_org_decision_slot_before);
+    let payload_ids_after_dry_runs = payload_ids();
+    assert_eq!(payload_ids_after_dry_runs, payload_ids_before + 2);
+
+    let mut state = harness.get_current_state();
+    complete_state_advance(&mut state, None, slot, &harness.spec).unwrap();
+    state.build_caches(&harness.spec).unwrap();
+    let proposer_index = state
+        .get_beacon_proposer_index(slot, &harness.spec)
+        .unwrap();
+    let randao_reveal = harness.sign_randao_reveal(&state, proposer_index, slot);
+
+    let (block, _, _) = harness
+        .chain
+        .produce_block::<FullPayload<MinimalEthSpec>>(randao_reveal, slot, None)
+        .await
+        .unwrap();
+    assert_eq!(block.slot(), slot);
+    assert_eq!(block.parent_root(), head_root);
+    assert_eq!(block.proposer_index(), proposer_index as u64);
+
+    // The real proposal made its own re-org decision, and requested a fresh payload rather than
+    // reusing a payload ID from the dry runs.
+    assert_eq!(last_re_org_decision_slot(), Some(slot));
+    assert_eq!(payload_ids(), payload_ids_after_dry_runs + 1);
+}
@@ -20,12 +20,13 @@
 //!
 //! ## Detail
 //!
-//! There is a single "manager" thread who listens to three event channels. These events are
+//! There is a single "manager" thread who listens to four event channels. These events are
 //! either:
 //!
 //! - A new parcel of work (work event).
 //! - Indication that a worker has finished a parcel of work (worker idle).
 //! - A work ready for reprocessing (work event).
+//! - A request for a snapshot of the queues (snapshot request).
 //!
 //! Then, there is a maximum of `n` "worker" blocking threads, where `n` is the CPU count.
 //!
@@ -39,8 +40,10 @@
 //! task.
 
 use crate::work_reprocessing_queue::{
-    QueuedBackfillBatch, QueuedGossipBlock, ReprocessQueueMessage,
+    QueuedBackfillBatch, QueuedGossipBlobSidecar, QueuedGossipBlock, ReprocessQueueMessage,
+    DEFAULT_MAXIMUM_QUEUED_ATTESTATIONS,
 };
+pub use eth2::lighthouse::{BeaconProcessorSnapshot, QueueSnapshot};
 use futures::stream::{Stream, StreamExt};
 use futures::task::Poll;
 use lighthouse_network::{MessageId, NetworkGlobals, PeerId};
@@ -56,10 +59,11 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::oneshot;
 use types::consts::deneb::MAX_BLOBS_PER_BLOCK;
 use types::{Attestation, Hash256, SignedAggregateAndProof, SubnetId};
 use types::{EthSpec, Slot};
@@ -70,8 +74,11 @@ use work_reprocessing_queue::{
 };
 
 mod metrics;
+mod priority_aging;
 pub mod work_reprocessing_queue;
 
+pub use priority_aging::PriorityAgingConfig;
+
 /// The maximum size of the channel for work events to the `BeaconProcessor`.
 ///
 /// Setting this too low will cause consensus messages to be dropped.
@@ -86,6 +93,11 @@ const MAX_IDLE_QUEUE_LEN: usize = 16_384;
 /// The maximum size of the channel for re-processing work events.
 const DEFAULT_MAX_SCHEDULED_WORK_QUEUE_LEN: usize = 3 * DEFAULT_MAX_WORK_EVENT_QUEUE_LEN / 4;
 
+/// The maximum number of snapshot requests that can be waiting for the manager task.
+///
+/// Snapshots are only requested by the HTTP API, so this can be small.
+const MAX_SNAPSHOT_REQUEST_QUEUE_LEN: usize = 16;
+
 /// The maximum number of queued `Attestation` objects that will be stored before we start dropping
 /// them.
 const MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN: usize = 16_384;
@@ -114,6 +126,10 @@ const MAX_GOSSIP_BLOB_QUEUE_LEN: usize = 1_024;
 /// within acceptable clock disparity) that will be queued before we start dropping them.
 const MAX_DELAYED_BLOCK_QUEUE_LEN: usize = 1_024;
 
+/// The factor by which the block, blob and aggregate queues are enlarged when
+/// `BeaconProcessorConfig::prioritize_blocks_and_aggregates` is set.
+const PRIORITIZED_QUEUE_LEN_MULTIPLIER: usize = 4;
+
 /// The maximum number of queued `SignedVoluntaryExit` objects received on gossip that will be stored
 /// before we start dropping them.
 const MAX_GOSSIP_EXIT_QUEUE_LEN: usize = 4_096;
@@ -260,6 +276,15 @@ pub struct BeaconProcessorConfig {
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
     pub enable_backfill_rate_limiting: bool,
+    /// Promote backfill chain segments ahead of attestations once they have been queued for too
+    /// long. `None` disables priority aging and restores strict priority ordering.
+    pub priority_aging: Option<PriorityAgingConfig>,
+    /// Enlarge the queues for gossip blocks, blobs and aggregates, and process aggregates ahead of
+    /// API requests. Intended for proposer-only nodes.
+    pub prioritize_blocks_and_aggregates: bool,
+    /// The maximum number of attestations and aggregates referencing an unknown block that are
+    /// held by the reprocessing queue until their block is imported.
+    pub max_queued_unknown_block_attestations: usize,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -271,6 +296,9 @@ impl Default for BeaconProcessorConfig {
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
             enable_backfill_rate_limiting: true,
+            priority_aging: Some(PriorityAgingConfig::default()),
+            prioritize_blocks_and_aggregates: false,
+            max_queued_unknown_block_attestations: DEFAULT_MAXIMUM_QUEUED_ATTESTATIONS,
         }
     }
 }
@@ -281,6 +309,8 @@ pub struct BeaconProcessorChannels<E: EthSpec> {
     pub beacon_processor_rx: mpsc::Receiver<WorkEvent<E>>,
     pub work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
     pub work_reprocessing_rx: mpsc::Receiver<ReprocessQueueMessage>,
+    pub snapshot_tx: BeaconProcessorSnapshotSend,
+    pub snapshot_rx: mpsc::Receiver<SnapshotRequest>,
 }
 
 impl<E: EthSpec> BeaconProcessorChannels<E> {
@@ -289,12 +319,15 @@ impl<E: EthSpec> BeaconProcessorChannels<E> {
             mpsc::channel(config.max_scheduled_work_queue_len);
         let (work_reprocessing_tx, work_reprocessing_rx) =
             mpsc::channel(config.max_scheduled_work_queue_len);
+        let (snapshot_tx, snapshot_rx) = mpsc::channel(MAX_SNAPSHOT_REQUEST_QUEUE_LEN);
 
         Self {
             beacon_processor_tx: BeaconProcessorSend(beacon_processor_tx),
             beacon_processor_rx,
             work_reprocessing_rx,
             work_reprocessing_tx,
+            snapshot_tx: BeaconProcessorSnapshotSend(snapshot_tx),
+            snapshot_rx,
         }
     }
 }
@@ -305,10 +338,35 @@ impl<E: EthSpec> Default for BeaconProcessorChannels<E> {
     }
 }
 
+/// A request for a `BeaconProcessorSnapshot`, answered by the manager task.
+pub type SnapshotRequest = oneshot::Sender<BeaconProcessorSnapshot>;
+
+/// A wrapper around a channel used to request a `BeaconProcessorSnapshot` from the manager task.
+#[derive(Clone)]
+pub struct BeaconProcessorSnapshotSend(pub mpsc::Sender<SnapshotRequest>);
+
+impl BeaconProcessorSnapshotSend {
+    /// Request a snapshot of the queues from the manager task.
+    ///
+    /// Snapshot requests bypass the work queues, so they are answered even when all workers are
+    /// busy.
+    pub async fn snapshot(&self) -> Result<BeaconProcessorSnapshot, String> {
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .try_send(tx)
+            .map_err(|e| format!("unable to request snapshot: {}", e))?;
+        rx.await
+            .map_err(|_| "beacon processor manager dropped snapshot request".to_string())
+    }
+}
+
 /// A simple first-in-first-out queue with a maximum length.
+///
+/// Records the time at which each item was enqueued, for priority aging.
 struct FifoQueue<T> {
-    queue: VecDeque<T>,
+    queue: VecDeque<(Instant, T)>,
     max_length: usize,
+    dropped: u64,
 }
 
 impl<T> FifoQueue<T> {
@@ -317,6 +375,7 @@ impl<T> FifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
@@ -324,6 +383,13 @@ impl<T> FifoQueue<T> {
     ///
     /// Drops `item` if the queue is full.
     pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
+        self.push_at(item, Instant::now(), item_desc, log)
+    }
+
+    /// Add a new item to the queue, recording that it was enqueued at `enqueued_at`.
+    ///
+    /// Drops `item` if the queue is full.
+    fn push_at(&mut self, item: T, enqueued_at: Instant, item_desc: &str, log: &Logger) {
         if self.queue.len() == self.max_length {
             error!(
                 log,
@@ -331,27 +397,43 @@ impl<T> FifoQueue<T> {
                 "msg" => "the system has insufficient resources for load",
                 "queue_len" => self.max_length,
                 "queue" => item_desc,
-            )
+            );
+            self.dropped = self.dropped.saturating_add(1);
         } else {
-            self.queue.push_back(item);
+            self.queue.push_back((enqueued_at, item));
         }
     }
 
     /// Remove the next item from the queue.
     pub fn pop(&mut self) -> Option<T> {
-        self.queue.pop_front()
+        self.queue.pop_front().map(|(_, item)| item)
+    }
+
+    /// Returns the time at which the next item in the queue was enqueued.
+    pub fn head_enqueued_at(&self) -> Option<Instant> {
+        self.queue.front().map(|(enqueued_at, _)| *enqueued_at)
     }
 
     /// Returns the current length of the queue.
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    /// Returns the current state of the queue.
+    pub fn snapshot(&self) -> QueueSnapshot {
+        QueueSnapshot {
+            length: self.queue.len(),
+            max_length: self.max_length,
+            dropped: self.dropped,
+        }
+    }
 }
 
 /// A simple last-in-first-out queue with a maximum length.
 struct LifoQueue<T> {
     queue: VecDeque<T>,
     max_length: usize,
+    dropped: u64,
 }
 
 impl<T> LifoQueue<T> {
@@ -360,6 +442,7 @@ impl<T> LifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
@@ -369,6 +452,7 @@ impl<T> LifoQueue<T> {
     pub fn push(&mut self, item: T) {
         if self.queue.len() == self.max_length {
             self.queue.pop_back();
+            self.dropped = self.dropped.saturating_add(1);
         }
         self.queue.push_front(item);
     }
@@ -387,6 +471,15 @@ impl<T> LifoQueue<T> {
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    /// Returns the current state of the queue.
+    pub fn snapshot(&self) -> QueueSnapshot {
+        QueueSnapshot {
+            length: self.queue.len(),
+            max_length: self.max_length,
+            dropped: self.dropped,
+        }
+    }
 }
 
 /// A handle that sends a message on the provided channel to a receiver when it gets dropped.
@@ -467,6 +560,15 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                     process_fn,
                 },
             },
+            ReadyWork::BlobSidecar(QueuedGossipBlobSidecar {
+                slot: _,
+                block_root: _,
+                index: _,
+                process_fn,
+            }) => Self {
+                drop_during_sync: false,
+                work: Work::GossipSignedBlobSidecar(process_fn),
+            },
             ReadyWork::RpcBlock(QueuedRpcBlock {
                 beacon_block_root: _,
                 process_fn,
@@ -679,6 +781,8 @@ enum InboundEvent<E: EthSpec> {
     WorkEvent(WorkEvent<E>),
     /// A work event that was queued for re-processing has become ready.
     ReprocessingWork(WorkEvent<E>),
+    /// A snapshot of the queues has been requested.
+    SnapshotRequest(SnapshotRequest),
 }
 
 /// Combines the various incoming event streams for the `BeaconProcessor` into a single stream.
@@ -692,6 +796,8 @@ struct InboundEvents<E: EthSpec> {
     event_rx: mpsc::Receiver<WorkEvent<E>>,
     /// Used internally for queuing work ready to be re-processed.
     reprocess_work_rx: mpsc::Receiver<ReadyWork>,
+    /// Used by the HTTP API to request a snapshot of the queues.
+    snapshot_rx: mpsc::Receiver<SnapshotRequest>,
 }
 
 impl<E: EthSpec> Stream for InboundEvents<E> {
@@ -710,6 +816,13 @@ impl<E: EthSpec> Stream for InboundEvents<E> {
             Poll::Pending => {}
         }
 
+        // Answer snapshot requests before new work so they aren't starved by a busy network. A
+        // closed channel just means that nobody is requesting snapshots, so it doesn't end the
+        // stream.
+        if let Poll::Ready(Some(request)) = self.snapshot_rx.poll_recv(cx) {
+            return Poll::Ready(Some(InboundEvent::SnapshotRequest(request)));
+        }
+
         // Poll for delayed blocks before polling for new work. It might be the case that a delayed
         // block is required to successfully process some new work.
         match self.reprocess_work_rx.poll_recv(cx) {
@@ -765,6 +878,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
         event_rx: mpsc::Receiver<WorkEvent<E>>,
         work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
         work_reprocessing_rx: mpsc::Receiver<ReprocessQueueMessage>,
+        snapshot_rx: mpsc::Receiver<SnapshotRequest>,
         work_journal_tx: Option<mpsc::Sender<&'static str>>,
         slot_clock: S,
         maximum_gossip_clock_disparity: Duration,
@@ -772,10 +886,20 @@ impl<E: EthSpec> BeaconProcessor<E> {
         // Used by workers to communicate that they are finished a task.
         let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
 
+        let prioritize_blocks_and_aggregates = self.config.prioritize_blocks_and_aggregates;
+        let prioritized_queue_len = |len: usize| {
+            if prioritize_blocks_and_aggregates {
+                len * PRIORITIZED_QUEUE_LEN_MULTIPLIER
+            } else {
+                len
+            }
+        };
+
         // Using LIFO queues for attestations since validator profits rely upon getting fresh
         // attestations into blocks. Additionally, later attestations contain more information than
         // earlier ones, so we consider them more valuable.
-        let mut aggregate_queue = LifoQueue::new(MAX_AGGREGATED_ATTESTATION_QUEUE_LEN);
+        let mut aggregate_queue =
+            LifoQueue::new(prioritized_queue_len(MAX_AGGREGATED_ATTESTATION_QUEUE_LEN));
         let mut aggregate_debounce = TimeLatch::default();
         let mut attestation_queue = LifoQueue::new(MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN);
         let mut attestation_debounce = TimeLatch::default();
@@ -809,9 +933,12 @@ impl<E: EthSpec> BeaconProcessor<E> {
         let mut rpc_blob_queue = FifoQueue::new(MAX_RPC_BLOB_QUEUE_LEN);
         let mut chain_segment_queue = FifoQueue::new(MAX_CHAIN_SEGMENT_QUEUE_LEN);
         let mut backfill_chain_segment = FifoQueue::new(MAX_CHAIN_SEGMENT_QUEUE_LEN);
-        let mut gossip_block_queue = FifoQueue::new(MAX_GOSSIP_BLOCK_QUEUE_LEN);
-        let mut gossip_blob_queue = FifoQueue::new(MAX_GOSSIP_BLOB_QUEUE_LEN);
-        let mut delayed_block_queue = FifoQueue::new(MAX_DELAYED_BLOCK_QUEUE_LEN);
+        let mut gossip_block_queue =
+            FifoQueue::new(prioritized_queue_len(MAX_GOSSIP_BLOCK_QUEUE_LEN));
+        let mut gossip_blob_queue =
+            FifoQueue::new(prioritized_queue_len(MAX_GOSSIP_BLOB_QUEUE_LEN));
+        let mut delayed_block_queue =
+            FifoQueue::new(prioritized_queue_len(MAX_DELAYED_BLOCK_QUEUE_LEN));
 
         let mut status_queue = FifoQueue::new(MAX_STATUS_QUEUE_LEN);
         let mut bbrange_queue = FifoQueue::new(MAX_BLOCKS_BY_RANGE_QUEUE_LEN);
@@ -838,6 +965,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
             slot_clock,
             self.log.clone(),
             maximum_gossip_clock_disparity,
+            self.config.max_queued_unknown_block_attestations,
         )?;
 
         let executor = self.executor.clone();
@@ -849,9 +977,11 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 idle_rx,
                 event_rx,
                 reprocess_work_rx: ready_work_rx,
+                snapshot_rx,
             };
 
             let enable_backfill_rate_limiting = self.config.enable_backfill_rate_limiting;
+            let priority_aging_config = self.config.priority_aging.clone();
 
             loop {
                 let work_event = match inbound_events.next().await {
@@ -859,6 +989,78 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         self.current_workers = self.current_workers.saturating_sub(1);
                         None
                     }
+                    Some(InboundEvent::SnapshotRequest(request)) => {
+                        let queues = [
+                            (GOSSIP_AGGREGATE, aggregate_queue.snapshot()),
+                            (GOSSIP_ATTESTATION, attestation_queue.snapshot()),
+                            (
+                                UNKNOWN_BLOCK_AGGREGATE,
+                                unknown_block_aggregate_queue.snapshot(),
+                            ),
+                            (
+                                UNKNOWN_BLOCK_ATTESTATION,
+                                unknown_block_attestation_queue.snapshot(),
+                            ),
+                            (GOSSIP_SYNC_SIGNATURE, sync_message_queue.snapshot()),
+                            (GOSSIP_SYNC_CONTRIBUTION, sync_contribution_queue.snapshot()),
+                            (
+                                GOSSIP_VOLUNTARY_EXIT,
+                                gossip_voluntary_exit_queue.snapshot(),
+                            ),
+                            (
+                                GOSSIP_PROPOSER_SLASHING,
+                                gossip_proposer_slashing_queue.snapshot(),
+                            ),
+                            (
+                                GOSSIP_ATTESTER_SLASHING,
+                                gossip_attester_slashing_queue.snapshot(),
+                            ),
+                            (
+                                GOSSIP_LIGHT_CLIENT_FINALITY_UPDATE,
+                                finality_update_queue.snapshot(),
+                            ),
+                            (
+                                GOSSIP_LIGHT_CLIENT_OPTIMISTIC_UPDATE,
+                                optimistic_update_queue.snapshot(),
+                            ),
+                            (
+                                UNKNOWN_LIGHT_CLIENT_UPDATE,
+                                unknown_light_client_update_queue.snapshot(),
+                            ),
+                            (RPC_BLOCK, rpc_block_queue.snapshot()),
+                            (RPC_BLOBS, rpc_blob_queue.snapshot()),
+                            (CHAIN_SEGMENT, chain_segment_queue.snapshot()),
+                            (CHAIN_SEGMENT_BACKFILL, backfill_chain_segment.snapshot()),
+                            (GOSSIP_BLOCK, gossip_block_queue.snapshot()),
+                            (GOSSIP_BLOBS_SIDECAR, gossip_blob_queue.snapshot()),
+                            (DELAYED_IMPORT_BLOCK, delayed_block_queue.snapshot()),
+                            (STATUS_PROCESSING, status_queue.snapshot()),
+                            (BLOCKS_BY_RANGE_REQUEST, bbrange_queue.snapshot()),
+                            (BLOCKS_BY_ROOTS_REQUEST, bbroots_queue.snapshot()),
+                            (BLOBS_BY_RANGE_REQUEST, blbrange_queue.snapshot()),
+                            (BLOBS_BY_ROOTS_REQUEST, blbroots_queue.snapshot()),
+                            (
+                                GOSSIP_BLS_TO_EXECUTION_CHANGE,
+                                gossip_bls_to_execution_change_queue.snapshot(),
+                            ),
+                            (LIGHT_CLIENT_BOOTSTRAP_REQUEST, lcbootstrap_queue.snapshot()),
+                            (API_REQUEST_P0, api_request_p0_queue.snapshot()),
+                            (API_REQUEST_P1, api_request_p1_queue.snapshot()),
+                        ]
+                        .into_iter()
+                        .map(|(id, queue)| (id.to_string(), queue))
+                        .collect();
+
+                        let snapshot = BeaconProcessorSnapshot {
+                            current_workers: self.current_workers,
+                            max_workers: self.config.max_workers,
+                            queues,
+                        };
+
+                        // The requester may have given up waiting, which is fine.
+                        let _ = request.send(snapshot);
+                        continue;
+                    }
                     Some(InboundEvent::WorkEvent(event)) if enable_backfill_rate_limiting => {
                         match QueuedBackfillBatch::try_from(event) {
                             Ok(backfill_batch) => {
@@ -946,6 +1148,11 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
+                        // When prioritizing aggregates, skip the API requests and aged work which
+                        // are normally checked before them.
+                        let aggregates_first =
+                            prioritize_blocks_and_aggregates && aggregate_queue.len() > 0;
+
                         // Check for chain segments first, they're the most efficient way to get
                         // blocks into the system.
                         if let Some(item) = chain_segment_queue.pop() {
@@ -967,7 +1174,25 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         } else if let Some(item) = gossip_blob_queue.pop() {
                             self.spawn_worker(item, idle_tx);
                         // Check the priority 0 API requests after blocks and blobs, but before attestations.
-                        } else if let Some(item) = api_request_p0_queue.pop() {
+                        } else if let Some(item) = (!aggregates_first)
+                            .then(|| api_request_p0_queue.pop())
+                            .flatten()
+                        {
+                            self.spawn_worker(item, idle_tx);
+                        // Check for backfill chain segments which have waited longer than their
+                        // priority aging threshold before attestations, so that backfill sync
+                        // can't be starved by a flood of attestations.
+                        } else if let Some(item) = priority_aging_config
+                            .as_ref()
+                            .filter(|_| !aggregates_first)
+                            .and_then(|aging| {
+                                priority_aging::pop_if_overdue(
+                                    Instant::now(),
+                                    &mut backfill_chain_segment,
+                                    aging.backfill_chain_segment_threshold,
+                                )
+                            })
+                        {
                             self.spawn_worker(item, idle_tx);
                         // Check the aggregates, *then* the unaggregates since we assume that
                         // aggregates are more valuable to local validators and effectively give us
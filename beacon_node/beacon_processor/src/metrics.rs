@@ -129,6 +129,12 @@ lazy_static::lazy_static! {
         "Count of items in a reprocessing queue.",
         &["type"]
     );
+    pub static ref BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+        "beacon_processor_reprocessing_queue_dropped_total",
+        "Count of items dropped because a reprocessing queue was full.",
+        &["type"]
+    );
     pub static ref BEACON_PROCESSOR_REPROCESSING_QUEUE_EXPIRED_ATTESTATIONS: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_reprocessing_queue_expired_attestations",
         "Number of queued attestations which have expired before a matching block has been found."
@@ -0,0 +1,257 @@
+//! Provides priority aging for the `BeaconProcessor` scheduler.
+//!
+//! The scheduler serves queues in strict priority order. Most block imports (chain segments, sync
+//! blocks and blobs, delayed blocks and gossip blocks and blobs) are checked before attestations,
+//! so they retain their strict priority and are never delayed by aging. Backfill chain segments
+//! are the exception: they are checked after every other kind of work, so a sustained flood of
+//! gossip attestations can starve them indefinitely and stall backfill sync.
+//!
+//! Priority aging promotes the head of the backfill queue ahead of attestations once it has waited
+//! longer than a configured threshold, which bounds how long it can be starved.
+
+use crate::FifoQueue;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// The default time after which a backfill chain segment is imported ahead of attestations.
+pub const DEFAULT_BACKFILL_CHAIN_SEGMENT_AGING_THRESHOLD: Duration = Duration::from_secs(4);
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PriorityAgingConfig {
+    /// Applies to backfill chain segments.
+    pub backfill_chain_segment_threshold: Duration,
+}
+
+impl Default for PriorityAgingConfig {
+    fn default() -> Self {
+        Self {
+            backfill_chain_segment_threshold: DEFAULT_BACKFILL_CHAIN_SEGMENT_AGING_THRESHOLD,
+        }
+    }
+}
+
+/// Pops the head of `queue` if it has waited for at least `threshold`.
+pub(crate) fn pop_if_overdue<T>(
+    now: Instant,
+    queue: &mut FifoQueue<T>,
+    threshold: Duration,
+) -> Option<T> {
+    let age = now.saturating_duration_since(queue.head_enqueued_at()?);
+    if age >= threshold {
+        queue.pop()
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LifoQueue;
+    use logging::test_logger;
+
+    const TICK: Duration = Duration::from_millis(10);
+
+    #[derive(Debug)]
+    enum Item {
+        ChainSegment(Instant),
+        GossipBlock(Instant),
+        Attestation,
+        BackfillChainSegment(Instant),
+    }
+
+    /// A model of the `BeaconProcessor` scheduler with a single worker which completes one item
+    /// per tick, mirroring the order in which the manager checks its queues.
+    struct Simulation {
+        aging: Option<PriorityAgingConfig>,
+        chain_segment_queue: FifoQueue<Item>,
+        gossip_block_queue: FifoQueue<Item>,
+        attestation_queue: LifoQueue<Item>,
+        backfill_chain_segment: FifoQueue<Item>,
+    }
+
+    impl Simulation {
+        fn new(aging: Option<PriorityAgingConfig>) -> Self {
+            Self {
+                aging,
+                chain_segment_queue: FifoQueue::new(64),
+                gossip_block_queue: FifoQueue::new(1_024),
+                attestation_queue: LifoQueue::new(16_384),
+                backfill_chain_segment: FifoQueue::new(64),
+            }
+        }
+
+        fn next(&mut self, now: Instant) -> Option<Item> {
+            if let Some(item) = self.chain_segment_queue.pop() {
+                Some(item)
+            } else if let Some(item) = self.gossip_block_queue.pop() {
+                Some(item)
+            } else if let Some(item) = self.aging.as_ref().and_then(|aging| {
+                pop_if_overdue(
+                    now,
+                    &mut self.backfill_chain_segment,
+                    aging.backfill_chain_segment_threshold,
+                )
+            }) {
+                Some(item)
+            } else if let Some(item) = self.attestation_queue.pop() {
+                Some(item)
+            } else {
+                self.backfill_chain_segment.pop()
+            }
+        }
+    }
+
+    /// The time that each kind of block import waited before being processed.
+    #[derive(Default)]
+    struct Waits {
+        chain_segments: Vec<Duration>,
+        gossip_blocks: Vec<Duration>,
+        backfill_chain_segments: Vec<Duration>,
+    }
+
+    /// Runs `ticks` ticks in which attestations arrive faster than they can be processed, along
+    /// with a backfill chain segment every 50 ticks and (optionally) chain segments and gossip
+    /// blocks every `blocks_every` ticks.
+    fn run_attestation_flood(
+        aging: Option<PriorityAgingConfig>,
+        ticks: u32,
+        blocks_every: Option<u32>,
+    ) -> Waits {
+        let log = test_logger();
+        let start = Instant::now();
+        let mut sim = Simulation::new(aging);
+        let mut waits = Waits::default();
+
+        for tick in 0..ticks {
+            let now = start + TICK * tick;
+
+            for _ in 0..4 {
+                sim.attestation_queue.push(Item::Attestation);
+            }
+            if tick % 50 == 0 {
+                sim.backfill_chain_segment.push_at(
+                    Item::BackfillChainSegment(now),
+                    now,
+                    "backfill",
+                    &log,
+                );
+            }
+            if blocks_every.map_or(false, |n| tick % n == 0) {
+                sim.chain_segment_queue.push_at(
+                    Item::ChainSegment(now),
+                    now,
+                    "chain_segment",
+                    &log,
+                );
+                sim.gossip_block_queue
+                    .push_at(Item::GossipBlock(now), now, "gossip_block", &log);
+            }
+
+            match sim.next(now) {
+                Some(Item::ChainSegment(enqueued)) => waits.chain_segments.push(now - enqueued),
+                Some(Item::GossipBlock(enqueued)) => waits.gossip_blocks.push(now - enqueued),
+                Some(Item::BackfillChainSegment(enqueued)) => {
+                    waits.backfill_chain_segments.push(now - enqueued)
+                }
+                Some(Item::Attestation) | None => (),
+            }
+        }
+
+        waits
+    }
+
+    #[test]
+    fn strict_priority_starves_backfill_under_attestation_flood() {
+        let waits = run_attestation_flood(None, 2_000, None);
+        assert!(waits.backfill_chain_segments.is_empty());
+    }
+
+    #[test]
+    fn aging_bounds_backfill_starvation() {
+        let aging = PriorityAgingConfig {
+            backfill_chain_segment_threshold: Duration::from_millis(200),
+        };
+        let waits = run_attestation_flood(Some(aging.clone()), 2_000, None);
+
+        // Backfill segments arrive every 500ms and each is imported once it has waited for the
+        // threshold, so none of them accumulate.
+        assert_eq!(waits.backfill_chain_segments.len(), 40);
+        assert!(waits
+            .backfill_chain_segments
+            .iter()
+            .all(|wait| *wait == aging.backfill_chain_segment_threshold));
+    }
+
+    #[test]
+    fn aging_bounds_backfill_starvation_with_block_imports() {
+        let aging = PriorityAgingConfig {
+            backfill_chain_segment_threshold: Duration::from_millis(200),
+        };
+        let waits = run_attestation_flood(Some(aging.clone()), 2_000, Some(7));
+
+        // Chain segments and gossip blocks keep their strict priority, and may delay an overdue
+        // backfill segment by the few ticks it takes to import them.
+        assert!(!waits.chain_segments.is_empty());
+        assert!(waits
+            .chain_segments
+            .iter()
+            .all(|wait| *wait == Duration::ZERO));
+        assert!(waits.gossip_blocks.iter().all(|wait| *wait <= TICK));
+        assert_eq!(waits.backfill_chain_segments.len(), 40);
+        assert!(waits
+            .backfill_chain_segments
+            .iter()
+            .all(|wait| *wait <= aging.backfill_chain_segment_threshold + TICK * 2));
+    }
+
+    #[test]
+    fn aged_backfill_never_precedes_block_imports() {
+        let log = test_logger();
+        let aging = PriorityAgingConfig {
+            backfill_chain_segment_threshold: Duration::from_millis(200),
+        };
+        let start = Instant::now();
+        let mut sim = Simulation::new(Some(aging));
+
+        sim.backfill_chain_segment.push_at(
+            Item::BackfillChainSegment(start),
+            start,
+            "backfill",
+            &log,
+        );
+        sim.attestation_queue.push(Item::Attestation);
+
+        // The backfill segment is long overdue when a chain segment and gossip block arrive, yet
+        // both are imported before it.
+        let now = start + Duration::from_secs(10);
+        sim.chain_segment_queue
+            .push_at(Item::ChainSegment(now), now, "chain_segment", &log);
+        sim.gossip_block_queue
+            .push_at(Item::GossipBlock(now), now, "gossip_block", &log);
+
+        assert!(matches!(sim.next(now), Some(Item::ChainSegment(_))));
+        assert!(matches!(sim.next(now), Some(Item::GossipBlock(_))));
+        assert!(matches!(sim.next(now), Some(Item::BackfillChainSegment(_))));
+        assert!(matches!(sim.next(now), Some(Item::Attestation)));
+        assert!(sim.next(now).is_none());
+    }
+
+    #[test]
+    fn pop_if_overdue_respects_threshold() {
+        let log = test_logger();
+        let start = Instant::now();
+        let mut queue = FifoQueue::new(8);
+        queue.push_at("a", start, "a", &log);
+        queue.push_at("b", start + Duration::from_secs(2), "b", &log);
+
+        let now = start + Duration::from_secs(5);
+        let threshold = Duration::from_secs(4);
+
+        // Only the head of the queue is considered, and only once it is overdue.
+        assert_eq!(pop_if_overdue(start, &mut queue, threshold), None);
+        assert_eq!(pop_if_overdue(now, &mut queue, threshold), Some("a"));
+        assert_eq!(pop_if_overdue(now, &mut queue, threshold), None);
+        assert_eq!(queue.len(), 1);
+    }
+}
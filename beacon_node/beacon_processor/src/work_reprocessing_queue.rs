@@ -8,10 +8,19 @@
 //! There is the edge-case where the slot arrives before this queue manages to process it. In that
 //! case, the block will be sent off for immediate processing (skipping the `DelayQueue`).
 //!
+//! Blob sidecars that arrive before their slot, but by less than a slot, are queued in the same way
+//! and sent back for gossip verification once the slot arrives.
+//!
 //! Aggregated and unaggregated attestations that failed verification due to referencing an unknown
 //! block will be re-queued until their block is imported, or until they expire.
+//!
+//! A `ReprocessQueueSnapshot` of the queued work can be requested with
+//! `ReprocessQueueMessage::Snapshot`, e.g. by the HTTP API.
 use crate::metrics;
 use crate::{AsyncFn, BlockingFn, Work, WorkEvent};
+pub use eth2::lighthouse::{
+    EarlyBlockSnapshot, ReprocessQueueCategorySnapshot, ReprocessQueueSnapshot,
+};
 use fnv::FnvHashMap;
 use futures::task::Poll;
 use futures::{Stream, StreamExt};
@@ -23,18 +32,22 @@ use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use strum::AsRefStr;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::oneshot;
 use tokio::time::error::Error as TimeError;
 use tokio_util::time::delay_queue::{DelayQueue, Key as DelayKey};
 use types::{EthSpec, Hash256, Slot};
 
 const TASK_NAME: &str = "beacon_processor_reprocess_queue";
 const GOSSIP_BLOCKS: &str = "gossip_blocks";
+const GOSSIP_BLOB_SIDECARS: &str = "gossip_blob_sidecars";
 const RPC_BLOCKS: &str = "rpc_blocks";
 const ATTESTATIONS: &str = "attestations";
+const UNAGGREGATES: &str = "unaggregates";
+const AGGREGATES: &str = "aggregates";
 const LIGHT_CLIENT_UPDATES: &str = "lc_updates";
 
 /// Queue blocks for re-processing with an `ADDITIONAL_QUEUED_BLOCK_DELAY` after the slot starts.
@@ -55,8 +68,13 @@ pub const QUEUED_RPC_BLOCK_DELAY: Duration = Duration::from_secs(4);
 /// it's nice to have extra protection.
 const MAXIMUM_QUEUED_BLOCKS: usize = 16;
 
-/// How many attestations we keep before new ones get dropped.
-const MAXIMUM_QUEUED_ATTESTATIONS: usize = 16_384;
+/// Set an arbitrary upper-bound on the number of queued blob sidecars. Unlike blocks, blob sidecars
+/// are queued before their proposer signature is verified.
+const MAXIMUM_QUEUED_BLOB_SIDECARS: usize = 96;
+
+/// How many attestations we keep before new ones get dropped, unless overridden by
+/// `BeaconProcessorConfig::max_queued_unknown_block_attestations`.
+pub const DEFAULT_MAXIMUM_QUEUED_ATTESTATIONS: usize = 16_384;
 
 /// How many light client updates we keep before new ones get dropped.
 const MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES: usize = 128;
@@ -79,6 +97,9 @@ pub const BACKFILL_SCHEDULE_IN_SLOT: [(u32, u32); 3] = [
 pub enum ReprocessQueueMessage {
     /// A block that has been received early and we should queue for later processing.
     EarlyBlock(QueuedGossipBlock),
+    /// A blob sidecar that has been received before its slot and should be verified once the slot
+    /// arrives.
+    EarlyBlobSidecar(QueuedGossipBlobSidecar),
     /// A gossip block for hash `X` is being imported, we should queue the rpc block for the same
     /// hash until the gossip block is imported.
     RpcBlock(QueuedRpcBlock),
@@ -96,11 +117,14 @@ pub enum ReprocessQueueMessage {
     UnknownLightClientOptimisticUpdate(QueuedLightClientUpdate),
     /// A new backfill batch that needs to be scheduled for processing.
     BackfillSync(QueuedBackfillBatch),
+    /// A request for a snapshot of the queued work.
+    Snapshot(ReprocessQueueSnapshotRequest),
 }
 
 /// Events sent by the scheduler once they are ready for re-processing.
 pub enum ReadyWork {
     Block(QueuedGossipBlock),
+    BlobSidecar(QueuedGossipBlobSidecar),
     RpcBlock(QueuedRpcBlock),
     IgnoredRpcBlock(IgnoredRpcBlock),
     Unaggregate(QueuedUnaggregate),
@@ -137,6 +161,15 @@ pub struct QueuedGossipBlock {
     pub process_fn: AsyncFn,
 }
 
+/// A blob sidecar that arrived before its slot and has been queued for later gossip verification.
+pub struct QueuedGossipBlobSidecar {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub index: u64,
+    /// Verifies the blob sidecar, without queuing it again.
+    pub process_fn: AsyncFn,
+}
+
 /// A block that arrived for processing when the same block was being imported over gossip.
 /// It is queued for later import.
 pub struct QueuedRpcBlock {
@@ -156,6 +189,43 @@ pub struct IgnoredRpcBlock {
 /// A backfill batch work that has been queued for processing later.
 pub struct QueuedBackfillBatch(pub AsyncFn);
 
+/// Summarises a category of `length` queued items which were queued at the instants in
+/// `queued_at`.
+fn category_snapshot(
+    length: usize,
+    max_length: usize,
+    queued_at: impl Iterator<Item = Instant>,
+    now: Instant,
+) -> ReprocessQueueCategorySnapshot {
+    ReprocessQueueCategorySnapshot {
+        length,
+        max_length,
+        oldest_age_ms: queued_at
+            .min()
+            .map(|queued_at| now.saturating_duration_since(queued_at).as_millis() as u64),
+    }
+}
+
+/// A request for a `ReprocessQueueSnapshot`, answered by the reprocessing queue task.
+pub type ReprocessQueueSnapshotRequest = oneshot::Sender<ReprocessQueueSnapshot>;
+
+/// A wrapper around the channel to the reprocessing queue, used to request a
+/// `ReprocessQueueSnapshot`.
+#[derive(Clone)]
+pub struct ReprocessQueueSnapshotSend(pub Sender<ReprocessQueueMessage>);
+
+impl ReprocessQueueSnapshotSend {
+    /// Request a snapshot of the queued work from the reprocessing queue task.
+    pub async fn snapshot(&self) -> Result<ReprocessQueueSnapshot, String> {
+        let (tx, rx) = oneshot::channel();
+        self.0
+            .try_send(ReprocessQueueMessage::Snapshot(tx))
+            .map_err(|e| format!("unable to request reprocess queue snapshot: {}", e))?;
+        rx.await
+            .map_err(|_| "reprocess queue dropped snapshot request".to_string())
+    }
+}
+
 impl<T: EthSpec> TryFrom<WorkEvent<T>> for QueuedBackfillBatch {
     type Error = WorkEvent<T>;
 
@@ -183,6 +253,8 @@ impl<T: EthSpec> From<QueuedBackfillBatch> for WorkEvent<T> {
 enum InboundEvent {
     /// A gossip block that was queued for later processing and is ready for import.
     ReadyGossipBlock(QueuedGossipBlock),
+    /// A gossip blob sidecar that was queued until its slot and is ready for verification.
+    ReadyGossipBlobSidecar(QueuedGossipBlobSidecar),
     /// A rpc block that was queued because the same gossip block was being imported
     /// will now be retried for import.
     ReadyRpcBlock(QueuedRpcBlock),
@@ -208,6 +280,8 @@ struct ReprocessQueue<S> {
     /* Queues */
     /// Queue to manage scheduled early blocks.
     gossip_block_delay_queue: DelayQueue<QueuedGossipBlock>,
+    /// Queue to manage scheduled early blob sidecars.
+    gossip_blob_delay_queue: DelayQueue<QueuedGossipBlobSidecar>,
     /// Queue to manage scheduled early blocks.
     rpc_block_delay_queue: DelayQueue<QueuedRpcBlock>,
     /// Queue to manage scheduled attestations.
@@ -216,26 +290,31 @@ struct ReprocessQueue<S> {
     lc_updates_delay_queue: DelayQueue<QueuedLightClientUpdateId>,
 
     /* Queued items */
-    /// Queued blocks.
-    queued_gossip_block_roots: HashSet<Hash256>,
+    /// Queued blocks, with their slot and the time at which they were queued.
+    queued_gossip_block_roots: HashMap<Hash256, (Slot, Instant)>,
+    /// Queued blob sidecars, by block root and index.
+    queued_gossip_blob_ids: HashSet<(Hash256, u64)>,
     /// Queued aggregated attestations.
-    queued_aggregates: FnvHashMap<usize, (QueuedAggregate, DelayKey)>,
+    queued_aggregates: FnvHashMap<usize, (QueuedAggregate, DelayKey, Instant)>,
     /// Queued attestations.
-    queued_unaggregates: FnvHashMap<usize, (QueuedUnaggregate, DelayKey)>,
+    queued_unaggregates: FnvHashMap<usize, (QueuedUnaggregate, DelayKey, Instant)>,
     /// Attestations (aggregated and unaggregated) per root.
     awaiting_attestations_per_root: HashMap<Hash256, Vec<QueuedAttestationId>>,
     /// Queued Light Client Updates.
-    queued_lc_updates: FnvHashMap<usize, (QueuedLightClientUpdate, DelayKey)>,
+    queued_lc_updates: FnvHashMap<usize, (QueuedLightClientUpdate, DelayKey, Instant)>,
     /// Light Client Updates per parent_root.
     awaiting_lc_updates_per_parent_root: HashMap<Hash256, Vec<QueuedLightClientUpdateId>>,
     /// Queued backfill batches
     queued_backfill_batches: Vec<QueuedBackfillBatch>,
 
     /* Aux */
+    /// How many aggregated and unaggregated attestations we keep before new ones get dropped.
+    max_queued_attestations: usize,
     /// Next attestation id, used for both aggregated and unaggregated attestations
     next_attestation: usize,
     next_lc_update: usize,
     early_block_debounce: TimeLatch,
+    early_blob_debounce: TimeLatch,
     rpc_block_debounce: TimeLatch,
     attestation_delay_debounce: TimeLatch,
     lc_update_delay_debounce: TimeLatch,
@@ -288,6 +367,20 @@ impl<S: SlotClock> Stream for ReprocessQueue<S> {
             Poll::Ready(None) | Poll::Pending => (),
         }
 
+        match self.gossip_blob_delay_queue.poll_expired(cx) {
+            Poll::Ready(Some(Ok(queued_blob))) => {
+                return Poll::Ready(Some(InboundEvent::ReadyGossipBlobSidecar(
+                    queued_blob.into_inner(),
+                )));
+            }
+            Poll::Ready(Some(Err(e))) => {
+                return Poll::Ready(Some(InboundEvent::DelayQueueError(e, "gossip_blob_queue")));
+            }
+            // `Poll::Ready(None)` means that there are no more entries in the delay queue and we
+            // will continue to get this result until something else is added into the queue.
+            Poll::Ready(None) | Poll::Pending => (),
+        }
+
         match self.rpc_block_delay_queue.poll_expired(cx) {
             Poll::Ready(Some(Ok(queued_block))) => {
                 return Poll::Ready(Some(InboundEvent::ReadyRpcBlock(queued_block.into_inner())));
@@ -362,6 +455,7 @@ pub fn spawn_reprocess_scheduler<S: SlotClock + 'static>(
     slot_clock: S,
     log: Logger,
     maximum_gossip_clock_disparity: Duration,
+    max_queued_attestations: usize,
 ) -> Result<(), String> {
     // Sanity check
     if ADDITIONAL_QUEUED_BLOCK_DELAY >= maximum_gossip_clock_disparity {
@@ -371,19 +465,23 @@ pub fn spawn_reprocess_scheduler<S: SlotClock + 'static>(
         work_reprocessing_rx,
         ready_work_tx,
         gossip_block_delay_queue: DelayQueue::new(),
+        gossip_blob_delay_queue: DelayQueue::new(),
         rpc_block_delay_queue: DelayQueue::new(),
         attestations_delay_queue: DelayQueue::new(),
         lc_updates_delay_queue: DelayQueue::new(),
-        queued_gossip_block_roots: HashSet::new(),
+        queued_gossip_block_roots: HashMap::new(),
+        queued_gossip_blob_ids: HashSet::new(),
         queued_lc_updates: FnvHashMap::default(),
         queued_aggregates: FnvHashMap::default(),
         queued_unaggregates: FnvHashMap::default(),
         awaiting_attestations_per_root: HashMap::new(),
         awaiting_lc_updates_per_parent_root: HashMap::new(),
         queued_backfill_batches: Vec::new(),
+        max_queued_attestations,
         next_attestation: 0,
         next_lc_update: 0,
         early_block_debounce: TimeLatch::default(),
+        early_blob_debounce: TimeLatch::default(),
         rpc_block_debounce: TimeLatch::default(),
         attestation_delay_debounce: TimeLatch::default(),
         lc_update_delay_debounce: TimeLatch::default(),
@@ -419,7 +517,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 let block_root = early_block.beacon_block_root;
 
                 // Don't add the same block to the queue twice. This prevents DoS attacks.
-                if self.queued_gossip_block_roots.contains(&block_root) {
+                if self.queued_gossip_block_roots.contains_key(&block_root) {
                     return;
                 }
 
@@ -435,10 +533,15 @@ impl<S: SlotClock> ReprocessQueue<S> {
                             );
                         }
                         // Drop the block.
+                        metrics::inc_counter_vec(
+                            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL,
+                            &[GOSSIP_BLOCKS],
+                        );
                         return;
                     }
 
-                    self.queued_gossip_block_roots.insert(block_root);
+                    self.queued_gossip_block_roots
+                        .insert(block_root, (block_slot, Instant::now()));
                     // Queue the block until the start of the appropriate slot, plus
                     // `ADDITIONAL_QUEUED_BLOCK_DELAY`.
                     self.gossip_block_delay_queue.insert(
@@ -471,6 +574,58 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     }
                 }
             }
+            // A blob sidecar has arrived before its slot and should be verified when the slot
+            // arrives.
+            InboundEvent::Msg(EarlyBlobSidecar(early_blob)) => {
+                let blob_id = (early_blob.block_root, early_blob.index);
+
+                // Don't add the same blob sidecar to the queue twice.
+                if self.queued_gossip_blob_ids.contains(&blob_id) {
+                    return;
+                }
+
+                if let Some(duration_till_slot) = slot_clock.duration_to_slot(early_blob.slot) {
+                    // Check to ensure this won't over-fill the queue.
+                    if self.queued_gossip_blob_ids.len() >= MAXIMUM_QUEUED_BLOB_SIDECARS {
+                        if self.early_blob_debounce.elapsed() {
+                            warn!(
+                                log,
+                                "Early blob sidecars queue is full";
+                                "queue_size" => MAXIMUM_QUEUED_BLOB_SIDECARS,
+                                "msg" => "check system clock"
+                            );
+                        }
+                        // Drop the blob sidecar.
+                        metrics::inc_counter_vec(
+                            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL,
+                            &[GOSSIP_BLOB_SIDECARS],
+                        );
+                        return;
+                    }
+
+                    self.queued_gossip_blob_ids.insert(blob_id);
+                    // Queue the blob sidecar until the start of the appropriate slot, plus
+                    // `ADDITIONAL_QUEUED_BLOCK_DELAY`.
+                    self.gossip_blob_delay_queue.insert(
+                        early_blob,
+                        duration_till_slot + ADDITIONAL_QUEUED_BLOCK_DELAY,
+                    );
+                } else if let Some(now) = slot_clock.now() {
+                    // As for blocks, send the blob sidecar for immediate verification if its slot
+                    // has arrived in the meantime.
+                    if early_blob.slot <= now
+                        && self
+                            .ready_work_tx
+                            .try_send(ReadyWork::BlobSidecar(early_blob))
+                            .is_err()
+                    {
+                        error!(
+                            log,
+                            "Failed to send blob sidecar";
+                        );
+                    }
+                }
+            }
             // A rpc block arrived for processing at the same time when a gossip block
             // for the same block hash is being imported. We wait for `QUEUED_RPC_BLOCK_DELAY`
             // and then send the rpc block back for processing assuming the gossip import
@@ -525,16 +680,20 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 }
             }
             InboundEvent::Msg(UnknownBlockAggregate(queued_aggregate)) => {
-                if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
+                if self.attestations_delay_queue.len() >= self.max_queued_attestations {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
                             log,
                             "Aggregate attestation delay queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_ATTESTATIONS,
+                            "queue_size" => self.max_queued_attestations,
                             "msg" => "check system clock"
                         );
                     }
                     // Drop the attestation.
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL,
+                        &[AGGREGATES],
+                    );
                     return;
                 }
 
@@ -552,22 +711,28 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     .push(att_id);
 
                 // Store the attestation and its info.
-                self.queued_aggregates
-                    .insert(self.next_attestation, (queued_aggregate, delay_key));
+                self.queued_aggregates.insert(
+                    self.next_attestation,
+                    (queued_aggregate, delay_key, Instant::now()),
+                );
 
                 self.next_attestation += 1;
             }
             InboundEvent::Msg(UnknownBlockUnaggregate(queued_unaggregate)) => {
-                if self.attestations_delay_queue.len() >= MAXIMUM_QUEUED_ATTESTATIONS {
+                if self.attestations_delay_queue.len() >= self.max_queued_attestations {
                     if self.attestation_delay_debounce.elapsed() {
                         error!(
                             log,
                             "Attestation delay queue is full";
-                            "queue_size" => MAXIMUM_QUEUED_ATTESTATIONS,
+                            "queue_size" => self.max_queued_attestations,
                             "msg" => "check system clock"
                         );
                     }
                     // Drop the attestation.
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL,
+                        &[UNAGGREGATES],
+                    );
                     return;
                 }
 
@@ -585,8 +750,10 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     .push(att_id);
 
                 // Store the attestation and its info.
-                self.queued_unaggregates
-                    .insert(self.next_attestation, (queued_unaggregate, delay_key));
+                self.queued_unaggregates.insert(
+                    self.next_attestation,
+                    (queued_unaggregate, delay_key, Instant::now()),
+                );
 
                 self.next_attestation += 1;
             }
@@ -603,6 +770,10 @@ impl<S: SlotClock> ReprocessQueue<S> {
                         );
                     }
                     // Drop the light client update.
+                    metrics::inc_counter_vec(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_TOTAL,
+                        &[LIGHT_CLIENT_UPDATES],
+                    );
                     return;
                 }
 
@@ -622,7 +793,11 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 // Store the light client update and its info.
                 self.queued_lc_updates.insert(
                     self.next_lc_update,
-                    (queued_light_client_optimistic_update, delay_key),
+                    (
+                        queued_light_client_optimistic_update,
+                        delay_key,
+                        Instant::now(),
+                    ),
                 );
 
                 self.next_lc_update += 1;
@@ -645,13 +820,13 @@ impl<S: SlotClock> ReprocessQueue<S> {
                             QueuedAttestationId::Aggregate(id) => self
                                 .queued_aggregates
                                 .remove(&id)
-                                .map(|(aggregate, delay_key)| {
+                                .map(|(aggregate, delay_key, _queued_at)| {
                                     (ReadyWork::Aggregate(aggregate), delay_key)
                                 }),
                             QueuedAttestationId::Unaggregate(id) => self
                                 .queued_unaggregates
                                 .remove(&id)
-                                .map(|(unaggregate, delay_key)| {
+                                .map(|(unaggregate, delay_key, _queued_at)| {
                                     (ReadyWork::Unaggregate(unaggregate), delay_key)
                                 }),
                         } {
@@ -705,7 +880,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
                             &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_MATCHED_OPTIMISTIC_UPDATES,
                         );
                         if let Some((work, delay_key)) = self.queued_lc_updates.remove(&lc_id).map(
-                            |(light_client_optimistic_update, delay_key)| {
+                            |(light_client_optimistic_update, delay_key, _queued_at)| {
                                 (
                                     ReadyWork::LightClientUpdate(light_client_optimistic_update),
                                     delay_key,
@@ -739,6 +914,10 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     }
                 }
             }
+            InboundEvent::Msg(Snapshot(request)) => {
+                // The requester may have given up waiting, which is fine.
+                let _ = request.send(self.snapshot(slot_clock));
+            }
             InboundEvent::Msg(BackfillSync(queued_backfill_batch)) => {
                 self.queued_backfill_batches
                     .insert(0, queued_backfill_batch);
@@ -751,7 +930,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
             InboundEvent::ReadyGossipBlock(ready_block) => {
                 let block_root = ready_block.beacon_block_root;
 
-                if !self.queued_gossip_block_roots.remove(&block_root) {
+                if self.queued_gossip_block_roots.remove(&block_root).is_none() {
                     // Log an error to alert that we've made a bad assumption about how this
                     // program works, but still process the block anyway.
                     error!(
@@ -772,6 +951,30 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     );
                 }
             }
+            // A blob sidecar that was queued until its slot is now ready to be verified.
+            InboundEvent::ReadyGossipBlobSidecar(ready_blob) => {
+                let blob_id = (ready_blob.block_root, ready_blob.index);
+
+                if !self.queued_gossip_blob_ids.remove(&blob_id) {
+                    error!(
+                        log,
+                        "Unknown blob sidecar in delay queue";
+                        "block_root" => ?ready_blob.block_root,
+                        "index" => ready_blob.index
+                    );
+                }
+
+                if self
+                    .ready_work_tx
+                    .try_send(ReadyWork::BlobSidecar(ready_blob))
+                    .is_err()
+                {
+                    error!(
+                        log,
+                        "Failed to pop queued blob sidecar";
+                    );
+                }
+            }
             InboundEvent::DelayQueueError(e, queue_name) => {
                 crit!(
                     log,
@@ -786,20 +989,18 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 );
 
                 if let Some((root, work)) = match queued_id {
-                    QueuedAttestationId::Aggregate(id) => {
-                        self.queued_aggregates
-                            .remove(&id)
-                            .map(|(aggregate, _delay_key)| {
-                                (
-                                    *aggregate.beacon_block_root(),
-                                    ReadyWork::Aggregate(aggregate),
-                                )
-                            })
-                    }
+                    QueuedAttestationId::Aggregate(id) => self.queued_aggregates.remove(&id).map(
+                        |(aggregate, _delay_key, _queued_at)| {
+                            (
+                                *aggregate.beacon_block_root(),
+                                ReadyWork::Aggregate(aggregate),
+                            )
+                        },
+                    ),
                     QueuedAttestationId::Unaggregate(id) => self
                         .queued_unaggregates
                         .remove(&id)
-                        .map(|(unaggregate, _delay_key)| {
+                        .map(|(unaggregate, _delay_key, _queued_at)| {
                             (
                                 *unaggregate.beacon_block_root(),
                                 ReadyWork::Unaggregate(unaggregate),
@@ -828,7 +1029,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 );
 
                 if let Some((parent_root, work)) = self.queued_lc_updates.remove(&queued_id).map(
-                    |(queued_lc_update, _delay_key)| {
+                    |(queued_lc_update, _delay_key, _queued_at)| {
                         (
                             queued_lc_update.parent_root,
                             ReadyWork::LightClientUpdate(queued_lc_update),
@@ -898,6 +1099,11 @@ impl<S: SlotClock> ReprocessQueue<S> {
             &[GOSSIP_BLOCKS],
             self.gossip_block_delay_queue.len() as i64,
         );
+        metrics::set_gauge_vec(
+            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
+            &[GOSSIP_BLOB_SIDECARS],
+            self.gossip_blob_delay_queue.len() as i64,
+        );
         metrics::set_gauge_vec(
             &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
             &[RPC_BLOCKS],
@@ -908,6 +1114,16 @@ impl<S: SlotClock> ReprocessQueue<S> {
             &[ATTESTATIONS],
             self.attestations_delay_queue.len() as i64,
         );
+        metrics::set_gauge_vec(
+            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
+            &[UNAGGREGATES],
+            self.queued_unaggregates.len() as i64,
+        );
+        metrics::set_gauge_vec(
+            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
+            &[AGGREGATES],
+            self.queued_aggregates.len() as i64,
+        );
         metrics::set_gauge_vec(
             &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
             &[LIGHT_CLIENT_UPDATES],
@@ -915,6 +1131,59 @@ impl<S: SlotClock> ReprocessQueue<S> {
         );
     }
 
+    fn snapshot(&self, slot_clock: &S) -> ReprocessQueueSnapshot {
+        let now = Instant::now();
+
+        let mut early_block_releases = self
+            .queued_gossip_block_roots
+            .iter()
+            .map(|(block_root, (slot, _))| EarlyBlockSnapshot {
+                block_root: *block_root,
+                slot: *slot,
+                release_time_ms: slot_clock
+                    .start_of(*slot)
+                    .map(|start| (start + ADDITIONAL_QUEUED_BLOCK_DELAY).as_millis() as u64),
+            })
+            .collect::<Vec<_>>();
+        early_block_releases.sort_by_key(|early_block| early_block.slot);
+
+        ReprocessQueueSnapshot {
+            early_blocks: category_snapshot(
+                self.queued_gossip_block_roots.len(),
+                MAXIMUM_QUEUED_BLOCKS,
+                self.queued_gossip_block_roots
+                    .values()
+                    .map(|(_, queued_at)| *queued_at),
+                now,
+            ),
+            unknown_block_attestations: category_snapshot(
+                self.queued_unaggregates.len(),
+                self.max_queued_attestations,
+                self.queued_unaggregates
+                    .values()
+                    .map(|(_, _, queued_at)| *queued_at),
+                now,
+            ),
+            unknown_block_aggregates: category_snapshot(
+                self.queued_aggregates.len(),
+                self.max_queued_attestations,
+                self.queued_aggregates
+                    .values()
+                    .map(|(_, _, queued_at)| *queued_at),
+                now,
+            ),
+            light_client_updates: category_snapshot(
+                self.queued_lc_updates.len(),
+                MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES,
+                self.queued_lc_updates
+                    .values()
+                    .map(|(_, _, queued_at)| *queued_at),
+                now,
+            ),
+            early_block_releases,
+        }
+    }
+
     fn recompute_next_backfill_batch_event(&mut self) {
         // only recompute the `next_backfill_batch_event` if there are backfill batches in the queue
         if !self.queued_backfill_batches.is_empty() {
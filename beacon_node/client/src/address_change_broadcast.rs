@@ -95,9 +95,7 @@ pub async fn broadcast_address_changes<T: BeaconChainTypes>(
             let validator_index = address_change.message.validator_index;
 
             let pubsub_message = PubsubMessage::BlsToExecutionChange(Box::new(address_change));
-            let message = NetworkMessage::Publish {
-                messages: vec![pubsub_message],
-            };
+            let message = NetworkMessage::publish(vec![pubsub_message]);
             // It seems highly unlikely that this unbounded send will fail, but
             // we handle the result nonetheless.
             if let Err(e) = network_send.send(message) {
@@ -263,7 +261,7 @@ mod tests {
             broadcast_address_changes_at_capella(&chain, sender, &logging::test_logger()).await;
 
             let mut broadcasted_changes = vec![];
-            while let Some(NetworkMessage::Publish { mut messages }) = receiver.recv().await {
+            while let Some(NetworkMessage::Publish { mut messages, .. }) = receiver.recv().await {
                 match messages.pop().unwrap() {
                     PubsubMessage::BlsToExecutionChange(change) => broadcasted_changes.push(change),
                     _ => panic!("unexpected message"),
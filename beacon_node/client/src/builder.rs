@@ -1,7 +1,9 @@
 use crate::address_change_broadcast::broadcast_address_changes_at_capella;
 use crate::config::{ClientGenesis, Config as ClientConfig};
+use crate::genesis_sync::check_genesis_sync;
 use crate::notifier::spawn_notifier;
 use crate::Client;
+use beacon_chain::blob_pruning_service::start_blob_pruning_service;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
@@ -14,6 +16,7 @@ use beacon_chain::{
     store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, MigratorConfig, ServerSentEventHandler,
 };
+use beacon_processor::work_reprocessing_queue::ReprocessQueueSnapshotSend;
 use beacon_processor::BeaconProcessorConfig;
 use beacon_processor::{BeaconProcessor, BeaconProcessorChannels};
 use environment::RuntimeContext;
@@ -29,21 +32,26 @@ use monitoring_api::{MonitoringHttpClient, ProcessType};
 use network::{NetworkConfig, NetworkSenders, NetworkService};
 use slasher::Slasher;
 use slasher_service::SlasherService;
-use slog::{debug, info, warn, Logger};
+use slog::{debug, error, info, warn, Logger};
+use ssz::Decode;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use timer::spawn_timer;
 use tokio::sync::oneshot;
 use types::{
-    test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
+    test_utils::generate_deterministic_keypairs, BeaconState, BlobSidecarList, ChainSpec, EthSpec,
     ExecutionBlockHash, Hash256, SignedBeaconBlock,
 };
 
 /// Interval between polling the eth1 node for genesis information.
 pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
 
+/// File in the data directory holding a downloaded genesis state, so that it is not downloaded
+/// again if the node restarts before its database is initialized.
+pub const GENESIS_STATE_CACHE_FILE: &str = "genesis_state.ssz";
+
 /// Builds a `Client` instance.
 ///
 /// ## Notes
@@ -76,6 +84,8 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
     beacon_processor_config: Option<BeaconProcessorConfig>,
     beacon_processor_channels: Option<BeaconProcessorChannels<T::EthSpec>>,
+    /// Whether to build the caches required for duties once the beacon chain has been built.
+    preheat_caches: bool,
     eth_spec_instance: T::EthSpec,
 }
 
@@ -111,6 +121,7 @@ where
             eth_spec_instance,
             beacon_processor_config: None,
             beacon_processor_channels: None,
+            preheat_caches: false,
         }
     }
 
@@ -236,6 +247,14 @@ where
             client_genesis
         };
 
+        // The caches of a checkpoint state are built lazily by the first duties requests, which
+        // can take long enough for duties to be missed.
+        self.preheat_caches = config.preheat_caches
+            && matches!(
+                client_genesis,
+                ClientGenesis::WeakSubjSszBytes { .. } | ClientGenesis::CheckpointSyncUrl { .. }
+            );
+
         let (beacon_chain_builder, eth1_service_option) = match client_genesis {
             ClientGenesis::Interop {
                 validator_count,
@@ -259,11 +278,31 @@ where
 
                 let genesis_state = genesis_state(&runtime_context, &config, log).await?;
 
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|e| format!("Unable to read system time: {:?}", e))?;
+                if let Some(slow_sync) = check_genesis_sync::<TEthSpec>(
+                    &spec,
+                    genesis_state.genesis_time(),
+                    now,
+                    config.allow_insecure_genesis_sync,
+                    &config.checkpoint_sync_url_suggestions,
+                )? {
+                    warn!(
+                        context.log(),
+                        "Syncing from genesis will be slow";
+                        "msg" => slow_sync.suggestion(),
+                        "estimated_sync_time" => slow_sync.estimated_sync_time_pretty(),
+                        "network_epoch" => slow_sync.network_epoch,
+                    );
+                }
+
                 builder.genesis_state(genesis_state).map(|v| (v, None))?
             }
             ClientGenesis::WeakSubjSszBytes {
                 anchor_state_bytes,
                 anchor_block_bytes,
+                anchor_blobs_bytes,
             } => {
                 info!(context.log(), "Starting checkpoint sync");
                 if config.chain.genesis_backfill {
@@ -277,10 +316,51 @@ where
                     .map_err(|e| format!("Unable to parse weak subj state SSZ: {:?}", e))?;
                 let anchor_block = SignedBeaconBlock::from_ssz_bytes(&anchor_block_bytes, &spec)
                     .map_err(|e| format!("Unable to parse weak subj block SSZ: {:?}", e))?;
+                let anchor_blobs = anchor_blobs_bytes
+                    .map(|bytes| {
+                        BlobSidecarList::<TEthSpec>::from_ssz_bytes(&bytes)
+                            .map_err(|e| format!("Unable to parse weak subj blobs SSZ: {:?}", e))
+                    })
+                    .transpose()?;
+
+                // The state must either be unadvanced (at the block's slot) or advanced to an
+                // epoch boundary after the block.
+                if anchor_block.slot() > anchor_state.slot() {
+                    return Err(format!(
+                        "Checkpoint block slot {} is later than checkpoint state slot {}",
+                        anchor_block.slot(),
+                        anchor_state.slot()
+                    ));
+                }
+                if anchor_state.slot() != anchor_block.slot()
+                    && anchor_state.slot() % TEthSpec::slots_per_epoch() != 0
+                {
+                    return Err(format!(
+                        "Checkpoint state at slot {} must either match the block slot {} or be \
+                         aligned to an epoch boundary",
+                        anchor_state.slot(),
+                        anchor_block.slot()
+                    ));
+                }
+
+                info!(
+                    context.log(),
+                    "Loaded checkpoint block and state";
+                    "block_slot" => anchor_block.slot(),
+                    "state_slot" => anchor_state.slot(),
+                    "block_root" => ?anchor_block.canonical_root(),
+                    "blobs" => anchor_blobs.as_ref().map_or(0, |blobs| blobs.len()),
+                );
+
                 let genesis_state = genesis_state(&runtime_context, &config, log).await?;
 
                 builder
-                    .weak_subjectivity_state(anchor_state, anchor_block, genesis_state)
+                    .weak_subjectivity_state(
+                        anchor_state,
+                        anchor_block,
+                        anchor_blobs,
+                        genesis_state,
+                    )
                     .map(|v| (v, None))?
             }
             ClientGenesis::CheckpointSyncUrl { url } => {
@@ -378,6 +458,20 @@ where
 
                 debug!(context.log(), "Downloaded finalized block");
 
+                let blobs = if block.num_expected_blobs() > 0 {
+                    debug!(context.log(), "Downloading finalized blobs");
+                    let blobs = remote
+                        .get_blobs::<TEthSpec>(BlockId::Root(block.canonical_root()))
+                        .await
+                        .map_err(|e| format!("Error fetching finalized blobs from remote: {e:?}"))?
+                        .ok_or("Finalized blobs missing from remote, it returned 404")?
+                        .data;
+                    debug!(context.log(), "Downloaded finalized blobs");
+                    Some(blobs)
+                } else {
+                    None
+                };
+
                 let genesis_state = genesis_state(&runtime_context, &config, log).await?;
 
                 info!(
@@ -413,7 +507,7 @@ where
                     });
 
                 builder
-                    .weak_subjectivity_state(state, block, genesis_state)
+                    .weak_subjectivity_state(state, block, blobs, genesis_state)
                     .map(|v| (v, service))?
             }
             ClientGenesis::DepositContract => {
@@ -450,9 +544,12 @@ where
                         network_senders: None,
                         network_globals: None,
                         beacon_processor_send: None,
+                        beacon_processor_snapshot_send: None,
+                        reprocess_queue_snapshot_send: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
                         log: context.log().clone(),
                         sse_logging_components: runtime_context.sse_logging_components.clone(),
+                        log_filters: runtime_context.log_filters.clone(),
                     });
 
                     // Discard the error from the oneshot.
@@ -694,13 +791,31 @@ where
                 network_globals: self.network_globals.clone(),
                 eth1_service: self.eth1_service.clone(),
                 beacon_processor_send: Some(beacon_processor_channels.beacon_processor_tx.clone()),
+                beacon_processor_snapshot_send: Some(beacon_processor_channels.snapshot_tx.clone()),
+                reprocess_queue_snapshot_send: Some(ReprocessQueueSnapshotSend(
+                    beacon_processor_channels.work_reprocessing_tx.clone(),
+                )),
                 sse_logging_components: runtime_context.sse_logging_components.clone(),
+                log_filters: runtime_context.log_filters.clone(),
                 log: log.clone(),
             });
 
+            // Stop accepting requests once the graceful shutdown starts, allowing in-flight
+            // requests to complete before the exit signal fires.
             let exit = runtime_context.executor.exit();
+            let draining = runtime_context
+                .executor
+                .graceful_shutdown()
+                .clone()
+                .draining();
+            let shutdown = async move {
+                tokio::select! {
+                    () = exit => {},
+                    () = draining => {},
+                }
+            };
 
-            let (listen_addr, server) = http_api::serve(ctx, exit)
+            let (listen_addr, server) = http_api::serve(ctx, shutdown)
                 .map_err(|e| format!("Unable to start HTTP API server: {:?}", e))?;
 
             let http_log = runtime_context.log().clone();
@@ -749,6 +864,28 @@ where
             self.start_slasher_service()?;
         }
 
+        // Persist the beacon chain once in-flight operations have drained on shutdown, before the
+        // network is torn down. A weak reference avoids keeping the chain alive until then.
+        if let Some(beacon_chain) = self.beacon_chain.as_ref() {
+            let chain = Arc::downgrade(beacon_chain);
+            let hook_log = log.clone();
+            runtime_context.executor.graceful_shutdown().register_hook(
+                "persist_beacon_chain",
+                move || {
+                    if let Some(chain) = chain.upgrade() {
+                        match chain.persist_to_disk() {
+                            Ok(()) => info!(hook_log, "Saved beacon chain to disk before shutdown"),
+                            Err(e) => error!(
+                                hook_log,
+                                "Failed to persist beacon chain on shutdown";
+                                "error" => ?e
+                            ),
+                        }
+                    }
+                },
+            );
+        }
+
         if let Some(beacon_chain) = self.beacon_chain.as_ref() {
             if let Some(network_globals) = &self.network_globals {
                 let beacon_processor_context = runtime_context.service_context("bproc".into());
@@ -763,6 +900,7 @@ where
                     beacon_processor_channels.beacon_processor_rx,
                     beacon_processor_channels.work_reprocessing_tx,
                     beacon_processor_channels.work_reprocessing_rx,
+                    beacon_processor_channels.snapshot_rx,
                     None,
                     beacon_chain.slot_clock.clone(),
                     beacon_chain.spec.maximum_gossip_clock_disparity(),
@@ -849,6 +987,7 @@ where
                 runtime_context.executor.clone(),
                 beacon_chain.clone(),
             );
+            start_blob_pruning_service(runtime_context.executor.clone(), beacon_chain.clone());
         }
 
         Ok(Client {
@@ -889,6 +1028,25 @@ where
             .build()
             .map_err(|e| format!("Failed to build beacon chain: {}", e))?;
 
+        if self.preheat_caches {
+            let log = context.log();
+            info!(log, "Pre-heating caches for checkpoint state");
+            let timer = Instant::now();
+            match chain.preheat_caches() {
+                Ok(()) => info!(
+                    log,
+                    "Caches pre-heated";
+                    "duration_ms" => timer.elapsed().as_millis(),
+                ),
+                // Failing to build the caches is not fatal, they will be built on demand.
+                Err(e) => warn!(
+                    log,
+                    "Failed to pre-heat caches";
+                    "error" => ?e,
+                ),
+            }
+        }
+
         self.beacon_chain = Some(Arc::new(chain));
         self.beacon_chain_builder = None;
 
@@ -954,6 +1112,17 @@ where
             context.log().clone(),
         )
         .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+        if let Some(migration) = store
+            .load_restore_point_migration()
+            .map_err(|e| format!("Unable to read restore point migration: {:?}", e))?
+        {
+            return Err(format!(
+                "Database has an unfinished restore point migration to {} slots per restore \
+                 point, complete it with `lighthouse db migrate-restore-points`",
+                migration.new_slots_per_restore_point
+            ));
+        }
         self.store = Some(store);
         Ok(self)
     }
@@ -1105,10 +1274,12 @@ async fn genesis_state<T: EthSpec>(
         .eth2_network_config
         .as_ref()
         .ok_or("An eth2_network_config is required to obtain the genesis state")?;
+    let cache_path = config.get_data_dir().join(GENESIS_STATE_CACHE_FILE);
     eth2_network_config
         .genesis_state::<T>(
-            config.genesis_state_url.as_deref(),
+            &config.genesis_state_urls,
             config.genesis_state_url_timeout,
+            Some(&cache_path),
             log,
         )
         .await?
@@ -32,6 +32,7 @@ pub enum ClientGenesis {
     WeakSubjSszBytes {
         anchor_state_bytes: Vec<u8>,
         anchor_block_bytes: Vec<u8>,
+        anchor_blobs_bytes: Option<Vec<u8>>,
     },
     CheckpointSyncUrl {
         url: SensitiveUrl,
@@ -81,8 +82,18 @@ pub struct Config {
     pub slasher: Option<slasher::Config>,
     pub logger_config: LoggerConfig,
     pub beacon_processor: BeaconProcessorConfig,
-    pub genesis_state_url: Option<String>,
+    /// Beacon-API servers to download the genesis state from, tried in order.
+    pub genesis_state_urls: Vec<String>,
     pub genesis_state_url_timeout: Duration,
+    /// Permit syncing from genesis on networks which otherwise refuse to, e.g. mainnet.
+    pub allow_insecure_genesis_sync: bool,
+    /// Checkpoint sync endpoints from the network config, suggested when syncing from genesis.
+    pub checkpoint_sync_url_suggestions: Vec<String>,
+    /// The maximum time to wait for in-flight block publications and imports on shutdown.
+    pub shutdown_grace_period: Duration,
+    /// Build the caches required for validator duties after a checkpoint sync, before the HTTP
+    /// API is started.
+    pub preheat_caches: bool,
 }
 
 impl Default for Config {
@@ -112,9 +123,13 @@ impl Default for Config {
             validator_monitor_individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
             logger_config: LoggerConfig::default(),
             beacon_processor: <_>::default(),
-            genesis_state_url: <_>::default(),
+            genesis_state_urls: vec![],
             // This default value should always be overwritten by the CLI default value.
             genesis_state_url_timeout: Duration::from_secs(60),
+            allow_insecure_genesis_sync: false,
+            checkpoint_sync_url_suggestions: vec![],
+            shutdown_grace_period: task_executor::DEFAULT_GRACE_PERIOD,
+            preheat_caches: true,
         }
     }
 }
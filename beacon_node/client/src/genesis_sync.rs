@@ -0,0 +1,173 @@
+//! Checks performed before a fresh database starts syncing from the genesis state.
+//!
+//! Syncing a long-lived network from genesis takes a very long time, so users are warned (and on
+//! mainnet, refused unless they opt in) when checkpoint sync would have been a better choice.
+
+use crate::notifier::seconds_pretty;
+use std::time::Duration;
+use types::{ChainSpec, Epoch, EthSpec, Slot};
+
+/// Genesis sync is considered slow once the network is more than this many epochs past genesis.
+pub const GENESIS_SYNC_WARNING_EPOCHS: u64 = 256;
+
+/// A rough estimate of the number of slots per second imported during a genesis sync, used to
+/// estimate how long the sync will take.
+const ESTIMATED_GENESIS_SYNC_SLOTS_PER_SECOND: u64 = 5;
+
+/// Networks which refuse to sync from genesis without `--allow-insecure-genesis-sync`.
+const GENESIS_SYNC_OPT_IN_NETWORKS: &[&str] = &["mainnet"];
+
+/// Describes a genesis sync that is expected to be slow.
+#[derive(Debug, PartialEq)]
+pub struct SlowGenesisSync {
+    /// The current epoch of the network, according to the system clock.
+    pub network_epoch: Epoch,
+    /// The estimated time it will take to sync to the head of the network.
+    pub estimated_sync_time: Duration,
+    /// The flag which could be used to checkpoint sync instead, if any endpoints are known.
+    pub suggested_flag: Option<String>,
+}
+
+impl SlowGenesisSync {
+    pub fn estimated_sync_time_pretty(&self) -> String {
+        seconds_pretty(self.estimated_sync_time.as_secs_f64())
+    }
+
+    pub fn suggestion(&self) -> String {
+        self.suggested_flag
+            .as_ref()
+            .map(|flag| format!("restart with {} to checkpoint sync", flag))
+            .unwrap_or_else(|| "consider checkpoint sync with --checkpoint-sync-url".to_string())
+    }
+}
+
+/// Determine whether a genesis sync of a network with the given `genesis_time` is expected to be
+/// slow at time `now` (since the UNIX epoch).
+///
+/// Returns an error if the sync is slow, the network requires an explicit opt-in to genesis sync
+/// and `allow_insecure_genesis_sync` is `false`.
+pub fn check_genesis_sync<E: EthSpec>(
+    spec: &ChainSpec,
+    genesis_time: u64,
+    now: Duration,
+    allow_insecure_genesis_sync: bool,
+    checkpoint_sync_url_suggestions: &[String],
+) -> Result<Option<SlowGenesisSync>, String> {
+    let network_slot =
+        Slot::new(now.as_secs().saturating_sub(genesis_time) / spec.seconds_per_slot);
+    let network_epoch = network_slot.epoch(E::slots_per_epoch());
+
+    if network_epoch <= GENESIS_SYNC_WARNING_EPOCHS {
+        return Ok(None);
+    }
+
+    let slow_sync = SlowGenesisSync {
+        network_epoch,
+        estimated_sync_time: Duration::from_secs(
+            network_slot.as_u64() / ESTIMATED_GENESIS_SYNC_SLOTS_PER_SECOND,
+        ),
+        suggested_flag: checkpoint_sync_url_suggestions
+            .first()
+            .map(|url| format!("--checkpoint-sync-url {}", url)),
+    };
+
+    let requires_opt_in = spec
+        .config_name
+        .as_deref()
+        .map_or(false, |name| GENESIS_SYNC_OPT_IN_NETWORKS.contains(&name));
+
+    if requires_opt_in && !allow_insecure_genesis_sync {
+        return Err(format!(
+            "Refusing to sync from genesis, which is estimated to take {}. Please {}, or use \
+             --allow-insecure-genesis-sync to sync from genesis anyway",
+            slow_sync.estimated_sync_time_pretty(),
+            slow_sync.suggestion(),
+        ));
+    }
+
+    Ok(Some(slow_sync))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    const GENESIS_TIME: u64 = 1_606_824_023;
+
+    fn testnet_spec() -> ChainSpec {
+        let mut spec = ChainSpec::mainnet();
+        spec.config_name = Some("holesky".to_string());
+        spec
+    }
+
+    /// The time at which the network is `epochs` epochs past genesis.
+    fn time_at_epoch(spec: &ChainSpec, epochs: u64) -> Duration {
+        Duration::from_secs(GENESIS_TIME + epochs * E::slots_per_epoch() * spec.seconds_per_slot)
+    }
+
+    fn suggestions() -> Vec<String> {
+        vec!["https://checkpoint.example.com".to_string()]
+    }
+
+    #[test]
+    fn recent_genesis_is_allowed() {
+        for spec in [ChainSpec::mainnet(), testnet_spec()] {
+            let now = time_at_epoch(&spec, GENESIS_SYNC_WARNING_EPOCHS);
+            assert_eq!(
+                check_genesis_sync::<E>(&spec, GENESIS_TIME, now, false, &[]),
+                Ok(None)
+            );
+        }
+    }
+
+    #[test]
+    fn mainnet_genesis_sync_is_refused() {
+        let spec = ChainSpec::mainnet();
+        let now = time_at_epoch(&spec, GENESIS_SYNC_WARNING_EPOCHS + 1);
+
+        let error =
+            check_genesis_sync::<E>(&spec, GENESIS_TIME, now, false, &suggestions()).unwrap_err();
+        assert!(error.contains("--checkpoint-sync-url https://checkpoint.example.com"));
+        assert!(error.contains("--allow-insecure-genesis-sync"));
+    }
+
+    #[test]
+    fn mainnet_genesis_sync_is_allowed_with_opt_in() {
+        let spec = ChainSpec::mainnet();
+        let now = time_at_epoch(&spec, GENESIS_SYNC_WARNING_EPOCHS + 1);
+
+        let slow_sync = check_genesis_sync::<E>(&spec, GENESIS_TIME, now, true, &suggestions())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            slow_sync.network_epoch,
+            Epoch::new(GENESIS_SYNC_WARNING_EPOCHS + 1)
+        );
+        assert_eq!(
+            slow_sync.suggested_flag.as_deref(),
+            Some("--checkpoint-sync-url https://checkpoint.example.com")
+        );
+    }
+
+    #[test]
+    fn testnet_genesis_sync_is_allowed_by_default() {
+        let spec = testnet_spec();
+        let epochs = 100_000;
+        let now = time_at_epoch(&spec, epochs);
+
+        let slow_sync = check_genesis_sync::<E>(&spec, GENESIS_TIME, now, false, &[])
+            .unwrap()
+            .unwrap();
+        assert_eq!(slow_sync.network_epoch, Epoch::new(epochs));
+        assert_eq!(
+            slow_sync.estimated_sync_time,
+            Duration::from_secs(
+                epochs * E::slots_per_epoch() / ESTIMATED_GENESIS_SYNC_SLOTS_PER_SECOND
+            )
+        );
+        assert_eq!(slow_sync.suggested_flag, None);
+    }
+}
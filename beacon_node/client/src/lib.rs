@@ -2,6 +2,7 @@ extern crate slog;
 
 mod address_change_broadcast;
 pub mod config;
+pub mod genesis_sync;
 mod metrics;
 mod notifier;
 
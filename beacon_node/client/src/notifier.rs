@@ -645,7 +645,7 @@ fn slot_distance_pretty(slot_span: Slot, slot_duration: Duration) -> String {
 
 /// Returns a nicely formatted string describing the `slot_span` in terms of weeks, days, hours
 /// and/or minutes.
-fn seconds_pretty(secs: f64) -> String {
+pub(crate) fn seconds_pretty(secs: f64) -> String {
     if secs <= 0.0 {
         return "--".into();
     }
@@ -1,8 +1,12 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
 
 use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use parking_lot::RwLock;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use slog::{info, warn, Logger};
 use zeroize::Zeroize;
 
 /// Default algorithm used for JWT token signing.
@@ -68,54 +72,181 @@ pub fn strip_prefix(s: &str) -> &str {
     }
 }
 
-/// Contains the JWT secret and claims parameters.
-pub struct Auth {
+/// Read a hex encoded JWT secret from the file at `path`.
+pub fn read_secret_file(path: &Path) -> Result<JwtKey, Error> {
+    let s = std::fs::read_to_string(path).map_err(|e| {
+        Error::InvalidKey(format!(
+            "Failed to read JWT secret file {:?}, error: {:?}",
+            path, e
+        ))
+    })?;
+    let secret_bytes = hex::decode(strip_prefix(s.trim_end()))
+        .map_err(|e| Error::InvalidKey(format!("Invalid hex string: {:?}", e)))?;
+    JwtKey::from_slice(&secret_bytes).map_err(Error::InvalidKey)
+}
+
+/// Identifies which of the configured secrets a token is signed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Secret {
+    Primary,
+    Secondary,
+}
+
+struct PrimarySecret {
     key: EncodingKey,
+    /// The modification time of the secret file when the key was read from it.
+    modified: Option<SystemTime>,
+}
+
+/// Contains the JWT secrets and claims parameters.
+///
+/// If the primary secret was read from a file it is re-read whenever the file is modified, so
+/// that the secret can be rotated without a restart. An optional secondary secret can be tried
+/// if the primary is rejected, e.g. while the execution node is being switched to a new secret.
+pub struct Auth {
+    primary: RwLock<PrimarySecret>,
+    primary_path: Option<PathBuf>,
+    secondary: Option<EncodingKey>,
+    use_secondary: AtomicBool,
     id: Option<String>,
     clv: Option<String>,
+    log: Logger,
 }
 
 impl Auth {
     pub fn new(secret: JwtKey, id: Option<String>, clv: Option<String>) -> Self {
         Self {
-            key: EncodingKey::from_secret(secret.as_bytes()),
+            primary: RwLock::new(PrimarySecret {
+                key: EncodingKey::from_secret(secret.as_bytes()),
+                modified: None,
+            }),
+            primary_path: None,
+            secondary: None,
+            use_secondary: AtomicBool::new(false),
             id,
             clv,
+            log: Logger::root(slog::Discard, slog::o!()),
         }
     }
 
     /// Create a new `Auth` struct given the path to the file containing the hex
     /// encoded jwt key.
+    ///
+    /// The secret is reloaded by `Self::reload_if_changed` when the file is modified.
     pub fn new_with_path(
         jwt_path: PathBuf,
         id: Option<String>,
         clv: Option<String>,
     ) -> Result<Self, Error> {
-        std::fs::read_to_string(&jwt_path)
-            .map_err(|e| {
-                Error::InvalidKey(format!(
-                    "Failed to read JWT secret file {:?}, error: {:?}",
-                    jwt_path, e
-                ))
-            })
-            .and_then(|ref s| {
-                let secret_bytes = hex::decode(strip_prefix(s.trim_end()))
-                    .map_err(|e| Error::InvalidKey(format!("Invalid hex string: {:?}", e)))?;
-                let secret = JwtKey::from_slice(&secret_bytes).map_err(Error::InvalidKey)?;
-                Ok(Self::new(secret, id, clv))
-            })
-    }
-
-    /// Generate a JWT token with `claims.iat` set to current time.
+        let modified = modified_time(&jwt_path);
+        let secret = read_secret_file(&jwt_path)?;
+        let mut auth = Self::new(secret, id, clv);
+        auth.primary.get_mut().modified = modified;
+        auth.primary_path = Some(jwt_path);
+        Ok(auth)
+    }
+
+    /// Sets a secondary secret to try when the primary secret is rejected.
+    pub fn with_secondary(mut self, secret: JwtKey) -> Self {
+        self.secondary = Some(EncodingKey::from_secret(secret.as_bytes()));
+        self
+    }
+
+    /// Sets the logger used to report secret reloads and fallbacks.
+    pub fn with_logger(mut self, log: Logger) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// Re-read the primary secret if its file has been modified since it was last read.
+    ///
+    /// If the file can't be read the current secret remains in use. A successful reload makes the
+    /// primary secret the preferred one again.
+    pub fn reload_if_changed(&self) {
+        let Some(path) = &self.primary_path else {
+            return;
+        };
+        let modified = modified_time(path);
+        if modified.is_none() || self.primary.read().modified == modified {
+            return;
+        }
+
+        let mut primary = self.primary.write();
+        if primary.modified == modified {
+            // Reloaded by another request.
+            return;
+        }
+        primary.modified = modified;
+        match read_secret_file(path) {
+            Ok(secret) => {
+                primary.key = EncodingKey::from_secret(secret.as_bytes());
+                self.use_secondary.store(false, Ordering::Relaxed);
+                info!(self.log, "Reloaded JWT secret"; "path" => ?path);
+            }
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Failed to reload JWT secret";
+                    "info" => "the previous secret will continue to be used",
+                    "path" => ?path,
+                    "error" => ?e,
+                );
+            }
+        }
+    }
+
+    /// Returns the secret that tokens are currently signed with by default.
+    pub fn preferred_secret(&self) -> Secret {
+        if self.use_secondary.load(Ordering::Relaxed) {
+            Secret::Secondary
+        } else {
+            Secret::Primary
+        }
+    }
+
+    /// Returns the secret to retry with when a token signed with `rejected` is refused, if any.
+    pub fn fallback_secret(&self, rejected: Secret) -> Option<Secret> {
+        self.secondary.as_ref().map(|_| match rejected {
+            Secret::Primary => Secret::Secondary,
+            Secret::Secondary => Secret::Primary,
+        })
+    }
+
+    /// Records that `secret` was accepted after the other secret was rejected, so that it is
+    /// tried first from now on.
+    pub fn set_preferred_secret(&self, secret: Secret) {
+        let use_secondary = secret == Secret::Secondary;
+        if self.use_secondary.swap(use_secondary, Ordering::Relaxed) != use_secondary {
+            info!(
+                self.log,
+                "Authenticated with fallback JWT secret";
+                "secret" => ?secret,
+                "primary_path" => ?self.primary_path,
+            );
+        }
+    }
+
+    /// Generate a JWT token signed with the preferred secret, with `claims.iat` set to current
+    /// time.
     pub fn generate_token(&self) -> Result<String, Error> {
+        self.generate_token_with_secret(self.preferred_secret())
+    }
+
+    /// Generate a JWT token signed with `secret`, with `claims.iat` set to current time.
+    ///
+    /// The primary secret is used if no secondary secret is configured.
+    pub fn generate_token_with_secret(&self, secret: Secret) -> Result<String, Error> {
         let claims = self.generate_claims_at_timestamp();
-        self.generate_token_with_claims(&claims)
+        self.generate_token_with_claims(secret, &claims)
     }
 
     /// Generate a JWT token with the given claims.
-    fn generate_token_with_claims(&self, claims: &Claims) -> Result<String, Error> {
+    fn generate_token_with_claims(&self, secret: Secret, claims: &Claims) -> Result<String, Error> {
         let header = Header::new(DEFAULT_ALGORITHM);
-        Ok(encode(&header, claims, &self.key)?)
+        match (secret, &self.secondary) {
+            (Secret::Secondary, Some(key)) => Ok(encode(&header, claims, key)?),
+            _ => Ok(encode(&header, claims, &self.primary.read().key)?),
+        }
     }
 
     /// Generate a `Claims` struct with `iat` set to current time
@@ -145,6 +276,12 @@ impl Auth {
     }
 }
 
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
 /// Claims struct as defined in https://github.com/ethereum/execution-apis/blob/main/src/engine/authentication.md#jwt-claims
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct Claims {
@@ -169,7 +306,9 @@ mod tests {
             Some("Lighthouse".into()),
         );
         let claims = auth.generate_claims_at_timestamp();
-        let token = auth.generate_token_with_claims(&claims).unwrap();
+        let token = auth
+            .generate_token_with_claims(Secret::Primary, &claims)
+            .unwrap();
 
         assert_eq!(
             Auth::validate_token(&token, &JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap())
@@ -178,4 +317,58 @@ mod tests {
             claims
         );
     }
+
+    #[test]
+    fn secret_is_reloaded_when_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        let (old_secret, new_secret) = (JwtKey::random(), JwtKey::random());
+        std::fs::write(&path, old_secret.hex_string()).unwrap();
+
+        let auth = Auth::new_with_path(path.clone(), None, None).unwrap();
+        let token = auth.generate_token().unwrap();
+        assert!(Auth::validate_token(&token, &old_secret).is_ok());
+
+        // Unchanged file, nothing to reload.
+        auth.reload_if_changed();
+        let token = auth.generate_token().unwrap();
+        assert!(Auth::validate_token(&token, &old_secret).is_ok());
+
+        // Ensure the modification time changes on filesystems with coarse timestamps.
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        std::fs::write(&path, format!("0x{}\n", new_secret.hex_string())).unwrap();
+        auth.reload_if_changed();
+        let token = auth.generate_token().unwrap();
+        assert!(Auth::validate_token(&token, &new_secret).is_ok());
+
+        // An invalid secret is ignored.
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        std::fs::write(&path, "not hex").unwrap();
+        auth.reload_if_changed();
+        let token = auth.generate_token().unwrap();
+        assert!(Auth::validate_token(&token, &new_secret).is_ok());
+    }
+
+    #[test]
+    fn secondary_secret_is_used_after_fallback() {
+        let (primary, secondary) = (JwtKey::random(), JwtKey::random());
+        let auth = Auth::new(primary.clone(), None, None);
+        assert_eq!(auth.fallback_secret(Secret::Primary), None);
+
+        let auth = auth.with_secondary(secondary.clone());
+        assert_eq!(auth.preferred_secret(), Secret::Primary);
+        assert_eq!(
+            auth.fallback_secret(Secret::Primary),
+            Some(Secret::Secondary)
+        );
+
+        auth.set_preferred_secret(Secret::Secondary);
+        let token = auth.generate_token().unwrap();
+        assert!(Auth::validate_token(&token, &secondary).is_ok());
+        assert!(Auth::validate_token(&token, &primary).is_err());
+        assert_eq!(
+            auth.fallback_secret(Secret::Secondary),
+            Some(Secret::Primary)
+        );
+    }
 }
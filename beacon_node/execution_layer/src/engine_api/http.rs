@@ -1,7 +1,7 @@
 //! Contains an implementation of `EngineAPI` using the JSON-RPC API via HTTP.
 
 use super::*;
-use crate::auth::Auth;
+use crate::auth::{self, Auth};
 use crate::json_structures::*;
 use reqwest::header::CONTENT_TYPE;
 use sensitive_url::SensitiveUrl;
@@ -49,6 +49,9 @@ pub const ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1: &str = "engine_getPayloadBodiesB
 pub const ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1: &str = "engine_getPayloadBodiesByRangeV1";
 pub const ENGINE_GET_PAYLOAD_BODIES_TIMEOUT: Duration = Duration::from_secs(10);
 
+pub const ENGINE_GET_BLOBS_V1: &str = "engine_getBlobsV1";
+pub const ENGINE_GET_BLOBS_TIMEOUT: Duration = Duration::from_secs(1);
+
 pub const ENGINE_EXCHANGE_CAPABILITIES: &str = "engine_exchangeCapabilities";
 pub const ENGINE_EXCHANGE_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(1);
 
@@ -70,6 +73,7 @@ pub static LIGHTHOUSE_CAPABILITIES: &[&str] = &[
     ENGINE_FORKCHOICE_UPDATED_V3,
     ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1,
     ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
+    ENGINE_GET_BLOBS_V1,
 ];
 
 /// This is necessary because a user might run a capella-enabled version of
@@ -87,6 +91,7 @@ pub static PRE_CAPELLA_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilit
     get_payload_v1: true,
     get_payload_v2: false,
     get_payload_v3: false,
+    get_blobs_v1: false,
 };
 
 /// Contains methods to convert arbitrary bytes to an ETH2 deposit contract object.
@@ -641,17 +646,47 @@ impl HttpJsonRpc {
             id: json!(STATIC_ID),
         };
 
+        let Some(auth) = &self.auth else {
+            return self.send_rpc_request(&body, timeout, None).await;
+        };
+
+        // Generate and add a jwt token to the header, retrying with the fallback secret if the
+        // token is rejected.
+        auth.reload_if_changed();
+        let secret = auth.preferred_secret();
+        let token = auth.generate_token_with_secret(secret)?;
+        match (
+            self.send_rpc_request(&body, timeout, Some(token)).await,
+            auth.fallback_secret(secret),
+        ) {
+            (Err(Error::Auth(auth::Error::InvalidToken)), Some(fallback)) => {
+                let token = auth.generate_token_with_secret(fallback)?;
+                let result = self.send_rpc_request(&body, timeout, Some(token)).await;
+                if !matches!(result, Err(Error::Auth(_)) | Err(Error::HttpClient(_))) {
+                    auth.set_preferred_secret(fallback);
+                }
+                result
+            }
+            (result, _) => result,
+        }
+    }
+
+    async fn send_rpc_request<D: DeserializeOwned>(
+        &self,
+        body: &JsonRequestBody<'_>,
+        timeout: Duration,
+        token: Option<String>,
+    ) -> Result<D, Error> {
         let mut request = self
             .client
             .post(self.url.full.clone())
             .timeout(timeout)
             .header(CONTENT_TYPE, "application/json")
-            .json(&body);
+            .json(body);
 
-        // Generate and add a jwt token to the header if auth is defined.
-        if let Some(auth) = &self.auth {
-            request = request.bearer_auth(auth.generate_token()?);
-        };
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
 
         let body: JsonResponseBody = request.send().await?.error_for_status()?.json().await?;
 
@@ -1010,6 +1045,26 @@ impl HttpJsonRpc {
             .collect())
     }
 
+    pub async fn get_blobs_v1<E: EthSpec>(
+        &self,
+        versioned_hashes: Vec<VersionedHash>,
+    ) -> Result<Vec<Option<BlobAndProofV1<E>>>, Error> {
+        let params = json!([versioned_hashes]);
+
+        let response: Vec<Option<JsonBlobAndProofV1<E>>> = self
+            .rpc_request(
+                ENGINE_GET_BLOBS_V1,
+                params,
+                ENGINE_GET_BLOBS_TIMEOUT * self.execution_timeout_multiplier,
+            )
+            .await?;
+
+        Ok(response
+            .into_iter()
+            .map(|opt_json| opt_json.map(From::from))
+            .collect())
+    }
+
     pub async fn exchange_capabilities(&self) -> Result<EngineCapabilities, Error> {
         let params = json!([LIGHTHOUSE_CAPABILITIES]);
 
@@ -1043,6 +1098,7 @@ impl HttpJsonRpc {
                 get_payload_v1: capabilities.contains(ENGINE_GET_PAYLOAD_V1),
                 get_payload_v2: capabilities.contains(ENGINE_GET_PAYLOAD_V2),
                 get_payload_v3: capabilities.contains(ENGINE_GET_PAYLOAD_V3),
+                get_blobs_v1: capabilities.contains(ENGINE_GET_BLOBS_V1),
             }),
         }
     }
@@ -1413,6 +1469,54 @@ mod test {
         );
     }
 
+    /// Rotates the secret expected by the mock EL while requests are being made, checking that
+    /// only the first request after the rotation is rejected and retried with the secondary
+    /// secret, and that a reloaded primary secret is used without any rejections.
+    #[tokio::test]
+    async fn jwt_secret_rotation() {
+        let server = MockServer::<MainnetEthSpec>::unit_testing();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        std::fs::write(&path, hex::encode(DEFAULT_JWT_SECRET)).unwrap();
+        let new_secret = JwtKey::random();
+
+        let auth = Auth::new_with_path(path.clone(), None, None)
+            .unwrap()
+            .with_secondary(new_secret.clone());
+        let client =
+            HttpJsonRpc::new_with_auth(SensitiveUrl::parse(&server.url()).unwrap(), auth, None)
+                .unwrap();
+
+        client.upcheck().await.unwrap();
+        assert_eq!(server.auth_failures(), 0);
+
+        server.set_jwt_key(new_secret.clone());
+        for _ in 0..4 {
+            client.upcheck().await.unwrap();
+        }
+        assert_eq!(server.auth_failures(), 1);
+
+        // Ensure the modification time changes on filesystems with coarse timestamps.
+        std::thread::sleep(Duration::from_millis(1100));
+        std::fs::write(&path, new_secret.hex_string()).unwrap();
+        for _ in 0..4 {
+            client.upcheck().await.unwrap();
+        }
+        assert_eq!(server.auth_failures(), 1);
+        assert_eq!(
+            client.auth.as_ref().unwrap().preferred_secret(),
+            auth::Secret::Primary
+        );
+
+        // Without a working secondary secret, rejected requests fail.
+        server.set_jwt_key(JwtKey::random());
+        assert!(matches!(
+            client.upcheck().await,
+            Err(Error::Auth(auth::Error::InvalidToken))
+        ));
+        assert_eq!(server.auth_failures(), 3);
+    }
+
     #[tokio::test]
     async fn get_block_by_number_request() {
         Tester::new(true)
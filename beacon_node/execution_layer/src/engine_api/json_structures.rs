@@ -5,8 +5,9 @@ use superstruct::superstruct;
 use types::beacon_block_body::KzgCommitments;
 use types::blob_sidecar::BlobsList;
 use types::{
-    EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadCapella, ExecutionPayloadDeneb,
-    ExecutionPayloadMerge, FixedVector, Transactions, Unsigned, VariableList, Withdrawal,
+    Blob, EthSpec, ExecutionBlockHash, ExecutionPayload, ExecutionPayloadCapella,
+    ExecutionPayloadDeneb, ExecutionPayloadMerge, FixedVector, KzgProof, Transactions, Unsigned,
+    VariableList, Withdrawal,
 };
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -463,6 +464,32 @@ impl<E: EthSpec> From<JsonBlobsBundleV1<E>> for BlobsBundle<E> {
     }
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct JsonBlobAndProofV1<E: EthSpec> {
+    #[serde(with = "ssz_types::serde_utils::hex_fixed_vec")]
+    pub blob: Blob<E>,
+    pub proof: KzgProof,
+}
+
+impl<E: EthSpec> From<BlobAndProofV1<E>> for JsonBlobAndProofV1<E> {
+    fn from(blob_and_proof: BlobAndProofV1<E>) -> Self {
+        Self {
+            blob: blob_and_proof.blob,
+            proof: blob_and_proof.proof,
+        }
+    }
+}
+
+impl<E: EthSpec> From<JsonBlobAndProofV1<E>> for BlobAndProofV1<E> {
+    fn from(json_blob_and_proof: JsonBlobAndProofV1<E>) -> Self {
+        Self {
+            blob: json_blob_and_proof.blob,
+            proof: json_blob_and_proof.proof,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct JsonForkchoiceStateV1 {
@@ -6,7 +6,7 @@
 
 use crate::payload_cache::PayloadCache;
 use arc_swap::ArcSwapOption;
-use auth::{strip_prefix, Auth, JwtKey};
+use auth::{Auth, JwtKey};
 use builder_client::BuilderHttpClient;
 pub use engine_api::EngineCapabilities;
 use engine_api::Error as ApiError;
@@ -18,6 +18,7 @@ use eth2::types::{builder_bid::SignedBuilderBid, BlobsBundle, ForkVersionedRespo
 use eth2::types::{FullPayloadContents, SignedBlockContents};
 use ethers_core::types::Transaction as EthersTransaction;
 use fork_choice::ForkchoiceUpdateParameters;
+use kzg::Kzg;
 use lru::LruCache;
 use payload_status::process_payload_status;
 pub use payload_status::PayloadStatus;
@@ -30,6 +31,7 @@ use std::fmt;
 use std::future::Future;
 use std::io::Write;
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
@@ -46,9 +48,13 @@ use types::sidecar::{BlobItems, Sidecar};
 use types::KzgProofs;
 use types::{
     AbstractExecPayload, BeaconStateError, BlindedPayload, BlockType, ChainSpec, Epoch,
-    ExecPayload, ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadMerge,
+    ExecPayload, ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadHeaderRef,
+    ExecutionPayloadMerge,
+};
+use types::{
+    KzgCommitment, ProposerPreparationData, PublicKeyBytes, Signature,
+    SignedValidatorRegistrationData, Slot, VersionedHash,
 };
-use types::{ProposerPreparationData, PublicKeyBytes, Signature, Slot};
 
 mod block_hash;
 mod engine_api;
@@ -69,6 +75,10 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: usize = 128;
 
+/// The number of epochs for which proposer preparation data is retained after it was last
+/// updated by a validator client.
+pub const PROPOSER_PREPARATION_LOOKAHEAD: u64 = 2;
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -145,6 +155,10 @@ pub enum Error {
     InvalidPayloadConversion,
     InvalidBlobConversion(String),
     BeaconStateError(BeaconStateError),
+    GetBlobsNotSupported,
+    /// The builder revealed an invalid blobs bundle for a signed blinded block.
+    InvalidBuilderBlobsBundle(InvalidBuilderBlobsBundle),
+    KzgNotInitialized,
 }
 
 impl From<BeaconStateError> for Error {
@@ -291,11 +305,52 @@ pub enum ChainHealth {
     PreMerge,
 }
 
-#[derive(Debug)]
+/// A chain health condition of the builder circuit breaker. When a condition fails the breaker
+/// trips, and payloads are only built by the local execution engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FailedCondition {
+    /// Too many skip slots in a row at the head of the chain.
     Skips,
+    /// Too many skip slots in the recent window of slots.
     SkipsPerEpoch,
+    /// The chain has not finalized for too many epochs.
     EpochsSinceFinalization,
+    /// Too many proposals were missed by the validators of this node in the recent window of
+    /// slots.
+    MissedProposals,
+}
+
+impl FailedCondition {
+    /// All conditions, in the order in which they are reported.
+    pub const ALL: [FailedCondition; 4] = [
+        FailedCondition::Skips,
+        FailedCondition::EpochsSinceFinalization,
+        FailedCondition::SkipsPerEpoch,
+        FailedCondition::MissedProposals,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailedCondition::Skips => "skips",
+            FailedCondition::SkipsPerEpoch => "skips_per_epoch",
+            FailedCondition::EpochsSinceFinalization => "epochs_since_finalization",
+            FailedCondition::MissedProposals => "missed_proposals",
+        }
+    }
+}
+
+impl FromStr for FailedCondition {
+    type Err = String;
+
+    /// Parses the name of a condition, with words separated by either `_` or `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|condition| condition.as_str() == name)
+            .ok_or_else(|| format!("unknown builder fallback condition: {}", s))
+    }
 }
 
 type PayloadContentsRefTuple<'a, T> = (ExecutionPayloadRef<'a, T>, Option<&'a BlobsBundle<T>>);
@@ -306,11 +361,15 @@ struct Inner<E: EthSpec> {
     execution_engine_forkchoice_lock: Mutex<()>,
     suggested_fee_recipient: Option<Address>,
     proposer_preparation_data: Mutex<HashMap<u64, ProposerPreparationDataEntry>>,
+    /// The latest builder registration received for each prepared proposer.
+    builder_registrations: parking_lot::RwLock<HashMap<u64, SignedValidatorRegistrationData>>,
     execution_blocks: Mutex<LruCache<ExecutionBlockHash, ExecutionBlock>>,
     proposers: RwLock<HashMap<ProposerKey, Proposer>>,
     executor: TaskExecutor,
     payload_cache: PayloadCache<E>,
     builder_profit_threshold: Uint256,
+    builder_extra_data_prefix: Option<Vec<u8>>,
+    strict_fee_recipient: bool,
     log: Logger,
     always_prefer_builder_payload: bool,
     ignore_builder_override_suggestion_threshold: f32,
@@ -331,6 +390,8 @@ pub struct Config {
     pub builder_user_agent: Option<String>,
     /// JWT secrets for the above endpoints running the engine api.
     pub secret_files: Vec<PathBuf>,
+    /// A JWT secret to try if the primary secret is rejected, e.g. during a secret rotation.
+    pub secondary_secret_file: Option<PathBuf>,
     /// The default fee recipient to use on the beacon node if none if provided from
     /// the validator client during block preparation.
     pub suggested_fee_recipient: Option<Address>,
@@ -342,6 +403,11 @@ pub struct Config {
     pub default_datadir: PathBuf,
     /// The minimum value of an external payload for it to be considered in a proposal.
     pub builder_profit_threshold: u128,
+    /// If set, builder payloads are only used if their `extra_data` starts with these bytes.
+    pub builder_extra_data_prefix: Option<Vec<u8>>,
+    /// If set, builder payloads are only used if their `fee_recipient` matches the one suggested
+    /// for the proposer.
+    pub strict_fee_recipient: bool,
     pub execution_timeout_multiplier: Option<u32>,
     pub always_prefer_builder_payload: bool,
     pub ignore_builder_override_suggestion_threshold: f32,
@@ -396,11 +462,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
             builder_url,
             builder_user_agent,
             secret_files,
+            secondary_secret_file,
             suggested_fee_recipient,
             jwt_id,
             jwt_version,
             default_datadir,
             builder_profit_threshold,
+            builder_extra_data_prefix,
+            strict_fee_recipient,
             execution_timeout_multiplier,
             always_prefer_builder_payload,
             ignore_builder_override_suggestion_threshold,
@@ -417,19 +486,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .next()
             .unwrap_or_else(|| default_datadir.join(DEFAULT_JWT_FILE));
 
-        let jwt_key = if secret_file.exists() {
-            // Read secret from file if it already exists
-            std::fs::read_to_string(&secret_file)
-                .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
-                .and_then(|ref s| {
-                    let secret = JwtKey::from_slice(
-                        &hex::decode(strip_prefix(s.trim_end()))
-                            .map_err(|e| format!("Invalid hex string: {:?}", e))?,
-                    )?;
-                    Ok(secret)
-                })
-                .map_err(Error::InvalidJWTSecret)
-        } else {
+        if !secret_file.exists() {
             // Create a new file and write a randomly generated secret to it if file does not exist
             warn!(log, "No JWT found on disk. Generating"; "path" => %secret_file.display());
             std::fs::File::options()
@@ -438,17 +495,31 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 .open(&secret_file)
                 .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
                 .and_then(|mut f| {
-                    let secret = auth::JwtKey::random();
+                    let secret = JwtKey::random();
                     f.write_all(secret.hex_string().as_bytes())
-                        .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))?;
-                    Ok(secret)
+                        .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))
                 })
-                .map_err(Error::InvalidJWTSecret)
-        }?;
+                .map_err(Error::InvalidJWTSecret)?;
+        }
+
+        // The secret is read from the file, and re-read whenever the file changes.
+        let mut auth = Auth::new_with_path(secret_file.clone(), jwt_id, jwt_version)
+            .map_err(|e| Error::InvalidJWTSecret(format!("{:?}", e)))?
+            .with_logger(log.clone());
+        if let Some(secondary_secret_file) = &secondary_secret_file {
+            let secondary = auth::read_secret_file(secondary_secret_file)
+                .map_err(|e| Error::InvalidJWTSecret(format!("{:?}", e)))?;
+            auth = auth.with_secondary(secondary);
+        }
 
         let engine: Engine = {
-            let auth = Auth::new(jwt_key, jwt_id, jwt_version);
-            debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
+            debug!(
+                log,
+                "Loaded execution endpoint";
+                "endpoint" => %execution_url,
+                "jwt_path" => ?secret_file.as_path(),
+                "secondary_jwt_path" => ?secondary_secret_file,
+            );
             let api = HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
                 .map_err(Error::ApiError)?;
             Engine::new(api, executor.clone(), &log)
@@ -460,11 +531,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
             execution_engine_forkchoice_lock: <_>::default(),
             suggested_fee_recipient,
             proposer_preparation_data: Mutex::new(HashMap::new()),
+            builder_registrations: parking_lot::RwLock::new(HashMap::new()),
             proposers: RwLock::new(HashMap::new()),
             execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
             executor,
             payload_cache: PayloadCache::default(),
             builder_profit_threshold: Uint256::from(builder_profit_threshold),
+            builder_extra_data_prefix,
+            strict_fee_recipient,
             log,
             always_prefer_builder_payload,
             ignore_builder_override_suggestion_threshold,
@@ -716,15 +790,82 @@ impl<T: EthSpec> ExecutionLayer<T> {
         }
     }
 
+    /// Updates the latest builder registrations of the validators with the given indices.
+    pub fn update_builder_registrations(
+        &self,
+        registrations: impl IntoIterator<Item = (u64, SignedValidatorRegistrationData)>,
+    ) {
+        self.inner
+            .builder_registrations
+            .write()
+            .extend(registrations);
+    }
+
+    /// Returns the latest builder registration of the validator, if any.
+    pub fn builder_registration(
+        &self,
+        validator_index: u64,
+    ) -> Option<SignedValidatorRegistrationData> {
+        self.inner
+            .builder_registrations
+            .read()
+            .get(&validator_index)
+            .cloned()
+    }
+
+    /// Seeds the proposer preparation data and builder registrations with entries restored from
+    /// disk after a restart.
+    ///
+    /// Entries which have already been provided via `Self::update_proposer_preparation` or
+    /// `Self::update_builder_registrations` are not replaced. Returns `false` if the data could
+    /// not be locked, in which case nothing is restored.
+    pub fn restore_proposer_preparation(
+        &self,
+        entries: impl IntoIterator<
+            Item = (
+                Epoch,
+                ProposerPreparationData,
+                Option<SignedValidatorRegistrationData>,
+            ),
+        >,
+    ) -> bool {
+        let Ok(mut proposer_preparation_data) = self.inner.proposer_preparation_data.try_lock()
+        else {
+            return false;
+        };
+        let mut builder_registrations = self.inner.builder_registrations.write();
+
+        for (update_epoch, preparation_data, registration) in entries {
+            if let Some(registration) = registration {
+                builder_registrations
+                    .entry(preparation_data.validator_index)
+                    .or_insert(registration);
+            }
+            proposer_preparation_data
+                .entry(preparation_data.validator_index)
+                .or_insert(ProposerPreparationDataEntry {
+                    update_epoch,
+                    preparation_data,
+                });
+        }
+
+        true
+    }
+
     /// Removes expired entries from proposer_preparation_data and proposers caches
     async fn clean_proposer_caches(&self, current_epoch: Epoch) -> Result<(), Error> {
         let mut proposer_preparation_data = self.proposer_preparation_data().await;
 
-        // Keep all entries that have been updated in the last 2 epochs
-        let retain_epoch = current_epoch.saturating_sub(Epoch::new(2));
+        // Keep all entries that have been updated in the last `PROPOSER_PREPARATION_LOOKAHEAD`
+        // epochs.
+        let retain_epoch = current_epoch.saturating_sub(PROPOSER_PREPARATION_LOOKAHEAD);
         proposer_preparation_data.retain(|_validator_index, preparation_entry| {
             preparation_entry.update_epoch >= retain_epoch
         });
+        self.inner
+            .builder_registrations
+            .write()
+            .retain(|validator_index, _| proposer_preparation_data.contains_key(validator_index));
         drop(proposer_preparation_data);
 
         let retain_slot = retain_epoch.start_slot(T::slots_per_epoch());
@@ -760,6 +901,19 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .contains_key(&proposer_index)
     }
 
+    /// Returns the proposer preparations received from validator clients, with the epoch in which
+    /// each was last updated, *from a synchronous context*.
+    ///
+    /// This method MUST NOT be called from an async task.
+    pub fn proposer_preparations_blocking(&self) -> Vec<(Epoch, ProposerPreparationData)> {
+        self.inner
+            .proposer_preparation_data
+            .blocking_lock()
+            .values()
+            .map(|entry| (entry.update_epoch, entry.preparation_data.clone()))
+            .collect()
+    }
+
     /// Returns the fee-recipient address that should be used to build a block
     pub async fn get_suggested_fee_recipient(&self, proposer_index: u64) -> Address {
         if let Some(preparation_data_entry) =
@@ -791,10 +945,16 @@ impl<T: EthSpec> ExecutionLayer<T> {
     /// However, it will attempt to call `self.prepare_payload` if it cannot find an existing
     /// payload id for the given parameters.
     ///
+    /// The local payload of a blinded block is kept in the payload cache, so that the block can be
+    /// unblinded once signed, unless `cache_local_payload` is `false`. In that case the payload ID
+    /// used to fetch the local payload isn't cached either, so that a later request with the same
+    /// attributes has no knowledge of this one.
+    ///
     /// ## Fallback Behavior
     ///
     /// The result will be returned from the first node that returns successfully. No more nodes
     /// will be contacted.
+    #[allow(clippy::too_many_arguments)]
     pub async fn get_payload<Payload: AbstractExecPayload<T>>(
         &self,
         parent_hash: ExecutionBlockHash,
@@ -803,6 +963,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         builder_params: BuilderParams,
         current_fork: ForkName,
         spec: &ChainSpec,
+        cache_local_payload: bool,
     ) -> Result<BlockProposalContents<T, Payload>, Error> {
         let payload_result = match Payload::block_type() {
             BlockType::Blinded => {
@@ -817,6 +978,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     builder_params,
                     current_fork,
                     spec,
+                    cache_local_payload,
                 )
                 .await
             }
@@ -830,6 +992,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     payload_attributes,
                     forkchoice_update_params,
                     current_fork,
+                    cache_local_payload,
                 )
                 .await
                 .and_then(GetPayloadResponse::try_into)
@@ -871,6 +1034,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_blinded_payload<Payload: AbstractExecPayload<T>>(
         &self,
         parent_hash: ExecutionBlockHash,
@@ -879,6 +1043,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         builder_params: BuilderParams,
         current_fork: ForkName,
         spec: &ChainSpec,
+        cache_local_payload: bool,
     ) -> Result<ProvenancedPayload<BlockProposalContents<T, Payload>>, Error> {
         if let Some(builder) = self.builder() {
             let slot = builder_params.slot;
@@ -907,6 +1072,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                 payload_attributes,
                                 forkchoice_update_params,
                                 current_fork,
+                                cache_local_payload,
                             )
                             .await
                         })
@@ -939,6 +1105,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                 "local_block_hash" => ?local.block_hash(),
                                 "parent_hash" => ?parent_hash,
                             );
+                            metrics::set_last_builder_fallback(metrics::FALLBACK_RELAY_ERROR);
                             Ok(ProvenancedPayload::Local(local.try_into()?))
                         }
                         (Ok(None), Ok(local)) => {
@@ -949,10 +1116,12 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                 "local_block_hash" => ?local.block_hash(),
                                 "parent_hash" => ?parent_hash,
                             );
+                            metrics::set_last_builder_fallback(metrics::FALLBACK_NO_BID);
                             Ok(ProvenancedPayload::Local(local.try_into()?))
                         }
                         (Ok(Some(relay)), Ok(local)) => {
                             let header = &relay.data.message.header();
+                            self.check_builder_fee_recipient(header, payload_attributes);
 
                             info!(
                                 self.log(),
@@ -973,6 +1142,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                         "local_block_value" => %local_value,
                                         "relay_value" => %relay_value
                                     );
+                                    metrics::set_last_builder_fallback(
+                                        metrics::FALLBACK_LOCAL_MORE_PROFITABLE,
+                                    );
                                     return Ok(ProvenancedPayload::Local(local.try_into()?));
                                 } else if local.should_override_builder().unwrap_or(false) {
                                     let percentage_difference =
@@ -989,6 +1161,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                             "local_block_value" => %local_value,
                                             "relay_value" => %relay_value
                                         );
+                                        metrics::set_last_builder_fallback(
+                                            metrics::FALLBACK_ENGINE_OVERRIDE,
+                                        );
                                         return Ok(ProvenancedPayload::Local(local.try_into()?));
                                     }
                                 } else {
@@ -1007,6 +1182,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                 payload_attributes,
                                 Some(local.block_number()),
                                 self.inner.builder_profit_threshold,
+                                self.inner.builder_extra_data_prefix.as_deref(),
+                                self.inner.strict_fee_recipient,
                                 current_fork,
                                 spec,
                             ) {
@@ -1020,6 +1197,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                         "relay_block_hash" => ?header.block_hash(),
                                         "parent_hash" => ?parent_hash,
                                     );
+                                    metrics::set_last_builder_fallback(
+                                        metrics::FALLBACK_BID_IGNORED,
+                                    );
                                     Ok(ProvenancedPayload::Local(local.try_into()?))
                                 }
                                 Err(reason) => {
@@ -1035,12 +1215,16 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                         "relay_block_hash" => ?header.block_hash(),
                                         "parent_hash" => ?parent_hash,
                                     );
+                                    metrics::set_last_builder_fallback(
+                                        metrics::FALLBACK_BID_INVALID,
+                                    );
                                     Ok(ProvenancedPayload::Local(local.try_into()?))
                                 }
                             }
                         }
                         (Ok(Some(relay)), Err(local_error)) => {
                             let header = &relay.data.message.header();
+                            self.check_builder_fee_recipient(header, payload_attributes);
 
                             info!(
                                 self.log(),
@@ -1056,6 +1240,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                 payload_attributes,
                                 None,
                                 self.inner.builder_profit_threshold,
+                                self.inner.builder_extra_data_prefix.as_deref(),
+                                self.inner.strict_fee_recipient,
                                 current_fork,
                                 spec,
                             ) {
@@ -1108,13 +1294,16 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         }
                     };
                 }
-                ChainHealth::Unhealthy(condition) => info!(
-                    self.log(),
-                    "Chain is unhealthy, using local payload";
-                    "info" => "this helps protect the network. the --builder-fallback flags \
-                        can adjust the expected health conditions.",
-                    "failed_condition" => ?condition
-                ),
+                ChainHealth::Unhealthy(condition) => {
+                    info!(
+                        self.log(),
+                        "Chain is unhealthy, using local payload";
+                        "info" => "this helps protect the network. the --builder-fallback flags \
+                            can adjust the expected health conditions.",
+                        "failed_condition" => ?condition
+                    );
+                    metrics::set_last_builder_fallback(metrics::FALLBACK_CHAIN_UNHEALTHY);
+                }
                 // Intentional no-op, so we never attempt builder API proposals pre-merge.
                 ChainHealth::PreMerge => (),
                 ChainHealth::Optimistic => info!(
@@ -1130,19 +1319,52 @@ impl<T: EthSpec> ExecutionLayer<T> {
             payload_attributes,
             forkchoice_update_params,
             current_fork,
+            cache_local_payload,
         )
         .await
         .and_then(GetPayloadResponse::try_into)
         .map(ProvenancedPayload::Local)
     }
 
+    /// Warns if the fee recipient of a builder bid differs from the one suggested for the proposer.
+    ///
+    /// A mismatch may indicate a misbehaving relay, though some builders pay the proposer with a
+    /// transaction instead. The bid is only rejected if `strict_fee_recipient` is set.
+    fn check_builder_fee_recipient(
+        &self,
+        header: &ExecutionPayloadHeaderRef<T>,
+        payload_attributes: &PayloadAttributes,
+    ) {
+        let fee_recipient = header.fee_recipient();
+        let suggested_fee_recipient = payload_attributes.suggested_fee_recipient();
+        if fee_recipient != suggested_fee_recipient {
+            metrics::inc_counter_vec(
+                &metrics::EXECUTION_LAYER_FEE_RECIPIENT_MISMATCH,
+                &[metrics::BUILDER],
+            );
+            warn!(
+                self.log(),
+                "Builder bid has unexpected fee recipient";
+                "msg" => "the fee recipient of the builder's payload differs from the one \
+                registered for the proposer, which may indicate a misbehaving relay",
+                "fee_recipient" => ?fee_recipient,
+                "suggested_fee_recipient" => ?suggested_fee_recipient,
+                "strict_fee_recipient" => self.inner.strict_fee_recipient,
+                "relay_block_hash" => ?header.block_hash(),
+            );
+        }
+    }
+
     /// Get a full payload without caching its result in the execution layer's payload cache.
+    ///
+    /// The payload ID is only cached if `cache_payload_id` is `true`.
     async fn get_full_payload(
         &self,
         parent_hash: ExecutionBlockHash,
         payload_attributes: &PayloadAttributes,
         forkchoice_update_params: ForkchoiceUpdateParameters,
         current_fork: ForkName,
+        cache_payload_id: bool,
     ) -> Result<GetPayloadResponse<T>, Error> {
         self.get_full_payload_with(
             parent_hash,
@@ -1150,24 +1372,28 @@ impl<T: EthSpec> ExecutionLayer<T> {
             forkchoice_update_params,
             current_fork,
             noop,
+            cache_payload_id,
         )
         .await
     }
 
-    /// Get a full payload and cache its result in the execution layer's payload cache.
+    /// Get a full payload and cache its result in the execution layer's payload cache, if `cache`
+    /// is `true`. The payload ID is also only cached if `cache` is `true`.
     async fn get_full_payload_caching(
         &self,
         parent_hash: ExecutionBlockHash,
         payload_attributes: &PayloadAttributes,
         forkchoice_update_params: ForkchoiceUpdateParameters,
         current_fork: ForkName,
+        cache: bool,
     ) -> Result<GetPayloadResponse<T>, Error> {
         self.get_full_payload_with(
             parent_hash,
             payload_attributes,
             forkchoice_update_params,
             current_fork,
-            Self::cache_payload,
+            if cache { Self::cache_payload } else { noop },
+            cache,
         )
         .await
     }
@@ -1182,6 +1408,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
             &ExecutionLayer<T>,
             PayloadContentsRefTuple<T>,
         ) -> Option<FullPayloadContents<T>>,
+        cache_payload_id: bool,
     ) -> Result<GetPayloadResponse<T>, Error> {
         self.engine()
             .request(move |engine| async move {
@@ -1212,13 +1439,20 @@ impl<T: EthSpec> ExecutionLayer<T> {
                             .unwrap_or_else(ExecutionBlockHash::zero),
                     };
 
-                    let response = engine
-                        .notify_forkchoice_updated(
-                            fork_choice_state,
-                            Some(payload_attributes.clone()),
-                            self.log(),
-                        )
-                        .await?;
+                    let response = if cache_payload_id {
+                        engine
+                            .notify_forkchoice_updated(
+                                fork_choice_state,
+                                Some(payload_attributes.clone()),
+                                self.log(),
+                            )
+                            .await?
+                    } else {
+                        engine
+                            .api
+                            .forkchoice_updated(fork_choice_state, Some(payload_attributes.clone()))
+                            .await?
+                    };
 
                     match response.payload_id {
                         Some(payload_id) => payload_id,
@@ -1248,6 +1482,10 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 }.await?;
 
                 if payload_response.execution_payload_ref().fee_recipient() != payload_attributes.suggested_fee_recipient() {
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_FEE_RECIPIENT_MISMATCH,
+                        &[metrics::LOCAL],
+                    );
                     error!(
                         self.log(),
                         "Inconsistent fee recipient";
@@ -1708,6 +1946,29 @@ impl<T: EthSpec> ExecutionLayer<T> {
             .map_err(Error::EngineError)
     }
 
+    /// Fetch blobs and their proofs from the execution node's mempool, by versioned hash.
+    ///
+    /// The response contains one entry per requested hash, which is `None` if the execution node
+    /// does not have the blob.
+    pub async fn get_blobs(
+        &self,
+        versioned_hashes: Vec<VersionedHash>,
+    ) -> Result<Vec<Option<BlobAndProofV1<T>>>, Error> {
+        let capabilities = self.get_engine_capabilities(None).await?;
+        if !capabilities.get_blobs_v1 {
+            return Err(Error::GetBlobsNotSupported);
+        }
+
+        let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_BLOBS);
+        self.engine()
+            .request(
+                |engine: &Engine| async move { engine.api.get_blobs_v1(versioned_hashes).await },
+            )
+            .await
+            .map_err(Box::new)
+            .map_err(Error::EngineError)
+    }
+
     /// Fetch a full payload from the execution node.
     ///
     /// This will fail if the payload is not from the finalized portion of the chain.
@@ -1899,10 +2160,15 @@ impl<T: EthSpec> ExecutionLayer<T> {
         Ok(Some(payload))
     }
 
+    /// Sends the signed blinded `block` to the builder, which should reveal its payload and blobs.
+    ///
+    /// The revealed blobs bundle is verified against the commitments of `block` before it is
+    /// returned, since nothing may be broadcast with blobs that the block doesn't commit to.
     pub async fn propose_blinded_beacon_block(
         &self,
         block_root: Hash256,
         block: &SignedBlockContents<T, BlindedPayload<T>>,
+        kzg: Option<&Kzg<T::Kzg>>,
     ) -> Result<FullPayloadContents<T>, Error> {
         debug!(
             self.log(),
@@ -1921,6 +2187,17 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 })
                 .await;
 
+            // Only count the reveal as successful once its blobs bundle has been verified.
+            let payload_result = payload_result.and_then(|payload_contents| {
+                // Blocks prior to Deneb don't commit to any blobs.
+                if let Ok(expected_commitments) =
+                    block.signed_block().message().body().blob_kzg_commitments()
+                {
+                    verify_builder_blobs_bundle(expected_commitments, &payload_contents, kzg)?;
+                }
+                Ok(payload_contents)
+            });
+
             match &payload_result {
                 Ok(unblinded_response) => {
                     metrics::inc_counter_vec(
@@ -2002,6 +2279,14 @@ enum InvalidBuilderPayload {
         payload: Option<Hash256>,
         expected: Option<Hash256>,
     },
+    ExtraData {
+        payload: Vec<u8>,
+        expected_prefix: Vec<u8>,
+    },
+    FeeRecipient {
+        payload: Address,
+        expected: Address,
+    },
 }
 
 impl InvalidBuilderPayload {
@@ -2017,6 +2302,11 @@ impl InvalidBuilderPayload {
             InvalidBuilderPayload::Fork { .. } => true,
             InvalidBuilderPayload::Signature { .. } => true,
             InvalidBuilderPayload::WithdrawalsRoot { .. } => true,
+            // A payload with unexpected extra data is valid, but the user would prefer to avoid it.
+            InvalidBuilderPayload::ExtraData { .. } => false,
+            // Builders may pay the proposer with a transaction rather than via the fee recipient,
+            // so a mismatch is only avoided when the user opts in.
+            InvalidBuilderPayload::FeeRecipient { .. } => false,
         }
     }
 }
@@ -2065,17 +2355,115 @@ impl fmt::Display for InvalidBuilderPayload {
                     opt_string(expected)
                 )
             }
+            InvalidBuilderPayload::ExtraData {
+                payload,
+                expected_prefix,
+            } => write!(
+                f,
+                "payload extra data 0x{} does not start with user-configured prefix 0x{}",
+                hex::encode(payload),
+                hex::encode(expected_prefix)
+            ),
+            InvalidBuilderPayload::FeeRecipient { payload, expected } => {
+                write!(
+                    f,
+                    "payload fee recipient was {:?} not {:?}",
+                    payload, expected
+                )
+            }
+        }
+    }
+}
+
+/// Reasons for rejecting the blobs bundle revealed by a builder for a signed blinded block.
+#[derive(Debug)]
+pub enum InvalidBuilderBlobsBundle {
+    /// The builder revealed a payload without a blobs bundle.
+    MissingBlobsBundle,
+    /// The commitments of the bundle differ from the commitments of the block, in order or count.
+    Commitments {
+        bundle: Vec<KzgCommitment>,
+        expected: Vec<KzgCommitment>,
+    },
+    BlobCount {
+        commitments: usize,
+        blobs: usize,
+        proofs: usize,
+    },
+    InvalidProofs,
+    Kzg(kzg::Error),
+}
+
+impl From<InvalidBuilderBlobsBundle> for Error {
+    fn from(e: InvalidBuilderBlobsBundle) -> Self {
+        Error::InvalidBuilderBlobsBundle(e)
+    }
+}
+
+/// Strictly verify the blobs bundle revealed by the builder against the `expected_commitments` of
+/// the signed blinded block.
+///
+/// The commitments must be identical, and the proof of each blob must verify against its
+/// commitment.
+fn verify_builder_blobs_bundle<T: EthSpec>(
+    expected_commitments: &KzgCommitments<T>,
+    payload_contents: &FullPayloadContents<T>,
+    kzg: Option<&Kzg<T::Kzg>>,
+) -> Result<(), Error> {
+    let FullPayloadContents::PayloadAndBlobs(payload_and_blobs) = payload_contents else {
+        return Err(InvalidBuilderBlobsBundle::MissingBlobsBundle.into());
+    };
+    let BlobsBundle {
+        commitments,
+        proofs,
+        blobs,
+    } = &payload_and_blobs.blobs_bundle;
+
+    if commitments != expected_commitments {
+        return Err(InvalidBuilderBlobsBundle::Commitments {
+            bundle: commitments.to_vec(),
+            expected: expected_commitments.to_vec(),
         }
+        .into());
+    }
+    if blobs.len() != commitments.len() || proofs.len() != commitments.len() {
+        return Err(InvalidBuilderBlobsBundle::BlobCount {
+            commitments: commitments.len(),
+            blobs: blobs.len(),
+            proofs: proofs.len(),
+        }
+        .into());
+    }
+    if blobs.is_empty() {
+        return Ok(());
     }
+
+    let kzg = kzg.ok_or(Error::KzgNotInitialized)?;
+    let kzg_blobs = blobs
+        .iter()
+        .map(|blob| T::blob_from_bytes(blob.as_ref()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(InvalidBuilderBlobsBundle::Kzg)?;
+    let valid = kzg
+        .verify_blob_kzg_proof_batch(&kzg_blobs, commitments, proofs)
+        .map_err(InvalidBuilderBlobsBundle::Kzg)?;
+    if !valid {
+        return Err(InvalidBuilderBlobsBundle::InvalidProofs.into());
+    }
+
+    Ok(())
 }
 
 /// Perform some cursory, non-exhaustive validation of the bid returned from the builder.
+#[allow(clippy::too_many_arguments)]
 fn verify_builder_bid<T: EthSpec>(
     bid: &ForkVersionedResponse<SignedBuilderBid<T>>,
     parent_hash: ExecutionBlockHash,
     payload_attributes: &PayloadAttributes,
     block_number: Option<u64>,
     profit_threshold: Uint256,
+    extra_data_prefix: Option<&[u8]>,
+    strict_fee_recipient: bool,
     current_fork: ForkName,
     spec: &ChainSpec,
 ) -> Result<(), Box<InvalidBuilderPayload>> {
@@ -2099,6 +2487,7 @@ fn verify_builder_bid<T: EthSpec>(
         .cloned()
         .map(|withdrawals| Withdrawals::<T>::from(withdrawals).tree_hash_root());
     let payload_withdrawals_root = header.withdrawals_root().ok().copied();
+    let payload_extra_data = header.extra_data();
 
     if *payload_value < profit_threshold {
         Err(Box::new(InvalidBuilderPayload::LowValue {
@@ -2140,6 +2529,20 @@ fn verify_builder_bid<T: EthSpec>(
             payload: payload_withdrawals_root,
             expected: expected_withdrawals_root,
         }))
+    } else if let Some(prefix) =
+        extra_data_prefix.filter(|prefix| !payload_extra_data.starts_with(prefix))
+    {
+        Err(Box::new(InvalidBuilderPayload::ExtraData {
+            payload: payload_extra_data.to_vec(),
+            expected_prefix: prefix.to_vec(),
+        }))
+    } else if strict_fee_recipient
+        && header.fee_recipient() != payload_attributes.suggested_fee_recipient()
+    {
+        Err(Box::new(InvalidBuilderPayload::FeeRecipient {
+            payload: header.fee_recipient(),
+            expected: payload_attributes.suggested_fee_recipient(),
+        }))
     } else {
         Ok(())
     }
@@ -2173,9 +2576,9 @@ fn noop<T: EthSpec>(
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_utils::MockExecutionLayer as GenericMockExecutionLayer;
+    use crate::test_utils::{generate_blobs, MockExecutionLayer as GenericMockExecutionLayer};
     use task_executor::test_utils::TestRuntime;
-    use types::MainnetEthSpec;
+    use types::{KzgProof, MainnetEthSpec};
 
     type MockExecutionLayer = GenericMockExecutionLayer<MainnetEthSpec>;
 
@@ -2353,4 +2756,112 @@ mod test {
         assert!(result > -57.43772);
         assert!(result <= -57.43771);
     }
+
+    fn load_kzg() -> Kzg<<MainnetEthSpec as EthSpec>::Kzg> {
+        let trusted_setup: kzg::TrustedSetup =
+            serde_json::from_reader(eth2_network_config::get_trusted_setup::<
+                <MainnetEthSpec as EthSpec>::Kzg,
+            >())
+            .unwrap();
+        Kzg::new_from_trusted_setup(trusted_setup).unwrap()
+    }
+
+    /// Returns the contents revealed by the mock builder for a block with `num_blobs` blobs.
+    fn builder_payload_contents(num_blobs: usize) -> FullPayloadContents<MainnetEthSpec> {
+        let (blobs_bundle, _) = generate_blobs::<MainnetEthSpec>(num_blobs).unwrap();
+        FullPayloadContents::new(ExecutionPayloadDeneb::default().into(), Some(blobs_bundle))
+    }
+
+    fn blobs_bundle_mut(
+        contents: &mut FullPayloadContents<MainnetEthSpec>,
+    ) -> &mut BlobsBundle<MainnetEthSpec> {
+        match contents {
+            FullPayloadContents::PayloadAndBlobs(payload_and_blobs) => {
+                &mut payload_and_blobs.blobs_bundle
+            }
+            FullPayloadContents::Payload(_) => panic!("expected a blobs bundle"),
+        }
+    }
+
+    #[test]
+    fn builder_blobs_bundle_valid() {
+        let kzg = load_kzg();
+        let contents = builder_payload_contents(2);
+        let commitments = contents.clone().deconstruct().1.unwrap().commitments;
+        verify_builder_blobs_bundle(&commitments, &contents, Some(&kzg)).unwrap();
+
+        // A block without blobs doesn't require KZG.
+        let contents = builder_payload_contents(0);
+        verify_builder_blobs_bundle(&KzgCommitments::default(), &contents, None).unwrap();
+    }
+
+    #[test]
+    fn builder_blobs_bundle_missing() {
+        let contents = FullPayloadContents::Payload(ExecutionPayloadDeneb::default().into());
+        assert!(matches!(
+            verify_builder_blobs_bundle::<MainnetEthSpec>(
+                &KzgCommitments::default(),
+                &contents,
+                None
+            ),
+            Err(Error::InvalidBuilderBlobsBundle(
+                InvalidBuilderBlobsBundle::MissingBlobsBundle
+            ))
+        ));
+    }
+
+    #[test]
+    fn builder_blobs_bundle_swapped_commitments() {
+        let kzg = load_kzg();
+        let mut contents = builder_payload_contents(2);
+        // The test blobs share a commitment, so make the block commit to two distinct ones.
+        let other_commitment = KzgCommitment::empty_for_testing();
+        let bundle = blobs_bundle_mut(&mut contents);
+        bundle.commitments[1] = other_commitment;
+        let expected_commitments =
+            KzgCommitments::<MainnetEthSpec>::from(vec![bundle.commitments[0], other_commitment]);
+        bundle.commitments.swap(0, 1);
+
+        assert!(matches!(
+            verify_builder_blobs_bundle(&expected_commitments, &contents, Some(&kzg)),
+            Err(Error::InvalidBuilderBlobsBundle(
+                InvalidBuilderBlobsBundle::Commitments { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn builder_blobs_bundle_missing_blob() {
+        let kzg = load_kzg();
+        let mut contents = builder_payload_contents(2);
+        let commitments = contents.clone().deconstruct().1.unwrap().commitments;
+        let bundle = blobs_bundle_mut(&mut contents);
+        bundle.blobs = vec![bundle.blobs[0].clone()].into();
+
+        assert!(matches!(
+            verify_builder_blobs_bundle(&commitments, &contents, Some(&kzg)),
+            Err(Error::InvalidBuilderBlobsBundle(
+                InvalidBuilderBlobsBundle::BlobCount {
+                    commitments: 2,
+                    blobs: 1,
+                    proofs: 2,
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn builder_blobs_bundle_invalid_proof() {
+        let kzg = load_kzg();
+        let mut contents = builder_payload_contents(2);
+        let commitments = contents.clone().deconstruct().1.unwrap().commitments;
+        blobs_bundle_mut(&mut contents).proofs[1] = KzgProof::empty();
+
+        assert!(matches!(
+            verify_builder_blobs_bundle(&commitments, &contents, Some(&kzg)),
+            Err(Error::InvalidBuilderBlobsBundle(
+                InvalidBuilderBlobsBundle::InvalidProofs
+            ))
+        ));
+    }
 }
@@ -15,6 +15,13 @@ pub const LOCAL: &str = "local";
 pub const BUILDER: &str = "builder";
 pub const SUCCESS: &str = "success";
 pub const FAILURE: &str = "failure";
+pub const FALLBACK_RELAY_ERROR: &str = "relay_error";
+pub const FALLBACK_NO_BID: &str = "no_bid";
+pub const FALLBACK_LOCAL_MORE_PROFITABLE: &str = "local_more_profitable";
+pub const FALLBACK_ENGINE_OVERRIDE: &str = "engine_override";
+pub const FALLBACK_BID_IGNORED: &str = "bid_ignored";
+pub const FALLBACK_BID_INVALID: &str = "bid_invalid";
+pub const FALLBACK_CHAIN_UNHEALTHY: &str = "chain_unhealthy";
 
 lazy_static::lazy_static! {
     pub static ref EXECUTION_LAYER_PROPOSER_INSERTED: Result<IntCounter> = try_create_int_counter(
@@ -49,6 +56,10 @@ lazy_static::lazy_static! {
         "execution_layer_get_payload_bodies_by_range_time",
         "Time to fetch a range of payload bodies from the EE"
     );
+    pub static ref EXECUTION_LAYER_GET_BLOBS: Result<Histogram> = try_create_histogram(
+        "execution_layer_get_blobs_time",
+        "Time to fetch blobs from the EE's mempool"
+    );
     pub static ref EXECUTION_LAYER_VERIFY_BLOCK_HASH: Result<Histogram> = try_create_histogram_with_buckets(
         "execution_layer_verify_block_hash_time",
         "Time to verify the execution block hash in Lighthouse, without the EL",
@@ -79,9 +90,28 @@ lazy_static::lazy_static! {
         "The reasons why a payload from a builder was rejected",
         &["reason"]
     );
+    pub static ref EXECUTION_LAYER_FEE_RECIPIENT_MISMATCH: Result<IntCounterVec> = try_create_int_counter_vec(
+        "fee_recipient_mismatch_total",
+        "Count of payloads whose fee recipient differs from the one suggested for the proposer",
+        &["source"]
+    );
     pub static ref EXECUTION_LAYER_PAYLOAD_BIDS: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "execution_layer_payload_bids",
         "The gwei bid value of payloads received by local EEs or builders. Only shows values up to i64::max_value.",
         &["source"]
     );
+    pub static ref EXECUTION_LAYER_LAST_BUILDER_FALLBACK: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "execution_layer_last_builder_fallback",
+        "Set to 1 for the reason the local payload was most recently used in place of a builder payload",
+        &["reason"]
+    );
+}
+
+/// Records `reason` as the reason for the most recent fallback from the builder to the local
+/// execution engine, clearing any previous reason.
+pub fn set_last_builder_fallback(reason: &str) {
+    if let Ok(gauge_vec) = EXECUTION_LAYER_LAST_BUILDER_FALLBACK.as_ref() {
+        gauge_vec.reset();
+        gauge_vec.with_label_values(&[reason]).set(1);
+    }
 }
@@ -5,7 +5,8 @@ use crate::{
         json_structures::{
             JsonForkchoiceUpdatedV1Response, JsonPayloadStatusV1, JsonPayloadStatusV1Status,
         },
-        ExecutionBlock, PayloadAttributes, PayloadId, PayloadStatusV1, PayloadStatusV1Status,
+        BlobAndProofV1, ExecutionBlock, PayloadAttributes, PayloadId, PayloadStatusV1,
+        PayloadStatusV1Status,
     },
     ExecutionBlockWithTransactions,
 };
@@ -15,6 +16,7 @@ use parking_lot::Mutex;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use ssz_types::VariableList;
+use state_processing::per_block_processing::deneb::kzg_commitment_to_versioned_hash;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tree_hash::TreeHash;
@@ -22,7 +24,7 @@ use tree_hash_derive::TreeHash;
 use types::{
     Blob, ChainSpec, EthSpec, EthSpecId, ExecutionBlockHash, ExecutionPayload,
     ExecutionPayloadCapella, ExecutionPayloadDeneb, ExecutionPayloadHeader, ExecutionPayloadMerge,
-    ForkName, Hash256, Transaction, Transactions, Uint256,
+    ForkName, Hash256, Transaction, Transactions, Uint256, VersionedHash,
 };
 
 use super::DEFAULT_TERMINAL_BLOCK;
@@ -63,6 +65,13 @@ impl<T: EthSpec> Block<T> {
         }
     }
 
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Block::PoW(block) => block.timestamp,
+            Block::PoS(payload) => payload.timestamp(),
+        }
+    }
+
     pub fn total_difficulty(&self) -> Option<Uint256> {
         match self {
             Block::PoW(block) => Some(block.total_difficulty),
@@ -137,6 +146,8 @@ pub struct ExecutionBlockGenerator<T: EthSpec> {
      * deneb stuff
      */
     pub blobs_bundles: HashMap<PayloadId, BlobsBundle<T>>,
+    /// Blobs which are known to the mempool, served by `engine_getBlobsV1`.
+    pub blob_pool: HashMap<VersionedHash, BlobAndProofV1<T>>,
     pub kzg: Option<Arc<Kzg<T::Kzg>>>,
     rng: Arc<Mutex<StdRng>>,
 }
@@ -170,6 +181,7 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
             shanghai_time,
             cancun_time,
             blobs_bundles: <_>::default(),
+            blob_pool: <_>::default(),
             kzg: kzg.map(Arc::new),
             rng: make_rng(),
         };
@@ -426,6 +438,13 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
         self.blobs_bundles.get(id).cloned()
     }
 
+    pub fn get_blobs(&self, versioned_hashes: &[VersionedHash]) -> Vec<Option<BlobAndProofV1<T>>> {
+        versioned_hashes
+            .iter()
+            .map(|hash| self.blob_pool.get(hash).cloned())
+            .collect()
+    }
+
     pub fn new_payload(&mut self, payload: ExecutionPayload<T>) -> PayloadStatusV1 {
         let parent = if let Some(parent) = self.blocks.get(&payload.parent_hash()) {
             parent
@@ -638,6 +657,20 @@ impl<T: EthSpec> ExecutionBlockGenerator<T> {
                         .push(tx)
                         .map_err(|_| "transactions are full".to_string())?;
                 }
+                for ((commitment, proof), blob) in bundle
+                    .commitments
+                    .iter()
+                    .zip(bundle.proofs.iter())
+                    .zip(bundle.blobs.iter())
+                {
+                    self.blob_pool.insert(
+                        kzg_commitment_to_versioned_hash(commitment),
+                        BlobAndProofV1 {
+                            blob: blob.clone(),
+                            proof: *proof,
+                        },
+                    );
+                }
                 self.blobs_bundles.insert(id, bundle);
             }
         }
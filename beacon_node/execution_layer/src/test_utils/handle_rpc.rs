@@ -1,11 +1,11 @@
-use super::Context;
+use super::{Context, ScriptedMethod};
 use crate::engine_api::{http::*, *};
 use crate::json_structures::*;
 use crate::test_utils::DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI;
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::Value as JsonValue;
 use std::sync::Arc;
-use types::{EthSpec, ForkName};
+use types::{EthSpec, ForkName, VersionedHash};
 
 pub const GENERIC_ERROR_CODE: i64 = -1234;
 pub const BAD_PARAMS_ERROR_CODE: i64 = -32602;
@@ -190,6 +190,23 @@ pub async fn handle_rpc<T: EthSpec>(
                     .map_err(|message| (message, GENERIC_ERROR_CODE));
             }
 
+            // Scripted responses take priority over the static responses.
+            let scripted = ctx.get_scripted_response(
+                ScriptedMethod::NewPayload,
+                *request.block_hash(),
+                Some(*request.timestamp()),
+            );
+            if let Some(latency) = scripted.latency {
+                tokio::time::sleep(latency).await;
+            }
+            if let Some(status) = scripted.status {
+                // Process the payload regardless of the status, so that it may become valid later.
+                ctx.execution_block_generator
+                    .write()
+                    .new_payload(request.into());
+                return Ok(serde_json::to_value(status).unwrap());
+            }
+
             let (static_response, should_import) =
                 if let Some(mut response) = ctx.static_new_payload_response.lock().clone() {
                     if response.status.status == PayloadStatusV1Status::Valid {
@@ -443,7 +460,27 @@ pub async fn handle_rpc<T: EthSpec>(
                 )
                 .map_err(|s| (s, GENERIC_ERROR_CODE))?;
 
-            if let Some(mut status) = ctx.static_forkchoice_updated_response.lock().clone() {
+            let timestamp = ctx
+                .execution_block_generator
+                .read()
+                .block_by_hash(head_block_hash)
+                .map(|block| block.timestamp());
+            let scripted = ctx.get_scripted_response(
+                ScriptedMethod::ForkchoiceUpdated,
+                head_block_hash,
+                timestamp,
+            );
+            if let Some(latency) = scripted.latency {
+                tokio::time::sleep(latency).await;
+            }
+
+            if let Some(status) = scripted.status {
+                // Payload IDs are only returned for valid heads.
+                if status.status != JsonPayloadStatusV1Status::Valid {
+                    response.payload_id = None;
+                }
+                response.payload_status = status;
+            } else if let Some(mut status) = ctx.static_forkchoice_updated_response.lock().clone() {
                 if status.status == PayloadStatusV1Status::Valid {
                     status.latest_valid_hash = Some(head_block_hash)
                 }
@@ -512,6 +549,20 @@ pub async fn handle_rpc<T: EthSpec>(
 
             Ok(serde_json::to_value(response).unwrap())
         }
+        ENGINE_GET_BLOBS_V1 => {
+            let versioned_hashes = get_param::<Vec<VersionedHash>>(params, 0)
+                .map_err(|s| (s, BAD_PARAMS_ERROR_CODE))?;
+
+            let response: Vec<Option<JsonBlobAndProofV1<T>>> = ctx
+                .execution_block_generator
+                .read()
+                .get_blobs(&versioned_hashes)
+                .into_iter()
+                .map(|opt_blob| opt_blob.map(Into::into))
+                .collect();
+
+            Ok(serde_json::to_value(response).unwrap())
+        }
         other => Err((
             format!("The method {} does not exist/is not available", other),
             METHOD_NOT_FOUND_CODE,
@@ -1,6 +1,6 @@
 use crate::test_utils::{DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_JWT_SECRET};
 use crate::{Config, ExecutionLayer, PayloadAttributes};
-use eth2::types::{BlobsBundle, BlockId, StateId, ValidatorId};
+use eth2::types::{BlobsBundle, BlockId, FullPayloadContents, StateId, ValidatorId};
 use eth2::{BeaconNodeHttpClient, Timeouts};
 use fork_choice::ForkchoiceUpdateParameters;
 use parking_lot::RwLock;
@@ -19,9 +19,9 @@ use types::builder_bid::{
 };
 use types::{
     Address, BeaconState, ChainSpec, EthSpec, ExecPayload, ExecutionPayload,
-    ExecutionPayloadHeaderRefMut, ForkName, ForkVersionedResponse, Hash256, PublicKeyBytes,
-    Signature, SignedBlindedBeaconBlock, SignedRoot, SignedValidatorRegistrationData, Slot,
-    Uint256,
+    ExecutionPayloadHeaderRefMut, ForkName, ForkVersionedResponse, Hash256, KzgCommitment,
+    KzgProof, PublicKeyBytes, Signature, SignedBlindedBeaconBlock, SignedRoot,
+    SignedValidatorRegistrationData, Slot, Uint256, VariableList,
 };
 use types::{ExecutionBlockHash, SecretKey};
 use warp::{Filter, Rejection};
@@ -36,6 +36,7 @@ pub enum Operation {
     BlockNumber(usize),
     Timestamp(usize),
     WithdrawalsRoot(Hash256),
+    ExtraData(Vec<u8>),
 }
 
 impl Operation {
@@ -49,10 +50,55 @@ impl Operation {
             Operation::BlockNumber(block_number) => bid.set_block_number(block_number as u64),
             Operation::Timestamp(timestamp) => bid.set_timestamp(timestamp as u64),
             Operation::WithdrawalsRoot(root) => bid.set_withdrawals_root(root),
+            Operation::ExtraData(extra_data) => bid.set_extra_data(extra_data),
         }
     }
 }
 
+/// Tampers with the payload contents the builder reveals for a signed blinded block.
+#[derive(Clone)]
+pub enum RevealOperation {
+    /// Reveal the payload without its blobs bundle.
+    DropBlobsBundle,
+    /// Drop the last blob from the bundle, leaving its commitment and proof.
+    DropBlob,
+    /// Replace the first commitment with one the block doesn't commit to.
+    InvalidCommitment,
+    /// Replace the first proof with one that doesn't prove its blob.
+    InvalidProof,
+}
+
+impl RevealOperation {
+    fn apply<E: EthSpec>(self, payload_contents: FullPayloadContents<E>) -> FullPayloadContents<E> {
+        let mut payload_and_blobs = match payload_contents {
+            FullPayloadContents::Payload(_) => return payload_contents,
+            FullPayloadContents::PayloadAndBlobs(payload_and_blobs) => payload_and_blobs,
+        };
+        let blobs_bundle = &mut payload_and_blobs.blobs_bundle;
+        match self {
+            RevealOperation::DropBlobsBundle => {
+                return FullPayloadContents::Payload(payload_and_blobs.execution_payload);
+            }
+            RevealOperation::DropBlob => {
+                let mut blobs = blobs_bundle.blobs.to_vec();
+                blobs.pop();
+                blobs_bundle.blobs = blobs.into();
+            }
+            RevealOperation::InvalidCommitment => {
+                if let Some(commitment) = blobs_bundle.commitments.first_mut() {
+                    *commitment = KzgCommitment::empty_for_testing();
+                }
+            }
+            RevealOperation::InvalidProof => {
+                if let Some(proof) = blobs_bundle.proofs.first_mut() {
+                    *proof = KzgProof::empty();
+                }
+            }
+        }
+        FullPayloadContents::PayloadAndBlobs(payload_and_blobs)
+    }
+}
+
 #[derive(Debug)]
 struct Custom(String);
 
@@ -68,6 +114,7 @@ pub trait BidStuff<E: EthSpec> {
     fn set_block_number(&mut self, block_number: u64);
     fn set_timestamp(&mut self, timestamp: u64);
     fn set_withdrawals_root(&mut self, withdrawals_root: Hash256);
+    fn set_extra_data(&mut self, extra_data: Vec<u8>);
 
     fn sign_builder_message(&mut self, sk: &SecretKey, spec: &ChainSpec) -> Signature;
 
@@ -177,6 +224,21 @@ impl<E: EthSpec> BidStuff<E> for BuilderBid<E> {
         }
     }
 
+    fn set_extra_data(&mut self, extra_data: Vec<u8>) {
+        let extra_data = VariableList::new(extra_data).expect("extra data should not be too long");
+        match self.to_mut().header_mut() {
+            ExecutionPayloadHeaderRefMut::Merge(header) => {
+                header.extra_data = extra_data;
+            }
+            ExecutionPayloadHeaderRefMut::Capella(header) => {
+                header.extra_data = extra_data;
+            }
+            ExecutionPayloadHeaderRefMut::Deneb(header) => {
+                header.extra_data = extra_data;
+            }
+        }
+    }
+
     fn sign_builder_message(&mut self, sk: &SecretKey, spec: &ChainSpec) -> Signature {
         let domain = spec.get_builder_domain();
         let message = self.signing_root(domain);
@@ -199,6 +261,7 @@ pub struct MockBuilder<E: EthSpec> {
     val_registration_cache: Arc<RwLock<HashMap<PublicKeyBytes, SignedValidatorRegistrationData>>>,
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
+    reveal_operations: Arc<RwLock<Vec<RevealOperation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
 }
 
@@ -249,6 +312,7 @@ impl<E: EthSpec> MockBuilder<E> {
             val_registration_cache: Arc::new(RwLock::new(HashMap::new())),
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
+            reveal_operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
         }
     }
@@ -259,6 +323,12 @@ impl<E: EthSpec> MockBuilder<E> {
         self.operations.write().insert(0, op);
     }
 
+    pub fn add_reveal_operation(&self, op: RevealOperation) {
+        // Insert operations at the front of the vec to make sure `apply_reveal_operations`
+        // applies them in the order they are added.
+        self.reveal_operations.write().insert(0, op);
+    }
+
     pub fn invalid_signatures(&self) {
         *self.invalidate_signatures.write() = true;
     }
@@ -273,6 +343,17 @@ impl<E: EthSpec> MockBuilder<E> {
             op.apply(bid);
         }
     }
+
+    fn apply_reveal_operations(
+        &self,
+        mut payload_contents: FullPayloadContents<E>,
+    ) -> FullPayloadContents<E> {
+        let mut guard = self.reveal_operations.write();
+        while let Some(op) = guard.pop() {
+            payload_contents = op.apply(payload_contents);
+        }
+        payload_contents
+    }
 }
 
 pub fn serve<E: EthSpec>(
@@ -335,6 +416,7 @@ pub fn serve<E: EthSpec>(
                     .el
                     .get_payload_by_root(&root)
                     .ok_or_else(|| reject("missing payload for tx root"))?;
+                let payload = builder.apply_reveal_operations(payload);
                 let resp = ForkVersionedResponse {
                     version: Some(fork_name),
                     data: payload,
@@ -31,6 +31,8 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             None,
             None,
             None,
+            None,
+            false,
             Some(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap()),
             spec,
             None,
@@ -44,6 +46,8 @@ impl<T: EthSpec> MockExecutionLayer<T> {
         shanghai_time: Option<u64>,
         cancun_time: Option<u64>,
         builder_threshold: Option<u128>,
+        builder_extra_data_prefix: Option<Vec<u8>>,
+        strict_fee_recipient: bool,
         jwt_key: Option<JwtKey>,
         spec: ChainSpec,
         kzg: Option<Kzg<T::Kzg>>,
@@ -73,6 +77,8 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             secret_files: vec![path],
             suggested_fee_recipient: Some(Address::repeat_byte(42)),
             builder_profit_threshold: builder_threshold.unwrap_or(DEFAULT_BUILDER_THRESHOLD_WEI),
+            builder_extra_data_prefix,
+            strict_fee_recipient,
             ..Default::default()
         };
         let el =
@@ -142,6 +148,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
                 builder_params,
                 ForkName::Merge,
                 &self.spec,
+                true,
             )
             .await
             .unwrap()
@@ -176,6 +183,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
                 builder_params,
                 ForkName::Merge,
                 &self.spec,
+                true,
             )
             .await
             .unwrap()
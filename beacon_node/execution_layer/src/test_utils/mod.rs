@@ -18,10 +18,11 @@ use std::convert::Infallible;
 use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::{runtime, sync::oneshot};
 use types::{EthSpec, ExecutionBlockHash, Uint256};
-use warp::{http::StatusCode, Filter, Rejection};
+use warp::{http::StatusCode, reply::Reply, Filter, Rejection};
 
 use crate::EngineCapabilities;
 pub use execution_block_generator::{
@@ -29,8 +30,9 @@ pub use execution_block_generator::{
     ExecutionBlockGenerator,
 };
 pub use hook::Hook;
-pub use mock_builder::{MockBuilder, Operation};
+pub use mock_builder::{MockBuilder, Operation, RevealOperation};
 pub use mock_execution_layer::MockExecutionLayer;
+pub use script::{PayloadScript, ScriptRule, ScriptedMethod, ScriptedResponse, SlotRange};
 
 pub const DEFAULT_TERMINAL_DIFFICULTY: u64 = 6400;
 pub const DEFAULT_TERMINAL_BLOCK: u64 = 64;
@@ -50,6 +52,7 @@ pub const DEFAULT_ENGINE_CAPABILITIES: EngineCapabilities = EngineCapabilities {
     get_payload_v1: true,
     get_payload_v2: true,
     get_payload_v3: true,
+    get_blobs_v1: true,
 };
 
 mod execution_block_generator;
@@ -57,6 +60,7 @@ mod handle_rpc;
 mod hook;
 mod mock_builder;
 mod mock_execution_layer;
+mod script;
 
 /// Configuration for the MockExecutionLayer.
 pub struct MockExecutionConfig {
@@ -131,7 +135,8 @@ impl<T: EthSpec> MockServer<T> {
 
         let ctx: Arc<Context<T>> = Arc::new(Context {
             config: server_config,
-            jwt_key,
+            jwt_key: RwLock::new(jwt_key),
+            auth_failures: <_>::default(),
             log: null_logger().unwrap(),
             last_echo_request: last_echo_request.clone(),
             execution_block_generator: RwLock::new(execution_block_generator),
@@ -144,6 +149,7 @@ impl<T: EthSpec> MockServer<T> {
             new_payload_statuses: <_>::default(),
             fcu_payload_statuses: <_>::default(),
             syncing_response: Arc::new(Mutex::new(Ok(false))),
+            script: <_>::default(),
             engine_capabilities: Arc::new(RwLock::new(DEFAULT_ENGINE_CAPABILITIES)),
             _phantom: PhantomData,
         });
@@ -175,6 +181,16 @@ impl<T: EthSpec> MockServer<T> {
         }
     }
 
+    /// Replaces the JWT secret that requests must be signed with.
+    pub fn set_jwt_key(&self, jwt_key: JwtKey) {
+        *self.ctx.jwt_key.write() = jwt_key;
+    }
+
+    /// Returns the number of requests rejected because of a missing or invalid JWT.
+    pub fn auth_failures(&self) -> usize {
+        self.ctx.auth_failures.load(Ordering::Relaxed)
+    }
+
     pub fn set_engine_capabilities(&self, engine_capabilities: EngineCapabilities) {
         *self.ctx.engine_capabilities.write() = engine_capabilities;
     }
@@ -457,6 +473,17 @@ impl<T: EthSpec> MockServer<T> {
     pub fn set_syncing_response(&self, res: Result<bool, String>) {
         *self.ctx.syncing_response.lock() = res;
     }
+
+    /// Replaces the `PayloadScript`, which takes priority over the static responses.
+    pub fn set_script(&self, script: PayloadScript) -> Result<(), String> {
+        script.validate()?;
+        *self.ctx.script.lock() = Some(script);
+        Ok(())
+    }
+
+    pub fn clear_script(&self) {
+        *self.ctx.script.lock() = None;
+    }
 }
 
 #[derive(Debug)]
@@ -497,7 +524,8 @@ impl warp::reject::Reject for AuthError {}
 /// The server will gracefully handle the case where any fields are `None`.
 pub struct Context<T: EthSpec> {
     pub config: Config,
-    pub jwt_key: JwtKey,
+    pub jwt_key: RwLock<JwtKey>,
+    pub auth_failures: AtomicUsize,
     pub log: Logger,
     pub last_echo_request: Arc<RwLock<Option<Bytes>>>,
     pub execution_block_generator: RwLock<ExecutionBlockGenerator<T>>,
@@ -518,11 +546,29 @@ pub struct Context<T: EthSpec> {
         Arc<Mutex<HashMap<ExecutionBlockHash, Result<PayloadStatusV1, String>>>>,
     pub syncing_response: Arc<Mutex<Result<bool, String>>>,
 
+    // Scripted responses, which may be updated at runtime via the `/script` endpoint.
+    pub script: Arc<Mutex<Option<PayloadScript>>>,
+
     pub engine_capabilities: Arc<RwLock<EngineCapabilities>>,
     pub _phantom: PhantomData<T>,
 }
 
 impl<T: EthSpec> Context<T> {
+    /// Returns the scripted response to `method` for the payload with `block_hash`, if a script
+    /// is set.
+    pub fn get_scripted_response(
+        &self,
+        method: ScriptedMethod,
+        block_hash: ExecutionBlockHash,
+        timestamp: Option<u64>,
+    ) -> ScriptedResponse {
+        self.script
+            .lock()
+            .as_mut()
+            .map(|script| script.respond(method, block_hash, timestamp))
+            .unwrap_or_default()
+    }
+
     pub fn get_new_payload_status(
         &self,
         block_hash: &ExecutionBlockHash,
@@ -563,13 +609,14 @@ struct ErrorMessage {
 
 /// Returns a `warp` header which filters out request that has a missing or incorrectly
 /// signed JWT token.
-fn auth_header_filter(jwt_key: JwtKey) -> warp::filters::BoxedFilter<()> {
+fn auth_header_filter<T: EthSpec>(ctx: Arc<Context<T>>) -> warp::filters::BoxedFilter<()> {
     warp::any()
         .and(warp::filters::header::optional("Authorization"))
         .and_then(move |authorization: Option<String>| {
-            let secret = jwt_key.clone();
+            let ctx = ctx.clone();
             async move {
-                match authorization {
+                let secret = ctx.jwt_key.read().clone();
+                let result = match authorization {
                     None => Err(warp::reject::custom(AuthError(
                         "auth absent from request".to_string(),
                     ))),
@@ -588,7 +635,11 @@ fn auth_header_filter(jwt_key: JwtKey) -> warp::filters::BoxedFilter<()> {
                             )))
                         }
                     }
+                };
+                if result.is_err() {
+                    ctx.auth_failures.fetch_add(1, Ordering::Relaxed);
                 }
+                result
             }
         })
         .untuple_one()
@@ -701,9 +752,51 @@ pub fn serve<T: EthSpec>(
             )
         });
 
+    // `/script`
+    //
+    // Gets, replaces or clears the `PayloadScript`. Not authenticated, so that it can be driven
+    // by test tooling.
+    let script_filter = warp::path("script").and(warp::path::end());
+    let script_ctx_filter = {
+        let ctx = ctx.clone();
+        warp::any().map(move || ctx.clone())
+    };
+    let get_script = warp::get()
+        .and(script_filter)
+        .and(script_ctx_filter.clone())
+        .map(|ctx: Arc<Context<T>>| warp::reply::json(&*ctx.script.lock()).into_response());
+    let post_script = warp::post()
+        .and(script_filter)
+        .and(warp::body::json())
+        .and(script_ctx_filter.clone())
+        .map(
+            |script: PayloadScript, ctx: Arc<Context<T>>| match script.validate() {
+                Ok(()) => {
+                    *ctx.script.lock() = Some(script);
+                    warp::reply().into_response()
+                }
+                Err(message) => warp::reply::with_status(
+                    warp::reply::json(&ErrorMessage {
+                        code: StatusCode::BAD_REQUEST.as_u16(),
+                        message,
+                    }),
+                    StatusCode::BAD_REQUEST,
+                )
+                .into_response(),
+            },
+        );
+    let delete_script = warp::delete()
+        .and(script_filter)
+        .and(script_ctx_filter)
+        .map(|ctx: Arc<Context<T>>| {
+            *ctx.script.lock() = None;
+            warp::reply().into_response()
+        });
+
     let routes = warp::post()
-        .and(auth_header_filter(ctx.jwt_key.clone()))
+        .and(auth_header_filter(ctx.clone()))
         .and(root.or(echo))
+        .or(get_script.or(post_script).unify().or(delete_script).unify())
         .recover(handle_rejection)
         // Add a `Server` header.
         .map(|reply| warp::reply::with_header(reply, "Server", "lighthouse-mock-execution-client"));
@@ -0,0 +1,335 @@
+//! Scripted payload statuses for the mock execution engine.
+//!
+//! A `PayloadScript` maps payloads, identified by a block hash prefix or by the range of slots
+//! containing their timestamps, to the statuses returned by `newPayload` and `forkchoiceUpdated`,
+//! along with an artificial latency. Each rule may list several statuses, which are returned by
+//! successive calls for matching payloads with the last status repeated thereafter. This makes it
+//! possible to simulate an engine which returns e.g. `SYNCING`, then `INVALID`, then `VALID`.
+//!
+//! Example script:
+//!
+//! ```json
+//! {
+//!   "genesis_time": 1606824023,
+//!   "seconds_per_slot": 12,
+//!   "rules": [
+//!     { "block_hash_prefix": "0xdead", "new_payload": [{ "status": "INVALID_BLOCK_HASH" }] },
+//!     {
+//!       "slots": { "start": 10, "end": 20 },
+//!       "new_payload": [{ "status": "SYNCING" }],
+//!       "forkchoice_updated": [{ "status": "SYNCING" }, { "status": "VALID" }],
+//!       "latency_ms": 500
+//!     }
+//!   ]
+//! }
+//! ```
+use crate::json_structures::{JsonPayloadStatusV1, JsonPayloadStatusV1Status};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use types::{ExecutionBlockHash, Slot};
+
+/// A set of rules determining the responses of the mock execution engine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PayloadScript {
+    /// The genesis time of the beacon chain, required to match rules by slot.
+    #[serde(default)]
+    pub genesis_time: Option<u64>,
+    /// The slot duration of the beacon chain, required to match rules by slot.
+    #[serde(default)]
+    pub seconds_per_slot: Option<u64>,
+    /// The rules, of which the first matching a payload applies.
+    #[serde(default)]
+    pub rules: Vec<ScriptRule>,
+}
+
+/// Determines the responses for matching payloads.
+///
+/// A rule with neither `block_hash_prefix` nor `slots` matches every payload.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptRule {
+    /// Matches payloads whose block hash starts with this hex string, e.g. `0xab12`.
+    #[serde(default)]
+    pub block_hash_prefix: Option<String>,
+    /// Matches payloads whose timestamps fall within this inclusive range of slots.
+    #[serde(default)]
+    pub slots: Option<SlotRange>,
+    /// Statuses returned by successive `newPayload` calls. The natural status is returned if empty.
+    #[serde(default)]
+    pub new_payload: Vec<JsonPayloadStatusV1>,
+    /// Statuses returned by successive `forkchoiceUpdated` calls. The natural status is returned
+    /// if empty.
+    #[serde(default)]
+    pub forkchoice_updated: Vec<JsonPayloadStatusV1>,
+    /// Delay before responding to matching calls.
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    #[serde(skip)]
+    new_payload_calls: usize,
+    #[serde(skip)]
+    forkchoice_updated_calls: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlotRange {
+    pub start: Slot,
+    pub end: Slot,
+}
+
+/// The method for which a scripted response is requested.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptedMethod {
+    NewPayload,
+    ForkchoiceUpdated,
+}
+
+/// The response to a call, as determined by the script.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScriptedResponse {
+    /// The status to return instead of the natural status, if any.
+    pub status: Option<JsonPayloadStatusV1>,
+    pub latency: Option<Duration>,
+}
+
+impl PayloadScript {
+    /// Checks that rules matching by slot can be evaluated and that block hash prefixes are hex.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, rule) in self.rules.iter().enumerate() {
+            if rule.slots.is_some()
+                && (self.genesis_time.is_none() || self.seconds_per_slot.is_none())
+            {
+                return Err(format!(
+                    "rule {} matches by slot but genesis_time and seconds_per_slot are not both set",
+                    i
+                ));
+            }
+            if self.seconds_per_slot == Some(0) {
+                return Err("seconds_per_slot must be non-zero".to_string());
+            }
+            if let Some(prefix) = &rule.block_hash_prefix {
+                let hex = prefix.strip_prefix("0x").unwrap_or(prefix);
+                if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(format!(
+                        "rule {} has an invalid block_hash_prefix {}",
+                        i, prefix
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the scripted response to `method` for the payload with `block_hash` and
+    /// `timestamp`, advancing through the statuses of the first matching rule.
+    ///
+    /// The `timestamp` may be `None` if the payload is unknown, in which case rules matching by
+    /// slot do not apply.
+    pub fn respond(
+        &mut self,
+        method: ScriptedMethod,
+        block_hash: ExecutionBlockHash,
+        timestamp: Option<u64>,
+    ) -> ScriptedResponse {
+        let slot = match (timestamp, self.genesis_time, self.seconds_per_slot) {
+            (Some(timestamp), Some(genesis_time), Some(seconds_per_slot))
+                if seconds_per_slot > 0 =>
+            {
+                timestamp
+                    .checked_sub(genesis_time)
+                    .map(|since_genesis| Slot::new(since_genesis / seconds_per_slot))
+            }
+            _ => None,
+        };
+
+        let Some(rule) = self
+            .rules
+            .iter_mut()
+            .find(|rule| rule.matches(block_hash, slot))
+        else {
+            return ScriptedResponse::default();
+        };
+
+        let (statuses, calls) = match method {
+            ScriptedMethod::NewPayload => (&rule.new_payload, &mut rule.new_payload_calls),
+            ScriptedMethod::ForkchoiceUpdated => {
+                (&rule.forkchoice_updated, &mut rule.forkchoice_updated_calls)
+            }
+        };
+        let status = statuses
+            .get(*calls)
+            .or_else(|| statuses.last())
+            .cloned()
+            .map(|mut status| {
+                if status.status == JsonPayloadStatusV1Status::Valid
+                    && status.latest_valid_hash.is_none()
+                {
+                    status.latest_valid_hash = Some(block_hash);
+                }
+                status
+            });
+        *calls += 1;
+
+        ScriptedResponse {
+            status,
+            latency: rule.latency_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+impl ScriptRule {
+    fn matches(&self, block_hash: ExecutionBlockHash, slot: Option<Slot>) -> bool {
+        let hash_matches = self.block_hash_prefix.as_ref().map_or(true, |prefix| {
+            let prefix = prefix.strip_prefix("0x").unwrap_or(prefix).to_lowercase();
+            format!("{:x}", block_hash.into_root()).starts_with(&prefix)
+        });
+        let slot_matches = self.slots.map_or(true, |range| {
+            slot.map_or(false, |slot| range.start <= slot && slot <= range.end)
+        });
+        hash_matches && slot_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockServer;
+    use types::{Hash256, MainnetEthSpec};
+
+    fn status(status: JsonPayloadStatusV1Status) -> JsonPayloadStatusV1 {
+        JsonPayloadStatusV1 {
+            status,
+            latest_valid_hash: None,
+            validation_error: None,
+        }
+    }
+
+    fn script() -> PayloadScript {
+        serde_json::from_str(
+            r#"{
+                "genesis_time": 1000,
+                "seconds_per_slot": 12,
+                "rules": [
+                    {
+                        "block_hash_prefix": "0xab",
+                        "new_payload": [{ "status": "INVALID_BLOCK_HASH" }]
+                    },
+                    {
+                        "slots": { "start": "2", "end": "3" },
+                        "new_payload": [{ "status": "SYNCING" }],
+                        "forkchoice_updated": [
+                            { "status": "SYNCING" },
+                            { "status": "INVALID", "latestValidHash": null },
+                            { "status": "VALID" }
+                        ],
+                        "latency_ms": 5
+                    }
+                ]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn respond() {
+        let mut script = script();
+        script.validate().unwrap();
+
+        let hash_ab = ExecutionBlockHash::from_root(Hash256::repeat_byte(0xab));
+        let hash_cd = ExecutionBlockHash::from_root(Hash256::repeat_byte(0xcd));
+        let slot_2 = Some(1000 + 2 * 12);
+        let slot_4 = Some(1000 + 4 * 12);
+
+        // Matched by block hash, regardless of slot.
+        assert_eq!(
+            script.respond(ScriptedMethod::NewPayload, hash_ab, slot_4),
+            ScriptedResponse {
+                status: Some(status(JsonPayloadStatusV1Status::InvalidBlockHash)),
+                latency: None,
+            }
+        );
+        // No `forkchoiceUpdated` statuses are scripted for the first rule.
+        assert_eq!(
+            script.respond(ScriptedMethod::ForkchoiceUpdated, hash_ab, slot_4),
+            ScriptedResponse::default()
+        );
+
+        // Matched by slot, stepping through the statuses and repeating the last.
+        let latency = Some(Duration::from_millis(5));
+        let mut valid = status(JsonPayloadStatusV1Status::Valid);
+        valid.latest_valid_hash = Some(hash_cd);
+        for expected in [
+            status(JsonPayloadStatusV1Status::Syncing),
+            status(JsonPayloadStatusV1Status::Invalid),
+            valid.clone(),
+            valid,
+        ] {
+            assert_eq!(
+                script.respond(ScriptedMethod::ForkchoiceUpdated, hash_cd, slot_2),
+                ScriptedResponse {
+                    status: Some(expected),
+                    latency,
+                }
+            );
+        }
+
+        // Unmatched payloads, including unknown payloads which cannot be matched by slot.
+        assert_eq!(
+            script.respond(ScriptedMethod::NewPayload, hash_cd, slot_4),
+            ScriptedResponse::default()
+        );
+        assert_eq!(
+            script.respond(ScriptedMethod::NewPayload, hash_cd, None),
+            ScriptedResponse::default()
+        );
+    }
+
+    #[test]
+    fn invalid_scripts() {
+        let mut script = script();
+        script.genesis_time = None;
+        assert!(script.validate().is_err());
+
+        let mut script = PayloadScript::default();
+        script.rules.push(ScriptRule {
+            block_hash_prefix: Some("0xzz".into()),
+            ..ScriptRule::default()
+        });
+        assert!(script.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn control_endpoint() {
+        let server = MockServer::<MainnetEthSpec>::unit_testing();
+        let url = format!("{}/script", server.url());
+        let client = reqwest::Client::new();
+
+        let get_script = || async {
+            client
+                .get(&url)
+                .send()
+                .await
+                .unwrap()
+                .json::<Option<PayloadScript>>()
+                .await
+                .unwrap()
+        };
+        assert_eq!(get_script().await, None);
+
+        let response = client.post(&url).json(&script()).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(get_script().await, Some(script()));
+
+        // Invalid scripts are rejected, leaving the current script in place.
+        let mut invalid = script();
+        invalid.seconds_per_slot = None;
+        let response = client.post(&url).json(&invalid).send().await.unwrap();
+        assert_eq!(response.status(), 400);
+        assert_eq!(get_script().await, Some(script()));
+
+        let response = client.delete(&url).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(get_script().await, None);
+    }
+}
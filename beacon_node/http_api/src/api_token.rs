@@ -0,0 +1,136 @@
+//! Bearer token authentication for the `lighthouse/admin` namespace.
+//!
+//! The token is generated randomly and stored in the data directory, similarly to the validator
+//! client's `api-token.txt`. Requests to admin endpoints must supply it in an
+//! `Authorization: Bearer <token>` header, otherwise they are rejected with a 403.
+//!
+//! The admin endpoints can be disabled entirely, in which case no token is generated and every
+//! request to them is rejected.
+
+use filesystem::create_with_600_perms;
+use rand::{thread_rng, Rng};
+use slog::{warn, Logger};
+use std::fs;
+use std::path::Path;
+use warp::filters::BoxedFilter;
+use warp::Filter;
+
+/// The name of the file which stores the token, within the data directory.
+pub const API_TOKEN_FILENAME: &str = "api-token.txt";
+
+/// The prefix of generated tokens, which makes them recognisable.
+const API_TOKEN_PREFIX: &str = "api-token-";
+
+/// The number of random bytes in generated tokens.
+const API_TOKEN_LEN: usize = 32;
+
+pub struct ApiToken {
+    /// The token, or `None` if the admin endpoints are disabled.
+    token: Option<String>,
+}
+
+impl ApiToken {
+    /// Reads the token from `path`, first generating a new token and writing it to `path` if the
+    /// file does not exist.
+    pub fn create_or_open(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            let bytes: [u8; API_TOKEN_LEN] = thread_rng().gen();
+            let token = format!("{}{}", API_TOKEN_PREFIX, hex::encode(bytes));
+            create_with_600_perms(path, token.as_bytes()).map_err(|e| {
+                format!(
+                    "Unable to create file with permissions for {:?}: {:?}",
+                    path, e
+                )
+            })?;
+        }
+
+        let token = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read API token from {:?}: {}", path, e))?
+            .trim_end()
+            .to_string();
+        if token.is_empty() {
+            return Err(format!("API token file {:?} is empty", path));
+        }
+
+        Ok(Self { token: Some(token) })
+    }
+
+    /// Returns a token which rejects every request, without reading or creating a token file.
+    pub fn disabled() -> Self {
+        Self { token: None }
+    }
+
+    /// Returns the token, or `None` if the admin endpoints are disabled.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Returns a filter which rejects requests that do not supply the token in the
+    /// `Authorization` header, or all requests if the admin endpoints are disabled.
+    pub fn authorization_filter(
+        &self,
+    ) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+        let expected = self.token().map(|token| format!("Bearer {}", token));
+        warp::header::optional::<String>("Authorization")
+            .and_then(move |header: Option<String>| {
+                let result = match (&expected, header) {
+                    (None, _) => Err(warp_utils::reject::invalid_auth(
+                        "admin endpoints are disabled".to_string(),
+                    )),
+                    (Some(expected), Some(header))
+                        if constant_time_eq(header.as_bytes(), expected.as_bytes()) =>
+                    {
+                        Ok(())
+                    }
+                    (Some(_), Some(_)) => Err(warp_utils::reject::invalid_auth(
+                        "incorrect admin API token".to_string(),
+                    )),
+                    (Some(_), None) => Err(warp_utils::reject::invalid_auth(
+                        "missing Authorization header".to_string(),
+                    )),
+                };
+                async move { result }
+            })
+            .untuple_one()
+    }
+
+    /// Returns a filter matching `lighthouse/admin/{segments}` for requests supplying the token.
+    ///
+    /// The deprecated `lighthouse/{segments}` alias is also matched, logging a warning, until
+    /// existing users have migrated to the admin namespace. It requires the token too, so that the
+    /// alias can't be used to bypass authentication.
+    pub fn admin_path(&self, segments: &[&'static str], log: Logger) -> BoxedFilter<()> {
+        let path = segments
+            .iter()
+            .fold(warp::any().boxed(), |filter, segment| {
+                filter.and(warp::path(*segment)).boxed()
+            })
+            .and(warp::path::end());
+        let alias = format!("/lighthouse/{}", segments.join("/"));
+
+        let admin = warp::path("lighthouse")
+            .and(warp::path("admin"))
+            .and(path.clone())
+            .and(self.authorization_filter());
+        let deprecated_alias = warp::path("lighthouse")
+            .and(path)
+            .and(self.authorization_filter())
+            .map(move || {
+                warn!(
+                    log,
+                    "Deprecated admin endpoint used";
+                    "path" => &alias,
+                    "msg" => "use the /lighthouse/admin namespace with the admin API token"
+                );
+            })
+            .untuple_one();
+
+        admin.or(deprecated_alias).unify().boxed()
+    }
+}
+
+/// Compares `a` and `b` in time which depends only on their lengths, so that the token cannot be
+/// recovered by timing requests.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
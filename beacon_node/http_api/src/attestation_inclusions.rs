@@ -0,0 +1,127 @@
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes, WhenSlotSkipped};
+use eth2::lighthouse::{
+    AttestationInclusion, AttestationInclusionProof, AttestationInclusionQuery,
+};
+use eth2::types::ValidatorId;
+use std::cmp;
+use types::{EthSpec, RelativeEpoch};
+
+/// Returns the attestations by a validator which were included in the canonical blocks of
+/// `query.epoch`, optionally with merkle proofs of their inclusion in each block.
+pub fn validator_attestation_inclusions<T: BeaconChainTypes>(
+    validator_id: &ValidatorId,
+    query: AttestationInclusionQuery,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<AttestationInclusion>, warp::Rejection> {
+    let epoch = query.epoch;
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let head_slot = chain.canonical_head.cached_head().head_slot();
+    if epoch > head_slot.epoch(slots_per_epoch) {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "epoch {epoch} is later than the head"
+        )));
+    }
+
+    // Only attestations from the epoch and the previous epoch can be included in the blocks of the
+    // epoch, and the state at the end of the epoch has the committees of both.
+    let state_slot = cmp::min(epoch.end_slot(slots_per_epoch), head_slot);
+    let (mut state, _execution_optimistic, _finalized) =
+        StateId::from_slot(state_slot).state(chain)?;
+
+    state
+        .update_pubkey_cache()
+        .map_err(warp_utils::reject::beacon_state_error)?;
+
+    let validator_index = match validator_id {
+        ValidatorId::Index(index) => *index as usize,
+        ValidatorId::PublicKey(pubkey) => state
+            .get_validator_index(pubkey)
+            .map_err(warp_utils::reject::beacon_state_error)?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_not_found(format!("unknown validator: {pubkey}"))
+            })?,
+    };
+    if validator_index >= state.validators().len() {
+        return Err(warp_utils::reject::custom_not_found(format!(
+            "unknown validator: {validator_index}"
+        )));
+    }
+
+    let mut duties = vec![];
+    for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+        state
+            .build_committee_cache(relative_epoch, &chain.spec)
+            .map_err(warp_utils::reject::beacon_state_error)?;
+        if let Some(duty) = state
+            .get_attestation_duties(validator_index, relative_epoch)
+            .map_err(warp_utils::reject::beacon_state_error)?
+        {
+            // In the genesis epoch the previous epoch is the current epoch.
+            if !duties.contains(&duty) {
+                duties.push(duty);
+            }
+        }
+    }
+
+    let mut inclusions = vec![];
+    for slot in epoch.slot_iter(slots_per_epoch) {
+        if slot > head_slot {
+            break;
+        }
+        let Some(block_root) = chain
+            .block_root_at_slot(slot, WhenSlotSkipped::None)
+            .map_err(warp_utils::reject::beacon_chain_error)?
+        else {
+            continue;
+        };
+        let block = chain
+            .get_blinded_block(&block_root)
+            .map_err(warp_utils::reject::beacon_chain_error)?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_server_error(format!(
+                    "missing canonical block {block_root:?}"
+                ))
+            })?;
+
+        let attestations = block.message().body().attestations();
+        for (attestation_index, attestation) in attestations.iter().enumerate() {
+            let Some(duty) = duties.iter().find(|duty| {
+                duty.slot == attestation.data.slot && duty.index == attestation.data.index
+            }) else {
+                continue;
+            };
+            if !attestation
+                .aggregation_bits
+                .get(duty.committee_position)
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let proof = if query.proof {
+                let (generalized_index, leaf, branch) = block
+                    .message()
+                    .attestation_proof(attestation_index)
+                    .map_err(warp_utils::reject::beacon_state_error)?;
+                Some(AttestationInclusionProof {
+                    generalized_index,
+                    leaf,
+                    branch,
+                })
+            } else {
+                None
+            };
+
+            inclusions.push(AttestationInclusion {
+                block_root,
+                block_slot: slot,
+                attestation_index,
+                attestation_slot: attestation.data.slot,
+                proof,
+            });
+        }
+    }
+
+    Ok(inclusions)
+}
@@ -1,14 +1,25 @@
-//! Contains the handler for the `GET validator/duties/attester/{epoch}` endpoint.
+//! Contains the handlers for the `GET validator/duties/attester/{epoch}` endpoint and the
+//! `GET lighthouse/validator/duties/attester/{epoch}/committees` extension.
 
 use crate::state_id::StateId;
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{AttesterCommittee, AttesterDataWithCommittee};
 use eth2::types::{self as api_types};
 use slot_clock::SlotClock;
 use state_processing::state_advance::partial_state_advance;
+use std::collections::HashMap;
 use types::{
-    AttestationDuty, BeaconState, ChainSpec, CloneConfig, Epoch, EthSpec, Hash256, RelativeEpoch,
+    AttestationDuty, BeaconState, ChainSpec, CloneConfig, CommitteeCache, CommitteeIndex, Epoch,
+    EthSpec, Hash256, RelativeEpoch, Slot,
 };
 
+/// The maximum number of validators for which the ordered committees will be included in an
+/// attester duties response.
+///
+/// Each committee may contain up to `MAX_VALIDATORS_PER_COMMITTEE` indices, so this limit bounds
+/// the size of the response.
+pub const MAX_INCLUDE_COMMITTEE_VALIDATORS: usize = 64;
+
 /// The struct that is returned to the requesting HTTP client.
 type ApiDuties = api_types::DutiesResponse<Vec<api_types::AttesterData>>;
 
@@ -18,6 +29,150 @@ pub fn attester_duties<T: BeaconChainTypes>(
     request_indices: &[u64],
     chain: &BeaconChain<T>,
 ) -> Result<ApiDuties, warp::reject::Rejection> {
+    let (duties, dependent_root, execution_optimistic) =
+        with_attester_shuffling(request_epoch, chain, |committee_cache| {
+            get_attestation_duties(committee_cache, request_indices)
+        })?;
+
+    convert_to_api_response(
+        duties,
+        request_indices,
+        dependent_root,
+        execution_optimistic,
+        chain,
+    )
+}
+
+/// Handles a request from the HTTP API for attester duties which includes the ordered committee
+/// of each duty.
+///
+/// Rejects requests for more than `MAX_INCLUDE_COMMITTEE_VALIDATORS` validators.
+pub fn attester_duties_with_committees<T: BeaconChainTypes>(
+    request_epoch: Epoch,
+    request_indices: &[u64],
+    chain: &BeaconChain<T>,
+) -> Result<api_types::DutiesResponse<Vec<AttesterDataWithCommittee>>, warp::reject::Rejection> {
+    if request_indices.len() > MAX_INCLUDE_COMMITTEE_VALIDATORS {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "committees can only be included for up to {} validators, requested {}",
+            MAX_INCLUDE_COMMITTEE_VALIDATORS,
+            request_indices.len()
+        )));
+    }
+
+    let ((duties, committees), dependent_root, execution_optimistic) =
+        with_attester_shuffling(request_epoch, chain, |committee_cache| {
+            let duties = get_attestation_duties(committee_cache, request_indices);
+            let committees = duties
+                .iter()
+                .flatten()
+                .filter_map(|duty| {
+                    let committee = get_committee(committee_cache, duty.slot, duty.index)?;
+                    Some(((duty.slot, duty.index), committee))
+                })
+                .collect::<HashMap<_, _>>();
+            (duties, committees)
+        })?;
+
+    let response = convert_to_api_response(
+        duties,
+        request_indices,
+        dependent_root,
+        execution_optimistic,
+        chain,
+    )?;
+
+    let data = response
+        .data
+        .into_iter()
+        .map(|duty| {
+            let committee = committees
+                .get(&(duty.slot, duty.committee_index))
+                .cloned()
+                .ok_or_else(|| {
+                    warp_utils::reject::custom_server_error(format!(
+                        "missing committee {} at slot {}",
+                        duty.committee_index, duty.slot
+                    ))
+                })?;
+            Ok(AttesterDataWithCommittee { duty, committee })
+        })
+        .collect::<Result<_, warp::reject::Rejection>>()?;
+
+    Ok(api_types::DutiesResponse {
+        dependent_root: response.dependent_root,
+        execution_optimistic: response.execution_optimistic,
+        data,
+    })
+}
+
+/// Handles a request from the HTTP API for the ordered validator indices of a single committee.
+pub fn attester_committee<T: BeaconChainTypes>(
+    request_epoch: Epoch,
+    slot: Slot,
+    index: CommitteeIndex,
+    chain: &BeaconChain<T>,
+) -> Result<api_types::DutiesResponse<AttesterCommittee>, warp::reject::Rejection> {
+    if slot.epoch(T::EthSpec::slots_per_epoch()) != request_epoch {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "slot {} is not in epoch {}",
+            slot, request_epoch
+        )));
+    }
+
+    let (committee, dependent_root, execution_optimistic) =
+        with_attester_shuffling(request_epoch, chain, |committee_cache| {
+            get_committee(committee_cache, slot, index)
+        })?;
+
+    let validators = committee.ok_or_else(|| {
+        warp_utils::reject::custom_not_found(format!(
+            "no committee with index {} at slot {}",
+            index, slot
+        ))
+    })?;
+
+    Ok(api_types::DutiesResponse {
+        dependent_root,
+        execution_optimistic: Some(execution_optimistic),
+        data: AttesterCommittee {
+            slot,
+            index,
+            validators,
+        },
+    })
+}
+
+fn get_attestation_duties(
+    committee_cache: &CommitteeCache,
+    request_indices: &[u64],
+) -> Vec<Option<AttestationDuty>> {
+    request_indices
+        .iter()
+        .map(|&validator_index| committee_cache.get_attestation_duties(validator_index as usize))
+        .collect()
+}
+
+fn get_committee(
+    committee_cache: &CommitteeCache,
+    slot: Slot,
+    index: CommitteeIndex,
+) -> Option<Vec<u64>> {
+    let committee = committee_cache.get_beacon_committee(slot, index)?;
+    Some(committee.committee.iter().map(|&i| i as u64).collect())
+}
+
+/// Calls `map_fn` with the attester shuffling for `request_epoch`, returning its result along
+/// with the dependent root of the shuffling and whether it was computed from an optimistic block.
+fn with_attester_shuffling<T, F, R>(
+    request_epoch: Epoch,
+    chain: &BeaconChain<T>,
+    map_fn: F,
+) -> Result<(R, Hash256, bool), warp::reject::Rejection>
+where
+    T: BeaconChainTypes,
+    F: Fn(&CommitteeCache) -> R,
+{
     let current_epoch = chain
         .epoch()
         .map_err(warp_utils::reject::beacon_chain_error)?;
@@ -38,7 +193,7 @@ pub fn attester_duties<T: BeaconChainTypes>(
         || request_epoch == current_epoch + 1
         || request_epoch == tolerant_current_epoch + 1
     {
-        cached_attestation_duties(request_epoch, request_indices, chain)
+        cached_attester_shuffling(request_epoch, chain, map_fn)
     } else if request_epoch > current_epoch + 1 {
         Err(warp_utils::reject::custom_bad_request(format!(
             "request epoch {} is more than one epoch past the current epoch {}",
@@ -46,37 +201,56 @@ pub fn attester_duties<T: BeaconChainTypes>(
         )))
     } else {
         // request_epoch < current_epoch, in fact we only allow `request_epoch == current_epoch-1` in this case
-        compute_historic_attester_duties(request_epoch, request_indices, chain)
+        compute_historic_attester_shuffling(request_epoch, chain, map_fn)
     }
 }
 
-fn cached_attestation_duties<T: BeaconChainTypes>(
+fn cached_attester_shuffling<T, F, R>(
     request_epoch: Epoch,
-    request_indices: &[u64],
     chain: &BeaconChain<T>,
-) -> Result<ApiDuties, warp::reject::Rejection> {
+    map_fn: F,
+) -> Result<(R, Hash256, bool), warp::reject::Rejection>
+where
+    T: BeaconChainTypes,
+    F: Fn(&CommitteeCache) -> R,
+{
     let head_block_root = chain.canonical_head.cached_head().head_block_root();
 
-    let (duties, dependent_root, execution_status) = chain
-        .validator_attestation_duties(request_indices, request_epoch, head_block_root)
+    let execution_status = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block_execution_status(&head_block_root)
+        .ok_or(BeaconChainError::AttestationHeadNotInForkChoice(
+            head_block_root,
+        ))
         .map_err(warp_utils::reject::beacon_chain_error)?;
 
-    convert_to_api_response(
-        duties,
-        request_indices,
+    let (result, dependent_root) = chain
+        .with_committee_cache(
+            head_block_root,
+            request_epoch,
+            |committee_cache, dependent_root| Ok((map_fn(committee_cache), dependent_root)),
+        )
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+    Ok((
+        result,
         dependent_root,
         execution_status.is_optimistic_or_invalid(),
-        chain,
-    )
+    ))
 }
 
-/// Compute some attester duties by reading a `BeaconState` from disk, completely ignoring the
+/// Compute an attester shuffling by reading a `BeaconState` from disk, completely ignoring the
 /// shuffling cache.
-fn compute_historic_attester_duties<T: BeaconChainTypes>(
+fn compute_historic_attester_shuffling<T, F, R>(
     request_epoch: Epoch,
-    request_indices: &[u64],
     chain: &BeaconChain<T>,
-) -> Result<ApiDuties, warp::reject::Rejection> {
+    map_fn: F,
+) -> Result<(R, Hash256, bool), warp::reject::Rejection>
+where
+    T: BeaconChainTypes,
+    F: Fn(&CommitteeCache) -> R,
+{
     // If the head is quite old then it might still be relevant for a historical request.
     //
     // Avoid holding the `cached_head` longer than necessary.
@@ -142,23 +316,16 @@ fn compute_historic_attester_duties<T: BeaconChainTypes>(
         .map_err(BeaconChainError::from)
         .map_err(warp_utils::reject::beacon_chain_error)?;
 
-    let duties = request_indices
-        .iter()
-        .map(|&validator_index| {
-            state
-                .get_attestation_duties(validator_index as usize, relative_epoch)
-                .map_err(BeaconChainError::from)
-        })
-        .collect::<Result<_, _>>()
+    let committee_cache = state
+        .committee_cache(relative_epoch)
+        .map_err(BeaconChainError::from)
         .map_err(warp_utils::reject::beacon_chain_error)?;
 
-    convert_to_api_response(
-        duties,
-        request_indices,
+    Ok((
+        map_fn(committee_cache),
         dependent_root,
         execution_optimistic,
-        chain,
-    )
+    ))
 }
 
 fn ensure_state_knows_attester_duties_for_epoch<E: EthSpec>(
@@ -0,0 +1,61 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{BlobPropagation, BlobPropagationStatus};
+use eth2::types::GenericResponse;
+use slot_clock::SlotClock;
+use std::collections::HashSet;
+use std::sync::Arc;
+use types::Hash256;
+use warp_utils::reject::{beacon_chain_error, custom_not_found};
+
+/// Handler for `GET lighthouse/beacon/blob_propagation/{block_root}`.
+///
+/// Reports, for each blob committed to by the block, whether it has been seen on gossip and
+/// whether it has been stored. Gossip observations are pruned on finalization, so blobs of
+/// finalized blocks are only reported as stored.
+pub fn blob_propagation<T: BeaconChainTypes>(
+    block_root: Hash256,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<BlobPropagation>, warp::Rejection> {
+    let block = chain
+        .get_blinded_block(&block_root)
+        .map_err(beacon_chain_error)?
+        .ok_or_else(|| custom_not_found(format!("block {:?} is not known", block_root)))?;
+    let slot = block.slot();
+    let num_blobs = block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .map_or(0, |commitments| commitments.len());
+
+    let first_seen_times = chain.blob_first_seen_times(block_root, slot);
+    let stored_indices = chain
+        .get_blobs(&block_root)
+        .map_err(beacon_chain_error)?
+        .iter()
+        .map(|blob| blob.index)
+        .collect::<HashSet<_>>();
+    let slot_start_ms = chain
+        .slot_clock
+        .start_of(slot)
+        .map(|start| start.as_millis() as u64);
+
+    let blobs = (0..num_blobs as u64)
+        .map(|index| {
+            let first_seen_ms = first_seen_times.get(&index).copied();
+            BlobPropagationStatus {
+                index,
+                seen_on_gossip: first_seen_ms.is_some(),
+                seen_delay_ms: first_seen_ms
+                    .zip(slot_start_ms)
+                    .map(|(seen, start)| seen.saturating_sub(start)),
+                in_db: stored_indices.contains(&index),
+            }
+        })
+        .collect();
+
+    Ok(GenericResponse::from(BlobPropagation {
+        block_root,
+        slot,
+        blobs,
+    }))
+}
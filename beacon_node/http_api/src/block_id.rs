@@ -1,4 +1,5 @@
-use crate::{state_id::checkpoint_slot_and_execution_optimistic, ExecutionOptimistic};
+use crate::state_id::{checkpoint_slot_and_execution_optimistic, head_is_finalized};
+use crate::ExecutionOptimistic;
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
 use eth2::types::BlobIndicesQuery;
 use eth2::types::BlockId as CoreBlockId;
@@ -37,7 +38,7 @@ impl BlockId {
                 Ok((
                     cached_head.head_block_root(),
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&cached_head),
                 ))
             }
             CoreBlockId::Genesis => Ok((chain.genesis_block_root, false, true)),
@@ -49,11 +50,14 @@ impl BlockId {
                 Ok((finalized_checkpoint.root, execution_optimistic, true))
             }
             CoreBlockId::Justified => {
-                let justified_checkpoint =
-                    chain.canonical_head.cached_head().justified_checkpoint();
+                let cached_head = chain.canonical_head.cached_head();
+                let justified_checkpoint = cached_head.justified_checkpoint();
                 let (_slot, execution_optimistic) =
                     checkpoint_slot_and_execution_optimistic(chain, justified_checkpoint)?;
-                Ok((justified_checkpoint.root, execution_optimistic, false))
+                // The justified checkpoint is only finalized if it is also the finalized one.
+                let finalized =
+                    justified_checkpoint.epoch <= cached_head.finalized_checkpoint().epoch;
+                Ok((justified_checkpoint.root, execution_optimistic, finalized))
             }
             CoreBlockId::Slot(slot) => {
                 let execution_optimistic = chain
@@ -144,7 +148,7 @@ impl BlockId {
                 Ok((
                     cached_head.snapshot.beacon_block.clone_as_blinded(),
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&cached_head),
                 ))
             }
             CoreBlockId::Slot(slot) => {
@@ -207,7 +211,7 @@ impl BlockId {
                 Ok((
                     cached_head.snapshot.beacon_block.clone(),
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&cached_head),
                 ))
             }
             CoreBlockId::Slot(slot) => {
@@ -0,0 +1,38 @@
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{CacheInfo, CachesPatchRequest};
+use eth2::types::GenericResponse;
+use std::sync::Arc;
+
+/// Handler for `GET lighthouse/caches`.
+pub fn info<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<Vec<CacheInfo>>, warp::Rejection> {
+    chain
+        .cache_info()
+        .map(GenericResponse::from)
+        .map_err(warp_utils::reject::beacon_chain_error)
+}
+
+/// Handler for `PATCH lighthouse/admin/caches`.
+///
+/// Returns the updated contents and configuration of all caches.
+pub fn resize<T: BeaconChainTypes>(
+    request: CachesPatchRequest,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<Vec<CacheInfo>>, warp::Rejection> {
+    chain
+        .resize_caches(&request.capacities)
+        .map_err(|e| match e {
+            BeaconChainError::CacheNotResizable(name) => {
+                warp_utils::reject::custom_bad_request(format!("{} cannot be resized", name))
+            }
+            BeaconChainError::InvalidCacheCapacity { name, capacity } => {
+                warp_utils::reject::custom_bad_request(format!(
+                    "invalid capacity for {}: {}",
+                    name, capacity
+                ))
+            }
+            e => warp_utils::reject::beacon_chain_error(e),
+        })?;
+    info(chain)
+}
@@ -1,6 +1,7 @@
 use beacon_chain::store::metadata::CURRENT_SCHEMA_VERSION;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2::lighthouse::DatabaseInfo;
+use eth2::lighthouse::{DatabaseCompaction, DatabaseInfo};
+use slog::{info, Logger};
 use std::sync::Arc;
 
 pub fn info<T: BeaconChainTypes>(
@@ -20,3 +21,25 @@ pub fn info<T: BeaconChainTypes>(
         blob_info,
     })
 }
+
+/// Compact the hot database, blocking until the compaction is complete.
+pub fn compact<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    log: Logger,
+) -> Result<DatabaseCompaction, warp::Rejection> {
+    info!(log, "Starting database compaction"; "trigger" => "http api");
+    let summary = chain.store.compact().map_err(|e| {
+        warp_utils::reject::custom_server_error(format!("compaction failed: {:?}", e))
+    })?;
+    info!(
+        log,
+        "Database compaction complete";
+        "duration" => ?summary.duration,
+        "reclaimed_bytes" => summary.reclaimed_bytes,
+    );
+
+    Ok(DatabaseCompaction {
+        duration_ms: summary.duration.as_millis() as u64,
+        reclaimed_bytes: summary.reclaimed_bytes,
+    })
+}
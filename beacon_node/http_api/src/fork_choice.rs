@@ -0,0 +1,59 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes, HeadRecompute};
+use eth2::lighthouse::{BlockExecutionStatusData, ForkChoiceRecompute};
+use eth2::types::GenericResponse;
+use std::sync::Arc;
+use types::Hash256;
+
+/// Handler for `POST lighthouse/fork_choice/recompute`.
+pub async fn recompute<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<ForkChoiceRecompute>, warp::Rejection> {
+    let recompute = chain
+        .recompute_head_manually()
+        .await
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    Ok(GenericResponse::from(to_api_recompute(recompute)))
+}
+
+/// Handler for `GET lighthouse/fork_choice/last_recompute`.
+pub fn last_recompute<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<Option<ForkChoiceRecompute>>, warp::Rejection> {
+    let last_recompute = chain.canonical_head.last_recompute().map(to_api_recompute);
+    Ok(GenericResponse::from(last_recompute))
+}
+
+/// Handler for `GET lighthouse/blocks/{block_root}/execution_status`.
+pub fn block_execution_status<T: BeaconChainTypes>(
+    block_root: Hash256,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<BlockExecutionStatusData>, warp::Rejection> {
+    let block = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&block_root)
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(format!(
+                "block {:?} is not known to fork choice",
+                block_root
+            ))
+        })?;
+    Ok(GenericResponse::from(BlockExecutionStatusData {
+        block_root,
+        slot: block.slot,
+        status: block.execution_status.into(),
+        execution_block_hash: block.execution_status.block_hash(),
+    }))
+}
+
+fn to_api_recompute(recompute: HeadRecompute) -> ForkChoiceRecompute {
+    ForkChoiceRecompute {
+        slot: recompute.slot,
+        started_at_ms: recompute.started_at.as_millis() as u64,
+        elapsed_ms: recompute.elapsed.as_millis() as u64,
+        old_head_block_root: recompute.old_head_block_root,
+        new_head_block_root: recompute.new_head_block_root,
+        head_changed: recompute.head_changed(),
+        manual: recompute.manual,
+    }
+}
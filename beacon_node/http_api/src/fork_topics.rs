@@ -0,0 +1,66 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ForkTopics, ForkTopicsQuery};
+use lighthouse_network::{types::fork_gossip_topics, NetworkGlobals};
+use network::service::SUBSCRIBE_DELAY_SLOTS;
+use std::sync::Arc;
+use types::{ChainSpec, EthSpec, ForkName, Hash256};
+
+/// Handler for `GET lighthouse/network/fork_topics`.
+pub fn get_fork_topics<T: BeaconChainTypes>(
+    query: ForkTopicsQuery,
+    chain: Arc<BeaconChain<T>>,
+    network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+) -> Result<ForkTopics, warp::Rejection> {
+    Ok(compute_fork_topics::<T::EthSpec>(
+        query.fork,
+        &chain.spec,
+        chain.genesis_validators_root,
+        chain.genesis_time,
+        network_globals.config.enable_light_client_server,
+        network_globals.config.subscribe_all_subnets,
+    ))
+}
+
+/// Compute the fork digest and gossip topics for `fork`, along with the time at which the network
+/// service will subscribe to them.
+pub fn compute_fork_topics<E: EthSpec>(
+    fork: ForkName,
+    spec: &ChainSpec,
+    genesis_validators_root: Hash256,
+    genesis_time: u64,
+    enable_light_client_server: bool,
+    subscribe_all_subnets: bool,
+) -> ForkTopics {
+    let fork_digest =
+        ChainSpec::compute_fork_digest(spec.fork_version_for_name(fork), genesis_validators_root);
+    let fork_epoch = spec.fork_epoch(fork);
+
+    // Topics for the next fork are subscribed to `SUBSCRIBE_DELAY_SLOTS` before the fork.
+    let subscription_slot = fork_epoch.map(|epoch| {
+        epoch
+            .start_slot(E::slots_per_epoch())
+            .saturating_sub(SUBSCRIBE_DELAY_SLOTS)
+    });
+    let subscription_time = subscription_slot.map(|slot| {
+        genesis_time.saturating_add(slot.as_u64().saturating_mul(spec.seconds_per_slot))
+    });
+
+    let topics = fork_gossip_topics::<E>(
+        fork,
+        fork_digest,
+        enable_light_client_server,
+        subscribe_all_subnets,
+    )
+    .into_iter()
+    .map(String::from)
+    .collect();
+
+    ForkTopics {
+        fork,
+        fork_epoch,
+        fork_digest,
+        subscription_slot,
+        subscription_time,
+        topics,
+    }
+}
@@ -0,0 +1,36 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{GossipRejection, GossipReplayRequest, GossipReplayResponse};
+use lighthouse_network::NetworkGlobals;
+use std::sync::Arc;
+use types::EthSpec;
+
+/// Handler for `GET lighthouse/network/gossip/rejections`.
+///
+/// Returns an empty list unless enabled with `--gossip-rejection-buffer-size`.
+pub fn get_rejections<E: EthSpec>(network_globals: &NetworkGlobals<E>) -> Vec<GossipRejection> {
+    network_globals
+        .gossip_rejections
+        .rejections()
+        .into_iter()
+        .map(|rejection| GossipRejection {
+            topic: rejection.topic,
+            peer_id: rejection.peer_id.to_string(),
+            data: rejection.data,
+            reason: rejection.reason,
+            seen_timestamp_ms: rejection.seen_timestamp.as_millis() as u64,
+        })
+        .collect()
+}
+
+/// Handler for `POST lighthouse/admin/network/gossip/replay`.
+pub fn replay<T: BeaconChainTypes>(
+    request: GossipReplayRequest,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GossipReplayResponse, warp::Rejection> {
+    let error = network::replay_gossip_message(&chain, &request.topic, &request.data)
+        .map_err(warp_utils::reject::custom_bad_request)?;
+    Ok(GossipReplayResponse {
+        accepted: error.is_none(),
+        error,
+    })
+}
@@ -0,0 +1,32 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{BeaconNodeGraffiti, GraffitiPostRequest};
+use eth2::types::GenericResponse;
+use slog::{info, Logger};
+use std::sync::Arc;
+
+/// Handler for `GET lighthouse/graffiti`.
+pub fn info<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<BeaconNodeGraffiti>, warp::Rejection> {
+    Ok(GenericResponse::from(BeaconNodeGraffiti {
+        graffiti: chain.graffiti().as_utf8_lossy(),
+        validator_graffiti_takes_precedence: true,
+    }))
+}
+
+/// Handler for `POST lighthouse/admin/graffiti`.
+///
+/// Returns the updated default graffiti, which is used from the next block produced.
+pub fn update<T: BeaconChainTypes>(
+    request: GraffitiPostRequest,
+    chain: Arc<BeaconChain<T>>,
+    log: Logger,
+) -> Result<GenericResponse<BeaconNodeGraffiti>, warp::Rejection> {
+    chain.set_graffiti(request.graffiti.into());
+    info!(
+        log,
+        "Updated default graffiti";
+        "graffiti" => chain.graffiti().as_utf8_lossy(),
+    );
+    info(chain)
+}
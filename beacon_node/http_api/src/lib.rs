@@ -5,17 +5,32 @@
 //! There are also some additional, non-standard endpoints behind the `/lighthouse/` path which are
 //! used for development.
 
+mod api_token;
+mod attestation_inclusions;
 mod attestation_performance;
 mod attester_duties;
+mod blob_propagation;
 mod block_id;
 mod block_packing_efficiency;
 mod block_rewards;
 mod build_block_contents;
 mod builder_states;
+mod caches;
 mod database;
+mod fork_choice;
+mod fork_topics;
+mod gossip_rejections;
+mod graffiti;
+mod log_filters;
 mod metrics;
+mod network_discovery;
+mod network_enr;
 mod proposer_duties;
+mod proposer_preparation;
 mod publish_blocks;
+mod request_limiter;
+mod request_tracing;
+mod ssz_body;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -23,34 +38,41 @@ mod sync_committees;
 mod task_spawner;
 pub mod test_utils;
 mod ui;
+mod unix_socket;
 mod validator;
 mod validator_inclusion;
 mod version;
 
+use api_token::ApiToken;
+pub use attester_duties::MAX_INCLUDE_COMMITTEE_VALIDATORS;
 use beacon_chain::{
     attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
     validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
     BeaconChainTypes, ProduceBlockVerification, WhenSlotSkipped,
 };
-use beacon_processor::BeaconProcessorSend;
+use beacon_processor::work_reprocessing_queue::ReprocessQueueSnapshotSend;
+use beacon_processor::{BeaconProcessorSend, BeaconProcessorSnapshotSend};
 pub use block_id::BlockId;
 use builder_states::get_next_withdrawals;
 use bytes::Bytes;
 use directory::DEFAULT_ROOT_DIR;
 use eth2::types::{
     self as api_types, BroadcastValidation, EndpointVersion, ForkChoice, ForkChoiceNode,
-    SignedBlindedBlockContents, SignedBlockContents, SkipRandaoVerification, ValidatorId,
-    ValidatorStatus,
+    ForkChoiceNodeExtraData, SignedBlindedBlockContents, SignedBlockContents,
+    SkipRandaoVerification, ValidatorId, ValidatorStatus,
 };
+use eth2::{ACCEPTED_ENCODINGS_HEADER, CONSENSUS_VERSION_HEADER, SNAPPY_ENCODING};
 use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
-use logging::SSELoggingComponents;
+use logging::{LogFilters, SSELoggingComponents};
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
 use operation_pool::ReceivedPreCapella;
 use parking_lot::RwLock;
 pub use publish_blocks::{
     publish_blinded_block, publish_block, reconstruct_block, ProvenancedBlock,
 };
+use request_limiter::RequestLimiter;
+use request_tracing::ServerTiming;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -62,8 +84,10 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_bn;
+use task_executor::InFlightGuard;
 use task_spawner::{Priority, TaskSpawner};
 use tokio::sync::{
     mpsc::{Sender, UnboundedSender},
@@ -76,20 +100,22 @@ use tokio_stream::{
 use types::{
     Attestation, AttestationData, AttestationShufflingId, AttesterSlashing, BeaconStateError,
     BlindedPayload, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName, FullPayload,
-    ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
-    SignedBlsToExecutionChange, SignedContributionAndProof, SignedValidatorRegistrationData,
-    SignedVoluntaryExit, Slot, SyncCommitteeMessage, SyncContributionData,
+    Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch, Signature,
+    SignedAggregateAndProof, SignedBlsToExecutionChange, SignedContributionAndProof,
+    SignedValidatorRegistrationData, SignedVoluntaryExit, Slot, SyncCommitteeMessage,
+    SyncContributionData,
 };
 use validator::pubkey_to_validator_index;
 use version::{
     add_consensus_version_header, execution_optimistic_finalized_fork_versioned_response,
     fork_versioned_response, inconsistent_fork_rejection, unsupported_version_rejection, V1, V2,
 };
-use warp::http::StatusCode;
+use warp::http::{Method, StatusCode};
+use warp::path::FullPath;
 use warp::sse::Event;
 use warp::Reply;
 use warp::{http::Response, Filter};
-use warp_utils::{query::multi_key_query, uor::UnifyingOrFilter};
+use warp_utils::{cors::Cors, query::multi_key_query, uor::UnifyingOrFilter};
 
 const API_PREFIX: &str = "eth";
 
@@ -111,6 +137,8 @@ pub type ExecutionOptimistic = bool;
 pub struct TlsConfig {
     pub cert: PathBuf,
     pub key: PathBuf,
+    /// If set, clients must present a certificate signed by one of the CAs in this file.
+    pub client_ca: Option<PathBuf>,
 }
 
 /// A wrapper around all the items required to spawn the HTTP server.
@@ -122,8 +150,11 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_senders: Option<NetworkSenders<T::EthSpec>>,
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub beacon_processor_send: Option<BeaconProcessorSend<T::EthSpec>>,
+    pub beacon_processor_snapshot_send: Option<BeaconProcessorSnapshotSend>,
+    pub reprocess_queue_snapshot_send: Option<ReprocessQueueSnapshotSend>,
     pub eth1_service: Option<eth1::Service>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    pub log_filters: Option<LogFilters>,
     pub log: Logger,
 }
 
@@ -133,15 +164,60 @@ pub struct Config {
     pub enabled: bool,
     pub listen_addr: IpAddr,
     pub listen_port: u16,
-    pub allow_origin: Option<String>,
+    /// The origins allowed to make cross-origin requests, which may be `*` or wildcard subdomain
+    /// patterns (e.g. `https://*.example.com`). If empty, only the listen address is allowed.
+    #[serde(alias = "allow_origin", deserialize_with = "deserialize_allow_origins")]
+    pub allow_origins: Vec<String>,
     pub tls_config: Option<TlsConfig>,
     pub allow_sync_stalled: bool,
     pub spec_fork_name: Option<ForkName>,
     pub data_dir: PathBuf,
     pub sse_capacity_multiplier: usize,
     pub enable_beacon_processor: bool,
+    pub enable_fork_choice_recompute: bool,
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
+    /// The maximum number of concurrent requests to endpoints which serve large parts of a state.
+    pub max_concurrent_state_requests: usize,
+    /// An artificial delay added to block publications, whilst they are tracked as in-flight.
+    ///
+    /// Only intended for testing graceful shutdown.
+    pub publish_block_delay: Option<Duration>,
+    /// The path of the token required by the `lighthouse/admin` endpoints. Defaults to
+    /// `api-token.txt` in the data directory, and is generated if it does not exist.
+    pub admin_token_path: Option<PathBuf>,
+    /// Refuse all requests to the `lighthouse/admin` endpoints, without loading a token.
+    pub disable_admin_endpoints: bool,
+    /// Refuse requests to debug endpoints which serve whole states or fork choice.
+    pub disable_expensive_endpoints: bool,
+    /// If set, the API is also served on a Unix domain socket at this path.
+    pub unix_socket: Option<PathBuf>,
+}
+
+/// Deserializes `Config::allow_origins`, also accepting the comma-separated string (or `null`) of
+/// the `allow_origin` field that it replaced.
+fn deserialize_allow_origins<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum AllowOrigins {
+        List(Vec<String>),
+        Legacy(Option<String>),
+    }
+
+    Ok(match AllowOrigins::deserialize(deserializer)? {
+        AllowOrigins::List(origins) => origins,
+        AllowOrigins::Legacy(origins) => origins
+            .map(|origins| {
+                origins
+                    .split(',')
+                    .map(|origin| origin.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
 }
 
 impl Default for Config {
@@ -150,14 +226,21 @@ impl Default for Config {
             enabled: false,
             listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
             listen_port: 5052,
-            allow_origin: None,
+            allow_origins: vec![],
             tls_config: None,
             allow_sync_stalled: false,
             spec_fork_name: None,
             data_dir: PathBuf::from(DEFAULT_ROOT_DIR),
             sse_capacity_multiplier: 1,
             enable_beacon_processor: true,
+            enable_fork_choice_recompute: false,
             duplicate_block_status_code: StatusCode::ACCEPTED,
+            max_concurrent_state_requests: 64,
+            publish_block_delay: None,
+            admin_token_path: None,
+            disable_admin_endpoints: false,
+            disable_expensive_endpoints: false,
+            unix_socket: None,
         }
     }
 }
@@ -180,38 +263,43 @@ impl From<String> for Error {
     }
 }
 
-/// Creates a `warp` logging wrapper which we use to create `slog` logs.
-pub fn slog_logging(
-    log: Logger,
-) -> warp::filters::log::Log<impl Fn(warp::filters::log::Info) + Clone> {
-    warp::log::custom(move |info| {
-        match info.status() {
-            status
-                if status == StatusCode::OK
-                    || status == StatusCode::NOT_FOUND
-                    || status == StatusCode::PARTIAL_CONTENT =>
-            {
-                debug!(
-                    log,
-                    "Processed HTTP API request";
-                    "elapsed" => format!("{:?}", info.elapsed()),
-                    "status" => status.to_string(),
-                    "path" => info.path(),
-                    "method" => info.method().to_string(),
-                );
-            }
-            status => {
-                warn!(
-                    log,
-                    "Error processing HTTP API request";
-                    "elapsed" => format!("{:?}", info.elapsed()),
-                    "status" => status.to_string(),
-                    "path" => info.path(),
-                    "method" => info.method().to_string(),
-                );
-            }
-        };
-    })
+/// Creates `slog` logs for a processed request.
+fn log_request(
+    log: &Logger,
+    request_id: &str,
+    method: &Method,
+    path: &FullPath,
+    status: StatusCode,
+    elapsed: Duration,
+) {
+    match status {
+        status
+            if status == StatusCode::OK
+                || status == StatusCode::NOT_FOUND
+                || status == StatusCode::PARTIAL_CONTENT =>
+        {
+            debug!(
+                log,
+                "Processed HTTP API request";
+                "elapsed" => format!("{:?}", elapsed),
+                "status" => status.to_string(),
+                "path" => path.as_str(),
+                "method" => method.to_string(),
+                "request_id" => request_id,
+            );
+        }
+        status => {
+            warn!(
+                log,
+                "Error processing HTTP API request";
+                "elapsed" => format!("{:?}", elapsed),
+                "status" => status.to_string(),
+                "path" => path.as_str(),
+                "method" => method.to_string(),
+                "request_id" => request_id,
+            );
+        }
+    };
 }
 
 /// Creates a `warp` logging wrapper which we use for Prometheus metrics (not necessarily logging,
@@ -302,17 +390,24 @@ pub fn serve<T: BeaconChainTypes>(
     let log = ctx.log.clone();
 
     // Configure CORS.
-    let cors_builder = {
-        let builder = warp::cors()
-            .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
-
-        warp_utils::cors::set_builder_origins(
-            builder,
-            config.allow_origin.as_deref(),
-            (config.listen_addr, config.listen_port),
-        )?
-    };
+    let cors = Cors::new(
+        &config.allow_origins,
+        (config.listen_addr, config.listen_port),
+    )?
+    .allow_methods([Method::GET, Method::POST])
+    .allow_headers(&[
+        "Content-Type",
+        "Content-Encoding",
+        CONSENSUS_VERSION_HEADER,
+        request_tracing::REQUEST_ID_HEADER,
+    ])?
+    .expose_headers(&[
+        ACCEPTED_ENCODINGS_HEADER,
+        CONSENSUS_VERSION_HEADER,
+        request_tracing::REQUEST_ID_HEADER,
+        request_tracing::RESPONSE_TIME_HEADER,
+        request_tracing::SERVER_TIMING_HEADER,
+    ])?;
 
     // Sanity check.
     if !config.enabled {
@@ -322,6 +417,24 @@ pub fn serve<T: BeaconChainTypes>(
         ));
     }
 
+    // Load the token for the admin endpoints, unless they are disabled.
+    let admin_token = if config.disable_admin_endpoints {
+        info!(log, "Admin API endpoints disabled");
+        ApiToken::disabled()
+    } else {
+        let admin_token_path = config
+            .admin_token_path
+            .clone()
+            .unwrap_or_else(|| config.data_dir.join(api_token::API_TOKEN_FILENAME));
+        let admin_token = ApiToken::create_or_open(&admin_token_path)?;
+        info!(
+            log,
+            "Admin API token loaded";
+            "path" => ?admin_token_path,
+        );
+        admin_token
+    };
+
     // Create a filter that extracts the endpoint version.
     let any_version = warp::path(API_PREFIX).and(warp::path::param::<EndpointVersion>().or_else(
         |_| async move {
@@ -468,13 +581,49 @@ pub fn serve<T: BeaconChainTypes>(
             )
             .untuple_one();
 
-    // Create a `warp` filter that provides access to the logger.
-    let inner_ctx = ctx.clone();
-    let log_filter = warp::any().map(move || inner_ctx.log.clone());
+    // Create a `warp` filter that provides access to the logger, tagged with the request ID if the
+    // client supplied one.
+    let log_filter = request_tracing::request_logger(ctx.log.clone());
+
+    // Create a `warp` filter that limits the number of concurrent requests for expensive states.
+    let state_limiter = RequestLimiter::new("state", ctx.config.max_concurrent_state_requests);
+    let state_limiter_filter = warp::any().map(move || state_limiter.clone());
+
+    // Create a `warp` filter that rejects requests to expensive debug endpoints if they are
+    // disabled.
+    let disable_expensive_endpoints = ctx.config.disable_expensive_endpoints;
+    let expensive_endpoint_filter = warp::any()
+        .and_then(move || async move {
+            if disable_expensive_endpoints {
+                Err(warp_utils::reject::custom_not_found(
+                    "this endpoint is disabled by the proposer beacon node profile".to_string(),
+                ))
+            } else {
+                Ok(())
+            }
+        })
+        .untuple_one();
+
+    // Create a `warp` filter that tracks block publications as in-flight operations, so that a
+    // graceful shutdown waits for them to complete.
+    let publish_block_delay = ctx.config.publish_block_delay;
+    let in_flight_publish_filter =
+        chain_filter
+            .clone()
+            .and_then(move |chain: Arc<BeaconChain<T>>| async move {
+                let in_flight = chain.task_executor.graceful_shutdown().track();
+                if let Some(delay) = publish_block_delay {
+                    tokio::time::sleep(delay).await;
+                }
+                Ok::<_, warp::Rejection>(in_flight)
+            });
 
     let inner_components = ctx.sse_logging_components.clone();
     let sse_component_filter = warp::any().map(move || inner_components.clone());
 
+    let inner_log_filters = ctx.log_filters.clone();
+    let log_filters_filter = warp::any().map(move || inner_log_filters.clone());
+
     // Create a `warp` filter that provides access to local system information.
     let system_info = Arc::new(RwLock::new(sysinfo::System::new()));
     {
@@ -514,6 +663,32 @@ pub fn serve<T: BeaconChainTypes>(
     let task_spawner_filter =
         warp::any().map(move || TaskSpawner::new(beacon_processor_send.clone()));
 
+    // Create a `warp` filter that provides access to the beacon processor snapshot channel.
+    let beacon_processor_snapshot_send = ctx.beacon_processor_snapshot_send.clone();
+    let beacon_processor_snapshot_filter = warp::any()
+        .map(move || beacon_processor_snapshot_send.clone())
+        .and_then(|snapshot_send| async move {
+            match snapshot_send {
+                Some(snapshot_send) => Ok(snapshot_send),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "The beacon processor has not yet started.".to_string(),
+                )),
+            }
+        });
+
+    // Create a `warp` filter that provides access to the reprocess queue snapshot channel.
+    let reprocess_queue_snapshot_send = ctx.reprocess_queue_snapshot_send.clone();
+    let reprocess_queue_snapshot_filter = warp::any()
+        .map(move || reprocess_queue_snapshot_send.clone())
+        .and_then(|snapshot_send| async move {
+            match snapshot_send {
+                Some(snapshot_send) => Ok(snapshot_send),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "The beacon processor has not yet started.".to_string(),
+                )),
+            }
+        });
+
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
 
     /*
@@ -640,15 +815,18 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator_balances"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::ValidatorBalancesQuery>())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query_res: Result<api_types::ValidatorBalancesQuery, warp::Rejection>,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_response_task(task_spawner, Priority::P1, move || {
                     let query = query_res?;
-                    let (data, execution_optimistic, finalized) = state_id
-                        .map_state_and_execution_optimistic_and_finalized(
+                    let mut timing = ServerTiming::default();
+                    let (data, execution_optimistic, finalized) = timing.time("state", || {
+                        state_id.map_state_and_execution_optimistic_and_finalized(
                             &chain,
                             |state, execution_optimistic, finalized| {
                                 Ok((
@@ -681,13 +859,16 @@ pub fn serve<T: BeaconChainTypes>(
                                     finalized,
                                 ))
                             },
-                        )?;
+                        )
+                    })?;
 
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        data,
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                    })
+                    Ok(
+                        timing.json_response(&api_types::ExecutionOptimisticFinalizedResponse {
+                            data,
+                            execution_optimistic: Some(execution_optimistic),
+                            finalized: Some(finalized),
+                        }),
+                    )
                 })
             },
         );
@@ -698,15 +879,18 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validators"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::ValidatorsQuery>())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query_res: Result<api_types::ValidatorsQuery, warp::Rejection>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query_res: Result<api_types::ValidatorsQuery, warp::Rejection>,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_response_task(task_spawner, Priority::P1, move || {
                     let query = query_res?;
-                    let (data, execution_optimistic, finalized) = state_id
-                        .map_state_and_execution_optimistic_and_finalized(
+                    let mut timing = ServerTiming::default();
+                    let (data, execution_optimistic, finalized) = timing.time("state", || {
+                        state_id.map_state_and_execution_optimistic_and_finalized(
                             &chain,
                             |state, execution_optimistic, finalized| {
                                 let epoch = state.current_epoch();
@@ -761,13 +945,16 @@ pub fn serve<T: BeaconChainTypes>(
                                     finalized,
                                 ))
                             },
-                        )?;
+                        )
+                    })?;
 
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        data,
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                    })
+                    Ok(
+                        timing.json_response(&api_types::ExecutionOptimisticFinalizedResponse {
+                            data,
+                            execution_optimistic: Some(execution_optimistic),
+                            finalized: Some(finalized),
+                        }),
+                    )
                 })
             },
         );
@@ -782,12 +969,14 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             validator_id: ValidatorId| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             validator_id: ValidatorId,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_json_task(task_spawner, Priority::P1, move || {
                     let (data, execution_optimistic, finalized) = state_id
                         .map_state_and_execution_optimistic_and_finalized(
                             &chain,
@@ -850,12 +1039,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("committees"))
         .and(warp::query::<api_types::CommitteesQuery>())
         .and(warp::path::end())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: api_types::CommitteesQuery| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query: api_types::CommitteesQuery,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_json_task(task_spawner, Priority::P1, move || {
                     let (data, execution_optimistic, finalized) = state_id
                         .map_state_and_execution_optimistic_and_finalized(
                             &chain,
@@ -1033,12 +1224,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("sync_committees"))
         .and(warp::query::<api_types::SyncCommitteesQuery>())
         .and(warp::path::end())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: api_types::SyncCommitteesQuery| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query: api_types::SyncCommitteesQuery,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_json_task(task_spawner, Priority::P1, move || {
                     let (sync_committee, execution_optimistic, finalized) = state_id
                         .map_state_and_execution_optimistic_and_finalized(
                             &chain,
@@ -1158,7 +1351,7 @@ pub fn serve<T: BeaconChainTypes>(
                                     cached_head.head_block_root(),
                                     cached_head.snapshot.beacon_block.clone_as_blinded(),
                                     execution_status.is_optimistic_or_invalid(),
-                                    false,
+                                    state_id::head_is_finalized(&cached_head),
                                 )
                             }
                             // Only the parent root parameter, do a forwards-iterator lookup.
@@ -1289,13 +1482,16 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |block_contents: SignedBlockContents<T::EthSpec>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
                     publish_blocks::publish_block(
                         None,
                         ProvenancedBlock::local(block_contents),
@@ -1314,18 +1510,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
         .and(warp::path::end())
-        .and(warp::body::bytes())
+        .and(ssz_body::ssz_body_filter())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
-            move |block_bytes: Bytes,
+            move |block_body: ssz_body::SszBody,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
+                    let block_bytes = block_body.decode()?;
                     let block_contents = SignedBlockContents::<T::EthSpec>::from_ssz_bytes(
                         &block_bytes,
                         &chain.spec,
@@ -1357,14 +1557,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
                   block_contents: SignedBlockContents<T::EthSpec>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
                     publish_blocks::publish_block(
                         None,
                         ProvenancedBlock::local(block_contents),
@@ -1384,19 +1587,23 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("blocks"))
         .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
-        .and(warp::body::bytes())
+        .and(ssz_body::ssz_body_filter())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
-                  block_bytes: Bytes,
+                  block_body: ssz_body::SszBody,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
+                    let block_bytes = block_body.decode()?;
                     let block_contents = SignedBlockContents::<T::EthSpec>::from_ssz_bytes(
                         &block_bytes,
                         &chain.spec,
@@ -1432,13 +1639,16 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |block_contents: SignedBlindedBlockContents<T::EthSpec>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
                     publish_blocks::publish_blinded_block(
                         block_contents,
                         chain,
@@ -1457,18 +1667,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("beacon"))
         .and(warp::path("blinded_blocks"))
         .and(warp::path::end())
-        .and(warp::body::bytes())
+        .and(ssz_body::ssz_body_filter())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
-            move |block_bytes: Bytes,
+            move |block_body: ssz_body::SszBody,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
+                    let block_bytes = block_body.decode()?;
                     let block =
                         SignedBlockContents::<T::EthSpec, BlindedPayload<_>>::from_ssz_bytes(
                             &block_bytes,
@@ -1500,14 +1714,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
                   block_contents: SignedBlindedBlockContents<T::EthSpec>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
                     publish_blocks::publish_blinded_block(
                         block_contents,
                         chain,
@@ -1526,19 +1743,23 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("blinded_blocks"))
         .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
-        .and(warp::body::bytes())
+        .and(ssz_body::ssz_body_filter())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
+        .and(in_flight_publish_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
-                  block_bytes: Bytes,
+                  block_body: ssz_body::SszBody,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
-                  log: Logger| {
+                  log: Logger,
+                  in_flight: InFlightGuard| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    let _in_flight = in_flight;
+                    let block_bytes = block_body.decode()?;
                     let block =
                         SignedBlockContents::<T::EthSpec, BlindedPayload<_>>::from_ssz_bytes(
                             &block_bytes,
@@ -1592,33 +1813,39 @@ pub fn serve<T: BeaconChainTypes>(
              chain: Arc<BeaconChain<T>>,
              accept_header: Option<api_types::Accept>| {
                 task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    let mut timing = ServerTiming::default();
+                    let block_load_start = Instant::now();
                     let (block, execution_optimistic, finalized) =
                         block_id.full_block(&chain).await?;
+                    timing.record("block", block_load_start.elapsed());
                     let fork_name = block
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
 
-                    match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .header("Content-Type", "application/octet-stream")
-                            .body(block.as_ssz_bytes().into())
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
-                        _ => execution_optimistic_finalized_fork_versioned_response(
-                            endpoint_version,
-                            fork_name,
-                            execution_optimistic,
-                            finalized,
-                            block,
-                        )
-                        .map(|res| warp::reply::json(&res).into_response()),
-                    }
-                    .map(|resp| add_consensus_version_header(resp, fork_name))
+                    timing
+                        .time("serialize", || match accept_header {
+                            Some(api_types::Accept::Ssz) => Response::builder()
+                                .status(200)
+                                .header("Content-Type", "application/octet-stream")
+                                .body(block.as_ssz_bytes().into())
+                                .map_err(|e| {
+                                    warp_utils::reject::custom_server_error(format!(
+                                        "failed to create response: {}",
+                                        e
+                                    ))
+                                }),
+                            _ => execution_optimistic_finalized_fork_versioned_response(
+                                endpoint_version,
+                                fork_name,
+                                execution_optimistic,
+                                finalized,
+                                block,
+                            )
+                            .map(|res| warp::reply::json(&res).into_response()),
+                        })
+                        .map(|resp| {
+                            timing.add_header(add_consensus_version_header(resp, fork_name))
+                        })
                 })
             },
         );
@@ -1996,11 +2223,14 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attester_slashings"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    let attestations = chain.op_pool.get_all_attester_slashings();
-                    Ok(api_types::GenericResponse::from(attestations))
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let slashings = chain.op_pool.get_all_attester_slashings();
+                    pool_operations_response(slashings, head_fork_name(&chain), accept_header)
                 })
             },
         );
@@ -2054,11 +2284,14 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("proposer_slashings"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    let attestations = chain.op_pool.get_all_proposer_slashings();
-                    Ok(api_types::GenericResponse::from(attestations))
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let slashings = chain.op_pool.get_all_proposer_slashings();
+                    pool_operations_response(slashings, head_fork_name(&chain), accept_header)
                 })
             },
         );
@@ -2110,11 +2343,14 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("voluntary_exits"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    let attestations = chain.op_pool.get_all_voluntary_exits();
-                    Ok(api_types::GenericResponse::from(attestations))
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let exits = chain.op_pool.get_all_voluntary_exits();
+                    pool_operations_response(exits, head_fork_name(&chain), accept_header)
                 })
             },
         );
@@ -2147,11 +2383,14 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("bls_to_execution_changes"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let address_changes = chain.op_pool.get_all_bls_to_execution_changes();
-                    Ok(api_types::GenericResponse::from(address_changes))
+                    pool_operations_response(address_changes, head_fork_name(&chain), accept_header)
                 })
             },
         );
@@ -2447,18 +2686,29 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("sync_committee"))
         .and(block_id_or_err)
         .and(warp::path::end())
-        .and(warp::body::json())
-        .and(log_filter.clone())
+        .and(warp::body::bytes())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
              block_id: BlockId,
-             validators: Vec<ValidatorId>,
-             log: Logger| {
+             body: Bytes| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
+                    // The list of validators is optional, an empty body requests the rewards of
+                    // the whole committee.
+                    let validators: Vec<ValidatorId> = if body.is_empty() {
+                        vec![]
+                    } else {
+                        serde_json::from_slice(&body).map_err(|e| {
+                            warp_utils::reject::custom_bad_request(format!(
+                                "invalid validator ids: {:?}",
+                                e
+                            ))
+                        })?
+                    };
+
                     let (rewards, execution_optimistic, finalized) =
                         sync_committee_rewards::compute_sync_committee_rewards(
-                            chain, block_id, validators, log,
+                            chain, block_id, validators,
                         )?;
 
                     Ok(rewards)
@@ -2545,57 +2795,63 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(expensive_endpoint_filter.clone())
         .and(warp::header::optional::<api_types::Accept>("accept"))
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(state_limiter_filter.clone())
         .then(
             |endpoint_version: EndpointVersion,
              state_id: StateId,
              accept_header: Option<api_types::Accept>,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_response_task(Priority::P1, move || match accept_header {
-                    Some(api_types::Accept::Ssz) => {
-                        // We can ignore the optimistic status for the "fork" since it's a
-                        // specification constant that doesn't change across competing heads of the
-                        // beacon chain.
-                        let (state, _execution_optimistic, _finalized) = state_id.state(&chain)?;
-                        let fork_name = state
-                            .fork_name(&chain.spec)
-                            .map_err(inconsistent_fork_rejection)?;
-                        Response::builder()
-                            .status(200)
-                            .header("Content-Type", "application/octet-stream")
-                            .body(state.as_ssz_bytes().into())
-                            .map(|resp: warp::reply::Response| {
-                                add_consensus_version_header(resp, fork_name)
-                            })
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            })
-                    }
-                    _ => state_id.map_state_and_execution_optimistic_and_finalized(
-                        &chain,
-                        |state, execution_optimistic, finalized| {
+             chain: Arc<BeaconChain<T>>,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_response_task(task_spawner, Priority::P1, move || {
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            // We can ignore the optimistic status for the "fork" since it's a
+                            // specification constant that doesn't change across competing heads of
+                            // the beacon chain.
+                            let (state, _execution_optimistic, _finalized) =
+                                state_id.state(&chain)?;
                             let fork_name = state
                                 .fork_name(&chain.spec)
                                 .map_err(inconsistent_fork_rejection)?;
-                            let res = execution_optimistic_finalized_fork_versioned_response(
-                                endpoint_version,
-                                fork_name,
-                                execution_optimistic,
-                                finalized,
-                                &state,
-                            )?;
-                            Ok(add_consensus_version_header(
-                                warp::reply::json(&res).into_response(),
-                                fork_name,
-                            ))
-                        },
-                    ),
+                            Response::builder()
+                                .status(200)
+                                .header("Content-Type", "application/octet-stream")
+                                .body(state.as_ssz_bytes().into())
+                                .map(|resp: warp::reply::Response| {
+                                    add_consensus_version_header(resp, fork_name)
+                                })
+                                .map_err(|e| {
+                                    warp_utils::reject::custom_server_error(format!(
+                                        "failed to create response: {}",
+                                        e
+                                    ))
+                                })
+                        }
+                        _ => state_id.map_state_and_execution_optimistic_and_finalized(
+                            &chain,
+                            |state, execution_optimistic, finalized| {
+                                let fork_name = state
+                                    .fork_name(&chain.spec)
+                                    .map_err(inconsistent_fork_rejection)?;
+                                let res = execution_optimistic_finalized_fork_versioned_response(
+                                    endpoint_version,
+                                    fork_name,
+                                    execution_optimistic,
+                                    finalized,
+                                    &state,
+                                )?;
+                                Ok(add_consensus_version_header(
+                                    warp::reply::json(&res).into_response(),
+                                    fork_name,
+                                ))
+                            },
+                        ),
+                    }
                 })
             },
         );
@@ -2645,6 +2901,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("debug"))
         .and(warp::path("fork_choice"))
         .and(warp::path::end())
+        .and(expensive_endpoint_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -2654,10 +2911,25 @@ pub fn serve<T: BeaconChainTypes>(
 
                     let proto_array = beacon_fork_choice.proto_array().core_proto_array();
 
+                    // Parents precede their children in the proto array, so whether each node
+                    // descends from the finalized checkpoint is found in a single pass rather
+                    // than walking the ancestors of every node.
+                    let mut finalized_descendants = Vec::with_capacity(proto_array.nodes.len());
+                    for node in &proto_array.nodes {
+                        let finalized_descendant = node.root
+                            == proto_array.finalized_checkpoint.root
+                            || node
+                                .parent
+                                .and_then(|index| finalized_descendants.get(index).copied())
+                                .unwrap_or(false);
+                        finalized_descendants.push(finalized_descendant);
+                    }
+
                     let fork_choice_nodes = proto_array
                         .nodes
                         .iter()
-                        .map(|node| {
+                        .zip(finalized_descendants)
+                        .map(|(node, finalized_descendant)| {
                             let execution_status = if node.execution_status.is_execution_enabled() {
                                 Some(node.execution_status.to_string())
                             } else {
@@ -2679,6 +2951,24 @@ pub fn serve<T: BeaconChainTypes>(
                                     .execution_status
                                     .block_hash()
                                     .map(|block_hash| block_hash.into_root()),
+                                extra_data: Some(ForkChoiceNodeExtraData {
+                                    state_root: node.state_root,
+                                    best_child: node
+                                        .best_child
+                                        .and_then(|index| proto_array.nodes.get(index))
+                                        .map(|child| child.root),
+                                    best_descendant: node
+                                        .best_descendant
+                                        .and_then(|index| proto_array.nodes.get(index))
+                                        .map(|descendant| descendant.root),
+                                    unrealized_justified_epoch: node
+                                        .unrealized_justified_checkpoint
+                                        .map(|checkpoint| checkpoint.epoch),
+                                    unrealized_finalized_epoch: node
+                                        .unrealized_finalized_checkpoint
+                                        .map(|checkpoint| checkpoint.epoch),
+                                    finalized_descendant,
+                                }),
                             }
                         })
                         .collect::<Vec<_>>();
@@ -2744,9 +3034,13 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         // Bypass the `task_spawner` since this method returns a static string.
         .then(|| async {
-            warp::reply::json(&api_types::GenericResponse::from(api_types::VersionData {
-                version: version_with_platform(),
-            }))
+            warp::reply::with_header(
+                warp::reply::json(&api_types::GenericResponse::from(api_types::VersionData {
+                    version: version_with_platform(),
+                })),
+                ACCEPTED_ENCODINGS_HEADER,
+                SNAPPY_ENCODING,
+            )
             .into_response()
         });
 
@@ -3073,25 +3367,8 @@ pub fn serve<T: BeaconChainTypes>(
                         "slot" => slot
                     );
 
-                    let randao_reveal = query.randao_reveal.decompress().map_err(|e| {
-                        warp_utils::reject::custom_bad_request(format!(
-                            "randao reveal is not a valid BLS signature: {:?}",
-                            e
-                        ))
-                    })?;
-
-                    let randao_verification =
-                        if query.skip_randao_verification == SkipRandaoVerification::Yes {
-                            if !randao_reveal.is_infinity() {
-                                return Err(warp_utils::reject::custom_bad_request(
-                                    "randao_reveal must be point-at-infinity if verification is skipped"
-                                        .into(),
-                                ));
-                            }
-                            ProduceBlockVerification::NoVerification
-                        } else {
-                            ProduceBlockVerification::VerifyRandao
-                        };
+                    let (randao_reveal, randao_verification) =
+                        randao_reveal_for_production(&query)?;
 
                     let (block, _, maybe_blobs) = chain
                         .produce_block_with_verification::<FullPayload<T::EthSpec>>(
@@ -3154,25 +3431,8 @@ pub fn serve<T: BeaconChainTypes>(
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
-                    let randao_reveal = query.randao_reveal.decompress().map_err(|e| {
-                        warp_utils::reject::custom_bad_request(format!(
-                            "randao reveal is not a valid BLS signature: {:?}",
-                            e
-                        ))
-                    })?;
-
-                    let randao_verification =
-                        if query.skip_randao_verification == SkipRandaoVerification::Yes {
-                            if !randao_reveal.is_infinity() {
-                                return Err(warp_utils::reject::custom_bad_request(
-                                "randao_reveal must be point-at-infinity if verification is skipped"
-                                    .into()
-                            ));
-                            }
-                            ProduceBlockVerification::NoVerification
-                        } else {
-                            ProduceBlockVerification::VerifyRandao
-                        };
+                    let (randao_reveal, randao_verification) =
+                        randao_reveal_for_production(&query)?;
 
                     let (block, _, maybe_blobs) = chain
                         .produce_block_with_verification::<BlindedPayload<T::EthSpec>>(
@@ -3298,17 +3558,25 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::AttesterDutiesQuery>())
         .and(not_while_syncing_filter.clone())
         .and(warp::body::json())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
             |epoch: Epoch,
+             query: eth2::lighthouse::AttesterDutiesQuery,
              indices: api_types::ValidatorIndexData,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P0, move || {
-                    attester_duties::attester_duties(epoch, &indices.0, &chain)
+                task_spawner.blocking_response_task(Priority::P0, move || {
+                    if query.include_committee.unwrap_or(false) {
+                        attester_duties::attester_duties_with_committees(epoch, &indices.0, &chain)
+                            .map(|res| warp::reply::json(&res).into_response())
+                    } else {
+                        attester_duties::attester_duties(epoch, &indices.0, &chain)
+                            .map(|res| warp::reply::json(&res).into_response())
+                    }
                 })
             },
         );
@@ -3447,7 +3715,7 @@ pub fn serve<T: BeaconChainTypes>(
 
                     // Publish aggregate attestations to the libp2p network
                     if !messages.is_empty() {
-                        publish_network_message(&network_tx, NetworkMessage::Publish { messages })?;
+                        publish_network_message(&network_tx, NetworkMessage::publish(messages))?;
                     }
 
                     // Import aggregate attestations
@@ -3493,7 +3761,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp::body::json())
-        .and(network_tx_filter)
+        .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
@@ -3602,6 +3870,16 @@ pub fn serve<T: BeaconChainTypes>(
                         .update_proposer_preparation(current_epoch, &preparation_data)
                         .await;
 
+                    if let Err(e) =
+                        chain.persist_proposer_preparations(current_epoch, &preparation_data)
+                    {
+                        warn!(
+                            log,
+                            "Failed to persist proposer preparation data";
+                            "error" => ?e,
+                        );
+                    }
+
                     chain
                         .prepare_beacon_proposer(current_slot)
                         .await
@@ -3704,6 +3982,22 @@ pub fn serve<T: BeaconChainTypes>(
                         execution_layer
                             .update_proposer_preparation(current_epoch, &preparation_data)
                             .await;
+                        execution_layer.update_builder_registrations(
+                            preparation_data
+                                .iter()
+                                .map(|data| data.validator_index)
+                                .zip(filtered_registration_data.iter().cloned()),
+                        );
+
+                        if let Err(e) =
+                            chain.persist_proposer_preparations(current_epoch, &preparation_data)
+                        {
+                            warn!(
+                                log,
+                                "Failed to persist validator registrations";
+                                "error" => ?e,
+                            );
+                        }
 
                         // Call prepare beacon proposer blocking with the latest update in order to make
                         // sure we have a local payload to fall back to in the event of the blinded block
@@ -3934,13 +4228,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("health"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .then(|task_spawner: TaskSpawner<T::EthSpec>| {
-            task_spawner.blocking_json_task(Priority::P0, move || {
-                eth2::lighthouse::Health::observe()
-                    .map(api_types::GenericResponse::from)
-                    .map_err(warp_utils::reject::custom_bad_request)
-            })
-        });
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    let mut health = eth2::lighthouse::Health::observe()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    health.head_vote_accuracy = chain.head_vote_accuracy.latest();
+                    Ok(api_types::GenericResponse::from(health))
+                })
+            },
+        );
 
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
@@ -3994,7 +4292,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
-            |request_data: ui::ValidatorMetricsRequestData,
+            |request_data: eth2::lighthouse::ValidatorMetricsRequestData,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
@@ -4086,7 +4384,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("connected"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(network_globals)
+        .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
@@ -4104,29 +4402,377 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
-    // GET lighthouse/proto_array
-    let get_lighthouse_proto_array = warp::path("lighthouse")
-        .and(warp::path("proto_array"))
+    // GET lighthouse/network
+    let get_lighthouse_network = warp::path("lighthouse")
+        .and(warp::path("network"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(chain_filter.clone())
+        .and(network_globals.clone())
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_response_task(Priority::P1, move || {
-                    Ok::<_, warp::Rejection>(warp::reply::json(
-                        &api_types::GenericResponseRef::from(
-                            chain
-                                .canonical_head
-                                .fork_choice_read_lock()
-                                .proto_array()
-                                .core_proto_array(),
-                        ),
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::NetworkStatus {
+                            peer_id: network_globals.local_peer_id().to_string(),
+                            listen_addresses: network_globals
+                                .listen_multiaddrs()
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect(),
+                            upnp: network_globals.upnp_status(),
+                        },
                     ))
                 })
             },
         );
 
-    // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
+    // GET lighthouse/network/fork_topics
+    let get_lighthouse_network_fork_topics = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("fork_topics"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::ForkTopicsQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |query,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    fork_topics::get_fork_topics(query, chain, network_globals)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/network/enr
+    let get_lighthouse_network_enr = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("enr"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_enr::get_local_enr(&network_globals),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/discovery
+    let get_lighthouse_network_discovery = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("discovery"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_tx_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    network_discovery::get_discovery_info(network_tx)
+                        .await
+                        .map(|info| {
+                            warp::reply::json(&api_types::GenericResponse::from(info))
+                                .into_response()
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/network/gossip/rejections
+    let get_lighthouse_network_gossip_rejections = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("gossip"))
+        .and(warp::path("rejections"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        gossip_rejections::get_rejections(&network_globals),
+                    ))
+                })
+            },
+        );
+
+    // POST lighthouse/admin/network/gossip/replay
+    //
+    // Replays run gossip verification on arbitrary messages, so the admin token is required.
+    let post_lighthouse_network_gossip_replay = admin_token
+        .admin_path(&["network", "gossip", "replay"], log.clone())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::GossipReplayRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    gossip_rejections::replay(request, chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/admin/network/enr
+    let post_lighthouse_network_enr = admin_token
+        .admin_path(&["network", "enr"], log.clone())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(network_globals)
+        .and(network_tx_filter)
+        .then(
+            |request: eth2::lighthouse::EnrUpdateRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    network_enr::update_local_enr(request, network_globals, network_tx)
+                        .await
+                        .map(|enr| {
+                            warp::reply::json(&api_types::GenericResponse::from(enr))
+                                .into_response()
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/beacon_processor
+    //
+    // The snapshot is taken by the beacon processor manager rather than by a worker, so this
+    // endpoint remains responsive whilst all workers are busy.
+    let get_lighthouse_beacon_processor = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path::end())
+        .and(beacon_processor_snapshot_filter)
+        .then(|snapshot_send: BeaconProcessorSnapshotSend| async move {
+            let result = snapshot_send
+                .snapshot()
+                .await
+                .map(|snapshot| warp::reply::json(&api_types::GenericResponse::from(snapshot)))
+                .map_err(warp_utils::reject::custom_server_error);
+            task_spawner::convert_rejection(result).await
+        });
+
+    // GET lighthouse/beacon_processor/reprocess_queue
+    let get_lighthouse_beacon_processor_reprocess_queue = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path("reprocess_queue"))
+        .and(warp::path::end())
+        .and(reprocess_queue_snapshot_filter)
+        .then(|snapshot_send: ReprocessQueueSnapshotSend| async move {
+            let result = snapshot_send
+                .snapshot()
+                .await
+                .map(|snapshot| warp::reply::json(&api_types::GenericResponse::from(snapshot)))
+                .map_err(warp_utils::reject::custom_server_error);
+            task_spawner::convert_rejection(result).await
+        });
+
+    // GET lighthouse/proto_array
+    let get_lighthouse_proto_array = warp::path("lighthouse")
+        .and(warp::path("proto_array"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    Ok::<_, warp::Rejection>(warp::reply::json(
+                        &api_types::GenericResponseRef::from(
+                            chain
+                                .canonical_head
+                                .fork_choice_read_lock()
+                                .proto_array()
+                                .core_proto_array(),
+                        ),
+                    ))
+                })
+            },
+        );
+
+    let fork_choice_path = warp::path("lighthouse").and(warp::path("fork_choice"));
+
+    // POST lighthouse/admin/fork_choice/recompute
+    let enable_fork_choice_recompute = config.enable_fork_choice_recompute;
+    let post_lighthouse_fork_choice_recompute = admin_token
+        .admin_path(&["fork_choice", "recompute"], log.clone())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            move |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    if !enable_fork_choice_recompute {
+                        return Err(warp_utils::reject::custom_not_found(
+                            "fork choice recompute is not enabled, \
+                            see the --http-enable-fork-choice-recompute CLI flag"
+                                .to_string(),
+                        ));
+                    }
+                    fork_choice::recompute(chain)
+                        .await
+                        .map(|response| warp::reply::json(&response).into_response())
+                })
+            },
+        );
+
+    // GET lighthouse/fork_choice/last_recompute
+    let get_lighthouse_fork_choice_last_recompute = fork_choice_path
+        .and(warp::path("last_recompute"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner
+                    .blocking_json_task(Priority::P1, move || fork_choice::last_recompute(chain))
+            },
+        );
+
+    // GET lighthouse/blocks/{block_root}/execution_status
+    let get_lighthouse_block_execution_status = warp::path("lighthouse")
+        .and(warp::path("blocks"))
+        .and(warp::path::param::<Hash256>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block root".to_string(),
+            ))
+        }))
+        .and(warp::path("execution_status"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |block_root: Hash256,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    fork_choice::block_execution_status(block_root, chain)
+                })
+            },
+        );
+
+    // GET lighthouse/beacon/blob_propagation/{block_root}
+    let get_lighthouse_beacon_blob_propagation = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("blob_propagation"))
+        .and(warp::path::param::<Hash256>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block root".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |block_root: Hash256,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    blob_propagation::blob_propagation(block_root, chain)
+                })
+            },
+        );
+
+    // GET lighthouse/validator_monitor/sync_committee
+    let get_lighthouse_validator_monitor_sync_committee = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path("sync_committee"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        chain.sync_committee_participation(),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/validator/duties/attester/{epoch}/committees?slot,index
+    let get_lighthouse_validator_duties_attester_committee = warp::path("lighthouse")
+        .and(warp::path("validator"))
+        .and(warp::path("duties"))
+        .and(warp::path("attester"))
+        .and(warp::path::param::<Epoch>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid epoch".to_string(),
+            ))
+        }))
+        .and(warp::path("committees"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::AttesterCommitteeQuery>())
+        .and(not_while_syncing_filter.clone())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |epoch: Epoch,
+             query: eth2::lighthouse::AttesterCommitteeQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    attester_duties::attester_committee(epoch, query.slot, query.index, &chain)
+                })
+            },
+        );
+
+    // GET lighthouse/validator/blocks/{slot}?dry_run=true
+    let get_lighthouse_validator_blocks_dry_run = warp::path("lighthouse")
+        .and(warp::path("validator"))
+        .and(warp::path("blocks"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::BlockProductionDryRunQuery>())
+        .and(not_while_syncing_filter.clone())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot,
+             query: eth2::lighthouse::BlockProductionDryRunQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    if !query.dry_run {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "only dry runs are supported, use dry_run=true".to_string(),
+                        ));
+                    }
+
+                    let dry_run = if query.blinded {
+                        chain
+                            .produce_block_dry_run::<BlindedPayload<T::EthSpec>>(slot)
+                            .await
+                    } else {
+                        chain
+                            .produce_block_dry_run::<FullPayload<T::EthSpec>>(slot)
+                            .await
+                    }
+                    .map_err(warp_utils::reject::block_production_error)?;
+
+                    Ok::<_, warp::reject::Rejection>(
+                        warp::reply::json(&api_types::GenericResponse::from(dry_run))
+                            .into_response(),
+                    )
+                })
+            },
+        );
+
+    // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
         .and(warp::path::param::<Epoch>())
@@ -4146,6 +4792,31 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/validators/{validator_id}/attestation_inclusions?epoch,proof
+    let get_lighthouse_validator_attestation_inclusions = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<ValidatorId>())
+        .and(warp::path("attestation_inclusions"))
+        .and(warp::query::<eth2::lighthouse::AttestationInclusionQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |validator_id: ValidatorId,
+             query: eth2::lighthouse::AttestationInclusionQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    attestation_inclusions::validator_attestation_inclusions(
+                        &validator_id,
+                        query,
+                        &chain,
+                    )
+                    .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/global
     let get_lighthouse_validator_inclusion = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4248,13 +4919,16 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<StateId>())
         .and(warp::path("ssz"))
         .and(warp::path::end())
+        .and(expensive_endpoint_filter)
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(state_limiter_filter.clone())
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_response_task(Priority::P1, move || {
+             chain: Arc<BeaconChain<T>>,
+             state_limiter: RequestLimiter| {
+                state_limiter.blocking_response_task(task_spawner, Priority::P1, move || {
                     // This debug endpoint provides no indication of optimistic status.
                     let (state, _execution_optimistic, _finalized) = state_id.state(&chain)?;
                     Response::builder()
@@ -4271,6 +4945,116 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/beacon/states/{state_id}/historical_summaries
+    let get_lighthouse_beacon_states_historical_summaries = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>())
+        .and(warp::path("historical_summaries"))
+        .and(warp::query::<eth2::lighthouse::HistoricalSummariesQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |state_id: StateId,
+             query: eth2::lighthouse::HistoricalSummariesQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let start_index = query.start_index.unwrap_or(0);
+                    let limit = query
+                        .limit
+                        .unwrap_or(eth2::lighthouse::MAX_HISTORICAL_SUMMARIES_PER_REQUEST)
+                        .min(eth2::lighthouse::MAX_HISTORICAL_SUMMARIES_PER_REQUEST);
+
+                    let (historical_summaries, execution_optimistic, finalized) = state_id
+                        .map_state_and_execution_optimistic_and_finalized(
+                            &chain,
+                            |state, execution_optimistic, finalized| {
+                                let historical_summaries =
+                                    state.historical_summaries().map_err(|_| {
+                                        warp_utils::reject::custom_bad_request(
+                                            "state has no historical summaries prior to Capella"
+                                                .to_string(),
+                                        )
+                                    })?;
+                                let total = historical_summaries.len() as u64;
+                                let page = historical_summaries
+                                    .iter()
+                                    .skip(start_index as usize)
+                                    .take(limit as usize)
+                                    .copied()
+                                    .collect();
+                                Ok((
+                                    eth2::lighthouse::HistoricalSummaries {
+                                        start_index,
+                                        total,
+                                        historical_summaries: page,
+                                    },
+                                    execution_optimistic,
+                                    finalized,
+                                ))
+                            },
+                        )?;
+
+                    Ok(api_types::GenericResponse::from(historical_summaries)
+                        .add_execution_optimistic_finalized(execution_optimistic, finalized))
+                })
+            },
+        );
+
+    // GET lighthouse/beacon/states/{state_id}/proof?generalized_index
+    let get_lighthouse_beacon_states_proof = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>())
+        .and(warp::path("proof"))
+        .and(warp::query::<eth2::lighthouse::StateProofQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |state_id: StateId,
+             query: eth2::lighthouse::StateProofQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let generalized_index = query.generalized_index;
+                    let (mut state, execution_optimistic, finalized) = state_id.state(&chain)?;
+
+                    state.update_tree_hash_cache().map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "unable to compute state root: {e:?}"
+                        ))
+                    })?;
+
+                    // Only the top-level fields, the finalized root and the historical summaries
+                    // may be proven, which bounds the cost of computing the proof.
+                    let (leaf, branch) = state
+                        .compute_merkle_proof_and_leaf(generalized_index)
+                        .map_err(|e| match e {
+                            BeaconStateError::IndexNotSupported(_) => {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "unsupported generalized index: {generalized_index}"
+                                ))
+                            }
+                            e => warp_utils::reject::custom_server_error(format!(
+                                "unable to compute proof: {e:?}"
+                            )),
+                        })?;
+
+                    Ok(
+                        api_types::GenericResponse::from(eth2::lighthouse::StateProof {
+                            generalized_index,
+                            leaf,
+                            branch,
+                        })
+                        .add_execution_optimistic_finalized(execution_optimistic, finalized),
+                    )
+                })
+            },
+        );
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -4297,6 +5081,7 @@ pub fn serve<T: BeaconChainTypes>(
 
     // GET lighthouse/database/info
     let get_lighthouse_database_info = database_path
+        .clone()
         .and(warp::path("info"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
@@ -4307,10 +5092,9 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
-    // POST lighthouse/database/reconstruct
-    let post_lighthouse_database_reconstruct = database_path
-        .and(warp::path("reconstruct"))
-        .and(warp::path::end())
+    // POST lighthouse/admin/database/reconstruct
+    let post_lighthouse_database_reconstruct = admin_token
+        .admin_path(&["database", "reconstruct"], log.clone())
         .and(not_while_syncing_filter)
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -4323,6 +5107,106 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/admin/database/compact
+    //
+    // Compaction runs on a blocking thread and the response is sent once it completes.
+    let post_lighthouse_database_compact = admin_token
+        .admin_path(&["database", "compact"], log.clone())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>, log: Logger| {
+                task_spawner.blocking_json_task(Priority::P1, move || database::compact(chain, log))
+            },
+        );
+
+    // GET lighthouse/caches
+    let get_lighthouse_caches = warp::path("lighthouse")
+        .and(warp::path("caches"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || caches::info(chain))
+            },
+        );
+
+    // PATCH lighthouse/admin/caches
+    let patch_lighthouse_caches = admin_token
+        .admin_path(&["caches"], log.clone())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::CachesPatchRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner
+                    .blocking_json_task(Priority::P0, move || caches::resize(request, chain))
+            },
+        );
+
+    // GET lighthouse/graffiti
+    let get_lighthouse_graffiti = warp::path("lighthouse")
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || graffiti::info(chain))
+            },
+        );
+
+    // GET lighthouse/proposer_preparation
+    let get_lighthouse_proposer_preparation = warp::path("lighthouse")
+        .and(warp::path("proposer_preparation"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    proposer_preparation::get_proposer_preparations(chain)
+                })
+            },
+        );
+
+    // POST lighthouse/admin/graffiti
+    let post_lighthouse_graffiti = admin_token
+        .admin_path(&["graffiti"], log.clone())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |request: eth2::lighthouse::GraffitiPostRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             log: Logger| {
+                task_spawner
+                    .blocking_json_task(Priority::P0, move || graffiti::update(request, chain, log))
+            },
+        );
+
+    // PATCH lighthouse/admin/logging
+    let patch_lighthouse_logging = admin_token
+        .admin_path(&["logging"], log.clone())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(log_filters_filter)
+        .then(
+            |request: eth2::lighthouse::LoggingPatchRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             log_filters: Option<LogFilters>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    log_filters::update(request, log_filters)
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4388,6 +5272,71 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/epoch_timings
+    let get_lighthouse_epoch_timings = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("epoch_timings"))
+        .and(warp::query::<eth2::lighthouse::EpochTimingsQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::EpochTimingsQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain.epoch_timings_cache.get(query.epoch).ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(format!(
+                            "no timings for epoch {}",
+                            query.epoch
+                        ))
+                    })
+                })
+            },
+        );
+
+    // GET lighthouse/proposer_reorg/last_decision
+    let get_lighthouse_proposer_reorg_last_decision = warp::path("lighthouse")
+        .and(warp::path("proposer_reorg"))
+        .and(warp::path("last_decision"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .last_re_org_decision
+                        .lock()
+                        .clone()
+                        .map(api_types::GenericResponse::from)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(
+                                "no proposer re-org decision has been made".to_string(),
+                            )
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/builder/circuit_breaker
+    let get_lighthouse_builder_circuit_breaker = warp::path("lighthouse")
+        .and(warp::path("builder"))
+        .and(warp::path("circuit_breaker"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .builder_circuit_breaker_status()
+                        .map(api_types::GenericResponse::from)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
     // GET lighthouse/merge_readiness
     let get_lighthouse_merge_readiness = warp::path("lighthouse")
         .and(warp::path("merge_readiness"))
@@ -4451,6 +5400,12 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::LateHead => {
                                     event_handler.subscribe_late_head()
                                 }
+                                api_types::EventTopic::ProposerDutiesReorg => {
+                                    event_handler.subscribe_proposer_duties_reorg()
+                                }
+                                api_types::EventTopic::ExecutionOptimisticStatus => {
+                                    event_handler.subscribe_execution_optimistic_status()
+                                }
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
@@ -4593,18 +5548,40 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_network)
+                .uor(get_lighthouse_network_fork_topics)
+                .uor(get_lighthouse_network_enr)
+                .uor(get_lighthouse_network_discovery)
+                .uor(get_lighthouse_network_gossip_rejections)
+                .uor(get_lighthouse_beacon_processor)
+                .uor(get_lighthouse_beacon_processor_reprocess_queue)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_fork_choice_last_recompute)
+                .uor(get_lighthouse_block_execution_status)
+                .uor(get_lighthouse_beacon_blob_propagation)
+                .uor(get_lighthouse_validator_monitor_sync_committee)
                 .uor(get_lighthouse_validator_inclusion_global)
+                .uor(get_lighthouse_validator_duties_attester_committee)
+                .uor(get_lighthouse_validator_blocks_dry_run)
                 .uor(get_lighthouse_validator_inclusion)
+                .uor(get_lighthouse_validator_attestation_inclusions)
                 .uor(get_lighthouse_eth1_syncing)
                 .uor(get_lighthouse_eth1_block_cache)
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_beacon_states_ssz)
+                .uor(get_lighthouse_beacon_states_historical_summaries)
+                .uor(get_lighthouse_beacon_states_proof)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_caches)
+                .uor(get_lighthouse_graffiti)
+                .uor(get_lighthouse_proposer_preparation)
                 .uor(get_lighthouse_block_rewards)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(get_lighthouse_block_packing_efficiency)
+                .uor(get_lighthouse_epoch_timings)
+                .uor(get_lighthouse_proposer_reorg_last_decision)
+                .uor(get_lighthouse_builder_circuit_breaker)
                 .uor(get_lighthouse_merge_readiness)
                 .uor(get_events)
                 .uor(get_expected_withdrawals)
@@ -4645,44 +5622,118 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_database_compact)
+                    .uor(post_lighthouse_graffiti)
+                    .uor(post_lighthouse_fork_choice_recompute)
+                    .uor(post_lighthouse_network_enr)
+                    .uor(post_lighthouse_network_gossip_replay)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
                     .recover(warp_utils::reject::handle_rejection),
             ),
         )
-        .recover(warp_utils::reject::handle_rejection)
-        .with(slog_logging(log.clone()))
+        .uor(
+            warp::patch().and(
+                patch_lighthouse_caches
+                    .uor(patch_lighthouse_logging)
+                    .recover(warp_utils::reject::handle_rejection),
+            ),
+        )
+        .recover(warp_utils::reject::handle_rejection);
+
+    // Assign each request an ID and record how long it took to handle.
+    let request_log = log.clone();
+    let routes = warp::method()
+        .and(warp::path::full())
+        .and(request_tracing::request_id())
+        .and(warp::any().map(Instant::now))
+        .and(routes)
+        .map(
+            move |method: Method, path: FullPath, request_id: String, start: Instant, reply| {
+                let response = reply.into_response();
+                let elapsed = start.elapsed();
+                log_request(
+                    &request_log,
+                    &request_id,
+                    &method,
+                    &path,
+                    response.status(),
+                    elapsed,
+                );
+                request_tracing::add_tracing_headers(response, &request_id, elapsed)
+            },
+        )
         .with(prometheus_metrics())
         // Add a `Server` header.
-        .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
-        .with(cors_builder.build())
-        .boxed();
+        .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()));
+    let routes = cors.wrap(routes).boxed();
+
+    // The server shuts down once both the TCP and Unix domain socket listeners have.
+    let shutdown = futures::future::FutureExt::shared(shutdown);
+
+    let unix_socket_server = match &config.unix_socket {
+        Some(path) => {
+            let (incoming, socket_file) = unix_socket::bind(path)?;
+            let server = warp::serve(routes.clone())
+                .serve_incoming_with_graceful_shutdown(incoming, shutdown.clone());
+            // Remove the socket once the server has stopped.
+            let server = async move {
+                server.await;
+                drop(socket_file);
+            };
+
+            info!(
+                log,
+                "HTTP API is being served on a Unix domain socket";
+                "path" => %path.display(),
+            );
+
+            Some(server)
+        }
+        None => None,
+    };
 
     let http_socket: SocketAddr = SocketAddr::new(config.listen_addr, config.listen_port);
     let http_server: HttpServer = match config.tls_config {
         Some(tls_config) => {
-            let (socket, server) = warp::serve(routes)
+            let client_auth = tls_config.client_ca.is_some();
+            let mut tls_server = warp::serve(routes)
                 .tls()
                 .cert_path(tls_config.cert)
-                .key_path(tls_config.key)
-                .try_bind_with_graceful_shutdown(http_socket, async {
-                    shutdown.await;
-                })?;
+                .key_path(tls_config.key);
+            // Unauthorized clients fail the TLS handshake, so never reach the handlers.
+            if let Some(client_ca) = tls_config.client_ca {
+                tls_server = tls_server.client_auth_required_path(client_ca);
+            }
+            let (socket, server) =
+                tls_server.try_bind_with_graceful_shutdown(http_socket, shutdown)?;
 
-            info!(log, "HTTP API is being served over TLS";);
+            info!(log, "HTTP API is being served over TLS"; "client_auth" => client_auth);
 
             (socket, Box::pin(server))
         }
         None => {
             let (socket, server) =
-                warp::serve(routes).try_bind_with_graceful_shutdown(http_socket, async {
-                    shutdown.await;
-                })?;
+                warp::serve(routes).try_bind_with_graceful_shutdown(http_socket, shutdown)?;
             (socket, Box::pin(server))
         }
     };
 
+    let http_server: HttpServer = match unix_socket_server {
+        Some(unix_socket_server) => {
+            let (socket, server) = http_server;
+            let both = futures::future::join(server, unix_socket_server);
+            (
+                socket,
+                Box::pin(async move {
+                    both.await;
+                }),
+            )
+        }
+        None => http_server,
+    };
+
     info!(
         log,
         "HTTP API started";
@@ -4692,17 +5743,70 @@ pub fn serve<T: BeaconChainTypes>(
     Ok(http_server)
 }
 
+/// Decompresses the RANDAO reveal of a block production request, returning it with the
+/// verification to apply to the produced block.
+///
+/// With `skip_randao_verification`, the reveal must be either the point at infinity or all zeros,
+/// and is not verified. Without it, an all-zero reveal is rejected rather than failing verification
+/// during block production.
+fn randao_reveal_for_production(
+    query: &api_types::ValidatorBlocksQuery,
+) -> Result<(Signature, ProduceBlockVerification), warp::Rejection> {
+    let randao_reveal = query.randao_reveal.decompress().map_err(|e| {
+        warp_utils::reject::custom_bad_request(format!(
+            "randao reveal is not a valid BLS signature: {:?}",
+            e
+        ))
+    })?;
+
+    if query.skip_randao_verification == SkipRandaoVerification::Yes {
+        if !randao_reveal.is_infinity() && !randao_reveal.is_empty() {
+            return Err(warp_utils::reject::custom_bad_request(
+                "randao_reveal must be point-at-infinity or zero if verification is skipped".into(),
+            ));
+        }
+        Ok((randao_reveal, ProduceBlockVerification::NoVerification))
+    } else if randao_reveal.is_empty() {
+        Err(warp_utils::reject::custom_bad_request(
+            "randao_reveal must not be zero unless skip_randao_verification is set".into(),
+        ))
+    } else {
+        Ok((randao_reveal, ProduceBlockVerification::VerifyRandao))
+    }
+}
+
+/// The fork of the head block, which the operations in the pools are for.
+fn head_fork_name<T: BeaconChainTypes>(chain: &BeaconChain<T>) -> ForkName {
+    let head_slot = chain.canonical_head.cached_head().head_slot();
+    chain.spec.fork_name_at_slot::<T::EthSpec>(head_slot)
+}
+
+/// Responds with the operations from one of the pools, encoded as SSZ if the `accept` header asks
+/// for it.
+fn pool_operations_response<O: Encode + Serialize>(
+    operations: Vec<O>,
+    fork_name: ForkName,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    match accept_header {
+        Some(api_types::Accept::Ssz) => Response::builder()
+            .status(200)
+            .header("Content-Type", "application/octet-stream")
+            .body(operations.as_ssz_bytes().into())
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+            }),
+        _ => Ok(warp::reply::json(&api_types::GenericResponse::from(operations)).into_response()),
+    }
+    .map(|resp| add_consensus_version_header(resp, fork_name))
+}
+
 /// Publish a message to the libp2p pubsub network.
 fn publish_pubsub_message<T: EthSpec>(
     network_tx: &UnboundedSender<NetworkMessage<T>>,
     message: PubsubMessage<T>,
 ) -> Result<(), warp::Rejection> {
-    publish_network_message(
-        network_tx,
-        NetworkMessage::Publish {
-            messages: vec![message],
-        },
-    )
+    publish_network_message(network_tx, NetworkMessage::publish(vec![message]))
 }
 
 /// Publish a message to the libp2p network.
@@ -0,0 +1,36 @@
+use eth2::lighthouse::{LogDrain, LoggingFilters, LoggingPatchRequest};
+use eth2::types::GenericResponse;
+use logging::LogFilters;
+
+/// Handler for `PATCH lighthouse/admin/logging`.
+///
+/// Returns the filters of every drain after the update.
+pub fn update(
+    request: LoggingPatchRequest,
+    log_filters: Option<LogFilters>,
+) -> Result<GenericResponse<LoggingFilters>, warp::Rejection> {
+    let log_filters = log_filters.ok_or_else(|| {
+        warp_utils::reject::custom_server_error("log filters are not available".to_string())
+    })?;
+
+    let handles = match request.drain {
+        None => std::iter::once(&log_filters.terminal)
+            .chain(log_filters.file.as_ref())
+            .collect::<Vec<_>>(),
+        Some(LogDrain::Terminal) => vec![&log_filters.terminal],
+        Some(LogDrain::File) => vec![log_filters.file.as_ref().ok_or_else(|| {
+            warp_utils::reject::custom_bad_request("file logging is disabled".to_string())
+        })?],
+    };
+    // Every handle accepts the same directives, so either all of them are updated or none are.
+    for handle in handles {
+        handle
+            .apply(&request.filter)
+            .map_err(warp_utils::reject::custom_bad_request)?;
+    }
+
+    Ok(GenericResponse::from(LoggingFilters {
+        terminal: log_filters.terminal.get().to_string(),
+        file: log_filters.file.map(|file| file.get().to_string()),
+    }))
+}
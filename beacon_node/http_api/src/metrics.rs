@@ -17,6 +17,12 @@ lazy_static::lazy_static! {
         &["path"]
     );
 
+    pub static ref HTTP_API_CONCURRENCY_LIMIT_REJECTIONS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "http_api_concurrency_limit_rejections_total",
+        "Count of HTTP requests rejected because too many requests of the same class were in flight",
+        &["class"]
+    );
+
     pub static ref HTTP_API_BEACON_PROPOSER_CACHE_TIMES: Result<Histogram> = try_create_histogram(
         "http_api_beacon_proposer_cache_build_times",
         "Duration to process HTTP requests per path",
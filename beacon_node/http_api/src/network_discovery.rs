@@ -0,0 +1,23 @@
+use crate::publish_network_message;
+use lighthouse_network::discovery::DiscoveryInfo;
+use network::NetworkMessage;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+use types::EthSpec;
+
+/// Handler for `GET lighthouse/network/discovery`.
+///
+/// The routing table and query statistics are owned by the discovery behaviour, so they are
+/// requested from the network service.
+pub async fn get_discovery_info<E: EthSpec>(
+    network_tx: UnboundedSender<NetworkMessage<E>>,
+) -> Result<DiscoveryInfo, warp::Rejection> {
+    let (result_tx, result_rx) = oneshot::channel();
+    publish_network_message(&network_tx, NetworkMessage::DiscoveryInfo { result_tx })?;
+
+    result_rx.await.map_err(|_| {
+        warp_utils::reject::custom_server_error(
+            "network service dropped the discovery info request".to_string(),
+        )
+    })
+}
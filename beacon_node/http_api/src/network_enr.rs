@@ -0,0 +1,65 @@
+use crate::publish_network_message;
+use eth2::lighthouse::{EnrUpdateRequest, LocalEnr};
+use lighthouse_network::discovery::EnrUpdate;
+use lighthouse_network::{Enr, EnrExt, NetworkGlobals};
+use network::NetworkMessage;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot;
+use types::EthSpec;
+
+/// Handler for `GET lighthouse/network/enr`.
+pub fn get_local_enr<E: EthSpec>(network_globals: &NetworkGlobals<E>) -> LocalEnr {
+    local_enr_info(&network_globals.local_enr())
+}
+
+/// Handler for `POST lighthouse/network/enr`.
+///
+/// The update is applied by the network service, which updates discv5 and the addresses reported
+/// via identify before the response is returned.
+pub async fn update_local_enr<E: EthSpec>(
+    request: EnrUpdateRequest,
+    network_globals: Arc<NetworkGlobals<E>>,
+    network_tx: UnboundedSender<NetworkMessage<E>>,
+) -> Result<LocalEnr, warp::Rejection> {
+    let update = EnrUpdate {
+        ip: request.ip,
+        tcp_port: request.tcp_port,
+        udp_port: request.udp_port,
+        quic_port: request.quic_port,
+    };
+    update
+        .validate(&network_globals.config)
+        .map_err(warp_utils::reject::custom_bad_request)?;
+
+    let (result_tx, result_rx) = oneshot::channel();
+    publish_network_message(&network_tx, NetworkMessage::UpdateEnr { update, result_tx })?;
+
+    let enr = result_rx
+        .await
+        .map_err(|_| {
+            warp_utils::reject::custom_server_error(
+                "network service dropped the ENR update".to_string(),
+            )
+        })?
+        .map_err(warp_utils::reject::custom_bad_request)?;
+
+    Ok(local_enr_info(&enr))
+}
+
+fn local_enr_info(enr: &Enr) -> LocalEnr {
+    LocalEnr {
+        enr: enr.to_base64(),
+        seq: enr.seq(),
+        node_id: enr.node_id().to_string(),
+        peer_id: enr.peer_id().to_string(),
+        ip: enr.ip4(),
+        tcp_port: enr.tcp4(),
+        udp_port: enr.udp4(),
+        quic_port: enr.quic4(),
+        ip6: enr.ip6(),
+        tcp6_port: enr.tcp6(),
+        udp6_port: enr.udp6(),
+        quic6_port: enr.quic6(),
+    }
+}
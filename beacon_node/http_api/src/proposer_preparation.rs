@@ -0,0 +1,38 @@
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::ProposerPreparationEntry;
+use eth2::types::GenericResponse;
+use serde_utils::quoted_u64::Quoted;
+use std::sync::Arc;
+use warp_utils::reject::beacon_chain_error;
+
+/// Handler for `GET lighthouse/proposer_preparation`.
+///
+/// Returns the fee recipient held for each prepared proposer, sorted by validator index, with the
+/// gas limit of its latest builder registration.
+pub fn get_proposer_preparations<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<GenericResponse<Vec<ProposerPreparationEntry>>, warp::Rejection> {
+    let execution_layer = chain
+        .execution_layer
+        .as_ref()
+        .ok_or(BeaconChainError::ExecutionLayerMissing)
+        .map_err(beacon_chain_error)?;
+
+    let mut entries = execution_layer
+        .proposer_preparations_blocking()
+        .into_iter()
+        .map(|(update_epoch, preparation)| ProposerPreparationEntry {
+            validator_index: preparation.validator_index,
+            fee_recipient: preparation.fee_recipient,
+            update_epoch,
+            gas_limit: execution_layer
+                .builder_registration(preparation.validator_index)
+                .map(|registration| Quoted {
+                    value: registration.message.gas_limit,
+                }),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.validator_index);
+
+    Ok(GenericResponse::from(entries))
+}
@@ -11,13 +11,14 @@ use eth2::types::{FullPayloadContents, SignedBlockContents};
 use execution_layer::ProvenancedPayload;
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
-use slog::{debug, error, info, warn, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
 use tree_hash::TreeHash;
+use types::blob_sidecar::BlobIdentifier;
 use types::{
     AbstractExecPayload, BeaconBlockRef, BlindedPayload, EthSpec, ExecPayload, ExecutionBlockHash,
     ForkName, FullPayload, FullPayloadMerge, Hash256, SignedBeaconBlock, SignedBlobSidecarList,
@@ -81,12 +82,18 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
             | SignedBeaconBlock::Altair(_)
             | SignedBeaconBlock::Merge(_)
             | SignedBeaconBlock::Capella(_) => {
-                crate::publish_pubsub_message(&sender, PubsubMessage::BeaconBlock(block.clone()))
-                    .map_err(|_| BlockError::BeaconChainError(BeaconChainError::UnableToPublish))?;
+                crate::publish_network_message(
+                    &sender,
+                    NetworkMessage::publish_own_block(block.clone()),
+                )
+                .map_err(|_| BlockError::BeaconChainError(BeaconChainError::UnableToPublish))?;
             }
             SignedBeaconBlock::Deneb(_) => {
-                crate::publish_pubsub_message(&sender, PubsubMessage::BeaconBlock(block.clone()))
-                    .map_err(|_| BlockError::BeaconChainError(BeaconChainError::UnableToPublish))?;
+                crate::publish_network_message(
+                    &sender,
+                    NetworkMessage::publish_own_block(block.clone()),
+                )
+                .map_err(|_| BlockError::BeaconChainError(BeaconChainError::UnableToPublish))?;
                 if let Some(signed_blobs) = blobs_opt {
                     for (blob_index, blob) in signed_blobs.into_iter().enumerate() {
                         crate::publish_pubsub_message(
@@ -147,6 +154,32 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlockConten
 
     let block_root = block_root.unwrap_or(gossip_verified_block.block_root);
 
+    // Blobs need not be submitted alongside the block if they have already been received on
+    // gossip, in which case they're claimed from the availability checker when the block is
+    // imported. Otherwise the block can't be imported until the rest of its blobs arrive.
+    if blobs_opt.is_none() {
+        let expected_blobs = block.num_expected_blobs();
+        let received_blobs = (0..expected_blobs as u64)
+            .filter(|&index| {
+                matches!(
+                    chain
+                        .data_availability_checker
+                        .get_blob(&BlobIdentifier { block_root, index }),
+                    Ok(Some(_))
+                )
+            })
+            .count();
+        if received_blobs < expected_blobs {
+            warn!(
+                log,
+                "Block published without all of its blobs";
+                "slot" => slot,
+                "expected_blobs" => expected_blobs,
+                "received_blobs" => received_blobs,
+            );
+        }
+    }
+
     if let BroadcastValidation::Gossip = validation_level {
         publish_block(
             block.clone(),
@@ -367,9 +400,20 @@ pub async fn reconstruct_block<T: BeaconChainTypes>(
             );
 
             let full_payload = el
-                .propose_blinded_beacon_block(block_root, &block_contents)
+                .propose_blinded_beacon_block(block_root, &block_contents, chain.kzg.as_deref())
                 .await
                 .map_err(|e| {
+                    if let execution_layer::Error::InvalidBuilderBlobsBundle(error) = &e {
+                        crit!(
+                            log,
+                            "Builder faulted after signature";
+                            "msg" => "the signed block will not be published",
+                            "reason" => "invalid blobs bundle",
+                            "error" => ?error,
+                            "block_root" => ?block_root,
+                            "slot" => block.slot(),
+                        );
+                    }
                     warp_utils::reject::custom_server_error(format!(
                         "Blind block proposal failed: {:?}",
                         e
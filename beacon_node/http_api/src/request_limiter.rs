@@ -0,0 +1,153 @@
+//! Limits the number of concurrent requests to classes of expensive HTTP API endpoints.
+//!
+//! Requests which arrive whilst the limit for their class is saturated are rejected immediately
+//! with a `429 Too Many Requests` response and a `Retry-After` header, rather than being queued.
+//! This prevents a single client from monopolising the beacon node by hammering an expensive
+//! endpoint.
+
+use crate::metrics;
+use crate::task_spawner::{convert_rejection, Priority, TaskSpawner};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use types::EthSpec;
+use warp::reply::{Reply, Response};
+
+/// The delay after which rejected clients are asked to retry.
+pub const RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Limits the number of in-flight requests to a class of endpoints.
+#[derive(Clone)]
+pub struct RequestLimiter {
+    class: &'static str,
+    max_concurrent_requests: usize,
+    semaphore: Arc<Semaphore>,
+}
+
+impl RequestLimiter {
+    pub fn new(class: &'static str, max_concurrent_requests: usize) -> Self {
+        Self {
+            class,
+            max_concurrent_requests,
+            semaphore: Arc::new(Semaphore::new(max_concurrent_requests)),
+        }
+    }
+
+    /// Executes `func` as a blocking task if the limit has not been reached, otherwise returns a
+    /// `429` response without running `func`.
+    ///
+    /// The permit is moved into the blocking task, so it is held until `func` returns even if the
+    /// request is dropped beforehand (e.g. because the client disconnected).
+    pub async fn blocking_response_task<E, F, T>(
+        self,
+        task_spawner: TaskSpawner<E>,
+        priority: Priority,
+        func: F,
+    ) -> Response
+    where
+        E: EthSpec,
+        F: FnOnce() -> Result<T, warp::Rejection> + Send + Sync + 'static,
+        T: Reply + Send + 'static,
+    {
+        let Ok(permit) = self.semaphore.clone().try_acquire_owned() else {
+            metrics::inc_counter_vec(
+                &metrics::HTTP_API_CONCURRENCY_LIMIT_REJECTIONS_TOTAL,
+                &[self.class],
+            );
+            return convert_rejection::<Response>(Err(warp_utils::reject::too_many_requests(
+                format!(
+                    "more than {} concurrent {} requests",
+                    self.max_concurrent_requests, self.class
+                ),
+                RETRY_AFTER,
+            )))
+            .await;
+        };
+
+        task_spawner
+            .blocking_response_task(priority, move || {
+                let _permit = permit;
+                func()
+            })
+            .await
+    }
+
+    /// Executes `func` as a blocking task which returns a JSON-serializable object, subject to the
+    /// limit. See `Self::blocking_response_task`.
+    pub async fn blocking_json_task<E, F, T>(
+        self,
+        task_spawner: TaskSpawner<E>,
+        priority: Priority,
+        func: F,
+    ) -> Response
+    where
+        E: EthSpec,
+        F: FnOnce() -> Result<T, warp::Rejection> + Send + Sync + 'static,
+        T: Serialize + Send + 'static,
+    {
+        let func = || func().map(|t| warp::reply::json(&t).into_response());
+        self.blocking_response_task(task_spawner, priority, func)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{mpsc, Mutex};
+    use tokio::sync::oneshot;
+    use types::MainnetEthSpec;
+    use warp::http::{header, StatusCode};
+
+    fn task_spawner() -> TaskSpawner<MainnetEthSpec> {
+        TaskSpawner::new(None)
+    }
+
+    /// A request holds its permit until its blocking task has completed, even if the request
+    /// itself is dropped first.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn permit_held_until_blocking_task_completes() {
+        let limiter = RequestLimiter::new("test", 1);
+        let (started_tx, started_rx) = oneshot::channel();
+        let (release_tx, release_rx) = mpsc::channel();
+        let release_rx = Mutex::new(release_rx);
+
+        let request = tokio::spawn(limiter.clone().blocking_response_task(
+            task_spawner(),
+            Priority::P1,
+            move || {
+                started_tx.send(()).unwrap();
+                release_rx.lock().unwrap().recv().unwrap();
+                Ok(warp::reply())
+            },
+        ));
+        started_rx.await.unwrap();
+
+        // Drop the request whilst its blocking task is still running, as happens when a client
+        // disconnects.
+        request.abort();
+        assert!(request.await.unwrap_err().is_cancelled());
+
+        let response = limiter
+            .clone()
+            .blocking_response_task(task_spawner(), Priority::P1, || Ok(warp::reply()))
+            .await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "1");
+
+        // The permit is returned once the blocking task completes.
+        release_tx.send(()).unwrap();
+        for _ in 0..100 {
+            if limiter.semaphore.available_permits() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let response = limiter
+            .blocking_response_task(task_spawner(), Priority::P1, || Ok(warp::reply()))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,119 @@
+//! Request tracing for the HTTP API.
+//!
+//! Every request is assigned a request ID, taken from the `X-Request-Id` header if the client
+//! supplied a valid one, or generated otherwise. The ID is returned in the `X-Request-Id` response
+//! header along with the total handling time in `X-Response-Time-Ms`, and logged when the request
+//! completes.
+//!
+//! Filters can't share values generated for a request, so only an ID supplied by the client is
+//! attached to the logger of the handler.
+//!
+//! Expensive handlers additionally record the time spent in each stage of the request (e.g.,
+//! loading the state and serializing the response) using `ServerTiming`, which is returned in the
+//! `Server-Timing` response header.
+
+use serde::Serialize;
+use slog::{o, Logger};
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+use warp::http::header::{HeaderMap, HeaderValue};
+use warp::reply::{Reply, Response};
+use warp::Filter;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+pub const RESPONSE_TIME_HEADER: &str = "X-Response-Time-Ms";
+pub const SERVER_TIMING_HEADER: &str = "Server-Timing";
+
+/// Client-supplied request IDs longer than this are ignored.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Returns the request ID supplied by the client, if it is present and valid.
+///
+/// Only non-empty IDs of printable ASCII characters are accepted, so that IDs can always be
+/// echoed in the response headers and logged verbatim.
+fn supplied_request_id(headers: &HeaderMap) -> Option<String> {
+    let id = headers.get(REQUEST_ID_HEADER)?.to_str().ok()?;
+    (!id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.chars().all(|c| c.is_ascii_graphic()))
+        .then(|| id.to_string())
+}
+
+/// Provides the ID of each request, generating a new one if the client did not supply one.
+pub fn request_id() -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(|headers: HeaderMap| {
+        supplied_request_id(&headers).unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+    })
+}
+
+/// Provides `log` with the client-supplied request ID attached, so that the log lines of a handler
+/// can be correlated with the request.
+pub fn request_logger(log: Logger) -> impl Filter<Extract = (Logger,), Error = Infallible> + Clone {
+    warp::header::headers_cloned().map(move |headers: HeaderMap| {
+        match supplied_request_id(&headers) {
+            Some(request_id) => log.new(o!("request_id" => request_id)),
+            None => log.clone(),
+        }
+    })
+}
+
+/// Adds the `X-Request-Id` and `X-Response-Time-Ms` headers to `response`.
+pub fn add_tracing_headers(
+    mut response: Response,
+    request_id: &str,
+    elapsed: Duration,
+) -> Response {
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(request_id) {
+        headers.insert(REQUEST_ID_HEADER, value);
+    }
+    headers.insert(
+        RESPONSE_TIME_HEADER,
+        HeaderValue::from(elapsed.as_millis() as u64),
+    );
+    response
+}
+
+/// Records the time spent in each stage of a request, for the `Server-Timing` header.
+#[derive(Debug, Default)]
+pub struct ServerTiming {
+    stages: Vec<(&'static str, Duration)>,
+}
+
+impl ServerTiming {
+    /// Runs `func`, recording its duration against `stage`.
+    pub fn time<T>(&mut self, stage: &'static str, func: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = func();
+        self.record(stage, start.elapsed());
+        result
+    }
+
+    /// Records `duration` against `stage`.
+    pub fn record(&mut self, stage: &'static str, duration: Duration) {
+        self.stages.push((stage, duration));
+    }
+
+    /// Serializes `data` as a JSON response, recording the time taken as the `serialize` stage,
+    /// and adds the `Server-Timing` header to the response.
+    pub fn json_response<T: Serialize>(mut self, data: &T) -> Response {
+        let response = self.time("serialize", || warp::reply::json(data).into_response());
+        self.add_header(response)
+    }
+
+    /// Adds the `Server-Timing` header to `response`.
+    pub fn add_header(&self, mut response: Response) -> Response {
+        if let Ok(value) = HeaderValue::from_str(&self.header_value()) {
+            response.headers_mut().insert(SERVER_TIMING_HEADER, value);
+        }
+        response
+    }
+
+    /// Formats the stages as per the `Server-Timing` specification, with durations in
+    /// milliseconds, e.g. `state;dur=12.345, serialize;dur=1.2`.
+    fn header_value(&self) -> String {
+        self.stages
+            .iter()
+            .map(|(stage, duration)| format!("{};dur={}", stage, duration.as_secs_f64() * 1_000.0))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
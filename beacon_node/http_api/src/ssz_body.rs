@@ -0,0 +1,63 @@
+//! Handles SSZ request bodies which may be compressed with framed snappy.
+//!
+//! Compression is advertised to clients with the `ACCEPTED_ENCODINGS_HEADER` on `node/version`
+//! and requested by clients with a `Content-Encoding: snappy` header. Other encodings are rejected
+//! with a `415`. The size of decompressed bodies is capped, so a small compressed body can't be
+//! used to exhaust memory.
+
+use bytes::Bytes;
+use eth2::SNAPPY_ENCODING;
+use snap::read::FrameDecoder;
+use std::io::Read;
+use warp::{Filter, Rejection};
+
+/// The maximum size of a decompressed SSZ body, which is comfortably larger than any valid block
+/// and its blobs.
+pub const MAX_DECOMPRESSED_SIZE: u64 = 32 * 1024 * 1024;
+
+/// A raw SSZ request body along with its `Content-Encoding`.
+pub struct SszBody {
+    content_encoding: Option<String>,
+    bytes: Bytes,
+}
+
+impl SszBody {
+    /// Returns the uncompressed SSZ bytes of the body.
+    pub fn decode(self) -> Result<Bytes, Rejection> {
+        match self.content_encoding.as_deref().map(str::trim) {
+            None | Some("identity") => Ok(self.bytes),
+            Some(encoding) if encoding.eq_ignore_ascii_case(SNAPPY_ENCODING) => {
+                decompress_snappy(&self.bytes)
+            }
+            Some(encoding) => Err(warp_utils::reject::unsupported_media_type(format!(
+                "unsupported Content-Encoding: {encoding}"
+            ))),
+        }
+    }
+}
+
+/// Extracts an `SszBody` from the request.
+pub fn ssz_body_filter() -> impl Filter<Extract = (SszBody,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("content-encoding")
+        .and(warp::body::bytes())
+        .map(|content_encoding, bytes| SszBody {
+            content_encoding,
+            bytes,
+        })
+}
+
+fn decompress_snappy(bytes: &[u8]) -> Result<Bytes, Rejection> {
+    let mut decompressed = vec![];
+    FrameDecoder::new(bytes)
+        .take(MAX_DECOMPRESSED_SIZE + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| warp_utils::reject::custom_bad_request(format!("invalid snappy body: {e}")))?;
+
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_SIZE {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "decompressed body is larger than {MAX_DECOMPRESSED_SIZE} bytes"
+        )));
+    }
+
+    Ok(decompressed.into())
+}
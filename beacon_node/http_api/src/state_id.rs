@@ -1,5 +1,5 @@
 use crate::ExecutionOptimistic;
-use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, CachedHead};
 use eth2::types::StateId as CoreStateId;
 use std::fmt;
 use std::str::FromStr;
@@ -32,7 +32,7 @@ impl StateId {
                 return Ok((
                     cached_head.head_state_root(),
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&cached_head),
                 ));
             }
             CoreStateId::Genesis => return Ok((chain.genesis_state_root, false, true)),
@@ -44,11 +44,14 @@ impl StateId {
                 (slot, execution_optimistic, true)
             }
             CoreStateId::Justified => {
-                let justified_checkpoint =
-                    chain.canonical_head.cached_head().justified_checkpoint();
+                let cached_head = chain.canonical_head.cached_head();
+                let justified_checkpoint = cached_head.justified_checkpoint();
                 let (slot, execution_optimistic) =
                     checkpoint_slot_and_execution_optimistic(chain, justified_checkpoint)?;
-                (slot, execution_optimistic, false)
+                // The justified checkpoint is only finalized if it is also the finalized one.
+                let finalized =
+                    justified_checkpoint.epoch <= cached_head.finalized_checkpoint().epoch;
+                (slot, execution_optimistic, finalized)
             }
             CoreStateId::Slot(slot) => (
                 *slot,
@@ -120,6 +123,12 @@ impl StateId {
             }
         };
 
+        if let CoreStateId::Slot(slot) = &self.0 {
+            if let Some((root, _)) = skip_slot_state(chain, *slot)? {
+                return Ok((root, execution_optimistic, finalized));
+            }
+        }
+
         let root = chain
             .state_root_at_slot(slot)
             .map_err(warp_utils::reject::beacon_chain_error)?
@@ -183,10 +192,18 @@ impl StateId {
                         .beacon_state
                         .clone_with_only_committee_caches(),
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&cached_head),
                 ));
             }
-            CoreStateId::Slot(slot) => (self.root(chain)?, Some(*slot)),
+            CoreStateId::Slot(slot) => {
+                if let Some((_, state)) = skip_slot_state(chain, *slot)? {
+                    let execution_optimistic = chain
+                        .is_optimistic_or_invalid_head()
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+                    return Ok((state, execution_optimistic, false));
+                }
+                (self.root(chain)?, Some(*slot))
+            }
             _ => (self.root(chain)?, None),
         };
 
@@ -229,7 +246,7 @@ impl StateId {
                 return func(
                     &head.snapshot.beacon_state,
                     execution_status.is_optimistic_or_invalid(),
-                    false,
+                    head_is_finalized(&head),
                 );
             }
             _ => self.state(chain)?,
@@ -253,6 +270,35 @@ impl fmt::Display for StateId {
     }
 }
 
+/// Returns the head state advanced through skipped slots to `slot`, along with its root, if `slot`
+/// is later than the head block but not later than the current slot.
+///
+/// Advanced states are cached, so repeated requests for the same slot are cheap.
+fn skip_slot_state<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    slot: Slot,
+) -> Result<Option<(Hash256, BeaconState<T::EthSpec>)>, warp::Rejection> {
+    let cached_head = chain.canonical_head.cached_head();
+    let current_slot = chain
+        .slot()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    if slot <= cached_head.head_slot() || slot > current_slot {
+        return Ok(None);
+    }
+
+    chain
+        .get_skip_slot_state(cached_head.head_state_root(), slot)
+        .map_err(warp_utils::reject::beacon_chain_error)
+}
+
+/// Returns `true` if the head block (and state) has been finalized.
+///
+/// The finalized checkpoint is always an ancestor of the head, so this is only the case if no
+/// blocks have been imported on top of the finalized block, e.g. at genesis.
+pub fn head_is_finalized<E: EthSpec>(cached_head: &CachedHead<E>) -> Finalized {
+    cached_head.head_block_root() == cached_head.finalized_checkpoint().root
+}
+
 /// Returns the first slot of the checkpoint's `epoch` and the execution status of the checkpoint's
 /// `root`.
 pub fn checkpoint_slot_and_execution_optimistic<T: BeaconChainTypes>(
@@ -1,19 +1,20 @@
 use crate::{BlockId, ExecutionOptimistic};
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
-use eth2::lighthouse::SyncCommitteeReward;
-use eth2::types::ValidatorId;
-use slog::{debug, Logger};
+use eth2::types::{SyncCommitteeReward, ValidatorId};
 use state_processing::BlockReplayer;
 use std::sync::Arc;
 use types::{BeaconState, SignedBlindedBeaconBlock};
 use warp_utils::reject::{beacon_chain_error, custom_not_found};
 
+/// Computes the sync committee rewards for the block identified by `block_id`.
+///
+/// Only the rewards of `validators` are returned, or those of the whole committee if `validators`
+/// is empty. Members that did not participate have negative rewards.
 pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     block_id: BlockId,
     validators: Vec<ValidatorId>,
-    log: Logger,
-) -> Result<(Option<Vec<SyncCommitteeReward>>, ExecutionOptimistic, bool), warp::Rejection> {
+) -> Result<(Vec<SyncCommitteeReward>, ExecutionOptimistic, bool), warp::Rejection> {
     let (block, execution_optimistic, finalized) = block_id.blinded_block(&chain)?;
 
     let mut state = get_state_before_applying_block(chain.clone(), &block)?;
@@ -22,26 +23,21 @@ pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
         .compute_sync_committee_rewards(block.message(), &mut state)
         .map_err(beacon_chain_error)?;
 
-    let data = if reward_payload.is_empty() {
-        debug!(log, "compute_sync_committee_rewards returned empty");
-        None
-    } else if validators.is_empty() {
-        Some(reward_payload)
+    let data = if validators.is_empty() {
+        reward_payload
     } else {
-        Some(
-            reward_payload
-                .into_iter()
-                .filter(|reward| {
-                    validators.iter().any(|validator| match validator {
-                        ValidatorId::Index(i) => reward.validator_index == *i,
-                        ValidatorId::PublicKey(pubkey) => match state.get_validator_index(pubkey) {
-                            Ok(Some(i)) => reward.validator_index == i as u64,
-                            _ => false,
-                        },
-                    })
+        reward_payload
+            .into_iter()
+            .filter(|reward| {
+                validators.iter().any(|validator| match validator {
+                    ValidatorId::Index(i) => reward.validator_index == *i,
+                    ValidatorId::PublicKey(pubkey) => match state.get_validator_index(pubkey) {
+                        Ok(Some(i)) => reward.validator_index == i as u64,
+                        _ => false,
+                    },
                 })
-                .collect::<Vec<SyncCommitteeReward>>(),
-        )
+            })
+            .collect::<Vec<SyncCommitteeReward>>()
     };
 
     Ok((data, execution_optimistic, finalized))
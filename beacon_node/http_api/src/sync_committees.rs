@@ -43,9 +43,10 @@ pub fn sync_committee_duties<T: BeaconChainTypes>(
         .is_optimistic_or_invalid_head()
         .map_err(warp_utils::reject::beacon_chain_error)?;
 
-    // Try using the head's sync committees to satisfy the request. This should be sufficient for
-    // the vast majority of requests. Rather than checking if we think the request will succeed in a
-    // way prone to data races, we attempt the request immediately and check the error code.
+    // Try using the cached duties or the head's sync committees to satisfy the request. This should
+    // be sufficient for the vast majority of requests. Rather than checking if we think the request
+    // will succeed in a way prone to data races, we attempt the request immediately and check the
+    // error code.
     match chain.sync_committee_duties_from_head(request_epoch, request_indices) {
         Ok(duties) => return Ok(convert_to_response(duties, execution_optimistic)),
         Err(BeaconChainError::SyncDutiesError(BeaconStateError::SyncCommitteeNotKnown {
@@ -110,9 +111,7 @@ fn duties_from_state_load<T: BeaconChainTypes>(
 
         let state = chain.state_at_slot(load_slot, StateSkipConfig::WithoutStateRoots)?;
 
-        state
-            .get_sync_committee_duties(request_epoch, request_indices, &chain.spec)
-            .map_err(BeaconChainError::SyncDutiesError)
+        chain.sync_committee_duties_from_state(request_epoch, request_indices, &state)
     } else {
         Err(BeaconChainError::SyncDutiesError(
             BeaconStateError::SyncCommitteeNotKnown {
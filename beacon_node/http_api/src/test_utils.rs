@@ -1,9 +1,15 @@
+use crate::api_token::ApiToken;
 use crate::{Config, Context};
 use beacon_chain::{
     test_utils::{BeaconChainHarness, BoxedMutator, Builder, EphemeralHarnessType},
     BeaconChain, BeaconChainTypes,
 };
-use beacon_processor::{BeaconProcessor, BeaconProcessorChannels, BeaconProcessorConfig};
+use beacon_processor::work_reprocessing_queue::{
+    ReprocessQueueMessage, ReprocessQueueSnapshotSend,
+};
+use beacon_processor::{
+    BeaconProcessor, BeaconProcessorChannels, BeaconProcessorConfig, BeaconProcessorSend,
+};
 use directory::DEFAULT_ROOT_DIR;
 use eth2::{BeaconNodeHttpClient, Timeouts};
 use lighthouse_network::{
@@ -14,7 +20,7 @@ use lighthouse_network::{
     },
     rpc::methods::{MetaData, MetaDataV2},
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield, SyncState},
-    ConnectedPoint, Enr, NetworkGlobals, PeerId, PeerManager,
+    ConnectedPoint, Enr, NetworkConfig, NetworkGlobals, PeerId, PeerManager,
 };
 use logging::test_logger;
 use network::{NetworkReceivers, NetworkSenders};
@@ -22,10 +28,12 @@ use sensitive_url::SensitiveUrl;
 use slog::Logger;
 use std::future::Future;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use store::MemoryStore;
 use task_executor::test_utils::TestRuntime;
+use tokio::sync::mpsc;
 use types::{ChainSpec, EthSpec};
 
 pub const TCP_PORT: u16 = 42;
@@ -38,6 +46,9 @@ pub struct InteractiveTester<E: EthSpec> {
     pub harness: BeaconChainHarness<EphemeralHarnessType<E>>,
     pub client: BeaconNodeHttpClient,
     pub network_rx: NetworkReceivers<E>,
+    /// The admin API token, which `client` has already been given, or `None` if the admin
+    /// endpoints are disabled.
+    pub admin_token: Option<String>,
 }
 
 /// The result of calling `create_api_server`.
@@ -49,6 +60,13 @@ pub struct ApiServer<E: EthSpec, SFut: Future<Output = ()>> {
     pub network_rx: NetworkReceivers<E>,
     pub local_enr: Enr,
     pub external_peer_id: PeerId,
+    pub beacon_processor_send: BeaconProcessorSend<E>,
+    pub work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
+    /// The admin API token, or `None` if the admin endpoints are disabled.
+    ///
+    /// Unless `Config::admin_token_path` is set, the token is written to a temporary file which is
+    /// removed once the server has started.
+    pub admin_token: Option<String>,
 }
 
 type HarnessBuilder<E> = Builder<EphemeralHarnessType<E>>;
@@ -60,11 +78,37 @@ impl<E: EthSpec> InteractiveTester<E> {
         Self::new_with_initializer_and_mutator(spec, validator_count, None, None).await
     }
 
+    pub async fn new_with_config(
+        spec: Option<ChainSpec>,
+        validator_count: usize,
+        config: Config,
+    ) -> Self {
+        Self::new_with_initializer_mutator_and_config(spec, validator_count, None, None, config)
+            .await
+    }
+
     pub async fn new_with_initializer_and_mutator(
         spec: Option<ChainSpec>,
         validator_count: usize,
         initializer: Option<Initializer<E>>,
         mutator: Option<Mutator<E>>,
+    ) -> Self {
+        Self::new_with_initializer_mutator_and_config(
+            spec,
+            validator_count,
+            initializer,
+            mutator,
+            test_config(),
+        )
+        .await
+    }
+
+    pub async fn new_with_initializer_mutator_and_config(
+        spec: Option<ChainSpec>,
+        validator_count: usize,
+        initializer: Option<Initializer<E>>,
+        mutator: Option<Mutator<E>>,
+        config: Config,
     ) -> Self {
         let mut harness_builder = BeaconChainHarness::builder(E::default())
             .spec_or_default(spec)
@@ -93,17 +137,19 @@ impl<E: EthSpec> InteractiveTester<E> {
             server,
             listening_socket,
             network_rx,
+            admin_token,
             ..
-        } = create_api_server(
+        } = create_api_server_with_config(
             harness.chain.clone(),
             &harness.runtime,
             harness.logger().clone(),
+            config,
         )
         .await;
 
         tokio::spawn(server);
 
-        let client = BeaconNodeHttpClient::new(
+        let mut client = BeaconNodeHttpClient::new(
             SensitiveUrl::parse(&format!(
                 "http://{}:{}",
                 listening_socket.ip(),
@@ -112,23 +158,58 @@ impl<E: EthSpec> InteractiveTester<E> {
             .unwrap(),
             Timeouts::set_all(Duration::from_secs(1)),
         );
+        if let Some(admin_token) = &admin_token {
+            client.set_admin_token(admin_token.clone().into());
+        }
 
         Self {
             harness,
             client,
             network_rx,
+            admin_token,
         }
     }
 }
 
+/// The configuration used for the HTTP API in tests.
+pub fn test_config() -> Config {
+    Config {
+        enabled: true,
+        // Use port 0 to allocate a new unused port.
+        listen_port: 0,
+        data_dir: PathBuf::from(DEFAULT_ROOT_DIR),
+        enable_fork_choice_recompute: true,
+        ..Config::default()
+    }
+}
+
 pub async fn create_api_server<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     test_runtime: &TestRuntime,
     log: Logger,
 ) -> ApiServer<T::EthSpec, impl Future<Output = ()>> {
-    // Use port 0 to allocate a new unused port.
-    let port = 0;
+    create_api_server_with_config(chain, test_runtime, log, test_config()).await
+}
 
+pub async fn create_api_server_with_config<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    test_runtime: &TestRuntime,
+    log: Logger,
+    mut config: Config,
+) -> ApiServer<T::EthSpec, impl Future<Output = ()>> {
+    // Use a new token for each server, outside of the (non-existent) data directory. The token is
+    // read when the server starts, so a generated token file is removed once the server is running.
+    let generated_admin_token_path =
+        (config.admin_token_path.is_none() && !config.disable_admin_endpoints).then(|| {
+            std::env::temp_dir().join(format!("api-token-{:016x}.txt", rand::random::<u64>()))
+        });
+    if let Some(path) = &generated_admin_token_path {
+        config.admin_token_path = Some(path.clone());
+    }
+    let admin_token_path = config
+        .admin_token_path
+        .clone()
+        .filter(|_| !config.disable_admin_endpoints);
     let (network_senders, network_receivers) = NetworkSenders::new();
 
     // Default metadata
@@ -144,12 +225,13 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         meta_data,
         vec![],
         false,
+        Arc::new(NetworkConfig::default()),
         &log,
     ));
 
     // Only a peer manager can add peers, so we create a dummy manager.
-    let config = lighthouse_network::peer_manager::config::Config::default();
-    let mut pm = PeerManager::new(config, network_globals.clone(), &log).unwrap();
+    let pm_config = lighthouse_network::peer_manager::config::Config::default();
+    let mut pm = PeerManager::new(pm_config, network_globals.clone(), &log).unwrap();
 
     // add a peer
     let peer_id = PeerId::random();
@@ -184,9 +266,12 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         beacon_processor_rx,
         work_reprocessing_tx,
         work_reprocessing_rx,
+        snapshot_tx,
+        snapshot_rx,
     } = BeaconProcessorChannels::new(&beacon_processor_config);
 
     let beacon_processor_send = beacon_processor_tx;
+    let reprocess_send = work_reprocessing_tx.clone();
     BeaconProcessor {
         network_globals: network_globals.clone(),
         executor: test_runtime.task_executor.clone(),
@@ -198,6 +283,7 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         beacon_processor_rx,
         work_reprocessing_tx,
         work_reprocessing_rx,
+        snapshot_rx,
         None,
         chain.slot_clock.clone(),
         chain.spec.maximum_gossip_clock_disparity(),
@@ -205,22 +291,30 @@ pub async fn create_api_server<T: BeaconChainTypes>(
     .unwrap();
 
     let ctx = Arc::new(Context {
-        config: Config {
-            enabled: true,
-            listen_port: port,
-            data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
-            ..Config::default()
-        },
+        config,
         chain: Some(chain),
         network_senders: Some(network_senders),
         network_globals: Some(network_globals),
-        beacon_processor_send: Some(beacon_processor_send),
+        beacon_processor_send: Some(beacon_processor_send.clone()),
+        beacon_processor_snapshot_send: Some(snapshot_tx),
+        reprocess_queue_snapshot_send: Some(ReprocessQueueSnapshotSend(reprocess_send.clone())),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
+        log_filters: None,
         log,
     });
 
     let (listening_socket, server) = crate::serve(ctx, test_runtime.task_executor.exit()).unwrap();
+    let admin_token = admin_token_path.map(|path| {
+        ApiToken::create_or_open(&path)
+            .unwrap()
+            .token()
+            .unwrap()
+            .to_string()
+    });
+    if let Some(path) = generated_admin_token_path {
+        std::fs::remove_file(path).unwrap();
+    }
 
     ApiServer {
         server,
@@ -228,5 +322,8 @@ pub async fn create_api_server<T: BeaconChainTypes>(
         network_rx: network_receivers,
         local_enr: enr,
         external_peer_id: peer_id,
+        beacon_processor_send,
+        work_reprocessing_tx: reprocess_send,
+        admin_token,
     }
 }
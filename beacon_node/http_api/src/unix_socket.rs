@@ -0,0 +1,100 @@
+//! Binds the Unix domain socket which the HTTP API can be served on, in addition to TCP.
+//!
+//! Clients on the same host can use the socket to avoid the overhead of a TCP connection. Access
+//! to the socket is controlled by its file permissions, so it is never exposed to the network.
+
+use std::path::{Path, PathBuf};
+
+/// The permissions of the socket, which allow the owner and group of the beacon node process to
+/// connect.
+#[cfg(unix)]
+const SOCKET_MODE: u32 = 0o660;
+
+/// The stream of connections accepted on the socket.
+#[cfg(unix)]
+pub type Incoming = tokio_stream::wrappers::UnixListenerStream;
+#[cfg(not(unix))]
+pub type Incoming = futures::stream::Empty<Result<tokio::net::TcpStream, std::io::Error>>;
+
+/// Removes the socket file when dropped, so that it doesn't outlive the server listening on it.
+pub struct SocketFile {
+    path: PathBuf,
+}
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Binds a Unix domain socket at `path`, replacing any socket left behind by a previous run.
+///
+/// Refuses to replace anything other than a socket. The socket is removed once the returned
+/// `SocketFile` is dropped.
+#[cfg(unix)]
+pub fn bind(path: &Path) -> Result<(Incoming, SocketFile), String> {
+    use std::fs;
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if let Ok(metadata) = fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(format!(
+                "Unable to bind HTTP API to {}: file exists and is not a socket",
+                path.display()
+            ));
+        }
+        fs::remove_file(path).map_err(|e| {
+            format!(
+                "Unable to remove stale HTTP API socket {}: {}",
+                path.display(),
+                e
+            )
+        })?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)
+        .map_err(|e| format!("Unable to bind HTTP API to {}: {}", path.display(), e))?;
+    let socket_file = SocketFile {
+        path: path.to_path_buf(),
+    };
+    fs::set_permissions(path, fs::Permissions::from_mode(SOCKET_MODE)).map_err(|e| {
+        format!(
+            "Unable to set permissions of HTTP API socket {}: {}",
+            path.display(),
+            e
+        )
+    })?;
+
+    Ok((Incoming::new(listener), socket_file))
+}
+
+#[cfg(not(unix))]
+pub fn bind(path: &Path) -> Result<(Incoming, SocketFile), String> {
+    Err(format!(
+        "Unable to bind HTTP API to {}: Unix domain sockets are not supported on this platform",
+        path.display()
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn socket_removed_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bn.sock");
+
+        let (incoming, socket_file) = bind(&path).unwrap();
+        assert!(path.exists());
+
+        // A socket left behind by a previous run is replaced.
+        std::mem::forget(socket_file);
+        drop(incoming);
+        let (_incoming, socket_file) = bind(&path).unwrap();
+        assert!(path.exists());
+
+        drop(socket_file);
+        assert!(!path.exists());
+    }
+}
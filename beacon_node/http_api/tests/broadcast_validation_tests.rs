@@ -1,6 +1,7 @@
 use beacon_chain::{
+    blob_verification::GossipVerifiedBlob,
     test_utils::{AttestationStrategy, BlockStrategy},
-    GossipVerifiedBlock, IntoGossipVerifiedBlockContents,
+    AvailabilityProcessingStatus, GossipVerifiedBlock, IntoGossipVerifiedBlockContents,
 };
 use eth2::types::{
     BroadcastValidation, SignedBeaconBlock, SignedBlindedBeaconBlock, SignedBlockContents,
@@ -11,8 +12,8 @@ use http_api::{publish_blinded_block, publish_block, reconstruct_block, Provenan
 use std::sync::Arc;
 use tree_hash::TreeHash;
 use types::{
-    BlindedBlobSidecar, BlindedPayload, BlobSidecar, FullPayload, Hash256, MainnetEthSpec,
-    SignedSidecarList, Slot,
+    BlindedBlobSidecar, BlindedPayload, BlobSidecar, EthSpec, ForkName, FullPayload, Hash256,
+    MainnetEthSpec, SignedSidecarList, Slot,
 };
 use warp::Rejection;
 use warp_utils::reject::CustomBadRequest;
@@ -1417,3 +1418,131 @@ fn into_blinded_blob_sidecars(
         .collect::<Vec<_>>()
         .into()
 }
+
+/// Build a Deneb chain and produce a block with blobs at the next slot, then process the blobs as
+/// though they had arrived on gossip ahead of the block.
+async fn block_with_blobs_from_gossip() -> (
+    InteractiveTester<E>,
+    SignedBeaconBlock<E>,
+    SignedSidecarList<E, BlobSidecar<E>>,
+) {
+    let validator_count = 64;
+    let num_initial: u64 = 31;
+    let mut spec = ForkName::latest().make_genesis_spec(E::default_spec());
+    spec.terminal_total_difficulty = 1.into();
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+    mock_el.server.all_payloads_valid();
+    mock_el
+        .server
+        .ctx
+        .execution_block_generator
+        .write()
+        .move_to_terminal_block()
+        .unwrap();
+
+    // Create some chain depth.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The mock execution layer includes a random number of blobs in each payload, so import
+    // blocks until one with blobs is produced.
+    let (block, blobs) = loop {
+        harness.advance_slot();
+        let slot = harness.get_current_slot();
+        assert!(
+            slot < num_initial + E::slots_per_epoch(),
+            "no block with blobs"
+        );
+
+        let ((block, blobs), _) = harness.make_block(harness.get_current_state(), slot).await;
+        match blobs {
+            Some(blobs) if !blobs.is_empty() => break (block, blobs),
+            blobs => {
+                harness
+                    .process_block(slot, block.canonical_root(), (block, blobs))
+                    .await
+                    .unwrap();
+            }
+        }
+    };
+
+    for blob in blobs.iter() {
+        let gossip_blob = GossipVerifiedBlob::new(blob.clone(), &harness.chain).unwrap();
+        let status = harness
+            .chain
+            .process_gossip_blob(gossip_blob)
+            .await
+            .unwrap();
+        assert!(matches!(
+            status,
+            AvailabilityProcessingStatus::MissingComponents(..)
+        ));
+    }
+
+    (tester, block, blobs)
+}
+
+/// This test checks that a block can be published without its blobs if they have already been
+/// received on gossip.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn blobs_from_gossip_block_without_blobs() {
+    let (tester, block, blobs) = block_with_blobs_from_gossip().await;
+    let block_root = block.canonical_root();
+    let channel = tokio::sync::mpsc::unbounded_channel();
+
+    let publication_result = publish_block(
+        None,
+        ProvenancedBlock::local(SignedBlockContents::new(block, None)),
+        tester.harness.chain.clone(),
+        &channel.0,
+        tester.harness.logger().clone(),
+        BroadcastValidation::ConsensusAndEquivocation,
+        StatusCode::ACCEPTED,
+    )
+    .await;
+
+    assert!(publication_result.is_ok());
+    assert!(tester
+        .harness
+        .chain
+        .block_is_known_to_fork_choice(&block_root));
+    let stored_blobs = tester.harness.chain.get_blobs(&block_root).unwrap();
+    assert_eq!(stored_blobs.len(), blobs.len());
+}
+
+/// This test checks that resubmitting blobs already received on gossip alongside the block does not
+/// cause them to be rejected as repeats, and that they are imported with the block.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn blobs_from_gossip_block_with_blobs() {
+    let (tester, block, blobs) = block_with_blobs_from_gossip().await;
+    let block_root = block.canonical_root();
+    let channel = tokio::sync::mpsc::unbounded_channel();
+
+    let publication_result = publish_block(
+        None,
+        ProvenancedBlock::local(SignedBlockContents::new(block, Some(blobs.clone()))),
+        tester.harness.chain.clone(),
+        &channel.0,
+        tester.harness.logger().clone(),
+        BroadcastValidation::ConsensusAndEquivocation,
+        StatusCode::ACCEPTED,
+    )
+    .await;
+
+    assert!(publication_result.is_ok());
+    assert!(tester
+        .harness
+        .chain
+        .block_is_known_to_fork_choice(&block_root));
+    let stored_blobs = tester.harness.chain.get_blobs(&block_root).unwrap();
+    assert_eq!(stored_blobs.len(), blobs.len());
+}
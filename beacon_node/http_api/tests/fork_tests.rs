@@ -116,6 +116,76 @@ async fn sync_committee_duties_across_fork() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn sync_committee_duties_cached_per_period() {
+    let validator_count = E::sync_committee_size();
+    let spec = altair_spec(Epoch::new(0));
+    let tester = InteractiveTester::<E>::new(Some(spec.clone()), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+    let cache = &harness.chain.sync_duties_cache;
+
+    let all_validators_u64 = harness
+        .get_all_validators()
+        .iter()
+        .map(|x| *x as u64)
+        .collect::<Vec<_>>();
+
+    // Repeated requests for the same period, and for subsets of its validators, only build the
+    // duties once.
+    let all_duties = client
+        .post_validator_duties_sync(Epoch::new(0), &all_validators_u64)
+        .await
+        .unwrap()
+        .data;
+    for _ in 0..3 {
+        assert_eq!(
+            client
+                .post_validator_duties_sync(Epoch::new(1), &all_validators_u64)
+                .await
+                .unwrap()
+                .data,
+            all_duties
+        );
+    }
+    let subset = client
+        .post_validator_duties_sync(Epoch::new(0), &all_validators_u64[..4])
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(
+        subset,
+        all_duties
+            .iter()
+            .filter(|duty| duty.validator_index < 4)
+            .cloned()
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(cache.builds(), 1);
+
+    // The next period is built separately.
+    client
+        .post_validator_duties_sync(spec.epochs_per_sync_committee_period, &all_validators_u64)
+        .await
+        .unwrap();
+    client
+        .post_validator_duties_sync(spec.epochs_per_sync_committee_period, &all_validators_u64)
+        .await
+        .unwrap();
+    assert_eq!(cache.builds(), 2);
+
+    // Unknown validators are still rejected.
+    assert_eq!(
+        client
+            .post_validator_duties_sync(Epoch::new(0), &[validator_count as u64])
+            .await
+            .unwrap_err()
+            .status()
+            .unwrap(),
+        500
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn attestations_across_fork_with_skip_slots() {
     let validator_count = E::sync_committee_size();
@@ -453,6 +523,27 @@ async fn bls_to_execution_changes_update_all_around_capella_fork() {
         .await
         .unwrap();
 
+    // The pooled changes are listed, and are the same as JSON and SSZ.
+    let pooled_address_changes = client
+        .get_beacon_pool_bls_to_execution_changes()
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(
+        pooled_address_changes
+            .iter()
+            .cloned()
+            .collect::<HashSet<_>>(),
+        HashSet::from_iter(valid_address_changes[..num_pre_capella].iter().cloned())
+    );
+    assert_eq!(
+        client
+            .get_beacon_pool_bls_to_execution_changes_ssz()
+            .await
+            .unwrap(),
+        pooled_address_changes
+    );
+
     // Invalid changes signed with the wrong keys should all be rejected without affecting the seen
     // indices filters (apply ALL of them).
     let error = client
@@ -1,24 +1,32 @@
 //! Generic tests that make use of the (newer) `InteractiveApiTester`
 use beacon_chain::{
     chain_config::{DisallowedReOrgOffsets, ReOrgThreshold},
-    test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
-    ChainConfig,
+    test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, SyncCommitteeStrategy},
+    ChainConfig, StateSkipConfig,
+};
+use eth2::lighthouse::ProposerReOrgDecision;
+use eth2::types::{
+    BlockId as CoreBlockId, DepositContractData, SignedBlockContents, StateId, ValidatorId,
 };
-use eth2::types::{DepositContractData, StateId};
 use execution_layer::{ForkchoiceState, PayloadAttributes};
-use http_api::test_utils::InteractiveTester;
+use http_api::{test_utils::InteractiveTester, BlockId};
 use parking_lot::Mutex;
 use slot_clock::SlotClock;
 use state_processing::{
     per_block_processing::get_expected_withdrawals, state_advance::complete_state_advance,
 };
 use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use tempfile::TempDir;
 use tree_hash::TreeHash;
+use types::consts::altair::{PROPOSER_WEIGHT, WEIGHT_DENOMINATOR};
 use types::{
     Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, FullPayload,
-    MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, Slot,
+    MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, Signature,
+    SignedValidatorRegistrationData, Slot, Unsigned, ValidatorRegistrationData,
 };
 
 type E = MainnetEthSpec;
@@ -173,7 +181,11 @@ pub struct ReOrgTest {
     /// Number of slots between head block and block proposal slot.
     head_distance: u64,
     re_org_threshold: u64,
+    re_org_margin: u64,
+    re_org_min_participation: u64,
     max_epochs_since_finalization: u64,
+    /// Percentage of validators attesting to the blocks prior to the parent block.
+    percent_initial_attesters: usize,
     percent_parent_votes: usize,
     percent_empty_votes: usize,
     percent_head_votes: usize,
@@ -193,7 +205,10 @@ impl Default for ReOrgTest {
             parent_distance: 1,
             head_distance: 1,
             re_org_threshold: 20,
+            re_org_margin: 0,
+            re_org_min_participation: 80,
             max_epochs_since_finalization: 2,
+            percent_initial_attesters: 100,
             percent_parent_votes: 100,
             percent_empty_votes: 100,
             percent_head_votes: 0,
@@ -366,6 +381,62 @@ pub async fn proposer_boost_re_org_weight_misprediction() {
     .await;
 }
 
+/// The head block is late and weak, but not by enough to clear the configured margin.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposer_boost_re_org_head_within_margin() {
+    let decision = proposer_boost_re_org_test(ReOrgTest {
+        percent_empty_votes: 90,
+        percent_head_votes: 10,
+        re_org_margin: 15,
+        should_re_org: false,
+        misprediction: true,
+        ..Default::default()
+    })
+    .await;
+    assert!(decision.head_weight.unwrap() < decision.re_org_weight_threshold.unwrap());
+    assert!(decision.reason.unwrap().contains("margin"));
+}
+
+/// The same weak head is re-orged if the margin is small enough.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposer_boost_re_org_head_outside_margin() {
+    proposer_boost_re_org_test(ReOrgTest {
+        percent_empty_votes: 90,
+        percent_head_votes: 10,
+        re_org_margin: 5,
+        ..Default::default()
+    })
+    .await;
+}
+
+/// Participation in the previous epoch is below the minimum, so the weak head is not re-orged.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposer_boost_re_org_low_participation() {
+    let decision = proposer_boost_re_org_test(ReOrgTest {
+        head_slot: Slot::new(33),
+        percent_initial_attesters: 50,
+        should_re_org: false,
+        ..Default::default()
+    })
+    .await;
+    let participation = decision.participation_percent.unwrap();
+    assert!(participation < 80, "{participation}");
+    assert!(decision.reason.unwrap().contains("participation"));
+}
+
+/// The same chain is re-orged once the participation check is relaxed.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposer_boost_re_org_low_participation_allowed() {
+    let decision = proposer_boost_re_org_test(ReOrgTest {
+        head_slot: Slot::new(33),
+        percent_initial_attesters: 50,
+        re_org_min_participation: 40,
+        ..Default::default()
+    })
+    .await;
+    assert!(decision.participation_percent.unwrap() >= 40);
+}
+
 /// Run a proposer boost re-org test.
 ///
 /// - `head_slot`: the slot of the canonical head to be reorged
@@ -373,13 +444,18 @@ pub async fn proposer_boost_re_org_weight_misprediction() {
 /// - `num_empty_votes`: percentage of comm of attestations for the parent block
 /// - `num_head_votes`: number of attestations for the head block
 /// - `should_re_org`: whether the proposer should build on the parent rather than the head
+///
+/// Returns the re-org decision made while producing the final block.
 pub async fn proposer_boost_re_org_test(
     ReOrgTest {
         head_slot,
         parent_distance,
         head_distance,
         re_org_threshold,
+        re_org_margin,
+        re_org_min_participation,
         max_epochs_since_finalization,
+        percent_initial_attesters,
         percent_parent_votes,
         percent_empty_votes,
         percent_head_votes,
@@ -388,7 +464,7 @@ pub async fn proposer_boost_re_org_test(
         expect_withdrawals_change_on_epoch,
         disallowed_offsets,
     }: ReOrgTest,
-) {
+) -> ProposerReOrgDecision {
     assert!(head_slot > 0);
 
     // Test using the latest fork so that we simulate conditions as similar to mainnet as possible.
@@ -418,6 +494,8 @@ pub async fn proposer_boost_re_org_test(
         Some(Box::new(move |builder| {
             builder
                 .proposer_re_org_threshold(Some(ReOrgThreshold(re_org_threshold)))
+                .proposer_re_org_margin(ReOrgThreshold(re_org_margin))
+                .proposer_re_org_participation_threshold(re_org_min_participation)
                 .proposer_re_org_max_epochs_since_finalization(Epoch::new(
                     max_epochs_since_finalization,
                 ))
@@ -461,12 +539,19 @@ pub async fn proposer_boost_re_org_test(
 
     // Create some chain depth. Sign sync committee signatures so validator balances don't dip
     // below 32 ETH and become ineligible for withdrawals.
+    let initial_attestation_strategy = if percent_initial_attesters == 100 {
+        AttestationStrategy::AllValidators
+    } else {
+        AttestationStrategy::SomeValidators(
+            all_validators[..validator_count * percent_initial_attesters / 100].to_vec(),
+        )
+    };
     harness.advance_slot();
     harness
         .extend_chain_with_sync(
             num_initial as usize,
             BlockStrategy::OnCanonicalHead,
-            AttestationStrategy::AllValidators,
+            initial_attestation_strategy,
             SyncCommitteeStrategy::AllValidators,
         )
         .await;
@@ -634,6 +719,18 @@ pub async fn proposer_boost_re_org_test(
         assert_eq!(block_c.parent_root(), block_b_root);
     }
 
+    // The decision made while producing block C should be available via the API.
+    let decision = tester
+        .client
+        .get_lighthouse_proposer_reorg_last_decision()
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(decision.slot, slot_c);
+    assert_eq!(decision.head_root, block_b_root);
+    assert_eq!(decision.attempted, should_re_org, "{:?}", decision.reason);
+
     // Sign blobs.
     let block_c_signed_blobs =
         block_c_blobs.map(|blobs| harness.sign_blobs(blobs, &state_b, proposer_index));
@@ -732,6 +829,8 @@ pub async fn proposer_boost_re_org_test(
             payload_attribs.prev_randao(),
         );
     }
+
+    decision
 }
 
 // Test that running fork choice before proposing results in selection of the correct head.
@@ -836,3 +935,1096 @@ pub async fn fork_choice_before_proposal() {
     // D's parent is B.
     assert_eq!(block_d.parent_root(), block_root_b.into());
 }
+
+// Test that a manual fork choice recompute via the HTTP API applies attestations that haven't yet
+// been reflected in the head.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn manual_fork_choice_recompute() {
+    let validator_count = 64;
+    let all_validators = (0..validator_count).collect::<Vec<_>>();
+    let num_initial: u64 = 31;
+
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Create two competing blocks B and C, both built upon A.
+    let slot_a = Slot::new(num_initial);
+    let slot_b = slot_a + 1;
+    let slot_c = slot_a + 2;
+
+    let state_a = harness.get_current_state();
+    let (block_b, state_b) = harness.make_block(state_a.clone(), slot_b).await;
+    let block_root_b = harness
+        .process_block(slot_b, block_b.0.canonical_root(), block_b)
+        .await
+        .unwrap();
+    let attestations_b = harness.make_attestations(
+        &all_validators,
+        &state_b,
+        state_b.tree_hash_root(),
+        block_root_b,
+        slot_b,
+    );
+
+    let (block_c, state_c) = harness.make_block(state_a, slot_c).await;
+    let block_root_c = harness
+        .process_block(slot_c, block_c.0.canonical_root(), block_c)
+        .await
+        .unwrap();
+    let attestations_c = harness.make_attestations(
+        &all_validators[..validator_count / 2],
+        &state_c,
+        state_c.tree_hash_root(),
+        block_root_c,
+        slot_c,
+    );
+    harness.process_attestations(attestations_c);
+
+    // Apply the heavier attestations to B without re-running fork choice.
+    harness.process_attestations(attestations_b);
+
+    // Block import recomputed the head automatically, selecting C.
+    let last_recompute = client
+        .get_lighthouse_fork_choice_last_recompute()
+        .await
+        .unwrap()
+        .data
+        .unwrap();
+    assert!(!last_recompute.manual);
+    assert_eq!(last_recompute.new_head_block_root, block_root_c.into());
+    assert_eq!(
+        harness.chain.canonical_head.cached_head().head_block_root(),
+        block_root_c.into()
+    );
+
+    // Move past slot C so that C loses its proposer boost, but don't run the per-slot task.
+    harness.advance_slot();
+
+    let recompute = client
+        .post_lighthouse_fork_choice_recompute()
+        .await
+        .unwrap()
+        .data;
+    assert!(recompute.manual);
+    assert!(recompute.head_changed);
+    assert_eq!(recompute.slot, slot_c + 1);
+    assert_eq!(recompute.old_head_block_root, block_root_c.into());
+    assert_eq!(recompute.new_head_block_root, block_root_b.into());
+    assert_eq!(
+        harness.chain.canonical_head.cached_head().head_block_root(),
+        block_root_b.into()
+    );
+
+    let last_recompute = client
+        .get_lighthouse_fork_choice_last_recompute()
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(last_recompute, Some(recompute));
+}
+
+// Test that states at skipped slots after the head are advanced from the head state, and that
+// repeated requests for the same slot are served from the cache without further slot processing.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn skip_slot_state_cache() {
+    let validator_count = 24;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Skip a few slots without producing blocks.
+    let num_skipped_slots = 3;
+    for _ in 0..num_skipped_slots {
+        harness.advance_slot();
+    }
+    let slot = harness.chain.slot().unwrap();
+    assert_eq!(
+        harness.chain.head_snapshot().beacon_block.slot() + num_skipped_slots,
+        slot
+    );
+
+    // Every lookup of a skip-slot state that misses the store's state cache is followed by per-slot
+    // processing, so the cache's own counters show whether a request repeated that processing.
+    let cache_lookups = || {
+        let state_cache = harness.chain.store.state_cache();
+        (state_cache.hits(), state_cache.misses())
+    };
+
+    let (hits_before, misses_before) = cache_lookups();
+    let validators = client
+        .get_beacon_states_validators(StateId::Slot(slot), None, None)
+        .await
+        .unwrap()
+        .unwrap()
+        .data;
+    assert_eq!(validators.len(), validator_count);
+    let (hits, misses) = cache_lookups();
+    assert_eq!(hits, hits_before);
+    assert_eq!(misses, misses_before + 1);
+    assert_eq!(harness.chain.store.state_cache().len(), 1);
+
+    // The second request for the same slot is served from the cache.
+    let (hits_before, misses_before) = cache_lookups();
+    let state_root = client
+        .get_beacon_states_root(StateId::Slot(slot))
+        .await
+        .unwrap()
+        .unwrap()
+        .data
+        .root;
+    client
+        .get_beacon_states_validators(StateId::Slot(slot), None, None)
+        .await
+        .unwrap()
+        .unwrap();
+    let (hits, misses) = cache_lookups();
+    assert!(hits > hits_before);
+    assert_eq!(misses, misses_before);
+
+    // The advanced state matches the one computed by the beacon chain.
+    let expected_state = harness
+        .chain
+        .state_at_slot(slot, StateSkipConfig::WithStateRoots)
+        .unwrap();
+    assert_eq!(state_root, expected_state.canonical_root());
+}
+
+// Test that requests for expensive states are rejected with a 429 when the concurrency limit is
+// saturated, and that other endpoints are unaffected.
+//
+// Releasing the limit once requests complete is covered by the unit tests of `RequestLimiter`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn state_requests_concurrency_limit() {
+    let validator_count = 24;
+    // A limit of zero is saturated without any requests in flight.
+    let config = http_api::Config {
+        max_concurrent_state_requests: 0,
+        ..http_api::test_utils::test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, validator_count, config).await;
+    let client = &tester.client;
+
+    let server = client.as_ref().trim_end_matches('/').to_string();
+    let validators_url = format!("{}/eth/v1/beacon/states/head/validators", server);
+    let http_client = eth2::reqwest::Client::new();
+
+    // State requests are rejected rather than queued.
+    let response = http_client.get(&validators_url).send().await.unwrap();
+    assert_eq!(response.status(), 429);
+    assert_eq!(response.headers().get("Retry-After").unwrap(), "1");
+
+    // Light endpoints are unaffected.
+    client.get_beacon_states_fork(StateId::Head).await.unwrap();
+    client.get_beacon_headers(None, None).await.unwrap();
+    client.get_node_version().await.unwrap();
+}
+
+// Test that the standard block rewards of the proposer sum to the proposer's balance increase from
+// the block, for each kind of block ID.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_beacon_rewards_blocks() {
+    let validator_count = 32;
+    let mut spec = MinimalEthSpec::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+
+    let tester = InteractiveTester::<MinimalEthSpec>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+    let chain = &harness.chain;
+
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            5 * MinimalEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+        )
+        .await;
+
+    let head_slot = chain.head_snapshot().beacon_block.slot();
+    assert!(
+        chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            > 0
+    );
+
+    for block_id in [
+        CoreBlockId::Head,
+        CoreBlockId::Finalized,
+        CoreBlockId::Slot(head_slot - 1),
+        CoreBlockId::Root(chain.head_snapshot().beacon_block.parent_root()),
+    ] {
+        let (block_root, execution_optimistic, finalized) = BlockId(block_id).root(chain).unwrap();
+
+        let response = client.get_beacon_rewards_blocks(block_id).await.unwrap();
+        assert_eq!(response.execution_optimistic, Some(execution_optimistic));
+        assert_eq!(response.finalized, Some(finalized));
+
+        let rewards = response.data;
+        assert_eq!(
+            rewards.total,
+            rewards.attestations
+                + rewards.sync_aggregate
+                + rewards.proposer_slashings
+                + rewards.attester_slashings
+        );
+        assert!(rewards.attestations > 0);
+        assert!(rewards.sync_aggregate > 0);
+
+        let block = chain.get_blinded_block(&block_root).unwrap().unwrap();
+        let proposer_index = block.message().proposer_index();
+        assert_eq!(rewards.proposer_index, proposer_index);
+
+        let parent_block = chain
+            .get_blinded_block(&block.parent_root())
+            .unwrap()
+            .unwrap();
+        let mut pre_state = chain
+            .get_state(&parent_block.state_root(), Some(parent_block.slot()))
+            .unwrap()
+            .unwrap();
+        complete_state_advance(&mut pre_state, None, block.slot(), &chain.spec).unwrap();
+        let post_state = chain
+            .get_state(&block.state_root(), Some(block.slot()))
+            .unwrap()
+            .unwrap();
+
+        // The proposer's balance also changes if it is a member of the sync committee.
+        let sync_committee_reward = chain
+            .compute_sync_committee_rewards(block.message(), &mut pre_state.clone())
+            .unwrap()
+            .into_iter()
+            .find(|reward| reward.validator_index == proposer_index)
+            .map_or(0, |reward| reward.reward);
+
+        let balance_delta = post_state.balances()[proposer_index as usize] as i64
+            - pre_state.balances()[proposer_index as usize] as i64;
+        assert_eq!(
+            balance_delta,
+            rewards.total as i64 + sync_committee_reward,
+            "block_id: {}",
+            block_id
+        );
+    }
+}
+
+// Test that the sync committee rewards of a block are consistent with the proposer's reward for
+// including the sync aggregate, according to the reward weights of the spec.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_rewards_sync_committee() {
+    let validator_count = 32;
+    let committee_size = <MinimalEthSpec as EthSpec>::SyncCommitteeSize::to_u64();
+    let mut spec = MinimalEthSpec::default_spec();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+
+    let tester = InteractiveTester::<MinimalEthSpec>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            MinimalEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+        )
+        .await;
+
+    // Every member of the committee participated, so each position earned the same reward.
+    let response = client
+        .post_beacon_rewards_sync_committee(CoreBlockId::Head, &[])
+        .await
+        .unwrap();
+    assert_eq!(response.execution_optimistic, Some(false));
+    assert_eq!(response.finalized, Some(false));
+    let rewards = response.data;
+    assert!(rewards.iter().all(|reward| reward.reward > 0));
+
+    let total_participant_rewards = rewards.iter().map(|reward| reward.reward).sum::<i64>() as u64;
+    assert_eq!(total_participant_rewards % committee_size, 0);
+    let participant_reward = total_participant_rewards / committee_size;
+    let proposer_reward =
+        participant_reward * PROPOSER_WEIGHT / (WEIGHT_DENOMINATOR - PROPOSER_WEIGHT);
+
+    let block_rewards = client
+        .get_beacon_rewards_blocks(CoreBlockId::Head)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(
+        block_rewards.sync_aggregate,
+        committee_size * proposer_reward
+    );
+
+    // Only the requested validators are returned, whether identified by index or public key.
+    let state = harness.get_current_state();
+    let requested = [&rewards[0], &rewards[rewards.len() - 1]];
+    let validator_ids = [
+        ValidatorId::Index(requested[0].validator_index),
+        ValidatorId::PublicKey(state.validators()[requested[1].validator_index as usize].pubkey),
+    ];
+    let filtered_rewards = client
+        .post_beacon_rewards_sync_committee(CoreBlockId::Head, &validator_ids)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(
+        filtered_rewards.iter().collect::<Vec<_>>(),
+        requested.to_vec()
+    );
+
+    // Without any participants, every member is penalized by the participant reward and the
+    // proposer earns nothing.
+    harness
+        .extend_chain_with_sync(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::NoValidators,
+        )
+        .await;
+
+    let rewards = client
+        .post_beacon_rewards_sync_committee(CoreBlockId::Head, &[])
+        .await
+        .unwrap()
+        .data;
+    assert!(rewards.iter().all(|reward| reward.reward < 0));
+    assert_eq!(
+        rewards.iter().map(|reward| reward.reward).sum::<i64>(),
+        -((committee_size * participant_reward) as i64)
+    );
+
+    let block_rewards = client
+        .get_beacon_rewards_blocks(CoreBlockId::Head)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(block_rewards.sync_aggregate, 0);
+}
+
+// Test that configs written before `allow_origin` was renamed to `allow_origins` still load.
+#[test]
+fn config_allow_origin_alias() {
+    let with_allow_origin = |allow_origin: serde_json::Value| {
+        let mut config = serde_json::to_value(http_api::test_utils::test_config()).unwrap();
+        let fields = config.as_object_mut().unwrap();
+        fields.remove("allow_origins").unwrap();
+        fields.insert("allow_origin".to_string(), allow_origin);
+        serde_json::from_value::<http_api::Config>(config)
+            .unwrap()
+            .allow_origins
+    };
+
+    assert_eq!(
+        with_allow_origin("https://a.example.com,https://b.example.com".into()),
+        vec!["https://a.example.com", "https://b.example.com"]
+    );
+    assert_eq!(
+        with_allow_origin(serde_json::Value::Null),
+        Vec::<String>::new()
+    );
+    assert_eq!(with_allow_origin(serde_json::json!(["*"])), vec!["*"]);
+}
+
+// Test that CORS preflight requests are answered for each of the configured origins, including
+// wildcard subdomains, and rejected for other origins.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn cors_preflight_multiple_origins() {
+    let config = http_api::Config {
+        allow_origins: vec![
+            "https://dashboard.example.com".to_string(),
+            "https://*.internal.example.com".to_string(),
+        ],
+        ..http_api::test_utils::test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 24, config).await;
+    let server = tester.client.as_ref().trim_end_matches('/').to_string();
+    let url = format!("{}/eth/v1/beacon/blocks", server);
+    let http_client = eth2::reqwest::Client::new();
+
+    let preflight = |origin: &'static str| {
+        http_client
+            .request(eth2::reqwest::Method::OPTIONS, &url)
+            .header("Origin", origin)
+            .header("Access-Control-Request-Method", "POST")
+            .header(
+                "Access-Control-Request-Headers",
+                "content-type, eth-consensus-version",
+            )
+            .send()
+    };
+
+    for origin in [
+        "https://dashboard.example.com",
+        "https://grafana.internal.example.com",
+    ] {
+        let response = preflight(origin).await.unwrap();
+        assert_eq!(response.status(), 200, "origin: {}", origin);
+        let headers = response.headers();
+        assert_eq!(headers["access-control-allow-origin"], origin);
+        assert_eq!(headers["access-control-allow-methods"], "GET, POST");
+        let allowed_headers = headers["access-control-allow-headers"]
+            .to_str()
+            .unwrap()
+            .to_lowercase();
+        assert!(allowed_headers.contains("content-type"));
+        assert!(allowed_headers.contains("eth-consensus-version"));
+    }
+
+    for origin in [
+        "https://example.com",
+        "https://internal.example.com",
+        "http://dashboard.example.com",
+        "https://dashboard.example.org",
+    ] {
+        let response = preflight(origin).await.unwrap();
+        assert_eq!(response.status(), 403, "origin: {}", origin);
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+    }
+
+    // Requests for headers which are not allowed are rejected.
+    let response = http_client
+        .request(eth2::reqwest::Method::OPTIONS, &url)
+        .header("Origin", "https://dashboard.example.com")
+        .header("Access-Control-Request-Method", "POST")
+        .header("Access-Control-Request-Headers", "x-unknown-header")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    // Actual requests from allowed origins have the origin echoed, others do not.
+    let version_url = format!("{}/eth/v1/node/version", server);
+    let response = http_client
+        .get(&version_url)
+        .header("Origin", "https://grafana.internal.example.com")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers()["access-control-allow-origin"],
+        "https://grafana.internal.example.com"
+    );
+    let response = http_client
+        .get(&version_url)
+        .header("Origin", "https://example.com")
+        .send()
+        .await
+        .unwrap();
+    assert!(response
+        .headers()
+        .get("access-control-allow-origin")
+        .is_none());
+}
+
+// Test that when a client CA is configured, only clients presenting a certificate signed by that
+// CA can complete the TLS handshake.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tls_client_authentication() {
+    let tls_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/tls");
+    let config = http_api::Config {
+        tls_config: Some(http_api::TlsConfig {
+            cert: tls_dir.join("server.pem"),
+            key: tls_dir.join("server.key"),
+            client_ca: Some(tls_dir.join("ca.pem")),
+        }),
+        ..http_api::test_utils::test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 24, config).await;
+    let url = format!(
+        "{}/eth/v1/node/version",
+        tester
+            .client
+            .as_ref()
+            .trim_end_matches('/')
+            .replacen("http://", "https://", 1)
+    );
+
+    let ca =
+        eth2::reqwest::Certificate::from_pem(&fs::read(tls_dir.join("ca.pem")).unwrap()).unwrap();
+    let identity = |name: &str| {
+        let mut pem = fs::read(tls_dir.join(format!("{}.pem", name))).unwrap();
+        pem.extend(fs::read(tls_dir.join(format!("{}.key", name))).unwrap());
+        eth2::reqwest::Identity::from_pem(&pem).unwrap()
+    };
+    let http_client = |identity: Option<eth2::reqwest::Identity>| {
+        let builder = eth2::reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(ca.clone());
+        match identity {
+            Some(identity) => builder.identity(identity),
+            None => builder,
+        }
+        .build()
+        .unwrap()
+    };
+
+    // A client with a certificate signed by the CA is served.
+    let response = http_client(Some(identity("client")))
+        .get(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Clients without a certificate, or with one not signed by the CA, fail the handshake.
+    for client in [
+        http_client(None),
+        http_client(Some(identity("untrusted_client"))),
+    ] {
+        let result = async { client.get(&url).send().await?.error_for_status() }.await;
+        assert!(result.is_err(), "{:?}", result);
+    }
+}
+
+// Test that the admin endpoints require the admin API token, including at their deprecated
+// aliases.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_endpoints_require_token() {
+    let tester = InteractiveTester::<E>::new(None, 24).await;
+    let server = eth2::SensitiveUrl::parse(tester.client.as_ref()).unwrap();
+    let timeouts = eth2::Timeouts::set_all(Duration::from_secs(10));
+
+    // A client without the token is rejected.
+    let unauthenticated = eth2::BeaconNodeHttpClient::new(server.clone(), timeouts.clone());
+    let error = unauthenticated
+        .post_lighthouse_fork_choice_recompute()
+        .await
+        .unwrap_err();
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(403));
+
+    // A client with the wrong token is rejected.
+    let mut wrong_token = eth2::BeaconNodeHttpClient::new(server, timeouts);
+    wrong_token.set_admin_token("api-token-wrong".to_string().into());
+    let error = wrong_token
+        .post_lighthouse_fork_choice_recompute()
+        .await
+        .unwrap_err();
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(403));
+
+    // The tester's client has the token.
+    tester
+        .client
+        .post_lighthouse_fork_choice_recompute()
+        .await
+        .unwrap();
+
+    // The deprecated alias is only served with the token.
+    let url = format!(
+        "{}/lighthouse/fork_choice/recompute",
+        tester.client.as_ref().trim_end_matches('/')
+    );
+    let response = eth2::reqwest::Client::new()
+        .post(&url)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 403);
+
+    let response = eth2::reqwest::Client::new()
+        .post(&url)
+        .bearer_auth(tester.admin_token.as_ref().unwrap())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+// Test that disabling the admin endpoints rejects every request to them without creating a token
+// file.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_endpoints_disabled() {
+    let dir = TempDir::new().unwrap();
+    let admin_token_path = dir.path().join("api-token.txt");
+    let config = http_api::Config {
+        admin_token_path: Some(admin_token_path.clone()),
+        disable_admin_endpoints: true,
+        ..http_api::test_utils::test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, 24, config).await;
+
+    assert_eq!(tester.admin_token, None);
+    assert!(!admin_token_path.exists());
+
+    let error = tester
+        .client
+        .post_lighthouse_fork_choice_recompute()
+        .await
+        .unwrap_err();
+    assert_eq!(error.status().map(|status| status.as_u16()), Some(403));
+}
+
+// Test that a block publication which is in-flight when a graceful shutdown begins is imported
+// before the shutdown completes.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn graceful_shutdown_drains_block_publication() {
+    let validator_count = 24;
+    let config = http_api::Config {
+        publish_block_delay: Some(Duration::from_millis(500)),
+        ..http_api::test_utils::test_config()
+    };
+    let tester = InteractiveTester::<E>::new_with_config(None, validator_count, config).await;
+    let harness = &tester.harness;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let slot = harness.get_current_slot();
+    let (block_contents_tuple, _) = harness.make_block(harness.get_current_state(), slot).await;
+    let block_root = block_contents_tuple.0.canonical_root();
+    let block_contents: SignedBlockContents<E> = block_contents_tuple.into();
+
+    // Start a slow publication, and wait for it to be tracked as in-flight.
+    let client = tester.client.clone();
+    let publication = tokio::spawn(async move { client.post_beacon_blocks(&block_contents).await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let graceful_shutdown = harness.chain.task_executor.graceful_shutdown().clone();
+    assert_eq!(graceful_shutdown.num_in_flight(), 1);
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+
+    // The drain waits for the publication, so the block is in fork choice before the exit signal
+    // would be fired.
+    assert!(graceful_shutdown.drain(harness.logger()).await);
+    assert!(harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&block_root));
+    assert_eq!(graceful_shutdown.num_in_flight(), 0);
+
+    publication.await.unwrap().unwrap();
+}
+
+// Test that proposer preparations and builder registrations sent via the HTTP API are restored
+// when the beacon chain is rebuilt from the same database.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn proposer_preparation_persists_across_restart() {
+    let validator_count = 24;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+
+    let preparation_data = (0..validator_count as u64)
+        .map(|validator_index| ProposerPreparationData {
+            validator_index,
+            fee_recipient: Address::from_low_u64_be(validator_index + 1),
+        })
+        .collect::<Vec<_>>();
+    tester
+        .client
+        .post_validator_prepare_beacon_proposer(&preparation_data)
+        .await
+        .unwrap();
+
+    // Register half of the validators with the same fee recipients.
+    let registrations = preparation_data
+        .iter()
+        .take(validator_count / 2)
+        .map(|data| SignedValidatorRegistrationData {
+            message: ValidatorRegistrationData {
+                fee_recipient: data.fee_recipient,
+                gas_limit: 30_000_000 + data.validator_index,
+                timestamp: 0,
+                pubkey: harness.validator_keypairs[data.validator_index as usize]
+                    .pk
+                    .compress(),
+            },
+            signature: Signature::empty(),
+        })
+        .collect::<Vec<_>>();
+    // No builder is configured, so forwarding the registrations fails after they are stored.
+    let error = tester
+        .client
+        .post_validator_register_validator(&registrations)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status().map(u16::from), Some(500));
+
+    harness.chain.persist_head_and_fork_choice().unwrap();
+
+    let resumed = BeaconChainHarness::builder(E::default())
+        .default_spec()
+        .deterministic_keypairs(validator_count)
+        .resumed_ephemeral_store(harness.chain.store.clone())
+        .mock_execution_layer()
+        .testing_slot_clock(harness.chain.slot_clock.clone())
+        .build();
+    let execution_layer = resumed.chain.execution_layer.as_ref().unwrap();
+
+    for data in &preparation_data {
+        assert!(
+            execution_layer
+                .has_proposer_preparation_data(data.validator_index)
+                .await
+        );
+        assert_eq!(
+            execution_layer
+                .get_suggested_fee_recipient(data.validator_index)
+                .await,
+            data.fee_recipient
+        );
+        assert_eq!(
+            execution_layer.builder_registration(data.validator_index),
+            registrations.get(data.validator_index as usize).cloned()
+        );
+    }
+}
+
+/// Assert the `finalized` metadata of each block endpoint for `block_id`.
+async fn assert_block_finalized(
+    client: &eth2::BeaconNodeHttpClient,
+    block_id: CoreBlockId,
+    expected: bool,
+) {
+    type E = MinimalEthSpec;
+
+    let finalized = [
+        (
+            "blocks",
+            client
+                .get_beacon_blocks::<E>(block_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "blinded_blocks",
+            client
+                .get_beacon_blinded_blocks::<E>(block_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "blocks_root",
+            client
+                .get_beacon_blocks_root(block_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "blocks_attestations",
+            client
+                .get_beacon_blocks_attestations::<E>(block_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "headers",
+            client
+                .get_beacon_headers_block_id(block_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+    ];
+    for (endpoint, finalized) in finalized {
+        assert_eq!(
+            finalized,
+            Some(expected),
+            "endpoint: {}, block_id: {}",
+            endpoint,
+            block_id
+        );
+    }
+}
+
+/// Assert the `finalized` metadata of each state endpoint for `state_id`.
+async fn assert_state_finalized(
+    client: &eth2::BeaconNodeHttpClient,
+    state_id: StateId,
+    expected: bool,
+) {
+    let finalized = [
+        (
+            "states_root",
+            client
+                .get_beacon_states_root(state_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "states_fork",
+            client
+                .get_beacon_states_fork(state_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "finality_checkpoints",
+            client
+                .get_beacon_states_finality_checkpoints(state_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+        (
+            "debug_states",
+            client
+                .get_debug_beacon_states::<MinimalEthSpec>(state_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .finalized,
+        ),
+    ];
+    for (endpoint, finalized) in finalized {
+        assert_eq!(
+            finalized,
+            Some(expected),
+            "endpoint: {}, state_id: {}",
+            endpoint,
+            state_id
+        );
+    }
+}
+
+// Test that the `finalized` metadata of block and state endpoints is correct for the head, a
+// finalized slot and a fork block which has been pruned from fork choice.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn finalized_metadata() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 32;
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        Some(E::default_spec()),
+        validator_count,
+        Some(Box::new(move |builder| {
+            builder
+                .deterministic_keypairs(validator_count)
+                .fresh_ephemeral_store()
+                .chain_config(ChainConfig {
+                    // Keep the fork block in the database after finalization.
+                    epochs_per_migration: 1024,
+                    ..ChainConfig::default()
+                })
+        })),
+        None,
+    )
+    .await;
+    let client = &tester.client;
+    let harness = &tester.harness;
+
+    // At genesis the head is the finalized block.
+    assert_block_finalized(client, CoreBlockId::Head, true).await;
+    assert_state_finalized(client, StateId::Head, true).await;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Build a block on the head which the canonical chain will not include.
+    let fork_parent_slot = harness.chain.head_snapshot().beacon_block.slot();
+    let (fork_block_hash, (fork_block, _), _) = harness
+        .add_block_at_slot(fork_parent_slot + 1, harness.get_current_state())
+        .await
+        .unwrap();
+    let fork_block_root = fork_block_hash.into();
+
+    // Prune fork choice on every finalization so that the fork block is removed from it.
+    harness
+        .chain
+        .canonical_head
+        .fork_choice_write_lock()
+        .proto_array_mut()
+        .set_prune_threshold(0);
+
+    harness
+        .extend_chain(
+            5 * E::slots_per_epoch() as usize,
+            BlockStrategy::ForkCanonicalChainAt {
+                previous_slot: fork_parent_slot,
+                first_slot: fork_parent_slot + 2,
+            },
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let cached_head = harness.chain.canonical_head.cached_head();
+    let finalized_checkpoint = cached_head.finalized_checkpoint();
+    assert!(finalized_checkpoint.epoch > 1);
+    assert!(!harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .contains_block(&fork_block_root));
+    let finalized_slot = finalized_checkpoint.epoch.start_slot(E::slots_per_epoch());
+    let justified_is_finalized =
+        cached_head.justified_checkpoint().epoch <= finalized_checkpoint.epoch;
+
+    for (block_id, expected) in [
+        (CoreBlockId::Head, false),
+        (CoreBlockId::Genesis, true),
+        (CoreBlockId::Finalized, true),
+        (CoreBlockId::Justified, justified_is_finalized),
+        (CoreBlockId::Slot(finalized_slot), true),
+        (CoreBlockId::Slot(cached_head.head_slot()), false),
+        (CoreBlockId::Root(finalized_checkpoint.root), true),
+        (CoreBlockId::Root(fork_block_root), false),
+    ] {
+        assert_block_finalized(client, block_id, expected).await;
+    }
+
+    for (state_id, expected) in [
+        (StateId::Head, false),
+        (StateId::Genesis, true),
+        (StateId::Finalized, true),
+        (StateId::Justified, justified_is_finalized),
+        (StateId::Slot(finalized_slot), true),
+        (StateId::Slot(cached_head.head_slot()), false),
+        (StateId::Root(fork_block.state_root()), false),
+    ] {
+        assert_state_finalized(client, state_id, expected).await;
+    }
+
+    // The list of headers defaults to the head.
+    let headers = client
+        .get_beacon_headers(None, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(headers.finalized, Some(false));
+}
+
+// Test that a block with blobs published as a snappy-compressed SSZ body is imported in the same way
+// as an uncompressed body.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_v2_ssz_snappy() {
+    let validator_count = 24;
+    let spec = ForkName::Deneb.make_genesis_spec(E::default_spec());
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+
+    let mut compressing_client = tester.client.clone();
+    compressing_client.set_compress_ssz_blocks(true);
+    assert_eq!(
+        compressing_client.get_accepted_encodings().await.unwrap(),
+        vec![eth2::SNAPPY_ENCODING.to_string()]
+    );
+    assert!(compressing_client.server_accepts_snappy().await);
+
+    // The number of blobs in each payload is random, so build blocks until one has some.
+    harness.advance_slot();
+    let block_contents_tuple = loop {
+        let slot = harness.get_current_slot();
+        let (block_contents_tuple, _) = harness.make_block(harness.get_current_state(), slot).await;
+        if block_contents_tuple
+            .1
+            .as_ref()
+            .map_or(false, |blobs| !blobs.is_empty())
+        {
+            break block_contents_tuple;
+        }
+        harness
+            .process_block_result(block_contents_tuple)
+            .await
+            .unwrap();
+        harness.advance_slot();
+    };
+    let block_root = block_contents_tuple.0.canonical_root();
+    let num_blobs = block_contents_tuple.1.as_ref().unwrap().len();
+    let block_contents: SignedBlockContents<E> = block_contents_tuple.into();
+
+    compressing_client
+        .post_beacon_blocks_v2_ssz(&block_contents, None)
+        .await
+        .unwrap();
+    assert!(harness.chain.block_is_known_to_fork_choice(&block_root));
+    assert_eq!(
+        harness.chain.get_blobs(&block_root).unwrap().len(),
+        num_blobs
+    );
+
+    // Re-publishing the block is handled identically whether or not it is compressed.
+    let compressed_error = compressing_client
+        .post_beacon_blocks_v2_ssz(&block_contents, None)
+        .await
+        .unwrap_err();
+    let uncompressed_error = tester
+        .client
+        .post_beacon_blocks_v2_ssz(&block_contents, None)
+        .await
+        .unwrap_err();
+    assert_eq!(compressed_error.status(), uncompressed_error.status());
+
+    // Bodies which are not valid snappy are rejected.
+    let response = eth2::reqwest::Client::new()
+        .post(tester.client.post_beacon_blocks_v2_path(None).unwrap())
+        .header("Content-Type", "application/octet-stream")
+        .header("Content-Encoding", "snappy")
+        .header(eth2::CONSENSUS_VERSION_HEADER, "deneb")
+        .body(vec![1, 2, 3])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+
+    // Unknown encodings are rejected with a 415.
+    let response = eth2::reqwest::Client::new()
+        .post(tester.client.post_beacon_blocks_v2_path(None).unwrap())
+        .header("Content-Type", "application/octet-stream")
+        .header("Content-Encoding", "zstd")
+        .header(eth2::CONSENSUS_VERSION_HEADER, "deneb")
+        .body(block_contents.as_ssz_bytes())
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 415);
+}
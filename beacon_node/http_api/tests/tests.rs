@@ -1,27 +1,39 @@
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
+    shuffling_cache,
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
+use beacon_processor::work_reprocessing_queue::{
+    ReprocessQueueMessage, ADDITIONAL_QUEUED_BLOCK_DELAY, DEFAULT_MAXIMUM_QUEUED_ATTESTATIONS,
+};
+use beacon_processor::{BeaconProcessorSend, BlockingOrAsync, Work, WorkEvent, API_REQUEST_P1};
 use environment::null_logger;
 use eth2::{
+    lighthouse::{
+        AttestationInclusion, AttesterCommittee, BeaconNodeGraffiti, CacheInfo, CacheName,
+        CachesPatchRequest, EarlyBlockSnapshot, EnrUpdateRequest, GossipReplayRequest,
+        GossipReplayResponse, GraffitiPostRequest, ReprocessQueueSnapshot,
+    },
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{BlockId as CoreBlockId, ForkChoiceNode, StateId as CoreStateId, *},
     BeaconNodeHttpClient, Error, StatusCode, Timeouts,
 };
 use execution_layer::test_utils::{
-    MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_BUILDER_THRESHOLD_WEI,
-    DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
+    MockBuilder, Operation, RevealOperation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI,
+    DEFAULT_BUILDER_THRESHOLD_WEI, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
 };
+use execution_layer::FailedCondition;
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
 use http_api::{
-    test_utils::{create_api_server, ApiServer},
-    BlockId, StateId,
+    test_utils::{create_api_server, create_api_server_with_config, test_config, ApiServer},
+    BlockId, StateId, MAX_INCLUDE_COMMITTEE_VALIDATORS,
 };
-use lighthouse_network::{Enr, EnrExt, PeerId};
-use network::NetworkReceivers;
+use lighthouse_network::types::{GossipEncoding, GossipTopic};
+use lighthouse_network::{Client, Enr, EnrExt, MessageId, PeerId, PubsubMessage};
+use network::{NetworkBeaconProcessor, NetworkReceivers};
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
@@ -29,13 +41,18 @@ use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::per_slot_processing;
 use state_processing::state_advance::partial_state_advance;
 use std::convert::TryInto;
+use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 use tokio::time::Duration;
 use tree_hash::TreeHash;
 use types::application_domain::ApplicationDomain;
+use types::beacon_state::CommitteeCache;
+use types::graffiti::GraffitiString;
 use types::{
-    AggregateSignature, BitList, Domain, EthSpec, ExecutionBlockHash, Hash256, Keypair,
-    MainnetEthSpec, RelativeEpoch, SelectionProof, SignedRoot, Slot,
+    light_client_update, AggregateSignature, BeaconState, BitList, Domain, EthSpec,
+    ExecutionBlockHash, Hash256, Keypair, MainnetEthSpec, RelativeEpoch, SelectionProof,
+    SignedRoot, Slot, SubnetId, HISTORICAL_SUMMARIES_FIELD_INDEX,
 };
 
 type E = MainnetEthSpec;
@@ -72,6 +89,8 @@ struct ApiTester {
     network_rx: NetworkReceivers<E>,
     local_enr: Enr,
     external_peer_id: PeerId,
+    beacon_processor_send: BeaconProcessorSend<E>,
+    work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
     mock_builder: Option<Arc<MockBuilder<E>>>,
 }
 
@@ -79,6 +98,11 @@ struct ApiTesterConfig {
     spec: ChainSpec,
     retain_historic_states: bool,
     builder_threshold: Option<u128>,
+    builder_extra_data_prefix: Option<Vec<u8>>,
+    strict_fee_recipient: bool,
+    /// Serve the API on a Unix domain socket, and send the client's requests over it.
+    unix_socket: bool,
+    chain_config: ChainConfig,
 }
 
 impl Default for ApiTesterConfig {
@@ -89,6 +113,10 @@ impl Default for ApiTesterConfig {
             spec,
             retain_historic_states: false,
             builder_threshold: None,
+            builder_extra_data_prefix: None,
+            strict_fee_recipient: false,
+            unix_socket: false,
+            chain_config: ChainConfig::default(),
         }
     }
 }
@@ -98,6 +126,11 @@ impl ApiTesterConfig {
         self.retain_historic_states = true;
         self
     }
+
+    fn unix_socket(mut self) -> Self {
+        self.unix_socket = true;
+        self
+    }
 }
 
 impl ApiTester {
@@ -125,12 +158,16 @@ impl ApiTester {
             .spec(spec.clone())
             .chain_config(ChainConfig {
                 reconstruct_historic_states: config.retain_historic_states,
-                ..ChainConfig::default()
+                ..config.chain_config
             })
             .logger(logging::test_logger())
             .deterministic_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
-            .mock_execution_layer_with_config(config.builder_threshold)
+            .mock_execution_layer_with_config(
+                config.builder_threshold,
+                config.builder_extra_data_prefix,
+                config.strict_fee_recipient,
+            )
             .build();
 
         harness
@@ -248,13 +285,24 @@ impl ApiTester {
 
         let log = null_logger().unwrap();
 
+        let unix_socket_path = config.unix_socket.then(|| {
+            std::env::temp_dir().join(format!("lighthouse-bn-{}.sock", uuid::Uuid::new_v4()))
+        });
+        let http_config = http_api::Config {
+            unix_socket: unix_socket_path.clone(),
+            ..test_config()
+        };
+
         let ApiServer {
             server,
             listening_socket,
             network_rx,
             local_enr,
             external_peer_id,
-        } = create_api_server(chain.clone(), &harness.runtime, log).await;
+            beacon_processor_send,
+            work_reprocessing_tx,
+            admin_token,
+        } = create_api_server_with_config(chain.clone(), &harness.runtime, log, http_config).await;
 
         harness.runtime.task_executor.spawn(server, "api_server");
 
@@ -273,10 +321,15 @@ impl ApiTester {
 
         let mock_builder = harness.mock_builder.clone();
 
-        let client = BeaconNodeHttpClient::new(
-            beacon_url,
+        let client_url = match &unix_socket_path {
+            Some(path) => SensitiveUrl::parse(&format!("unix://{}", path.display())).unwrap(),
+            None => beacon_url,
+        };
+        let mut client = BeaconNodeHttpClient::new(
+            client_url,
             Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
         );
+        client.set_admin_token(admin_token.unwrap().into());
 
         Self {
             harness: Arc::new(harness),
@@ -292,6 +345,8 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            beacon_processor_send,
+            work_reprocessing_tx,
             mock_builder,
         }
     }
@@ -347,11 +402,14 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            beacon_processor_send,
+            work_reprocessing_tx,
+            admin_token,
         } = create_api_server(chain.clone(), &harness.runtime, log).await;
 
         harness.runtime.task_executor.spawn(server, "api_server");
 
-        let client = BeaconNodeHttpClient::new(
+        let mut client = BeaconNodeHttpClient::new(
             SensitiveUrl::parse(&format!(
                 "http://{}:{}",
                 listening_socket.ip(),
@@ -360,6 +418,7 @@ impl ApiTester {
             .unwrap(),
             Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
         );
+        client.set_admin_token(admin_token.unwrap().into());
 
         Self {
             harness,
@@ -375,6 +434,8 @@ impl ApiTester {
             network_rx,
             local_enr,
             external_peer_id,
+            beacon_processor_send,
+            work_reprocessing_tx,
             mock_builder: None,
         }
     }
@@ -399,11 +460,36 @@ impl ApiTester {
         tester
     }
 
+    pub async fn new_mev_tester_with_chain_config(chain_config: ChainConfig) -> Self {
+        let mut config = ApiTesterConfig {
+            chain_config,
+            ..ApiTesterConfig::default()
+        };
+        config.spec.altair_fork_epoch = Some(Epoch::new(0));
+        config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        let tester = Self::new_from_config(config)
+            .await
+            .test_post_validator_register_validator()
+            .await;
+        tester
+            .mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_BUILDER_THRESHOLD_WEI,
+            )));
+        tester
+    }
+
     pub async fn new_mev_tester_no_builder_threshold() -> Self {
         let mut config = ApiTesterConfig {
             builder_threshold: Some(0),
+            builder_extra_data_prefix: None,
+            strict_fee_recipient: false,
             retain_historic_states: false,
             spec: E::default_spec(),
+            unix_socket: false,
+            chain_config: ChainConfig::default(),
         };
         config.spec.altair_fork_epoch = Some(Epoch::new(0));
         config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -1096,6 +1182,83 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_request_tracing_headers(self) -> Self {
+        let server = self.client.as_ref().trim_end_matches('/').to_string();
+        let validators_url = format!("{}/eth/v1/beacon/states/head/validators", server);
+        let blocks_url = self
+            .client
+            .get_beacon_blocks_path(CoreBlockId::Head)
+            .unwrap()
+            .to_string();
+        let genesis_url = format!("{}/eth/v1/beacon/genesis", server);
+
+        let mut request_ids = vec![];
+        for (url, stages) in [
+            (&validators_url, vec!["state", "serialize"]),
+            (&blocks_url, vec!["block", "serialize"]),
+            (&genesis_url, vec![]),
+        ] {
+            let response = self.client.get_response(url, |b| b).await.unwrap();
+            let headers = response.headers();
+
+            let request_id = headers.get("X-Request-Id").unwrap().to_str().unwrap();
+            assert!(!request_id.is_empty());
+            request_ids.push(request_id.to_string());
+
+            headers
+                .get("X-Response-Time-Ms")
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .parse::<u64>()
+                .unwrap();
+
+            // Only expensive endpoints break down their timings.
+            match headers.get("Server-Timing") {
+                Some(server_timing) => {
+                    let server_timing = server_timing.to_str().unwrap();
+                    assert!(!stages.is_empty());
+                    for stage in stages {
+                        assert!(server_timing.contains(&format!("{};dur=", stage)));
+                    }
+                }
+                None => assert!(stages.is_empty()),
+            }
+        }
+
+        // Each request without an ID is assigned a unique one.
+        request_ids.sort();
+        request_ids.dedup();
+        assert_eq!(request_ids.len(), 3);
+
+        // A request ID supplied by the client is echoed back.
+        let response = self
+            .client
+            .get_response(&validators_url, |b| b.header("X-Request-Id", "trace-1234"))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers().get("X-Request-Id").unwrap(),
+            "trace-1234"
+        );
+
+        // An invalid request ID is replaced.
+        let invalid_request_id = "a".repeat(129);
+        let response = self
+            .client
+            .get_response(&genesis_url, |b| {
+                b.header("X-Request-Id", invalid_request_id.as_str())
+            })
+            .await
+            .unwrap();
+        assert_ne!(
+            response.headers().get("X-Request-Id").unwrap(),
+            invalid_request_id.as_str()
+        );
+
+        self
+    }
+
     pub async fn test_beacon_headers_all_slots(self) -> Self {
         for slot in 0..CHAIN_LENGTH {
             let slot = Slot::from(slot);
@@ -1564,6 +1727,66 @@ impl ApiTester {
         self
     }
 
+    /// Checks that the blinded blocks served from the store commit to the same payload as the full
+    /// blocks.
+    pub async fn test_beacon_blinded_blocks_match_full_blocks(self) -> Self {
+        for block_id in self.interesting_block_ids() {
+            let full = self
+                .client
+                .get_beacon_blocks::<E>(block_id.0)
+                .await
+                .unwrap();
+            let blinded = self
+                .client
+                .get_beacon_blinded_blocks::<E>(block_id.0)
+                .await
+                .unwrap();
+
+            let (full, blinded) = match (full, blinded) {
+                (Some(full), Some(blinded)) => (full, blinded),
+                (None, None) => continue,
+                (full, blinded) => panic!(
+                    "{:?}: full block {:?}, blinded block {:?}",
+                    block_id, full, blinded
+                ),
+            };
+
+            assert_eq!(full.version, blinded.version, "{:?}", block_id);
+            assert_eq!(full.finalized, blinded.finalized, "{:?}", block_id);
+            assert_eq!(
+                full.execution_optimistic, blinded.execution_optimistic,
+                "{:?}",
+                block_id
+            );
+
+            // The block roots match because the payload header has the same root as the payload.
+            assert_eq!(
+                full.data.canonical_root(),
+                blinded.data.canonical_root(),
+                "{:?}",
+                block_id
+            );
+            match (
+                full.data.message().body().execution_payload(),
+                blinded.data.message().body().execution_payload(),
+            ) {
+                (Ok(payload), Ok(header)) => assert_eq!(
+                    TreeHash::tree_hash_root(&payload),
+                    TreeHash::tree_hash_root(&header),
+                    "{:?}",
+                    block_id
+                ),
+                // Blocks prior to Bellatrix have no payload, so the blinded block is the block.
+                (Err(_), Err(_)) => {
+                    assert_eq!(full.data.clone_as_blinded(), blinded.data, "{:?}", block_id)
+                }
+                _ => panic!("{:?}: payload mismatch", block_id),
+            }
+        }
+
+        self
+    }
+
     pub async fn test_beacon_blocks_attestations(self) -> Self {
         for block_id in self.interesting_block_ids() {
             let result = self
@@ -1798,6 +2021,111 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_beacon_pool_ssz(self) -> Self {
+        let attester_slashings = self
+            .client
+            .get_beacon_pool_attester_slashings_ssz::<E>()
+            .await
+            .unwrap();
+        assert!(!attester_slashings.is_empty());
+        assert_eq!(
+            attester_slashings,
+            self.client
+                .get_beacon_pool_attester_slashings::<E>()
+                .await
+                .unwrap()
+                .data
+        );
+        assert_eq!(
+            attester_slashings,
+            self.chain.op_pool.get_all_attester_slashings()
+        );
+
+        let proposer_slashings = self
+            .client
+            .get_beacon_pool_proposer_slashings_ssz()
+            .await
+            .unwrap();
+        assert!(!proposer_slashings.is_empty());
+        assert_eq!(
+            proposer_slashings,
+            self.client
+                .get_beacon_pool_proposer_slashings()
+                .await
+                .unwrap()
+                .data
+        );
+        assert_eq!(
+            proposer_slashings,
+            self.chain.op_pool.get_all_proposer_slashings()
+        );
+
+        let exits = self
+            .client
+            .get_beacon_pool_voluntary_exits_ssz()
+            .await
+            .unwrap();
+        assert!(!exits.is_empty());
+        assert_eq!(
+            exits,
+            self.client
+                .get_beacon_pool_voluntary_exits()
+                .await
+                .unwrap()
+                .data
+        );
+        assert_eq!(exits, self.chain.op_pool.get_all_voluntary_exits());
+
+        let address_changes = self
+            .client
+            .get_beacon_pool_bls_to_execution_changes_ssz()
+            .await
+            .unwrap();
+        assert_eq!(
+            address_changes,
+            self.client
+                .get_beacon_pool_bls_to_execution_changes()
+                .await
+                .unwrap()
+                .data
+        );
+        assert_eq!(
+            address_changes,
+            self.chain.op_pool.get_all_bls_to_execution_changes()
+        );
+
+        // Check that the version headers are those of the head's fork.
+        let head_slot = self.chain.canonical_head.cached_head().head_slot();
+        let head_fork = self.chain.spec.fork_name_at_slot::<E>(head_slot);
+        for operation in [
+            "attester_slashings",
+            "proposer_slashings",
+            "voluntary_exits",
+            "bls_to_execution_changes",
+        ] {
+            let url = self.client.get_beacon_pool_path(operation).unwrap();
+
+            let builders: Vec<fn(RequestBuilder) -> RequestBuilder> =
+                vec![|b| b, |b| b.accept(Accept::Ssz), |b| b.accept(Accept::Json)];
+
+            for req_builder in builders {
+                let raw_res = self
+                    .client
+                    .get_response(url.clone(), req_builder)
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    raw_res.fork_name_from_header().unwrap(),
+                    Some(head_fork),
+                    "{}",
+                    operation
+                );
+            }
+        }
+
+        self
+    }
+
     pub async fn test_get_config_fork_schedule(self) -> Self {
         let result = self.client.get_config_fork_schedule().await.unwrap().data;
 
@@ -2114,6 +2442,25 @@ impl ApiTester {
                         .execution_status
                         .block_hash()
                         .map(|block_hash| block_hash.into_root()),
+                    extra_data: Some(ForkChoiceNodeExtraData {
+                        state_root: node.state_root,
+                        best_child: node
+                            .best_child
+                            .and_then(|index| expected_proto_array.nodes.get(index))
+                            .map(|child| child.root),
+                        best_descendant: node
+                            .best_descendant
+                            .and_then(|index| expected_proto_array.nodes.get(index))
+                            .map(|descendant| descendant.root),
+                        unrealized_justified_epoch: node
+                            .unrealized_justified_checkpoint
+                            .map(|checkpoint| checkpoint.epoch),
+                        unrealized_finalized_epoch: node
+                            .unrealized_finalized_checkpoint
+                            .map(|checkpoint| checkpoint.epoch),
+                        finalized_descendant: expected_proto_array
+                            .is_finalized_checkpoint_or_descendant::<E>(node.root),
+                    }),
                 }
             })
             .collect();
@@ -2127,6 +2474,83 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_debug_fork_choice_after_finalization(self) -> Self {
+        let finalized_epoch_before = self
+            .chain
+            .head_snapshot()
+            .beacon_state
+            .finalized_checkpoint()
+            .epoch;
+
+        // Extend the chain far enough for finalization to advance.
+        self.harness
+            .extend_chain(
+                E::slots_per_epoch() as usize * 3,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let finalized_checkpoint = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint();
+        assert!(finalized_checkpoint.epoch > finalized_epoch_before);
+
+        let result = self.client.get_debug_fork_choice().await.unwrap();
+        assert_eq!(result.finalized_checkpoint, finalized_checkpoint);
+
+        // The node reported as the best descendant of the justified root must be the canonical
+        // head.
+        let head_root = self.chain.canonical_head.cached_head().head_block_root();
+        let head_node = result
+            .fork_choice_nodes
+            .iter()
+            .find(|node| node.block_root == head_root)
+            .expect("head should be in fork choice");
+        assert_eq!(head_node.validity.as_deref(), Some("valid"));
+        assert!(head_node.extra_data.as_ref().unwrap().finalized_descendant);
+        assert_eq!(head_node.extra_data.as_ref().unwrap().best_child, None);
+        assert_eq!(head_node.extra_data.as_ref().unwrap().best_descendant, None);
+
+        let justified_node = result
+            .fork_choice_nodes
+            .iter()
+            .find(|node| node.block_root == result.justified_checkpoint.root)
+            .expect("justified block should be in fork choice");
+        assert_eq!(
+            justified_node.extra_data.as_ref().unwrap().best_descendant,
+            Some(head_root)
+        );
+
+        // Every node on the canonical chain from the finalized block onwards is a finalized
+        // descendant, while any ancestor of the finalized block is not.
+        let finalized_slot = finalized_checkpoint.epoch.start_slot(E::slots_per_epoch());
+        for node in &result.fork_choice_nodes {
+            let is_canonical = self
+                .chain
+                .block_root_at_slot(node.slot, WhenSlotSkipped::None)
+                .unwrap()
+                == Some(node.block_root);
+            if is_canonical && node.slot >= finalized_slot {
+                assert!(
+                    node.extra_data.as_ref().unwrap().finalized_descendant,
+                    "canonical node at slot {} should be a finalized descendant",
+                    node.slot
+                );
+            } else if node.slot < finalized_slot && node.block_root != finalized_checkpoint.root {
+                assert!(
+                    !node.extra_data.as_ref().unwrap().finalized_descendant,
+                    "node at slot {} should not be a finalized descendant",
+                    node.slot
+                );
+            }
+        }
+
+        self
+    }
+
     fn validator_count(&self) -> usize {
         self.chain.head_snapshot().beacon_state.validators().len()
     }
@@ -2275,60 +2699,37 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_get_validator_duties_proposer(self) -> Self {
+    pub async fn test_get_validator_duties_attester_with_committees(self) -> Self {
         let current_epoch = self.chain.epoch().unwrap();
+        let indices = (0..self.chain.head_snapshot().beacon_state.validators().len() as u64)
+            .collect::<Vec<_>>();
 
-        for epoch in 0..=self.chain.epoch().unwrap().as_u64() + 1 {
-            let epoch = Epoch::from(epoch);
-
-            let dependent_root = self
-                .chain
-                .block_root_at_slot(
-                    epoch.start_slot(E::slots_per_epoch()) - 1,
-                    WhenSlotSkipped::Prev,
-                )
-                .unwrap()
-                .unwrap_or(self.chain.head_beacon_block_root());
-
-            // Presently, the beacon chain harness never runs the code that primes the proposer
-            // cache. If this changes in the future then we'll need some smarter logic here, but
-            // this is succinct and effective for the time being.
-            assert!(
-                self.chain
-                    .beacon_proposer_cache
-                    .lock()
-                    .get_epoch::<E>(dependent_root, epoch)
-                    .is_none(),
-                "the proposer cache should miss initially"
-            );
-
-            let result = self
+        for epoch in [
+            current_epoch.saturating_sub(1_u64),
+            current_epoch,
+            current_epoch + 1,
+        ] {
+            let results = self
                 .client
-                .get_validator_duties_proposer(epoch)
+                .post_validator_duties_attester_with_committees(epoch, &indices)
                 .await
                 .unwrap();
 
-            // Check that current-epoch requests prime the proposer cache, whilst non-current
-            // requests don't.
-            if epoch == current_epoch {
-                assert!(
-                    self.chain
-                        .beacon_proposer_cache
-                        .lock()
-                        .get_epoch::<E>(dependent_root, epoch)
-                        .is_some(),
-                    "a current-epoch request should prime the proposer cache"
-                );
-            } else {
-                assert!(
-                    self.chain
-                        .beacon_proposer_cache
-                        .lock()
-                        .get_epoch::<E>(dependent_root, epoch)
-                        .is_none(),
-                    "a non-current-epoch request should not prime the proposer cache"
-                );
-            }
+            // The duties must match those returned without committees.
+            let duties = self
+                .client
+                .post_validator_duties_attester(epoch, &indices)
+                .await
+                .unwrap();
+            assert_eq!(results.dependent_root, duties.dependent_root);
+            assert_eq!(
+                results
+                    .data
+                    .iter()
+                    .map(|duty| duty.duty.clone())
+                    .collect::<Vec<_>>(),
+                duties.data
+            );
 
             let mut state = self
                 .chain
@@ -2337,23 +2738,208 @@ impl ApiTester {
                     StateSkipConfig::WithStateRoots,
                 )
                 .unwrap();
-
             state
                 .build_committee_cache(RelativeEpoch::Current, &self.chain.spec)
                 .unwrap();
 
-            let expected_duties = epoch
-                .slot_iter(E::slots_per_epoch())
-                .map(|slot| {
-                    let index = state
-                        .get_beacon_proposer_index(slot, &self.chain.spec)
-                        .unwrap();
-                    let pubkey = state.validators()[index].pubkey.clone().into();
+            for result in results.data {
+                let expected = state
+                    .get_beacon_committee(result.duty.slot, result.duty.committee_index)
+                    .unwrap()
+                    .committee
+                    .iter()
+                    .map(|&i| i as u64)
+                    .collect::<Vec<_>>();
+                assert_eq!(result.committee, expected, "epoch: {}", epoch);
+                assert_eq!(
+                    result.committee[result.duty.validator_committee_index as usize],
+                    result.duty.validator_index
+                );
+            }
+        }
 
-                    ProposerData {
-                        pubkey,
-                        validator_index: index as u64,
-                        slot,
+        // Committees are only included for small requests.
+        let too_many = (0..=MAX_INCLUDE_COMMITTEE_VALIDATORS as u64).collect::<Vec<_>>();
+        assert_eq!(
+            self.client
+                .post_validator_duties_attester_with_committees(current_epoch, &too_many)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_duties_attester_committee(self) -> Self {
+        let current_epoch = self.chain.epoch().unwrap();
+
+        for epoch in [
+            current_epoch.saturating_sub(1_u64),
+            current_epoch,
+            current_epoch + 1,
+        ] {
+            let mut state = self
+                .chain
+                .state_at_slot(
+                    epoch.start_slot(E::slots_per_epoch()),
+                    StateSkipConfig::WithStateRoots,
+                )
+                .unwrap();
+            state
+                .build_committee_cache(RelativeEpoch::Current, &self.chain.spec)
+                .unwrap();
+
+            let dependent_root = self
+                .chain
+                .block_root_at_slot(
+                    (epoch - 1).start_slot(E::slots_per_epoch()) - 1,
+                    WhenSlotSkipped::Prev,
+                )
+                .unwrap()
+                .unwrap_or(self.chain.head_beacon_block_root());
+
+            for slot in epoch.slot_iter(E::slots_per_epoch()) {
+                let committee_count = state.get_committee_count_at_slot(slot).unwrap();
+
+                for index in 0..committee_count {
+                    let result = self
+                        .client
+                        .get_lighthouse_validator_duties_attester_committee(epoch, slot, index)
+                        .await
+                        .unwrap();
+
+                    let expected = AttesterCommittee {
+                        slot,
+                        index,
+                        validators: state
+                            .get_beacon_committee(slot, index)
+                            .unwrap()
+                            .committee
+                            .iter()
+                            .map(|&i| i as u64)
+                            .collect(),
+                    };
+                    assert_eq!(result.data, expected);
+                    assert_eq!(result.dependent_root, dependent_root);
+                }
+
+                // Committee indices past the end of the slot do not exist.
+                assert_eq!(
+                    self.client
+                        .get_lighthouse_validator_duties_attester_committee(
+                            epoch,
+                            slot,
+                            committee_count
+                        )
+                        .await
+                        .unwrap_err()
+                        .status()
+                        .map(Into::into),
+                    Some(404)
+                );
+            }
+        }
+
+        // The slot must be in the requested epoch.
+        assert_eq!(
+            self.client
+                .get_lighthouse_validator_duties_attester_committee(
+                    current_epoch + 1,
+                    current_epoch.start_slot(E::slots_per_epoch()),
+                    0
+                )
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        self
+    }
+
+    pub async fn test_get_validator_duties_proposer(self) -> Self {
+        let current_epoch = self.chain.epoch().unwrap();
+
+        for epoch in 0..=self.chain.epoch().unwrap().as_u64() + 1 {
+            let epoch = Epoch::from(epoch);
+
+            let dependent_root = self
+                .chain
+                .block_root_at_slot(
+                    epoch.start_slot(E::slots_per_epoch()) - 1,
+                    WhenSlotSkipped::Prev,
+                )
+                .unwrap()
+                .unwrap_or(self.chain.head_beacon_block_root());
+
+            // Presently, the beacon chain harness never runs the code that primes the proposer
+            // cache. If this changes in the future then we'll need some smarter logic here, but
+            // this is succinct and effective for the time being.
+            assert!(
+                self.chain
+                    .beacon_proposer_cache
+                    .lock()
+                    .get_epoch::<E>(dependent_root, epoch)
+                    .is_none(),
+                "the proposer cache should miss initially"
+            );
+
+            let result = self
+                .client
+                .get_validator_duties_proposer(epoch)
+                .await
+                .unwrap();
+
+            // Check that current-epoch requests prime the proposer cache, whilst non-current
+            // requests don't.
+            if epoch == current_epoch {
+                assert!(
+                    self.chain
+                        .beacon_proposer_cache
+                        .lock()
+                        .get_epoch::<E>(dependent_root, epoch)
+                        .is_some(),
+                    "a current-epoch request should prime the proposer cache"
+                );
+            } else {
+                assert!(
+                    self.chain
+                        .beacon_proposer_cache
+                        .lock()
+                        .get_epoch::<E>(dependent_root, epoch)
+                        .is_none(),
+                    "a non-current-epoch request should not prime the proposer cache"
+                );
+            }
+
+            let mut state = self
+                .chain
+                .state_at_slot(
+                    epoch.start_slot(E::slots_per_epoch()),
+                    StateSkipConfig::WithStateRoots,
+                )
+                .unwrap();
+
+            state
+                .build_committee_cache(RelativeEpoch::Current, &self.chain.spec)
+                .unwrap();
+
+            let expected_duties = epoch
+                .slot_iter(E::slots_per_epoch())
+                .map(|slot| {
+                    let index = state
+                        .get_beacon_proposer_index(slot, &self.chain.spec)
+                        .unwrap();
+                    let pubkey = state.validators()[index].pubkey.clone().into();
+
+                    ProposerData {
+                        pubkey,
+                        validator_index: index as u64,
+                        slot,
                     }
                 })
                 .collect::<Vec<_>>();
@@ -2536,6 +3122,170 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_graffiti(self) -> Self {
+        let request = GraffitiPostRequest {
+            graffiti: GraffitiString::from_str("updated at runtime").unwrap(),
+        };
+
+        // The admin token is required.
+        let unauthenticated = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(self.client.as_ref()).unwrap(),
+            Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
+        );
+        let error = unauthenticated
+            .post_lighthouse_graffiti(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+
+        let expected = BeaconNodeGraffiti {
+            graffiti: "updated at runtime".to_string(),
+            validator_graffiti_takes_precedence: true,
+        };
+        assert_eq!(
+            self.client
+                .post_lighthouse_graffiti(&request)
+                .await
+                .unwrap()
+                .data,
+            expected
+        );
+        assert_eq!(
+            self.client.get_lighthouse_graffiti().await.unwrap().data,
+            expected
+        );
+
+        // The next block produced without graffiti from the validator uses the new default.
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+        let block = self
+            .client
+            .get_validator_blocks::<E, FullPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .deconstruct()
+            .0;
+        let expected_graffiti: Graffiti = request.graffiti.into();
+        assert_eq!(*block.body().graffiti(), expected_graffiti);
+
+        // Graffiti from the validator still takes precedence.
+        let validator_graffiti = Graffiti::from([42; GRAFFITI_BYTES_LEN]);
+        let block = self
+            .client
+            .get_validator_blocks::<E, FullPayload<E>>(
+                slot,
+                &randao_reveal,
+                Some(&validator_graffiti),
+            )
+            .await
+            .unwrap()
+            .data
+            .deconstruct()
+            .0;
+        assert_eq!(*block.body().graffiti(), validator_graffiti);
+
+        self
+    }
+
+    pub async fn test_block_production_dry_run(self) -> Self {
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        for _ in 0..E::slots_per_epoch() * 3 {
+            let slot = self.chain.slot().unwrap();
+            let epoch = self.chain.epoch().unwrap();
+            let head_root = self.chain.head_beacon_block_root();
+
+            let dry_run = self
+                .client
+                .get_lighthouse_validator_blocks_dry_run(slot, false)
+                .await
+                .unwrap()
+                .data;
+            let blinded_dry_run = self
+                .client
+                .get_lighthouse_validator_blocks_dry_run(slot, true)
+                .await
+                .unwrap()
+                .data;
+
+            for dry_run in [&dry_run, &blinded_dry_run] {
+                assert_eq!(dry_run.slot, slot);
+                assert_eq!(dry_run.parent_root, head_root);
+                assert!(dry_run.timings.total_ms >= dry_run.timings.state_load_ms);
+            }
+
+            // The dry runs must not have changed the head.
+            assert_eq!(self.chain.head_beacon_block_root(), head_root);
+
+            // A real block produced after the dry runs is unaffected by them.
+            let proposer_pubkey_bytes = self
+                .client
+                .get_validator_duties_proposer(epoch)
+                .await
+                .unwrap()
+                .data
+                .into_iter()
+                .find(|duty| duty.slot == slot)
+                .map(|duty| duty.pubkey)
+                .unwrap();
+            let proposer_pubkey = (&proposer_pubkey_bytes).try_into().unwrap();
+
+            let sk = self
+                .validator_keypairs()
+                .iter()
+                .find(|kp| kp.pk == proposer_pubkey)
+                .map(|kp| kp.sk.clone())
+                .unwrap();
+
+            let randao_reveal = {
+                let domain = self.chain.spec.get_domain(
+                    epoch,
+                    Domain::Randao,
+                    &fork,
+                    genesis_validators_root,
+                );
+                let message = epoch.signing_root(domain);
+                sk.sign(message).into()
+            };
+
+            let block = self
+                .client
+                .get_validator_blocks::<E, FullPayload<E>>(slot, &randao_reveal, None)
+                .await
+                .unwrap()
+                .data
+                .deconstruct()
+                .0;
+
+            assert_eq!(block.proposer_index(), dry_run.proposer_index);
+            assert_eq!(block.parent_root(), dry_run.parent_root);
+            assert_eq!(
+                block.body().attestations().len() as u64,
+                dry_run.attestations
+            );
+            // The RANDAO reveal of the dry run differs, so the state roots differ too.
+            assert_ne!(block.state_root(), dry_run.state_root);
+
+            let signed_block = block.sign(&sk, &fork, genesis_validators_root, &self.chain.spec);
+            let signed_block_contents =
+                SignedBlockContents::try_from(signed_block.clone()).unwrap();
+
+            self.client
+                .post_beacon_blocks(&signed_block_contents)
+                .await
+                .unwrap();
+
+            assert_eq!(self.chain.head_beacon_block().as_ref(), &signed_block);
+
+            self.chain.slot_clock.set_slot(slot.as_u64() + 1);
+        }
+
+        self
+    }
+
     pub async fn test_block_production_ssz(self) -> Self {
         let fork = self.chain.canonical_head.cached_head().head_fork();
         let genesis_validators_root = self.chain.genesis_validators_root;
@@ -2628,6 +3378,36 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_block_production_zero_randao(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+
+        // A zero reveal is accepted with `skip_randao_verification`.
+        let block = self
+            .client
+            .get_validator_blocks_modular::<E, FullPayload<E>>(
+                slot,
+                &SignatureBytes::empty(),
+                None,
+                SkipRandaoVerification::Yes,
+            )
+            .await
+            .unwrap()
+            .data
+            .deconstruct()
+            .0;
+        assert_eq!(block.slot(), slot);
+
+        // Without it the request is invalid.
+        let error = self
+            .client
+            .get_validator_blocks::<E, FullPayload<E>>(slot, &SignatureBytes::empty(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_block_production_verify_randao_invalid(self) -> Self {
         let fork = self.chain.canonical_head.cached_head().head_fork();
         let genesis_validators_root = self.chain.genesis_validators_root;
@@ -2843,6 +3623,34 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_blinded_block_production_zero_randao<Payload: AbstractExecPayload<E>>(
+        self,
+    ) -> Self {
+        let slot = self.chain.slot().unwrap();
+
+        let block_contents = self
+            .client
+            .get_validator_blinded_blocks_modular::<E, Payload>(
+                slot,
+                &SignatureBytes::empty(),
+                None,
+                SkipRandaoVerification::Yes,
+            )
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(block_contents.block().slot(), slot);
+
+        let error = self
+            .client
+            .get_validator_blinded_blocks::<E, Payload>(slot, &SignatureBytes::empty(), None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_blinded_block_production_verify_randao_invalid<
         Payload: AbstractExecPayload<E>,
     >(
@@ -3159,7 +3967,7 @@ impl ApiTester {
             .beacon_state
             .validators()
             .into_iter()
-            .zip(fee_recipients.into_iter())
+            .zip(fee_recipients.iter().copied())
             .enumerate()
         {
             let actual = self
@@ -3172,6 +3980,25 @@ impl ApiTester {
             assert_eq!(actual, fee_recipient);
         }
 
+        // The preparations are exposed with the gas limit of each registration.
+        let preparations = self
+            .client
+            .get_lighthouse_proposer_preparation()
+            .await
+            .unwrap()
+            .data;
+        assert!(!preparations.is_empty());
+        for preparation in preparations {
+            assert_eq!(
+                preparation.fee_recipient,
+                fee_recipients[preparation.validator_index as usize]
+            );
+            assert_eq!(
+                preparation.gas_limit.map(|gas_limit| gas_limit.value),
+                Some(expected_gas_limit)
+            );
+        }
+
         self
     }
 
@@ -3572,6 +4399,66 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_payload_not_cached_without_randao_verification(self) -> Self {
+        // Make the builder's payload invalid so that the local payload is used.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::PrevRandao(Hash256::repeat_byte(0x42)));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks_modular::<E, BlindedPayload<E>>(
+                slot,
+                &SignatureBytes::empty(),
+                None,
+                SkipRandaoVerification::Yes,
+            )
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // A block produced without RANDAO verification can't be a real proposal, so its local
+        // payload is not kept for unblinding.
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_none());
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some());
+
+        self
+    }
+
     pub async fn test_payload_rejects_invalid_block_number(self) -> Self {
         let invalid_block_number = 2;
 
@@ -3696,6 +4583,24 @@ impl ApiTester {
         self
     }
 
+    /// Asserts that `condition` is the only condition tripping the builder circuit breaker.
+    async fn assert_builder_circuit_breaker_tripped(&self, condition: &str) {
+        let status = self
+            .client
+            .get_lighthouse_builder_circuit_breaker()
+            .await
+            .unwrap()
+            .data;
+        assert!(status.tripped);
+        let tripped = status
+            .conditions
+            .iter()
+            .filter(|c| c.tripped)
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(tripped, vec![condition]);
+    }
+
     pub async fn test_builder_chain_health_skips(self) -> Self {
         let slot = self.chain.slot().unwrap();
 
@@ -3708,6 +4613,7 @@ impl ApiTester {
         for _ in (prev_slot - head_slot).as_usize()..=self.chain.config.builder_fallback_skips {
             self.harness.advance_slot();
         }
+        self.assert_builder_circuit_breaker_tripped("skips").await;
 
         let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
 
@@ -3779,6 +4685,8 @@ impl ApiTester {
         // Without proposing, advance into the next slot, this should make us cross the threshold
         // number of skips, causing us to use the fallback.
         self.harness.advance_slot();
+        self.assert_builder_circuit_breaker_tripped("skips_per_epoch")
+            .await;
         let next_slot = self.chain.slot().unwrap();
 
         let (_, randao_reveal) = self
@@ -3828,6 +4736,8 @@ impl ApiTester {
                 .await;
             self.harness.advance_slot();
         }
+        self.assert_builder_circuit_breaker_tripped("epochs_since_finalization")
+            .await;
 
         let next_slot = self.chain.slot().unwrap();
 
@@ -3899,6 +4809,105 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_builder_chain_health_missed_proposals(self) -> Self {
+        let status = self
+            .client
+            .get_lighthouse_builder_circuit_breaker()
+            .await
+            .unwrap()
+            .data;
+        assert!(!status.tripped);
+
+        // Every validator is registered with the node, so each skipped slot is a missed proposal.
+        // Since we are proposing this slot, start the count from the previous slot.
+        let prev_slot = self.chain.slot().unwrap() - Slot::new(1);
+        let head_slot = self.chain.canonical_head.cached_head().head_slot();
+        let max_missed_proposals = self.chain.config.builder_fallback_missed_proposals.unwrap();
+        for _ in (prev_slot - head_slot).as_usize()..=max_missed_proposals {
+            self.harness.advance_slot();
+        }
+        self.assert_builder_circuit_breaker_tripped("missed_proposals")
+            .await;
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // If this cache is populated, it indicates fallback to the local EE was correctly used.
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some());
+        self
+    }
+
+    pub async fn test_builder_chain_health_disabled_condition(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let prev_slot = slot - Slot::new(1);
+        let head_slot = self.chain.canonical_head.cached_head().head_slot();
+        let epoch = self.chain.epoch().unwrap();
+
+        // Exceed the skips threshold, which is disabled.
+        for _ in (prev_slot - head_slot).as_usize()..=self.chain.config.builder_fallback_skips {
+            self.harness.advance_slot();
+        }
+
+        let status = self
+            .client
+            .get_lighthouse_builder_circuit_breaker()
+            .await
+            .unwrap()
+            .data;
+        assert!(!status.tripped);
+        let skips = status
+            .conditions
+            .iter()
+            .find(|c| c.name == "skips")
+            .unwrap();
+        assert!(!skips.enabled);
+        assert!(skips.value > skips.threshold);
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // This cache should not be populated because fallback should not have been used.
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_none());
+        self
+    }
+
     pub async fn test_builder_chain_health_optimistic_head(self) -> Self {
         // Make sure the next payload verification will return optimistic before advancing the chain.
         self.harness.mock_execution_layer.as_ref().map(|el| {
@@ -4020,14 +5029,15 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_local_payload_chosen_when_equally_profitable(self) -> Self {
-        // Mutate value.
-        self.mock_builder
-            .as_ref()
-            .unwrap()
-            .add_operation(Operation::Value(Uint256::from(
-                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
-            )));
+    pub async fn test_builder_payload_chosen_with_extra_data_prefix(self, prefix: &[u8]) -> Self {
+        let mut extra_data = prefix.to_vec();
+        extra_data.extend_from_slice(b" builder");
+
+        let mock_builder = self.mock_builder.as_ref().unwrap();
+        mock_builder.add_operation(Operation::Value(Uint256::from(
+            DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+        )));
+        mock_builder.add_operation(Operation::ExtraData(extra_data));
 
         let slot = self.chain.slot().unwrap();
         let epoch = self.chain.epoch().unwrap();
@@ -4046,25 +5056,23 @@ impl ApiTester {
             .unwrap()
             .into();
 
-        // The local payload should've been chosen, so this cache should be populated
+        // The builder's payload should've been chosen, so this cache should not be populated
         assert!(self
             .chain
             .execution_layer
             .as_ref()
             .unwrap()
             .get_payload_by_root(&payload.tree_hash_root())
-            .is_some());
+            .is_none());
         self
     }
 
-    pub async fn test_local_payload_chosen_when_more_profitable(self) -> Self {
-        // Mutate value.
-        self.mock_builder
-            .as_ref()
-            .unwrap()
-            .add_operation(Operation::Value(Uint256::from(
-                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1,
-            )));
+    pub async fn test_payload_rejects_builder_extra_data_without_prefix(self) -> Self {
+        let mock_builder = self.mock_builder.as_ref().unwrap();
+        mock_builder.add_operation(Operation::Value(Uint256::from(
+            DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+        )));
+        mock_builder.add_operation(Operation::ExtraData(b"someone else".to_vec()));
 
         let slot = self.chain.slot().unwrap();
         let epoch = self.chain.epoch().unwrap();
@@ -4083,7 +5091,7 @@ impl ApiTester {
             .unwrap()
             .into();
 
-        // The local payload should've been chosen, so this cache should be populated
+        // If this cache is populated, it indicates fallback to the local EE was correctly used.
         assert!(self
             .chain
             .execution_layer
@@ -4094,17 +5102,23 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_builder_works_post_capella(self) -> Self {
-        // Ensure builder payload is chosen
-        self.mock_builder
-            .as_ref()
-            .unwrap()
-            .add_operation(Operation::Value(Uint256::from(
-                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
-            )));
+    pub async fn test_builder_payload_with_changed_fee_recipient(
+        self,
+        strict_fee_recipient: bool,
+    ) -> Self {
+        let test_fee_recipient = "0x4242424242424242424242424242424242424242"
+            .parse::<Address>()
+            .unwrap();
+
+        let mock_builder = self.mock_builder.as_ref().unwrap();
+        mock_builder.add_operation(Operation::Value(Uint256::from(
+            DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+        )));
+        mock_builder.add_operation(Operation::FeeRecipient(test_fee_recipient));
 
         let slot = self.chain.slot().unwrap();
         let epoch = self.chain.epoch().unwrap();
+
         let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
 
         let payload: BlindedPayload<E> = self
@@ -4119,50 +5133,303 @@ impl ApiTester {
             .unwrap()
             .into();
 
-        // The builder's payload should've been chosen, so this cache should not be populated
-        assert!(self
+        // The local payload is only used, and cached, if the builder's payload was rejected.
+        let local_payload_used = self
             .chain
             .execution_layer
             .as_ref()
             .unwrap()
             .get_payload_by_root(&payload.tree_hash_root())
-            .is_none());
+            .is_some();
+        assert_eq!(local_payload_used, strict_fee_recipient);
+        assert_eq!(
+            payload.fee_recipient() == test_fee_recipient,
+            !strict_fee_recipient
+        );
         self
     }
 
-    pub async fn test_builder_works_post_deneb(self) -> Self {
-        // Ensure builder payload is chosen
+    pub async fn test_local_payload_chosen_when_equally_profitable(self) -> Self {
+        // Mutate value.
         self.mock_builder
             .as_ref()
             .unwrap()
             .add_operation(Operation::Value(Uint256::from(
-                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
             )));
 
         let slot = self.chain.slot().unwrap();
         let epoch = self.chain.epoch().unwrap();
+
         let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
 
-        let block_contents = self
+        let payload: BlindedPayload<E> = self
             .client
             .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
             .await
             .unwrap()
-            .data;
-        let (block, maybe_sidecars) = block_contents.deconstruct();
-
-        // Response should contain blob sidecars
-        assert!(maybe_sidecars.is_some());
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
 
-        // The builder's payload should've been chosen, so this cache should not be populated
-        let payload: BlindedPayload<E> = block.body().execution_payload().unwrap().into();
+        // The local payload should've been chosen, so this cache should be populated
         assert!(self
             .chain
             .execution_layer
             .as_ref()
             .unwrap()
             .get_payload_by_root(&payload.tree_hash_root())
-            .is_none());
+            .is_some());
+        self
+    }
+
+    pub async fn test_local_payload_chosen_when_more_profitable(self) -> Self {
+        // Mutate value.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI - 1,
+            )));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // The local payload should've been chosen, so this cache should be populated
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some());
+        self
+    }
+
+    pub async fn test_builder_works_post_capella(self) -> Self {
+        // Ensure builder payload is chosen
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+            )));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // The builder's payload should've been chosen, so this cache should not be populated
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_none());
+        self
+    }
+
+    pub async fn test_builder_works_post_deneb(self) -> Self {
+        // Ensure builder payload is chosen
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+            )));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let block_contents = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data;
+        let (block, maybe_sidecars) = block_contents.deconstruct();
+
+        // Response should contain blob sidecars
+        assert!(maybe_sidecars.is_some());
+
+        // The builder's payload should've been chosen, so this cache should not be populated
+        let payload: BlindedPayload<E> = block.body().execution_payload().unwrap().into();
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_none());
+        self
+    }
+
+    /// Produces and signs a blinded block carrying the builder's payload and at least one blob.
+    async fn produce_signed_builder_block_with_blobs(
+        &self,
+    ) -> SignedBlockContents<E, BlindedPayload<E>> {
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        // The mock EL picks a random number of blobs for each payload, so skip slots until the
+        // builder's payload has some.
+        for _ in 0..E::slots_per_epoch() {
+            // Ensure builder payload is chosen
+            self.mock_builder
+                .as_ref()
+                .unwrap()
+                .add_operation(Operation::Value(Uint256::from(
+                    DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1,
+                )));
+
+            let slot = self.chain.slot().unwrap();
+            let epoch = self.chain.epoch().unwrap();
+            let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+            let block_contents = self
+                .client
+                .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+                .await
+                .unwrap()
+                .data;
+
+            if !block_contents
+                .block()
+                .body()
+                .blob_kzg_commitments()
+                .unwrap()
+                .is_empty()
+            {
+                let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+                return block_contents.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+            }
+
+            self.chain.slot_clock.set_slot(slot.as_u64() + 1);
+        }
+        panic!("builder did not produce a payload with blobs");
+    }
+
+    pub async fn test_builder_reveal_with_blobs_post_deneb(self) -> Self {
+        let signed_block_contents = self.produce_signed_builder_block_with_blobs().await;
+        let block_root = signed_block_contents.signed_block().canonical_root();
+
+        self.client
+            .post_beacon_blinded_blocks(&signed_block_contents)
+            .await
+            .unwrap();
+
+        assert_eq!(self.chain.head_beacon_block_root(), block_root);
+        let blobs = self.chain.get_blobs(&block_root).unwrap();
+        assert_eq!(
+            blobs.len(),
+            signed_block_contents
+                .signed_block()
+                .message()
+                .body()
+                .blob_kzg_commitments()
+                .unwrap()
+                .len()
+        );
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_blob_propagation_post_deneb(self) -> Self {
+        let signed_block_contents = self.produce_signed_builder_block_with_blobs().await;
+        let block_root = signed_block_contents.signed_block().canonical_root();
+        let num_blobs = signed_block_contents
+            .signed_block()
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .unwrap()
+            .len();
+
+        self.client
+            .post_beacon_blinded_blocks(&signed_block_contents)
+            .await
+            .unwrap();
+
+        let result = self
+            .client
+            .get_lighthouse_beacon_blob_propagation(block_root)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        // Blobs published through the API are gossip verified, so they are reported as seen as
+        // well as stored.
+        assert_eq!(result.block_root, block_root);
+        assert_eq!(result.slot, signed_block_contents.signed_block().slot());
+        assert_eq!(result.blobs.len(), num_blobs);
+        for (index, blob) in result.blobs.iter().enumerate() {
+            assert_eq!(blob.index, index as u64);
+            assert!(blob.seen_on_gossip, "blob {index} should be seen");
+            assert!(
+                blob.seen_delay_ms.is_some(),
+                "blob {index} should have a delay"
+            );
+            assert!(blob.in_db, "blob {index} should be stored");
+        }
+        assert!(result.missing_indices().is_empty());
+
+        self
+    }
+
+    pub async fn test_builder_reveal_with_invalid_blobs_bundle_post_deneb(
+        self,
+        reveal_operation: RevealOperation,
+    ) -> Self {
+        let signed_block_contents = self.produce_signed_builder_block_with_blobs().await;
+        let head_root = self.chain.head_beacon_block_root();
+
+        // Tamper with the blobs bundle the builder reveals for the signed block.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_reveal_operation(reveal_operation);
+
+        let error = self
+            .client
+            .post_beacon_blinded_blocks(&signed_block_contents)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(error.to_string().contains("InvalidBuilderBlobsBundle"));
+
+        // The block must not have been imported.
+        assert_eq!(self.chain.head_beacon_block_root(), head_root);
         self
     }
 
@@ -4180,55 +5447,554 @@ impl ApiTester {
             .unwrap()
             .add_operation(Operation::WithdrawalsRoot(Hash256::repeat_byte(0x42)));
 
-        let slot = self.chain.slot().unwrap();
-        let epoch = self.chain.epoch().unwrap();
-        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .block()
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // The local payload should've been chosen because the builder's was invalid
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_some());
+        self
+    }
+
+    #[cfg(target_os = "linux")]
+    pub async fn test_get_lighthouse_health(self) -> Self {
+        self.client.get_lighthouse_health().await.unwrap();
+
+        self
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub async fn test_get_lighthouse_health(self) -> Self {
+        self.client.get_lighthouse_health().await.unwrap_err();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_syncing(self) -> Self {
+        self.client.get_lighthouse_syncing().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proto_array(self) -> Self {
+        self.client.get_lighthouse_proto_array().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_block_execution_status(self) -> Self {
+        let head_block_root = self.chain.head_snapshot().beacon_block_root;
+        let result = self
+            .client
+            .get_lighthouse_block_execution_status(head_block_root)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        let expected = self
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block(&head_block_root)
+            .unwrap();
+        assert_eq!(result.block_root, head_block_root);
+        assert_eq!(result.slot, expected.slot);
+        assert_eq!(
+            result.status,
+            BlockExecutionStatus::from(expected.execution_status)
+        );
+        assert_eq!(
+            result.execution_block_hash,
+            expected.execution_status.block_hash()
+        );
+
+        // Unknown blocks are not found.
+        assert!(self
+            .client
+            .get_lighthouse_block_execution_status(Hash256::repeat_byte(0x42))
+            .await
+            .unwrap()
+            .is_none());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_blob_propagation(self) -> Self {
+        let head = self.chain.head_snapshot();
+        let result = self
+            .client
+            .get_lighthouse_beacon_blob_propagation(head.beacon_block_root)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+
+        let num_blobs = head
+            .beacon_block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_or(0, |commitments| commitments.len());
+        assert_eq!(result.block_root, head.beacon_block_root);
+        assert_eq!(result.slot, head.beacon_block.slot());
+        assert_eq!(result.blobs.len(), num_blobs);
+        // Blobs of imported blocks are always stored.
+        assert!(result.missing_indices().is_empty());
+        assert!(result.blobs.iter().all(|blob| blob.in_db));
+
+        // Unknown blocks are not found.
+        assert!(self
+            .client
+            .get_lighthouse_beacon_blob_propagation(Hash256::repeat_byte(0x42))
+            .await
+            .unwrap()
+            .is_none());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_monitor_sync_committee(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_validator_monitor_sync_committee()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(result, self.chain.sync_committee_participation());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network(self) -> Self {
+        let result = self.client.get_lighthouse_network().await.unwrap().data;
+
+        assert_eq!(result.peer_id, self.local_enr.peer_id().to_string());
+        // UPnP is not used by the test network.
+        assert!(result.upnp.is_none());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_enr(self) -> Self {
+        let result = self.client.get_lighthouse_network_enr().await.unwrap().data;
+
+        assert_eq!(result.enr, self.local_enr.to_base64());
+        assert_eq!(result.seq, self.local_enr.seq());
+        assert_eq!(result.peer_id, self.local_enr.peer_id().to_string());
+        assert_eq!(result.tcp_port, self.local_enr.tcp4());
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_network_enr_invalid(mut self) -> Self {
+        // The address is maintained by discovery since ENR auto-update is enabled.
+        let update = EnrUpdateRequest {
+            ip: Some(std::net::Ipv4Addr::new(10, 0, 0, 1).into()),
+            ..Default::default()
+        };
+        let err = self
+            .client
+            .post_lighthouse_network_enr(&update)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        let zero_port = EnrUpdateRequest {
+            tcp_port: Some(0),
+            ..Default::default()
+        };
+        let err = self
+            .client
+            .post_lighthouse_network_enr(&zero_port)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        // Invalid updates are never sent to the network service.
+        assert!(self.network_rx.network_recv.recv().now_or_never().is_none());
+
+        self
+    }
+
+    pub async fn test_lighthouse_network_gossip_replay(self) -> Self {
+        // Rejections are not recorded by default.
+        let rejections = self
+            .client
+            .get_lighthouse_network_gossip_rejections()
+            .await
+            .unwrap()
+            .data;
+        assert!(rejections.is_empty());
+
+        // Sign the attestation with the signature of another validator.
+        let mut attestation = self.attestations[0].clone();
+        attestation.signature = self.attestations[1].signature.clone();
+
+        let committee_count = self
+            .chain
+            .head_snapshot()
+            .beacon_state
+            .get_committee_count_at_slot(attestation.data.slot)
+            .unwrap();
+        let subnet_id = SubnetId::compute_subnet_for_attestation_data::<E>(
+            &attestation.data,
+            committee_count,
+            &self.chain.spec,
+        )
+        .unwrap();
+        let message = PubsubMessage::Attestation(Box::new((subnet_id, attestation)));
+        let topic = GossipTopic::new(
+            message.kind(),
+            GossipEncoding::default(),
+            self.chain.enr_fork_id().fork_digest,
+        );
+        let request = GossipReplayRequest {
+            topic: topic.to_string(),
+            data: message.encode(GossipEncoding::default()),
+        };
+
+        // The admin token is required.
+        let unauthenticated = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(self.client.as_ref()).unwrap(),
+            Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
+        );
+        let error = unauthenticated
+            .post_lighthouse_network_gossip_replay(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+
+        let result = self
+            .client
+            .post_lighthouse_network_gossip_replay(&request)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            result,
+            GossipReplayResponse {
+                accepted: false,
+                error: Some("InvalidSignature".to_string()),
+            }
+        );
+
+        // Messages which cannot be decoded are a bad request.
+        let invalid = GossipReplayRequest {
+            topic: request.topic,
+            data: vec![1, 2, 3],
+        };
+        let err = self
+            .client
+            .post_lighthouse_network_gossip_replay(&invalid)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_fork_topics(self) -> Self {
+        for fork in ForkName::list_all() {
+            let result = self
+                .client
+                .get_lighthouse_network_fork_topics(fork)
+                .await
+                .unwrap()
+                .data;
+
+            let expected_digest = ChainSpec::compute_fork_digest(
+                self.chain.spec.fork_version_for_name(fork),
+                self.chain.genesis_validators_root,
+            );
+            assert_eq!(result.fork, fork);
+            assert_eq!(result.fork_digest, expected_digest);
+            assert_eq!(result.fork_epoch, self.chain.spec.fork_epoch(fork));
+
+            let block_topic = format!(
+                "/eth2/{}/beacon_block/ssz_snappy",
+                hex::encode(expected_digest)
+            );
+            assert!(result.topics.contains(&block_topic));
+
+            let subscription_slot = result
+                .fork_epoch
+                .map(|epoch| epoch.start_slot(E::slots_per_epoch()).saturating_sub(2_u64));
+            assert_eq!(result.subscription_slot, subscription_slot);
+            assert_eq!(
+                result.subscription_time,
+                subscription_slot.map(|slot| {
+                    self.chain.genesis_time + slot.as_u64() * self.chain.spec.seconds_per_slot
+                })
+            );
+        }
 
-        let payload: BlindedPayload<E> = self
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_processor(self) -> Self {
+        let snapshot = self
             .client
-            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .get_lighthouse_beacon_processor()
             .await
             .unwrap()
-            .data
-            .block()
-            .body()
-            .execution_payload()
-            .unwrap()
-            .into();
+            .data;
+        assert_eq!(snapshot.current_workers, 0);
+        assert!(snapshot.queues.values().all(|queue| queue.length == 0));
+        let max_workers = snapshot.max_workers;
+
+        // Occupy every worker so that subsequent work is queued.
+        let mut release_txs = vec![];
+        for _ in 0..max_workers {
+            let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+            release_txs.push(release_tx);
+            self.beacon_processor_send
+                .try_send(WorkEvent {
+                    drop_during_sync: false,
+                    work: Work::ApiRequestP1(BlockingOrAsync::Blocking(Box::new(move || {
+                        let _ = release_rx.blocking_recv();
+                    }))),
+                })
+                .unwrap();
+        }
 
-        // The local payload should've been chosen because the builder's was invalid
-        assert!(self
-            .chain
-            .execution_layer
-            .as_ref()
-            .unwrap()
-            .get_payload_by_root(&payload.tree_hash_root())
-            .is_some());
-        self
-    }
+        let num_queued = 3;
+        for _ in 0..num_queued {
+            self.beacon_processor_send
+                .try_send(WorkEvent {
+                    drop_during_sync: false,
+                    work: Work::ApiRequestP1(BlockingOrAsync::Blocking(Box::new(|| ()))),
+                })
+                .unwrap();
+        }
 
-    #[cfg(target_os = "linux")]
-    pub async fn test_get_lighthouse_health(self) -> Self {
-        self.client.get_lighthouse_health().await.unwrap();
+        // Snapshot requests skip ahead of queued work events, so wait for the manager to catch up.
+        let mut snapshot = None;
+        for _ in 0..100 {
+            let data = self
+                .client
+                .get_lighthouse_beacon_processor()
+                .await
+                .unwrap()
+                .data;
+            if data.queues[API_REQUEST_P1].length == num_queued {
+                snapshot = Some(data);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let snapshot = snapshot.expect("queued work should be observed");
+
+        assert_eq!(snapshot.current_workers, max_workers);
+        let queue = &snapshot.queues[API_REQUEST_P1];
+        assert!(queue.max_length >= num_queued);
+        assert_eq!(queue.dropped, 0);
+
+        // Release the workers and check that the queue drains.
+        drop(release_txs);
+        let mut drained = false;
+        for _ in 0..100 {
+            let data = self
+                .client
+                .get_lighthouse_beacon_processor()
+                .await
+                .unwrap()
+                .data;
+            if data.queues[API_REQUEST_P1].length == 0 && data.current_workers == 0 {
+                drained = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(drained, "queued work should be processed");
 
         self
     }
 
-    #[cfg(not(target_os = "linux"))]
-    pub async fn test_get_lighthouse_health(self) -> Self {
-        self.client.get_lighthouse_health().await.unwrap_err();
-
-        self
+    /// Polls the reprocess queue endpoint until `condition` holds for its snapshot.
+    async fn wait_for_reprocess_queue(
+        &self,
+        condition: impl Fn(&ReprocessQueueSnapshot) -> bool,
+    ) -> ReprocessQueueSnapshot {
+        for _ in 0..200 {
+            let snapshot = self
+                .client
+                .get_lighthouse_beacon_processor_reprocess_queue()
+                .await
+                .unwrap()
+                .data;
+            if condition(&snapshot) {
+                return snapshot;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("reprocess queue did not reach the expected state");
     }
 
-    pub async fn test_get_lighthouse_syncing(self) -> Self {
-        self.client.get_lighthouse_syncing().await.unwrap();
+    pub async fn test_get_lighthouse_beacon_processor_reprocess_queue(self) -> Self {
+        let snapshot = self
+            .client
+            .get_lighthouse_beacon_processor_reprocess_queue()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(snapshot.unknown_block_attestations.length, 0);
+        assert_eq!(snapshot.unknown_block_attestations.oldest_age_ms, None);
+        assert_eq!(
+            snapshot.unknown_block_attestations.max_length,
+            DEFAULT_MAXIMUM_QUEUED_ATTESTATIONS
+        );
+        assert_eq!(snapshot.unknown_block_aggregates.length, 0);
+        assert_eq!(snapshot.early_blocks.length, 0);
+        assert!(snapshot.early_block_releases.is_empty());
+
+        // Gossip work is processed by the same beacon processor as the HTTP API.
+        let network_beacon_processor = Arc::new(NetworkBeaconProcessor::for_testing(
+            self.chain.clone(),
+            self.beacon_processor_send.clone(),
+            self.work_reprocessing_tx.clone(),
+            self.harness.runtime.task_executor.clone(),
+            null_logger().unwrap(),
+        ));
+        let seen_timestamp = || self.chain.slot_clock.now_duration().unwrap();
+
+        // Gossip attestations and aggregates for a block which has not been imported.
+        let head = self.chain.head_snapshot();
+        let slot = self.chain.slot().unwrap();
+        let ((unknown_block, _), unknown_state) = self
+            .harness
+            .make_block(head.beacon_state.clone(), slot)
+            .await;
+        let unknown_block = Arc::new(unknown_block);
+        let unknown_block_root = unknown_block.canonical_root();
+        let attestations = self.harness.make_attestations(
+            &self.harness.get_all_validators(),
+            &unknown_state,
+            unknown_block.state_root(),
+            unknown_block_root.into(),
+            slot,
+        );
+        let mut num_attestations = 0;
+        let mut num_aggregates = 0;
+        for (committee_attestations, aggregate) in attestations {
+            for (attestation, subnet_id) in committee_attestations {
+                network_beacon_processor
+                    .send_unaggregated_attestation(
+                        MessageId::new(&[]),
+                        self.external_peer_id,
+                        attestation,
+                        subnet_id,
+                        true,
+                        seen_timestamp(),
+                    )
+                    .unwrap();
+                num_attestations += 1;
+            }
+            if let Some(aggregate) = aggregate {
+                network_beacon_processor
+                    .send_aggregated_attestation(
+                        MessageId::new(&[]),
+                        self.external_peer_id,
+                        aggregate,
+                        seen_timestamp(),
+                    )
+                    .unwrap();
+                num_aggregates += 1;
+            }
+        }
+        assert!(num_aggregates > 0, "precondition: aggregates for testing");
 
-        self
-    }
+        let snapshot = self
+            .wait_for_reprocess_queue(|snapshot| {
+                snapshot.unknown_block_attestations.length == num_attestations
+                    && snapshot.unknown_block_aggregates.length == num_aggregates
+            })
+            .await;
+        assert!(snapshot.unknown_block_attestations.oldest_age_ms.is_some());
+        assert!(snapshot.unknown_block_aggregates.oldest_age_ms.is_some());
+        assert_eq!(snapshot.light_client_updates.length, 0);
+        assert_eq!(snapshot.early_blocks.length, 0);
+
+        // Importing the block from gossip releases its attestations.
+        network_beacon_processor
+            .send_gossip_beacon_block(
+                MessageId::new(&[]),
+                self.external_peer_id,
+                Client::default(),
+                unknown_block,
+                seen_timestamp(),
+            )
+            .unwrap();
+        let snapshot = self
+            .wait_for_reprocess_queue(|snapshot| {
+                snapshot.unknown_block_attestations.length == 0
+                    && snapshot.unknown_block_aggregates.length == 0
+            })
+            .await;
+        assert_eq!(snapshot.unknown_block_attestations.oldest_age_ms, None);
+        assert_eq!(snapshot.unknown_block_aggregates.oldest_age_ms, None);
+        assert!(self
+            .chain
+            .canonical_head
+            .fork_choice_read_lock()
+            .contains_block(&unknown_block_root));
+
+        // Gossip a block for the next slot shortly before the slot starts, within the gossip clock
+        // disparity, so that it is queued until the start of its slot.
+        let early_block = self.reorg_block.signed_block().clone();
+        let early_block_root = early_block.canonical_root();
+        let early_block_slot = early_block.slot();
+        assert_eq!(early_block_slot, slot + 1);
+        let early_block_start = self.chain.slot_clock.start_of(early_block_slot).unwrap();
+        self.chain
+            .slot_clock
+            .set_current_time(early_block_start - Duration::from_millis(400));
+        network_beacon_processor
+            .send_gossip_beacon_block(
+                MessageId::new(&[]),
+                self.external_peer_id,
+                Client::default(),
+                early_block,
+                seen_timestamp(),
+            )
+            .unwrap();
 
-    pub async fn test_get_lighthouse_proto_array(self) -> Self {
-        self.client.get_lighthouse_proto_array().await.unwrap();
+        let snapshot = self
+            .wait_for_reprocess_queue(|snapshot| snapshot.early_blocks.length == 1)
+            .await;
+        assert_eq!(
+            snapshot.early_block_releases,
+            vec![EarlyBlockSnapshot {
+                block_root: early_block_root,
+                slot: early_block_slot,
+                release_time_ms: Some(
+                    (early_block_start + ADDITIONAL_QUEUED_BLOCK_DELAY).as_millis() as u64
+                ),
+            }]
+        );
+
+        // The block is released once its slot starts.
+        self.wait_for_reprocess_queue(|snapshot| snapshot.early_blocks.length == 0)
+            .await;
 
         self
     }
@@ -4253,6 +6019,123 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_validator_attestation_inclusions(self) -> Self {
+        let head_epoch = self.chain.epoch().unwrap();
+        for epoch in [Epoch::new(0), head_epoch - 2, head_epoch] {
+            // Every attester in the canonical blocks of the epoch should be found exactly once.
+            let mut expected_inclusions = 0;
+            for slot in epoch.slot_iter(E::slots_per_epoch()) {
+                if let Some(root) = self
+                    .chain
+                    .block_root_at_slot(slot, WhenSlotSkipped::None)
+                    .unwrap()
+                {
+                    let block = self.chain.get_blinded_block(&root).unwrap().unwrap();
+                    expected_inclusions += block
+                        .message()
+                        .body()
+                        .attestations()
+                        .iter()
+                        .map(|attestation| attestation.aggregation_bits.num_set_bits())
+                        .sum::<usize>();
+                }
+            }
+
+            let mut num_inclusions = 0;
+            for validator_index in 0..VALIDATOR_COUNT as u64 {
+                let validator_id = ValidatorId::Index(validator_index);
+                let inclusions = self
+                    .client
+                    .get_lighthouse_validator_attestation_inclusions(&validator_id, epoch, true)
+                    .await
+                    .unwrap()
+                    .data;
+
+                for inclusion in &inclusions {
+                    assert_eq!(inclusion.block_slot.epoch(E::slots_per_epoch()), epoch);
+                    let block = self
+                        .chain
+                        .get_blinded_block(&inclusion.block_root)
+                        .unwrap()
+                        .unwrap();
+                    assert_eq!(block.slot(), inclusion.block_slot);
+                    let attestation =
+                        &block.message().body().attestations()[inclusion.attestation_index];
+                    assert_eq!(attestation.data.slot, inclusion.attestation_slot);
+
+                    // The proof must verify against the block root.
+                    let proof = inclusion.proof.as_ref().expect("proof was requested");
+                    assert_eq!(proof.leaf, attestation.tree_hash_root());
+                    let depth = proof.branch.len();
+                    assert!(merkle_proof::verify_merkle_proof(
+                        proof.leaf,
+                        &proof.branch,
+                        depth,
+                        proof.generalized_index - (1 << depth),
+                        inclusion.block_root,
+                    ));
+                }
+                num_inclusions += inclusions.len();
+
+                // Proofs are only returned on request.
+                let without_proofs = self
+                    .client
+                    .get_lighthouse_validator_attestation_inclusions(&validator_id, epoch, false)
+                    .await
+                    .unwrap()
+                    .data;
+                assert_eq!(
+                    without_proofs,
+                    inclusions
+                        .into_iter()
+                        .map(|inclusion| AttestationInclusion {
+                            proof: None,
+                            ..inclusion
+                        })
+                        .collect::<Vec<_>>()
+                );
+            }
+            assert_eq!(num_inclusions, expected_inclusions, "epoch {epoch}");
+        }
+
+        // Validators can also be identified by their public key.
+        let epoch = head_epoch - 1;
+        let pubkey = self.validator_keypairs()[0].pk.compress();
+        assert_eq!(
+            self.client
+                .get_lighthouse_validator_attestation_inclusions(
+                    &ValidatorId::PublicKey(pubkey),
+                    epoch,
+                    false
+                )
+                .await
+                .unwrap()
+                .data,
+            self.client
+                .get_lighthouse_validator_attestation_inclusions(
+                    &ValidatorId::Index(0),
+                    epoch,
+                    false
+                )
+                .await
+                .unwrap()
+                .data
+        );
+
+        // Epochs after the head can't be queried.
+        let result = self
+            .client
+            .get_lighthouse_validator_attestation_inclusions(
+                &ValidatorId::Index(0),
+                head_epoch + 1,
+                false,
+            )
+            .await;
+        assert_eq!(result.unwrap_err().status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
         self.client.get_lighthouse_eth1_syncing().await.unwrap();
 
@@ -4299,6 +6182,121 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_beacon_states_historical_summaries(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            for (start_index, limit) in [(None, None), (Some(1), Some(1))] {
+                let result = self
+                    .client
+                    .get_lighthouse_beacon_states_historical_summaries(
+                        state_id.0,
+                        start_index,
+                        limit,
+                    )
+                    .await;
+
+                let Some((state, _execution_optimistic, _finalized)) =
+                    state_id.state(&self.chain).ok()
+                else {
+                    assert_eq!(result.unwrap(), None, "{:?}", state_id);
+                    continue;
+                };
+
+                // Pre-Capella states have no historical summaries.
+                let Ok(historical_summaries) = state.historical_summaries() else {
+                    assert!(result.is_err(), "{:?}", state_id);
+                    continue;
+                };
+
+                let page = result.unwrap().unwrap().data;
+                assert_eq!(page.start_index, start_index.unwrap_or(0));
+                assert_eq!(page.total, historical_summaries.len() as u64);
+                assert_eq!(
+                    page.historical_summaries,
+                    historical_summaries
+                        .iter()
+                        .skip(start_index.unwrap_or(0) as usize)
+                        .take(limit.unwrap_or(u64::MAX) as usize)
+                        .copied()
+                        .collect::<Vec<_>>()
+                );
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_states_proof(self) -> Self {
+        let generalized_indices = [
+            light_client_update::FINALIZED_ROOT_INDEX,
+            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX,
+            light_client_update::NEXT_SYNC_COMMITTEE_INDEX,
+            32 + HISTORICAL_SUMMARIES_FIELD_INDEX,
+        ];
+
+        for state_id in self.interesting_state_ids() {
+            let Some((mut state, _execution_optimistic, _finalized)) =
+                state_id.state(&self.chain).ok()
+            else {
+                let result = self
+                    .client
+                    .get_lighthouse_beacon_states_proof(
+                        state_id.0,
+                        light_client_update::FINALIZED_ROOT_INDEX,
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(result, None, "{:?}", state_id);
+                continue;
+            };
+            let state_root = state.update_tree_hash_cache().unwrap();
+
+            for generalized_index in generalized_indices {
+                let result = self
+                    .client
+                    .get_lighthouse_beacon_states_proof(state_id.0, generalized_index)
+                    .await;
+
+                // Fields which don't exist at the state's fork are not supported.
+                let Ok((leaf, branch)) = state.compute_merkle_proof_and_leaf(generalized_index)
+                else {
+                    assert!(result.is_err(), "{:?} {}", state_id, generalized_index);
+                    continue;
+                };
+
+                let proof = result.unwrap().unwrap().data;
+                assert_eq!(proof.generalized_index, generalized_index);
+                assert_eq!(proof.leaf, leaf);
+                assert_eq!(proof.branch, branch);
+
+                let depth = generalized_index.ilog2() as usize;
+                assert!(merkle_proof::verify_merkle_proof(
+                    proof.leaf,
+                    &proof.branch,
+                    depth,
+                    generalized_index - (1 << depth),
+                    state_root
+                ));
+            }
+
+            // Internal nodes and summaries beyond the end of the list are rejected.
+            let num_historical_summaries = state
+                .historical_summaries()
+                .map_or(0, |historical_summaries| historical_summaries.len());
+            for generalized_index in [
+                1,
+                BeaconState::<E>::historical_summary_generalized_index(num_historical_summaries),
+            ] {
+                assert!(self
+                    .client
+                    .get_lighthouse_beacon_states_proof(state_id.0, generalized_index)
+                    .await
+                    .is_err());
+            }
+        }
+
+        self
+    }
+
     pub async fn test_get_lighthouse_staking(self) -> Self {
         let result = self.client.get_lighthouse_staking().await.unwrap();
 
@@ -4320,13 +6318,132 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
-        let response = self
+    pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
+        let response = self
+            .client
+            .post_lighthouse_database_reconstruct()
+            .await
+            .unwrap();
+        assert_eq!(response, "success");
+        self
+    }
+
+    pub async fn test_post_lighthouse_database_compact(self) -> Self {
+        // The store remains readable while the compaction runs.
+        let (compaction, head_block) = tokio::join!(
+            self.client.post_lighthouse_database_compact(),
+            self.client.get_beacon_blocks::<E>(CoreBlockId::Head),
+        );
+        compaction.unwrap();
+
+        let head_root = self.chain.head_snapshot().beacon_block_root;
+        assert_eq!(
+            head_block.unwrap().unwrap().data.canonical_root(),
+            head_root
+        );
+        assert!(self.chain.get_blinded_block(&head_root).unwrap().is_some());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_caches(self) -> Self {
+        let caches = self.client.get_lighthouse_caches().await.unwrap().data;
+
+        assert_eq!(
+            caches.iter().map(|cache| cache.name).collect::<Vec<_>>(),
+            CacheName::ALL.to_vec()
+        );
+        let pubkey_cache = caches
+            .iter()
+            .find(|cache| cache.name == CacheName::PubkeyCache)
+            .unwrap();
+        assert_eq!(pubkey_cache.entries, VALIDATOR_COUNT);
+        assert!(!pubkey_cache.resizable);
+
+        self
+    }
+
+    pub async fn test_patch_lighthouse_caches(self) -> Self {
+        let shuffling_cache_info = |caches: &[CacheInfo]| {
+            caches
+                .iter()
+                .find(|cache| cache.name == CacheName::ShufflingCache)
+                .cloned()
+                .unwrap()
+        };
+
+        // Fill the cache with old shufflings, which are evicted before the head's shufflings.
+        let old_shuffling_ids = (0..4)
+            .map(|i| AttestationShufflingId {
+                shuffling_epoch: Epoch::new(0),
+                shuffling_decision_block: Hash256::repeat_byte(i + 1),
+            })
+            .collect::<Vec<_>>();
+        {
+            let mut shuffling_cache = self
+                .chain
+                .shuffling_cache
+                .try_write_for(Duration::from_secs(1))
+                .unwrap();
+            for shuffling_id in &old_shuffling_ids {
+                shuffling_cache.insert_committee_cache(
+                    shuffling_id.clone(),
+                    &Arc::new(CommitteeCache::default()),
+                );
+            }
+        }
+        let before = shuffling_cache_info(&self.client.get_lighthouse_caches().await.unwrap().data);
+        assert!(before.entries > old_shuffling_ids.len());
+
+        let request = CachesPatchRequest {
+            capacities: [(CacheName::ShufflingCache, 1)].into(),
+        };
+
+        // The admin token is required.
+        let unauthenticated = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(self.client.as_ref()).unwrap(),
+            Timeouts::set_all(Duration::from_secs(SECONDS_PER_SLOT)),
+        );
+        let error = unauthenticated
+            .patch_lighthouse_caches(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::FORBIDDEN));
+
+        let after = shuffling_cache_info(
+            &self
+                .client
+                .patch_lighthouse_caches(&request)
+                .await
+                .unwrap()
+                .data,
+        );
+        assert_eq!(before.capacity, Some(shuffling_cache::DEFAULT_CACHE_SIZE));
+        assert_eq!(after.capacity, Some(1));
+        assert!(after.entries < before.entries);
+        {
+            let shuffling_cache = self
+                .chain
+                .shuffling_cache
+                .try_read_for(Duration::from_secs(1))
+                .unwrap();
+            for shuffling_id in &old_shuffling_ids {
+                assert!(!shuffling_cache.contains(shuffling_id));
+            }
+        }
+
+        // Caches which are not resizable are rejected, without resizing the other caches.
+        let error = self
             .client
-            .post_lighthouse_database_reconstruct()
+            .patch_lighthouse_caches(&CachesPatchRequest {
+                capacities: [(CacheName::ShufflingCache, 2), (CacheName::PubkeyCache, 1)].into(),
+            })
             .await
-            .unwrap();
-        assert_eq!(response, "success");
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        let caches = self.client.get_lighthouse_caches().await.unwrap().data;
+        assert_eq!(shuffling_cache_info(&caches).capacity, Some(1));
+
         self
     }
 
@@ -4800,6 +6917,8 @@ async fn beacon_get() {
         .await
         .test_beacon_states_randao()
         .await
+        .test_request_tracing_headers()
+        .await
         .test_beacon_headers_all_slots()
         .await
         .test_beacon_headers_all_parents()
@@ -4877,6 +6996,20 @@ async fn beacon_pools_post_attestations_invalid() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_pools_get_ssz() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_pool_attester_slashings_valid()
+        .await
+        .test_post_beacon_pool_proposer_slashings_valid()
+        .await
+        .test_post_beacon_pool_voluntary_exits_valid()
+        .await
+        .test_get_beacon_pool_ssz()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn beacon_pools_post_attester_slashings_valid() {
     ApiTester::new()
@@ -4949,6 +7082,42 @@ async fn debug_get() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn debug_get_fork_choice_after_finalization() {
+    ApiTester::new()
+        .await
+        .test_get_debug_fork_choice_after_finalization()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unix_socket_client() {
+    ApiTester::new_from_config(ApiTesterConfig::default().unix_socket())
+        .await
+        .test_beacon_genesis()
+        .await
+        .test_beacon_states_root()
+        .await
+        .test_beacon_blocks_finalized::<MainnetEthSpec>()
+        .await
+        .test_get_node_version()
+        .await
+        .test_get_node_health()
+        .await
+        .test_patch_lighthouse_caches()
+        .await
+        .test_post_beacon_blocks_ssz_valid()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unix_socket_client_events() {
+    ApiTester::new_from_config(ApiTesterConfig::default().unix_socket())
+        .await
+        .test_get_events()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn node_get() {
     ApiTester::new()
@@ -4994,6 +7163,27 @@ async fn get_validator_duties_attester_with_skip_slots() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_duties_attester_with_committees() {
+    ApiTester::new()
+        .await
+        .test_get_validator_duties_attester_with_committees()
+        .await
+        .test_get_lighthouse_validator_duties_attester_committee()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_duties_attester_with_committees_with_skip_slots() {
+    ApiTester::new()
+        .await
+        .skip_slots(E::slots_per_epoch() * 2)
+        .test_get_validator_duties_attester_with_committees()
+        .await
+        .test_get_lighthouse_validator_duties_attester_committee()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_duties_proposer() {
     ApiTester::new_from_config(ApiTesterConfig::default().retain_historic_states())
@@ -5016,6 +7206,16 @@ async fn block_production() {
     ApiTester::new().await.test_block_production().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_production_with_updated_graffiti() {
+    ApiTester::new().await.test_post_lighthouse_graffiti().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_production_dry_run() {
+    ApiTester::new().await.test_block_production_dry_run().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn block_production_with_skip_slots() {
     ApiTester::new()
@@ -5033,6 +7233,14 @@ async fn block_production_no_verify_randao() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_production_zero_randao() {
+    ApiTester::new()
+        .await
+        .test_block_production_zero_randao()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn block_production_verify_randao_invalid() {
     ApiTester::new()
@@ -5130,6 +7338,14 @@ async fn blinded_block_production_no_verify_randao_blinded_payload_premerge() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn blinded_block_production_zero_randao_blinded_payload_premerge() {
+    ApiTester::new()
+        .await
+        .test_blinded_block_production_zero_randao::<BlindedPayload<_>>()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn blinded_block_production_verify_randao_invalid_blinded_payload_premerge() {
     ApiTester::new()
@@ -5270,6 +7486,14 @@ async fn get_blinded_block_invalid_prev_randao() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_blinded_block_without_randao_verification_not_cached() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_payload_not_cached_without_randao_verification()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_blinded_block_invalid_block_number() {
     ApiTester::new_mev_tester()
@@ -5318,6 +7542,28 @@ async fn builder_chain_health_epochs_since_finalization() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_chain_health_missed_proposals() {
+    ApiTester::new_mev_tester_with_chain_config(ChainConfig {
+        builder_fallback_missed_proposals: Some(1),
+        ..ChainConfig::default()
+    })
+    .await
+    .test_builder_chain_health_missed_proposals()
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_chain_health_disabled_condition() {
+    ApiTester::new_mev_tester_with_chain_config(ChainConfig {
+        builder_fallback_disabled_conditions: vec![FailedCondition::Skips],
+        ..ChainConfig::default()
+    })
+    .await
+    .test_builder_chain_health_disabled_condition()
+    .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_chain_health_optimistic_head() {
     ApiTester::new_mev_tester()
@@ -5346,12 +7592,65 @@ async fn builder_payload_chosen_by_profit() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_extra_data_prefix() {
+    let prefix = b"solo";
+    let mut config = ApiTesterConfig {
+        builder_threshold: Some(0),
+        builder_extra_data_prefix: Some(prefix.to_vec()),
+        strict_fee_recipient: false,
+        retain_historic_states: false,
+        spec: E::default_spec(),
+        unix_socket: false,
+        chain_config: ChainConfig::default(),
+    };
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_payload_chosen_with_extra_data_prefix(prefix)
+        .await
+        .test_payload_rejects_builder_extra_data_without_prefix()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_fee_recipient_mismatch() {
+    for strict_fee_recipient in [false, true] {
+        let mut config = ApiTesterConfig {
+            builder_threshold: Some(0),
+            builder_extra_data_prefix: None,
+            strict_fee_recipient,
+            retain_historic_states: false,
+            spec: E::default_spec(),
+            unix_socket: false,
+            chain_config: ChainConfig::default(),
+        };
+        config.spec.altair_fork_epoch = Some(Epoch::new(0));
+        config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+        ApiTester::new_from_config(config)
+            .await
+            .test_post_validator_register_validator()
+            .await
+            .test_builder_payload_with_changed_fee_recipient(strict_fee_recipient)
+            .await;
+    }
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_works_post_capella() {
     let mut config = ApiTesterConfig {
         builder_threshold: Some(0),
+        builder_extra_data_prefix: None,
+        strict_fee_recipient: false,
         retain_historic_states: false,
         spec: E::default_spec(),
+        unix_socket: false,
+        chain_config: ChainConfig::default(),
     };
     config.spec.altair_fork_epoch = Some(Epoch::new(0));
     config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -5371,8 +7670,12 @@ async fn builder_works_post_capella() {
 async fn builder_works_post_deneb() {
     let mut config = ApiTesterConfig {
         builder_threshold: Some(0),
+        builder_extra_data_prefix: None,
+        strict_fee_recipient: false,
         retain_historic_states: false,
         spec: E::default_spec(),
+        unix_socket: false,
+        chain_config: ChainConfig::default(),
     };
     config.spec.altair_fork_epoch = Some(Epoch::new(0));
     config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -5387,6 +7690,126 @@ async fn builder_works_post_deneb() {
         .await;
 }
 
+fn builder_post_deneb_config() -> ApiTesterConfig {
+    let mut config = ApiTesterConfig {
+        builder_threshold: Some(0),
+        builder_extra_data_prefix: None,
+        strict_fee_recipient: false,
+        retain_historic_states: false,
+        spec: E::default_spec(),
+        unix_socket: false,
+        chain_config: ChainConfig::default(),
+    };
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+    config
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_with_blobs_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_reveal_with_blobs_post_deneb()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_beacon_blob_propagation_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_get_lighthouse_beacon_blob_propagation_post_deneb()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_without_blobs_bundle_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_reveal_with_invalid_blobs_bundle_post_deneb(RevealOperation::DropBlobsBundle)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_with_missing_blob_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_reveal_with_invalid_blobs_bundle_post_deneb(RevealOperation::DropBlob)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_with_invalid_commitment_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_reveal_with_invalid_blobs_bundle_post_deneb(
+            RevealOperation::InvalidCommitment,
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_with_invalid_proof_post_deneb() {
+    ApiTester::new_from_config(builder_post_deneb_config())
+        .await
+        .test_post_validator_register_validator()
+        .await
+        .test_builder_reveal_with_invalid_blobs_bundle_post_deneb(RevealOperation::InvalidProof)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_blinded_blocks_pre_bellatrix() {
+    ApiTester::new_with_hard_forks(true, false)
+        .await
+        .test_beacon_blinded_blocks()
+        .await
+        .test_beacon_blinded_blocks_match_full_blocks()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_blinded_blocks_capella() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_beacon_blinded_blocks()
+        .await
+        .test_beacon_blinded_blocks_match_full_blocks()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_blinded_blocks_deneb() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_beacon_blinded_blocks()
+        .await
+        .test_beacon_blinded_blocks_match_full_blocks()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_validator_liveness_epoch() {
     ApiTester::new()
@@ -5405,8 +7828,26 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_block_execution_status()
+        .await
+        .test_get_lighthouse_beacon_blob_propagation()
+        .await
+        .test_get_lighthouse_validator_monitor_sync_committee()
+        .await
+        .test_get_lighthouse_network_fork_topics()
+        .await
+        .test_get_lighthouse_network()
+        .await
+        .test_get_lighthouse_network_enr()
+        .await
+        .test_post_lighthouse_network_enr_invalid()
+        .await
+        .test_lighthouse_network_gossip_replay()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
+        .test_get_lighthouse_validator_attestation_inclusions()
+        .await
         .test_get_lighthouse_validator_inclusion_global()
         .await
         .test_get_lighthouse_eth1_syncing()
@@ -5417,16 +7858,57 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
+        .test_get_lighthouse_beacon_states_historical_summaries()
+        .await
+        .test_get_lighthouse_beacon_states_proof()
+        .await
         .test_get_lighthouse_staking()
         .await
         .test_get_lighthouse_database_info()
         .await
         .test_post_lighthouse_database_reconstruct()
         .await
+        .test_post_lighthouse_database_compact()
+        .await
+        .test_get_lighthouse_caches()
+        .await
+        .test_patch_lighthouse_caches()
+        .await
         .test_post_lighthouse_liveness()
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_beacon_states_post_capella() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_lighthouse_beacon_states_historical_summaries()
+        .await
+        .test_get_lighthouse_beacon_states_proof()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_beacon_processor() {
+    ApiTester::new()
+        .await
+        .test_get_lighthouse_beacon_processor()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_beacon_processor_reprocess_queue() {
+    ApiTester::new()
+        .await
+        .test_get_lighthouse_beacon_processor_reprocess_queue()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn optimistic_responses() {
     ApiTester::new_with_hard_forks(true, true)
@@ -0,0 +1,31 @@
+#!/bin/bash
+# Generates a CA, a server certificate and a client certificate signed by the CA, and a
+# self-signed client certificate which is not trusted by the server.
+set -e
+
+gen_key() {
+    openssl genpkey -algorithm RSA -pkeyopt rsa_keygen_bits:2048 -out $1
+}
+
+# Self-signs the key `$1` as `$2.pem`, using the extensions in section `$3`.
+self_sign() {
+    openssl req -new -x509 -sha256 -days 36500 -key $1 -out $2.pem -subj "/CN=lighthouse-test-$2" \
+        -config <(printf "[req]\ndistinguished_name = dn\n[dn]\n"; cat extensions.cnf) \
+        -extensions $3
+}
+
+gen_key ca.key
+self_sign ca.key ca ca_ext
+
+for name in server client; do
+    gen_key $name.key
+    openssl req -new -sha256 -key $name.key -out $name.csr -subj "/CN=lighthouse-test-$name"
+    openssl x509 -req -sha256 -days 36500 -in $name.csr -CA ca.pem -CAkey ca.key \
+        -CAcreateserial -out $name.pem -extfile extensions.cnf -extensions ${name}_ext
+    rm $name.csr
+done
+
+gen_key untrusted_client.key
+self_sign untrusted_client.key untrusted_client untrusted_client_ext
+
+rm ca.srl
@@ -15,6 +15,7 @@ use std::num::NonZeroU16;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use strum::{Display, EnumString, EnumVariantNames};
 use types::{ForkContext, ForkName};
 
 pub const DEFAULT_IPV4_ADDRESS: Ipv4Addr = Ipv4Addr::UNSPECIFIED;
@@ -36,6 +37,19 @@ pub const DEFAULT_QUIC_PORT: u16 = 9001u16;
 /// another 500ms for "fudge factor".
 pub const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(33 * 12 + 1);
 
+/// The default maximum number of unknown ancestors a parent lookup will download.
+pub const DEFAULT_PARENT_LOOKUP_MAX_DEPTH: usize = 32;
+
+/// The default delay into the slot after which the publication of our own block is reported as late.
+pub const DEFAULT_LATE_OWN_BLOCK_THRESHOLD: Duration = Duration::from_secs(3);
+
+/// The default time we remain subscribed to a short lived attestation subnet after the end of the
+/// duty slot, to allow for late messages.
+pub const DEFAULT_SUBNET_UNSUBSCRIBE_LINGER: Duration = Duration::from_millis(500);
+
+/// The lease duration requested for UPnP port mappings.
+pub const UPNP_LEASE_DURATION: Duration = Duration::from_secs(3600);
+
 /// The maximum size of gossip messages.
 pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize {
     if is_merge_enabled {
@@ -45,6 +59,57 @@ pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize
     }
 }
 
+/// The order in which the transports advertised by a peer are dialed.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumVariantNames,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum TransportPreference {
+    /// Dial QUIC addresses, falling back to TCP addresses if they all fail.
+    #[default]
+    QuicFirst,
+    /// Dial TCP addresses, falling back to QUIC addresses if they all fail.
+    TcpFirst,
+    /// Only dial QUIC addresses.
+    QuicOnly,
+    /// Only dial TCP addresses.
+    TcpOnly,
+}
+
+impl TransportPreference {
+    /// Orders the `quic` and `tcp` addresses of a peer in the order they should be dialed,
+    /// omitting those of a transport which should not be dialed.
+    pub fn order_addresses(self, quic: Vec<Multiaddr>, tcp: Vec<Multiaddr>) -> Vec<Multiaddr> {
+        match self {
+            TransportPreference::QuicFirst => quic.into_iter().chain(tcp).collect(),
+            TransportPreference::TcpFirst => tcp.into_iter().chain(quic).collect(),
+            TransportPreference::QuicOnly => quic,
+            TransportPreference::TcpOnly => tcp,
+        }
+    }
+
+    /// Returns `true` if QUIC addresses may be dialed.
+    pub fn allows_quic(self) -> bool {
+        !matches!(self, TransportPreference::TcpOnly)
+    }
+
+    /// Returns `true` if TCP addresses may be dialed.
+    pub fn allows_tcp(self) -> bool {
+        !matches!(self, TransportPreference::QuicOnly)
+    }
+}
+
 pub struct GossipsubConfigParams {
     pub message_domain_valid_snappy: [u8; 4],
     pub gossip_max_size: usize,
@@ -108,6 +173,9 @@ pub struct Config {
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
+    /// Discards the peer scores and bans persisted by a previous run on startup.
+    pub purge_peer_scores: bool,
+
     /// Client version
     pub client_version: String,
 
@@ -117,9 +185,16 @@ pub struct Config {
     /// Disables quic support.
     pub disable_quic_support: bool,
 
+    /// The order in which the QUIC and TCP addresses of peers are dialed.
+    pub transport_preference: TransportPreference,
+
     /// Attempt to construct external port mappings with UPnP.
     pub upnp_enabled: bool,
 
+    /// The interval at which UPnP port mappings are renewed. Defaults to half of
+    /// `UPNP_LEASE_DURATION` if not set.
+    pub upnp_renewal_interval: Option<Duration>,
+
     /// Subscribe to all subnets for the duration of the runtime.
     pub subscribe_all_subnets: bool,
 
@@ -127,6 +202,10 @@ pub struct Config {
     /// runtime.
     pub import_all_attestations: bool,
 
+    /// How long to remain subscribed to a short lived attestation subnet after the end of the
+    /// slot of the aggregation duty that required it.
+    pub subnet_unsubscribe_linger: Duration,
+
     /// A setting specifying a range of values that tune the network parameters of lighthouse. The
     /// lower the value the less bandwidth used, but the slower messages will be received.
     pub network_load: u8,
@@ -138,6 +217,18 @@ pub struct Config {
     /// Shutdown beacon node after sync is completed.
     pub shutdown_after_sync: bool,
 
+    /// The maximum number of unknown ancestors a parent lookup will download. Longer chains are
+    /// synced in batches by range sync instead.
+    pub parent_lookup_max_depth: usize,
+
+    /// Delay into the slot after which the publication of a block proposed by one of our
+    /// validators is logged and counted as late.
+    pub late_own_block_threshold: Duration,
+
+    /// The number of rejected gossip messages to retain for inspection via the HTTP API. Zero
+    /// disables recording.
+    pub gossip_rejection_buffer_size: usize,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
 
@@ -158,6 +249,11 @@ pub struct Config {
 
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
+
+    /// Artificial latency and bandwidth limits on connections, for network simulations.
+    #[cfg(feature = "testing")]
+    #[serde(skip)]
+    pub link_shaping: Option<Arc<crate::link_shaping::LinkShaping>>,
 }
 
 impl Config {
@@ -277,6 +373,22 @@ impl Config {
         }
     }
 
+    /// Returns the transport preference to dial peers with, which is limited to TCP if QUIC
+    /// support is disabled.
+    pub fn dial_transport_preference(&self) -> TransportPreference {
+        if self.disable_quic_support {
+            TransportPreference::TcpOnly
+        } else {
+            self.transport_preference
+        }
+    }
+
+    /// Returns the interval at which UPnP port mappings are renewed.
+    pub fn upnp_renewal_interval(&self) -> Duration {
+        self.upnp_renewal_interval
+            .unwrap_or(UPNP_LEASE_DURATION / 2)
+    }
+
     pub fn listen_addrs(&self) -> &ListenAddress {
         &self.listen_addresses
     }
@@ -359,15 +471,22 @@ impl Default for Config {
             libp2p_nodes: vec![],
             trusted_peers: vec![],
             disable_peer_scoring: false,
+            purge_peer_scores: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             disable_quic_support: false,
+            transport_preference: TransportPreference::default(),
             upnp_enabled: true,
+            upnp_renewal_interval: None,
             network_load: 3,
             private: false,
             subscribe_all_subnets: false,
             import_all_attestations: false,
+            subnet_unsubscribe_linger: DEFAULT_SUBNET_UNSUBSCRIBE_LINGER,
             shutdown_after_sync: false,
+            parent_lookup_max_depth: DEFAULT_PARENT_LOOKUP_MAX_DEPTH,
+            late_own_block_threshold: DEFAULT_LATE_OWN_BLOCK_THRESHOLD,
+            gossip_rejection_buffer_size: 0,
             topics: Vec::new(),
             proposer_only: false,
             metrics_enabled: false,
@@ -375,6 +494,8 @@ impl Default for Config {
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
+            #[cfg(feature = "testing")]
+            link_shaping: None,
         }
     }
 }
@@ -19,6 +19,7 @@ pub use enr_ext::{peer_id_to_node_id, CombinedKeyExt, EnrExt};
 pub use libp2p::identity::{Keypair, PublicKey};
 
 use enr::{ATTESTATION_BITFIELD_ENR_KEY, ETH2_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY};
+use enr_ext::{QUIC6_ENR_KEY, QUIC_ENR_KEY};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
 use libp2p::multiaddr::Protocol;
@@ -47,9 +48,13 @@ use std::{
 use tokio::sync::mpsc;
 use types::{EnrForkId, EthSpec};
 
+mod stats;
 mod subnet_predicate;
+pub use stats::{BucketInfo, DiscoveryInfo, RecentQueries, RECENT_QUERIES_LEN};
 pub use subnet_predicate::subnet_predicate;
 
+use stats::{DiscoveryStats, QueryOutcome};
+
 /// Local ENR storage filename.
 pub const ENR_FILENAME: &str = "enr.dat";
 /// Target number of peers to search for given a grouped subnet query.
@@ -91,6 +96,59 @@ pub struct UpdatePorts {
     pub quic6: bool,
 }
 
+/// A manual update to the address and ports of the local ENR, e.g. after a NAT has reassigned the
+/// node's external ports.
+///
+/// Ports are applied to the address family of `ip`, or to that of the ENR's existing address if
+/// no `ip` is given.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnrUpdate {
+    pub ip: Option<IpAddr>,
+    pub tcp_port: Option<u16>,
+    pub udp_port: Option<u16>,
+    pub quic_port: Option<u16>,
+}
+
+impl EnrUpdate {
+    /// Checks that the update is well-formed and doesn't conflict with discovery maintaining the
+    /// ENR's address itself.
+    pub fn validate(&self, config: &NetworkConfig) -> Result<(), String> {
+        if self.ip.is_none()
+            && self.tcp_port.is_none()
+            && self.udp_port.is_none()
+            && self.quic_port.is_none()
+        {
+            return Err("at least one of ip, tcp_port, udp_port or quic_port is required".into());
+        }
+        if let Some(ip) = self.ip {
+            if ip.is_unspecified() || ip.is_multicast() {
+                return Err(format!("{ip} is not a valid ENR address"));
+            }
+        }
+        for (name, port) in [
+            ("tcp_port", self.tcp_port),
+            ("udp_port", self.udp_port),
+            ("quic_port", self.quic_port),
+        ] {
+            if port == Some(0) {
+                return Err(format!("{name} must be non-zero"));
+            }
+        }
+        if self.quic_port.is_some() && config.disable_quic_support {
+            return Err("quic_port cannot be set when QUIC is disabled".into());
+        }
+        // With ENR auto-update enabled, discv5 sets the address and UDP port from the votes of
+        // its peers and would overwrite a manual update.
+        if (self.ip.is_some() || self.udp_port.is_some()) && config.discv5_config.enr_update {
+            return Err(
+                "ip and udp_port are maintained by discovery unless --disable-enr-auto-update is set"
+                    .into(),
+            );
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, PartialEq)]
 struct SubnetQuery {
     subnet: Subnet,
@@ -194,6 +252,9 @@ pub struct Discovery<TSpec: EthSpec> {
     /// Specifies whether various port numbers should be updated after the discovery service has been started
     update_ports: UpdatePorts,
 
+    /// Statistics about queries and our externally observed address.
+    stats: DiscoveryStats,
+
     /// Logger for the discovery behaviour.
     log: slog::Logger,
 }
@@ -327,6 +388,7 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
             event_stream,
             started: !config.disable_discovery,
             update_ports,
+            stats: DiscoveryStats::default(),
             log,
             enr_dir,
         })
@@ -397,6 +459,29 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         self.discv5.table_entries_enr()
     }
 
+    /// Returns a snapshot of the routing table, queries and other discovery statistics.
+    pub fn info(&self) -> DiscoveryInfo {
+        let entries = self
+            .discv5
+            .table_entries()
+            .into_iter()
+            .map(|(node_id, _, status)| (node_id, status.is_connected()))
+            .collect::<Vec<_>>();
+        let connected = entries.iter().filter(|(_, connected)| *connected).count();
+
+        DiscoveryInfo {
+            started: self.started,
+            buckets: stats::bucket_occupancy(&self.local_enr().node_id(), entries.iter().copied()),
+            connected,
+            disconnected: entries.len() - connected,
+            active_queries: self.active_queries.len(),
+            queued_queries: self.queued_queries.len(),
+            recent_queries: self.stats.recent_queries(),
+            external_socket: self.stats.external_socket(),
+            fork_digest_mismatches: self.stats.fork_digest_mismatches(),
+        }
+    }
+
     /// Updates the local ENR TCP port.
     /// There currently isn't a case to update the address here. We opt for discovery to
     /// automatically update the external address.
@@ -445,6 +530,71 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         Ok(())
     }
 
+    /// Applies a manual update to the address and ports of the local ENR, bumping its sequence
+    /// number.
+    ///
+    /// The updated ports are no longer modified automatically when listening addresses change, so
+    /// that the update is not overwritten.
+    pub fn update_local_enr(&mut self, update: &EnrUpdate) -> Result<Enr, String> {
+        let local_enr = self.discv5.local_enr();
+        let is_ipv6 = match update.ip {
+            Some(ip) => ip.is_ipv6(),
+            None => local_enr.ip4().is_none() && local_enr.ip6().is_some(),
+        };
+
+        if update.ip.is_some() || update.udp_port.is_some() {
+            let (current_ip, current_udp_port) = if is_ipv6 {
+                (local_enr.ip6().map(IpAddr::from), local_enr.udp6())
+            } else {
+                (local_enr.ip4().map(IpAddr::from), local_enr.udp4())
+            };
+            let ip = update
+                .ip
+                .or(current_ip)
+                .ok_or("the ENR has no address; ip is required")?;
+            let udp_port = update
+                .udp_port
+                .or(current_udp_port)
+                .ok_or("the ENR has no UDP port; udp_port is required")?;
+
+            const IS_TCP: bool = false;
+            self.discv5
+                .update_local_enr_socket(SocketAddr::new(ip, udp_port), IS_TCP);
+        }
+
+        if let Some(tcp_port) = update.tcp_port {
+            let key = if is_ipv6 { "tcp6" } else { "tcp" };
+            self.discv5
+                .enr_insert(key, &tcp_port)
+                .map_err(|e| format!("{:?}", e))?;
+            if is_ipv6 {
+                self.update_ports.tcp6 = false;
+            } else {
+                self.update_ports.tcp4 = false;
+            }
+        }
+
+        if let Some(quic_port) = update.quic_port {
+            let key = if is_ipv6 { QUIC6_ENR_KEY } else { QUIC_ENR_KEY };
+            self.discv5
+                .enr_insert(key, &quic_port)
+                .map_err(|e| format!("{:?}", e))?;
+            if is_ipv6 {
+                self.update_ports.quic6 = false;
+            } else {
+                self.update_ports.quic4 = false;
+            }
+        }
+
+        let enr = self.discv5.local_enr();
+        // replace the global version
+        *self.network_globals.local_enr.write() = enr.clone();
+        // persist modified enr to disk
+        enr::save_enr_to_disk(Path::new(&self.enr_dir), &enr, &self.log);
+        info!(self.log, "Updated local ENR"; "enr" => enr.to_base64(), "seq" => enr.seq(), "ip4" => ?enr.ip4(), "udp4"=> ?enr.udp4(), "tcp4" => ?enr.tcp4(), "quic4" => ?enr.quic4(), "ip6" => ?enr.ip6(), "udp6" => ?enr.udp6(), "tcp6" => ?enr.tcp6(), "quic6" => ?enr.quic6());
+        Ok(enr)
+    }
+
     /// Adds/Removes a subnet from the ENR attnets/syncnets Bitfield
     pub fn update_enr_bitfield(&mut self, subnet: Subnet, value: bool) -> Result<(), String> {
         let local_enr = self.discv5.local_enr();
@@ -755,13 +905,18 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                 return;
             }
         };
+        let fork_digest_mismatches = self.stats.fork_digest_mismatch_counter();
         // predicate for finding nodes with a matching fork and valid tcp port
         let eth2_fork_predicate = move |enr: &Enr| {
             // `next_fork_epoch` and `next_fork_version` can be different so that
             // we can connect to peers who aren't compatible with an upcoming fork.
             // `fork_digest` **must** be same.
-            enr.eth2().map(|e| e.fork_digest) == Ok(enr_fork_id.fork_digest)
-                && (enr.tcp4().is_some() || enr.tcp6().is_some())
+            if enr.eth2().map(|e| e.fork_digest) != Ok(enr_fork_id.fork_digest) {
+                fork_digest_mismatches.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                metrics::inc_counter(&metrics::DISCOVERY_FORK_DIGEST_MISMATCHES);
+                return false;
+            }
+            enr.tcp4().is_some() || enr.tcp6().is_some()
         };
 
         // General predicate
@@ -787,6 +942,12 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
         &mut self,
         query: QueryResult,
     ) -> Option<HashMap<Enr, Option<Instant>>> {
+        self.stats.record_query(match &query.result {
+            Ok(r) if r.is_empty() => QueryOutcome::Empty,
+            Ok(_) => QueryOutcome::Found,
+            Err(_) => QueryOutcome::Failed,
+        });
+
         match query.query_type {
             QueryType::FindPeers => {
                 self.find_peer_active = false;
@@ -1013,6 +1174,7 @@ impl<TSpec: EthSpec> NetworkBehaviour for Discovery<TSpec> {
                             info!(self.log, "Address updated"; "ip" => %socket_addr.ip(), "udp_port" => %socket_addr.port());
                             metrics::inc_counter(&metrics::ADDRESS_UPDATE_COUNT);
                             metrics::check_nat();
+                            self.stats.set_external_socket(socket_addr);
                             // Discv5 will have updated our local ENR. We save the updated version
                             // to disk.
 
@@ -1189,6 +1351,7 @@ mod tests {
             }),
             vec![],
             false,
+            Arc::new(config.clone()),
             &log,
         );
         let keypair = keypair.into();
@@ -1291,4 +1454,61 @@ mod tests {
         // when a peer belongs to multiple subnet ids, we use the highest ttl.
         assert_eq!(results.get(&enr1).unwrap(), &instant1);
     }
+
+    #[tokio::test]
+    async fn test_update_local_enr_ports() {
+        let mut discovery = build_discovery().await;
+        let initial_enr = discovery.local_enr();
+
+        let update = EnrUpdate {
+            tcp_port: Some(30303),
+            quic_port: Some(30304),
+            ..Default::default()
+        };
+        let enr = discovery.update_local_enr(&update).unwrap();
+
+        assert!(enr.seq() > initial_enr.seq());
+        assert_eq!(enr.tcp4(), Some(30303));
+        assert_eq!(enr.quic4(), Some(30304));
+        assert_eq!(enr.udp4(), initial_enr.udp4());
+        assert_eq!(discovery.network_globals.local_enr(), enr);
+        assert!(!discovery.update_ports.tcp4 && !discovery.update_ports.quic4);
+    }
+
+    #[test]
+    fn test_enr_update_validation() {
+        let mut config = NetworkConfig::default();
+
+        assert!(EnrUpdate::default().validate(&config).is_err());
+        let zero_port = EnrUpdate {
+            tcp_port: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_port.validate(&config).is_err());
+
+        // The address and UDP port may only be set when discovery doesn't maintain them.
+        let socket = EnrUpdate {
+            ip: Some("203.0.113.1".parse().unwrap()),
+            udp_port: Some(9000),
+            ..Default::default()
+        };
+        config.discv5_config.enr_update = true;
+        assert!(socket.validate(&config).is_err());
+        config.discv5_config.enr_update = false;
+        assert!(socket.validate(&config).is_ok());
+
+        let unspecified = EnrUpdate {
+            ip: Some("0.0.0.0".parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(unspecified.validate(&config).is_err());
+
+        let quic = EnrUpdate {
+            quic_port: Some(9001),
+            ..Default::default()
+        };
+        assert!(quic.validate(&config).is_ok());
+        config.disable_quic_support = true;
+        assert!(quic.validate(&config).is_err());
+    }
 }
@@ -0,0 +1,262 @@
+//! Statistics about the discv5 routing table and discovery queries, used to diagnose poor peer
+//! discovery.
+use discv5::enr::NodeId;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// The number of completed queries from which the recent query rates are calculated.
+pub const RECENT_QUERIES_LEN: usize = 64;
+
+/// The outcome of a completed discovery query.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum QueryOutcome {
+    /// The query found at least one peer matching its predicate.
+    Found,
+    /// The query completed without finding any matching peers.
+    Empty,
+    /// The query failed.
+    Failed,
+}
+
+/// Statistics collected by the discovery behaviour.
+#[derive(Default)]
+pub(crate) struct DiscoveryStats {
+    /// The outcomes of the most recent queries, oldest first.
+    recent_queries: VecDeque<QueryOutcome>,
+    /// The socket most recently reported by discv5's IP vote mechanism.
+    external_socket: Option<SocketAddr>,
+    /// The number of ENRs discarded by queries due to a fork digest mismatch.
+    ///
+    /// Shared with the query predicates, which are run by discv5.
+    fork_digest_mismatches: Arc<AtomicU64>,
+}
+
+impl DiscoveryStats {
+    pub fn record_query(&mut self, outcome: QueryOutcome) {
+        if self.recent_queries.len() >= RECENT_QUERIES_LEN {
+            self.recent_queries.pop_front();
+        }
+        self.recent_queries.push_back(outcome);
+    }
+
+    pub fn set_external_socket(&mut self, socket: SocketAddr) {
+        self.external_socket = Some(socket);
+    }
+
+    pub fn external_socket(&self) -> Option<SocketAddr> {
+        self.external_socket
+    }
+
+    /// Returns the counter to increment when an ENR is discarded due to a fork digest mismatch.
+    pub fn fork_digest_mismatch_counter(&self) -> Arc<AtomicU64> {
+        self.fork_digest_mismatches.clone()
+    }
+
+    pub fn fork_digest_mismatches(&self) -> u64 {
+        self.fork_digest_mismatches.load(Ordering::Relaxed)
+    }
+
+    pub fn recent_queries(&self) -> RecentQueries {
+        let count = |outcome| {
+            self.recent_queries
+                .iter()
+                .filter(|recent| **recent == outcome)
+                .count()
+        };
+        let total = self.recent_queries.len();
+        let found_peers = count(QueryOutcome::Found);
+        let failed = count(QueryOutcome::Failed);
+        let rate = |n: usize| {
+            if total == 0 {
+                0.0
+            } else {
+                n as f64 / total as f64
+            }
+        };
+
+        RecentQueries {
+            total,
+            found_peers,
+            empty: count(QueryOutcome::Empty),
+            failed,
+            success_rate: rate(found_peers),
+            failure_rate: rate(failed),
+        }
+    }
+}
+
+/// A snapshot of the state of discovery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiscoveryInfo {
+    /// Whether the discv5 service is running.
+    pub started: bool,
+    /// The occupancy of each non-empty routing table bucket, ordered by distance.
+    pub buckets: Vec<BucketInfo>,
+    /// The number of connected entries in the routing table.
+    pub connected: usize,
+    /// The number of disconnected entries in the routing table.
+    pub disconnected: usize,
+    /// The number of queries currently running.
+    pub active_queries: usize,
+    /// The number of subnet queries waiting to be run.
+    pub queued_queries: usize,
+    /// The outcomes of the most recently completed queries.
+    pub recent_queries: RecentQueries,
+    /// Our socket as observed by other nodes, determined by discv5's IP vote mechanism.
+    pub external_socket: Option<SocketAddr>,
+    /// The number of ENRs discarded by queries because their fork digest didn't match ours.
+    pub fork_digest_mismatches: u64,
+}
+
+/// The entries of a single routing table bucket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BucketInfo {
+    /// The log2 distance of the bucket's entries from the local node.
+    pub distance: u64,
+    pub connected: usize,
+    pub disconnected: usize,
+}
+
+/// The outcomes of the last `RECENT_QUERIES_LEN` completed queries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentQueries {
+    pub total: usize,
+    /// Queries which found at least one peer.
+    pub found_peers: usize,
+    /// Queries which completed without finding any peers.
+    pub empty: usize,
+    pub failed: usize,
+    /// The fraction of queries which found at least one peer.
+    pub success_rate: f64,
+    /// The fraction of queries which failed.
+    pub failure_rate: f64,
+}
+
+/// Returns the log2 distance between two nodes, i.e. the index of the routing table bucket
+/// `other` belongs in, or `None` if the nodes are the same.
+pub fn log2_distance(local: &NodeId, other: &NodeId) -> Option<u64> {
+    let local = local.raw();
+    let other = other.raw();
+    let leading_zeros = local
+        .iter()
+        .zip(other.iter())
+        .map(|(a, b)| a ^ b)
+        .enumerate()
+        .find(|(_, xor)| *xor != 0)
+        .map(|(i, xor)| i as u64 * 8 + u64::from(xor.leading_zeros()))?;
+    Some(256 - leading_zeros)
+}
+
+/// Groups routing table entries into buckets by their distance from `local`.
+///
+/// Each entry is the node ID of the entry and whether it is connected.
+pub fn bucket_occupancy(
+    local: &NodeId,
+    entries: impl IntoIterator<Item = (NodeId, bool)>,
+) -> Vec<BucketInfo> {
+    let mut buckets: Vec<BucketInfo> = Vec::new();
+    for (node_id, connected) in entries {
+        let Some(distance) = log2_distance(local, &node_id) else {
+            continue;
+        };
+        let bucket = match buckets.binary_search_by_key(&distance, |b| b.distance) {
+            Ok(i) => &mut buckets[i],
+            Err(i) => {
+                buckets.insert(
+                    i,
+                    BucketInfo {
+                        distance,
+                        connected: 0,
+                        disconnected: 0,
+                    },
+                );
+                &mut buckets[i]
+            }
+        };
+        if connected {
+            bucket.connected += 1;
+        } else {
+            bucket.disconnected += 1;
+        }
+    }
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_id(bytes: &[(usize, u8)]) -> NodeId {
+        let mut raw = [0; 32];
+        for (i, byte) in bytes {
+            raw[*i] = *byte;
+        }
+        NodeId::new(&raw)
+    }
+
+    #[test]
+    fn distances() {
+        let local = node_id(&[]);
+        assert_eq!(log2_distance(&local, &local), None);
+        assert_eq!(log2_distance(&local, &node_id(&[(31, 1)])), Some(1));
+        assert_eq!(log2_distance(&local, &node_id(&[(31, 0xff)])), Some(8));
+        assert_eq!(log2_distance(&local, &node_id(&[(30, 1)])), Some(9));
+        assert_eq!(log2_distance(&local, &node_id(&[(0, 0x80)])), Some(256));
+    }
+
+    #[test]
+    fn buckets_are_grouped_by_distance() {
+        let local = node_id(&[]);
+        let buckets = bucket_occupancy(
+            &local,
+            vec![
+                (node_id(&[(0, 0x80)]), true),
+                (node_id(&[(31, 1)]), false),
+                (node_id(&[(0, 0xff)]), false),
+                (local, true),
+            ],
+        );
+        assert_eq!(
+            buckets,
+            vec![
+                BucketInfo {
+                    distance: 1,
+                    connected: 0,
+                    disconnected: 1,
+                },
+                BucketInfo {
+                    distance: 256,
+                    connected: 1,
+                    disconnected: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn recent_queries_are_bounded() {
+        let mut stats = DiscoveryStats::default();
+        assert_eq!(stats.recent_queries().success_rate, 0.0);
+
+        for _ in 0..RECENT_QUERIES_LEN {
+            stats.record_query(QueryOutcome::Failed);
+        }
+        for _ in 0..RECENT_QUERIES_LEN / 2 {
+            stats.record_query(QueryOutcome::Found);
+        }
+        for _ in 0..RECENT_QUERIES_LEN / 4 {
+            stats.record_query(QueryOutcome::Empty);
+        }
+
+        let recent = stats.recent_queries();
+        assert_eq!(recent.total, RECENT_QUERIES_LEN);
+        assert_eq!(recent.found_peers, RECENT_QUERIES_LEN / 2);
+        assert_eq!(recent.empty, RECENT_QUERIES_LEN / 4);
+        assert_eq!(recent.failed, RECENT_QUERIES_LEN / 4);
+        assert_eq!(recent.success_rate, 0.5);
+        assert_eq!(recent.failure_rate, 0.25);
+    }
+}
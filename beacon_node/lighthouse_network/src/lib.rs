@@ -10,6 +10,8 @@ pub mod service;
 
 #[allow(clippy::mutable_key_type)] // PeerId in hashmaps are no longer permitted by clippy
 pub mod discovery;
+#[cfg(feature = "testing")]
+pub mod link_shaping;
 pub mod listen_addr;
 pub mod metrics;
 pub mod peer_manager;
@@ -106,12 +108,13 @@ impl<'a> std::fmt::Display for ClearDialError<'a> {
 
 pub use crate::types::{
     error, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals, PubsubMessage, Subnet,
-    SubnetDiscovery,
+    SubnetDiscovery, UPnPStatus,
 };
 
 pub use prometheus_client;
 
 pub use config::Config as NetworkConfig;
+pub use config::{TransportPreference, DEFAULT_PARENT_LOOKUP_MAX_DEPTH, UPNP_LEASE_DURATION};
 pub use discovery::{CombinedKeyExt, EnrExt, Eth2Enr};
 pub use discv5;
 pub use libp2p;
@@ -125,6 +128,7 @@ pub use peer_manager::{
     peerdb::score::{PeerAction, ReportSource},
     peerdb::PeerDB,
     ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
+    Transport,
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Request, Response};
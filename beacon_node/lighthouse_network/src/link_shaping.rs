@@ -0,0 +1,413 @@
+//! Artificial latency and bandwidth limits on libp2p connections, for simulating realistic
+//! networks between nodes running on a single machine.
+//!
+//! Shaping is applied to the data each node sends over the substreams of its connections, so the
+//! link from node A to node B may be shaped differently to the link from B to A. Nodes sharing a
+//! `LinkShaping` are identified by the order in which they are added to it, which indexes the
+//! optional latency matrix. Links are looked up whenever data is sent, so nodes may be added after
+//! their connections have been established.
+
+use futures::{ready, AsyncRead, AsyncWrite, Future};
+use libp2p::core::muxing::{StreamMuxer, StreamMuxerBox, StreamMuxerEvent, SubstreamBox};
+use libp2p::core::transport::Boxed;
+use libp2p::{PeerId, Transport};
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{Instant, Sleep};
+
+/// The number of bytes a substream buffers before further writes wait for buffered data to be
+/// sent.
+const MAX_BUFFERED_BYTES: usize = 1 << 20;
+
+/// The characteristics of a one-way link between two nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LinkProfile {
+    /// The delay before data sent over the link arrives.
+    pub latency: Duration,
+    /// The upper bound of an additional random delay, drawn for each write.
+    pub jitter: Duration,
+    /// The throughput of the link in bytes per second, or `None` if unlimited.
+    pub bandwidth: Option<u64>,
+}
+
+impl LinkProfile {
+    /// A link without any delay or throughput limit.
+    pub fn ideal() -> Self {
+        Self::default()
+    }
+
+    /// A typical link between nodes in different regions.
+    pub fn wan() -> Self {
+        Self {
+            latency: Duration::from_millis(100),
+            jitter: Duration::from_millis(20),
+            // 100 Mbit/s.
+            bandwidth: Some(12_500_000),
+        }
+    }
+
+    /// A slow link with occasional long delays, as caused by retransmitting lost packets.
+    pub fn lossy() -> Self {
+        Self {
+            latency: Duration::from_millis(150),
+            jitter: Duration::from_millis(300),
+            // 10 Mbit/s.
+            bandwidth: Some(1_250_000),
+        }
+    }
+}
+
+impl FromStr for LinkProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ideal" => Ok(Self::ideal()),
+            "wan" => Ok(Self::wan()),
+            "lossy" => Ok(Self::lossy()),
+            other => Err(format!(
+                "Unknown link profile {}, expected one of ideal, wan or lossy",
+                other
+            )),
+        }
+    }
+}
+
+/// Shapes the links between a set of nodes.
+#[derive(Debug, Default)]
+pub struct LinkShaping {
+    /// The profile of every link, with the latency overridden by `latency_matrix` where given.
+    profile: LinkProfile,
+    /// The latency from the `i`th to the `j`th node at `latency_matrix[i][j]`.
+    latency_matrix: Option<Vec<Vec<Duration>>>,
+    /// The index of each node added.
+    nodes: RwLock<HashMap<PeerId, usize>>,
+    /// The time at which each link will have finished transmitting the data sent over it.
+    busy_until: Mutex<HashMap<(PeerId, PeerId), Instant>>,
+}
+
+impl LinkShaping {
+    pub fn new(profile: LinkProfile, latency_matrix: Option<Vec<Vec<Duration>>>) -> Self {
+        Self {
+            profile,
+            latency_matrix,
+            ..Self::default()
+        }
+    }
+
+    /// Adds the node with `peer_id`, returning its index in the latency matrix.
+    pub fn add_node(&self, peer_id: PeerId) -> usize {
+        let mut nodes = self.nodes.write();
+        let next_index = nodes.len();
+        *nodes.entry(peer_id).or_insert(next_index)
+    }
+
+    /// Returns the profile of the link from `from` to `to`.
+    pub fn link_profile(&self, from: &PeerId, to: &PeerId) -> LinkProfile {
+        let latency = self.latency_matrix.as_ref().and_then(|matrix| {
+            let nodes = self.nodes.read();
+            let (from, to) = (nodes.get(from)?, nodes.get(to)?);
+            matrix.get(*from)?.get(*to).copied()
+        });
+
+        LinkProfile {
+            latency: latency.unwrap_or(self.profile.latency),
+            ..self.profile
+        }
+    }
+
+    /// Returns the minimum latency of any link between distinct nodes.
+    pub fn min_latency(&self) -> Duration {
+        self.latency_matrix
+            .as_ref()
+            .and_then(|matrix| {
+                matrix
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, row)| {
+                        row.iter()
+                            .enumerate()
+                            .filter(move |(j, _)| i != *j)
+                            .map(|(_, latency)| *latency)
+                    })
+                    .min()
+            })
+            .unwrap_or(self.profile.latency)
+    }
+
+    /// Returns the time at which `len` bytes sent now from `from` to `to` arrive.
+    fn arrival_time(&self, from: PeerId, to: PeerId, len: usize) -> Instant {
+        let profile = self.link_profile(&from, &to);
+        let now = Instant::now();
+
+        let sent = match profile.bandwidth {
+            Some(bandwidth) if bandwidth > 0 => {
+                let mut busy_until = self.busy_until.lock();
+                let busy_until = busy_until.entry((from, to)).or_insert(now);
+                *busy_until = std::cmp::max(*busy_until, now)
+                    + Duration::from_secs_f64(len as f64 / bandwidth as f64);
+                *busy_until
+            }
+            _ => now,
+        };
+        let jitter = if profile.jitter.is_zero() {
+            Duration::ZERO
+        } else {
+            rand::thread_rng().gen_range(Duration::ZERO..=profile.jitter)
+        };
+
+        sent + profile.latency + jitter
+    }
+
+    /// Wraps `transport` so that the data sent over each of its connections by `local_peer_id` is
+    /// shaped.
+    pub fn wrap_transport(
+        self: &Arc<Self>,
+        transport: Boxed<(PeerId, StreamMuxerBox)>,
+        local_peer_id: PeerId,
+    ) -> Boxed<(PeerId, StreamMuxerBox)> {
+        let shaping = self.clone();
+        transport
+            .map(move |(remote_peer_id, muxer), _| {
+                let link = Link {
+                    shaping: shaping.clone(),
+                    from: local_peer_id,
+                    to: remote_peer_id,
+                };
+                let muxer = ShapedMuxer { inner: muxer, link };
+                (remote_peer_id, StreamMuxerBox::new(muxer))
+            })
+            .boxed()
+    }
+}
+
+/// One direction of the link between two nodes.
+#[derive(Clone)]
+struct Link {
+    shaping: Arc<LinkShaping>,
+    from: PeerId,
+    to: PeerId,
+}
+
+impl Link {
+    fn arrival_time(&self, len: usize) -> Instant {
+        self.shaping.arrival_time(self.from, self.to, len)
+    }
+}
+
+/// A muxer whose substreams are shaped.
+struct ShapedMuxer {
+    inner: StreamMuxerBox,
+    link: Link,
+}
+
+impl StreamMuxer for ShapedMuxer {
+    type Substream = ShapedStream<SubstreamBox>;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.inner)
+            .poll_inbound(cx)
+            .map_ok(|substream| ShapedStream::new(substream, this.link.clone()))
+    }
+
+    fn poll_outbound(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let this = self.get_mut();
+        Pin::new(&mut this.inner)
+            .poll_outbound(cx)
+            .map_ok(|substream| ShapedStream::new(substream, this.link.clone()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+
+    fn poll(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        Pin::new(&mut self.get_mut().inner).poll(cx)
+    }
+}
+
+/// A stream which holds back written data until it would have arrived over its link.
+///
+/// Data is released when the stream is flushed or closed, which does not complete until all of
+/// the data written has been released.
+struct ShapedStream<S> {
+    inner: S,
+    link: Link,
+    /// Written data which has not yet been released, along with the time it should arrive.
+    queue: VecDeque<(Instant, Vec<u8>)>,
+    /// The number of bytes of the front of `queue` already released.
+    released: usize,
+    /// The total number of bytes in `queue`.
+    buffered: usize,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl<S: AsyncWrite + Unpin> ShapedStream<S> {
+    fn new(inner: S, link: Link) -> Self {
+        Self {
+            inner,
+            link,
+            queue: VecDeque::new(),
+            released: 0,
+            buffered: 0,
+            delay: None,
+        }
+    }
+
+    /// Writes the queued data to the inner stream as it becomes due.
+    fn poll_release(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Some((arrival_time, data)) = self.queue.front() {
+            let arrival_time = *arrival_time;
+            if arrival_time > Instant::now() {
+                let delay = self
+                    .delay
+                    .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(arrival_time)));
+                delay.as_mut().reset(arrival_time);
+                ready!(delay.as_mut().poll(cx));
+                continue;
+            }
+
+            let len = data.len();
+            let written = ready!(Pin::new(&mut self.inner).poll_write(cx, &data[self.released..]))?;
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.released += written;
+            if self.released == len {
+                self.queue.pop_front();
+                self.released = 0;
+                self.buffered -= len;
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ShapedStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ShapedStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.buffered >= MAX_BUFFERED_BYTES {
+            ready!(this.poll_release(cx))?;
+        }
+        this.queue
+            .push_back((this.link.arrival_time(buf.len()), buf.to_vec()));
+        this.buffered += buf.len();
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_release(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_release(cx))?;
+        Pin::new(&mut this.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::{AsyncWriteExt, Cursor};
+
+    fn shaped_stream(shaping: LinkShaping) -> ShapedStream<Cursor<Vec<u8>>> {
+        let link = Link {
+            shaping: Arc::new(shaping),
+            from: PeerId::random(),
+            to: PeerId::random(),
+        };
+        ShapedStream::new(Cursor::new(vec![]), link)
+    }
+
+    #[tokio::test]
+    async fn latency() {
+        let profile = LinkProfile {
+            latency: Duration::from_millis(50),
+            ..LinkProfile::ideal()
+        };
+        let mut stream = shaped_stream(LinkShaping::new(profile, None));
+
+        let start = Instant::now();
+        stream.write_all(b"hello").await.unwrap();
+        assert!(stream.inner.get_ref().is_empty());
+
+        stream.flush().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(stream.inner.get_ref(), b"hello");
+    }
+
+    #[tokio::test]
+    async fn bandwidth() {
+        // 10,000 bytes take 100ms to send at 100,000 bytes per second.
+        let profile = LinkProfile {
+            bandwidth: Some(100_000),
+            ..LinkProfile::ideal()
+        };
+        let mut stream = shaped_stream(LinkShaping::new(profile, None));
+
+        let start = Instant::now();
+        stream.write_all(&[1; 5_000]).await.unwrap();
+        stream.write_all(&[2; 5_000]).await.unwrap();
+        stream.flush().await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(stream.inner.get_ref().len(), 10_000);
+    }
+
+    #[test]
+    fn asymmetric_latency_matrix() {
+        let ms = Duration::from_millis;
+        let shaping = LinkShaping::new(
+            LinkProfile::wan(),
+            Some(vec![vec![ms(0), ms(200)], vec![ms(50), ms(0)]]),
+        );
+        let (a, b, c) = (PeerId::random(), PeerId::random(), PeerId::random());
+        assert_eq!(shaping.add_node(a), 0);
+        assert_eq!(shaping.add_node(b), 1);
+        assert_eq!(shaping.add_node(a), 0);
+
+        assert_eq!(shaping.link_profile(&a, &b).latency, ms(200));
+        assert_eq!(shaping.link_profile(&b, &a).latency, ms(50));
+        assert_eq!(
+            shaping.link_profile(&a, &b).bandwidth,
+            LinkProfile::wan().bandwidth
+        );
+        // Nodes outside of the matrix use the latency of the profile.
+        assert_eq!(shaping.link_profile(&a, &c), LinkProfile::wan());
+        assert_eq!(shaping.min_latency(), ms(50));
+    }
+}
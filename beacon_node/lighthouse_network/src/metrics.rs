@@ -1,5 +1,7 @@
+use crate::PubsubMessage;
 use libp2p::bandwidth::BandwidthSinks;
 use std::sync::Arc;
+use types::EthSpec;
 
 pub use lighthouse_metrics::*;
 
@@ -27,6 +29,26 @@ lazy_static! {
         "Count of libp2p peers currently connected via QUIC"
     );
 
+    pub static ref PEER_DIAL_SUCCESS_PER_TRANSPORT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_peer_dial_success_per_transport_total",
+        "Count of successful dials to libp2p peers per transport",
+        &["transport"]
+    );
+    pub static ref PEER_DIAL_FAILURE_PER_TRANSPORT: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_peer_dial_failure_per_transport_total",
+        "Count of failed dials to libp2p peer addresses per transport",
+        &["transport"]
+    );
+
+    pub static ref WARM_START_DIALS_ATTEMPTED: Result<IntCounter> = try_create_int_counter(
+        "libp2p_warm_start_dials_attempted_total",
+        "Count of dials to peers loaded from the peer cache on startup"
+    );
+    pub static ref WARM_START_DIALS_SUCCEEDED: Result<IntCounter> = try_create_int_counter(
+        "libp2p_warm_start_dials_succeeded_total",
+        "Count of successful dials to peers loaded from the peer cache on startup"
+    );
+
     pub static ref PEER_CONNECT_EVENT_COUNT: Result<IntCounter> = try_create_int_counter(
         "libp2p_peer_connect_event_total",
         "Count of libp2p peer connect events (not the current number of connected peers)"
@@ -47,6 +69,10 @@ lazy_static! {
         "discovery_queue_size",
         "The number of discovery queries awaiting execution"
     );
+    pub static ref DISCOVERY_FORK_DIGEST_MISMATCHES: Result<IntCounter> = try_create_int_counter(
+        "discovery_fork_digest_mismatches_total",
+        "Count of ENRs discarded by discovery queries due to a fork digest mismatch"
+    );
     pub static ref DISCOVERY_REQS: Result<Gauge> = try_create_float_gauge(
         "discovery_requests",
         "The number of unsolicited discovery requests per second"
@@ -112,6 +138,12 @@ lazy_static! {
             "Messages that failed to be published on retry to gossipsub per topic kind.",
             &["topic_kind"]
         );
+    pub static ref GOSSIP_SSZ_DECODE_ATTEMPTS_PER_TOPIC_KIND: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_ssz_decode_attempts_per_topic_kind",
+            "Gossip messages which passed the size limits of their topic and were SSZ decoded, per topic kind.",
+            &["topic_kind"]
+        );
     pub static ref PEER_SCORE_DISTRIBUTION: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "peer_score_distribution",
@@ -157,6 +189,15 @@ lazy_static! {
         "Number of peer reports per msg",
         &["msg"]
     );
+
+    /*
+     * Gossip bandwidth
+     */
+    pub static ref GOSSIP_BLOB_SUBNET_BYTES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "gossip_blob_subnet_bytes_total",
+        "Total uncompressed size of the blob sidecars received on each blob subnet",
+        &["subnet"]
+    );
 }
 
 /// Checks if we consider the NAT open.
@@ -179,6 +220,17 @@ pub fn check_nat() {
     }
 }
 
+/// Records the uncompressed size, `num_bytes`, of a gossip `message` received from a peer.
+pub fn register_gossip_message_bytes<E: EthSpec>(message: &PubsubMessage<E>, num_bytes: usize) {
+    if let PubsubMessage::BlobSidecar(blob_sidecar) = message {
+        inc_counter_vec_by(
+            &GOSSIP_BLOB_SUBNET_BYTES,
+            &[&blob_sidecar.0.to_string()],
+            num_bytes as u64,
+        );
+    }
+}
+
 pub fn scrape_discovery_metrics() {
     let metrics =
         discv5::metrics::Metrics::from(discv5::Discv5::<discv5::DefaultProtocolId>::raw_metrics());
@@ -230,3 +282,39 @@ impl AggregatedBandwidthSinks {
         self.tcp_sinks.total_outbound()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::marker::PhantomData;
+    use types::{BlobSidecar, MainnetEthSpec, Signature, SignedBlobSidecar};
+
+    type E = MainnetEthSpec;
+
+    fn blob_subnet_bytes(subnet: u64) -> u64 {
+        get_int_counter(&GOSSIP_BLOB_SUBNET_BYTES, &[&subnet.to_string()])
+            .map_or(0, |counter| counter.get())
+    }
+
+    #[test]
+    fn blob_subnet_bytes_are_counted() {
+        let blob_sidecar = |subnet| {
+            PubsubMessage::<E>::BlobSidecar(Box::new((
+                subnet,
+                SignedBlobSidecar {
+                    message: Arc::new(BlobSidecar::empty()),
+                    signature: Signature::empty(),
+                    _phantom: PhantomData,
+                },
+            )))
+        };
+        let before = [blob_subnet_bytes(1), blob_subnet_bytes(2)];
+
+        register_gossip_message_bytes(&blob_sidecar(1), 100);
+        register_gossip_message_bytes(&blob_sidecar(1), 50);
+        register_gossip_message_bytes(&blob_sidecar(2), 10);
+
+        assert_eq!(blob_subnet_bytes(1), before[0] + 150);
+        assert_eq!(blob_subnet_bytes(2), before[1] + 10);
+    }
+}
@@ -10,6 +10,9 @@ pub const DEFAULT_PING_INTERVAL_INBOUND: u64 = 20;
 /// Default number of peers to connect to.
 pub const DEFAULT_TARGET_PEERS: usize = 50;
 
+use crate::TransportPreference;
+use std::path::PathBuf;
+
 /// Configurations for the PeerManager.
 #[derive(Debug)]
 pub struct Config {
@@ -30,6 +33,14 @@ pub struct Config {
     pub ping_interval_inbound: u64,
     /// Interval between PING events for peers dialed by us.
     pub ping_interval_outbound: u64,
+    /// The order in which the QUIC and TCP addresses of peers are dialed.
+    pub transport_preference: TransportPreference,
+
+    /* Persistence related configurations */
+    /// The directory peer scores and bans are persisted to. Persistence is disabled if `None`.
+    pub network_dir: Option<PathBuf>,
+    /// Whether to discard any persisted peer scores and bans on startup.
+    pub purge_peer_scores: bool,
 }
 
 impl Default for Config {
@@ -41,6 +52,9 @@ impl Default for Config {
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
+            transport_preference: TransportPreference::default(),
+            network_dir: None,
+            purge_peer_scores: false,
         }
     }
 }
@@ -1,10 +1,11 @@
 //! Implementation of Lighthouse's peer management system.
 
 use crate::discovery::enr_ext::EnrExt;
+use crate::discovery::Eth2Enr;
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
 use crate::service::TARGET_SUBNET_PEERS;
 use crate::{error, metrics, Gossipsub};
-use crate::{NetworkGlobals, PeerId};
+use crate::{NetworkGlobals, PeerId, TransportPreference};
 use crate::{Subnet, SubnetDiscovery};
 use delay_map::HashSetDelay;
 use discv5::Enr;
@@ -28,12 +29,13 @@ pub use libp2p::identity::Keypair;
 pub mod peerdb;
 
 pub use peerdb::peer_info::{
-    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
+    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo, Transport,
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::net::IpAddr;
+use std::path::PathBuf;
 pub mod config;
 mod network_behaviour;
 
@@ -41,6 +43,10 @@ mod network_behaviour;
 /// requests. This defines the interval in seconds.
 const HEARTBEAT_INTERVAL: u64 = 30;
 
+/// The interval at which peer scores, bans and the peer cache are checkpointed to disk, in
+/// addition to on shutdown.
+const PEER_DB_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(300);
+
 /// The minimum amount of time we allow peers to reconnect to us after a disconnect when we are
 /// saturated with peers. This effectively looks like a swarm BAN for this amount of time.
 pub const PEER_RECONNECTION_TIMEOUT: Duration = Duration::from_secs(600);
@@ -79,6 +85,8 @@ pub struct PeerManager<TSpec: EthSpec> {
     target_peers: usize,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
+    /// Peers loaded from the peer cache on startup whose dials have not yet completed.
+    warm_start_peers: HashSet<PeerId>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
     /// reconnection.
     // NOTE: This just prevents re-connections. The state of the peer is otherwise unaffected. A
@@ -104,6 +112,12 @@ pub struct PeerManager<TSpec: EthSpec> {
     discovery_enabled: bool,
     /// Keeps track if the current instance is reporting metrics or not.
     metrics_enabled: bool,
+    /// The order in which the QUIC and TCP addresses of peers are dialed.
+    transport_preference: TransportPreference,
+    /// The directory peer scores and bans are persisted to, if any.
+    network_dir: Option<PathBuf>,
+    /// The last time peer scores and bans were persisted to disk.
+    last_peer_db_checkpoint: Instant,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
@@ -149,15 +163,39 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             status_interval,
             ping_interval_inbound,
             ping_interval_outbound,
+            transport_preference,
+            network_dir,
+            purge_peer_scores,
         } = cfg;
 
+        // Restore the reputation of peers from a previous run, informing the swarm of the peers
+        // which are still banned.
+        let mut events = SmallVec::new();
+        if let Some(dir) = &network_dir {
+            if purge_peer_scores {
+                debug!(log, "Purging persisted peer scores");
+                peerdb::persisted::remove_from_disk(dir, log);
+            } else if let Some(persisted) = peerdb::persisted::load_from_disk(dir, log) {
+                let banned_peers = network_globals
+                    .peers
+                    .write()
+                    .restore_persisted_peers(persisted);
+                events.extend(
+                    banned_peers
+                        .into_iter()
+                        .map(|(peer_id, banned_ips)| PeerManagerEvent::Banned(peer_id, banned_ips)),
+                );
+            }
+        }
+
         // Set up the peer manager heartbeat interval
         let heartbeat = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_INTERVAL));
 
         Ok(PeerManager {
             network_globals,
-            events: SmallVec::new(),
+            events,
             peers_to_dial: Default::default(),
+            warm_start_peers: Default::default(),
             inbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_inbound)),
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
@@ -167,12 +205,66 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             heartbeat,
             discovery_enabled,
             metrics_enabled,
+            transport_preference,
+            network_dir,
+            last_peer_db_checkpoint: Instant::now(),
             log: log.clone(),
         })
     }
 
     /* Public accessible functions */
 
+    /// Writes the scores and bans of known peers to the network directory, so that they survive a
+    /// restart.
+    pub fn persist_peer_db(&self) {
+        if let Some(dir) = &self.network_dir {
+            let persisted = self.network_globals.peers.read().persisted_peers();
+            peerdb::persisted::save_to_disk(dir, &persisted, &self.log);
+        }
+    }
+
+    /// Writes the ENRs of recently connected healthy peers to the network directory, so that
+    /// they can be dialed straight away after a restart.
+    pub fn persist_peer_cache(&self) {
+        if let Some(dir) = &self.network_dir {
+            let peer_cache = self.network_globals.peers.read().cached_peers();
+            peerdb::peer_cache::save_to_disk(dir, &peer_cache, &self.log);
+        }
+    }
+
+    /// Loads the peer cache from the network directory and dials the cached peers that are still
+    /// on our fork.
+    ///
+    /// Returns the usable cached ENRs so they can also be added to the discovery routing
+    /// table.
+    pub fn warm_start(&mut self) -> Vec<Enr> {
+        let Some(dir) = &self.network_dir else {
+            return vec![];
+        };
+        let Some(peer_cache) = peerdb::peer_cache::load_from_disk(dir, &self.log) else {
+            return vec![];
+        };
+        let fork_digest = match self.network_globals.local_enr().eth2() {
+            Ok(enr_fork_id) => enr_fork_id.fork_digest,
+            Err(e) => {
+                warn!(self.log, "Not using peer cache, local ENR has no fork id"; "error" => e);
+                return vec![];
+            }
+        };
+
+        let enrs = peer_cache.usable_enrs(fork_digest, &self.log);
+        for enr in &enrs {
+            let peer_id = enr.peer_id();
+            if self.network_globals.peers.read().should_dial(&peer_id) {
+                self.warm_start_peers.insert(peer_id);
+                self.peers_to_dial.push(enr.clone());
+                metrics::inc_counter(&metrics::WARM_START_DIALS_ATTEMPTED);
+            }
+        }
+        debug!(self.log, "Dialing cached peers"; "count" => self.warm_start_peers.len());
+        enrs
+    }
+
     /// The application layer wants to disconnect from a peer for a particular reason.
     ///
     /// All instant disconnections are fatal and we ban the associated peer.
@@ -1218,6 +1310,13 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
 
         // Maintains memory by shrinking mappings
         self.shrink_mappings();
+
+        // Periodically checkpoint peer scores in case we don't shut down cleanly.
+        if self.last_peer_db_checkpoint.elapsed() >= PEER_DB_CHECKPOINT_INTERVAL {
+            self.persist_peer_db();
+            self.persist_peer_cache();
+            self.last_peer_db_checkpoint = Instant::now();
+        }
     }
 
     // Reduce memory footprint by routinely shrinking associating mappings.
@@ -1357,6 +1456,42 @@ mod tests {
         PeerManager::new(config, Arc::new(globals), &log).unwrap()
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_bans_restored_peers() {
+        let log = build_log(slog::Level::Debug, false);
+        let network_dir = tempfile::tempdir().unwrap();
+        let banned_peer = PeerId::random();
+        let persisted =
+            peerdb::persisted::PersistedPeerDB::new(vec![peerdb::persisted::PersistedPeer {
+                peer_id: banned_peer.to_base58(),
+                lighthouse_score: -100.0,
+                ban_remaining_secs: 3600,
+                ip_addresses: vec![],
+            }]);
+        peerdb::persisted::save_to_disk(network_dir.path(), &persisted, &log);
+
+        let config = config::Config {
+            discovery_enabled: false,
+            network_dir: Some(network_dir.path().into()),
+            ..Default::default()
+        };
+        let globals = NetworkGlobals::new_test_globals(vec![], &log);
+        let peer_manager: PeerManager<E> =
+            PeerManager::new(config, Arc::new(globals), &log).unwrap();
+
+        // The restored ban is applied to the swarm, as well as the peer DB.
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&banned_peer)
+            .unwrap()
+            .is_banned());
+        assert!(peer_manager.events.iter().any(
+            |event| matches!(event, PeerManagerEvent::Banned(peer_id, _) if *peer_id == banned_peer)
+        ));
+    }
+
     #[tokio::test]
     async fn test_peer_manager_disconnects_correctly_during_heartbeat() {
         // Create 6 peers to connect to with a target of 3.
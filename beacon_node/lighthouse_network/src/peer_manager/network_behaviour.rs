@@ -1,20 +1,24 @@
 //! Implementation of [`NetworkBehaviour`] for the [`PeerManager`].
 
+use std::collections::HashSet;
 use std::net::IpAddr;
+use std::num::NonZeroU8;
 use std::task::{Context, Poll};
 
 use futures::StreamExt;
-use libp2p::core::{multiaddr, ConnectedPoint};
+use libp2p::core::{multiaddr, ConnectedPoint, Multiaddr};
 use libp2p::identity::PeerId;
 use libp2p::swarm::behaviour::{ConnectionClosed, ConnectionEstablished, DialFailure, FromSwarm};
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::swarm::dummy::ConnectionHandler;
-use libp2p::swarm::{ConnectionDenied, ConnectionId, NetworkBehaviour, PollParameters, ToSwarm};
+use libp2p::swarm::{
+    ConnectionDenied, ConnectionId, DialError, NetworkBehaviour, PollParameters, ToSwarm,
+};
 use slog::{debug, error, trace};
 use types::EthSpec;
 
 use crate::discovery::enr_ext::EnrExt;
-use crate::peer_manager::peerdb::BanResult;
+use crate::peer_manager::peerdb::{peer_info::Transport, BanResult};
 use crate::rpc::GoodbyeReason;
 use crate::types::SyncState;
 use crate::{metrics, ClearDialError};
@@ -97,23 +101,26 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
             self.events.shrink_to_fit();
         }
 
-        if let Some(enr) = self.peers_to_dial.pop() {
+        while let Some(enr) = self.peers_to_dial.pop() {
             let peer_id = enr.peer_id();
-            self.inject_peer_connection(&peer_id, ConnectingType::Dialing, Some(enr.clone()));
-            let quic_multiaddrs = enr.multiaddr_quic();
-            if !quic_multiaddrs.is_empty() {
-                debug!(self.log, "Dialing QUIC supported peer"; "peer_id"=> %peer_id, "quic_multiaddrs" => ?quic_multiaddrs);
+            let multiaddrs = self
+                .transport_preference
+                .order_addresses(enr.multiaddr_quic(), enr.multiaddr_tcp());
+            if multiaddrs.is_empty() {
+                trace!(self.log, "Peer has no addresses for the allowed transports"; "peer_id" => %peer_id, "transport_preference" => %self.transport_preference);
+                continue;
             }
 
-            // Prioritize Quic connections over Tcp ones.
-            let multiaddrs = quic_multiaddrs
-                .into_iter()
-                .chain(enr.multiaddr_tcp())
-                .collect();
+            self.inject_peer_connection(&peer_id, ConnectingType::Dialing, Some(enr));
+            debug!(self.log, "Dialing peer"; "peer_id" => %peer_id, "transport_preference" => %self.transport_preference, "multiaddrs" => ?multiaddrs);
+
             return Poll::Ready(ToSwarm::Dial {
                 opts: DialOpts::peer_id(peer_id)
                     .condition(PeerCondition::Disconnected)
                     .addresses(multiaddrs)
+                    // Dial the addresses one at a time, in order, so that the other transport is
+                    // only dialed if the preferred one fails.
+                    .override_dial_concurrency_factor(NonZeroU8::new(1).expect("1 is non-zero"))
                     .build(),
             });
         }
@@ -126,9 +133,13 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
             FromSwarm::ConnectionEstablished(ConnectionEstablished {
                 peer_id,
                 endpoint,
+                failed_addresses,
                 other_established,
                 ..
             }) => {
+                if let ConnectedPoint::Dialer { address, .. } = endpoint {
+                    self.on_dial_outcome(&peer_id, Some(address), failed_addresses.iter());
+                }
                 // NOTE: We still need to handle the [`ConnectionEstablished`] because the
                 // [`NetworkBehaviour::handle_established_inbound_connection`] and
                 // [`NetworkBehaviour::handle_established_outbound_connection`] are fallible. This
@@ -149,6 +160,9 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
                 connection_id: _,
             }) => {
                 debug!(self.log, "Failed to dial peer"; "peer_id"=> ?peer_id, "error" => %ClearDialError(error));
+                if let (Some(peer_id), DialError::Transport(failed)) = (peer_id, error) {
+                    self.on_dial_outcome(&peer_id, None, failed.iter().map(|(addr, _)| addr));
+                }
                 self.on_dial_failure(peer_id);
             }
             FromSwarm::ExternalAddrConfirmed(_) => {
@@ -232,6 +246,35 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
 }
 
 impl<TSpec: EthSpec> PeerManager<TSpec> {
+    /// Counts the outcome of each address dialed for a peer per transport, noting when the peer
+    /// was only reachable via a fallback transport.
+    fn on_dial_outcome<'a>(
+        &self,
+        peer_id: &PeerId,
+        succeeded: Option<&Multiaddr>,
+        failed: impl Iterator<Item = &'a Multiaddr>,
+    ) {
+        let mut failed_transports = HashSet::new();
+        for transport in failed.filter_map(Transport::from_multiaddr) {
+            metrics::inc_counter_vec(
+                &metrics::PEER_DIAL_FAILURE_PER_TRANSPORT,
+                &[transport.as_ref()],
+            );
+            failed_transports.insert(transport);
+        }
+
+        let Some(transport) = succeeded.and_then(Transport::from_multiaddr) else {
+            return;
+        };
+        metrics::inc_counter_vec(
+            &metrics::PEER_DIAL_SUCCESS_PER_TRANSPORT,
+            &[transport.as_ref()],
+        );
+        if failed_transports.iter().any(|failed| *failed != transport) {
+            debug!(self.log, "Peer only reachable via fallback transport"; "peer_id" => %peer_id, "transport" => transport.as_ref(), "transport_preference" => %self.transport_preference);
+        }
+    }
+
     fn on_connection_established(
         &mut self,
         peer_id: PeerId,
@@ -302,6 +345,9 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
                     .push(PeerManagerEvent::PeerConnectedIncoming(peer_id));
             }
             ConnectedPoint::Dialer { address, .. } => {
+                if self.warm_start_peers.remove(&peer_id) {
+                    metrics::inc_counter(&metrics::WARM_START_DIALS_SUCCEEDED);
+                }
                 self.inject_connect_outgoing(&peer_id, address.clone(), None);
                 self.events
                     .push(PeerManagerEvent::PeerConnectedOutgoing(peer_id));
@@ -370,6 +416,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     /// the peer is not already connected.
     fn on_dial_failure(&mut self, peer_id: Option<PeerId>) {
         if let Some(peer_id) = peer_id {
+            self.warm_start_peers.remove(&peer_id);
             if !self.network_globals.peers.read().is_connected(&peer_id) {
                 self.inject_disconnect(&peer_id);
             }
@@ -1,20 +1,24 @@
 use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, Gossipsub, PeerId};
+use peer_cache::{CachedPeer, PersistedPeerCache, MAX_CACHED_PEERS, PEER_CACHE_MAX_AGE};
 use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use persisted::{PersistedPeer, PersistedPeerDB};
 use rand::seq::SliceRandom;
-use score::{PeerAction, ReportSource, Score, ScoreState};
+use score::{PeerAction, ReportSource, Score, ScoreState, DEFAULT_SCORE};
 use slog::{crit, debug, error, trace, warn};
 use std::net::IpAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::{cmp::Ordering, fmt::Display};
 use std::{
     collections::{HashMap, HashSet},
     fmt::Formatter,
 };
 use sync_status::SyncStatus;
-use types::EthSpec;
+use types::{EthSpec, Slot};
 
 pub mod client;
+pub mod peer_cache;
 pub mod peer_info;
+pub mod persisted;
 pub mod score;
 pub mod sync_status;
 
@@ -218,6 +222,13 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map(|(peer_id, _)| peer_id)
     }
 
+    /// Returns `false` if the peer has advertised that it can't serve the block at `slot`.
+    pub fn may_have_block_at(&self, peer_id: &PeerId, slot: Slot) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(true, |info| info.sync_status().may_have_block_at(slot))
+    }
+
     /// Gives the `peer_id` of all known connected and advanced peers.
     pub fn advanced_peers(&self) -> impl Iterator<Item = &PeerId> {
         self.peers
@@ -608,6 +619,133 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         }
     }
 
+    /// Returns a snapshot of the reputation of all peers which have a below-default score or
+    /// are banned, to be persisted across restarts.
+    // VISIBILITY: The peer manager persists the peerdb.
+    pub(crate) fn persisted_peers(&self) -> PersistedPeerDB {
+        let peers = self
+            .peers
+            .iter()
+            .filter_map(|(peer_id, info)| {
+                let (lighthouse_score, ban_remaining) = info.score().to_persisted()?;
+                if lighthouse_score >= DEFAULT_SCORE && ban_remaining.is_zero() {
+                    return None;
+                }
+                Some(PersistedPeer {
+                    peer_id: peer_id.to_base58(),
+                    lighthouse_score,
+                    ban_remaining_secs: ban_remaining.as_secs(),
+                    ip_addresses: info.seen_ip_addresses().collect(),
+                })
+            })
+            .collect();
+        PersistedPeerDB::new(peers)
+    }
+
+    /// Restores the reputation of peers from a persisted snapshot, aging their scores by the time
+    /// elapsed since the snapshot was taken.
+    ///
+    /// Peers which are already known (e.g. trusted peers) are left untouched.
+    ///
+    /// Returns the restored peers which are still banned, with their banned IP addresses, so that
+    /// they can be banned at the swarm level.
+    // VISIBILITY: The peer manager restores the peerdb on startup.
+    pub(crate) fn restore_persisted_peers(
+        &mut self,
+        persisted: PersistedPeerDB,
+    ) -> Vec<(PeerId, Vec<IpAddr>)> {
+        if self.disable_peer_scoring {
+            return vec![];
+        }
+
+        let downtime = persisted.downtime();
+        let now = Instant::now();
+        let num_peers = persisted.peers.len();
+        let mut banned_peers = vec![];
+        for peer in persisted.peers {
+            let Ok(peer_id) = peer.peer_id.parse::<PeerId>() else {
+                warn!(self.log, "Invalid persisted peer id"; "peer_id" => peer.peer_id);
+                continue;
+            };
+            if self.peers.contains_key(&peer_id) {
+                continue;
+            }
+
+            let score = Score::from_persisted(
+                peer.lighthouse_score,
+                Duration::from_secs(peer.ban_remaining_secs),
+                downtime,
+            );
+            let connection_status = if score.state() == ScoreState::Banned {
+                self.banned_peers_count
+                    .add_banned_peer(peer.ip_addresses.iter().copied());
+                banned_peers.push((peer_id, peer.ip_addresses.clone()));
+                PeerConnectionStatus::Banned { since: now }
+            } else {
+                self.disconnected_peers += 1;
+                PeerConnectionStatus::Disconnected { since: now }
+            };
+
+            self.peers.insert(
+                peer_id,
+                PeerInfo::from_persisted(score, &peer.ip_addresses, connection_status),
+            );
+        }
+
+        debug!(self.log, "Restored persisted peer scores"; "num_peers" => num_peers, "num_banned" => banned_peers.len(), "downtime_secs" => downtime.as_secs());
+
+        // Nothing has been banned in libp2p yet, so there is nothing to unban.
+        let _ = self.shrink_to_fit();
+
+        let known_banned_ips = self.banned_peers_count.banned_ips();
+        banned_peers
+            .into_iter()
+            .map(|(peer_id, ip_addresses)| {
+                let banned_ips = ip_addresses
+                    .into_iter()
+                    .filter(|ip| known_banned_ips.contains(ip))
+                    .collect();
+                (peer_id, banned_ips)
+            })
+            .collect()
+    }
+
+    /// Returns the ENRs of the healthy peers we have been connected to within
+    /// `PEER_CACHE_MAX_AGE`, most recently connected first, to be dialed after a restart.
+    // VISIBILITY: The peer manager persists the peer cache.
+    pub(crate) fn cached_peers(&self) -> PersistedPeerCache {
+        let now = peer_cache::unix_now();
+        let mut peers = self
+            .peers
+            .values()
+            .filter(|info| !info.is_trusted() && info.score().state() == ScoreState::Healthy)
+            .filter_map(|info| {
+                let enr = info.enr()?;
+                let since_connected = match info.connection_status() {
+                    PeerConnectionStatus::Connected { .. } => Duration::ZERO,
+                    // A peer is only disconnected with a connection direction if it was connected
+                    // this session.
+                    PeerConnectionStatus::Disconnected { since }
+                        if info.connection_direction().is_some() =>
+                    {
+                        since.elapsed()
+                    }
+                    _ => return None,
+                };
+                if since_connected > PEER_CACHE_MAX_AGE {
+                    return None;
+                }
+                Some(CachedPeer {
+                    enr: enr.to_base64(),
+                    last_connected: now.saturating_sub(since_connected).as_secs(),
+                })
+            })
+            .collect::<Vec<_>>();
+        peers.sort_unstable_by(|a, b| b.last_connected.cmp(&a.last_connected));
+        peers.truncate(MAX_CACHED_PEERS);
+        PersistedPeerCache::new(peers)
+    }
+
     /// Extends the ttl of all peers on the given subnet that have a shorter
     /// min_ttl than what's given.
     // VISIBILITY: The behaviour is able to adjust subscriptions.
@@ -2024,4 +2162,140 @@ mod tests {
             Score::max_score().score()
         );
     }
+
+    #[test]
+    fn test_persisted_ban_survives_restart() {
+        let mut pdb = get_db();
+        let banned_peer = PeerId::random();
+        let healthy_peer = PeerId::random();
+        let ip: IpAddr = Ipv4Addr::new(10, 0, 0, 1).into();
+
+        pdb.connect_ingoing(&banned_peer, "/ip4/10.0.0.1".parse().unwrap(), None);
+        pdb.connect_ingoing(&healthy_peer, "/ip4/10.0.0.2".parse().unwrap(), None);
+        let _ = pdb.report_peer(
+            &banned_peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            "",
+        );
+        pdb.inject_disconnect(&banned_peer);
+        assert!(matches!(
+            pdb.ban_status(&banned_peer),
+            Some(BanResult::BadScore)
+        ));
+
+        // Only peers with a reputation worth remembering are persisted.
+        let persisted = pdb.persisted_peers();
+        assert_eq!(persisted.peers.len(), 1);
+        assert_eq!(persisted.peers[0].ip_addresses, vec![ip]);
+
+        let bytes = serde_json::to_vec(&persisted).unwrap();
+        let mut restored = get_db();
+        let banned_peers =
+            restored.restore_persisted_peers(serde_json::from_slice(&bytes).unwrap());
+        assert_eq!(banned_peers, vec![(banned_peer, vec![])]);
+
+        // The peer is still banned, with (almost) the full ban remaining.
+        assert!(matches!(
+            restored.ban_status(&banned_peer),
+            Some(BanResult::BadScore)
+        ));
+        assert!(restored.peer_info(&banned_peer).unwrap().is_banned());
+        assert_eq!(restored.banned_peers_count.banned_peers(), 1);
+        assert!(restored.peer_info(&healthy_peer).is_none());
+        let (_, ban_remaining) = restored
+            .peer_info(&banned_peer)
+            .unwrap()
+            .score()
+            .to_persisted()
+            .unwrap();
+        assert!(ban_remaining > Duration::from_secs(12 * 3600 - 60));
+
+        // The restored ban is persisted again.
+        let persisted_again = restored.persisted_peers();
+        assert_eq!(persisted_again.peers.len(), 1);
+        assert_eq!(persisted_again.peers[0].peer_id, persisted.peers[0].peer_id);
+    }
+
+    #[test]
+    fn test_persisted_ban_expires_over_downtime() {
+        let mut pdb = get_db();
+        let peer = PeerId::random();
+
+        pdb.connect_ingoing(&peer, "/ip4/10.0.0.1".parse().unwrap(), None);
+        let _ = pdb.report_peer(&peer, PeerAction::Fatal, ReportSource::PeerManager, "");
+        pdb.inject_disconnect(&peer);
+
+        // Simulate the node being offline for longer than the ban.
+        let mut persisted = pdb.persisted_peers();
+        persisted.saved_at -= 13 * 3600;
+
+        let mut restored = get_db();
+        assert!(restored.restore_persisted_peers(persisted).is_empty());
+
+        assert!(restored.ban_status(&peer).is_none());
+        assert!(restored.peer_info(&peer).unwrap().is_disconnected());
+        assert_eq!(restored.banned_peers_count.banned_peers(), 0);
+        assert_eq!(restored.disconnected_peers, 1);
+    }
+
+    #[test]
+    fn test_persisted_peer_db_on_disk() {
+        let log = build_log(slog::Level::Debug, false);
+        let dir = tempfile::tempdir().unwrap();
+        let mut pdb = get_db();
+        let peer = PeerId::random();
+
+        pdb.connect_ingoing(&peer, "/ip4/10.0.0.1".parse().unwrap(), None);
+        let _ = pdb.report_peer(&peer, PeerAction::Fatal, ReportSource::PeerManager, "");
+        pdb.inject_disconnect(&peer);
+
+        let snapshot = pdb.persisted_peers();
+        persisted::save_to_disk(dir.path(), &snapshot, &log);
+        assert_eq!(persisted::load_from_disk(dir.path(), &log), Some(snapshot));
+
+        persisted::remove_from_disk(dir.path(), &log);
+        assert_eq!(persisted::load_from_disk(dir.path(), &log), None);
+    }
+
+    #[test]
+    fn test_cached_peers() {
+        let mut pdb = get_db();
+        let enr = |_| {
+            crate::discovery::enr::EnrBuilder::new("v4")
+                .build(&crate::discovery::CombinedKey::generate_secp256k1())
+                .unwrap()
+        };
+        let (connected, disconnected, dialed, banned, no_enr) = (
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+            PeerId::random(),
+        );
+        let enrs = (0..4).map(enr).collect::<Vec<_>>();
+        let multiaddr: Multiaddr = "/ip4/10.0.0.1".parse().unwrap();
+
+        pdb.connect_outgoing(&connected, multiaddr.clone(), Some(enrs[0].clone()));
+        pdb.connect_ingoing(&disconnected, multiaddr.clone(), Some(enrs[1].clone()));
+        pdb.inject_disconnect(&disconnected);
+        // Peers that were never connected are not cached.
+        pdb.dialing_peer(&dialed, Some(enrs[2].clone()));
+        pdb.inject_disconnect(&dialed);
+        pdb.connect_ingoing(&banned, multiaddr.clone(), Some(enrs[3].clone()));
+        let _ = pdb.report_peer(&banned, PeerAction::Fatal, ReportSource::PeerManager, "");
+        pdb.inject_disconnect(&banned);
+        pdb.connect_ingoing(&no_enr, multiaddr, None);
+
+        let cached = pdb
+            .cached_peers()
+            .peers
+            .into_iter()
+            .map(|peer| peer.enr)
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            cached,
+            HashSet::from([enrs[0].to_base64(), enrs[1].to_base64()])
+        );
+    }
 }
@@ -0,0 +1,183 @@
+//! Persistence of the ENRs of recently useful peers across restarts.
+//!
+//! On startup the cached ENRs are added to the discovery routing table and dialed straight away,
+//! so that a restarted node does not have to rediscover its peers from the boot nodes.
+
+use crate::discovery::Eth2Enr;
+use crate::Enr;
+use serde::{Deserialize, Serialize};
+use slog::{debug, warn};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The name of the file within the network directory that the peer cache is persisted to.
+pub const PEER_CACHE_FILENAME: &str = "peer_cache.json";
+/// The maximum number of peers kept in the cache.
+pub const MAX_CACHED_PEERS: usize = 100;
+/// Peers we have not been connected to within this time are not cached, nor dialed on startup.
+pub const PEER_CACHE_MAX_AGE: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// The ENRs of peers we were recently connected to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedPeerCache {
+    /// The time the snapshot was taken, in seconds since the UNIX epoch.
+    pub saved_at: u64,
+    pub peers: Vec<CachedPeer>,
+}
+
+/// A single peer in the cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPeer {
+    /// The base64 encoded ENR of the peer.
+    pub enr: String,
+    /// The last time we were connected to the peer, in seconds since the UNIX epoch.
+    pub last_connected: u64,
+}
+
+impl PersistedPeerCache {
+    pub fn new(peers: Vec<CachedPeer>) -> Self {
+        Self {
+            saved_at: unix_now().as_secs(),
+            peers,
+        }
+    }
+
+    /// Returns the ENRs of the cached peers that are worth dialing: those we were connected to
+    /// within `PEER_CACHE_MAX_AGE` and which are on the fork identified by `fork_digest`.
+    pub fn usable_enrs(&self, fork_digest: [u8; 4], log: &slog::Logger) -> Vec<Enr> {
+        let now = unix_now();
+        let mut num_stale = 0;
+        let mut num_wrong_fork = 0;
+        let enrs = self
+            .peers
+            .iter()
+            .filter_map(|peer| {
+                if now.saturating_sub(Duration::from_secs(peer.last_connected)) > PEER_CACHE_MAX_AGE
+                {
+                    num_stale += 1;
+                    return None;
+                }
+                let enr = match peer.enr.parse::<Enr>() {
+                    Ok(enr) => enr,
+                    Err(e) => {
+                        warn!(log, "Invalid ENR in peer cache"; "error" => e);
+                        return None;
+                    }
+                };
+                if enr.eth2().map(|fork_id| fork_id.fork_digest) != Ok(fork_digest) {
+                    num_wrong_fork += 1;
+                    return None;
+                }
+                Some(enr)
+            })
+            .collect::<Vec<_>>();
+
+        debug!(
+            log,
+            "Loaded peer cache";
+            "usable" => enrs.len(),
+            "stale" => num_stale,
+            "fork_digest_mismatch" => num_wrong_fork,
+        );
+        enrs
+    }
+}
+
+pub(super) fn unix_now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Writes `peer_cache` to the peer cache file in `dir`.
+pub fn save_to_disk(dir: &Path, peer_cache: &PersistedPeerCache, log: &slog::Logger) {
+    let _ = std::fs::create_dir_all(dir);
+    let result = serde_json::to_vec(peer_cache)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| {
+            File::create(dir.join(PEER_CACHE_FILENAME))
+                .and_then(|mut f| f.write_all(&bytes))
+                .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => {
+            debug!(log, "Peer cache written to disk"; "num_peers" => peer_cache.peers.len())
+        }
+        Err(e) => warn!(log, "Could not write peer cache to disk"; "error" => e),
+    }
+}
+
+/// Reads the peer cache file from `dir`, if it exists and is valid.
+pub fn load_from_disk(dir: &Path, log: &slog::Logger) -> Option<PersistedPeerCache> {
+    let file = File::open(dir.join(PEER_CACHE_FILENAME)).ok()?;
+    match serde_json::from_reader(file) {
+        Ok(peer_cache) => Some(peer_cache),
+        Err(e) => {
+            warn!(log, "Could not read peer cache"; "error" => %e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::enr::{CombinedKey, EnrBuilder, ETH2_ENR_KEY};
+    use slog::{o, Drain};
+    use ssz::Encode;
+    use types::EnrForkId;
+
+    fn build_log() -> slog::Logger {
+        let decorator = slog_term::TermDecorator::new().build();
+        let drain = slog_term::FullFormat::new(decorator).build().fuse();
+        let drain = slog_async::Async::new(drain).build().fuse();
+        slog::Logger::root(drain.filter(|_| false).fuse(), o!())
+    }
+
+    fn cached_peer(fork_digest: [u8; 4], age: Duration) -> CachedPeer {
+        let enr_fork_id = EnrForkId {
+            fork_digest,
+            ..EnrForkId::default()
+        };
+        let enr = EnrBuilder::new("v4")
+            .add_value(ETH2_ENR_KEY, &enr_fork_id.as_ssz_bytes())
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap();
+        CachedPeer {
+            enr: enr.to_base64(),
+            last_connected: (unix_now() - age).as_secs(),
+        }
+    }
+
+    #[test]
+    fn skips_stale_and_other_fork_peers() {
+        let log = build_log();
+        let fork_digest = [1, 2, 3, 4];
+        let fresh = cached_peer(fork_digest, Duration::from_secs(60));
+        let stale = cached_peer(fork_digest, PEER_CACHE_MAX_AGE + Duration::from_secs(60));
+        let other_fork = cached_peer([4, 3, 2, 1], Duration::from_secs(60));
+        let invalid = CachedPeer {
+            enr: "not an enr".into(),
+            last_connected: unix_now().as_secs(),
+        };
+
+        let peer_cache = PersistedPeerCache::new(vec![fresh.clone(), stale, other_fork, invalid]);
+        let enrs = peer_cache.usable_enrs(fork_digest, &log);
+        assert_eq!(enrs.len(), 1);
+        assert_eq!(enrs[0].to_base64(), fresh.enr);
+    }
+
+    #[test]
+    fn peer_cache_on_disk() {
+        let log = build_log();
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(load_from_disk(dir.path(), &log), None);
+
+        let peer_cache =
+            PersistedPeerCache::new(vec![cached_peer([0; 4], Duration::from_secs(60))]);
+        save_to_disk(dir.path(), &peer_cache, &log);
+        assert_eq!(load_from_disk(dir.path(), &log), Some(peer_cache));
+    }
+}
@@ -49,6 +49,9 @@ pub struct PeerInfo<T: EthSpec> {
     /// Direction of the first connection of the last (or current) connected session with this peer.
     /// None if this peer was never connected.
     connection_direction: Option<ConnectionDirection>,
+    /// The transport of the most recently established connection with this peer. None if this
+    /// peer was never connected.
+    transport: Option<Transport>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
 }
@@ -67,6 +70,7 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             min_ttl: None,
             is_trusted: false,
             connection_direction: None,
+            transport: None,
             enr: None,
         }
     }
@@ -82,6 +86,20 @@ impl<T: EthSpec> PeerInfo<T> {
         }
     }
 
+    /// Return a PeerInfo struct for a peer restored from a persisted `PeerDB`.
+    pub(super) fn from_persisted(
+        score: Score,
+        ip_addresses: &[IpAddr],
+        connection_status: PeerConnectionStatus,
+    ) -> Self {
+        PeerInfo {
+            score,
+            connection_status,
+            seen_multiaddrs: ip_addresses.iter().map(|ip| Multiaddr::from(*ip)).collect(),
+            ..Default::default()
+        }
+    }
+
     /// Returns if the peer is subscribed to a given `Subnet` from the metadata attnets/syncnets field.
     pub fn on_subnet_metadata(&self, subnet: &Subnet) -> bool {
         if let Some(meta_data) = &self.meta_data {
@@ -114,6 +132,11 @@ impl<T: EthSpec> PeerInfo<T> {
         self.connection_direction.as_ref()
     }
 
+    /// Returns the transport of the most recently established connection with this peer.
+    pub fn transport(&self) -> Option<Transport> {
+        self.transport
+    }
+
     /// Returns the sync status of the peer.
     pub fn sync_status(&self) -> &SyncStatus {
         &self.sync_status
@@ -434,6 +457,9 @@ impl<T: EthSpec> PeerInfo<T> {
             }
         }
 
+        if let Some(transport) = seen_multiaddr.as_ref().and_then(Transport::from_multiaddr) {
+            self.transport = Some(transport);
+        }
         if let Some(multiaddr) = seen_multiaddr {
             self.seen_multiaddrs.insert(multiaddr);
         }
@@ -453,6 +479,9 @@ impl<T: EthSpec> PeerInfo<T> {
                 self.connection_direction = Some(ConnectionDirection::Outgoing);
             }
         }
+        if let Some(transport) = seen_multiaddr.as_ref().and_then(Transport::from_multiaddr) {
+            self.transport = Some(transport);
+        }
         if let Some(multiaddr) = seen_multiaddr {
             self.seen_multiaddrs.insert(multiaddr);
         }
@@ -482,6 +511,26 @@ pub enum ConnectionDirection {
     Outgoing,
 }
 
+/// The transport a connection was established over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Transport {
+    Tcp,
+    Quic,
+}
+
+impl Transport {
+    /// Returns the transport of `multiaddr`, if it is a TCP or QUIC address.
+    pub fn from_multiaddr(multiaddr: &Multiaddr) -> Option<Self> {
+        multiaddr.iter().find_map(|protocol| match protocol {
+            Protocol::QuicV1 => Some(Transport::Quic),
+            Protocol::Tcp(_) => Some(Transport::Tcp),
+            _ => None,
+        })
+    }
+}
+
 /// Connection Status of the peer.
 #[derive(Debug, Clone, Default)]
 pub enum PeerConnectionStatus {
@@ -0,0 +1,96 @@
+//! Persistence of peer scores and bans across restarts.
+//!
+//! Only the reputation of peers is persisted: their lighthouse score, the remainder of any ban and
+//! the IP addresses they were seen on (which may themselves be banned). Everything else about a
+//! peer is re-learned when it next connects.
+
+use serde::{Deserialize, Serialize};
+use slog::{debug, warn};
+use std::fs::File;
+use std::io::Write;
+use std::net::IpAddr;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The name of the file within the network directory that peer scores are persisted to.
+pub const PEER_DB_FILENAME: &str = "peer_db.json";
+
+/// A snapshot of the reputations held in the `PeerDB`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedPeerDB {
+    /// The time the snapshot was taken, in seconds since the UNIX epoch.
+    pub saved_at: u64,
+    pub peers: Vec<PersistedPeer>,
+}
+
+/// The persisted reputation of a single peer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PersistedPeer {
+    /// The base58 encoded peer id.
+    pub peer_id: String,
+    pub lighthouse_score: f64,
+    /// The number of seconds remaining in the peer's ban when the snapshot was taken.
+    pub ban_remaining_secs: u64,
+    /// The IP addresses the peer was seen on.
+    pub ip_addresses: Vec<IpAddr>,
+}
+
+impl PersistedPeerDB {
+    pub fn new(peers: Vec<PersistedPeer>) -> Self {
+        Self {
+            saved_at: unix_now().as_secs(),
+            peers,
+        }
+    }
+
+    /// Returns the time elapsed since the snapshot was taken.
+    pub fn downtime(&self) -> Duration {
+        unix_now().saturating_sub(Duration::from_secs(self.saved_at))
+    }
+}
+
+fn unix_now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Writes `persisted` to the peer DB file in `dir`.
+pub fn save_to_disk(dir: &Path, persisted: &PersistedPeerDB, log: &slog::Logger) {
+    let _ = std::fs::create_dir_all(dir);
+    let result = serde_json::to_vec(persisted)
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| {
+            File::create(dir.join(PEER_DB_FILENAME))
+                .and_then(|mut f| f.write_all(&bytes))
+                .map_err(|e| e.to_string())
+        });
+    match result {
+        Ok(()) => {
+            debug!(log, "Peer scores written to disk"; "num_peers" => persisted.peers.len())
+        }
+        Err(e) => warn!(log, "Could not write peer scores to disk"; "error" => e),
+    }
+}
+
+/// Reads the peer DB file from `dir`, if it exists and is valid.
+pub fn load_from_disk(dir: &Path, log: &slog::Logger) -> Option<PersistedPeerDB> {
+    let file = File::open(dir.join(PEER_DB_FILENAME)).ok()?;
+    match serde_json::from_reader(file) {
+        Ok(persisted) => Some(persisted),
+        Err(e) => {
+            warn!(log, "Could not read persisted peer scores"; "error" => %e);
+            None
+        }
+    }
+}
+
+/// Removes the peer DB file from `dir`, if any.
+pub fn remove_from_disk(dir: &Path, log: &slog::Logger) {
+    let path = dir.join(PEER_DB_FILENAME);
+    if path.exists() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            warn!(log, "Could not remove persisted peer scores"; "error" => %e);
+        }
+    }
+}
@@ -259,6 +259,40 @@ impl RealScore {
     pub fn is_good_gossipsub_peer(&self) -> bool {
         self.gossipsub_score >= 0.0
     }
+
+    /// Returns the lighthouse score and the time remaining before it begins to decay (i.e. the
+    /// remainder of a ban).
+    fn to_persisted(&self) -> (f64, Duration) {
+        (
+            self.lighthouse_score,
+            self.last_updated.saturating_duration_since(Instant::now()),
+        )
+    }
+
+    /// Restores a score which was persisted `downtime` ago.
+    ///
+    /// The downtime first counts towards the remainder of any ban, after which the score decays
+    /// as if the node had been running.
+    fn from_persisted(lighthouse_score: f64, ban_remaining: Duration, downtime: Duration) -> Self {
+        let now = Instant::now();
+        let mut score = RealScore {
+            lighthouse_score: lighthouse_score.clamp(MIN_SCORE, MAX_SCORE),
+            last_updated: now,
+            ..Default::default()
+        };
+
+        match ban_remaining.checked_sub(downtime) {
+            Some(remaining) if !remaining.is_zero() => score.last_updated = now + remaining,
+            _ => {
+                let secs_decayed = downtime.saturating_sub(ban_remaining).as_secs();
+                score.lighthouse_score *= (*HALFLIFE_DECAY * secs_decayed as f64).exp();
+            }
+        }
+
+        // Avoid `update_state`, which would extend the ban of a peer restored as banned.
+        score.recompute_score();
+        score
+    }
 }
 
 #[derive(PartialEq, Clone, Debug, Serialize)]
@@ -309,6 +343,28 @@ impl Score {
         Self::Max
     }
 
+    /// Returns the lighthouse score and remaining ban of a peer for persistence, or `None` for
+    /// trusted peers.
+    pub(crate) fn to_persisted(&self) -> Option<(f64, Duration)> {
+        match self {
+            Self::Max => None,
+            Self::Real(score) => Some(score.to_persisted()),
+        }
+    }
+
+    /// Restores a score which was persisted `downtime` ago, see `Self::to_persisted`.
+    pub(crate) fn from_persisted(
+        lighthouse_score: f64,
+        ban_remaining: Duration,
+        downtime: Duration,
+    ) -> Self {
+        Self::Real(RealScore::from_persisted(
+            lighthouse_score,
+            ban_remaining,
+            downtime,
+        ))
+    }
+
     /// Returns the expected state of the peer given it's score.
     pub(crate) fn state(&self) -> ScoreState {
         match self.score() {
@@ -418,4 +474,22 @@ mod tests {
         assert!(!score.is_good_gossipsub_peer());
         assert_eq!(score.score(), 0.0);
     }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_persisted_score_ages_over_downtime() {
+        let hour = Duration::from_secs(3600);
+
+        // A ban which outlasts the downtime resumes with its remaining time and no decay.
+        let score = RealScore::from_persisted(MIN_SCORE, BANNED_BEFORE_DECAY, hour);
+        assert_eq!(score.score(), MIN_SCORE);
+        let (_, ban_remaining) = score.to_persisted();
+        assert!(ban_remaining <= BANNED_BEFORE_DECAY - hour);
+        assert!(ban_remaining > BANNED_BEFORE_DECAY - hour - Duration::from_secs(60));
+
+        // Downtime beyond the remainder of the ban decays the score.
+        let score = RealScore::from_persisted(MIN_SCORE, hour, hour + Duration::from_secs(600));
+        assert!((score.score() - MIN_SCORE / 2.0).abs() < 1e-9);
+        assert_eq!(score.to_persisted().1, Duration::ZERO);
+    }
 }
@@ -25,6 +25,8 @@ pub struct SyncInfo {
     pub head_root: Hash256,
     pub finalized_epoch: Epoch,
     pub finalized_root: Hash256,
+    /// The earliest slot from which the peer can serve blocks, if it has advertised it.
+    pub earliest_available_slot: Option<Slot>,
 }
 
 impl std::cmp::PartialEq for SyncStatus {
@@ -56,6 +58,21 @@ impl SyncStatus {
         matches!(self, SyncStatus::Behind { .. })
     }
 
+    /// Returns `false` if the peer has advertised that it can't serve the block at `slot`, e.g.
+    /// because it was checkpoint synced and hasn't backfilled that far.
+    pub fn may_have_block_at(&self, slot: Slot) -> bool {
+        match self {
+            SyncStatus::Synced { info }
+            | SyncStatus::Advanced { info }
+            | SyncStatus::Behind { info } => info
+                .earliest_available_slot
+                .map_or(true, |earliest_available_slot| {
+                    slot >= earliest_available_slot
+                }),
+            SyncStatus::IrrelevantPeer | SyncStatus::Unknown => true,
+        }
+    }
+
     /// Updates the peer's sync status, returning whether the status transitioned.
     ///
     /// E.g. returns `true` if the state changed from `Synced` to `Advanced`, but not if
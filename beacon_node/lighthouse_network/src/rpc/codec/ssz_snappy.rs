@@ -69,7 +69,13 @@ impl<TSpec: EthSpec> Encoder<RPCCodedResponse<TSpec>> for SSZSnappyInboundCodec<
     ) -> Result<(), Self::Error> {
         let bytes = match &item {
             RPCCodedResponse::Success(resp) => match &resp {
-                RPCResponse::Status(res) => res.as_ssz_bytes(),
+                RPCResponse::Status(res) => match self.protocol.versioned_protocol {
+                    SupportedProtocol::StatusV1 => res.status_v1().as_ssz_bytes(),
+                    SupportedProtocol::StatusV2 => res.status_v2().as_ssz_bytes(),
+                    _ => {
+                        unreachable!("We only send status responses on negotiating status requests")
+                    }
+                },
                 RPCResponse::BlocksByRange(res) => res.as_ssz_bytes(),
                 RPCResponse::BlocksByRoot(res) => res.as_ssz_bytes(),
                 RPCResponse::BlobsByRange(res) => res.as_ssz_bytes(),
@@ -215,7 +221,11 @@ impl<TSpec: EthSpec> Encoder<OutboundRequest<TSpec>> for SSZSnappyOutboundCodec<
         dst: &mut BytesMut,
     ) -> Result<(), Self::Error> {
         let bytes = match item {
-            OutboundRequest::Status(req) => req.as_ssz_bytes(),
+            OutboundRequest::Status(req) => match self.protocol.versioned_protocol {
+                SupportedProtocol::StatusV1 => req.status_v1().as_ssz_bytes(),
+                SupportedProtocol::StatusV2 => req.status_v2().as_ssz_bytes(),
+                _ => unreachable!("Status requests are only sent on status protocols"),
+            },
             OutboundRequest::Goodbye(req) => req.as_ssz_bytes(),
             OutboundRequest::BlocksByRange(r) => match r {
                 OldBlocksByRangeRequest::V1(req) => req.as_ssz_bytes(),
@@ -458,7 +468,10 @@ fn handle_rpc_request<T: EthSpec>(
 ) -> Result<Option<InboundRequest<T>>, RPCError> {
     match versioned_protocol {
         SupportedProtocol::StatusV1 => Ok(Some(InboundRequest::Status(
-            StatusMessage::from_ssz_bytes(decoded_buffer)?,
+            StatusMessageV1::from_ssz_bytes(decoded_buffer)?.into(),
+        ))),
+        SupportedProtocol::StatusV2 => Ok(Some(InboundRequest::Status(
+            StatusMessageV2::from_ssz_bytes(decoded_buffer)?.into(),
         ))),
         SupportedProtocol::GoodbyeV1 => Ok(Some(InboundRequest::Goodbye(
             GoodbyeReason::from_ssz_bytes(decoded_buffer)?,
@@ -529,7 +542,10 @@ fn handle_rpc_response<T: EthSpec>(
 ) -> Result<Option<RPCResponse<T>>, RPCError> {
     match versioned_protocol {
         SupportedProtocol::StatusV1 => Ok(Some(RPCResponse::Status(
-            StatusMessage::from_ssz_bytes(decoded_buffer)?,
+            StatusMessageV1::from_ssz_bytes(decoded_buffer)?.into(),
+        ))),
+        SupportedProtocol::StatusV2 => Ok(Some(RPCResponse::Status(
+            StatusMessageV2::from_ssz_bytes(decoded_buffer)?.into(),
         ))),
         // This case should be unreachable as `Goodbye` has no response.
         SupportedProtocol::GoodbyeV1 => Err(RPCError::InvalidData(
@@ -759,6 +775,14 @@ mod tests {
             finalized_epoch: Epoch::new(1),
             head_root: Hash256::from_low_u64_be(0),
             head_slot: Slot::new(1),
+            earliest_available_slot: None,
+        }
+    }
+
+    fn status_message_v2() -> StatusMessage {
+        StatusMessage {
+            earliest_available_slot: Some(Slot::new(64)),
+            ..status_message()
         }
     }
 
@@ -1061,6 +1085,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_then_decode_status() {
+        let chain_spec = Spec::default_spec();
+
+        assert_eq!(
+            encode_then_decode_response(
+                SupportedProtocol::StatusV2,
+                RPCCodedResponse::Success(RPCResponse::Status(status_message_v2())),
+                ForkName::Base,
+                &chain_spec,
+            ),
+            Ok(Some(RPCResponse::Status(status_message_v2())))
+        );
+
+        // The earliest available slot is dropped when responding to a V1 request.
+        assert_eq!(
+            encode_then_decode_response(
+                SupportedProtocol::StatusV1,
+                RPCCodedResponse::Success(RPCResponse::Status(status_message_v2())),
+                ForkName::Base,
+                &chain_spec,
+            ),
+            Ok(Some(RPCResponse::Status(status_message())))
+        );
+
+        // A V1 message is too short to be decoded as V2.
+        let mut v1_bytes = encode_response(
+            SupportedProtocol::StatusV1,
+            RPCCodedResponse::Success(RPCResponse::Status(status_message())),
+            ForkName::Base,
+            &chain_spec,
+        )
+        .unwrap();
+        assert!(matches!(
+            decode_response(
+                SupportedProtocol::StatusV2,
+                &mut v1_bytes,
+                ForkName::Base,
+                &chain_spec
+            )
+            .unwrap_err(),
+            RPCError::InvalidData(_)
+        ));
+    }
+
     // Test RPCResponse encoding/decoding for V1 messages
     #[test]
     fn test_encode_then_decode_v2() {
@@ -1398,6 +1467,7 @@ mod tests {
         let requests: &[OutboundRequest<Spec>] = &[
             OutboundRequest::Ping(ping_message()),
             OutboundRequest::Status(status_message()),
+            OutboundRequest::Status(status_message_v2()),
             OutboundRequest::Goodbye(GoodbyeReason::Fault),
             OutboundRequest::BlocksByRange(bbrange_request_v1()),
             OutboundRequest::BlocksByRange(bbrange_request_v2()),
@@ -1432,7 +1502,7 @@ mod tests {
         let malicious_padding: &'static [u8] = b"\xFE\x00\x00\x00";
 
         // Status message is 84 bytes uncompressed. `max_compressed_len` is 32 + 84 + 84/6 = 130.
-        let status_message_bytes = StatusMessage {
+        let status_message_bytes = StatusMessageV1 {
             fork_digest: [0; 4],
             finalized_root: Hash256::from_low_u64_be(0),
             finalized_epoch: Epoch::new(1),
@@ -1555,7 +1625,7 @@ mod tests {
         assert_eq!(stream_identifier.len(), 10);
 
         // Status message is 84 bytes uncompressed. `max_compressed_len` is 32 + 84 + 84/6 = 130.
-        let status_message_bytes = StatusMessage {
+        let status_message_bytes = StatusMessageV1 {
             fork_digest: [0; 4],
             finalized_root: Hash256::from_low_u64_be(0),
             finalized_epoch: Epoch::new(1),
@@ -71,7 +71,9 @@ impl ToString for ErrorType {
 /* Requests */
 
 /// The STATUS request/response handshake message.
-#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+///
+/// It is encoded as a `StatusMessageV1` or `StatusMessageV2` depending on the negotiated protocol.
+#[derive(Clone, Debug, PartialEq)]
 pub struct StatusMessage {
     /// The fork version of the chain we are broadcasting.
     pub fork_digest: [u8; 4],
@@ -87,6 +89,82 @@ pub struct StatusMessage {
 
     /// The slot associated with the latest block root.
     pub head_slot: Slot,
+
+    /// The earliest slot from which blocks are available.
+    ///
+    /// This is only present in messages of the `StatusV2` protocol.
+    pub earliest_available_slot: Option<Slot>,
+}
+
+/// The STATUS message of the `StatusV1` protocol.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct StatusMessageV1 {
+    pub fork_digest: [u8; 4],
+    pub finalized_root: Hash256,
+    pub finalized_epoch: Epoch,
+    pub head_root: Hash256,
+    pub head_slot: Slot,
+}
+
+/// The STATUS message of the `StatusV2` protocol, which adds the earliest available slot.
+#[derive(Encode, Decode, Clone, Debug, PartialEq)]
+pub struct StatusMessageV2 {
+    pub fork_digest: [u8; 4],
+    pub finalized_root: Hash256,
+    pub finalized_epoch: Epoch,
+    pub head_root: Hash256,
+    pub head_slot: Slot,
+    pub earliest_available_slot: Slot,
+}
+
+impl StatusMessage {
+    pub fn status_v1(&self) -> StatusMessageV1 {
+        StatusMessageV1 {
+            fork_digest: self.fork_digest,
+            finalized_root: self.finalized_root,
+            finalized_epoch: self.finalized_epoch,
+            head_root: self.head_root,
+            head_slot: self.head_slot,
+        }
+    }
+
+    /// A message without an earliest available slot claims that all blocks are available.
+    pub fn status_v2(&self) -> StatusMessageV2 {
+        StatusMessageV2 {
+            fork_digest: self.fork_digest,
+            finalized_root: self.finalized_root,
+            finalized_epoch: self.finalized_epoch,
+            head_root: self.head_root,
+            head_slot: self.head_slot,
+            earliest_available_slot: self.earliest_available_slot.unwrap_or(Slot::new(0)),
+        }
+    }
+}
+
+impl From<StatusMessageV1> for StatusMessage {
+    fn from(status: StatusMessageV1) -> Self {
+        Self {
+            fork_digest: status.fork_digest,
+            finalized_root: status.finalized_root,
+            finalized_epoch: status.finalized_epoch,
+            head_root: status.head_root,
+            head_slot: status.head_slot,
+            earliest_available_slot: None,
+        }
+    }
+}
+
+impl From<StatusMessageV2> for StatusMessage {
+    fn from(status: StatusMessageV2) -> Self {
+        Self {
+            fork_digest: status.fork_digest,
+            finalized_root: status.finalized_root,
+            finalized_epoch: status.finalized_epoch,
+            head_root: status.head_root,
+            head_slot: status.head_slot,
+            earliest_available_slot: Some(status.earliest_available_slot),
+        }
+    }
 }
 
 /// The PING request/response message.
@@ -548,7 +626,7 @@ impl std::fmt::Display for RPCResponseErrorCode {
 
 impl std::fmt::Display for StatusMessage {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Status Message: Fork Digest: {:?}, Finalized Root: {}, Finalized Epoch: {}, Head Root: {}, Head Slot: {}", self.fork_digest, self.finalized_root, self.finalized_epoch, self.head_root, self.head_slot)
+        write!(f, "Status Message: Fork Digest: {:?}, Finalized Root: {}, Finalized Epoch: {}, Head Root: {}, Head Slot: {}, Earliest Available Slot: {:?}", self.fork_digest, self.finalized_root, self.finalized_epoch, self.head_root, self.head_slot, self.earliest_available_slot)
     }
 }
 
@@ -658,6 +736,14 @@ impl slog::KV for StatusMessage {
         serializer.emit_arguments("finalized_root", &format_args!("{}", self.finalized_root))?;
         Value::serialize(&self.head_slot, record, "head_slot", serializer)?;
         serializer.emit_arguments("head_root", &format_args!("{}", self.head_root))?;
+        if let Some(earliest_available_slot) = self.earliest_available_slot {
+            Value::serialize(
+                &earliest_available_slot,
+                record,
+                "earliest_available_slot",
+                serializer,
+            )?;
+        }
         slog::Result::Ok(())
     }
 }
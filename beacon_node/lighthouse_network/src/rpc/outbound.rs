@@ -56,10 +56,10 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
     pub fn supported_protocols(&self) -> Vec<ProtocolId> {
         match self {
             // add more protocols when versions/encodings are supported
-            OutboundRequest::Status(_) => vec![ProtocolId::new(
-                SupportedProtocol::StatusV1,
-                Encoding::SSZSnappy,
-            )],
+            OutboundRequest::Status(_) => vec![
+                ProtocolId::new(SupportedProtocol::StatusV2, Encoding::SSZSnappy),
+                ProtocolId::new(SupportedProtocol::StatusV1, Encoding::SSZSnappy),
+            ],
             OutboundRequest::Goodbye(_) => vec![ProtocolId::new(
                 SupportedProtocol::GoodbyeV1,
                 Encoding::SSZSnappy,
@@ -109,7 +109,10 @@ impl<TSpec: EthSpec> OutboundRequest<TSpec> {
     /// Gives the corresponding `SupportedProtocol` to this request.
     pub fn versioned_protocol(&self) -> SupportedProtocol {
         match self {
-            OutboundRequest::Status(_) => SupportedProtocol::StatusV1,
+            OutboundRequest::Status(status) => match status.earliest_available_slot {
+                Some(_) => SupportedProtocol::StatusV2,
+                None => SupportedProtocol::StatusV1,
+            },
             OutboundRequest::Goodbye(_) => SupportedProtocol::GoodbyeV1,
             OutboundRequest::BlocksByRange(req) => match req {
                 OldBlocksByRangeRequest::V1(_) => SupportedProtocol::BlocksByRangeV1,
@@ -2,7 +2,8 @@ use self::behaviour::Behaviour;
 use self::gossip_cache::GossipCache;
 use crate::config::{gossipsub_config, GossipsubConfigParams, NetworkLoad};
 use crate::discovery::{
-    subnet_predicate, DiscoveredPeers, Discovery, FIND_NODE_QUERY_CLOSEST_PEERS,
+    subnet_predicate, DiscoveredPeers, Discovery, DiscoveryInfo, EnrUpdate,
+    FIND_NODE_QUERY_CLOSEST_PEERS,
 };
 use crate::metrics::AggregatedBandwidthSinks;
 use crate::peer_manager::{
@@ -168,6 +169,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                     .map(|x| PeerId::from(x.clone()))
                     .collect(),
                 config.disable_peer_scoring,
+                Arc::new(config.clone()),
                 &log,
             );
             Arc::new(globals)
@@ -262,7 +264,10 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 .gossipsub_registry
                 .map(|registry| (registry, Default::default()));
 
-            let snappy_transform = SnappyTransform::new(config.gs_config.max_transmit_size());
+            let snappy_transform = SnappyTransform::new::<TSpec>(
+                config.gs_config.max_transmit_size(),
+                ctx.fork_context.clone(),
+            );
             let mut gossipsub = Gossipsub::new_with_subscription_filter_and_transform(
                 MessageAuthenticity::Anonymous,
                 config.gs_config.clone(),
@@ -293,7 +298,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             network_params,
         );
 
-        let discovery = {
+        let mut discovery = {
             // Build and start the discovery sub-behaviour
             let mut discovery = Discovery::new(
                 local_keypair.clone(),
@@ -323,16 +328,25 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             identify::Behaviour::new(identify_config)
         };
 
-        let peer_manager = {
+        let mut peer_manager = {
             let peer_manager_cfg = PeerManagerCfg {
                 discovery_enabled: !config.disable_discovery,
                 metrics_enabled: config.metrics_enabled,
                 target_peer_count: config.target_peers,
+                transport_preference: config.dial_transport_preference(),
+                network_dir: Some(config.network_dir.clone()),
+                purge_peer_scores: config.purge_peer_scores,
                 ..Default::default()
             };
             PeerManager::new(peer_manager_cfg, network_globals.clone(), &log)?
         };
 
+        // Dial the peers we were recently connected to and seed the routing table with them, so
+        // that we don't depend solely on the boot nodes after a restart.
+        for enr in peer_manager.warm_start() {
+            discovery.add_enr(enr);
+        }
+
         let connection_limits = {
             let limits = libp2p::connection_limits::ConnectionLimits::default()
                 .with_max_pending_incoming(Some(5))
@@ -371,6 +385,12 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 build_transport(local_keypair.clone(), !config.disable_quic_support)
                     .map_err(|e| format!("Failed to build transport: {:?}", e))?;
 
+            #[cfg(feature = "testing")]
+            let transport = match &config.link_shaping {
+                Some(link_shaping) => link_shaping.wrap_transport(transport, local_peer_id),
+                None => transport,
+            };
+
             // use the executor for libp2p
             struct Executor(task_executor::TaskExecutor);
             impl libp2p::swarm::Executor for Executor {
@@ -473,9 +493,10 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         let mut boot_nodes = config.boot_nodes_enr.clone();
         boot_nodes.dedup();
 
+        let transport_preference = config.dial_transport_preference();
         for bootnode_enr in boot_nodes {
             // If QUIC is enabled, attempt QUIC connections first
-            if !config.disable_quic_support {
+            if transport_preference.allows_quic() {
                 for quic_multiaddr in &bootnode_enr.multiaddr_quic() {
                     if !self
                         .network_globals
@@ -488,6 +509,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 }
             }
 
+            if !transport_preference.allows_tcp() {
+                continue;
+            }
             for multiaddr in &bootnode_enr.multiaddr() {
                 // ignore udp multiaddr if it exists
                 let components = multiaddr.iter().collect::<Vec<_>>();
@@ -762,6 +786,10 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         message_id: MessageId,
         validation_result: MessageAcceptance,
     ) {
+        self.network_globals
+            .gossip_rejections
+            .remove_pending(&message_id);
+
         if let Some(result) = match validation_result {
             MessageAcceptance::Accept => None,
             MessageAcceptance::Ignore => Some("ignore"),
@@ -900,6 +928,32 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         self.discovery_mut().add_enr(enr);
     }
 
+    /// Returns a snapshot of the discovery routing table and query statistics.
+    pub fn discovery_info(&self) -> DiscoveryInfo {
+        self.discovery().info()
+    }
+
+    /// Applies a manual update to the address and ports of the local ENR, and advertises the
+    /// updated TCP and QUIC addresses to peers via identify.
+    pub fn update_local_enr(&mut self, update: &EnrUpdate) -> Result<Enr, String> {
+        update.validate(&self.network_globals.config)?;
+        let old_enr = self.local_enr();
+        let enr = self.discovery_mut().update_local_enr(update)?;
+
+        for multiaddr in old_enr
+            .multiaddr_tcp()
+            .into_iter()
+            .chain(old_enr.multiaddr_quic())
+        {
+            self.swarm.remove_external_address(&multiaddr);
+        }
+        for multiaddr in enr.multiaddr_tcp().into_iter().chain(enr.multiaddr_quic()) {
+            self.swarm.add_external_address(multiaddr);
+        }
+
+        Ok(enr)
+    }
+
     /// Updates a subnet value to the ENR attnets/syncnets bitfield.
     ///
     /// The `value` is `true` if a subnet is being added and false otherwise.
@@ -1160,6 +1214,18 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                         }
                     }
                     Ok(msg) => {
+                        metrics::register_gossip_message_bytes(&msg, gs_msg.data.len());
+                        if matches!(
+                            msg,
+                            PubsubMessage::Attestation(_)
+                                | PubsubMessage::AggregateAndProofAttestation(_)
+                        ) {
+                            self.network_globals.gossip_rejections.track_pending(
+                                &id,
+                                &gs_msg.topic,
+                                &gs_msg.data,
+                            );
+                        }
                         // Notify the network
                         return Some(NetworkEvent::PubsubMessage {
                             id,
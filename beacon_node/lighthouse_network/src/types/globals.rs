@@ -1,12 +1,13 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
 use crate::rpc::{MetaData, MetaDataV2};
-use crate::types::{BackFillState, SyncState};
+use crate::types::{BackFillState, GossipRejectionBuffer, SyncState, UPnPStatus};
 use crate::Client;
 use crate::EnrExt;
-use crate::{Enr, GossipTopic, Multiaddr, PeerId};
+use crate::{Enr, GossipTopic, Multiaddr, NetworkConfig, PeerId};
 use parking_lot::RwLock;
 use std::collections::HashSet;
+use std::sync::Arc;
 use types::EthSpec;
 
 pub struct NetworkGlobals<TSpec: EthSpec> {
@@ -26,6 +27,12 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// The state of the UPnP port mappings, or `None` if UPnP is not in use.
+    pub upnp_status: RwLock<Option<UPnPStatus>>,
+    /// The most recently rejected gossip messages, if enabled.
+    pub gossip_rejections: GossipRejectionBuffer,
+    /// The network configuration the node was started with.
+    pub config: Arc<NetworkConfig>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -34,6 +41,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         local_metadata: MetaData<TSpec>,
         trusted_peers: Vec<PeerId>,
         disable_peer_scoring: bool,
+        config: Arc<NetworkConfig>,
         log: &slog::Logger,
     ) -> Self {
         NetworkGlobals {
@@ -45,6 +53,9 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            upnp_status: RwLock::new(None),
+            gossip_rejections: GossipRejectionBuffer::new(config.gossip_rejection_buffer_size),
+            config,
         }
     }
 
@@ -64,6 +75,11 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.listen_multiaddrs.read().clone()
     }
 
+    /// Returns the state of the UPnP port mappings, if UPnP is in use.
+    pub fn upnp_status(&self) -> Option<UPnPStatus> {
+        self.upnp_status.read().clone()
+    }
+
     /// Returns the number of libp2p connected peers.
     pub fn connected_peers(&self) -> usize {
         self.peers.read().connected_peer_ids().count()
@@ -128,6 +144,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             }),
             trusted_peers,
             false,
+            Arc::new(NetworkConfig::default()),
             log,
         )
     }
@@ -0,0 +1,195 @@
+//! A record of the gossip messages most recently rejected by this node, so that rejections
+//! reported by peers can be reproduced.
+//!
+//! Only attestations and aggregate attestations are recorded. They are the messages for which
+//! verification can be replayed without side effects (see `network::replay_gossip_message`);
+//! verifying other messages imports them or marks them as observed, so a replay could not
+//! reproduce the original rejection.
+use crate::PeerId;
+use libp2p::gossipsub::{MessageId, TopicHash};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The maximum number of messages awaiting verification for which the raw bytes are retained.
+///
+/// Messages are removed once their validation result is reported, so this only needs to cover the
+/// messages queued for verification. If it overflows, rejections of the evicted messages are not
+/// recorded.
+const MAX_PENDING_MESSAGES: usize = 16_384;
+
+/// A gossip message which was rejected during verification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GossipRejection {
+    /// The topic the message was received on.
+    pub topic: String,
+    /// The peer which sent the message.
+    pub peer_id: PeerId,
+    /// The bytes of the message exactly as received, after snappy decompression.
+    pub data: Vec<u8>,
+    /// The verification error which caused the rejection.
+    pub reason: String,
+    /// The time since the UNIX epoch at which the message was received.
+    pub seen_timestamp: Duration,
+}
+
+/// A message received via gossip which is awaiting verification.
+#[derive(Debug)]
+struct PendingMessage {
+    topic: TopicHash,
+    data: Vec<u8>,
+}
+
+/// A ring buffer holding the last `capacity` gossip rejections.
+///
+/// A capacity of zero disables recording.
+#[derive(Debug)]
+pub struct GossipRejectionBuffer {
+    capacity: usize,
+    /// The raw bytes of messages awaiting verification, so that the exact message can be recorded
+    /// if it's rejected.
+    pending: Mutex<LruCache<MessageId, PendingMessage>>,
+    rejections: Mutex<VecDeque<GossipRejection>>,
+}
+
+impl GossipRejectionBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let max_pending = if capacity > 0 {
+            MAX_PENDING_MESSAGES
+        } else {
+            0
+        };
+        Self {
+            capacity,
+            pending: Mutex::new(LruCache::new(max_pending)),
+            rejections: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Retains the raw `data` of a message received on `topic` until its validation result is
+    /// reported, so that it can be recorded if it's rejected.
+    pub fn track_pending(&self, message_id: &MessageId, topic: &TopicHash, data: &[u8]) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.pending.lock().put(
+            message_id.clone(),
+            PendingMessage {
+                topic: topic.clone(),
+                data: data.to_vec(),
+            },
+        );
+    }
+
+    /// Discards the raw bytes of a message once its validation result has been reported.
+    pub fn remove_pending(&self, message_id: &MessageId) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.pending.lock().pop(message_id);
+    }
+
+    /// Records the rejection of the pending message with `message_id`, evicting the oldest
+    /// rejection if the buffer is full.
+    ///
+    /// Nothing is recorded if the message isn't pending, e.g. because it was evicted before it
+    /// was verified.
+    pub fn record(
+        &self,
+        message_id: &MessageId,
+        peer_id: PeerId,
+        reason: String,
+        seen_timestamp: Duration,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let Some(PendingMessage { topic, data }) = self.pending.lock().pop(message_id) else {
+            return;
+        };
+
+        let mut rejections = self.rejections.lock();
+        if rejections.len() >= self.capacity {
+            rejections.pop_front();
+        }
+        rejections.push_back(GossipRejection {
+            topic: topic.into_string(),
+            peer_id,
+            data,
+            reason,
+            seen_timestamp,
+        });
+    }
+
+    /// Returns the recorded rejections, oldest first.
+    pub fn rejections(&self) -> Vec<GossipRejection> {
+        self.rejections.lock().iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reject(buffer: &GossipRejectionBuffer, reason: &str) {
+        let message_id = MessageId::new(reason.as_bytes());
+        let topic = TopicHash::from_raw("/eth2/00000000/beacon_attestation_0/ssz_snappy");
+        buffer.track_pending(&message_id, &topic, reason.as_bytes());
+        buffer.record(
+            &message_id,
+            PeerId::random(),
+            reason.to_string(),
+            Duration::from_secs(0),
+        );
+    }
+
+    #[test]
+    fn evicts_oldest() {
+        let buffer = GossipRejectionBuffer::new(2);
+        reject(&buffer, "a");
+        reject(&buffer, "b");
+        reject(&buffer, "c");
+
+        let reasons = buffer
+            .rejections()
+            .into_iter()
+            .map(|rejection| rejection.reason)
+            .collect::<Vec<_>>();
+        assert_eq!(reasons, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn records_raw_bytes() {
+        let buffer = GossipRejectionBuffer::new(2);
+        reject(&buffer, "a");
+        assert_eq!(buffer.rejections()[0].data, b"a");
+
+        // Messages are no longer recorded once their validation result has been reported.
+        let message_id = MessageId::new(b"b");
+        let topic = TopicHash::from_raw("/eth2/00000000/beacon_attestation_0/ssz_snappy");
+        buffer.track_pending(&message_id, &topic, b"b");
+        buffer.remove_pending(&message_id);
+        buffer.record(
+            &message_id,
+            PeerId::random(),
+            "b".to_string(),
+            Duration::from_secs(0),
+        );
+        assert_eq!(buffer.rejections().len(), 1);
+    }
+
+    #[test]
+    fn disabled() {
+        let buffer = GossipRejectionBuffer::new(0);
+        reject(&buffer, "a");
+        assert!(buffer.rejections().is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 pub mod error;
 mod globals;
+mod gossip_rejections;
 mod pubsub;
 mod subnet;
 mod sync_state;
 mod topics;
+mod upnp;
 
 use types::{BitVector, EthSpec};
 
@@ -13,11 +15,13 @@ pub type EnrSyncCommitteeBitfield<T> = BitVector<<T as EthSpec>::SyncCommitteeSu
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
+pub use gossip_rejections::{GossipRejection, GossipRejectionBuffer};
 pub use pubsub::{PubsubMessage, SnappyTransform};
 pub use subnet::{Subnet, SubnetDiscovery};
 pub use sync_state::{BackFillState, SyncState};
 pub use topics::{
-    core_topics_to_subscribe, fork_core_topics, subnet_from_topic_hash, GossipEncoding, GossipKind,
-    GossipTopic, ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS,
-    LIGHT_CLIENT_GOSSIP_TOPICS,
+    core_topics_to_subscribe, fork_core_topics, fork_gossip_topics, subnet_from_topic_hash,
+    GossipEncoding, GossipKind, GossipTopic, ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS,
+    CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS, LIGHT_CLIENT_GOSSIP_TOPICS,
 };
+pub use upnp::UPnPStatus;
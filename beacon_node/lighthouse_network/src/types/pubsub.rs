@@ -1,19 +1,23 @@
 //! Handles the encoding and decoding of pubsub messages.
 
+use crate::metrics;
+use crate::rpc::rpc_block_limits_by_fork;
 use crate::types::{GossipEncoding, GossipKind, GossipTopic};
 use crate::TopicHash;
 use libp2p::gossipsub;
-use snap::raw::{decompress_len, Decoder, Encoder};
-use ssz::{Decode, Encode};
+use snap::raw::{decompress_len, max_compress_len, Decoder, Encoder};
+use ssz::{Decode, Encode, BYTES_PER_LENGTH_OFFSET};
 use std::boxed::Box;
 use std::io::{Error, ErrorKind};
 use std::sync::Arc;
 use types::{
-    Attestation, AttesterSlashing, EthSpec, ForkContext, ForkName, LightClientFinalityUpdate,
-    LightClientOptimisticUpdate, ProposerSlashing, SignedAggregateAndProof, SignedBeaconBlock,
+    Attestation, AttesterSlashing, BlobSidecar, Eth1Data, EthSpec, ForkContext, ForkName, Graffiti,
+    Hash256, KzgCommitment, LightClientFinalityUpdate, LightClientOptimisticUpdate,
+    ProposerSlashing, Signature, SignedAggregateAndProof, SignedBeaconBlock,
     SignedBeaconBlockAltair, SignedBeaconBlockBase, SignedBeaconBlockCapella,
     SignedBeaconBlockDeneb, SignedBeaconBlockMerge, SignedBlobSidecar, SignedBlsToExecutionChange,
-    SignedContributionAndProof, SignedVoluntaryExit, SubnetId, SyncCommitteeMessage, SyncSubnetId,
+    SignedContributionAndProof, SignedVoluntaryExit, Slot, SubnetId, SyncCommitteeMessage,
+    SyncSubnetId,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,14 +52,36 @@ pub enum PubsubMessage<T: EthSpec> {
 pub struct SnappyTransform {
     /// Sets the maximum size we allow gossipsub messages to decompress to.
     max_size_per_message: usize,
+    /// Used to determine the fork of a message from its topic.
+    fork_context: Arc<ForkContext>,
+    /// Returns the maximum uncompressed size of a message for a topic kind and fork.
+    max_size_for_kind: fn(&GossipKind, ForkName) -> usize,
 }
 
 impl SnappyTransform {
-    pub fn new(max_size_per_message: usize) -> Self {
+    pub fn new<T: EthSpec>(max_size_per_message: usize, fork_context: Arc<ForkContext>) -> Self {
         SnappyTransform {
             max_size_per_message,
+            fork_context,
+            max_size_for_kind: max_gossip_message_size::<T>,
         }
     }
+
+    /// Returns the maximum size that a message on `topic` may decompress to.
+    ///
+    /// Messages on unknown topics or forks are only bounded by `max_size_per_message`, they will
+    /// be rejected when decoded.
+    fn max_size_for_topic(&self, topic: &TopicHash) -> usize {
+        GossipTopic::decode(topic.as_str())
+            .ok()
+            .and_then(|topic| {
+                let fork_name = self.fork_context.from_context_bytes(topic.fork_digest)?;
+                Some((self.max_size_for_kind)(topic.kind(), *fork_name))
+            })
+            .map_or(self.max_size_per_message, |max_size| {
+                std::cmp::min(max_size, self.max_size_per_message)
+            })
+    }
 }
 
 impl gossipsub::DataTransform for SnappyTransform {
@@ -64,12 +90,22 @@ impl gossipsub::DataTransform for SnappyTransform {
         &self,
         raw_message: gossipsub::RawMessage,
     ) -> Result<gossipsub::Message, std::io::Error> {
+        let max_size = self.max_size_for_topic(&raw_message.topic);
+
+        // Reject messages that can't possibly decompress to a valid size before decompressing.
+        if raw_message.data.len() > max_compress_len(max_size) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "ssz_snappy encoded data > max compressed size for topic",
+            ));
+        }
+
         // check the length of the raw bytes
         let len = decompress_len(&raw_message.data)?;
-        if len > self.max_size_per_message {
+        if len > max_size {
             return Err(Error::new(
                 ErrorKind::InvalidData,
-                "ssz_snappy decoded data > GOSSIP_MAX_SIZE",
+                "ssz_snappy decoded data > max size for topic",
             ));
         }
 
@@ -104,6 +140,120 @@ impl gossipsub::DataTransform for SnappyTransform {
     }
 }
 
+/// Returns the maximum number of blobs per block at `fork_name`.
+fn max_blobs_per_block<T: EthSpec>(fork_name: ForkName) -> usize {
+    match fork_name {
+        ForkName::Base | ForkName::Altair | ForkName::Merge | ForkName::Capella => 0,
+        ForkName::Deneb => T::max_blobs_per_block(),
+    }
+}
+
+/// Returns the maximum uncompressed size of a message on a topic of `kind` at `fork_name`.
+///
+/// Topics without a tighter bound return `usize::MAX`, they remain bounded by the gossipsub
+/// `max_transmit_size`.
+pub fn max_gossip_message_size<T: EthSpec>(kind: &GossipKind, fork_name: ForkName) -> usize {
+    match kind {
+        GossipKind::BeaconBlock => rpc_block_limits_by_fork(fork_name).max,
+        GossipKind::BlobSidecar(_) => {
+            if max_blobs_per_block::<T>(fork_name) > 0 {
+                BlobSidecar::<T>::max_size() + <Signature as Encode>::ssz_fixed_len()
+            } else {
+                0
+            }
+        }
+        GossipKind::VoluntaryExit => <SignedVoluntaryExit as Encode>::ssz_fixed_len(),
+        GossipKind::ProposerSlashing => <ProposerSlashing as Encode>::ssz_fixed_len(),
+        GossipKind::SignedContributionAndProof => {
+            <SignedContributionAndProof<T> as Encode>::ssz_fixed_len()
+        }
+        GossipKind::SyncCommitteeMessage(_) => <SyncCommitteeMessage as Encode>::ssz_fixed_len(),
+        GossipKind::BlsToExecutionChange => <SignedBlsToExecutionChange as Encode>::ssz_fixed_len(),
+        GossipKind::BeaconAggregateAndProof
+        | GossipKind::Attestation(_)
+        | GossipKind::AttesterSlashing
+        | GossipKind::LightClientFinalityUpdate
+        | GossipKind::LightClientOptimisticUpdate => usize::MAX,
+    }
+}
+
+/// Verifies the size of `data` and, where possible, the number of blobs it refers to without SSZ
+/// decoding it.
+fn verify_gossip_message_limits<T: EthSpec>(
+    kind: &GossipKind,
+    fork_name: ForkName,
+    data: &[u8],
+) -> Result<(), String> {
+    let max_size = max_gossip_message_size::<T>(kind, fork_name);
+    if data.len() > max_size {
+        return Err(format!(
+            "Gossip message of {} bytes exceeds the {} byte limit for {} at {}",
+            data.len(),
+            max_size,
+            kind,
+            fork_name
+        ));
+    }
+
+    let max_blobs = max_blobs_per_block::<T>(fork_name);
+    match kind {
+        GossipKind::BlobSidecar(blob_index) if *blob_index >= max_blobs as u64 => Err(format!(
+            "Blob sidecar index {} exceeds the maximum of {} blobs at {}",
+            blob_index, max_blobs, fork_name
+        )),
+        GossipKind::BeaconBlock if fork_name == ForkName::Deneb => {
+            match deneb_blob_kzg_commitments_len(data) {
+                Some(num_commitments) if num_commitments > max_blobs => Err(format!(
+                    "Block with {} blob commitments exceeds the maximum of {} blobs at {}",
+                    num_commitments, max_blobs, fork_name
+                )),
+                // Malformed blocks are rejected by the SSZ decoder.
+                _ => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the number of KZG commitments in the SSZ bytes of a `SignedBeaconBlockDeneb` by
+/// following the SSZ offsets, without decoding the block.
+///
+/// Returns `None` if the offsets are malformed.
+fn deneb_blob_kzg_commitments_len(bytes: &[u8]) -> Option<usize> {
+    let read_offset = |position: usize| -> Option<usize> {
+        let offset_bytes = bytes.get(position..position.checked_add(BYTES_PER_LENGTH_OFFSET)?)?;
+        Some(u32::from_le_bytes(offset_bytes.try_into().ok()?) as usize)
+    };
+
+    // The block's only variable-length field is its body, which follows the slot, proposer index,
+    // parent root and state root.
+    let block_body_offset_position = <Slot as Encode>::ssz_fixed_len()
+        + <u64 as Encode>::ssz_fixed_len()
+        + 2 * <Hash256 as Encode>::ssz_fixed_len();
+    // The first variable-length field of the body (the proposer slashings) follows the RANDAO
+    // reveal, eth1 data and graffiti. Its offset is the length of the body's fixed part.
+    let body_first_offset_position = <Signature as Encode>::ssz_fixed_len()
+        + <Eth1Data as Encode>::ssz_fixed_len()
+        + <Graffiti as Encode>::ssz_fixed_len();
+
+    // The block is the first field of the signed block.
+    let block_start = read_offset(0)?;
+    let body_start = block_start.checked_add(read_offset(
+        block_start.checked_add(block_body_offset_position)?,
+    )?)?;
+    let body_fixed_len = read_offset(body_start.checked_add(body_first_offset_position)?)?;
+    // The commitments are the last field of the body, so their offset is the last offset of the
+    // body's fixed part and they extend to the end of the message.
+    let commitments_start = body_start.checked_add(read_offset(
+        body_start
+            .checked_add(body_fixed_len)?
+            .checked_sub(BYTES_PER_LENGTH_OFFSET)?,
+    )?)?;
+    let commitments_bytes = bytes.len().checked_sub(commitments_start)?;
+
+    Some(commitments_bytes / <KzgCommitment as Encode>::ssz_fixed_len())
+}
+
 impl<T: EthSpec> PubsubMessage<T> {
     /// Returns the topics that each pubsub message will be sent across, given a supported
     /// gossipsub encoding and fork version.
@@ -152,6 +302,18 @@ impl<T: EthSpec> PubsubMessage<T> {
                 // Therefore compression has already been handled for us by the time we are
                 // decoding the objects here.
 
+                // Cheaply reject messages that exceed the limits of their fork before SSZ
+                // decoding them.
+                if let Some(fork_name) = fork_context.from_context_bytes(gossip_topic.fork_digest) {
+                    verify_gossip_message_limits::<T>(gossip_topic.kind(), *fork_name, data)?;
+                }
+
+                let topic_kind: &str = gossip_topic.kind().as_ref();
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_SSZ_DECODE_ATTEMPTS_PER_TOPIC_KIND,
+                    &[topic_kind],
+                );
+
                 // the ssz decoders
                 match gossip_topic.kind() {
                     GossipKind::BeaconAggregateAndProof => {
@@ -357,3 +519,166 @@ impl<T: EthSpec> std::fmt::Display for PubsubMessage<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gossipsub::DataTransform;
+    use ssz_types::VariableList;
+    use types::{BeaconBlock, BeaconBlockDeneb, EmptyBlock, Epoch, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn fork_context() -> Arc<ForkContext> {
+        let mut spec = E::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(0));
+        spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+        spec.capella_fork_epoch = Some(Epoch::new(0));
+        spec.deneb_fork_epoch = Some(Epoch::new(1));
+        Arc::new(ForkContext::new::<E>(Slot::new(0), Hash256::zero(), &spec))
+    }
+
+    fn topic_hash(kind: GossipKind, fork_name: ForkName, fork_context: &ForkContext) -> TopicHash {
+        let fork_digest = fork_context.to_context_bytes(fork_name).unwrap();
+        TopicHash::from_raw(
+            GossipTopic::new(kind, GossipEncoding::default(), fork_digest).to_string(),
+        )
+    }
+
+    fn raw_message(topic: TopicHash, data: Vec<u8>) -> gossipsub::RawMessage {
+        gossipsub::RawMessage {
+            source: None,
+            data,
+            sequence_number: None,
+            topic,
+            signature: None,
+            key: None,
+            validated: false,
+        }
+    }
+
+    /// Returns the number of messages on `topic` which have reached SSZ decoding.
+    ///
+    /// Each test decodes a different topic kind, so that the counts aren't affected by tests
+    /// running in parallel.
+    fn ssz_decode_attempts(topic: &TopicHash) -> u64 {
+        let gossip_topic = GossipTopic::decode(topic.as_str()).unwrap();
+        metrics::get_int_counter(
+            &metrics::GOSSIP_SSZ_DECODE_ATTEMPTS_PER_TOPIC_KIND,
+            &[gossip_topic.kind().as_ref()],
+        )
+        .map_or(0, |counter| counter.get())
+    }
+
+    /// Decodes `data`, asserting that it is rejected by `verify_gossip_message_limits` rather than
+    /// by the SSZ decoder.
+    fn assert_rejected_before_ssz_decoding(
+        topic: &TopicHash,
+        data: &[u8],
+        fork_context: &ForkContext,
+    ) {
+        let gossip_topic = GossipTopic::decode(topic.as_str()).unwrap();
+        let fork_name = fork_context
+            .from_context_bytes(gossip_topic.fork_digest)
+            .unwrap();
+        let limits_error =
+            verify_gossip_message_limits::<E>(gossip_topic.kind(), *fork_name, data).unwrap_err();
+        let attempts = ssz_decode_attempts(topic);
+        assert_eq!(
+            PubsubMessage::<E>::decode(topic, data, fork_context),
+            Err(limits_error)
+        );
+        assert_eq!(ssz_decode_attempts(topic), attempts);
+    }
+
+    fn deneb_block_bytes(num_commitments: usize) -> Vec<u8> {
+        let mut block = BeaconBlockDeneb::<E>::empty(&E::default_spec());
+        block.body.blob_kzg_commitments =
+            VariableList::new(vec![KzgCommitment([0; 48]); num_commitments]).unwrap();
+        SignedBeaconBlock::from_block(BeaconBlock::Deneb(block), Signature::empty()).as_ssz_bytes()
+    }
+
+    #[test]
+    fn oversized_snappy_frames_are_rejected() {
+        let fork_context = fork_context();
+        let transform = SnappyTransform::new::<E>(10 * 1_048_576, fork_context.clone());
+        let topic = topic_hash(GossipKind::VoluntaryExit, ForkName::Deneb, &fork_context);
+        let max_size = <SignedVoluntaryExit as Encode>::ssz_fixed_len();
+
+        // A frame which decompresses to the exact size of a voluntary exit is accepted.
+        let valid = Encoder::new().compress_vec(&vec![0; max_size]).unwrap();
+        assert!(transform
+            .inbound_transform(raw_message(topic.clone(), valid))
+            .is_ok());
+
+        // A frame which claims to decompress to more than a voluntary exit is rejected.
+        let too_large = Encoder::new().compress_vec(&vec![0; max_size + 1]).unwrap();
+        assert!(transform
+            .inbound_transform(raw_message(topic.clone(), too_large))
+            .is_err());
+
+        // A frame which is longer than any valid compression of a voluntary exit is rejected.
+        let too_long = vec![0; max_compress_len(max_size) + 1];
+        assert!(transform
+            .inbound_transform(raw_message(topic, too_long))
+            .is_err());
+    }
+
+    #[test]
+    fn oversized_messages_are_rejected_before_ssz_decoding() {
+        let fork_context = fork_context();
+        let topic = topic_hash(GossipKind::VoluntaryExit, ForkName::Deneb, &fork_context);
+        let data = vec![0; <SignedVoluntaryExit as Encode>::ssz_fixed_len() + 1];
+
+        assert_rejected_before_ssz_decoding(&topic, &data, &fork_context);
+    }
+
+    #[test]
+    fn over_long_commitment_lists_are_rejected_before_ssz_decoding() {
+        let fork_context = fork_context();
+        let topic = topic_hash(GossipKind::BeaconBlock, ForkName::Deneb, &fork_context);
+        let max_blobs = E::max_blobs_per_block();
+
+        let valid = deneb_block_bytes(max_blobs);
+        assert_eq!(deneb_blob_kzg_commitments_len(&valid), Some(max_blobs));
+        assert!(verify_gossip_message_limits::<E>(
+            &GossipKind::BeaconBlock,
+            ForkName::Deneb,
+            &valid
+        )
+        .is_ok());
+        let attempts = ssz_decode_attempts(&topic);
+        assert!(PubsubMessage::<E>::decode(&topic, &valid, &fork_context).is_ok());
+        assert_eq!(ssz_decode_attempts(&topic), attempts + 1);
+
+        for num_commitments in [max_blobs + 1, 4096] {
+            let invalid = deneb_block_bytes(num_commitments);
+            assert_eq!(
+                deneb_blob_kzg_commitments_len(&invalid),
+                Some(num_commitments)
+            );
+            assert_rejected_before_ssz_decoding(&topic, &invalid, &fork_context);
+        }
+    }
+
+    #[test]
+    fn blob_sidecar_limits_are_fork_aware() {
+        let fork_context = fork_context();
+        let max_blobs = E::max_blobs_per_block() as u64;
+
+        assert_eq!(
+            max_gossip_message_size::<E>(&GossipKind::BlobSidecar(0), ForkName::Capella),
+            0
+        );
+        assert!(max_gossip_message_size::<E>(&GossipKind::BlobSidecar(0), ForkName::Deneb) > 0);
+
+        let topic = topic_hash(
+            GossipKind::BlobSidecar(max_blobs),
+            ForkName::Deneb,
+            &fork_context,
+        );
+        let data =
+            vec![0; max_gossip_message_size::<E>(&GossipKind::BlobSidecar(0), ForkName::Deneb)];
+        assert_rejected_before_ssz_decoding(&topic, &data, &fork_context);
+    }
+}
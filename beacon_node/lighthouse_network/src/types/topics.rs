@@ -2,7 +2,7 @@ use libp2p::gossipsub::{IdentTopic as Topic, TopicHash};
 use serde::{Deserialize, Serialize};
 use strum::AsRefStr;
 use types::consts::deneb::BLOB_SIDECAR_SUBNET_COUNT;
-use types::{EthSpec, ForkName, SubnetId, SyncSubnetId};
+use types::{EthSpec, ForkName, SubnetId, SyncSubnetId, Unsigned};
 
 use crate::Subnet;
 
@@ -75,6 +75,40 @@ pub fn core_topics_to_subscribe<T: EthSpec>(mut current_fork: ForkName) -> Vec<G
     topics
 }
 
+/// Returns all the gossip topics that we subscribe to for `fork_name`, given its `fork_digest`.
+///
+/// This includes the core topics for the fork and all prior forks, the light client topics if
+/// `enable_light_client_server` is set and every attestation and sync committee subnet topic if
+/// `subscribe_all_subnets` is set. Subnets subscribed for validator duties are not included.
+pub fn fork_gossip_topics<T: EthSpec>(
+    fork_name: ForkName,
+    fork_digest: [u8; 4],
+    enable_light_client_server: bool,
+    subscribe_all_subnets: bool,
+) -> Vec<GossipTopic> {
+    let mut kinds = core_topics_to_subscribe::<T>(fork_name);
+
+    if enable_light_client_server {
+        kinds.extend(LIGHT_CLIENT_GOSSIP_TOPICS.iter().cloned());
+    }
+
+    if subscribe_all_subnets {
+        kinds.extend(
+            (0..T::SubnetBitfieldLength::to_u64())
+                .map(|subnet_id| GossipKind::Attestation(SubnetId::new(subnet_id))),
+        );
+        kinds.extend(
+            (0..T::SyncCommitteeSubnetCount::to_u64())
+                .map(|subnet_id| GossipKind::SyncCommitteeMessage(SyncSubnetId::new(subnet_id))),
+        );
+    }
+
+    kinds
+        .into_iter()
+        .map(|kind| GossipTopic::new(kind, GossipEncoding::default(), fork_digest))
+        .collect()
+}
+
 /// A gossipsub topic which encapsulates the type of messages that should be sent and received over
 /// the pubsub protocol and the way the messages should be encoded.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -304,7 +338,7 @@ fn subnet_topic_index(topic: &str) -> Option<GossipKind> {
 
 #[cfg(test)]
 mod tests {
-    use types::MainnetEthSpec;
+    use types::{ChainSpec, Hash256, MainnetEthSpec};
 
     use super::GossipKind::*;
     use super::*;
@@ -445,4 +479,73 @@ mod tests {
         let latest_fork = *ForkName::list_all().last().unwrap();
         assert_eq!(core_topics_to_subscribe::<E>(latest_fork), all_topics);
     }
+
+    #[test]
+    fn test_fork_gossip_topics_deneb_blob_subnets() {
+        type E = MainnetEthSpec;
+        let fork_digest = [0; 4];
+
+        // Every blob subnet is a core topic, so all of them are subscribed without any flags.
+        let topics = fork_gossip_topics::<E>(ForkName::Deneb, fork_digest, false, false);
+        let blob_subnets = topics
+            .iter()
+            .filter_map(|topic| match topic.kind() {
+                GossipKind::BlobSidecar(subnet) => Some(*subnet),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            blob_subnets,
+            (0..BLOB_SIDECAR_SUBNET_COUNT).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_fork_gossip_topics_mainnet_capella() {
+        type E = MainnetEthSpec;
+        let spec = ChainSpec::mainnet();
+        let genesis_validators_root = Hash256::from_slice(
+            &hex::decode("4b363db94e286120d76eb905340fdd4e54bfe9f06bf33ff6cf5ad27f511bfe95")
+                .unwrap(),
+        );
+        let fork_digest = ChainSpec::compute_fork_digest(
+            spec.fork_version_for_name(ForkName::Capella),
+            genesis_validators_root,
+        );
+        assert_eq!(hex::encode(fork_digest), "bba4da96");
+
+        let core_topics = fork_gossip_topics::<E>(ForkName::Capella, fork_digest, false, false)
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            core_topics,
+            vec![
+                "/eth2/bba4da96/bls_to_execution_change/ssz_snappy",
+                "/eth2/bba4da96/sync_committee_contribution_and_proof/ssz_snappy",
+                "/eth2/bba4da96/beacon_block/ssz_snappy",
+                "/eth2/bba4da96/beacon_aggregate_and_proof/ssz_snappy",
+                "/eth2/bba4da96/voluntary_exit/ssz_snappy",
+                "/eth2/bba4da96/proposer_slashing/ssz_snappy",
+                "/eth2/bba4da96/attester_slashing/ssz_snappy",
+            ]
+        );
+
+        let all_topics = fork_gossip_topics::<E>(ForkName::Capella, fork_digest, true, true)
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            all_topics.len(),
+            core_topics.len()
+                + LIGHT_CLIENT_GOSSIP_TOPICS.len()
+                + E::SubnetBitfieldLength::to_usize()
+                + E::SyncCommitteeSubnetCount::to_usize()
+        );
+        assert!(all_topics.contains(&"/eth2/bba4da96/beacon_attestation_63/ssz_snappy".into()));
+        assert!(all_topics.contains(&"/eth2/bba4da96/sync_committee_3/ssz_snappy".into()));
+        assert!(
+            all_topics.contains(&"/eth2/bba4da96/light_client_finality_update/ssz_snappy".into())
+        );
+    }
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// The state of the UPnP port mappings maintained by the network service.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UPnPStatus {
+    /// The external address reported by the gateway.
+    pub external_ip: Option<Ipv4Addr>,
+    /// The mapped TCP port for libp2p.
+    pub tcp_port: Option<u16>,
+    /// The mapped UDP port for the QUIC libp2p transport.
+    pub udp_quic_port: Option<u16>,
+    /// The mapped UDP port for discv5.
+    pub udp_disc_port: Option<u16>,
+    /// The UNIX timestamp (in seconds) at which the mappings were last successfully renewed.
+    pub last_renewal: Option<u64>,
+    /// The error from the most recent failed attempt to establish or renew the mappings.
+    pub last_error: Option<String>,
+    /// The number of consecutive attempts that have failed.
+    pub consecutive_failures: u32,
+}
@@ -4,7 +4,7 @@ use lighthouse_network::service::Network as LibP2PService;
 use lighthouse_network::Enr;
 use lighthouse_network::EnrExt;
 use lighthouse_network::Multiaddr;
-use lighthouse_network::{NetworkConfig, NetworkEvent};
+use lighthouse_network::{NetworkConfig, NetworkEvent, NetworkGlobals};
 use slog::{debug, error, o, Drain};
 use std::sync::Arc;
 use std::sync::Weak;
@@ -103,25 +103,35 @@ pub async fn build_libp2p_instance(
     spec: &ChainSpec,
 ) -> Libp2pInstance {
     let config = build_config(boot_nodes);
+    build_libp2p_instance_with_config(rt, &config, log, fork_name, spec)
+        .await
+        .0
+}
+
+/// Builds a libp2p instance from `config`, returning it along with its network globals.
+pub async fn build_libp2p_instance_with_config(
+    rt: Weak<Runtime>,
+    config: &NetworkConfig,
+    log: slog::Logger,
+    fork_name: ForkName,
+    spec: &ChainSpec,
+) -> (Libp2pInstance, Arc<NetworkGlobals<E>>) {
     // launch libp2p service
 
     let (signal, exit) = exit_future::signal();
     let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
     let executor = task_executor::TaskExecutor::new(rt, exit, log.clone(), shutdown_tx);
     let libp2p_context = lighthouse_network::Context {
-        config: &config,
+        config,
         enr_fork_id: EnrForkId::default(),
         fork_context: Arc::new(fork_context(fork_name)),
         chain_spec: spec,
         gossipsub_registry: None,
     };
-    Libp2pInstance(
-        LibP2PService::new(executor, libp2p_context, &log)
-            .await
-            .expect("should build libp2p instance")
-            .0,
-        signal,
-    )
+    let (network, network_globals) = LibP2PService::new(executor, libp2p_context, &log)
+        .await
+        .expect("should build libp2p instance");
+    (Libp2pInstance(network, signal), network_globals)
 }
 
 #[allow(dead_code)]
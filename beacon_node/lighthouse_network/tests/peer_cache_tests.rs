@@ -0,0 +1,131 @@
+#![cfg(test)]
+
+mod common;
+
+use lighthouse_network::multiaddr::Protocol as MProtocol;
+use lighthouse_network::peer_manager::peerdb::peer_cache::{self, PEER_CACHE_FILENAME};
+use lighthouse_network::{EnrExt, NetworkEvent};
+use slog::{debug, Level};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+use types::{EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+/// A node that was connected to a peer before a restart should dial that peer on startup using
+/// its persisted peer cache, without any boot nodes.
+#[test]
+fn test_warm_start_dials_cached_peers() {
+    let log = common::build_log(Level::Debug, false);
+    let rt = Arc::new(Runtime::new().unwrap());
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        let mut receiver_config = common::build_config(vec![]);
+        let tcp_port = unused_port::unused_tcp4_port().unwrap();
+        let quic_port = unused_port::unused_udp4_port().unwrap();
+        receiver_config.set_ipv4_listening_address(
+            std::net::Ipv4Addr::UNSPECIFIED,
+            tcp_port,
+            0,
+            quic_port,
+        );
+        let sender_config = common::build_config(vec![]);
+        let restarted_config = common::build_config(vec![]);
+
+        let (mut receiver, _) = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            &receiver_config,
+            log.clone(),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        let (mut sender, _) = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            &sender_config,
+            log.clone(),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+
+        // Wait for the receiver to listen, then connect the sender to it.
+        loop {
+            if let NetworkEvent::NewListenAddr(addr) = receiver.next_event().await {
+                if addr.iter().any(|p| matches!(p, MProtocol::Tcp(_))) {
+                    break;
+                }
+            }
+        }
+        let receiver_enr = receiver.local_enr();
+        let receiver_peer_id = receiver_enr.peer_id();
+        sender.peer_manager_mut().dial_peer(receiver_enr.clone());
+
+        let sender_future = async {
+            loop {
+                if let NetworkEvent::PeerConnectedOutgoing(peer_id) = sender.next_event().await {
+                    return peer_id;
+                }
+            }
+        };
+        let receiver_future = async {
+            loop {
+                receiver.next_event().await;
+            }
+        };
+        tokio::select! {
+            peer_id = sender_future => assert_eq!(peer_id, receiver_peer_id),
+            _ = receiver_future => unreachable!(),
+            _ = sleep(Duration::from_secs(30)) => panic!("Sender did not connect"),
+        };
+
+        // Persist the sender's peer cache and hand it to a fresh node.
+        sender.peer_manager().persist_peer_cache();
+        let cached = peer_cache::load_from_disk(&sender_config.network_dir, &log)
+            .expect("peer cache is persisted");
+        assert_eq!(cached.peers.len(), 1);
+        assert_eq!(cached.peers[0].enr, receiver_enr.to_base64());
+        std::fs::copy(
+            sender_config.network_dir.join(PEER_CACHE_FILENAME),
+            restarted_config.network_dir.join(PEER_CACHE_FILENAME),
+        )
+        .unwrap();
+        drop(sender);
+
+        let (mut restarted, restarted_globals) = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            &restarted_config,
+            log.clone(),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        debug!(log, "Restarted node built"; "enr" => %restarted.local_enr());
+
+        let restarted_future = async {
+            loop {
+                if let NetworkEvent::PeerConnectedOutgoing(peer_id) = restarted.next_event().await {
+                    return peer_id;
+                }
+            }
+        };
+        let receiver_future = async {
+            loop {
+                receiver.next_event().await;
+            }
+        };
+        tokio::select! {
+            peer_id = restarted_future => assert_eq!(peer_id, receiver_peer_id),
+            _ = receiver_future => unreachable!(),
+            _ = sleep(Duration::from_secs(30)) => panic!("Cached peer was not dialed"),
+        };
+
+        assert!(restarted_globals
+            .peers
+            .read()
+            .is_connected(&receiver_peer_id));
+    });
+}
@@ -80,6 +80,7 @@ fn test_tcp_status_rpc() {
             finalized_epoch: Epoch::new(1),
             head_root: Hash256::from_low_u64_be(0),
             head_slot: Slot::new(1),
+            earliest_available_slot: Some(Slot::new(0)),
         });
 
         // Dummy STATUS RPC message
@@ -89,6 +90,7 @@ fn test_tcp_status_rpc() {
             finalized_epoch: Epoch::new(1),
             head_root: Hash256::from_low_u64_be(0),
             head_slot: Slot::new(1),
+            earliest_available_slot: Some(Slot::new(0)),
         });
 
         // build the sender future
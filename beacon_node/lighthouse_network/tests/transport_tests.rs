@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+mod common;
+
+use lighthouse_network::multiaddr::Protocol as MProtocol;
+use lighthouse_network::{NetworkEvent, Transport, TransportPreference};
+use slog::{debug, Level};
+use std::num::NonZeroU16;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+use types::{EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+/// Has the sender dial the receiver's ENR with `transport_preference`, returning the transport
+/// the sender records for the receiver once connected.
+///
+/// The receiver always advertises a QUIC port in its ENR. If `quic_reachable` is false nothing
+/// is listening on that port, so QUIC dials to it fail.
+fn dial_with_preference(
+    transport_preference: TransportPreference,
+    quic_reachable: bool,
+) -> (Transport, serde_json::Value) {
+    let log = common::build_log(Level::Debug, false);
+    let rt = Arc::new(Runtime::new().unwrap());
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        let mut receiver_config = common::build_config(vec![]);
+        let tcp_port = unused_port::unused_tcp4_port().unwrap();
+        let quic_port = unused_port::unused_udp4_port().unwrap();
+        if quic_reachable {
+            receiver_config.set_ipv4_listening_address(
+                std::net::Ipv4Addr::UNSPECIFIED,
+                tcp_port,
+                0,
+                quic_port,
+            );
+        } else {
+            receiver_config.set_ipv4_listening_address(
+                std::net::Ipv4Addr::UNSPECIFIED,
+                tcp_port,
+                0,
+                0,
+            );
+            receiver_config.enr_quic4_port = NonZeroU16::new(quic_port);
+        }
+
+        let mut sender_config = common::build_config(vec![]);
+        sender_config.transport_preference = transport_preference;
+
+        let (mut receiver, _) = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            &receiver_config,
+            log.clone(),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        let (mut sender, sender_globals) = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            &sender_config,
+            log.clone(),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+
+        // Wait for the receiver to listen on both transports.
+        let (mut tcp_listening, mut quic_listening) = (false, false);
+        while !(tcp_listening && quic_listening) {
+            if let NetworkEvent::NewListenAddr(addr) = receiver.next_event().await {
+                tcp_listening |= addr.iter().any(|p| matches!(p, MProtocol::Tcp(_)));
+                quic_listening |= addr.iter().any(|p| matches!(p, MProtocol::QuicV1));
+            }
+        }
+
+        let receiver_enr = receiver.local_enr();
+        debug!(log, "Dialing receiver"; "enr" => %receiver_enr);
+        sender.peer_manager_mut().dial_peer(receiver_enr.clone());
+
+        let sender_future = async {
+            loop {
+                if let NetworkEvent::PeerConnectedOutgoing(peer_id) = sender.next_event().await {
+                    return peer_id;
+                }
+            }
+        };
+        let receiver_future = async {
+            loop {
+                receiver.next_event().await;
+            }
+        };
+
+        let peer_id = tokio::select! {
+            peer_id = sender_future => peer_id,
+            _ = receiver_future => unreachable!(),
+            _ = sleep(Duration::from_secs(30)) => {
+                panic!("Future timed out");
+            }
+        };
+
+        let peers = sender_globals.peers.read();
+        let peer_info = peers.peer_info(&peer_id).expect("receiver is known");
+        (
+            peer_info.transport().expect("transport is recorded"),
+            serde_json::to_value(peer_info).unwrap(),
+        )
+    })
+}
+
+#[test]
+fn test_quic_first_connects_over_quic() {
+    let (transport, peer_info) = dial_with_preference(TransportPreference::QuicFirst, true);
+    assert_eq!(transport, Transport::Quic);
+    assert_eq!(peer_info["transport"], "quic");
+}
+
+#[test]
+fn test_tcp_first_connects_over_tcp() {
+    let (transport, peer_info) = dial_with_preference(TransportPreference::TcpFirst, true);
+    assert_eq!(transport, Transport::Tcp);
+    assert_eq!(peer_info["transport"], "tcp");
+}
+
+#[test]
+fn test_quic_first_falls_back_to_tcp() {
+    let (transport, peer_info) = dial_with_preference(TransportPreference::QuicFirst, false);
+    assert_eq!(transport, Transport::Tcp);
+    assert_eq!(peer_info["transport"], "tcp");
+}
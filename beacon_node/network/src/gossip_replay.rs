@@ -0,0 +1,49 @@
+//! Re-runs gossip verification on raw gossip messages, to reproduce the rejections recorded in
+//! the gossip rejection buffer.
+use beacon_chain::attestation_verification::{
+    verify_aggregated_attestation_dry_run, verify_unaggregated_attestation_dry_run,
+};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use lighthouse_network::{PubsubMessage, TopicHash};
+use std::sync::Arc;
+use types::ForkContext;
+
+/// Decodes the SSZ `data` received on `topic` and runs it through gossip verification.
+///
+/// Returns `Ok(None)` if the message passes verification, `Ok(Some(error))` with the full
+/// verification error if it fails, or `Err` if the message cannot be decoded or its topic is not
+/// supported. Only attestation and aggregate topics are supported, which are also the only messages
+/// recorded in the gossip rejection buffer.
+///
+/// Verification is a dry run: the message is not marked as observed or supplied to the slasher, so
+/// a genuine copy of it received via gossip is still processed.
+pub fn replay_gossip_message<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    topic: &str,
+    data: &[u8],
+) -> Result<Option<String>, String> {
+    let current_slot = chain
+        .slot()
+        .map_err(|e| format!("unable to read slot clock: {:?}", e))?;
+    let fork_context =
+        ForkContext::new::<T::EthSpec>(current_slot, chain.genesis_validators_root, &chain.spec);
+    let message = PubsubMessage::decode(&TopicHash::from_raw(topic), data, &fork_context)?;
+
+    let result = match &message {
+        PubsubMessage::Attestation(subnet_and_attestation) => {
+            let (subnet_id, attestation) = subnet_and_attestation.as_ref();
+            verify_unaggregated_attestation_dry_run(chain, attestation, Some(*subnet_id))
+        }
+        PubsubMessage::AggregateAndProofAttestation(aggregate) => {
+            verify_aggregated_attestation_dry_run(chain, aggregate)
+        }
+        _ => {
+            return Err(format!(
+                "replay is not supported for {} messages, only attestations and aggregates",
+                message.kind().as_ref()
+            ))
+        }
+    };
+
+    Ok(result.err().map(|e| format!("{:?}", e)))
+}
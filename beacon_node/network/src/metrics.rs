@@ -41,6 +41,10 @@ lazy_static! {
         "validator_subnet_subscriptions_aggregator_total",
         "Count of validator subscription requests where the subscriber is an aggregator."
     );
+    pub static ref SUBNET_SHORT_LIVED_SUBSCRIPTIONS: Result<IntGauge> = try_create_int_gauge(
+        "validator_attestation_subnet_short_lived_subscriptions",
+        "Number of attestation subnets currently subscribed to for aggregation duties."
+    );
     pub static ref SYNC_COMMITTEE_SUBSCRIPTION_REQUESTS: Result<IntCounter> = try_create_int_counter(
         "validator_sync_committee_subnet_subscriptions_total",
         "Count of validator sync committee subscription requests."
@@ -71,6 +75,10 @@ lazy_static! {
         "beacon_processor_gossip_blob_verified_total",
         "Total number of gossip blob verified for propagation."
     );
+    pub static ref BEACON_PROCESSOR_GOSSIP_BLOB_REQUEUED_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_processor_gossip_blob_requeued_total",
+        "Total number of gossip blobs that arrived early and were re-queued for later verification."
+    );
     // Gossip Exits.
     pub static ref BEACON_PROCESSOR_EXIT_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_exit_verified_total",
@@ -219,6 +227,29 @@ lazy_static! {
         "Time taken for network to handle an event sent to the network service.",
         &["type"]
     );
+
+    /*
+     * Gossip publish metrics
+     */
+    pub static ref NETWORK_PUBLISH_ENQUEUE_TO_PUBLISH_TIME: Result<HistogramVec> = try_create_histogram_vec(
+        "network_publish_enqueue_to_publish_seconds",
+        "Time from a message being sent to the network service until it has been published, per gossip topic",
+        &["topic"]
+    );
+    pub static ref NETWORK_PUBLISH_TIME: Result<HistogramVec> = try_create_histogram_vec(
+        "network_publish_seconds",
+        "Time taken to encode a message and send it to the gossipsub mesh peers, per gossip topic",
+        &["topic"]
+    );
+    pub static ref OWN_BLOCK_PUBLISH_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_block_own_publish_slot_start_delay_time",
+        "Duration between the start of the slot and the publication of a block proposed by one of our validators.",
+        Ok(vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 3.5, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 15.0, 20.0])
+    );
+    pub static ref OWN_BLOCK_PUBLISHED_LATE_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_own_published_late_total",
+        "Count of blocks proposed by our validators which were published after the late block threshold.",
+    );
 }
 
 lazy_static! {
@@ -250,6 +281,18 @@ lazy_static! {
         "sync_parent_block_lookups",
         "Number of parent block lookups underway"
     );
+    pub static ref SYNC_PARENT_LOOKUP_BUFFERED_BYTES: Result<IntGauge> = try_create_int_gauge(
+        "sync_parent_lookup_buffered_bytes",
+        "Approximate size of the blocks and blobs held in memory by parent block lookups"
+    );
+    pub static ref SYNC_RANGE_KNOWN_BLOCKS_SKIPPED: Result<IntCounter> = try_create_int_counter(
+        "sync_range_known_blocks_skipped_total",
+        "Number of blocks downloaded by range sync that were already known and not processed"
+    );
+    pub static ref SYNC_RANGE_SHORTENED_REQUESTS: Result<IntCounter> = try_create_int_counter(
+        "sync_range_shortened_requests_total",
+        "Number of range sync batch requests that skipped slots already on the local chain"
+    );
 
     /*
      * Block Delay Metrics
@@ -316,6 +359,23 @@ lazy_static! {
         "beacon_processor_reprocessing_queue_sent_optimistic_updates",
         "Number of queued light client optimistic updates where as matching block has been imported."
     );
+
+    /*
+     * UPnP
+     */
+    pub static ref UPNP_MAPPED_PORT: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "upnp_mapped_port",
+        "The external port mapped via UPnP, or 0 if the mapping is not established",
+        &["mapping"]
+    );
+    pub static ref UPNP_LAST_RENEWAL: Result<IntGauge> = try_create_int_gauge(
+        "upnp_last_renewal_timestamp_seconds",
+        "The UNIX timestamp at which the UPnP port mappings were last renewed"
+    );
+    pub static ref UPNP_CONSECUTIVE_FAILURES: Result<IntGauge> = try_create_int_gauge(
+        "upnp_consecutive_failures",
+        "The number of consecutive failed attempts to establish or renew the UPnP port mappings"
+    );
 }
 
 pub fn update_bandwidth_metrics(bandwidth: &AggregatedBandwidthSinks) {
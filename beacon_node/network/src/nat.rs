@@ -2,15 +2,28 @@
 //!
 //! Currently supported strategies:
 //! - UPnP
+//!
+//! UPnP port mappings are requested with a finite lease and renewed periodically for as long as
+//! the network service is running, since some routers silently drop mappings once their lease
+//! expires. Failed attempts are retried with an exponential backoff.
 
-use crate::{NetworkConfig, NetworkMessage};
+use crate::{metrics, NetworkConfig, NetworkMessage};
 use if_addrs::get_if_addrs;
-use slog::{debug, info};
-use std::net::{IpAddr, SocketAddr, SocketAddrV4};
+use igd::PortMappingProtocol;
+use lighthouse_network::{NetworkGlobals, UPnPStatus, UPNP_LEASE_DURATION};
+use slog::{debug, info, warn};
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use types::EthSpec;
 
+/// The delay before retrying after the first failed attempt to establish the mappings. The delay
+/// doubles with each consecutive failure, up to the renewal interval.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(30);
+
 /// Configuration required to construct the UPnP port mappings.
+#[derive(Debug, Clone)]
 pub struct UPnPConfig {
     /// The local TCP port.
     tcp_port: u16,
@@ -22,10 +35,14 @@ pub struct UPnPConfig {
     disable_discovery: bool,
     /// Whether quic is enabled or not.
     disable_quic_support: bool,
+    /// The lease duration requested for each mapping.
+    lease_duration: Duration,
+    /// The interval at which the mappings are renewed.
+    renewal_interval: Duration,
 }
 
 /// Contains mappings that managed to be established.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct EstablishedUPnPMappings {
     /// A TCP port mapping for libp2p.
     pub tcp_port: Option<u16>,
@@ -55,147 +72,362 @@ impl UPnPConfig {
             quic_port: v4_addr.quic_port,
             disable_discovery: config.disable_discovery,
             disable_quic_support: config.disable_quic_support,
+            lease_duration: UPNP_LEASE_DURATION,
+            renewal_interval: config.upnp_renewal_interval(),
         })
     }
 }
 
-/// Attempts to construct external port mappings with UPnP.
-pub fn construct_upnp_mappings<T: EthSpec>(
+/// An error returned by a gateway when adding a port mapping.
+#[derive(Debug)]
+pub enum AddPortError {
+    /// The port is already mapped to another client.
+    PortInUse,
+    Other(String),
+}
+
+/// The operations of an Internet Gateway Device used to maintain the port mappings.
+pub trait Gateway {
+    /// Returns the external address of the gateway.
+    fn external_ip(&self) -> Result<Ipv4Addr, String>;
+
+    /// Maps the external `local_socket.port()` to `local_socket` for `lease_duration`.
+    fn map_port(
+        &self,
+        protocol: PortMappingProtocol,
+        local_socket: SocketAddrV4,
+        lease_duration: Duration,
+        description: &str,
+    ) -> Result<(), AddPortError>;
+
+    /// Removes the mapping of the external `port`.
+    fn unmap_port(&self, protocol: PortMappingProtocol, port: u16) -> Result<(), String>;
+}
+
+impl Gateway for igd::Gateway {
+    fn external_ip(&self) -> Result<Ipv4Addr, String> {
+        self.get_external_ip().map_err(|e| e.to_string())
+    }
+
+    fn map_port(
+        &self,
+        protocol: PortMappingProtocol,
+        local_socket: SocketAddrV4,
+        lease_duration: Duration,
+        description: &str,
+    ) -> Result<(), AddPortError> {
+        let lease_duration = u32::try_from(lease_duration.as_secs()).unwrap_or(u32::MAX);
+        self.add_port(
+            protocol,
+            local_socket.port(),
+            local_socket,
+            lease_duration,
+            description,
+        )
+        .map_err(|e| match e {
+            igd::AddPortError::PortInUse => AddPortError::PortInUse,
+            e => AddPortError::Other(e.to_string()),
+        })
+    }
+
+    fn unmap_port(&self, protocol: PortMappingProtocol, port: u16) -> Result<(), String> {
+        self.remove_port(protocol, port).map_err(|e| e.to_string())
+    }
+}
+
+/// Maintains the UPnP port mappings along with their status.
+pub struct UPnPMapper {
+    config: UPnPConfig,
+    mappings: EstablishedUPnPMappings,
+    status: UPnPStatus,
+    /// The time at which the mappings were last successfully renewed.
+    last_renewal: Option<Instant>,
+    log: slog::Logger,
+}
+
+impl UPnPMapper {
+    pub fn new(config: UPnPConfig, log: slog::Logger) -> Self {
+        Self {
+            config,
+            mappings: EstablishedUPnPMappings::default(),
+            status: UPnPStatus::default(),
+            last_renewal: None,
+            log,
+        }
+    }
+
+    /// Returns the mappings that are currently established.
+    pub fn mappings(&self) -> &EstablishedUPnPMappings {
+        &self.mappings
+    }
+
+    pub fn status(&self) -> &UPnPStatus {
+        &self.status
+    }
+
+    /// Searches for the gateway and establishes or renews the mappings with it, returning the
+    /// delay until the next attempt.
+    pub fn renew_with_igd(&mut self) -> Duration {
+        let gateway = match igd::search_gateway(Default::default()) {
+            Ok(gateway) => gateway,
+            Err(e) => return self.renewal_failed(format!("UPnP not available: {e}")),
+        };
+        match local_ipv4() {
+            Ok(local_ip) => self.renew(&gateway, local_ip),
+            Err(e) => self.renewal_failed(e),
+        }
+    }
+
+    /// Establishes or renews the mappings with `gateway` for the local address `local_ip`,
+    /// returning the delay until the next attempt.
+    ///
+    /// The attempt fails if any of the mappings could not be established, although those that
+    /// were established are still recorded.
+    pub fn renew<G: Gateway>(&mut self, gateway: &G, local_ip: Ipv4Addr) -> Duration {
+        let mut mappings = EstablishedUPnPMappings::default();
+        let mut errors = vec![];
+
+        let mut map = |protocol, port, protocol_string| {
+            add_port_mapping(
+                gateway,
+                protocol,
+                SocketAddrV4::new(local_ip, port),
+                self.config.lease_duration,
+                protocol_string,
+                &self.log,
+            )
+            .map(|()| port)
+            .map_err(|e| errors.push(format!("{protocol_string} port {port}: {e}")))
+            .ok()
+        };
+
+        mappings.tcp_port = map(PortMappingProtocol::TCP, self.config.tcp_port, "tcp");
+        // Set the discovery UDP port mapping
+        if !self.config.disable_discovery {
+            mappings.udp_disc_port = map(PortMappingProtocol::UDP, self.config.disc_port, "udp");
+        }
+        // Set the quic UDP port mapping
+        if !self.config.disable_quic_support {
+            mappings.udp_quic_port = map(PortMappingProtocol::UDP, self.config.quic_port, "udp");
+        }
+
+        match gateway.external_ip() {
+            Ok(external_ip) => self.status.external_ip = Some(external_ip),
+            Err(e) => debug!(self.log, "UPnP failed to get external IP"; "error" => e),
+        }
+
+        // If no mappings could be established the previous mappings may still be valid until
+        // their lease expires.
+        if !mappings.is_some() {
+            return self.renewal_failed(errors.join(", "));
+        }
+
+        let newly_established = !self.mappings.is_some();
+        self.mappings = mappings;
+        self.update_status_mappings();
+
+        self.last_renewal = Some(Instant::now());
+        self.status.last_renewal = Some(unix_now().as_secs());
+
+        if errors.is_empty() {
+            if newly_established {
+                info!(
+                    self.log,
+                    "UPnP routes established";
+                    "external_ip" => ?self.status.external_ip,
+                    "tcp_port" => ?self.mappings.tcp_port,
+                    "udp_disc_port" => ?self.mappings.udp_disc_port,
+                    "udp_quic_port" => ?self.mappings.udp_quic_port,
+                );
+            } else {
+                debug!(self.log, "UPnP routes renewed");
+            }
+            self.status.last_error = None;
+            self.status.consecutive_failures = 0;
+            self.config.renewal_interval
+        } else {
+            self.renewal_failed(errors.join(", "))
+        }
+    }
+
+    /// Records a failed attempt to establish or renew the mappings, returning the delay until
+    /// the next attempt.
+    ///
+    /// Mappings that have not been renewed within their lease duration are assumed to have
+    /// expired.
+    pub fn renewal_failed(&mut self, error: String) -> Duration {
+        let expired = self.last_renewal.map_or(true, |instant| {
+            instant.elapsed() >= self.config.lease_duration
+        });
+        if expired && self.mappings.is_some() {
+            warn!(self.log, "UPnP port mappings have expired"; "error" => &error);
+            self.mappings = EstablishedUPnPMappings::default();
+            self.update_status_mappings();
+        }
+
+        self.status.consecutive_failures = self.status.consecutive_failures.saturating_add(1);
+        let delay = retry_delay(
+            self.status.consecutive_failures,
+            self.config.renewal_interval,
+        );
+        info!(
+            self.log,
+            "UPnP failed to establish port mappings";
+            "error" => &error,
+            "retry_in_secs" => delay.as_secs(),
+        );
+        self.status.last_error = Some(error);
+        delay
+    }
+
+    fn update_status_mappings(&mut self) {
+        self.status.tcp_port = self.mappings.tcp_port;
+        self.status.udp_quic_port = self.mappings.udp_quic_port;
+        self.status.udp_disc_port = self.mappings.udp_disc_port;
+    }
+}
+
+/// Returns the delay before the next attempt after `consecutive_failures` failed attempts.
+fn retry_delay(consecutive_failures: u32, renewal_interval: Duration) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(16);
+    INITIAL_RETRY_DELAY
+        .saturating_mul(2_u32.pow(exponent))
+        .min(renewal_interval)
+}
+
+fn unix_now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Finds the local IPv4 address to map the ports to.
+fn local_ipv4() -> Result<Ipv4Addr, String> {
+    // Need to find the local listening address matched with the router subnet
+    let interfaces =
+        get_if_addrs().map_err(|e| format!("UPnP failed to get local interfaces: {e}"))?;
+    // Just use the first IP of the first interface that is not a loopback and not an ipv6
+    // address.
+    interfaces
+        .iter()
+        .filter(|interface| !interface.is_loopback())
+        .find_map(|interface| match interface.ip() {
+            IpAddr::V4(address) => Some(address),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| "UPnP failed to find local IPv4 address".to_string())
+}
+
+/// Maintains UPnP port mappings for as long as the network service is running, publishing their
+/// status to `network_globals` and reporting new mappings to the network service.
+pub async fn maintain_upnp_mappings<T: EthSpec>(
     config: UPnPConfig,
+    network_globals: Arc<NetworkGlobals<T>>,
     network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
     log: slog::Logger,
 ) {
     info!(log, "UPnP Attempting to initialise routes");
-    match igd::search_gateway(Default::default()) {
-        Err(e) => info!(log, "UPnP not available"; "error" => %e),
-        Ok(gateway) => {
-            // Need to find the local listening address matched with the router subnet
-            let interfaces = match get_if_addrs() {
-                Ok(v) => v,
-                Err(e) => {
-                    info!(log, "UPnP failed to get local interfaces"; "error" => %e);
-                    return;
-                }
-            };
-            let local_ip = interfaces.iter().find_map(|interface| {
-                // Just use the first IP of the first interface that is not a loopback and not an
-                // ipv6 address.
-                if !interface.is_loopback() {
-                    interface.ip().is_ipv4().then(|| interface.ip())
-                } else {
-                    None
-                }
-            });
-
-            let local_ip = match local_ip {
-                None => {
-                    info!(log, "UPnP failed to find local IP address");
-                    return;
-                }
-                Some(v) => v,
-            };
-
-            debug!(log, "UPnP Local IP Discovered"; "ip" => ?local_ip);
-
-            let mut mappings = EstablishedUPnPMappings::default();
-
-            match local_ip {
-                IpAddr::V4(address) => {
-                    let libp2p_socket = SocketAddrV4::new(address, config.tcp_port);
-                    let external_ip = gateway.get_external_ip();
-                    // We add specific port mappings rather than getting the router to arbitrary assign
-                    // one.
-                    // I've found this to be more reliable. If multiple users are behind a single
-                    // router, they should ideally try to set different port numbers.
-                    mappings.tcp_port = add_port_mapping(
-                        &gateway,
-                        igd::PortMappingProtocol::TCP,
-                        libp2p_socket,
-                        "tcp",
-                        &log,
-                    ).map(|_| {
-                        let external_socket = external_ip.as_ref().map(|ip| SocketAddr::new((*ip).into(), config.tcp_port)).map_err(|_| ());
-                        info!(log, "UPnP TCP route established"; "external_socket" => format!("{}:{}", external_socket.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), config.tcp_port));
-                        config.tcp_port
-                    }).ok();
-
-                    let set_udp_mapping = |udp_port| {
-                        let udp_socket = SocketAddrV4::new(address, udp_port);
-                        add_port_mapping(
-                            &gateway,
-                            igd::PortMappingProtocol::UDP,
-                            udp_socket,
-                            "udp",
-                            &log,
-                        ).map(|_| {
-                            info!(log, "UPnP UDP route established"; "external_socket" => format!("{}:{}", external_ip.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), udp_port));
-                        })
-                    };
-
-                    // Set the discovery UDP port mapping
-                    if !config.disable_discovery && set_udp_mapping(config.disc_port).is_ok() {
-                        mappings.udp_disc_port = Some(config.disc_port);
-                    }
+    let mut mapper = UPnPMapper::new(config, log.clone());
+    *network_globals.upnp_status.write() = Some(mapper.status().clone());
 
-                    // Set the quic UDP port mapping
-                    if !config.disable_quic_support && set_udp_mapping(config.quic_port).is_ok() {
-                        mappings.udp_quic_port = Some(config.quic_port);
-                    }
+    loop {
+        let previous_mappings = mapper.mappings().clone();
 
-                    // report any updates to the network service.
-                    if mappings.is_some() {
-                        network_send.send(NetworkMessage::UPnPMappingEstablished{ mappings })
-                .unwrap_or_else(|e| debug!(log, "Could not send message to the network service"; "error" => %e));
-                    }
-                }
-                _ => debug!(log, "UPnP no routes constructed. IPv6 not supported"),
+        // The gateway is queried via blocking requests.
+        let (renewed_mapper, delay) = match tokio::task::spawn_blocking(move || {
+            let delay = mapper.renew_with_igd();
+            (mapper, delay)
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                warn!(log, "UPnP task failed"; "error" => %e);
+                return;
             }
+        };
+        mapper = renewed_mapper;
+
+        let status = mapper.status().clone();
+        update_upnp_metrics(&status);
+        *network_globals.upnp_status.write() = Some(status);
+
+        // Report any updates to the network service.
+        let mappings = mapper.mappings();
+        if mappings.is_some() && *mappings != previous_mappings {
+            network_send
+                .send(NetworkMessage::UPnPMappingEstablished {
+                    mappings: mappings.clone(),
+                })
+                .unwrap_or_else(
+                    |e| debug!(log, "Could not send message to the network service"; "error" => %e),
+                );
         }
-    };
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+fn update_upnp_metrics(status: &UPnPStatus) {
+    for (mapping, port) in [
+        ("tcp", status.tcp_port),
+        ("udp_quic", status.udp_quic_port),
+        ("udp_disc", status.udp_disc_port),
+    ] {
+        metrics::set_gauge_vec(
+            &metrics::UPNP_MAPPED_PORT,
+            &[mapping],
+            port.map_or(0, i64::from),
+        );
+    }
+    if let Some(last_renewal) = status.last_renewal {
+        metrics::set_gauge(&metrics::UPNP_LAST_RENEWAL, last_renewal as i64);
+    }
+    metrics::set_gauge(
+        &metrics::UPNP_CONSECUTIVE_FAILURES,
+        i64::from(status.consecutive_failures),
+    );
 }
 
-/// Sets up a port mapping for a protocol returning the mapped port if successful.
-fn add_port_mapping(
-    gateway: &igd::Gateway,
-    protocol: igd::PortMappingProtocol,
+/// Sets up a port mapping for a protocol.
+fn add_port_mapping<G: Gateway>(
+    gateway: &G,
+    protocol: PortMappingProtocol,
     socket: SocketAddrV4,
+    lease_duration: Duration,
     protocol_string: &'static str,
     log: &slog::Logger,
-) -> Result<(), ()> {
+) -> Result<(), String> {
     // We add specific port mappings rather than getting the router to arbitrary assign
     // one.
     // I've found this to be more reliable. If multiple users are behind a single
     // router, they should ideally try to set different port numbers.
     let mapping_string = &format!("lighthouse-{}", protocol_string);
     for _ in 0..2 {
-        match gateway.add_port(protocol, socket.port(), socket, 0, mapping_string) {
-            Err(e) => {
-                match e {
-                    igd::AddPortError::PortInUse => {
-                        // Try and remove and re-create
-                        debug!(log, "UPnP port in use, attempting to remap"; "protocol" => protocol_string, "port" => socket.port());
-                        match gateway.remove_port(protocol, socket.port()) {
-                            Ok(()) => {
-                                debug!(log, "UPnP Removed port mapping"; "protocol" => protocol_string,  "port" => socket.port())
-                            }
-                            Err(e) => {
-                                debug!(log, "UPnP Port remove failure"; "protocol" => protocol_string, "port" => socket.port(), "error" => %e);
-                                return Err(());
-                            }
-                        }
+        match gateway.map_port(protocol, socket, lease_duration, mapping_string) {
+            Err(AddPortError::PortInUse) => {
+                // Try and remove and re-create
+                debug!(log, "UPnP port in use, attempting to remap"; "protocol" => protocol_string, "port" => socket.port());
+                match gateway.unmap_port(protocol, socket.port()) {
+                    Ok(()) => {
+                        debug!(log, "UPnP Removed port mapping"; "protocol" => protocol_string,  "port" => socket.port())
                     }
-                    e => {
-                        info!(log, "UPnP TCP route not set"; "error" => %e);
-                        return Err(());
+                    Err(e) => {
+                        debug!(log, "UPnP Port remove failure"; "protocol" => protocol_string, "port" => socket.port(), "error" => &e);
+                        return Err(e);
                     }
                 }
             }
-            Ok(_) => {
+            Err(AddPortError::Other(e)) => {
+                debug!(log, "UPnP route not set"; "protocol" => protocol_string, "error" => &e);
+                return Err(e);
+            }
+            Ok(()) => {
                 return Ok(());
             }
         }
     }
-    Err(())
+    Err("port in use".to_string())
 }
 
 /// Removes the specified TCP and UDP port mappings.
@@ -203,25 +435,197 @@ pub fn remove_mappings(mappings: &EstablishedUPnPMappings, log: &slog::Logger) {
     if mappings.is_some() {
         debug!(log, "Removing UPnP port mappings");
         match igd::search_gateway(Default::default()) {
-            Ok(gateway) => {
-                if let Some(tcp_port) = mappings.tcp_port {
-                    match gateway.remove_port(igd::PortMappingProtocol::TCP, tcp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp_port),
-                        Err(e) => {
-                            debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp_port, "error" => %e)
-                        }
-                    }
-                }
-                for udp_port in mappings.udp_ports() {
-                    match gateway.remove_port(igd::PortMappingProtocol::UDP, *udp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed UDP port mapping"; "port" => udp_port),
-                        Err(e) => {
-                            debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp_port, "error" => %e)
-                        }
-                    }
-                }
-            }
+            Ok(gateway) => unmap_ports(&gateway, mappings, log),
             Err(e) => debug!(log, "UPnP failed to remove mappings"; "error" => %e),
         }
     }
 }
+
+/// Removes the specified TCP and UDP port mappings from `gateway`.
+fn unmap_ports<G: Gateway>(gateway: &G, mappings: &EstablishedUPnPMappings, log: &slog::Logger) {
+    if let Some(tcp_port) = mappings.tcp_port {
+        match gateway.unmap_port(PortMappingProtocol::TCP, tcp_port) {
+            Ok(()) => debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp_port),
+            Err(e) => {
+                debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp_port, "error" => e)
+            }
+        }
+    }
+    for udp_port in mappings.udp_ports() {
+        match gateway.unmap_port(PortMappingProtocol::UDP, *udp_port) {
+            Ok(()) => debug!(log, "UPnP Removed UDP port mapping"; "port" => udp_port),
+            Err(e) => {
+                debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp_port, "error" => e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+
+    /// A gateway whose mappings expire after their lease duration.
+    #[derive(Default)]
+    struct MockGateway {
+        /// The expiry of each mapping, keyed by whether it is TCP and the external port.
+        mappings: Mutex<HashMap<(bool, u16), Instant>>,
+        /// The error returned by `map_port`, if any.
+        error: Mutex<Option<String>>,
+    }
+
+    impl MockGateway {
+        fn is_mapped(&self, protocol: PortMappingProtocol, port: u16) -> bool {
+            self.mappings
+                .lock()
+                .get(&(is_tcp(protocol), port))
+                .map_or(false, |expiry| *expiry > Instant::now())
+        }
+
+        /// Simulates the gateway dropping all mappings, e.g. due to lease expiry.
+        fn expire_all(&self) {
+            self.mappings.lock().clear();
+        }
+    }
+
+    fn is_tcp(protocol: PortMappingProtocol) -> bool {
+        matches!(protocol, PortMappingProtocol::TCP)
+    }
+
+    impl Gateway for MockGateway {
+        fn external_ip(&self) -> Result<Ipv4Addr, String> {
+            Ok(Ipv4Addr::new(203, 0, 113, 1))
+        }
+
+        fn map_port(
+            &self,
+            protocol: PortMappingProtocol,
+            local_socket: SocketAddrV4,
+            lease_duration: Duration,
+            _description: &str,
+        ) -> Result<(), AddPortError> {
+            if let Some(e) = self.error.lock().clone() {
+                return Err(AddPortError::Other(e));
+            }
+            self.mappings.lock().insert(
+                (is_tcp(protocol), local_socket.port()),
+                Instant::now() + lease_duration,
+            );
+            Ok(())
+        }
+
+        fn unmap_port(&self, protocol: PortMappingProtocol, port: u16) -> Result<(), String> {
+            self.mappings
+                .lock()
+                .remove(&(is_tcp(protocol), port))
+                .map(|_| ())
+                .ok_or_else(|| "no such mapping".to_string())
+        }
+    }
+
+    fn config(lease_duration: Duration) -> UPnPConfig {
+        UPnPConfig {
+            tcp_port: 9000,
+            disc_port: 9000,
+            quic_port: 9001,
+            disable_discovery: false,
+            disable_quic_support: false,
+            lease_duration,
+            renewal_interval: lease_duration / 2,
+        }
+    }
+
+    fn mapper(lease_duration: Duration) -> UPnPMapper {
+        UPnPMapper::new(config(lease_duration), logging::test_logger())
+    }
+
+    const LOCAL_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 0, 2);
+
+    #[test]
+    fn renews_expired_mappings() {
+        let gateway = MockGateway::default();
+        let mut mapper = mapper(UPNP_LEASE_DURATION);
+
+        let delay = mapper.renew(&gateway, LOCAL_IP);
+        assert_eq!(delay, UPNP_LEASE_DURATION / 2);
+        assert!(gateway.is_mapped(PortMappingProtocol::TCP, 9000));
+        assert!(gateway.is_mapped(PortMappingProtocol::UDP, 9000));
+        assert!(gateway.is_mapped(PortMappingProtocol::UDP, 9001));
+        assert_eq!(
+            mapper.mappings(),
+            &EstablishedUPnPMappings {
+                tcp_port: Some(9000),
+                udp_quic_port: Some(9001),
+                udp_disc_port: Some(9000),
+            }
+        );
+        let status = mapper.status().clone();
+        assert_eq!(status.external_ip, Some(Ipv4Addr::new(203, 0, 113, 1)));
+        assert!(status.last_renewal.is_some());
+        assert!(status.last_error.is_none());
+
+        // The lease expires on the gateway and the next renewal restores the mappings.
+        gateway.expire_all();
+        assert!(!gateway.is_mapped(PortMappingProtocol::TCP, 9000));
+        mapper.renew(&gateway, LOCAL_IP);
+        assert!(gateway.is_mapped(PortMappingProtocol::TCP, 9000));
+        assert!(gateway.is_mapped(PortMappingProtocol::UDP, 9001));
+        assert_eq!(mapper.status().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn failures_back_off_and_expire_mappings() {
+        let lease_duration = Duration::from_millis(50);
+        let gateway = MockGateway::default();
+        let mut mapper = mapper(lease_duration);
+        mapper.renew(&gateway, LOCAL_IP);
+        assert!(mapper.mappings().is_some());
+
+        *gateway.error.lock() = Some("gateway unreachable".to_string());
+
+        // The mappings are retained whilst their lease may still be valid.
+        let first_delay = mapper.renewal_failed("gateway unreachable".to_string());
+        assert!(mapper.mappings().is_some());
+        assert_eq!(mapper.status().consecutive_failures, 1);
+        assert_eq!(
+            mapper.status().last_error.as_deref(),
+            Some("gateway unreachable")
+        );
+
+        std::thread::sleep(lease_duration);
+        let second_delay = mapper.renew(&gateway, LOCAL_IP);
+        assert!(!mapper.mappings().is_some());
+        assert_eq!(mapper.status().tcp_port, None);
+        assert_eq!(mapper.status().consecutive_failures, 2);
+        assert!(first_delay <= second_delay);
+        assert!(second_delay <= lease_duration / 2);
+
+        // The gateway recovers and the mappings are re-established.
+        *gateway.error.lock() = None;
+        mapper.renew(&gateway, LOCAL_IP);
+        assert!(mapper.mappings().is_some());
+        assert_eq!(mapper.status().consecutive_failures, 0);
+        assert!(mapper.status().last_error.is_none());
+    }
+
+    #[test]
+    fn retry_delay_is_exponential_and_bounded() {
+        let renewal_interval = UPNP_LEASE_DURATION / 2;
+        assert_eq!(retry_delay(1, renewal_interval), INITIAL_RETRY_DELAY);
+        assert_eq!(retry_delay(2, renewal_interval), INITIAL_RETRY_DELAY * 2);
+        assert_eq!(retry_delay(3, renewal_interval), INITIAL_RETRY_DELAY * 4);
+        assert_eq!(retry_delay(u32::MAX, renewal_interval), renewal_interval);
+    }
+
+    #[test]
+    fn shutdown_removes_mappings() {
+        let gateway = MockGateway::default();
+        let mut mapper = mapper(UPNP_LEASE_DURATION);
+        mapper.renew(&gateway, LOCAL_IP);
+
+        unmap_ports(&gateway, mapper.mappings(), &logging::test_logger());
+        assert!(gateway.mappings.lock().is_empty());
+    }
+}
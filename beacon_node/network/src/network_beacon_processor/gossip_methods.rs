@@ -41,8 +41,8 @@ use types::{
 
 use beacon_processor::{
     work_reprocessing_queue::{
-        QueuedAggregate, QueuedGossipBlock, QueuedLightClientUpdate, QueuedUnaggregate,
-        ReprocessQueueMessage,
+        QueuedAggregate, QueuedGossipBlobSidecar, QueuedGossipBlock, QueuedLightClientUpdate,
+        QueuedUnaggregate, ReprocessQueueMessage,
     },
     DuplicateCache, GossipAggregatePackage, GossipAttestationPackage,
 };
@@ -150,6 +150,13 @@ impl<T: EthSpec> FailedAtt<T> {
             FailedAtt::Aggregate { attestation, .. } => &attestation.message.aggregate,
         }
     }
+
+    pub fn seen_timestamp(&self) -> Duration {
+        match self {
+            FailedAtt::Unaggregate { seen_timestamp, .. }
+            | FailedAtt::Aggregate { seen_timestamp, .. } => *seen_timestamp,
+        }
+    }
 }
 
 impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
@@ -165,6 +172,27 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         })
     }
 
+    /// Rejects an attestation which failed verification, recording it in the gossip rejection
+    /// buffer.
+    ///
+    /// The rejection must be recorded before the validation result is reported, after which the
+    /// raw message is discarded.
+    fn reject_attestation(
+        &self,
+        message_id: MessageId,
+        peer_id: PeerId,
+        failed_att: &FailedAtt<T::EthSpec>,
+        error: &AttnError,
+    ) {
+        self.network_globals.gossip_rejections.record(
+            &message_id,
+            peer_id,
+            format!("{:?}", error),
+            failed_att.seen_timestamp(),
+        );
+        self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+    }
+
     /// Send a message on `message_tx` that the `message_id` sent by `peer_id` should be propagated on
     /// the gossip network.
     ///
@@ -605,9 +633,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         self: &Arc<Self>,
         message_id: MessageId,
         peer_id: PeerId,
-        _peer_client: Client,
+        peer_client: Client,
         blob_index: u64,
         signed_blob: SignedBlobSidecar<T::EthSpec>,
+        reprocess_tx: Option<mpsc::Sender<ReprocessQueueMessage>>,
         seen_duration: Duration,
     ) {
         let slot = signed_blob.message.slot;
@@ -617,6 +646,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         // Log metrics to track delay from other nodes on the network.
         metrics::observe_duration(&metrics::BEACON_BLOB_GOSSIP_SLOT_START_DELAY_TIME, delay);
         metrics::set_gauge(&metrics::BEACON_BLOB_LAST_DELAY, delay.as_millis() as i64);
+        // Keep a copy of the blob sidecar in case it arrived early and has to be queued.
+        let early_blob = reprocess_tx.as_ref().map(|_| signed_blob.clone());
         match self
             .chain
             .verify_blob_sidecar_for_gossip(signed_blob, blob_index)
@@ -697,6 +728,75 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                             MessageAcceptance::Reject,
                         );
                     }
+                    // Like blocks, blob sidecars are sent slightly early by peers with fast
+                    // clocks. If the slot starts within a slot duration, verify the blob sidecar
+                    // once the slot arrives instead of penalizing the peer.
+                    GossipBlobError::FutureSlot { message_slot, .. }
+                        if self
+                            .chain
+                            .slot_clock
+                            .duration_to_slot(message_slot)
+                            .map_or(false, |duration| {
+                                duration <= self.chain.slot_clock.slot_duration()
+                            }) =>
+                    {
+                        if let (Some(sender), Some(early_blob)) = (reprocess_tx, early_blob) {
+                            debug!(
+                                self.log,
+                                "Gossip blob arrived early";
+                                "action" => "queuing for verification at slot start",
+                                "slot" => %slot,
+                                "root" => %root,
+                                "index" => %index
+                            );
+                            metrics::inc_counter(
+                                &metrics::BEACON_PROCESSOR_GOSSIP_BLOB_REQUEUED_TOTAL,
+                            );
+                            let msg =
+                                ReprocessQueueMessage::EarlyBlobSidecar(QueuedGossipBlobSidecar {
+                                    slot,
+                                    block_root: root,
+                                    index,
+                                    // Do not queue this blob sidecar again.
+                                    process_fn: self.gossip_blob_sidecar_process_fn(
+                                        message_id.clone(),
+                                        peer_id,
+                                        peer_client,
+                                        blob_index,
+                                        early_blob,
+                                        None,
+                                        seen_duration,
+                                    ),
+                                });
+                            if sender.try_send(msg).is_err() {
+                                error!(
+                                    self.log,
+                                    "Failed to defer blob verification";
+                                    "slot" => %slot,
+                                    "root" => %root,
+                                    "index" => %index
+                                );
+                                self.propagate_validation_result(
+                                    message_id,
+                                    peer_id,
+                                    MessageAcceptance::Ignore,
+                                );
+                            }
+                        } else {
+                            debug!(
+                                self.log,
+                                "Ignoring early blob sidecar which has already been queued";
+                                "slot" => %slot,
+                                "root" => %root,
+                                "index" => %index
+                            );
+                            self.propagate_validation_result(
+                                message_id,
+                                peer_id,
+                                MessageAcceptance::Ignore,
+                            );
+                        }
+                    }
                     GossipBlobError::FutureSlot { .. }
                     | GossipBlobError::BeaconChainError(_)
                     | GossipBlobError::RepeatBlob { .. }
@@ -1934,7 +2034,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1948,7 +2048,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  * This is forbidden by the p2p spec. Reject the message.
                  *
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1969,7 +2069,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -1990,7 +2090,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2070,7 +2170,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "block" => ?beacon_block_root,
                     "type" => ?attestation_type,
                 );
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2185,7 +2285,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2199,7 +2299,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2212,7 +2312,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2225,7 +2325,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2238,7 +2338,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2255,7 +2355,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "expected" => ?expected,
                     "received" => ?received,
                 );
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2268,7 +2368,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2281,7 +2381,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2294,7 +2394,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                  *
                  * The peer has published an invalid consensus message.
                  */
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::LowToleranceError,
@@ -2318,7 +2418,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 );
                 // In this case we wish to penalize gossipsub peers that do this to avoid future
                 // attestations that have too many skip slots.
-                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                self.reject_attestation(message_id, peer_id, &failed_att, &error);
                 self.gossip_penalize_peer(
                     peer_id,
                     PeerAction::MidToleranceError,
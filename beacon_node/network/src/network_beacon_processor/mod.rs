@@ -8,8 +8,8 @@ use beacon_chain::{
 };
 use beacon_chain::{BeaconChainTypes, NotifyExecutionLayer};
 use beacon_processor::{
-    work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorChannels, BeaconProcessorSend,
-    DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, Work,
+    work_reprocessing_queue::ReprocessQueueMessage, AsyncFn, BeaconProcessorChannels,
+    BeaconProcessorSend, DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, Work,
     WorkEvent as BeaconWorkEvent,
 };
 use environment::null_logger;
@@ -215,8 +215,38 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         blob: SignedBlobSidecar<T::EthSpec>,
         seen_timestamp: Duration,
     ) -> Result<(), Error<T::EthSpec>> {
+        let process_fn = self.gossip_blob_sidecar_process_fn(
+            message_id,
+            peer_id,
+            peer_client,
+            blob_index,
+            blob,
+            Some(self.reprocess_tx.clone()),
+            seen_timestamp,
+        );
+
+        self.try_send(BeaconWorkEvent {
+            drop_during_sync: false,
+            work: Work::GossipSignedBlobSidecar(process_fn),
+        })
+    }
+
+    /// Returns the work which verifies a gossip blob sidecar. If `reprocess_tx` is provided, a blob
+    /// sidecar which arrives shortly before its slot is queued for verification once the slot
+    /// arrives.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn gossip_blob_sidecar_process_fn(
+        self: &Arc<Self>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        peer_client: Client,
+        blob_index: u64,
+        blob: SignedBlobSidecar<T::EthSpec>,
+        reprocess_tx: Option<mpsc::Sender<ReprocessQueueMessage>>,
+        seen_timestamp: Duration,
+    ) -> AsyncFn {
         let processor = self.clone();
-        let process_fn = async move {
+        Box::pin(async move {
             processor
                 .process_gossip_blob(
                     message_id,
@@ -224,14 +254,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     peer_client,
                     blob_index,
                     blob,
+                    reprocess_tx,
                     seen_timestamp,
                 )
                 .await
-        };
-
-        self.try_send(BeaconWorkEvent {
-            drop_during_sync: false,
-            work: Work::GossipSignedBlobSidecar(Box::pin(process_fn)),
         })
     }
 
@@ -694,6 +720,37 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 }
 
+impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
+    /// Instantiates a version of `Self` which sends work to an existing beacon processor, but
+    /// discards any messages for the network and sync services. This is useful for driving gossip
+    /// messages through the beacon processor in the tests of other crates.
+    pub fn for_testing(
+        chain: Arc<BeaconChain<T>>,
+        beacon_processor_send: BeaconProcessorSend<T::EthSpec>,
+        reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
+        executor: TaskExecutor,
+        log: Logger,
+    ) -> Self {
+        let (network_tx, _network_rx) = mpsc::unbounded_channel();
+        let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
+        let network_globals = Arc::new(NetworkGlobals::new_test_globals(vec![], &log));
+
+        Self {
+            beacon_processor_send,
+            duplicate_cache: DuplicateCache::default(),
+            chain,
+            network_tx,
+            sync_tx,
+            reprocess_tx,
+            network_globals,
+            invalid_block_storage: InvalidBlockStorage::Disabled,
+            delayed_lookup_peers: Mutex::new(LruCache::new(DELAYED_PEER_CACHE_SIZE)),
+            executor,
+            log,
+        }
+    }
+}
+
 type TestBeaconChainType<E> =
     Witness<ManualSlotClock, CachingEth1Backend<E>, E, MemoryStore<E>, MemoryStore<E>>;
 
@@ -710,6 +767,7 @@ impl<E: EthSpec> NetworkBeaconProcessor<TestBeaconChainType<E>> {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx: _work_reprocessing_rx,
+            ..
         } = <_>::default();
 
         let (network_tx, _network_rx) = mpsc::unbounded_channel();
@@ -122,6 +122,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     head_root: status.head_root,
                     finalized_epoch: status.finalized_epoch,
                     finalized_root: status.finalized_root,
+                    earliest_available_slot: status.earliest_available_slot,
                 };
                 self.send_sync_message(SyncMessage::AddPeer(peer_id, info));
             }
@@ -295,30 +296,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         request: LightClientBootstrapRequest,
     ) {
         let block_root = request.root;
-        let state_root = match self.chain.get_blinded_block(&block_root) {
-            Ok(signed_block) => match signed_block {
-                Some(signed_block) => signed_block.state_root(),
-                None => {
-                    self.send_error_response(
-                        peer_id,
-                        RPCResponseErrorCode::ResourceUnavailable,
-                        "Bootstrap not available".into(),
-                        request_id,
-                    );
-                    return;
-                }
-            },
-            Err(_) => {
-                self.send_error_response(
-                    peer_id,
-                    RPCResponseErrorCode::ResourceUnavailable,
-                    "Bootstrap not available".into(),
-                    request_id,
-                );
-                return;
-            }
-        };
-        let mut beacon_state = match self.chain.get_state(&state_root, None) {
+        let mut beacon_state = match self.chain.store.get_block_state(&block_root) {
             Ok(beacon_state) => match beacon_state {
                 Some(state) => state,
                 None => {
@@ -393,10 +371,30 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             );
         }
 
-        let forwards_block_root_iter = match self
-            .chain
-            .forwards_iter_block_roots(Slot::from(*req.start_slot()))
-        {
+        // Blocks prior to the earliest available slot are not stored, e.g. because the node was
+        // checkpoint synced. Rather than failing, the request is clamped to the available blocks,
+        // which we advertise in our status.
+        let request_end_slot = Slot::new(req.start_slot().saturating_add(*req.count()));
+        let start_slot = std::cmp::max(
+            Slot::new(*req.start_slot()),
+            self.chain.store.get_oldest_block_slot(),
+        );
+        if start_slot >= request_end_slot {
+            debug!(self.log, "Range request is prior to the earliest available slot";
+                "peer" => %peer_id,
+                "start_slot" => req.start_slot(),
+                "count" => req.count(),
+                "earliest_available_slot" => start_slot,
+            );
+            // send the stream terminator
+            return self.send_network_message(NetworkMessage::SendResponse {
+                peer_id,
+                response: Response::BlocksByRange(None),
+                id: request_id,
+            });
+        }
+
+        let forwards_block_root_iter = match self.chain.forwards_iter_block_roots(start_slot) {
             Ok(iter) => iter,
             Err(BeaconChainError::HistoricalBlockError(
                 HistoricalBlockError::BlockOutOfRange {
@@ -433,20 +431,18 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         // Pick out the required blocks, ignoring skip-slots.
         let mut last_block_root = None;
         let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
-            iter.take_while(|(_, slot)| {
-                slot.as_u64() < req.start_slot().saturating_add(*req.count())
-            })
-            // map skip slots to None
-            .map(|(root, _)| {
-                let result = if Some(root) == last_block_root {
-                    None
-                } else {
-                    Some(root)
-                };
-                last_block_root = Some(root);
-                result
-            })
-            .collect::<Vec<Option<Hash256>>>()
+            iter.take_while(|(_, slot)| *slot < request_end_slot)
+                // map skip slots to None
+                .map(|(root, _)| {
+                    let result = if Some(root) == last_block_root {
+                        None
+                    } else {
+                        Some(root)
+                    };
+                    last_block_root = Some(root);
+                    result
+                })
+                .collect::<Vec<Option<Hash256>>>()
         });
 
         let block_roots = match maybe_block_roots {
@@ -625,7 +621,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             );
         }
 
-        let request_start_slot = Slot::from(req.start_slot);
+        let request_end_slot = Slot::new(req.start_slot.saturating_add(req.count));
 
         let data_availability_boundary_slot = match self.chain.data_availability_boundary() {
             Some(boundary) => boundary.start_slot(T::EthSpec::slots_per_epoch()),
@@ -647,30 +643,35 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             .get_blob_info()
             .oldest_blob_slot
             .unwrap_or(data_availability_boundary_slot);
-        if request_start_slot < oldest_blob_slot {
+        if Slot::new(req.start_slot) < oldest_blob_slot {
             debug!(
                 self.log,
                 "Range request start slot is older than data availability boundary.";
-                "requested_slot" => request_start_slot,
+                "requested_slot" => req.start_slot,
                 "oldest_blob_slot" => oldest_blob_slot,
                 "data_availability_boundary" => data_availability_boundary_slot
             );
 
-            return if data_availability_boundary_slot < oldest_blob_slot {
-                self.send_error_response(
+            if data_availability_boundary_slot < oldest_blob_slot {
+                return self.send_error_response(
                     peer_id,
                     RPCResponseErrorCode::ResourceUnavailable,
                     "blobs pruned within boundary".into(),
                     request_id,
-                )
-            } else {
-                self.send_error_response(
-                    peer_id,
-                    RPCResponseErrorCode::InvalidRequest,
-                    "Req outside availability period".into(),
-                    request_id,
-                )
-            };
+                );
+            }
+        }
+
+        // Blobs outside of the data availability period are not stored, so the request is clamped
+        // to the blobs which are available.
+        let request_start_slot = std::cmp::max(Slot::new(req.start_slot), oldest_blob_slot);
+        if request_start_slot >= request_end_slot {
+            // send the stream terminator
+            return self.send_network_message(NetworkMessage::SendResponse {
+                peer_id,
+                response: Response::BlobsByRange(None),
+                id: request_id,
+            });
         }
 
         let forwards_block_root_iter =
@@ -710,16 +711,20 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         // Use `WhenSlotSkipped::Prev` to get the most recent block root prior to
         // `request_start_slot` in order to check whether the `request_start_slot` is a skip.
-        let mut last_block_root = req.start_slot.checked_sub(1).and_then(|prev_slot| {
-            self.chain
-                .block_root_at_slot(Slot::new(prev_slot), WhenSlotSkipped::Prev)
-                .ok()
-                .flatten()
-        });
+        let mut last_block_root =
+            request_start_slot
+                .as_u64()
+                .checked_sub(1)
+                .and_then(|prev_slot| {
+                    self.chain
+                        .block_root_at_slot(Slot::new(prev_slot), WhenSlotSkipped::Prev)
+                        .ok()
+                        .flatten()
+                });
 
         // Pick out the required blocks, ignoring skip-slots.
         let maybe_block_roots = process_results(forwards_block_root_iter, |iter| {
-            iter.take_while(|(_, slot)| slot.as_u64() < req.start_slot.saturating_add(req.count))
+            iter.take_while(|(_, slot)| *slot < request_end_slot)
                 // map skip slots to None
                 .map(|(root, _)| {
                     let result = if Some(root) == last_block_root {
@@ -6,7 +6,9 @@ use crate::{
     network_beacon_processor::{
         ChainSegmentProcessId, DuplicateCache, InvalidBlockStorage, NetworkBeaconProcessor,
     },
+    replay_gossip_message,
     service::NetworkMessage,
+    status::status_message,
     sync::{manager::BlockProcessType, SyncMessage},
 };
 use beacon_chain::block_verification_types::RpcBlock;
@@ -16,13 +18,13 @@ use beacon_chain::test_utils::{
 use beacon_chain::{BeaconChain, WhenSlotSkipped};
 use beacon_processor::{work_reprocessing_queue::*, *};
 use lighthouse_network::discovery::ConnectionId;
-use lighthouse_network::rpc::methods::BlobsByRangeRequest;
+use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlocksByRangeRequest};
 use lighthouse_network::rpc::SubstreamId;
 use lighthouse_network::{
     discv5::enr::{CombinedKey, EnrBuilder},
     rpc::methods::{MetaData, MetaDataV2},
-    types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
-    Client, MessageId, NetworkGlobals, PeerId, Response,
+    types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield, GossipEncoding, GossipTopic},
+    Client, MessageId, NetworkConfig, NetworkGlobals, PeerId, PubsubMessage, Response, TopicHash,
 };
 use lru::LruCache;
 use parking_lot::Mutex;
@@ -30,6 +32,7 @@ use slot_clock::SlotClock;
 use std::iter::Iterator;
 use std::sync::Arc;
 use std::time::Duration;
+use store::AnchorInfo;
 use tokio::sync::mpsc;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::{
@@ -193,6 +196,8 @@ impl TestRig {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx,
+            snapshot_rx,
+            ..
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
         let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
@@ -205,7 +210,18 @@ impl TestRig {
         });
         let enr_key = CombinedKey::generate_secp256k1();
         let enr = EnrBuilder::new("v4").build(&enr_key).unwrap();
-        let network_globals = Arc::new(NetworkGlobals::new(enr, meta_data, vec![], false, &log));
+        let network_config = NetworkConfig {
+            gossip_rejection_buffer_size: 16,
+            ..NetworkConfig::default()
+        };
+        let network_globals = Arc::new(NetworkGlobals::new(
+            enr,
+            meta_data,
+            vec![],
+            false,
+            Arc::new(network_config),
+            &log,
+        ));
 
         let executor = harness.runtime.task_executor.clone();
 
@@ -238,6 +254,7 @@ impl TestRig {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx,
+            snapshot_rx,
             Some(work_journal_tx),
             harness.chain.slot_clock.clone(),
             chain.spec.maximum_gossip_clock_disparity(),
@@ -358,6 +375,57 @@ impl TestRig {
             .unwrap();
     }
 
+    pub fn enqueue_blocks_by_range_request(&self, start_slot: u64, count: u64) {
+        self.network_beacon_processor
+            .send_blocks_by_range_request(
+                PeerId::random(),
+                (ConnectionId::new_unchecked(42), SubstreamId::new(24)),
+                BlocksByRangeRequest::new(start_slot, count),
+            )
+            .unwrap();
+    }
+
+    /// Makes the blocks prior to `slot` unavailable, as if the node was checkpoint synced at
+    /// `slot` and hasn't backfilled.
+    pub fn set_earliest_available_slot(&self, slot: Slot) {
+        let oldest_block_parent = self
+            .chain
+            .block_root_at_slot(slot - 1, WhenSlotSkipped::Prev)
+            .unwrap()
+            .unwrap();
+        let anchor = AnchorInfo {
+            anchor_slot: slot,
+            oldest_block_slot: slot,
+            oldest_block_parent,
+            state_upper_limit: slot,
+            state_lower_limit: Slot::new(0),
+        };
+        self.chain
+            .store
+            .compare_and_set_anchor_info_with_write(None, Some(anchor))
+            .unwrap();
+    }
+
+    /// Receives the blocks of a `BlocksByRange` response up to its stream terminator, panicking on
+    /// an error response.
+    pub async fn receive_blocks_by_range(&mut self) -> Vec<Slot> {
+        let mut slots = vec![];
+        while let Some(next) = self._network_rx.recv().await {
+            match next {
+                NetworkMessage::SendResponse {
+                    response: Response::BlocksByRange(Some(block)),
+                    ..
+                } => slots.push(block.slot()),
+                NetworkMessage::SendResponse {
+                    response: Response::BlocksByRange(None),
+                    ..
+                } => break,
+                other => panic!("unexpected message {:?}", other),
+            }
+        }
+        slots
+    }
+
     pub fn enqueue_backfill_batch(&self) {
         self.network_beacon_processor
             .send_chain_segment(
@@ -649,6 +717,107 @@ async fn import_gossip_block_unacceptably_early() {
     );
 }
 
+/// Blob sidecars within the gossip clock disparity of their slot should be verified straight away.
+#[tokio::test]
+async fn import_gossip_blob_acceptably_early() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    let slot_start = rig
+        .chain
+        .slot_clock
+        .start_of(rig.next_block.slot())
+        .unwrap();
+
+    rig.chain
+        .slot_clock
+        .set_current_time(slot_start - rig.chain.spec.maximum_gossip_clock_disparity());
+
+    let num_blobs = rig.next_blobs.as_ref().map(|b| b.len()).unwrap_or(0);
+    for i in 0..num_blobs {
+        rig.enqueue_gossip_blob(i);
+        rig.assert_event_journal(&[GOSSIP_BLOBS_SIDECAR, WORKER_FREED, NOTHING_TO_DO])
+            .await;
+    }
+
+    // No blob sidecar should have been queued for later verification.
+    rig.assert_no_events_for(Duration::from_secs(1)).await;
+}
+
+/// Blob sidecars that are earlier than the gossip clock disparity, but by less than a slot, should
+/// be queued and verified once their slot arrives.
+#[tokio::test]
+async fn import_gossip_blob_queued_until_slot() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    let slot_start = rig
+        .chain
+        .slot_clock
+        .start_of(rig.next_block.slot())
+        .unwrap();
+
+    rig.chain.slot_clock.set_current_time(
+        slot_start - rig.chain.spec.maximum_gossip_clock_disparity() - Duration::from_millis(1),
+    );
+
+    assert_eq!(
+        rig.chain.slot().unwrap(),
+        rig.next_block.slot() - 1,
+        "chain should be at the correct slot"
+    );
+
+    let num_blobs = rig.next_blobs.as_ref().map(|b| b.len()).unwrap_or(0);
+    for i in 0..num_blobs {
+        rig.enqueue_gossip_blob(i);
+        rig.assert_event_journal(&[GOSSIP_BLOBS_SIDECAR, WORKER_FREED, NOTHING_TO_DO])
+            .await;
+    }
+
+    // The queued blob sidecars are verified once the slot arrives.
+    rig.chain.slot_clock.set_slot(rig.next_block.slot().into());
+    if num_blobs > 0 {
+        rig.assert_event_journal_contains_ordered(&vec![GOSSIP_BLOBS_SIDECAR; num_blobs])
+            .await;
+    }
+
+    // With its blobs already available, the block is imported as soon as it arrives.
+    rig.enqueue_gossip_block();
+    rig.assert_event_journal_contains_ordered(&[GOSSIP_BLOCK])
+        .await;
+
+    assert_eq!(
+        rig.head_root(),
+        rig.next_block.canonical_root(),
+        "block should be imported and become head"
+    );
+}
+
+/// Blob sidecars that are more than a slot early shouldn't get into the delay queue.
+#[tokio::test]
+async fn import_gossip_blob_unacceptably_early() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    let slot_start = rig
+        .chain
+        .slot_clock
+        .start_of(rig.next_block.slot())
+        .unwrap();
+
+    rig.chain.slot_clock.set_current_time(
+        slot_start - rig.chain.slot_clock.slot_duration() - Duration::from_millis(1),
+    );
+
+    let num_blobs = rig.next_blobs.as_ref().map(|b| b.len()).unwrap_or(0);
+    for i in 0..num_blobs {
+        rig.enqueue_gossip_blob(i);
+        rig.assert_event_journal(&[GOSSIP_BLOBS_SIDECAR, WORKER_FREED, NOTHING_TO_DO])
+            .await;
+    }
+
+    // Waiting for 5 seconds is a bit arbitrary, however it *should* be long enough to ensure the
+    // blob sidecars aren't queued.
+    rig.assert_no_events_for(Duration::from_secs(5)).await;
+}
+
 /// Blocks that arrive on-time should be processed normally.
 #[tokio::test]
 async fn import_gossip_block_at_current_slot() {
@@ -704,6 +873,94 @@ async fn import_gossip_attestation() {
     );
 }
 
+/// Ensure a rejected attestation is recorded and replays to the same error.
+#[tokio::test]
+async fn gossip_rejection_recorded_and_replayed() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    // Sign the attestation with the signature of another validator.
+    let (mut attestation, subnet_id) = rig.attestations[0].clone();
+    attestation.signature = rig.attestations[1].0.signature.clone();
+
+    // Retain the message as received, as the network service does.
+    let message = PubsubMessage::Attestation(Box::new((subnet_id, attestation.clone())));
+    let topic = GossipTopic::new(
+        message.kind(),
+        GossipEncoding::default(),
+        rig.chain.enr_fork_id().fork_digest,
+    );
+    let data = message.encode(GossipEncoding::default());
+    let gossip_rejections = &rig
+        .network_beacon_processor
+        .network_globals
+        .gossip_rejections;
+    gossip_rejections.track_pending(
+        &junk_message_id(),
+        &TopicHash::from_raw(topic.to_string()),
+        &data,
+    );
+
+    rig.network_beacon_processor
+        .send_unaggregated_attestation(
+            junk_message_id(),
+            junk_peer_id(),
+            attestation,
+            subnet_id,
+            true,
+            Duration::from_secs(0),
+        )
+        .unwrap();
+
+    rig.assert_event_journal(&[GOSSIP_ATTESTATION, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+
+    let rejections = rig
+        .network_beacon_processor
+        .network_globals
+        .gossip_rejections
+        .rejections();
+    assert_eq!(rejections.len(), 1);
+    let rejection = &rejections[0];
+    assert_eq!(rejection.reason, "InvalidSignature");
+    assert_eq!(rejection.data, data);
+
+    let replay_error = replay_gossip_message(&rig.chain, &rejection.topic, &rejection.data)
+        .expect("message should decode");
+    assert_eq!(replay_error.as_deref(), Some(rejection.reason.as_str()));
+}
+
+/// Ensure replaying a valid attestation doesn't cause a genuine copy received later to be ignored.
+#[tokio::test]
+async fn gossip_replay_is_dry_run() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+    let fork_digest = rig.chain.enr_fork_id().fork_digest;
+    let encode = |message: PubsubMessage<E>| {
+        let topic = GossipTopic::new(message.kind(), GossipEncoding::default(), fork_digest);
+        (topic.to_string(), message.encode(GossipEncoding::default()))
+    };
+
+    let (attestation, subnet_id) = rig.attestations[0].clone();
+    let (topic, data) = encode(PubsubMessage::Attestation(Box::new((
+        subnet_id,
+        attestation,
+    ))));
+    assert_eq!(replay_gossip_message(&rig.chain, &topic, &data), Ok(None));
+
+    let initial_attns = rig.chain.naive_aggregation_pool.read().num_items();
+    rig.enqueue_unaggregated_attestation();
+    rig.assert_event_journal(&[GOSSIP_ATTESTATION, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+    assert_eq!(
+        rig.chain.naive_aggregation_pool.read().num_items(),
+        initial_attns + 1,
+        "the attestation should be imported after its replay"
+    );
+
+    // Messages other than attestations and aggregates are rejected.
+    let (topic, data) = encode(PubsubMessage::BeaconBlock(rig.next_block.clone()));
+    assert!(replay_gossip_message(&rig.chain, &topic, &data).is_err());
+}
+
 enum BlockImportMethod {
     Gossip,
     Rpc,
@@ -1116,3 +1373,35 @@ async fn test_blobs_by_range() {
     }
     assert_eq!(blob_count, actual_count);
 }
+
+/// A node whose blocks are only available from a later slot, e.g. after checkpoint sync, should
+/// clamp range requests to the available blocks and terminate the stream without an error.
+#[tokio::test]
+async fn test_blocks_by_range_clamped_to_earliest_available_slot() {
+    let mut rig = TestRig::new(LONG_CHAIN).await;
+    let earliest_available_slot = Slot::new(SLOTS_PER_EPOCH);
+    rig.set_earliest_available_slot(earliest_available_slot);
+
+    assert_eq!(
+        status_message(&rig.chain).earliest_available_slot,
+        Some(earliest_available_slot)
+    );
+
+    // A request from genesis only receives the available blocks.
+    rig.enqueue_blocks_by_range_request(0, LONG_CHAIN);
+    let expected_slots = (earliest_available_slot.as_u64()..LONG_CHAIN)
+        .map(Slot::new)
+        .filter(|slot| {
+            rig.chain
+                .block_root_at_slot(*slot, WhenSlotSkipped::None)
+                .unwrap()
+                .is_some()
+        })
+        .collect::<Vec<_>>();
+    assert!(!expected_slots.is_empty());
+    assert_eq!(rig.receive_blocks_by_range().await, expected_slots);
+
+    // A request entirely prior to the earliest available slot is only terminated.
+    rig.enqueue_blocks_by_range_request(0, earliest_available_slot.as_u64());
+    assert_eq!(rig.receive_blocks_by_range().await, vec![]);
+}
@@ -491,7 +491,8 @@ impl<T: BeaconChainTypes> Router<T> {
                 SyncId::SingleBlock { .. }
                 | SyncId::SingleBlob { .. }
                 | SyncId::ParentLookup { .. }
-                | SyncId::ParentLookupBlob { .. } => {
+                | SyncId::ParentLookupBlob { .. }
+                | SyncId::BatchedBlobs { .. } => {
                     crit!(self.log, "Block lookups do not request BBRange requests"; "peer_id" => %peer_id);
                     return;
                 }
@@ -564,7 +565,9 @@ impl<T: BeaconChainTypes> Router<T> {
                     crit!(self.log, "Batch syncing do not request BBRoot requests"; "peer_id" => %peer_id);
                     return;
                 }
-                SyncId::SingleBlob { .. } | SyncId::ParentLookupBlob { .. } => {
+                SyncId::SingleBlob { .. }
+                | SyncId::ParentLookupBlob { .. }
+                | SyncId::BatchedBlobs { .. } => {
                     crit!(self.log, "Blob response to block by roots request"; "peer_id" => %peer_id);
                     return;
                 }
@@ -597,7 +600,9 @@ impl<T: BeaconChainTypes> Router<T> {
     ) {
         let request_id = match request_id {
             RequestId::Sync(sync_id) => match sync_id {
-                id @ (SyncId::SingleBlob { .. } | SyncId::ParentLookupBlob { .. }) => id,
+                id @ (SyncId::SingleBlob { .. }
+                | SyncId::ParentLookupBlob { .. }
+                | SyncId::BatchedBlobs { .. }) => id,
                 SyncId::SingleBlock { .. } | SyncId::ParentLookup { .. } => {
                     crit!(self.log, "Block response to blobs by roots request"; "peer_id" => %peer_id);
                     return;
@@ -15,6 +15,7 @@ use futures::channel::mpsc::Sender;
 use futures::future::OptionFuture;
 use futures::prelude::*;
 use futures::StreamExt;
+use lighthouse_network::discovery::{DiscoveryInfo, EnrUpdate};
 use lighthouse_network::service::Network;
 use lighthouse_network::types::GossipKind;
 use lighthouse_network::{prometheus_client::registry::Registry, MessageAcceptance};
@@ -24,18 +25,24 @@ use lighthouse_network::{
 };
 use lighthouse_network::{
     types::{core_topics_to_subscribe, GossipEncoding, GossipTopic},
-    MessageId, NetworkEvent, NetworkGlobals, PeerId,
+    Enr, MessageId, NetworkEvent, NetworkGlobals, PeerId,
 };
 use slog::{crit, debug, error, info, o, trace, warn};
-use std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration};
+use slot_clock::SlotClock;
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use store::HotColdDB;
 use strum::IntoStaticStr;
 use task_executor::ShutdownReason;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio::time::Sleep;
 use types::{
-    ChainSpec, EthSpec, ForkContext, Slot, SubnetId, SyncCommitteeSubscription, SyncSubnetId,
-    Unsigned, ValidatorSubscription,
+    ChainSpec, EthSpec, ForkContext, SignedBeaconBlock, Slot, SubnetId, SyncCommitteeSubscription,
+    SyncSubnetId, Unsigned, ValidatorSubscription,
 };
 
 mod tests;
@@ -43,7 +50,7 @@ mod tests;
 /// The interval (in seconds) that various network metrics will update.
 const METRIC_UPDATE_INTERVAL: u64 = 5;
 /// Number of slots before the fork when we should subscribe to the new fork topics.
-const SUBSCRIBE_DELAY_SLOTS: u64 = 2;
+pub const SUBSCRIBE_DELAY_SLOTS: u64 = 2;
 /// Delay after a fork where we unsubscribe from pre-fork topics.
 const UNSUBSCRIBE_DELAY_EPOCHS: u64 = 2;
 /// Size of the queue for validator subnet subscriptions. The number is chosen so that we may be
@@ -84,7 +91,13 @@ pub enum NetworkMessage<T: EthSpec> {
         id: PeerRequestId,
     },
     /// Publish a list of messages to the gossipsub protocol.
-    Publish { messages: Vec<PubsubMessage<T>> },
+    Publish {
+        messages: Vec<PubsubMessage<T>>,
+        /// When the messages were sent to the network service.
+        enqueued_at: Instant,
+        /// Whether the messages include a block proposed by one of our validators.
+        own_block: bool,
+    },
     /// Validates a received gossipsub message. This will propagate the message on the network.
     ValidationResult {
         /// The peer that sent us the message. We don't send back to this peer.
@@ -112,6 +125,35 @@ pub enum NetworkMessage<T: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Manually updates the address and ports of the local ENR, returning the updated ENR.
+    UpdateEnr {
+        update: EnrUpdate,
+        result_tx: oneshot::Sender<Result<Enr, String>>,
+    },
+    /// Requests a snapshot of the discovery routing table and query statistics.
+    DiscoveryInfo {
+        result_tx: oneshot::Sender<DiscoveryInfo>,
+    },
+}
+
+impl<T: EthSpec> NetworkMessage<T> {
+    /// Publish a list of messages to the gossipsub protocol.
+    pub fn publish(messages: Vec<PubsubMessage<T>>) -> Self {
+        NetworkMessage::Publish {
+            messages,
+            enqueued_at: Instant::now(),
+            own_block: false,
+        }
+    }
+
+    /// Publish a block proposed by one of our validators, reporting its delay into the slot.
+    pub fn publish_own_block(block: Arc<SignedBeaconBlock<T>>) -> Self {
+        NetworkMessage::Publish {
+            messages: vec![PubsubMessage::BeaconBlock(block)],
+            enqueued_at: Instant::now(),
+            own_block: true,
+        }
+    }
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -209,6 +251,8 @@ pub struct NetworkService<T: BeaconChainTypes> {
     gossipsub_parameter_update: tokio::time::Interval,
     /// enable_light_client_server indicator
     enable_light_client_server: bool,
+    /// Delay into the slot after which the publication of our own block is reported as late.
+    late_own_block_threshold: Duration,
     /// The logger for the network service.
     fork_context: Arc<ForkContext>,
     log: slog::Logger,
@@ -237,24 +281,6 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             "Backfill is disabled. DO NOT RUN IN PRODUCTION"
         );
 
-        // try and construct UPnP port mappings if required.
-        if let Some(upnp_config) = crate::nat::UPnPConfig::from_config(config) {
-            let upnp_log = network_log.new(o!("service" => "UPnP"));
-            let upnp_network_send = network_senders.network_send();
-            if config.upnp_enabled {
-                executor.spawn_blocking(
-                    move || {
-                        crate::nat::construct_upnp_mappings(
-                            upnp_config,
-                            upnp_network_send,
-                            upnp_log,
-                        )
-                    },
-                    "UPnP",
-                );
-            }
-        }
-
         // get a reference to the beacon chain store
         let store = beacon_chain.store.clone();
 
@@ -292,6 +318,23 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         let (mut libp2p, network_globals) =
             Network::new(executor.clone(), service_context, &network_log).await?;
 
+        // try and construct and maintain UPnP port mappings if required.
+        if let Some(upnp_config) = crate::nat::UPnPConfig::from_config(config) {
+            let upnp_log = network_log.new(o!("service" => "UPnP"));
+            let upnp_network_send = network_senders.network_send();
+            if config.upnp_enabled {
+                executor.spawn(
+                    crate::nat::maintain_upnp_mappings(
+                        upnp_config,
+                        network_globals.clone(),
+                        upnp_network_send,
+                        upnp_log,
+                    ),
+                    "UPnP",
+                );
+            }
+        }
+
         // Repopulate the DHT with stored ENR's if discovery is not disabled.
         if !config.disable_discovery {
             let enrs_to_load = load_dht::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
@@ -370,6 +413,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             fork_context,
             log: network_log,
             enable_light_client_server: config.enable_light_client_server,
+            late_own_block_threshold: config.late_own_block_threshold,
         };
 
         Ok((network_service, network_globals, network_senders))
@@ -668,7 +712,11 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     validation_result,
                 );
             }
-            NetworkMessage::Publish { messages } => {
+            NetworkMessage::Publish {
+                messages,
+                enqueued_at,
+                own_block,
+            } => {
                 let mut topic_kinds = Vec::new();
                 for message in &messages {
                     if !topic_kinds.contains(&message.kind()) {
@@ -681,7 +729,35 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     "count" => messages.len(),
                     "topics" => ?topic_kinds
                 );
-                self.libp2p.publish(messages);
+
+                let own_block_slot = if own_block {
+                    messages.iter().find_map(|message| match message {
+                        PubsubMessage::BeaconBlock(block) => Some(block.slot()),
+                        _ => None,
+                    })
+                } else {
+                    None
+                };
+
+                for message in messages {
+                    let kind = message.kind();
+                    let publish_start = Instant::now();
+                    self.libp2p.publish(vec![message]);
+                    metrics::observe_timer_vec(
+                        &metrics::NETWORK_PUBLISH_TIME,
+                        &[kind.as_ref()],
+                        publish_start.elapsed(),
+                    );
+                    metrics::observe_timer_vec(
+                        &metrics::NETWORK_PUBLISH_ENQUEUE_TO_PUBLISH_TIME,
+                        &[kind.as_ref()],
+                        enqueued_at.elapsed(),
+                    );
+                }
+
+                if let Some(slot) = own_block_slot {
+                    self.check_own_block_publish_delay(slot);
+                }
             }
             NetworkMessage::ReportPeer {
                 peer_id,
@@ -694,6 +770,18 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdateEnr { update, result_tx } => {
+                let result = self.libp2p.update_local_enr(&update);
+                if let Err(e) = &result {
+                    warn!(self.log, "Failed to update ENR"; "error" => e);
+                }
+                // The requester may have given up waiting for the result.
+                let _ = result_tx.send(result);
+            }
+            NetworkMessage::DiscoveryInfo { result_tx } => {
+                // The requester may have given up waiting for the result.
+                let _ = result_tx.send(self.libp2p.discovery_info());
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -852,6 +940,32 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Records the delay into the slot at which a block proposed by one of our validators was
+    /// published, warning if it exceeds the late block threshold.
+    fn check_own_block_publish_delay(&self, slot: Slot) {
+        let slot_clock = &self.beacon_chain.slot_clock;
+        let Some(delay) = slot_clock
+            .now_duration()
+            .zip(slot_clock.start_of(slot))
+            .map(|(now, slot_start)| now.saturating_sub(slot_start))
+        else {
+            return;
+        };
+
+        metrics::observe_duration(&metrics::OWN_BLOCK_PUBLISH_SLOT_START_DELAY_TIME, delay);
+        if delay > self.late_own_block_threshold {
+            metrics::inc_counter(&metrics::OWN_BLOCK_PUBLISHED_LATE_TOTAL);
+            warn!(
+                self.log,
+                "Own block published late";
+                "slot" => slot,
+                "delay" => ?delay,
+                "threshold" => ?self.late_own_block_threshold,
+                "info" => "the block may be orphaned, check the timing of block production"
+            );
+        }
+    }
+
     fn on_attestation_service_msg(&mut self, msg: SubnetServiceMessage) {
         match msg {
             SubnetServiceMessage::Subscribe(subnet) => {
@@ -1007,6 +1121,11 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
             ),
         }
 
+        // Persist peer scores and bans so that they are restored on restart, along with the
+        // peers to dial on startup.
+        self.libp2p.peer_manager().persist_peer_db();
+        self.libp2p.peer_manager().persist_peer_cache();
+
         // attempt to remove port mappings
         crate::nat::remove_mappings(&self.upnp_mappings, &self.log);
 
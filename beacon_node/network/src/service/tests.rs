@@ -1,19 +1,27 @@
 #[cfg(not(debug_assertions))]
 #[cfg(test)]
 mod tests {
+    use crate::metrics;
     use crate::persisted_dht::load_dht;
-    use crate::{NetworkConfig, NetworkService};
+    use crate::{NetworkConfig, NetworkMessage, NetworkService};
     use beacon_chain::test_utils::BeaconChainHarness;
-    use beacon_chain::BeaconChainTypes;
+    use beacon_chain::{BeaconChain, BeaconChainTypes};
     use beacon_processor::{BeaconProcessorChannels, BeaconProcessorConfig};
     use futures::StreamExt;
+    use lighthouse_network::discovery::{DiscoveryInfo, EnrUpdate};
     use lighthouse_network::types::{GossipEncoding, GossipKind};
-    use lighthouse_network::{Enr, GossipTopic};
+    use lighthouse_network::{Enr, EnrExt, GossipTopic};
     use slog::{o, Drain, Level, Logger};
     use sloggers::{null::NullLoggerBuilder, Build};
+    use slot_clock::SlotClock;
+    use std::net::Ipv4Addr;
+    use std::num::NonZeroU16;
     use std::str::FromStr;
     use std::sync::Arc;
+    use std::time::{Duration, Instant};
+    use tempfile::{tempdir, TempDir};
     use tokio::runtime::Runtime;
+    use tokio::sync::oneshot;
     use types::{Epoch, EthSpec, ForkName, MinimalEthSpec, SubnetId};
 
     impl<T: BeaconChainTypes> NetworkService<T> {
@@ -71,7 +79,9 @@ mod tests {
             shutdown_tx,
         );
 
+        let network_dir = tempdir().unwrap();
         let mut config = NetworkConfig::default();
+        config.network_dir = network_dir.path().into();
         config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21212, 21212, 21213);
         config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
         config.upnp_enabled = false;
@@ -85,6 +95,7 @@ mod tests {
                 beacon_processor_rx: _beacon_processor_rx,
                 work_reprocessing_tx,
                 work_reprocessing_rx: _work_reprocessing_rx,
+                ..
             } = <_>::default();
 
             let _network_service = NetworkService::start(
@@ -138,6 +149,7 @@ mod tests {
         assert_eq!(next_fork_name, ForkName::Capella);
 
         // Build network service.
+        let network_dir = tempdir().unwrap();
         let (mut network_service, network_globals, _network_senders) = runtime.block_on(async {
             let (_, exit) = exit_future::signal();
             let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
@@ -149,6 +161,7 @@ mod tests {
             );
 
             let mut config = NetworkConfig::default();
+            config.network_dir = network_dir.path().into();
             config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21214, 21214, 21215);
             config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
             config.upnp_enabled = false;
@@ -236,4 +249,305 @@ mod tests {
             .expect("topic score params");
         assert_eq!(0.0, old_topic_params2.topic_weight);
     }
+
+    #[test]
+    fn test_update_local_enr() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+
+        let beacon_chain = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build()
+            .chain;
+
+        let network_dir = tempdir().unwrap();
+        let (mut network_service, network_globals, _network_senders) = runtime.block_on(async {
+            let (_, exit) = exit_future::signal();
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = task_executor::TaskExecutor::new(
+                Arc::downgrade(&runtime),
+                exit,
+                get_logger(false),
+                shutdown_tx,
+            );
+
+            let mut config = NetworkConfig::default();
+            config.network_dir = network_dir.path().into();
+            config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21216, 21216, 21217);
+            config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
+            config.upnp_enabled = false;
+
+            let beacon_processor_channels =
+                BeaconProcessorChannels::new(&BeaconProcessorConfig::default());
+            NetworkService::build(
+                beacon_chain.clone(),
+                &config,
+                executor.clone(),
+                None,
+                beacon_processor_channels.beacon_processor_tx,
+                beacon_processor_channels.work_reprocessing_tx,
+            )
+            .await
+            .unwrap()
+        });
+
+        let old_seq = network_globals.local_enr().seq();
+
+        let result = runtime.block_on(async {
+            let (result_tx, result_rx) = oneshot::channel();
+            let (mut shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+            let update = EnrUpdate {
+                tcp_port: Some(21218),
+                quic_port: Some(21219),
+                ..Default::default()
+            };
+            network_service
+                .on_network_msg(
+                    NetworkMessage::UpdateEnr { update, result_tx },
+                    &mut shutdown_tx,
+                )
+                .await;
+            result_rx.await.unwrap()
+        });
+
+        let enr = result.expect("update should succeed");
+        assert!(enr.seq() > old_seq);
+        assert_eq!(enr.tcp4(), Some(21218));
+        assert_eq!(enr.quic4(), Some(21219));
+
+        // The update is visible to the rest of the node.
+        let local_enr = network_globals.local_enr();
+        assert_eq!(local_enr.seq(), enr.seq());
+        assert_eq!(local_enr.tcp4(), Some(21218));
+        assert_eq!(local_enr.quic4(), Some(21219));
+
+        // The address is maintained by discovery, so it can't be updated manually.
+        let result = runtime.block_on(async {
+            let (result_tx, result_rx) = oneshot::channel();
+            let (mut shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+            let update = EnrUpdate {
+                ip: Some(std::net::Ipv4Addr::new(10, 0, 0, 1).into()),
+                ..Default::default()
+            };
+            network_service
+                .on_network_msg(
+                    NetworkMessage::UpdateEnr { update, result_tx },
+                    &mut shutdown_tx,
+                )
+                .await;
+            result_rx.await.unwrap()
+        });
+        assert!(result.is_err());
+        assert_eq!(network_globals.local_enr().seq(), enr.seq());
+    }
+
+    #[test]
+    fn test_own_block_publish_metrics() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        harness.advance_slot();
+        let slot = harness.get_current_slot();
+        let ((block, _), _) =
+            runtime.block_on(harness.make_block(harness.get_current_state(), slot));
+
+        // Publish the block after the default late block threshold of 3s.
+        let slot_clock = &harness.chain.slot_clock;
+        slot_clock.set_current_time(slot_clock.start_of(slot).unwrap() + Duration::from_secs(4));
+
+        let network_dir = tempdir().unwrap();
+        let (mut network_service, _network_globals, network_senders) = runtime.block_on(async {
+            let (_, exit) = exit_future::signal();
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = task_executor::TaskExecutor::new(
+                Arc::downgrade(&runtime),
+                exit,
+                get_logger(false),
+                shutdown_tx,
+            );
+
+            let mut config = NetworkConfig::default();
+            config.network_dir = network_dir.path().into();
+            config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21220, 21220, 21221);
+            config.upnp_enabled = false;
+
+            let beacon_processor_channels =
+                BeaconProcessorChannels::new(&BeaconProcessorConfig::default());
+            NetworkService::build(
+                harness.chain.clone(),
+                &config,
+                executor.clone(),
+                None,
+                beacon_processor_channels.beacon_processor_tx,
+                beacon_processor_channels.work_reprocessing_tx,
+            )
+            .await
+            .unwrap()
+        });
+
+        let publish_count = || {
+            metrics::get_histogram(
+                &metrics::NETWORK_PUBLISH_ENQUEUE_TO_PUBLISH_TIME,
+                &["beacon_block"],
+            )
+            .unwrap()
+            .get_sample_count()
+        };
+        let late_count = || {
+            metrics::OWN_BLOCK_PUBLISHED_LATE_TOTAL
+                .as_ref()
+                .unwrap()
+                .get()
+        };
+        let publish_count_before = publish_count();
+        let late_count_before = late_count();
+
+        runtime.block_on(async {
+            network_senders
+                .network_send()
+                .send(NetworkMessage::publish_own_block(Arc::new(block)))
+                .unwrap();
+
+            // The message is still tagged as our own block once received by the network service.
+            let message = network_service.network_recv.recv().await.unwrap();
+            assert!(matches!(
+                message,
+                NetworkMessage::Publish {
+                    own_block: true,
+                    ..
+                }
+            ));
+
+            let (mut shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+            network_service
+                .on_network_msg(message, &mut shutdown_tx)
+                .await;
+        });
+
+        assert_eq!(publish_count(), publish_count_before + 1);
+        assert_eq!(late_count(), late_count_before + 1);
+    }
+
+    /// Builds a network service with discovery enabled on localhost, listening on `port` for
+    /// TCP and discovery and on `port + 1` for QUIC.
+    fn build_discovery_node<T: BeaconChainTypes>(
+        runtime: &Arc<Runtime>,
+        beacon_chain: Arc<BeaconChain<T>>,
+        port: u16,
+        boot_nodes: Vec<Enr>,
+    ) -> (NetworkService<T>, exit_future::Signal, TempDir) {
+        let network_dir = tempdir().unwrap();
+        let (signal, exit) = exit_future::signal();
+
+        let (network_service, _network_globals, _network_senders) = runtime.block_on(async {
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = task_executor::TaskExecutor::new(
+                Arc::downgrade(runtime),
+                exit,
+                get_logger(false),
+                shutdown_tx,
+            );
+
+            let mut config = NetworkConfig::default();
+            config.set_ipv4_listening_address(Ipv4Addr::UNSPECIFIED, port, port, port + 1);
+            config.enr_address = (Some(Ipv4Addr::LOCALHOST), None);
+            config.enr_udp4_port = NonZeroU16::new(port);
+            config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
+            config.boot_nodes_enr = boot_nodes;
+            config.network_dir = network_dir.path().into();
+            config.upnp_enabled = false;
+
+            let beacon_processor_channels =
+                BeaconProcessorChannels::new(&BeaconProcessorConfig::default());
+            NetworkService::build(
+                beacon_chain,
+                &config,
+                executor,
+                None,
+                beacon_processor_channels.beacon_processor_tx,
+                beacon_processor_channels.work_reprocessing_tx,
+            )
+            .await
+            .unwrap()
+        });
+
+        (network_service, signal, network_dir)
+    }
+
+    async fn request_discovery_info<T: BeaconChainTypes>(
+        network_service: &mut NetworkService<T>,
+    ) -> DiscoveryInfo {
+        let (result_tx, result_rx) = oneshot::channel();
+        let (mut shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+        network_service
+            .on_network_msg(
+                NetworkMessage::DiscoveryInfo { result_tx },
+                &mut shutdown_tx,
+            )
+            .await;
+        result_rx.await.unwrap()
+    }
+
+    #[test]
+    fn test_discovery_info() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+
+        let beacon_chain = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build()
+            .chain;
+
+        let (mut boot_node, _boot_node_signal, _boot_node_dir) =
+            build_discovery_node(&runtime, beacon_chain.clone(), 21222, vec![]);
+        let boot_enr = boot_node.libp2p.local_enr();
+        let (mut node, _node_signal, _node_dir) =
+            build_discovery_node(&runtime, beacon_chain, 21224, vec![boot_enr]);
+
+        runtime.block_on(async {
+            // The boot node is added to the routing table on startup.
+            let info = request_discovery_info(&mut node).await;
+            assert!(info.started);
+            assert_eq!(info.connected + info.disconnected, 1);
+            assert_eq!(info.buckets.len(), 1);
+            assert_eq!(info.recent_queries.total, 0);
+
+            node.libp2p.discovery_mut().discover_peers(16);
+
+            let deadline = Instant::now() + Duration::from_secs(30);
+            loop {
+                tokio::select! {
+                    _ = node.libp2p.next_event() => {}
+                    _ = boot_node.libp2p.next_event() => {}
+                    _ = tokio::time::sleep(Duration::from_millis(100)) => {}
+                }
+
+                let info = request_discovery_info(&mut node).await;
+                let boot_node_info = request_discovery_info(&mut boot_node).await;
+                if info.recent_queries.total > 0
+                    && info.connected == 1
+                    && boot_node_info.connected + boot_node_info.disconnected == 1
+                {
+                    // The query contacted the boot node, which learned of the node in turn.
+                    assert_eq!(info.disconnected, 0);
+                    assert_eq!(info.buckets.iter().map(|b| b.connected).sum::<usize>(), 1);
+                    assert_eq!(info.recent_queries.failed, 0);
+                    assert_eq!(info.fork_digest_mismatches, 0);
+                    assert_eq!(boot_node_info.buckets.len(), 1);
+                    break;
+                }
+                assert!(
+                    Instant::now() < deadline,
+                    "discovery session was not established: {:?}",
+                    info
+                );
+            }
+        });
+    }
 }
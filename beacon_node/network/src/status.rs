@@ -35,5 +35,6 @@ pub(crate) fn status_message<T: BeaconChainTypes>(beacon_chain: &BeaconChain<T>)
         finalized_epoch: finalized_checkpoint.epoch,
         head_root: cached_head.head_block_root(),
         head_slot: cached_head.head_slot(),
+        earliest_available_slot: Some(beacon_chain.store.get_oldest_block_slot()),
     }
 }
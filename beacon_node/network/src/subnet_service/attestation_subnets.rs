@@ -4,14 +4,14 @@
 
 use super::SubnetServiceMessage;
 use std::collections::HashSet;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeSet, HashMap, VecDeque};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use delay_map::{HashMapDelay, HashSetDelay};
+use delay_map::HashSetDelay;
 use futures::prelude::*;
 use lighthouse_network::{discv5::enr::NodeId, NetworkConfig, Subnet, SubnetDiscovery};
 use slog::{debug, error, info, o, trace, warn};
@@ -50,6 +50,78 @@ pub struct ExactSubnet {
     pub slot: Slot,
 }
 
+/// The aggregation duties that short lived subscriptions are held for.
+///
+/// Duties on the same subnet are coalesced: we remain subscribed to a subnet until the last of its
+/// duties has ended, so the end of an earlier duty never drops a subscription a later one needs.
+#[derive(Default)]
+struct ShortLivedSubscriptions {
+    duties: HashMap<SubnetId, BTreeSet<Slot>>,
+}
+
+impl ShortLivedSubscriptions {
+    /// Registers a duty, returning `true` if there were no duties on its subnet yet.
+    fn insert(&mut self, exact_subnet: ExactSubnet) -> bool {
+        let is_new_subnet = !self.duties.contains_key(&exact_subnet.subnet_id);
+        self.duties
+            .entry(exact_subnet.subnet_id)
+            .or_default()
+            .insert(exact_subnet.slot);
+        is_new_subnet
+    }
+
+    /// Removes a duty that has ended, returning `true` if no duties remain on its subnet.
+    fn remove(&mut self, exact_subnet: &ExactSubnet) -> bool {
+        let Some(slots) = self.duties.get_mut(&exact_subnet.subnet_id) else {
+            return false;
+        };
+        if !slots.remove(&exact_subnet.slot) || !slots.is_empty() {
+            return false;
+        }
+        self.duties.remove(&exact_subnet.subnet_id);
+        true
+    }
+
+    /// Returns whether there is a duty on the subnet.
+    fn contains(&self, subnet_id: &SubnetId) -> bool {
+        self.duties.contains_key(subnet_id)
+    }
+
+    /// Returns the subnets with at least one duty.
+    fn subnets(&self) -> impl Iterator<Item = &SubnetId> {
+        self.duties.keys()
+    }
+
+    /// The number of subnets with at least one duty.
+    fn len(&self) -> usize {
+        self.duties.len()
+    }
+}
+
+/// Returns how long from now until a short lived subscription for a duty at `slot` should start
+/// and end.
+///
+/// The subscription starts `1 / ADVANCE_SUBSCRIBE_SLOT_FRACTION` of a slot before the duty slot and
+/// ends `linger` after the end of the duty slot, to allow for late messages. Returns `None` if the
+/// subscription would already have ended.
+pub(crate) fn short_lived_subscription_window<S: SlotClock>(
+    slot_clock: &S,
+    slot: Slot,
+    linger: Duration,
+) -> Option<(Duration, Duration)> {
+    let now = slot_clock.now_duration()?;
+    let advance_subscription_duration =
+        slot_clock.slot_duration() / ADVANCE_SUBSCRIBE_SLOT_FRACTION;
+    let start = slot_clock
+        .start_of(slot)?
+        .saturating_sub(advance_subscription_duration)
+        .saturating_sub(now);
+    let end = (slot_clock.start_of(slot + 1)? + linger)
+        .checked_sub(now)
+        .filter(|end| !end.is_zero())?;
+    Some((start, end))
+}
+
 pub struct AttestationService<T: BeaconChainTypes> {
     /// Queued events to return to the driving service.
     events: VecDeque<SubnetServiceMessage>,
@@ -57,11 +129,20 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// A reference to the beacon chain to process received attestations.
     pub(crate) beacon_chain: Arc<BeaconChain<T>>,
 
-    /// Subnets we are currently subscribed to as short lived subscriptions.
+    /// Subnets we are currently subscribed to as short lived subscriptions, along with the
+    /// duties that require them.
     ///
-    /// Once they expire, we unsubscribe from these.
+    /// Once all of a subnet's duties have ended, we unsubscribe from it.
     /// We subscribe to subnets when we are an aggregator for an exact subnet.
-    short_lived_subscriptions: HashMapDelay<SubnetId, Slot>,
+    short_lived_subscriptions: ShortLivedSubscriptions,
+
+    /// The times at which each short lived subscription ends: the end of its duty slot plus
+    /// `unsubscribe_linger`.
+    short_lived_subscription_ends: HashSetDelay<ExactSubnet>,
+
+    /// How long to remain subscribed to a short lived subnet after the end of the duty slot, to
+    /// allow for late messages.
+    unsubscribe_linger: Duration,
 
     /// Subnets we are currently subscribed to as long lived subscriptions.
     ///
@@ -74,7 +155,7 @@ pub struct AttestationService<T: BeaconChainTypes> {
     scheduled_short_lived_subscriptions: HashSetDelay<ExactSubnet>,
 
     /// A collection timeouts to track the existence of aggregate validator subscriptions at an
-    /// `ExactSubnet`.
+    /// `ExactSubnet`. These expire together with the corresponding short lived subscription.
     aggregate_validators_on_subnet: Option<HashSetDelay<ExactSubnet>>,
 
     /// The waker for the current thread.
@@ -113,7 +194,12 @@ impl<T: BeaconChainTypes> AttestationService<T> {
 
         let slot_duration = beacon_chain.slot_clock.slot_duration();
 
-        if config.subscribe_all_subnets {
+        if config.proposer_only {
+            slog::info!(
+                log,
+                "Proposer-only mode, not subscribing to long lived subnets"
+            );
+        } else if config.subscribe_all_subnets {
             slog::info!(log, "Subscribing to all subnets");
         } else {
             slog::info!(log, "Deterministic long lived subnets enabled"; "subnets_per_node" => beacon_chain.spec.subnets_per_node, "subscription_duration_in_epochs" => beacon_chain.spec.epochs_per_subnet_subscription);
@@ -125,7 +211,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         let mut service = AttestationService {
             events: VecDeque::with_capacity(10),
             beacon_chain,
-            short_lived_subscriptions: HashMapDelay::new(slot_duration),
+            short_lived_subscriptions: ShortLivedSubscriptions::default(),
+            short_lived_subscription_ends: HashSetDelay::new(slot_duration),
+            unsubscribe_linger: config.subnet_unsubscribe_linger,
             long_lived_subscriptions: HashSet::default(),
             scheduled_short_lived_subscriptions: HashSetDelay::default(),
             aggregate_validators_on_subnet,
@@ -142,8 +230,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             log,
         };
 
-        // If we are not subscribed to all subnets, handle the deterministic set of subnets
-        if !config.subscribe_all_subnets {
+        // If we are not subscribed to all subnets, handle the deterministic set of subnets. A
+        // proposer-only node has no long lived subnets.
+        if !config.subscribe_all_subnets && !config.proposer_only {
             service.recompute_long_lived_subnets();
         }
 
@@ -158,7 +247,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         } else {
             let count = self
                 .short_lived_subscriptions
-                .keys()
+                .subnets()
                 .chain(self.long_lived_subscriptions.iter())
                 .collect::<HashSet<_>>()
                 .len();
@@ -175,7 +264,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ) -> bool {
         match subscription_kind {
             SubscriptionKind::LongLived => self.long_lived_subscriptions.contains(subnet_id),
-            SubscriptionKind::ShortLived => self.short_lived_subscriptions.contains_key(subnet_id),
+            SubscriptionKind::ShortLived => self.short_lived_subscriptions.contains(subnet_id),
         }
     }
 
@@ -348,7 +437,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             // Add the events for those subnets that are new as long lived subscriptions.
             if !self.long_lived_subscriptions.contains(subnet) {
                 // Check if this subnet is new and send the subscription event if needed.
-                if !self.short_lived_subscriptions.contains_key(subnet) {
+                if !self.short_lived_subscriptions.contains(subnet) {
                     debug!(self.log, "Subscribing to subnet";
                         "subnet" => ?subnet,
                         "subscription_kind" => ?SubscriptionKind::LongLived,
@@ -461,39 +550,32 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     // Subscribes to the subnet if it should be done immediately, or schedules it if required.
     fn subscribe_to_short_lived_subnet(
         &mut self,
-        ExactSubnet { subnet_id, slot }: ExactSubnet,
+        exact_subnet: ExactSubnet,
     ) -> Result<(), &'static str> {
-        let slot_duration = self.beacon_chain.slot_clock.slot_duration();
-
-        // Calculate how long before we need to subscribe to the subnet.
-        let time_to_subscription_start = {
-            // The short time we schedule the subscription before it's actually required. This
-            // ensures we are subscribed on time, and allows consecutive subscriptions to the same
-            // subnet to overlap, reducing subnet churn.
-            let advance_subscription_duration = slot_duration / ADVANCE_SUBSCRIBE_SLOT_FRACTION;
-            // The time to the required slot.
-            let time_to_subscription_slot = self
-                .beacon_chain
-                .slot_clock
-                .duration_to_slot(slot)
-                .unwrap_or_default(); // If this is a past slot we will just get a 0 duration.
-            time_to_subscription_slot.saturating_sub(advance_subscription_duration)
-        };
-
+        let (time_to_subscription_start, time_to_subscription_end) =
+            short_lived_subscription_window(
+                &self.beacon_chain.slot_clock,
+                exact_subnet.slot,
+                self.unsubscribe_linger,
+            )
+            .ok_or("Time when subscription would end has already passed.")?;
+
+        // Track the aggregator until the subscription ends, rather than for a fixed duration from
+        // now.
         if let Some(tracked_vals) = self.aggregate_validators_on_subnet.as_mut() {
-            tracked_vals.insert(ExactSubnet { subnet_id, slot });
+            tracked_vals.insert_at(exact_subnet, time_to_subscription_end);
         }
 
         // If the subscription should be done in the future, schedule it. Otherwise subscribe
         // immediately.
         if time_to_subscription_start.is_zero() {
             // This is a current or past slot, we subscribe immediately.
-            self.subscribe_to_short_lived_subnet_immediately(subnet_id, slot + 1)?;
+            self.subscribe_to_short_lived_subnet_immediately(exact_subnet)?;
         } else {
             // This is a future slot, schedule subscribing.
-            trace!(self.log, "Scheduling subnet subscription"; "subnet" => ?subnet_id, "time_to_subscription_start" => ?time_to_subscription_start);
+            trace!(self.log, "Scheduling subnet subscription"; "subnet" => ?exact_subnet.subnet_id, "time_to_subscription_start" => ?time_to_subscription_start);
             self.scheduled_short_lived_subscriptions
-                .insert_at(ExactSubnet { subnet_id, slot }, time_to_subscription_start);
+                .insert_at(exact_subnet, time_to_subscription_start);
         }
 
         Ok(())
@@ -501,86 +583,85 @@ impl<T: BeaconChainTypes> AttestationService<T> {
 
     /* A collection of functions that handle the various timeouts */
 
-    /// Registers a subnet as subscribed.
+    /// Registers a subnet as subscribed for the duty at `exact_subnet`.
     ///
     /// Checks that the time in which the subscription would end is not in the past. If we are
-    /// already subscribed, extends the timeout if necessary. If this is a new subscription, we send
-    /// out the appropriate events.
+    /// already subscribed for another duty, the subscriptions are coalesced and we remain
+    /// subscribed until the last of them ends. If this is a new subscription, we send out the
+    /// appropriate events.
     ///
     /// On determinist long lived subnets, this is only used for short lived subscriptions.
     fn subscribe_to_short_lived_subnet_immediately(
         &mut self,
-        subnet_id: SubnetId,
-        end_slot: Slot,
+        exact_subnet: ExactSubnet,
     ) -> Result<(), &'static str> {
         if self.subscribe_all_subnets {
             // Case not handled by this service.
             return Ok(());
         }
 
-        let time_to_subscription_end = self
-            .beacon_chain
-            .slot_clock
-            .duration_to_slot(end_slot)
-            .unwrap_or_default();
-
         // First check this is worth doing.
-        if time_to_subscription_end.is_zero() {
-            return Err("Time when subscription would end has already passed.");
-        }
+        let (_, time_to_subscription_end) = short_lived_subscription_window(
+            &self.beacon_chain.slot_clock,
+            exact_subnet.slot,
+            self.unsubscribe_linger,
+        )
+        .ok_or("Time when subscription would end has already passed.")?;
 
         let subscription_kind = SubscriptionKind::ShortLived;
+        let subnet_id = exact_subnet.subnet_id;
 
         // We need to check and add a subscription for the right kind, regardless of the presence
         // of the subnet as a subscription of the other kind. This is mainly since long lived
         // subscriptions can be removed at any time when a validator goes offline.
-
-        let (subscriptions, already_subscribed_as_other_kind) = (
-            &mut self.short_lived_subscriptions,
-            self.long_lived_subscriptions.contains(&subnet_id),
-        );
-
-        match subscriptions.get(&subnet_id) {
-            Some(current_end_slot) => {
-                // We are already subscribed. Check if we need to extend the subscription.
-                if &end_slot > current_end_slot {
-                    trace!(self.log, "Extending subscription to subnet";
-                        "subnet" => ?subnet_id,
-                        "prev_end_slot" => current_end_slot,
-                        "new_end_slot" => end_slot,
-                        "subscription_kind" => ?subscription_kind,
-                    );
-                    subscriptions.insert_at(subnet_id, end_slot, time_to_subscription_end);
-                }
-            }
-            None => {
-                // This is a new subscription. Add with the corresponding timeout and send the
-                // notification.
-                subscriptions.insert_at(subnet_id, end_slot, time_to_subscription_end);
-
-                // Inform of the subscription.
-                if !already_subscribed_as_other_kind {
-                    debug!(self.log, "Subscribing to subnet";
-                        "subnet" => ?subnet_id,
-                        "end_slot" => end_slot,
-                        "subscription_kind" => ?subscription_kind,
-                    );
-                    self.queue_event(SubnetServiceMessage::Subscribe(Subnet::Attestation(
-                        subnet_id,
-                    )));
-                }
+        self.short_lived_subscription_ends
+            .insert_at(exact_subnet, time_to_subscription_end);
+        if self.short_lived_subscriptions.insert(exact_subnet) {
+            // This is a new subscription, send the notification.
+            if !self.long_lived_subscriptions.contains(&subnet_id) {
+                debug!(self.log, "Subscribing to subnet";
+                    "subnet" => ?subnet_id,
+                    "slot" => exact_subnet.slot,
+                    "subscription_kind" => ?subscription_kind,
+                );
+                self.queue_event(SubnetServiceMessage::Subscribe(Subnet::Attestation(
+                    subnet_id,
+                )));
             }
+        } else {
+            trace!(self.log, "Coalescing subscription to subnet";
+                "subnet" => ?subnet_id,
+                "slot" => exact_subnet.slot,
+                "subscription_kind" => ?subscription_kind,
+            );
         }
+        self.update_short_lived_subscriptions_metric();
 
         Ok(())
     }
 
+    /// Ends the short lived subscription for the duty at `exact_subnet`, unsubscribing from the
+    /// subnet if no other duty requires it.
+    fn end_short_lived_subscription(&mut self, exact_subnet: ExactSubnet) {
+        if self.short_lived_subscriptions.remove(&exact_subnet) {
+            self.handle_removed_subnet(exact_subnet.subnet_id, SubscriptionKind::ShortLived);
+        }
+        self.update_short_lived_subscriptions_metric();
+    }
+
+    fn update_short_lived_subscriptions_metric(&self) {
+        metrics::set_gauge(
+            &metrics::SUBNET_SHORT_LIVED_SUBSCRIPTIONS,
+            self.short_lived_subscriptions.len() as i64,
+        );
+    }
+
     // Unsubscribes from a subnet that was removed if it does not continue to exist as a
     // subscription of the other kind. For long lived subscriptions, it also removes the
     // advertisement from our ENR.
     fn handle_removed_subnet(&mut self, subnet_id: SubnetId, subscription_kind: SubscriptionKind) {
         let exists_in_other_subscriptions = match subscription_kind {
-            SubscriptionKind::LongLived => self.short_lived_subscriptions.contains_key(&subnet_id),
+            SubscriptionKind::LongLived => self.short_lived_subscriptions.contains(&subnet_id),
             SubscriptionKind::ShortLived => self.long_lived_subscriptions.contains(&subnet_id),
         };
 
@@ -620,7 +701,7 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
         }
 
         // If we aren't subscribed to all subnets, handle the deterministic long-lived subnets
-        if !self.subscribe_all_subnets {
+        if !self.subscribe_all_subnets && !self.proposer_only {
             match self.next_long_lived_subscription_event.as_mut().poll(cx) {
                 Poll::Ready(_) => {
                     self.recompute_long_lived_subnets();
@@ -637,11 +718,9 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
         // Process scheduled subscriptions that might be ready, since those can extend a soon to
         // expire subscription.
         match self.scheduled_short_lived_subscriptions.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok(ExactSubnet { subnet_id, slot }))) => {
-                if let Err(e) =
-                    self.subscribe_to_short_lived_subnet_immediately(subnet_id, slot + 1)
-                {
-                    debug!(self.log, "Failed to subscribe to short lived subnet"; "subnet" => ?subnet_id, "err" => e);
+            Poll::Ready(Some(Ok(exact_subnet))) => {
+                if let Err(e) = self.subscribe_to_short_lived_subnet_immediately(exact_subnet) {
+                    debug!(self.log, "Failed to subscribe to short lived subnet"; "subnet" => ?exact_subnet.subnet_id, "err" => e);
                 }
                 self.waker
                     .as_ref()
@@ -655,9 +734,9 @@ impl<T: BeaconChainTypes> Stream for AttestationService<T> {
         }
 
         // Finally process any expired subscriptions.
-        match self.short_lived_subscriptions.poll_next_unpin(cx) {
-            Poll::Ready(Some(Ok((subnet_id, _end_slot)))) => {
-                self.handle_removed_subnet(subnet_id, SubscriptionKind::ShortLived);
+        match self.short_lived_subscription_ends.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(exact_subnet))) => {
+                self.end_short_lived_subscription(exact_subnet);
                 // We re-wake the task as there could be other subscriptions to process
                 self.waker
                     .as_ref()
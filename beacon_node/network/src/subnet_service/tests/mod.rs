@@ -11,7 +11,7 @@ use lazy_static::lazy_static;
 use lighthouse_network::NetworkConfig;
 use slog::{o, Drain, Logger};
 use sloggers::{null::NullLoggerBuilder, Build};
-use slot_clock::{SlotClock, SystemTimeSlotClock};
+use slot_clock::{ManualSlotClock, SlotClock, SystemTimeSlotClock};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use store::config::StoreConfig;
@@ -118,9 +118,20 @@ lazy_static! {
 
 fn get_attestation_service(
     log_level: Option<slog::Level>,
+) -> AttestationService<TestBeaconChainType> {
+    // The tests below expect to unsubscribe exactly at the end of the duty slot.
+    let config = NetworkConfig {
+        subnet_unsubscribe_linger: Duration::ZERO,
+        ..NetworkConfig::default()
+    };
+    get_attestation_service_with_config(log_level, config)
+}
+
+fn get_attestation_service_with_config(
+    log_level: Option<slog::Level>,
+    config: NetworkConfig,
 ) -> AttestationService<TestBeaconChainType> {
     let log = get_logger(log_level);
-    let config = NetworkConfig::default();
 
     let beacon_chain = CHAIN.chain.clone();
 
@@ -216,6 +227,141 @@ mod attestation_service {
             .collect()
     }
 
+    /// A short lived subscription starts a slot before the duty slot and ends `linger` after it.
+    #[test]
+    fn short_lived_subscription_window() {
+        let slot_duration = Duration::from_secs(12);
+        let linger = Duration::from_millis(500);
+        let slot_clock = ManualSlotClock::new(Slot::new(0), Duration::ZERO, slot_duration);
+        slot_clock.set_slot(10);
+
+        assert_eq!(
+            attestation_subnets::short_lived_subscription_window(
+                &slot_clock,
+                Slot::new(12),
+                linger
+            ),
+            Some((slot_duration, slot_duration * 3 + linger))
+        );
+
+        // A duty in the current slot subscribes immediately, one whose slot and linger have passed
+        // is not subscribed to at all.
+        slot_clock.set_current_time(slot_duration * 10 + slot_duration / 2);
+        assert_eq!(
+            attestation_subnets::short_lived_subscription_window(
+                &slot_clock,
+                Slot::new(10),
+                linger
+            ),
+            Some((Duration::ZERO, slot_duration / 2 + linger))
+        );
+        assert_eq!(
+            attestation_subnets::short_lived_subscription_window(&slot_clock, Slot::new(9), linger),
+            None
+        );
+    }
+
+    /// Overlapping aggregation duties on the same subnet should be coalesced into a single
+    /// subscription that starts a slot before the first duty and ends `linger` after the last.
+    #[tokio::test]
+    async fn short_lived_subscription_timing_for_overlapping_duties() {
+        let linger = Duration::from_millis(100);
+        let committee_count = 1;
+        let validator_index = 1;
+
+        let mut attestation_service = get_attestation_service_with_config(
+            None,
+            NetworkConfig {
+                subnet_unsubscribe_linger: linger,
+                ..NetworkConfig::default()
+            },
+        );
+        let slot_clock = attestation_service.beacon_chain.slot_clock.clone();
+        let spec = attestation_service.beacon_chain.spec.clone();
+        let current_slot = slot_clock.now().expect("Could not get current slot");
+
+        // Aggregation duties in two consecutive slots, on the same subnet which isn't one of the
+        // long lived subnets.
+        let slot1 = current_slot + 2;
+        let slot2 = current_slot + 3;
+        let subnet_for = |slot: Slot, committee_index: CommitteeIndex| {
+            SubnetId::compute_subnet::<MainnetEthSpec>(
+                slot,
+                committee_index,
+                committee_count,
+                &spec,
+            )
+            .unwrap()
+        };
+        let com1 = (0..spec.attestation_subnet_count)
+            .find(|com| {
+                !attestation_service.is_subscribed(
+                    &subnet_for(slot1, *com),
+                    attestation_subnets::SubscriptionKind::LongLived,
+                )
+            })
+            .unwrap();
+        let subnet_id = subnet_for(slot1, com1);
+        let com2 = (0..spec.attestation_subnet_count)
+            .find(|com| subnet_for(slot2, *com) == subnet_id)
+            .unwrap();
+
+        attestation_service
+            .validator_subscriptions(vec![
+                get_subscription(validator_index, com1, slot1, committee_count, true),
+                get_subscription(validator_index, com2, slot2, committee_count, true),
+            ])
+            .unwrap();
+
+        // Record when the service subscribes to and unsubscribes from the subnet.
+        let mut transitions = vec![];
+        let timeout = tokio::time::sleep(Duration::from_millis(SLOT_DURATION_MILLIS) * 6);
+        futures::pin_mut!(timeout);
+        loop {
+            tokio::select! {
+                Some(event) = attestation_service.next() => {
+                    let now = slot_clock.now_duration().unwrap();
+                    match event {
+                        SubnetServiceMessage::Subscribe(Subnet::Attestation(subnet))
+                            if subnet == subnet_id => transitions.push((true, now)),
+                        SubnetServiceMessage::Unsubscribe(Subnet::Attestation(subnet))
+                            if subnet == subnet_id =>
+                        {
+                            transitions.push((false, now));
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                _ = timeout.as_mut() => break,
+            }
+        }
+
+        let expected = [
+            (true, slot_clock.start_of(slot1 - 1).unwrap()),
+            (false, slot_clock.start_of(slot2 + 1).unwrap() + linger),
+        ];
+        assert_eq!(transitions.len(), expected.len(), "{:?}", transitions);
+        for ((is_subscribe, time), (expected_is_subscribe, expected_time)) in
+            transitions.into_iter().zip(expected)
+        {
+            assert_eq!(is_subscribe, expected_is_subscribe);
+            // Allow for the system clock and the timers disagreeing slightly.
+            assert!(
+                time + Duration::from_millis(10) >= expected_time
+                    && time < expected_time + Duration::from_millis(100),
+                "expected {:?} at {:?}, got {:?}",
+                if is_subscribe {
+                    "subscribe"
+                } else {
+                    "unsubscribe"
+                },
+                expected_time,
+                time
+            );
+        }
+    }
+
     #[tokio::test]
     async fn subscribe_current_slot_wait_for_unsubscribe() {
         // subscription config
@@ -695,6 +841,32 @@ mod attestation_service {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn proposer_only_makes_no_subscriptions() {
+        let config = NetworkConfig {
+            proposer_only: true,
+            ..NetworkConfig::default()
+        };
+        let mut attestation_service = get_attestation_service_with_config(None, config);
+
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        // Aggregator subscriptions would normally result in short lived subscriptions.
+        let subscriptions = get_subscriptions(20, current_slot + 2, 30, true);
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        let events = get_events(&mut attestation_service, None, 4).await;
+        assert!(events.is_empty(), "unexpected events: {:?}", events);
+        assert!(attestation_service.long_lived_subscriptions().is_empty());
+        assert_eq!(attestation_service.subscription_count(), 0);
+    }
 }
 
 mod sync_committee_service {
@@ -916,13 +916,15 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         // Find a peer to request the batch
         let failed_peers = batch.failed_peers();
+        let batch_start_slot = batch.start_slot();
 
         let new_peer = {
-            let mut priorized_peers = self
-                .network_globals
-                .peers
-                .read()
+            let peers = self.network_globals.peers.read();
+            let mut priorized_peers = peers
                 .synced_peers()
+                // Peers which don't have the blocks of the batch, e.g. after checkpoint sync,
+                // can't serve it.
+                .filter(|peer| peers.may_have_block_at(peer, batch_start_slot))
                 .map(|peer| {
                     (
                         failed_peers.contains(peer),
@@ -1056,6 +1058,17 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         idle_peers.shuffle(&mut rng);
 
         while let Some(peer) = idle_peers.pop() {
+            let next_batch_start_slot = self
+                .to_be_downloaded
+                .start_slot(T::EthSpec::slots_per_epoch());
+            if !self
+                .network_globals
+                .peers
+                .read()
+                .may_have_block_at(&peer, next_batch_start_slot)
+            {
+                continue;
+            }
             if let Some(batch_id) = self.include_next_batch(network) {
                 // send the batch
                 self.send_batch(network, batch_id, peer)?;
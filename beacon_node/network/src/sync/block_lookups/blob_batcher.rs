@@ -0,0 +1,314 @@
+//! Coalesces the blob requests of block lookups into a single `BlobsByRoot` request per peer.
+//!
+//! When several blocks are missing blobs, each lookup would otherwise send its own request to the
+//! same peer. Instead, requests for a peer are held back for a short window and then sent
+//! together, up to the maximum request size. The blobs streamed back for a batched request are
+//! routed to the lookup that asked for their block root.
+
+use crate::sync::block_lookups::common::LookupType;
+use crate::sync::manager::{Id, SingleLookupReqId};
+use fnv::FnvHashMap;
+use lighthouse_network::rpc::methods::MAX_REQUEST_BLOB_SIDECARS;
+use lighthouse_network::PeerId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use types::blob_sidecar::BlobIdentifier;
+use types::Hash256;
+
+/// How long the blob requests for a peer are held back, waiting for others to batch with.
+pub const BLOB_BATCH_WINDOW: Duration = Duration::from_millis(50);
+
+/// The blobs a single lookup wants from a peer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlobLookupRequest {
+    pub id: SingleLookupReqId,
+    pub lookup_type: LookupType,
+    pub blob_ids: Vec<BlobIdentifier>,
+}
+
+impl BlobLookupRequest {
+    fn block_root(&self) -> Option<Hash256> {
+        self.blob_ids.first().map(|id| id.block_root)
+    }
+
+    /// Whether `other` is a request from the same lookup.
+    fn same_lookup(&self, other: &Self) -> bool {
+        self.id.id == other.id.id && self.lookup_type == other.lookup_type
+    }
+}
+
+/// A batch of blob lookup requests that could not be sent to its peer.
+#[derive(Debug)]
+pub struct FailedBlobBatch {
+    pub peer_id: PeerId,
+    pub requests: Vec<BlobLookupRequest>,
+    pub error: &'static str,
+}
+
+/// Where a blob received for a batched request should be sent.
+#[derive(Debug, PartialEq)]
+pub enum BatchedBlobRoute {
+    /// The blob belongs to this lookup.
+    Lookup {
+        id: SingleLookupReqId,
+        lookup_type: LookupType,
+    },
+    /// The peer sent a blob for a block root that is not part of the batch.
+    Unrequested,
+    /// The batch has already completed or failed.
+    UnknownBatch,
+}
+
+/// Requests for a peer that are waiting to be sent.
+struct PendingBatch {
+    queued_at: Instant,
+    requests: Vec<BlobLookupRequest>,
+}
+
+impl PendingBatch {
+    fn num_blob_ids(&self) -> usize {
+        self.requests.iter().map(|req| req.blob_ids.len()).sum()
+    }
+}
+
+pub struct BlobRequestBatcher {
+    /// How long requests are held back. A zero window disables batching.
+    window: Duration,
+    /// Requests waiting to be sent, per peer.
+    pending: HashMap<PeerId, PendingBatch>,
+    /// Batches that can't take any more requests and are due to be sent straight away.
+    ready: Vec<(PeerId, PendingBatch)>,
+    /// Batched requests that have been sent and whose responses have not yet ended.
+    in_flight: FnvHashMap<Id, Vec<BlobLookupRequest>>,
+    /// The id of the next batched request.
+    next_batch_id: Id,
+}
+
+impl BlobRequestBatcher {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+            ready: vec![],
+            in_flight: FnvHashMap::default(),
+            next_batch_id: 1,
+        }
+    }
+
+    /// Whether requests are batched at all. With a zero window each request is sent on its own.
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Queues `request` to be sent to `peer_id`.
+    ///
+    /// If the request can't join the peer's pending batch, because the batch would exceed the
+    /// maximum request size or already asks for the same block root, the pending batch is made due
+    /// straight away.
+    pub fn queue(&mut self, peer_id: PeerId, request: BlobLookupRequest, now: Instant) {
+        // A retry of a lookup supersedes any of its requests that are still waiting to be sent.
+        for batch in self
+            .pending
+            .values_mut()
+            .chain(self.ready.iter_mut().map(|(_, batch)| batch))
+        {
+            batch
+                .requests
+                .retain(|queued| !queued.same_lookup(&request));
+        }
+        self.pending.retain(|_, batch| !batch.requests.is_empty());
+        self.ready.retain(|(_, batch)| !batch.requests.is_empty());
+
+        let send_pending = self.pending.get(&peer_id).map_or(false, |batch| {
+            batch.num_blob_ids() + request.blob_ids.len() > MAX_REQUEST_BLOB_SIDECARS as usize
+                || batch
+                    .requests
+                    .iter()
+                    .any(|queued| queued.block_root() == request.block_root())
+        });
+        if send_pending {
+            if let Some(batch) = self.pending.remove(&peer_id) {
+                self.ready.push((peer_id, batch));
+            }
+        }
+
+        self.pending
+            .entry(peer_id)
+            .or_insert_with(|| PendingBatch {
+                queued_at: now,
+                requests: vec![],
+            })
+            .requests
+            .push(request);
+    }
+
+    /// The time at which the next batch is due to be sent.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let ready = self.ready.iter().map(|(_, batch)| batch.queued_at);
+        self.pending
+            .values()
+            .map(|batch| batch.queued_at + self.window)
+            .chain(ready)
+            .min()
+    }
+
+    /// Removes and returns the batches that are ready and the pending batches that have waited
+    /// for the full window.
+    pub fn take_due(&mut self, now: Instant) -> Vec<(PeerId, Vec<BlobLookupRequest>)> {
+        let due = self
+            .pending
+            .iter()
+            .filter(|(_, batch)| now.saturating_duration_since(batch.queued_at) >= self.window)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+
+        let ready = std::mem::take(&mut self.ready);
+        ready
+            .into_iter()
+            .chain(
+                due.into_iter()
+                    .filter_map(|peer_id| self.pending.remove(&peer_id).map(|b| (peer_id, b))),
+            )
+            .map(|(peer_id, batch)| (peer_id, batch.requests))
+            .collect()
+    }
+
+    /// Records a batched request that is about to be sent, returning the id its responses will
+    /// arrive with.
+    pub fn register_in_flight(&mut self, requests: Vec<BlobLookupRequest>) -> Id {
+        let id = self.next_batch_id;
+        self.next_batch_id = self.next_batch_id.wrapping_add(1);
+        self.in_flight.insert(id, requests);
+        id
+    }
+
+    /// Finds the lookup a blob for `block_root` received for the batch `batch_id` belongs to.
+    pub fn route_blob(&self, batch_id: Id, block_root: Hash256) -> BatchedBlobRoute {
+        let Some(requests) = self.in_flight.get(&batch_id) else {
+            return BatchedBlobRoute::UnknownBatch;
+        };
+        requests
+            .iter()
+            .find(|req| req.block_root() == Some(block_root))
+            .map(|req| BatchedBlobRoute::Lookup {
+                id: req.id,
+                lookup_type: req.lookup_type,
+            })
+            .unwrap_or(BatchedBlobRoute::Unrequested)
+    }
+
+    /// Removes a batch once its response stream has ended or the request failed, returning the
+    /// requests of the lookups it served.
+    pub fn remove_in_flight(&mut self, batch_id: Id) -> Option<Vec<BlobLookupRequest>> {
+        self.in_flight.remove(&batch_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{EthSpec, MinimalEthSpec as E};
+
+    fn request(id: Id, block_root: Hash256) -> BlobLookupRequest {
+        BlobLookupRequest {
+            id: SingleLookupReqId { id, req_counter: 1 },
+            lookup_type: LookupType::Current,
+            blob_ids: BlobIdentifier::get_all_blob_ids::<E>(block_root),
+        }
+    }
+
+    #[test]
+    fn batches_requests_per_peer_until_the_window_ends() {
+        let mut batcher = BlobRequestBatcher::new(BLOB_BATCH_WINDOW);
+        let (peer_a, peer_b) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+
+        batcher.queue(peer_a, request(1, Hash256::random()), now);
+        batcher.queue(peer_a, request(2, Hash256::random()), now);
+        batcher.queue(peer_b, request(3, Hash256::random()), now);
+        assert_eq!(batcher.next_deadline(), Some(now + BLOB_BATCH_WINDOW));
+
+        assert!(batcher.take_due(now).is_empty());
+        let mut due = batcher.take_due(now + BLOB_BATCH_WINDOW);
+        due.sort_by_key(|(_, requests)| requests.len());
+        assert_eq!(due.len(), 2);
+        assert_eq!(due[0].0, peer_b);
+        assert_eq!(due[1].0, peer_a);
+        assert_eq!(due[1].1.len(), 2);
+        assert_eq!(batcher.next_deadline(), None);
+    }
+
+    #[test]
+    fn full_or_conflicting_batches_are_sent_early() {
+        let mut batcher = BlobRequestBatcher::new(BLOB_BATCH_WINDOW);
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        // Fill the batch up to the maximum request size.
+        let max_lookups = MAX_REQUEST_BLOB_SIDECARS as usize / E::max_blobs_per_block();
+        for id in 0..max_lookups {
+            batcher.queue(peer_id, request(id as Id, Hash256::random()), now);
+        }
+        assert!(batcher.take_due(now).is_empty());
+        let block_root = Hash256::random();
+        batcher.queue(peer_id, request(max_lookups as Id, block_root), now);
+        assert_eq!(batcher.next_deadline(), Some(now));
+        let due = batcher.take_due(now);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].1.len(), max_lookups);
+
+        // A second request for the same block root from another lookup can't share the batch.
+        batcher.queue(peer_id, request(max_lookups as Id + 1, block_root), now);
+        assert_eq!(
+            batcher.take_due(now),
+            vec![(peer_id, vec![request(max_lookups as Id, block_root)])]
+        );
+        assert_eq!(
+            batcher.take_due(now + BLOB_BATCH_WINDOW),
+            vec![(peer_id, vec![request(max_lookups as Id + 1, block_root)])]
+        );
+    }
+
+    #[test]
+    fn retries_supersede_queued_requests() {
+        let mut batcher = BlobRequestBatcher::new(BLOB_BATCH_WINDOW);
+        let (peer_a, peer_b) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+        let block_root = Hash256::random();
+
+        batcher.queue(peer_a, request(1, block_root), now);
+        let mut retry = request(1, block_root);
+        retry.id.req_counter += 1;
+        batcher.queue(peer_b, retry.clone(), now);
+
+        let due = batcher.take_due(now + BLOB_BATCH_WINDOW);
+        assert_eq!(due, vec![(peer_b, vec![retry])]);
+    }
+
+    #[test]
+    fn routes_blobs_by_block_root() {
+        let mut batcher = BlobRequestBatcher::new(BLOB_BATCH_WINDOW);
+        let (root_a, root_b) = (Hash256::random(), Hash256::random());
+        let batch_id = batcher.register_in_flight(vec![request(1, root_a), request(2, root_b)]);
+
+        assert_eq!(
+            batcher.route_blob(batch_id, root_b),
+            BatchedBlobRoute::Lookup {
+                id: SingleLookupReqId {
+                    id: 2,
+                    req_counter: 1
+                },
+                lookup_type: LookupType::Current,
+            }
+        );
+        assert_eq!(
+            batcher.route_blob(batch_id, Hash256::random()),
+            BatchedBlobRoute::Unrequested
+        );
+        assert_eq!(batcher.remove_in_flight(batch_id).map(|r| r.len()), Some(2));
+        assert_eq!(
+            batcher.route_blob(batch_id, root_a),
+            BatchedBlobRoute::UnknownBatch
+        );
+    }
+}
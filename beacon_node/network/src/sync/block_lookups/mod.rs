@@ -5,6 +5,9 @@ use super::BatchProcessResult;
 use super::{manager::BlockProcessType, network_context::SyncNetworkContext};
 use crate::metrics;
 use crate::network_beacon_processor::ChainSegmentProcessId;
+use crate::sync::block_lookups::blob_batcher::{
+    BatchedBlobRoute, BlobLookupRequest, FailedBlobBatch,
+};
 use crate::sync::block_lookups::common::LookupType;
 use crate::sync::block_lookups::parent_lookup::{ParentLookup, RequestError};
 use crate::sync::block_lookups::single_block_lookup::{
@@ -36,8 +39,9 @@ use std::time::Duration;
 use store::Hash256;
 use strum::Display;
 use types::blob_sidecar::FixedBlobSidecarList;
-use types::Slot;
+use types::{BlobSidecar, Slot};
 
+pub mod blob_batcher;
 pub mod common;
 mod parent_lookup;
 mod single_block_lookup;
@@ -95,6 +99,18 @@ pub struct BlockLookups<T: BeaconChainTypes> {
     /// A cache of failed chain lookups to prevent duplicate searches.
     failed_chains: LRUTimeCache<Hash256>,
 
+    /// The maximum number of unknown ancestors a parent lookup will download.
+    parent_lookup_max_depth: usize,
+
+    /// Chains that were too long to be found by a parent lookup, identified by the root and slot
+    /// of the block at their tip, along with the peers that served them. These are to be synced by
+    /// range sync instead.
+    chains_for_range_sync: Vec<(Hash256, Slot, Vec<PeerId>)>,
+
+    /// A cache of chains recently handed to range sync, to prevent searching for them again
+    /// whilst they are synced.
+    chains_synced_by_range: LRUTimeCache<Hash256>,
+
     single_block_lookups: FnvHashMap<Id, SingleBlockLookup<Current, T>>,
 
     pub(crate) da_checker: Arc<DataAvailabilityChecker<T>>,
@@ -104,13 +120,22 @@ pub struct BlockLookups<T: BeaconChainTypes> {
 }
 
 impl<T: BeaconChainTypes> BlockLookups<T> {
-    pub fn new(da_checker: Arc<DataAvailabilityChecker<T>>, log: Logger) -> Self {
+    pub fn new(
+        da_checker: Arc<DataAvailabilityChecker<T>>,
+        parent_lookup_max_depth: usize,
+        log: Logger,
+    ) -> Self {
         Self {
             parent_lookups: Default::default(),
             processing_parent_lookups: Default::default(),
             failed_chains: LRUTimeCache::new(Duration::from_secs(
                 FAILED_CHAINS_CACHE_EXPIRY_SECONDS,
             )),
+            parent_lookup_max_depth,
+            chains_for_range_sync: vec![],
+            chains_synced_by_range: LRUTimeCache::new(Duration::from_secs(
+                FAILED_CHAINS_CACHE_EXPIRY_SECONDS,
+            )),
             single_block_lookups: Default::default(),
             da_checker,
             log,
@@ -258,6 +283,15 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             return;
         }
 
+        // If this block or its parent is being synced by range sync, don't search for it again.
+        if self.chains_synced_by_range.contains(&parent_root)
+            || self.chains_synced_by_range.contains(&block_root)
+        {
+            debug!(self.log, "Block is from a chain being synced by range. Dropping";
+                "block_root" => ?block_root, "block_slot" => slot);
+            return;
+        }
+
         // Make sure this block is not already downloaded, and that neither it or its parent is
         // being searched for.
         if let Some(parent_lookup) = self.parent_lookups.iter_mut().find(|parent_req| {
@@ -278,8 +312,10 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         }
         let parent_lookup = ParentLookup::new(
             block_root,
+            slot,
             parent_root,
             peer_source,
+            self.parent_lookup_max_depth,
             self.da_checker.clone(),
             cx,
         );
@@ -541,10 +577,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             }
         }
 
-        metrics::set_gauge(
-            &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
-            self.parent_lookups.len() as i64,
-        );
+        self.update_parent_lookup_metrics();
     }
 
     /// Consolidates error handling for `parent_lookup_response`. An `Err` here should always mean
@@ -630,6 +663,80 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         Ok(())
     }
 
+    /// Process a blob received for a batched request, routing it to the lookup that requested its
+    /// block root. The stream terminator is passed on to every lookup in the batch, so those still
+    /// missing blobs request them again.
+    pub fn batched_blob_response(
+        &mut self,
+        batch_id: Id,
+        peer_id: PeerId,
+        blob: Option<Arc<BlobSidecar<T::EthSpec>>>,
+        seen_timestamp: Duration,
+        cx: &SyncNetworkContext<T>,
+    ) {
+        let Some(blob) = blob else {
+            for request in cx.remove_blob_batch(batch_id).unwrap_or_default() {
+                self.blob_lookup_response(
+                    request.id,
+                    request.lookup_type,
+                    peer_id,
+                    None,
+                    seen_timestamp,
+                    cx,
+                );
+            }
+            return;
+        };
+
+        match cx.batched_blob_route(batch_id, blob.block_root) {
+            BatchedBlobRoute::Lookup { id, lookup_type } => {
+                self.blob_lookup_response(id, lookup_type, peer_id, Some(blob), seen_timestamp, cx)
+            }
+            BatchedBlobRoute::Unrequested => {
+                warn!(self.log, "Peer sent blob for a block root that was not requested";
+                    "peer_id" => %peer_id, "blob_id" => ?blob.id());
+                cx.report_peer(
+                    peer_id,
+                    PeerAction::LowToleranceError,
+                    "unrequested_blob_root",
+                );
+            }
+            BatchedBlobRoute::UnknownBatch => {
+                debug!(self.log, "Response for a batched blob request that was not found";
+                    "peer_id" => %peer_id);
+            }
+        }
+    }
+
+    fn blob_lookup_response(
+        &mut self,
+        id: SingleLookupReqId,
+        lookup_type: LookupType,
+        peer_id: PeerId,
+        blob: Option<Arc<BlobSidecar<T::EthSpec>>>,
+        seen_timestamp: Duration,
+        cx: &SyncNetworkContext<T>,
+    ) {
+        match lookup_type {
+            LookupType::Current => self
+                .single_lookup_response::<BlobRequestState<Current, T::EthSpec>>(
+                    id,
+                    peer_id,
+                    blob,
+                    seen_timestamp,
+                    cx,
+                ),
+            LookupType::Parent => self
+                .parent_lookup_response::<BlobRequestState<Parent, T::EthSpec>>(
+                    id,
+                    peer_id,
+                    blob,
+                    seen_timestamp,
+                    cx,
+                ),
+        }
+    }
+
     /// Handle logging and peer scoring for `RequestError`s during parent lookup requests.
     fn handle_parent_request_error(
         &mut self,
@@ -643,9 +750,20 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 // Probably shutting down, nothing to do here. Drop the request
             }
             RequestError::ChainTooLong => {
-                self.failed_chains.insert(parent_lookup.chain_hash());
+                // Rather than holding more blocks in memory, the chain is synced in batches from
+                // our finalized checkpoint up to the block at its tip.
+                let chain_hash = parent_lookup.chain_hash();
+                let peers = parent_lookup.used_peers().copied().collect::<Vec<_>>();
+                debug!(self.log, "Parent chain too long, syncing it by range";
+                    "chain_hash" => %chain_hash, "peers" => ?peers);
+                self.chains_synced_by_range.insert(chain_hash);
+                self.chains_for_range_sync.push((
+                    chain_hash,
+                    parent_lookup.chain_slot(),
+                    peers.clone(),
+                ));
                 // This indicates faulty peers.
-                for &peer_id in parent_lookup.used_peers() {
+                for peer_id in peers {
                     cx.report_peer(peer_id, PeerAction::LowToleranceError, e.as_static())
                 }
             }
@@ -715,10 +833,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
 
         self.request_parent(parent_lookup, cx);
 
-        metrics::set_gauge(
-            &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
-            self.parent_lookups.len() as i64,
-        );
+        self.update_parent_lookup_metrics();
     }
 
     /// An RPC error has occurred during a single lookup. This function handles this case.\
@@ -762,6 +877,59 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         );
     }
 
+    /// An RPC error has occurred during a batched blob request. Every lookup in the batch handles
+    /// it as a failure of its own request.
+    pub fn batched_blob_lookup_failed(
+        &mut self,
+        batch_id: Id,
+        peer_id: PeerId,
+        cx: &SyncNetworkContext<T>,
+        error: RPCError,
+    ) {
+        let requests = cx.remove_blob_batch(batch_id).unwrap_or_default();
+        self.blob_lookup_requests_failed(requests, peer_id, cx, error);
+    }
+
+    /// A batch of blob requests could not be sent. Every lookup in the batch handles it as a
+    /// failure of its own request, retrying it or being dropped.
+    pub fn blob_batch_send_failed(&mut self, batch: FailedBlobBatch, cx: &SyncNetworkContext<T>) {
+        debug!(self.log, "Failed to send batched BlobsByRoot request";
+            "peer_id" => %batch.peer_id, "num_lookups" => batch.requests.len(), "error" => batch.error);
+        self.blob_lookup_requests_failed(
+            batch.requests,
+            batch.peer_id,
+            cx,
+            RPCError::InternalError(batch.error),
+        );
+    }
+
+    fn blob_lookup_requests_failed(
+        &mut self,
+        requests: Vec<BlobLookupRequest>,
+        peer_id: PeerId,
+        cx: &SyncNetworkContext<T>,
+        error: RPCError,
+    ) {
+        for request in requests {
+            match request.lookup_type {
+                LookupType::Current => self
+                    .single_block_lookup_failed::<BlobRequestState<Current, T::EthSpec>>(
+                        request.id,
+                        &peer_id,
+                        cx,
+                        error.clone(),
+                    ),
+                LookupType::Parent => self
+                    .parent_lookup_failed::<BlobRequestState<Parent, T::EthSpec>>(
+                        request.id,
+                        peer_id,
+                        cx,
+                        error.clone(),
+                    ),
+            }
+        }
+    }
+
     /* Processing responses */
 
     pub fn single_block_component_processed<R: RequestState<Current, T>>(
@@ -1070,10 +1238,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             }
         }
 
-        metrics::set_gauge(
-            &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
-            self.parent_lookups.len() as i64,
-        );
+        self.update_parent_lookup_metrics();
     }
 
     /// Find the child block that spawned the parent lookup request and add it to the chain
@@ -1272,10 +1437,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             }
         }
 
-        metrics::set_gauge(
-            &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
-            self.parent_lookups.len() as i64,
-        );
+        self.update_parent_lookup_metrics();
     }
 
     /* Helper functions */
@@ -1366,10 +1528,28 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         }
 
         // We remove and add back again requests so we want this updated regardless of outcome.
+        self.update_parent_lookup_metrics();
+    }
+
+    /// Returns the chains that were too long to be found by a parent lookup, identified by the
+    /// root and slot of the block at their tip, along with the peers that served them, so that
+    /// they can be synced by range sync.
+    pub fn take_chains_for_range_sync(&mut self) -> Vec<(Hash256, Slot, Vec<PeerId>)> {
+        std::mem::take(&mut self.chains_for_range_sync)
+    }
+
+    fn update_parent_lookup_metrics(&self) {
         metrics::set_gauge(
             &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
             self.parent_lookups.len() as i64,
         );
+        metrics::set_gauge(
+            &metrics::SYNC_PARENT_LOOKUP_BUFFERED_BYTES,
+            self.parent_lookups
+                .iter()
+                .map(ParentLookup::buffered_bytes)
+                .sum::<usize>() as i64,
+        );
     }
 
     /// Drops all the single block requests and returns how many requests were dropped.
@@ -1381,6 +1561,8 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
 
     /// Drops all the parent chain requests and returns how many requests were dropped.
     pub fn drop_parent_chain_requests(&mut self) -> usize {
-        self.parent_lookups.drain(..).len()
+        let requests_to_drop = self.parent_lookups.drain(..).len();
+        self.update_parent_lookup_metrics();
+        requests_to_drop
     }
 }
@@ -2,31 +2,34 @@ use super::single_block_lookup::{LookupRequestError, LookupVerifyError, SingleBl
 use super::{DownloadedBlock, PeerShouldHave};
 use crate::sync::block_lookups::common::Parent;
 use crate::sync::block_lookups::common::RequestState;
-use crate::sync::{manager::SLOT_IMPORT_TOLERANCE, network_context::SyncNetworkContext};
+use crate::sync::network_context::SyncNetworkContext;
 use beacon_chain::block_verification_types::AsBlock;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::data_availability_checker::{ChildComponents, DataAvailabilityChecker};
 use beacon_chain::BeaconChainTypes;
 use itertools::Itertools;
 use lighthouse_network::PeerId;
+use ssz::Encode;
 use std::collections::VecDeque;
 use std::sync::Arc;
 use store::Hash256;
 use strum::IntoStaticStr;
+use types::{BlobSidecar, Slot};
 
 /// How many attempts we try to find a parent of a block before we give up trying.
 pub(crate) const PARENT_FAIL_TOLERANCE: u8 = 5;
-/// The maximum depth we will search for a parent block. In principle we should have sync'd any
-/// canonical chain to its head once the peer connects. A chain should not appear where it's depth
-/// is further back than the most recent head slot.
-pub(crate) const PARENT_DEPTH_TOLERANCE: usize = SLOT_IMPORT_TOLERANCE * 2;
 
 /// Maintains a sequential list of parents to lookup and the lookup's current state.
 pub(crate) struct ParentLookup<T: BeaconChainTypes> {
     /// The root of the block triggering this parent request.
     chain_hash: Hash256,
+    /// The slot of the block at `chain_hash`.
+    chain_slot: Slot,
     /// The blocks that have currently been downloaded.
     downloaded_blocks: Vec<DownloadedBlock<T::EthSpec>>,
+    /// The maximum number of blocks to download before the chain is considered too long to be
+    /// found by a parent lookup.
+    max_depth: usize,
     /// Request of the last parent.
     pub current_parent_request: SingleBlockLookup<Parent, T>,
 }
@@ -60,8 +63,10 @@ pub enum RequestError {
 impl<T: BeaconChainTypes> ParentLookup<T> {
     pub fn new(
         block_root: Hash256,
+        block_slot: Slot,
         parent_root: Hash256,
         peer_id: PeerShouldHave,
+        max_depth: usize,
         da_checker: Arc<DataAvailabilityChecker<T>>,
         cx: &mut SyncNetworkContext<T>,
     ) -> Self {
@@ -75,7 +80,9 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
 
         Self {
             chain_hash: block_root,
+            chain_slot: block_slot,
             downloaded_blocks: vec![],
+            max_depth,
             current_parent_request,
         }
     }
@@ -93,7 +100,7 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
     /// Attempts to request the next unknown parent. If the request fails, it should be removed.
     pub fn request_parent(&mut self, cx: &SyncNetworkContext<T>) -> Result<(), RequestError> {
         // check to make sure this request hasn't failed
-        if self.downloaded_blocks.len() + 1 >= PARENT_DEPTH_TOLERANCE {
+        if self.downloaded_blocks.len() + 1 >= self.max_depth {
             return Err(RequestError::ChainTooLong);
         }
 
@@ -152,7 +159,9 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
     ) {
         let ParentLookup {
             chain_hash,
+            chain_slot: _,
             downloaded_blocks,
+            max_depth: _,
             current_parent_request,
         } = self;
         let block_count = downloaded_blocks.len();
@@ -170,6 +179,19 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
         self.chain_hash
     }
 
+    pub fn chain_slot(&self) -> Slot {
+        self.chain_slot
+    }
+
+    /// Returns the approximate number of bytes used by the downloaded blocks and blobs.
+    pub fn buffered_bytes(&self) -> usize {
+        let blob_size = BlobSidecar::<T::EthSpec>::max_size();
+        self.downloaded_blocks
+            .iter()
+            .map(|(_, block)| block.as_block().ssz_bytes_len() + block.n_blobs() * blob_size)
+            .sum()
+    }
+
     pub fn processing_failed(&mut self) {
         self.current_parent_request
             .block_request_state
@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use super::*;
 
+use crate::sync::block_lookups::blob_batcher::BLOB_BATCH_WINDOW;
 use crate::sync::block_lookups::common::ResponseType;
 use beacon_chain::builder::Witness;
 use beacon_chain::eth1_chain::CachingEth1Backend;
@@ -15,10 +16,13 @@ use beacon_chain::test_utils::{
 };
 use beacon_processor::WorkEvent;
 use lighthouse_network::rpc::RPCResponseErrorCode;
-use lighthouse_network::{NetworkGlobals, Request};
+use lighthouse_network::{NetworkGlobals, Request, DEFAULT_PARENT_LOOKUP_MAX_DEPTH};
 use slot_clock::{ManualSlotClock, SlotClock, TestingSlotClock};
+use ssz::Encode;
+use std::time::Instant;
 use store::MemoryStore;
 use tokio::sync::mpsc;
+use types::blob_sidecar::BlobIdentifier;
 use types::{
     test_utils::{SeedableRng, XorShiftRng},
     BlobSidecar, EthSpec, ForkName, MinimalEthSpec as E, SignedBeaconBlock,
@@ -68,9 +72,10 @@ impl TestRig {
 
         let bl = BlockLookups::new(
             chain.data_availability_checker.clone(),
+            DEFAULT_PARENT_LOOKUP_MAX_DEPTH,
             log.new(slog::o!("component" => "block_lookups")),
         );
-        let cx = {
+        let mut cx = {
             SyncNetworkContext::new(
                 network_tx,
                 Arc::new(network_beacon_processor),
@@ -78,6 +83,8 @@ impl TestRig {
                 log.new(slog::o!("component" => "network_context")),
             )
         };
+        // Send blob requests straight away, tests of batching enable it explicitly.
+        cx.set_blob_batch_window(Duration::ZERO);
 
         (bl, cx, rig)
     }
@@ -143,6 +150,18 @@ impl TestRig {
         }
     }
 
+    #[track_caller]
+    fn expect_batched_blob_request(&mut self) -> (Id, Vec<BlobIdentifier>) {
+        match self.network_rx.try_recv() {
+            Ok(NetworkMessage::SendRequest {
+                peer_id: _,
+                request: Request::BlobsByRoot(request),
+                request_id: RequestId::Sync(SyncId::BatchedBlobs { id }),
+            }) => (id, request.blob_ids.into()),
+            other => panic!("Expected batched blob request, found {:?}", other),
+        }
+    }
+
     #[track_caller]
     fn expect_block_process(&mut self, response_type: ResponseType) {
         match response_type {
@@ -891,8 +910,8 @@ fn test_parent_lookup_too_deep() {
         .spec
         .fork_name_at_slot::<E>(rig.harness.chain.slot().unwrap());
     let mut blocks =
-        Vec::<Arc<SignedBeaconBlock<E>>>::with_capacity(parent_lookup::PARENT_DEPTH_TOLERANCE);
-    while blocks.len() < parent_lookup::PARENT_DEPTH_TOLERANCE {
+        Vec::<Arc<SignedBeaconBlock<E>>>::with_capacity(DEFAULT_PARENT_LOOKUP_MAX_DEPTH);
+    while blocks.len() < DEFAULT_PARENT_LOOKUP_MAX_DEPTH {
         let parent = blocks
             .last()
             .map(|b| b.canonical_root())
@@ -915,6 +934,7 @@ fn test_parent_lookup_too_deep() {
         &mut cx,
     );
 
+    let mut buffered_bytes = 0;
     for block in blocks.into_iter().rev() {
         let id = rig.expect_parent_request(response_type);
         // If we're in deneb, a blob request should have been triggered as well,
@@ -935,15 +955,49 @@ fn test_parent_lookup_too_deep() {
         // the processing request
         rig.expect_block_process(response_type);
         // the processing result
+        buffered_bytes += block.ssz_bytes_len();
         bl.parent_block_processed(
             chain_hash,
             BlockError::ParentUnknown(RpcBlock::new_without_blobs(None, block)).into(),
             &mut cx,
-        )
+        );
+        if let Some(parent_lookup) = bl.parent_lookups.first() {
+            assert_eq!(parent_lookup.buffered_bytes(), buffered_bytes);
+        }
     }
 
+    // At the maximum depth the lookup is abandoned, the peer is penalized and the chain is handed
+    // to range sync, targeting the block at its tip. The chain isn't considered failed.
     rig.expect_penalty();
-    assert!(bl.failed_chains.contains(&chain_hash));
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_lookups.len(), 0);
+    assert!(!bl.failed_chains.contains(&chain_hash));
+    assert_eq!(
+        bl.take_chains_for_range_sync(),
+        vec![(chain_hash, trigger_slot, vec![peer_id])]
+    );
+    assert!(bl.take_chains_for_range_sync().is_empty());
+
+    // Neither the chain nor its descendants are searched for again whilst range sync is syncing
+    // it.
+    bl.search_parent(
+        trigger_slot,
+        trigger_block_root,
+        trigger_parent_root,
+        peer_id,
+        &mut cx,
+    );
+    rig.expect_empty_network();
+    let child_block = rig.rand_block(fork_name);
+    bl.search_parent(
+        trigger_slot + 1,
+        child_block.canonical_root(),
+        chain_hash,
+        peer_id,
+        &mut cx,
+    );
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_lookups.len(), 0);
 }
 
 #[test]
@@ -2241,4 +2295,268 @@ mod deneb_only {
             .parent_block_imported()
             .expect_parent_chain_process();
     }
+
+    /// A lookup for a block whose blobs are requested in a batch.
+    struct BatchedLookup {
+        block: Arc<SignedBeaconBlock<E>>,
+        blobs: Vec<Arc<BlobSidecar<E>>>,
+        block_req_id: SingleLookupReqId,
+    }
+
+    impl BatchedLookup {
+        fn block_root(&self) -> Hash256 {
+            self.block.canonical_root()
+        }
+    }
+
+    struct BatchedBlobTester {
+        bl: BlockLookups<T>,
+        cx: SyncNetworkContext<T>,
+        rig: TestRig,
+        peer_id: PeerId,
+        lookups: Vec<BatchedLookup>,
+    }
+
+    impl BatchedBlobTester {
+        /// Starts lookups for `num_blocks` blocks with blobs, all attested to by the same peer.
+        fn new(num_blocks: usize) -> Option<Self> {
+            let fork_name = get_fork_name();
+            if !matches!(fork_name, ForkName::Deneb) {
+                return None;
+            }
+            let (mut bl, mut cx, mut rig) = TestRig::test_setup(false);
+            cx.set_blob_batch_window(BLOB_BATCH_WINDOW);
+            rig.harness.chain.slot_clock.set_slot(
+                E::slots_per_epoch() * rig.harness.spec.deneb_fork_epoch.unwrap().as_u64(),
+            );
+
+            let peer_id = PeerId::random();
+            let mut lookups = vec![];
+            for _ in 0..num_blocks {
+                let (block, blobs) = rig.rand_block_and_blobs(fork_name, NumBlobs::Random);
+                let block = Arc::new(block);
+                bl.search_block(
+                    block.canonical_root(),
+                    &[PeerShouldHave::BlockAndBlobs(peer_id)],
+                    &mut cx,
+                );
+                let block_req_id = rig.expect_lookup_request(ResponseType::Block);
+                lookups.push(BatchedLookup {
+                    block,
+                    blobs: blobs.into_iter().map(Arc::new).collect(),
+                    block_req_id,
+                });
+            }
+            // The blob requests are held back to be batched.
+            rig.expect_empty_network();
+
+            Some(Self {
+                bl,
+                cx,
+                rig,
+                peer_id,
+                lookups,
+            })
+        }
+
+        fn send_blob_batches(&mut self) {
+            let failed = self
+                .cx
+                .send_due_blob_batches(Instant::now() + BLOB_BATCH_WINDOW);
+            assert!(failed.is_empty(), "failed to send {:?}", failed);
+        }
+
+        fn blob_response(&mut self, batch_id: Id, blob: Option<Arc<BlobSidecar<E>>>) {
+            self.bl
+                .batched_blob_response(batch_id, self.peer_id, blob, D, &self.cx);
+        }
+    }
+
+    #[test]
+    fn batched_blob_request_with_interleaved_response() {
+        let Some(mut tester) = BatchedBlobTester::new(2) else {
+            return;
+        };
+
+        // The possible blobs of both blocks are requested at once.
+        tester.send_blob_batches();
+        let (batch_id, blob_ids) = tester.rig.expect_batched_blob_request();
+        tester.rig.expect_empty_network();
+        let block_roots = tester
+            .lookups
+            .iter()
+            .map(BatchedLookup::block_root)
+            .collect::<Vec<_>>();
+        assert_eq!(blob_ids.len(), 2 * E::max_blobs_per_block());
+        assert!(blob_ids
+            .iter()
+            .all(|id| block_roots.contains(&id.block_root)));
+
+        // The peer interleaves the blobs of both blocks.
+        let first_blobs = tester.lookups[0].blobs.clone();
+        let second_blobs = tester.lookups[1].blobs.clone();
+        for i in 0..first_blobs.len().max(second_blobs.len()) {
+            for blobs in [&first_blobs, &second_blobs] {
+                if let Some(blob) = blobs.get(i) {
+                    tester.blob_response(batch_id, Some(blob.clone()));
+                }
+            }
+        }
+        tester.blob_response(batch_id, None);
+
+        // Each lookup gets all of its blobs, nobody is penalized and nothing is requested again.
+        tester.rig.expect_empty_network();
+        tester.rig.expect_block_process(ResponseType::Blob);
+        tester.rig.expect_block_process(ResponseType::Blob);
+        tester.rig.expect_empty_beacon_processor();
+        assert_eq!(tester.bl.single_block_lookups.len(), 2);
+    }
+
+    #[test]
+    fn batched_blob_request_with_missing_root() {
+        let Some(mut tester) = BatchedBlobTester::new(2) else {
+            return;
+        };
+
+        // Both blocks are downloaded, but can't be imported without their blobs.
+        for i in 0..tester.lookups.len() {
+            let block = tester.lookups[i].block.clone();
+            let block_root = tester.lookups[i].block_root();
+            let block_req_id = tester.lookups[i].block_req_id;
+            tester
+                .bl
+                .single_lookup_response::<BlockRequestState<Current>>(
+                    block_req_id,
+                    tester.peer_id,
+                    Some(block.clone()),
+                    D,
+                    &tester.cx,
+                );
+            tester.rig.expect_block_process(ResponseType::Block);
+            tester
+                .bl
+                .single_block_component_processed::<BlockRequestState<Current>>(
+                    block_req_id.id,
+                    BlockProcessingResult::Ok(AvailabilityProcessingStatus::MissingComponents(
+                        block.slot(),
+                        block_root,
+                    )),
+                    &mut tester.cx,
+                );
+        }
+        tester.rig.expect_empty_network();
+
+        // The peer only returns the blobs of the first block.
+        tester.send_blob_batches();
+        let (batch_id, _) = tester.rig.expect_batched_blob_request();
+        for blob in tester.lookups[0].blobs.clone() {
+            tester.blob_response(batch_id, Some(blob));
+        }
+        tester.blob_response(batch_id, None);
+        tester.rig.expect_empty_network();
+        tester.rig.expect_block_process(ResponseType::Blob);
+        tester.rig.expect_empty_beacon_processor();
+
+        // The first block is imported.
+        let first_root = tester.lookups[0].block_root();
+        tester
+            .bl
+            .single_block_component_processed::<BlobRequestState<Current, E>>(
+                tester.lookups[0].block_req_id.id,
+                BlockProcessingResult::Ok(AvailabilityProcessingStatus::Imported(first_root)),
+                &mut tester.cx,
+            );
+        assert_eq!(tester.bl.single_block_lookups.len(), 1);
+
+        // The second block is still missing its blobs, so the peer is penalized and only those
+        // blobs are requested again.
+        let second_root = tester.lookups[1].block_root();
+        tester
+            .bl
+            .single_block_component_processed::<BlobRequestState<Current, E>>(
+                tester.lookups[1].block_req_id.id,
+                BlockProcessingResult::Ok(AvailabilityProcessingStatus::MissingComponents(
+                    tester.lookups[1].block.slot(),
+                    second_root,
+                )),
+                &mut tester.cx,
+            );
+        tester.rig.expect_penalty();
+        tester.rig.expect_empty_network();
+        tester.send_blob_batches();
+        match tester.rig.network_rx.try_recv() {
+            Ok(NetworkMessage::SendRequest {
+                peer_id,
+                request: Request::BlobsByRoot(request),
+                request_id: RequestId::Sync(SyncId::SingleBlob { .. }),
+            }) => {
+                assert_eq!(peer_id, tester.peer_id);
+                assert!(!request.blob_ids.is_empty());
+                assert!(request
+                    .blob_ids
+                    .iter()
+                    .all(|id| id.block_root == second_root));
+            }
+            other => panic!("Expected blob request, found {:?}", other),
+        }
+        assert_eq!(tester.bl.single_block_lookups.len(), 1);
+    }
+
+    #[test]
+    fn batched_blob_response_with_unrequested_root() {
+        let Some(mut tester) = BatchedBlobTester::new(2) else {
+            return;
+        };
+        tester.send_blob_batches();
+        let (batch_id, _) = tester.rig.expect_batched_blob_request();
+
+        // A blob for a block that is not part of the batch is penalized.
+        let (_, other_blobs) = tester
+            .rig
+            .rand_block_and_blobs(get_fork_name(), NumBlobs::Random);
+        let other_blob = other_blobs.into_iter().next().map(Arc::new);
+        tester.blob_response(batch_id, other_blob);
+        tester.rig.expect_penalty();
+        tester.rig.expect_empty_network();
+
+        // The lookups in the batch are unaffected.
+        for blob in tester
+            .lookups
+            .iter()
+            .flat_map(|lookup| lookup.blobs.clone())
+            .collect::<Vec<_>>()
+        {
+            tester.blob_response(batch_id, Some(blob));
+        }
+        tester.blob_response(batch_id, None);
+        tester.rig.expect_empty_network();
+        tester.rig.expect_block_process(ResponseType::Blob);
+        tester.rig.expect_block_process(ResponseType::Blob);
+        tester.rig.expect_empty_beacon_processor();
+    }
+
+    #[test]
+    fn batched_blob_request_send_failure_fails_lookups() {
+        let Some(mut tester) = BatchedBlobTester::new(2) else {
+            return;
+        };
+        tester.rig.network_rx.close();
+
+        // Each failure to send is handled by the lookups in the batch, which retry their requests
+        // until they run out of attempts.
+        for _ in 0..=SINGLE_BLOCK_LOOKUP_MAX_ATTEMPTS {
+            let failed = tester
+                .cx
+                .send_due_blob_batches(Instant::now() + BLOB_BATCH_WINDOW);
+            if failed.is_empty() {
+                break;
+            }
+            for batch in failed {
+                assert_eq!(batch.peer_id, tester.peer_id);
+                tester.bl.blob_batch_send_failed(batch, &tester.cx);
+            }
+        }
+        assert!(tester.bl.single_block_lookups.is_empty());
+        assert_eq!(tester.cx.next_blob_batch_deadline(), None);
+    }
 }
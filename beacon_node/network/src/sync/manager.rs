@@ -96,6 +96,8 @@ pub enum RequestId {
     /// Request searching for a block's parent blobs. The id is the chain, shared with the corresponding
     /// block id.
     ParentLookupBlob { id: SingleLookupReqId },
+    /// Request for the blobs of several block lookups, batched into one request to the same peer.
+    BatchedBlobs { id: Id },
     /// Request was from the backfill sync algorithm.
     BackFillBlocks { id: Id },
     /// Backfill request that is composed by both a block range request and a blob range request.
@@ -242,6 +244,7 @@ pub fn spawn<T: BeaconChainTypes>(
 
     // create an instance of the SyncManager
     let network_globals = beacon_processor.network_globals.clone();
+    let parent_lookup_max_depth = network_globals.config.parent_lookup_max_depth;
     let mut sync_manager = SyncManager {
         chain: beacon_chain.clone(),
         input_channel: sync_recv,
@@ -255,6 +258,7 @@ pub fn spawn<T: BeaconChainTypes>(
         backfill_sync: BackFillSync::new(beacon_chain.clone(), network_globals, log.clone()),
         block_lookups: BlockLookups::new(
             beacon_chain.data_availability_checker.clone(),
+            parent_lookup_max_depth,
             log.clone(),
         ),
         log: log.clone(),
@@ -289,6 +293,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             head_root: status.head_root,
             finalized_epoch: status.finalized_epoch,
             finalized_root: status.finalized_root,
+            earliest_available_slot: status.earliest_available_slot,
         };
 
         let sync_type = remote_sync_type(&local, &remote, &self.chain);
@@ -344,6 +349,10 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                         error,
                     );
             }
+            RequestId::BatchedBlobs { id } => {
+                self.block_lookups
+                    .batched_blob_lookup_failed(id, peer_id, &self.network, error);
+            }
             RequestId::BackFillBlocks { id } => {
                 if let Some(batch_id) = self
                     .network
@@ -594,6 +603,14 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
         // process any inbound messages
         loop {
+            // Batched blob lookup requests are sent once their window has passed.
+            let blob_batch_deadline = self.network.next_blob_batch_deadline();
+            let send_blob_batches = tokio::time::sleep_until(
+                blob_batch_deadline
+                    .map(tokio::time::Instant::from_std)
+                    .unwrap_or_else(tokio::time::Instant::now),
+            );
+
             tokio::select! {
                 Some(sync_message) = self.input_channel.recv() => {
                     self.handle_message(sync_message);
@@ -601,6 +618,11 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 Some(engine_state) = check_ee_stream.next(), if check_ee => {
                     self.handle_new_execution_engine_state(engine_state);
                 }
+                _ = send_blob_batches, if blob_batch_deadline.is_some() => {
+                    for failed in self.network.send_due_blob_batches(std::time::Instant::now()) {
+                        self.block_lookups.blob_batch_send_failed(failed, &self.network);
+                    }
+                }
             }
         }
     }
@@ -744,6 +766,49 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     .parent_chain_processed(chain_hash, result, &self.network),
             },
         }
+
+        self.range_sync_long_parent_chains();
+    }
+
+    /// Hands the chains that were too long to be found by a parent lookup to range sync, which
+    /// syncs them in batches from our finalized checkpoint using the peers that served them.
+    fn range_sync_long_parent_chains(&mut self) {
+        let chains = self.block_lookups.take_chains_for_range_sync();
+        if chains.is_empty() {
+            return;
+        }
+
+        let status = self.chain.status_message();
+        let local = SyncInfo {
+            head_slot: status.head_slot,
+            head_root: status.head_root,
+            finalized_epoch: status.finalized_epoch,
+            finalized_root: status.finalized_root,
+            earliest_available_slot: status.earliest_available_slot,
+        };
+
+        for (chain_hash, chain_slot, peers) in chains {
+            // Target the tip of the chain itself, rather than whatever the peers' heads are, as a
+            // head chain built upon our finalized checkpoint.
+            let remote = SyncInfo {
+                head_slot: chain_slot,
+                head_root: chain_hash,
+                finalized_epoch: local.finalized_epoch,
+                finalized_root: local.finalized_root,
+                earliest_available_slot: None,
+            };
+            for peer_id in peers {
+                if !self.network_globals().peers.read().is_connected(&peer_id) {
+                    continue;
+                }
+                debug!(self.log, "Range syncing long parent chain";
+                    "chain_hash" => %chain_hash, "chain_slot" => chain_slot, "peer_id" => %peer_id);
+                self.range_sync
+                    .add_peer(&mut self.network, local.clone(), peer_id, remote.clone());
+            }
+        }
+
+        self.update_sync_state();
     }
 
     fn handle_unknown_parent(
@@ -886,6 +951,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             RequestId::ParentLookupBlob { id: _ } => {
                 crit!(self.log, "Block received during parent blob request"; "peer_id" => %peer_id  );
             }
+            RequestId::BatchedBlobs { id: _ } => {
+                crit!(self.log, "Block received during batched blob request"; "peer_id" => %peer_id  );
+            }
             RequestId::BackFillBlocks { id } => {
                 let is_stream_terminator = block.is_none();
                 if let Some(batch_id) = self
@@ -984,6 +1052,22 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                         &self.network,
                     )
             }
+            RequestId::BatchedBlobs { id } => {
+                if let Some(blob) = blob.as_ref() {
+                    debug!(self.log,
+                        "Peer returned blob for batched lookup request";
+                        "peer_id" => %peer_id ,
+                        "blob_id" =>?blob.id()
+                    );
+                }
+                self.block_lookups.batched_blob_response(
+                    id,
+                    peer_id,
+                    blob,
+                    seen_timestamp,
+                    &self.network,
+                )
+            }
             RequestId::BackFillBlocks { id: _ } => {
                 crit!(self.log, "Blob received during backfill block request"; "peer_id" => %peer_id  );
             }
@@ -7,6 +7,9 @@ use super::range_sync::{BatchId, ByRangeRequestType, ChainId};
 use crate::network_beacon_processor::NetworkBeaconProcessor;
 use crate::service::{NetworkMessage, RequestId};
 use crate::status::ToStatusMessage;
+use crate::sync::block_lookups::blob_batcher::{
+    BatchedBlobRoute, BlobLookupRequest, BlobRequestBatcher, FailedBlobBatch, BLOB_BATCH_WINDOW,
+};
 use crate::sync::block_lookups::common::LookupType;
 use crate::sync::manager::SingleLookupReqId;
 use beacon_chain::block_verification_types::RpcBlock;
@@ -15,11 +18,14 @@ use fnv::FnvHashMap;
 use lighthouse_network::rpc::methods::{BlobsByRangeRequest, BlobsByRootRequest};
 use lighthouse_network::rpc::{BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason};
 use lighthouse_network::{Client, NetworkGlobals, PeerAction, PeerId, ReportSource, Request};
+use parking_lot::Mutex;
 use slog::{debug, trace, warn};
+use ssz_types::VariableList;
 use std::collections::hash_map::Entry;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
-use types::{BlobSidecar, EthSpec, SignedBeaconBlock};
+use types::{BlobSidecar, EthSpec, Hash256, SignedBeaconBlock};
 
 pub struct BlocksAndBlobsByRangeResponse<T: EthSpec> {
     pub batch_id: BatchId,
@@ -53,6 +59,10 @@ pub struct SyncNetworkContext<T: BeaconChainTypes> {
     backfill_blocks_and_blobs_requests:
         FnvHashMap<Id, (BatchId, BlocksAndBlobsRequestInfo<T::EthSpec>)>,
 
+    /// BlobsByRoot requests of block lookups, batched per peer. Lookups only hold a shared
+    /// reference to the context when requesting, hence the lock.
+    blob_batcher: Mutex<BlobRequestBatcher>,
+
     /// Whether the ee is online. If it's not, we don't allow access to the
     /// `beacon_processor_send`.
     execution_engine_state: EngineState,
@@ -99,6 +109,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             backfill_requests: FnvHashMap::default(),
             range_blocks_and_blobs_requests: FnvHashMap::default(),
             backfill_blocks_and_blobs_requests: FnvHashMap::default(),
+            blob_batcher: Mutex::new(BlobRequestBatcher::new(BLOB_BATCH_WINDOW)),
             network_beacon_processor,
             chain,
             log,
@@ -435,12 +446,130 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         Ok(())
     }
 
+    /// Requests blobs for a block lookup. Unless batching is disabled, the request is held back
+    /// for a short while so that it can be sent to the peer along with those of other lookups.
+    ///
+    /// Batched requests are only sent by `Self::send_due_blob_batches`, which returns those that
+    /// failed so that their lookups can be notified.
     pub fn blob_lookup_request(
         &self,
         id: SingleLookupReqId,
         blob_peer_id: PeerId,
         blob_request: BlobsByRootRequest,
         lookup_type: LookupType,
+    ) -> Result<(), &'static str> {
+        let mut batcher = self.blob_batcher.lock();
+        if !batcher.is_enabled() {
+            drop(batcher);
+            return self.send_blob_lookup_request(id, blob_peer_id, blob_request, lookup_type);
+        }
+        if blob_request.blob_ids.is_empty() {
+            return Ok(());
+        }
+        let request = BlobLookupRequest {
+            id,
+            lookup_type,
+            blob_ids: blob_request.blob_ids.into(),
+        };
+        batcher.queue(blob_peer_id, request, Instant::now());
+        Ok(())
+    }
+
+    /// The time at which the next batch of blob lookup requests is due to be sent.
+    pub fn next_blob_batch_deadline(&self) -> Option<Instant> {
+        self.blob_batcher.lock().next_deadline()
+    }
+
+    /// Sends the batches of blob lookup requests that are due, returning those that could not be
+    /// sent.
+    pub fn send_due_blob_batches(&self, now: Instant) -> Vec<FailedBlobBatch> {
+        let due = self.blob_batcher.lock().take_due(now);
+        due.into_iter()
+            .filter_map(|(peer_id, requests)| self.send_blob_batch(peer_id, requests).err())
+            .collect()
+    }
+
+    /// Finds the lookup a blob received for the batched request `batch_id` belongs to.
+    pub fn batched_blob_route(&self, batch_id: Id, block_root: Hash256) -> BatchedBlobRoute {
+        self.blob_batcher.lock().route_blob(batch_id, block_root)
+    }
+
+    /// Removes a batched request whose response has ended or failed, returning the requests of the
+    /// lookups it served.
+    pub fn remove_blob_batch(&self, batch_id: Id) -> Option<Vec<BlobLookupRequest>> {
+        self.blob_batcher.lock().remove_in_flight(batch_id)
+    }
+
+    #[cfg(test)]
+    pub fn set_blob_batch_window(&mut self, window: std::time::Duration) {
+        *self.blob_batcher.get_mut() = BlobRequestBatcher::new(window);
+    }
+
+    /// Sends the requests of a batch as a single BlobsByRoot request. A lone request is sent as it
+    /// would be without batching, so that its responses go straight to its lookup.
+    fn send_blob_batch(
+        &self,
+        peer_id: PeerId,
+        requests: Vec<BlobLookupRequest>,
+    ) -> Result<(), FailedBlobBatch> {
+        if let [request] = requests.as_slice() {
+            let blob_request = BlobsByRootRequest {
+                blob_ids: VariableList::from(request.blob_ids.clone()),
+            };
+            let (id, lookup_type) = (request.id, request.lookup_type);
+            return self
+                .send_blob_lookup_request(id, peer_id, blob_request, lookup_type)
+                .map_err(|error| FailedBlobBatch {
+                    peer_id,
+                    requests,
+                    error,
+                });
+        }
+
+        let block_roots = requests
+            .iter()
+            .filter_map(|req| req.blob_ids.first().map(|id| id.block_root))
+            .collect::<Vec<_>>();
+        let blob_ids = requests
+            .iter()
+            .flat_map(|req| req.blob_ids.iter().copied())
+            .collect::<Vec<_>>();
+        let batch_id = self.blob_batcher.lock().register_in_flight(requests);
+
+        debug!(
+            self.log,
+            "Sending batched BlobsByRoot Request";
+            "method" => "BlobsByRoot",
+            "block_roots" => ?block_roots,
+            "num_blobs" => blob_ids.len(),
+            "peer" => %peer_id,
+        );
+
+        let request_id = RequestId::Sync(SyncRequestId::BatchedBlobs { id: batch_id });
+        self.send_network_msg(NetworkMessage::SendRequest {
+            peer_id,
+            request: Request::BlobsByRoot(BlobsByRootRequest {
+                blob_ids: VariableList::from(blob_ids),
+            }),
+            request_id,
+        })
+        .map_err(|error| FailedBlobBatch {
+            peer_id,
+            requests: self
+                .blob_batcher
+                .lock()
+                .remove_in_flight(batch_id)
+                .unwrap_or_default(),
+            error,
+        })
+    }
+
+    fn send_blob_lookup_request(
+        &self,
+        id: SingleLookupReqId,
+        blob_peer_id: PeerId,
+        blob_request: BlobsByRootRequest,
+        lookup_type: LookupType,
     ) -> Result<(), &'static str> {
         let sync_id = match lookup_type {
             LookupType::Current => SyncRequestId::SingleBlob { id },
@@ -97,6 +97,12 @@ pub struct BatchInfo<T: EthSpec, B: BatchConfig = RangeSyncBatchConfig> {
     start_slot: Slot,
     /// End slot of the batch.
     end_slot: Slot,
+    /// The first slot requested from peers. This is later than `start_slot` if the local chain
+    /// already had blocks in the batch when it was requested.
+    request_start_slot: Slot,
+    /// Whether the request may skip the slots the local chain already has. This is disabled once
+    /// a shortened request returns no blocks, or blocks that don't connect to the local chain.
+    skip_known_slots: bool,
     /// The `Attempts` that have been made and failed to send us this batch.
     failed_processing_attempts: Vec<Attempt>,
     /// Number of processing attempts that have failed but we do not count.
@@ -161,6 +167,8 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         BatchInfo {
             start_slot,
             end_slot,
+            request_start_slot: start_slot,
+            skip_known_slots: true,
             failed_processing_attempts: Vec::new(),
             failed_download_attempts: Vec::new(),
             non_faulty_processing_attempts: 0,
@@ -170,6 +178,11 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
         }
     }
 
+    /// Returns the first slot of the batch.
+    pub fn start_slot(&self) -> Slot {
+        self.start_slot
+    }
+
     /// Gives a list of peers from which this batch has had a failed download or processing
     /// attempt.
     pub fn failed_peers(&self) -> HashSet<PeerId> {
@@ -221,13 +234,41 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
     pub fn to_blocks_by_range_request(&self) -> (BlocksByRangeRequest, ByRangeRequestType) {
         (
             BlocksByRangeRequest::new(
-                self.start_slot.into(),
-                self.end_slot.sub(self.start_slot).into(),
+                self.request_start_slot.into(),
+                self.end_slot.sub(self.request_start_slot).into(),
             ),
             self.batch_type,
         )
     }
 
+    /// Shortens the next request for this batch so that it starts after the latest slot of the
+    /// batch at which the local chain has a block, as returned by `latest_known_slot` for the
+    /// range of the batch. The last slot of the batch is always requested.
+    ///
+    /// Returns true if the request skips any slots of the batch.
+    pub fn skip_known_slots(
+        &mut self,
+        latest_known_slot: impl FnOnce(Slot, Slot) -> Option<Slot>,
+    ) -> bool {
+        if !self.skip_known_slots || !matches!(self.state, BatchState::AwaitingDownload) {
+            return false;
+        }
+        self.request_start_slot = latest_known_slot(self.start_slot, self.end_slot)
+            .map_or(self.start_slot, |slot| (slot + 1).min(self.end_slot - 1));
+        self.is_shortened()
+    }
+
+    /// Returns true if the batch is requested from a later slot than its start slot.
+    pub fn is_shortened(&self) -> bool {
+        self.request_start_slot > self.start_slot
+    }
+
+    /// Requests the full range of the batch from now on.
+    pub fn request_full_range(&mut self) {
+        self.request_start_slot = self.start_slot;
+        self.skip_known_slots = false;
+    }
+
     /// After different operations over a batch, this could be in a state that allows it to
     /// continue, or in failed state. When the batch has failed, we check if it did mainly due to
     /// processing failures. In this case the batch is considered failed and faulty.
@@ -285,8 +326,8 @@ impl<T: EthSpec, B: BatchConfig> BatchInfo<T, B> {
                     // the batch is non-empty
                     let first_slot = blocks[0].slot();
 
-                    let failed_range = if first_slot < self.start_slot {
-                        Some((self.start_slot, first_slot))
+                    let failed_range = if first_slot < self.request_start_slot {
+                        Some((self.request_start_slot, first_slot))
                     } else if self.end_slot < last_slot {
                         Some((self.end_slot, last_slot))
                     } else {
@@ -505,6 +546,14 @@ impl<T: EthSpec, B: BatchConfig> slog::KV for BatchInfo<T, B> {
     ) -> slog::Result {
         use slog::Value;
         Value::serialize(&self.start_slot, record, "start_slot", serializer)?;
+        if self.is_shortened() {
+            Value::serialize(
+                &self.request_start_slot,
+                record,
+                "request_start_slot",
+                serializer,
+            )?;
+        }
         Value::serialize(
             &(self.end_slot - 1), // NOTE: The -1 shows inclusive blocks
             record,
@@ -1,13 +1,32 @@
-use beacon_chain::{BeaconChain, BeaconChainTypes};
-use types::Hash256;
+use beacon_chain::{BeaconChain, BeaconChainTypes, WhenSlotSkipped};
+use types::{Hash256, Slot};
 
 /// Trait that helps maintain RangeSync's implementation split from the BeaconChain
-pub trait BlockStorage {
+pub trait BlockStorage: Send + Sync {
     fn is_block_known(&self, block_root: &Hash256) -> bool;
+
+    /// Returns the slot of the latest block in `start_slot..end_slot` on the local canonical
+    /// chain, if any.
+    fn latest_known_slot(&self, start_slot: Slot, end_slot: Slot) -> Option<Slot>;
 }
 
 impl<T: BeaconChainTypes> BlockStorage for BeaconChain<T> {
     fn is_block_known(&self, block_root: &Hash256) -> bool {
         self.block_is_known_to_fork_choice(block_root)
     }
+
+    fn latest_known_slot(&self, start_slot: Slot, end_slot: Slot) -> Option<Slot> {
+        let head_slot = self.canonical_head.cached_head().head_slot();
+        (start_slot.as_u64()..end_slot.min(head_slot + 1).as_u64())
+            .rev()
+            .map(Slot::new)
+            .find(|slot| {
+                self.block_root_at_slot(*slot, WhenSlotSkipped::None)
+                    .ok()
+                    .flatten()
+                    .map_or(false, |block_root| {
+                        self.block_is_known_to_fork_choice(&block_root)
+                    })
+            })
+    }
 }
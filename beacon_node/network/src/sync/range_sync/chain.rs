@@ -1,9 +1,11 @@
 use super::batch::{BatchInfo, BatchProcessingResult, BatchState};
+use super::block_storage::BlockStorage;
+use crate::metrics;
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::{
     manager::Id, network_context::SyncNetworkContext, BatchOperationOutcome, BatchProcessResult,
 };
-use beacon_chain::block_verification_types::RpcBlock;
+use beacon_chain::block_verification_types::{AsBlock, RpcBlock};
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
 use lighthouse_network::{PeerAction, PeerId};
@@ -11,6 +13,7 @@ use rand::seq::SliceRandom;
 use slog::{crit, debug, o, warn};
 use std::collections::{btree_map::Entry, BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use types::{Epoch, EthSpec, Hash256, Slot};
 
 /// Blocks are downloaded in batches from peers. This constant specifies how many epochs worth of
@@ -101,6 +104,9 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// Batches validated by this chain.
     validated_batches: u64,
 
+    /// Used to skip blocks that have already been imported, e.g. via gossip.
+    block_storage: Arc<dyn BlockStorage>,
+
     /// The chain's log.
     log: slog::Logger,
 }
@@ -126,6 +132,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         target_head_slot: Slot,
         target_head_root: Hash256,
         peer_id: PeerId,
+        block_storage: Arc<dyn BlockStorage>,
         log: &slog::Logger,
     ) -> Self {
         let mut peers = FnvHashMap::default();
@@ -147,6 +154,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             state: ChainSyncingState::Stopped,
             current_processing_batch: None,
             validated_batches: 0,
+            block_storage,
             log: log.new(o!("chain" => id)),
         }
     }
@@ -294,8 +302,10 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             return Ok(KeepChain);
         }
 
+        // The processor is cloned so that `network` can be used if the batch completes without
+        // being sent to it.
         let beacon_processor = match network.beacon_processor_if_enabled() {
-            Some(beacon_processor) => beacon_processor,
+            Some(beacon_processor) => beacon_processor.clone(),
             None => return Ok(KeepChain),
         };
 
@@ -313,10 +323,54 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // result callback. This is done, because an empty batch could end a chain and the logic
         // for removing chains and checking completion is in the callback.
 
-        let blocks = batch.start_processing()?;
+        let is_shortened = batch.is_shortened();
+        let mut blocks = batch.start_processing()?;
         let process_id = ChainSegmentProcessId::RangeBatchId(self.id, batch_id);
         self.current_processing_batch = Some(batch_id);
 
+        // Blocks may have been imported via gossip since the batch was requested. These are
+        // removed from the start and end of the batch so that the remaining blocks are still a
+        // contiguous segment.
+        let known_blocks = trim_known_blocks(&mut blocks, self.block_storage.as_ref());
+        if known_blocks > 0 {
+            debug!(self.log, "Skipping known blocks in batch"; "batch_epoch" => batch_id,
+                "known_blocks" => known_blocks, "remaining_blocks" => blocks.len());
+            metrics::inc_counter_by(
+                &metrics::SYNC_RANGE_KNOWN_BLOCKS_SKIPPED,
+                known_blocks as u64,
+            );
+            if blocks.is_empty() {
+                // The batch only contained blocks that have been imported, so it is as good as
+                // processed.
+                return self.on_batch_process_result(
+                    network,
+                    batch_id,
+                    &BatchProcessResult::Success {
+                        was_non_empty: true,
+                    },
+                );
+            }
+        }
+
+        // A shortened batch assumes that the peer's chain includes the local chain up to the start
+        // of the request. If its first block doesn't build on a known block this assumption was
+        // wrong, which is not the peer's fault. An empty response can't be checked, so it is
+        // treated the same way rather than as a successful batch.
+        if is_shortened
+            && blocks.first().map_or(true, |block| {
+                !self.block_storage.is_block_known(&block.parent_root())
+            })
+        {
+            debug!(self.log, "Shortened batch does not connect to the local chain";
+                "batch_epoch" => batch_id, "blocks" => blocks.len());
+            self.current_processing_batch = None;
+            if let Some(batch) = self.batches.get_mut(&batch_id) {
+                batch.processing_completed(BatchProcessingResult::NonFaultyFailure)?;
+                batch.request_full_range();
+            }
+            return self.retry_batch_download(network, batch_id);
+        }
+
         if let Err(e) = beacon_processor.send_chain_segment(process_id, blocks) {
             crit!(self.log, "Failed to send chain segment to processor."; "msg" => "process_batch",
                 "error" => %e, "batch" => self.processing_target);
@@ -859,16 +913,26 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // Find a peer to request the batch
         let failed_peers = batch.failed_peers();
+        let batch_start_slot = batch.start_slot();
 
         let new_peer = {
+            let peers = network.network_globals().peers.read();
             let mut priorized_peers = self
                 .peers
                 .iter()
-                .map(|(peer, requests)| (failed_peers.contains(peer), requests.len(), *peer))
+                .map(|(peer, requests)| {
+                    (
+                        !peers.may_have_block_at(peer, batch_start_slot),
+                        failed_peers.contains(peer),
+                        requests.len(),
+                        *peer,
+                    )
+                })
                 .collect::<Vec<_>>();
-            // Sort peers prioritizing unrelated peers with less active requests.
+            // Sort peers prioritizing peers which have the blocks of the batch, then unrelated
+            // peers with less active requests.
             priorized_peers.sort_unstable();
-            priorized_peers.get(0).map(|&(_, _, peer)| peer)
+            priorized_peers.get(0).map(|&(_, _, _, peer)| peer)
         };
 
         if let Some(peer) = new_peer {
@@ -887,6 +951,13 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         peer: PeerId,
     ) -> ProcessingResult {
         if let Some(batch) = self.batches.get_mut(&batch_id) {
+            // Skip the slots of the batch the local chain already has, e.g. via gossip.
+            let block_storage = &self.block_storage;
+            if batch.skip_known_slots(|start_slot, end_slot| {
+                block_storage.latest_known_slot(start_slot, end_slot)
+            }) {
+                metrics::inc_counter(&metrics::SYNC_RANGE_SHORTENED_REQUESTS);
+            }
             let (request, batch_type) = batch.to_blocks_by_range_request();
             match network.blocks_by_range_request(peer, batch_type, request, self.id, batch_id) {
                 Ok(request_id) => {
@@ -974,17 +1045,24 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // randomize the peers for load balancing
         let mut rng = rand::thread_rng();
+        // Batches are requested in increasing order, so peers which don't have the blocks of the
+        // next batch, e.g. after checkpoint sync, are left for later batches.
+        let next_batch_start_slot = self
+            .to_be_downloaded
+            .start_slot(T::EthSpec::slots_per_epoch());
+        let peer_db = network.network_globals().peers.read();
         let mut idle_peers = self
             .peers
             .iter()
             .filter_map(|(peer, requests)| {
-                if requests.is_empty() {
+                if requests.is_empty() && peer_db.may_have_block_at(peer, next_batch_start_slot) {
                     Some(*peer)
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
+        drop(peer_db);
         idle_peers.shuffle(&mut rng);
 
         // check if we have the batch for our optimistic start. If not, request it first.
@@ -1102,6 +1180,27 @@ impl<T: BeaconChainTypes> slog::KV for SyncingChain<T> {
     }
 }
 
+/// Removes the blocks already known to fork choice from the start and end of `blocks`, returning
+/// the number of blocks removed.
+fn trim_known_blocks<E: EthSpec>(
+    blocks: &mut Vec<RpcBlock<E>>,
+    block_storage: &dyn BlockStorage,
+) -> usize {
+    let original_len = blocks.len();
+    let is_known = |block: &RpcBlock<E>| block_storage.is_block_known(&block.block_root());
+
+    let known_prefix = blocks.iter().take_while(|block| is_known(block)).count();
+    blocks.drain(..known_prefix);
+    let known_suffix = blocks
+        .iter()
+        .rev()
+        .take_while(|block| is_known(block))
+        .count();
+    blocks.truncate(blocks.len() - known_suffix);
+
+    original_len - blocks.len()
+}
+
 use super::batch::WrongState as WrongBatchState;
 impl From<WrongBatchState> for RemoveChain {
     fn from(err: WrongBatchState) -> Self {
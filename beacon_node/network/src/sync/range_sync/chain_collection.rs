@@ -51,7 +51,7 @@ pub struct ChainCollection<T: BeaconChainTypes, C> {
     log: slog::Logger,
 }
 
-impl<T: BeaconChainTypes, C: BlockStorage> ChainCollection<T, C> {
+impl<T: BeaconChainTypes, C: BlockStorage + 'static> ChainCollection<T, C> {
     pub fn new(beacon_chain: Arc<C>, log: slog::Logger) -> Self {
         ChainCollection {
             beacon_chain,
@@ -493,6 +493,7 @@ impl<T: BeaconChainTypes, C: BlockStorage> ChainCollection<T, C> {
                     target_head_slot,
                     target_head_root,
                     peer,
+                    self.beacon_chain.clone(),
                     &self.log,
                 );
                 debug_assert_eq!(new_chain.get_id(), id);
@@ -81,7 +81,7 @@ pub struct RangeSync<T: BeaconChainTypes, C = BeaconChain<T>> {
 
 impl<T: BeaconChainTypes, C> RangeSync<T, C>
 where
-    C: BlockStorage + ToStatusMessage,
+    C: BlockStorage + ToStatusMessage + 'static,
     T: BeaconChainTypes,
 {
     pub fn new(beacon_chain: Arc<C>, log: slog::Logger) -> Self {
@@ -354,6 +354,7 @@ where
             head_root: status.head_root,
             finalized_epoch: status.finalized_epoch,
             finalized_root: status.finalized_root,
+            earliest_available_slot: status.earliest_available_slot,
         };
 
         // update the state of the collection
@@ -391,14 +392,17 @@ mod tests {
     use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
     use beacon_chain::EngineState;
     use beacon_processor::WorkEvent as BeaconWorkEvent;
-    use lighthouse_network::{rpc::StatusMessage, NetworkGlobals};
+    use lighthouse_network::rpc::{BlocksByRangeRequest, StatusMessage};
+    use lighthouse_network::{NetworkGlobals, Request};
     use slog::{o, Drain};
     use slot_clock::TestingSlotClock;
     use std::collections::HashSet;
     use std::sync::Arc;
     use store::MemoryStore;
     use tokio::sync::mpsc;
-    use types::{ForkName, Hash256, MinimalEthSpec as E};
+    use types::{
+        BeaconBlock, ForkName, Hash256, MinimalEthSpec as E, Signature, SignedBeaconBlock,
+    };
 
     #[derive(Debug)]
     struct FakeStorage {
@@ -416,6 +420,7 @@ mod tests {
                     finalized_epoch: 0usize.into(),
                     head_root: Hash256::zero(),
                     head_slot: 0usize.into(),
+                    earliest_available_slot: Some(0usize.into()),
                 }),
             }
         }
@@ -436,6 +441,12 @@ mod tests {
         fn is_block_known(&self, block_root: &store::Hash256) -> bool {
             self.known_blocks.read().contains(block_root)
         }
+
+        fn latest_known_slot(&self, start_slot: Slot, end_slot: Slot) -> Option<Slot> {
+            // There is a block at every slot up to the head.
+            let head_slot = self.status.read().head_slot;
+            (head_slot >= start_slot).then(|| head_slot.min(end_slot - 1))
+        }
     }
 
     impl ToStatusMessage for FakeStorage {
@@ -503,12 +514,14 @@ mod tests {
                 finalized_epoch,
                 head_root,
                 head_slot,
+                earliest_available_slot,
             } = self.chain.status.read().clone();
             SyncInfo {
                 head_slot,
                 head_root,
                 finalized_epoch,
                 finalized_root,
+                earliest_available_slot,
             }
         }
 
@@ -519,14 +532,25 @@ mod tests {
             expected_peer: &PeerId,
             fork_name: ForkName,
         ) -> (RequestId, Option<RequestId>) {
-            let block_req_id = if let Ok(NetworkMessage::SendRequest {
+            let (block_req_id, blob_req_id, _) = self.grab_blocks_request(expected_peer, fork_name);
+            (block_req_id, blob_req_id)
+        }
+
+        /// Like `grab_request`, but also returns the requested `BlocksByRange` range.
+        #[track_caller]
+        fn grab_blocks_request(
+            &mut self,
+            expected_peer: &PeerId,
+            fork_name: ForkName,
+        ) -> (RequestId, Option<RequestId>, BlocksByRangeRequest) {
+            let (block_req_id, blocks_request) = if let Ok(NetworkMessage::SendRequest {
                 peer_id,
-                request: _,
+                request: Request::BlocksByRange(blocks_request),
                 request_id,
             }) = self.network_rx.try_recv()
             {
                 assert_eq!(&peer_id, expected_peer);
-                request_id
+                (request_id, blocks_request)
             } else {
                 panic!("Should have sent a batch request to the peer")
             };
@@ -546,7 +570,74 @@ mod tests {
                 }
                 _ => None,
             };
-            (block_req_id, blob_req_id)
+            (block_req_id, blob_req_id, blocks_request)
+        }
+
+        /// Responds to a batch request of `peer` with `blocks`, followed by the stream terminator.
+        fn complete_request(
+            &mut self,
+            range: &mut RangeSync<TestBeaconChainType, FakeStorage>,
+            peer: PeerId,
+            (block_req, blob_req_opt): (RequestId, Option<RequestId>),
+            blocks: Vec<Arc<SignedBeaconBlock<E>>>,
+        ) {
+            if blob_req_opt.is_some() {
+                let id = match block_req {
+                    RequestId::Sync(crate::sync::manager::RequestId::RangeBlockAndBlobs { id }) => {
+                        id
+                    }
+                    other => panic!("unexpected request {:?}", other),
+                };
+                for block in blocks {
+                    let _ = self
+                        .cx
+                        .range_sync_block_and_blob_response(id, BlockOrBlob::Block(Some(block)));
+                }
+                let _ = self
+                    .cx
+                    .range_sync_block_and_blob_response(id, BlockOrBlob::Block(None));
+                let (chain, response) = self
+                    .cx
+                    .range_sync_block_and_blob_response(id, BlockOrBlob::Blob(None))
+                    .unwrap();
+                for block in response.responses.unwrap() {
+                    range.blocks_by_range_response(
+                        &mut self.cx,
+                        peer,
+                        chain,
+                        response.batch_id,
+                        id,
+                        Some(block),
+                    );
+                }
+                range.blocks_by_range_response(
+                    &mut self.cx,
+                    peer,
+                    chain,
+                    response.batch_id,
+                    id,
+                    None,
+                );
+            } else {
+                let id = match block_req {
+                    RequestId::Sync(crate::sync::manager::RequestId::RangeBlocks { id }) => id,
+                    other => panic!("unexpected request {:?}", other),
+                };
+                for block in blocks {
+                    let (chain, batch) = self.cx.range_sync_block_only_response(id, false).unwrap();
+                    let block = RpcBlock::new_without_blobs(None, block);
+                    range.blocks_by_range_response(
+                        &mut self.cx,
+                        peer,
+                        chain,
+                        batch,
+                        id,
+                        Some(block),
+                    );
+                }
+                let (chain, batch) = self.cx.range_sync_block_only_response(id, true).unwrap();
+                range.blocks_by_range_response(&mut self.cx, peer, chain, batch, id, None);
+            }
         }
 
         /// Produce a head peer
@@ -589,6 +680,7 @@ mod tests {
                 finalized_root,
                 head_slot,
                 head_root,
+                earliest_available_slot: None,
             };
 
             let peer_id = PeerId::random();
@@ -817,4 +909,88 @@ mod tests {
         rig.expect_chain_segment();
         rig.expect_chain_segment();
     }
+
+    #[test]
+    fn requests_skip_slots_imported_via_gossip() {
+        let (mut rig, mut range) = range(false);
+        let fork = rig
+            .cx
+            .chain
+            .spec
+            .fork_name_at_epoch(rig.cx.chain.epoch().unwrap());
+
+        let (peer1, local_info, finalized_info) = rig.finalized_peer();
+        range.add_peer(&mut rig.cx, local_info, peer1, finalized_info.clone());
+        // The genesis block is already known, so it isn't requested.
+        let (_, _, request) = rig.grab_blocks_request(&peer1, fork);
+        assert_eq!(*request.start_slot(), 1);
+        assert_eq!(*request.count(), E::slots_per_epoch() - 1);
+
+        // Gossip advances the local head into the second epoch whilst the first batch downloads.
+        let head_slot = Slot::new(E::slots_per_epoch() + 3);
+        rig.chain.status.write().head_slot = head_slot;
+
+        // The next batch is requested from another peer on the same chain, and only covers the
+        // slots after the local head.
+        let peer2 = PeerId::random();
+        range.add_peer(&mut rig.cx, rig.local_info(), peer2, finalized_info);
+        let (_, _, request) = rig.grab_blocks_request(&peer2, fork);
+        assert_eq!(*request.start_slot(), head_slot.as_u64() + 1);
+        assert_eq!(*request.count(), E::slots_per_epoch() - 4);
+    }
+
+    #[test]
+    fn empty_shortened_batch_is_retried() {
+        let (mut rig, mut range) = range(false);
+        let fork = rig
+            .cx
+            .chain
+            .spec
+            .fork_name_at_epoch(rig.cx.chain.epoch().unwrap());
+
+        let (peer, local_info, finalized_info) = rig.finalized_peer();
+        range.add_peer(&mut rig.cx, local_info, peer, finalized_info);
+        let (block_req, blob_req, request) = rig.grab_blocks_request(&peer, fork);
+        assert_eq!(*request.start_slot(), 1);
+
+        // The peer returns no blocks for the shortened request.
+        rig.complete_request(&mut range, peer, (block_req, blob_req), vec![]);
+
+        // The idle peer is sent the next batch.
+        let (_, _, request) = rig.grab_blocks_request(&peer, fork);
+        assert_eq!(*request.start_slot(), E::slots_per_epoch());
+
+        // An empty response can't be checked against the local chain, so rather than being
+        // processed the first batch is requested again over its full range.
+        rig.expect_empty_processor();
+        let (_, _, request) = rig.grab_blocks_request(&peer, fork);
+        assert_eq!(*request.start_slot(), 0);
+        assert_eq!(*request.count(), E::slots_per_epoch());
+    }
+
+    #[test]
+    fn batch_imported_via_gossip_is_not_processed() {
+        let (mut rig, mut range) = range(false);
+        let fork = rig
+            .cx
+            .chain
+            .spec
+            .fork_name_at_epoch(rig.cx.chain.epoch().unwrap());
+
+        let (peer, local_info, head_info) = rig.head_peer();
+        let peer_head_slot = head_info.head_slot;
+        range.add_peer(&mut rig.cx, local_info, peer, head_info);
+        let request_ids = rig.grab_request(&peer, fork);
+
+        // The peer's head block is imported via gossip before the batch arrives.
+        let mut block = BeaconBlock::empty(&rig.cx.chain.spec);
+        *block.slot_mut() = peer_head_slot;
+        let block = Arc::new(SignedBeaconBlock::from_block(block, Signature::empty()));
+        rig.chain.remember_block(block.canonical_root());
+
+        rig.complete_request(&mut range, peer, request_ids, vec![block]);
+
+        // There is nothing left in the batch to process.
+        rig.expect_empty_processor();
+    }
 }
@@ -53,16 +53,8 @@ pub struct AttestationDataMap<T: EthSpec> {
 
 impl<T: EthSpec> SplitAttestation<T> {
     pub fn new(attestation: Attestation<T>, attesting_indices: Vec<u64>) -> Self {
-        let checkpoint = CheckpointKey {
-            source: attestation.data.source,
-            target_epoch: attestation.data.target.epoch,
-        };
-        let data = CompactAttestationData {
-            slot: attestation.data.slot,
-            index: attestation.data.index,
-            beacon_block_root: attestation.data.beacon_block_root,
-            target_root: attestation.data.target.root,
-        };
+        let checkpoint = CheckpointKey::from_data(&attestation.data);
+        let data = CompactAttestationData::from_data(&attestation.data);
         let indexed = CompactIndexedAttestation {
             attesting_indices,
             aggregation_bits: attestation.aggregation_bits,
@@ -108,6 +100,13 @@ impl<'a, T: EthSpec> AttestationRef<'a, T> {
 }
 
 impl CheckpointKey {
+    pub fn from_data(data: &AttestationData) -> Self {
+        CheckpointKey {
+            source: data.source,
+            target_epoch: data.target.epoch,
+        }
+    }
+
     /// Return two checkpoint keys: `(previous, current)` for the previous and current epochs of
     /// the `state`.
     pub fn keys_for_state<T: EthSpec>(state: &BeaconState<T>) -> (Self, Self) {
@@ -124,6 +123,17 @@ impl CheckpointKey {
     }
 }
 
+impl CompactAttestationData {
+    pub fn from_data(data: &AttestationData) -> Self {
+        CompactAttestationData {
+            slot: data.slot,
+            index: data.index,
+            beacon_block_root: data.beacon_block_root,
+            target_root: data.target.root,
+        }
+    }
+}
+
 impl<T: EthSpec> CompactIndexedAttestation<T> {
     pub fn signers_disjoint_from(&self, other: &Self) -> bool {
         self.aggregation_bits
@@ -172,6 +182,25 @@ impl<T: EthSpec> AttestationMap<T> {
         }
     }
 
+    /// Returns `true` if the map contains an attestation with the same data as `attestation`
+    /// whose aggregation bits are a non-strict superset of those of `attestation`.
+    pub fn contains_superset(&self, attestation: &Attestation<T>) -> bool {
+        self.checkpoint_map
+            .get(&CheckpointKey::from_data(&attestation.data))
+            .and_then(|attestation_map| {
+                attestation_map
+                    .attestations
+                    .get(&CompactAttestationData::from_data(&attestation.data))
+            })
+            .map_or(false, |existing| {
+                existing.iter().any(|indexed| {
+                    attestation
+                        .aggregation_bits
+                        .is_subset(&indexed.aggregation_bits)
+                })
+            })
+    }
+
     /// Iterate all attestations matching the given `checkpoint_key`.
     pub fn get_attestations<'a>(
         &'a self,
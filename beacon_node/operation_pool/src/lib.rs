@@ -206,6 +206,15 @@ impl<T: EthSpec> OperationPool<T> {
         Ok(())
     }
 
+    /// Returns `true` if the pool contains an attestation with the same data as `attestation`
+    /// which includes all of its signers.
+    ///
+    /// Attestations in the pool may have been aggregated from several attestations, so this can
+    /// return `true` even if no single attestation that was inserted covers `attestation`.
+    pub fn contains_attestation_superset(&self, attestation: &Attestation<T>) -> bool {
+        self.attestations.read().contains_superset(attestation)
+    }
+
     /// Total number of attestations in the pool, including attestations for the same data.
     pub fn num_attestations(&self) -> usize {
         self.attestation_stats().num_attestations
@@ -1038,6 +1047,63 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), committees.len());
     }
 
+    #[test]
+    fn attestation_contains_superset() {
+        let (harness, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let state = harness.get_current_state();
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slot = state.slot();
+        let num_validators =
+            MainnetEthSpec::slots_per_epoch() as usize * spec.target_committee_size;
+        let (committee_attestations, _) = harness
+            .make_attestations(
+                (0..num_validators).collect::<Vec<_>>().as_slice(),
+                &state,
+                Hash256::zero(),
+                SignedBeaconBlockHash::from(Hash256::zero()),
+                slot,
+            )
+            .into_iter()
+            .next()
+            .unwrap();
+
+        // Aggregates the attestations of the given committee positions.
+        let aggregate_of = |positions: &[usize]| {
+            positions
+                .iter()
+                .map(|&i| committee_attestations[i].0.clone())
+                .reduce(|mut aggregate, att| {
+                    aggregate.aggregate(&att);
+                    aggregate
+                })
+                .unwrap()
+        };
+
+        // Two disjoint aggregates are merged by the pool.
+        for positions in [&[0, 1][..], &[2, 3][..]] {
+            let att = aggregate_of(positions);
+            let attesting_indices = get_attesting_indices_from_state(&state, &att).unwrap();
+            op_pool.insert_attestation(att, attesting_indices).unwrap();
+        }
+
+        // Subset.
+        assert!(op_pool.contains_attestation_superset(&aggregate_of(&[1, 2])));
+        // Equal.
+        assert!(op_pool.contains_attestation_superset(&aggregate_of(&[0, 1, 2, 3])));
+        // Overlapping, but not a subset.
+        assert!(!op_pool.contains_attestation_superset(&aggregate_of(&[3, 4])));
+        // Disjoint.
+        assert!(!op_pool.contains_attestation_superset(&aggregate_of(&[4, 5])));
+
+        // Different data.
+        let mut att = aggregate_of(&[0]);
+        att.data.beacon_block_root = Hash256::repeat_byte(1);
+        assert!(!op_pool.contains_attestation_superset(&att));
+    }
+
     /// Adding lots of attestations that only intersect pairwise should lead to two aggregate
     /// attestations.
     #[test]
@@ -1411,6 +1477,27 @@ mod release_tests {
         );
     }
 
+    // Attester slashings which have slashed some of their validators are still listed.
+    #[test]
+    fn partially_applied_attester_slashing_is_listed() {
+        let harness = get_harness(32, None);
+        let spec = &harness.spec;
+        let mut state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let slashing = harness.make_attester_slashing(vec![1, 3, 5]);
+        op_pool.insert_attester_slashing(slashing.clone().validate(&state, spec).unwrap());
+
+        state.validators_mut().get_mut(1).unwrap().slashed = true;
+        op_pool.prune_attester_slashings(&state);
+
+        assert_eq!(op_pool.get_all_attester_slashings(), vec![slashing.clone()]);
+        assert_eq!(
+            op_pool.get_slashings_and_exits(&state, spec).1,
+            vec![slashing]
+        );
+    }
+
     // Check that we get maximum coverage for attester slashings (highest qty of validators slashed)
     #[test]
     fn simple_max_cover_attester_slashing() {
@@ -1,4 +1,5 @@
 use clap::{App, Arg, ArgGroup};
+use lighthouse_network::TransportPreference;
 use strum::VariantNames;
 use types::ProgressiveBalancesMode;
 
@@ -67,6 +68,42 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        not be performed before shutdown.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("parent-lookup-max-depth")
+                .long("parent-lookup-max-depth")
+                .value_name("BLOCKS")
+                .help("The maximum number of unknown ancestors to download one at a time when \
+                       searching for the parent of a block. Longer chains are synced in batches \
+                       from the finalized checkpoint instead. Defaults to 32.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("late-own-block-threshold")
+                .long("late-own-block-threshold")
+                .value_name("MILLISECONDS")
+                .help("Delay after the start of the slot after which a block proposed by one of \
+                       this node's validators is reported as published late. Defaults to 3000.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("subnet-unsubscribe-linger")
+                .long("subnet-unsubscribe-linger")
+                .value_name("MILLISECONDS")
+                .help("How long to remain subscribed to an attestation subnet joined for an \
+                       aggregation duty after the end of the duty's slot, to receive late \
+                       attestations. Defaults to 500.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("gossip-rejection-buffer-size")
+                .long("gossip-rejection-buffer-size")
+                .value_name("COUNT")
+                .help("The number of rejected gossip attestations and aggregates to retain for \
+                       inspection via the /lighthouse/network/gossip/rejections HTTP API \
+                       endpoint. Other gossip messages are not recorded. Defaults to 0, which \
+                       disables recording.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("zero-ports")
                 .long("zero-ports")
@@ -171,6 +208,15 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Disables UPnP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("upnp-renewal-interval")
+                .long("upnp-renewal-interval")
+                .value_name("SECONDS")
+                .help("The interval at which UPnP port mappings are renewed. Mappings are requested \
+                      with a lease of one hour, so this must be less than 3600 seconds. Defaults to \
+                      half of the lease duration.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("private")
                 .long("private")
@@ -277,6 +323,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("disable-quic")
                 .help("Disables the quic transport. The node will rely solely on the TCP transport for libp2p connections.")
         )
+        .arg(
+            Arg::with_name("transport-preference")
+                .long("transport-preference")
+                .value_name("PREFERENCE")
+                .help("The order in which the QUIC and TCP addresses of peers are dialed. With \
+                        `quic-first` or `tcp-first`, the other transport is dialed if the \
+                        preferred one fails. With `quic-only` or `tcp-only`, the other transport \
+                        is never dialed. If QUIC is disabled, only TCP is dialed.")
+                .takes_value(true)
+                .possible_values(TransportPreference::VARIANTS)
+                .default_value("quic-first")
+        )
         .arg(
             Arg::with_name("disable-peer-scoring")
                 .long("disable-peer-scoring")
@@ -285,6 +343,13 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(false)
                 .hidden(true),
         )
+        .arg(
+            Arg::with_name("purge-peer-scores")
+                .long("purge-peer-scores")
+                .help("Discards the peer scores and bans persisted in the network directory by a \
+                        previous run, rather than restoring them on startup.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("trusted-peers")
                 .long("trusted-peers")
@@ -327,6 +392,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        for a beacon node being referenced by validator client using the --proposer-node flag. This configuration is for enabling more secure setups.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("beacon-node-profile")
+                .long("beacon-node-profile")
+                .value_name("PROFILE")
+                .help("Tunes the beacon node for a specific role. The `proposer` profile implies \
+                       --proposer-only, and additionally enlarges and prioritizes the block and \
+                       aggregate processing queues, only backfills blocks within the data \
+                       availability window and disables the debug endpoints which serve whole \
+                       states or fork choice.")
+                .takes_value(true)
+                .possible_values(&["proposer"]),
+        )
         .arg(
             Arg::with_name("inbound-rate-limiter")
             .long("inbound-rate-limiter")
@@ -382,10 +459,11 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("http-allow-origin")
                 .requires("enable_http")
                 .value_name("ORIGIN")
-                .help("Set the value of the Access-Control-Allow-Origin response HTTP header. \
-                    Use * to allow any origin (not recommended in production). \
-                    If no value is supplied, the CORS allowed origin is set to the listen \
-                    address of this server (e.g., http://localhost:5052).")
+                .help("Set the origins allowed to make cross-origin requests, as a \
+                    comma-separated list. Origins may use a wildcard subdomain, e.g. \
+                    https://*.example.com. Use * to allow any origin (not recommended in \
+                    production). If no value is supplied, the CORS allowed origin is set to the \
+                    listen address of this server (e.g., http://localhost:5052).")
                 .takes_value(true),
         )
         .arg(
@@ -428,6 +506,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     over TLS. Must not be password-protected.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("http-tls-client-ca")
+                .long("http-tls-client-ca")
+                .value_name("FILE")
+                .requires("http-enable-tls")
+                .help("The path of a file containing the certificates of the CAs trusted to \
+                    sign client certificates. When supplied, clients of the HTTP API server must \
+                    present a certificate signed by one of these CAs, otherwise the TLS \
+                    handshake fails.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("http-allow-sync-stalled")
                 .long("http-allow-sync-stalled")
@@ -456,6 +545,47 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Status code to send when a block that is already known is POSTed to the \
                        HTTP API.")
         )
+        .arg(
+            Arg::with_name("http-max-concurrent-state-requests")
+                .long("http-max-concurrent-state-requests")
+                .requires("enable_http")
+                .takes_value(true)
+                .default_value_if("enable_http", None, "64")
+                .value_name("N")
+                .help("Maximum number of concurrent requests to HTTP API endpoints which serve \
+                       large parts of a state, such as the validators endpoint. Further requests \
+                       are rejected with a 429 status code until an earlier request completes.")
+        )
+        .arg(
+            Arg::with_name("http-admin-token-path")
+                .long("http-admin-token-path")
+                .requires("enable_http")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Path to the file containing the bearer token required by the \
+                       /lighthouse/admin endpoints. A token is generated and written to this \
+                       file if it does not exist. Defaults to api-token.txt in the data \
+                       directory.")
+        )
+        .arg(
+            Arg::with_name("http-disable-admin-endpoints")
+                .long("http-disable-admin-endpoints")
+                .requires("enable_http")
+                .conflicts_with("http-admin-token-path")
+                .takes_value(false)
+                .help("Reject all requests to the /lighthouse/admin endpoints. No admin token is \
+                       generated or read.")
+        )
+        .arg(
+            Arg::with_name("http-unix-socket")
+                .long("http-unix-socket")
+                .requires("enable_http")
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Also serve the HTTP API on a Unix domain socket at this path, which \
+                       validator clients on the same host can use with a unix:// URL. The \
+                       socket is only accessible to the user and group running the beacon node.")
+        )
         .arg(
             Arg::with_name("http-enable-beacon-processor")
                 .long("http-enable-beacon-processor")
@@ -468,6 +598,13 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value_if("enable_http", None, "true")
         )
+        .arg(
+            Arg::with_name("http-enable-fork-choice-recompute")
+                .long("http-enable-fork-choice-recompute")
+                .requires("enable_http")
+                .help("Enables the POST lighthouse/fork_choice/recompute endpoint, which forces \
+                    fork choice to be run immediately. Disabled by default.")
+        )
         /* Prometheus metrics HTTP server related arguments */
         .arg(
             Arg::with_name("metrics")
@@ -512,6 +649,33 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Shufflings are dependent on validator count and setting this value to a large number can consume a large amount of memory.")
             .takes_value(true)
         )
+        .arg(
+            Arg::with_name("epoch-timings-retention")
+            .long("epoch-timings-retention")
+            .value_name("EPOCHS")
+            .help("The number of recent epochs for which block arrival and attestation inclusion \
+            statistics are kept for the /lighthouse/analysis/epoch_timings HTTP endpoint. \
+            Defaults to 64.")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("head-vote-accuracy-warn-threshold")
+            .long("head-vote-accuracy-warn-threshold")
+            .value_name("PERCENT")
+            .help("Log a warning if fewer than this percentage of the attestations observed in two \
+            consecutive epochs voted for the block which became canonical. Defaults to 90.")
+            .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("observed-attestations-max-mb")
+            .long("observed-attestations-max-mb")
+            .value_name("MEGABYTES")
+            .help("The approximate maximum memory used to remember which aggregate attestations \
+            have been seen on gossip. When exceeded, the attestation data roots which have been \
+            seen the fewest times are forgotten, and their aggregates may be verified again if \
+            they are received again. Defaults to 128.")
+            .takes_value(true)
+        )
 
         /*
          * Monitoring metrics
@@ -643,7 +807,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("historic-state-cache-size")
                 .long("historic-state-cache-size")
                 .value_name("SIZE")
-                .help("Specifies how many states from the freezer database should cache in memory [default: 1]")
+                .help("Specifies how many states from the freezer database or advanced through skipped slots should cache in memory [default: 1]")
                 .takes_value(true)
         )
         /*
@@ -673,7 +837,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("EXECUTION-JWT")
                 .alias("jwt-secrets")
                 .help("File path which contains the hex-encoded JWT secret for the \
-                       execution endpoint provided in the --execution-endpoint flag.")
+                       execution endpoint provided in the --execution-endpoint flag. \
+                       The secret is reloaded if the file changes.")
+                .requires("execution-endpoint")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("execution-jwt-secondary")
+                .long("execution-jwt-secondary")
+                .value_name("EXECUTION-JWT-SECONDARY")
+                .help("File path which contains a second hex-encoded JWT secret, which is \
+                       used if the execution endpoint rejects the primary secret. This allows \
+                       the secret to be rotated without restarting the beacon node.")
                 .requires("execution-endpoint")
                 .takes_value(true)
         )
@@ -768,6 +943,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("true")
         )
+        .arg(
+            Arg::with_name("compact-db-interval-epochs")
+                .long("compact-db-interval-epochs")
+                .value_name("EPOCHS")
+                .help("Compact the database on finalization once at least this many epochs have \
+                       passed since the last compaction. By default compaction is scheduled \
+                       based on how far finality has advanced. Has no effect if \
+                       auto-compaction is disabled.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("prune-payloads")
                 .long("prune-payloads")
@@ -805,6 +990,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("0")
         )
+        .arg(
+            Arg::with_name("blob-prune-interval-epochs")
+                .long("blob-prune-interval-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs between runs of the blob pruning service. Blob \
+                       pruning runs on this schedule independently of finalization, so that \
+                       blobs are pruned even when the chain is not finalizing. Must be at \
+                       least 1.")
+                .takes_value(true)
+                .default_value("1")
+        )
 
         /*
          * Misc.
@@ -814,7 +1010,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("graffiti")
                 .help(
                     "Specify your custom graffiti to be included in blocks. \
-                    Defaults to the current version and commit, truncated to fit in 32 bytes. "
+                    Defaults to the current version and commit, truncated to fit in 32 bytes. \
+                    May contain the variables {version}, {slot} and {epoch}, which are \
+                    replaced when a block is produced."
                 )
                 .value_name("GRAFFITI")
                 .takes_value(true)
@@ -971,6 +1169,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .requires("checkpoint-state")
         )
+        .arg(
+            Arg::with_name("checkpoint-blobs")
+                .long("checkpoint-blobs")
+                .help("Set the checkpoint blobs to start syncing from. Must match \
+                       --checkpoint-block and is required if the block has blob commitments. \
+                       Using --checkpoint-sync-url instead is recommended.")
+                .value_name("BLOBS_SSZ")
+                .takes_value(true)
+                .requires("checkpoint-block")
+        )
         .arg(
             Arg::with_name("checkpoint-sync-url")
                 .long("checkpoint-sync-url")
@@ -987,6 +1195,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("180")
         )
+        .arg(
+            Arg::with_name("disable-cache-preheat")
+                .long("disable-cache-preheat")
+                .help("Do not build the committee and proposer caches of the checkpoint state \
+                       before starting the HTTP API after a checkpoint sync. The caches will \
+                       instead be built by the first validator duties requests, which may be \
+                       slow enough for duties to be missed.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("allow-insecure-genesis-sync")
+                .long("allow-insecure-genesis-sync")
+                .help("Enable syncing from genesis on networks which would otherwise refuse to, \
+                       such as mainnet. Syncing from genesis takes a very long time and is \
+                       generally insecure, checkpoint sync is strongly recommended instead.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("reconstruct-historic-states")
                 .long("reconstruct-historic-states")
@@ -1051,6 +1276,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        Default: 20%")
                 .conflicts_with("disable-proposer-reorgs")
         )
+        .arg(
+            Arg::with_name("proposer-reorg-margin")
+                .long("proposer-reorg-margin")
+                .value_name("PERCENT")
+                .help("Percentage of vote weight by which the head's weight must fall below the \
+                       proposer reorg threshold in order to attempt a reorg. A non-zero margin \
+                       avoids reorgs when the decision is sensitive to small differences between \
+                       our view of the head's weight and that of the network. Default: 0%")
+                .conflicts_with("disable-proposer-reorgs")
+        )
+        .arg(
+            Arg::with_name("proposer-reorg-min-participation")
+                .long("proposer-reorg-min-participation")
+                .value_name("PERCENT")
+                .help("Minimum percentage of active stake which must have attested to the correct \
+                       target during the previous epoch for proposer reorgs to be attempted. \
+                       Set to 0 to disable this check. Default: 80%")
+                .conflicts_with("disable-proposer-reorgs")
+        )
         .arg(
             Arg::with_name("proposer-reorg-epochs-since-finalization")
                 .long("proposer-reorg-epochs-since-finalization")
@@ -1151,6 +1395,35 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("3")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("builder-fallback-skips-window")
+                .long("builder-fallback-skips-window")
+                .value_name("SLOTS")
+                .help("The number of recent slots in which skip slots are counted for \
+                        --builder-fallback-skips-per-epoch and missed proposals are counted for \
+                        --builder-fallback-missed-proposals. Defaults to `SLOTS_PER_EPOCH`.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("builder-fallback-missed-proposals")
+                .long("builder-fallback-missed-proposals")
+                .value_name("COUNT")
+                .help("If this node is proposing a block and validators connected to this node \
+                        have missed more than this number of proposals in the past \
+                        --builder-fallback-skips-window slots, it will NOT query any connected \
+                        builders, and will use the local execution engine for payload \
+                        construction. This condition is not checked unless this flag is set.")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("builder-fallback-disable-conditions")
+                .long("builder-fallback-disable-conditions")
+                .value_name("CONDITIONS")
+                .help("A comma-separated list of chain health conditions which are not checked \
+                        when deciding whether to use connected builders. Possible values are \
+                        skips, skips-per-epoch, epochs-since-finalization and missed-proposals.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("builder-fallback-disable-checks")
                 .long("builder-fallback-disable-checks")
@@ -1174,6 +1447,26 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("0")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("require-builder-extra-data-prefix")
+                .long("require-builder-extra-data-prefix")
+                .value_name("PREFIX")
+                .help("Only use payloads from a block builder if their extra data starts with \
+                    PREFIX, otherwise fall back to the local EE's payload. PREFIX is either a \
+                    0x-prefixed hex string or a UTF-8 string of at most 32 bytes.")
+                .requires("builder")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("strict-fee-recipient")
+                .long("strict-fee-recipient")
+                .help("Only use payloads from a block builder if their fee recipient matches the \
+                    one registered for the proposer, otherwise fall back to the local EE's \
+                    payload. Some builders pay the proposer with a transaction instead of via \
+                    the fee recipient, so their payloads will always be ignored.")
+                .requires("builder")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("ignore-builder-override-suggestion-threshold")
                 .long("ignore-builder-override-suggestion-threshold")
@@ -1315,6 +1608,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("12288")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("beacon-processor-unknown-block-attestation-queue-len")
+                .long("beacon-processor-unknown-block-attestation-queue-len")
+                .value_name("INTEGER")
+                .help("Specifies the maximum number of attestations and aggregates referencing an \
+                        unknown block that are held until the block is imported. Networks with \
+                        many validators may need a higher value to avoid dropping attestations.")
+                .default_value("16384")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("beacon-processor-attestation-batch-size")
                 .long("beacon-processor-attestation-batch-size")
@@ -1336,5 +1639,15 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("64")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("shutdown-grace-period")
+                .long("shutdown-grace-period")
+                .value_name("SECONDS")
+                .help("On shutdown, the maximum time to wait for in-flight block publications and \
+                       imports to complete before the caches are persisted and the network is \
+                       torn down.")
+                .default_value("5")
+                .takes_value(true)
+        )
         .group(ArgGroup::with_name("enable_http").args(&["http", "gui", "staking"]).multiple(true))
 }
@@ -1,6 +1,8 @@
 use beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
-    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
+    DisallowedReOrgOffsets, FailedCondition, ReOrgThreshold,
+    DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR, DEFAULT_RE_ORG_MARGIN,
+    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD,
+    DEFAULT_RE_ORG_THRESHOLD,
 };
 use beacon_chain::TrustedSetup;
 use clap::ArgMatches;
@@ -13,7 +15,10 @@ use execution_layer::DEFAULT_JWT_FILE;
 use genesis::Eth1Endpoint;
 use http_api::TlsConfig;
 use lighthouse_network::ListenAddress;
-use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use lighthouse_network::{
+    multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized, TransportPreference,
+    UPNP_LEASE_DURATION,
+};
 use sensitive_url::SensitiveUrl;
 use slog::{info, warn, Logger};
 use std::cmp;
@@ -26,7 +31,7 @@ use std::num::NonZeroU16;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
-use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, GRAFFITI_BYTES_LEN};
+use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, Unsigned, GRAFFITI_BYTES_LEN};
 
 /// Gets the fully-initialized global client.
 ///
@@ -112,12 +117,17 @@ pub fn get_config<E: EthSpec>(
         }
 
         if let Some(allow_origin) = cli_args.value_of("http-allow-origin") {
-            // Pre-validate the config value to give feedback to the user on node startup, instead of
-            // as late as when the first API response is produced.
-            hyper::header::HeaderValue::from_str(allow_origin)
-                .map_err(|_| "Invalid allow-origin value")?;
-
-            client_config.http_api.allow_origin = Some(allow_origin.to_string());
+            for origin in allow_origin.split(',').map(str::trim) {
+                // Pre-validate the config value to give feedback to the user on node startup,
+                // instead of as late as when the first API response is produced.
+                hyper::header::HeaderValue::from_str(origin)
+                    .map_err(|_| format!("Invalid allow-origin value: {}", origin))?;
+
+                client_config
+                    .http_api
+                    .allow_origins
+                    .push(origin.to_string());
+            }
         }
 
         if cli_args.is_present("http-disable-legacy-spec") {
@@ -143,6 +153,7 @@ pub fn get_config<E: EthSpec>(
                     .ok_or("--http-tls-key was not provided.")?
                     .parse::<PathBuf>()
                     .map_err(|_| "http-tls-key is not a valid path name.")?,
+                client_ca: clap_utils::parse_optional(cli_args, "http-tls-client-ca")?,
             });
         }
 
@@ -156,14 +167,50 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.enable_beacon_processor =
             parse_required(cli_args, "http-enable-beacon-processor")?;
 
+        if cli_args.is_present("http-enable-fork-choice-recompute") {
+            client_config.http_api.enable_fork_choice_recompute = true;
+        }
+
         client_config.http_api.duplicate_block_status_code =
             parse_required(cli_args, "http-duplicate-block-status")?;
+
+        client_config.http_api.max_concurrent_state_requests =
+            parse_required(cli_args, "http-max-concurrent-state-requests")?;
+
+        client_config.http_api.admin_token_path =
+            clap_utils::parse_optional(cli_args, "http-admin-token-path")?;
+
+        if cli_args.is_present("http-disable-admin-endpoints") {
+            client_config.http_api.disable_admin_endpoints = true;
+        }
+
+        client_config.http_api.unix_socket =
+            clap_utils::parse_optional(cli_args, "http-unix-socket")?;
     }
 
     if let Some(cache_size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
         client_config.chain.shuffling_cache_size = cache_size;
     }
 
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "epoch-timings-retention")? {
+        client_config.chain.epoch_timings_retention_epochs = epochs;
+    }
+
+    if let Some(percent) =
+        clap_utils::parse_optional::<u64>(cli_args, "head-vote-accuracy-warn-threshold")?
+    {
+        if percent > 100 {
+            return Err("--head-vote-accuracy-warn-threshold must be a percentage".into());
+        }
+        client_config.chain.head_vote_accuracy_warn_threshold = percent;
+    }
+
+    if let Some(megabytes) =
+        clap_utils::parse_optional::<usize>(cli_args, "observed-attestations-max-mb")?
+    {
+        client_config.chain.observed_attestations_max_bytes = megabytes * 1024 * 1024;
+    }
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -348,6 +395,8 @@ pub fn get_config<E: EthSpec>(
 
         // Set config values from parse values.
         el_config.secret_files = vec![secret_file.clone()];
+        el_config.secondary_secret_file =
+            clap_utils::parse_optional(cli_args, "execution-jwt-secondary")?;
         el_config.execution_endpoints = vec![execution_endpoint.clone()];
         el_config.suggested_fee_recipient =
             clap_utils::parse_optional(cli_args, "suggested-fee-recipient")?;
@@ -356,6 +405,11 @@ pub fn get_config<E: EthSpec>(
         el_config.default_datadir = client_config.data_dir().clone();
         el_config.builder_profit_threshold =
             clap_utils::parse_required(cli_args, "builder-profit-threshold")?;
+        el_config.builder_extra_data_prefix = cli_args
+            .value_of("require-builder-extra-data-prefix")
+            .map(parse_extra_data_prefix::<E>)
+            .transpose()?;
+        el_config.strict_fee_recipient = cli_args.is_present("strict-fee-recipient");
         el_config.always_prefer_builder_payload =
             cli_args.is_present("always-prefer-builder-payload");
         el_config.ignore_builder_override_suggestion_threshold =
@@ -431,6 +485,14 @@ pub fn get_config<E: EthSpec>(
             .parse()
             .map_err(|_| "auto-compact-db takes a boolean".to_string())?;
     }
+    if let Some(compact_interval_epochs) =
+        clap_utils::parse_optional(cli_args, "compact-db-interval-epochs")?
+    {
+        if compact_interval_epochs == 0 {
+            return Err("compact-db-interval-epochs must be greater than 0".into());
+        }
+        client_config.store.compact_interval_epochs = Some(compact_interval_epochs);
+    }
 
     if let Some(prune_payloads) = clap_utils::parse_optional(cli_args, "prune-payloads")? {
         client_config.store.prune_payloads = prune_payloads;
@@ -458,6 +520,12 @@ pub fn get_config<E: EthSpec>(
         client_config.store.blob_prune_margin_epochs = blob_prune_margin_epochs;
     }
 
+    if let Some(blob_prune_interval_epochs) =
+        clap_utils::parse_optional(cli_args, "blob-prune-interval-epochs")?
+    {
+        client_config.store.blob_prune_interval_epochs = blob_prune_interval_epochs;
+    }
+
     /*
      * Zero-ports
      *
@@ -510,27 +578,36 @@ pub fn get_config<E: EthSpec>(
     client_config.chain.checkpoint_sync_url_timeout =
         clap_utils::parse_required::<u64>(cli_args, "checkpoint-sync-url-timeout")?;
 
+    client_config.allow_insecure_genesis_sync = cli_args.is_present("allow-insecure-genesis-sync");
+    client_config.preheat_caches = !cli_args.is_present("disable-cache-preheat");
+    client_config.checkpoint_sync_url_suggestions = eth2_network_config
+        .checkpoint_sync_url_suggestions
+        .clone()
+        .unwrap_or_default();
+
     client_config.genesis_state_url_timeout =
         clap_utils::parse_required(cli_args, "genesis-state-url-timeout")
             .map(Duration::from_secs)?;
 
-    let genesis_state_url_opt =
-        clap_utils::parse_optional::<String>(cli_args, "genesis-state-url")?;
+    let genesis_state_urls = cli_args
+        .values_of("genesis-state-url")
+        .map(|urls| urls.map(String::from).collect::<Vec<_>>())
+        .unwrap_or_default();
     let checkpoint_sync_url_opt =
         clap_utils::parse_optional::<String>(cli_args, "checkpoint-sync-url")?;
 
-    // If the `--genesis-state-url` is defined, use that to download the
-    // genesis state bytes. If it's not defined, try `--checkpoint-sync-url`.
-    client_config.genesis_state_url = if let Some(genesis_state_url) = genesis_state_url_opt {
-        Some(genesis_state_url)
+    // If any `--genesis-state-url` is defined, use those to download the
+    // genesis state bytes. If none are defined, try `--checkpoint-sync-url`.
+    client_config.genesis_state_urls = if !genesis_state_urls.is_empty() {
+        genesis_state_urls
     } else if let Some(checkpoint_sync_url) = checkpoint_sync_url_opt {
         // If the checkpoint sync URL is going to be used to download the
         // genesis state, adopt the timeout from the checkpoint sync URL too.
         client_config.genesis_state_url_timeout =
             Duration::from_secs(client_config.chain.checkpoint_sync_url_timeout);
-        Some(checkpoint_sync_url)
+        vec![checkpoint_sync_url]
     } else {
-        None
+        vec![]
     };
 
     client_config.genesis = if eth2_network_config.genesis_state_is_known() {
@@ -553,10 +630,15 @@ pub fn get_config<E: EthSpec>(
 
             let anchor_state_bytes = read(initial_state_path)?;
             let anchor_block_bytes = read(initial_block_path)?;
+            let anchor_blobs_bytes = cli_args
+                .value_of("checkpoint-blobs")
+                .map(read)
+                .transpose()?;
 
             ClientGenesis::WeakSubjSszBytes {
                 anchor_state_bytes,
                 anchor_block_bytes,
+                anchor_blobs_bytes,
             }
         } else if let Some(remote_bn_url) = cli_args.value_of("checkpoint-sync-url") {
             let url = SensitiveUrl::parse(remote_bn_url)
@@ -767,6 +849,16 @@ pub fn get_config<E: EthSpec>(
                 .map(ReOrgThreshold)
                 .unwrap_or(DEFAULT_RE_ORG_THRESHOLD),
         );
+        client_config.chain.re_org_margin =
+            clap_utils::parse_optional(cli_args, "proposer-reorg-margin")?
+                .map(ReOrgThreshold)
+                .unwrap_or(DEFAULT_RE_ORG_MARGIN);
+        client_config.chain.re_org_participation_threshold =
+            clap_utils::parse_optional(cli_args, "proposer-reorg-min-participation")?
+                .unwrap_or(DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD);
+        if client_config.chain.re_org_participation_threshold > 100 {
+            return Err("proposer-reorg-min-participation must not exceed 100".into());
+        }
         client_config.chain.re_org_max_epochs_since_finalization =
             clap_utils::parse_optional(cli_args, "proposer-reorg-epochs-since-finalization")?
                 .unwrap_or(DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION);
@@ -842,6 +934,18 @@ pub fn get_config<E: EthSpec>(
         .chain
         .builder_fallback_epochs_since_finalization =
         clap_utils::parse_required(cli_args, "builder-fallback-epochs-since-finalization")?;
+    client_config.chain.builder_fallback_skips_window =
+        clap_utils::parse_optional(cli_args, "builder-fallback-skips-window")?;
+    client_config.chain.builder_fallback_missed_proposals =
+        clap_utils::parse_optional(cli_args, "builder-fallback-missed-proposals")?;
+    if let Some(conditions) =
+        clap_utils::parse_optional::<String>(cli_args, "builder-fallback-disable-conditions")?
+    {
+        client_config.chain.builder_fallback_disabled_conditions = conditions
+            .split(',')
+            .map(|condition| condition.trim().parse())
+            .collect::<Result<Vec<FailedCondition>, _>>()?;
+    }
     client_config.chain.builder_fallback_disable_checks =
         cli_args.is_present("builder-fallback-disable-checks");
 
@@ -863,6 +967,15 @@ pub fn get_config<E: EthSpec>(
     client_config.beacon_processor.enable_backfill_rate_limiting =
         !cli_args.is_present("disable-backfill-rate-limiting");
 
+    // The proposer profile also implies `--proposer-only`, see `set_network_config`.
+    if proposer_profile(cli_args) {
+        client_config
+            .beacon_processor
+            .prioritize_blocks_and_aggregates = true;
+        client_config.chain.backfill_to_data_availability_boundary = true;
+        client_config.http_api.disable_expensive_endpoints = true;
+    }
+
     if let Some(path) = clap_utils::parse_optional(cli_args, "invalid-gossip-verified-blocks-path")?
     {
         client_config.network.invalid_block_storage = Some(path);
@@ -887,6 +1000,12 @@ pub fn get_config<E: EthSpec>(
         clap_utils::parse_required(cli_args, "beacon-processor-work-queue-len")?;
     client_config.beacon_processor.max_scheduled_work_queue_len =
         clap_utils::parse_required(cli_args, "beacon-processor-reprocess-queue-len")?;
+    client_config
+        .beacon_processor
+        .max_queued_unknown_block_attestations = clap_utils::parse_required(
+        cli_args,
+        "beacon-processor-unknown-block-attestation-queue-len",
+    )?;
     client_config
         .beacon_processor
         .max_gossip_attestation_batch_size =
@@ -896,6 +1015,9 @@ pub fn get_config<E: EthSpec>(
         .max_gossip_aggregate_batch_size =
         clap_utils::parse_required(cli_args, "beacon-processor-aggregate-batch-size")?;
 
+    client_config.shutdown_grace_period =
+        clap_utils::parse_required(cli_args, "shutdown-grace-period").map(Duration::from_secs)?;
+
     Ok(client_config)
 }
 
@@ -1116,6 +1238,11 @@ pub fn parse_listening_addresses(
     Ok(listening_addresses)
 }
 
+/// Returns `true` if the beacon node is configured with `--beacon-node-profile proposer`.
+fn proposer_profile(cli_args: &ArgMatches) -> bool {
+    cli_args.value_of("beacon-node-profile") == Some("proposer")
+}
+
 /// Sets the network config from the command line arguments.
 pub fn set_network_config(
     config: &mut NetworkConfig,
@@ -1142,6 +1269,26 @@ pub fn set_network_config(
         config.shutdown_after_sync = true;
     }
 
+    if let Some(max_depth) = clap_utils::parse_optional(cli_args, "parent-lookup-max-depth")? {
+        if max_depth == 0 {
+            return Err("--parent-lookup-max-depth must be at least 1".to_string());
+        }
+        config.parent_lookup_max_depth = max_depth;
+    }
+
+    if let Some(threshold_ms) = clap_utils::parse_optional(cli_args, "late-own-block-threshold")? {
+        config.late_own_block_threshold = Duration::from_millis(threshold_ms);
+    }
+
+    if let Some(linger_ms) = clap_utils::parse_optional(cli_args, "subnet-unsubscribe-linger")? {
+        config.subnet_unsubscribe_linger = Duration::from_millis(linger_ms);
+    }
+
+    if let Some(buffer_size) = clap_utils::parse_optional(cli_args, "gossip-rejection-buffer-size")?
+    {
+        config.gossip_rejection_buffer_size = buffer_size;
+    }
+
     config.set_listening_addr(parse_listening_addresses(cli_args, log)?);
 
     // A custom target-peers command will overwrite the --proposer-only default.
@@ -1200,6 +1347,10 @@ pub fn set_network_config(
         config.disable_peer_scoring = true;
     }
 
+    if cli_args.is_present("purge-peer-scores") {
+        config.purge_peer_scores = true;
+    }
+
     if let Some(trusted_peers_str) = cli_args.value_of("trusted-peers") {
         config.trusted_peers = trusted_peers_str
             .split(',')
@@ -1396,10 +1547,34 @@ pub fn set_network_config(
         config.disable_quic_support = true;
     }
 
+    if let Some(transport_preference) =
+        clap_utils::parse_optional::<TransportPreference>(cli_args, "transport-preference")?
+    {
+        if config.disable_quic_support && transport_preference == TransportPreference::QuicOnly {
+            return Err(
+                "--transport-preference quic-only cannot be used with --disable-quic".to_string(),
+            );
+        }
+        config.transport_preference = transport_preference;
+    }
+
     if cli_args.is_present("disable-upnp") {
         config.upnp_enabled = false;
     }
 
+    if let Some(renewal_interval) =
+        clap_utils::parse_optional::<u64>(cli_args, "upnp-renewal-interval")?
+    {
+        let renewal_interval = Duration::from_secs(renewal_interval);
+        if renewal_interval.is_zero() || renewal_interval >= UPNP_LEASE_DURATION {
+            return Err(format!(
+                "--upnp-renewal-interval must be between 1 and {} seconds",
+                UPNP_LEASE_DURATION.as_secs() - 1
+            ));
+        }
+        config.upnp_renewal_interval = Some(renewal_interval);
+    }
+
     if cli_args.is_present("private") {
         config.private = true;
     }
@@ -1426,7 +1601,7 @@ pub fn set_network_config(
     // Proposer-only mode overrides a number of previous configuration parameters.
     // Specifically, we avoid subscribing to long-lived subnets and wish to maintain a minimal set
     // of peers.
-    if cli_args.is_present("proposer-only") {
+    if cli_args.is_present("proposer-only") || proposer_profile(cli_args) {
         config.subscribe_all_subnets = false;
 
         if cli_args.value_of("target-peers").is_none() {
@@ -1494,6 +1669,26 @@ pub fn get_slots_per_restore_point<E: EthSpec>(
     }
 }
 
+/// Parses the value of `--require-builder-extra-data-prefix`, which is either a 0x-prefixed hex
+/// string or a UTF-8 string.
+fn parse_extra_data_prefix<E: EthSpec>(value: &str) -> Result<Vec<u8>, String> {
+    let prefix = if let Some(hex_str) = value.strip_prefix("0x") {
+        hex::decode(hex_str)
+            .map_err(|e| format!("Invalid --require-builder-extra-data-prefix: {:?}", e))?
+    } else {
+        value.as_bytes().to_vec()
+    };
+
+    let max_len = E::MaxExtraDataBytes::to_usize();
+    if prefix.len() > max_len {
+        return Err(format!(
+            "--require-builder-extra-data-prefix must be at most {} bytes",
+            max_len
+        ));
+    }
+    Ok(prefix)
+}
+
 /// Parses the `cli_value` as a comma-separated string of values to be parsed with `parser`.
 ///
 /// If there is more than one value, log a warning. If there are no values, return an error.
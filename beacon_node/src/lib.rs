@@ -66,6 +66,9 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let freezer_db_path = client_config.create_freezer_db_path()?;
         let blobs_db_path = client_config.create_blobs_db_path()?;
         let executor = context.executor.clone();
+        executor
+            .graceful_shutdown()
+            .set_grace_period(client_config.shutdown_grace_period);
 
         if let Some(legacy_dir) = client_config.get_existing_legacy_data_dir() {
             warn!(
@@ -0,0 +1,288 @@
+//! An in-memory index of the block roots stored in the hot database.
+//!
+//! Lookups of blocks that don't exist are common (e.g. `BlocksByRoot` requests and `block_id`
+//! resolution in the HTTP API) and would otherwise each require a database read. The index allows
+//! the store to answer them without touching the disk.
+//!
+//! Blocks at or after the split slot are tracked exactly, along with their slots. All other blocks
+//! are tracked by Bloom filters sized to the number of roots they hold, so that memory usage stays
+//! at around one byte per block. Neither structure produces false negatives: a root which is absent
+//! from all of them is definitely not stored in the database.
+
+use std::collections::HashMap;
+use std::mem::size_of;
+use types::{Hash256, Slot};
+
+/// The number of Bloom filter bits per block root, giving a false positive rate of approximately
+/// 2.4% for a full filter.
+const FILTER_BITS_PER_ROOT: usize = 8;
+
+/// The minimum number of block roots a Bloom filter is sized for.
+const MIN_FILTER_CAPACITY: usize = 1024;
+
+/// The number of bits set in a Bloom filter for each block root.
+///
+/// Block roots are uniformly distributed, so each bit index is taken directly from 8 bytes of the
+/// root rather than from a separate hash function.
+const FILTER_HASHES: usize = 4;
+
+#[derive(Debug, Default)]
+pub struct BlockRootIndex {
+    /// The slots of blocks at or after `split_slot`.
+    hot: HashMap<Hash256, Slot>,
+    /// Bloom filters of all other block roots.
+    ///
+    /// Roots are added to the last filter. Once it is full a new filter with twice the capacity is
+    /// added, so that the false positive rate stays bounded as the chain grows.
+    filters: Vec<BloomFilter>,
+    /// The number of roots the first filter is sized for.
+    initial_filter_capacity: usize,
+    /// Roots of blocks prior to this slot are stored in the filters.
+    split_slot: Slot,
+}
+
+impl BlockRootIndex {
+    pub fn new(split_slot: Slot) -> Self {
+        Self::with_capacity(split_slot, 0)
+    }
+
+    /// Create an index whose filter is sized for `expected_roots` roots without a known slot.
+    pub fn with_capacity(split_slot: Slot, expected_roots: usize) -> Self {
+        Self {
+            split_slot,
+            initial_filter_capacity: expected_roots,
+            ..Self::default()
+        }
+    }
+
+    /// Add the root of a block at `slot` to the index.
+    pub fn insert(&mut self, block_root: Hash256, slot: Slot) {
+        if slot >= self.split_slot {
+            self.hot.insert(block_root, slot);
+        } else {
+            self.insert_into_filter(block_root);
+        }
+    }
+
+    /// Add the root of a block with an unknown slot to the index.
+    pub fn insert_without_slot(&mut self, block_root: Hash256) {
+        self.insert_into_filter(block_root);
+    }
+
+    /// Remove the root of a deleted block from the index.
+    ///
+    /// Roots can't be removed from the filters, so roots of blocks prior to the split slot remain
+    /// as false positives.
+    pub fn remove(&mut self, block_root: &Hash256) {
+        self.hot.remove(block_root);
+    }
+
+    /// Returns `false` if the block with `block_root` is definitely not in the database.
+    pub fn may_contain(&self, block_root: &Hash256) -> bool {
+        self.hot.contains_key(block_root)
+            || self
+                .filters
+                .iter()
+                .any(|filter| filter.may_contain(block_root))
+    }
+
+    /// Move the roots of all blocks prior to `split_slot` into the filters.
+    pub fn set_split_slot(&mut self, split_slot: Slot) {
+        self.split_slot = split_slot;
+
+        let finalized = self
+            .hot
+            .iter()
+            .filter(|(_, slot)| **slot < split_slot)
+            .map(|(block_root, _)| *block_root)
+            .collect::<Vec<_>>();
+        for block_root in finalized {
+            self.hot.remove(&block_root);
+            self.insert_into_filter(block_root);
+        }
+        self.hot.shrink_to_fit();
+    }
+
+    /// Returns the number of blocks indexed by slot.
+    pub fn hot_len(&self) -> usize {
+        self.hot.len()
+    }
+
+    /// Returns the approximate heap memory used by the index, in bytes.
+    pub fn size_bytes(&self) -> usize {
+        // Each map entry has one byte of control metadata.
+        let hot_entry_size = size_of::<(Hash256, Slot)>() + 1;
+        self.hot.capacity() * hot_entry_size
+            + self
+                .filters
+                .iter()
+                .map(BloomFilter::size_bytes)
+                .sum::<usize>()
+    }
+
+    fn insert_into_filter(&mut self, block_root: Hash256) {
+        // Filters are only allocated once needed, so that stores which never finalize don't pay
+        // for them.
+        if self.filters.last().map_or(true, BloomFilter::is_full) {
+            let capacity = self
+                .filters
+                .last()
+                .map_or(self.initial_filter_capacity, |filter| filter.capacity * 2);
+            self.filters.push(BloomFilter::with_capacity(capacity));
+        }
+        if let Some(filter) = self.filters.last_mut() {
+            filter.insert(&block_root);
+        }
+    }
+}
+
+/// A Bloom filter sized for a fixed number of block roots.
+#[derive(Debug)]
+struct BloomFilter {
+    words: Vec<u64>,
+    /// The number of roots the filter is sized for.
+    capacity: usize,
+    /// The number of roots inserted into the filter.
+    len: usize,
+}
+
+impl BloomFilter {
+    fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(MIN_FILTER_CAPACITY);
+        let num_words = (capacity * FILTER_BITS_PER_ROOT + 63) / 64;
+        Self {
+            words: vec![0; num_words],
+            capacity,
+            len: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    fn insert(&mut self, block_root: &Hash256) {
+        let num_bits = self.words.len() * 64;
+        for bit in filter_bits(block_root, num_bits) {
+            self.words[bit / 64] |= 1 << (bit % 64);
+        }
+        self.len += 1;
+    }
+
+    fn may_contain(&self, block_root: &Hash256) -> bool {
+        let num_bits = self.words.len() * 64;
+        filter_bits(block_root, num_bits).all(|bit| self.words[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    fn size_bytes(&self) -> usize {
+        self.words.capacity() * size_of::<u64>()
+    }
+}
+
+fn filter_bits(block_root: &Hash256, num_bits: usize) -> impl Iterator<Item = usize> + '_ {
+    block_root
+        .as_bytes()
+        .chunks_exact(8)
+        .take(FILTER_HASHES)
+        .map(move |chunk| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(chunk);
+            (u64::from_le_bytes(bytes) % num_bits as u64) as usize
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A pseudo-random root derived from `i`, so that every byte of the root varies.
+    fn root(i: u64) -> Hash256 {
+        let mut bytes = [0; 32];
+        let mut x = i;
+        for chunk in bytes.chunks_exact_mut(8) {
+            x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = x;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            chunk.copy_from_slice(&(z ^ (z >> 31)).to_le_bytes());
+        }
+        Hash256::from(bytes)
+    }
+
+    #[test]
+    fn no_false_negatives() {
+        let mut index = BlockRootIndex::new(Slot::new(0));
+
+        for i in 0..1_000 {
+            index.insert(root(i), Slot::new(i));
+        }
+        for i in 1_000..2_000 {
+            index.insert_without_slot(root(i));
+        }
+        index.set_split_slot(Slot::new(500));
+
+        for i in 0..2_000 {
+            assert!(index.may_contain(&root(i)), "{i}");
+        }
+        for i in 500..1_000 {
+            assert_eq!(index.hot.get(&root(i)), Some(&Slot::new(i)));
+        }
+        assert_eq!(index.hot_len(), 500);
+    }
+
+    #[test]
+    fn definite_misses() {
+        let mut index = BlockRootIndex::new(Slot::new(100));
+        assert!(!index.may_contain(&root(1)));
+
+        index.insert(root(1), Slot::new(100));
+        assert!(index.may_contain(&root(1)));
+        assert!(!index.may_contain(&root(2)));
+
+        // Removing a hot block is exact.
+        index.remove(&root(1));
+        assert!(!index.may_contain(&root(1)));
+
+        // Blocks prior to the split are retained as false positives.
+        index.insert(root(3), Slot::new(99));
+        index.remove(&root(3));
+        assert!(index.may_contain(&root(3)));
+        assert!(!index.may_contain(&root(4)));
+    }
+
+    #[test]
+    fn size_grows_with_finalized_roots() {
+        let mut index = BlockRootIndex::new(Slot::new(0));
+        assert_eq!(index.size_bytes(), 0);
+
+        for i in 0..100_000 {
+            index.insert(root(i), Slot::new(i));
+        }
+        index.set_split_slot(Slot::new(100_000));
+
+        // Filters double in capacity, so less than twice the memory needed by the roots is used.
+        let root_bytes = 100_000 * FILTER_BITS_PER_ROOT / 8;
+        assert_eq!(index.hot_len(), 0);
+        assert!(index.size_bytes() >= root_bytes);
+        assert!(index.size_bytes() < 2 * root_bytes);
+        for i in 0..100_000 {
+            assert!(index.may_contain(&root(i)), "{i}");
+        }
+    }
+
+    #[test]
+    fn size_matches_expected_roots() {
+        let mut index = BlockRootIndex::with_capacity(Slot::new(0), 100_000);
+        for i in 0..100_000 {
+            index.insert_without_slot(root(i));
+        }
+        assert_eq!(index.filters.len(), 1);
+        assert_eq!(index.size_bytes(), 100_000 * FILTER_BITS_PER_ROOT / 8);
+
+        // The index keeps growing once it holds more roots than expected.
+        index.insert_without_slot(root(100_000));
+        assert_eq!(index.filters.len(), 2);
+        assert!(index.may_contain(&root(0)));
+        assert!(index.may_contain(&root(100_000)));
+    }
+}
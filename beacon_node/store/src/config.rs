@@ -10,6 +10,7 @@ pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 5;
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: usize = 1;
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_BLOB_PRUNE_INTERVAL_EPOCHS: u64 = 1;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -20,12 +21,18 @@ pub struct StoreConfig {
     pub slots_per_restore_point_set_explicitly: bool,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
-    /// Maximum number of states from freezer database to store in the in-memory state cache.
+    /// Maximum number of states to store in the in-memory state cache, including both states from
+    /// the freezer database and states advanced through skipped slots.
     pub historic_state_cache_size: usize,
     /// Whether to compact the database on initialization.
     pub compact_on_init: bool,
     /// Whether to compact the database during database pruning.
     pub compact_on_prune: bool,
+    /// Minimum number of epochs between compactions during database pruning.
+    ///
+    /// If `None`, compaction is scheduled based on the time since the last compaction and the
+    /// distance finality has advanced.
+    pub compact_interval_epochs: Option<u64>,
     /// Whether to prune payloads on initialization and finalization.
     pub prune_payloads: bool,
     /// Whether to prune blobs older than the blob data availability boundary.
@@ -35,6 +42,9 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Number of epochs between runs of the blob pruning service, which is independent of
+    /// finalization. Default: 1 (every epoch).
+    pub blob_prune_interval_epochs: u64,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -58,10 +68,12 @@ impl Default for StoreConfig {
             historic_state_cache_size: DEFAULT_HISTORIC_STATE_CACHE_SIZE,
             compact_on_init: false,
             compact_on_prune: true,
+            compact_interval_epochs: None,
             prune_payloads: true,
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            blob_prune_interval_epochs: DEFAULT_BLOB_PRUNE_INTERVAL_EPOCHS,
         }
     }
 }
@@ -45,8 +45,16 @@ pub enum Error {
     BlockReplayError(BlockReplayError),
     AddPayloadLogicError,
     SlotClockUnavailableForMigration,
-    UnableToDowngrade,
+    /// The schema can't be downgraded because the database holds data the older schema can't
+    /// represent.
+    UnableToDowngrade(String),
     InconsistentFork(InconsistentFork),
+    /// A historic block did not match the parent root of its successor.
+    HistoricBlockRootMismatch {
+        slot: Slot,
+        block_root: Hash256,
+        expected_block_root: Hash256,
+    },
 }
 
 pub trait HandleUnavailable<T> {
@@ -0,0 +1,137 @@
+//! Bulk import of finalized historic blocks from a trusted source, such as era files.
+use crate::chunked_vector::BlockRoots;
+use crate::hot_cold_store::HotColdDB;
+use crate::{AnchorInfo, BlobInfo, ChunkWriter, Error, ItemStore, KeyValueStore};
+use types::{BlobSidecarList, EthSpec, Hash256, SignedBlindedBeaconBlock};
+
+/// A finalized block to be imported, along with its blobs if they are available.
+pub struct HistoricBlock<E: EthSpec> {
+    pub block_root: Hash256,
+    pub block: SignedBlindedBeaconBlock<E>,
+    pub blobs: Option<BlobSidecarList<E>>,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Extend the block history backwards from the anchor with a batch of historic blocks.
+    ///
+    /// The `blocks` must be in ascending slot order. Blocks at or after the anchor's
+    /// `oldest_block_slot` are ignored, so that batches may overlap the existing history. The
+    /// remaining blocks must form a chain ending at the anchor's `oldest_block_parent`.
+    ///
+    /// Unlike backfill sync, no signatures are verified and no blocks are processed: the caller is
+    /// responsible for the provenance of the blocks, which are authenticated only by their chain
+    /// of parent roots. The block roots are written straight to the freezer database, and the
+    /// anchor is updated once all of the batch has been written, so an interrupted import may
+    /// safely be restarted.
+    ///
+    /// If the oldest block is a child of `genesis_block_root` then the history is completed.
+    ///
+    /// Returns the number of blocks imported.
+    pub fn import_historic_blocks(
+        &self,
+        mut blocks: Vec<HistoricBlock<E>>,
+        genesis_block_root: Hash256,
+    ) -> Result<usize, Error> {
+        let Some(anchor_info) = self.get_anchor_info() else {
+            // The block history is already complete.
+            return Ok(0);
+        };
+        let blob_info = self.get_blob_info();
+
+        let num_relevant =
+            blocks.partition_point(|block| block.block.slot() < anchor_info.oldest_block_slot);
+        blocks.truncate(num_relevant);
+
+        if blocks.is_empty() || anchor_info.oldest_block_parent.is_zero() {
+            return Ok(0);
+        }
+
+        let mut expected_block_root = anchor_info.oldest_block_parent;
+        let mut prev_block_slot = anchor_info.oldest_block_slot;
+        let mut chunk_writer =
+            ChunkWriter::<BlockRoots, _, _>::new(&self.cold_db, prev_block_slot.as_usize())?;
+        let mut new_oldest_blob_slot = blob_info.oldest_blob_slot;
+
+        let mut block_batch = Vec::with_capacity(blocks.len());
+        let mut blobs_batch = vec![];
+        let mut cold_batch = Vec::with_capacity(blocks.len());
+
+        for HistoricBlock {
+            block_root,
+            block,
+            blobs,
+        } in blocks.into_iter().rev()
+        {
+            if block_root != expected_block_root {
+                return Err(Error::HistoricBlockRootMismatch {
+                    slot: block.slot(),
+                    block_root,
+                    expected_block_root,
+                });
+            }
+
+            self.blinded_block_as_kv_store_ops(&block_root, &block, &mut block_batch);
+            if let Some(blobs) = blobs {
+                new_oldest_blob_slot = Some(block.slot());
+                self.blobs_as_kv_store_ops(&block_root, blobs, &mut blobs_batch);
+            }
+
+            // Store block roots, including at all skip slots in the freezer DB.
+            for slot in (block.slot().as_usize()..prev_block_slot.as_usize()).rev() {
+                chunk_writer.set(slot, block_root, &mut cold_batch)?;
+            }
+
+            prev_block_slot = block.slot();
+            expected_block_root = block.message().parent_root();
+
+            // If we've reached genesis, add the genesis block root to the batch and set the
+            // anchor slot to 0 to indicate completion.
+            if expected_block_root == genesis_block_root {
+                let genesis_slot = self.spec.genesis_slot;
+                chunk_writer.set(genesis_slot.as_usize(), genesis_block_root, &mut cold_batch)?;
+                prev_block_slot = genesis_slot;
+                expected_block_root = Hash256::zero();
+                break;
+            }
+        }
+        chunk_writer.write(&mut cold_batch)?;
+
+        // Write the blocks and blobs first, as it's better for the hot DB to contain extra blocks
+        // than for the cold DB to point to blocks that do not exist.
+        self.hot_db.do_atomically(block_batch)?;
+        self.blobs_db
+            .as_ref()
+            .unwrap_or(&self.cold_db)
+            .do_atomically(blobs_batch)?;
+        self.cold_db.do_atomically(cold_batch)?;
+
+        let mut anchor_and_blob_batch = Vec::with_capacity(2);
+
+        if new_oldest_blob_slot != blob_info.oldest_blob_slot {
+            if let Some(oldest_blob_slot) = new_oldest_blob_slot {
+                let new_blob_info = BlobInfo {
+                    oldest_blob_slot: Some(oldest_blob_slot),
+                    ..blob_info.clone()
+                };
+                anchor_and_blob_batch
+                    .push(self.compare_and_set_blob_info(blob_info, new_blob_info)?);
+            }
+        }
+
+        let new_anchor = AnchorInfo {
+            oldest_block_slot: prev_block_slot,
+            oldest_block_parent: expected_block_root,
+            ..anchor_info
+        };
+        anchor_and_blob_batch
+            .push(self.compare_and_set_anchor_info(Some(anchor_info), Some(new_anchor))?);
+        self.hot_db.do_atomically(anchor_and_blob_batch)?;
+
+        Ok(num_relevant)
+    }
+}
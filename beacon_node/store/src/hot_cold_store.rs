@@ -1,3 +1,4 @@
+use crate::block_root_index::BlockRootIndex;
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, HistoricalSummaries, RandaoMixes, StateRoots,
 };
@@ -12,11 +13,13 @@ use crate::leveldb_store::BytesKey;
 use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
-    AnchorInfo, BlobInfo, CompactionTimestamp, PruningCheckpoint, SchemaVersion, ANCHOR_INFO_KEY,
-    BLOB_INFO_KEY, COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION,
-    PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
+    AbandonedForks, AnchorInfo, BlobInfo, CompactionTimestamp, PruningCheckpoint, PruningCursor,
+    SchemaVersion, ABANDONED_FORKS_KEY, ANCHOR_INFO_KEY, BLOB_INFO_KEY, COMPACTION_TIMESTAMP_KEY,
+    CONFIG_KEY, CURRENT_SCHEMA_VERSION, PRUNING_CHECKPOINT_KEY, PRUNING_CURSOR_KEY,
+    SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
 };
 use crate::metrics;
+use crate::state_cache::{StateCache, StateCacheKey};
 use crate::{
     get_key_for_col, ChunkWriter, DBColumn, DatabaseBlock, Error, ItemStore, KeyValueStoreOp,
     PartialBeaconState, StoreItem, StoreOp,
@@ -24,7 +27,7 @@ use crate::{
 use itertools::process_results;
 use leveldb::iterator::LevelDBIterator;
 use lru::LruCache;
-use parking_lot::{Mutex, RwLock};
+use parking_lot::{Mutex, MutexGuard, RwLock};
 use serde::{Deserialize, Serialize};
 use slog::{debug, error, info, trace, warn, Logger};
 use ssz::{Decode, Encode};
@@ -33,11 +36,12 @@ use state_processing::{
     BlockProcessingError, BlockReplayer, SlotProcessingError, StateProcessingStrategy,
 };
 use std::cmp::min;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use types::blob_sidecar::BlobSidecarList;
 use types::consts::deneb::MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS;
 use types::*;
@@ -68,8 +72,12 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub hot_db: Hot,
     /// LRU cache of deserialized blocks and blobs. Updated whenever a block or blob is loaded.
     block_cache: Mutex<BlockCache<E>>,
-    /// LRU cache of replayed states.
-    state_cache: Mutex<LruCache<Slot, BeaconState<E>>>,
+    /// Index of the roots of all blocks in the hot database, used to skip reads for missing blocks.
+    ///
+    /// This is `None` until the index has been built from the database on start-up.
+    block_root_index: RwLock<Option<BlockRootIndex>>,
+    /// LRU cache of replayed states, holding both frozen states and skip-slot states.
+    state_cache: Mutex<StateCache<E>>,
     /// Chain spec.
     pub(crate) spec: ChainSpec,
     /// Logger.
@@ -152,6 +160,12 @@ pub enum HotColdDBError {
         slots_per_epoch: u64,
     },
     ZeroEpochsPerBlobPrune,
+    ZeroBlobPruneIntervalEpochs,
+    /// A restore point migration was resumed with different parameters than it was started with.
+    RestorePointMigrationMismatch {
+        old_slots_per_restore_point: u64,
+        new_slots_per_restore_point: u64,
+    },
     BlobPruneLogicError,
     RestorePointBlockHashError(BeaconStateError),
     IterationError {
@@ -181,7 +195,8 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             blobs_db: Some(MemoryStore::open()),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
-            state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            block_root_index: RwLock::new(Some(BlockRootIndex::default())),
+            state_cache: Mutex::new(StateCache::new(config.historic_state_cache_size)),
             config,
             spec,
             log,
@@ -208,7 +223,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         spec: ChainSpec,
         log: Logger,
     ) -> Result<Arc<Self>, Error> {
-        Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_config(&config)?;
 
         let mut db = HotColdDB {
             split: RwLock::new(Split::default()),
@@ -218,7 +233,8 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             blobs_db: None,
             hot_db: LevelDB::open(hot_path)?,
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
-            state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            block_root_index: RwLock::new(None),
+            state_cache: Mutex::new(StateCache::new(config.historic_state_cache_size)),
             config,
             spec,
             log,
@@ -330,11 +346,19 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         // Run a garbage collection pass.
         db.remove_garbage()?;
 
+        // Index the blocks on disk, now that schema migrations and garbage collection are done.
+        db.build_block_root_index()?;
+
         // If configured, run a foreground compaction pass.
         if db.config.compact_on_init {
             info!(db.log, "Running foreground compaction");
-            db.compact()?;
-            info!(db.log, "Foreground compaction complete");
+            let summary = db.compact()?;
+            info!(
+                db.log,
+                "Foreground compaction complete";
+                "duration" => ?summary.duration,
+                "reclaimed_bytes" => summary.reclaimed_bytes,
+            );
         }
 
         Ok(db)
@@ -412,6 +436,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         blinded_block: &SignedBeaconBlock<E, BlindedPayload<E>>,
         ops: &mut Vec<KeyValueStoreOp>,
     ) {
+        // Index the block before it is written, so that the index never misses a stored block.
+        self.update_block_root_index(|index| index.insert(*key, blinded_block.slot()));
+
         let db_key = get_key_for_col(DBColumn::BeaconBlock.into(), key.as_bytes());
         ops.push(KeyValueStoreOp::PutKeyValue(
             db_key,
@@ -419,6 +446,50 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         ));
     }
 
+    /// Build the block root index from the blocks stored in the hot database.
+    ///
+    /// Until this is called all block lookups fall through to the database.
+    pub fn build_block_root_index(&self) -> Result<(), Error> {
+        // Size the index to the blocks on disk, with room for a quarter as many again to be
+        // finalized before it needs to grow.
+        let block_count = self.hot_db.iter_column_keys(DBColumn::BeaconBlock).count();
+        let mut index =
+            BlockRootIndex::with_capacity(self.get_split_slot(), block_count + block_count / 4);
+        for block_root in self.hot_db.iter_column_keys(DBColumn::BeaconBlock) {
+            index.insert_without_slot(block_root?);
+        }
+        info!(
+            self.log,
+            "Built block root index";
+            "block_count" => block_count,
+            "size_bytes" => index.size_bytes(),
+        );
+        metrics::set_gauge(&metrics::BLOCK_ROOT_INDEX_SIZE, index.size_bytes() as i64);
+        *self.block_root_index.write() = Some(index);
+        Ok(())
+    }
+
+    /// Apply `f` to the block root index, if it has been built.
+    fn update_block_root_index(&self, f: impl FnOnce(&mut BlockRootIndex)) {
+        if let Some(index) = self.block_root_index.write().as_mut() {
+            f(index);
+            metrics::set_gauge(&metrics::BLOCK_ROOT_INDEX_SIZE, index.size_bytes() as i64);
+        }
+    }
+
+    /// Returns `false` if the block with `block_root` is definitely not in the database.
+    pub fn block_may_exist(&self, block_root: &Hash256) -> bool {
+        let may_exist = self
+            .block_root_index
+            .read()
+            .as_ref()
+            .map_or(true, |index| index.may_contain(block_root));
+        if !may_exist {
+            metrics::inc_counter(&metrics::BEACON_BLOCK_INDEX_MISS_COUNT);
+        }
+        may_exist
+    }
+
     pub fn try_get_full_block(
         &self,
         block_root: &Hash256,
@@ -536,6 +607,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         block_root: &Hash256,
         decoder: impl FnOnce(&[u8]) -> Result<SignedBeaconBlock<E, Payload>, ssz::DecodeError>,
     ) -> Result<Option<SignedBeaconBlock<E, Payload>>, Error> {
+        if !self.block_may_exist(block_root) {
+            return Ok(None);
+        }
         self.hot_db
             .get_bytes(DBColumn::BeaconBlock.into(), block_root.as_bytes())?
             .map(|block_bytes| decoder(&block_bytes))
@@ -582,6 +656,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Determine whether a block exists in the database.
     pub fn block_exists(&self, block_root: &Hash256) -> Result<bool, Error> {
+        if !self.block_may_exist(block_root) {
+            return Ok(false);
+        }
         self.hot_db
             .key_exists(DBColumn::BeaconBlock.into(), block_root.as_bytes())
     }
@@ -591,6 +668,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.block_cache.lock().delete(block_root);
         self.hot_db
             .key_delete(DBColumn::BeaconBlock.into(), block_root.as_bytes())?;
+        self.update_block_root_index(|index| index.remove(block_root));
         self.hot_db
             .key_delete(DBColumn::ExecPayload.into(), block_root.as_bytes())?;
         let blobs_db = self.blobs_db.as_ref().unwrap_or(&self.cold_db);
@@ -671,6 +749,68 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Fetch the post-state of the block with `block_root` from the store.
+    ///
+    /// Blocks which are definitely missing are answered by the block root index, and the states of
+    /// blocks prior to the split are loaded straight from the freezer.
+    pub fn get_block_state(&self, block_root: &Hash256) -> Result<Option<BeaconState<E>>, Error> {
+        match self.get_blinded_block(block_root)? {
+            Some(block) => self.get_state(&block.state_root(), Some(block.slot())),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the state with root `base_state_root` advanced through skipped slots to `slot` from
+    /// the state cache, along with the root of the advanced state, if present.
+    pub fn get_cached_skip_slot_state(
+        &self,
+        base_state_root: Hash256,
+        slot: Slot,
+    ) -> Option<(Hash256, BeaconState<E>)> {
+        let key = StateCacheKey::SkipSlot {
+            base_state_root,
+            slot,
+        };
+        let cached = self
+            .state_cache
+            .lock()
+            .get_with_root(&key)
+            .map(|(state_root, state)| (state_root, state.clone()));
+        if cached.is_some() {
+            metrics::inc_counter(&metrics::BEACON_STATE_CACHE_HIT_COUNT);
+        }
+        cached
+    }
+
+    /// Store the state with root `base_state_root` advanced through skipped slots to `slot` in the
+    /// state cache, along with `state_root`, the root of the advanced state.
+    ///
+    /// Only the committee caches of the state are kept, so that each skip-slot state costs no more
+    /// memory than a frozen state. Skip-slot states share the capacity of the cache with frozen
+    /// states, which is set by `StoreConfig::historic_state_cache_size`.
+    pub fn cache_skip_slot_state(
+        &self,
+        base_state_root: Hash256,
+        slot: Slot,
+        state_root: Hash256,
+        state: &BeaconState<E>,
+    ) {
+        let key = StateCacheKey::SkipSlot {
+            base_state_root,
+            slot,
+        };
+        self.state_cache.lock().put_with_root(
+            key,
+            state_root,
+            state.clone_with(CloneConfig::committee_caches_only()),
+        );
+    }
+
+    /// Returns the cache of replayed frozen and skip-slot states.
+    pub fn state_cache(&self) -> MutexGuard<StateCache<E>> {
+        self.state_cache.lock()
+    }
+
     /// Fetch a state from the store, but don't compute all of the values when replaying blocks
     /// upon that state (e.g., state roots). Additionally, only states from the hot store are
     /// returned.
@@ -1041,6 +1181,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
                 StoreOp::DeleteBlock(block_root) => {
                     guard.delete_block(&block_root);
+                    self.update_block_root_index(|index| index.remove(&block_root));
                 }
 
                 StoreOp::DeleteBlobs(_) => (),
@@ -1174,6 +1315,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "state_root" => format!("{:?}", state_root)
         );
 
+        self.store_restore_point_state(state_root, state, ops)?;
+
+        // 3. Store restore point.
+        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
+        self.store_restore_point_hash(restore_point_index, *state_root, ops);
+
+        Ok(())
+    }
+
+    /// Store the partial state and vector entries of a restore point, without its index entry.
+    pub(crate) fn store_restore_point_state(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
         // 1. Convert to PartialBeaconState and store that in the DB.
         let partial_state = PartialBeaconState::from_state_forgetful(state);
         let op = partial_state.as_kv_store_op(*state_root);
@@ -1190,10 +1347,6 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
         store_updated_vector(HistoricalSummaries, db, state, &self.spec, ops)?;
 
-        // 3. Store restore point.
-        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
-        self.store_restore_point_hash(restore_point_index, *state_root, ops);
-
         Ok(())
     }
 
@@ -1259,7 +1412,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Load a frozen state that lies between restore points.
     fn load_cold_intermediate_state(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
-        if let Some(state) = self.state_cache.lock().get(&slot) {
+        if let Some(state) = self.state_cache.lock().get(&StateCacheKey::Historic(slot)) {
             return Ok(state.clone());
         }
 
@@ -1273,7 +1426,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         let mut low_state: Option<BeaconState<E>> = None;
 
         // Try to get a more recent state from the cache to avoid massive blocks replay.
-        for (s, state) in self.state_cache.lock().iter() {
+        for (key, state) in self.state_cache.lock().iter() {
+            let StateCacheKey::Historic(s) = key else {
+                continue;
+            };
             if s.as_u64() / self.config.slots_per_restore_point == low_restore_point_idx
                 && *s < slot
                 && low_slot < *s
@@ -1320,7 +1476,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         )?;
 
         // If state is not error, put it in the cache.
-        self.state_cache.lock().put(slot, state.clone());
+        self.state_cache
+            .lock()
+            .put(StateCacheKey::Historic(slot), state.clone());
 
         Ok(state)
     }
@@ -1483,6 +1641,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             state_root,
             block_root,
         };
+        self.update_block_root_index(|index| index.set_split_slot(slot));
     }
 
     /// Fetch the slot of the most recently stored restore point (if any).
@@ -1783,7 +1942,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Store the state root of a restore point.
-    fn store_restore_point_hash(
+    pub(crate) fn store_restore_point_hash(
         &self,
         restore_point_index: u64,
         state_root: Hash256,
@@ -1795,7 +1954,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Convert a `restore_point_index` into a database key.
-    fn restore_point_key(restore_point_index: u64) -> Hash256 {
+    pub(crate) fn restore_point_key(restore_point_index: u64) -> Hash256 {
         Hash256::from_low_u64_be(restore_point_index)
     }
 
@@ -1829,7 +1988,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// Verify that a parsed config is valid.
     fn verify_config(config: &StoreConfig) -> Result<(), HotColdDBError> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
-        Self::verify_epochs_per_blob_prune(config.epochs_per_blob_prune)
+        Self::verify_epochs_per_blob_prune(config.epochs_per_blob_prune)?;
+        Self::verify_blob_prune_interval_epochs(config.blob_prune_interval_epochs)
     }
 
     /// Check that the restore point frequency is valid.
@@ -1845,7 +2005,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// (2) ensures that restore points align with hot state summaries, making it
     /// quick to migrate hot to cold.
-    fn verify_slots_per_restore_point(slots_per_restore_point: u64) -> Result<(), HotColdDBError> {
+    pub(crate) fn verify_slots_per_restore_point(
+        slots_per_restore_point: u64,
+    ) -> Result<(), HotColdDBError> {
         let slots_per_historical_root = E::SlotsPerHistoricalRoot::to_u64();
         let slots_per_epoch = E::slots_per_epoch();
         if slots_per_restore_point > 0
@@ -1872,10 +2034,51 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    // Check that blob_prune_interval_epochs is at least 1 epoch, so that the blob pruning service
+    // has a well-defined schedule.
+    fn verify_blob_prune_interval_epochs(
+        blob_prune_interval_epochs: u64,
+    ) -> Result<(), HotColdDBError> {
+        if blob_prune_interval_epochs > 0 {
+            Ok(())
+        } else {
+            Err(HotColdDBError::ZeroBlobPruneIntervalEpochs)
+        }
+    }
+
     /// Run a compaction pass to free up space used by deleted states.
-    pub fn compact(&self) -> Result<(), Error> {
+    ///
+    /// The time of the compaction is recorded so that it counts towards the compaction schedule,
+    /// regardless of what triggered it.
+    pub fn compact(&self) -> Result<CompactionSummary, Error> {
+        let size_before = self.hot_db.disk_size();
+        let start = Instant::now();
+
         self.hot_db.compact()?;
-        Ok(())
+
+        let duration = start.elapsed();
+        let reclaimed_bytes = size_before
+            .zip(self.hot_db.disk_size())
+            .map(|(before, after)| before.saturating_sub(after));
+
+        metrics::inc_counter(&metrics::DISK_DB_COMPACTION_COUNT);
+        metrics::observe_duration(&metrics::DISK_DB_COMPACTION_TIMES, duration);
+        if let Some(reclaimed_bytes) = reclaimed_bytes {
+            metrics::inc_counter_by(
+                &metrics::DISK_DB_COMPACTION_RECLAIMED_BYTES,
+                reclaimed_bytes,
+            );
+        }
+
+        let finish_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        self.store_compaction_timestamp(finish_time)?;
+
+        Ok(CompactionSummary {
+            duration,
+            reclaimed_bytes,
+        })
     }
 
     /// Return `true` if compaction on finalization/pruning is enabled.
@@ -1902,6 +2105,44 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         PruningCheckpoint { checkpoint }.as_kv_store_op(PRUNING_CHECKPOINT_KEY)
     }
 
+    /// Load the abandoned forks of an unfinished prune, and the number of their blocks and states
+    /// which have been deleted.
+    pub fn load_abandoned_forks(&self) -> Result<Option<(AbandonedForks, u64)>, Error> {
+        let Some(abandoned_forks) = self.hot_db.get::<AbandonedForks>(&ABANDONED_FORKS_KEY)? else {
+            return Ok(None);
+        };
+        let cursor = self
+            .hot_db
+            .get::<PruningCursor>(&PRUNING_CURSOR_KEY)?
+            .map_or(0, |cursor| cursor.0);
+        Ok(Some((abandoned_forks, cursor)))
+    }
+
+    /// Create a staged store for the abandoned forks to prune, with none of them deleted yet.
+    pub fn abandoned_forks_store_ops(
+        &self,
+        abandoned_forks: &AbandonedForks,
+    ) -> [KeyValueStoreOp; 2] {
+        [
+            abandoned_forks.as_kv_store_op(ABANDONED_FORKS_KEY),
+            self.pruning_cursor_store_op(0),
+        ]
+    }
+
+    /// Create a staged store for the number of abandoned blocks and states deleted so far.
+    pub fn pruning_cursor_store_op(&self, deleted: u64) -> KeyValueStoreOp {
+        PruningCursor(deleted).as_kv_store_op(PRUNING_CURSOR_KEY)
+    }
+
+    /// Create staged deletions of the abandoned forks and the pruning cursor.
+    pub fn abandoned_forks_delete_ops(&self) -> [KeyValueStoreOp; 2] {
+        let column = DBColumn::BeaconMeta.as_str();
+        [
+            KeyValueStoreOp::DeleteKey(get_key_for_col(column, ABANDONED_FORKS_KEY.as_bytes())),
+            KeyValueStoreOp::DeleteKey(get_key_for_col(column, PRUNING_CURSOR_KEY.as_bytes())),
+        ]
+    }
+
     /// Load the timestamp of the last compaction as a `Duration` since the UNIX epoch.
     pub fn load_compaction_timestamp(&self) -> Result<Option<Duration>, Error> {
         Ok(self
@@ -2055,14 +2296,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         };
         // The current epoch is >= split_epoch + 2. It could be greater if the database is
         // configured to delay updating the split or finalization has ceased. In this instance we
-        // choose to also delay the pruning of blobs (we never prune without finalization anyway).
+        // choose to also delay the pruning of blobs, as the current epoch isn't known.
         let min_current_epoch = self.get_split_slot().epoch(E::slots_per_epoch()) + 2;
         let min_data_availability_boundary = std::cmp::max(
             deneb_fork_epoch,
             min_current_epoch.saturating_sub(MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS),
         );
 
-        self.try_prune_blobs(force, min_data_availability_boundary)
+        // The boundary is prior to the split, so no unfinalized blocks are needed.
+        self.try_prune_blobs(force, min_data_availability_boundary, &[])
     }
 
     /// Try to prune blobs older than the data availability boundary.
@@ -2070,8 +2312,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// Blobs from the epoch `data_availability_boundary - blob_prune_margin_epochs` are retained.
     /// This epoch is an _exclusive_ endpoint for the pruning process.
     ///
-    /// This function only supports pruning blobs older than the split point, which is older than
-    /// (or equal to) finalization. Pruning blobs newer than finalization is not supported.
+    /// The data availability boundary should be computed from the current slot, so that blobs
+    /// continue to be pruned if finalization stalls. Blobs of finalized blocks are found by
+    /// iterating the canonical chain, while blobs of newer blocks are only pruned if the block is
+    /// in `unfinalized_blocks`. These should be the `(block_root, slot)` of every block in fork
+    /// choice prior to the boundary, so that blobs from every fork older than the boundary are
+    /// pruned. Blobs of unfinalized blocks within the data availability window are never pruned.
+    ///
+    /// The oldest blob slot is only advanced over slots whose blobs were all deleted. Hot blocks
+    /// with blobs which are not in `unfinalized_blocks` hold it back until they are pruned along
+    /// with their fork.
     ///
     /// This function also assumes that the split is stationary while it runs. It should only be
     /// run from the migrator thread (where `migrate_database` runs) or the database manager.
@@ -2079,6 +2329,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self,
         force: bool,
         data_availability_boundary: Epoch,
+        unfinalized_blocks: &[(Hash256, Slot)],
     ) -> Result<(), Error> {
         if self.spec.deneb_fork_epoch.is_none() {
             debug!(self.log, "Deneb fork is disabled");
@@ -2108,14 +2359,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         // The start epoch is inclusive (blobs in this epoch will be pruned).
         let start_epoch = oldest_blob_slot.epoch(E::slots_per_epoch());
 
-        // Prune blobs up until the `data_availability_boundary - margin` or the split
-        // slot's epoch, whichever is older. We can't prune blobs newer than the split.
+        // Prune blobs up until the `data_availability_boundary - margin`.
         // The end epoch is also inclusive (blobs in this epoch will be pruned).
         let split = self.get_split_info();
-        let end_epoch = std::cmp::min(
-            data_availability_boundary - margin_epochs - 1,
-            split.slot.epoch(E::slots_per_epoch()) - 1,
-        );
+        let end_epoch = data_availability_boundary - margin_epochs - 1;
         let end_slot = end_epoch.end_slot(E::slots_per_epoch());
 
         let can_prune = end_epoch != 0 && start_epoch <= end_epoch;
@@ -2157,10 +2404,66 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         );
 
         let mut ops = vec![];
+        let mut new_oldest_blob_slot = end_slot + 1;
+
+        if oldest_blob_slot < split.slot {
+            let finalized_end_slot = std::cmp::min(end_slot, split.slot - 1);
+            if let Some(retained_slot) = self.finalized_blob_prune_ops(
+                oldest_blob_slot,
+                finalized_end_slot,
+                split,
+                &mut ops,
+            )? {
+                new_oldest_blob_slot = std::cmp::min(new_oldest_blob_slot, retained_slot);
+            }
+        }
+        if end_slot >= split.slot {
+            let unfinalized_start_slot = std::cmp::max(oldest_blob_slot, split.slot);
+            if let Some(retained_slot) = self.unfinalized_blob_prune_ops(
+                unfinalized_blocks,
+                unfinalized_start_slot,
+                end_slot,
+                &mut ops,
+            )? {
+                new_oldest_blob_slot = std::cmp::min(new_oldest_blob_slot, retained_slot);
+            }
+        }
+
+        let blob_lists_pruned = ops.len();
+        let new_blob_info = BlobInfo {
+            oldest_blob_slot: Some(new_oldest_blob_slot),
+            blobs_db: blob_info.blobs_db,
+        };
+        let update_blob_info = self.compare_and_set_blob_info(blob_info, new_blob_info)?;
+        ops.push(StoreOp::KeyValueOp(update_blob_info));
+
+        self.do_atomically_with_block_and_blobs_cache(ops)?;
+        debug!(
+            self.log,
+            "Blob pruning complete";
+            "blob_lists_pruned" => blob_lists_pruned,
+            "oldest_blob_slot" => new_oldest_blob_slot,
+        );
+
+        Ok(())
+    }
+
+    /// Push ops deleting the blobs of canonical blocks from `start_slot` to `end_slot` (inclusive),
+    /// both of which must be prior to the split.
+    ///
+    /// Returns the first slot which wasn't reached if the iteration stopped early.
+    fn finalized_blob_prune_ops(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        split: Split,
+        ops: &mut Vec<StoreOp<E>>,
+    ) -> Result<Option<Slot>, Error> {
         let mut last_pruned_block_root = None;
+        let mut next_slot = start_slot;
 
         for res in self.forwards_block_roots_iterator_until(
-            oldest_blob_slot,
+            start_slot,
             end_slot,
             || {
                 let (_, split_state) = self
@@ -2182,9 +2485,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                         "Stopping blob pruning early";
                         "error" => ?e,
                     );
-                    break;
+                    return Ok(Some(next_slot));
                 }
             };
+            next_slot = slot + 1;
 
             if Some(block_root) != last_pruned_block_root && self.blobs_exist(&block_root)? {
                 trace!(
@@ -2201,22 +2505,75 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 break;
             }
         }
-        let blob_lists_pruned = ops.len();
-        let new_blob_info = BlobInfo {
-            oldest_blob_slot: Some(end_slot + 1),
-            blobs_db: blob_info.blobs_db,
-        };
-        let update_blob_info = self.compare_and_set_blob_info(blob_info, new_blob_info)?;
-        ops.push(StoreOp::KeyValueOp(update_blob_info));
+        if next_slot <= end_slot {
+            Ok(Some(next_slot))
+        } else {
+            Ok(None)
+        }
+    }
 
-        self.do_atomically_with_block_and_blobs_cache(ops)?;
-        debug!(
-            self.log,
-            "Blob pruning complete";
-            "blob_lists_pruned" => blob_lists_pruned,
-        );
+    /// Push ops deleting the blobs of the `unfinalized_blocks` from `start_slot` to `end_slot`
+    /// (inclusive), on any fork.
+    ///
+    /// Blocks which conflict with finalization are not in fork choice, but their blobs are deleted
+    /// along with them when abandoned forks are pruned. Until then they are retained, and the slot
+    /// of the oldest such block with blobs is returned.
+    fn unfinalized_blob_prune_ops(
+        &self,
+        unfinalized_blocks: &[(Hash256, Slot)],
+        start_slot: Slot,
+        end_slot: Slot,
+        ops: &mut Vec<StoreOp<E>>,
+    ) -> Result<Option<Slot>, Error> {
+        let prunable_block_roots = unfinalized_blocks
+            .iter()
+            .filter(|(_, slot)| *slot >= start_slot && *slot <= end_slot)
+            .map(|(block_root, _)| *block_root)
+            .collect::<HashSet<_>>();
+
+        for block_root in &prunable_block_roots {
+            if self.blobs_exist(block_root)? {
+                trace!(
+                    self.log,
+                    "Pruning blobs of unfinalized block";
+                    "block_root" => ?block_root,
+                );
+                ops.push(StoreOp::DeleteBlobs(*block_root));
+            }
+        }
 
-        Ok(())
+        // Every block in the hot database has a state summary at its slot, so the slot of each
+        // block is the earliest slot of the summaries which reference it.
+        let mut hot_block_slots = HashMap::<Hash256, Slot>::new();
+        for res in self.hot_db.iter_column(DBColumn::BeaconStateSummary) {
+            let (_, summary_bytes) = res?;
+            let summary = HotStateSummary::from_ssz_bytes(&summary_bytes)?;
+            hot_block_slots
+                .entry(summary.latest_block_root)
+                .and_modify(|slot| *slot = std::cmp::min(*slot, summary.slot))
+                .or_insert(summary.slot);
+        }
+
+        let mut oldest_retained_slot = None;
+        for (block_root, slot) in hot_block_slots {
+            if slot < start_slot
+                || slot > end_slot
+                || prunable_block_roots.contains(&block_root)
+                || oldest_retained_slot.map_or(false, |oldest| slot >= oldest)
+            {
+                continue;
+            }
+            if self.blobs_exist(&block_root)? {
+                debug!(
+                    self.log,
+                    "Retaining blobs of block outside fork choice";
+                    "slot" => slot,
+                    "block_root" => ?block_root,
+                );
+                oldest_retained_slot = Some(slot);
+            }
+        }
+        Ok(oldest_retained_slot)
     }
 }
 
@@ -2371,6 +2728,7 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         // hasn't been modified elsewhere since we keep a write lock on it.  It's safe to update
         // the in-memory split point now.
         *split_guard = split;
+        store.update_block_root_index(|index| index.set_split_slot(split.slot));
     }
 
     // Delete the states from the hot database if we got this far.
@@ -2385,6 +2743,16 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     Ok(())
 }
 
+/// The outcome of a compaction of the hot database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionSummary {
+    pub duration: Duration,
+    /// The decrease in the size of the database on disk, or `None` if it isn't stored on disk.
+    ///
+    /// This is an estimate, as other writes may happen concurrently with the compaction.
+    pub reclaimed_bytes: Option<u64>,
+}
+
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, Deserialize, Serialize)]
 pub struct Split {
@@ -2422,7 +2790,7 @@ fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error
 /// Struct for summarising a state in the hot database.
 ///
 /// Allows full reconstruction by replaying blocks.
-#[derive(Debug, Clone, Copy, Default, Encode, Decode)]
+#[derive(Debug, Clone, Copy, Default, Encode, Decode, Serialize)]
 pub struct HotStateSummary {
     pub slot: Slot,
     pub latest_block_root: Hash256,
@@ -2,6 +2,7 @@ use super::*;
 use crate::hot_cold_store::HotColdDBError;
 use crate::metrics;
 use db_key::Key;
+use directory::size_of_dir;
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
@@ -11,11 +12,13 @@ use leveldb::iterator::{Iterable, KeyIterator, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
 use parking_lot::{Mutex, MutexGuard};
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// A wrapped leveldb database.
 pub struct LevelDB<E: EthSpec> {
     db: Database<BytesKey>,
+    /// The directory containing the database files.
+    path: PathBuf,
     /// A mutex to synchronise sensitive read-write transactions.
     transaction_mutex: Mutex<()>,
     _phantom: PhantomData<E>,
@@ -33,6 +36,7 @@ impl<E: EthSpec> LevelDB<E> {
 
         Ok(Self {
             db,
+            path: path.to_path_buf(),
             transaction_mutex,
             _phantom: PhantomData,
         })
@@ -176,6 +180,10 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         Ok(())
     }
 
+    fn disk_size(&self) -> Option<u64> {
+        Some(size_of_dir(&self.path))
+    }
+
     /// Iterate through all keys and values in a particular column.
     fn iter_column(&self, column: DBColumn) -> ColumnIter {
         let start_key =
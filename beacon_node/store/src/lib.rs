@@ -10,6 +10,7 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod block_root_index;
 mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
@@ -17,6 +18,7 @@ pub mod config;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
+pub mod historic_blocks;
 pub mod hot_cold_store;
 mod impls;
 mod leveldb_store;
@@ -25,16 +27,20 @@ pub mod metadata;
 pub mod metrics;
 mod partial_beacon_state;
 pub mod reconstruct;
+pub mod restore_points;
+mod state_cache;
 
 pub mod iter;
 
 pub use self::chunk_writer::ChunkWriter;
 pub use self::config::StoreConfig;
-pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
+pub use self::historic_blocks::HistoricBlock;
+pub use self::hot_cold_store::{CompactionSummary, HotColdDB, HotStateSummary, Split};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
-pub use crate::metadata::BlobInfo;
+pub use self::state_cache::StateCache;
+pub use crate::metadata::{AbandonedForks, AbandonedState, BlobInfo};
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
 pub use metadata::AnchorInfo;
@@ -83,6 +89,11 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Compact the database, freeing space used by deleted items.
     fn compact(&self) -> Result<(), Error>;
 
+    /// Return the number of bytes used by the database on disk, if it is stored on disk.
+    fn disk_size(&self) -> Option<u64> {
+        None
+    }
+
     /// Iterate through all keys and values in a particular column.
     fn iter_column(&self, _column: DBColumn) -> ColumnIter {
         // Default impl for non LevelDB databases
@@ -234,6 +245,9 @@ pub enum DBColumn {
     BeaconHistoricalSummaries,
     #[strum(serialize = "olc")]
     OverflowLRUCache,
+    /// For proposer preparations and builder registrations received from validator clients.
+    #[strum(serialize = "ppr")]
+    ProposerPreparation,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -242,6 +256,29 @@ pub enum DatabaseBlock<E: EthSpec> {
     Blinded(SignedBeaconBlock<E, BlindedPayload<E>>),
 }
 
+impl<E: EthSpec> DatabaseBlock<E> {
+    pub fn slot(&self) -> Slot {
+        match self {
+            DatabaseBlock::Full(block) => block.slot(),
+            DatabaseBlock::Blinded(block) => block.slot(),
+        }
+    }
+
+    pub fn canonical_root(&self) -> Hash256 {
+        match self {
+            DatabaseBlock::Full(block) => block.canonical_root(),
+            DatabaseBlock::Blinded(block) => block.canonical_root(),
+        }
+    }
+
+    pub fn clone_as_blinded(&self) -> SignedBeaconBlock<E, BlindedPayload<E>> {
+        match self {
+            DatabaseBlock::Full(block) => block.clone_as_blinded(),
+            DatabaseBlock::Blinded(block) => block.clone(),
+        }
+    }
+}
+
 impl DBColumn {
     pub fn as_str(self) -> &'static str {
         self.into()
@@ -16,6 +16,9 @@ pub const PRUNING_CHECKPOINT_KEY: Hash256 = Hash256::repeat_byte(3);
 pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
 pub const BLOB_INFO_KEY: Hash256 = Hash256::repeat_byte(6);
+pub const ABANDONED_FORKS_KEY: Hash256 = Hash256::repeat_byte(7);
+pub const PRUNING_CURSOR_KEY: Hash256 = Hash256::repeat_byte(8);
+pub const RESTORE_POINT_MIGRATION_KEY: Hash256 = Hash256::repeat_byte(9);
 
 /// State upper limit value used to indicate that a node is not storing historic states.
 pub const STATE_UPPER_LIMIT_NO_RETAIN: Slot = Slot::new(u64::MAX);
@@ -67,6 +70,89 @@ impl StoreItem for PruningCheckpoint {
     }
 }
 
+/// The blocks and states of abandoned forks which are being deleted in chunks.
+///
+/// Written once when pruning starts, and deleted once every block and state has been deleted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct AbandonedForks {
+    pub block_roots: Vec<Hash256>,
+    pub states: Vec<AbandonedState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct AbandonedState {
+    pub slot: Slot,
+    pub state_root: Hash256,
+}
+
+impl AbandonedForks {
+    /// The total number of blocks and states to delete.
+    pub fn len(&self) -> usize {
+        self.block_roots.len() + self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl StoreItem for AbandonedForks {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
+/// The number of entries of the `AbandonedForks` (blocks first, then states) already deleted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruningCursor(pub u64);
+
+impl StoreItem for PruningCursor {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(PruningCursor(u64::from_ssz_bytes(bytes)?))
+    }
+}
+
+/// The progress of an offline change of the freezer's `slots_per_restore_point`.
+///
+/// Written when the change starts, and deleted atomically with the update of the on-disk config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct RestorePointMigration {
+    pub old_slots_per_restore_point: u64,
+    pub new_slots_per_restore_point: u64,
+    /// The slot of the next restore point to store at the new spacing.
+    pub next_slot: Slot,
+}
+
+impl StoreItem for RestorePointMigration {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 /// The last time the database was compacted.
 pub struct CompactionTimestamp(pub u64);
 
@@ -43,6 +43,19 @@ lazy_static! {
         "store_disk_db_delete_count_total",
         "Total number of deletions from the hot on-disk DB"
     );
+    pub static ref DISK_DB_COMPACTION_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_disk_db_compaction_total",
+        "Total number of compactions of the hot on-disk DB"
+    );
+    pub static ref DISK_DB_COMPACTION_TIMES: Result<Histogram> = try_create_histogram_with_buckets(
+        "store_disk_db_compaction_seconds",
+        "Time taken to compact the hot on-disk DB",
+        Ok(vec![1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0])
+    );
+    pub static ref DISK_DB_COMPACTION_RECLAIMED_BYTES: Result<IntCounter> = try_create_int_counter(
+        "store_disk_db_compaction_reclaimed_bytes_total",
+        "Estimated number of bytes reclaimed by compacting the hot on-disk DB"
+    );
     /*
      * Beacon State
      */
@@ -101,6 +114,14 @@ lazy_static! {
         "store_beacon_block_cache_hit_total",
         "Number of hits to the store's block cache"
     );
+    pub static ref BEACON_BLOCK_INDEX_MISS_COUNT: Result<IntCounter> = try_create_int_counter(
+        "store_beacon_block_index_miss_total",
+        "Number of block requests answered as missing by the block root index without a DB read"
+    );
+    pub static ref BLOCK_ROOT_INDEX_SIZE: Result<IntGauge> = try_create_int_gauge(
+        "store_block_root_index_size_bytes",
+        "Approximate memory used by the in-memory block root index (bytes)"
+    );
     pub static ref BEACON_BLOBS_CACHE_HIT_COUNT: Result<IntCounter> = try_create_int_counter(
         "store_beacon_blobs_cache_hit_total",
         "Number of hits to the store's blob cache"
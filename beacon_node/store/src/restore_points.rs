@@ -0,0 +1,282 @@
+//! Offline change of the spacing of restore points in the freezer database.
+//!
+//! Restore points at the new spacing are regenerated from the existing restore points and blocks,
+//! one at a time in ascending slot order, recording progress so that an interrupted change can be
+//! resumed. Once all of them are stored the restore point index is rewritten and the on-disk
+//! config and anchor info are updated atomically.
+use crate::hot_cold_store::{HotColdDB, HotColdDBError, Split};
+use crate::metadata::{RestorePointMigration, CONFIG_KEY, RESTORE_POINT_MIGRATION_KEY};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, StoreItem};
+use slog::{info, warn};
+use std::sync::Arc;
+use types::{EthSpec, Hash256, Slot};
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Return the progress of an unfinished change of `slots_per_restore_point`, if any.
+    ///
+    /// The database can't be used by the beacon node until the change is complete.
+    pub fn load_restore_point_migration(&self) -> Result<Option<RestorePointMigration>, Error> {
+        self.hot_db.get(&RESTORE_POINT_MIGRATION_KEY)
+    }
+
+    /// Rewrite the restore points of the freezer database to `new_slots_per_restore_point`,
+    /// resuming an interrupted migration if there is one.
+    ///
+    /// Every `validation_interval`th regenerated state has its root computed and checked against
+    /// the stored state root.
+    ///
+    /// The database must be opened with the `slots_per_restore_point` currently on disk, and must
+    /// be re-opened with the new value once this function returns.
+    pub fn migrate_restore_points(
+        self: &Arc<Self>,
+        new_slots_per_restore_point: u64,
+        validation_interval: u64,
+    ) -> Result<(), Error> {
+        Self::verify_slots_per_restore_point(new_slots_per_restore_point)?;
+        let old_slots_per_restore_point = self.config.slots_per_restore_point;
+
+        let mut migration = match self.load_restore_point_migration()? {
+            Some(migration) => {
+                if migration.old_slots_per_restore_point != old_slots_per_restore_point
+                    || migration.new_slots_per_restore_point != new_slots_per_restore_point
+                {
+                    return Err(HotColdDBError::RestorePointMigrationMismatch {
+                        old_slots_per_restore_point: migration.old_slots_per_restore_point,
+                        new_slots_per_restore_point: migration.new_slots_per_restore_point,
+                    }
+                    .into());
+                }
+                info!(
+                    self.log,
+                    "Resuming restore point migration";
+                    "next_slot" => migration.next_slot,
+                );
+                migration
+            }
+            None if new_slots_per_restore_point == old_slots_per_restore_point => return Ok(()),
+            None => RestorePointMigration {
+                old_slots_per_restore_point,
+                new_slots_per_restore_point,
+                next_slot: Slot::new(0),
+            },
+        };
+
+        let split = self.get_split_info();
+        let (old_lower_limit, old_upper_limit) = self.get_historic_state_limits();
+        let (new_lower_limit, new_upper_limit) = new_historic_state_limits(
+            old_lower_limit,
+            old_upper_limit,
+            split.slot,
+            new_slots_per_restore_point,
+        );
+        if new_lower_limit < old_lower_limit || new_upper_limit > old_upper_limit {
+            warn!(
+                self.log,
+                "Some historic states will become unavailable";
+                "info" => "the state limits must be aligned to the new restore point spacing",
+                "old_limits" => ?(old_lower_limit, old_upper_limit),
+                "new_limits" => ?(new_lower_limit, new_upper_limit),
+            );
+        }
+
+        info!(
+            self.log,
+            "Migrating restore points";
+            "old_slots_per_restore_point" => old_slots_per_restore_point,
+            "new_slots_per_restore_point" => new_slots_per_restore_point,
+            "split_slot" => split.slot,
+        );
+
+        // 1. Store the partial states of the new restore points which aren't already restore
+        // points, regenerating them from the existing restore points.
+        let validation_interval = validation_interval.max(1);
+        let mut regenerated = 0;
+        let mut slot = migration.next_slot;
+        while slot < split.slot {
+            if slot % old_slots_per_restore_point != 0
+                && is_state_available(slot, new_lower_limit, new_upper_limit)
+            {
+                let state = self
+                    .load_cold_state_by_slot(slot)?
+                    .ok_or(HotColdDBError::MissingLowerLimitState(slot))?;
+                let state_root = self.cold_state_root_at_slot(slot, &split)?;
+
+                if regenerated % validation_interval == 0 {
+                    let computed = state.canonical_root();
+                    if computed != state_root {
+                        return Err(Error::StateReconstructionRootMismatch {
+                            slot,
+                            expected: state_root,
+                            computed,
+                        });
+                    }
+                }
+                regenerated += 1;
+
+                let mut cold_ops = vec![];
+                self.store_restore_point_state(&state_root, &state, &mut cold_ops)?;
+                self.cold_db.do_atomically(cold_ops)?;
+
+                info!(
+                    self.log,
+                    "Restore point stored";
+                    "slot" => slot,
+                    "remaining" => (split.slot - 1 - slot) / new_slots_per_restore_point,
+                );
+            }
+
+            slot += new_slots_per_restore_point;
+            migration.next_slot = slot;
+            self.hot_db.put(&RESTORE_POINT_MIGRATION_KEY, &migration)?;
+        }
+
+        // 2. Rewrite the restore point index for the new spacing, and delete the partial states of
+        // old restore points which are no longer needed. Roots are read from the state roots
+        // table rather than the old index, so that this step can be repeated if interrupted.
+        let mut cold_ops = vec![];
+        let end_index = split.slot.as_u64().saturating_sub(1)
+            / std::cmp::min(old_slots_per_restore_point, new_slots_per_restore_point);
+        for index in 0..=end_index {
+            let new_slot = Slot::new(index * new_slots_per_restore_point);
+            if new_slot < split.slot
+                && is_state_available(new_slot, new_lower_limit, new_upper_limit)
+            {
+                let state_root = self.cold_state_root_at_slot(new_slot, &split)?;
+                self.store_restore_point_hash(index, state_root, &mut cold_ops);
+            } else {
+                cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconRestorePoint.as_str(),
+                    Self::restore_point_key(index).as_bytes(),
+                )));
+            }
+
+            let old_slot = Slot::new(index * old_slots_per_restore_point);
+            if old_slot < split.slot
+                && old_slot % new_slots_per_restore_point != 0
+                && is_state_available(old_slot, old_lower_limit, old_upper_limit)
+            {
+                let state_root = self.cold_state_root_at_slot(old_slot, &split)?;
+                cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconState.as_str(),
+                    state_root.as_bytes(),
+                )));
+            }
+        }
+        self.cold_db.do_atomically(cold_ops)?;
+        self.cold_db.sync()?;
+
+        // 3. Update the config and anchor, and mark the migration as complete.
+        let mut new_config = self.config.clone();
+        new_config.slots_per_restore_point = new_slots_per_restore_point;
+        let mut hot_ops = vec![new_config.as_disk_config().as_kv_store_op(CONFIG_KEY)];
+        if let Some(anchor) = self
+            .get_anchor_info()
+            .filter(|_| old_lower_limit < old_upper_limit)
+        {
+            let mut new_anchor = anchor.clone();
+            new_anchor.state_lower_limit = new_lower_limit;
+            new_anchor.state_upper_limit = if anchor.state_upper_limit < split.slot {
+                new_upper_limit
+            } else {
+                // The upper limit is a future restore point, or states aren't being retained.
+                align_up(anchor.state_upper_limit, new_slots_per_restore_point)
+            };
+            hot_ops.push(self.compare_and_set_anchor_info(Some(anchor), Some(new_anchor))?);
+        }
+        hot_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+            DBColumn::BeaconMeta.as_str(),
+            RESTORE_POINT_MIGRATION_KEY.as_bytes(),
+        )));
+        self.hot_db.do_atomically(hot_ops)?;
+
+        info!(
+            self.log,
+            "Restore point migration complete";
+            "slots_per_restore_point" => new_slots_per_restore_point,
+            "regenerated" => regenerated,
+        );
+
+        Ok(())
+    }
+
+    /// Load the root of the frozen state at `slot` from the state roots table.
+    fn cold_state_root_at_slot(&self, slot: Slot, split: &Split) -> Result<Hash256, Error> {
+        let mut iter = self.forwards_state_roots_iterator_until(
+            slot,
+            slot,
+            || {
+                let split_state = self.get_state(&split.state_root, Some(split.slot))?.ok_or(
+                    HotColdDBError::MissingSplitState(split.state_root, split.slot),
+                )?;
+                Ok((split_state, split.state_root))
+            },
+            &self.spec,
+        )?;
+        match iter.next().transpose()? {
+            Some((state_root, root_slot)) if root_slot == slot => Ok(state_root),
+            _ => Err(Error::NoContinuationData),
+        }
+    }
+}
+
+/// Return `true` if the state at `slot` is retained in the freezer with the given state limits.
+fn is_state_available(slot: Slot, lower_limit: Slot, upper_limit: Slot) -> bool {
+    slot <= lower_limit || slot >= upper_limit
+}
+
+/// Shrink the range of unavailable states so that both limits lie on restore points.
+fn new_historic_state_limits(
+    lower_limit: Slot,
+    upper_limit: Slot,
+    split_slot: Slot,
+    slots_per_restore_point: u64,
+) -> (Slot, Slot) {
+    if lower_limit >= upper_limit {
+        // All states are available.
+        return (split_slot, Slot::new(0));
+    }
+    let new_lower_limit = lower_limit / slots_per_restore_point * slots_per_restore_point;
+    (
+        new_lower_limit,
+        align_up(upper_limit, slots_per_restore_point),
+    )
+}
+
+/// Round `slot` up to a multiple of `n`, saturating at the maximum slot.
+fn align_up(slot: Slot, n: u64) -> Slot {
+    let slot = slot.as_u64();
+    if slot % n == 0 {
+        Slot::new(slot)
+    } else {
+        Slot::new((slot / n).saturating_add(1).saturating_mul(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_limits_are_aligned() {
+        let (lower, upper) =
+            new_historic_state_limits(Slot::new(96), Slot::new(160), Slot::new(512), 64);
+        assert_eq!((lower, upper), (Slot::new(64), Slot::new(192)));
+
+        // All states available.
+        let (lower, upper) =
+            new_historic_state_limits(Slot::new(512), Slot::new(0), Slot::new(512), 64);
+        assert!(lower >= upper);
+    }
+
+    #[test]
+    fn align_up_saturates() {
+        assert_eq!(align_up(Slot::new(64), 64), Slot::new(64));
+        assert_eq!(align_up(Slot::new(65), 64), Slot::new(128));
+        assert_eq!(align_up(Slot::new(u64::MAX), 64), Slot::new(u64::MAX));
+    }
+}
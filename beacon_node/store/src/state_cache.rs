@@ -0,0 +1,117 @@
+//! The LRU cache of replayed states held by the `HotColdDB`.
+use lru::LruCache;
+use ssz::Encode;
+use types::{BeaconState, EthSpec, Hash256, Slot};
+
+/// Key for a state held in the `StateCache`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StateCacheKey {
+    /// The frozen state at a slot, reconstructed from the freezer database.
+    Historic(Slot),
+    /// The state with root `base_state_root`, advanced through skipped slots to `slot`.
+    SkipSlot {
+        base_state_root: Hash256,
+        slot: Slot,
+    },
+}
+
+/// LRU cache of replayed states, holding both frozen states and skip-slot states.
+///
+/// Each state is held alongside its root, if it was known when the state was cached. This allows
+/// states to be cached without their tree hash cache, which is several times larger than the state.
+///
+/// Lookups via `Self::get` and `Self::get_with_root` are counted, so that the effectiveness of the
+/// configured capacity can be inspected at runtime.
+#[derive(Debug)]
+pub struct StateCache<E: EthSpec> {
+    cache: LruCache<StateCacheKey, (Option<Hash256>, BeaconState<E>)>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<E: EthSpec> StateCache<E> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            cache: LruCache::new(capacity),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub(crate) fn get(&mut self, key: &StateCacheKey) -> Option<&BeaconState<E>> {
+        self.get_entry(key).map(|(_, state)| state)
+    }
+
+    /// Returns the state cached under `key` along with its root, if the root was cached with it.
+    pub(crate) fn get_with_root(
+        &mut self,
+        key: &StateCacheKey,
+    ) -> Option<(Hash256, &BeaconState<E>)> {
+        match self.get_entry(key)? {
+            (Some(state_root), state) => Some((*state_root, state)),
+            (None, _) => None,
+        }
+    }
+
+    fn get_entry(&mut self, key: &StateCacheKey) -> Option<&(Option<Hash256>, BeaconState<E>)> {
+        let entry = self.cache.get(key);
+        if entry.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        entry
+    }
+
+    pub(crate) fn put(&mut self, key: StateCacheKey, state: BeaconState<E>) {
+        self.cache.put(key, (None, state));
+    }
+
+    pub(crate) fn put_with_root(
+        &mut self,
+        key: StateCacheKey,
+        state_root: Hash256,
+        state: BeaconState<E>,
+    ) {
+        self.cache.put(key, (Some(state_root), state));
+    }
+
+    /// Iterates the cached states without updating their recency or the lookup counters.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&StateCacheKey, &BeaconState<E>)> {
+        self.cache.iter().map(|(key, (_, state))| (key, state))
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.cache.cap()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Returns the total SSZ size of the cached states, which approximates their memory usage.
+    pub fn byte_estimate(&self) -> usize {
+        self.cache
+            .iter()
+            .map(|(_, (_, state))| state.ssz_bytes_len())
+            .sum()
+    }
+
+    /// Sets the capacity of the cache, evicting the least recently used states if it is shrunk
+    /// below the number of states held.
+    pub fn resize(&mut self, capacity: usize) {
+        self.cache.resize(capacity);
+    }
+}
@@ -97,15 +97,17 @@ impl<T: EthSpec> BootNodeConfig<T> {
             let enr_fork = {
                 let spec = eth2_network_config.chain_spec::<T>()?;
 
-                let genesis_state_url: Option<String> =
-                    clap_utils::parse_optional(matches, "genesis-state-url")?;
+                let genesis_state_urls = matches
+                    .values_of("genesis-state-url")
+                    .map(|urls| urls.map(String::from).collect::<Vec<_>>())
+                    .unwrap_or_default();
                 let genesis_state_url_timeout =
                     clap_utils::parse_required(matches, "genesis-state-url-timeout")
                         .map(Duration::from_secs)?;
 
                 if eth2_network_config.genesis_state_is_known() {
                     let genesis_state = eth2_network_config
-                        .genesis_state::<T>(genesis_state_url.as_deref(), genesis_state_url_timeout, &logger).await?
+                        .genesis_state::<T>(&genesis_state_urls, genesis_state_url_timeout, None, &logger).await?
                         .ok_or_else(|| {
                             "The genesis state for this network is not known, this is an unsupported mode"
                                 .to_string()
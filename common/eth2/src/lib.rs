@@ -13,9 +13,12 @@ pub mod lighthouse;
 pub mod lighthouse_vc;
 pub mod mixin;
 pub mod types;
+pub mod unix_socket;
 
 use self::mixin::{RequestAccept, ResponseOptional};
 use self::types::{Error as ResponseError, *};
+use self::unix_socket::{placeholder_base_url, UnixSocketTransport};
+use account_utils::ZeroizeString;
 use futures::Stream;
 use futures_util::StreamExt;
 use lighthouse_network::PeerId;
@@ -28,11 +31,14 @@ use reqwest::{
 pub use reqwest::{StatusCode, Url};
 pub use sensitive_url::{SensitiveError, SensitiveUrl};
 use serde::{de::DeserializeOwned, Serialize};
+use snap::write::FrameEncoder;
 use ssz::Encode;
 use std::convert::TryFrom;
 use std::fmt;
+use std::io::Write;
 use std::iter::Iterator;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use store::fork_versioned_response::ExecutionOptimisticFinalizedForkVersionedResponse;
 
@@ -40,6 +46,10 @@ pub const V1: EndpointVersion = EndpointVersion(1);
 pub const V2: EndpointVersion = EndpointVersion(2);
 
 pub const CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
+/// Lists the `Content-Encoding`s accepted by the server for SSZ request bodies.
+pub const ACCEPTED_ENCODINGS_HEADER: &str = "X-Accepted-Encodings";
+/// The `Content-Encoding` of framed snappy compression.
+pub const SNAPPY_ENCODING: &str = "snappy";
 
 #[derive(Debug)]
 pub enum Error {
@@ -71,6 +81,8 @@ pub enum Error {
     NoServerPubkey,
     /// The client has been configured without an API token, but requires one for this request.
     NoToken,
+    /// A request sent over a Unix domain socket failed.
+    UnixSocket(String),
 }
 
 impl From<reqwest::Error> for Error {
@@ -96,6 +108,7 @@ impl Error {
             Error::InvalidSsz(_) => None,
             Error::TokenReadError(..) => None,
             Error::NoServerPubkey | Error::NoToken => None,
+            Error::UnixSocket(_) => None,
         }
     }
 }
@@ -143,11 +156,21 @@ impl Timeouts {
 
 /// A wrapper around `reqwest::Client` which provides convenience methods for interfacing with a
 /// Lighthouse Beacon Node HTTP server (`http_api`).
+///
+/// Servers with a `unix://` URL are reached over the Unix domain socket at the URL's path.
 #[derive(Clone)]
 pub struct BeaconNodeHttpClient {
     client: reqwest::Client,
+    /// The URL of the server. For a Unix domain socket, `server.full` is a placeholder against
+    /// which request URLs are built.
     server: SensitiveUrl,
+    unix_socket: Option<UnixSocketTransport>,
     timeouts: Timeouts,
+    admin_token: Option<ZeroizeString>,
+    /// Whether to compress SSZ blocks when the server accepts snappy.
+    compress_ssz_blocks: bool,
+    /// Whether the server accepts snappy-compressed bodies, once known.
+    server_accepts_snappy: Arc<Mutex<Option<bool>>>,
 }
 
 impl fmt::Display for BeaconNodeHttpClient {
@@ -164,25 +187,46 @@ impl AsRef<str> for BeaconNodeHttpClient {
 
 impl BeaconNodeHttpClient {
     pub fn new(server: SensitiveUrl, timeouts: Timeouts) -> Self {
-        Self {
-            client: reqwest::Client::new(),
-            server,
-            timeouts,
-        }
+        Self::from_components(server, reqwest::Client::new(), timeouts)
     }
 
     pub fn from_components(
-        server: SensitiveUrl,
+        mut server: SensitiveUrl,
         client: reqwest::Client,
         timeouts: Timeouts,
     ) -> Self {
+        let unix_socket = UnixSocketTransport::from_url(&server.full);
+        if unix_socket.is_some() {
+            server.full = placeholder_base_url();
+        }
+
         Self {
             client,
             server,
+            unix_socket,
             timeouts,
+            admin_token: None,
+            compress_ssz_blocks: false,
+            server_accepts_snappy: <_>::default(),
         }
     }
 
+    /// Sets the token sent with requests to the `lighthouse/admin` endpoints.
+    pub fn set_admin_token(&mut self, token: ZeroizeString) {
+        self.admin_token = Some(token);
+    }
+
+    /// Enables snappy compression of SSZ blocks published with `post_beacon_blocks_v2_ssz`, if the
+    /// server supports it.
+    pub fn set_compress_ssz_blocks(&mut self, enabled: bool) {
+        self.compress_ssz_blocks = enabled;
+    }
+
+    /// Returns `true` if SSZ blocks are compressed with snappy when the server supports it.
+    pub fn compress_ssz_blocks(&self) -> bool {
+        self.compress_ssz_blocks
+    }
+
     /// Return the path with the standard `/eth/vX` prefix applied.
     fn eth_path(&self, version: EndpointVersion) -> Result<Url, Error> {
         let mut path = self.server.full.clone();
@@ -195,6 +239,14 @@ impl BeaconNodeHttpClient {
         Ok(path)
     }
 
+    /// Send the request built by `builder`, over the Unix domain socket if one is configured.
+    async fn send(&self, builder: RequestBuilder) -> Result<Response, Error> {
+        match &self.unix_socket {
+            Some(unix_socket) => unix_socket.execute(builder.build()?).await,
+            None => Ok(builder.send().await?),
+        }
+    }
+
     /// Perform a HTTP GET request.
     async fn get<T: DeserializeOwned, U: IntoUrl>(&self, url: U) -> Result<T, Error> {
         let response = self.get_response(url, |b| b).await?;
@@ -207,7 +259,7 @@ impl BeaconNodeHttpClient {
         url: U,
         builder: impl FnOnce(RequestBuilder) -> RequestBuilder,
     ) -> Result<Response, Error> {
-        let response = builder(self.client.get(url)).send().await?;
+        let response = self.send(builder(self.client.get(url))).await?;
         ok_or_error(response).await
     }
 
@@ -324,7 +376,7 @@ impl BeaconNodeHttpClient {
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
-        let response = builder.json(body).send().await?;
+        let response = self.send(builder.json(body)).await?;
         ok_or_error(response).await
     }
 
@@ -339,10 +391,12 @@ impl BeaconNodeHttpClient {
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
-        let response = builder
-            .header("Content-Type", "application/octet-stream")
-            .body(body)
-            .send()
+        let response = self
+            .send(
+                builder
+                    .header("Content-Type", "application/octet-stream")
+                    .body(body),
+            )
             .await?;
         ok_or_error(response).await
     }
@@ -359,10 +413,12 @@ impl BeaconNodeHttpClient {
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
-        let response = builder
-            .header(CONSENSUS_VERSION_HEADER, fork.to_string())
-            .json(body)
-            .send()
+        let response = self
+            .send(
+                builder
+                    .header(CONSENSUS_VERSION_HEADER, fork.to_string())
+                    .json(body),
+            )
             .await?;
         ok_or_error(response).await
     }
@@ -374,6 +430,7 @@ impl BeaconNodeHttpClient {
         body: T,
         timeout: Option<Duration>,
         fork: ForkName,
+        content_encoding: Option<&'static str>,
     ) -> Result<Response, Error> {
         let mut builder = self.client.post(url);
         if let Some(timeout) = timeout {
@@ -388,7 +445,13 @@ impl BeaconNodeHttpClient {
             "Content-Type",
             HeaderValue::from_static("application/octet-stream"),
         );
-        let response = builder.headers(headers).body(body).send().await?;
+        if let Some(content_encoding) = content_encoding {
+            headers.insert(
+                "Content-Encoding",
+                HeaderValue::from_static(content_encoding),
+            );
+        }
+        let response = self.send(builder.headers(headers).body(body)).await?;
         ok_or_error(response).await
     }
 
@@ -822,22 +885,90 @@ impl BeaconNodeHttpClient {
     }
 
     /// `POST v2/beacon/blocks`
+    ///
+    /// The block is compressed with snappy if enabled with `set_compress_ssz_blocks` and the
+    /// server advertises that it accepts it.
     pub async fn post_beacon_blocks_v2_ssz<T: EthSpec, Payload: AbstractExecPayload<T>>(
         &self,
         block_contents: &SignedBlockContents<T, Payload>,
         validation_level: Option<BroadcastValidation>,
     ) -> Result<(), Error> {
+        let ssz_bytes = block_contents.as_ssz_bytes();
+        let compressed = if self.compress_ssz_blocks && self.server_accepts_snappy().await {
+            // Compressing into memory can't fail, but fall back to the uncompressed body anyway.
+            snappy_compress(&ssz_bytes).ok()
+        } else {
+            None
+        };
+        let (body, content_encoding) = match compressed {
+            Some(compressed) => (compressed, Some(SNAPPY_ENCODING)),
+            None => (ssz_bytes, None),
+        };
+
         self.post_generic_with_consensus_version_and_ssz_body(
             self.post_beacon_blocks_v2_path(validation_level)?,
-            block_contents.as_ssz_bytes(),
+            body,
             Some(self.timeouts.proposal),
             block_contents.signed_block().message().body().fork_name(),
+            content_encoding,
         )
         .await?;
 
         Ok(())
     }
 
+    /// Returns `true` if the server advertises support for snappy-compressed SSZ bodies.
+    ///
+    /// The result is cached after the first successful request. Servers which can't be reached
+    /// are assumed not to support compression.
+    pub async fn server_accepts_snappy(&self) -> bool {
+        if let Some(accepts_snappy) = *self
+            .server_accepts_snappy
+            .lock()
+            .expect("lock is not poisoned")
+        {
+            return accepts_snappy;
+        }
+
+        let Ok(encodings) = self.get_accepted_encodings().await else {
+            return false;
+        };
+        let accepts_snappy = encodings
+            .iter()
+            .any(|encoding| encoding.eq_ignore_ascii_case(SNAPPY_ENCODING));
+        *self
+            .server_accepts_snappy
+            .lock()
+            .expect("lock is not poisoned") = Some(accepts_snappy);
+        accepts_snappy
+    }
+
+    /// `GET node/version`, returning the `Content-Encoding`s which the server accepts for SSZ
+    /// request bodies.
+    pub async fn get_accepted_encodings(&self) -> Result<Vec<String>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("node")
+            .push("version");
+
+        let response = self.get_response(path, |b| b).await?;
+        Ok(response
+            .headers()
+            .get(ACCEPTED_ENCODINGS_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(|encodings| {
+                encodings
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|encoding| !encoding.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     /// `POST v2/beacon/blinded_blocks`
     pub async fn post_beacon_blinded_blocks_v2<T: EthSpec, Payload: AbstractExecPayload<T>>(
         &self,
@@ -866,6 +997,7 @@ impl BeaconNodeHttpClient {
             block_contents.as_ssz_bytes(),
             Some(self.timeouts.proposal),
             block_contents.signed_block().message().body().fork_name(),
+            None,
         )
         .await?;
 
@@ -1128,6 +1260,13 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET beacon/pool/attester_slashings` as SSZ
+    pub async fn get_beacon_pool_attester_slashings_ssz<T: EthSpec>(
+        &self,
+    ) -> Result<Vec<AttesterSlashing<T>>, Error> {
+        self.get_beacon_pool_ssz("attester_slashings").await
+    }
+
     /// `POST beacon/pool/proposer_slashings`
     pub async fn post_beacon_pool_proposer_slashings(
         &self,
@@ -1161,6 +1300,13 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET beacon/pool/proposer_slashings` as SSZ
+    pub async fn get_beacon_pool_proposer_slashings_ssz(
+        &self,
+    ) -> Result<Vec<ProposerSlashing>, Error> {
+        self.get_beacon_pool_ssz("proposer_slashings").await
+    }
+
     /// `POST beacon/pool/voluntary_exits`
     pub async fn post_beacon_pool_voluntary_exits(
         &self,
@@ -1194,6 +1340,13 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET beacon/pool/voluntary_exits` as SSZ
+    pub async fn get_beacon_pool_voluntary_exits_ssz(
+        &self,
+    ) -> Result<Vec<SignedVoluntaryExit>, Error> {
+        self.get_beacon_pool_ssz("voluntary_exits").await
+    }
+
     /// `POST beacon/pool/sync_committees`
     pub async fn post_beacon_pool_sync_committee_signatures(
         &self,
@@ -1230,6 +1383,50 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// `GET beacon/pool/bls_to_execution_changes`
+    pub async fn get_beacon_pool_bls_to_execution_changes(
+        &self,
+    ) -> Result<GenericResponse<Vec<SignedBlsToExecutionChange>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("pool")
+            .push("bls_to_execution_changes");
+
+        self.get(path).await
+    }
+
+    /// `GET beacon/pool/bls_to_execution_changes` as SSZ
+    pub async fn get_beacon_pool_bls_to_execution_changes_ssz(
+        &self,
+    ) -> Result<Vec<SignedBlsToExecutionChange>, Error> {
+        self.get_beacon_pool_ssz("bls_to_execution_changes").await
+    }
+
+    /// Path for `v1/beacon/pool/{operation}`
+    pub fn get_beacon_pool_path(&self, operation: &str) -> Result<Url, Error> {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("pool")
+            .push(operation);
+        Ok(path)
+    }
+
+    /// `GET beacon/pool/{operation}` as SSZ
+    async fn get_beacon_pool_ssz<O: ssz::Decode>(&self, operation: &str) -> Result<Vec<O>, Error> {
+        let path = self.get_beacon_pool_path(operation)?;
+        let bytes = self
+            .get_response(path, |b| b.accept(Accept::Ssz))
+            .await?
+            .bytes()
+            .await?;
+        ssz::Decode::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz)
+    }
+
     /// `GET beacon/deposit_snapshot`
     pub async fn get_deposit_snapshot(&self) -> Result<Option<types::DepositTreeSnapshot>, Error> {
         let mut path = self.eth_path(V1)?;
@@ -1242,36 +1439,43 @@ impl BeaconNodeHttpClient {
             .map(|opt| opt.map(|r| r.data))
     }
 
-    /// `POST beacon/rewards/sync_committee`
+    /// `POST beacon/rewards/sync_committee/{block_id}`
+    ///
+    /// Returns the rewards of the whole sync committee if `validators` is empty.
     pub async fn post_beacon_rewards_sync_committee(
         &self,
-        rewards: &[Option<Vec<lighthouse::SyncCommitteeReward>>],
-    ) -> Result<(), Error> {
+        block_id: BlockId,
+        validators: &[ValidatorId],
+    ) -> Result<ExecutionOptimisticFinalizedResponse<Vec<SyncCommitteeReward>>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("sync_committee");
-
-        self.post(path, &rewards).await?;
+            .push("sync_committee")
+            .push(&block_id.to_string());
 
-        Ok(())
+        self.post_generic(path, &validators, None)
+            .await?
+            .json()
+            .await
+            .map_err(Into::into)
     }
 
-    /// `GET beacon/rewards/blocks`
-    pub async fn get_beacon_rewards_blocks(&self, epoch: Epoch) -> Result<(), Error> {
+    /// `GET beacon/rewards/blocks/{block_id}`
+    pub async fn get_beacon_rewards_blocks(
+        &self,
+        block_id: BlockId,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<StandardBlockReward>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("blocks");
-
-        path.query_pairs_mut()
-            .append_pair("epoch", &epoch.to_string());
+            .push("blocks")
+            .push(&block_id.to_string());
 
         self.get(path).await
     }
@@ -1452,7 +1656,7 @@ impl BeaconNodeHttpClient {
             .push("node")
             .push("health");
 
-        let status = self.client.get(path).send().await?.status();
+        let status = self.send(self.client.get(path)).await?.status();
         if status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT {
             Ok(status)
         } else {
@@ -2035,9 +2239,7 @@ impl BeaconNodeHttpClient {
         path.query_pairs_mut().append_pair("topics", &topic_string);
 
         Ok(self
-            .client
-            .get(path)
-            .send()
+            .send(self.client.get(path))
             .await?
             .bytes_stream()
             .map(|next| match next {
@@ -2070,6 +2272,15 @@ impl BeaconNodeHttpClient {
     }
 }
 
+/// Compresses `bytes` with framed snappy.
+fn snappy_compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = FrameEncoder::new(vec![]);
+    encoder.write_all(bytes)?;
+    encoder
+        .into_inner()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+}
+
 /// Returns `Ok(response)` if the response is a `200 OK` response. Otherwise, creates an
 /// appropriate error message.
 pub async fn ok_or_error(response: Response) -> Result<Response, Error> {
@@ -2,37 +2,78 @@
 
 mod attestation_performance;
 pub mod attestation_rewards;
+mod attester_duties;
+mod beacon_processor;
+mod blob_propagation;
 mod block_packing_efficiency;
+mod block_production_dry_run;
 mod block_rewards;
-mod standard_block_rewards;
-mod sync_committee_rewards;
+mod builder_circuit_breaker;
+mod caches;
+mod epoch_timings;
+mod graffiti;
+mod logging;
+mod proposer_preparation;
+mod proposer_reorg;
+mod sync_committee_participation;
+mod validator_metrics;
 
 use crate::{
     ok_or_error,
     types::{
-        BeaconState, ChainSpec, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock,
-        GenericResponse, ValidatorId,
+        BeaconState, BlockExecutionStatus, ChainSpec, DepositTreeSnapshot, DutiesResponse, Epoch,
+        EthSpec, ExecutionBlockHash, ExecutionOptimisticFinalizedResponse, FinalizedExecutionBlock,
+        ForkName, GenericResponse, ValidatorId, ValidatorIndexDataRef,
     },
-    BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot, StateId, StatusCode,
+    BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot, StateId, StatusCode, Url,
+    V1,
 };
 use proto_array::core::ProtoArray;
-use reqwest::IntoUrl;
-use serde::{Deserialize, Serialize};
+use reqwest::{IntoUrl, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
+use types::historical_summary::HistoricalSummary;
 
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
 pub use attestation_rewards::StandardAttestationRewards;
+pub use attester_duties::{
+    AttesterCommittee, AttesterCommitteeQuery, AttesterDataWithCommittee, AttesterDutiesQuery,
+};
+pub use beacon_processor::{
+    BeaconProcessorSnapshot, EarlyBlockSnapshot, QueueSnapshot, ReprocessQueueCategorySnapshot,
+    ReprocessQueueSnapshot,
+};
+pub use blob_propagation::{BlobPropagation, BlobPropagationStatus};
 pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
+pub use block_production_dry_run::{
+    BlockProductionDryRun, BlockProductionDryRunQuery, BlockProductionTimings,
+};
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
-pub use standard_block_rewards::StandardBlockReward;
-pub use sync_committee_rewards::SyncCommitteeReward;
+pub use builder_circuit_breaker::{BuilderCircuitBreakerCondition, BuilderCircuitBreakerStatus};
+pub use caches::{CacheInfo, CacheName, CachesPatchRequest};
+pub use epoch_timings::{BlockArrivalDelays, EpochTimings, EpochTimingsQuery};
+pub use graffiti::{BeaconNodeGraffiti, GraffitiPostRequest};
+pub use lighthouse_network::discovery::{BucketInfo, DiscoveryInfo, RecentQueries};
+pub use lighthouse_network::{types::SyncState, PeerInfo, UPnPStatus};
+pub use logging::{LogDrain, LoggingFilters, LoggingPatchRequest};
+pub use proposer_preparation::ProposerPreparationEntry;
+pub use proposer_reorg::ProposerReOrgDecision;
+// Re-exported from their previous location, now that they are standard types.
+pub use crate::types::{StandardBlockReward, SyncCommitteeReward};
+pub use sync_committee_participation::{
+    SyncCommitteeEpochParticipation, SyncCommitteeParticipation,
+    SyncCommitteeValidatorParticipation,
+};
+pub use validator_metrics::{
+    ValidatorMetrics, ValidatorMetricsRequestData, ValidatorMetricsResponse,
+};
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
 // selector.
@@ -106,6 +147,10 @@ pub struct Health {
     pub system: SystemHealth,
     #[serde(flatten)]
     pub process: ProcessHealth,
+    /// Fraction of observed head votes in the latest evaluated epoch which were for the canonical
+    /// block. Only reported by the beacon node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_vote_accuracy: Option<f64>,
 }
 
 /// System related health.
@@ -295,6 +340,7 @@ impl Health {
         Ok(Self {
             process: ProcessHealth::observe()?,
             system: SystemHealth::observe()?,
+            head_vote_accuracy: None,
         })
     }
 }
@@ -367,10 +413,208 @@ pub struct DatabaseInfo {
     pub blob_info: BlobInfo,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseCompaction {
+    pub duration_ms: u64,
+    /// The estimated decrease in the size of the hot database on disk, in bytes.
+    pub reclaimed_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkTopicsQuery {
+    pub fork: ForkName,
+}
+
+/// The gossip topics that will be used at a fork, as returned by `lighthouse/network/fork_topics`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkTopics {
+    pub fork: ForkName,
+    /// The epoch at which the fork is scheduled, or `None` if it is not scheduled.
+    pub fork_epoch: Option<Epoch>,
+    #[serde(with = "serde_utils::bytes_4_hex")]
+    pub fork_digest: [u8; 4],
+    /// The slot at which the node will subscribe to the fork's topics, if scheduled.
+    pub subscription_slot: Option<Slot>,
+    /// The UNIX timestamp (in seconds) at which the node will subscribe to the fork's topics, if
+    /// scheduled.
+    pub subscription_time: Option<u64>,
+    pub topics: Vec<String>,
+}
+
+/// The status of the local network stack, as returned by `lighthouse/network`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkStatus {
+    pub peer_id: String,
+    pub listen_addresses: Vec<String>,
+    /// The state of the UPnP port mappings, or `None` if UPnP is disabled.
+    pub upnp: Option<UPnPStatus>,
+}
+
+/// The fields of the local ENR, as returned by `lighthouse/network/enr`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalEnr {
+    /// The base64 encoded ENR.
+    pub enr: String,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub seq: u64,
+    pub node_id: String,
+    pub peer_id: String,
+    pub ip: Option<Ipv4Addr>,
+    pub tcp_port: Option<u16>,
+    pub udp_port: Option<u16>,
+    pub quic_port: Option<u16>,
+    pub ip6: Option<Ipv6Addr>,
+    pub tcp6_port: Option<u16>,
+    pub udp6_port: Option<u16>,
+    pub quic6_port: Option<u16>,
+}
+
+/// The request body of `POST lighthouse/network/enr`.
+///
+/// Fields that are `None` are left unchanged. The IP address determines whether the IPv4 or IPv6
+/// fields of the ENR are updated.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnrUpdateRequest {
+    #[serde(default)]
+    pub ip: Option<IpAddr>,
+    #[serde(default)]
+    pub tcp_port: Option<u16>,
+    #[serde(default)]
+    pub udp_port: Option<u16>,
+    #[serde(default)]
+    pub quic_port: Option<u16>,
+}
+
+/// A gossip message rejected by the node, as returned by `lighthouse/network/gossip/rejections`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipRejection {
+    pub topic: String,
+    pub peer_id: String,
+    /// The SSZ bytes of the message, after snappy decompression.
+    #[serde(with = "serde_utils::hex_vec")]
+    pub data: Vec<u8>,
+    /// The verification error which caused the rejection.
+    pub reason: String,
+    /// The UNIX timestamp (in milliseconds) at which the message was received.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub seen_timestamp_ms: u64,
+}
+
+/// Request body for `POST lighthouse/admin/network/gossip/replay`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipReplayRequest {
+    /// The topic the message was received on, e.g. as reported in a `GossipRejection`.
+    pub topic: String,
+    /// The SSZ bytes of the message, after snappy decompression.
+    #[serde(with = "serde_utils::hex_vec")]
+    pub data: Vec<u8>,
+}
+
+/// The result of re-running gossip verification on a message, as returned by
+/// `lighthouse/admin/network/gossip/replay`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GossipReplayResponse {
+    pub accepted: bool,
+    /// The full verification error, if the message was not accepted.
+    pub error: Option<String>,
+}
+
+/// The maximum number of historical summaries returned by a single request to
+/// `lighthouse/beacon/states/{state_id}/historical_summaries`.
+pub const MAX_HISTORICAL_SUMMARIES_PER_REQUEST: u64 = 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoricalSummariesQuery {
+    /// The index of the first summary to return, defaults to `0`.
+    pub start_index: Option<u64>,
+    /// The maximum number of summaries to return, capped at `MAX_HISTORICAL_SUMMARIES_PER_REQUEST`.
+    pub limit: Option<u64>,
+}
+
+/// A page of the `historical_summaries` of a state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoricalSummaries {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub start_index: u64,
+    /// The length of the whole `historical_summaries` list in the state.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total: u64,
+    pub historical_summaries: Vec<HistoricalSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateProofQuery {
+    pub generalized_index: usize,
+}
+
+/// A merkle proof of a single leaf of a state, in the same bottom-up encoding as the light client
+/// branches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateProof {
+    pub generalized_index: usize,
+    pub leaf: Hash256,
+    pub branch: Vec<Hash256>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestationInclusionQuery {
+    pub epoch: Epoch,
+    /// Include a merkle proof of each attestation within its block.
+    #[serde(default)]
+    pub proof: bool,
+}
+
+/// An attestation by a validator that was included in a canonical block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationInclusion {
+    pub block_root: Hash256,
+    pub block_slot: Slot,
+    /// The index of the attestation within the attestations of the block body.
+    pub attestation_index: usize,
+    pub attestation_slot: Slot,
+    pub proof: Option<AttestationInclusionProof>,
+}
+
+/// A merkle proof of an attestation within a block, whose root is the block root.
+///
+/// The branch is in bottom-up order, as for `StateProof`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttestationInclusionProof {
+    pub generalized_index: usize,
+    pub leaf: Hash256,
+    pub branch: Vec<Hash256>,
+}
+
+/// A single run of fork choice, as returned by the `lighthouse/fork_choice` endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForkChoiceRecompute {
+    pub slot: Slot,
+    /// The UNIX timestamp (in milliseconds) at which the recompute started.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub started_at_ms: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub elapsed_ms: u64,
+    pub old_head_block_root: Hash256,
+    pub new_head_block_root: Hash256,
+    pub head_changed: bool,
+    /// True if the recompute was triggered via `POST lighthouse/fork_choice/recompute`.
+    pub manual: bool,
+}
+
+/// The execution status of a block, as returned by `GET lighthouse/blocks/{block_root}/execution_status`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockExecutionStatusData {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub status: BlockExecutionStatus,
+    /// The hash of the block's execution payload, or `None` if it is prior to the merge.
+    pub execution_block_hash: Option<ExecutionBlockHash>,
+}
+
 impl BeaconNodeHttpClient {
     /// Perform a HTTP GET request, returning `None` on a 404 error.
     async fn get_bytes_opt<U: IntoUrl>(&self, url: U) -> Result<Option<Vec<u8>>, Error> {
-        let response = self.client.get(url).send().await.map_err(Error::from)?;
+        let response = self.send(self.client.get(url)).await?;
         match ok_or_error(response).await {
             Ok(resp) => Ok(Some(
                 resp.bytes()
@@ -389,6 +633,58 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Return the path of the `lighthouse/admin` endpoint identified by `segments`.
+    fn admin_path(&self, segments: &[&str]) -> Result<Url, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("admin")
+            .extend(segments);
+
+        Ok(path)
+    }
+
+    /// Perform a HTTP POST request to an admin endpoint, returning a JSON response.
+    ///
+    /// The admin token is supplied in the `Authorization` header, if it has been set.
+    async fn post_admin_with_response<T: Serialize, R: DeserializeOwned>(
+        &self,
+        url: Url,
+        body: &T,
+    ) -> Result<R, Error> {
+        self.send_admin_with_response(self.client.post(url).json(body))
+            .await
+    }
+
+    /// Perform a HTTP PATCH request to an admin endpoint, returning a JSON response.
+    ///
+    /// The admin token is supplied in the `Authorization` header, if it has been set.
+    async fn patch_admin_with_response<T: Serialize, R: DeserializeOwned>(
+        &self,
+        url: Url,
+        body: &T,
+    ) -> Result<R, Error> {
+        self.send_admin_with_response(self.client.patch(url).json(body))
+            .await
+    }
+
+    async fn send_admin_with_response<R: DeserializeOwned>(
+        &self,
+        mut builder: RequestBuilder,
+    ) -> Result<R, Error> {
+        if let Some(token) = &self.admin_token {
+            builder = builder.bearer_auth(token.as_str());
+        }
+        let response = self.send(builder).await?;
+        ok_or_error(response)
+            .await?
+            .json()
+            .await
+            .map_err(Into::into)
+    }
+
     /// `GET lighthouse/health`
     pub async fn get_lighthouse_health(&self) -> Result<GenericResponse<Health>, Error> {
         let mut path = self.server.full.clone();
@@ -413,6 +709,130 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/network`
+    pub async fn get_lighthouse_network(&self) -> Result<GenericResponse<NetworkStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/fork_topics?fork`
+    pub async fn get_lighthouse_network_fork_topics(
+        &self,
+        fork: ForkName,
+    ) -> Result<GenericResponse<ForkTopics>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("fork_topics");
+
+        path.query_pairs_mut()
+            .append_pair("fork", &fork.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/enr`
+    pub async fn get_lighthouse_network_enr(&self) -> Result<GenericResponse<LocalEnr>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("enr");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/discovery`
+    pub async fn get_lighthouse_network_discovery(
+        &self,
+    ) -> Result<GenericResponse<DiscoveryInfo>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("discovery");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/gossip/rejections`
+    pub async fn get_lighthouse_network_gossip_rejections(
+        &self,
+    ) -> Result<GenericResponse<Vec<GossipRejection>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("gossip")
+            .push("rejections");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/admin/network/gossip/replay`
+    pub async fn post_lighthouse_network_gossip_replay(
+        &self,
+        request: &GossipReplayRequest,
+    ) -> Result<GenericResponse<GossipReplayResponse>, Error> {
+        let path = self.admin_path(&["network", "gossip", "replay"])?;
+
+        self.post_admin_with_response(path, request).await
+    }
+
+    /// `POST lighthouse/admin/network/enr`
+    pub async fn post_lighthouse_network_enr(
+        &self,
+        update: &EnrUpdateRequest,
+    ) -> Result<GenericResponse<LocalEnr>, Error> {
+        let path = self.admin_path(&["network", "enr"])?;
+
+        self.post_admin_with_response(path, update).await
+    }
+
+    /// `GET lighthouse/beacon_processor`
+    pub async fn get_lighthouse_beacon_processor(
+        &self,
+    ) -> Result<GenericResponse<BeaconProcessorSnapshot>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon_processor/reprocess_queue`
+    pub async fn get_lighthouse_beacon_processor_reprocess_queue(
+        &self,
+    ) -> Result<GenericResponse<ReprocessQueueSnapshot>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor")
+            .push("reprocess_queue");
+
+        self.get(path).await
+    }
+
     /*
      * Note:
      *
@@ -433,6 +853,105 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/blocks/{block_root}/execution_status`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_block_execution_status(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<GenericResponse<BlockExecutionStatusData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("blocks")
+            .push(&format!("{:?}", block_root))
+            .push("execution_status");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/beacon/blob_propagation/{block_root}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_beacon_blob_propagation(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<GenericResponse<BlobPropagation>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("blob_propagation")
+            .push(&format!("{:?}", block_root));
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/validator_monitor/sync_committee`
+    pub async fn get_lighthouse_validator_monitor_sync_committee(
+        &self,
+    ) -> Result<GenericResponse<SyncCommitteeParticipation>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor")
+            .push("sync_committee");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/admin/fork_choice/recompute`
+    pub async fn post_lighthouse_fork_choice_recompute(
+        &self,
+    ) -> Result<GenericResponse<ForkChoiceRecompute>, Error> {
+        let path = self.admin_path(&["fork_choice", "recompute"])?;
+
+        self.post_admin_with_response(path, &()).await
+    }
+
+    /// `GET lighthouse/fork_choice/last_recompute`
+    pub async fn get_lighthouse_fork_choice_last_recompute(
+        &self,
+    ) -> Result<GenericResponse<Option<ForkChoiceRecompute>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("last_recompute");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/ui/validator_metrics`
+    pub async fn post_lighthouse_ui_validator_metrics(
+        &self,
+        indices: &[u64],
+    ) -> Result<GenericResponse<ValidatorMetricsResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("validator_metrics");
+
+        self.post_with_response(
+            path,
+            &ValidatorMetricsRequestData {
+                indices: indices.to_vec(),
+            },
+        )
+        .await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -535,6 +1054,85 @@ impl BeaconNodeHttpClient {
             .transpose()
     }
 
+    /// `GET lighthouse/beacon/states/{state_id}/historical_summaries`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_beacon_states_historical_summaries(
+        &self,
+        state_id: StateId,
+        start_index: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<HistoricalSummaries>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("historical_summaries");
+
+        if let Some(start_index) = start_index {
+            path.query_pairs_mut()
+                .append_pair("start_index", &start_index.to_string());
+        }
+
+        if let Some(limit) = limit {
+            path.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/beacon/states/{state_id}/proof?generalized_index`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_beacon_states_proof(
+        &self,
+        state_id: StateId,
+        generalized_index: usize,
+    ) -> Result<Option<ExecutionOptimisticFinalizedResponse<StateProof>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("proof");
+
+        path.query_pairs_mut()
+            .append_pair("generalized_index", &generalized_index.to_string());
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/validators/{validator_id}/attestation_inclusions?epoch,proof`
+    pub async fn get_lighthouse_validator_attestation_inclusions(
+        &self,
+        validator_id: &ValidatorId,
+        epoch: Epoch,
+        proof: bool,
+    ) -> Result<GenericResponse<Vec<AttestationInclusion>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&validator_id.to_string())
+            .push("attestation_inclusions");
+
+        path.query_pairs_mut()
+            .append_pair("epoch", &epoch.to_string())
+            .append_pair("proof", &proof.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.full.clone();
@@ -560,17 +1158,166 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
-    /// `POST lighthouse/database/reconstruct`
+    /// `POST lighthouse/admin/database/reconstruct`
     pub async fn post_lighthouse_database_reconstruct(&self) -> Result<String, Error> {
+        let path = self.admin_path(&["database", "reconstruct"])?;
+
+        self.post_admin_with_response(path, &()).await
+    }
+
+    /// `POST lighthouse/admin/database/compact`
+    pub async fn post_lighthouse_database_compact(&self) -> Result<DatabaseCompaction, Error> {
+        let path = self.admin_path(&["database", "compact"])?;
+
+        self.post_admin_with_response(path, &()).await
+    }
+
+    /// `GET lighthouse/validator/duties/attester/{epoch}/committees?slot,index`
+    pub async fn get_lighthouse_validator_duties_attester_committee(
+        &self,
+        epoch: Epoch,
+        slot: Slot,
+        index: u64,
+    ) -> Result<DutiesResponse<AttesterCommittee>, Error> {
         let mut path = self.server.full.clone();
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("lighthouse")
-            .push("database")
-            .push("reconstruct");
+            .push("validator")
+            .push("duties")
+            .push("attester")
+            .push(&epoch.to_string())
+            .push("committees");
+
+        path.query_pairs_mut()
+            .append_pair("slot", &slot.to_string())
+            .append_pair("index", &index.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/validator/blocks/{slot}?dry_run=true`
+    ///
+    /// Runs block production for `slot` without returning the block.
+    pub async fn get_lighthouse_validator_blocks_dry_run(
+        &self,
+        slot: Slot,
+        blinded: bool,
+    ) -> Result<GenericResponse<BlockProductionDryRun>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator")
+            .push("blocks")
+            .push(&slot.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("dry_run", "true")
+            .append_pair("blinded", &blinded.to_string());
+
+        self.get(path).await
+    }
+
+    /// `POST validator/duties/attester/{epoch}?include_committee=true`
+    ///
+    /// Lighthouse extension to the standard endpoint which includes the ordered committee of each
+    /// duty.
+    pub async fn post_validator_duties_attester_with_committees(
+        &self,
+        epoch: Epoch,
+        indices: &[u64],
+    ) -> Result<DutiesResponse<Vec<AttesterDataWithCommittee>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("validator")
+            .push("duties")
+            .push("attester")
+            .push(&epoch.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("include_committee", "true");
+
+        self.post_with_timeout_and_response(
+            path,
+            &ValidatorIndexDataRef(indices),
+            self.timeouts.attester_duties,
+        )
+        .await
+    }
+
+    /// `GET lighthouse/caches`
+    pub async fn get_lighthouse_caches(&self) -> Result<GenericResponse<Vec<CacheInfo>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("caches");
+
+        self.get(path).await
+    }
+
+    /// `PATCH lighthouse/admin/caches`
+    pub async fn patch_lighthouse_caches(
+        &self,
+        request: &CachesPatchRequest,
+    ) -> Result<GenericResponse<Vec<CacheInfo>>, Error> {
+        let path = self.admin_path(&["caches"])?;
+
+        self.patch_admin_with_response(path, request).await
+    }
+
+    /// `GET lighthouse/graffiti`
+    pub async fn get_lighthouse_graffiti(
+        &self,
+    ) -> Result<GenericResponse<BeaconNodeGraffiti>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("graffiti");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/proposer_preparation`
+    pub async fn get_lighthouse_proposer_preparation(
+        &self,
+    ) -> Result<GenericResponse<Vec<ProposerPreparationEntry>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proposer_preparation");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/admin/graffiti`
+    pub async fn post_lighthouse_graffiti(
+        &self,
+        request: &GraffitiPostRequest,
+    ) -> Result<GenericResponse<BeaconNodeGraffiti>, Error> {
+        let path = self.admin_path(&["graffiti"])?;
+
+        self.post_admin_with_response(path, request).await
+    }
 
-        self.post_with_response(path, &()).await
+    /// `PATCH lighthouse/admin/logging`
+    pub async fn patch_lighthouse_logging(
+        &self,
+        request: &LoggingPatchRequest,
+    ) -> Result<GenericResponse<LoggingFilters>, Error> {
+        let path = self.admin_path(&["logging"])?;
+
+        self.patch_admin_with_response(path, request).await
     }
 
     ///
@@ -619,6 +1366,57 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET` lighthouse/analysis/epoch_timings?epoch
+    pub async fn get_lighthouse_analysis_epoch_timings(
+        &self,
+        epoch: Epoch,
+    ) -> Result<EpochTimings, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("epoch_timings");
+
+        path.query_pairs_mut()
+            .append_pair("epoch", &epoch.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/proposer_reorg/last_decision`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_proposer_reorg_last_decision(
+        &self,
+    ) -> Result<Option<GenericResponse<ProposerReOrgDecision>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proposer_reorg")
+            .push("last_decision");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/builder/circuit_breaker`
+    pub async fn get_lighthouse_builder_circuit_breaker(
+        &self,
+    ) -> Result<GenericResponse<BuilderCircuitBreakerStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("builder")
+            .push("circuit_breaker");
+
+        self.get(path).await
+    }
+
     /// `GET` lighthouse/analysis/attestation_performance/{index}?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_attestation_performance(
         &self,
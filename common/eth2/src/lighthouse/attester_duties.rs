@@ -0,0 +1,37 @@
+use crate::types::AttesterData;
+use serde::{Deserialize, Serialize};
+use types::{CommitteeIndex, Slot};
+
+/// Query parameters for `GET lighthouse/validator/duties/attester/{epoch}/committees`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttesterCommitteeQuery {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: CommitteeIndex,
+}
+
+/// The validators of a single beacon committee, in committee order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttesterCommittee {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: CommitteeIndex,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub validators: Vec<u64>,
+}
+
+/// Query parameters for the Lighthouse extension to `POST validator/duties/attester/{epoch}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AttesterDutiesQuery {
+    /// Attach the ordered committee to each duty.
+    pub include_committee: Option<bool>,
+}
+
+/// An attester duty along with the validators of its committee, in committee order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttesterDataWithCommittee {
+    #[serde(flatten)]
+    pub duty: AttesterData,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub committee: Vec<u64>,
+}
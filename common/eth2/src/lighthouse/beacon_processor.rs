@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use types::{Hash256, Slot};
+
+/// The state of a single `BeaconProcessor` queue at the time of a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    pub length: usize,
+    pub max_length: usize,
+    /// The number of items dropped because the queue was full, since the processor started.
+    pub dropped: u64,
+}
+
+/// A point-in-time view of the `BeaconProcessor` queues and workers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorSnapshot {
+    pub current_workers: usize,
+    pub max_workers: usize,
+    /// Queues, keyed by the ID of the work type that they hold (e.g. `gossip_block`).
+    pub queues: BTreeMap<String, QueueSnapshot>,
+}
+
+/// The state of a category of queued work at the time of a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReprocessQueueCategorySnapshot {
+    pub length: usize,
+    pub max_length: usize,
+    /// How long the oldest item has been queued for, in milliseconds.
+    pub oldest_age_ms: Option<u64>,
+}
+
+/// An early block waiting for its slot to arrive.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EarlyBlockSnapshot {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    /// When the block is due to be sent for import, in milliseconds since the UNIX epoch.
+    pub release_time_ms: Option<u64>,
+}
+
+/// A point-in-time view of the work held by the reprocessing queue.
+///
+/// Unaggregated and aggregated attestations share a single limit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReprocessQueueSnapshot {
+    pub early_blocks: ReprocessQueueCategorySnapshot,
+    pub unknown_block_attestations: ReprocessQueueCategorySnapshot,
+    pub unknown_block_aggregates: ReprocessQueueCategorySnapshot,
+    pub light_client_updates: ReprocessQueueCategorySnapshot,
+    /// The queued early blocks, ordered by slot.
+    pub early_block_releases: Vec<EarlyBlockSnapshot>,
+}
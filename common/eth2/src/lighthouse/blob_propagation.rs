@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// Reports whether the blobs of a block have been seen by the beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlobPropagation {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    /// One entry for each blob committed to by the block, in index order.
+    pub blobs: Vec<BlobPropagationStatus>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlobPropagationStatus {
+    pub index: u64,
+    /// `true` if the blob has passed gossip verification, either after being received from a peer
+    /// or published through this node.
+    pub seen_on_gossip: bool,
+    /// The time between the start of the slot and the blob first being seen, in milliseconds.
+    ///
+    /// Blobs seen before the start of the slot have a delay of zero.
+    pub seen_delay_ms: Option<u64>,
+    /// `true` if the blob is stored in the database.
+    pub in_db: bool,
+}
+
+impl BlobPropagation {
+    /// Returns the indices of the blobs which have neither been seen on gossip nor stored.
+    pub fn missing_indices(&self) -> Vec<u64> {
+        self.blobs
+            .iter()
+            .filter(|blob| !blob.seen_on_gossip && !blob.in_db)
+            .map(|blob| blob.index)
+            .collect()
+    }
+}
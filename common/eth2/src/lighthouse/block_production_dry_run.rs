@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot, Uint256};
+
+/// Query parameters for the `lighthouse/validator/blocks/{slot}` endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockProductionDryRunQuery {
+    /// Must be `true`, the endpoint only supports dry runs.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Produce a blinded block, requesting a payload from the builder if one is configured.
+    #[serde(default)]
+    pub blinded: bool,
+}
+
+/// The outcome of running the block production pipeline without signing or publishing the block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockProductionDryRun {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    pub parent_root: Hash256,
+    pub state_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestations: u64,
+    /// The value of the execution payload in wei, zero prior to the merge.
+    #[serde(with = "serde_utils::quoted_u256")]
+    pub payload_value: Uint256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub blobs: u64,
+    pub timings: BlockProductionTimings,
+}
+
+/// The time taken by each stage of block production, in milliseconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockProductionTimings {
+    /// Loading the parent state, including waiting for fork choice to run.
+    pub state_load_ms: u64,
+    /// Advancing the parent state to the slot of the block.
+    pub state_advance_ms: u64,
+    /// Packing attestations, slashings, exits, deposits and the sync aggregate.
+    pub block_packing_ms: u64,
+    /// Waiting for the execution payload once block packing had finished.
+    ///
+    /// The payload is requested before packing starts, so this is only the part of its latency
+    /// which packing did not hide.
+    pub payload_wait_ms: u64,
+    /// Applying the block to the state and computing the state root.
+    pub state_root_ms: u64,
+    pub total_ms: u64,
+}
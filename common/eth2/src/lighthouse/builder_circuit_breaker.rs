@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use types::Slot;
+
+/// The state of the builder circuit breaker, which stops connected builders from being used for
+/// payload construction while the chain is unhealthy.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BuilderCircuitBreakerStatus {
+    /// The slot at which the conditions were evaluated.
+    pub slot: Slot,
+    /// Whether any enabled condition failed, in which case only local payloads are used.
+    pub tripped: bool,
+    pub conditions: Vec<BuilderCircuitBreakerCondition>,
+}
+
+/// A single chain health condition of the circuit breaker.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BuilderCircuitBreakerCondition {
+    /// The name of the condition, e.g. `skips_per_epoch`.
+    pub name: String,
+    /// Whether the condition is checked. Disabled conditions never trip the breaker.
+    pub enabled: bool,
+    /// The observed value, e.g. the number of skip slots.
+    pub value: u64,
+    /// The maximum value for which the condition holds.
+    pub threshold: u64,
+    /// Whether the condition is enabled and `value` exceeds `threshold`.
+    pub tripped: bool,
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The caches reported by the `/lighthouse/caches` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheName {
+    /// The database's cache of replayed frozen and skip-slot states.
+    StateCache,
+    /// The cache of recent states used for block processing and production.
+    SnapshotCache,
+    /// The cache of committee shufflings.
+    ShufflingCache,
+    /// The cache of decompressed validator public keys.
+    PubkeyCache,
+    /// The single-item cache of the block being imported as the head.
+    EarlyAttesterCache,
+}
+
+impl CacheName {
+    pub const ALL: [CacheName; 5] = [
+        CacheName::StateCache,
+        CacheName::SnapshotCache,
+        CacheName::ShufflingCache,
+        CacheName::PubkeyCache,
+        CacheName::EarlyAttesterCache,
+    ];
+}
+
+impl fmt::Display for CacheName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CacheName::StateCache => write!(f, "state_cache"),
+            CacheName::SnapshotCache => write!(f, "snapshot_cache"),
+            CacheName::ShufflingCache => write!(f, "shuffling_cache"),
+            CacheName::PubkeyCache => write!(f, "pubkey_cache"),
+            CacheName::EarlyAttesterCache => write!(f, "early_attester_cache"),
+        }
+    }
+}
+
+/// The contents and configuration of a single cache.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub name: CacheName,
+    pub entries: usize,
+    /// An approximation of the memory used by the entries, based on their SSZ size.
+    pub byte_estimate: usize,
+    /// The number of lookups which found an entry, or `None` if lookups are not counted.
+    pub hits: Option<u64>,
+    /// The number of lookups which did not find an entry, or `None` if lookups are not counted.
+    pub misses: Option<u64>,
+    /// The maximum number of entries, or `None` if the cache is unbounded.
+    pub capacity: Option<usize>,
+    /// Whether the capacity can be adjusted with `PATCH /lighthouse/caches`.
+    pub resizable: bool,
+}
+
+/// Request body for `PATCH /lighthouse/caches`.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct CachesPatchRequest {
+    /// The new capacity of each cache to be resized.
+    pub capacities: BTreeMap<CacheName, usize>,
+}
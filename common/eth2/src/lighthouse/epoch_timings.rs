@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use types::Epoch;
+
+/// Timing statistics for the blocks and attestations of a single epoch.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EpochTimings {
+    pub epoch: Epoch,
+    /// Statistics of the delay between the start of each block's slot and its arrival via gossip.
+    ///
+    /// `None` if no block in the epoch was observed on gossip.
+    pub block_arrival_delays: Option<BlockArrivalDelays>,
+    /// A histogram of `inclusion_slot - attestation_slot` for the attestations included in the
+    /// blocks of the epoch, mapping each distance to the number of attestations.
+    pub inclusion_distances: BTreeMap<u64, u64>,
+    /// The number of blobs imported with the blocks of the epoch.
+    #[serde(default)]
+    pub blobs: u64,
+}
+
+/// Summary of the gossip arrival delays of the blocks of an epoch, in milliseconds.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockArrivalDelays {
+    pub blocks: u64,
+    pub min_ms: u64,
+    pub mean_ms: u64,
+    pub median_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Query parameters for the `/lighthouse/analysis/epoch_timings` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EpochTimingsQuery {
+    pub epoch: Epoch,
+}
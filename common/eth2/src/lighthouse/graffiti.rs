@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use types::graffiti::GraffitiString;
+
+/// The request body of `POST lighthouse/graffiti`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraffitiPostRequest {
+    /// The new default graffiti, which may contain template variables such as `{slot}`.
+    pub graffiti: GraffitiString,
+}
+
+/// The beacon node's default graffiti, as reported by `lighthouse/graffiti`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeGraffiti {
+    /// The default graffiti, before any template variables are expanded.
+    pub graffiti: String,
+    /// Always `true`: the default is only used when the validator client supplies no graffiti.
+    ///
+    /// Graffiti from the validator client, including graffiti read from its `--graffiti-file`,
+    /// always takes precedence over the beacon node's default.
+    pub validator_graffiti_takes_precedence: bool,
+}
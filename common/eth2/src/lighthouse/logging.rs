@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// The outputs of the logger, which are filtered independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogDrain {
+    Terminal,
+    File,
+}
+
+/// The request body of `PATCH lighthouse/logging`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingPatchRequest {
+    /// Directives to merge into the current filters, e.g. `sync=debug,libp2p_gossipsub=warn`.
+    ///
+    /// A bare level, e.g. `info`, changes the level of targets which aren't listed.
+    pub filter: String,
+    /// The drain to update, or every drain if `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain: Option<LogDrain>,
+}
+
+/// The filters currently applied to each drain, as directives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingFilters {
+    pub terminal: String,
+    /// `None` if file logging is disabled.
+    pub file: Option<String>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use serde_utils::quoted_u64::Quoted;
+use types::{Address, Epoch};
+
+/// A proposer preparation held by the beacon node, as reported by
+/// `lighthouse/proposer_preparation`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposerPreparationEntry {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// The fee recipient the beacon node will use for blocks proposed by the validator.
+    pub fee_recipient: Address,
+    /// The epoch in which the preparation was last received from a validator client.
+    pub update_epoch: Epoch,
+    /// The gas limit of the validator's latest builder registration, if any.
+    pub gas_limit: Option<Quoted<u64>>,
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// The inputs and outcome of the most recent decision on whether to re-org the head when
+/// proposing a block.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProposerReOrgDecision {
+    /// The slot of the block proposal.
+    pub slot: Slot,
+    /// The canonical head at the time of the proposal, which would be re-orged.
+    pub head_root: Hash256,
+    /// The parent of the head, which a re-orging block would be built upon.
+    ///
+    /// The weights below are `None` if the decision was made before fork choice was consulted,
+    /// or if fork choice rejected the re-org outright.
+    pub parent_root: Option<Hash256>,
+    pub head_weight: Option<u64>,
+    pub parent_weight: Option<u64>,
+    /// The weight below which the head is considered weak.
+    pub re_org_weight_threshold: Option<u64>,
+    /// The weight by which the head must fall short of `re_org_weight_threshold`.
+    pub re_org_margin_weight: Option<u64>,
+    /// The percentage of active stake that attested to the correct target in the previous epoch.
+    ///
+    /// `None` if participation was not evaluated.
+    pub participation_percent: Option<u64>,
+    /// The configured minimum for `participation_percent`.
+    pub min_participation_percent: u64,
+    /// Whether a re-org was attempted.
+    pub attempted: bool,
+    /// The reason a re-org was not attempted, if it was not.
+    pub reason: Option<String>,
+}
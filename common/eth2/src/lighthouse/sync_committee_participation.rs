@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use types::Epoch;
+
+/// Sync committee participation in the canonical blocks of the current sync committee period.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncCommitteeParticipation {
+    /// The sync committee period, or `None` if no sync aggregates have been observed.
+    pub period: Option<u64>,
+    /// One entry for each epoch of the period with a canonical block, in ascending order.
+    pub epochs: Vec<SyncCommitteeEpochParticipation>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncCommitteeEpochParticipation {
+    pub epoch: Epoch,
+    /// The number of canonical blocks in the epoch.
+    pub blocks: u64,
+    /// The number of sync committee bits which could have been set in those blocks.
+    pub expected_participants: u64,
+    /// The number of sync committee bits which were set in those blocks.
+    pub participants: u64,
+    /// `participants / expected_participants`, or `0.0` if there were no blocks.
+    pub participation_rate: f64,
+    /// Participation of the monitored validators which are members of the sync committee.
+    pub validators: Vec<SyncCommitteeValidatorParticipation>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncCommitteeValidatorParticipation {
+    pub index: u64,
+    /// The number of committee positions held by the validator, summed over the blocks.
+    pub expected: u64,
+    /// The number of those positions for which the validator's bit was set.
+    pub hits: u64,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Request body for the `/lighthouse/ui/validator_metrics` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMetricsRequestData {
+    pub indices: Vec<u64>,
+}
+
+/// Attestation effectiveness of a single validator, as observed by the validator monitor.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMetrics {
+    pub attestation_hits: u64,
+    pub attestation_misses: u64,
+    pub attestation_hit_percentage: f64,
+    pub attestation_head_hits: u64,
+    pub attestation_head_misses: u64,
+    pub attestation_head_hit_percentage: f64,
+    pub attestation_target_hits: u64,
+    pub attestation_target_misses: u64,
+    pub attestation_target_hit_percentage: f64,
+    pub latest_attestation_inclusion_distance: u64,
+}
+
+/// The metrics of each requested validator which is monitored, keyed by validator index.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMetricsResponse {
+    pub validators: HashMap<String, ValidatorMetrics>,
+}
@@ -382,6 +382,21 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/validators/signing_capabilities`
+    pub async fn get_lighthouse_validators_signing_capabilities(
+        &self,
+    ) -> Result<GenericResponse<Vec<SigningCapabilities>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("signing_capabilities");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/validators/{validator_pubkey}`
     pub async fn get_lighthouse_validators_pubkey(
         &self,
@@ -398,6 +413,23 @@ impl ValidatorClientHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET lighthouse/validators/{validator_pubkey}/protection_history`
+    pub async fn get_lighthouse_validators_protection_history(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+    ) -> Result<Option<GenericResponse<ProtectionHistory>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&validator_pubkey.to_string())
+            .push("protection_history");
+
+        self.get_opt(path).await
+    }
+
     /// `POST lighthouse/validators`
     pub async fn post_lighthouse_validators(
         &self,
@@ -490,6 +522,45 @@ impl ValidatorClientHttpClient {
         .await
     }
 
+    /// `GET lighthouse/ui/fallback_health`
+    pub async fn get_lighthouse_ui_fallback_health(
+        &self,
+    ) -> Result<GenericResponse<FallbackHealth>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("fallback_health");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/timing`
+    pub async fn get_lighthouse_timing(&self) -> Result<GenericResponse<SlotTimingOffsets>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("timing");
+
+        self.get(path).await
+    }
+
+    /// `PATCH lighthouse/timing`
+    pub async fn patch_lighthouse_timing(&self, request: &TimingPatchRequest) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("timing");
+
+        self.patch(path, request).await
+    }
+
     /// `DELETE eth/v1/keystores`
     pub async fn delete_lighthouse_keystores(
         &self,
@@ -549,6 +620,18 @@ impl ValidatorClientHttpClient {
         Ok(url)
     }
 
+    fn make_graffiti_url(&self, pubkey: &PublicKeyBytes) -> Result<Url, Error> {
+        let mut url = self.server.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("eth")
+            .push("v1")
+            .push("validator")
+            .push(&pubkey.to_string())
+            .push("graffiti");
+        Ok(url)
+    }
+
     /// `GET lighthouse/auth`
     pub async fn get_auth(&self) -> Result<AuthResponse, Error> {
         let mut url = self.server.full.clone();
@@ -661,6 +744,33 @@ impl ValidatorClientHttpClient {
         self.delete_with_raw_response(url, &()).await
     }
 
+    /// `GET /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn get_graffiti(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<GetGraffitiResponse, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.get(url)
+            .await
+            .map(|generic: GenericResponse<GetGraffitiResponse>| generic.data)
+    }
+
+    /// `POST /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn post_graffiti(
+        &self,
+        pubkey: &PublicKeyBytes,
+        req: &UpdateGraffitiRequest,
+    ) -> Result<Response, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.post_with_raw_response(url, req).await
+    }
+
+    /// `DELETE /eth/v1/validator/{pubkey}/graffiti`
+    pub async fn delete_graffiti(&self, pubkey: &PublicKeyBytes) -> Result<Response, Error> {
+        let url = self.make_graffiti_url(pubkey)?;
+        self.delete_with_raw_response(url, &()).await
+    }
+
     /// `POST /eth/v1/validator/{pubkey}/voluntary_exit`
     pub async fn post_validator_voluntary_exit(
         &self,
@@ -18,6 +18,12 @@ pub struct GetGasLimitResponse {
     pub gas_limit: u64,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct GetGraffitiResponse {
+    pub pubkey: PublicKeyBytes,
+    pub graffiti: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct AuthResponse {
     pub token_path: String,
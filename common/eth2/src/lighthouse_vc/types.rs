@@ -2,6 +2,7 @@ use account_utils::ZeroizeString;
 use eth2_keystore::Keystore;
 use graffiti::GraffitiString;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 pub use crate::lighthouse::Health;
@@ -16,6 +17,39 @@ pub struct ValidatorData {
     pub voting_pubkey: PublicKeyBytes,
 }
 
+/// The low watermarks of a validator's slashing protection history.
+///
+/// Blocks at or below `min_block_slot`, and attestations with a source below
+/// `min_attestation_source` or a target at or below `min_attestation_target` will not be signed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtectionHistory {
+    pub voting_pubkey: PublicKeyBytes,
+    pub min_block_slot: Option<Slot>,
+    pub min_attestation_source: Option<Epoch>,
+    pub min_attestation_target: Option<Epoch>,
+}
+
+/// Whether the signer of a validator signs a type of message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningCapability {
+    Allowed,
+    Refused,
+    /// The message type has not been probed or signed since the signer was last checked.
+    Unknown,
+}
+
+/// The message types the signer of a validator is known to sign or refuse, keyed by their
+/// Web3Signer name (e.g. `SYNC_COMMITTEE_MESSAGE`).
+///
+/// Local keystores sign every type of message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningCapabilities {
+    pub voting_pubkey: PublicKeyBytes,
+    pub remote_signer: bool,
+    pub capabilities: BTreeMap<String, SigningCapability>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorRequest {
     pub enable: bool,
@@ -88,6 +122,47 @@ pub struct ValidatorPatchRequest {
     pub graffiti: Option<GraffitiString>,
 }
 
+/// Offsets from the start of each slot at which attestations and aggregates are produced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlotTimingOffsets {
+    pub attestation_offset_ms: u64,
+    pub aggregate_offset_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingPatchRequest {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation_offset_ms: Option<u64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aggregate_offset_ms: Option<u64>,
+}
+
+/// The health of each of the beacon nodes used by the validator client, in order of preference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackHealth {
+    /// The current epoch, if it is known.
+    pub epoch: Option<Epoch>,
+    pub nodes: Vec<BeaconNodeFallbackHealth>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeFallbackHealth {
+    /// The host and port of the beacon node.
+    pub node: String,
+    pub status: String,
+    pub current_epoch: BeaconNodeRequestSummary,
+    pub previous_epoch: BeaconNodeRequestSummary,
+}
+
+/// The number of requests to a beacon node which it served, and which failed, by endpoint.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeRequestSummary {
+    pub served: BTreeMap<String, u64>,
+    pub failed: BTreeMap<String, u64>,
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct KeystoreValidatorsPostRequest {
     pub password: ZeroizeString,
@@ -148,6 +223,11 @@ pub struct UpdateGasLimitRequest {
     pub gas_limit: u64,
 }
 
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct UpdateGraffitiRequest {
+    pub graffiti: GraffitiString,
+}
+
 #[derive(Deserialize)]
 pub struct VoluntaryExitQuery {
     pub epoch: Option<Epoch>,
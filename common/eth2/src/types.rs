@@ -4,6 +4,7 @@
 use crate::Error as ServerError;
 use lighthouse_network::{ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
 use mediatype::{names, MediaType, MediaTypeList};
+use proto_array::ExecutionStatus;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use ssz::{Decode, DecodeError};
@@ -716,13 +717,15 @@ pub enum SkipRandaoVerification {
 }
 
 /// Parse a `skip_randao_verification` query parameter.
+///
+/// The parameter is a flag without a value, but `true` and `false` are also accepted.
 impl TryFrom<Option<String>> for SkipRandaoVerification {
     type Error = String;
 
     fn try_from(opt: Option<String>) -> Result<Self, String> {
         match opt.as_deref() {
-            None => Ok(SkipRandaoVerification::No),
-            Some("") => Ok(SkipRandaoVerification::Yes),
+            None | Some("false") => Ok(SkipRandaoVerification::No),
+            Some("") | Some("true") => Ok(SkipRandaoVerification::Yes),
             Some(s) => Err(format!(
                 "skip_randao_verification does not take a value, got: {s}"
             )),
@@ -957,6 +960,45 @@ pub struct SseLateHead {
     pub execution_optimistic: bool,
 }
 
+/// The proposer duties of `epoch` now depend on `dependent_root`, following a change of head.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseProposerDutiesReorg {
+    pub epoch: Epoch,
+    pub dependent_root: Hash256,
+}
+
+/// The status of a block's execution payload, as known to fork choice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockExecutionStatus {
+    Valid,
+    Invalid,
+    Optimistic,
+    /// The block is prior to the merge and has no execution payload.
+    Irrelevant,
+}
+
+impl From<ExecutionStatus> for BlockExecutionStatus {
+    fn from(status: ExecutionStatus) -> Self {
+        match status {
+            ExecutionStatus::Valid(_) => BlockExecutionStatus::Valid,
+            ExecutionStatus::Invalid(_) => BlockExecutionStatus::Invalid,
+            ExecutionStatus::Optimistic(_) => BlockExecutionStatus::Optimistic,
+            ExecutionStatus::Irrelevant(_) => BlockExecutionStatus::Irrelevant,
+        }
+    }
+}
+
+/// The execution status of `block_root` has changed, e.g. an optimistically imported block has
+/// been verified by the execution engine.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseExecutionOptimisticStatus {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub old_status: BlockExecutionStatus,
+    pub new_status: BlockExecutionStatus,
+}
+
 #[superstruct(
     variants(V1, V2, V3),
     variant_attributes(derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize))
@@ -1050,6 +1092,8 @@ pub enum EventKind<T: EthSpec> {
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
     PayloadAttributes(VersionedSsePayloadAttributes),
+    ProposerDutiesReorg(SseProposerDutiesReorg),
+    ExecutionOptimisticStatus(SseExecutionOptimisticStatus),
 }
 
 impl<T: EthSpec> EventKind<T> {
@@ -1065,6 +1109,8 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::ContributionAndProof(_) => "contribution_and_proof",
             EventKind::PayloadAttributes(_) => "payload_attributes",
             EventKind::LateHead(_) => "late_head",
+            EventKind::ProposerDutiesReorg(_) => "proposer_duties_reorg",
+            EventKind::ExecutionOptimisticStatus(_) => "execution_optimistic_status",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
         }
@@ -1127,6 +1173,19 @@ impl<T: EthSpec> EventKind<T> {
                     ServerError::InvalidServerSentEvent(format!("Payload Attributes: {:?}", e))
                 })?,
             )),
+            "proposer_duties_reorg" => Ok(EventKind::ProposerDutiesReorg(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Proposer Duties Reorg: {:?}", e))
+                })?,
+            )),
+            "execution_optimistic_status" => Ok(EventKind::ExecutionOptimisticStatus(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!(
+                        "Execution Optimistic Status: {:?}",
+                        e
+                    ))
+                })?,
+            )),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventKind::BlockReward(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Reward: {:?}", e)),
@@ -1158,6 +1217,8 @@ pub enum EventTopic {
     ContributionAndProof,
     LateHead,
     PayloadAttributes,
+    ProposerDutiesReorg,
+    ExecutionOptimisticStatus,
     #[cfg(feature = "lighthouse")]
     BlockReward,
 }
@@ -1177,6 +1238,8 @@ impl FromStr for EventTopic {
             "contribution_and_proof" => Ok(EventTopic::ContributionAndProof),
             "payload_attributes" => Ok(EventTopic::PayloadAttributes),
             "late_head" => Ok(EventTopic::LateHead),
+            "proposer_duties_reorg" => Ok(EventTopic::ProposerDutiesReorg),
+            "execution_optimistic_status" => Ok(EventTopic::ExecutionOptimisticStatus),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
             _ => Err("event topic cannot be parsed.".to_string()),
@@ -1197,6 +1260,8 @@ impl fmt::Display for EventTopic {
             EventTopic::ContributionAndProof => write!(f, "contribution_and_proof"),
             EventTopic::PayloadAttributes => write!(f, "payload_attributes"),
             EventTopic::LateHead => write!(f, "late_head"),
+            EventTopic::ProposerDutiesReorg => write!(f, "proposer_duties_reorg"),
+            EventTopic::ExecutionOptimisticStatus => write!(f, "execution_optimistic_status"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
         }
@@ -1300,6 +1365,42 @@ pub struct LivenessResponseData {
     pub is_live: bool,
 }
 
+// Details about the rewards for a single block
+// All rewards in GWei
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct StandardBlockReward {
+    // proposer of the block, the proposer index who receives these rewards
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    // total block reward in gwei,
+    // equal to attestations + sync_aggregate + proposer_slashings + attester_slashings
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total: u64,
+    // block reward component due to included attestations in gwei
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestations: u64,
+    // block reward component due to included sync_aggregate in gwei
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub sync_aggregate: u64,
+    // block reward component due to included proposer_slashings in gwei
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_slashings: u64,
+    // block reward component due to included attester_slashings in gwei
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attester_slashings: u64,
+}
+
+// Details about the rewards paid to sync committee members for attesting headers
+// All rewards in GWei
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SyncCommitteeReward {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    // sync committee reward in gwei for the validator
+    #[serde(with = "serde_utils::quoted_i64")]
+    pub reward: i64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ForkChoice {
     pub justified_checkpoint: Checkpoint,
@@ -1318,6 +1419,22 @@ pub struct ForkChoiceNode {
     pub weight: u64,
     pub validity: Option<String>,
     pub execution_block_hash: Option<Hash256>,
+    /// Client-specific data, which other beacon nodes may omit or structure differently.
+    #[serde(default)]
+    pub extra_data: Option<ForkChoiceNodeExtraData>,
+}
+
+/// Lighthouse-specific data attached to each node in the `extra_data` field of the standard
+/// `GET eth/v1/debug/fork_choice` response.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ForkChoiceNodeExtraData {
+    pub state_root: Hash256,
+    pub best_child: Option<Hash256>,
+    pub best_descendant: Option<Hash256>,
+    pub unrealized_justified_epoch: Option<Epoch>,
+    pub unrealized_finalized_epoch: Option<Epoch>,
+    /// `true` if the node is the finalized checkpoint block or one of its descendants.
+    pub finalized_descendant: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -1495,6 +1612,22 @@ mod tests {
             SignedBlockContents::BlindedBlockAndBlobSidecars(_)
         ));
     }
+
+    #[test]
+    fn fork_choice_node_without_extra_data() {
+        let node: ForkChoiceNode = serde_json::from_value(serde_json::json!({
+            "slot": "1",
+            "block_root": Hash256::repeat_byte(1),
+            "parent_root": Hash256::zero(),
+            "justified_epoch": "0",
+            "finalized_epoch": "0",
+            "weight": "32000000000",
+            "validity": "valid",
+            "execution_block_hash": Hash256::repeat_byte(2),
+        }))
+        .expect("should decode a node without extra data");
+        assert_eq!(node.extra_data, None);
+    }
 }
 
 /// A wrapper over a [`BeaconBlock`] or a [`BeaconBlockAndBlobSidecars`].
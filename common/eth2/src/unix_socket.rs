@@ -0,0 +1,133 @@
+//! Sends requests to a beacon node serving its HTTP API on a Unix domain socket.
+//!
+//! Requests are built with `reqwest` as usual, against a placeholder `http://localhost/` base URL,
+//! and then sent over the socket with `hyper`. This lets every method of `BeaconNodeHttpClient`
+//! work over either transport.
+
+use crate::Error;
+use reqwest::{Request, Response, Url};
+use std::path::PathBuf;
+
+/// The scheme of URLs which refer to a Unix domain socket, e.g. `unix:///run/lighthouse/bn.sock`.
+pub const UNIX_SCHEME: &str = "unix";
+
+/// The base URL used to build requests sent over a Unix domain socket.
+pub fn placeholder_base_url() -> Url {
+    Url::parse("http://localhost/").expect("placeholder URL is valid")
+}
+
+/// Sends requests over the Unix domain socket at `socket_path`.
+///
+/// A new connection is made for each request, which is cheap for a local socket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnixSocketTransport {
+    socket_path: PathBuf,
+}
+
+impl UnixSocketTransport {
+    /// Returns a transport for `url` if it uses the `unix` scheme, taking its path as the path of
+    /// the socket.
+    pub fn from_url(url: &Url) -> Option<Self> {
+        (url.scheme() == UNIX_SCHEME).then(|| Self {
+            socket_path: PathBuf::from(url.path()),
+        })
+    }
+
+    pub fn socket_path(&self) -> &PathBuf {
+        &self.socket_path
+    }
+
+    /// Sends `request` over the socket, respecting its timeout.
+    pub async fn execute(&self, request: Request) -> Result<Response, Error> {
+        match request.timeout().copied() {
+            Some(timeout) => tokio::time::timeout(timeout, self.execute_without_timeout(request))
+                .await
+                .map_err(|_| Error::UnixSocket("request timed out".to_string()))?,
+            None => self.execute_without_timeout(request).await,
+        }
+    }
+
+    #[cfg(unix)]
+    async fn execute_without_timeout(&self, request: Request) -> Result<Response, Error> {
+        let stream = tokio::net::UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(|e| {
+                Error::UnixSocket(format!(
+                    "unable to connect to {}: {}",
+                    self.socket_path.display(),
+                    e
+                ))
+            })?;
+        let (mut sender, connection) = hyper::client::conn::handshake(stream)
+            .await
+            .map_err(|e| Error::UnixSocket(format!("handshake failed: {}", e)))?;
+        // The connection completes once the response body has been read.
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+
+        let response = sender
+            .send_request(to_hyper_request(request)?)
+            .await
+            .map_err(|e| Error::UnixSocket(format!("request failed: {}", e)))?;
+        let (parts, body) = response.into_parts();
+        Ok(Response::from(hyper::Response::from_parts(
+            parts,
+            reqwest::Body::wrap_stream(body),
+        )))
+    }
+
+    #[cfg(not(unix))]
+    async fn execute_without_timeout(&self, _request: Request) -> Result<Response, Error> {
+        Err(Error::UnixSocket(
+            "Unix domain sockets are not supported on this platform".to_string(),
+        ))
+    }
+}
+
+/// Converts a request built by `reqwest` into one which can be sent with `hyper`.
+///
+/// Only requests with in-memory bodies are supported, which includes all those sent by
+/// `BeaconNodeHttpClient`.
+#[cfg(unix)]
+fn to_hyper_request(request: Request) -> Result<hyper::Request<hyper::Body>, Error> {
+    let path_and_query = match request.url().query() {
+        Some(query) => format!("{}?{}", request.url().path(), query),
+        None => request.url().path().to_string(),
+    };
+    let body = match request.body() {
+        Some(body) => body
+            .as_bytes()
+            .ok_or_else(|| Error::UnixSocket("streaming request bodies are unsupported".into()))?
+            .to_vec(),
+        None => vec![],
+    };
+
+    let mut builder = hyper::Request::builder()
+        .method(request.method().clone())
+        .uri(path_and_query)
+        .header(hyper::header::HOST, "localhost");
+    for (name, value) in request.headers() {
+        builder = builder.header(name, value);
+    }
+    builder
+        .body(hyper::Body::from(body))
+        .map_err(|e| Error::UnixSocket(format!("invalid request: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_url() {
+        let url = Url::parse("unix:///run/lighthouse/bn.sock").unwrap();
+        assert_eq!(
+            UnixSocketTransport::from_url(&url).unwrap().socket_path(),
+            &PathBuf::from("/run/lighthouse/bn.sock")
+        );
+
+        let url = Url::parse("http://localhost:5052").unwrap();
+        assert!(UnixSocketTransport::from_url(&url).is_none());
+    }
+}
@@ -31,6 +31,7 @@ const HOLESKY_GENESIS_STATE_SOURCE: GenesisStateSource = GenesisStateSource::Url
     ],
     checksum: "0xd750639607c337bbb192b15c27f447732267bf72d1650180a0e44c2d93a80741",
     genesis_validators_root: "0x9143aa7c615a7f7115e2b6aac319c03529df8242ae705fba9df39b79c59fa8b1",
+    genesis_state_root: None,
 };
 
 const CHIADO_GENESIS_STATE_SOURCE: GenesisStateSource = GenesisStateSource::Url {
@@ -38,6 +39,7 @@ const CHIADO_GENESIS_STATE_SOURCE: GenesisStateSource = GenesisStateSource::Url
     urls: &[],
     checksum: "0xd4a039454c7429f1dfaa7e11e397ef3d0f50d2d5e4c0e4dc04919d153aa13af1",
     genesis_validators_root: "0x9d642dac73058fbf39c0ae41ab1e34e4d889043cb199851ded7095bc99eb4c1e",
+    genesis_state_root: None,
 };
 
 /// The core configuration of a Lighthouse beacon node.
@@ -102,6 +104,11 @@ pub enum GenesisStateSource {
         ///
         /// The format should be 0x-prefixed ASCII bytes.
         genesis_validators_root: &'static str,
+        /// The hash tree root of the genesis state, if known. Downloaded states with a different
+        /// root are rejected.
+        ///
+        /// The format should be 0x-prefixed ASCII bytes.
+        genesis_state_root: Option<&'static str>,
     },
 }
 
@@ -140,6 +147,7 @@ pub struct HardcodedNet {
     pub config: &'static [u8],
     pub deploy_block: &'static [u8],
     pub boot_enr: &'static [u8],
+    pub checkpoint_sync_url_suggestions: &'static [u8],
     pub genesis_state_bytes: &'static [u8],
 }
 
@@ -194,6 +202,11 @@ macro_rules! define_net {
             config: $this_crate::$include_file!($this_crate, "../", "config.yaml"),
             deploy_block: $this_crate::$include_file!($this_crate, "../", "deploy_block.txt"),
             boot_enr: $this_crate::$include_file!($this_crate, "../", "boot_enr.yaml"),
+            checkpoint_sync_url_suggestions: $this_crate::$include_file!(
+                $this_crate,
+                "../",
+                "checkpoint_sync_url_suggestions.yaml"
+            ),
             genesis_state_bytes: $this_crate::$include_file!($this_crate, "../", "genesis.ssz"),
         }
     }};
@@ -20,9 +20,9 @@ use reqwest::{Client, Error};
 use sensitive_url::SensitiveUrl;
 use sha2::{Digest, Sha256};
 use slog::{info, warn, Logger};
-use std::fs::{create_dir_all, File};
+use std::fs::{self, create_dir_all, File};
 use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use types::{BeaconState, ChainSpec, Config, Epoch, EthSpec, EthSpecId, Hash256};
@@ -32,6 +32,7 @@ pub use eth2_config::GenesisStateSource;
 
 pub const DEPLOY_BLOCK_FILE: &str = "deploy_block.txt";
 pub const BOOT_ENR_FILE: &str = "boot_enr.yaml";
+pub const CHECKPOINT_SYNC_URL_SUGGESTIONS_FILE: &str = "checkpoint_sync_url_suggestions.yaml";
 pub const GENESIS_STATE_FILE: &str = "genesis.ssz";
 pub const BASE_CONFIG_FILE: &str = "config.yaml";
 
@@ -118,6 +119,8 @@ pub struct Eth2NetworkConfig {
     /// value to be the block number where the first deposit occurs.
     pub deposit_contract_deploy_block: u64,
     pub boot_enr: Option<Vec<Enr<CombinedKey>>>,
+    /// Public beacon node endpoints which may be suggested to users for checkpoint sync.
+    pub checkpoint_sync_url_suggestions: Option<Vec<String>>,
     pub genesis_state_source: GenesisStateSource,
     pub genesis_state_bytes: Option<GenesisStateBytes>,
     pub config: Config,
@@ -147,6 +150,11 @@ impl Eth2NetworkConfig {
                 serde_yaml::from_reader(net.boot_enr)
                     .map_err(|e| format!("Unable to parse boot enr: {:?}", e))?,
             ),
+            checkpoint_sync_url_suggestions: Some(
+                serde_yaml::from_reader(net.checkpoint_sync_url_suggestions).map_err(|e| {
+                    format!("Unable to parse checkpoint sync url suggestions: {:?}", e)
+                })?,
+            ),
             genesis_state_source: net.genesis_state_source,
             genesis_state_bytes: Some(net.genesis_state_bytes)
                 .filter(|bytes| !bytes.is_empty())
@@ -203,11 +211,17 @@ impl Eth2NetworkConfig {
     /// Attempts to deserialize `self.beacon_state`, returning an error if it's missing or invalid.
     ///
     /// If the genesis state is configured to be downloaded from a URL, then the
-    /// `genesis_state_url` will override the built-in list of download URLs.
+    /// `genesis_state_urls` will override the built-in list of download URLs. The URLs are tried
+    /// in order until one serves a state which matches the checksum, `genesis_validators_root`
+    /// and (if known) state root of the network.
+    ///
+    /// If a `cache_path` is supplied, a verified state is loaded from that file in preference to
+    /// downloading it, and a downloaded state is written to it.
     pub async fn genesis_state<E: EthSpec>(
         &self,
-        genesis_state_url: Option<&str>,
+        genesis_state_urls: &[String],
         timeout: Duration,
+        cache_path: Option<&Path>,
         log: &Logger,
     ) -> Result<Option<BeaconState<E>>, String> {
         let spec = self.chain_spec::<E>()?;
@@ -221,32 +235,50 @@ impl Eth2NetworkConfig {
                 urls: built_in_urls,
                 checksum,
                 genesis_validators_root,
+                genesis_state_root,
             } => {
-                let checksum = Hash256::from_str(checksum).map_err(|e| {
-                    format!("Unable to parse genesis state bytes checksum: {:?}", e)
-                })?;
-                let bytes = if let Some(specified_url) = genesis_state_url {
-                    download_genesis_state(&[specified_url], timeout, checksum, log).await
-                } else {
-                    download_genesis_state(built_in_urls, timeout, checksum, log).await
-                }?;
-                let state = BeaconState::from_ssz_bytes(bytes.as_ref(), &spec).map_err(|e| {
-                    format!("Downloaded genesis state SSZ bytes are invalid: {:?}", e)
-                })?;
+                let expected = ExpectedGenesisState::new(
+                    checksum,
+                    genesis_validators_root,
+                    *genesis_state_root,
+                )?;
+
+                if let Some(path) = cache_path.filter(|path| path.exists()) {
+                    match load_cached_genesis_state(path, &expected, &spec) {
+                        Ok(state) => {
+                            info!(
+                                log,
+                                "Loaded cached genesis state";
+                                "path" => ?path,
+                            );
+                            return Ok(Some(state));
+                        }
+                        Err(e) => warn!(
+                            log,
+                            "Ignoring invalid cached genesis state";
+                            "path" => ?path,
+                            "error" => e,
+                        ),
+                    }
+                }
 
-                let genesis_validators_root =
-                    Hash256::from_str(genesis_validators_root).map_err(|e| {
-                        format!(
-                            "Unable to parse genesis state genesis_validators_root: {:?}",
-                            e
-                        )
-                    })?;
-                if state.genesis_validators_root() != genesis_validators_root {
-                    return Err(format!(
-                        "Downloaded genesis validators root {:?} does not match expected {:?}",
-                        state.genesis_validators_root(),
-                        genesis_validators_root
-                    ));
+                let urls = if genesis_state_urls.is_empty() {
+                    built_in_urls.to_vec()
+                } else {
+                    genesis_state_urls.iter().map(String::as_str).collect()
+                };
+                let (bytes, state) =
+                    download_genesis_state(&urls, timeout, &expected, &spec, log).await?;
+
+                if let Some(path) = cache_path {
+                    if let Err(e) = fs::write(path, &bytes) {
+                        warn!(
+                            log,
+                            "Unable to cache genesis state";
+                            "path" => ?path,
+                            "error" => ?e,
+                        );
+                    }
                 }
 
                 Ok(Some(state))
@@ -311,6 +343,13 @@ impl Eth2NetworkConfig {
             write_to_yaml_file!(BOOT_ENR_FILE, boot_enr);
         }
 
+        if let Some(checkpoint_sync_url_suggestions) = &self.checkpoint_sync_url_suggestions {
+            write_to_yaml_file!(
+                CHECKPOINT_SYNC_URL_SUGGESTIONS_FILE,
+                checkpoint_sync_url_suggestions
+            );
+        }
+
         write_to_yaml_file!(BASE_CONFIG_FILE, &self.config);
 
         // The genesis state is a special case because it uses SSZ, not YAML.
@@ -352,6 +391,8 @@ impl Eth2NetworkConfig {
 
         let deposit_contract_deploy_block = load_from_file!(DEPLOY_BLOCK_FILE);
         let boot_enr = optional_load_from_file!(BOOT_ENR_FILE);
+        let checkpoint_sync_url_suggestions =
+            optional_load_from_file!(CHECKPOINT_SYNC_URL_SUGGESTIONS_FILE);
         let config = load_from_file!(BASE_CONFIG_FILE);
 
         // The genesis state is a special case because it uses SSZ, not YAML.
@@ -381,6 +422,7 @@ impl Eth2NetworkConfig {
         Ok(Self {
             deposit_contract_deploy_block,
             boot_enr,
+            checkpoint_sync_url_suggestions,
             genesis_state_source,
             genesis_state_bytes: genesis_state_bytes.map(Into::into),
             config,
@@ -389,15 +431,86 @@ impl Eth2NetworkConfig {
     }
 }
 
+/// The properties a genesis state obtained from outside the binary must match.
+struct ExpectedGenesisState {
+    checksum: Hash256,
+    genesis_validators_root: Hash256,
+    genesis_state_root: Option<Hash256>,
+}
+
+impl ExpectedGenesisState {
+    fn new(
+        checksum: &str,
+        genesis_validators_root: &str,
+        genesis_state_root: Option<&str>,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            checksum: Hash256::from_str(checksum)
+                .map_err(|e| format!("Unable to parse genesis state bytes checksum: {:?}", e))?,
+            genesis_validators_root: Hash256::from_str(genesis_validators_root).map_err(|e| {
+                format!(
+                    "Unable to parse genesis state genesis_validators_root: {:?}",
+                    e
+                )
+            })?,
+            genesis_state_root: genesis_state_root
+                .map(Hash256::from_str)
+                .transpose()
+                .map_err(|e| format!("Unable to parse genesis state root: {:?}", e))?,
+        })
+    }
+
+    /// Decodes the `bytes`, returning an error if they do not match the expected genesis state.
+    fn verify<E: EthSpec>(&self, bytes: &[u8], spec: &ChainSpec) -> Result<BeaconState<E>, String> {
+        if Sha256::digest(bytes)[..] != self.checksum[..] {
+            return Err("bytes did not match local checksum".to_string());
+        }
+
+        let state = BeaconState::from_ssz_bytes(bytes, spec)
+            .map_err(|e| format!("SSZ bytes are invalid: {:?}", e))?;
+
+        if state.genesis_validators_root() != self.genesis_validators_root {
+            return Err(format!(
+                "genesis validators root {:?} does not match expected {:?}",
+                state.genesis_validators_root(),
+                self.genesis_validators_root
+            ));
+        }
+
+        if let Some(expected_root) = self.genesis_state_root {
+            let state_root = state.canonical_root();
+            if state_root != expected_root {
+                return Err(format!(
+                    "state root {:?} does not match expected {:?}",
+                    state_root, expected_root
+                ));
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+/// Load and verify a genesis state previously written to `path` by `genesis_state`.
+fn load_cached_genesis_state<E: EthSpec>(
+    path: &Path,
+    expected: &ExpectedGenesisState,
+    spec: &ChainSpec,
+) -> Result<BeaconState<E>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Unable to read file: {:?}", e))?;
+    expected.verify(&bytes, spec)
+}
+
 /// Try to download a genesis state from each of the `urls` in the order they
-/// are defined. Return `Ok` if any url returns a response that matches the
-/// given `checksum`.
-async fn download_genesis_state(
+/// are defined. Return `Ok` with the bytes and the decoded state from the first
+/// url which returns a state matching `expected`.
+async fn download_genesis_state<E: EthSpec>(
     urls: &[&str],
     timeout: Duration,
-    checksum: Hash256,
+    expected: &ExpectedGenesisState,
+    spec: &ChainSpec,
     log: &Logger,
-) -> Result<Vec<u8>, String> {
+) -> Result<(Vec<u8>, BeaconState<E>), String> {
     if urls.is_empty() {
         return Err(
             "The genesis state is not present in the binary and there are no known download URLs. \
@@ -424,33 +537,31 @@ async fn download_genesis_state(
         );
 
         let client = Client::new();
-        let response = get_state_bytes(timeout, url, client).await;
-
-        match response {
-            Ok(bytes) => {
-                // Check the server response against our local checksum.
-                if Sha256::digest(bytes.as_ref())[..] == checksum[..] {
-                    return Ok(bytes.into());
-                } else {
-                    warn!(
-                        log,
-                        "Genesis state download failed";
-                        "server" => &redacted_url,
-                        "timeout" => ?timeout,
-                    );
-                    errors.push(format!(
-                        "Response from {} did not match local checksum",
-                        redacted_url
-                    ))
-                }
+        let error = match get_state_bytes(timeout, url, client).await {
+            Ok(bytes) => match expected.verify::<E>(bytes.as_ref(), spec) {
+                Ok(state) => return Ok((bytes.into(), state)),
+                Err(e) => format!("Response from {} was rejected: {}", redacted_url, e),
+            },
+            Err(e) if e.is_timeout() => {
+                format!("Request to {} timed out after {:?}", redacted_url, timeout)
             }
-            Err(e) => errors.push(PrettyReqwestError::from(e).to_string()),
-        }
+            Err(e) => format!(
+                "Request to {} failed: {}",
+                redacted_url,
+                PrettyReqwestError::from(e)
+            ),
+        };
+        warn!(
+            log,
+            "Genesis state download failed";
+            "error" => &error,
+        );
+        errors.push(error);
     }
     Err(format!(
         "Unable to download a genesis state from {} source(s): {}",
         errors.len(),
-        errors.join(",")
+        errors.join(", ")
     ))
 }
 
@@ -514,7 +625,7 @@ mod tests {
     async fn mainnet_genesis_state() {
         let config = Eth2NetworkConfig::from_hardcoded_net(&MAINNET).unwrap();
         config
-            .genesis_state::<E>(None, Duration::from_secs(1), &logging::test_logger())
+            .genesis_state::<E>(&[], Duration::from_secs(1), None, &logging::test_logger())
             .await
             .expect("beacon state can decode");
     }
@@ -550,11 +661,16 @@ mod tests {
                 urls,
                 checksum,
                 genesis_validators_root,
+                genesis_state_root,
             } = net.genesis_state_source
             {
                 Hash256::from_str(checksum).expect("the checksum must be a valid 32-byte value");
                 Hash256::from_str(genesis_validators_root)
                     .expect("the GVR must be a valid 32-byte value");
+                if let Some(genesis_state_root) = genesis_state_root {
+                    Hash256::from_str(genesis_state_root)
+                        .expect("the state root must be a valid 32-byte value");
+                }
                 for url in urls {
                     parse_state_download_url(url).expect("url must be valid");
                 }
@@ -579,12 +695,20 @@ mod tests {
         let genesis_state = Some(BeaconState::new(42, eth1_data, spec));
         let config = Config::from_chain_spec::<E>(spec);
 
-        do_test::<E>(boot_enr, genesis_state, config.clone());
-        do_test::<E>(None, None, config);
+        let checkpoint_sync_url_suggestions = Some(vec!["http://localhost:5052".to_string()]);
+
+        do_test::<E>(
+            boot_enr,
+            checkpoint_sync_url_suggestions,
+            genesis_state,
+            config.clone(),
+        );
+        do_test::<E>(None, None, None, config);
     }
 
     fn do_test<E: EthSpec>(
         boot_enr: Option<Vec<Enr<CombinedKey>>>,
+        checkpoint_sync_url_suggestions: Option<Vec<String>>,
         genesis_state: Option<BeaconState<E>>,
         config: Config,
     ) {
@@ -604,6 +728,7 @@ mod tests {
         let testnet = Eth2NetworkConfig {
             deposit_contract_deploy_block,
             boot_enr,
+            checkpoint_sync_url_suggestions,
             genesis_state_source,
             genesis_state_bytes: genesis_state
                 .as_ref()
@@ -621,4 +746,140 @@ mod tests {
 
         assert_eq!(testnet, decoded, "should decode as encoded");
     }
+
+    /// Serves `bytes` as the genesis state from a beacon-API mock, returning its base URL.
+    fn serve_genesis_state(bytes: Vec<u8>) -> String {
+        use warp::Filter;
+
+        let route = warp::path!("eth" / "v2" / "debug" / "beacon" / "states" / "genesis")
+            .map(move || bytes.clone());
+        let (listen_addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}/", listen_addr)
+    }
+
+    fn test_genesis_state(spec: &ChainSpec) -> BeaconState<E> {
+        let eth1_data = Eth1Data {
+            deposit_root: Hash256::zero(),
+            deposit_count: 0,
+            block_hash: Hash256::zero(),
+        };
+        BeaconState::<E>::new(42, eth1_data, spec)
+    }
+
+    /// A network config which downloads a genesis state with the given properties.
+    fn url_network_config(
+        spec: &ChainSpec,
+        checksum: Hash256,
+        genesis_validators_root: Hash256,
+        genesis_state_root: Hash256,
+    ) -> Eth2NetworkConfig {
+        let leak = |value: Hash256| -> &'static str { Box::leak(format!("{:?}", value).into()) };
+        Eth2NetworkConfig {
+            deposit_contract_deploy_block: 0,
+            boot_enr: None,
+            checkpoint_sync_url_suggestions: None,
+            genesis_state_source: GenesisStateSource::Url {
+                urls: &[],
+                checksum: leak(checksum),
+                genesis_validators_root: leak(genesis_validators_root),
+                genesis_state_root: Some(leak(genesis_state_root)),
+            },
+            genesis_state_bytes: None,
+            config: Config::from_chain_spec::<E>(spec),
+            kzg_trusted_setup: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn genesis_state_url_fallback() {
+        let spec = E::default_spec();
+        let genesis_state = test_genesis_state(&spec);
+        let bytes = genesis_state.as_ssz_bytes();
+        let mut corrupted_bytes = bytes.clone();
+        corrupted_bytes[0] ^= 1;
+
+        let config = url_network_config(
+            &spec,
+            Hash256::from_slice(&Sha256::digest(&bytes)),
+            genesis_state.genesis_validators_root(),
+            genesis_state.canonical_root(),
+        );
+
+        let urls = vec![
+            serve_genesis_state(corrupted_bytes),
+            serve_genesis_state(bytes),
+        ];
+        let timeout = Duration::from_secs(5);
+        let log = logging::test_logger();
+        let temp_dir = TempBuilder::new()
+            .prefix("genesis_state_cache")
+            .tempdir()
+            .expect("should create temp dir");
+        let cache_path = temp_dir.path().join("genesis_state.ssz");
+
+        // The corrupted state is rejected.
+        let error = config
+            .genesis_state::<E>(&urls[..1], timeout, Some(&cache_path), &log)
+            .await
+            .unwrap_err();
+        assert!(error.contains("did not match local checksum"), "{}", error);
+        assert!(!cache_path.exists());
+
+        // The second URL is used when the first serves a corrupted state.
+        let state = config
+            .genesis_state::<E>(&urls, timeout, Some(&cache_path), &log)
+            .await
+            .unwrap()
+            .expect("genesis state should be known");
+        assert_eq!(state.canonical_root(), genesis_state.canonical_root());
+        assert!(cache_path.exists());
+
+        // The cached state is used without downloading it again.
+        let state = config
+            .genesis_state::<E>(&[], timeout, Some(&cache_path), &log)
+            .await
+            .unwrap()
+            .expect("genesis state should be known");
+        assert_eq!(state.canonical_root(), genesis_state.canonical_root());
+    }
+
+    /// A state which matches the checksum and genesis validators root is still rejected if its
+    /// state root is wrong.
+    #[tokio::test]
+    async fn genesis_state_root_mismatch() {
+        let spec = E::default_spec();
+        let genesis_state = test_genesis_state(&spec);
+
+        // Changing the genesis time changes the state root, but not the genesis validators root.
+        let mut corrupted_state = genesis_state.clone();
+        *corrupted_state.genesis_time_mut() += 1;
+        assert_eq!(
+            corrupted_state.genesis_validators_root(),
+            genesis_state.genesis_validators_root()
+        );
+        let corrupted_bytes = corrupted_state.as_ssz_bytes();
+
+        let config = url_network_config(
+            &spec,
+            Hash256::from_slice(&Sha256::digest(&corrupted_bytes)),
+            genesis_state.genesis_validators_root(),
+            genesis_state.canonical_root(),
+        );
+        let urls = vec![serve_genesis_state(corrupted_bytes)];
+
+        let error = config
+            .genesis_state::<E>(&urls, Duration::from_secs(5), None, &logging::test_logger())
+            .await
+            .unwrap_err();
+        assert!(
+            error.contains(&format!(
+                "state root {:?} does not match expected {:?}",
+                corrupted_state.canonical_root(),
+                genesis_state.canonical_root()
+            )),
+            "{}",
+            error
+        );
+    }
 }
@@ -0,0 +1,213 @@
+//! Expands graffiti templates such as `LH{version}-{slot}` at block production time.
+//!
+//! The supported variables are:
+//!
+//! - `{version}`: the short client version, e.g. `v4.5.0`.
+//! - `{slot}`: the slot of the block.
+//! - `{epoch}`: the epoch of the block.
+//! - `{validator_index}`: the index of the proposer. Only available to graffiti supplied by the
+//!   validator client.
+//!
+//! Unknown variables, and variables without a value, are left as-is.
+use std::cmp;
+use types::{Epoch, Graffiti, Slot, GRAFFITI_BYTES_LEN};
+
+/// The values of the template variables for a single block.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraffitiVariables {
+    pub slot: Slot,
+    pub epoch: Epoch,
+    pub validator_index: Option<u64>,
+}
+
+/// Expands the variables in `graffiti`, truncating the result to fit in a `Graffiti`.
+///
+/// Graffiti which is not valid UTF-8 or has no variables is returned unchanged.
+pub fn expand_graffiti(graffiti: Graffiti, variables: &GraffitiVariables) -> Graffiti {
+    let len = graffiti
+        .0
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |i| i + 1);
+    let Ok(template) = std::str::from_utf8(&graffiti.0[..len]) else {
+        return graffiti;
+    };
+    if !template.contains('{') {
+        return graffiti;
+    }
+
+    truncate_graffiti(&expand_template(template, variables))
+}
+
+/// Replaces each variable in `template` with its value.
+pub fn expand_template(template: &str, variables: &GraffitiVariables) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let variable = rest
+            .find('}')
+            .and_then(|end| variable_value(&rest[1..end], variables).map(|value| (end, value)));
+        match variable {
+            Some((end, value)) => {
+                expanded.push_str(&value);
+                rest = &rest[end + 1..];
+            }
+            // Keep the brace, a variable may still start after it (e.g. `{{slot}`).
+            None => {
+                expanded.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+
+    expanded
+}
+
+fn variable_value(name: &str, variables: &GraffitiVariables) -> Option<String> {
+    match name {
+        "version" => Some(lighthouse_version::short_version().to_string()),
+        "slot" => Some(variables.slot.to_string()),
+        "epoch" => Some(variables.epoch.to_string()),
+        "validator_index" => variables.validator_index.map(|index| index.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts `s` to a `Graffiti`, truncating it to the longest prefix which fits in
+/// `GRAFFITI_BYTES_LEN` bytes without splitting a character.
+pub fn truncate_graffiti(s: &str) -> Graffiti {
+    let mut len = cmp::min(s.len(), GRAFFITI_BYTES_LEN);
+    while !s.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    let mut graffiti = [0; GRAFFITI_BYTES_LEN];
+    graffiti[..len].copy_from_slice(&s.as_bytes()[..len]);
+    Graffiti::from(graffiti)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables(validator_index: Option<u64>) -> GraffitiVariables {
+        GraffitiVariables {
+            slot: Slot::new(7_654_321),
+            epoch: Epoch::new(239_197),
+            validator_index,
+        }
+    }
+
+    fn graffiti(s: &str) -> Graffiti {
+        truncate_graffiti(s)
+    }
+
+    fn graffiti_str(graffiti: &Graffiti) -> &str {
+        let len = graffiti
+            .0
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |i| i + 1);
+        std::str::from_utf8(&graffiti.0[..len]).expect("graffiti is valid UTF-8")
+    }
+
+    #[test]
+    fn expands_variables() {
+        assert_eq!(
+            expand_template(
+                "LH{version}-{slot}-{epoch}-{validator_index}",
+                &variables(Some(42))
+            ),
+            format!(
+                "LH{}-7654321-239197-42",
+                lighthouse_version::short_version()
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_and_missing_variables() {
+        let vars = variables(None);
+        assert_eq!(
+            expand_template("{validator_index}", &vars),
+            "{validator_index}"
+        );
+        assert_eq!(expand_template("{unknown}", &vars), "{unknown}");
+        assert_eq!(expand_template("{slot", &vars), "{slot");
+        assert_eq!(expand_template("slot}", &vars), "slot}");
+        assert_eq!(expand_template("{}", &vars), "{}");
+        assert_eq!(expand_template("{{slot}}", &vars), "{7654321}");
+    }
+
+    #[test]
+    fn graffiti_without_variables_is_unchanged() {
+        let vars = variables(Some(1));
+        for g in [
+            graffiti(""),
+            graffiti("Lighthouse/v4.5.0-67da032"),
+            graffiti("{unknown}"),
+            Graffiti::from([0xff; GRAFFITI_BYTES_LEN]),
+        ] {
+            assert_eq!(expand_graffiti(g, &vars), g);
+        }
+    }
+
+    #[test]
+    fn expands_graffiti() {
+        let expanded = expand_graffiti(graffiti("slot {slot}"), &variables(None));
+        assert_eq!(graffiti_str(&expanded), "slot 7654321");
+    }
+
+    #[test]
+    fn expanded_graffiti_is_truncated() {
+        let expanded =
+            expand_graffiti(graffiti("{slot}{slot}{slot}{slot}{slot}"), &variables(None));
+        assert_eq!(graffiti_str(&expanded), "76543217654321765432176543217654");
+    }
+
+    #[test]
+    fn truncates_on_char_boundaries() {
+        // Characters which are 1, 2, 3 and 4 bytes long in UTF-8.
+        for c in ['a', 'é', '€', '🦀'] {
+            // Place the character at every offset around the end of the graffiti.
+            for prefix_len in GRAFFITI_BYTES_LEN - 4..=GRAFFITI_BYTES_LEN {
+                let s = format!("{}{}{}", "x".repeat(prefix_len), c, c);
+                let truncated = truncate_graffiti(&s);
+                let truncated_str = graffiti_str(&truncated);
+
+                // The result is the longest prefix of whole characters which fits.
+                let expected_len = s
+                    .char_indices()
+                    .map(|(i, c)| i + c.len_utf8())
+                    .take_while(|end| *end <= GRAFFITI_BYTES_LEN)
+                    .last()
+                    .unwrap_or(0);
+                assert_eq!(truncated_str, &s[..expected_len], "{:?}", s);
+                assert!(
+                    s.len() <= GRAFFITI_BYTES_LEN
+                        || GRAFFITI_BYTES_LEN - expected_len < c.len_utf8()
+                );
+                assert!(truncated.0[expected_len..].iter().all(|byte| *byte == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn truncates_multi_byte_string() {
+        // 11 three-byte characters are 33 bytes long, so the last one is dropped.
+        let s = "€".repeat(11);
+        assert_eq!(graffiti_str(&truncate_graffiti(&s)), "€".repeat(10));
+    }
+
+    #[test]
+    fn short_strings_are_not_truncated() {
+        for s in ["", "a", "🦀", "x".repeat(GRAFFITI_BYTES_LEN).as_str()] {
+            assert_eq!(graffiti_str(&truncate_graffiti(s)), s);
+        }
+    }
+}
@@ -30,6 +30,16 @@ pub fn version_with_platform() -> String {
     format!("{}/{}-{}", VERSION, Target::arch(), Target::os())
 }
 
+/// Returns the release of `VERSION`, without the client name or commit.
+///
+/// ## Example
+///
+/// `v1.5.1`
+pub fn short_version() -> &'static str {
+    let version = VERSION.strip_prefix("Lighthouse/").unwrap_or(VERSION);
+    version.split('-').next().unwrap_or(version)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -46,4 +56,14 @@ mod test {
             VERSION
         );
     }
+
+    #[test]
+    fn short_version_formatting() {
+        let re = Regex::new(r"^v[0-9]+\.[0-9]+\.[0-9]+$").unwrap();
+        assert!(
+            re.is_match(short_version()),
+            "short version doesn't match regex: {}",
+            short_version()
+        );
+    }
 }
@@ -0,0 +1,306 @@
+//! Provides the `TargetFilter` drain, which drops records below the level configured for the module
+//! that emitted them.
+//!
+//! Filters are written as comma-separated directives, e.g. `sync=debug,libp2p_gossipsub=warn,info`.
+//! A directive of the form `target=level` applies to every module whose path contains `target` as
+//! a run of whole `::`-separated segments, so `sync` matches `network::sync::manager`. When several
+//! targets match a module the most specific one wins. A bare `level` sets the level used for
+//! modules without a matching target.
+//!
+//! Filters are shared through a `LogFilterHandle`, so they can be changed while the node is running.
+
+use parking_lot::RwLock;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+/// Parses a level as accepted by the `--debug-level` flag.
+pub fn parse_level(level: &str) -> Option<Level> {
+    match level {
+        "info" => Some(Level::Info),
+        "debug" => Some(Level::Debug),
+        "trace" => Some(Level::Trace),
+        "warn" => Some(Level::Warning),
+        "error" => Some(Level::Error),
+        "crit" => Some(Level::Critical),
+        _ => None,
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Info => "info",
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+        Level::Warning => "warn",
+        Level::Error => "error",
+        Level::Critical => "crit",
+    }
+}
+
+/// A default level along with per-target overrides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default_level: Level,
+    targets: BTreeMap<String, Level>,
+}
+
+impl LogFilter {
+    pub fn new(default_level: Level) -> Self {
+        Self {
+            default_level,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Parses `directives` on top of a filter which uses `default_level` for every module.
+    pub fn parse(directives: &str, default_level: Level) -> Result<Self, String> {
+        let mut filter = Self::new(default_level);
+        filter.apply(directives)?;
+        Ok(filter)
+    }
+
+    /// Merges `directives` into the filter, replacing the levels of any targets they mention.
+    ///
+    /// The filter is left unchanged if any directive is invalid.
+    pub fn apply(&mut self, directives: &str) -> Result<(), String> {
+        let mut updated = self.clone();
+        for directive in directives
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+        {
+            let (target, level) = match directive.split_once('=') {
+                Some((target, level)) => (Some(target.trim()), level.trim()),
+                None => (None, directive),
+            };
+            let level = parse_level(level)
+                .ok_or_else(|| format!("Unknown level in log filter directive: {}", directive))?;
+            match target {
+                Some(target) => {
+                    if target.is_empty() || target.split("::").any(str::is_empty) {
+                        return Err(format!(
+                            "Invalid target in log filter directive: {}",
+                            directive
+                        ));
+                    }
+                    updated.targets.insert(target.to_string(), level);
+                }
+                None => updated.default_level = level,
+            }
+        }
+        *self = updated;
+        Ok(())
+    }
+
+    /// Returns the minimum level of records from `module` which pass the filter.
+    pub fn level_for(&self, module: &str) -> Level {
+        let segments: Vec<&str> = module.split("::").collect();
+        self.targets
+            .iter()
+            .filter_map(|(target, level)| {
+                let target_segments: Vec<&str> = target.split("::").collect();
+                segments
+                    .windows(target_segments.len())
+                    .any(|window| window == target_segments.as_slice())
+                    .then_some((target_segments.len(), target.len(), *level))
+            })
+            .max_by_key(|(segments, len, _)| (*segments, *len))
+            .map_or(self.default_level, |(_, _, level)| level)
+    }
+
+    /// Returns `true` if a record from `module` at `level` passes the filter.
+    pub fn is_enabled(&self, module: &str, level: Level) -> bool {
+        level.is_at_least(self.level_for(module))
+    }
+
+    /// Returns the most verbose level which any module may be logged at.
+    fn most_verbose_level(&self) -> Level {
+        self.targets
+            .values()
+            .copied()
+            .fold(self.default_level, |most_verbose, level| {
+                if most_verbose.is_at_least(level) {
+                    level
+                } else {
+                    most_verbose
+                }
+            })
+    }
+}
+
+/// Formats the filter as directives which parse back to the same filter.
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", level_name(self.default_level))?;
+        for (target, level) in &self.targets {
+            write!(f, ",{}={}", target, level_name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+/// A shared, mutable `LogFilter`.
+///
+/// Drains must be unwind safe, which is asserted here because a filter is never left partially
+/// updated.
+#[derive(Debug, Clone)]
+pub struct LogFilterHandle(Arc<AssertUnwindSafe<RwLock<LogFilter>>>);
+
+impl LogFilterHandle {
+    pub fn new(filter: LogFilter) -> Self {
+        Self(Arc::new(AssertUnwindSafe(RwLock::new(filter))))
+    }
+
+    /// Returns a copy of the current filter.
+    pub fn get(&self) -> LogFilter {
+        self.0.read().clone()
+    }
+
+    /// Merges `directives` into the filter. See `LogFilter::apply`.
+    pub fn apply(&self, directives: &str) -> Result<(), String> {
+        self.0.write().apply(directives)
+    }
+}
+
+/// The filters applied to each of the drains of the logger.
+#[derive(Debug, Clone)]
+pub struct LogFilters {
+    pub terminal: LogFilterHandle,
+    /// `None` if file logging is disabled.
+    pub file: Option<LogFilterHandle>,
+}
+
+/// A drain which only passes on records allowed by a `LogFilterHandle`.
+pub struct TargetFilter<D> {
+    drain: D,
+    filter: LogFilterHandle,
+}
+
+impl<D> TargetFilter<D> {
+    pub fn new(drain: D, filter: LogFilterHandle) -> Self {
+        Self { drain, filter }
+    }
+}
+
+impl<D: Drain> Drain for TargetFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self
+            .filter
+            .0
+            .read()
+            .is_enabled(record.module(), record.level())
+        {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_enabled(&self, level: Level) -> bool {
+        level.is_at_least(self.filter.0.read().most_verbose_level()) && self.drain.is_enabled(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use slog::{debug, info, o, trace, warn, Logger, Never};
+
+    /// Records the message of every record it receives.
+    #[derive(Clone, Default)]
+    struct Collector(Arc<Mutex<Vec<String>>>);
+
+    impl Drain for Collector {
+        type Ok = ();
+        type Err = Never;
+
+        fn log(&self, record: &Record, _: &OwnedKVList) -> Result<(), Never> {
+            self.0.lock().push(record.msg().to_string());
+            Ok(())
+        }
+    }
+
+    fn logger(directives: &str) -> (Logger, Collector, LogFilterHandle) {
+        let collector = Collector::default();
+        let handle = LogFilterHandle::new(LogFilter::parse(directives, Level::Info).unwrap());
+        let drain = TargetFilter::new(collector.clone(), handle.clone());
+        (Logger::root(drain.fuse(), o!()), collector, handle)
+    }
+
+    #[test]
+    fn drops_records_below_target_level() {
+        // This module is `logging::log_filter::tests`.
+        let (log, collector, _) = logger("log_filter=warn");
+
+        info!(log, "info");
+        warn!(log, "warn");
+        assert_eq!(*collector.0.lock(), vec!["warn"]);
+    }
+
+    #[test]
+    fn most_specific_target_wins() {
+        let (log, collector, _) = logger("logging=warn,log_filter::tests=trace,crit");
+
+        trace!(log, "trace");
+        assert_eq!(*collector.0.lock(), vec!["trace"]);
+
+        let filter =
+            LogFilter::parse("logging=warn,log_filter::tests=trace,crit", Level::Info).unwrap();
+        assert_eq!(filter.level_for("logging::log_filter::tests"), Level::Trace);
+        assert_eq!(
+            filter.level_for("logging::sse_logging_components"),
+            Level::Warning
+        );
+        assert_eq!(filter.level_for("network::sync"), Level::Critical);
+        // Targets only match whole segments.
+        assert_eq!(filter.level_for("logging_extra"), Level::Critical);
+    }
+
+    #[test]
+    fn unmatched_modules_use_default_level() {
+        let (log, collector, _) = logger("network=trace");
+
+        debug!(log, "debug");
+        info!(log, "info");
+        assert_eq!(*collector.0.lock(), vec!["info"]);
+    }
+
+    #[test]
+    fn updates_apply_to_existing_drain() {
+        let (log, collector, handle) = logger("");
+
+        debug!(log, "before");
+        handle.apply("tests=debug").unwrap();
+        debug!(log, "after");
+        assert_eq!(*collector.0.lock(), vec!["after"]);
+        assert_eq!(handle.get().to_string(), "info,tests=debug");
+    }
+
+    #[test]
+    fn invalid_directives_leave_filter_unchanged() {
+        let handle = LogFilterHandle::new(LogFilter::new(Level::Info));
+
+        assert!(handle.apply("sync=debug,network=loud").is_err());
+        assert!(handle.apply("=debug").is_err());
+        assert!(handle.apply("sync::=debug").is_err());
+        assert_eq!(handle.get(), LogFilter::new(Level::Info));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let filter =
+            LogFilter::parse("sync=debug, libp2p_gossipsub=warn, error", Level::Info).unwrap();
+        assert_eq!(filter.to_string(), "error,libp2p_gossipsub=warn,sync=debug");
+        assert_eq!(
+            LogFilter::parse(&filter.to_string(), Level::Info).unwrap(),
+            filter
+        );
+    }
+}
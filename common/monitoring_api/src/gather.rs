@@ -1,6 +1,5 @@
 use super::types::{BeaconProcessMetrics, ValidatorProcessMetrics};
-use lazy_static::lazy_static;
-use lighthouse_metrics::{MetricFamily, MetricType};
+use lighthouse_metrics::{Metric, MetricFamily, MetricType};
 use serde_json::json;
 use std::collections::HashMap;
 use std::path::Path;
@@ -15,6 +14,10 @@ pub struct JsonMetric {
     json_output_key: &'static str,
     /// Type of the json value to be sent to the remote monitoring endpoint
     ty: JsonType,
+    /// Only count the series of the metric with this `(name, value)` label pair.
+    label: Option<(&'static str, &'static str)>,
+    /// Omit the metric rather than sending a default value if it does not exist.
+    optional: bool,
 }
 
 impl JsonMetric {
@@ -27,30 +30,52 @@ impl JsonMetric {
             lighthouse_metric_name,
             json_output_key,
             ty,
+            label: None,
+            optional: false,
         }
     }
 
+    /// A metric which is omitted from the output if it does not exist.
+    const fn optional(
+        lighthouse_metric_name: &'static str,
+        json_output_key: &'static str,
+        ty: JsonType,
+    ) -> Self {
+        Self {
+            lighthouse_metric_name,
+            json_output_key,
+            ty,
+            label: None,
+            optional: true,
+        }
+    }
+
+    /// Restrict the metric to the series with the given label.
+    const fn with_label(mut self, name: &'static str, value: &'static str) -> Self {
+        self.label = Some((name, value));
+        self
+    }
+
     /// Return a json value given given the metric type.
-    fn get_typed_value(&self, value: i64) -> serde_json::Value {
+    fn get_typed_value(&self, mf: &MetricFamily) -> Option<serde_json::Value> {
         match self.ty {
-            JsonType::Integer => json!(value),
-            JsonType::Boolean => {
-                if value > 0 {
-                    json!(true)
-                } else {
-                    json!(false)
-                }
+            JsonType::Integer => Some(json!(get_value(mf, self.label).unwrap_or_default())),
+            JsonType::Boolean => Some(json!(get_value(mf, self.label).unwrap_or_default() > 0)),
+            JsonType::LabelValue(label_name) => {
+                get_label_value(mf, label_name).map(|value| json!(value))
             }
         }
     }
 
     /// Return a default json value given given the metric type.
-    fn get_typed_value_default(&self) -> serde_json::Value {
+    fn get_typed_value_default(&self) -> Option<serde_json::Value> {
+        if self.optional {
+            return None;
+        }
         match self.ty {
-            JsonType::Integer => json!(0),
-            JsonType::Boolean => {
-                json!(false)
-            }
+            JsonType::Integer => Some(json!(0)),
+            JsonType::Boolean => Some(json!(false)),
+            JsonType::LabelValue(_) => Some(serde_json::Value::Null),
         }
     }
 }
@@ -94,6 +119,33 @@ const BEACON_PROCESS_METRICS: &[JsonMetric] = &[
         JsonType::Integer,
     ),
     JsonMetric::new("sync_eth2_synced", "sync_eth2_synced", JsonType::Boolean),
+    JsonMetric::optional(
+        "beacon_epoch_timings_previous_epoch_blobs",
+        "blobs_imported_previous_epoch",
+        JsonType::Integer,
+    ),
+    JsonMetric::optional(
+        "beacon_data_availability_check_failures_total",
+        "data_availability_check_failures_total",
+        JsonType::Integer,
+    ),
+    JsonMetric::optional(
+        "execution_layer_get_payload_source",
+        "payloads_builder_total",
+        JsonType::Integer,
+    )
+    .with_label("source", "builder"),
+    JsonMetric::optional(
+        "execution_layer_get_payload_source",
+        "payloads_local_total",
+        JsonType::Integer,
+    )
+    .with_label("source", "local"),
+    JsonMetric::optional(
+        "execution_layer_last_builder_fallback",
+        "builder_last_fallback_reason",
+        JsonType::LabelValue("reason"),
+    ),
 ];
 
 const VALIDATOR_PROCESS_METRICS: &[JsonMetric] = &[
@@ -124,58 +176,75 @@ const VALIDATOR_PROCESS_METRICS: &[JsonMetric] = &[
 pub enum JsonType {
     Integer,
     Boolean,
+    /// The value of the named label on the series which is set, e.g. the reason recorded by a
+    /// gauge vec which flags the most recent of several outcomes.
+    LabelValue(&'static str),
 }
 
-lazy_static! {
-    /// HashMap representing the `BEACON_PROCESS_METRICS`.
-    pub static ref BEACON_METRICS_MAP: HashMap<String, JsonMetric> = BEACON_PROCESS_METRICS
-        .iter()
-        .map(|metric| (metric.lighthouse_metric_name.to_string(), metric.clone()))
-        .collect();
-    /// HashMap representing the `VALIDATOR_PROCESS_METRICS`.
-    pub static ref VALIDATOR_METRICS_MAP: HashMap<String,JsonMetric> =
-        VALIDATOR_PROCESS_METRICS
+/// Returns whether `metric` has the given label, or `true` if `label` is `None`.
+fn has_label(metric: &Metric, label: Option<(&str, &str)>) -> bool {
+    label.map_or(true, |(name, value)| {
+        metric
+            .get_label()
+            .iter()
+            .any(|pair| pair.get_name() == name && pair.get_value() == value)
+    })
+}
+
+/// Returns the sum of the values of the series of a Counter/Gauge `MetricType` which match
+/// `label`, or `None` if there are no such series.
+fn get_value(mf: &MetricFamily, label: Option<(&str, &str)>) -> Option<i64> {
+    let mut values = mf
+        .get_metric()
         .iter()
-        .map(|metric| (metric.lighthouse_metric_name.to_string(), metric.clone()))
-        .collect();
+        .filter(|metric| has_label(metric, label))
+        .map(|metric| match mf.get_field_type() {
+            MetricType::COUNTER => Some(metric.get_counter().get_value() as i64),
+            MetricType::GAUGE => Some(metric.get_gauge().get_value() as i64),
+            _ => None,
+        })
+        .peekable();
+    values.peek()?;
+    values.sum()
 }
 
-/// Returns the value from a Counter/Gauge `MetricType` assuming that it has no associated labels
-/// else it returns `None`.
-fn get_value(mf: &MetricFamily) -> Option<i64> {
-    let metric = mf.get_metric().first()?;
-    match mf.get_field_type() {
-        MetricType::COUNTER => Some(metric.get_counter().get_value() as i64),
-        MetricType::GAUGE => Some(metric.get_gauge().get_value() as i64),
-        _ => None,
+/// Returns the value of the label `label_name` of the first series of a Gauge `MetricType` with
+/// a positive value.
+fn get_label_value(mf: &MetricFamily, label_name: &str) -> Option<String> {
+    if mf.get_field_type() != MetricType::GAUGE {
+        return None;
     }
+    mf.get_metric()
+        .iter()
+        .find(|metric| metric.get_gauge().get_value() > 0.0)?
+        .get_label()
+        .iter()
+        .find(|pair| pair.get_name() == label_name)
+        .map(|pair| pair.get_value().to_string())
 }
 
 /// Collects all metrics and returns a `serde_json::Value` object with the required metrics
-/// from the metrics hashmap.
-pub fn gather_metrics(metrics_map: &HashMap<String, JsonMetric>) -> Option<serde_json::Value> {
+/// from the given list.
+pub fn gather_metrics(json_metrics: &[JsonMetric]) -> serde_json::Value {
     let metric_families = lighthouse_metrics::gather();
-    let mut res = serde_json::Map::with_capacity(metrics_map.len());
-    for mf in metric_families.iter() {
-        let metric_name = mf.get_name();
-        if metrics_map.contains_key(metric_name) {
-            let value = get_value(mf).unwrap_or_default();
-            let metric = metrics_map.get(metric_name)?;
-            let value = metric.get_typed_value(value);
-            let _ = res.insert(metric.json_output_key.to_string(), value);
-        };
-    }
-    // Insert default metrics for all monitoring service metrics that do not
-    // exist as lighthouse metrics.
-    for json_metric in metrics_map.values() {
-        if !res.contains_key(json_metric.json_output_key) {
-            let _ = res.insert(
-                json_metric.json_output_key.to_string(),
-                json_metric.get_typed_value_default(),
-            );
+    let families = metric_families
+        .iter()
+        .map(|mf| (mf.get_name(), mf))
+        .collect::<HashMap<_, _>>();
+
+    let mut res = serde_json::Map::with_capacity(json_metrics.len());
+    for json_metric in json_metrics {
+        // Insert default values for monitoring service metrics that do not exist as lighthouse
+        // metrics.
+        let value = families
+            .get(json_metric.lighthouse_metric_name)
+            .and_then(|mf| json_metric.get_typed_value(mf))
+            .or_else(|| json_metric.get_typed_value_default());
+        if let Some(value) = value {
+            res.insert(json_metric.json_output_key.to_string(), value);
         }
     }
-    Some(serde_json::Value::Object(res))
+    serde_json::Value::Object(res)
 }
 
 /// Gathers and returns the lighthouse beacon metrics.
@@ -186,8 +255,7 @@ pub fn gather_beacon_metrics(
     // Update db size metrics
     store::metrics::scrape_for_metrics(db_path, freezer_db_path);
 
-    let beacon_metrics = gather_metrics(&BEACON_METRICS_MAP)
-        .ok_or_else(|| "Failed to gather beacon metrics".to_string())?;
+    let beacon_metrics = gather_metrics(BEACON_PROCESS_METRICS);
     let process = eth2::lighthouse::ProcessHealth::observe()?.into();
 
     Ok(BeaconProcessMetrics {
@@ -198,12 +266,126 @@ pub fn gather_beacon_metrics(
 
 /// Gathers and returns the lighthouse validator metrics.
 pub fn gather_validator_metrics() -> Result<ValidatorProcessMetrics, String> {
-    let validator_metrics = gather_metrics(&VALIDATOR_METRICS_MAP)
-        .ok_or_else(|| "Failed to gather validator metrics".to_string())?;
+    let validator_metrics = gather_metrics(VALIDATOR_PROCESS_METRICS);
 
     let process = eth2::lighthouse::ProcessHealth::observe()?.into();
     Ok(ValidatorProcessMetrics {
         validator: validator_metrics,
+        validator_effectiveness: None,
         common: process,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_metrics::{
+        try_create_int_counter_vec, try_create_int_gauge, try_create_int_gauge_vec,
+    };
+
+    #[test]
+    fn gather_labelled_metrics() {
+        let sources = try_create_int_counter_vec(
+            "monitoring_api_test_payload_source",
+            "Test counter vec",
+            &["source"],
+        )
+        .unwrap();
+        sources.with_label_values(&["builder"]).inc_by(3);
+        sources.with_label_values(&["local"]).inc();
+        let fallback = try_create_int_gauge_vec(
+            "monitoring_api_test_last_fallback",
+            "Test gauge vec",
+            &["reason"],
+        )
+        .unwrap();
+        fallback.with_label_values(&["no_bid"]).set(0);
+        fallback.with_label_values(&["bid_invalid"]).set(1);
+
+        let json_metrics = [
+            JsonMetric::optional(
+                "monitoring_api_test_payload_source",
+                "builder",
+                JsonType::Integer,
+            )
+            .with_label("source", "builder"),
+            JsonMetric::new(
+                "monitoring_api_test_payload_source",
+                "all",
+                JsonType::Integer,
+            ),
+            JsonMetric::optional(
+                "monitoring_api_test_last_fallback",
+                "reason",
+                JsonType::LabelValue("reason"),
+            ),
+            JsonMetric::new("monitoring_api_test_missing", "missing", JsonType::Boolean),
+            JsonMetric::optional(
+                "monitoring_api_test_missing",
+                "missing_optional",
+                JsonType::Integer,
+            ),
+        ];
+        assert_eq!(
+            gather_metrics(&json_metrics),
+            json!({
+                "builder": 3,
+                "all": 4,
+                "reason": "bid_invalid",
+                "missing": false,
+            })
+        );
+    }
+
+    #[test]
+    fn gather_beacon_metrics_from_registry() {
+        try_create_int_gauge("sync_eth2_synced", "Test synced gauge")
+            .unwrap()
+            .set(1);
+        try_create_int_gauge(
+            "beacon_epoch_timings_previous_epoch_blobs",
+            "Test blobs gauge",
+        )
+        .unwrap()
+        .set(96);
+        let sources = try_create_int_counter_vec(
+            "execution_layer_get_payload_source",
+            "Test payload source counter vec",
+            &["source"],
+        )
+        .unwrap();
+        sources.with_label_values(&["builder"]).inc_by(3);
+        sources.with_label_values(&["local"]).inc_by(2);
+        let fallback = try_create_int_gauge_vec(
+            "execution_layer_last_builder_fallback",
+            "Test fallback gauge vec",
+            &["reason"],
+        )
+        .unwrap();
+        fallback.with_label_values(&["no_bid"]).set(1);
+        fallback.reset();
+        fallback
+            .with_label_values(&["local_more_profitable"])
+            .set(1);
+
+        let db_path = std::env::temp_dir();
+        let metrics = gather_beacon_metrics(&db_path, &db_path).unwrap();
+        let encoded = serde_json::to_value(&metrics).unwrap();
+
+        assert_eq!(encoded["client_name"], json!("lighthouse"));
+        assert_eq!(encoded["sync_eth2_synced"], json!(true));
+        assert_eq!(encoded["blobs_imported_previous_epoch"], json!(96));
+        assert_eq!(encoded["payloads_builder_total"], json!(3));
+        assert_eq!(encoded["payloads_local_total"], json!(2));
+        assert_eq!(
+            encoded["builder_last_fallback_reason"],
+            json!("local_more_profitable")
+        );
+        // Required metrics which are not registered are sent with a default value.
+        assert_eq!(encoded["network_peers_connected"], json!(0));
+        // Optional metrics which are not registered are omitted.
+        assert!(encoded
+            .get("data_availability_check_failures_total")
+            .is_none());
+    }
+}
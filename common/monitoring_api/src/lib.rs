@@ -1,14 +1,18 @@
 mod gather;
 mod types;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 use std::{path::PathBuf, time::Duration};
 
-use eth2::lighthouse::SystemHealth;
+use eth2::lighthouse::{SystemHealth, ValidatorMetrics};
 use gather::{gather_beacon_metrics, gather_validator_metrics};
 use reqwest::{IntoUrl, Response};
 pub use reqwest::{StatusCode, Url};
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
-use slog::{debug, error, info};
+use slog::{debug, error, info, warn};
 use task_executor::TaskExecutor;
 use tokio::time::{interval_at, Instant};
 use types::*;
@@ -59,6 +63,15 @@ pub struct Config {
     pub update_period_secs: Option<u64>,
 }
 
+/// Fetches the attestation effectiveness of the validators of the validator client, keyed by
+/// validator index.
+pub type ValidatorEffectivenessSource = Arc<
+    dyn Fn() -> Pin<
+            Box<dyn Future<Output = Result<HashMap<String, ValidatorMetrics>, String>> + Send>,
+        > + Send
+        + Sync,
+>;
+
 #[derive(Clone)]
 pub struct MonitoringHttpClient {
     client: reqwest::Client,
@@ -68,6 +81,8 @@ pub struct MonitoringHttpClient {
     freezer_db_path: Option<PathBuf>,
     update_period: Duration,
     monitoring_endpoint: SensitiveUrl,
+    /// Source of the validator effectiveness included in the validator metrics, if any.
+    validator_effectiveness: Option<ValidatorEffectivenessSource>,
     log: slog::Logger,
 }
 
@@ -82,10 +97,17 @@ impl MonitoringHttpClient {
             ),
             monitoring_endpoint: SensitiveUrl::parse(&config.monitoring_endpoint)
                 .map_err(|e| format!("Invalid monitoring endpoint: {:?}", e))?,
+            validator_effectiveness: None,
             log,
         })
     }
 
+    /// Include the validator effectiveness returned by `source` in the validator metrics.
+    pub fn with_validator_effectiveness(mut self, source: ValidatorEffectivenessSource) -> Self {
+        self.validator_effectiveness = Some(source);
+        self
+    }
+
     /// Perform a HTTP POST request.
     async fn post<T: Serialize, U: IntoUrl>(&self, url: U, body: &T) -> Result<(), Error> {
         let response = self
@@ -151,8 +173,20 @@ impl MonitoringHttpClient {
     }
 
     /// Gets validator process metrics by querying the validator metrics endpoint
-    pub fn get_validator_metrics(&self) -> Result<MonitoringMetrics, Error> {
-        let metrics = gather_validator_metrics().map_err(Error::BeaconMetricsFailed)?;
+    ///
+    /// The validator effectiveness is omitted if it cannot be fetched.
+    pub async fn get_validator_metrics(&self) -> Result<MonitoringMetrics, Error> {
+        let mut metrics = gather_validator_metrics().map_err(Error::BeaconMetricsFailed)?;
+        if let Some(source) = &self.validator_effectiveness {
+            match source().await {
+                Ok(effectiveness) => metrics.validator_effectiveness = Some(effectiveness),
+                Err(e) => warn!(
+                    self.log,
+                    "Unable to fetch validator effectiveness";
+                    "error" => e
+                ),
+            }
+        }
         Ok(MonitoringMetrics {
             metadata: Metadata::new(ProcessType::Validator),
             process_metrics: Process::Validator(metrics),
@@ -176,7 +210,7 @@ impl MonitoringHttpClient {
         match process_type {
             ProcessType::BeaconNode => self.get_beacon_metrics(),
             ProcessType::System => self.get_system_metrics(),
-            ProcessType::Validator => self.get_validator_metrics(),
+            ProcessType::Validator => self.get_validator_metrics().await,
         }
     }
 
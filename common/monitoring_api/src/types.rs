@@ -1,9 +1,14 @@
+use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use eth2::lighthouse::{ProcessHealth, SystemHealth};
+use eth2::lighthouse::{ProcessHealth, SystemHealth, ValidatorMetrics};
 use serde::{Deserialize, Serialize};
 
-pub const VERSION: u64 = 1;
+/// The version of the JSON schema.
+///
+/// Version 2 adds the optional blob and builder statistics of the beacon process and the
+/// `validator_effectiveness` of the validator process.
+pub const VERSION: u64 = 2;
 pub const CLIENT_NAME: &str = "lighthouse";
 
 /// An API error serializable to JSON.
@@ -159,6 +164,10 @@ pub struct BeaconProcessMetrics {
 pub struct ValidatorProcessMetrics {
     #[serde(flatten)]
     pub common: ProcessMetrics,
+    /// Attestation effectiveness from the beacon node's validator monitor, keyed by validator
+    /// index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator_effectiveness: Option<HashMap<String, ValidatorMetrics>>,
     #[serde(flatten)]
     pub validator: serde_json::Value,
 }
@@ -175,3 +184,62 @@ fn client_version() -> Option<String> {
 fn client_build() -> u64 {
     0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn validator_metrics() -> ValidatorMetrics {
+        ValidatorMetrics {
+            attestation_hits: 9,
+            attestation_misses: 1,
+            attestation_hit_percentage: 90.0,
+            attestation_head_hits: 8,
+            attestation_head_misses: 2,
+            attestation_head_hit_percentage: 80.0,
+            attestation_target_hits: 9,
+            attestation_target_misses: 1,
+            attestation_target_hit_percentage: 90.0,
+            latest_attestation_inclusion_distance: 1,
+        }
+    }
+
+    #[test]
+    fn metadata_version() {
+        let metadata = serde_json::to_value(Metadata::new(ProcessType::BeaconNode)).unwrap();
+        assert_eq!(metadata["version"], json!(VERSION));
+        assert_eq!(metadata["process"], json!("beaconnode"));
+    }
+
+    #[test]
+    fn validator_metrics_effectiveness() {
+        let metrics = ValidatorProcessMetrics {
+            common: ProcessMetrics::default(),
+            validator_effectiveness: Some(HashMap::from([("42".to_string(), validator_metrics())])),
+            validator: json!({ "validator_total": 1 }),
+        };
+        let encoded = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(
+            encoded["validator_effectiveness"]["42"]["attestation_hits"],
+            json!(9)
+        );
+        assert_eq!(encoded["validator_total"], json!(1));
+        assert_eq!(
+            serde_json::from_value::<ValidatorProcessMetrics>(encoded).unwrap(),
+            metrics
+        );
+
+        // The field is omitted when the effectiveness is unavailable.
+        let metrics = ValidatorProcessMetrics {
+            validator_effectiveness: None,
+            ..metrics
+        };
+        let encoded = serde_json::to_value(&metrics).unwrap();
+        assert!(encoded.get("validator_effectiveness").is_none());
+        assert_eq!(
+            serde_json::from_value::<ValidatorProcessMetrics>(encoded).unwrap(),
+            metrics
+        );
+    }
+}
@@ -69,6 +69,9 @@ impl FromStr for SensitiveUrl {
     }
 }
 
+/// The scheme of URLs which address a Unix domain socket by its path, e.g. `unix:///path/bn.sock`.
+const UNIX_SCHEME: &str = "unix";
+
 impl SensitiveUrl {
     pub fn parse(url: &str) -> Result<Self, SensitiveError> {
         let surl = Url::parse(url).map_err(SensitiveError::ParseError)?;
@@ -77,13 +80,20 @@ impl SensitiveUrl {
 
     pub fn new(full: Url) -> Result<Self, SensitiveError> {
         let mut redacted = full.clone();
-        redacted
-            .path_segments_mut()
-            .map_err(|_| SensitiveError::InvalidUrl("URL cannot be a base.".to_string()))?
-            .clear();
+        {
+            let mut path_segments = redacted
+                .path_segments_mut()
+                .map_err(|_| SensitiveError::InvalidUrl("URL cannot be a base.".to_string()))?;
+            // The path of a `unix://` URL is the address of the socket, rather than a resource on
+            // the server which might contain secrets, so it is kept.
+            if full.scheme() != UNIX_SCHEME {
+                path_segments.clear();
+            }
+        }
         redacted.set_query(None);
 
-        if redacted.has_authority() {
+        // URLs with an empty host, like `unix:///path`, can't contain credentials.
+        if !redacted.username().is_empty() || redacted.password().is_some() {
             redacted.set_username("").map_err(|_| {
                 SensitiveError::RedactError("Unable to redact username.".to_string())
             })?;
@@ -117,4 +127,15 @@ mod tests {
         assert_eq!(surl.to_string(), "http://localhost:5052/");
         assert_eq!(surl.full.to_string(), full);
     }
+    #[test]
+    fn redact_unix_socket_url() {
+        let full = "unix:///run/lighthouse/bn.sock";
+        let surl = SensitiveUrl::parse(full).unwrap();
+        assert_eq!(surl.to_string(), full);
+        assert_eq!(surl.full.to_string(), full);
+
+        let full = "unix:///run/lighthouse/bn.sock?somequery";
+        let surl = SensitiveUrl::parse(full).unwrap();
+        assert_eq!(surl.to_string(), "unix:///run/lighthouse/bn.sock");
+    }
 }
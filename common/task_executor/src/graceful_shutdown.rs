@@ -0,0 +1,216 @@
+//! Coordinates the work that must complete between a shutdown being requested and the exit signal
+//! being fired.
+//!
+//! Operations which must not be cancelled mid-way (e.g. publishing a block) hold an
+//! `InFlightGuard` whilst they run. On shutdown, `GracefulShutdown::drain` signals that new work
+//! should no longer be accepted, waits up to the grace period for the in-flight operations to
+//! complete and then runs the registered shutdown hooks (e.g. persisting caches to disk).
+use slog::{debug, info, warn, Logger};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// The default time to wait for in-flight operations to complete on shutdown.
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// Tracks in-flight operations and shutdown hooks. Clones share the same state.
+#[derive(Clone)]
+pub struct GracefulShutdown {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    in_flight: AtomicUsize,
+    draining: AtomicBool,
+    /// Notified when the drain starts and whenever an in-flight operation completes.
+    notify: Notify,
+    grace_period: Mutex<Duration>,
+    hooks: Mutex<Vec<(&'static str, ShutdownHook)>>,
+}
+
+impl Default for GracefulShutdown {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                in_flight: AtomicUsize::new(0),
+                draining: AtomicBool::new(false),
+                notify: Notify::new(),
+                grace_period: Mutex::new(DEFAULT_GRACE_PERIOD),
+                hooks: Mutex::new(vec![]),
+            }),
+        }
+    }
+}
+
+impl GracefulShutdown {
+    /// Sets the maximum time `Self::drain` waits for in-flight operations.
+    pub fn set_grace_period(&self, grace_period: Duration) {
+        if let Ok(mut current) = self.inner.grace_period.lock() {
+            *current = grace_period;
+        }
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        self.inner
+            .grace_period
+            .lock()
+            .map(|grace_period| *grace_period)
+            .unwrap_or(DEFAULT_GRACE_PERIOD)
+    }
+
+    /// Registers an operation as in-flight until the returned guard is dropped.
+    pub fn track(&self) -> InFlightGuard {
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard {
+            inner: self.inner.clone(),
+        }
+    }
+
+    /// Returns the number of operations currently in-flight.
+    pub fn num_in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` once `Self::drain` has been called.
+    pub fn is_draining(&self) -> bool {
+        self.inner.draining.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once `Self::drain` has been called, at which point servers should stop accepting
+    /// new requests.
+    pub async fn draining(self) {
+        loop {
+            // Create the `Notified` future before checking the flag so that a notification sent
+            // in between is not missed.
+            let notified = self.inner.notify.notified();
+            if self.is_draining() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Registers a hook to be run by `Self::run_hooks`, after the in-flight operations have
+    /// completed. Hooks are run in the order they are registered.
+    pub fn register_hook(&self, name: &'static str, hook: impl FnOnce() + Send + 'static) {
+        if let Ok(mut hooks) = self.inner.hooks.lock() {
+            hooks.push((name, Box::new(hook)));
+        }
+    }
+
+    /// Signals that no new work should be accepted and waits up to the grace period for the
+    /// in-flight operations to complete.
+    ///
+    /// Returns `true` if all operations completed within the grace period.
+    pub async fn drain(&self, log: &Logger) -> bool {
+        self.inner.draining.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+
+        let grace_period = self.grace_period();
+        let wait_for_idle = async {
+            loop {
+                let notified = self.inner.notify.notified();
+                if self.num_in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+
+        if self.num_in_flight() > 0 {
+            info!(
+                log,
+                "Waiting for in-flight operations";
+                "count" => self.num_in_flight(),
+                "grace_period" => ?grace_period,
+            );
+        }
+        if tokio::time::timeout(grace_period, wait_for_idle)
+            .await
+            .is_err()
+        {
+            warn!(
+                log,
+                "In-flight operations did not complete before shutdown";
+                "count" => self.num_in_flight(),
+                "grace_period" => ?grace_period,
+            );
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Runs and removes the registered shutdown hooks.
+    pub fn run_hooks(&self, log: &Logger) {
+        let hooks = self
+            .inner
+            .hooks
+            .lock()
+            .map(|mut hooks| std::mem::take(&mut *hooks))
+            .unwrap_or_default();
+        for (name, hook) in hooks {
+            debug!(log, "Running shutdown hook"; "hook" => name);
+            hook();
+        }
+    }
+}
+
+/// Marks an operation as in-flight until dropped.
+pub struct InFlightGuard {
+    inner: Arc<Inner>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.inner.in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::null_logger;
+
+    #[tokio::test]
+    async fn drain_waits_for_in_flight() {
+        let log = null_logger().unwrap();
+        let graceful = GracefulShutdown::default();
+        let draining = tokio::spawn(graceful.clone().draining());
+
+        let guard = graceful.track();
+        let completed = Arc::new(AtomicBool::new(false));
+        let completed_1 = completed.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            completed_1.store(true, Ordering::SeqCst);
+            drop(guard);
+        });
+
+        let hook_ran = Arc::new(AtomicBool::new(false));
+        let hook_ran_1 = hook_ran.clone();
+        graceful.register_hook("test", move || hook_ran_1.store(true, Ordering::SeqCst));
+
+        assert!(graceful.drain(&log).await);
+        assert!(completed.load(Ordering::SeqCst));
+        draining.await.unwrap();
+
+        graceful.run_hooks(&log);
+        assert!(hook_ran.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn drain_times_out() {
+        let log = null_logger().unwrap();
+        let graceful = GracefulShutdown::default();
+        graceful.set_grace_period(Duration::from_millis(10));
+
+        let _guard = graceful.track();
+        assert!(!graceful.drain(&log).await);
+        assert_eq!(graceful.num_in_flight(), 1);
+    }
+}
@@ -1,3 +1,4 @@
+mod graceful_shutdown;
 mod metrics;
 pub mod test_utils;
 
@@ -7,6 +8,7 @@ use slog::{crit, debug, o, trace};
 use std::sync::Weak;
 use tokio::runtime::{Handle, Runtime};
 
+pub use graceful_shutdown::{GracefulShutdown, InFlightGuard, DEFAULT_GRACE_PERIOD};
 pub use tokio::task::JoinHandle;
 
 /// Provides a reason when Lighthouse is shut down.
@@ -79,6 +81,8 @@ pub struct TaskExecutor {
     ///
     /// The task must provide a reason for shutting down.
     signal_tx: Sender<ShutdownReason>,
+    /// Tracks the operations which should complete before the exit future fires.
+    graceful_shutdown: GracefulShutdown,
 
     log: slog::Logger,
 }
@@ -101,16 +105,24 @@ impl TaskExecutor {
             handle_provider: handle.into(),
             exit,
             signal_tx,
+            graceful_shutdown: GracefulShutdown::default(),
             log,
         }
     }
 
+    /// Use `graceful_shutdown` to track in-flight operations, sharing it with other executors.
+    pub fn with_graceful_shutdown(mut self, graceful_shutdown: GracefulShutdown) -> Self {
+        self.graceful_shutdown = graceful_shutdown;
+        self
+    }
+
     /// Clones the task executor adding a service name.
     pub fn clone_with_name(&self, service_name: String) -> Self {
         TaskExecutor {
             handle_provider: self.handle_provider.clone(),
             exit: self.exit.clone(),
             signal_tx: self.signal_tx.clone(),
+            graceful_shutdown: self.graceful_shutdown.clone(),
             log: self.log.new(o!("service" => service_name)),
         }
     }
@@ -379,6 +391,11 @@ impl TaskExecutor {
         self.exit.clone()
     }
 
+    /// Returns the tracker of operations which should complete before shutting down.
+    pub fn graceful_shutdown(&self) -> &GracefulShutdown {
+        &self.graceful_shutdown
+    }
+
     /// Get a channel to request shutting down.
     pub fn shutdown_sender(&self) -> Sender<ShutdownReason> {
         self.signal_tx.clone()
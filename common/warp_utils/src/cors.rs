@@ -1,5 +1,12 @@
+use std::convert::Infallible;
 use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
 use warp::filters::cors::Builder;
+use warp::http::header::{self, HeaderMap, HeaderName, HeaderValue};
+use warp::http::{Method, StatusCode};
+use warp::reply::{Reply, Response};
+use warp::Filter;
 
 /// Configure a `cors::Builder`.
 ///
@@ -30,6 +37,266 @@ pub fn set_builder_origins(
     }
 }
 
+/// An origin which is allowed to make cross-origin requests.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AllowedOrigin {
+    /// Any origin, configured as `*`.
+    Any,
+    /// A single origin, e.g. `https://dashboard.example.com`.
+    Exact(String),
+    /// Any subdomain of a domain, e.g. `https://*.example.com`.
+    ///
+    /// The scheme and port must match exactly, and the domain itself is not matched.
+    Subdomain {
+        scheme: String,
+        /// The part of the origin following the wildcard, e.g. `.example.com:8080`.
+        suffix: String,
+    },
+}
+
+impl FromStr for AllowedOrigin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "*" {
+            return Ok(AllowedOrigin::Any);
+        }
+
+        match s.split_once("://") {
+            Some((scheme, rest)) if rest.starts_with("*.") => {
+                // Check that the pattern is valid with the wildcard replaced by a subdomain.
+                verify_cors_origin_str(&format!("{}://wildcard{}", scheme, &rest[1..]))?;
+                Ok(AllowedOrigin::Subdomain {
+                    scheme: scheme.to_ascii_lowercase(),
+                    suffix: rest[1..].to_ascii_lowercase(),
+                })
+            }
+            _ => {
+                verify_cors_origin_str(s)?;
+                Ok(AllowedOrigin::Exact(s.to_ascii_lowercase()))
+            }
+        }
+    }
+}
+
+impl AllowedOrigin {
+    /// Returns `true` if requests from `origin` are allowed.
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        match self {
+            AllowedOrigin::Any => true,
+            AllowedOrigin::Exact(allowed) => origin == *allowed,
+            AllowedOrigin::Subdomain { scheme, suffix } => origin
+                .split_once("://")
+                .filter(|(origin_scheme, _)| origin_scheme == scheme)
+                .and_then(|(_, rest)| rest.strip_suffix(suffix.as_str()))
+                .map_or(false, |subdomain| {
+                    !subdomain.is_empty()
+                        && !subdomain.starts_with('.')
+                        && !subdomain.ends_with('.')
+                        && subdomain
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                }),
+        }
+    }
+}
+
+/// Handles CORS for a `warp` server, including preflight requests.
+///
+/// Unlike `warp::cors`, this supports wildcard subdomain origins (e.g. `https://*.example.com`).
+#[derive(Debug, Clone)]
+pub struct Cors {
+    origins: Vec<AllowedOrigin>,
+    allowed_methods: Vec<Method>,
+    allowed_headers: Vec<HeaderName>,
+    exposed_headers: Vec<HeaderName>,
+}
+
+impl Cors {
+    /// Allows requests from `allow_origins`, or from `default_origin` if `allow_origins` is empty.
+    ///
+    /// Each origin may be `*`, a single origin or a wildcard subdomain pattern.
+    pub fn new(allow_origins: &[String], default_origin: (IpAddr, u16)) -> Result<Self, String> {
+        let origins = if allow_origins.is_empty() {
+            let origin = match default_origin.0 {
+                IpAddr::V4(_) => format!("http://{}:{}", default_origin.0, default_origin.1),
+                IpAddr::V6(_) => format!("http://[{}]:{}", default_origin.0, default_origin.1),
+            };
+            vec![origin.parse()?]
+        } else {
+            allow_origins
+                .iter()
+                .map(|origin| origin.parse())
+                .collect::<Result<_, _>>()?
+        };
+
+        Ok(Self {
+            origins,
+            allowed_methods: vec![],
+            allowed_headers: vec![],
+            exposed_headers: vec![],
+        })
+    }
+
+    /// Sets the methods allowed in cross-origin requests.
+    pub fn allow_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.allowed_methods = methods.into_iter().collect();
+        self
+    }
+
+    /// Sets the request headers allowed in cross-origin requests.
+    pub fn allow_headers(mut self, headers: &[&str]) -> Result<Self, String> {
+        self.allowed_headers = parse_header_names(headers)?;
+        Ok(self)
+    }
+
+    /// Sets the response headers which may be read by cross-origin requests.
+    pub fn expose_headers(mut self, headers: &[&str]) -> Result<Self, String> {
+        self.exposed_headers = parse_header_names(headers)?;
+        Ok(self)
+    }
+
+    /// Returns the `Access-Control-Allow-Origin` value for requests from `origin`, or `None` if
+    /// the origin is not allowed.
+    fn allow_origin_value(&self, origin: &HeaderValue) -> Option<HeaderValue> {
+        let origin_str = origin.to_str().ok()?;
+        let allowed = self
+            .origins
+            .iter()
+            .find(|allowed| allowed.matches(origin_str))?;
+        if *allowed == AllowedOrigin::Any {
+            Some(HeaderValue::from_static("*"))
+        } else {
+            Some(origin.clone())
+        }
+    }
+
+    /// Returns the response to a preflight request, or `None` if `headers` are not those of a
+    /// preflight request.
+    fn preflight_response(&self, headers: &HeaderMap) -> Option<Response> {
+        let origin = headers.get(header::ORIGIN)?;
+        let request_method = headers.get(header::ACCESS_CONTROL_REQUEST_METHOD)?;
+
+        let Some(allow_origin) = self.allow_origin_value(origin) else {
+            return Some(forbidden("origin not allowed"));
+        };
+
+        let method_allowed = Method::from_bytes(request_method.as_bytes())
+            .map_or(false, |method| self.allowed_methods.contains(&method));
+        if !method_allowed {
+            return Some(forbidden("request-method not allowed"));
+        }
+
+        let headers_allowed = headers
+            .get_all(header::ACCESS_CONTROL_REQUEST_HEADERS)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .all(|name| {
+                self.allowed_headers
+                    .iter()
+                    .any(|allowed| allowed.as_str().eq_ignore_ascii_case(name))
+            });
+        if !headers_allowed {
+            return Some(forbidden("header not allowed"));
+        }
+
+        let mut response = StatusCode::OK.into_response();
+        let response_headers = response.headers_mut();
+        response_headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+        response_headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            join_header_values(self.allowed_methods.iter().map(Method::as_str)),
+        );
+        if !self.allowed_headers.is_empty() {
+            response_headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                join_header_values(self.allowed_headers.iter().map(HeaderName::as_str)),
+            );
+        }
+        response_headers.append(header::VARY, HeaderValue::from_static("origin"));
+        Some(response)
+    }
+
+    /// Adds the CORS headers to the `response` to a request with `headers`.
+    ///
+    /// Responses to requests from disallowed origins are left without CORS headers, so that
+    /// browsers refuse to expose them.
+    fn add_headers(&self, mut response: Response, headers: &HeaderMap) -> Response {
+        let allow_origin = headers
+            .get(header::ORIGIN)
+            .and_then(|origin| self.allow_origin_value(origin));
+        let response_headers = response.headers_mut();
+        if let Some(allow_origin) = allow_origin {
+            response_headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+            if !self.exposed_headers.is_empty() {
+                response_headers.insert(
+                    header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                    join_header_values(self.exposed_headers.iter().map(HeaderName::as_str)),
+                );
+            }
+        }
+        response_headers.append(header::VARY, HeaderValue::from_static("origin"));
+        response
+    }
+
+    /// Wraps `filter`, responding to preflight requests and adding CORS headers to its responses.
+    pub fn wrap<F, R>(
+        self,
+        filter: F,
+    ) -> impl Filter<Extract = (Response,), Error = Infallible> + Clone + Send + Sync + 'static
+    where
+        F: Filter<Extract = (R,), Error = Infallible> + Clone + Send + Sync + 'static,
+        R: Reply,
+    {
+        let cors = Arc::new(self);
+        let preflight_cors = cors.clone();
+
+        let preflight = warp::options()
+            .and(warp::header::headers_cloned())
+            .and_then(move |headers: HeaderMap| {
+                let response = preflight_cors.preflight_response(&headers);
+                async move { response.ok_or_else(warp::reject) }
+            });
+
+        let actual =
+            warp::header::headers_cloned()
+                .and(filter)
+                .map(move |headers: HeaderMap, reply: R| {
+                    cors.add_headers(reply.into_response(), &headers)
+                });
+
+        preflight.or(actual).unify()
+    }
+}
+
+fn parse_header_names(headers: &[&str]) -> Result<Vec<HeaderName>, String> {
+    headers
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| format!("Invalid header name {}: {}", name, e))
+        })
+        .collect()
+}
+
+fn join_header_values<'a>(values: impl Iterator<Item = &'a str>) -> HeaderValue {
+    HeaderValue::from_str(&values.collect::<Vec<_>>().join(", "))
+        .expect("methods and header names are valid header values")
+}
+
+fn forbidden(reason: &str) -> Response {
+    warp::reply::with_status(
+        format!("CORS request forbidden: {}", reason),
+        StatusCode::FORBIDDEN,
+    )
+    .into_response()
+}
+
 /// Verify that `s` can be used as a CORS origin.
 ///
 /// ## Notes
@@ -83,4 +350,33 @@ mod test {
         verify_cors_origin_str("localhost").unwrap_err();
         verify_cors_origin_str("[::1]").unwrap_err();
     }
+
+    #[test]
+    fn allowed_origins() {
+        let any = AllowedOrigin::from_str("*").unwrap();
+        assert_eq!(any, AllowedOrigin::Any);
+        assert!(any.matches("https://example.com"));
+
+        let exact = AllowedOrigin::from_str("https://Dashboard.example.com").unwrap();
+        assert!(exact.matches("https://dashboard.example.com"));
+        assert!(!exact.matches("http://dashboard.example.com"));
+        assert!(!exact.matches("https://dashboard.example.com:8080"));
+
+        let subdomain = AllowedOrigin::from_str("https://*.example.com").unwrap();
+        assert!(subdomain.matches("https://dashboard.example.com"));
+        assert!(subdomain.matches("https://a.b.example.com"));
+        assert!(!subdomain.matches("https://example.com"));
+        assert!(!subdomain.matches("https://.example.com"));
+        assert!(!subdomain.matches("https://evilexample.com"));
+        assert!(!subdomain.matches("https://evil.com/.example.com"));
+        assert!(!subdomain.matches("http://dashboard.example.com"));
+        assert!(!subdomain.matches("https://dashboard.example.com:8080"));
+
+        let subdomain_with_port = AllowedOrigin::from_str("https://*.example.com:8080").unwrap();
+        assert!(subdomain_with_port.matches("https://dashboard.example.com:8080"));
+        assert!(!subdomain_with_port.matches("https://dashboard.example.com"));
+
+        AllowedOrigin::from_str("example.com").unwrap_err();
+        AllowedOrigin::from_str("https://*.exam ple.com").unwrap_err();
+    }
 }
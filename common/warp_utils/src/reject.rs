@@ -2,7 +2,8 @@ use eth2::types::{ErrorMessage, Failure, IndexedErrorMessage};
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt;
-use warp::{http::StatusCode, reject::Reject};
+use std::time::Duration;
+use warp::{http::StatusCode, reject::Reject, reply::Response, Reply};
 
 #[derive(Debug)]
 pub struct ServerSentEventError(pub String);
@@ -82,6 +83,24 @@ pub fn custom_bad_request(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(CustomBadRequest(msg))
 }
 
+#[derive(Debug)]
+pub struct UnsupportedMediaType(pub String);
+
+impl Reject for UnsupportedMediaType {}
+
+pub fn unsupported_media_type(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(UnsupportedMediaType(msg))
+}
+
+#[derive(Debug)]
+pub struct CustomForbidden(pub String);
+
+impl Reject for CustomForbidden {}
+
+pub fn custom_forbidden(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CustomForbidden(msg))
+}
+
 #[derive(Debug)]
 pub struct CustomServerError(pub String);
 
@@ -139,9 +158,24 @@ pub fn indexed_bad_request(message: String, failures: Vec<Failure>) -> warp::rej
     warp::reject::custom(IndexedBadRequestErrors { message, failures })
 }
 
+#[derive(Debug)]
+pub struct TooManyRequests {
+    pub message: String,
+    pub retry_after: Duration,
+}
+
+impl Reject for TooManyRequests {}
+
+pub fn too_many_requests(message: String, retry_after: Duration) -> warp::reject::Rejection {
+    warp::reject::custom(TooManyRequests {
+        message,
+        retry_after,
+    })
+}
+
 /// This function receives a `Rejection` and tries to return a custom
 /// value, otherwise simply passes the rejection along.
-pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
+pub async fn handle_rejection(err: warp::Rejection) -> Result<Response, Infallible> {
     let code;
     let message;
 
@@ -155,7 +189,27 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
             failures: e.failures.clone(),
         });
 
-        return Ok(warp::reply::with_status(json, code));
+        return Ok(warp::reply::with_status(json, code).into_response());
+    }
+
+    if let Some(e) = err.find::<crate::reject::TooManyRequests>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+
+        let json = warp::reply::json(&ErrorMessage {
+            code: code.as_u16(),
+            message: format!("TOO_MANY_REQUESTS: {}", e.message),
+            stacktraces: vec![],
+        });
+
+        // Round up so that clients never retry before the suggested delay has elapsed.
+        let retry_after_secs =
+            e.retry_after.as_secs() + u64::from(e.retry_after.subsec_nanos() > 0);
+        return Ok(warp::reply::with_header(
+            warp::reply::with_status(json, code),
+            "Retry-After",
+            retry_after_secs.to_string(),
+        )
+        .into_response());
     }
 
     if err.is_not_found() {
@@ -185,6 +239,12 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::CustomBadRequest>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::UnsupportedMediaType>() {
+        code = StatusCode::UNSUPPORTED_MEDIA_TYPE;
+        message = format!("UNSUPPORTED_MEDIA_TYPE: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::CustomForbidden>() {
+        code = StatusCode::FORBIDDEN;
+        message = format!("FORBIDDEN: {}", e.0);
     } else if let Some(e) = err.find::<crate::reject::CustomServerError>() {
         code = StatusCode::INTERNAL_SERVER_ERROR;
         message = format!("INTERNAL_SERVER_ERROR: {}", e.0);
@@ -229,5 +289,5 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
         stacktraces: vec![],
     });
 
-    Ok(warp::reply::with_status(json, code))
+    Ok(warp::reply::with_status(json, code).into_response())
 }
@@ -1,8 +1,8 @@
 use crate::{ForkChoiceStore, InvalidationOperation};
 use per_epoch_processing::altair::participation_cache::Error as ParticipationCacheError;
 use proto_array::{
-    Block as ProtoBlock, DisallowedReOrgOffsets, ExecutionStatus, ProposerHeadError,
-    ProposerHeadInfo, ProtoArrayForkChoice, ReOrgThreshold,
+    Block as ProtoBlock, DisallowedReOrgOffsets, ExecutionStatus, ExecutionStatusChange,
+    ProposerHeadError, ProposerHeadInfo, ProtoArrayForkChoice, ReOrgThreshold,
 };
 use slog::{crit, debug, error, warn, Logger};
 use ssz_derive::{Decode, Encode};
@@ -533,6 +533,7 @@ where
         current_slot: Slot,
         canonical_head: Hash256,
         re_org_threshold: ReOrgThreshold,
+        re_org_margin: ReOrgThreshold,
         disallowed_offsets: &DisallowedReOrgOffsets,
         max_epochs_since_finalization: Epoch,
     ) -> Result<ProposerHeadInfo, ProposerHeadError<Error<proto_array::Error>>> {
@@ -565,6 +566,7 @@ where
                 canonical_head,
                 self.fc_store.justified_balances(),
                 re_org_threshold,
+                re_org_margin,
                 disallowed_offsets,
                 max_epochs_since_finalization,
             )
@@ -575,6 +577,7 @@ where
         &self,
         canonical_head: Hash256,
         re_org_threshold: ReOrgThreshold,
+        re_org_margin: ReOrgThreshold,
         disallowed_offsets: &DisallowedReOrgOffsets,
         max_epochs_since_finalization: Epoch,
     ) -> Result<ProposerHeadInfo, ProposerHeadError<Error<proto_array::Error>>> {
@@ -585,6 +588,7 @@ where
                 canonical_head,
                 self.fc_store.justified_balances(),
                 re_org_threshold,
+                re_org_margin,
                 disallowed_offsets,
                 max_epochs_since_finalization,
             )
@@ -631,6 +635,11 @@ where
             .map_err(Error::FailedToProcessInvalidExecutionPayload)
     }
 
+    /// See `ProtoArrayForkChoice::take_execution_status_changes` for documentation.
+    pub fn take_execution_status_changes(&mut self) -> (Vec<ExecutionStatusChange>, usize) {
+        self.proto_array.take_execution_status_changes()
+    }
+
     /// Add `block` to the fork choice DAG.
     ///
     /// - `block_root` is the root of `block.
@@ -1425,6 +1434,13 @@ where
         &self.fc_store
     }
 
+    /// Returns a mutable reference to the underlying `fc_store`.
+    ///
+    /// Modifications must not change the checkpoints or balances used by fork choice.
+    pub fn fc_store_mut(&mut self) -> &mut T {
+        &mut self.fc_store
+    }
+
     /// Returns a reference to the currently queued attestations.
     pub fn queued_attestations(&self) -> &[QueuedAttestation] {
         &self.queued_attestations
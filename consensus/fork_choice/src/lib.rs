@@ -7,4 +7,6 @@ pub use crate::fork_choice::{
     QueuedAttestation, ResetPayloadStatuses,
 };
 pub use fork_choice_store::ForkChoiceStore;
-pub use proto_array::{Block as ProtoBlock, ExecutionStatus, InvalidationOperation};
+pub use proto_array::{
+    Block as ProtoBlock, ExecutionStatus, ExecutionStatusChange, InvalidationOperation,
+};
@@ -122,7 +122,7 @@ impl ForkChoiceTestDefinition {
                         "Operation at index {} failed head check. Operation: {:?}",
                         op_index, op
                     );
-                    check_bytes_round_trip(&fork_choice);
+                    check_bytes_round_trip(&mut fork_choice);
                 }
                 Operation::ProposerBoostFindHead {
                     justified_checkpoint,
@@ -153,7 +153,7 @@ impl ForkChoiceTestDefinition {
                         "Operation at index {} failed head check. Operation: {:?}",
                         op_index, op
                     );
-                    check_bytes_round_trip(&fork_choice);
+                    check_bytes_round_trip(&mut fork_choice);
                 }
                 Operation::InvalidFindHead {
                     justified_checkpoint,
@@ -179,7 +179,7 @@ impl ForkChoiceTestDefinition {
                         op_index,
                         op
                     );
-                    check_bytes_round_trip(&fork_choice);
+                    check_bytes_round_trip(&mut fork_choice);
                 }
                 Operation::ProcessBlock {
                     slot,
@@ -219,7 +219,7 @@ impl ForkChoiceTestDefinition {
                                 op_index, e
                             )
                         });
-                    check_bytes_round_trip(&fork_choice);
+                    check_bytes_round_trip(&mut fork_choice);
                 }
                 Operation::ProcessAttestation {
                     validator_index,
@@ -234,7 +234,7 @@ impl ForkChoiceTestDefinition {
                                 op_index
                             )
                         });
-                    check_bytes_round_trip(&fork_choice);
+                    check_bytes_round_trip(&mut fork_choice);
                 }
                 Operation::Prune {
                     finalized_root,
@@ -304,7 +304,9 @@ fn get_checkpoint(i: u64) -> Checkpoint {
     }
 }
 
-fn check_bytes_round_trip(original: &ProtoArrayForkChoice) {
+fn check_bytes_round_trip(original: &mut ProtoArrayForkChoice) {
+    // Pending execution status changes are not persisted, so collect them before comparing.
+    original.take_execution_status_changes();
     let bytes = original.as_bytes();
     let decoded =
         ProtoArrayForkChoice::from_bytes(&bytes).expect("fork choice should decode from bytes");
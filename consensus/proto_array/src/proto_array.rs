@@ -67,6 +67,47 @@ impl InvalidationOperation {
     }
 }
 
+/// The maximum number of execution status changes buffered between calls to
+/// `ProtoArray::take_execution_status_changes`. Validating or invalidating a long optimistic chain
+/// may change the status of many blocks at once, so changes beyond this limit are dropped (and
+/// counted) rather than being allowed to grow without bound.
+pub const MAX_PENDING_EXECUTION_STATUS_CHANGES: usize = 1_024;
+
+/// A change to the `execution_status` of a block, e.g. from `Optimistic` to `Valid`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExecutionStatusChange {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub old_status: ExecutionStatus,
+    pub new_status: ExecutionStatus,
+}
+
+/// Execution status changes which have not yet been collected with
+/// `ProtoArray::take_execution_status_changes`.
+///
+/// These are transient and are not persisted.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExecutionStatusChanges {
+    changes: Vec<ExecutionStatusChange>,
+    dropped: usize,
+}
+
+impl ExecutionStatusChanges {
+    fn push(&mut self, change: ExecutionStatusChange) {
+        if self.changes.len() < MAX_PENDING_EXECUTION_STATUS_CHANGES {
+            self.changes.push(change);
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// Returns the buffered changes, oldest first, and the number of changes which were dropped.
+    fn take(&mut self) -> (Vec<ExecutionStatusChange>, usize) {
+        let changes = std::mem::take(self);
+        (changes.changes, changes.dropped)
+    }
+}
+
 pub type ProtoNode = ProtoNodeV17;
 
 #[superstruct(
@@ -192,9 +233,17 @@ pub struct ProtoArray {
     pub nodes: Vec<ProtoNode>,
     pub indices: HashMap<Hash256, usize>,
     pub previous_proposer_boost: ProposerBoost,
+    #[serde(skip)]
+    pub execution_status_changes: ExecutionStatusChanges,
 }
 
 impl ProtoArray {
+    /// Returns the execution status changes made since the last call to this function, along with
+    /// the number of changes which were not recorded because too many were pending.
+    pub fn take_execution_status_changes(&mut self) -> (Vec<ExecutionStatusChange>, usize) {
+        self.execution_status_changes.take()
+    }
+
     /// Iterate backwards through the array, touching all nodes and their parents and potentially
     /// the best-child of each parent.
     ///
@@ -472,6 +521,12 @@ impl ProtoArray {
                 // payload can be considered valid.
                 ExecutionStatus::Optimistic(payload_block_hash) => {
                     node.execution_status = ExecutionStatus::Valid(payload_block_hash);
+                    self.execution_status_changes.push(ExecutionStatusChange {
+                        block_root: node.root,
+                        slot: node.slot,
+                        old_status: ExecutionStatus::Optimistic(payload_block_hash),
+                        new_status: node.execution_status,
+                    });
                     if let Some(parent_index) = node.parent {
                         parent_index
                     } else {
@@ -598,6 +653,12 @@ impl ProtoArray {
                     }
                     ExecutionStatus::Optimistic(hash) => {
                         invalidated_indices.insert(index);
+                        self.execution_status_changes.push(ExecutionStatusChange {
+                            block_root: node.root,
+                            slot: node.slot,
+                            old_status: node.execution_status,
+                            new_status: ExecutionStatus::Invalid(*hash),
+                        });
                         node.execution_status = ExecutionStatus::Invalid(*hash);
 
                         // It's impossible for an invalid block to lead to a "best" block, so set these
@@ -660,9 +721,16 @@ impl ProtoArray {
                                 payload_block_hash: *hash,
                             })
                         }
-                        ExecutionStatus::Optimistic(hash) | ExecutionStatus::Invalid(hash) => {
+                        ExecutionStatus::Optimistic(hash) => {
+                            self.execution_status_changes.push(ExecutionStatusChange {
+                                block_root: node.root,
+                                slot: node.slot,
+                                old_status: node.execution_status,
+                                new_status: ExecutionStatus::Invalid(*hash),
+                            });
                             node.execution_status = ExecutionStatus::Invalid(*hash)
                         }
+                        ExecutionStatus::Invalid(_) => (),
                         ExecutionStatus::Irrelevant(_) => {
                             return Err(Error::IrrelevantDescendant {
                                 block_root: node.root,
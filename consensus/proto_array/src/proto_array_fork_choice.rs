@@ -1,8 +1,8 @@
 use crate::{
     error::Error,
     proto_array::{
-        calculate_committee_fraction, InvalidationOperation, Iter, ProposerBoost, ProtoArray,
-        ProtoNode,
+        calculate_committee_fraction, ExecutionStatusChange, InvalidationOperation, Iter,
+        ProposerBoost, ProtoArray, ProtoNode,
     },
     ssz_container::SszContainer,
     JustifiedBalances,
@@ -197,11 +197,42 @@ pub struct ProposerHeadInfo {
     pub parent_node: ProtoNode,
     /// The computed fraction of the active committee balance below which we can re-org.
     pub re_org_weight_threshold: u64,
+    /// The computed fraction of the active committee balance by which the head's weight must fall
+    /// short of `re_org_weight_threshold` for it to be considered weak.
+    pub re_org_margin_weight: u64,
     /// The current slot from fork choice's point of view, may lead the wall-clock slot by upto
     /// 500ms.
     pub current_slot: Slot,
 }
 
+impl ProposerHeadInfo {
+    /// Check that the head is weak enough to be re-orged.
+    ///
+    /// The head must weigh less than the re-org threshold by at least the margin, so that small
+    /// discrepancies between our view of the head's weight and that of other nodes cannot flip
+    /// the decision.
+    pub fn check_head_weak(&self) -> Result<(), DoNotReOrg> {
+        let head_weight = self.head_node.weight;
+        let re_org_weight_threshold = self.re_org_weight_threshold;
+        let re_org_margin_weight = self.re_org_margin_weight;
+
+        if head_weight >= re_org_weight_threshold {
+            return Err(DoNotReOrg::HeadNotWeak {
+                head_weight,
+                re_org_weight_threshold,
+            });
+        }
+        if head_weight.saturating_add(re_org_margin_weight) >= re_org_weight_threshold {
+            return Err(DoNotReOrg::HeadWeightWithinMargin {
+                head_weight,
+                re_org_weight_threshold,
+                re_org_margin_weight,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Error type to enable short-circuiting checks in `get_proposer_head`.
 ///
 /// This type intentionally does not implement `Debug` so that callers are forced to handle the
@@ -261,6 +292,16 @@ pub enum DoNotReOrg {
         head_weight: u64,
         re_org_weight_threshold: u64,
     },
+    HeadWeightWithinMargin {
+        head_weight: u64,
+        re_org_weight_threshold: u64,
+        re_org_margin_weight: u64,
+    },
+    ParticipationTooLow {
+        participation_percent: u64,
+        min_participation_percent: u64,
+    },
+    ParticipationUnknown,
     HeadNotLate,
     NotProposing,
     ReOrgsDisabled,
@@ -292,6 +333,30 @@ impl std::fmt::Display for DoNotReOrg {
             } => {
                 write!(f, "head not weak ({head_weight}/{re_org_weight_threshold})")
             }
+            Self::HeadWeightWithinMargin {
+                head_weight,
+                re_org_weight_threshold,
+                re_org_margin_weight,
+            } => {
+                write!(
+                    f,
+                    "head weight within margin of threshold \
+                     ({head_weight}+{re_org_margin_weight}/{re_org_weight_threshold})"
+                )
+            }
+            Self::ParticipationTooLow {
+                participation_percent,
+                min_participation_percent,
+            } => {
+                write!(
+                    f,
+                    "previous epoch participation too low \
+                     ({participation_percent}%/{min_participation_percent}%)"
+                )
+            }
+            Self::ParticipationUnknown => {
+                write!(f, "previous epoch participation unknown")
+            }
             Self::HeadNotLate => {
                 write!(f, "head arrived on time")
             }
@@ -361,6 +426,7 @@ impl ProtoArrayForkChoice {
             nodes: Vec::with_capacity(1),
             indices: HashMap::with_capacity(1),
             previous_proposer_boost: ProposerBoost::default(),
+            execution_status_changes: <_>::default(),
         };
 
         let block = Block {
@@ -411,6 +477,11 @@ impl ProtoArrayForkChoice {
             .map_err(|e| format!("Failed to process invalid payload: {:?}", e))
     }
 
+    /// See `ProtoArray::take_execution_status_changes` for documentation.
+    pub fn take_execution_status_changes(&mut self) -> (Vec<ExecutionStatusChange>, usize) {
+        self.proto_array.take_execution_status_changes()
+    }
+
     pub fn process_attestation(
         &mut self,
         validator_index: usize,
@@ -492,6 +563,7 @@ impl ProtoArrayForkChoice {
         canonical_head: Hash256,
         justified_balances: &JustifiedBalances,
         re_org_threshold: ReOrgThreshold,
+        re_org_margin: ReOrgThreshold,
         disallowed_offsets: &DisallowedReOrgOffsets,
         max_epochs_since_finalization: Epoch,
     ) -> Result<ProposerHeadInfo, ProposerHeadError<Error>> {
@@ -500,6 +572,7 @@ impl ProtoArrayForkChoice {
             canonical_head,
             justified_balances,
             re_org_threshold,
+            re_org_margin,
             disallowed_offsets,
             max_epochs_since_finalization,
         )?;
@@ -510,17 +583,9 @@ impl ProtoArrayForkChoice {
             return Err(DoNotReOrg::HeadDistance.into());
         }
 
-        // Only re-org if the head's weight is less than the configured committee fraction.
-        let head_weight = info.head_node.weight;
-        let re_org_weight_threshold = info.re_org_weight_threshold;
-        let weak_head = head_weight < re_org_weight_threshold;
-        if !weak_head {
-            return Err(DoNotReOrg::HeadNotWeak {
-                head_weight,
-                re_org_weight_threshold,
-            }
-            .into());
-        }
+        // Only re-org if the head's weight is less than the configured committee fraction, by at
+        // least the configured margin.
+        info.check_head_weak()?;
 
         // All checks have passed, build upon the parent to re-org the head.
         Ok(info)
@@ -535,6 +600,7 @@ impl ProtoArrayForkChoice {
         canonical_head: Hash256,
         justified_balances: &JustifiedBalances,
         re_org_threshold: ReOrgThreshold,
+        re_org_margin: ReOrgThreshold,
         disallowed_offsets: &DisallowedReOrgOffsets,
         max_epochs_since_finalization: Epoch,
     ) -> Result<ProposerHeadInfo, ProposerHeadError<Error>> {
@@ -599,11 +665,15 @@ impl ProtoArrayForkChoice {
         let re_org_weight_threshold =
             calculate_committee_fraction::<E>(justified_balances, re_org_threshold.0)
                 .ok_or(Error::ReOrgThresholdOverflow)?;
+        let re_org_margin_weight =
+            calculate_committee_fraction::<E>(justified_balances, re_org_margin.0)
+                .ok_or(Error::ReOrgThresholdOverflow)?;
 
         Ok(ProposerHeadInfo {
             head_node,
             parent_node,
             re_org_weight_threshold,
+            re_org_margin_weight,
             current_slot,
         })
     }
@@ -101,6 +101,7 @@ impl TryFrom<SszContainer> for ProtoArrayForkChoice {
             nodes: from.nodes,
             indices: from.indices.into_iter().collect::<HashMap<_, _>>(),
             previous_proposer_boost: from.previous_proposer_boost,
+            execution_status_changes: <_>::default(),
         };
 
         Ok(Self {
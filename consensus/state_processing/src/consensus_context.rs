@@ -2,6 +2,8 @@ use crate::common::get_indexed_attestation;
 use crate::per_block_processing::errors::{AttestationInvalid, BlockOperationError};
 use ssz_derive::{Decode, Encode};
 use std::collections::{hash_map::Entry, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
 use tree_hash::TreeHash;
 use types::{
     AbstractExecPayload, Attestation, AttestationData, BeaconState, BeaconStateError, BitList,
@@ -21,6 +23,48 @@ pub struct ConsensusContext<T: EthSpec> {
     #[ssz(skip_serializing, skip_deserializing)]
     indexed_attestations:
         HashMap<(AttestationData, BitList<T::MaxValidatorsPerCommittee>), IndexedAttestation<T>>,
+    /// Time spent in each stage of block processing, if recording was enabled.
+    #[ssz(skip_serializing, skip_deserializing)]
+    stage_timings: Option<Vec<(BlockProcessingStage, Duration)>>,
+}
+
+/// A stage of `per_block_processing` which may be timed, for profiling.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BlockProcessingStage {
+    SignatureVerification,
+    BlockHeader,
+    Withdrawals,
+    ExecutionPayload,
+    Randao,
+    Eth1Data,
+    ProposerSlashings,
+    AttesterSlashings,
+    Attestations,
+    Deposits,
+    VoluntaryExits,
+    BlsToExecutionChanges,
+    SyncAggregate,
+}
+
+impl fmt::Display for BlockProcessingStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::SignatureVerification => "signature_verification",
+            Self::BlockHeader => "block_header",
+            Self::Withdrawals => "withdrawals",
+            Self::ExecutionPayload => "execution_payload",
+            Self::Randao => "randao",
+            Self::Eth1Data => "eth1_data",
+            Self::ProposerSlashings => "proposer_slashings",
+            Self::AttesterSlashings => "attester_slashings",
+            Self::Attestations => "attestations",
+            Self::Deposits => "deposits",
+            Self::VoluntaryExits => "voluntary_exits",
+            Self::BlsToExecutionChanges => "bls_to_execution_changes",
+            Self::SyncAggregate => "sync_aggregate",
+        };
+        write!(f, "{}", name)
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -43,6 +87,32 @@ impl<T: EthSpec> ConsensusContext<T> {
             proposer_index: None,
             current_block_root: None,
             indexed_attestations: HashMap::new(),
+            stage_timings: None,
+        }
+    }
+
+    /// Record the time spent in each `BlockProcessingStage`, for profiling.
+    ///
+    /// Recording is disabled by default, in which case the stages are not timed at all.
+    pub fn record_stage_timings(mut self) -> Self {
+        self.stage_timings = Some(vec![]);
+        self
+    }
+
+    /// The time spent in each stage so far, in the order they were processed.
+    pub fn stage_timings(&self) -> &[(BlockProcessingStage, Duration)] {
+        self.stage_timings.as_deref().unwrap_or_default()
+    }
+
+    /// Start timing a stage, returning `None` if recording is disabled.
+    pub(crate) fn start_stage(&self) -> Option<Instant> {
+        self.stage_timings.as_ref().map(|_| Instant::now())
+    }
+
+    /// Record the time elapsed since `start` against `stage`.
+    pub(crate) fn finish_stage(&mut self, stage: BlockProcessingStage, start: Option<Instant>) {
+        if let (Some(stage_timings), Some(start)) = (&mut self.stage_timings, start) {
+            stage_timings.push((stage, start.elapsed()));
         }
     }
 
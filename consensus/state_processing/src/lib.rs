@@ -28,7 +28,7 @@ pub mod upgrade;
 pub mod verify_operation;
 
 pub use block_replayer::{BlockReplayError, BlockReplayer, StateProcessingStrategy};
-pub use consensus_context::{ConsensusContext, ContextError};
+pub use consensus_context::{BlockProcessingStage, ConsensusContext, ContextError};
 pub use genesis::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
     process_activations,
@@ -1,4 +1,4 @@
-use crate::consensus_context::ConsensusContext;
+use crate::consensus_context::{BlockProcessingStage, ConsensusContext};
 use errors::{BlockOperationError, BlockProcessingError, HeaderInvalid};
 use rayon::prelude::*;
 use safe_arith::{ArithError, SafeArith};
@@ -123,6 +123,7 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
     let verify_signatures = match block_signature_strategy {
         BlockSignatureStrategy::VerifyBulk => {
             // Verify all signatures in the block at once.
+            let start = ctxt.start_stage();
             block_verify!(
                 BlockSignatureVerifier::verify_entire_block(
                     state,
@@ -135,6 +136,7 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
                 .is_ok(),
                 BlockProcessingError::BulkSignatureVerificationFailed
             );
+            ctxt.finish_stage(BlockProcessingStage::SignatureVerification, start);
             VerifySignatures::False
         }
         BlockSignatureStrategy::VerifyIndividual => VerifySignatures::True,
@@ -142,6 +144,7 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
         BlockSignatureStrategy::VerifyRandao => VerifySignatures::False,
     };
 
+    let start = ctxt.start_stage();
     let proposer_index = process_block_header(
         state,
         block.temporary_block_header(),
@@ -149,6 +152,7 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
         ctxt,
         spec,
     )?;
+    ctxt.finish_stage(BlockProcessingStage::BlockHeader, start);
 
     if verify_signatures.is_true() {
         verify_block_signature(state, signed_block, ctxt, spec)?;
@@ -169,16 +173,27 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
     if is_execution_enabled(state, block.body()) {
         let body = block.body();
         if state_processing_strategy == StateProcessingStrategy::Accurate {
+            let start = ctxt.start_stage();
             process_withdrawals::<T, Payload>(state, body.execution_payload()?, spec)?;
+            ctxt.finish_stage(BlockProcessingStage::Withdrawals, start);
         }
+        let start = ctxt.start_stage();
         process_execution_payload::<T, Payload>(state, body, spec)?;
+        ctxt.finish_stage(BlockProcessingStage::ExecutionPayload, start);
     }
 
+    let start = ctxt.start_stage();
     process_randao(state, block, verify_randao, ctxt, spec)?;
+    ctxt.finish_stage(BlockProcessingStage::Randao, start);
+
+    let start = ctxt.start_stage();
     process_eth1_data(state, block.body().eth1_data())?;
+    ctxt.finish_stage(BlockProcessingStage::Eth1Data, start);
+
     process_operations(state, block.body(), verify_signatures, ctxt, spec)?;
 
     if let Ok(sync_aggregate) = block.body().sync_aggregate() {
+        let start = ctxt.start_stage();
         process_sync_aggregate(
             state,
             sync_aggregate,
@@ -186,6 +201,7 @@ pub fn per_block_processing<T: EthSpec, Payload: AbstractExecPayload<T>>(
             verify_signatures,
             spec,
         )?;
+        ctxt.finish_stage(BlockProcessingStage::SyncAggregate, start);
     }
 
     if is_progressive_balances_enabled(state) {
@@ -13,8 +13,6 @@ pub fn process_sync_aggregate<T: EthSpec>(
     verify_signatures: VerifySignatures,
     spec: &ChainSpec,
 ) -> Result<(), BlockProcessingError> {
-    let current_sync_committee = state.current_sync_committee()?.clone();
-
     // Verify sync committee aggregate signature signing over the previous slot block root
     if verify_signatures.is_true() {
         // This decompression could be avoided with a cache, but we're not likely
@@ -41,27 +39,89 @@ pub fn process_sync_aggregate<T: EthSpec>(
         }
     }
 
-    // Compute participant and proposer rewards
-    let (participant_reward, proposer_reward) = compute_sync_aggregate_rewards(state, spec)?;
-
     // Apply participant and proposer rewards
-    let committee_indices = state.get_sync_committee_indices(&current_sync_committee)?;
-
-    for (participant_index, participation_bit) in committee_indices
-        .into_iter()
-        .zip(aggregate.sync_committee_bits.iter())
-    {
-        if participation_bit {
-            increase_balance(state, participant_index, participant_reward)?;
-            increase_balance(state, proposer_index as usize, proposer_reward)?;
-        } else {
-            decrease_balance(state, participant_index, participant_reward)?;
+    for change in sync_aggregate_balance_changes(state, aggregate, proposer_index, spec)? {
+        match change {
+            SyncAggregateBalanceChange::ParticipantReward {
+                validator_index,
+                reward,
+            }
+            | SyncAggregateBalanceChange::ProposerReward {
+                proposer_index: validator_index,
+                reward,
+            } => increase_balance(state, validator_index, reward)?,
+            SyncAggregateBalanceChange::NonParticipantPenalty {
+                validator_index,
+                penalty,
+            } => decrease_balance(state, validator_index, penalty)?,
         }
     }
 
     Ok(())
 }
 
+/// A change to a validator's balance made when processing a sync aggregate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncAggregateBalanceChange {
+    /// Reward for a member of the sync committee which signed the aggregate.
+    ParticipantReward { validator_index: usize, reward: u64 },
+    /// Penalty for a member of the sync committee which did not sign the aggregate.
+    ///
+    /// The penalty is applied with `decrease_balance`, so it is capped at the validator's balance.
+    NonParticipantPenalty {
+        validator_index: usize,
+        penalty: u64,
+    },
+    /// Reward for the block proposer for including a participant's signature.
+    ProposerReward { proposer_index: usize, reward: u64 },
+}
+
+/// Returns the balance changes made by `process_sync_aggregate`, in the order they are applied.
+///
+/// Each sync committee position yields a reward or penalty for its member, followed by a reward
+/// for the proposer if the member participated. This allows the rewards of a block to be accounted
+/// for without duplicating the reward calculation.
+///
+/// The `state` should be the pre-state from the same slot as the block containing the aggregate.
+pub fn sync_aggregate_balance_changes<'a, T: EthSpec>(
+    state: &mut BeaconState<T>,
+    aggregate: &'a SyncAggregate<T>,
+    proposer_index: u64,
+    spec: &ChainSpec,
+) -> Result<impl Iterator<Item = SyncAggregateBalanceChange> + 'a, BlockProcessingError> {
+    let (participant_reward, proposer_reward) = compute_sync_aggregate_rewards(state, spec)?;
+    let current_sync_committee = state.current_sync_committee()?.clone();
+    let committee_indices = state.get_sync_committee_indices(&current_sync_committee)?;
+    let proposer_index = proposer_index as usize;
+
+    Ok(committee_indices
+        .into_iter()
+        .zip(aggregate.sync_committee_bits.iter())
+        .flat_map(move |(validator_index, participation_bit)| {
+            if participation_bit {
+                [
+                    Some(SyncAggregateBalanceChange::ParticipantReward {
+                        validator_index,
+                        reward: participant_reward,
+                    }),
+                    Some(SyncAggregateBalanceChange::ProposerReward {
+                        proposer_index,
+                        reward: proposer_reward,
+                    }),
+                ]
+            } else {
+                [
+                    Some(SyncAggregateBalanceChange::NonParticipantPenalty {
+                        validator_index,
+                        penalty: participant_reward,
+                    }),
+                    None,
+                ]
+            }
+        })
+        .flatten())
+}
+
 /// Compute the `(participant_reward, proposer_reward)` for a sync aggregate.
 ///
 /// The `state` should be the pre-state from the same slot as the block containing the aggregate.
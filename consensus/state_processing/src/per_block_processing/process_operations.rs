@@ -16,6 +16,7 @@ pub fn process_operations<T: EthSpec, Payload: AbstractExecPayload<T>>(
     ctxt: &mut ConsensusContext<T>,
     spec: &ChainSpec,
 ) -> Result<(), BlockProcessingError> {
+    let start = ctxt.start_stage();
     process_proposer_slashings(
         state,
         block_body.proposer_slashings(),
@@ -23,6 +24,9 @@ pub fn process_operations<T: EthSpec, Payload: AbstractExecPayload<T>>(
         ctxt,
         spec,
     )?;
+    ctxt.finish_stage(BlockProcessingStage::ProposerSlashings, start);
+
+    let start = ctxt.start_stage();
     process_attester_slashings(
         state,
         block_body.attester_slashings(),
@@ -30,12 +34,24 @@ pub fn process_operations<T: EthSpec, Payload: AbstractExecPayload<T>>(
         ctxt,
         spec,
     )?;
+    ctxt.finish_stage(BlockProcessingStage::AttesterSlashings, start);
+
+    let start = ctxt.start_stage();
     process_attestations(state, block_body, verify_signatures, ctxt, spec)?;
+    ctxt.finish_stage(BlockProcessingStage::Attestations, start);
+
+    let start = ctxt.start_stage();
     process_deposits(state, block_body.deposits(), spec)?;
+    ctxt.finish_stage(BlockProcessingStage::Deposits, start);
+
+    let start = ctxt.start_stage();
     process_exits(state, block_body.voluntary_exits(), verify_signatures, spec)?;
+    ctxt.finish_stage(BlockProcessingStage::VoluntaryExits, start);
 
     if let Ok(bls_to_execution_changes) = block_body.bls_to_execution_changes() {
+        let start = ctxt.start_stage();
         process_bls_to_execution_changes(state, bls_to_execution_changes, verify_signatures, spec)?;
+        ctxt.finish_stage(BlockProcessingStage::BlsToExecutionChanges, start);
     }
 
     Ok(())
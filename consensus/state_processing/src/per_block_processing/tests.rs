@@ -8,7 +8,8 @@ use crate::per_block_processing::errors::{
 use crate::{per_block_processing, BlockReplayError, BlockReplayer, StateProcessingStrategy};
 use crate::{
     per_block_processing::{process_operations, verify_exit::verify_exit},
-    BlockSignatureStrategy, ConsensusContext, VerifyBlockRoot, VerifySignatures,
+    BlockProcessingStage, BlockSignatureStrategy, ConsensusContext, VerifyBlockRoot,
+    VerifySignatures,
 };
 use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
 use lazy_static::lazy_static;
@@ -81,6 +82,64 @@ async fn valid_block_ok() {
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn stage_timings_recorded() {
+    let spec = MainnetEthSpec::default_spec();
+    let harness = get_harness::<MainnetEthSpec>(EPOCH_OFFSET, VALIDATOR_COUNT).await;
+    let state = harness.get_current_state();
+
+    let slot = state.slot();
+    let ((block, _), state) = harness
+        .make_block_return_pre_state(state, slot + Slot::new(1))
+        .await;
+
+    // Stages are not timed by default.
+    let mut ctxt = ConsensusContext::new(block.slot());
+    per_block_processing(
+        &mut state.clone(),
+        &block,
+        BlockSignatureStrategy::VerifyBulk,
+        StateProcessingStrategy::Accurate,
+        VerifyBlockRoot::True,
+        &mut ctxt,
+        &spec,
+    )
+    .unwrap();
+    assert!(ctxt.stage_timings().is_empty());
+
+    let mut ctxt = ConsensusContext::new(block.slot()).record_stage_timings();
+    per_block_processing(
+        &mut state.clone(),
+        &block,
+        BlockSignatureStrategy::VerifyBulk,
+        StateProcessingStrategy::Accurate,
+        VerifyBlockRoot::True,
+        &mut ctxt,
+        &spec,
+    )
+    .unwrap();
+    let stages = ctxt
+        .stage_timings()
+        .iter()
+        .map(|(stage, _)| *stage)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        &stages[..2],
+        &[
+            BlockProcessingStage::SignatureVerification,
+            BlockProcessingStage::BlockHeader
+        ]
+    );
+    for stage in [
+        BlockProcessingStage::Randao,
+        BlockProcessingStage::Attestations,
+        BlockProcessingStage::Deposits,
+        BlockProcessingStage::VoluntaryExits,
+    ] {
+        assert_eq!(stages.iter().filter(|s| **s == stage).count(), 1);
+    }
+}
+
 #[tokio::test]
 async fn invalid_block_header_state_slot() {
     let spec = MainnetEthSpec::default_spec();
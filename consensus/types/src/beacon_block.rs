@@ -15,6 +15,12 @@ use test_random_derive::TestRandom;
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
+/// The depth of the tree of the fields of a block, i.e. `ceil(log2(5))`.
+const BLOCK_FIELDS_DEPTH: usize = 3;
+
+/// The index of the `body` field within a block.
+const BODY_FIELD_INDEX: usize = 4;
+
 /// A block of the `BeaconChain`.
 #[superstruct(
     variants(Base, Altair, Merge, Capella, Deneb),
@@ -270,6 +276,35 @@ impl<'a, T: EthSpec, Payload: AbstractExecPayload<T>> BeaconBlockRef<'a, T, Payl
     pub fn execution_payload(&self) -> Result<Payload::Ref<'a>, Error> {
         self.body().execution_payload()
     }
+
+    /// Computes the root of the attestation at `index` in the body and a merkle proof of its
+    /// inclusion in the block, along with the generalized index of the attestation relative to the
+    /// block.
+    ///
+    /// The proof is in bottom-up order, and its root is the block root.
+    pub fn attestation_proof(&self, index: usize) -> Result<(usize, Hash256, Vec<Hash256>), Error> {
+        let (body_generalized_index, leaf, mut proof) = self.body().attestation_proof(index)?;
+
+        let header = self.block_header();
+        let leaves = [
+            header.slot.tree_hash_root(),
+            header.proposer_index.tree_hash_root(),
+            header.parent_root.tree_hash_root(),
+            header.state_root.tree_hash_root(),
+            header.body_root.tree_hash_root(),
+        ];
+        let tree = merkle_proof::MerkleTree::create(&leaves, BLOCK_FIELDS_DEPTH);
+        let (_, body_proof) = tree.generate_proof(BODY_FIELD_INDEX, BLOCK_FIELDS_DEPTH)?;
+        proof.extend(body_proof);
+
+        // Replace the root of the body's generalized index with the body's position in the block.
+        let body_depth = proof.len() - BLOCK_FIELDS_DEPTH;
+        let block_generalized_index = (1 << BLOCK_FIELDS_DEPTH) + BODY_FIELD_INDEX;
+        let generalized_index =
+            (block_generalized_index << body_depth) + (body_generalized_index - (1 << body_depth));
+
+        Ok((generalized_index, leaf, proof))
+    }
 }
 
 impl<'a, T: EthSpec, Payload: AbstractExecPayload<T>> BeaconBlockRefMut<'a, T, Payload> {
@@ -953,4 +988,44 @@ mod tests {
                 .expect_err("bad deneb block cannot be decoded");
         }
     }
+
+    #[test]
+    fn attestation_proofs_verify_against_block_root() {
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+
+        let blocks = [
+            BeaconBlock::Base(BeaconBlockBase::random_for_test(rng)),
+            BeaconBlock::Deneb(BeaconBlockDeneb::random_for_test(rng)),
+        ];
+        for mut block in blocks {
+            for _ in 0..3 {
+                block
+                    .body_mut()
+                    .attestations_mut()
+                    .push(Attestation::random_for_test(rng))
+                    .unwrap();
+            }
+            let block_root = block.canonical_root();
+            let attestations = block.body().attestations().clone();
+
+            for (index, attestation) in attestations.iter().enumerate() {
+                let (generalized_index, leaf, proof) =
+                    block.to_ref().attestation_proof(index).unwrap();
+                assert_eq!(leaf, attestation.tree_hash_root());
+                let depth = proof.len();
+                assert!(merkle_proof::verify_merkle_proof(
+                    leaf,
+                    &proof,
+                    depth,
+                    generalized_index - (1 << depth),
+                    block_root
+                ));
+            }
+
+            assert!(block
+                .to_ref()
+                .attestation_proof(attestations.len())
+                .is_err());
+        }
+    }
 }
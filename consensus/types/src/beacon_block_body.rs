@@ -7,8 +7,12 @@ use ssz_types::VariableList;
 use std::marker::PhantomData;
 use superstruct::superstruct;
 use test_random_derive::TestRandom;
+use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
+/// The index of the `attestations` field within the body, which is the same in every fork.
+pub const ATTESTATIONS_FIELD_INDEX: usize = 5;
+
 pub type KzgCommitments<T> =
     VariableList<KzgCommitment, <T as EthSpec>::MaxBlobCommitmentsPerBlock>;
 pub type KzgCommitmentOpts<T> =
@@ -111,6 +115,76 @@ impl<'a, T: EthSpec, Payload: AbstractExecPayload<T>> BeaconBlockBodyRef<'a, T,
             BeaconBlockBodyRef::Deneb { .. } => ForkName::Deneb,
         }
     }
+
+    /// Returns the hash tree roots of the fields of the body, in order.
+    pub fn field_roots(&self) -> Vec<Hash256> {
+        let mut roots = vec![
+            self.randao_reveal().tree_hash_root(),
+            self.eth1_data().tree_hash_root(),
+            self.graffiti().tree_hash_root(),
+            self.proposer_slashings().tree_hash_root(),
+            self.attester_slashings().tree_hash_root(),
+            self.attestations().tree_hash_root(),
+            self.deposits().tree_hash_root(),
+            self.voluntary_exits().tree_hash_root(),
+        ];
+        match self {
+            Self::Base(_) => {}
+            Self::Altair(body) => {
+                roots.push(body.sync_aggregate.tree_hash_root());
+            }
+            Self::Merge(body) => {
+                roots.push(body.sync_aggregate.tree_hash_root());
+                roots.push(body.execution_payload.tree_hash_root());
+            }
+            Self::Capella(body) => {
+                roots.push(body.sync_aggregate.tree_hash_root());
+                roots.push(body.execution_payload.tree_hash_root());
+                roots.push(body.bls_to_execution_changes.tree_hash_root());
+            }
+            Self::Deneb(body) => {
+                roots.push(body.sync_aggregate.tree_hash_root());
+                roots.push(body.execution_payload.tree_hash_root());
+                roots.push(body.bls_to_execution_changes.tree_hash_root());
+                roots.push(body.blob_kzg_commitments.tree_hash_root());
+            }
+        }
+        roots
+    }
+
+    /// Computes the root of the attestation at `index` and a merkle proof of its inclusion in the
+    /// body, along with the generalized index of the attestation relative to the body.
+    ///
+    /// The proof is in bottom-up order.
+    pub fn attestation_proof(&self, index: usize) -> Result<(usize, Hash256, Vec<Hash256>), Error> {
+        let attestations = self.attestations();
+        if index >= attestations.len() {
+            return Err(Error::IndexNotSupported(index));
+        }
+
+        // 1. Prove the attestation within the list, then mix in the length of the list.
+        let list_depth = T::MaxAttestations::to_usize().trailing_zeros() as usize;
+        let leaves = attestations
+            .iter()
+            .map(|attestation| attestation.tree_hash_root())
+            .collect::<Vec<_>>();
+        let tree = merkle_proof::MerkleTree::create(&leaves, list_depth);
+        let (leaf, mut proof) = tree.generate_proof(index, list_depth)?;
+        proof.push((attestations.len() as u64).tree_hash_root());
+
+        // 2. Prove the list within the body.
+        let field_roots = self.field_roots();
+        let body_depth = field_roots.len().next_power_of_two().trailing_zeros() as usize;
+        let tree = merkle_proof::MerkleTree::create(&field_roots, body_depth);
+        let (_, field_proof) = tree.generate_proof(ATTESTATIONS_FIELD_INDEX, body_depth)?;
+        proof.extend(field_proof);
+
+        // The elements are below the left child of the field, its right child is the length.
+        let field_generalized_index = (1 << body_depth) + ATTESTATIONS_FIELD_INDEX;
+        let generalized_index = ((field_generalized_index * 2) << list_depth) + index;
+
+        Ok((generalized_index, leaf, proof))
+    }
 }
 
 // We can convert pre-Bellatrix block bodies without payloads into block bodies "with" payloads.
@@ -46,6 +46,8 @@ mod tests;
 mod tree_hash_cache;
 
 pub const CACHED_EPOCHS: usize = 3;
+/// The position of the `historical_summaries` field in Capella and later states.
+pub const HISTORICAL_SUMMARIES_FIELD_INDEX: usize = 27;
 const MAX_RANDOM_BYTE: u64 = (1 << 8) - 1;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -1823,32 +1825,19 @@ impl<T: EthSpec> BeaconState<T> {
         &mut self,
         generalized_index: usize,
     ) -> Result<Vec<Hash256>, Error> {
-        // 1. Convert generalized index to field index.
-        let field_index = match generalized_index {
-            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX
-            | light_client_update::NEXT_SYNC_COMMITTEE_INDEX => {
-                // Sync committees are top-level fields, subtract off the generalized indices
-                // for the internal nodes. Result should be 22 or 23, the field offset of the committee
-                // in the `BeaconState`:
-                // https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/beacon-chain.md#beaconstate
-                generalized_index
-                    .checked_sub(tree_hash_cache::NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES)
-                    .ok_or(Error::IndexNotSupported(generalized_index))?
-            }
-            light_client_update::FINALIZED_ROOT_INDEX => {
-                // Finalized root is the right child of `finalized_checkpoint`, divide by two to get
-                // the generalized index of `state.finalized_checkpoint`.
-                let finalized_checkpoint_generalized_index = generalized_index / 2;
-                // Subtract off the internal nodes. Result should be 105/2 - 32 = 20 which matches
-                // position of `finalized_checkpoint` in `BeaconState`.
-                finalized_checkpoint_generalized_index
-                    .checked_sub(tree_hash_cache::NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES)
-                    .ok_or(Error::IndexNotSupported(generalized_index))?
-            }
-            _ => return Err(Error::IndexNotSupported(generalized_index)),
-        };
+        self.compute_merkle_proof_and_leaf(generalized_index)
+            .map(|(_, proof)| proof)
+    }
 
-        // 2. Get all `BeaconState` leaves.
+    /// Compute the leaf at `generalized_index` and a merkle proof of its inclusion in the state.
+    ///
+    /// Supported indices are those of the top-level fields, the finalized root and the elements of
+    /// `historical_summaries`. The proof is in bottom-up order.
+    pub fn compute_merkle_proof_and_leaf(
+        &mut self,
+        generalized_index: usize,
+    ) -> Result<(Hash256, Vec<Hash256>), Error> {
+        // 1. Get all `BeaconState` leaves.
         let mut cache = self
             .tree_hash_cache_mut()
             .take()
@@ -1856,18 +1845,86 @@ impl<T: EthSpec> BeaconState<T> {
         let leaves = cache.recalculate_tree_hash_leaves(self)?;
         self.tree_hash_cache_mut().restore(cache);
 
-        // 3. Make deposit tree.
+        // 2. Prove the leaf within its top-level field, and find the position of that field in
+        // the `BeaconState`:
+        // https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/beacon-chain.md#beaconstate
+        let (leaf, field_index, mut proof) =
+            if generalized_index == light_client_update::FINALIZED_ROOT_INDEX {
+                // Finalized root is the right child of `finalized_checkpoint`, divide by two to get
+                // the generalized index of `state.finalized_checkpoint`, then subtract off the
+                // internal nodes. Patch in the finalized epoch to prove the root within the
+                // checkpoint.
+                let field_index = (generalized_index / 2)
+                    .checked_sub(tree_hash_cache::NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES)
+                    .ok_or(Error::IndexNotSupported(generalized_index))?;
+                let finalized_checkpoint = self.finalized_checkpoint();
+                (
+                    finalized_checkpoint.root,
+                    field_index,
+                    vec![finalized_checkpoint.epoch.tree_hash_root()],
+                )
+            } else if let Some(field_index) = generalized_index
+                .checked_sub(tree_hash_cache::NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES)
+                .filter(|field_index| *field_index < leaves.len())
+            {
+                // Top-level fields (e.g. the sync committees), subtract off the generalized
+                // indices for the internal nodes.
+                (leaves[field_index], field_index, vec![])
+            } else if let Some((leaf, proof)) = self.historical_summary_proof(generalized_index)? {
+                (leaf, HISTORICAL_SUMMARIES_FIELD_INDEX, proof)
+            } else {
+                return Err(Error::IndexNotSupported(generalized_index));
+            };
+
+        // 3. Make the tree of the fields and prove the field within the state.
         // Use the depth of the `BeaconState` fields (i.e. `log2(32) = 5`).
         let depth = light_client_update::CURRENT_SYNC_COMMITTEE_PROOF_LEN;
         let tree = merkle_proof::MerkleTree::create(&leaves, depth);
-        let (_, mut proof) = tree.generate_proof(field_index, depth)?;
+        let (_, field_proof) = tree.generate_proof(field_index, depth)?;
+        proof.extend(field_proof);
 
-        // 4. If we're proving the finalized root, patch in the finalized epoch to complete the proof.
-        if generalized_index == light_client_update::FINALIZED_ROOT_INDEX {
-            proof.insert(0, self.finalized_checkpoint().epoch.tree_hash_root());
-        }
+        Ok((leaf, proof))
+    }
+
+    /// Returns the generalized index of the element of `historical_summaries` at `index`.
+    pub fn historical_summary_generalized_index(index: usize) -> usize {
+        let field_generalized_index = tree_hash_cache::NUM_BEACON_STATE_HASH_TREE_ROOT_LEAVES
+            + HISTORICAL_SUMMARIES_FIELD_INDEX;
+        // The elements are below the left child of the field, its right child is the length.
+        let depth = T::HistoricalRootsLimit::to_usize().trailing_zeros();
+        ((field_generalized_index * 2) << depth) + index
+    }
+
+    /// Compute the element of `historical_summaries` at `generalized_index` and a proof of its
+    /// inclusion in the `historical_summaries` field.
+    ///
+    /// Returns `None` if `generalized_index` isn't the index of an element in the list.
+    fn historical_summary_proof(
+        &self,
+        generalized_index: usize,
+    ) -> Result<Option<(Hash256, Vec<Hash256>)>, Error> {
+        let Ok(historical_summaries) = self.historical_summaries() else {
+            return Ok(None);
+        };
+        let Some(index) = generalized_index
+            .checked_sub(Self::historical_summary_generalized_index(0))
+            .filter(|index| *index < historical_summaries.len())
+        else {
+            return Ok(None);
+        };
+
+        let depth = T::HistoricalRootsLimit::to_usize().trailing_zeros() as usize;
+        let leaves = historical_summaries
+            .iter()
+            .map(|summary| summary.tree_hash_root())
+            .collect::<Vec<_>>();
+        let tree = merkle_proof::MerkleTree::create(&leaves, depth);
+        let (leaf, mut proof) = tree.generate_proof(index, depth)?;
+
+        // Mix in the length of the list.
+        proof.push((historical_summaries.len() as u64).tree_hash_root());
 
-        Ok(proof)
+        Ok(Some((leaf, proof)))
     }
 }
 
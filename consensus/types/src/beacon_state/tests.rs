@@ -6,8 +6,9 @@ use beacon_chain::test_utils::{
     DEFAULT_ETH1_BLOCK_HASH,
 };
 use beacon_chain::types::{
-    test_utils::TestRandom, BeaconState, BeaconStateAltair, BeaconStateBase, BeaconStateError,
-    ChainSpec, CloneConfig, Domain, Epoch, EthSpec, FixedVector, Hash256, Keypair, MainnetEthSpec,
+    beacon_state::HISTORICAL_SUMMARIES_FIELD_INDEX, light_client_update, test_utils::TestRandom,
+    BeaconState, BeaconStateAltair, BeaconStateBase, BeaconStateError, ChainSpec, CloneConfig,
+    Domain, Epoch, EthSpec, FixedVector, ForkName, Hash256, Keypair, MainnetEthSpec,
     MinimalEthSpec, RelativeEpoch, Slot,
 };
 use safe_arith::SafeArith;
@@ -604,3 +605,74 @@ fn tree_hash_cache_linear_history_long_skip() {
         target_slot
     );
 }
+
+#[test]
+fn merkle_proofs_verify_against_state_root() {
+    let validator_count = 16;
+    let keypairs = generate_deterministic_keypairs(validator_count);
+
+    let spec = &ForkName::Capella.make_genesis_spec(MinimalEthSpec::default_spec());
+
+    let mut state: BeaconState<MinimalEthSpec> = interop_genesis_state_with_eth1(
+        &keypairs,
+        0,
+        Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH),
+        None,
+        spec,
+    )
+    .unwrap();
+
+    // Advance far enough for two historical summaries to be appended.
+    let target_slot = Slot::new(2 * MinimalEthSpec::slots_per_historical_root() as u64);
+    while state.slot() < target_slot {
+        let state_root = state.update_tree_hash_cache().unwrap();
+        per_slot_processing(&mut state, Some(state_root), spec).unwrap();
+    }
+    assert_eq!(state.historical_summaries().unwrap().len(), 2);
+
+    let state_root = state.update_tree_hash_cache().unwrap();
+    let generalized_indices = [
+        32 + HISTORICAL_SUMMARIES_FIELD_INDEX,
+        light_client_update::FINALIZED_ROOT_INDEX,
+        light_client_update::CURRENT_SYNC_COMMITTEE_INDEX,
+        light_client_update::NEXT_SYNC_COMMITTEE_INDEX,
+        BeaconState::<MinimalEthSpec>::historical_summary_generalized_index(0),
+        BeaconState::<MinimalEthSpec>::historical_summary_generalized_index(1),
+    ];
+    for generalized_index in generalized_indices {
+        let (leaf, proof) = state
+            .compute_merkle_proof_and_leaf(generalized_index)
+            .unwrap();
+        let depth = generalized_index.ilog2() as usize;
+        assert_eq!(proof.len(), depth);
+        assert!(merkle_proof::verify_merkle_proof(
+            leaf,
+            &proof,
+            depth,
+            generalized_index - (1 << depth),
+            state_root
+        ));
+    }
+
+    // The leaf of a historical summary is the root of that summary.
+    let (leaf, _) = state
+        .compute_merkle_proof_and_leaf(
+            BeaconState::<MinimalEthSpec>::historical_summary_generalized_index(1),
+        )
+        .unwrap();
+    assert_eq!(
+        leaf,
+        state.historical_summaries().unwrap()[1].tree_hash_root()
+    );
+
+    // Internal nodes and elements beyond the length of the list are not supported.
+    for generalized_index in [
+        1,
+        BeaconState::<MinimalEthSpec>::historical_summary_generalized_index(2),
+    ] {
+        assert!(matches!(
+            state.compute_merkle_proof_and_leaf(generalized_index),
+            Err(BeaconStateError::IndexNotSupported(i)) if i == generalized_index
+        ));
+    }
+}
@@ -97,6 +97,8 @@ pub mod withdrawal;
 pub mod slot_data;
 #[cfg(feature = "sqlite")]
 pub mod sqlite;
+#[macro_use]
+pub mod stable_container;
 
 pub mod blob_sidecar;
 pub mod sidecar;
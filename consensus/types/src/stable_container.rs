@@ -0,0 +1,738 @@
+//! Support for the `StableContainer` and `Profile` SSZ types of EIP-7495.
+//!
+//! A `StableContainer[N]` is a container whose fields are all optional and which has capacity for
+//! up to `N` fields. It is serialized as a `Bitvector[N]` of the fields which are present, followed
+//! by the present fields serialized as a regular container. Its tree hash root is the root of the
+//! field roots padded with zero chunks to `N` leaves, mixed in with the root of the bitvector, so
+//! fields can be added to the container without changing the generalized index of any other field.
+//!
+//! A `Profile[B]` is a view of the `StableContainer` `B` in which some of the fields are required
+//! and the others are optional or absent. Only the presence of the optional fields is serialized,
+//! and its tree hash root is the root of the equivalent `B`.
+//!
+//! Both are defined with macros, see `stable_container!` and `profile!`.
+
+use crate::Hash256;
+use ethereum_hashing::hash32_concat;
+use ssz::DecodeError;
+
+#[doc(hidden)]
+pub use {ssz as __ssz, tree_hash as __tree_hash};
+
+/// Returns the number of bytes in a bitvector of `len` bits.
+pub fn bitvector_len(len: usize) -> usize {
+    len.saturating_add(7) / 8
+}
+
+/// Returns the mask of bit `i` within its byte of a bitvector.
+fn bit_mask(i: usize) -> u8 {
+    1u8.checked_shl((i % 8) as u32).unwrap_or(0)
+}
+
+/// Appends the bitvector of `bits`, padded with zeros to `len` bits, to `buf`.
+pub fn encode_bitvector(bits: &[bool], len: usize, buf: &mut Vec<u8>) {
+    let start = buf.len();
+    buf.resize(start.saturating_add(bitvector_len(len)), 0);
+    for (i, _) in bits.iter().enumerate().filter(|(_, bit)| **bit) {
+        if let Some(byte) = buf.get_mut(start.saturating_add(i / 8)) {
+            *byte |= bit_mask(i);
+        }
+    }
+}
+
+/// Decodes a bitvector of `len` bits from the start of `bytes`, returning the first `num_bits`
+/// bits and the remaining bytes.
+///
+/// Bits beyond `num_bits` must be unset, since they don't correspond to any field.
+pub fn decode_bitvector(
+    bytes: &[u8],
+    len: usize,
+    num_bits: usize,
+) -> Result<(Vec<bool>, &[u8]), DecodeError> {
+    let bitvector_len = bitvector_len(len);
+    if bytes.len() < bitvector_len {
+        return Err(DecodeError::InvalidByteLength {
+            len: bytes.len(),
+            expected: bitvector_len,
+        });
+    }
+    let (bitvector, rest) = bytes.split_at(bitvector_len);
+
+    let bit = |i: usize| {
+        bitvector
+            .get(i / 8)
+            .map_or(false, |byte| byte & bit_mask(i) != 0)
+    };
+    if let Some(i) = (num_bits..bitvector_len.saturating_mul(8)).find(|i| bit(*i)) {
+        return Err(DecodeError::BytesInvalid(format!(
+            "bit {} is set but only {} fields are defined",
+            i, num_bits
+        )));
+    }
+
+    Ok(((0..num_bits).map(bit).collect(), rest))
+}
+
+/// Returns the tree hash root of a `StableContainer` with capacity for `max_fields`, given the
+/// roots of its fields, which are `None` if the field is absent.
+pub fn stable_container_root(field_roots: &[Option<Hash256>], max_fields: usize) -> Hash256 {
+    let mut chunks = Vec::with_capacity(field_roots.len().saturating_mul(32));
+    for root in field_roots {
+        chunks.extend_from_slice(root.unwrap_or_else(Hash256::zero).as_bytes());
+    }
+    let fields_root = tree_hash::merkle_root(&chunks, max_fields);
+
+    let active_fields = field_roots.iter().map(Option::is_some).collect::<Vec<_>>();
+    let mut bitvector = vec![];
+    encode_bitvector(&active_fields, max_fields, &mut bitvector);
+    let active_fields_root =
+        tree_hash::merkle_root(&bitvector, max_fields.saturating_add(255) / 256);
+
+    Hash256::from_slice(&hash32_concat(
+        fields_root.as_bytes(),
+        active_fields_root.as_bytes(),
+    ))
+}
+
+/// Defines a `StableContainer` with capacity for `N` fields, e.g.
+///
+/// ```
+/// use types::stable_container;
+///
+/// stable_container! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Shape[4] {
+///         pub side: u16,
+///         pub color: u8,
+///         pub radius: u16,
+///     }
+/// }
+///
+/// let shape = Shape { side: Some(16), color: Some(2), radius: None };
+/// ```
+///
+/// Each field is wrapped in an `Option`, which is `None` if the field is absent. `Default`,
+/// `ssz::Encode`, `ssz::Decode` and `tree_hash::TreeHash` are implemented for the container.
+#[macro_export]
+macro_rules! stable_container {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident [$max_fields:expr] {
+            $(
+                $(#[$field_attr:meta])*
+                $field_vis:vis $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $field_vis $field: Option<$ty>,
+            )*
+        }
+
+        impl $name {
+            /// The number of fields which the container has capacity for.
+            pub const MAX_FIELDS: usize = $max_fields;
+            /// The names of the defined fields, in order.
+            pub const FIELD_NAMES: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            /// Returns whether each of the defined fields is present.
+            pub fn active_fields(&self) -> Vec<bool> {
+                vec![$(self.$field.is_some()),*]
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self {
+                    $($field: None,)*
+                }
+            }
+        }
+
+        impl $crate::stable_container::__ssz::Encode for $name {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                use $crate::stable_container::__ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
+
+                let mut len = $crate::stable_container::bitvector_len(Self::MAX_FIELDS);
+                $(
+                    if let Some(value) = &self.$field {
+                        if !<$ty as Encode>::is_ssz_fixed_len() {
+                            len = len.saturating_add(BYTES_PER_LENGTH_OFFSET);
+                        }
+                        len = len.saturating_add(value.ssz_bytes_len());
+                    }
+                )*
+                len
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                use $crate::stable_container::__ssz::{Encode, SszEncoder};
+
+                $crate::stable_container::encode_bitvector(
+                    &self.active_fields(),
+                    Self::MAX_FIELDS,
+                    buf,
+                );
+
+                let mut num_fixed_bytes: usize = 0;
+                $(
+                    if self.$field.is_some() {
+                        num_fixed_bytes =
+                            num_fixed_bytes.saturating_add(<$ty as Encode>::ssz_fixed_len());
+                    }
+                )*
+                let mut encoder = SszEncoder::container(buf, num_fixed_bytes);
+                $(
+                    if let Some(value) = &self.$field {
+                        encoder.append(value);
+                    }
+                )*
+                encoder.finalize();
+            }
+        }
+
+        impl $crate::stable_container::__ssz::Decode for $name {
+            fn is_ssz_fixed_len() -> bool {
+                false
+            }
+
+            fn from_ssz_bytes(
+                bytes: &[u8],
+            ) -> Result<Self, $crate::stable_container::__ssz::DecodeError> {
+                use $crate::stable_container::__ssz::SszDecoderBuilder;
+
+                let (active_fields, bytes) = $crate::stable_container::decode_bitvector(
+                    bytes,
+                    Self::MAX_FIELDS,
+                    Self::FIELD_NAMES.len(),
+                )?;
+
+                let mut builder = SszDecoderBuilder::new(bytes);
+                let mut active = active_fields.iter().copied();
+                $(
+                    if active.next() == Some(true) {
+                        builder.register_type::<$ty>()?;
+                    }
+                )*
+                let mut decoder = builder.build()?;
+
+                let mut active = active_fields.iter().copied();
+                Ok(Self {
+                    $(
+                        $field: if active.next() == Some(true) {
+                            Some(decoder.decode_next::<$ty>()?)
+                        } else {
+                            None
+                        },
+                    )*
+                })
+            }
+        }
+
+        impl $crate::stable_container::__tree_hash::TreeHash for $name {
+            fn tree_hash_type() -> $crate::stable_container::__tree_hash::TreeHashType {
+                $crate::stable_container::__tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> $crate::stable_container::__tree_hash::PackedEncoding {
+                unreachable!("StableContainer should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("StableContainer should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> $crate::Hash256 {
+                $crate::stable_container::stable_container_root(
+                    &[$(self.$field.as_ref().map(|value| {
+                        $crate::stable_container::__tree_hash::TreeHash::tree_hash_root(value)
+                    })),*],
+                    Self::MAX_FIELDS,
+                )
+            }
+        }
+    };
+}
+
+/// Defines a `Profile` of a container defined with `stable_container!`, e.g.
+///
+/// ```
+/// use types::{profile, stable_container};
+///
+/// stable_container! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Shape[4] {
+///         pub side: u16,
+///         pub color: u8,
+///         pub radius: u16,
+///     }
+/// }
+///
+/// profile! {
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Square(Shape) {
+///         required pub side: u16,
+///         optional pub color: u8,
+///     }
+/// }
+///
+/// let square = Square { side: 16, color: None };
+/// ```
+///
+/// Fields are marked as either `required` or `optional`, and optional fields are wrapped in an
+/// `Option`. Fields must appear in the same order as in the base container, and fields of the base
+/// container which are omitted are always absent. `ssz::Encode`, `ssz::Decode`,
+/// `tree_hash::TreeHash` and conversion into the base container are implemented for the profile.
+#[macro_export]
+macro_rules! profile {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident ($base:ty) {
+            $(
+                $(#[$field_attr:meta])*
+                $kind:ident $field_vis:vis $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $field_vis $field: $crate::__profile_field!(type $kind $ty),
+            )*
+        }
+
+        impl $name {
+            /// Returns whether each of the optional fields is present.
+            pub fn optional_fields(&self) -> Vec<bool> {
+                let mut optional_fields = vec![];
+                $(
+                    if $crate::__profile_field!(is_optional $kind) {
+                        optional_fields.push($crate::__profile_field!(ref $kind self.$field).is_some());
+                    }
+                )*
+                optional_fields
+            }
+
+            fn num_optional_fields() -> usize {
+                [$($crate::__profile_field!(is_optional $kind)),*]
+                    .iter()
+                    .filter(|optional| **optional)
+                    .count()
+            }
+        }
+
+        impl From<$name> for $base {
+            #[allow(clippy::needless_update)]
+            fn from(profile: $name) -> Self {
+                Self {
+                    $($field: $crate::__profile_field!(into_option $kind profile.$field),)*
+                    ..Default::default()
+                }
+            }
+        }
+
+        impl $crate::stable_container::__ssz::Encode for $name {
+            fn is_ssz_fixed_len() -> bool {
+                use $crate::stable_container::__ssz::Encode;
+
+                true $(&& !$crate::__profile_field!(is_optional $kind)
+                    && <$ty as Encode>::is_ssz_fixed_len())*
+            }
+
+            fn ssz_fixed_len() -> usize {
+                use $crate::stable_container::__ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
+
+                if <Self as Encode>::is_ssz_fixed_len() {
+                    0 $(.saturating_add(<$ty as Encode>::ssz_fixed_len()))*
+                } else {
+                    BYTES_PER_LENGTH_OFFSET
+                }
+            }
+
+            fn ssz_bytes_len(&self) -> usize {
+                use $crate::stable_container::__ssz::{Encode, BYTES_PER_LENGTH_OFFSET};
+
+                let mut len = $crate::stable_container::bitvector_len(Self::num_optional_fields());
+                $(
+                    if let Some(value) = $crate::__profile_field!(ref $kind self.$field) {
+                        if !<$ty as Encode>::is_ssz_fixed_len() {
+                            len = len.saturating_add(BYTES_PER_LENGTH_OFFSET);
+                        }
+                        len = len.saturating_add(value.ssz_bytes_len());
+                    }
+                )*
+                len
+            }
+
+            fn ssz_append(&self, buf: &mut Vec<u8>) {
+                use $crate::stable_container::__ssz::{Encode, SszEncoder};
+
+                $crate::stable_container::encode_bitvector(
+                    &self.optional_fields(),
+                    Self::num_optional_fields(),
+                    buf,
+                );
+
+                let mut num_fixed_bytes: usize = 0;
+                $(
+                    if $crate::__profile_field!(ref $kind self.$field).is_some() {
+                        num_fixed_bytes =
+                            num_fixed_bytes.saturating_add(<$ty as Encode>::ssz_fixed_len());
+                    }
+                )*
+                let mut encoder = SszEncoder::container(buf, num_fixed_bytes);
+                $(
+                    if let Some(value) = $crate::__profile_field!(ref $kind self.$field) {
+                        encoder.append(value);
+                    }
+                )*
+                encoder.finalize();
+            }
+        }
+
+        impl $crate::stable_container::__ssz::Decode for $name {
+            fn is_ssz_fixed_len() -> bool {
+                <Self as $crate::stable_container::__ssz::Encode>::is_ssz_fixed_len()
+            }
+
+            fn ssz_fixed_len() -> usize {
+                <Self as $crate::stable_container::__ssz::Encode>::ssz_fixed_len()
+            }
+
+            fn from_ssz_bytes(
+                bytes: &[u8],
+            ) -> Result<Self, $crate::stable_container::__ssz::DecodeError> {
+                use $crate::stable_container::__ssz::SszDecoderBuilder;
+
+                let num_optional_fields = Self::num_optional_fields();
+                let (optional_fields, bytes) = $crate::stable_container::decode_bitvector(
+                    bytes,
+                    num_optional_fields,
+                    num_optional_fields,
+                )?;
+
+                let mut optional = optional_fields.iter().copied();
+                let present = vec![$(
+                    !$crate::__profile_field!(is_optional $kind) || optional.next() == Some(true)
+                ),*];
+
+                let mut builder = SszDecoderBuilder::new(bytes);
+                let mut active = present.iter().copied();
+                $(
+                    if active.next() == Some(true) {
+                        builder.register_type::<$ty>()?;
+                    }
+                )*
+                let mut decoder = builder.build()?;
+
+                let mut active = present.iter().copied();
+                Ok(Self {
+                    $(
+                        $field: {
+                            let value = if active.next() == Some(true) {
+                                Some(decoder.decode_next::<$ty>()?)
+                            } else {
+                                None
+                            };
+                            $crate::__profile_field!(from_option $kind value, stringify!($field))
+                        },
+                    )*
+                })
+            }
+        }
+
+        impl $crate::stable_container::__tree_hash::TreeHash for $name {
+            fn tree_hash_type() -> $crate::stable_container::__tree_hash::TreeHashType {
+                $crate::stable_container::__tree_hash::TreeHashType::Container
+            }
+
+            fn tree_hash_packed_encoding(&self) -> $crate::stable_container::__tree_hash::PackedEncoding {
+                unreachable!("Profile should never be packed.")
+            }
+
+            fn tree_hash_packing_factor() -> usize {
+                unreachable!("Profile should never be packed.")
+            }
+
+            fn tree_hash_root(&self) -> $crate::Hash256 {
+                let mut field_roots = vec![None; <$base>::FIELD_NAMES.len()];
+                $(
+                    if let Some(value) = $crate::__profile_field!(ref $kind self.$field) {
+                        // The field exists in the base container, otherwise `From` wouldn't compile.
+                        if let Some(root) = <$base>::FIELD_NAMES
+                            .iter()
+                            .position(|name| *name == stringify!($field))
+                            .and_then(|index| field_roots.get_mut(index))
+                        {
+                            *root = Some($crate::stable_container::__tree_hash::TreeHash::tree_hash_root(value));
+                        }
+                    }
+                )*
+                $crate::stable_container::stable_container_root(&field_roots, <$base>::MAX_FIELDS)
+            }
+        }
+    };
+}
+
+/// Implementation detail of `profile!`, which handles `required` and `optional` fields.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __profile_field {
+    (type required $ty:ty) => { $ty };
+    (type optional $ty:ty) => { Option<$ty> };
+    (is_optional required) => { false };
+    (is_optional optional) => { true };
+    (ref required $value:expr) => { Some(&$value) };
+    (ref optional $value:expr) => { $value.as_ref() };
+    (into_option required $value:expr) => { Some($value) };
+    (into_option optional $value:expr) => { $value };
+    (from_option required $value:expr, $name:expr) => {
+        $value.ok_or_else(|| {
+            $crate::stable_container::__ssz::DecodeError::BytesInvalid(format!(
+                "missing field {}",
+                $name
+            ))
+        })?
+    };
+    (from_option optional $value:expr, $name:expr) => { $value };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use crate::{typenum::U8, BitList, BitVector, VariableList};
+    use ethereum_hashing::hash;
+    use rand::RngCore;
+    use ssz::{Decode, Encode};
+    use tree_hash::TreeHash;
+
+    stable_container! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Fixed[4] {
+            a: u8,
+            b: u64,
+            c: u32,
+        }
+    }
+
+    stable_container! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Variable[300] {
+            a: u16,
+            b: VariableList<u16, U8>,
+            c: Fixed,
+            d: BitList<U8>,
+            e: BitVector<U8>,
+        }
+    }
+
+    profile! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct RequiredOnly(Fixed) {
+            required a: u8,
+            required c: u32,
+        }
+    }
+
+    profile! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Mixed(Variable) {
+            required a: u16,
+            optional b: VariableList<u16, U8>,
+            optional c: Fixed,
+            required e: BitVector<U8>,
+        }
+    }
+
+    fn random_option<T: TestRandom>(rng: &mut XorShiftRng) -> Option<T> {
+        (rng.next_u32() % 2 == 0).then(|| T::random_for_test(rng))
+    }
+
+    fn random_fixed(rng: &mut XorShiftRng) -> Fixed {
+        Fixed {
+            a: random_option(rng),
+            b: random_option(rng),
+            c: random_option(rng),
+        }
+    }
+
+    fn random_variable(rng: &mut XorShiftRng) -> Variable {
+        Variable {
+            a: (rng.next_u32() % 2 == 0).then(|| rng.next_u32() as u16),
+            b: (rng.next_u32() % 2 == 0)
+                .then(|| VariableList::new(vec![rng.next_u32() as u16; 3]).unwrap()),
+            c: (rng.next_u32() % 2 == 0).then(|| random_fixed(rng)),
+            d: random_option(rng),
+            e: random_option(rng),
+        }
+    }
+
+    /// Merkleizes `chunks` padded to `limit` leaves by hashing every layer in full.
+    fn reference_merkleize(mut chunks: Vec<[u8; 32]>, limit: usize) -> [u8; 32] {
+        chunks.resize(limit.next_power_of_two(), [0; 32]);
+        while chunks.len() > 1 {
+            chunks = chunks
+                .chunks(2)
+                .map(|pair| {
+                    let mut root = [0; 32];
+                    root.copy_from_slice(&hash(&[pair[0], pair[1]].concat()));
+                    root
+                })
+                .collect();
+        }
+        chunks[0]
+    }
+
+    /// Computes the tree hash root of a `StableContainer` as written in EIP-7495.
+    fn reference_root(field_roots: &[Option<Hash256>], max_fields: usize) -> Hash256 {
+        let chunks = field_roots
+            .iter()
+            .map(|root| root.unwrap_or_default().to_fixed_bytes())
+            .collect();
+        let fields_root = reference_merkleize(chunks, max_fields);
+
+        let mut bits = vec![0u8; (max_fields + 7) / 8];
+        for (i, root) in field_roots.iter().enumerate() {
+            if root.is_some() {
+                bits[i / 8] |= 1 << (i % 8);
+            }
+        }
+        let bit_chunks = bits
+            .chunks(32)
+            .map(|chunk| {
+                let mut padded = [0; 32];
+                padded[..chunk.len()].copy_from_slice(chunk);
+                padded
+            })
+            .collect();
+        let active_fields_root = reference_merkleize(bit_chunks, (max_fields + 255) / 256);
+
+        Hash256::from_slice(&hash(&[fields_root, active_fields_root].concat()))
+    }
+
+    fn fixed_field_roots(fixed: &Fixed) -> Vec<Option<Hash256>> {
+        vec![
+            fixed.a.map(|a| a.tree_hash_root()),
+            fixed.b.map(|b| b.tree_hash_root()),
+            fixed.c.map(|c| c.tree_hash_root()),
+        ]
+    }
+
+    fn variable_field_roots(variable: &Variable) -> Vec<Option<Hash256>> {
+        vec![
+            variable.a.map(|a| a.tree_hash_root()),
+            variable.b.as_ref().map(TreeHash::tree_hash_root),
+            variable
+                .c
+                .as_ref()
+                .map(|c| reference_root(&fixed_field_roots(c), Fixed::MAX_FIELDS)),
+            variable.d.as_ref().map(TreeHash::tree_hash_root),
+            variable.e.as_ref().map(TreeHash::tree_hash_root),
+        ]
+    }
+
+    fn round_trip<T: Encode + Decode + PartialEq + std::fmt::Debug>(value: &T) {
+        let bytes = value.as_ssz_bytes();
+        assert_eq!(bytes.len(), value.ssz_bytes_len());
+        assert_eq!(&T::from_ssz_bytes(&bytes).unwrap(), value);
+    }
+
+    #[test]
+    fn random_round_trips() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        for _ in 0..256 {
+            let fixed = random_fixed(&mut rng);
+            round_trip(&fixed);
+            assert_eq!(
+                fixed.tree_hash_root(),
+                reference_root(&fixed_field_roots(&fixed), Fixed::MAX_FIELDS)
+            );
+
+            let variable = random_variable(&mut rng);
+            round_trip(&variable);
+            assert_eq!(
+                variable.tree_hash_root(),
+                reference_root(&variable_field_roots(&variable), Variable::MAX_FIELDS)
+            );
+        }
+    }
+
+    #[test]
+    fn profiles_round_trip_and_hash_as_base() {
+        let mut rng = XorShiftRng::from_seed([42; 16]);
+        for _ in 0..256 {
+            let required = RequiredOnly {
+                a: u8::random_for_test(&mut rng),
+                c: u32::random_for_test(&mut rng),
+            };
+            round_trip(&required);
+            assert_eq!(
+                required.tree_hash_root(),
+                Fixed::from(required.clone()).tree_hash_root()
+            );
+
+            let base = random_variable(&mut rng);
+            let mixed = Mixed {
+                a: base.a.unwrap_or_default(),
+                b: base.b,
+                c: base.c,
+                e: base.e.unwrap_or_default(),
+            };
+            round_trip(&mixed);
+            assert_eq!(
+                mixed.tree_hash_root(),
+                Variable::from(mixed.clone()).tree_hash_root()
+            );
+        }
+    }
+
+    #[test]
+    fn serialization_layout() {
+        let fixed = Fixed {
+            a: Some(1),
+            b: None,
+            c: Some(2),
+        };
+        assert_eq!(fixed.as_ssz_bytes(), vec![0b101, 1, 2, 0, 0, 0]);
+
+        // Profiles without optional fields are serialized as regular fixed-length containers.
+        let required = RequiredOnly { a: 1, c: 2 };
+        assert!(<RequiredOnly as Encode>::is_ssz_fixed_len());
+        assert_eq!(<RequiredOnly as Encode>::ssz_fixed_len(), 5);
+        assert_eq!(required.as_ssz_bytes(), vec![1, 2, 0, 0, 0]);
+
+        // An empty `Variable` is just its bitvector, which is 38 bytes for 300 fields.
+        assert_eq!(
+            Variable::default().as_ssz_bytes(),
+            vec![0; bitvector_len(300)]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_bitvectors() {
+        // Bit 3 doesn't correspond to a field.
+        assert!(Fixed::from_ssz_bytes(&[0b1000]).is_err());
+        // Missing bitvector.
+        assert!(Variable::from_ssz_bytes(&[0]).is_err());
+        // Field marked as present but missing.
+        assert!(Fixed::from_ssz_bytes(&[0b1]).is_err());
+        // Trailing bytes after the present fields.
+        assert!(Fixed::from_ssz_bytes(&[0b1, 1, 1]).is_err());
+        assert_eq!(
+            Fixed::from_ssz_bytes(&[0b1, 1]).unwrap(),
+            Fixed {
+                a: Some(1),
+                ..Default::default()
+            }
+        );
+    }
+}
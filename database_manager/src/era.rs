@@ -0,0 +1,787 @@
+//! Import of finalized blocks from era files.
+//!
+//! An era file is an [e2store](https://github.com/eth-clients/e2store-format-specs) file holding
+//! the blocks of one period of `SLOTS_PER_HISTORICAL_ROOT` slots, followed by the state at the end
+//! of the period. Era `N` contains the blocks with slots in `[(N - 1) * SLOTS_PER_HISTORICAL_ROOT,
+//! N * SLOTS_PER_HISTORICAL_ROOT)` and the state at `N * SLOTS_PER_HISTORICAL_ROOT`, whose
+//! `historical_roots` or `historical_summaries` commit to the `block_roots` of the era.
+//!
+//! In addition to the standard records, the blob sidecars of a block may follow it in a
+//! `CompressedBlobSidecars` record, which is ignored by other clients.
+//!
+//! Eras are imported from newest to oldest, extending the block history backwards from the
+//! database's anchor, so that each era is authenticated by the parent roots of the blocks which
+//! have already been imported, and by the accumulator of the newer era's state. The anchor is
+//! updated as each era is imported, so an interrupted import resumes from the last imported era.
+use slog::{info, warn, Logger};
+use ssz::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use store::{DatabaseBlock, HistoricBlock, HotColdDB, ItemStore};
+use tree_hash::TreeHash;
+use types::{
+    historical_summary::HistoricalSummary, AbstractExecPayload, BeaconState, BlobSidecarList,
+    ChainSpec, EthSpec, Hash256, HistoricalBatch, SignedBeaconBlock, SignedBlindedBeaconBlock,
+    Slot,
+};
+
+type EntryType = [u8; 2];
+
+const VERSION: EntryType = [0x65, 0x32];
+const COMPRESSED_SIGNED_BEACON_BLOCK: EntryType = [0x01, 0x00];
+const COMPRESSED_BEACON_STATE: EntryType = [0x02, 0x00];
+const SLOT_INDEX: EntryType = [0x69, 0x32];
+/// Lighthouse-specific record holding the SSZ-encoded `BlobSidecarList` of the preceding block.
+const COMPRESSED_BLOB_SIDECARS: EntryType = [0x03, 0x4c];
+/// Lighthouse-specific record holding a block whose execution payload was unavailable.
+const COMPRESSED_SIGNED_BLINDED_BEACON_BLOCK: EntryType = [0x04, 0x4c];
+
+/// The length of the header preceding the data of each record.
+const HEADER_LEN: usize = 8;
+/// The length of each field of a `SlotIndex` record.
+const INDEX_FIELD_LEN: usize = 8;
+
+/// The decoded contents of an era file.
+pub struct Era<E: EthSpec> {
+    pub number: u64,
+    /// The blocks of the era in ascending slot order, along with their blobs if present.
+    pub blocks: Vec<(DatabaseBlock<E>, Option<BlobSidecarList<E>>)>,
+    /// The state at the end of the era.
+    pub state: BeaconState<E>,
+}
+
+impl<E: EthSpec> Era<E> {
+    /// Decode the era file with the given `number` from `reader`.
+    pub fn read<R: Read>(reader: R, number: u64, spec: &ChainSpec) -> Result<Self, String> {
+        let mut reader = EraReader::<E, _>::new(reader, spec);
+        match reader.next_record()? {
+            Some((_, Record::Version)) => (),
+            _ => return Err("file does not begin with a version record".to_string()),
+        }
+
+        let mut blocks: Vec<(DatabaseBlock<E>, Option<BlobSidecarList<E>>)> = vec![];
+        let mut state = None;
+        while let Some((_, record)) = reader.next_record()? {
+            match record {
+                Record::Block(block) => blocks.push((block, None)),
+                Record::Blobs(blobs) => match blocks.last_mut() {
+                    Some((_, block_blobs @ None)) => *block_blobs = Some(blobs),
+                    _ => return Err("blobs record does not follow a block".to_string()),
+                },
+                Record::State(decoded) => {
+                    if state.replace(decoded).is_some() {
+                        return Err("multiple state records".to_string());
+                    }
+                }
+                Record::Version => return Err("multiple version records".to_string()),
+                // Records are located by scanning, so the indices are redundant.
+                Record::SlotIndex { .. } | Record::Unknown => (),
+            }
+        }
+
+        Ok(Self {
+            number,
+            blocks,
+            state: state.ok_or("file does not contain a state")?,
+        })
+    }
+
+    /// Encode the era as an e2store file, with indices for the blocks and the state.
+    pub fn as_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut writer = EraWriter::new(vec![], self.number)?;
+        for (block, blobs) in &self.blocks {
+            writer.write_block(block, blobs.as_ref())?;
+        }
+        writer.finish(&self.state)
+    }
+
+    /// Check that the era's blocks are committed to by its state, that its state commits to its
+    /// own block and state roots, and that the era is committed to by the state of the `newer`
+    /// era, if known.
+    ///
+    /// This does not check that the blocks form a chain, which is checked during import.
+    pub fn verify(&self, newer: Option<&Self>) -> Result<(), String> {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let end_slot = Slot::new(self.number * slots_per_era);
+        let start_slot = end_slot.saturating_sub(slots_per_era);
+
+        if self.number == 0 {
+            return Err("the genesis era cannot be imported".to_string());
+        }
+        if self.state.slot() != end_slot {
+            return Err(format!(
+                "state has slot {} rather than {}",
+                self.state.slot(),
+                end_slot
+            ));
+        }
+
+        // Each slot's root in the state must be the root of the latest block at or before that
+        // slot. Slots before the first block of the era are checked by the chain of parent roots.
+        let mut blocks = self.blocks.iter().peekable();
+        let mut latest_block_root = None;
+        for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+            if let Some((block, blobs)) = blocks.next_if(|(block, _)| block.slot() <= slot) {
+                if block.slot() != slot {
+                    return Err(format!(
+                        "block at slot {} is out of order or outside the era",
+                        block.slot()
+                    ));
+                }
+                let block_root = block.canonical_root();
+                if let Some(blobs) = blobs {
+                    match block {
+                        DatabaseBlock::Full(block) => verify_blobs(block, block_root, blobs)?,
+                        DatabaseBlock::Blinded(block) => verify_blobs(block, block_root, blobs)?,
+                    }
+                }
+                latest_block_root = Some(block_root);
+            }
+
+            if let Some(block_root) = latest_block_root {
+                let state_block_root = *self
+                    .state
+                    .get_block_root(slot)
+                    .map_err(|e| format!("unable to read block root from state: {:?}", e))?;
+                if block_root != state_block_root {
+                    return Err(format!(
+                        "block root {:?} at slot {} does not match the state's {:?}",
+                        block_root, slot, state_block_root
+                    ));
+                }
+            }
+        }
+        if let Some((block, _)) = blocks.next() {
+            return Err(format!(
+                "block at slot {} is out of order or outside the era",
+                block.slot()
+            ));
+        }
+
+        let accumulator_root = accumulator_root(&self.state, self.number);
+        let index = self.number as usize - 1;
+        if accumulator_entry(&self.state, index) != Some(accumulator_root) {
+            return Err(format!(
+                "state's accumulator does not commit to its own roots at index {}",
+                index
+            ));
+        }
+        if let Some(newer) = newer {
+            if accumulator_entry(&newer.state, index) != Some(accumulator_root) {
+                return Err(format!(
+                    "accumulator of the state for era {} does not commit to this era",
+                    newer.number
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes an era file record by record, so that the blocks of an era needn't be held in memory.
+///
+/// Blocks must be written in ascending slot order, followed by the state at the end of the era.
+pub struct EraWriter<E: EthSpec, W: Write> {
+    writer: W,
+    number: u64,
+    /// The number of bytes written so far.
+    position: u64,
+    /// The position of the block at each slot of the era, or zero for empty slots.
+    block_offsets: Vec<u64>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec, W: Write> EraWriter<E, W> {
+    pub fn new(writer: W, number: u64) -> Result<Self, String> {
+        let mut era_writer = Self {
+            writer,
+            number,
+            position: 0,
+            block_offsets: vec![0; E::slots_per_historical_root()],
+            _phantom: PhantomData,
+        };
+        era_writer.write_entry(VERSION, &[])?;
+        Ok(era_writer)
+    }
+
+    fn start_slot(&self) -> u64 {
+        self.number.saturating_sub(1) * E::slots_per_historical_root() as u64
+    }
+
+    /// Write a block, which is written as a blinded block if it lacks its execution payload.
+    pub fn write_block(
+        &mut self,
+        block: &DatabaseBlock<E>,
+        blobs: Option<&BlobSidecarList<E>>,
+    ) -> Result<(), String> {
+        let slot = block.slot();
+        let index = slot
+            .as_u64()
+            .checked_sub(self.start_slot())
+            .map(|index| index as usize)
+            .filter(|index| self.number > 0 && *index < self.block_offsets.len())
+            .ok_or_else(|| format!("block at slot {} is outside era {}", slot, self.number))?;
+        if self.block_offsets[index..]
+            .iter()
+            .any(|offset| *offset != 0)
+        {
+            return Err(format!("block at slot {} is out of order", slot));
+        }
+        self.block_offsets[index] = self.position;
+
+        let (entry_type, bytes) = match block {
+            DatabaseBlock::Full(block) => (COMPRESSED_SIGNED_BEACON_BLOCK, block.as_ssz_bytes()),
+            DatabaseBlock::Blinded(block) => {
+                (COMPRESSED_SIGNED_BLINDED_BEACON_BLOCK, block.as_ssz_bytes())
+            }
+        };
+        self.write_entry(entry_type, &compress(&bytes)?)?;
+        if let Some(blobs) = blobs {
+            self.write_entry(COMPRESSED_BLOB_SIDECARS, &compress(&blobs.as_ssz_bytes())?)?;
+        }
+        Ok(())
+    }
+
+    /// Write the state at the end of the era followed by the indices, returning the writer.
+    pub fn finish(mut self, state: &BeaconState<E>) -> Result<W, String> {
+        let state_offset = self.position;
+        self.write_entry(COMPRESSED_BEACON_STATE, &compress(&state.as_ssz_bytes())?)?;
+
+        // The genesis era has no blocks, and therefore no block index.
+        if self.number > 0 {
+            let block_offsets = std::mem::take(&mut self.block_offsets);
+            self.write_slot_index(self.start_slot(), &block_offsets)?;
+        }
+        self.write_slot_index(state.slot().as_u64(), &[state_offset])?;
+
+        self.writer
+            .flush()
+            .map_err(|e| format!("unable to write era file: {:?}", e))?;
+        Ok(self.writer)
+    }
+
+    fn write_entry(&mut self, entry_type: EntryType, data: &[u8]) -> Result<(), String> {
+        let mut header = [0; HEADER_LEN];
+        header[..2].copy_from_slice(&entry_type);
+        header[2..6].copy_from_slice(&(data.len() as u32).to_le_bytes());
+        self.writer
+            .write_all(&header)
+            .and_then(|()| self.writer.write_all(data))
+            .map_err(|e| format!("unable to write era file: {:?}", e))?;
+        self.position += (HEADER_LEN + data.len()) as u64;
+        Ok(())
+    }
+
+    /// Write a `SlotIndex` record for `offsets` relative to the start of the file.
+    ///
+    /// Offsets within the record are relative to its own start, and zero for missing slots.
+    fn write_slot_index(&mut self, start_slot: u64, offsets: &[u64]) -> Result<(), String> {
+        let index_position = self.position as i64;
+        let mut data = Vec::with_capacity((offsets.len() + 2) * INDEX_FIELD_LEN);
+        data.extend_from_slice(&start_slot.to_le_bytes());
+        for offset in offsets {
+            let relative_offset = if *offset == 0 {
+                0
+            } else {
+                *offset as i64 - index_position
+            };
+            data.extend_from_slice(&relative_offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(offsets.len() as i64).to_le_bytes());
+        self.write_entry(SLOT_INDEX, &data)
+    }
+}
+
+/// A decoded record of an era file.
+enum Record<E: EthSpec> {
+    Version,
+    Block(DatabaseBlock<E>),
+    Blobs(BlobSidecarList<E>),
+    State(BeaconState<E>),
+    SlotIndex { start_slot: u64, offsets: Vec<i64> },
+    Unknown,
+}
+
+/// Reads the records of an era file in order.
+struct EraReader<'a, E: EthSpec, R: Read> {
+    reader: R,
+    /// The number of bytes read so far.
+    position: u64,
+    spec: &'a ChainSpec,
+    _phantom: PhantomData<E>,
+}
+
+impl<'a, E: EthSpec, R: Read> EraReader<'a, E, R> {
+    fn new(reader: R, spec: &'a ChainSpec) -> Self {
+        Self {
+            reader,
+            position: 0,
+            spec,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the next record along with its position in the file, or `None` at the end of the
+    /// file.
+    fn next_record(&mut self) -> Result<Option<(u64, Record<E>)>, String> {
+        let position = self.position;
+        let mut header = [0; HEADER_LEN];
+        let mut filled = 0;
+        while filled < HEADER_LEN {
+            match self.reader.read(&mut header[filled..]) {
+                Ok(0) if filled == 0 => return Ok(None),
+                Ok(0) => return Err("truncated record header".to_string()),
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(e) => return Err(format!("unable to read record: {:?}", e)),
+            }
+        }
+        let entry_type = [header[0], header[1]];
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        if header[6..] != [0, 0] {
+            return Err("non-zero reserved header bytes".to_string());
+        }
+        let mut data = vec![0; len];
+        self.reader
+            .read_exact(&mut data)
+            .map_err(|e| format!("truncated record: {:?}", e))?;
+        self.position += (HEADER_LEN + len) as u64;
+
+        let record = match entry_type {
+            VERSION => Record::Version,
+            COMPRESSED_SIGNED_BEACON_BLOCK => Record::Block(DatabaseBlock::Full(
+                SignedBeaconBlock::from_ssz_bytes(&decompress(&data)?, self.spec)
+                    .map_err(|e| format!("unable to decode block: {:?}", e))?,
+            )),
+            COMPRESSED_SIGNED_BLINDED_BEACON_BLOCK => Record::Block(DatabaseBlock::Blinded(
+                SignedBlindedBeaconBlock::from_ssz_bytes(&decompress(&data)?, self.spec)
+                    .map_err(|e| format!("unable to decode blinded block: {:?}", e))?,
+            )),
+            COMPRESSED_BLOB_SIDECARS => Record::Blobs(
+                BlobSidecarList::<E>::from_ssz_bytes(&decompress(&data)?)
+                    .map_err(|e| format!("unable to decode blobs: {:?}", e))?,
+            ),
+            COMPRESSED_BEACON_STATE => Record::State(
+                BeaconState::from_ssz_bytes(&decompress(&data)?, self.spec)
+                    .map_err(|e| format!("unable to decode state: {:?}", e))?,
+            ),
+            SLOT_INDEX => {
+                let fields = data
+                    .chunks(INDEX_FIELD_LEN)
+                    .map(|field| field.try_into().map(i64::from_le_bytes))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| "slot index has a truncated field".to_string())?;
+                match fields.as_slice() {
+                    [start_slot, offsets @ .., count] if *count == offsets.len() as i64 => {
+                        Record::SlotIndex {
+                            start_slot: *start_slot as u64,
+                            offsets: offsets.to_vec(),
+                        }
+                    }
+                    _ => return Err("slot index has an incorrect count".to_string()),
+                }
+            }
+            // Unknown records must be skipped.
+            _ => Record::Unknown,
+        };
+        Ok(Some((position, record)))
+    }
+}
+
+/// Check that the era file at `path` contains the blocks with `block_roots` and the state with
+/// `state_root`, and that its indices point to them.
+fn verify_era_file<E: EthSpec>(
+    path: &Path,
+    block_roots: &[Hash256],
+    state_root: Hash256,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let file = File::open(path).map_err(|e| format!("unable to open file: {:?}", e))?;
+    let mut reader = EraReader::<E, _>::new(BufReader::new(file), spec);
+    match reader.next_record()? {
+        Some((_, Record::Version)) => (),
+        _ => return Err("file does not begin with a version record".to_string()),
+    }
+
+    let mut read_block_roots = vec![];
+    // The slots of the blocks and state, by their positions in the file.
+    let mut block_slots = BTreeMap::new();
+    let mut state_slots = BTreeMap::new();
+    let mut read_state_root = None;
+    let mut indices = vec![];
+    while let Some((position, record)) = reader.next_record()? {
+        match record {
+            Record::Block(block) => {
+                block_slots.insert(position, block.slot());
+                read_block_roots.push(block.canonical_root());
+            }
+            Record::State(state) => {
+                state_slots.insert(position, state.slot());
+                read_state_root = Some(state.canonical_root());
+            }
+            Record::SlotIndex {
+                start_slot,
+                offsets,
+            } => indices.push((position, start_slot, offsets)),
+            Record::Version => return Err("multiple version records".to_string()),
+            Record::Blobs(_) | Record::Unknown => (),
+        }
+    }
+
+    if read_block_roots != block_roots {
+        return Err("blocks differ from those written".to_string());
+    }
+    if read_state_root != Some(state_root) {
+        return Err("state differs from that written".to_string());
+    }
+
+    // The block index precedes the state index, except in the genesis era.
+    let expected_indices = if block_roots.is_empty() && indices.len() == 1 {
+        vec![state_slots]
+    } else {
+        vec![block_slots, state_slots]
+    };
+    if indices.len() != expected_indices.len() {
+        return Err(format!("expected {} slot indices", expected_indices.len()));
+    }
+    for ((index_position, start_slot, offsets), slots) in indices.into_iter().zip(expected_indices)
+    {
+        let mut num_entries = 0;
+        for (i, offset) in offsets.into_iter().enumerate() {
+            if offset == 0 {
+                continue;
+            }
+            let slot = Slot::new(start_slot + i as u64);
+            let position = (index_position as i64 + offset) as u64;
+            if slots.get(&position) != Some(&slot) {
+                return Err(format!("slot index entry for slot {} is incorrect", slot));
+            }
+            num_entries += 1;
+        }
+        if num_entries != slots.len() {
+            return Err("slot index is incomplete".to_string());
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the accumulator entry for era `number` from the roots in its `state`.
+///
+/// Eras completed before Capella are committed to by a `HistoricalBatch` root in
+/// `historical_roots`, and later eras by a `HistoricalSummary` in `historical_summaries`.
+fn accumulator_root<E: EthSpec>(state: &BeaconState<E>, number: u64) -> Hash256 {
+    let index = number.saturating_sub(1) as usize;
+    if index < state.historical_roots().len() {
+        HistoricalBatch::<E> {
+            block_roots: state.block_roots().clone(),
+            state_roots: state.state_roots().clone(),
+        }
+        .tree_hash_root()
+    } else {
+        HistoricalSummary::new(state).tree_hash_root()
+    }
+}
+
+/// The name of the file for era `number` with the given `state`, following the
+/// `<config-name>-<era-number>-<short-root>.era` convention.
+pub fn era_file_name<E: EthSpec>(state: &BeaconState<E>, number: u64, spec: &ChainSpec) -> String {
+    let short_root = if number == 0 {
+        state.genesis_validators_root()
+    } else {
+        accumulator_root(state, number)
+    };
+    format!(
+        "{}-{:05}-{}.era",
+        spec.config_name.as_deref().unwrap_or("unknown"),
+        number,
+        short_root.as_bytes()[..4]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>()
+    )
+}
+
+/// Returns the root of the entry at `index` in the accumulator of `state`, treating the
+/// `historical_summaries` as a continuation of the `historical_roots`.
+fn accumulator_entry<E: EthSpec>(state: &BeaconState<E>, index: usize) -> Option<Hash256> {
+    let historical_roots = state.historical_roots();
+    if let Some(root) = historical_roots.get(index) {
+        return Some(*root);
+    }
+    state
+        .historical_summaries()
+        .ok()?
+        .get(index - historical_roots.len())
+        .map(TreeHash::tree_hash_root)
+}
+
+/// Check that `blobs` belong to the `block` with `block_root`.
+fn verify_blobs<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    block: &SignedBeaconBlock<E, Payload>,
+    block_root: Hash256,
+    blobs: &BlobSidecarList<E>,
+) -> Result<(), String> {
+    let commitments = block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .map_err(|_| format!("block at slot {} cannot have blobs", block.slot()))?;
+    for blob in blobs.iter() {
+        if blob.block_root != block_root
+            || blob.slot != block.slot()
+            || commitments.get(blob.index as usize) != Some(&blob.kzg_commitment)
+        {
+            return Err(format!(
+                "blob {} does not belong to the block at slot {}",
+                blob.index,
+                block.slot()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Find the era files in `era_dir`, indexed by their era numbers.
+pub fn era_files_in_dir(era_dir: &Path) -> Result<BTreeMap<u64, PathBuf>, String> {
+    let mut era_files = BTreeMap::new();
+    let entries = fs::read_dir(era_dir)
+        .map_err(|e| format!("Unable to read era directory {:?}: {:?}", era_dir, e))?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| format!("Unable to read era directory {:?}: {:?}", era_dir, e))?
+            .path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("era") {
+            continue;
+        }
+        let number = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.rsplitn(3, '-').nth(1))
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| format!("Unable to parse era number from {:?}", path))?;
+        if let Some(other) = era_files.insert(number, path.clone()) {
+            return Err(format!(
+                "Multiple files for era {}: {:?} and {:?}",
+                number, other, path
+            ));
+        }
+    }
+    Ok(era_files)
+}
+
+/// Write era `number` from the freezer database of `db` to a file in `era_dir`, and check the
+/// file by reading it back.
+///
+/// The era must be finalized and its blocks must be available. Blocks whose execution payloads
+/// have been pruned are written as blinded blocks if `blinded_ok` is set, and are otherwise an
+/// error.
+pub fn export_era<E, Hot, Cold>(
+    db: &HotColdDB<E, Hot, Cold>,
+    number: u64,
+    era_dir: &Path,
+    blinded_ok: bool,
+) -> Result<PathBuf, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let spec = db.get_chain_spec();
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let end_slot = Slot::new(number * slots_per_era);
+    // The genesis block is not included in any era.
+    let start_slot = std::cmp::max(end_slot.saturating_sub(slots_per_era), Slot::new(1));
+
+    if number == 0 {
+        return Err("The genesis era cannot be exported".to_string());
+    }
+    if end_slot >= db.get_split_slot() {
+        return Err(format!(
+            "Era {} is not finalized, it ends at slot {} and the split slot is {}",
+            number,
+            end_slot,
+            db.get_split_slot()
+        ));
+    }
+    if let Some(anchor) = db.get_anchor_info() {
+        if start_slot < anchor.oldest_block_slot {
+            return Err(format!(
+                "Era {} is not available, the oldest block is at slot {}",
+                number, anchor.oldest_block_slot
+            ));
+        }
+    }
+    let state = db
+        .load_cold_state_by_slot(end_slot)
+        .map_err(|e| format!("Unable to load state at slot {}: {:?}", end_slot, e))?
+        .ok_or_else(|| format!("State at slot {} is not available", end_slot))?;
+
+    let path = era_dir.join(era_file_name(&state, number, spec));
+    let file =
+        File::create(&path).map_err(|e| format!("Unable to create file {:?}: {:?}", path, e))?;
+    let mut writer = EraWriter::new(BufWriter::new(file), number)?;
+
+    let write_blocks = |writer: &mut EraWriter<E, _>| -> Result<Vec<Hash256>, String> {
+        let mut block_roots = vec![];
+        let mut prev_block_root = None;
+        for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+            let block_root = *state
+                .get_block_root(slot)
+                .map_err(|e| format!("Unable to read block root at slot {}: {:?}", slot, e))?;
+            if prev_block_root == Some(block_root) {
+                continue;
+            }
+            prev_block_root = Some(block_root);
+
+            let block = db
+                .try_get_full_block(&block_root)
+                .map_err(|e| format!("Unable to load block {:?}: {:?}", block_root, e))?
+                .ok_or_else(|| format!("Block {:?} is not available", block_root))?;
+            // The first slot of the era may be a skip slot.
+            if block.slot() != slot {
+                continue;
+            }
+            if matches!(block, DatabaseBlock::Blinded(_)) && !blinded_ok {
+                return Err(format!(
+                    "The execution payload of the block at slot {} has been pruned, use \
+                     --blinded-ok to export blocks without their payloads",
+                    slot
+                ));
+            }
+            let blobs = db
+                .get_blobs(&block_root)
+                .map_err(|e| format!("Unable to load blobs for {:?}: {:?}", block_root, e))?
+                .filter(|blobs| !blobs.is_empty());
+
+            writer.write_block(&block, blobs.as_ref())?;
+            block_roots.push(block_root);
+        }
+        Ok(block_roots)
+    };
+
+    let result = write_blocks(&mut writer)
+        .and_then(|block_roots| {
+            writer.finish(&state)?;
+            Ok(block_roots)
+        })
+        .and_then(|block_roots| {
+            verify_era_file::<E>(&path, &block_roots, state.canonical_root(), spec)
+                .map_err(|e| format!("Era file {:?} failed verification: {}", path, e))
+        });
+    if result.is_err() {
+        let _ = fs::remove_file(&path);
+    }
+    result.map(|()| path)
+}
+
+/// Import the blocks of the era files in `era_dir` into `db`, from the era preceding the
+/// database's oldest block back to genesis, or until an era file is missing.
+pub fn import_era_files<E, Hot, Cold>(
+    db: &HotColdDB<E, Hot, Cold>,
+    era_dir: &Path,
+    genesis_block_root: Hash256,
+    log: &Logger,
+) -> Result<(), String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let spec = db.get_chain_spec();
+    let era_files = era_files_in_dir(era_dir)?;
+
+    let Some(anchor) = db
+        .get_anchor_info()
+        .filter(|anchor| anchor.oldest_block_slot > 0)
+    else {
+        info!(log, "Block history is already complete");
+        return Ok(());
+    };
+
+    // Start with the era containing the parent of the oldest block.
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let mut number = (anchor.oldest_block_slot.as_u64() - 1) / slots_per_era + 1;
+    let mut newer_era: Option<Era<E>> = None;
+
+    info!(
+        log,
+        "Importing era files";
+        "oldest_block_slot" => anchor.oldest_block_slot,
+        "first_era" => number,
+        "era_dir" => ?era_dir,
+    );
+
+    while number > 0 {
+        let Some(path) = era_files.get(&number) else {
+            warn!(
+                log,
+                "Stopping import at missing era file";
+                "era" => number,
+                "oldest_block_slot" => db.get_anchor_info().map(|anchor| anchor.oldest_block_slot),
+            );
+            return Ok(());
+        };
+        let era_error = |e: String| format!("Unable to import era file {:?}: {}", path, e);
+
+        let file = File::open(path).map_err(|e| era_error(format!("{:?}", e)))?;
+        let era = Era::<E>::read(BufReader::new(file), number, spec).map_err(era_error)?;
+        era.verify(newer_era.as_ref()).map_err(era_error)?;
+
+        let blocks = era
+            .blocks
+            .iter()
+            .map(|(block, blobs)| HistoricBlock {
+                block_root: block.canonical_root(),
+                block: block.clone_as_blinded(),
+                blobs: blobs.clone(),
+            })
+            .collect();
+        let num_imported = db
+            .import_historic_blocks(blocks, genesis_block_root)
+            .map_err(|e| era_error(format!("{:?}", e)))?;
+
+        let oldest_block_slot = db
+            .get_anchor_info()
+            .map_or(Slot::new(0), |anchor| anchor.oldest_block_slot);
+        info!(
+            log,
+            "Imported era file";
+            "era" => number,
+            "blocks" => num_imported,
+            "oldest_block_slot" => oldest_block_slot,
+            "file" => ?path,
+        );
+        if oldest_block_slot == 0 {
+            break;
+        }
+
+        newer_era = Some(era);
+        number -= 1;
+    }
+
+    info!(log, "Block history is complete");
+    Ok(())
+}
+
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = snap::write::FrameEncoder::new(vec![]);
+    encoder
+        .write_all(bytes)
+        .map_err(|e| format!("unable to compress record: {:?}", e))?;
+    encoder
+        .into_inner()
+        .map_err(|e| format!("unable to compress record: {:?}", e))
+}
+
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decompressed = vec![];
+    snap::read::FrameDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("unable to decompress record: {:?}", e))?;
+    Ok(decompressed)
+}
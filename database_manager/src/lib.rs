@@ -1,21 +1,28 @@
 use beacon_chain::{
-    builder::Witness, eth1_chain::CachingEth1Backend, schema_change::migrate_schema,
+    builder::Witness,
+    eth1_chain::CachingEth1Backend,
+    schema_change::{check_migration, migrate_schema},
     slot_clock::SystemTimeSlotClock,
 };
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::{App, Arg, ArgMatches};
 use environment::{Environment, RuntimeContext};
 use slog::{info, Logger};
+use ssz::Decode;
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use store::{
     errors::Error,
     metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    ColumnIter, DBColumn, HotColdDB, HotStateSummary, ItemStore, KeyValueStore, LevelDB,
+    StoreConfig, StoreItem,
 };
 use strum::{EnumString, EnumVariantNames, VariantNames};
-use types::EthSpec;
+use types::{BlobSidecarList, ChainSpec, Epoch, EthSpec, Hash256, SignedBlindedBeaconBlock, Slot};
+
+pub mod era;
 
 pub const CMD: &str = "database_manager";
 
@@ -38,6 +45,15 @@ pub fn migrate_cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .required(true),
         )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help(
+                    "Print the migration steps which would be applied and check that they are \
+                     possible, without modifying the database",
+                )
+                .takes_value(false),
+        )
 }
 
 pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
@@ -58,13 +74,25 @@ pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("TARGET")
                 .help("Select the type of output to show")
                 .default_value("sizes")
-                .possible_values(InspectTarget::VARIANTS),
+                .possible_values(InspectTarget::VARIANTS)
+                // Deprecated alias of `ssz-files`.
+                .possible_value("values"),
+        )
+        .arg(
+            Arg::with_name("key")
+                .long("key")
+                .value_name("HEX")
+                .help("Only inspect the value with this 32-byte key, e.g. a block root")
+                .takes_value(true),
         )
         .arg(
             Arg::with_name("output-dir")
                 .long("output-dir")
                 .value_name("DIR")
-                .help("Base directory for the output files. Defaults to the current directory")
+                .help(
+                    "Base directory for the `json` and `ssz-files` output files. Defaults to the \
+                     current directory",
+                )
                 .takes_value(true),
         )
 }
@@ -81,6 +109,99 @@ pub fn prune_blobs_app<'a, 'b>() -> App<'a, 'b> {
         .about("Prune blobs older than data availability boundary")
 }
 
+pub fn compact_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("compact")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about("Compact the hot database, freeing space used by deleted states")
+}
+
+pub fn migrate_restore_points_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("migrate-restore-points")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Change the spacing of restore points in the freezer database without resyncing. \
+             --slots-per-restore-point must be set to the current spacing. An interrupted \
+             migration may be resumed by running the command again",
+        )
+        .arg(
+            Arg::with_name("new-spacing")
+                .long("new-spacing")
+                .value_name("SLOT_COUNT")
+                .help("The new number of slots per restore point")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("validation-interval")
+                .long("validation-interval")
+                .value_name("N")
+                .help("Check the root of every Nth regenerated state against the stored root")
+                .takes_value(true)
+                .default_value("16"),
+        )
+}
+
+pub fn import_era_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("import-era")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Import finalized blocks older than the oldest block in the database from era files. \
+             The blocks are checked against the era states and the chain of parent roots. \
+             An interrupted import may be resumed by running the command again",
+        )
+        .arg(
+            Arg::with_name("era-dir")
+                .long("era-dir")
+                .value_name("DIR")
+                .help("Directory containing the era files")
+                .takes_value(true)
+                .required(true),
+        )
+}
+
+pub fn export_era_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("export-era")
+        .setting(clap::AppSettings::ColoredHelp)
+        .about(
+            "Export finalized blocks and states to era files. Each era file covers \
+             SLOTS_PER_HISTORICAL_ROOT slots, and all eras overlapping the epoch range are \
+             exported. Each file is checked by reading it back after it is written",
+        )
+        .arg(
+            Arg::with_name("start-epoch")
+                .long("start-epoch")
+                .value_name("EPOCH")
+                .help("First epoch to export")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("end-epoch")
+                .long("end-epoch")
+                .value_name("EPOCH")
+                .help("Last epoch to export, inclusive")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("output-dir")
+                .long("output-dir")
+                .value_name("DIR")
+                .help("Directory to write the era files to")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("blinded-ok")
+                .long("blinded-ok")
+                .help(
+                    "Export blocks whose execution payloads have been pruned without their \
+                     payloads. Such files can only be imported by Lighthouse",
+                )
+                .takes_value(false),
+        )
+}
+
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .visible_aliases(&["db"])
@@ -127,6 +248,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(inspect_cli_app())
         .subcommand(prune_payloads_app())
         .subcommand(prune_blobs_app())
+        .subcommand(compact_app())
+        .subcommand(migrate_restore_points_app())
+        .subcommand(import_era_app())
+        .subcommand(export_era_app())
 }
 
 fn parse_client_config<E: EthSpec>(
@@ -197,17 +322,24 @@ pub fn display_db_version<E: EthSpec>(
 
 #[derive(Debug, EnumString, EnumVariantNames)]
 pub enum InspectTarget {
+    /// Print the size of each value, followed by a histogram of the sizes.
     #[strum(serialize = "sizes")]
     ValueSizes,
     #[strum(serialize = "total")]
     ValueTotal,
-    #[strum(serialize = "values")]
-    Values,
+    /// Decode each value and write it to `<column>_<key>.json`. Only supported for some columns.
+    #[strum(serialize = "json")]
+    Json,
+    /// Write each raw value to `<column>_<key>.ssz`. Also accepted as `values`, its former name.
+    #[strum(serialize = "ssz-files", serialize = "values")]
+    SszFiles,
 }
 
 pub struct InspectConfig {
     column: DBColumn,
     target: InspectTarget,
+    /// Only inspect the value with this key, if set.
+    key: Option<Hash256>,
     /// Configures where the inspect output should be stored.
     output_dir: PathBuf,
 }
@@ -215,11 +347,13 @@ pub struct InspectConfig {
 fn parse_inspect_config(cli_args: &ArgMatches) -> Result<InspectConfig, String> {
     let column = clap_utils::parse_required(cli_args, "column")?;
     let target = clap_utils::parse_required(cli_args, "output")?;
+    let key = clap_utils::parse_optional(cli_args, "key")?;
     let output_dir: PathBuf =
         clap_utils::parse_optional(cli_args, "output-dir")?.unwrap_or_else(PathBuf::new);
     Ok(InspectConfig {
         column,
         target,
+        key,
         output_dir,
     })
 }
@@ -246,40 +380,76 @@ pub fn inspect_db<E: EthSpec>(
     )
     .map_err(|e| format!("{:?}", e))?;
 
+    inspect_store(&inspect_config, &db)
+}
+
+/// Inspect the values of a single column of an open database.
+fn inspect_store<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    inspect_config: &InspectConfig,
+    db: &HotColdDB<E, Hot, Cold>,
+) -> Result<(), String> {
+    let column = inspect_config.column;
     let mut total = 0;
+    let mut num_values = 0;
+    // Number of values by size, rounded up to the next power of two.
+    let mut size_histogram = BTreeMap::<usize, usize>::new();
     let base_path = &inspect_config.output_dir;
 
-    if let InspectTarget::Values = inspect_config.target {
+    if let InspectTarget::Json = inspect_config.target {
+        if !supports_json(column) {
+            return Err(format!(
+                "JSON output is not supported for column {}",
+                column.as_str()
+            ));
+        }
+    }
+
+    if let InspectTarget::Json | InspectTarget::SszFiles = inspect_config.target {
         fs::create_dir_all(base_path)
             .map_err(|e| format!("Unable to create import directory: {:?}", e))?;
     }
 
-    for res in db.hot_db.iter_column(inspect_config.column) {
+    // Blobs are stored in their own database, everything else lives in the hot database.
+    let values = if column == DBColumn::BeaconBlob {
+        let blobs_db = db.blobs_db.as_ref().unwrap_or(&db.cold_db);
+        column_values(blobs_db, column, inspect_config.key)
+    } else {
+        column_values(&db.hot_db, column, inspect_config.key)
+    };
+
+    for res in values {
         let (key, value) = res.map_err(|e| format!("{:?}", e))?;
+        num_values += 1;
 
         match inspect_config.target {
             InspectTarget::ValueSizes => {
                 println!("{:?}: {} bytes", key, value.len());
+                *size_histogram
+                    .entry(value.len().next_power_of_two())
+                    .or_default() += 1;
                 total += value.len();
             }
             InspectTarget::ValueTotal => {
                 total += value.len();
             }
-            InspectTarget::Values => {
-                let file_path =
-                    base_path.join(format!("{}_{}.ssz", inspect_config.column.as_str(), key));
-
-                let write_result = fs::OpenOptions::new()
-                    .create(true)
-                    .write(true)
-                    .open(&file_path)
-                    .map_err(|e| format!("Failed to open file: {:?}", e))
-                    .map(|mut file| {
-                        file.write_all(&value)
-                            .map_err(|e| format!("Failed to write file: {:?}", e))
-                    });
+            InspectTarget::Json => {
+                let file_path = base_path.join(format!("{}_{:?}.json", column.as_str(), key));
+
+                let write_result = decode_to_json::<E>(column, &value, db.get_chain_spec())
+                    .and_then(|json| write_file(&file_path, json.as_bytes()));
                 if let Err(e) = write_result {
-                    println!("Error writing values to file {:?}: {:?}", file_path, e);
+                    println!("Error writing JSON to file {:?}: {}", file_path, e);
+                } else {
+                    println!("Successfully saved JSON to file: {:?}", file_path);
+                }
+
+                total += value.len();
+            }
+            InspectTarget::SszFiles => {
+                let file_path = base_path.join(format!("{}_{:?}.ssz", column.as_str(), key));
+
+                if let Err(e) = write_file(&file_path, &value) {
+                    println!("Error writing values to file {:?}: {}", file_path, e);
                 } else {
                     println!("Successfully saved values to file: {:?}", file_path);
                 }
@@ -289,8 +459,28 @@ pub fn inspect_db<E: EthSpec>(
         }
     }
 
+    if let Some(key) = inspect_config.key {
+        if num_values == 0 {
+            return Err(format!(
+                "No value for key {:?} in column {}",
+                key,
+                column.as_str()
+            ));
+        }
+    }
+
+    if let InspectTarget::ValueSizes = inspect_config.target {
+        println!("Value size histogram:");
+        for (max_size, count) in size_histogram {
+            println!("<= {} bytes: {}", max_size, count);
+        }
+    }
+
     match inspect_config.target {
-        InspectTarget::ValueSizes | InspectTarget::ValueTotal | InspectTarget::Values => {
+        InspectTarget::ValueSizes
+        | InspectTarget::ValueTotal
+        | InspectTarget::Json
+        | InspectTarget::SszFiles => {
             println!("Total: {} bytes", total);
         }
     }
@@ -298,14 +488,81 @@ pub fn inspect_db<E: EthSpec>(
     Ok(())
 }
 
+/// Iterate the values of `column`, or just the value for `key` if it is set.
+fn column_values<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    column: DBColumn,
+    key: Option<Hash256>,
+) -> ColumnIter {
+    match key {
+        Some(key) => Box::new(
+            db.get_bytes(column.as_str(), key.as_bytes())
+                .transpose()
+                .map(move |res| res.map(|value| (key, value)))
+                .into_iter(),
+        ),
+        None => db.iter_column(column),
+    }
+}
+
+/// Returns `true` if the values of `column` can be decoded by `decode_to_json`.
+fn supports_json(column: DBColumn) -> bool {
+    matches!(
+        column,
+        DBColumn::BeaconBlock | DBColumn::BeaconStateSummary | DBColumn::BeaconBlob
+    )
+}
+
+/// Decode a value from `column` as its type and encode it as JSON.
+fn decode_to_json<E: EthSpec>(
+    column: DBColumn,
+    value: &[u8],
+    spec: &ChainSpec,
+) -> Result<String, String> {
+    let json = match column {
+        DBColumn::BeaconBlock => serde_json::to_string_pretty(
+            &SignedBlindedBeaconBlock::<E>::from_ssz_bytes(value, spec)
+                .map_err(|e| format!("Unable to decode block: {:?}", e))?,
+        ),
+        DBColumn::BeaconStateSummary => serde_json::to_string_pretty(
+            &HotStateSummary::from_store_bytes(value)
+                .map_err(|e| format!("Unable to decode state summary: {:?}", e))?,
+        ),
+        DBColumn::BeaconBlob => serde_json::to_string_pretty(
+            &BlobSidecarList::<E>::from_ssz_bytes(value)
+                .map_err(|e| format!("Unable to decode blobs: {:?}", e))?,
+        ),
+        _ => {
+            return Err(format!(
+                "JSON output is not supported for column {}",
+                column.as_str()
+            ))
+        }
+    };
+    json.map_err(|e| format!("Unable to encode JSON: {:?}", e))
+}
+
+fn write_file(file_path: &Path, bytes: &[u8]) -> Result<(), String> {
+    fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(file_path)
+        .map_err(|e| format!("Failed to open file: {:?}", e))?
+        .write_all(bytes)
+        .map_err(|e| format!("Failed to write file: {:?}", e))
+}
+
 pub struct MigrateConfig {
     to: SchemaVersion,
+    dry_run: bool,
 }
 
 fn parse_migrate_config(cli_args: &ArgMatches) -> Result<MigrateConfig, String> {
     let to = SchemaVersion(clap_utils::parse_required(cli_args, "to")?);
+    let dry_run = cli_args.is_present("dry-run");
 
-    Ok(MigrateConfig { to })
+    Ok(MigrateConfig { to, dry_run })
 }
 
 pub fn migrate_db<E: EthSpec>(
@@ -334,6 +591,32 @@ pub fn migrate_db<E: EthSpec>(
         log.clone(),
     )?;
 
+    // Check every step before applying any of them, so that an irreversible downgrade is refused
+    // without leaving the database at an intermediate version.
+    let steps = check_migration::<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, _, _, _>>(
+        &db, from, to, &log,
+    )?;
+    for step in &steps {
+        info!(
+            log,
+            "Schema migration step";
+            "from" => step.from.as_u64(),
+            "to" => step.to.as_u64(),
+            "change" => step.description,
+        );
+    }
+
+    if migrate_config.dry_run {
+        info!(
+            log,
+            "Dry run complete, database not modified";
+            "from" => from.as_u64(),
+            "to" => to.as_u64(),
+            "steps" => steps.len(),
+        );
+        return Ok(());
+    }
+
     info!(
         log,
         "Migrating database schema";
@@ -402,6 +685,218 @@ pub fn prune_blobs<E: EthSpec>(
     db.try_prune_most_blobs(true)
 }
 
+/// Compact the hot database using the same code path as the beacon node.
+pub fn compact_db<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    // Compact once, below, rather than also compacting when the database is opened.
+    let store_config = StoreConfig {
+        compact_on_init: false,
+        ..client_config.store
+    };
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        blobs_path,
+        |_, _, _| Ok(()),
+        store_config,
+        spec.clone(),
+        log.clone(),
+    )?;
+
+    info!(log, "Compacting database");
+    let summary = db.compact()?;
+    info!(
+        log,
+        "Database compaction complete";
+        "duration" => ?summary.duration,
+        "reclaimed_bytes" => summary.reclaimed_bytes,
+    );
+    Ok(())
+}
+
+pub struct MigrateRestorePointsConfig {
+    new_slots_per_restore_point: u64,
+    validation_interval: u64,
+}
+
+fn parse_migrate_restore_points_config(
+    cli_args: &ArgMatches,
+) -> Result<MigrateRestorePointsConfig, String> {
+    let new_slots_per_restore_point = clap_utils::parse_required(cli_args, "new-spacing")?;
+    let validation_interval = clap_utils::parse_required(cli_args, "validation-interval")?;
+    if validation_interval == 0 {
+        return Err("--validation-interval must be greater than 0".to_string());
+    }
+    Ok(MigrateRestorePointsConfig {
+        new_slots_per_restore_point,
+        validation_interval,
+    })
+}
+
+/// Change the `slots_per_restore_point` of the freezer database, resuming any interrupted change.
+pub fn migrate_restore_points<E: EthSpec>(
+    migrate_config: MigrateRestorePointsConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )?;
+
+    db.migrate_restore_points(
+        migrate_config.new_slots_per_restore_point,
+        migrate_config.validation_interval,
+    )?;
+    info!(
+        log,
+        "Restart the beacon node with the new spacing";
+        "slots_per_restore_point" => migrate_config.new_slots_per_restore_point,
+    );
+    Ok(())
+}
+
+pub fn import_era<E: EthSpec>(
+    era_dir: PathBuf,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("{:?}", e))?;
+
+    // The genesis state is always stored in the freezer database, even after checkpoint sync.
+    let genesis_state = db
+        .load_cold_state_by_slot(Slot::new(0))
+        .map_err(|e| format!("Unable to load genesis state: {:?}", e))?
+        .ok_or("Genesis state is missing from the database")?;
+    let mut genesis_block_header = genesis_state.latest_block_header().clone();
+    genesis_block_header.state_root = genesis_state.canonical_root();
+    let genesis_block_root = genesis_block_header.canonical_root();
+
+    era::import_era_files(&db, &era_dir, genesis_block_root, &log)
+}
+
+pub struct ExportEraConfig {
+    start_epoch: Epoch,
+    /// The last epoch to export, inclusive.
+    end_epoch: Epoch,
+    output_dir: PathBuf,
+    /// Write blocks whose payloads have been pruned as blinded blocks, rather than failing.
+    blinded_ok: bool,
+}
+
+fn parse_export_era_config(cli_args: &ArgMatches) -> Result<ExportEraConfig, String> {
+    let start_epoch = clap_utils::parse_required(cli_args, "start-epoch")?;
+    let end_epoch = clap_utils::parse_required(cli_args, "end-epoch")?;
+    let output_dir = clap_utils::parse_required(cli_args, "output-dir")?;
+    let blinded_ok = cli_args.is_present("blinded-ok");
+    if end_epoch < start_epoch {
+        return Err("--end-epoch must not be less than --start-epoch".to_string());
+    }
+    Ok(ExportEraConfig {
+        start_epoch,
+        end_epoch,
+        output_dir,
+        blinded_ok,
+    })
+}
+
+pub fn export_era<E: EthSpec>(
+    export_config: ExportEraConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("{:?}", e))?;
+
+    export_eras(&export_config, &db, &log).map(|_| ())
+}
+
+/// Export the eras overlapping the configured epochs from an open database, returning the paths
+/// of the era files.
+fn export_eras<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    export_config: &ExportEraConfig,
+    db: &HotColdDB<E, Hot, Cold>,
+    log: &Logger,
+) -> Result<Vec<PathBuf>, String> {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let first_era = export_config
+        .start_epoch
+        .start_slot(E::slots_per_epoch())
+        .as_u64()
+        / slots_per_era
+        + 1;
+    let last_era = export_config
+        .end_epoch
+        .end_slot(E::slots_per_epoch())
+        .as_u64()
+        / slots_per_era
+        + 1;
+
+    fs::create_dir_all(&export_config.output_dir)
+        .map_err(|e| format!("Unable to create output directory: {:?}", e))?;
+
+    (first_era..=last_era)
+        .map(|number| {
+            let path = era::export_era(
+                db,
+                number,
+                &export_config.output_dir,
+                export_config.blinded_ok,
+            )?;
+            info!(log, "Exported era file"; "era" => number, "file" => ?path);
+            Ok(path)
+        })
+        .collect()
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<T>) -> Result<(), String> {
     let client_config = parse_client_config(cli_args, &env)?;
@@ -425,6 +920,445 @@ pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<T>) -> Result
             prune_payloads(client_config, &context, log).map_err(format_err)
         }
         ("prune_blobs", Some(_)) => prune_blobs(client_config, &context, log).map_err(format_err),
+        ("compact", Some(_)) => compact_db(client_config, &context, log).map_err(format_err),
+        ("migrate-restore-points", Some(cli_args)) => {
+            let migrate_config = parse_migrate_restore_points_config(cli_args)?;
+            migrate_restore_points(migrate_config, client_config, &context, log).map_err(format_err)
+        }
+        ("export-era", Some(cli_args)) => {
+            let export_config = parse_export_era_config(cli_args)?;
+            export_era(export_config, client_config, &context, log)
+        }
+        ("import-era", Some(cli_args)) => {
+            let era_dir = clap_utils::parse_required(cli_args, "era-dir")?;
+            import_era(era_dir, client_config, &context, log)
+        }
         _ => Err("Unknown subcommand, for help `lighthouse database_manager --help`".into()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, DiskHarnessType,
+    };
+    use beacon_chain::WhenSlotSkipped;
+    use era::{era_files_in_dir, export_era, import_era_files, Era};
+    use logging::test_logger;
+    use store::chunked_iter::ChunkedVectorIter;
+    use store::chunked_vector::BlockRoots;
+    use store::{AnchorInfo, DatabaseBlock, StoreConfig};
+    use tempfile::tempdir;
+    use types::{MinimalEthSpec, SignedBeaconBlock};
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn inspect_values_alias() {
+        let matches = inspect_cli_app()
+            .get_matches_from_safe(["inspect", "--column", "blk", "--output", "values"])
+            .unwrap();
+        let inspect_config = parse_inspect_config(&matches).unwrap();
+        assert!(matches!(inspect_config.target, InspectTarget::SszFiles));
+    }
+
+    #[tokio::test]
+    async fn inspect_output_round_trips() {
+        let db_dir = tempdir().unwrap();
+        let output_dir = tempdir().unwrap();
+        let spec = test_spec::<E>();
+        let log = test_logger();
+
+        let store = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &db_dir.path().join("hot_db"),
+            &db_dir.path().join("cold_db"),
+            None,
+            |_, _, _| Ok(()),
+            StoreConfig::default(),
+            spec.clone(),
+            log.clone(),
+        )
+        .unwrap();
+        let harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(E::default())
+            .spec(spec.clone())
+            .deterministic_keypairs(8)
+            .logger(log)
+            .fresh_disk_store(store.clone())
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        // Every block is written to its own SSZ file, which decodes to the stored block.
+        let ssz_config = InspectConfig {
+            column: DBColumn::BeaconBlock,
+            target: InspectTarget::SszFiles,
+            key: None,
+            output_dir: output_dir.path().join("ssz"),
+        };
+        inspect_store(&ssz_config, &store).unwrap();
+
+        let mut num_files = 0;
+        for entry in fs::read_dir(&ssz_config.output_dir).unwrap() {
+            let path = entry.unwrap().path();
+            let block_root: Hash256 = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.strip_prefix("blk_"))
+                .unwrap()
+                .parse()
+                .unwrap();
+            let block =
+                SignedBlindedBeaconBlock::<E>::from_ssz_bytes(&fs::read(&path).unwrap(), &spec)
+                    .unwrap();
+            assert_eq!(block.canonical_root(), block_root);
+            assert_eq!(Some(block), store.get_blinded_block(&block_root).unwrap());
+            num_files += 1;
+        }
+        assert!(num_files > E::slots_per_epoch() as usize);
+        assert_eq!(
+            num_files,
+            store.hot_db.iter_column_keys(DBColumn::BeaconBlock).count()
+        );
+
+        // A single block can be decoded to JSON.
+        let head_block_root = harness.head_block_root();
+        let json_config = InspectConfig {
+            column: DBColumn::BeaconBlock,
+            target: InspectTarget::Json,
+            key: Some(head_block_root),
+            output_dir: output_dir.path().join("json"),
+        };
+        inspect_store(&json_config, &store).unwrap();
+
+        assert_eq!(fs::read_dir(&json_config.output_dir).unwrap().count(), 1);
+        let json_path = json_config
+            .output_dir
+            .join(format!("blk_{:?}.json", head_block_root));
+        let json: serde_json::Value =
+            serde_json::from_slice(&fs::read(json_path).unwrap()).unwrap();
+        let head_block = store.get_blinded_block(&head_block_root).unwrap().unwrap();
+        assert_eq!(json, serde_json::to_value(&head_block).unwrap());
+
+        // Missing keys and columns without a known type are errors.
+        let missing_key_config = InspectConfig {
+            key: Some(Hash256::repeat_byte(0xff)),
+            ..json_config
+        };
+        assert!(inspect_store(&missing_key_config, &store).is_err());
+
+        let unsupported_config = InspectConfig {
+            column: DBColumn::BeaconState,
+            target: InspectTarget::Json,
+            key: None,
+            output_dir: output_dir.path().join("state"),
+        };
+        assert!(inspect_store(&unsupported_config, &store).is_err());
+    }
+
+    #[tokio::test]
+    async fn import_era_files_resumes_and_reports_bad_file() {
+        let source_dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let era_dir = tempdir().unwrap();
+        let partial_era_dir = tempdir().unwrap();
+        let spec = test_spec::<E>();
+        let log = test_logger();
+        // Payloads are needed to export full blocks.
+        let store_config = StoreConfig {
+            prune_payloads: false,
+            ..StoreConfig::default()
+        };
+        let slots_per_era = E::slots_per_historical_root() as u64;
+
+        let source = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &source_dir.path().join("hot_db"),
+            &source_dir.path().join("cold_db"),
+            None,
+            |_, _, _| Ok(()),
+            store_config.clone(),
+            spec.clone(),
+            log.clone(),
+        )
+        .unwrap();
+        let harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(E::default())
+            .spec(spec.clone())
+            .deterministic_keypairs(8)
+            .logger(log.clone())
+            .fresh_disk_store(source.clone())
+            .mock_execution_layer()
+            .build();
+
+        // Build a chain spanning five eras, skipping the first slots of era 3.
+        let skip_slots = 3;
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                2 * slots_per_era as usize - 1,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        harness
+            .extend_chain(
+                3 * slots_per_era as usize + E::slots_per_epoch() as usize * 3 - skip_slots,
+                BlockStrategy::ForkCanonicalChainAt {
+                    previous_slot: Slot::new(2 * slots_per_era - 1),
+                    first_slot: Slot::new(2 * slots_per_era + skip_slots as u64),
+                },
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        assert!(source.get_split_slot() > 4 * slots_per_era);
+
+        for number in 1..=4 {
+            export_era(&source, number, era_dir.path(), false).unwrap();
+        }
+        let era_files = era_files_in_dir(era_dir.path()).unwrap();
+        assert_eq!(
+            era_files.keys().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        for number in [3, 4] {
+            let path = &era_files[&number];
+            fs::copy(path, partial_era_dir.path().join(path.file_name().unwrap())).unwrap();
+        }
+
+        // The target database has been checkpoint synced to a block in the middle of era 4.
+        let target = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &target_dir.path().join("hot_db"),
+            &target_dir.path().join("cold_db"),
+            None,
+            |_, _, _| Ok(()),
+            store_config,
+            spec.clone(),
+            log.clone(),
+        )
+        .unwrap();
+        let anchor_slot = Slot::new(3 * slots_per_era + 8);
+        let anchor_block_root = harness
+            .chain
+            .block_root_at_slot(anchor_slot, WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        let anchor_block = source
+            .get_blinded_block(&anchor_block_root)
+            .unwrap()
+            .unwrap();
+        let anchor = AnchorInfo {
+            anchor_slot,
+            oldest_block_slot: anchor_slot,
+            oldest_block_parent: anchor_block.parent_root(),
+            state_upper_limit: Slot::new(4 * slots_per_era),
+            state_lower_limit: Slot::new(0),
+        };
+        target
+            .compare_and_set_anchor_info_with_write(None, Some(anchor))
+            .unwrap();
+        let genesis_block_root = harness
+            .chain
+            .block_root_at_slot(Slot::new(0), WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+
+        // Importing eras 3 and 4 stops at the oldest block of era 3.
+        import_era_files(&target, partial_era_dir.path(), genesis_block_root, &log).unwrap();
+        let first_era_3_slot = Slot::new(2 * slots_per_era + skip_slots as u64);
+        assert_eq!(
+            target.get_anchor_info().unwrap().oldest_block_slot,
+            first_era_3_slot
+        );
+
+        // A block which doesn't match the state of its era is reported with the era file, and
+        // nothing more is imported.
+        let era_2_path = &era_files[&2];
+        let mut era_2 = Era::<E>::read(fs::File::open(era_2_path).unwrap(), 2, &spec).unwrap();
+        let (mut block, signature) = era_2.blocks[10].0.clone_as_blinded().deconstruct();
+        *block.state_root_mut() = Hash256::repeat_byte(0xff);
+        era_2.blocks[10].0 =
+            DatabaseBlock::Blinded(SignedBeaconBlock::from_block(block, signature));
+        fs::write(era_2_path, era_2.as_bytes().unwrap()).unwrap();
+
+        let error =
+            import_era_files(&target, era_dir.path(), genesis_block_root, &log).unwrap_err();
+        assert!(error.contains(era_2_path.to_str().unwrap()), "{}", error);
+        assert_eq!(
+            target.get_anchor_info().unwrap().oldest_block_slot,
+            first_era_3_slot
+        );
+
+        // Once the era file is replaced the import resumes from era 2 and completes the history.
+        export_era(&source, 2, era_dir.path(), false).unwrap();
+        import_era_files(&target, era_dir.path(), genesis_block_root, &log).unwrap();
+        let anchor = target.get_anchor_info().unwrap();
+        assert_eq!(anchor.oldest_block_slot, 0);
+        assert_eq!(anchor.oldest_block_parent, Hash256::zero());
+
+        let target_block_roots =
+            ChunkedVectorIter::<BlockRoots, E, _, _>::new(&target, 0, anchor_slot, &spec)
+                .map(|(_, block_root)| block_root)
+                .collect::<Vec<_>>();
+        assert_eq!(target_block_roots.len(), anchor_slot.as_usize());
+        for (slot, block_root) in target_block_roots.into_iter().enumerate() {
+            let expected_block_root = harness
+                .chain
+                .block_root_at_slot(Slot::new(slot as u64), WhenSlotSkipped::Prev)
+                .unwrap()
+                .unwrap();
+            assert_eq!(block_root, expected_block_root, "slot {}", slot);
+            if slot > 0 {
+                assert_eq!(
+                    target.get_blinded_block(&block_root).unwrap(),
+                    source.get_blinded_block(&block_root).unwrap()
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn export_era_round_trip() {
+        let source_dir = tempdir().unwrap();
+        let target_dir = tempdir().unwrap();
+        let era_dir = tempdir().unwrap();
+        let spec = test_spec::<E>();
+        let log = test_logger();
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let epochs_per_era = slots_per_era / E::slots_per_epoch();
+
+        // Execution payloads are pruned as blocks are finalized.
+        let source = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &source_dir.path().join("hot_db"),
+            &source_dir.path().join("cold_db"),
+            None,
+            |_, _, _| Ok(()),
+            StoreConfig::default(),
+            spec.clone(),
+            log.clone(),
+        )
+        .unwrap();
+        let harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(E::default())
+            .spec(spec.clone())
+            .deterministic_keypairs(8)
+            .logger(log.clone())
+            .fresh_disk_store(source.clone())
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                3 * slots_per_era as usize + 3 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        assert!(source.get_split_slot() > 3 * slots_per_era);
+
+        let export_config = |start_epoch: u64, end_epoch: u64, blinded_ok| ExportEraConfig {
+            start_epoch: Epoch::new(start_epoch),
+            end_epoch: Epoch::new(end_epoch),
+            output_dir: era_dir.path().to_path_buf(),
+            blinded_ok,
+        };
+
+        // Era 4 has not been finalized.
+        let error = export_eras(
+            &export_config(3 * epochs_per_era, 3 * epochs_per_era, true),
+            &source,
+            &log,
+        )
+        .unwrap_err();
+        assert!(error.contains("not finalized"), "{}", error);
+
+        // Blocks without their payloads are only exported when blinded blocks are allowed.
+        let first_block = source
+            .get_blinded_block(
+                &harness
+                    .chain
+                    .block_root_at_slot(Slot::new(1), WhenSlotSkipped::None)
+                    .unwrap()
+                    .unwrap(),
+            )
+            .unwrap()
+            .unwrap();
+        if first_block.message().execution_payload().is_ok() {
+            let error = export_eras(&export_config(0, 0, false), &source, &log).unwrap_err();
+            assert!(error.contains("--blinded-ok"), "{}", error);
+            assert_eq!(fs::read_dir(era_dir.path()).unwrap().count(), 0);
+        }
+
+        // Eras 1 to 3 overlap epochs 1 to the first epoch of era 3.
+        let paths =
+            export_eras(&export_config(1, 2 * epochs_per_era, true), &source, &log).unwrap();
+        assert_eq!(paths.len(), 3);
+        assert_eq!(
+            era_files_in_dir(era_dir.path())
+                .unwrap()
+                .into_values()
+                .collect::<Vec<_>>(),
+            paths
+        );
+
+        // Import the eras into a database which has been checkpoint synced to the end of era 3.
+        let target = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+            &target_dir.path().join("hot_db"),
+            &target_dir.path().join("cold_db"),
+            None,
+            |_, _, _| Ok(()),
+            StoreConfig::default(),
+            spec.clone(),
+            log.clone(),
+        )
+        .unwrap();
+        let anchor_slot = Slot::new(3 * slots_per_era);
+        let anchor_block_root = harness
+            .chain
+            .block_root_at_slot(anchor_slot, WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        let anchor_block = source
+            .get_blinded_block(&anchor_block_root)
+            .unwrap()
+            .unwrap();
+        target
+            .compare_and_set_anchor_info_with_write(
+                None,
+                Some(AnchorInfo {
+                    anchor_slot,
+                    oldest_block_slot: anchor_slot,
+                    oldest_block_parent: anchor_block.parent_root(),
+                    state_upper_limit: anchor_slot,
+                    state_lower_limit: Slot::new(0),
+                }),
+            )
+            .unwrap();
+        let genesis_block_root = harness
+            .chain
+            .block_root_at_slot(Slot::new(0), WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        import_era_files(&target, era_dir.path(), genesis_block_root, &log).unwrap();
+        assert_eq!(target.get_anchor_info().unwrap().oldest_block_slot, 0);
+
+        let block_roots = |store| {
+            ChunkedVectorIter::<BlockRoots, E, _, _>::new(store, 0, anchor_slot, &spec)
+                .map(|(_, block_root)| block_root)
+                .collect::<Vec<_>>()
+        };
+        let target_block_roots = block_roots(&target);
+        assert_eq!(target_block_roots.len(), anchor_slot.as_usize());
+        assert_eq!(target_block_roots, block_roots(&source));
+        for block_root in &target_block_roots[1..] {
+            assert_eq!(
+                target.get_blinded_block(block_root).unwrap(),
+                source.get_blinded_block(block_root).unwrap()
+            );
+        }
+    }
+}
@@ -11,10 +11,12 @@ mod indexed_attestations;
 mod insecure_validators;
 mod interop_genesis;
 mod mnemonic_validators;
+mod mock_el;
 mod new_testnet;
 mod parse_ssz;
 mod replace_state_pubkeys;
 mod skip_slots;
+mod state_diff;
 mod state_root;
 mod transition_blocks;
 
@@ -211,6 +213,23 @@ fn main() {
                         .help("If present, don't rebuild the tree-hash-cache after applying \
                             the block."),
                 )
+                .arg(
+                    Arg::with_name("profile")
+                        .long("profile")
+                        .takes_value(false)
+                        .help("Print the time spent in each stage of the transition, including \
+                            each type of operation processed by the block, averaged over all \
+                            runs."),
+                )
+                .arg(
+                    Arg::with_name("compare-state")
+                        .long("compare-state")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Path to load an expected post-state from as SSZ. The post-state is \
+                            compared to it field-by-field and the first divergent value is \
+                            reported along with its tree hash chunk."),
+                )
         )
         .subcommand(
             SubCommand::with_name("pretty-ssz")
@@ -891,6 +910,72 @@ fn main() {
                         .help("Number of repeat runs, useful for benchmarking."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("mock-el")
+                .about("Creates a mock execution layer server. This is NOT SAFE and should only \
+                be used for testing and development on testnets. Do not use in production. Do not \
+                use on mainnet. It cannot perform validator duties.")
+                .arg(
+                    Arg::with_name("jwt-output-path")
+                        .long("jwt-output-path")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to write the JWT secret."),
+                )
+                .arg(
+                    Arg::with_name("listen-address")
+                        .long("listen-address")
+                        .value_name("IP_ADDRESS")
+                        .takes_value(true)
+                        .help("The server will listen on this address.")
+                        .default_value("127.0.0.1")
+                )
+                .arg(
+                    Arg::with_name("listen-port")
+                        .long("listen-port")
+                        .value_name("PORT")
+                        .takes_value(true)
+                        .help("The server will listen on this port.")
+                        .default_value("8551")
+                )
+                .arg(
+                    Arg::with_name("all-payloads-valid")
+                        .long("all-payloads-valid")
+                        .takes_value(true)
+                        .help("Controls the response to newPayload and forkchoiceUpdated. \
+                            Set to 'true' to return VALID for every payload. Set to 'false' to \
+                            verify payloads against the mock chain.")
+                        .default_value("false")
+                        .hidden(true)
+                )
+                .arg(
+                    Arg::with_name("shanghai-time")
+                        .long("shanghai-time")
+                        .value_name("UNIX_TIMESTAMP")
+                        .takes_value(true)
+                        .help("The payload timestamp that enables Shanghai. Shanghai is not enabled if \
+                            omitted.")
+                )
+                .arg(
+                    Arg::with_name("cancun-time")
+                        .long("cancun-time")
+                        .value_name("UNIX_TIMESTAMP")
+                        .takes_value(true)
+                        .help("The payload timestamp that enables Cancun. Cancun is not enabled if \
+                            omitted.")
+                )
+                .arg(
+                    Arg::with_name("script")
+                        .long("script")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("Path to a JSON script mapping block hash prefixes or slot ranges to \
+                            the statuses returned by newPayload and forkchoiceUpdated, and to \
+                            artificial latencies. The script can be replaced at runtime by POSTing \
+                            it to the /script endpoint of the server.")
+                )
+        )
         .get_matches();
 
     let result = matches
@@ -923,6 +1008,8 @@ fn run<T: EthSpec>(
             path: None,
             debug_level: String::from("trace"),
             logfile_debug_level: String::from("trace"),
+            log_filter: None,
+            logfile_filter: None,
             log_format: None,
             logfile_format: None,
             log_color: false,
@@ -1032,6 +1119,8 @@ fn run<T: EthSpec>(
             state_root::run::<T>(env, network_config, matches)
                 .map_err(|e| format!("Failed to run state-root command: {}", e))
         }
+        ("mock-el", Some(matches)) => mock_el::run::<T>(env, matches)
+            .map_err(|e| format!("Failed to run mock-el command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
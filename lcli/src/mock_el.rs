@@ -0,0 +1,93 @@
+//! # Mock-EL
+//!
+//! Runs the mock execution engine used by the Lighthouse tests as a standalone server, so that a
+//! beacon node can be pointed at it with `--execution-endpoint` and `--execution-jwt`.
+//!
+//! The responses to `newPayload` and `forkchoiceUpdated` may be scripted with `--script`, e.g. to
+//! return `SYNCING` for a range of slots before returning `VALID`. See
+//! `execution_layer::test_utils::PayloadScript` for the format of the script. The script can be
+//! replaced at runtime by `POST`ing it to the `/script` endpoint, or removed with `DELETE`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli mock-el \
+//!     --jwt-output-path /tmp/jwt.hex \
+//!     --listen-port 8551 \
+//!     --script /tmp/script.json
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use execution_layer::{
+    auth::JwtKey,
+    test_utils::{Config, MockExecutionConfig, MockServer, PayloadScript},
+};
+use std::fs::File;
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::time::Duration;
+use types::EthSpec;
+
+pub fn run<T: EthSpec>(env: Environment<T>, matches: &ArgMatches) -> Result<(), String> {
+    let jwt_path: PathBuf = parse_required(matches, "jwt-output-path")?;
+    let listen_addr: Ipv4Addr = parse_required(matches, "listen-address")?;
+    let listen_port: u16 = parse_required(matches, "listen-port")?;
+    let all_payloads_valid: bool = parse_required(matches, "all-payloads-valid")?;
+    let shanghai_time = parse_optional(matches, "shanghai-time")?;
+    let cancun_time = parse_optional(matches, "cancun-time")?;
+    let script_path: Option<PathBuf> = parse_optional(matches, "script")?;
+
+    let handle = env
+        .core_context()
+        .executor
+        .handle()
+        .ok_or("shutdown in progress")?;
+
+    let jwt_key = JwtKey::random();
+    std::fs::write(jwt_path, jwt_key.hex_string())
+        .map_err(|e| format!("Unable to write JWT secret: {:?}", e))?;
+
+    let config = MockExecutionConfig {
+        server_config: Config {
+            listen_addr,
+            listen_port,
+        },
+        jwt_key,
+        shanghai_time,
+        cancun_time,
+        ..Default::default()
+    };
+    let server: MockServer<T> = MockServer::new_with_config(&handle, config, None);
+
+    if all_payloads_valid {
+        eprintln!(
+            "Using --all-payloads-valid=true can be dangerous. \
+            Never use this flag when operating validators."
+        );
+        // Indicate that all payloads are valid.
+        server.all_payloads_valid();
+    }
+
+    if let Some(script_path) = script_path {
+        let file = File::open(&script_path)
+            .map_err(|e| format!("Unable to open script {:?}: {:?}", script_path, e))?;
+        let script: PayloadScript = serde_json::from_reader(file)
+            .map_err(|e| format!("Unable to parse script {:?}: {:?}", script_path, e))?;
+        server
+            .set_script(script)
+            .map_err(|e| format!("Invalid script {:?}: {}", script_path, e))?;
+    }
+
+    eprintln!(
+        "Serving mock execution layer on {}:{}, with the script control endpoint at {}/script",
+        listen_addr,
+        listen_port,
+        server.url()
+    );
+
+    // The server is shut down when dropped, so keep it alive indefinitely.
+    loop {
+        std::thread::sleep(Duration::from_secs(60));
+    }
+}
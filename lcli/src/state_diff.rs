@@ -0,0 +1,422 @@
+//! # State Diff
+//!
+//! Compares two `BeaconState`s field-by-field, in the order the fields are merkleized, and reports
+//! the first divergent value along with the path of the tree hash chunk containing it.
+//!
+//! List and vector fields are compared element-by-element so that a divergence can be pinpointed
+//! to e.g. a single balance, rather than just the `balances` field. Elements of basic types are
+//! packed into 32-byte chunks by the tree hash, so the chunk path of `balances[17]` is
+//! `balances/4`.
+//!
+//! Unlike `CompareFields`, only the divergent value is formatted, so this is suitable for
+//! mainnet-sized states.
+use std::fmt;
+use tree_hash::{TreeHash, TreeHashType};
+use types::{BeaconState, EthSpec, Hash256, Validator};
+
+/// The first value which differs between an expected and an actual state.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// The path of the value, e.g. `balances[17]`.
+    pub path: String,
+    /// The path of the tree hash chunk containing the value, e.g. `balances/4`.
+    pub chunk_path: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} (tree hash chunk {}): expected {}, got {}",
+            self.path, self.chunk_path, self.expected, self.actual
+        )
+    }
+}
+
+impl Divergence {
+    fn new(
+        path: String,
+        chunk_path: String,
+        expected: impl fmt::Debug,
+        actual: impl fmt::Debug,
+    ) -> Self {
+        Self {
+            path,
+            chunk_path,
+            expected: format!("{:?}", expected),
+            actual: format!("{:?}", actual),
+        }
+    }
+}
+
+/// Returns the first divergence between `expected` and `actual`, or `None` if they are equal.
+pub fn first_divergence<T: EthSpec>(
+    expected: &BeaconState<T>,
+    actual: &BeaconState<T>,
+) -> Option<Divergence> {
+    let (expected_fork, actual_fork) =
+        (expected.fork_name_unchecked(), actual.fork_name_unchecked());
+    if expected_fork != actual_fork {
+        return Some(Divergence::new(
+            "<variant>".into(),
+            "<root>".into(),
+            expected_fork,
+            actual_fork,
+        ));
+    }
+
+    diff_field(
+        "genesis_time",
+        &expected.genesis_time(),
+        &actual.genesis_time(),
+    )
+    .or_else(|| {
+        diff_field(
+            "genesis_validators_root",
+            &expected.genesis_validators_root(),
+            &actual.genesis_validators_root(),
+        )
+    })
+    .or_else(|| diff_field("slot", &expected.slot(), &actual.slot()))
+    .or_else(|| diff_field("fork", &expected.fork(), &actual.fork()))
+    .or_else(|| {
+        diff_field(
+            "latest_block_header",
+            expected.latest_block_header(),
+            actual.latest_block_header(),
+        )
+    })
+    .or_else(|| diff_list("block_roots", expected.block_roots(), actual.block_roots()))
+    .or_else(|| diff_list("state_roots", expected.state_roots(), actual.state_roots()))
+    .or_else(|| {
+        diff_list(
+            "historical_roots",
+            expected.historical_roots(),
+            actual.historical_roots(),
+        )
+    })
+    .or_else(|| diff_field("eth1_data", expected.eth1_data(), actual.eth1_data()))
+    .or_else(|| {
+        diff_list(
+            "eth1_data_votes",
+            expected.eth1_data_votes(),
+            actual.eth1_data_votes(),
+        )
+    })
+    .or_else(|| {
+        diff_field(
+            "eth1_deposit_index",
+            &expected.eth1_deposit_index(),
+            &actual.eth1_deposit_index(),
+        )
+    })
+    .or_else(|| diff_validators(expected.validators(), actual.validators()))
+    .or_else(|| diff_list("balances", expected.balances(), actual.balances()))
+    .or_else(|| {
+        diff_list(
+            "randao_mixes",
+            expected.randao_mixes(),
+            actual.randao_mixes(),
+        )
+    })
+    .or_else(|| diff_list("slashings", expected.slashings(), actual.slashings()))
+    .or_else(|| match (expected, actual) {
+        (BeaconState::Base(expected), BeaconState::Base(actual)) => diff_list(
+            "previous_epoch_attestations",
+            &expected.previous_epoch_attestations,
+            &actual.previous_epoch_attestations,
+        )
+        .or_else(|| {
+            diff_list(
+                "current_epoch_attestations",
+                &expected.current_epoch_attestations,
+                &actual.current_epoch_attestations,
+            )
+        }),
+        _ => both(
+            expected.previous_epoch_participation(),
+            actual.previous_epoch_participation(),
+        )
+        .and_then(|(e, a)| diff_list("previous_epoch_participation", e, a))
+        .or_else(|| {
+            both(
+                expected.current_epoch_participation(),
+                actual.current_epoch_participation(),
+            )
+            .and_then(|(e, a)| diff_list("current_epoch_participation", e, a))
+        }),
+    })
+    .or_else(|| {
+        diff_field(
+            "justification_bits",
+            expected.justification_bits(),
+            actual.justification_bits(),
+        )
+    })
+    .or_else(|| {
+        diff_field(
+            "previous_justified_checkpoint",
+            &expected.previous_justified_checkpoint(),
+            &actual.previous_justified_checkpoint(),
+        )
+    })
+    .or_else(|| {
+        diff_field(
+            "current_justified_checkpoint",
+            &expected.current_justified_checkpoint(),
+            &actual.current_justified_checkpoint(),
+        )
+    })
+    .or_else(|| {
+        diff_field(
+            "finalized_checkpoint",
+            &expected.finalized_checkpoint(),
+            &actual.finalized_checkpoint(),
+        )
+    })
+    .or_else(|| {
+        both(expected.inactivity_scores(), actual.inactivity_scores())
+            .and_then(|(e, a)| diff_list("inactivity_scores", e, a))
+    })
+    .or_else(|| {
+        both(
+            expected.current_sync_committee(),
+            actual.current_sync_committee(),
+        )
+        .and_then(|(e, a)| diff_root("current_sync_committee", &**e, &**a))
+    })
+    .or_else(|| {
+        both(expected.next_sync_committee(), actual.next_sync_committee())
+            .and_then(|(e, a)| diff_root("next_sync_committee", &**e, &**a))
+    })
+    .or_else(|| {
+        both(
+            expected.latest_execution_payload_header(),
+            actual.latest_execution_payload_header(),
+        )
+        .and_then(|(e, a)| diff_root("latest_execution_payload_header", &e, &a))
+    })
+    .or_else(|| {
+        both(
+            expected.next_withdrawal_index(),
+            actual.next_withdrawal_index(),
+        )
+        .and_then(|(e, a)| diff_field("next_withdrawal_index", &e, &a))
+    })
+    .or_else(|| {
+        both(
+            expected.next_withdrawal_validator_index(),
+            actual.next_withdrawal_validator_index(),
+        )
+        .and_then(|(e, a)| diff_field("next_withdrawal_validator_index", &e, &a))
+    })
+    .or_else(|| {
+        both(
+            expected.historical_summaries(),
+            actual.historical_summaries(),
+        )
+        .and_then(|(e, a)| diff_list("historical_summaries", e, a))
+    })
+}
+
+/// Returns both values of a fork-specific field, which are present in both states or neither
+/// since the states are of the same fork.
+fn both<V, E>(expected: Result<V, E>, actual: Result<V, E>) -> Option<(V, V)> {
+    expected.ok().zip(actual.ok())
+}
+
+/// Compares a field which is a single chunk, or small enough to be printed in full.
+fn diff_field<V: PartialEq + fmt::Debug>(
+    field: &str,
+    expected: &V,
+    actual: &V,
+) -> Option<Divergence> {
+    (expected != actual).then(|| Divergence::new(field.into(), field.into(), expected, actual))
+}
+
+/// Compares a large container field by its tree hash root.
+fn diff_root<V: TreeHash>(field: &str, expected: &V, actual: &V) -> Option<Divergence> {
+    let expected: Hash256 = expected.tree_hash_root();
+    let actual: Hash256 = actual.tree_hash_root();
+    diff_field(field, &expected, &actual)
+}
+
+/// Compares a list or vector field element-by-element, followed by its length.
+fn diff_list<V: PartialEq + TreeHash + fmt::Debug>(
+    field: &str,
+    expected: &[V],
+    actual: &[V],
+) -> Option<Divergence> {
+    let elements_per_chunk = match V::tree_hash_type() {
+        TreeHashType::Basic => V::tree_hash_packing_factor(),
+        _ => 1,
+    };
+
+    first_divergent_index(expected, actual)
+        .map(|i| {
+            Divergence::new(
+                format!("{}[{}]", field, i),
+                format!("{}/{}", field, i / elements_per_chunk),
+                &expected[i],
+                &actual[i],
+            )
+        })
+        .or_else(|| diff_length(field, expected, actual))
+}
+
+/// Compares the validators, descending into the fields of the first divergent validator.
+fn diff_validators(expected: &[Validator], actual: &[Validator]) -> Option<Divergence> {
+    first_divergent_index(expected, actual)
+        .and_then(|i| {
+            let (e, a) = (&expected[i], &actual[i]);
+            let field = |name: &str, e: &dyn fmt::Debug, a: &dyn fmt::Debug| {
+                Divergence::new(
+                    format!("validators[{}].{}", i, name),
+                    format!("validators/{}/{}", i, name),
+                    e,
+                    a,
+                )
+            };
+
+            if e.pubkey != a.pubkey {
+                Some(field("pubkey", &e.pubkey, &a.pubkey))
+            } else if e.withdrawal_credentials != a.withdrawal_credentials {
+                Some(field(
+                    "withdrawal_credentials",
+                    &e.withdrawal_credentials,
+                    &a.withdrawal_credentials,
+                ))
+            } else if e.effective_balance != a.effective_balance {
+                Some(field(
+                    "effective_balance",
+                    &e.effective_balance,
+                    &a.effective_balance,
+                ))
+            } else if e.slashed != a.slashed {
+                Some(field("slashed", &e.slashed, &a.slashed))
+            } else if e.activation_eligibility_epoch != a.activation_eligibility_epoch {
+                Some(field(
+                    "activation_eligibility_epoch",
+                    &e.activation_eligibility_epoch,
+                    &a.activation_eligibility_epoch,
+                ))
+            } else if e.activation_epoch != a.activation_epoch {
+                Some(field(
+                    "activation_epoch",
+                    &e.activation_epoch,
+                    &a.activation_epoch,
+                ))
+            } else if e.exit_epoch != a.exit_epoch {
+                Some(field("exit_epoch", &e.exit_epoch, &a.exit_epoch))
+            } else if e.withdrawable_epoch != a.withdrawable_epoch {
+                Some(field(
+                    "withdrawable_epoch",
+                    &e.withdrawable_epoch,
+                    &a.withdrawable_epoch,
+                ))
+            } else {
+                None
+            }
+        })
+        .or_else(|| diff_length("validators", expected, actual))
+}
+
+fn first_divergent_index<V: PartialEq>(expected: &[V], actual: &[V]) -> Option<usize> {
+    expected.iter().zip(actual).position(|(e, a)| e != a)
+}
+
+/// Compares the lengths of two lists, which is mixed in to the root of the list.
+fn diff_length<V>(field: &str, expected: &[V], actual: &[V]) -> Option<Divergence> {
+    (expected.len() != actual.len()).then(|| {
+        Divergence::new(
+            format!("{}.len()", field),
+            format!("{}/length", field),
+            expected.len(),
+            actual.len(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ChainSpec, Epoch, Eth1Data, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    fn state_with_validators(spec: &ChainSpec, count: usize) -> BeaconState<E> {
+        let mut state = BeaconState::new(0, Eth1Data::default(), spec);
+        for _ in 0..count {
+            state
+                .validators_mut()
+                .push(Validator {
+                    effective_balance: spec.max_effective_balance,
+                    activation_epoch: Epoch::new(0),
+                    exit_epoch: spec.far_future_epoch,
+                    withdrawable_epoch: spec.far_future_epoch,
+                    ..Validator::default()
+                })
+                .unwrap();
+            state
+                .balances_mut()
+                .push(spec.max_effective_balance)
+                .unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn equal_states() {
+        let spec = E::default_spec();
+        let state = state_with_validators(&spec, 8);
+        assert_eq!(first_divergence(&state, &state.clone()), None);
+    }
+
+    #[test]
+    fn corrupted_balance() {
+        let spec = E::default_spec();
+        let expected = state_with_validators(&spec, 24);
+        let mut actual = expected.clone();
+        *actual.get_balance_mut(17).unwrap() -= 1;
+        // A later field which also differs should not be reported.
+        actual.slashings_mut()[0] = 1;
+
+        assert_eq!(
+            first_divergence(&expected, &actual),
+            Some(Divergence {
+                path: "balances[17]".into(),
+                chunk_path: "balances/4".into(),
+                expected: spec.max_effective_balance.to_string(),
+                actual: (spec.max_effective_balance - 1).to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn corrupted_validator() {
+        let spec = E::default_spec();
+        let expected = state_with_validators(&spec, 4);
+        let mut actual = expected.clone();
+        actual.get_validator_mut(2).unwrap().exit_epoch = Epoch::new(3);
+
+        let divergence = first_divergence(&expected, &actual).unwrap();
+        assert_eq!(divergence.path, "validators[2].exit_epoch");
+        assert_eq!(divergence.chunk_path, "validators/2/exit_epoch");
+    }
+
+    #[test]
+    fn missing_validator() {
+        let spec = E::default_spec();
+        let expected = state_with_validators(&spec, 4);
+        let actual = state_with_validators(&spec, 3);
+
+        let divergence = first_divergence(&expected, &actual).unwrap();
+        assert_eq!(divergence.chunk_path, "validators/length");
+        assert_eq!(
+            (divergence.expected, divergence.actual),
+            ("4".into(), "3".into())
+        );
+    }
+}
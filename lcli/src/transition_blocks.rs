@@ -61,6 +61,22 @@
 //!     --exclude-cache-builds \
 //!     --exclude-post-block-thc
 //! ```
+//!
+//! ### Profile block processing and check the post-state
+//!
+//! Print the time spent in each stage of the transition, including each type of operation in the
+//! block, and compare the post-state to an expected post-state, reporting the first divergent
+//! field:
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --block-path /tmp/block-0x6c69.ssz \
+//!     --pre-state-path /tmp/pre-state-0x6c69.ssz \
+//!     --runs 10 \
+//!     --profile \
+//!     --compare-state /tmp/post-state-0x6c69.ssz
+//! ```
+use crate::state_diff::first_divergence;
 use beacon_chain::{
     test_utils::EphemeralHarnessType, validator_pubkey_cache::ValidatorPubkeyCache,
 };
@@ -94,6 +110,22 @@ struct Config {
     no_signature_verification: bool,
     exclude_cache_builds: bool,
     exclude_post_block_thc: bool,
+    profile: bool,
+}
+
+/// The time spent in each stage of a single run.
+#[derive(Default)]
+struct StageTimings {
+    stages: Vec<(String, Duration)>,
+}
+
+impl StageTimings {
+    /// Records the time elapsed since `start` against `stage`.
+    fn record(&mut self, stage: &str, start: Instant) {
+        let duration = start.elapsed();
+        debug!("{}: {:?}", stage, duration);
+        self.stages.push((stage.to_string(), duration));
+    }
 }
 
 pub fn run<T: EthSpec>(
@@ -114,12 +146,14 @@ pub fn run<T: EthSpec>(
         parse_optional(matches, "post-state-output-path")?;
     let pre_state_output_path: Option<PathBuf> = parse_optional(matches, "pre-state-output-path")?;
     let block_output_path: Option<PathBuf> = parse_optional(matches, "block-output-path")?;
+    let compare_state_path: Option<PathBuf> = parse_optional(matches, "compare-state")?;
     let beacon_url: Option<SensitiveUrl> = parse_optional(matches, "beacon-url")?;
     let runs: usize = parse_required(matches, "runs")?;
     let config = Config {
         no_signature_verification: matches.is_present("no-signature-verification"),
         exclude_cache_builds: matches.is_present("exclude-cache-builds"),
         exclude_post_block_thc: matches.is_present("exclude-post-block-thc"),
+        profile: matches.is_present("profile"),
     };
 
     info!("Using {} spec", T::spec_name());
@@ -232,9 +266,11 @@ pub fn run<T: EthSpec>(
      */
 
     let mut output_post_state = None;
+    let mut run_timings = Vec::with_capacity(runs);
     for i in 0..runs {
         let pre_state = pre_state.clone_with(CloneConfig::all());
         let block = block.clone();
+        let mut timings = StageTimings::default();
 
         let start = Instant::now();
 
@@ -245,17 +281,49 @@ pub fn run<T: EthSpec>(
             state_root_opt,
             &config,
             &validator_pubkey_cache,
+            &mut timings,
             spec,
         )?;
 
         let duration = Instant::now().duration_since(start);
         info!("Run {}: {:?}", i, duration);
+        timings.stages.push(("total".to_string(), duration));
+        run_timings.push(timings);
 
         if output_post_state.is_none() {
             output_post_state = Some(post_state)
         }
     }
 
+    if config.profile {
+        print_profile(&run_timings);
+    }
+
+    /*
+     * Compare the post-state to the expected post-state, if required.
+     */
+
+    if let Some(path) = compare_state_path {
+        let post_state = output_post_state.as_ref().ok_or_else(|| {
+            format!(
+                "Post state was not computed, cannot compare it (runs = {})",
+                runs
+            )
+        })?;
+        let expected_state = load_from_ssz_with(&path, spec, BeaconState::from_ssz_bytes)?;
+
+        match first_divergence(&expected_state, post_state) {
+            None => info!("Post-state matches {}", path.display()),
+            Some(divergence) => {
+                return Err(format!(
+                    "Post-state differs from {} at {}",
+                    path.display(),
+                    divergence
+                ))
+            }
+        }
+    }
+
     /*
      * Write artifacts to disk, if required.
      */
@@ -297,6 +365,7 @@ pub fn run<T: EthSpec>(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn do_transition<T: EthSpec>(
     mut pre_state: BeaconState<T>,
     block_root: Hash256,
@@ -304,6 +373,7 @@ fn do_transition<T: EthSpec>(
     mut state_root_opt: Option<Hash256>,
     config: &Config,
     validator_pubkey_cache: &ValidatorPubkeyCache<EphemeralHarnessType<T>>,
+    timings: &mut StageTimings,
     spec: &ChainSpec,
 ) -> Result<BeaconState<T>, String> {
     if !config.exclude_cache_builds {
@@ -311,13 +381,13 @@ fn do_transition<T: EthSpec>(
         pre_state
             .build_caches(spec)
             .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-        debug!("Build caches: {:?}", t.elapsed());
+        timings.record("build_caches", t);
 
         let t = Instant::now();
         let state_root = pre_state
             .update_tree_hash_cache()
             .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-        debug!("Initial tree hash: {:?}", t.elapsed());
+        timings.record("tree_hash_cache_build", t);
 
         if state_root_opt.map_or(false, |expected| expected != state_root) {
             return Err(format!(
@@ -335,17 +405,20 @@ fn do_transition<T: EthSpec>(
     let t = Instant::now();
     complete_state_advance(&mut pre_state, Some(state_root), block.slot(), spec)
         .map_err(|e| format!("Unable to perform complete advance: {e:?}"))?;
-    debug!("Slot processing: {:?}", t.elapsed());
+    timings.record("slot_processing", t);
 
     let t = Instant::now();
     pre_state
         .build_caches(spec)
         .map_err(|e| format!("Unable to build caches: {:?}", e))?;
-    debug!("Build all caches (again): {:?}", t.elapsed());
+    timings.record("build_caches_post_advance", t);
 
     let mut ctxt = ConsensusContext::new(pre_state.slot())
         .set_current_block_root(block_root)
         .set_proposer_index(block.message().proposer_index());
+    if config.profile {
+        ctxt = ctxt.record_stage_timings();
+    }
 
     if !config.no_signature_verification {
         let get_pubkey = move |validator_index| {
@@ -371,7 +444,7 @@ fn do_transition<T: EthSpec>(
             spec,
         )
         .map_err(|e| format!("Invalid block signature: {:?}", e))?;
-        debug!("Batch verify block signatures: {:?}", t.elapsed());
+        timings.record("signature_verification", t);
 
         // Signature verification should prime the indexed attestation cache.
         assert_eq!(
@@ -391,19 +464,62 @@ fn do_transition<T: EthSpec>(
         spec,
     )
     .map_err(|e| format!("State transition failed: {:?}", e))?;
-    debug!("Process block: {:?}", t.elapsed());
+    timings.record("process_block", t);
+    for (stage, duration) in ctxt.stage_timings() {
+        timings
+            .stages
+            .push((format!("process_block/{}", stage), *duration));
+    }
 
     if !config.exclude_post_block_thc {
         let t = Instant::now();
         pre_state
             .update_tree_hash_cache()
             .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
-        debug!("Post-block tree hash: {:?}", t.elapsed());
+        timings.record("state_root", t);
     }
 
     Ok(pre_state)
 }
 
+/// Prints the mean, minimum and maximum time spent in each stage across all runs.
+fn print_profile(run_timings: &[StageTimings]) {
+    let mut stages: Vec<(&str, Vec<Duration>)> = vec![];
+    for (stage, duration) in run_timings.iter().flat_map(|timings| &timings.stages) {
+        match stages.iter().position(|(name, _)| *name == stage.as_str()) {
+            Some(i) => stages[i].1.push(*duration),
+            None => stages.push((stage.as_str(), vec![*duration])),
+        }
+    }
+
+    let width = stages
+        .iter()
+        .map(|(stage, _)| stage.len())
+        .max()
+        .unwrap_or(0);
+    println!(
+        "{:<width$}  {:>12}  {:>12}  {:>12}",
+        "stage",
+        "mean",
+        "min",
+        "max",
+        width = width
+    );
+    for (stage, durations) in stages {
+        let mean = durations.iter().sum::<Duration>() / durations.len() as u32;
+        let min = durations.iter().min().copied().unwrap_or_default();
+        let max = durations.iter().max().copied().unwrap_or_default();
+        println!(
+            "{:<width$}  {:>12}  {:>12}  {:>12}",
+            stage,
+            format!("{:.3?}", mean),
+            format!("{:.3?}", min),
+            format!("{:.3?}", max),
+            width = width
+        );
+    }
+}
+
 pub fn load_from_ssz_with<T>(
     path: &Path,
     spec: &ChainSpec,
@@ -12,15 +12,15 @@ use eth2_network_config::Eth2NetworkConfig;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::{future, StreamExt};
 
-use logging::SSELoggingComponents;
+use logging::{LogFilter, LogFilterHandle, LogFilters, SSELoggingComponents, TargetFilter};
 use serde::{Deserialize, Serialize};
-use slog::{error, info, o, warn, Drain, Duplicate, Level, Logger};
+use slog::{error, info, o, warn, Drain, Duplicate, Logger};
 use sloggers::{file::FileLoggerBuilder, types::Format, types::Severity, Build};
 use std::fs::create_dir_all;
 use std::io::{Result as IOResult, Write};
 use std::path::PathBuf;
 use std::sync::Arc;
-use task_executor::{ShutdownReason, TaskExecutor};
+use task_executor::{GracefulShutdown, ShutdownReason, TaskExecutor};
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
 use types::{EthSpec, GnosisEthSpec, MainnetEthSpec, MinimalEthSpec};
 
@@ -51,6 +51,10 @@ pub struct LoggerConfig {
     pub path: Option<PathBuf>,
     pub debug_level: String,
     pub logfile_debug_level: String,
+    /// Per-target levels for the terminal, e.g. `sync=debug,libp2p_gossipsub=warn`.
+    pub log_filter: Option<String>,
+    /// Per-target levels for the log file.
+    pub logfile_filter: Option<String>,
     pub log_format: Option<String>,
     pub logfile_format: Option<String>,
     pub log_color: bool,
@@ -67,6 +71,8 @@ impl Default for LoggerConfig {
             path: None,
             debug_level: String::from("info"),
             logfile_debug_level: String::from("debug"),
+            log_filter: None,
+            logfile_filter: None,
             log_format: None,
             logfile_format: None,
             log_color: false,
@@ -91,6 +97,7 @@ pub struct RuntimeContext<E: EthSpec> {
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    pub log_filters: Option<LogFilters>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -104,6 +111,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_filters: self.log_filters.clone(),
         }
     }
 
@@ -123,6 +131,7 @@ pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Arc<Runtime>>,
     log: Option<Logger>,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_filters: Option<LogFilters>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     eth2_network_config: Option<Eth2NetworkConfig>,
@@ -135,6 +144,7 @@ impl EnvironmentBuilder<MinimalEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_filters: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             eth2_network_config: None,
@@ -149,6 +159,7 @@ impl EnvironmentBuilder<MainnetEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_filters: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             eth2_network_config: None,
@@ -163,6 +174,7 @@ impl EnvironmentBuilder<GnosisEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_filters: None,
             eth_spec_instance: GnosisEthSpec,
             eth2_config: Eth2Config::gnosis(),
             eth2_network_config: None,
@@ -235,16 +247,18 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let stdout_drain = match config.debug_level.as_str() {
-            "info" => stdout_drain.filter_level(Level::Info),
-            "debug" => stdout_drain.filter_level(Level::Debug),
-            "trace" => stdout_drain.filter_level(Level::Trace),
-            "warn" => stdout_drain.filter_level(Level::Warning),
-            "error" => stdout_drain.filter_level(Level::Error),
-            "crit" => stdout_drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let debug_level = logging::parse_level(&config.debug_level)
+            .ok_or_else(|| format!("Unknown debug-level: {}", config.debug_level))?;
+        let terminal_filter = LogFilterHandle::new(
+            LogFilter::parse(config.log_filter.as_deref().unwrap_or(""), debug_level)
+                .map_err(|e| format!("Invalid log-filter: {}", e))?,
+        );
+        self.log_filters = Some(LogFilters {
+            terminal: terminal_filter.clone(),
+            file: None,
+        });
 
+        let stdout_drain = TargetFilter::new(stdout_drain, terminal_filter.clone());
         let stdout_logger = Logger::root(stdout_drain.fuse(), o!());
 
         // Disable file logging if values set to 0.
@@ -287,18 +301,23 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             }
         }
 
-        let logfile_level = match config.logfile_debug_level.as_str() {
-            "info" => Severity::Info,
-            "debug" => Severity::Debug,
-            "trace" => Severity::Trace,
-            "warn" => Severity::Warning,
-            "error" => Severity::Error,
-            "crit" => Severity::Critical,
-            unknown => return Err(format!("Unknown loglevel-debug-level: {}", unknown)),
-        };
+        let logfile_level = logging::parse_level(&config.logfile_debug_level).ok_or_else(|| {
+            format!(
+                "Unknown loglevel-debug-level: {}",
+                config.logfile_debug_level
+            )
+        })?;
+        let file_filter = LogFilterHandle::new(
+            LogFilter::parse(
+                config.logfile_filter.as_deref().unwrap_or(""),
+                logfile_level,
+            )
+            .map_err(|e| format!("Invalid logfile-filter: {}", e))?,
+        );
 
+        // Levels are enforced by the `TargetFilter` below, so the file logger accepts everything.
         let file_logger = FileLoggerBuilder::new(&path)
-            .level(logfile_level)
+            .level(Severity::Trace)
             .channel_size(LOG_CHANNEL_SIZE)
             .format(match config.logfile_format.as_deref() {
                 Some("JSON") => Format::Json,
@@ -311,6 +330,12 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             .build()
             .map_err(|e| format!("Unable to build file logger: {}", e))?;
 
+        let file_logger = TargetFilter::new(file_logger, file_filter.clone());
+        self.log_filters = Some(LogFilters {
+            terminal: terminal_filter,
+            file: Some(file_filter),
+        });
+
         let mut log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
 
         info!(
@@ -356,8 +381,10 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             signal_rx: Some(signal_rx),
             signal: Some(signal),
             exit,
+            graceful_shutdown: GracefulShutdown::default(),
             log: self.log.ok_or("Cannot build environment without log")?,
             sse_logging_components: self.sse_logging_components,
+            log_filters: self.log_filters,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
@@ -375,8 +402,11 @@ pub struct Environment<E: EthSpec> {
     signal_tx: Sender<ShutdownReason>,
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
+    /// Operations and hooks to complete before `Self::fire_signal`, shared by all executors.
+    graceful_shutdown: GracefulShutdown,
     log: Logger,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_filters: Option<LogFilters>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
@@ -399,11 +429,13 @@ impl<E: EthSpec> Environment<E> {
                 self.exit.clone(),
                 self.log.clone(),
                 self.signal_tx.clone(),
-            ),
+            )
+            .with_graceful_shutdown(self.graceful_shutdown.clone()),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_filters: self.log_filters.clone(),
         }
     }
 
@@ -415,11 +447,13 @@ impl<E: EthSpec> Environment<E> {
                 self.exit.clone(),
                 self.log.new(o!("service" => service_name)),
                 self.signal_tx.clone(),
-            ),
+            )
+            .with_graceful_shutdown(self.graceful_shutdown.clone()),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_filters: self.log_filters.clone(),
         }
     }
 
@@ -541,6 +575,16 @@ impl<E: EthSpec> Environment<E> {
         }
     }
 
+    /// Stop accepting new requests, wait up to the grace period for in-flight operations (e.g.
+    /// block publications) to complete and then run the shutdown hooks (e.g. persisting caches).
+    ///
+    /// Should be called before `Self::fire_signal`, which tears down the remaining services.
+    pub fn graceful_shutdown(&self) {
+        self.runtime
+            .block_on(self.graceful_shutdown.drain(&self.log));
+        self.graceful_shutdown.run_hooks(&self.log);
+    }
+
     /// Fire exit signal which shuts down all spawned services
     pub fn fire_signal(&mut self) {
         if let Some(signal) = self.signal.take() {
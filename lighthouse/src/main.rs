@@ -121,6 +121,18 @@ fn main() {
                 .default_value("debug")
                 .global(true),
         )
+        .arg(
+            Arg::with_name("logfile-filter")
+                .long("logfile-filter")
+                .value_name("DIRECTIVES")
+                .help(
+                    "Comma-separated per-target verbosity levels used when emitting logs to the \
+                    log file, e.g. `sync=debug,libp2p_gossipsub=warn`. A target matches any \
+                    module path containing it. Targets which are not listed use \
+                    `--logfile-debug-level`.")
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("logfile-format")
                 .long("logfile-format")
@@ -202,6 +214,18 @@ fn main() {
                 .global(true)
                 .default_value("info"),
         )
+        .arg(
+            Arg::with_name("log-filter")
+                .long("log-filter")
+                .value_name("DIRECTIVES")
+                .help(
+                    "Comma-separated per-target verbosity levels used when emitting logs to the \
+                    terminal, e.g. `sync=debug,libp2p_gossipsub=warn,beacon_chain=info`. A target \
+                    matches any module path containing it. Targets which are not listed use \
+                    `--debug-level`.")
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("datadir")
                 .long("datadir")
@@ -332,9 +356,13 @@ fn main() {
                 .help(
                     "A URL of a beacon-API compatible server from which to download the genesis state. \
                     Checkpoint sync server URLs can generally be used with this flag. \
+                    This flag can be supplied multiple times, in which case the URLs are tried in order \
+                    until one serves a state matching the network's genesis state. \
                     If not supplied, a default URL or the --checkpoint-sync-url may be used. \
                     If the genesis state is already included in this binary then this value will be ignored.",
                 )
+                .multiple(true)
+                .number_of_values(1)
                 .takes_value(true)
                 .global(true),
         )
@@ -523,6 +551,8 @@ fn run<E: EthSpec>(
         path: log_path,
         debug_level: String::from(debug_level),
         logfile_debug_level: String::from(logfile_debug_level),
+        log_filter: matches.value_of("log-filter").map(String::from),
+        logfile_filter: matches.value_of("logfile-filter").map(String::from),
         log_format: log_format.map(String::from),
         logfile_format: logfile_format.map(String::from),
         log_color,
@@ -689,6 +719,8 @@ fn run<E: EthSpec>(
     let shutdown_reason = environment.block_until_shutdown_requested()?;
     info!(log, "Shutting down.."; "reason" => ?shutdown_reason);
 
+    // Finish in-flight operations and persist caches before tearing down the network.
+    environment.graceful_shutdown();
     environment.fire_signal();
 
     // Shutdown the environment once all tasks have completed.
@@ -1,13 +1,14 @@
-use beacon_node::ClientConfig as Config;
+use beacon_node::{ClientConfig as Config, ClientGenesis};
 
 use crate::exec::{CommandLineTestExec, CompletedTest};
 use beacon_node::beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR,
-    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
+    DisallowedReOrgOffsets, FailedCondition, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR,
+    DEFAULT_RE_ORG_MARGIN, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
+    DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD, DEFAULT_RE_ORG_THRESHOLD,
 };
-use beacon_processor::BeaconProcessorConfig;
+use beacon_processor::{BeaconProcessorConfig, PriorityAgingConfig};
 use eth1::Eth1Endpoint;
-use lighthouse_network::PeerId;
+use lighthouse_network::{PeerId, TransportPreference};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -149,6 +150,71 @@ fn shuffling_cache_set() {
         .with_config(|config| assert_eq!(config.chain.shuffling_cache_size, 500));
 }
 
+#[test]
+fn epoch_timings_retention_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.epoch_timings_retention_epochs,
+                beacon_node::beacon_chain::epoch_timings::DEFAULT_RETENTION_EPOCHS
+            )
+        });
+}
+
+#[test]
+fn epoch_timings_retention_flag() {
+    CommandLineTest::new()
+        .flag("epoch-timings-retention", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.epoch_timings_retention_epochs, 8));
+}
+
+#[test]
+fn head_vote_accuracy_warn_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.head_vote_accuracy_warn_threshold,
+                beacon_node::beacon_chain::head_vote_accuracy::DEFAULT_WARN_THRESHOLD_PERCENT
+            )
+        });
+}
+
+#[test]
+fn head_vote_accuracy_warn_threshold_flag() {
+    CommandLineTest::new()
+        .flag("head-vote-accuracy-warn-threshold", Some("75"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.head_vote_accuracy_warn_threshold, 75));
+}
+
+#[test]
+fn observed_attestations_max_mb_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.observed_attestations_max_bytes,
+                128 * 1024 * 1024
+            )
+        });
+}
+
+#[test]
+fn observed_attestations_max_mb_flag() {
+    CommandLineTest::new()
+        .flag("observed-attestations-max-mb", Some("16"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.observed_attestations_max_bytes,
+                16 * 1024 * 1024
+            )
+        });
+}
+
 #[test]
 fn fork_choice_before_proposal_timeout_default() {
     CommandLineTest::new()
@@ -188,6 +254,99 @@ fn checkpoint_sync_url_timeout_default() {
         });
 }
 
+#[test]
+fn allow_insecure_genesis_sync_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.allow_insecure_genesis_sync);
+        });
+}
+
+#[test]
+fn allow_insecure_genesis_sync_flag() {
+    CommandLineTest::new()
+        .flag("allow-insecure-genesis-sync", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.allow_insecure_genesis_sync);
+        });
+}
+
+#[test]
+fn preheat_caches_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.preheat_caches);
+        });
+}
+
+#[test]
+fn disable_cache_preheat_flag() {
+    CommandLineTest::new()
+        .flag("disable-cache-preheat", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.preheat_caches);
+        });
+}
+
+#[test]
+fn checkpoint_sync_url_suggestions_mainnet() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.checkpoint_sync_url_suggestions,
+                vec!["https://mainnet.checkpoint.sigp.io".to_string()]
+            );
+        });
+}
+
+#[test]
+fn checkpoint_sync_url_suggestions_holesky() {
+    CommandLineTest::new()
+        .flag("network", Some("holesky"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.checkpoint_sync_url_suggestions.is_empty());
+        });
+}
+
+#[test]
+fn checkpoint_state_block_and_blobs_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let write = |name: &str, bytes: &[u8]| {
+        let path = dir.path().join(name);
+        File::create(&path)
+            .and_then(|mut file| file.write_all(bytes))
+            .expect("Unable to write file");
+        path
+    };
+    let state_path = write("state.ssz", &[1, 2, 3]);
+    let block_path = write("block.ssz", &[4, 5, 6]);
+    let blobs_path = write("blobs.ssz", &[7, 8, 9]);
+
+    CommandLineTest::new()
+        .flag("checkpoint-state", state_path.to_str())
+        .flag("checkpoint-block", block_path.to_str())
+        .flag("checkpoint-blobs", blobs_path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| match &config.genesis {
+            ClientGenesis::WeakSubjSszBytes {
+                anchor_state_bytes,
+                anchor_block_bytes,
+                anchor_blobs_bytes,
+            } => {
+                assert_eq!(anchor_state_bytes, &[1, 2, 3]);
+                assert_eq!(anchor_block_bytes, &[4, 5, 6]);
+                assert_eq!(anchor_blobs_bytes.as_deref(), Some(&[7, 8, 9][..]));
+            }
+            other => panic!("unexpected genesis config: {other:?}"),
+        });
+}
+
 #[test]
 fn prepare_payload_lookahead_default() {
     CommandLineTest::new()
@@ -372,6 +531,33 @@ fn genesis_backfill_with_historic_flag() {
         .with_config(|config| assert_eq!(config.chain.genesis_backfill, true));
 }
 
+#[test]
+fn beacon_node_profile_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.network.proposer_only);
+            assert!(!config.beacon_processor.prioritize_blocks_and_aggregates);
+            assert!(!config.chain.backfill_to_data_availability_boundary);
+            assert!(!config.http_api.disable_expensive_endpoints);
+        });
+}
+
+#[test]
+fn beacon_node_profile_proposer() {
+    CommandLineTest::new()
+        .flag("beacon-node-profile", Some("proposer"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.proposer_only);
+            assert!(!config.network.subscribe_all_subnets);
+            assert_eq!(config.network.target_peers, 15);
+            assert!(config.beacon_processor.prioritize_blocks_and_aggregates);
+            assert!(config.chain.backfill_to_data_availability_boundary);
+            assert!(config.http_api.disable_expensive_endpoints);
+        });
+}
+
 // Tests for Eth1 flags.
 #[test]
 fn dummy_eth1_flag() {
@@ -520,6 +706,26 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn execution_jwt_secondary_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let secondary_path = dir.path().join("jwt-secondary");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag(
+            "execution-jwt-secondary",
+            secondary_path.as_os_str().to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.secondary_secret_file, Some(secondary_path.clone()));
+        });
+}
+#[test]
 fn merge_execution_endpoints_flag() {
     run_merge_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -694,6 +900,39 @@ fn builder_fallback_flags() {
             assert_eq!(config.chain.builder_fallback_disable_checks, true);
         },
     );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-fallback-skips-window"),
+        Some("16"),
+        |config| {
+            assert_eq!(config.chain.builder_fallback_skips_window, Some(16));
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-fallback-missed-proposals"),
+        Some("2"),
+        |config| {
+            assert_eq!(config.chain.builder_fallback_missed_proposals, Some(2));
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-fallback-disable-conditions"),
+        Some("skips,epochs-since-finalization"),
+        |config| {
+            assert_eq!(
+                config.chain.builder_fallback_disabled_conditions,
+                vec![
+                    FailedCondition::Skips,
+                    FailedCondition::EpochsSinceFinalization
+                ]
+            );
+        },
+    );
     run_payload_builder_flag_test_with_config(
         "builder",
         "http://meow.cats",
@@ -726,6 +965,38 @@ fn builder_fallback_flags() {
             );
         },
     );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("require-builder-extra-data-prefix"),
+        Some("0x736f6c6f"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_extra_data_prefix,
+                Some(b"solo".to_vec())
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("require-builder-extra-data-prefix"),
+        Some("solo"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_extra_data_prefix,
+                Some(b"solo".to_vec())
+            );
+        },
+    );
     run_payload_builder_flag_test_with_config(
         "builder",
         "http://meow.cats",
@@ -758,6 +1029,36 @@ fn builder_fallback_flags() {
             );
         },
     );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("strict-fee-recipient"),
+        None,
+        |config| {
+            assert!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .strict_fee_recipient
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert!(
+                !config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .strict_fee_recipient
+            );
+        },
+    );
     run_payload_builder_flag_test_with_config(
         "builder",
         "http://meow.cats",
@@ -966,6 +1267,80 @@ fn network_shutdown_after_sync_disabled_flag() {
         .with_config(|config| assert!(!config.network.shutdown_after_sync));
 }
 #[test]
+fn parent_lookup_max_depth_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.parent_lookup_max_depth, 32));
+}
+#[test]
+fn parent_lookup_max_depth_flag() {
+    CommandLineTest::new()
+        .flag("parent-lookup-max-depth", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.parent_lookup_max_depth, 8));
+}
+#[test]
+#[should_panic]
+fn parent_lookup_max_depth_zero() {
+    CommandLineTest::new()
+        .flag("parent-lookup-max-depth", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
+fn late_own_block_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.late_own_block_threshold,
+                Duration::from_secs(3)
+            )
+        });
+}
+#[test]
+fn late_own_block_threshold_flag() {
+    CommandLineTest::new()
+        .flag("late-own-block-threshold", Some("2500"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.late_own_block_threshold,
+                Duration::from_millis(2500)
+            )
+        });
+}
+#[test]
+fn subnet_unsubscribe_linger_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.subnet_unsubscribe_linger,
+                Duration::from_millis(500)
+            )
+        });
+}
+#[test]
+fn subnet_unsubscribe_linger_flag() {
+    CommandLineTest::new()
+        .flag("subnet-unsubscribe-linger", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.subnet_unsubscribe_linger, Duration::ZERO));
+}
+#[test]
+fn gossip_rejection_buffer_size_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.gossip_rejection_buffer_size, 0));
+}
+#[test]
+fn gossip_rejection_buffer_size_flag() {
+    CommandLineTest::new()
+        .flag("gossip-rejection-buffer-size", Some("64"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.gossip_rejection_buffer_size, 64));
+}
+#[test]
 fn network_listen_address_flag_v4() {
     let addr = "127.0.0.2".parse::<Ipv4Addr>().unwrap();
     CommandLineTest::new()
@@ -1203,6 +1578,37 @@ fn disable_quic_flag() {
         .with_config(|config| assert!(config.network.disable_quic_support));
 }
 #[test]
+fn transport_preference_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.transport_preference,
+                TransportPreference::QuicFirst
+            )
+        });
+}
+#[test]
+fn transport_preference_flag() {
+    CommandLineTest::new()
+        .flag("transport-preference", Some("tcp-only"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.transport_preference,
+                TransportPreference::TcpOnly
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn transport_preference_quic_only_with_quic_disabled() {
+    CommandLineTest::new()
+        .flag("transport-preference", Some("quic-only"))
+        .flag("disable-quic", None)
+        .run_with_zero_port();
+}
+#[test]
 fn disable_peer_scoring_flag() {
     CommandLineTest::new()
         .flag("disable-peer-scoring", None)
@@ -1210,6 +1616,16 @@ fn disable_peer_scoring_flag() {
         .with_config(|config| assert!(config.network.disable_peer_scoring));
 }
 #[test]
+fn purge_peer_scores_flag() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.network.purge_peer_scores));
+    CommandLineTest::new()
+        .flag("purge-peer-scores", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.purge_peer_scores));
+}
+#[test]
 fn disable_upnp_flag() {
     CommandLineTest::new()
         .flag("disable-upnp", None)
@@ -1217,6 +1633,37 @@ fn disable_upnp_flag() {
         .with_config(|config| assert!(!config.network.upnp_enabled));
 }
 #[test]
+fn upnp_renewal_interval_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.upnp_renewal_interval, None);
+            assert_eq!(
+                config.network.upnp_renewal_interval(),
+                Duration::from_secs(1800)
+            );
+        });
+}
+#[test]
+fn upnp_renewal_interval_flag() {
+    CommandLineTest::new()
+        .flag("upnp-renewal-interval", Some("600"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.upnp_renewal_interval(),
+                Duration::from_secs(600)
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn upnp_renewal_interval_exceeds_lease() {
+    CommandLineTest::new()
+        .flag("upnp-renewal-interval", Some("3600"))
+        .run_with_zero_port();
+}
+#[test]
 fn disable_backfill_rate_limiting_flag() {
     CommandLineTest::new()
         .flag("disable-backfill-rate-limiting", None)
@@ -1632,8 +2079,8 @@ fn http_allow_origin_flag() {
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.http_api.allow_origin,
-                Some("http://127.0.0.99".to_string())
+                config.http_api.allow_origins,
+                vec!["http://127.0.0.99".to_string()]
             );
         });
 }
@@ -1643,7 +2090,26 @@ fn http_allow_origin_all_flag() {
         .flag("http", None)
         .flag("http-allow-origin", Some("*"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
+        .with_config(|config| assert_eq!(config.http_api.allow_origins, vec!["*".to_string()]));
+}
+#[test]
+fn http_allow_origin_multiple_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag(
+            "http-allow-origin",
+            Some("http://127.0.0.99, https://*.example.com"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.allow_origins,
+                vec![
+                    "http://127.0.0.99".to_string(),
+                    "https://*.example.com".to_string()
+                ]
+            );
+        });
 }
 #[test]
 fn http_allow_sync_stalled_flag() {
@@ -1654,6 +2120,19 @@ fn http_allow_sync_stalled_flag() {
         .with_config(|config| assert_eq!(config.http_api.allow_sync_stalled, true));
 }
 #[test]
+fn http_enable_fork_choice_recompute_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.http_api.enable_fork_choice_recompute));
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-fork-choice-recompute", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.http_api.enable_fork_choice_recompute));
+}
+#[test]
 fn http_enable_beacon_processor() {
     CommandLineTest::new()
         .flag("http", None)
@@ -1688,6 +2167,28 @@ fn http_tls_flags() {
                 .expect("tls_config was empty.");
             assert_eq!(tls_config.cert, Path::new("tests/tls/cert.pem"));
             assert_eq!(tls_config.key, Path::new("tests/tls/key.rsa"));
+            assert_eq!(tls_config.client_ca, None);
+        });
+}
+#[test]
+fn http_tls_client_ca_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-tls", None)
+        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
+        .flag("http-tls-key", Some("tests/tls/key.rsa"))
+        .flag("http-tls-client-ca", Some("tests/tls/cert.pem"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(
+                tls_config.client_ca.as_deref(),
+                Some(Path::new("tests/tls/cert.pem"))
+            );
         });
 }
 
@@ -1914,6 +2415,19 @@ fn compact_db_flag() {
         .with_config(|config| assert!(config.store.compact_on_init));
 }
 #[test]
+fn compact_db_interval_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.compact_interval_epochs, None));
+}
+#[test]
+fn compact_db_interval_epochs_flag() {
+    CommandLineTest::new()
+        .flag("compact-db-interval-epochs", Some("64"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.compact_interval_epochs, Some(64)));
+}
+#[test]
 fn prune_payloads_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -1966,6 +2480,19 @@ fn blob_prune_margin_epochs_on_startup_ten() {
         .with_config(|config| assert!(config.store.blob_prune_margin_epochs == 10));
 }
 #[test]
+fn blob_prune_interval_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.blob_prune_interval_epochs == 1));
+}
+#[test]
+fn blob_prune_interval_epochs_on_startup_four() {
+    CommandLineTest::new()
+        .flag("blob-prune-interval-epochs", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.blob_prune_interval_epochs == 4));
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -2250,6 +2777,11 @@ fn enable_proposer_re_orgs_default() {
                 config.chain.re_org_threshold,
                 Some(DEFAULT_RE_ORG_THRESHOLD)
             );
+            assert_eq!(config.chain.re_org_margin, DEFAULT_RE_ORG_MARGIN);
+            assert_eq!(
+                config.chain.re_org_participation_threshold,
+                DEFAULT_RE_ORG_PARTICIPATION_THRESHOLD
+            );
             assert_eq!(
                 config.chain.re_org_max_epochs_since_finalization,
                 DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
@@ -2277,6 +2809,30 @@ fn proposer_re_org_threshold() {
         .with_config(|config| assert_eq!(config.chain.re_org_threshold.unwrap().0, 90));
 }
 
+#[test]
+fn proposer_re_org_margin() {
+    CommandLineTest::new()
+        .flag("proposer-reorg-margin", Some("5"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_margin.0, 5));
+}
+
+#[test]
+fn proposer_re_org_min_participation() {
+    CommandLineTest::new()
+        .flag("proposer-reorg-min-participation", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_participation_threshold, 0));
+}
+
+#[test]
+#[should_panic]
+fn proposer_re_org_min_participation_invalid() {
+    CommandLineTest::new()
+        .flag("proposer-reorg-min-participation", Some("101"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn proposer_re_org_max_epochs_since_finalization() {
     CommandLineTest::new()
@@ -2565,6 +3121,10 @@ fn beacon_processor() {
         .flag("beacon-processor-reprocess-queue-len", Some("3"))
         .flag("beacon-processor-attestation-batch-size", Some("4"))
         .flag("beacon-processor-aggregate-batch-size", Some("5"))
+        .flag(
+            "beacon-processor-unknown-block-attestation-queue-len",
+            Some("6"),
+        )
         .flag("disable-backfill-rate-limiting", None)
         .run_with_zero_port()
         .with_config(|config| {
@@ -2576,7 +3136,10 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    enable_backfill_rate_limiting: false,
+                    priority_aging: Some(PriorityAgingConfig::default()),
+                    prioritize_blocks_and_aggregates: false,
+                    max_queued_unknown_block_attestations: 6,
                 }
             )
         });
@@ -2590,6 +3153,17 @@ fn beacon_processor_zero_workers() {
         .run_with_zero_port();
 }
 
+#[test]
+fn shutdown_grace_period() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.shutdown_grace_period, Duration::from_secs(5)));
+    CommandLineTest::new()
+        .flag("shutdown-grace-period", Some("12"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.shutdown_grace_period, Duration::from_secs(12)));
+}
+
 #[test]
 fn http_sse_capacity_multiplier_default() {
     CommandLineTest::new()
@@ -2626,12 +3200,80 @@ fn http_duplicate_block_status_override() {
         });
 }
 
+#[test]
+fn http_max_concurrent_state_requests_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.max_concurrent_state_requests, 64));
+}
+
+#[test]
+fn http_max_concurrent_state_requests_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-max-concurrent-state-requests", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.max_concurrent_state_requests, 8));
+}
+
+#[test]
+fn http_admin_token_path_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.admin_token_path, None));
+}
+
+#[test]
+fn http_admin_token_path_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("admin-token.txt");
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-admin-token-path", path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.admin_token_path, Some(path.clone())));
+}
+
+#[test]
+fn http_disable_admin_endpoints_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.http_api.disable_admin_endpoints));
+}
+
+#[test]
+fn http_disable_admin_endpoints_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-disable-admin-endpoints", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.http_api.disable_admin_endpoints));
+}
+
+#[test]
+fn http_unix_socket_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.unix_socket, None));
+
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("bn.sock");
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-unix-socket", path.to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.unix_socket, Some(path.clone())));
+}
+
 #[test]
 fn genesis_state_url_default() {
     CommandLineTest::new()
         .run_with_zero_port()
         .with_config(|config| {
-            assert_eq!(config.genesis_state_url, None);
+            assert!(config.genesis_state_urls.is_empty());
             assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(180));
         });
 }
@@ -2642,11 +3284,22 @@ fn genesis_state_url_value() {
         .flag("genesis-state-url", Some("http://genesis.com"))
         .flag("genesis-state-url-timeout", Some("42"))
         .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.genesis_state_urls, vec!["http://genesis.com"]);
+            assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(42));
+        });
+}
+
+#[test]
+fn genesis_state_url_multiple_values() {
+    CommandLineTest::new()
+        .flag("genesis-state-url", Some("http://genesis.com"))
+        .flag("genesis-state-url", Some("http://fallback.com"))
+        .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.genesis_state_url.as_deref(),
-                Some("http://genesis.com")
+                config.genesis_state_urls,
+                vec!["http://genesis.com", "http://fallback.com"]
             );
-            assert_eq!(config.genesis_state_url_timeout, Duration::from_secs(42));
         });
 }
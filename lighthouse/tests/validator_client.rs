@@ -1,7 +1,8 @@
-use validator_client::Config;
+use validator_client::{ApiTopic, Config};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
+use eth2::types::BroadcastValidation;
 use std::fs::File;
 use std::io::Write;
 use std::net::IpAddr;
@@ -9,6 +10,7 @@ use std::path::PathBuf;
 use std::process::Command;
 use std::str::FromStr;
 use std::string::ToString;
+use std::time::Duration;
 use tempfile::TempDir;
 use types::Address;
 
@@ -123,6 +125,41 @@ fn init_slashing_protections_flag() {
         .with_config(|config| assert!(config.init_slashing_protection));
 }
 
+#[test]
+fn slashing_protection_busy_timeout_flag() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.slashing_protection_busy_timeout,
+            Duration::from_secs(5)
+        )
+    });
+    CommandLineTest::new()
+        .flag("slashing-protection-busy-timeout-ms", Some("250"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_busy_timeout,
+                Duration::from_millis(250)
+            )
+        });
+}
+
+#[test]
+fn slot_offset_flags() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.attestation_offset, None);
+        assert_eq!(config.aggregate_offset, None);
+    });
+    CommandLineTest::new()
+        .flag("attestation-offset-ms", Some("3500"))
+        .flag("aggregate-offset-ms", Some("7500"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.attestation_offset, Some(Duration::from_millis(3500)));
+            assert_eq!(config.aggregate_offset, Some(Duration::from_millis(7500)));
+        });
+}
+
 #[test]
 fn use_long_timeouts_flag() {
     CommandLineTest::new()
@@ -131,6 +168,39 @@ fn use_long_timeouts_flag() {
         .with_config(|config| assert!(config.use_long_timeouts));
 }
 
+#[test]
+fn compress_ssz_blocks_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.compress_ssz_blocks));
+    CommandLineTest::new()
+        .flag("compress-ssz-blocks", None)
+        .run()
+        .with_config(|config| assert!(config.compress_ssz_blocks));
+}
+
+#[test]
+fn proposer_duties_events_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.proposer_duties_events));
+    CommandLineTest::new()
+        .flag("proposer-duties-events", None)
+        .run()
+        .with_config(|config| assert!(config.proposer_duties_events));
+}
+
+#[test]
+fn distributed_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.distributed));
+    CommandLineTest::new()
+        .flag("distributed", None)
+        .run()
+        .with_config(|config| assert!(config.distributed));
+}
+
 #[test]
 fn beacon_nodes_tls_certs_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
@@ -500,9 +570,10 @@ fn monitoring_endpoint() {
         });
 }
 #[test]
-fn disable_run_on_all_default() {
+fn broadcast_default() {
     CommandLineTest::new().run().with_config(|config| {
-        assert!(!config.disable_run_on_all);
+        assert_eq!(config.broadcast_topics, vec![ApiTopic::Subscriptions]);
+        assert_eq!(config.broadcast_validation, None);
     });
 }
 
@@ -512,7 +583,51 @@ fn disable_run_on_all() {
         .flag("disable-run-on-all", None)
         .run()
         .with_config(|config| {
-            assert!(config.disable_run_on_all);
+            assert!(config.broadcast_topics.is_empty());
+        });
+}
+
+#[test]
+fn broadcast_flag() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("none"))
+        .run()
+        .with_config(|config| {
+            assert!(config.broadcast_topics.is_empty());
+        });
+    CommandLineTest::new()
+        .flag("broadcast", Some("attestations,blocks,sync-committee"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_topics,
+                vec![
+                    ApiTopic::Attestations,
+                    ApiTopic::Blocks,
+                    ApiTopic::SyncCommittee
+                ]
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn broadcast_flag_unknown_topic() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("attestations,deposits"))
+        .run();
+}
+
+#[test]
+fn broadcast_validation_flag() {
+    CommandLineTest::new()
+        .flag("broadcast-validation", Some("consensus_and_equivocation"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_validation,
+                Some(BroadcastValidation::ConsensusAndEquivocation)
+            );
         });
 }
 
@@ -5,10 +5,12 @@ use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::str::FromStr;
+use std::time::Duration;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
     create_validators::CreateConfig,
+    exit_validators::ExitConfig,
     import_validators::ImportConfig,
     move_validators::{MoveConfig, PasswordSource, Validators},
 };
@@ -100,6 +102,12 @@ impl CommandLineTest<MoveConfig> {
     }
 }
 
+impl CommandLineTest<ExitConfig> {
+    fn validators_exit() -> Self {
+        Self::default().flag("exit", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -249,6 +257,7 @@ pub fn validator_move_defaults() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
             };
             assert_eq!(expected, config);
         });
@@ -283,6 +292,7 @@ pub fn validator_move_misc_flags_0() {
                 fee_recipient: Some(Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap()),
                 gas_limit: Some(1337),
                 password_source: PasswordSource::Interactive { stdin_inputs: true },
+                dry_run: false,
             };
             assert_eq!(expected, config);
         });
@@ -312,6 +322,7 @@ pub fn validator_move_misc_flags_1() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
             };
             assert_eq!(expected, config);
         });
@@ -338,7 +349,100 @@ pub fn validator_move_count() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_move_dry_run_stdin_passwords() {
+    CommandLineTest::validators_move()
+        .flag("--src-vc-url", Some("http://localhost:1"))
+        .flag("--src-vc-token", Some("./1.json"))
+        .flag("--dest-vc-url", Some("http://localhost:2"))
+        .flag("--dest-vc-token", Some("./2.json"))
+        .flag("--validators", Some("all"))
+        .flag("--stdin-passwords", None)
+        .flag("--dry-run", None)
+        .assert_success(|config| {
+            let expected = MoveConfig {
+                src_vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                src_vc_token_path: PathBuf::from("./1.json"),
+                dest_vc_url: SensitiveUrl::parse("http://localhost:2").unwrap(),
+                dest_vc_token_path: PathBuf::from("./2.json"),
+                validators: Validators::All,
+                builder_proposals: None,
+                fee_recipient: None,
+                gas_limit: None,
+                password_source: PasswordSource::Interactive { stdin_inputs: true },
+                dry_run: true,
             };
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn validator_exit_defaults() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--validators", Some("all"))
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                bn_url: SensitiveUrl::parse("http://localhost:5052").unwrap(),
+                validators: Validators::All,
+                submission_delay: Duration::from_millis(100),
+                poll_interval: Duration::from_secs(12),
+                no_wait: false,
+                no_confirmation: false,
+                stdin_inputs: cfg!(windows) || false,
+                report_path: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_misc_flags() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-url", Some("http://localhost:1"))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--beacon-node", Some("http://localhost:2"))
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--submission-delay", Some("1000"))
+        .flag("--poll-interval", Some("60"))
+        .flag("--no-wait", None)
+        .flag("--no-confirmation", None)
+        .flag("--report-file", Some("./report.json"))
+        .flag("--stdin-inputs", None)
+        .assert_success(|config| {
+            let expected = ExitConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                bn_url: SensitiveUrl::parse("http://localhost:2").unwrap(),
+                validators: Validators::Specific(vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ]),
+                submission_delay: Duration::from_secs(1),
+                poll_interval: Duration::from_secs(60),
+                no_wait: true,
+                no_confirmation: true,
+                stdin_inputs: true,
+                report_path: Some(PathBuf::from("./report.json")),
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_exit_missing_validators() {
+    CommandLineTest::validators_exit()
+        .flag("--vc-token", Some("./token.json"))
+        .assert_failed();
+}
@@ -297,11 +297,9 @@ impl<T: BeaconChainTypes> SlasherService<T> {
 
         if let ObservationOutcome::New(slashing) = outcome {
             network_sender
-                .send(NetworkMessage::Publish {
-                    messages: vec![PubsubMessage::AttesterSlashing(Box::new(
-                        slashing.into_inner(),
-                    ))],
-                })
+                .send(NetworkMessage::publish(vec![
+                    PubsubMessage::AttesterSlashing(Box::new(slashing.into_inner())),
+                ]))
                 .map_err(|e| format!("network error: {:?}", e))?;
         }
         Ok(())
@@ -318,11 +316,9 @@ impl<T: BeaconChainTypes> SlasherService<T> {
 
         if let ObservationOutcome::New(slashing) = outcome {
             network_sender
-                .send(NetworkMessage::Publish {
-                    messages: vec![PubsubMessage::ProposerSlashing(Box::new(
-                        slashing.into_inner(),
-                    ))],
-                })
+                .send(NetworkMessage::publish(vec![
+                    PubsubMessage::ProposerSlashing(Box::new(slashing.into_inner())),
+                ]))
                 .map_err(|e| format!("network error: {:?}", e))?;
         }
         Ok(())
@@ -10,7 +10,7 @@ use ssz_derive::{Decode, Encode};
 use std::path::{Path, PathBuf};
 use tree_hash_derive::TreeHash;
 use types::typenum::*;
-use types::{BitList, BitVector, FixedVector, ForkName, VariableList};
+use types::{stable_container, BitList, BitVector, FixedVector, ForkName, VariableList};
 
 #[derive(Debug, Clone, Deserialize)]
 struct Metadata {
@@ -109,6 +109,21 @@ macro_rules! type_dispatch {
             _ => Err(Error::FailedToParseTest(format!("unsupported: {}", $value))),
         }
     };
+    ($function:ident,
+     ($($arg:expr),*),
+     $base_ty:tt,
+     <$($param_ty:ty),*>,
+     [ $value:expr => test_stable_container ] $($rest:tt)*) => {
+        match $value {
+            "SingleFieldTestStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* SingleFieldTestStableStruct>, $($rest)*),
+            "SmallTestStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* SmallTestStableStruct>, $($rest)*),
+            "FixedTestStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* FixedTestStableStruct>, $($rest)*),
+            "VarTestStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* VarTestStableStruct>, $($rest)*),
+            "ComplexTestStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* ComplexTestStableStruct>, $($rest)*),
+            "BitsStableStruct" => type_dispatch!($function, ($($arg),*), $base_ty, <$($param_ty,)* BitsStableStruct>, $($rest)*),
+            _ => Err(Error::FailedToParseTest(format!("unsupported: {}", $value))),
+        }
+    };
     // No base type: apply type params to function
     ($function:ident, ($($arg:expr),*), _, <$($param_ty:ty),*>,) => {
         $function::<$($param_ty),*>($($arg),*)
@@ -188,6 +203,17 @@ impl Case for SszGeneric {
                     [type_name => test_container]
                 )?;
             }
+            "stable_containers" => {
+                let type_name = parts[0];
+
+                type_dispatch!(
+                    ssz_generic_test,
+                    (&self.path),
+                    _,
+                    <>,
+                    [type_name => test_stable_container]
+                )?;
+            }
             _ => panic!("unsupported handler: {}", self.handler_name),
         }
         Ok(())
@@ -278,6 +304,85 @@ struct BitsStruct {
     E: BitVector<U8>,
 }
 
+// EIP-7495 stable containers for SSZ generic tests
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct SingleFieldTestStableStruct[4] {
+        A: u8,
+    }
+}
+
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct SmallTestStableStruct[4] {
+        A: u16,
+        B: u16,
+    }
+}
+
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct FixedTestStableStruct[4] {
+        A: u8,
+        B: u64,
+        C: u32,
+    }
+}
+
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct VarTestStableStruct[4] {
+        A: u16,
+        B: VariableList<u16, U1024>,
+        C: u8,
+    }
+}
+
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct ComplexTestStableStruct[8] {
+        A: u16,
+        B: VariableList<u16, U128>,
+        C: u8,
+        #[serde(deserialize_with = "option_byte_list_from_hex_str")]
+        D: VariableList<u8, U256>,
+        E: VarTestStableStruct,
+        F: FixedVector<FixedTestStableStruct, U4>,
+        G: FixedVector<VarTestStableStruct, U2>,
+    }
+}
+
+stable_container! {
+    #[derive(Debug, Clone, PartialEq, Deserialize)]
+    #[serde(default)]
+    struct BitsStableStruct[8] {
+        A: BitList<U5>,
+        B: BitVector<U2>,
+        C: BitVector<U1>,
+        D: BitList<U6>,
+        E: BitVector<U8>,
+    }
+}
+
+fn option_byte_list_from_hex_str<'de, D, N: Unsigned>(
+    deserializer: D,
+) -> Result<Option<VariableList<u8, N>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = serde::de::Deserialize::deserialize(deserializer)?;
+    s.map(|s| {
+        let decoded: Vec<u8> = hex::decode(&s.as_str()[2..]).map_err(D::Error::custom)?;
+        VariableList::new(decoded).map_err(|e| D::Error::custom(format!("{:?}", e)))
+    })
+    .transpose()
+}
+
 fn byte_list_from_hex_str<'de, D, N: Unsigned>(
     deserializer: D,
 ) -> Result<VariableList<u8, N>, D::Error>
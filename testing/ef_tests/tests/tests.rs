@@ -405,6 +405,7 @@ fn ssz_generic() {
     SszGenericHandler::<Boolean>::default().run();
     SszGenericHandler::<Uints>::default().run();
     SszGenericHandler::<Containers>::default().run();
+    SszGenericHandler::<StableContainers>::default().run();
 }
 
 #[test]
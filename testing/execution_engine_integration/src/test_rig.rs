@@ -332,6 +332,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
                 builder_params,
                 TEST_FORK,
                 &self.spec,
+                true,
             )
             .await
             .unwrap()
@@ -478,6 +479,7 @@ impl<E: GenericExecutionEngine> TestRig<E> {
                 builder_params,
                 TEST_FORK,
                 &self.spec,
+                true,
             )
             .await
             .unwrap()
@@ -43,6 +43,47 @@ pub async fn verify_first_finalization<E: EthSpec>(
     Ok(())
 }
 
+/// Checks that the average delay from the start of each slot until its block is received via
+/// gossip is within `min_delay` and `max_delay`.
+///
+/// The minimum is the latency injected between nodes, which ensures that link shaping is in
+/// effect, whilst the maximum ensures that blocks still arrive in time to be attested to.
+pub async fn verify_block_arrival_delay<E: EthSpec>(
+    network: LocalNetwork<E>,
+    slot_duration: Duration,
+    min_delay: Duration,
+    max_delay: Duration,
+) -> Result<(), String> {
+    epoch_delay(Epoch::new(4), slot_duration, E::slots_per_epoch()).await;
+
+    let delays = network.block_arrival_delays();
+    if delays.is_empty() {
+        return Err("No blocks were received via gossip".to_string());
+    }
+    let average = delays.iter().sum::<Duration>() / delays.len() as u32;
+    println!(
+        "Average block arrival delay: {}ms over {} blocks",
+        average.as_millis(),
+        delays.len()
+    );
+
+    if average < min_delay {
+        Err(format!(
+            "Average block arrival delay of {}ms is less than the link latency of {}ms",
+            average.as_millis(),
+            min_delay.as_millis()
+        ))
+    } else if average > max_delay {
+        Err(format!(
+            "Average block arrival delay of {}ms exceeds the maximum of {}ms",
+            average.as_millis(),
+            max_delay.as_millis()
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 /// Delays for `epochs`, plus half a slot extra.
 pub async fn epoch_delay(epochs: Epoch, slot_duration: Duration, slots_per_epoch: u64) {
     let duration = slot_duration * (epochs.as_u64() * slots_per_epoch) as u32 + slot_duration / 2;
@@ -52,6 +52,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .long("continue_after_checks")
                         .takes_value(false)
                         .help("Continue after checks (default false)"))
+                    .args(&link_shaping_args())
         )
         .subcommand(
             SubCommand::with_name("no-eth1-sim")
@@ -86,6 +87,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .long("continue_after_checks")
                         .takes_value(false)
                         .help("Continue after checks (default false)"))
+                    .args(&link_shaping_args())
         )
         .subcommand(
             SubCommand::with_name("syncing-sim")
@@ -123,3 +125,37 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 ),
         )
 }
+
+/// Arguments configuring the links between nodes, shared by the simulations which check block
+/// propagation.
+fn link_shaping_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("link-profile")
+            .long("link-profile")
+            .takes_value(true)
+            .default_value("ideal")
+            .possible_values(&["ideal", "wan", "lossy"])
+            .help("The latency, jitter and bandwidth of the links between nodes."),
+        Arg::with_name("link-latency-ms")
+            .long("link-latency-ms")
+            .takes_value(true)
+            .help("Overrides the one-way latency of the links between nodes, in milliseconds."),
+        Arg::with_name("link-latency-matrix")
+            .long("link-latency-matrix")
+            .takes_value(true)
+            .help(
+                "One-way latencies in milliseconds between each pair of nodes, overriding \
+                the latency of the link profile. Rows are separated by ';' and columns by ','. \
+                The latency from the i'th to the j'th node is in row i and column j, where the \
+                boot node is first, followed by the beacon nodes and then the proposer nodes, \
+                e.g. '0,200;50,0'.",
+            ),
+        Arg::with_name("max-block-arrival-delay-ms")
+            .long("max-block-arrival-delay-ms")
+            .takes_value(true)
+            .help(
+                "The maximum average delay from the start of a slot until its block is \
+                received via gossip, in milliseconds. Defaults to a third of the slot.",
+            ),
+    ]
+}
@@ -1,3 +1,4 @@
+use crate::link_shaping::{link_shaping_from_matches, max_block_arrival_delay};
 use crate::local_network::{EXECUTION_PORT, TERMINAL_BLOCK, TERMINAL_DIFFICULTY};
 use crate::{checks, LocalNetwork};
 use clap::ArgMatches;
@@ -7,6 +8,7 @@ use eth1_test_rig::AnvilEth1Instance;
 use crate::retry::with_retry;
 use execution_layer::http::deposit_methods::Eth1Id;
 use futures::prelude::*;
+use lighthouse_network::link_shaping::LinkShaping;
 use node_test_rig::environment::RuntimeContext;
 use node_test_rig::{
     environment::{EnvironmentBuilder, LoggerConfig},
@@ -16,6 +18,7 @@ use rayon::prelude::*;
 use sensitive_url::SensitiveUrl;
 use std::cmp::max;
 use std::net::Ipv4Addr;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::sleep;
 use types::{Epoch, EthSpec, MinimalEthSpec};
@@ -44,6 +47,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
     println!(" validators_per_node:{}", validators_per_node);
     println!(" post merge simulation:{}", post_merge_sim);
     println!(" continue_after_checks:{}", continue_after_checks);
+    let link_shaping = link_shaping_from_matches(matches)?;
 
     // Generate the directories and keystores required for the validator clients.
     let validator_files = (0..node_count)
@@ -66,6 +70,8 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
             path: None,
             debug_level: String::from("debug"),
             logfile_debug_level: String::from("debug"),
+            log_filter: None,
+            logfile_filter: None,
             log_format: None,
             logfile_format: None,
             log_color: false,
@@ -103,6 +109,10 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
 
     let seconds_per_slot = spec.seconds_per_slot;
     let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+    let max_block_arrival_delay = max_block_arrival_delay(matches, slot_duration)?;
+    let min_block_arrival_delay = link_shaping
+        .as_ref()
+        .map_or(Duration::ZERO, |link_shaping| link_shaping.min_latency());
     let initial_validator_count = spec.min_genesis_active_validator_count as usize;
     let deposit_amount = env.eth2_config.spec.max_effective_balance;
 
@@ -122,6 +132,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
                     node_count,
                     proposer_nodes,
                     post_merge_sim,
+                    link_shaping: link_shaping.clone(),
                 },
                 context.clone(),
             ))
@@ -204,6 +215,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
             fork,
             sync_aggregate,
             transition,
+            block_arrival,
         ) = futures::join!(
             // Check that the chain finalizes at the first given opportunity.
             checks::verify_first_finalization(network.clone(), slot_duration),
@@ -256,6 +268,13 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
                 Epoch::new(TERMINAL_BLOCK / MinimalEthSpec::slots_per_epoch()),
                 slot_duration,
                 post_merge_sim
+            ),
+            // Check that blocks arrive with the delay injected between nodes.
+            checks::verify_block_arrival_delay(
+                network.clone(),
+                slot_duration,
+                min_block_arrival_delay,
+                max_block_arrival_delay,
             )
         );
 
@@ -266,6 +285,7 @@ pub fn run_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         fork?;
         sync_aggregate?;
         transition?;
+        block_arrival?;
 
         // The `final_future` either completes immediately or never completes, depending on the value
         // of `continue_after_checks`.
@@ -304,6 +324,7 @@ struct LocalNetworkParams {
     node_count: usize,
     proposer_nodes: usize,
     post_merge_sim: bool,
+    link_shaping: Option<Arc<LinkShaping>>,
 }
 
 async fn create_local_network<E: EthSpec>(
@@ -314,6 +335,7 @@ async fn create_local_network<E: EthSpec>(
         node_count,
         proposer_nodes,
         post_merge_sim,
+        link_shaping,
     }: LocalNetworkParams,
     context: RuntimeContext<E>,
 ) -> Result<(LocalNetwork<E>, ClientConfig), String> {
@@ -364,6 +386,7 @@ async fn create_local_network<E: EthSpec>(
     beacon_config.network.target_peers = node_count + proposer_nodes - 1;
 
     beacon_config.network.enr_address = (Some(Ipv4Addr::LOCALHOST), None);
+    beacon_config.network.link_shaping = link_shaping;
 
     if post_merge_sim {
         let el_config = execution_layer::Config {
@@ -0,0 +1,76 @@
+//! Configures artificial latency and bandwidth limits on the links between simulated nodes.
+
+use clap::ArgMatches;
+use lighthouse_network::link_shaping::{LinkProfile, LinkShaping};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Returns the link shaping configured by the CLI flags, or `None` if links should be ideal.
+pub fn link_shaping_from_matches(matches: &ArgMatches) -> Result<Option<Arc<LinkShaping>>, String> {
+    let mut profile = value_t!(matches, "link-profile", LinkProfile).map_err(|e| e.to_string())?;
+    if let Some(latency) = matches.value_of("link-latency-ms") {
+        let latency = latency
+            .parse()
+            .map_err(|e| format!("Invalid link-latency-ms {}: {:?}", latency, e))?;
+        profile.latency = Duration::from_millis(latency);
+    }
+    let latency_matrix = matches
+        .value_of("link-latency-matrix")
+        .map(parse_latency_matrix)
+        .transpose()?;
+
+    if profile == LinkProfile::ideal() && latency_matrix.is_none() {
+        return Ok(None);
+    }
+
+    println!(" link_profile:{:?}", profile);
+    if let Some(matrix) = &latency_matrix {
+        println!(" link_latency_matrix:{:?}", matrix);
+    }
+
+    Ok(Some(Arc::new(LinkShaping::new(profile, latency_matrix))))
+}
+
+/// Parses a matrix of one-way latencies in milliseconds, with rows separated by `;` and columns
+/// by `,`. The latency from the `i`th to the `j`th node is in row `i` and column `j`.
+fn parse_latency_matrix(s: &str) -> Result<Vec<Vec<Duration>>, String> {
+    let matrix = s
+        .split(';')
+        .map(|row| {
+            row.split(',')
+                .map(|latency| {
+                    latency
+                        .trim()
+                        .parse()
+                        .map(Duration::from_millis)
+                        .map_err(|e| format!("Invalid latency {} in matrix: {:?}", latency, e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if matrix.iter().any(|row| row.len() != matrix.len()) {
+        return Err(format!(
+            "Latency matrix must be square, but has {} rows",
+            matrix.len()
+        ));
+    }
+    Ok(matrix)
+}
+
+/// Returns the maximum average block arrival delay, which defaults to a third of the slot so that
+/// blocks arrive before attestations are produced.
+pub fn max_block_arrival_delay(
+    matches: &ArgMatches,
+    slot_duration: Duration,
+) -> Result<Duration, String> {
+    matches
+        .value_of("max-block-arrival-delay-ms")
+        .map(|delay| {
+            delay
+                .parse()
+                .map(Duration::from_millis)
+                .map_err(|e| format!("Invalid max-block-arrival-delay-ms {}: {:?}", delay, e))
+        })
+        .unwrap_or(Ok(slot_duration / 3))
+}
@@ -1,3 +1,4 @@
+use lighthouse_network::{link_shaping::LinkShaping, EnrExt};
 use node_test_rig::{
     environment::RuntimeContext,
     eth2::{types::StateId, BeaconNodeHttpClient},
@@ -93,9 +94,11 @@ impl<E: EthSpec> LocalNetwork<E> {
             vec![]
         };
 
+        let link_shaping = beacon_config.network.link_shaping.clone();
         let beacon_node =
             LocalBeaconNode::production(context.service_context("boot_node".into()), beacon_config)
                 .await?;
+        add_to_link_shaping(link_shaping.as_deref(), &beacon_node);
         Ok(Self {
             inner: Arc::new(Inner {
                 context,
@@ -189,11 +192,13 @@ impl<E: EthSpec> LocalNetwork<E> {
         // We create the beacon node without holding the lock, so that the lock isn't held
         // across the await. This is only correct if this function never runs in parallel
         // with itself (which at the time of writing, it does not).
+        let link_shaping = beacon_config.network.link_shaping.clone();
         let beacon_node = LocalBeaconNode::production(
             self.context.service_context(format!("node_{}", count)),
             beacon_config,
         )
         .await?;
+        add_to_link_shaping(link_shaping.as_deref(), &beacon_node);
         if is_proposer {
             self_1.proposer_nodes.write().push(beacon_node);
         } else {
@@ -282,6 +287,33 @@ impl<E: EthSpec> LocalNetwork<E> {
             .collect()
     }
 
+    /// Returns the delay from the start of the slot until the arrival of each block received via
+    /// gossip by each beacon node, for the blocks in their block times caches.
+    pub fn block_arrival_delays(&self) -> Vec<Duration> {
+        let beacon_nodes = self.beacon_nodes.read();
+        let proposer_nodes = self.proposer_nodes.read();
+
+        beacon_nodes
+            .iter()
+            .chain(proposer_nodes.iter())
+            .filter_map(|beacon_node| beacon_node.client.beacon_chain())
+            .flat_map(|chain| {
+                let block_times_cache = chain.block_times_cache.read();
+                block_times_cache
+                    .cache
+                    .values()
+                    .filter_map(|block_times| {
+                        let slot_start = Duration::from_secs(
+                            chain.genesis_time
+                                + block_times.slot.as_u64() * chain.spec.seconds_per_slot,
+                        );
+                        block_times.timestamps.observed?.checked_sub(slot_start)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Return current epoch of bootnode.
     pub async fn bootnode_epoch(&self) -> Result<Epoch, String> {
         let nodes = self.remote_nodes().expect("Failed to get remote nodes");
@@ -318,3 +350,14 @@ impl<E: EthSpec> LocalNetwork<E> {
         genesis_time - now
     }
 }
+
+/// Adds `beacon_node` to `link_shaping`, so that its index in the latency matrix is the order in
+/// which it was added to the network.
+fn add_to_link_shaping<E: EthSpec>(
+    link_shaping: Option<&LinkShaping>,
+    beacon_node: &LocalBeaconNode<E>,
+) {
+    if let (Some(link_shaping), Some(enr)) = (link_shaping, beacon_node.client.enr()) {
+        link_shaping.add_node(enr.peer_id());
+    }
+}
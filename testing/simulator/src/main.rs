@@ -19,6 +19,7 @@ extern crate clap;
 mod checks;
 mod cli;
 mod eth1_sim;
+mod link_shaping;
 mod local_network;
 mod no_eth1_sim;
 mod retry;
@@ -1,3 +1,4 @@
+use crate::link_shaping::{link_shaping_from_matches, max_block_arrival_delay};
 use crate::{checks, LocalNetwork};
 use clap::ArgMatches;
 use futures::prelude::*;
@@ -24,6 +25,7 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
     println!(" nodes:{}", node_count);
     println!(" validators_per_node:{}", validators_per_node);
     println!(" continue_after_checks:{}", continue_after_checks);
+    let link_shaping = link_shaping_from_matches(matches)?;
 
     // Generate the directories and keystores required for the validator clients.
     let validator_files = (0..node_count)
@@ -46,6 +48,8 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
             path: None,
             debug_level: String::from("debug"),
             logfile_debug_level: String::from("debug"),
+            log_filter: None,
+            logfile_filter: None,
             log_format: None,
             logfile_format: None,
             log_color: false,
@@ -80,6 +84,10 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         + genesis_delay;
 
     let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+    let max_block_arrival_delay = max_block_arrival_delay(matches, slot_duration)?;
+    let min_block_arrival_delay = link_shaping
+        .as_ref()
+        .map_or(Duration::ZERO, |link_shaping| link_shaping.min_latency());
 
     let context = env.core_context();
 
@@ -93,6 +101,7 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
     beacon_config.sync_eth1_chain = true;
 
     beacon_config.network.enr_address = (Some(Ipv4Addr::LOCALHOST), None);
+    beacon_config.network.link_shaping = link_shaping;
 
     let main_future = async {
         let network = LocalNetwork::new(context.clone(), beacon_config.clone()).await?;
@@ -129,7 +138,7 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
         println!("Duration to genesis: {}", duration_to_genesis.as_secs());
         sleep(duration_to_genesis).await;
 
-        let (finalization, block_prod) = futures::join!(
+        let (finalization, block_prod, block_arrival) = futures::join!(
             // Check that the chain finalizes at the first given opportunity.
             checks::verify_first_finalization(network.clone(), slot_duration),
             // Check that a block is produced at every slot.
@@ -138,9 +147,17 @@ pub fn run_no_eth1_sim(matches: &ArgMatches) -> Result<(), String> {
                 Epoch::new(4).start_slot(MainnetEthSpec::slots_per_epoch()),
                 slot_duration,
             ),
+            // Check that blocks arrive with the delay injected between nodes.
+            checks::verify_block_arrival_delay(
+                network.clone(),
+                slot_duration,
+                min_block_arrival_delay,
+                max_block_arrival_delay,
+            ),
         );
         finalization?;
         block_prod?;
+        block_arrival?;
 
         // The `final_future` either completes immediately or never completes, depending on the value
         // of `continue_after_checks`.
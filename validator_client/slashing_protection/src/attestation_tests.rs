@@ -387,3 +387,162 @@ fn invalid_prev_surrounds_new_multiple() {
     }
     .run()
 }
+
+fn batch_test_db(num_validators: usize) -> (tempfile::TempDir, SlashingDatabase) {
+    let dir = tempfile::tempdir().unwrap();
+    let slashing_db =
+        SlashingDatabase::create(&dir.path().join("slashing_protection.sqlite")).unwrap();
+    for i in 0..num_validators {
+        slashing_db.register_validator(pubkey(i)).unwrap();
+    }
+    (dir, slashing_db)
+}
+
+#[test]
+fn batch_mixed_outcomes() {
+    let (_dir, slashing_db) = batch_test_db(5);
+
+    // Prior history: validator 1 already signed the batch attestation, validator 2 signed a
+    // conflicting attestation with the same target and validator 3 signed a surrounding vote.
+    let att = attestation_data_builder(2, 3);
+    let conflicting = attestation_data_builder(1, 3);
+    let surrounding = attestation_data_builder(1, 4);
+    for (i, data) in [(1, &att), (2, &conflicting), (3, &surrounding)] {
+        slashing_db
+            .check_and_insert_attestation(&pubkey(i), data, DEFAULT_DOMAIN)
+            .unwrap();
+    }
+
+    let batch = (0..6)
+        .map(|i| (pubkey(i), &att, DEFAULT_DOMAIN))
+        .collect::<Vec<_>>();
+    let outcomes = slashing_db.check_and_insert_attestations(&batch).unwrap();
+
+    assert_eq!(
+        outcomes,
+        vec![
+            Ok(Safe::Valid),
+            Ok(Safe::SameData),
+            Err(NotSafe::InvalidAttestation {
+                reason: InvalidAttestation::DoubleVote(signed_att(&conflicting)),
+                attempt: signed_att(&att),
+            }),
+            Err(NotSafe::InvalidAttestation {
+                reason: InvalidAttestation::PrevSurroundsNew {
+                    prev: signed_att(&surrounding)
+                },
+                attempt: signed_att(&att),
+            }),
+            Ok(Safe::Valid),
+            Err(NotSafe::UnregisteredValidator(pubkey(5))),
+        ]
+    );
+
+    // The safe entries were recorded, and the unsafe entries did not overwrite any history.
+    for i in [0, 1, 4] {
+        assert_eq!(
+            slashing_db.check_and_insert_attestation(&pubkey(i), &att, DEFAULT_DOMAIN),
+            Ok(Safe::SameData)
+        );
+    }
+    assert_eq!(
+        slashing_db.check_and_insert_attestation(&pubkey(2), &conflicting, DEFAULT_DOMAIN),
+        Ok(Safe::SameData)
+    );
+    assert_eq!(
+        slashing_db.check_and_insert_attestation(&pubkey(3), &surrounding, DEFAULT_DOMAIN),
+        Ok(Safe::SameData)
+    );
+}
+
+#[test]
+fn batch_entries_checked_against_each_other() {
+    let (_dir, slashing_db) = batch_test_db(1);
+
+    let first = attestation_data_builder(2, 3);
+    let mut double = attestation_data_builder(2, 3);
+    double.beacon_block_root = Hash256::repeat_byte(1);
+    let surrounding = attestation_data_builder(1, 4);
+
+    let batch = [&first, &first, &double, &surrounding]
+        .into_iter()
+        .map(|data| (pubkey(0), data, DEFAULT_DOMAIN))
+        .collect::<Vec<_>>();
+    let outcomes = slashing_db.check_and_insert_attestations(&batch).unwrap();
+
+    assert_eq!(
+        outcomes,
+        vec![
+            Ok(Safe::Valid),
+            Ok(Safe::SameData),
+            Err(NotSafe::InvalidAttestation {
+                reason: InvalidAttestation::DoubleVote(signed_att(&first)),
+                attempt: signed_att(&double),
+            }),
+            Err(NotSafe::InvalidAttestation {
+                reason: InvalidAttestation::NewSurroundsPrev {
+                    prev: signed_att(&first)
+                },
+                attempt: signed_att(&surrounding),
+            }),
+        ]
+    );
+}
+
+#[test]
+fn batch_empty() {
+    let (_dir, slashing_db) = batch_test_db(1);
+    assert_eq!(slashing_db.check_and_insert_attestations(&[]), Ok(vec![]));
+}
+
+#[test]
+fn refusal_context_double_vote() {
+    let (_dir, slashing_db) = batch_test_db(1);
+    let first = attestation_data_builder(1, 2);
+    let mut second = attestation_data_builder(1, 2);
+    second.beacon_block_root = Hash256::repeat_byte(1);
+
+    slashing_db
+        .check_and_insert_attestation(&pubkey(0), &first, DEFAULT_DOMAIN)
+        .unwrap();
+    let err = slashing_db
+        .check_and_insert_attestation(&pubkey(0), &second, DEFAULT_DOMAIN)
+        .unwrap_err();
+
+    assert_eq!(err.reason(), NotSafeReason::DoubleVote);
+    let NotSafe::InvalidAttestation { reason, attempt } = err else {
+        panic!("unexpected error: {:?}", err);
+    };
+    assert_eq!(reason.recorded(), Some(&signed_att(&first)));
+    assert_eq!(attempt, signed_att(&second));
+    assert_eq!(
+        (attempt.source_epoch, attempt.target_epoch),
+        (Epoch::new(1), Epoch::new(2))
+    );
+}
+
+#[test]
+fn refusal_context_surround() {
+    let (_dir, slashing_db) = batch_test_db(1);
+    let surrounded = attestation_data_builder(2, 3);
+    let surrounding = attestation_data_builder(1, 4);
+
+    slashing_db
+        .check_and_insert_attestation(&pubkey(0), &surrounded, DEFAULT_DOMAIN)
+        .unwrap();
+    let err = slashing_db
+        .check_and_insert_attestation(&pubkey(0), &surrounding, DEFAULT_DOMAIN)
+        .unwrap_err();
+
+    assert_eq!(err.reason(), NotSafeReason::SurroundingVote);
+    assert_eq!(err.reason().as_str(), "surrounding_vote");
+    let NotSafe::InvalidAttestation { reason, attempt } = err else {
+        panic!("unexpected error: {:?}", err);
+    };
+    let recorded = reason.recorded().expect("surrounded vote is recorded");
+    assert_eq!(
+        (recorded.source_epoch, recorded.target_epoch),
+        (Epoch::new(2), Epoch::new(3))
+    );
+    assert_eq!(attempt, signed_att(&surrounding));
+}
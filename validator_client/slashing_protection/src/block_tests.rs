@@ -122,3 +122,36 @@ fn invalid_unregistered_validator() {
     }
     .run()
 }
+
+#[test]
+fn refusal_context_stale_slot() {
+    let dir = tempfile::tempdir().unwrap();
+    let slashing_db =
+        SlashingDatabase::create(&dir.path().join("slashing_protection.sqlite")).unwrap();
+    let pk = pubkey(DEFAULT_VALIDATOR_INDEX);
+    slashing_db.register_validator(pk).unwrap();
+
+    slashing_db
+        .check_and_insert_block_proposal(&pk, &block(10), DEFAULT_DOMAIN)
+        .unwrap();
+
+    let stale = block(9);
+    let err = slashing_db
+        .check_and_insert_block_proposal(&pk, &stale, DEFAULT_DOMAIN)
+        .unwrap_err();
+    assert_eq!(err.reason(), NotSafeReason::LowerBound);
+    assert_eq!(
+        err,
+        NotSafe::InvalidBlock {
+            reason: InvalidBlock::SlotViolatesLowerBound {
+                block_slot: Slot::new(9),
+                bound_slot: Slot::new(10),
+            },
+            attempt: SignedBlock::from_header(&stale, DEFAULT_DOMAIN),
+        }
+    );
+
+    let summary = slashing_db.get_validator_summary(&pk).unwrap();
+    assert_eq!(summary.min_block_slot, Some(Slot::new(10)));
+    assert_eq!(summary.min_attestation_target, None);
+}
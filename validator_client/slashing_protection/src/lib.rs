@@ -13,8 +13,8 @@ pub mod test_utils;
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::{
-    InterchangeError, InterchangeImportOutcome, SlashingDatabase,
-    SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+    InterchangeError, InterchangeImportOutcome, SlashingDatabase, SlashingDatabaseConfig,
+    ValidatorSummary, DEFAULT_BUSY_TIMEOUT, SUPPORTED_INTERCHANGE_FORMAT_VERSION,
 };
 use rusqlite::Error as SQLError;
 use std::io::{Error as IOError, ErrorKind};
@@ -31,13 +31,96 @@ pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
 pub enum NotSafe {
     UnregisteredValidator(PublicKeyBytes),
     DisabledValidator(PublicKeyBytes),
-    InvalidBlock(InvalidBlock),
-    InvalidAttestation(InvalidAttestation),
+    /// The block is slashable or invalid for `reason`, which includes the conflicting block from
+    /// the database where there is one. The block that was refused is `attempt`.
+    InvalidBlock {
+        reason: InvalidBlock,
+        attempt: SignedBlock,
+    },
+    /// The attestation is slashable or invalid for `reason`, which includes the conflicting
+    /// attestation from the database where there is one. The attestation that was refused is
+    /// `attempt`.
+    InvalidAttestation {
+        reason: InvalidAttestation,
+        attempt: SignedAttestation,
+    },
     PermissionsError,
     IOError(ErrorKind),
     SQLError(String),
     SQLPoolError(String),
+    /// The database remained locked for longer than the busy timeout.
+    DatabaseBusy,
     ConsistencyError,
+    /// The database was created for a different chain.
+    GenesisValidatorsRootMismatch {
+        database: Hash256,
+        provided: Hash256,
+    },
+}
+
+/// A coarse reason for a `NotSafe` refusal, with a bounded set of values for use in metrics.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum NotSafeReason {
+    DoubleVote,
+    SurroundingVote,
+    SurroundedVote,
+    SourceExceedsTarget,
+    DoubleBlockProposal,
+    /// The slot or epoch is at or below the low watermark of the validator's signing history.
+    LowerBound,
+    UnregisteredValidator,
+    DisabledValidator,
+    GenesisValidatorsRootMismatch,
+    DatabaseError,
+}
+
+impl NotSafeReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotSafeReason::DoubleVote => "double_vote",
+            NotSafeReason::SurroundingVote => "surrounding_vote",
+            NotSafeReason::SurroundedVote => "surrounded_vote",
+            NotSafeReason::SourceExceedsTarget => "source_exceeds_target",
+            NotSafeReason::DoubleBlockProposal => "double_block_proposal",
+            NotSafeReason::LowerBound => "lower_bound",
+            NotSafeReason::UnregisteredValidator => "unregistered_validator",
+            NotSafeReason::DisabledValidator => "disabled_validator",
+            NotSafeReason::GenesisValidatorsRootMismatch => "genesis_validators_root_mismatch",
+            NotSafeReason::DatabaseError => "database_error",
+        }
+    }
+}
+
+impl NotSafe {
+    pub fn reason(&self) -> NotSafeReason {
+        match self {
+            NotSafe::UnregisteredValidator(_) => NotSafeReason::UnregisteredValidator,
+            NotSafe::DisabledValidator(_) => NotSafeReason::DisabledValidator,
+            NotSafe::InvalidBlock { reason, .. } => match reason {
+                InvalidBlock::DoubleBlockProposal(_) => NotSafeReason::DoubleBlockProposal,
+                InvalidBlock::SlotViolatesLowerBound { .. } => NotSafeReason::LowerBound,
+            },
+            NotSafe::InvalidAttestation { reason, .. } => match reason {
+                InvalidAttestation::DoubleVote(_) => NotSafeReason::DoubleVote,
+                InvalidAttestation::NewSurroundsPrev { .. } => NotSafeReason::SurroundingVote,
+                InvalidAttestation::PrevSurroundsNew { .. } => NotSafeReason::SurroundedVote,
+                InvalidAttestation::SourceExceedsTarget => NotSafeReason::SourceExceedsTarget,
+                InvalidAttestation::SourceLessThanLowerBound { .. }
+                | InvalidAttestation::TargetLessThanOrEqLowerBound { .. } => {
+                    NotSafeReason::LowerBound
+                }
+            },
+            NotSafe::GenesisValidatorsRootMismatch { .. } => {
+                NotSafeReason::GenesisValidatorsRootMismatch
+            }
+            NotSafe::PermissionsError
+            | NotSafe::IOError(_)
+            | NotSafe::SQLError(_)
+            | NotSafe::SQLPoolError(_)
+            | NotSafe::DatabaseBusy
+            | NotSafe::ConsistencyError => NotSafeReason::DatabaseError,
+        }
+    }
 }
 
 /// The attestation or block is safe to sign, and will not cause the signer to be slashed.
@@ -109,9 +192,27 @@ impl From<IOError> for NotSafe {
     }
 }
 
+/// Returns `true` if `error` was caused by another connection holding a lock on the database.
+fn is_busy_error(error: &SQLError) -> bool {
+    matches!(
+        error,
+        SQLError::SqliteFailure(
+            rusqlite::ffi::Error {
+                code: rusqlite::ErrorCode::DatabaseBusy | rusqlite::ErrorCode::DatabaseLocked,
+                ..
+            },
+            _
+        )
+    )
+}
+
 impl From<SQLError> for NotSafe {
     fn from(error: SQLError) -> NotSafe {
-        NotSafe::SQLError(error.to_string())
+        if is_busy_error(&error) {
+            NotSafe::DatabaseBusy
+        } else {
+            NotSafe::SQLError(error.to_string())
+        }
     }
 }
 
@@ -3,10 +3,13 @@
 
 use crate::attestation_tests::attestation_data_builder;
 use crate::block_tests::block;
+use crate::interchange::{Interchange, InterchangeData, InterchangeMetadata};
 use crate::test_utils::*;
 use crate::*;
 use rayon::prelude::*;
+use std::sync::Arc;
 use tempfile::tempdir;
+use types::{Epoch, Hash256};
 
 #[test]
 fn block_same_slot() {
@@ -80,3 +83,157 @@ fn attestation_surround_fest() {
     let num_successes = results.iter().filter(|res| res.is_ok()).count();
     assert_eq!(num_successes, 1);
 }
+
+#[test]
+fn concurrent_signing_from_tokio_tasks() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = Arc::new(SlashingDatabase::create(&slashing_db_file).unwrap());
+
+    let num_validators = 32;
+    let num_epochs = 4;
+    let pubkeys = (0..num_validators).map(pubkey).collect::<Vec<_>>();
+    slashing_db.register_validators(pubkeys.iter()).unwrap();
+
+    // An interchange for a distinct set of validators, imported while signing is in progress.
+    let imported_pubkeys = (num_validators..num_validators + 4)
+        .map(pubkey)
+        .collect::<Vec<_>>();
+    let interchange = Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root: DEFAULT_GENESIS_VALIDATORS_ROOT,
+        },
+        data: imported_pubkeys
+            .iter()
+            .map(|pubkey| InterchangeData {
+                pubkey: *pubkey,
+                signed_blocks: vec![],
+                signed_attestations: vec![interchange::SignedAttestation {
+                    source_epoch: Epoch::new(0),
+                    target_epoch: Epoch::new(num_epochs),
+                    signing_root: None,
+                }],
+            })
+            .collect(),
+    };
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(8)
+        .build()
+        .unwrap();
+
+    let (signing_results, read_results, import_result) = runtime.block_on(async {
+        // Two conflicting attestations for every validator and target, each signed from its
+        // own task.
+        let mut signing_handles = vec![];
+        for pk in &pubkeys {
+            for target in 1..=num_epochs {
+                for block_root in [1, 2] {
+                    let slashing_db = slashing_db.clone();
+                    let pk = *pk;
+                    signing_handles.push(tokio::spawn(async move {
+                        let mut att = attestation_data_builder(target - 1, target);
+                        att.beacon_block_root = Hash256::repeat_byte(block_root);
+                        let result =
+                            slashing_db.check_and_insert_attestation(&pk, &att, DEFAULT_DOMAIN);
+                        (pk, target, result)
+                    }));
+                }
+            }
+        }
+
+        // Read-only queries interleaved with the signing.
+        let read_handles = (0..num_validators)
+            .map(|_| {
+                let slashing_db = slashing_db.clone();
+                tokio::spawn(async move { slashing_db.num_validator_rows() })
+            })
+            .collect::<Vec<_>>();
+
+        let import_handle = {
+            let slashing_db = slashing_db.clone();
+            tokio::spawn(async move {
+                slashing_db.import_interchange_info(interchange, DEFAULT_GENESIS_VALIDATORS_ROOT)
+            })
+        };
+
+        let mut signing_results = vec![];
+        for handle in signing_handles {
+            signing_results.push(handle.await.unwrap());
+        }
+        let mut read_results = vec![];
+        for handle in read_handles {
+            read_results.push(handle.await.unwrap());
+        }
+        (signing_results, read_results, import_handle.await.unwrap())
+    });
+
+    // Exactly one of each pair of conflicting attestations was signed, and the other was
+    // rejected as a double vote. Any lock error would show up here as a different outcome.
+    for pk in &pubkeys {
+        for target in 1..=num_epochs {
+            let outcomes = signing_results
+                .iter()
+                .filter(|(result_pk, result_target, _)| result_pk == pk && *result_target == target)
+                .map(|(_, _, result)| result)
+                .collect::<Vec<_>>();
+            assert_eq!(outcomes.len(), 2);
+            assert_eq!(
+                outcomes
+                    .iter()
+                    .filter(|result| ***result == Ok(Safe::Valid))
+                    .count(),
+                1,
+                "{:?}",
+                outcomes
+            );
+            assert_eq!(
+                outcomes
+                    .iter()
+                    .filter(|result| matches!(
+                        result,
+                        Err(NotSafe::InvalidAttestation {
+                            reason: InvalidAttestation::DoubleVote(_),
+                            ..
+                        })
+                    ))
+                    .count(),
+                1,
+                "{:?}",
+                outcomes
+            );
+        }
+    }
+
+    for result in read_results {
+        let num_rows = result.unwrap();
+        assert!(num_rows == num_validators as u32 || num_rows == num_validators as u32 + 4);
+    }
+
+    import_result.unwrap();
+    assert_eq!(
+        slashing_db.num_validator_rows().unwrap(),
+        num_validators as u32 + 4
+    );
+    // The imported history is enforced.
+    for pk in &imported_pubkeys {
+        assert_eq!(
+            slashing_db.check_and_insert_attestation(
+                pk,
+                &attestation_data_builder(0, num_epochs - 1),
+                DEFAULT_DOMAIN
+            ),
+            Err(NotSafe::InvalidAttestation {
+                reason: InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: Epoch::new(num_epochs - 1),
+                    bound_epoch: Epoch::new(num_epochs),
+                },
+                attempt: SignedAttestation::from_attestation(
+                    &attestation_data_builder(0, num_epochs - 1),
+                    DEFAULT_DOMAIN
+                ),
+            })
+        );
+    }
+}
@@ -34,6 +34,20 @@ pub enum InvalidAttestation {
     },
 }
 
+impl InvalidAttestation {
+    /// The attestation from the database that the refused attestation conflicts with, if any.
+    pub fn recorded(&self) -> Option<&SignedAttestation> {
+        match self {
+            InvalidAttestation::DoubleVote(prev)
+            | InvalidAttestation::NewSurroundsPrev { prev }
+            | InvalidAttestation::PrevSurroundsNew { prev } => Some(prev),
+            InvalidAttestation::SourceExceedsTarget
+            | InvalidAttestation::SourceLessThanLowerBound { .. }
+            | InvalidAttestation::TargetLessThanOrEqLowerBound { .. } => None,
+        }
+    }
+}
+
 impl SignedAttestation {
     pub fn new(source_epoch: Epoch, target_epoch: Epoch, signing_root: SigningRoot) -> Self {
         Self {
@@ -15,6 +15,16 @@ pub enum InvalidBlock {
     SlotViolatesLowerBound { block_slot: Slot, bound_slot: Slot },
 }
 
+impl InvalidBlock {
+    /// The block from the database that the refused block conflicts with, if any.
+    pub fn recorded(&self) -> Option<&SignedBlock> {
+        match self {
+            InvalidBlock::DoubleBlockProposal(prev) => Some(prev),
+            InvalidBlock::SlotViolatesLowerBound { .. } => None,
+        }
+    }
+}
+
 impl SignedBlock {
     pub fn new(slot: Slot, signing_root: SigningRoot) -> Self {
         Self { slot, signing_root }
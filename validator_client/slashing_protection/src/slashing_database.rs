@@ -9,7 +9,7 @@ use filesystem::restrict_file_permissions;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
 
@@ -20,6 +20,11 @@ type Pool = r2d2::Pool<SqliteConnectionManager>;
 /// This is perhaps overkill in the presence of exclusive transactions, but has
 /// the added bonus of preventing other processes from trying to use our slashing database.
 pub const POOL_SIZE: u32 = 1;
+/// Default time for which SQLite will wait on a locked database before returning `SQLITE_BUSY`.
+#[cfg(not(test))]
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(test)]
+pub const DEFAULT_BUSY_TIMEOUT: Duration = Duration::from_millis(100);
 #[cfg(not(test))]
 pub const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
 #[cfg(test)]
@@ -31,6 +36,24 @@ pub const SUPPORTED_INTERCHANGE_FORMAT_VERSION: u64 = 5;
 /// Column ID of the `validators.enabled` column.
 pub const VALIDATORS_ENABLED_CID: i64 = 2;
 
+/// Key of the genesis validators root in the `metadata` table.
+const GENESIS_VALIDATORS_ROOT_KEY: &str = "genesis_validators_root";
+
+/// Connection settings for the slashing protection database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlashingDatabaseConfig {
+    /// Time for which a connection will wait on a locked database before returning an error.
+    pub busy_timeout: Duration,
+}
+
+impl Default for SlashingDatabaseConfig {
+    fn default() -> Self {
+        Self {
+            busy_timeout: DEFAULT_BUSY_TIMEOUT,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SlashingDatabase {
     conn_pool: Pool,
@@ -39,10 +62,18 @@ pub struct SlashingDatabase {
 impl SlashingDatabase {
     /// Open an existing database at the given `path`, or create one if none exists.
     pub fn open_or_create(path: &Path) -> Result<Self, NotSafe> {
+        Self::open_or_create_with_config(path, SlashingDatabaseConfig::default())
+    }
+
+    /// As for `open_or_create`, but using the connection settings from `config`.
+    pub fn open_or_create_with_config(
+        path: &Path,
+        config: SlashingDatabaseConfig,
+    ) -> Result<Self, NotSafe> {
         if path.exists() {
-            Self::open(path)
+            Self::open_with_config(path, config)
         } else {
-            Self::create(path)
+            Self::create_with_config(path, config)
         }
     }
 
@@ -50,6 +81,14 @@ impl SlashingDatabase {
     ///
     /// Error if a database (or any file) already exists at `path`.
     pub fn create(path: &Path) -> Result<Self, NotSafe> {
+        Self::create_with_config(path, SlashingDatabaseConfig::default())
+    }
+
+    /// As for `create`, but using the connection settings from `config`.
+    pub fn create_with_config(
+        path: &Path,
+        config: SlashingDatabaseConfig,
+    ) -> Result<Self, NotSafe> {
         let _file = File::options()
             .write(true)
             .read(true)
@@ -57,8 +96,8 @@ impl SlashingDatabase {
             .open(path)?;
 
         restrict_file_permissions(path).map_err(|_| NotSafe::PermissionsError)?;
-        let conn_pool = Self::open_conn_pool(path)?;
-        let mut conn = conn_pool.get()?;
+        let db = Self::connect(path, config)?;
+        let mut conn = db.conn_pool.get()?;
 
         conn.execute(
             "CREATE TABLE validators (
@@ -97,19 +136,30 @@ impl SlashingDatabase {
         Self::apply_schema_migrations(&txn)?;
         txn.commit()?;
 
-        Ok(Self { conn_pool })
+        Ok(db)
     }
 
     /// Open an existing `SlashingDatabase` from disk.
     ///
     /// This will automatically check for and apply the latest schema migrations.
     pub fn open(path: &Path) -> Result<Self, NotSafe> {
-        let conn_pool = Self::open_conn_pool(path)?;
-        let db = Self { conn_pool };
+        Self::open_with_config(path, SlashingDatabaseConfig::default())
+    }
+
+    /// As for `open`, but using the connection settings from `config`.
+    pub fn open_with_config(path: &Path, config: SlashingDatabaseConfig) -> Result<Self, NotSafe> {
+        let db = Self::connect(path, config)?;
         db.with_transaction(Self::apply_schema_migrations)?;
         Ok(db)
     }
 
+    /// Open the connection pool for the database at `path`.
+    fn connect(path: &Path, config: SlashingDatabaseConfig) -> Result<Self, NotSafe> {
+        restrict_wal_file_permissions(path)?;
+        let conn_pool = Self::open_conn_pool(path, config)?;
+        Ok(Self { conn_pool })
+    }
+
     fn apply_schema_migrations(txn: &Transaction) -> Result<(), NotSafe> {
         // Add the `enabled` column to the `validators` table if it does not already exist.
         let enabled_col_exists = txn
@@ -139,14 +189,24 @@ impl SlashingDatabase {
             )?;
         }
 
+        // Add the `metadata` table, which records the genesis validators root of the chain.
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS metadata (
+                key TEXT PRIMARY KEY,
+                value BLOB NOT NULL
+            )",
+            params![],
+        )?;
+
         Ok(())
     }
 
     /// Open a new connection pool with all of the necessary settings and tweaks.
-    fn open_conn_pool(path: &Path) -> Result<Pool, NotSafe> {
+    fn open_conn_pool(path: &Path, config: SlashingDatabaseConfig) -> Result<Pool, NotSafe> {
+        let busy_timeout = config.busy_timeout;
         let manager = SqliteConnectionManager::file(path)
             .with_flags(rusqlite::OpenFlags::SQLITE_OPEN_READ_WRITE)
-            .with_init(Self::apply_pragmas);
+            .with_init(move |conn| Self::apply_pragmas(conn, busy_timeout));
         let conn_pool = Pool::builder()
             .max_size(POOL_SIZE)
             .connection_timeout(CONNECTION_TIMEOUT)
@@ -161,9 +221,17 @@ impl SlashingDatabase {
     /// to serialise all DB access (to prevent slashable data being checked and signed in parallel).
     /// The exclusive locking mode also has the benefit of applying to other processes, so multiple
     /// Lighthouse processes trying to access the same database will also be blocked.
-    fn apply_pragmas(conn: &mut rusqlite::Connection) -> Result<(), rusqlite::Error> {
+    ///
+    /// Exclusive locking is enabled before WAL mode, so that SQLite keeps the WAL index in heap
+    /// memory and never creates a shared-memory file.
+    fn apply_pragmas(
+        conn: &mut rusqlite::Connection,
+        busy_timeout: Duration,
+    ) -> Result<(), rusqlite::Error> {
         conn.pragma_update(None, "foreign_keys", true)?;
         conn.pragma_update(None, "locking_mode", "EXCLUSIVE")?;
+        conn.busy_timeout(busy_timeout)?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
         Ok(())
     }
 
@@ -330,6 +398,10 @@ impl SlashingDatabase {
         signing_root: SigningRoot,
     ) -> Result<Safe, NotSafe> {
         let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
+        let invalid = |reason| NotSafe::InvalidBlock {
+            reason,
+            attempt: SignedBlock::new(slot, signing_root),
+        };
 
         let existing_block = txn
             .prepare(
@@ -346,9 +418,7 @@ impl SlashingDatabase {
                 return Ok(Safe::SameData);
             } else {
                 // Same epoch but not the same hash -> it's a DoubleBlockProposal
-                return Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
-                    existing_block,
-                )));
+                return Err(invalid(InvalidBlock::DoubleBlockProposal(existing_block)));
             }
         }
 
@@ -358,12 +428,10 @@ impl SlashingDatabase {
 
         if let Some(min_slot) = min_slot {
             if slot <= min_slot {
-                return Err(NotSafe::InvalidBlock(
-                    InvalidBlock::SlotViolatesLowerBound {
-                        block_slot: slot,
-                        bound_slot: min_slot,
-                    },
-                ));
+                return Err(invalid(InvalidBlock::SlotViolatesLowerBound {
+                    block_slot: slot,
+                    bound_slot: min_slot,
+                }));
             }
         }
 
@@ -379,12 +447,15 @@ impl SlashingDatabase {
         att_target_epoch: Epoch,
         att_signing_root: SigningRoot,
     ) -> Result<Safe, NotSafe> {
+        let invalid = |reason| NotSafe::InvalidAttestation {
+            reason,
+            attempt: SignedAttestation::new(att_source_epoch, att_target_epoch, att_signing_root),
+        };
+
         // Although it's not required to avoid slashing, we disallow attestations
         // which are obviously invalid by virtue of their source epoch exceeding their target.
         if att_source_epoch > att_target_epoch {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::SourceExceedsTarget,
-            ));
+            return Err(invalid(InvalidAttestation::SourceExceedsTarget));
         }
 
         let validator_id = self.get_validator_id_in_txn(txn, validator_pubkey)?;
@@ -410,7 +481,7 @@ impl SlashingDatabase {
                 return Ok(Safe::SameData);
             // Otherwise if the hashes are different, this is a double vote.
             } else {
-                return Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                return Err(invalid(InvalidAttestation::DoubleVote(
                     existing_attestation,
                 )));
             }
@@ -433,9 +504,7 @@ impl SlashingDatabase {
             .optional()?;
 
         if let Some(prev) = surrounding_attestation {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::PrevSurroundsNew { prev },
-            ));
+            return Err(invalid(InvalidAttestation::PrevSurroundsNew { prev }));
         }
 
         // Check that no previous vote is surrounded by `attestation`.
@@ -455,9 +524,7 @@ impl SlashingDatabase {
             .optional()?;
 
         if let Some(prev) = surrounded_attestation {
-            return Err(NotSafe::InvalidAttestation(
-                InvalidAttestation::NewSurroundsPrev { prev },
-            ));
+            return Err(invalid(InvalidAttestation::NewSurroundsPrev { prev }));
         }
 
         // Check lower bounds: ensure that source is greater than or equal to min source,
@@ -469,12 +536,10 @@ impl SlashingDatabase {
 
         if let Some(min_source) = min_source {
             if att_source_epoch < min_source {
-                return Err(NotSafe::InvalidAttestation(
-                    InvalidAttestation::SourceLessThanLowerBound {
-                        source_epoch: att_source_epoch,
-                        bound_epoch: min_source,
-                    },
-                ));
+                return Err(invalid(InvalidAttestation::SourceLessThanLowerBound {
+                    source_epoch: att_source_epoch,
+                    bound_epoch: min_source,
+                }));
             }
         }
 
@@ -484,12 +549,10 @@ impl SlashingDatabase {
 
         if let Some(min_target) = min_target {
             if att_target_epoch <= min_target {
-                return Err(NotSafe::InvalidAttestation(
-                    InvalidAttestation::TargetLessThanOrEqLowerBound {
-                        target_epoch: att_target_epoch,
-                        bound_epoch: min_target,
-                    },
-                ));
+                return Err(invalid(InvalidAttestation::TargetLessThanOrEqLowerBound {
+                    target_epoch: att_target_epoch,
+                    bound_epoch: min_target,
+                }));
             }
         }
 
@@ -620,6 +683,39 @@ impl SlashingDatabase {
         )
     }
 
+    /// Check and insert a batch of attestations in a single transaction.
+    ///
+    /// Each `(validator_pubkey, attestation, domain)` entry is checked and recorded as if by
+    /// `check_and_insert_attestation`, and the outcome for each entry is returned in the same
+    /// order. A slashable or otherwise unsafe entry does not prevent the remaining entries from
+    /// being recorded. Entries are processed in order, so a later entry is checked against the
+    /// attestations recorded by earlier entries of the same batch.
+    ///
+    /// All safe attestations are committed to the database before this function returns. If the
+    /// transaction cannot be opened or committed an error is returned and none of the entries are
+    /// safe to sign.
+    pub fn check_and_insert_attestations(
+        &self,
+        attestations: &[(PublicKeyBytes, &AttestationData, Hash256)],
+    ) -> Result<Vec<Result<Safe, NotSafe>>, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        let outcomes = attestations
+            .iter()
+            .map(|(validator_pubkey, attestation, domain)| {
+                self.check_and_insert_attestation_signing_root_txn(
+                    validator_pubkey,
+                    attestation.source.epoch,
+                    attestation.target.epoch,
+                    attestation.signing_root(*domain).into(),
+                    &txn,
+                )
+            })
+            .collect();
+        txn.commit()?;
+        Ok(outcomes)
+    }
+
     /// As for `check_and_insert_attestation` but without requiring the whole `AttestationData`.
     pub fn check_and_insert_attestation_signing_root(
         &self,
@@ -691,10 +787,10 @@ impl SlashingDatabase {
             });
         }
 
-        // Create a single transaction for the entire batch, which will only be committed if
-        // all records are imported successfully.
+        // Create a single exclusive transaction for the entire batch, which will only be
+        // committed if all records are imported successfully.
         let mut conn = self.conn_pool.get()?;
-        let txn = conn.transaction()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
 
         let mut import_outcomes = vec![];
         let mut commit = true;
@@ -1012,6 +1108,50 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Get the genesis validators root of the chain this database is used for, if it has been
+    /// recorded.
+    pub fn genesis_validators_root(&self) -> Result<Option<Hash256>, NotSafe> {
+        self.with_transaction(Self::genesis_validators_root_in_txn)
+    }
+
+    fn genesis_validators_root_in_txn(txn: &Transaction) -> Result<Option<Hash256>, NotSafe> {
+        txn.query_row(
+            "SELECT value FROM metadata WHERE key = ?1",
+            params![GENESIS_VALIDATORS_ROOT_KEY],
+            |row| signing_root_from_row(0, row),
+        )
+        .optional()
+        .map(|root| root.map(SigningRoot::to_hash256_raw))
+        .map_err(Into::into)
+    }
+
+    /// Check that the database is used for the chain with `genesis_validators_root`, recording it
+    /// if the database has not been used for any chain yet.
+    pub fn check_or_set_genesis_validators_root(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<(), NotSafe> {
+        self.with_transaction(|txn| match Self::genesis_validators_root_in_txn(txn)? {
+            Some(database) if database != genesis_validators_root => {
+                Err(NotSafe::GenesisValidatorsRootMismatch {
+                    database,
+                    provided: genesis_validators_root,
+                })
+            }
+            Some(_) => Ok(()),
+            None => {
+                txn.execute(
+                    "INSERT INTO metadata (key, value) VALUES (?1, ?2)",
+                    params![
+                        GENESIS_VALIDATORS_ROOT_KEY,
+                        genesis_validators_root.as_bytes()
+                    ],
+                )?;
+                Ok(())
+            }
+        })
+    }
+
     pub fn num_validator_rows(&self) -> Result<u32, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
@@ -1028,6 +1168,26 @@ impl SlashingDatabase {
         txn: &Transaction,
     ) -> Result<ValidatorSummary, NotSafe> {
         let validator_id = self.get_validator_id_in_txn(txn, public_key)?;
+        Self::validator_summary_for_id(validator_id, txn)
+    }
+
+    /// Get a summary of a validator's slashing protection data, including disabled validators.
+    ///
+    /// The minimums are the low watermarks below which nothing will be signed.
+    pub fn get_validator_summary(
+        &self,
+        public_key: &PublicKeyBytes,
+    ) -> Result<ValidatorSummary, NotSafe> {
+        self.with_transaction(|txn| {
+            let validator_id = self.get_validator_id_ignoring_status(txn, public_key)?;
+            Self::validator_summary_for_id(validator_id, txn)
+        })
+    }
+
+    fn validator_summary_for_id(
+        validator_id: i64,
+        txn: &Transaction,
+    ) -> Result<ValidatorSummary, NotSafe> {
         let (min_block_slot, max_block_slot) = txn
             .prepare(
                 "SELECT MIN(slot), MAX(slot)
@@ -1104,6 +1264,29 @@ impl ValidatorSummary {
     }
 }
 
+/// Restrict the permissions of the write-ahead log files stored alongside the database at `path`.
+///
+/// The `-wal` file is created if it does not exist (without truncating an existing log), so that
+/// SQLite never creates it with looser permissions. A `-shm` file is only restricted if one has
+/// been left behind, as it is not used in exclusive locking mode.
+fn restrict_wal_file_permissions(path: &Path) -> Result<(), NotSafe> {
+    let wal_path = path_with_suffix(path, "-wal");
+    File::options().write(true).create(true).open(&wal_path)?;
+    restrict_file_permissions(&wal_path).map_err(|_| NotSafe::PermissionsError)?;
+
+    let shm_path = path_with_suffix(path, "-shm");
+    if shm_path.exists() {
+        restrict_file_permissions(&shm_path).map_err(|_| NotSafe::PermissionsError)?;
+    }
+    Ok(())
+}
+
+fn path_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
 /// Take the maximum of `opt_x` and `y`, returning `y` if `opt_x` is `None`.
 fn max_or<T: Copy + Ord>(opt_x: Option<T>, y: T) -> T {
     opt_x.map_or(y, |x| std::cmp::max(x, y))
@@ -1162,7 +1345,11 @@ impl From<NotSafe> for InterchangeError {
 
 impl From<rusqlite::Error> for InterchangeError {
     fn from(error: rusqlite::Error) -> Self {
-        Self::SQLError(error.to_string())
+        if crate::is_busy_error(&error) {
+            Self::NotSafe(NotSafe::DatabaseBusy)
+        } else {
+            Self::SQLError(error.to_string())
+        }
     }
 }
 
@@ -1200,6 +1387,44 @@ mod tests {
         SlashingDatabase::open(&file).unwrap_err();
     }
 
+    // The exclusive lock is released once every handle to the database has been dropped.
+    #[test]
+    fn reopen_after_drop() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db1 = SlashingDatabase::create(&file).unwrap();
+        let db2 = db1.clone();
+        drop(db1);
+        SlashingDatabase::open(&file).unwrap_err();
+        drop(db2);
+        SlashingDatabase::open(&file).unwrap();
+    }
+
+    // The genesis validators root is recorded on first use and persists across restarts.
+    #[test]
+    fn genesis_validators_root_recorded() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let root = Hash256::repeat_byte(1);
+        let other_root = Hash256::repeat_byte(2);
+
+        let db = SlashingDatabase::create(&file).unwrap();
+        assert_eq!(db.genesis_validators_root().unwrap(), None);
+        db.check_or_set_genesis_validators_root(root).unwrap();
+        db.check_or_set_genesis_validators_root(root).unwrap();
+        drop(db);
+
+        let db = SlashingDatabase::open(&file).unwrap();
+        assert_eq!(db.genesis_validators_root().unwrap(), Some(root));
+        assert_eq!(
+            db.check_or_set_genesis_validators_root(other_root),
+            Err(NotSafe::GenesisValidatorsRootMismatch {
+                database: root,
+                provided: other_root,
+            })
+        );
+    }
+
     // Attempting to create the same database twice should error.
     #[test]
     fn double_create_error() {
@@ -1215,10 +1440,14 @@ mod tests {
     fn connection_settings_applied() {
         let dir = tempdir().unwrap();
         let file = dir.path().join("db.sqlite");
+        let config = SlashingDatabaseConfig {
+            busy_timeout: Duration::from_millis(1234),
+        };
 
         let check = |db: &SlashingDatabase| {
             assert_eq!(db.conn_pool.max_size(), POOL_SIZE);
             assert_eq!(db.conn_pool.connection_timeout(), CONNECTION_TIMEOUT);
+
             let conn = db.conn_pool.get().unwrap();
             assert!(conn
                 .pragma_query_value(None, "foreign_keys", |row| { row.get::<_, bool>(0) })
@@ -1229,15 +1458,44 @@ mod tests {
                     .to_uppercase(),
                 "EXCLUSIVE"
             );
+            assert_eq!(
+                conn.pragma_query_value(None, "journal_mode", |row| { row.get::<_, String>(0) })
+                    .unwrap()
+                    .to_lowercase(),
+                "wal"
+            );
+            assert_eq!(
+                conn.pragma_query_value(None, "busy_timeout", |row| { row.get::<_, u64>(0) })
+                    .unwrap(),
+                config.busy_timeout.as_millis() as u64
+            );
         };
 
-        let db1 = SlashingDatabase::create(&file).unwrap();
+        let db1 = SlashingDatabase::create_with_config(&file, config).unwrap();
         check(&db1);
         drop(db1);
-        let db2 = SlashingDatabase::open(&file).unwrap();
+        let db2 = SlashingDatabase::open_with_config(&file, config).unwrap();
         check(&db2);
     }
 
+    // The write-ahead log is only accessible to the owner of the database, and no shared-memory
+    // file is created while the database is locked exclusively.
+    #[cfg(unix)]
+    #[test]
+    fn wal_file_permissions_restricted() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.register_validator(PublicKeyBytes::empty()).unwrap();
+
+        let wal_path = path_with_suffix(&file, "-wal");
+        let mode = std::fs::metadata(wal_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(!path_with_suffix(&file, "-shm").exists());
+    }
+
     #[test]
     fn test_transaction_failure() {
         let dir = tempdir().unwrap();
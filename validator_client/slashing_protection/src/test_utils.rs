@@ -44,16 +44,28 @@ impl<T> Test<T> {
         self
     }
 
-    pub fn expect_invalid_att(self, error: InvalidAttestation) -> Self {
-        self.expect_result(Err(NotSafe::InvalidAttestation(error)))
+    pub fn expect_same_data(self) -> Self {
+        self.expect_result(Ok(Safe::SameData))
     }
+}
 
-    pub fn expect_invalid_block(self, error: InvalidBlock) -> Self {
-        self.expect_result(Err(NotSafe::InvalidBlock(error)))
+impl Test<AttestationData> {
+    /// Expect the attestation to be refused for `reason`.
+    ///
+    /// Must be called after `with_domain`, as the domain is part of the refused attempt.
+    pub fn expect_invalid_att(self, reason: InvalidAttestation) -> Self {
+        let attempt = SignedAttestation::from_attestation(&self.data, self.domain);
+        self.expect_result(Err(NotSafe::InvalidAttestation { reason, attempt }))
     }
+}
 
-    pub fn expect_same_data(self) -> Self {
-        self.expect_result(Ok(Safe::SameData))
+impl Test<BeaconBlockHeader> {
+    /// Expect the block to be refused for `reason`.
+    ///
+    /// Must be called after `with_domain`, as the domain is part of the refused attempt.
+    pub fn expect_invalid_block(self, reason: InvalidBlock) -> Self {
+        let attempt = SignedBlock::from_header(&self.data, self.domain);
+        self.expect_result(Err(NotSafe::InvalidBlock { reason, attempt }))
     }
 }
 
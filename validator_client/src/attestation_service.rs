@@ -1,7 +1,8 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
     http_metrics::metrics,
+    slot_timing::SlotTiming,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
@@ -24,6 +25,7 @@ pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     duties_service: Option<Arc<DutiesService<T, E>>>,
     validator_store: Option<Arc<ValidatorStore<T, E>>>,
     slot_clock: Option<T>,
+    slot_timing: Option<Arc<SlotTiming>>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
 }
@@ -34,6 +36,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             duties_service: None,
             validator_store: None,
             slot_clock: None,
+            slot_timing: None,
             beacon_nodes: None,
             context: None,
         }
@@ -54,6 +57,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn slot_timing(mut self, slot_timing: Arc<SlotTiming>) -> Self {
+        self.slot_timing = Some(slot_timing);
+        self
+    }
+
     pub fn beacon_nodes(mut self, beacon_nodes: Arc<BeaconNodeFallback<T, E>>) -> Self {
         self.beacon_nodes = Some(beacon_nodes);
         self
@@ -76,6 +84,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 slot_clock: self
                     .slot_clock
                     .ok_or("Cannot build AttestationService without slot_clock")?,
+                slot_timing: self
+                    .slot_timing
+                    .ok_or("Cannot build AttestationService without slot_timing")?,
                 beacon_nodes: self
                     .beacon_nodes
                     .ok_or("Cannot build AttestationService without beacon_nodes")?,
@@ -92,11 +103,13 @@ pub struct Inner<T, E: EthSpec> {
     duties_service: Arc<DutiesService<T, E>>,
     validator_store: Arc<ValidatorStore<T, E>>,
     slot_clock: T,
+    slot_timing: Arc<SlotTiming>,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators at the attestation offset of each
+/// slot (1/3rd of the way through by default).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -127,26 +140,29 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         let log = self.context.log().clone();
 
         let slot_duration = Duration::from_secs(spec.seconds_per_slot);
-        let duration_to_next_slot = self
-            .slot_clock
-            .duration_to_next_slot()
+        let duration_to_next_attestation = self
+            .slot_timing
+            .duration_to_next_attestation_production(&self.slot_clock)
             .ok_or("Unable to determine duration to next slot")?;
 
         info!(
             log,
             "Attestation production service started";
-            "next_update_millis" => duration_to_next_slot.as_millis()
+            "next_update_millis" => duration_to_next_attestation.as_millis()
         );
 
         let executor = self.context.executor.clone();
 
         let interval_fut = async move {
             loop {
-                if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
-                    sleep(duration_to_next_slot + slot_duration / 3).await;
+                if let Some(duration_to_next_attestation) = self
+                    .slot_timing
+                    .duration_to_next_attestation_production(&self.slot_clock)
+                {
+                    sleep(duration_to_next_attestation).await;
                     let log = self.context.log();
 
-                    if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
+                    if let Err(e) = self.spawn_attestation_tasks() {
                         crit!(
                             log,
                             "Failed to spawn attestation tasks";
@@ -173,19 +189,16 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
     /// For each each required attestation, spawn a new task that downloads, signs and uploads the
     /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
+    fn spawn_attestation_tasks(&self) -> Result<(), String> {
         let slot = self.slot_clock.now().ok_or("Failed to read slot clock")?;
-        let duration_to_next_slot = self
-            .slot_clock
-            .duration_to_next_slot()
-            .ok_or("Unable to determine duration to next slot")?;
 
-        // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
+        // If a validator needs to publish an aggregate attestation, they must do so at the
+        // aggregate offset (2/3 through the slot by default). This delay triggers at this time.
         let aggregate_production_instant = Instant::now()
-            + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
-                .unwrap_or_else(|| Duration::from_secs(0));
+            + self
+                .slot_timing
+                .duration_to_aggregate_production(&self.slot_clock)
+                .ok_or("Unable to determine duration to next slot")?;
 
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
             .duties_service
@@ -276,7 +289,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         // If an attestation was produced, make an aggregate.
         if let Some(attestation_data) = attestation_opt {
             // First, wait until the `aggregation_production_instant` (2/3rds
-            // of the way though the slot). As verified in the
+            // of the way though the slot by default). As verified in the
             // `delay_triggers_when_in_the_past` test, this code will still run
             // even if the instant has already elapsed.
             sleep_until(aggregate_production_instant).await;
@@ -341,6 +354,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                metrics::ATTESTATIONS_HTTP_GET,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::ATTESTATION_SERVICE_TIMES,
@@ -356,45 +370,74 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .await
             .map_err(|e| e.to_string())?;
 
-        // Create futures to produce signed `Attestation` objects.
-        let attestation_data_ref = &attestation_data;
-        let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
-            let duty = &duty_and_proof.duty;
-            let attestation_data = attestation_data_ref;
+        // Create unsigned `Attestation` objects for each duty.
+        let (duties, unsigned_attestations): (Vec<_>, Vec<_>) = validator_duties
+            .iter()
+            .filter_map(|duty_and_proof| {
+                let duty = &duty_and_proof.duty;
+
+                // Ensure that the attestation matches the duties.
+                #[allow(clippy::suspicious_operation_groupings)]
+                if duty.slot != attestation_data.slot
+                    || duty.committee_index != attestation_data.index
+                {
+                    crit!(
+                        log,
+                        "Inconsistent validator duties during signing";
+                        "validator" => ?duty.pubkey,
+                        "duty_slot" => duty.slot,
+                        "attestation_slot" => attestation_data.slot,
+                        "duty_index" => duty.committee_index,
+                        "attestation_index" => attestation_data.index,
+                    );
+                    return None;
+                }
 
-            // Ensure that the attestation matches the duties.
-            #[allow(clippy::suspicious_operation_groupings)]
-            if duty.slot != attestation_data.slot || duty.committee_index != attestation_data.index
-            {
+                let attestation = Attestation {
+                    aggregation_bits: BitList::with_capacity(duty.committee_length as usize)
+                        .unwrap(),
+                    data: attestation_data.clone(),
+                    signature: AggregateSignature::infinity(),
+                };
+
+                Some((
+                    duty,
+                    (
+                        duty.pubkey,
+                        duty.validator_committee_index as usize,
+                        attestation,
+                    ),
+                ))
+            })
+            .unzip();
+
+        // Sign all the attestations at once, so that they are checked against the slashing
+        // protection database in a single transaction.
+        let signing_results = match self
+            .validator_store
+            .sign_attestations(unsigned_attestations, current_epoch)
+            .await
+        {
+            Ok(signing_results) => signing_results,
+            Err(e) => {
                 crit!(
                     log,
-                    "Inconsistent validator duties during signing";
-                    "validator" => ?duty.pubkey,
-                    "duty_slot" => duty.slot,
-                    "attestation_slot" => attestation_data.slot,
-                    "duty_index" => duty.committee_index,
-                    "attestation_index" => attestation_data.index,
+                    "Failed to sign attestations";
+                    "error" => ?e,
+                    "count" => duties.len(),
+                    "committee_index" => committee_index,
+                    "slot" => slot.as_u64(),
                 );
-                return None;
+                vec![]
             }
+        };
 
-            let mut attestation = Attestation {
-                aggregation_bits: BitList::with_capacity(duty.committee_length as usize).unwrap(),
-                data: attestation_data.clone(),
-                signature: AggregateSignature::infinity(),
-            };
-
-            match self
-                .validator_store
-                .sign_attestation(
-                    duty.pubkey,
-                    duty.validator_committee_index as usize,
-                    &mut attestation,
-                    current_epoch,
-                )
-                .await
-            {
-                Ok(()) => Some((attestation, duty.validator_index)),
+        // Collect the successful results.
+        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = duties
+            .into_iter()
+            .zip(signing_results)
+            .filter_map(|(duty, result)| match result {
+                Ok(attestation) => Some((attestation, duty.validator_index)),
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
                     // removed via the API.
@@ -420,22 +463,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     );
                     None
                 }
-            }
-        });
-
-        // Execute all the futures in parallel, collecting any successful results.
-        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
-            .await
-            .into_iter()
-            .flatten()
+            })
             .unzip();
 
         // Post the attestations to the BN.
         match self
             .beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Attestations,
+                metrics::ATTESTATIONS_HTTP_POST,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::ATTESTATION_SERVICE_TIMES,
@@ -504,6 +542,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                metrics::AGGREGATES_HTTP_GET,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::ATTESTATION_SERVICE_TIMES,
@@ -582,9 +621,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
             match self
                 .beacon_nodes
-                .first_success(
+                .request(
                     RequireSynced::No,
                     OfflineOnFailure::Yes,
+                    ApiTopic::Attestations,
+                    metrics::AGGREGATES_HTTP_POST,
                     |beacon_node| async move {
                         let _timer = metrics::start_timer_vec(
                             &metrics::ATTESTATION_SERVICE_TIMES,
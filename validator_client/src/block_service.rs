@@ -1,6 +1,6 @@
 use crate::beacon_node_fallback::{Error as FallbackError, Errors};
 use crate::{
-    beacon_node_fallback::{BeaconNodeFallback, RequireSynced},
+    beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced},
     determine_graffiti,
     graffiti_file::GraffitiFile,
     OfflineOnFailure,
@@ -11,8 +11,10 @@ use crate::{
 };
 use bls::SignatureBytes;
 use environment::RuntimeContext;
-use eth2::types::{BlockContents, SignedBlockContents};
+use eth2::types::{BlockContents, BroadcastValidation, SignedBlockContents};
 use eth2::{BeaconNodeHttpClient, StatusCode};
+use futures::future;
+use graffiti_template::{expand_graffiti, GraffitiVariables};
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt::Debug;
@@ -23,10 +25,14 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 use types::{
-    AbstractExecPayload, BlindedPayload, BlockType, EthSpec, FullPayload, Graffiti, PublicKeyBytes,
-    Slot,
+    AbstractExecPayload, BlindedPayload, BlockType, EthSpec, FullPayload, Graffiti, Hash256,
+    PublicKeyBytes, Slot,
 };
 
+/// The time to wait after publishing a block with blobs before checking that the beacon node has
+/// seen all of them.
+const BLOB_PROPAGATION_CHECK_DELAY: Duration = Duration::from_secs(4);
+
 #[derive(Debug)]
 pub enum BlockError {
     Recoverable(String),
@@ -58,6 +64,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
     block_delay: Option<Duration>,
+    broadcast_validation: Option<BroadcastValidation>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -71,6 +78,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             graffiti: None,
             graffiti_file: None,
             block_delay: None,
+            broadcast_validation: None,
         }
     }
 
@@ -114,6 +122,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn broadcast_validation(
+        mut self,
+        broadcast_validation: Option<BroadcastValidation>,
+    ) -> Self {
+        self.broadcast_validation = broadcast_validation;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -133,6 +149,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 graffiti: self.graffiti,
                 graffiti_file: self.graffiti_file,
                 block_delay: self.block_delay,
+                broadcast_validation: self.broadcast_validation,
             }),
         })
     }
@@ -151,6 +168,7 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         &'a self,
         require_synced: RequireSynced,
         offline_on_failure: OfflineOnFailure,
+        endpoint: &'static str,
         func: F,
     ) -> Result<O, Errors<Err>>
     where
@@ -161,7 +179,7 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         // If there are proposer nodes, try calling `func` on them and return early if they are successful.
         if let Some(proposer_nodes) = &self.proposer_nodes {
             if let Ok(result) = proposer_nodes
-                .first_success(require_synced, offline_on_failure, func.clone())
+                .first_success(require_synced, offline_on_failure, endpoint, func.clone())
                 .await
             {
                 return Ok(result);
@@ -170,15 +188,87 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
 
         // If the proposer nodes failed, try on the non-proposer nodes.
         self.beacon_nodes
-            .first_success(require_synced, offline_on_failure, func)
+            .first_success(require_synced, offline_on_failure, endpoint, func)
             .await
     }
 
+    // Call `func` on all proposer and non-proposer nodes if messages of `topic` are broadcast,
+    // succeeding if any of them succeed. Otherwise, try `self.proposer_nodes` first.
+    //
+    // When broadcasting, failures of one set of nodes are logged against `slot` if the other set
+    // succeeds.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn request_proposers_first<'a, F, Err, R>(
+        &'a self,
+        require_synced: RequireSynced,
+        offline_on_failure: OfflineOnFailure,
+        topic: ApiTopic,
+        endpoint: &'static str,
+        slot: Slot,
+        log: &Logger,
+        func: F,
+    ) -> Result<(), Errors<Err>>
+    where
+        F: Fn(&'a BeaconNodeHttpClient) -> R + Clone,
+        R: Future<Output = Result<(), Err>>,
+        Err: Debug,
+    {
+        if !self.beacon_nodes.broadcasts(topic) {
+            return self
+                .first_success_try_proposers_first(
+                    require_synced,
+                    offline_on_failure,
+                    endpoint,
+                    func,
+                )
+                .await;
+        }
+
+        let beacon_nodes_future =
+            self.beacon_nodes
+                .broadcast(require_synced, offline_on_failure, endpoint, func.clone());
+        let Some(proposer_nodes) = &self.proposer_nodes else {
+            return beacon_nodes_future.await;
+        };
+
+        match future::join(
+            proposer_nodes.broadcast(require_synced, offline_on_failure, endpoint, func),
+            beacon_nodes_future,
+        )
+        .await
+        {
+            (Ok(()), Ok(())) => Ok(()),
+            (Ok(()), Err(Errors(errors))) | (Err(Errors(errors)), Ok(())) => {
+                for (beacon_node_id, error) in &errors {
+                    warn!(
+                        log,
+                        "Failed to broadcast to beacon node";
+                        "node" => beacon_node_id,
+                        "error" => ?error,
+                        "endpoint" => endpoint,
+                        "slot" => slot,
+                        "epoch" => slot.epoch(E::slots_per_epoch()),
+                    );
+                    metrics::inc_counter_vec(
+                        &metrics::ENDPOINT_BROADCAST_ERRORS,
+                        &[beacon_node_id.as_str()],
+                    );
+                }
+                Ok(())
+            }
+            (Err(Errors(mut errors)), Err(Errors(beacon_node_errors))) => {
+                errors.extend(beacon_node_errors);
+                Err(Errors(errors))
+            }
+        }
+    }
+
     // Try `func` on `self.beacon_nodes` first. If that doesn't work, try `self.proposer_nodes`.
     pub async fn first_success_try_proposers_last<'a, F, O, Err, R>(
         &'a self,
         require_synced: RequireSynced,
         offline_on_failure: OfflineOnFailure,
+        endpoint: &'static str,
         func: F,
     ) -> Result<O, Errors<Err>>
     where
@@ -189,7 +279,7 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         // Try running `func` on the non-proposer beacon nodes.
         let beacon_nodes_result = self
             .beacon_nodes
-            .first_success(require_synced, offline_on_failure, func.clone())
+            .first_success(require_synced, offline_on_failure, endpoint, func.clone())
             .await;
 
         match (beacon_nodes_result, &self.proposer_nodes) {
@@ -200,7 +290,7 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
             // The non-proposer node call failed, try the same call on the proposer nodes.
             (Err(_), Some(proposer_nodes)) => {
                 proposer_nodes
-                    .first_success(require_synced, offline_on_failure, func)
+                    .first_success(require_synced, offline_on_failure, endpoint, func)
                     .await
             }
         }
@@ -217,6 +307,7 @@ pub struct Inner<T, E: EthSpec> {
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
     block_delay: Option<Duration>,
+    broadcast_validation: Option<BroadcastValidation>,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -417,6 +508,9 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let log = self.context.log();
         let _timer =
             metrics::start_timer_vec(&metrics::BLOCK_SERVICE_TIMES, &[metrics::BEACON_BLOCK]);
+        // Allow the proposal to be signed and published if a shutdown is requested part way
+        // through.
+        let _in_flight = self.context.executor.graceful_shutdown().track();
 
         let current_slot = self.slot_clock.now().ok_or_else(|| {
             BlockError::Recoverable("Unable to determine current slot from clock".to_string())
@@ -448,17 +542,27 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             }
         };
 
+        let proposer_index = self.validator_store.validator_index(&validator_pubkey);
         let graffiti = determine_graffiti(
             &validator_pubkey,
             log,
             self.graffiti_file.clone(),
             self.validator_store.graffiti(&validator_pubkey),
             self.graffiti,
-        );
+        )
+        .map(|graffiti| {
+            expand_graffiti(
+                graffiti,
+                &GraffitiVariables {
+                    slot,
+                    epoch: slot.epoch(E::slots_per_epoch()),
+                    validator_index: proposer_index,
+                },
+            )
+        });
 
         let randao_reveal_ref = &randao_reveal;
         let self_ref = &self;
-        let proposer_index = self.validator_store.validator_index(&validator_pubkey);
         let validator_pubkey_ref = &validator_pubkey;
         let proposer_fallback = ProposerFallback {
             beacon_nodes: self.beacon_nodes.clone(),
@@ -479,6 +583,10 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .first_success_try_proposers_last(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                match Payload::block_type() {
+                    BlockType::Full => metrics::BEACON_BLOCK_HTTP_GET,
+                    BlockType::Blinded => metrics::BLINDED_BEACON_BLOCK_HTTP_GET,
+                },
                 move |beacon_node| {
                     Self::get_validator_block(
                         beacon_node,
@@ -563,15 +671,23 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
 
         let signed_block_contents = SignedBlockContents::from((signed_block, maybe_signed_blobs));
 
-        // Publish block with first available beacon node.
+        // Publish block with first available beacon node, or with all of them if blocks are
+        // broadcast.
         //
         // Try the proposer nodes first, since we've likely gone to efforts to
         // protect them from DoS attacks and they're most likely to successfully
         // publish a block.
         proposer_fallback
-            .first_success_try_proposers_first(
+            .request_proposers_first(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Blocks,
+                match Payload::block_type() {
+                    BlockType::Full => metrics::BEACON_BLOCK_HTTP_POST,
+                    BlockType::Blinded => metrics::BLINDED_BEACON_BLOCK_HTTP_POST,
+                },
+                slot,
+                log,
                 |beacon_node| async {
                     self.publish_signed_block_contents::<Payload>(
                         &signed_block_contents,
@@ -592,9 +708,90 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             "slot" => signed_block_contents.signed_block().slot().as_u64(),
         );
 
+        let num_blobs = signed_block_contents
+            .signed_block()
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_or(0, |commitments| commitments.len());
+        if num_blobs > 0 {
+            self.spawn_blob_propagation_check(
+                signed_block_contents.signed_block().canonical_root(),
+                slot,
+                num_blobs,
+            );
+        }
+
         Ok(())
     }
 
+    /// Asks the beacon node whether it has seen each blob of the block at `block_root` after
+    /// `BLOB_PROPAGATION_CHECK_DELAY`, logging a warning if any are missing.
+    fn spawn_blob_propagation_check(&self, block_root: Hash256, slot: Slot, num_blobs: usize) {
+        let service = self.clone();
+        self.context.executor.spawn(
+            async move {
+                sleep(BLOB_PROPAGATION_CHECK_DELAY).await;
+
+                let log = service.context.log();
+                let result = service
+                    .beacon_nodes
+                    .first_success(
+                        RequireSynced::No,
+                        OfflineOnFailure::No,
+                        metrics::BLOB_PROPAGATION_HTTP_GET,
+                        |beacon_node| async move {
+                            beacon_node
+                                .get_lighthouse_beacon_blob_propagation(block_root)
+                                .await
+                        },
+                    )
+                    .await;
+
+                match result {
+                    Ok(Some(response)) => {
+                        let missing_indices = response.data.missing_indices();
+                        if missing_indices.is_empty() {
+                            debug!(
+                                log,
+                                "All blobs seen by beacon node";
+                                "num_blobs" => num_blobs,
+                                "block_root" => ?block_root,
+                                "slot" => slot.as_u64(),
+                            );
+                        } else {
+                            warn!(
+                                log,
+                                "Blobs not seen by beacon node";
+                                "info" => "the blobs may not have propagated",
+                                "missing_indices" => ?missing_indices,
+                                "num_blobs" => num_blobs,
+                                "block_root" => ?block_root,
+                                "slot" => slot.as_u64(),
+                            );
+                        }
+                    }
+                    // Non-Lighthouse beacon nodes don't support the endpoint.
+                    Ok(None) => debug!(
+                        log,
+                        "Unable to check blob propagation";
+                        "info" => "block unknown or endpoint unsupported by beacon node",
+                        "block_root" => ?block_root,
+                        "slot" => slot.as_u64(),
+                    ),
+                    Err(e) => debug!(
+                        log,
+                        "Unable to check blob propagation";
+                        "error" => %e,
+                        "block_root" => ?block_root,
+                        "slot" => slot.as_u64(),
+                    ),
+                }
+            },
+            "blob_propagation_check",
+        );
+    }
+
     async fn publish_signed_block_contents<Payload: AbstractExecPayload<E>>(
         &self,
         signed_block_contents: &SignedBlockContents<E, Payload>,
@@ -608,20 +805,46 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                     &metrics::BLOCK_SERVICE_TIMES,
                     &[metrics::BEACON_BLOCK_HTTP_POST],
                 );
-                beacon_node
-                    .post_beacon_blocks(signed_block_contents)
-                    .await
-                    .or_else(|e| handle_block_post_error(e, slot, log))?
+                if beacon_node.compress_ssz_blocks() {
+                    beacon_node
+                        .post_beacon_blocks_v2_ssz(signed_block_contents, self.broadcast_validation)
+                        .await
+                } else {
+                    match self.broadcast_validation {
+                        Some(validation_level) => {
+                            beacon_node
+                                .post_beacon_blocks_v2(
+                                    signed_block_contents,
+                                    Some(validation_level),
+                                )
+                                .await
+                        }
+                        None => beacon_node.post_beacon_blocks(signed_block_contents).await,
+                    }
+                }
+                .or_else(|e| handle_block_post_error(e, slot, log))?
             }
             BlockType::Blinded => {
                 let _post_timer = metrics::start_timer_vec(
                     &metrics::BLOCK_SERVICE_TIMES,
                     &[metrics::BLINDED_BEACON_BLOCK_HTTP_POST],
                 );
-                beacon_node
-                    .post_beacon_blinded_blocks(signed_block_contents)
-                    .await
-                    .or_else(|e| handle_block_post_error(e, slot, log))?
+                match self.broadcast_validation {
+                    Some(validation_level) => {
+                        beacon_node
+                            .post_beacon_blinded_blocks_v2(
+                                signed_block_contents,
+                                Some(validation_level),
+                            )
+                            .await
+                    }
+                    None => {
+                        beacon_node
+                            .post_beacon_blinded_blocks(signed_block_contents)
+                            .await
+                    }
+                }
+                .or_else(|e| handle_block_post_error(e, slot, log))?
             }
         }
         Ok::<_, BlockError>(())
@@ -22,6 +22,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("beacon-nodes")
                 .value_name("NETWORK_ADDRESSES")
                 .help("Comma-separated addresses to one or more beacon node HTTP APIs. \
+                       A beacon node on the same host serving its API on a Unix domain socket \
+                       can be reached with a unix:// URL, e.g. unix:///run/lighthouse/bn.sock. \
                        Default is http://localhost:5052."
                 )
                 .takes_value(true),
@@ -39,12 +41,32 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("disable-run-on-all")
                 .long("disable-run-on-all")
                 .value_name("DISABLE_RUN_ON_ALL")
-                .help("By default, Lighthouse publishes attestation, sync committee subscriptions \
-                       and proposer preparation messages to all beacon nodes provided in the \
-                       `--beacon-nodes flag`. This option changes that behaviour such that these \
-                       api calls only go out to the first available and synced beacon node")
+                .help("DEPRECATED. Use `--broadcast none` instead.")
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("broadcast")
+                .long("broadcast")
+                .value_name("TOPICS")
+                .help("Comma-separated list of the kinds of message to publish to all beacon \
+                       nodes provided in the `--beacon-nodes` flag, rather than only the first \
+                       available and synced node. A message is considered published if any of \
+                       the beacon nodes accepts it. Possible values are: none, attestations, \
+                       blocks, subscriptions, sync-committee. Subscriptions include proposer \
+                       preparations. [default: subscriptions]")
+                .takes_value(true)
+                .conflicts_with("disable-run-on-all")
+        )
+        .arg(
+            Arg::with_name("broadcast-validation")
+                .long("broadcast-validation")
+                .value_name("LEVEL")
+                .help("The validation the beacon node should perform on a block before \
+                       broadcasting it. When set, blocks are published via the v2 block \
+                       endpoints.")
+                .possible_values(&["gossip", "consensus", "consensus_and_equivocation"])
+                .takes_value(true)
+        )
         // This argument is deprecated, use `--beacon-nodes` instead.
         .arg(
             Arg::with_name("server")
@@ -98,6 +120,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                      misplace your database and then run with this flag you risk being slashed."
                 )
         )
+        .arg(
+            Arg::with_name("slashing-protection-busy-timeout-ms")
+                .long("slashing-protection-busy-timeout-ms")
+                .value_name("MILLIS")
+                .help(
+                    "Time to wait for the slashing protection database to become available \
+                     when it is locked by another connection, before failing the operation."
+                )
+                .default_value("5000")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("disable-auto-discover")
             .long("disable-auto-discover")
@@ -118,6 +151,33 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         made to the beacon node. This flag is generally not recommended, \
                         longer timeouts can cause missed duties when fallbacks are used.")
         )
+        .arg(
+            Arg::with_name("compress-ssz-blocks")
+                .long("compress-ssz-blocks")
+                .help("If present, the validator client will publish full blocks to the beacon \
+                        node as snappy-compressed SSZ. Beacon nodes which don't advertise \
+                        support for compressed blocks are sent them uncompressed. Reduces \
+                        bandwidth when the beacon node is on a different host.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("proposer-duties-events")
+                .long("proposer-duties-events")
+                .help("If present, the validator client will subscribe to the Lighthouse \
+                        `proposer_duties_reorg` event stream of the beacon node and re-fetch \
+                        proposer duties as soon as they change, rather than polling for them \
+                        every slot. Requires a Lighthouse beacon node.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("distributed")
+                .long("distributed")
+                .help("Enables functionality required for running the validator client in a \
+                        distributed validator cluster, where selection proofs are combined with \
+                        those of other co-signers. Attestation subnets are subscribed to as a \
+                        potential aggregator until the combined selection proof is known.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("beacon-nodes-tls-certs")
                 .long("beacon-nodes-tls-certs")
@@ -132,7 +192,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("graffiti")
                 .long("graffiti")
-                .help("Specify your custom graffiti to be included in blocks.")
+                .help("Specify your custom graffiti to be included in blocks. May contain the \
+                    variables {version}, {slot}, {epoch} and {validator_index}, which are \
+                    replaced when a block is produced.")
                 .value_name("GRAFFITI")
                 .takes_value(true)
         )
@@ -369,6 +431,25 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("500")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("attestation-offset-ms")
+                .long("attestation-offset-ms")
+                .value_name("MILLIS")
+                .help("Time from the start of the slot at which to request attestation data and \
+                    publish attestations. Defaults to 1/3 of the slot. Can be updated at \
+                    runtime via the HTTP API.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("aggregate-offset-ms")
+                .long("aggregate-offset-ms")
+                .value_name("MILLIS")
+                .help("Time from the start of the slot at which to publish aggregate \
+                    attestations. Must be later than the attestation offset and within the \
+                    slot. Defaults to 2/3 of the slot. Can be updated at runtime via the HTTP \
+                    API.")
+                .takes_value(true),
+        )
         /*
          * Experimental/development options.
          */
@@ -1,3 +1,4 @@
+use crate::beacon_node_fallback::ApiTopic;
 use crate::graffiti_file::GraffitiFile;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
@@ -6,9 +7,10 @@ use directory::{
     get_network_dir, DEFAULT_HARDCODED_NETWORK, DEFAULT_ROOT_DIR, DEFAULT_SECRET_DIR,
     DEFAULT_VALIDATOR_DIR,
 };
-use eth2::types::Graffiti;
+use eth2::types::{BroadcastValidation, Graffiti};
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
+use slashing_protection::DEFAULT_BUSY_TIMEOUT;
 use slog::{info, warn, Logger};
 use std::fs;
 use std::net::IpAddr;
@@ -38,8 +40,17 @@ pub struct Config {
     pub disable_auto_discover: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// Time to wait on a locked slashing protection database before failing.
+    pub slashing_protection_busy_timeout: Duration,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
+    /// If true, publish full blocks as snappy-compressed SSZ when the beacon node supports it.
+    pub compress_ssz_blocks: bool,
+    /// If true, re-fetch proposer duties when notified of a change by the beacon node rather than
+    /// every slot.
+    pub proposer_duties_events: bool,
+    /// If true, this client is part of a distributed validator cluster.
+    pub distributed: bool,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Option<Graffiti>,
     /// Graffiti file to load per validator graffitis.
@@ -73,12 +84,20 @@ pub struct Config {
     ///
     /// This is *not* recommended in prod and should only be used for testing.
     pub block_delay: Option<Duration>,
-    /// Disables publishing http api requests to all beacon nodes for select api calls.
-    pub disable_run_on_all: bool,
+    /// The kinds of message which are published to all beacon nodes, rather than only the first
+    /// that accepts them.
+    pub broadcast_topics: Vec<ApiTopic>,
+    /// The validation level requested when publishing blocks, if any. When set, blocks are
+    /// published via the v2 endpoints.
+    pub broadcast_validation: Option<BroadcastValidation>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
+    /// Time from the start of the slot at which to produce attestations, if not 1/3 of the slot.
+    pub attestation_offset: Option<Duration>,
+    /// Time from the start of the slot at which to produce aggregates, if not 2/3 of the slot.
+    pub aggregate_offset: Option<Duration>,
 }
 
 impl Default for Config {
@@ -103,7 +122,11 @@ impl Default for Config {
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
             init_slashing_protection: false,
+            slashing_protection_busy_timeout: DEFAULT_BUSY_TIMEOUT,
             use_long_timeouts: false,
+            compress_ssz_blocks: false,
+            proposer_duties_events: false,
+            distributed: false,
             graffiti: None,
             graffiti_file: None,
             fee_recipient: None,
@@ -117,9 +140,12 @@ impl Default for Config {
             builder_proposals: false,
             builder_registration_timestamp_override: None,
             gas_limit: None,
-            disable_run_on_all: false,
+            broadcast_topics: vec![ApiTopic::Subscriptions],
+            broadcast_validation: None,
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
+            attestation_offset: None,
+            aggregate_offset: None,
         }
     }
 }
@@ -215,10 +241,33 @@ impl Config {
                 "msg" => "it no longer has any effect",
             );
         }
-        config.disable_run_on_all = cli_args.is_present("disable-run-on-all");
+        if cli_args.is_present("disable-run-on-all") {
+            warn!(
+                log,
+                "The --disable-run-on-all flag is deprecated";
+                "msg" => "please use --broadcast none instead",
+            );
+            config.broadcast_topics = vec![];
+        }
+        if let Some(broadcast_topics) = cli_args.value_of("broadcast") {
+            config.broadcast_topics = broadcast_topics
+                .split(',')
+                .map(str::trim)
+                .filter(|topic| *topic != "none")
+                .map(str::parse::<ApiTopic>)
+                .collect::<Result<_, _>>()?;
+        }
+        config.broadcast_validation = parse_optional(cli_args, "broadcast-validation")?;
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
         config.init_slashing_protection = cli_args.is_present("init-slashing-protection");
+        config.slashing_protection_busy_timeout = Duration::from_millis(parse_required(
+            cli_args,
+            "slashing-protection-busy-timeout-ms",
+        )?);
         config.use_long_timeouts = cli_args.is_present("use-long-timeouts");
+        config.compress_ssz_blocks = cli_args.is_present("compress-ssz-blocks");
+        config.proposer_duties_events = cli_args.is_present("proposer-duties-events");
+        config.distributed = cli_args.is_present("distributed");
 
         if let Some(graffiti_file_path) = cli_args.value_of("graffiti-file") {
             let mut graffiti_file = GraffitiFile::new(graffiti_file_path.into());
@@ -397,6 +446,12 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        // The offsets are checked against the slot duration once the spec is known.
+        config.attestation_offset =
+            parse_optional::<u64>(cli_args, "attestation-offset-ms")?.map(Duration::from_millis);
+        config.aggregate_offset =
+            parse_optional::<u64>(cli_args, "aggregate-offset-ms")?.map(Duration::from_millis);
+
         /*
          * Experimental
          */
@@ -30,12 +30,14 @@
 //! Doppelganger protection is a best-effort, last-line-of-defence mitigation. Do not rely upon it.
 
 use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::http_metrics::metrics;
 use crate::validator_store::ValidatorStore;
 use crate::OfflineOnFailure;
 use environment::RuntimeContext;
 use eth2::types::LivenessResponseData;
+use eth2::{BeaconNodeHttpClient, StatusCode};
 use parking_lot::RwLock;
-use slog::{crit, error, info, Logger};
+use slog::{crit, debug, error, info, Logger};
 use slot_clock::SlotClock;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
@@ -151,6 +153,84 @@ impl DoppelgangerState {
     }
 }
 
+/// The beacon node endpoint from which liveness data was obtained.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LivenessEndpoint {
+    /// The standard `POST validator/liveness/{epoch}` endpoint.
+    Standard,
+    /// The Lighthouse-specific `POST lighthouse/liveness` endpoint.
+    Lighthouse,
+}
+
+impl LivenessEndpoint {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LivenessEndpoint::Standard => "standard",
+            LivenessEndpoint::Lighthouse => "lighthouse",
+        }
+    }
+}
+
+/// Request the liveness data for `validator_indices` in `epoch` from the standard endpoint,
+/// falling back to the Lighthouse-specific endpoint if the BN does not support it (e.g. an older
+/// Lighthouse BN).
+///
+/// Any other error from the standard endpoint is returned, rather than hidden by the fallback.
+///
+/// The endpoint which provided the response is recorded in the
+/// `DOPPELGANGER_LIVENESS_RESPONSES_TOTAL` metric.
+async fn post_liveness(
+    beacon_node: &BeaconNodeHttpClient,
+    validator_indices: &[u64],
+    epoch: Epoch,
+    log: &Logger,
+) -> Result<(Vec<LivenessResponseData>, LivenessEndpoint), String> {
+    let result = match beacon_node
+        .post_validator_liveness_epoch(epoch, validator_indices.to_vec())
+        .await
+    {
+        Ok(response) => {
+            let responses = response
+                .data
+                .into_iter()
+                .map(|response| LivenessResponseData {
+                    index: response.index,
+                    epoch,
+                    is_live: response.is_live,
+                })
+                .collect();
+            (responses, LivenessEndpoint::Standard)
+        }
+        Err(e)
+            if matches!(
+                e.status(),
+                Some(StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED)
+            ) =>
+        {
+            debug!(
+                log,
+                "Standard liveness endpoint unsupported";
+                "msg" => "falling back to the Lighthouse liveness endpoint",
+                "error" => ?e,
+                "endpoint" => %beacon_node,
+            );
+            let response = beacon_node
+                .post_lighthouse_liveness(validator_indices, epoch)
+                .await
+                .map_err(|e| format!("Failed query for validator liveness: {:?}", e))?;
+            (response.data, LivenessEndpoint::Lighthouse)
+        }
+        Err(e) => return Err(format!("Failed query for validator liveness: {:?}", e)),
+    };
+
+    metrics::inc_counter_vec(
+        &metrics::DOPPELGANGER_LIVENESS_RESPONSES_TOTAL,
+        &[result.1.as_str()],
+    );
+
+    Ok(result)
+}
+
 /// Perform two requests to the BN to obtain the liveness data for `validator_indices`. One
 /// request will pertain to the `current_epoch`, the other to the `previous_epoch`.
 ///
@@ -164,6 +244,7 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
     validator_indices: Vec<u64>,
 ) -> LivenessResponses {
     let validator_indices = validator_indices.as_slice();
+    let log = &log;
 
     let previous_epoch = current_epoch.saturating_sub(1_u64);
 
@@ -180,12 +261,11 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
             .first_success(
                 RequireSynced::Yes,
                 OfflineOnFailure::Yes,
+                metrics::LIVENESS_HTTP_POST,
                 |beacon_node| async move {
-                    beacon_node
-                        .post_lighthouse_liveness(validator_indices, previous_epoch)
+                    post_liveness(beacon_node, validator_indices, previous_epoch, log)
                         .await
-                        .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
-                        .map(|result| result.data)
+                        .map(|(responses, _)| responses)
                 },
             )
             .await
@@ -207,12 +287,11 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
         .first_success(
             RequireSynced::Yes,
             OfflineOnFailure::Yes,
+            metrics::LIVENESS_HTTP_POST,
             |beacon_node| async move {
-                beacon_node
-                    .post_lighthouse_liveness(validator_indices, current_epoch)
+                post_liveness(beacon_node, validator_indices, current_epoch, log)
                     .await
-                    .map_err(|e| format!("Failed query for validator liveness: {:?}", e))
-                    .map(|result| result.data)
+                    .map(|(responses, _)| responses)
             },
         )
         .await
@@ -672,15 +751,20 @@ impl DoppelgangerService {
 mod test {
     use super::*;
     use environment::null_logger;
+    use eth2::types::{GenericResponse, LivenessRequestData, StandardLivenessResponseData};
+    use eth2::Timeouts;
     use futures::executor::block_on;
+    use sensitive_url::SensitiveUrl;
     use slot_clock::TestingSlotClock;
     use std::collections::HashSet;
     use std::future;
+    use std::net::{Ipv4Addr, SocketAddr};
     use std::time::Duration;
     use types::{
         test_utils::{SeedableRng, TestRandom, XorShiftRng},
         MainnetEthSpec,
     };
+    use warp::Filter;
 
     const DEFAULT_VALIDATORS: usize = 8;
 
@@ -1445,4 +1529,110 @@ mod test {
 
         scenario.assert_all_enabled();
     }
+
+    /// Spawns a beacon node which reports every validator as live. Unless `standard_error` is
+    /// set, in which case the standard liveness endpoint responds with that status instead.
+    fn spawn_liveness_beacon_node(standard_error: Option<StatusCode>) -> BeaconNodeHttpClient {
+        let standard = warp::path!("eth" / "v1" / "validator" / "liveness" / Epoch)
+            .and(warp::body::json())
+            .map(move |_epoch: Epoch, indices: Vec<u64>| {
+                if let Some(status) = standard_error {
+                    return warp::reply::with_status(
+                        warp::reply::json(&status.as_u16()),
+                        warp::http::StatusCode::from_u16(status.as_u16()).unwrap(),
+                    );
+                }
+                let data = indices
+                    .into_iter()
+                    .map(|index| StandardLivenessResponseData {
+                        index,
+                        is_live: true,
+                    })
+                    .collect::<Vec<_>>();
+                warp::reply::with_status(
+                    warp::reply::json(&GenericResponse::from(data)),
+                    warp::http::StatusCode::OK,
+                )
+            });
+        let lighthouse = warp::path!("lighthouse" / "liveness")
+            .and(warp::body::json())
+            .map(|request: LivenessRequestData| {
+                let data = request
+                    .indices
+                    .into_iter()
+                    .map(|index| LivenessResponseData {
+                        index,
+                        epoch: request.epoch,
+                        is_live: true,
+                    })
+                    .collect::<Vec<_>>();
+                warp::reply::json(&GenericResponse::from(data))
+            });
+
+        let (addr, server) = warp::serve(warp::post().and(standard.or(lighthouse)))
+            .bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+        tokio::spawn(server);
+
+        BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(&format!("http://{}", addr)).unwrap(),
+            Timeouts::set_all(Duration::from_secs(1)),
+        )
+    }
+
+    #[tokio::test]
+    async fn liveness_prefers_standard_endpoint() {
+        let beacon_node = spawn_liveness_beacon_node(None);
+        let epoch = Epoch::new(3);
+
+        let (responses, endpoint) =
+            post_liveness(&beacon_node, &[1, 2], epoch, &null_logger().unwrap())
+                .await
+                .unwrap();
+        assert_eq!(endpoint, LivenessEndpoint::Standard);
+        assert_eq!(
+            responses,
+            vec![
+                LivenessResponseData {
+                    index: 1,
+                    epoch,
+                    is_live: true,
+                },
+                LivenessResponseData {
+                    index: 2,
+                    epoch,
+                    is_live: true,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn liveness_falls_back_to_lighthouse_endpoint() {
+        let epoch = Epoch::new(3);
+
+        for status in [StatusCode::NOT_FOUND, StatusCode::METHOD_NOT_ALLOWED] {
+            let beacon_node = spawn_liveness_beacon_node(Some(status));
+            let (responses, endpoint) =
+                post_liveness(&beacon_node, &[1, 2], epoch, &null_logger().unwrap())
+                    .await
+                    .unwrap();
+            assert_eq!(endpoint, LivenessEndpoint::Lighthouse, "{}", status);
+            assert_eq!(responses.len(), 2);
+            assert!(responses
+                .iter()
+                .all(|response| response.epoch == epoch && response.is_live));
+        }
+    }
+
+    #[tokio::test]
+    async fn liveness_does_not_fall_back_on_other_errors() {
+        let epoch = Epoch::new(3);
+
+        for status in [StatusCode::BAD_REQUEST, StatusCode::INTERNAL_SERVER_ERROR] {
+            let beacon_node = spawn_liveness_beacon_node(Some(status));
+            post_liveness(&beacon_node, &[1, 2], epoch, &null_logger().unwrap())
+                .await
+                .unwrap_err();
+        }
+    }
 }
@@ -1,27 +1,33 @@
 //! The `DutiesService` contains the attester/proposer duties for all local validators.
 //!
 //! It learns of the local validator via the `crate::ValidatorStore` struct. It keeps the duties
-//! up-to-date by polling the beacon node on regular intervals.
+//! up-to-date by polling the beacon node on regular intervals. Optionally, proposer duties are
+//! instead re-fetched when the beacon node reports that they have changed.
 //!
 //! The `DutiesService` is also responsible for sending events to the `BlockService` which trigger
 //! block production.
 
-mod sync;
+mod selection_proof_cache;
+pub mod sync;
 
-use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, OfflineOnFailure, RequireSynced};
 use crate::http_metrics::metrics::{get_int_gauge, set_int_gauge, ATTESTATION_DUTY};
 use crate::{
     block_service::BlockServiceNotification,
     http_metrics::metrics,
+    selection_proof_hook::SelectionProofHook,
+    slot_timing::SlotTiming,
     validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, ValidatorStore},
 };
 use environment::RuntimeContext;
 use eth2::types::{
-    AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
+    AttesterData, BeaconCommitteeSubscription, DutiesResponse, EventKind, EventTopic, ProposerData,
+    StateId, ValidatorId,
 };
-use futures::{stream, StreamExt};
+use futures::{future::join_all, stream, StreamExt};
 use parking_lot::RwLock;
 use safe_arith::{ArithError, SafeArith};
+use selection_proof_cache::{AttestationSelectionProofCache, SyncSelectionProofCache};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp::min;
@@ -31,7 +37,10 @@ use std::sync::Arc;
 use std::time::Duration;
 use sync::poll_sync_committee_duties;
 use sync::SyncDutiesMap;
-use tokio::{sync::mpsc::Sender, time::sleep};
+use tokio::{
+    sync::{mpsc::Sender, Notify},
+    time::sleep,
+};
 use types::{ChainSpec, Epoch, EthSpec, Hash256, PublicKeyBytes, SelectionProof, Slot};
 
 /// Only retain `HISTORICAL_DUTIES_EPOCHS` duties prior to the current epoch.
@@ -42,9 +51,6 @@ const HISTORICAL_DUTIES_EPOCHS: u64 = 2;
 /// At start-up selection proofs will be computed with less lookahead out of necessity.
 const SELECTION_PROOF_SLOT_LOOKAHEAD: u64 = 8;
 
-/// Fraction of a slot at which selection proof signing should happen (2 means half way).
-const SELECTION_PROOF_SCHEDULE_DENOM: u32 = 2;
-
 /// Minimum number of validators for which we auto-enable per-validator metrics.
 /// For validators greater than this value, we need to manually set the `enable-per-validator-metrics`
 /// flag in the cli to enable collection of per validator metrics.
@@ -107,6 +113,8 @@ pub struct DutyAndProof {
     pub duty: AttesterData,
     /// This value is only set to `Some` if the proof indicates that the validator is an aggregator.
     pub selection_proof: Option<SelectionProof>,
+    /// Whether the selection proof has been produced, and `selection_proof` is final.
+    pub selection_proof_resolved: bool,
     /// Track which slots we should send subscriptions at for this duty.
     ///
     /// This value is updated after each subscription is successfully sent.
@@ -121,34 +129,60 @@ pub struct SubscriptionSlots {
 
 impl DutyAndProof {
     /// Instantiate `Self`, computing the selection proof as well.
+    ///
+    /// The selection proof is only signed if it is not already present in `selection_proofs`.
     pub async fn new_with_selection_proof<T: SlotClock + 'static, E: EthSpec>(
         duty: AttesterData,
         validator_store: &ValidatorStore<T, E>,
+        selection_proofs: &AttestationSelectionProofCache,
+        selection_proof_hook: &dyn SelectionProofHook,
         spec: &ChainSpec,
     ) -> Result<Self, Error> {
-        let selection_proof = validator_store
-            .produce_selection_proof(duty.pubkey, duty.slot)
+        let selection_proof = selection_proofs
+            .get_or_try_insert((duty.pubkey, duty.slot), || {
+                validator_store.produce_selection_proof(duty.pubkey, duty.slot)
+            })
             .await
             .map_err(Error::FailedToProduceSelectionProof)?;
 
-        let selection_proof = selection_proof
-            .is_aggregator(duty.committee_length as usize, spec)
-            .map_err(Error::InvalidModulo)
-            .map(|is_aggregator| {
-                if is_aggregator {
-                    Some(selection_proof)
-                } else {
-                    // Don't bother storing the selection proof if the validator isn't an
-                    // aggregator, we won't need it.
-                    None
-                }
-            })?;
+        Self::new_with_signed_selection_proof(duty, selection_proof, selection_proof_hook, spec)
+            .await
+    }
+
+    /// Instantiate `Self` from the selection proof signed by this client, which is first passed
+    /// through the `selection_proof_hook`.
+    pub async fn new_with_signed_selection_proof(
+        duty: AttesterData,
+        selection_proof: SelectionProof,
+        selection_proof_hook: &dyn SelectionProofHook,
+        spec: &ChainSpec,
+    ) -> Result<Self, Error> {
+        let selection_proof = match selection_proof_hook
+            .attestation_selection_proof(&duty, selection_proof)
+            .await
+        {
+            Some(selection_proof) => selection_proof
+                .is_aggregator(duty.committee_length as usize, spec)
+                .map_err(Error::InvalidModulo)
+                .map(|is_aggregator| {
+                    if is_aggregator {
+                        Some(selection_proof)
+                    } else {
+                        // Don't bother storing the selection proof if the validator isn't an
+                        // aggregator, we won't need it.
+                        None
+                    }
+                })?,
+            // Without a selection proof the validator can't aggregate.
+            None => None,
+        };
 
         let subscription_slots = SubscriptionSlots::new(duty.slot);
 
         Ok(Self {
             duty,
             selection_proof,
+            selection_proof_resolved: true,
             subscription_slots,
         })
     }
@@ -159,9 +193,18 @@ impl DutyAndProof {
         Self {
             duty,
             selection_proof: None,
+            selection_proof_resolved: false,
             subscription_slots,
         }
     }
+
+    /// Returns the `is_aggregator` value to send in subnet subscriptions for this duty.
+    ///
+    /// In `distributed` mode the validator subscribes as an aggregator until its selection proof
+    /// has been produced, since the proof may not be known until shortly before aggregation.
+    pub fn subscribe_as_aggregator(&self, distributed: bool) -> bool {
+        self.selection_proof.is_some() || (distributed && !self.selection_proof_resolved)
+    }
 }
 
 impl SubscriptionSlots {
@@ -212,13 +255,30 @@ pub struct DutiesService<T, E: EthSpec> {
     pub proposers: RwLock<ProposerMap>,
     /// Map from validator index to sync committee duties.
     pub sync_duties: SyncDutiesMap,
+    /// Attestation selection proofs which have already been signed, keyed by pubkey and slot.
+    pub attestation_selection_proofs: AttestationSelectionProofCache,
+    /// Sync selection proofs which have already been signed, keyed by pubkey, slot and subnet.
+    pub sync_selection_proofs: SyncSelectionProofCache,
+    /// Produces the final selection proofs from those signed by this client.
+    pub selection_proof_hook: Arc<dyn SelectionProofHook>,
+    /// Whether this client is part of a distributed validator cluster, see
+    /// `crate::selection_proof_hook`.
+    pub distributed: bool,
     /// Provides the canonical list of locally-managed validators.
     pub validator_store: Arc<ValidatorStore<T, E>>,
     /// Tracks the current slot.
     pub slot_clock: T,
+    /// Determines when within each slot attestation duties are performed.
+    pub slot_timing: Arc<SlotTiming>,
     /// Provides HTTP access to remote beacon nodes.
     pub beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     pub enable_high_validator_count_metrics: bool,
+    /// Whether to re-fetch proposer duties when notified by `proposer_duties_reorg` events.
+    pub proposer_duties_events: bool,
+    /// Whether the `proposer_duties_reorg` event stream is currently connected.
+    pub proposer_duties_events_connected: AtomicBool,
+    /// Notified when the proposer duties of the current epoch should be re-fetched.
+    pub proposer_duties_refetch: Notify,
     pub context: RuntimeContext<E>,
     pub spec: ChainSpec,
 }
@@ -372,17 +432,29 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
     core_duties_service.context.executor.spawn(
         async move {
             loop {
-                if let Some(duration) = duties_service.slot_clock.duration_to_next_slot() {
-                    sleep(duration).await;
-                } else {
-                    // Just sleep for one slot if we are unable to read the system clock, this gives
-                    // us an opportunity for the clock to eventually come good.
-                    sleep(duties_service.slot_clock.slot_duration()).await;
-                    continue;
-                }
+                let result = tokio::select! {
+                    _ = duties_service.proposer_duties_refetch.notified() => {
+                        refetch_beacon_proposers(&duties_service, &mut block_service_tx).await
+                    }
+                    next_slot = async {
+                        if let Some(duration) = duties_service.slot_clock.duration_to_next_slot() {
+                            sleep(duration).await;
+                            true
+                        } else {
+                            // Just sleep for one slot if we are unable to read the system clock,
+                            // this gives us an opportunity for the clock to eventually come good.
+                            sleep(duties_service.slot_clock.slot_duration()).await;
+                            false
+                        }
+                    } => {
+                        if !next_slot {
+                            continue;
+                        }
+                        poll_beacon_proposers(&duties_service, &mut block_service_tx).await
+                    }
+                };
 
-                if let Err(e) = poll_beacon_proposers(&duties_service, &mut block_service_tx).await
-                {
+                if let Err(e) = result {
                     error!(
                        log,
                        "Failed to poll beacon proposers";
@@ -394,6 +466,17 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
         "duties_service_proposers",
     );
 
+    /*
+     * Spawn the task which listens for changes to the proposer duties, if enabled.
+     */
+    if core_duties_service.proposer_duties_events {
+        let duties_service = core_duties_service.clone();
+        core_duties_service.context.executor.spawn(
+            async move { listen_for_proposer_duties_reorgs(&duties_service).await },
+            "duties_service_proposer_events",
+        );
+    }
+
     /*
      * Spawn the task which keeps track of local attestation duties.
      */
@@ -489,6 +572,7 @@ async fn poll_validator_indices<T: SlotClock + 'static, E: EthSpec>(
                 .first_success(
                     RequireSynced::No,
                     OfflineOnFailure::Yes,
+                    metrics::VALIDATOR_ID_HTTP_GET,
                     |beacon_node| async move {
                         let _timer = metrics::start_timer_vec(
                             &metrics::DUTIES_SERVICE_TIMES,
@@ -682,7 +766,8 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
             })
             .for_each(|(_, duty_and_proof)| {
                 let duty = &duty_and_proof.duty;
-                let is_aggregator = duty_and_proof.selection_proof.is_some();
+                let is_aggregator =
+                    duty_and_proof.subscribe_as_aggregator(duties_service.distributed);
 
                 subscriptions.push(BeaconCommitteeSubscription {
                     validator_index: duty.validator_index,
@@ -700,9 +785,11 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
         let subscriptions_ref = &subscriptions;
         if let Err(e) = duties_service
             .beacon_nodes
-            .run(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
+                metrics::SUBSCRIPTIONS_HTTP_POST,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::DUTIES_SERVICE_TIMES,
@@ -744,6 +831,11 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
             map.retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch)
         });
 
+    // Prune selection proofs for slots that have passed.
+    duties_service
+        .attestation_selection_proofs
+        .prune(current_slot);
+
     Ok(())
 }
 
@@ -951,6 +1043,7 @@ async fn post_validator_duties_attester<T: SlotClock + 'static, E: EthSpec>(
         .first_success(
             RequireSynced::No,
             OfflineOnFailure::Yes,
+            metrics::ATTESTER_DUTIES_HTTP_POST,
             |beacon_node| async move {
                 let _timer = metrics::start_timer_vec(
                     &metrics::DUTIES_SERVICE_TIMES,
@@ -983,14 +1076,17 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
         duties_by_slot.entry(duty.slot).or_default().push(duty);
     }
 
-    // At halfway through each slot when nothing else is likely to be getting signed, sign a batch
-    // of selection proofs and insert them into the duties service `attesters` map.
+    // Halfway between attestation and aggregate production in each slot, when nothing else is
+    // likely to be getting signed, sign a batch of selection proofs and insert them into the
+    // duties service `attesters` map.
     let slot_clock = &duties_service.slot_clock;
-    let slot_offset = duties_service.slot_clock.slot_duration() / SELECTION_PROOF_SCHEDULE_DENOM;
 
     while !duties_by_slot.is_empty() {
-        if let Some(duration) = slot_clock.duration_to_next_slot() {
-            sleep(duration.saturating_sub(slot_offset)).await;
+        if let Some(duration) = duties_service
+            .slot_timing
+            .duration_to_selection_proof_signing(slot_clock)
+        {
+            sleep(duration).await;
 
             let Some(current_slot) = slot_clock.now() else {
                 continue;
@@ -1012,18 +1108,28 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 &[metrics::ATTESTATION_SELECTION_PROOFS],
             );
 
-            // Sign selection proofs (serially).
-            let duty_and_proof_results = stream::iter(relevant_duties.into_values().flatten())
-                .then(|duty| async {
+            let duty_and_proof_futures =
+                relevant_duties.into_values().flatten().map(|duty| async {
                     DutyAndProof::new_with_selection_proof(
                         duty,
                         &duties_service.validator_store,
+                        &duties_service.attestation_selection_proofs,
+                        duties_service.selection_proof_hook.as_ref(),
                         &duties_service.spec,
                     )
                     .await
-                })
-                .collect::<Vec<_>>()
-                .await;
+                });
+            let duty_and_proof_results = if duties_service.distributed {
+                // Combining proofs with the other members of the cluster may be slow, so don't
+                // wait for each one in turn.
+                join_all(duty_and_proof_futures).await
+            } else {
+                // Sign selection proofs (serially).
+                stream::iter(duty_and_proof_futures)
+                    .then(|future| future)
+                    .collect::<Vec<_>>()
+                    .await
+            };
 
             // Add to attesters store.
             let mut attesters = duties_service.attesters.write();
@@ -1060,16 +1166,12 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 let epoch = duty_and_proof.duty.slot.epoch(E::slots_per_epoch());
                 match attester_map.entry(epoch) {
                     hash_map::Entry::Occupied(mut entry) => {
-                        // No need to update duties for which no proof was computed.
-                        let Some(selection_proof) = duty_and_proof.selection_proof else {
-                            continue;
-                        };
-
                         let (existing_dependent_root, existing_duty) = entry.get_mut();
 
                         if *existing_dependent_root == dependent_root {
                             // Replace existing proof.
-                            existing_duty.selection_proof = Some(selection_proof);
+                            existing_duty.selection_proof = duty_and_proof.selection_proof;
+                            existing_duty.selection_proof_resolved = true;
                         } else {
                             // Our selection proofs are no longer relevant due to a reorg, abandon
                             // this entire background process.
@@ -1156,6 +1258,68 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
     )
     .await;
 
+    // Whilst the event stream is connected, the cached duties for the current epoch are only
+    // replaced when the beacon node reports that they have changed.
+    let events_connected = duties_service
+        .proposer_duties_events_connected
+        .load(Ordering::Relaxed);
+    if !events_connected || !duties_service.proposers.read().contains_key(&current_epoch) {
+        download_beacon_proposers(
+            duties_service,
+            current_slot,
+            &initial_block_proposers,
+            block_service_tx,
+        )
+        .await;
+    }
+
+    // Prune old duties.
+    duties_service
+        .proposers
+        .write()
+        .retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch);
+
+    Ok(())
+}
+
+/// Download the proposer duties for the current epoch after the beacon node has reported that
+/// they have changed, notifying the `BlockService` of any new proposers for the current slot.
+async fn refetch_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    block_service_tx: &mut Sender<BlockServiceNotification>,
+) -> Result<(), Error> {
+    let _timer =
+        metrics::start_timer_vec(&metrics::DUTIES_SERVICE_TIMES, &[metrics::UPDATE_PROPOSERS]);
+
+    let current_slot = duties_service
+        .slot_clock
+        .now()
+        .ok_or(Error::UnableToReadSlotClock)?;
+
+    // The proposers in the cache have already been notified at the start of the slot.
+    let notified_block_proposers = duties_service.block_proposers(current_slot);
+    download_beacon_proposers(
+        duties_service,
+        current_slot,
+        &notified_block_proposers,
+        block_service_tx,
+    )
+    .await;
+
+    Ok(())
+}
+
+/// Download the proposer duties for the epoch of `current_slot` and notify the `BlockService` of
+/// any proposers for `current_slot` which are not in `initial_block_proposers`.
+async fn download_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    current_slot: Slot,
+    initial_block_proposers: &HashSet<PublicKeyBytes>,
+    block_service_tx: &mut Sender<BlockServiceNotification>,
+) {
+    let log = duties_service.context.log();
+    let current_epoch = current_slot.epoch(E::slots_per_epoch());
+
     // Collect *all* pubkeys, even those undergoing doppelganger protection.
     //
     // It is useful to keep the duties for all validators around, so they're on hand when
@@ -1172,6 +1336,7 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                metrics::PROPOSER_DUTIES_HTTP_GET,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::DUTIES_SERVICE_TIMES,
@@ -1232,7 +1397,7 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
         // which were not included in the initial notification to the `BlockService`.
         let additional_block_producers = duties_service
             .block_proposers(current_slot)
-            .difference(&initial_block_proposers)
+            .difference(initial_block_proposers)
             .copied()
             .collect::<HashSet<PublicKeyBytes>>();
 
@@ -1257,14 +1422,92 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
             metrics::inc_counter(&metrics::PROPOSAL_CHANGED);
         }
     }
+}
 
-    // Prune old duties.
-    duties_service
-        .proposers
-        .write()
-        .retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch);
+/// Subscribe to `proposer_duties_reorg` events from the beacon node, requesting a re-fetch of the
+/// proposer duties whenever those of the current epoch change. Never returns.
+///
+/// The HTTP client times out requests after a slot, so the stream is re-established regularly.
+/// Whilst it is disconnected the proposer duties are polled every slot as usual.
+async fn listen_for_proposer_duties_reorgs<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+) {
+    let log = duties_service.context.log();
 
-    Ok(())
+    loop {
+        let subscribe_result = duties_service
+            .beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::No,
+                metrics::PROPOSER_DUTIES_EVENTS_HTTP_GET,
+                |beacon_node| async move {
+                    beacon_node
+                        .get_events::<E>(&[EventTopic::ProposerDutiesReorg])
+                        .await
+                },
+            )
+            .await;
+
+        match subscribe_result {
+            Ok(stream) => {
+                let mut stream = Box::pin(stream);
+                duties_service
+                    .proposer_duties_events_connected
+                    .store(true, Ordering::Relaxed);
+
+                while let Some(event) = stream.next().await {
+                    let reorg = match event {
+                        Ok(EventKind::ProposerDutiesReorg(reorg)) => reorg,
+                        Ok(_) => continue,
+                        Err(e) => {
+                            debug!(
+                                log,
+                                "Proposer duties event stream ended";
+                                "error" => %e,
+                            );
+                            break;
+                        }
+                    };
+
+                    let Some(current_slot) = duties_service.slot_clock.now() else {
+                        continue;
+                    };
+                    if reorg.epoch != current_slot.epoch(E::slots_per_epoch()) {
+                        continue;
+                    }
+                    let is_known = duties_service
+                        .proposers
+                        .read()
+                        .get(&reorg.epoch)
+                        .map_or(false, |(dependent_root, _)| {
+                            *dependent_root == reorg.dependent_root
+                        });
+                    if !is_known {
+                        debug!(
+                            log,
+                            "Proposer duties changed";
+                            "epoch" => reorg.epoch,
+                            "dependent_root" => %reorg.dependent_root,
+                        );
+                        duties_service.proposer_duties_refetch.notify_one();
+                    }
+                }
+
+                duties_service
+                    .proposer_duties_events_connected
+                    .store(false, Ordering::Relaxed);
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to subscribe to proposer duties events";
+                    "error" => %e,
+                );
+                sleep(duties_service.slot_clock.slot_duration()).await;
+            }
+        }
+    }
 }
 
 /// Notify the block service if it should produce a block.
@@ -1302,6 +1545,81 @@ async fn notify_block_production_service<T: SlotClock + 'static, E: EthSpec>(
 #[cfg(test)]
 mod test {
     use super::*;
+    use futures::future::BoxFuture;
+    use types::{MainnetEthSpec, Signature, SyncDuty, SyncSelectionProof, SyncSubnetId};
+
+    /// A hook which takes a while to combine selection proofs, and fails to do so for every third
+    /// validator.
+    struct DelayedHook;
+
+    impl DelayedHook {
+        async fn combine<P>(validator_index: u64, proof: P) -> Option<P> {
+            sleep(Duration::from_millis(100)).await;
+            (validator_index % 3 != 0).then_some(proof)
+        }
+    }
+
+    impl SelectionProofHook for DelayedHook {
+        fn attestation_selection_proof<'a>(
+            &'a self,
+            duty: &'a AttesterData,
+            proof: SelectionProof,
+        ) -> BoxFuture<'a, Option<SelectionProof>> {
+            Box::pin(Self::combine(duty.validator_index, proof))
+        }
+
+        fn sync_selection_proof<'a>(
+            &'a self,
+            duty: &'a SyncDuty,
+            _slot: Slot,
+            _subnet_id: SyncSubnetId,
+            proof: SyncSelectionProof,
+        ) -> BoxFuture<'a, Option<SyncSelectionProof>> {
+            Box::pin(Self::combine(duty.validator_index, proof))
+        }
+    }
+
+    #[tokio::test]
+    async fn delayed_selection_proofs() {
+        let spec = MainnetEthSpec::default_spec();
+        // With a committee of one, every selection proof is an aggregator.
+        let duties = (0..9).map(|validator_index| AttesterData {
+            pubkey: PublicKeyBytes::empty(),
+            validator_index,
+            committees_at_slot: 1,
+            committee_index: 0,
+            committee_length: 1,
+            validator_committee_index: 0,
+            slot: Slot::new(10),
+        });
+
+        // Until the selection proofs are known, distributed validators subscribe as aggregators.
+        for duty in duties.clone() {
+            let duty_and_proof = DutyAndProof::new_without_selection_proof(duty);
+            assert!(duty_and_proof.subscribe_as_aggregator(true));
+            assert!(!duty_and_proof.subscribe_as_aggregator(false));
+        }
+
+        let results = join_all(duties.map(|duty| {
+            DutyAndProof::new_with_signed_selection_proof(
+                duty,
+                Signature::empty().into(),
+                &DelayedHook,
+                &spec,
+            )
+        }))
+        .await;
+
+        // Every duty is resolved, and only validators with a combined proof aggregate.
+        assert_eq!(results.len(), 9);
+        for result in results {
+            let duty_and_proof = result.unwrap();
+            let is_aggregator = duty_and_proof.duty.validator_index % 3 != 0;
+            assert!(duty_and_proof.selection_proof_resolved);
+            assert_eq!(duty_and_proof.selection_proof.is_some(), is_aggregator);
+            assert_eq!(duty_and_proof.subscribe_as_aggregator(true), is_aggregator);
+        }
+    }
 
     #[test]
     fn subscription_slots_exact() {
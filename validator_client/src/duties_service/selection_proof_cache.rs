@@ -0,0 +1,272 @@
+//! Caches signed selection proofs so that each proof is only signed once.
+//!
+//! Selection proofs depend only on the validator's key and the slot (plus the subcommittee index
+//! for sync committee proofs). When duties are re-downloaded, for example after falling back to
+//! another beacon node, the proofs that were already signed can be reused instead of making
+//! another request to the signer. Concurrent requests for the same proof share a single signing
+//! request.
+
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use types::{PublicKeyBytes, SelectionProof, Slot, SyncSelectionProof, SyncSubnetId};
+
+/// Key for an attestation `SelectionProof`.
+pub type AttestationProofKey = (PublicKeyBytes, Slot);
+
+/// Key for a `SyncSelectionProof`.
+pub type SyncProofKey = (PublicKeyBytes, Slot, SyncSubnetId);
+
+pub type AttestationSelectionProofCache = SelectionProofCache<AttestationProofKey, SelectionProof>;
+pub type SyncSelectionProofCache = SelectionProofCache<SyncProofKey, SyncSelectionProof>;
+
+/// A key which identifies the slot that a selection proof was signed for.
+pub trait ProofKey: Eq + Hash + Clone {
+    fn slot(&self) -> Slot;
+}
+
+impl ProofKey for AttestationProofKey {
+    fn slot(&self) -> Slot {
+        self.1
+    }
+}
+
+impl ProofKey for SyncProofKey {
+    fn slot(&self) -> Slot {
+        self.1
+    }
+}
+
+pub struct SelectionProofCache<K, V> {
+    /// A proof is signed at most once at a time by whichever caller initializes its cell first,
+    /// while the others wait for the result.
+    proofs: RwLock<HashMap<K, Arc<OnceCell<V>>>>,
+}
+
+impl<K, V> Default for SelectionProofCache<K, V> {
+    fn default() -> Self {
+        Self {
+            proofs: RwLock::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K: ProofKey, V: Clone> SelectionProofCache<K, V> {
+    /// Return the cached proof for `key`, or sign it with `produce` and cache the result.
+    ///
+    /// If the proof is already being signed by another caller, waits for that signature instead of
+    /// calling `produce`. Errors from `produce` are not cached, and the next waiting caller retries.
+    pub async fn get_or_try_insert<F, Fut, Err>(&self, key: K, produce: F) -> Result<V, Err>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, Err>>,
+    {
+        let cell = self.proofs.write().entry(key).or_default().clone();
+        cell.get_or_try_init(produce).await.cloned()
+    }
+
+    /// Remove all proofs for slots prior to `current_slot`.
+    pub fn prune(&self, current_slot: Slot) {
+        self.proofs
+            .write()
+            .retain(|key, _| key.slot() >= current_slot);
+    }
+
+    /// Returns the number of signed proofs in the cache.
+    pub fn len(&self) -> usize {
+        self.proofs
+            .read()
+            .values()
+            .filter(|cell| cell.initialized())
+            .count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use types::test_utils::generate_deterministic_keypair;
+    use types::{Hash256, Signature};
+
+    fn pubkey(i: u8) -> PublicKeyBytes {
+        let mut bytes = [0; 48];
+        bytes[0] = i;
+        PublicKeyBytes::deserialize(&bytes).unwrap()
+    }
+
+    /// A signature which is unique to the `call`th signing request.
+    fn signature(call: usize) -> Signature {
+        generate_deterministic_keypair(call)
+            .sk
+            .sign(Hash256::zero())
+    }
+
+    async fn sign(calls: &AtomicUsize) -> Result<SelectionProof, ()> {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        Ok(signature(call).into())
+    }
+
+    #[tokio::test]
+    async fn one_signature_per_key_and_slot() {
+        let cache = AttestationSelectionProofCache::default();
+        let calls = AtomicUsize::new(0);
+        let slots = [Slot::new(10), Slot::new(11)];
+        let pubkeys = [pubkey(1), pubkey(2)];
+
+        // Simulate the duties being downloaded from the primary and then retried against a
+        // fallback node. The retries must reuse the proofs signed by the first attempt.
+        let mut proofs = HashMap::new();
+        for _attempt in 0..3 {
+            for pubkey in pubkeys {
+                for slot in slots {
+                    let proof = cache
+                        .get_or_try_insert((pubkey, slot), || sign(&calls))
+                        .await
+                        .unwrap();
+                    assert_eq!(
+                        *proofs.entry((pubkey, slot)).or_insert(proof.clone()),
+                        proof
+                    );
+                }
+            }
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), pubkeys.len() * slots.len());
+        assert_eq!(cache.len(), pubkeys.len() * slots.len());
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_share_signature() {
+        let cache = AttestationSelectionProofCache::default();
+        let calls = AtomicUsize::new(0);
+        let key = (pubkey(1), Slot::new(10));
+
+        // Each signature yields to the other requests before completing, so that they all find
+        // it in flight.
+        let requests = (0..8).map(|_| {
+            cache.get_or_try_insert(key, || async {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                for _ in 0..8 {
+                    tokio::task::yield_now().await;
+                }
+                Ok::<SelectionProof, ()>(signature(call).into())
+            })
+        });
+        let proofs = futures::future::join_all(requests).await;
+
+        let expected = SelectionProof::from(signature(0));
+        assert!(proofs.iter().all(|proof| *proof == Ok(expected.clone())));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_retry_after_error() {
+        let cache = AttestationSelectionProofCache::default();
+        let calls = AtomicUsize::new(0);
+        let key = (pubkey(1), Slot::new(10));
+
+        // The first signature fails, after which a single waiting request signs the proof for all
+        // of the others.
+        let requests = (0..8).map(|_| {
+            cache.get_or_try_insert(key, || async {
+                let call = calls.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                if call == 0 {
+                    Err(())
+                } else {
+                    Ok::<SelectionProof, ()>(signature(call).into())
+                }
+            })
+        });
+        let proofs = futures::future::join_all(requests).await;
+
+        let expected = SelectionProof::from(signature(1));
+        assert_eq!(proofs.iter().filter(|proof| proof.is_err()).count(), 1);
+        assert!(proofs
+            .iter()
+            .filter_map(|proof| proof.as_ref().ok())
+            .all(|proof| *proof == expected));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn sync_proofs_keyed_by_subnet() {
+        let cache = SyncSelectionProofCache::default();
+        let calls = AtomicUsize::new(0);
+        let slot = Slot::new(10);
+
+        for _attempt in 0..2 {
+            for subnet_id in 0..4 {
+                let proof = cache
+                    .get_or_try_insert((pubkey(1), slot, SyncSubnetId::new(subnet_id)), || async {
+                        let call = calls.fetch_add(1, Ordering::SeqCst);
+                        Ok::<_, ()>(SyncSelectionProof::from(signature(call)))
+                    })
+                    .await
+                    .unwrap();
+                assert_eq!(
+                    proof,
+                    SyncSelectionProof::from(signature(subnet_id as usize))
+                );
+            }
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let cache = AttestationSelectionProofCache::default();
+        let key = (pubkey(1), Slot::new(10));
+
+        assert!(cache
+            .get_or_try_insert(key, || async { Err::<SelectionProof, _>(()) })
+            .await
+            .is_err());
+        assert!(cache.is_empty());
+
+        let calls = AtomicUsize::new(0);
+        let proof = cache.get_or_try_insert(key, || sign(&calls)).await.unwrap();
+        assert_eq!(proof, signature(0).into());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn prune_evicts_past_slots() {
+        let cache = AttestationSelectionProofCache::default();
+        let calls = AtomicUsize::new(0);
+
+        for slot in 0..4 {
+            cache
+                .get_or_try_insert((pubkey(1), Slot::new(slot)), || sign(&calls))
+                .await
+                .unwrap();
+        }
+
+        cache.prune(Slot::new(2));
+        assert_eq!(cache.len(), 2);
+
+        // Proofs for pruned slots are signed again if requested, while the others are reused.
+        let resigned = cache
+            .get_or_try_insert((pubkey(1), Slot::new(1)), || sign(&calls))
+            .await
+            .unwrap();
+        assert_eq!(resigned, signature(4).into());
+        let reused = cache
+            .get_or_try_insert((pubkey(1), Slot::new(2)), || sign(&calls))
+            .await
+            .unwrap();
+        assert_eq!(reused, signature(2).into());
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+}
@@ -278,11 +278,11 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
 ) -> Result<(), Error> {
     let sync_duties = &duties_service.sync_duties;
     let spec = &duties_service.spec;
-    let current_epoch = duties_service
+    let current_slot = duties_service
         .slot_clock
         .now()
-        .ok_or(Error::UnableToReadSlotClock)?
-        .epoch(E::slots_per_epoch());
+        .ok_or(Error::UnableToReadSlotClock)?;
+    let current_epoch = current_slot.epoch(E::slots_per_epoch());
 
     // If the Altair fork is yet to be activated, do not attempt to poll for duties.
     if spec
@@ -292,6 +292,9 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
         return Ok(());
     }
 
+    // Prune selection proofs for slots that have passed.
+    duties_service.sync_selection_proofs.prune(current_slot);
+
     let current_sync_committee_period = current_epoch.sync_committee_period(spec)?;
     let next_sync_committee_period = current_sync_committee_period + 1;
 
@@ -427,6 +430,7 @@ pub async fn poll_sync_committee_duties_for_period<T: SlotClock + 'static, E: Et
         .first_success(
             RequireSynced::No,
             OfflineOnFailure::Yes,
+            metrics::VALIDATOR_DUTIES_SYNC_HTTP_POST,
             |beacon_node| async move {
                 let _timer = metrics::start_timer_vec(
                     &metrics::DUTIES_SERVICE_TIMES,
@@ -541,8 +545,12 @@ pub async fn fill_in_aggregation_proofs<T: SlotClock + 'static, E: EthSpec>(
                     let slot = duty_slot - 1;
 
                     let proof = match duties_service_ref
-                        .validator_store
-                        .produce_sync_selection_proof(&duty.pubkey, slot, *subnet_id)
+                        .sync_selection_proofs
+                        .get_or_try_insert((duty.pubkey, slot, *subnet_id), || {
+                            duties_service_ref
+                                .validator_store
+                                .produce_sync_selection_proof(&duty.pubkey, slot, *subnet_id)
+                        })
                         .await
                     {
                         Ok(proof) => proof,
@@ -570,6 +578,21 @@ pub async fn fill_in_aggregation_proofs<T: SlotClock + 'static, E: EthSpec>(
                         }
                     };
 
+                    let Some(proof) = duties_service_ref
+                        .selection_proof_hook
+                        .sync_selection_proof(duty, slot, *subnet_id, proof)
+                        .await
+                    else {
+                        debug!(
+                            log,
+                            "No sync selection proof produced";
+                            "pubkey" => ?duty.pubkey,
+                            "slot" => slot,
+                            "subnet_id" => %subnet_id,
+                        );
+                        return None;
+                    };
+
                     match proof.is_aggregator::<E>() {
                         Ok(true) => {
                             debug!(
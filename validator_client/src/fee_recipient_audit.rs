@@ -0,0 +1,512 @@
+//! Periodically checks that the fee recipient and gas limit configured for each validator are the
+//! ones actually in use.
+//!
+//! Once per epoch, the configuration of each validator is compared against:
+//!
+//! - The proposer preparation held by the beacon node, from `GET lighthouse/proposer_preparation`.
+//! - The fee recipient of the last payload the validator proposed, if any.
+//!
+//! Any discrepancy is logged at `WARN` and reported by the `fee_recipient_drift` metric, so that a
+//! misconfiguration is noticed before a block pays the wrong address.
+
+use crate::beacon_node_fallback::{BeaconNodeFallback, OfflineOnFailure, RequireSynced};
+use crate::duties_service::DutiesService;
+use crate::http_metrics::metrics;
+use crate::preparation_service::ProposalData;
+use crate::validator_store::DoppelgangerStatus;
+use bls::PublicKeyBytes;
+use environment::RuntimeContext;
+use eth2::lighthouse::ProposerPreparationEntry;
+use eth2::types::{BlockId, ProposerData};
+use eth2::BeaconNodeHttpClient;
+use slog::{debug, warn, Logger};
+use slot_clock::SlotClock;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use tokio::time::sleep;
+use types::{Address, EthSpec, ExecPayload, ExecutionBlockHash, Slot};
+
+/// The payload of the last block proposed by a validator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProposedPayload {
+    pub slot: Slot,
+    pub fee_recipient: Address,
+}
+
+/// A difference between the configuration of a validator and what is in use.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// The beacon node has no preparation for the validator, so it would use its own default fee
+    /// recipient.
+    MissingPreparation,
+    /// The beacon node holds a different fee recipient.
+    BeaconNodeFeeRecipient { beacon_node: Address },
+    /// The latest builder registration held by the beacon node has a different gas limit.
+    BeaconNodeGasLimit { beacon_node: u64 },
+    /// The last payload proposed by the validator paid a different fee recipient.
+    PayloadFeeRecipient { slot: Slot, payload: Address },
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Drift::MissingPreparation => write!(f, "beacon node has no proposer preparation"),
+            Drift::BeaconNodeFeeRecipient { beacon_node } => {
+                write!(f, "beacon node fee recipient is {:?}", beacon_node)
+            }
+            Drift::BeaconNodeGasLimit { beacon_node } => {
+                write!(f, "beacon node gas limit is {}", beacon_node)
+            }
+            Drift::PayloadFeeRecipient { slot, payload } => {
+                write!(f, "payload at slot {} paid {:?}", slot, payload)
+            }
+        }
+    }
+}
+
+/// Compares the configuration of a validator against the beacon node's preparation and its last
+/// proposed payload.
+///
+/// The payload fee recipient is not checked for validators using a builder, since builders pay the
+/// proposer with a transaction rather than through the payload's fee recipient.
+pub fn find_drift(
+    configured: &ProposalData,
+    preparation: Option<&ProposerPreparationEntry>,
+    payload: Option<&ProposedPayload>,
+) -> Vec<Drift> {
+    let mut drift = vec![];
+
+    if let Some(fee_recipient) = configured.fee_recipient {
+        match preparation {
+            None => drift.push(Drift::MissingPreparation),
+            Some(preparation) if preparation.fee_recipient != fee_recipient => {
+                drift.push(Drift::BeaconNodeFeeRecipient {
+                    beacon_node: preparation.fee_recipient,
+                })
+            }
+            Some(_) => (),
+        }
+
+        if let Some(payload) = payload {
+            if !configured.builder_proposals && payload.fee_recipient != fee_recipient {
+                drift.push(Drift::PayloadFeeRecipient {
+                    slot: payload.slot,
+                    payload: payload.fee_recipient,
+                })
+            }
+        }
+    }
+
+    if let Some(gas_limit) = preparation.and_then(|preparation| preparation.gas_limit) {
+        let gas_limit = gas_limit.value;
+        if gas_limit != configured.gas_limit {
+            drift.push(Drift::BeaconNodeGasLimit {
+                beacon_node: gas_limit,
+            });
+        }
+    }
+
+    drift
+}
+
+/// Fetches the proposer preparations held by `beacon_node`, keyed by validator index.
+pub async fn fetch_preparations(
+    beacon_node: &BeaconNodeHttpClient,
+) -> Result<HashMap<u64, ProposerPreparationEntry>, eth2::Error> {
+    Ok(beacon_node
+        .get_lighthouse_proposer_preparation()
+        .await?
+        .data
+        .into_iter()
+        .map(|entry| (entry.validator_index, entry))
+        .collect())
+}
+
+/// Fetches the payload of the block proposed for `duty`, if the block is on chain and contains a
+/// post-merge payload.
+pub async fn fetch_proposed_payload<E: EthSpec>(
+    beacon_node: &BeaconNodeHttpClient,
+    duty: &ProposerData,
+) -> Result<Option<ProposedPayload>, eth2::Error> {
+    let Some(response) = beacon_node
+        .get_beacon_blinded_blocks::<E>(BlockId::Slot(duty.slot))
+        .await?
+    else {
+        return Ok(None);
+    };
+    let block = response.data.message();
+    if block.slot() != duty.slot || block.proposer_index() != duty.validator_index {
+        return Ok(None);
+    }
+
+    Ok(block
+        .body()
+        .execution_payload()
+        .ok()
+        .filter(|payload| payload.block_hash() != ExecutionBlockHash::zero())
+        .map(|payload| ProposedPayload {
+            slot: duty.slot,
+            fee_recipient: payload.fee_recipient(),
+        }))
+}
+
+/// The state kept by the audit between epochs.
+pub struct FeeRecipientAudit {
+    /// The last payload found on chain for each validator.
+    proposed_payloads: HashMap<PublicKeyBytes, ProposedPayload>,
+    /// Proposals up to and including this slot have already been looked up.
+    last_checked_slot: Option<Slot>,
+    log: Logger,
+}
+
+impl FeeRecipientAudit {
+    pub fn new(log: Logger) -> Self {
+        Self {
+            proposed_payloads: HashMap::new(),
+            last_checked_slot: None,
+            log,
+        }
+    }
+
+    /// Returns the proposer duties in `proposers` which are before `current_slot` and have not yet
+    /// been looked up, marking them as looked up.
+    fn take_unchecked_duties(
+        &mut self,
+        proposers: impl IntoIterator<Item = ProposerData>,
+        current_slot: Slot,
+    ) -> Vec<ProposerData> {
+        let last_checked_slot = self.last_checked_slot;
+        let mut duties = proposers
+            .into_iter()
+            .filter(|duty| {
+                duty.slot < current_slot && last_checked_slot.map_or(true, |slot| duty.slot > slot)
+            })
+            .collect::<Vec<_>>();
+        duties.sort_by_key(|duty| duty.slot);
+        self.last_checked_slot = Some(current_slot.saturating_sub(1_u64));
+        duties
+    }
+
+    /// Records the payload proposed by `pubkey`, keeping only the latest one.
+    pub fn record_payload(&mut self, pubkey: PublicKeyBytes, payload: ProposedPayload) {
+        let latest = self.proposed_payloads.entry(pubkey).or_insert(payload);
+        if payload.slot > latest.slot {
+            *latest = payload;
+        }
+    }
+
+    /// Compares each configured validator against `preparations` and the payloads recorded so far,
+    /// logging and reporting any drift.
+    ///
+    /// Returns the validators with drift. Validators without a known index are skipped.
+    pub fn check(
+        &self,
+        configured: &[(PublicKeyBytes, ProposalData)],
+        preparations: &HashMap<u64, ProposerPreparationEntry>,
+    ) -> Vec<(PublicKeyBytes, Vec<Drift>)> {
+        let mut drifted = vec![];
+
+        for (pubkey, proposal_data) in configured {
+            let Some(validator_index) = proposal_data.validator_index else {
+                continue;
+            };
+            let drift = find_drift(
+                proposal_data,
+                preparations.get(&validator_index),
+                self.proposed_payloads.get(pubkey),
+            );
+
+            metrics::set_int_gauge(
+                &metrics::FEE_RECIPIENT_DRIFT,
+                &[&validator_index.to_string()],
+                i64::from(!drift.is_empty()),
+            );
+
+            for drift in &drift {
+                warn!(
+                    self.log,
+                    "Fee recipient or gas limit drift";
+                    "drift" => %drift,
+                    "fee_recipient" => ?proposal_data.fee_recipient,
+                    "gas_limit" => proposal_data.gas_limit,
+                    "validator_index" => validator_index,
+                    "pubkey" => ?pubkey,
+                );
+            }
+
+            if !drift.is_empty() {
+                drifted.push((*pubkey, drift));
+            }
+        }
+
+        drifted
+    }
+}
+
+/// Starts a service which audits the fee recipient and gas limit of each validator once per epoch.
+pub fn start_fee_recipient_audit_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    duties_service: Arc<DutiesService<T, E>>,
+) {
+    let log = context.log().clone();
+
+    let future = async move {
+        let mut audit = FeeRecipientAudit::new(log.clone());
+
+        loop {
+            let slot_clock = &duties_service.slot_clock;
+            // Run half-way through the first slot of each epoch, after the preparations for the
+            // epoch have been sent.
+            let sleep_time = slot_clock
+                .duration_to_next_epoch(E::slots_per_epoch())
+                .map(|next_epoch| next_epoch + slot_clock.slot_duration() / 2)
+                .unwrap_or_else(|| slot_clock.slot_duration());
+            sleep(sleep_time).await;
+
+            let Some(current_slot) = slot_clock.now() else {
+                continue;
+            };
+
+            audit_once(&mut audit, &duties_service, current_slot).await;
+        }
+    };
+
+    context.executor.spawn(future, "fee_recipient_audit");
+}
+
+async fn audit_once<T: SlotClock + 'static, E: EthSpec>(
+    audit: &mut FeeRecipientAudit,
+    duties_service: &DutiesService<T, E>,
+    current_slot: Slot,
+) {
+    let beacon_nodes: &BeaconNodeFallback<T, E> = &duties_service.beacon_nodes;
+
+    let past_duties = duties_service
+        .proposers
+        .read()
+        .values()
+        .flat_map(|(_, duties)| duties.iter().cloned())
+        .collect::<Vec<_>>();
+    for duty in audit.take_unchecked_duties(past_duties, current_slot) {
+        match beacon_nodes
+            .first_success(
+                RequireSynced::No,
+                OfflineOnFailure::No,
+                metrics::PROPOSED_PAYLOAD_HTTP_GET,
+                |beacon_node| fetch_proposed_payload::<E>(beacon_node, &duty),
+            )
+            .await
+        {
+            Ok(Some(payload)) => audit.record_payload(duty.pubkey, payload),
+            Ok(None) => (),
+            Err(e) => debug!(
+                audit.log,
+                "Unable to fetch proposed payload";
+                "slot" => duty.slot,
+                "error" => %e,
+            ),
+        }
+    }
+
+    // Non-Lighthouse beacon nodes do not serve the preparations, so failures are not worth a
+    // warning.
+    let preparations = match beacon_nodes
+        .first_success(
+            RequireSynced::No,
+            OfflineOnFailure::No,
+            metrics::PROPOSER_PREPARATION_HTTP_GET,
+            fetch_preparations,
+        )
+        .await
+    {
+        Ok(preparations) => preparations,
+        Err(e) => {
+            debug!(
+                audit.log,
+                "Unable to fetch proposer preparations";
+                "error" => %e,
+            );
+            return;
+        }
+    };
+
+    let validator_store = &duties_service.validator_store;
+    let configured = validator_store
+        .voting_pubkeys::<Vec<_>, _>(DoppelgangerStatus::ignored)
+        .into_iter()
+        .filter_map(|pubkey| Some((pubkey, validator_store.proposal_data(&pubkey)?)))
+        .collect::<Vec<_>>();
+
+    let drifted = audit.check(&configured, &preparations);
+    debug!(
+        audit.log,
+        "Audited fee recipients";
+        "validators" => configured.len(),
+        "drifted" => drifted.len(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::types::GenericResponse;
+    use eth2::Timeouts;
+    use logging::test_logger;
+    use sensitive_url::SensitiveUrl;
+    use serde_utils::quoted_u64::Quoted;
+    use std::net::{Ipv4Addr, SocketAddr};
+    use std::time::Duration;
+    use types::Epoch;
+    use warp::Filter;
+
+    const GAS_LIMIT: u64 = 30_000_000;
+
+    /// Spawns a beacon node which serves `preparations` from `lighthouse/proposer_preparation`.
+    fn spawn_beacon_node(preparations: Vec<ProposerPreparationEntry>) -> BeaconNodeHttpClient {
+        let routes = warp::path!("lighthouse" / "proposer_preparation")
+            .map(move || warp::reply::json(&GenericResponse::from(preparations.clone())));
+        let (addr, server) =
+            warp::serve(routes).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+        tokio::spawn(server);
+
+        BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(&format!("http://{}", addr)).unwrap(),
+            Timeouts::set_all(Duration::from_secs(1)),
+        )
+    }
+
+    fn configured(validator_index: u64, fee_recipient: Address) -> (PublicKeyBytes, ProposalData) {
+        (
+            PublicKeyBytes::empty(),
+            ProposalData {
+                validator_index: Some(validator_index),
+                fee_recipient: Some(fee_recipient),
+                gas_limit: GAS_LIMIT,
+                builder_proposals: false,
+            },
+        )
+    }
+
+    fn preparation(
+        validator_index: u64,
+        fee_recipient: Address,
+        gas_limit: Option<u64>,
+    ) -> ProposerPreparationEntry {
+        ProposerPreparationEntry {
+            validator_index,
+            fee_recipient,
+            update_epoch: Epoch::new(0),
+            gas_limit: gas_limit.map(|value| Quoted { value }),
+        }
+    }
+
+    fn drift_metric(validator_index: u64) -> i64 {
+        metrics::get_int_gauge(
+            &metrics::FEE_RECIPIENT_DRIFT,
+            &[&validator_index.to_string()],
+        )
+        .unwrap()
+        .get()
+    }
+
+    #[tokio::test]
+    async fn mismatched_preparation_is_detected_in_one_cycle() {
+        let validator_index = 1_000_001;
+        let fee_recipient = Address::repeat_byte(1);
+        let beacon_node = spawn_beacon_node(vec![preparation(
+            validator_index,
+            Address::repeat_byte(2),
+            Some(GAS_LIMIT + 1),
+        )]);
+
+        let audit = FeeRecipientAudit::new(test_logger());
+        let preparations = fetch_preparations(&beacon_node).await.unwrap();
+        let drifted = audit.check(&[configured(validator_index, fee_recipient)], &preparations);
+
+        assert_eq!(
+            drifted,
+            vec![(
+                PublicKeyBytes::empty(),
+                vec![
+                    Drift::BeaconNodeFeeRecipient {
+                        beacon_node: Address::repeat_byte(2)
+                    },
+                    Drift::BeaconNodeGasLimit {
+                        beacon_node: GAS_LIMIT + 1
+                    },
+                ]
+            )]
+        );
+        assert_eq!(drift_metric(validator_index), 1);
+    }
+
+    #[tokio::test]
+    async fn matching_preparation_has_no_drift() {
+        let validator_index = 1_000_002;
+        let fee_recipient = Address::repeat_byte(1);
+        let beacon_node = spawn_beacon_node(vec![preparation(
+            validator_index,
+            fee_recipient,
+            Some(GAS_LIMIT),
+        )]);
+
+        let audit = FeeRecipientAudit::new(test_logger());
+        let preparations = fetch_preparations(&beacon_node).await.unwrap();
+        let drifted = audit.check(&[configured(validator_index, fee_recipient)], &preparations);
+
+        assert!(drifted.is_empty());
+        assert_eq!(drift_metric(validator_index), 0);
+    }
+
+    #[test]
+    fn payload_fee_recipient_drift() {
+        let (_, mut proposal_data) = configured(1, Address::repeat_byte(1));
+        let preparation = preparation(1, Address::repeat_byte(1), None);
+        let payload = ProposedPayload {
+            slot: Slot::new(10),
+            fee_recipient: Address::repeat_byte(3),
+        };
+
+        assert_eq!(
+            find_drift(&proposal_data, Some(&preparation), Some(&payload)),
+            vec![Drift::PayloadFeeRecipient {
+                slot: Slot::new(10),
+                payload: Address::repeat_byte(3)
+            }]
+        );
+        assert_eq!(
+            find_drift(&proposal_data, None, None),
+            vec![Drift::MissingPreparation]
+        );
+
+        // Builders pay the proposer with a transaction, so the payload fee recipient differs.
+        proposal_data.builder_proposals = true;
+        assert!(find_drift(&proposal_data, Some(&preparation), Some(&payload)).is_empty());
+    }
+
+    #[test]
+    fn only_the_latest_payload_is_kept() {
+        let mut audit = FeeRecipientAudit::new(test_logger());
+        let pubkey = PublicKeyBytes::empty();
+        let payload = |slot| ProposedPayload {
+            slot: Slot::new(slot),
+            fee_recipient: Address::zero(),
+        };
+        let duty = |slot| ProposerData {
+            pubkey,
+            validator_index: 0,
+            slot: Slot::new(slot),
+        };
+
+        audit.record_payload(pubkey, payload(5));
+        audit.record_payload(pubkey, payload(3));
+        assert_eq!(audit.proposed_payloads[&pubkey], payload(5));
+
+        // Duties are only looked up once they are in the past, and only once.
+        let duties = audit.take_unchecked_duties(vec![duty(4), duty(6)], Slot::new(5));
+        assert_eq!(duties, vec![duty(4)]);
+        let duties = audit.take_unchecked_duties(vec![duty(4), duty(6)], Slot::new(7));
+        assert_eq!(duties, vec![duty(6)]);
+    }
+}
@@ -104,8 +104,10 @@ fn read_line(line: &str) -> Result<(Option<PublicKeyBytes>, Graffiti), Error> {
 mod tests {
     use super::*;
     use bls::Keypair;
+    use graffiti_template::{expand_graffiti, GraffitiVariables};
     use std::io::LineWriter;
     use tempfile::TempDir;
+    use types::{Epoch, Slot};
 
     const DEFAULT_GRAFFITI: &str = "lighthouse";
     const CUSTOM_GRAFFITI1: &str = "custom-graffiti1";
@@ -175,4 +177,34 @@ mod tests {
             GraffitiString::from_str(DEFAULT_GRAFFITI).unwrap().into()
         );
     }
+
+    #[test]
+    fn test_reload_graffiti_template() {
+        let graffiti_file_path = create_graffiti_file();
+        let mut gf = GraffitiFile::new(graffiti_file_path.clone());
+        let random_pk = Keypair::random().pk.compress();
+
+        assert_eq!(
+            gf.load_graffiti(&random_pk).unwrap().unwrap(),
+            GraffitiString::from_str(DEFAULT_GRAFFITI).unwrap().into()
+        );
+
+        // Templates are re-read when the file changes and expanded at proposal time.
+        let template = "LH{version}-{slot}";
+        std::fs::write(&graffiti_file_path, format!("default: {}\n", template)).unwrap();
+        let graffiti = gf.load_graffiti(&random_pk).unwrap().unwrap();
+        assert_eq!(graffiti, GraffitiString::from_str(template).unwrap().into());
+
+        let variables = GraffitiVariables {
+            slot: Slot::new(100),
+            epoch: Epoch::new(3),
+            validator_index: Some(1),
+        };
+        assert_eq!(
+            expand_graffiti(graffiti, &variables),
+            GraffitiString::from_str(&format!("LH{}-100", lighthouse_version::short_version()))
+                .unwrap()
+                .into()
+        );
+    }
 }
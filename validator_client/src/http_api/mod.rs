@@ -8,7 +8,9 @@ mod tests;
 pub mod test_utils;
 
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
-use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
+use crate::{
+    determine_graffiti, slot_timing::SlotTiming, BeaconNodeFallback, GraffitiFile, ValidatorStore,
+};
 use account_utils::{
     mnemonic_from_phrase,
     validator_definitions::{SigningDefinition, ValidatorDefinition, Web3SignerDefinition},
@@ -18,13 +20,14 @@ use create_validator::{
     create_validators_mnemonic, create_validators_web3signer, get_voting_password_storage,
 };
 use eth2::lighthouse_vc::{
-    std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse},
+    std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse, GetGraffitiResponse},
     types::{self as api_types, GenericResponse, Graffiti, PublicKey, PublicKeyBytes},
 };
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use slashing_protection::NotSafe;
 use slog::{crit, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -33,6 +36,7 @@ use std::marker::PhantomData;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
 use task_executor::TaskExecutor;
@@ -83,6 +87,8 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub log: Logger,
     pub sse_logging_components: Option<SSELoggingComponents>,
     pub slot_clock: T,
+    pub slot_timing: Option<Arc<SlotTiming>>,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     pub _phantom: PhantomData<E>,
 }
 
@@ -221,6 +227,26 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     let inner_slot_clock = ctx.slot_clock.clone();
     let slot_clock_filter = warp::any().map(move || inner_slot_clock.clone());
 
+    let inner_slot_timing = ctx.slot_timing.clone();
+    let slot_timing_filter = warp::any().map(move || inner_slot_timing.clone()).and_then(
+        |slot_timing: Option<_>| async move {
+            slot_timing.ok_or_else(|| {
+                warp_utils::reject::custom_not_found("slot timing is not initialized.".to_string())
+            })
+        },
+    );
+
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any()
+        .map(move || inner_beacon_nodes.clone())
+        .and_then(|beacon_nodes: Option<_>| async move {
+            beacon_nodes.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "beacon nodes are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_spec = Arc::new(ctx.spec.clone());
     let spec_filter = warp::any().map(move || inner_spec.clone());
 
@@ -323,6 +349,21 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/validators/signing_capabilities
+    let get_lighthouse_validators_signing_capabilities = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("signing_capabilities"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(|validator_store: Arc<ValidatorStore<T, E>>, signer| {
+            blocking_signed_json_task(signer, move || {
+                Ok(api_types::GenericResponse::from(
+                    validator_store.signing_capabilities(),
+                ))
+            })
+        });
+
     // GET lighthouse/validators/{validator_pubkey}
     let get_lighthouse_validators_pubkey = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -356,6 +397,45 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/validators/{validator_pubkey}/protection_history
+    let get_lighthouse_validators_protection_history = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("protection_history"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey, validator_store: Arc<ValidatorStore<T, E>>, signer| {
+                blocking_signed_json_task(signer, move || {
+                    let voting_pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    let summary = validator_store
+                        .slashing_protection_summary(&voting_pubkey)
+                        .map_err(|e| match e {
+                            NotSafe::UnregisteredValidator(_) => {
+                                warp_utils::reject::custom_not_found(format!(
+                                    "no slashing protection history for {:?}",
+                                    validator_pubkey
+                                ))
+                            }
+                            e => warp_utils::reject::custom_server_error(format!(
+                                "unable to read slashing protection history: {:?}",
+                                e
+                            )),
+                        })?;
+
+                    Ok(api_types::GenericResponse::from(
+                        api_types::ProtectionHistory {
+                            voting_pubkey,
+                            min_block_slot: summary.min_block_slot,
+                            min_attestation_source: summary.min_attestation_source,
+                            min_attestation_target: summary.min_attestation_target,
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -374,13 +454,37 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/ui/fallback_health
+    let get_lighthouse_ui_fallback_health = warp::path("lighthouse")
+        .and(warp::path("ui"))
+        .and(warp::path("fallback_health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter)
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        Ok(api_types::GenericResponse::from(
+                            handle.block_on(beacon_nodes.fallback_health()),
+                        ))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     let get_lighthouse_ui_graffiti = warp::path("lighthouse")
         .and(warp::path("ui"))
         .and(warp::path("graffiti"))
         .and(warp::path::end())
         .and(validator_store_filter.clone())
         .and(graffiti_file_filter.clone())
-        .and(graffiti_flag_filter)
+        .and(graffiti_flag_filter.clone())
         .and(signer.clone())
         .and(log_filter.clone())
         .and_then(
@@ -653,7 +757,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path::end())
         .and(warp::body::json())
         .and(validator_store_filter.clone())
-        .and(graffiti_file_filter)
+        .and(graffiti_file_filter.clone())
         .and(signer.clone())
         .and(task_executor_filter.clone())
         .and_then(
@@ -721,6 +825,41 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/timing
+    let get_lighthouse_timing = warp::path("lighthouse")
+        .and(warp::path("timing"))
+        .and(warp::path::end())
+        .and(slot_timing_filter.clone())
+        .and(signer.clone())
+        .and_then(|slot_timing: Arc<SlotTiming>, signer| {
+            blocking_signed_json_task(signer, move || {
+                Ok(api_types::GenericResponse::from(
+                    api_types::SlotTimingOffsets::from(slot_timing.offsets()),
+                ))
+            })
+        });
+
+    // PATCH lighthouse/timing
+    let patch_lighthouse_timing = warp::path("lighthouse")
+        .and(warp::path("timing"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(slot_timing_filter)
+        .and(signer.clone())
+        .and_then(
+            |body: api_types::TimingPatchRequest, slot_timing: Arc<SlotTiming>, signer| {
+                blocking_signed_json_task(signer, move || {
+                    slot_timing
+                        .update(
+                            body.attestation_offset_ms.map(Duration::from_millis),
+                            body.aggregate_offset_ms.map(Duration::from_millis),
+                        )
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+                    Ok(())
+                })
+            },
+        );
+
     // GET /lighthouse/auth
     let get_auth = warp::path("lighthouse").and(warp::path("auth").and(warp::path::end()));
     let get_auth = get_auth
@@ -988,6 +1127,144 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         )
         .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
 
+    // GET /eth/v1/validator/{pubkey}/graffiti
+    let get_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(graffiti_file_filter)
+        .and(graffiti_flag_filter)
+        .and(signer.clone())
+        .and(log_filter.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             graffiti_file: Option<GraffitiFile>,
+             graffiti_flag: Option<Graffiti>,
+             signer,
+             log| {
+                blocking_signed_json_task(signer, move || {
+                    if validator_store
+                        .initialized_validators()
+                        .read()
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    let pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    let graffiti = determine_graffiti(
+                        &pubkey,
+                        &log,
+                        graffiti_file,
+                        validator_store.graffiti(&pubkey),
+                        graffiti_flag,
+                    );
+                    Ok(GenericResponse::from(GetGraffitiResponse {
+                        pubkey,
+                        graffiti: graffiti.map(|g| g.as_utf8_lossy()).unwrap_or_default(),
+                    }))
+                })
+            },
+        );
+
+    // POST /eth/v1/validator/{pubkey}/graffiti
+    let post_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::body::json())
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey,
+             request: api_types::UpdateGraffitiRequest,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    let initialized_validators_rw_lock = validator_store.initialized_validators();
+                    let initialized_validators = initialized_validators_rw_lock.read();
+                    if initialized_validators
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    if initialized_validators.is_read_only(&validator_pubkey) {
+                        return Err(warp_utils::reject::custom_forbidden(format!(
+                            "the definition of validator {:?} is read-only",
+                            validator_pubkey
+                        )));
+                    }
+                    drop(initialized_validators);
+                    validator_store
+                        .initialized_validators()
+                        .write()
+                        .set_validator_graffiti(&validator_pubkey, request.graffiti)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "Error persisting graffiti: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        )
+        .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::ACCEPTED));
+
+    // DELETE /eth/v1/validator/{pubkey}/graffiti
+    let delete_graffiti = eth_v1
+        .and(warp::path("validator"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("graffiti"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |validator_pubkey: PublicKey, validator_store: Arc<ValidatorStore<T, E>>, signer| {
+                blocking_signed_json_task(signer, move || {
+                    let initialized_validators_rw_lock = validator_store.initialized_validators();
+                    let initialized_validators = initialized_validators_rw_lock.read();
+                    if initialized_validators
+                        .is_enabled(&validator_pubkey)
+                        .is_none()
+                    {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "no validator found with pubkey {:?}",
+                            validator_pubkey
+                        )));
+                    }
+                    if initialized_validators.is_read_only(&validator_pubkey) {
+                        return Err(warp_utils::reject::custom_forbidden(format!(
+                            "the definition of validator {:?} is read-only",
+                            validator_pubkey
+                        )));
+                    }
+                    drop(initialized_validators);
+                    validator_store
+                        .initialized_validators()
+                        .write()
+                        .delete_validator_graffiti(&validator_pubkey)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "Error persisting graffiti removal: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        )
+        .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
+
     // POST /eth/v1/validator/{pubkey}/voluntary_exit
     let post_validators_voluntary_exits = eth_v1
         .and(warp::path("validator"))
@@ -1170,13 +1447,18 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_health)
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
+                        .or(get_lighthouse_validators_signing_capabilities)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_validators_protection_history)
                         .or(get_lighthouse_ui_health)
+                        .or(get_lighthouse_ui_fallback_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
+                        .or(get_graffiti)
                         .or(get_std_keystores)
                         .or(get_std_remotekeys)
+                        .or(get_lighthouse_timing)
                         .recover(warp_utils::reject::handle_rejection),
                 )
                 .or(warp::post().and(
@@ -1187,16 +1469,21 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_validators_voluntary_exits)
                         .or(post_fee_recipient)
                         .or(post_gas_limit)
+                        .or(post_graffiti)
                         .or(post_std_keystores)
                         .or(post_std_remotekeys)
                         .recover(warp_utils::reject::handle_rejection),
                 ))
-                .or(warp::patch()
-                    .and(patch_validators.recover(warp_utils::reject::handle_rejection)))
+                .or(warp::patch().and(
+                    patch_validators
+                        .or(patch_lighthouse_timing)
+                        .recover(warp_utils::reject::handle_rejection),
+                ))
                 .or(warp::delete().and(
                     delete_lighthouse_keystores
                         .or(delete_fee_recipient)
                         .or(delete_gas_limit)
+                        .or(delete_graffiti)
                         .or(delete_std_keystores)
                         .or(delete_std_remotekeys)
                         .recover(warp_utils::reject::handle_rejection),
@@ -70,6 +70,15 @@ impl ApiTester {
     }
 
     pub async fn new_with_http_config(http_config: HttpConfig) -> Self {
+        Self::new_with_genesis_validators_root(http_config, Hash256::repeat_byte(42)).await
+    }
+
+    /// Creates a validator client which signs messages for the chain with
+    /// `genesis_validators_root`, so they can be verified by a beacon node on that chain.
+    pub async fn new_with_genesis_validators_root(
+        http_config: HttpConfig,
+        genesis_validators_root: Hash256,
+    ) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
@@ -108,7 +117,7 @@ impl ApiTester {
         let validator_store = Arc::new(ValidatorStore::<_, E>::new(
             initialized_validators,
             slashing_protection,
-            Hash256::repeat_byte(42),
+            genesis_validators_root,
             spec,
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
             slot_clock.clone(),
@@ -136,6 +145,8 @@ impl ApiTester {
             log,
             sse_logging_components: None,
             slot_clock,
+            slot_timing: None,
+            beacon_nodes: None,
             _phantom: PhantomData,
         });
         let ctx = context;
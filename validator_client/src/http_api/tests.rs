@@ -3,37 +3,51 @@
 
 mod keystores;
 
+use crate::attestation_service::AttestationServiceBuilder;
+use crate::beacon_node_fallback::{BeaconNodeFallback, CandidateBeaconNode};
+use crate::block_service::{BlockServiceBuilder, BlockServiceNotification};
 use crate::doppelganger_service::DoppelgangerService;
+use crate::duties_service::{sync::poll_sync_committee_duties, DutiesService, DutyAndProof};
+use crate::preparation_service::PreparationServiceBuilder;
+use crate::selection_proof_hook::LocalSelectionProofs;
+use crate::sync_committee_service::SyncCommitteeService;
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
     initialized_validators::InitializedValidators,
-    Config, ValidatorDefinitions, ValidatorStore,
+    signing_capabilities::probe_signing_capabilities,
+    signing_method::{MessageType, REFUSALS_BEFORE_REFUSED},
+    slot_timing::SlotTiming,
+    Config, GraffitiFile, ValidatorDefinitions, ValidatorStore,
 };
 use account_utils::{
     eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, ZeroizeString,
+    random_password_string, validator_definitions::CONFIG_FILENAME, ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
+use environment::RuntimeContext;
 use eth2::{
     lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
-    types::ErrorMessage as ApiErrorMessage,
-    Error as ApiError,
+    types::{AttesterData, ErrorMessage as ApiErrorMessage},
+    BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
+use eth2_config::Eth2Config;
 use eth2_keystore::KeystoreBuilder;
 use logging::test_logger;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use slot_clock::{SlotClock, TestingSlotClock};
+use std::collections::HashMap;
 use std::future::Future;
 use std::marker::PhantomData;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use task_executor::test_utils::TestRuntime;
 use tempfile::{tempdir, TempDir};
 use types::graffiti::GraffitiString;
+use warp::{http::StatusCode, path::FullPath, Filter};
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -46,12 +60,18 @@ struct ApiTester {
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
     slot_clock: TestingSlotClock,
-    _validator_dir: TempDir,
-    _test_runtime: TestRuntime,
+    graffiti_file: Option<GraffitiFile>,
+    graffiti_flag: Option<Graffiti>,
+    validator_dir: TempDir,
+    test_runtime: TestRuntime,
 }
 
 impl ApiTester {
     pub async fn new() -> Self {
+        Self::new_with_graffiti_file(None).await
+    }
+
+    pub async fn new_with_graffiti_file(graffiti_file: Option<GraffitiFile>) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
@@ -107,13 +127,16 @@ impl ApiTester {
 
         let initialized_validators = validator_store.initialized_validators();
 
+        let slot_timing =
+            Arc::new(SlotTiming::new(Duration::from_secs(12), None, None, log.clone()).unwrap());
+
         let context = Arc::new(Context {
             task_executor: test_runtime.task_executor.clone(),
             api_secret,
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
-            graffiti_file: None,
+            graffiti_file: graffiti_file.clone(),
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
             config: HttpConfig {
@@ -127,6 +150,8 @@ impl ApiTester {
             sse_logging_components: None,
             log,
             slot_clock: slot_clock.clone(),
+            slot_timing: Some(slot_timing),
+            beacon_nodes: None,
             _phantom: PhantomData,
         });
         let ctx = context.clone();
@@ -150,8 +175,10 @@ impl ApiTester {
             validator_store,
             url,
             slot_clock,
-            _validator_dir: validator_dir,
-            _test_runtime: test_runtime,
+            graffiti_file,
+            graffiti_flag: Some(Graffiti::default()),
+            validator_dir,
+            test_runtime,
         }
     }
 
@@ -573,6 +600,188 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_protection_history(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        // A new validator has no signing history, so no low watermarks.
+        assert_eq!(
+            self.client
+                .get_lighthouse_validators_protection_history(&validator.voting_pubkey)
+                .await
+                .unwrap()
+                .unwrap()
+                .data,
+            ProtectionHistory {
+                voting_pubkey: validator.voting_pubkey,
+                min_block_slot: None,
+                min_attestation_source: None,
+                min_attestation_target: None,
+            }
+        );
+
+        // An unknown key has no history at all.
+        assert_eq!(
+            self.client
+                .get_lighthouse_validators_protection_history(&Keypair::random().pk.compress())
+                .await
+                .unwrap(),
+            None
+        );
+
+        self
+    }
+
+    pub async fn test_signing_capabilities(self) -> Self {
+        let held = Keypair::random();
+        let not_held = Keypair::random();
+        let (url, _) = spawn_mock_web3signer(vec![held.clone()], "SYNC_COMMITTEE_MESSAGE");
+
+        let request = [&held, &not_held]
+            .into_iter()
+            .map(|keypair| Web3SignerValidatorRequest {
+                enable: true,
+                description: String::new(),
+                graffiti: None,
+                suggested_fee_recipient: None,
+                gas_limit: None,
+                builder_proposals: None,
+                voting_public_key: keypair.pk.clone(),
+                url: url.clone(),
+                root_certificate_path: None,
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            })
+            .collect::<Vec<_>>();
+        self.client
+            .post_lighthouse_validators_web3signer(&request)
+            .await
+            .unwrap();
+
+        let capabilities = |pubkey: PublicKeyBytes| {
+            let client = self.client.clone();
+            async move {
+                client
+                    .get_lighthouse_validators_signing_capabilities()
+                    .await
+                    .unwrap()
+                    .data
+                    .into_iter()
+                    .find(|capabilities| capabilities.voting_pubkey == pubkey)
+                    .unwrap()
+            }
+        };
+
+        // Like a real signing request, a probe must be refused repeatedly before its type is
+        // refused.
+        let held_pubkey = held.pk.compress();
+        for i in 1..=REFUSALS_BEFORE_REFUSED {
+            probe_signing_capabilities(&self.validator_store, &test_logger()).await;
+            let expected = if i < REFUSALS_BEFORE_REFUSED {
+                SigningCapability::Unknown
+            } else {
+                SigningCapability::Refused
+            };
+            assert_eq!(
+                capabilities(held_pubkey).await.capabilities["SYNC_COMMITTEE_MESSAGE"],
+                expected
+            );
+        }
+
+        // Only the refused message type is refused, and messages which are not probed are unknown.
+        let held_capabilities = capabilities(held_pubkey).await;
+        assert!(held_capabilities.remote_signer);
+        for (message_type, capability) in &held_capabilities.capabilities {
+            let expected = match message_type.as_str() {
+                "SYNC_COMMITTEE_MESSAGE" => SigningCapability::Refused,
+                "AGGREGATE_AND_PROOF" | "ATTESTATION" | "BLOCK_V2" | "VOLUNTARY_EXIT" => {
+                    SigningCapability::Unknown
+                }
+                _ => SigningCapability::Allowed,
+            };
+            assert_eq!(*capability, expected, "{}", message_type);
+        }
+        assert!(!self
+            .validator_store
+            .can_sign(&held_pubkey, MessageType::SyncCommitteeMessage));
+        assert!(self
+            .validator_store
+            .can_sign(&held_pubkey, MessageType::Attestation));
+
+        // Real signing requests are recorded.
+        self.validator_store
+            .sign_voluntary_exit(
+                held_pubkey,
+                VoluntaryExit {
+                    epoch: Epoch::new(0),
+                    validator_index: 0,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            capabilities(held_pubkey).await.capabilities["VOLUNTARY_EXIT"],
+            SigningCapability::Allowed
+        );
+
+        // A key the signer does not hold can't sign anything.
+        let not_held_capabilities = capabilities(not_held.pk.compress()).await;
+        assert!(not_held_capabilities
+            .capabilities
+            .values()
+            .all(|capability| *capability == SigningCapability::Refused));
+
+        // A real signing request must be refused repeatedly before its type is refused.
+        let exiting = Keypair::random();
+        let exiting_pubkey = exiting.pk.compress();
+        let (url, _) = spawn_mock_web3signer(vec![exiting.clone()], "VOLUNTARY_EXIT");
+        self.client
+            .post_lighthouse_validators_web3signer(&[Web3SignerValidatorRequest {
+                enable: true,
+                description: String::new(),
+                graffiti: None,
+                suggested_fee_recipient: None,
+                gas_limit: None,
+                builder_proposals: None,
+                voting_public_key: exiting.pk.clone(),
+                url,
+                root_certificate_path: None,
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            }])
+            .await
+            .unwrap();
+        let voluntary_exit = VoluntaryExit {
+            epoch: Epoch::new(0),
+            validator_index: 0,
+        };
+        for i in 1..=REFUSALS_BEFORE_REFUSED {
+            self.validator_store
+                .sign_voluntary_exit(exiting_pubkey, voluntary_exit.clone())
+                .await
+                .unwrap_err();
+            let expected = if i < REFUSALS_BEFORE_REFUSED {
+                SigningCapability::Unknown
+            } else {
+                SigningCapability::Refused
+            };
+            assert_eq!(
+                capabilities(exiting_pubkey).await.capabilities["VOLUNTARY_EXIT"],
+                expected
+            );
+        }
+
+        // The next probe forgets the refusal so that the next exit is tried again.
+        probe_signing_capabilities(&self.validator_store, &test_logger()).await;
+        assert_eq!(
+            capabilities(exiting_pubkey).await.capabilities["VOLUNTARY_EXIT"],
+            SigningCapability::Unknown
+        );
+
+        self
+    }
+
     pub async fn assert_gas_limit(self, index: usize, gas_limit: u64) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
 
@@ -640,6 +849,534 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn post_graffiti(self, index: usize, graffiti: &str) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let response = self
+            .client
+            .post_graffiti(
+                &validator.voting_pubkey,
+                &UpdateGraffitiRequest {
+                    graffiti: GraffitiString::from_str(graffiti).unwrap(),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 202);
+
+        self
+    }
+
+    pub async fn delete_graffiti(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let response = self
+            .client
+            .delete_graffiti(&validator.voting_pubkey)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 204);
+
+        self
+    }
+
+    pub async fn assert_get_graffiti(self, index: usize, graffiti: &str) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        assert_eq!(
+            self.client
+                .get_graffiti(&validator.voting_pubkey)
+                .await
+                .unwrap(),
+            GetGraffitiResponse {
+                pubkey: validator.voting_pubkey,
+                graffiti: graffiti.to_string(),
+            }
+        );
+
+        self
+    }
+
+    pub async fn assert_graffiti_not_found(self) -> Self {
+        let pubkey = Keypair::random().pk.compress();
+        let request = UpdateGraffitiRequest {
+            graffiti: GraffitiString::from_str("nobody").unwrap(),
+        };
+        let errors = [
+            self.client.get_graffiti(&pubkey).await.unwrap_err(),
+            self.client
+                .post_graffiti(&pubkey, &request)
+                .await
+                .unwrap_err(),
+            self.client.delete_graffiti(&pubkey).await.unwrap_err(),
+        ];
+        for err in errors {
+            assert_eq!(err.status().unwrap(), 404);
+        }
+
+        self
+    }
+
+    /// Makes the validator definitions file read-only, or writable again.
+    pub fn set_definitions_read_only(self, read_only: bool) -> Self {
+        let path = self.validator_dir.path().join(CONFIG_FILENAME);
+        let mut permissions = std::fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(read_only);
+        std::fs::set_permissions(&path, permissions).unwrap();
+
+        self
+    }
+
+    pub async fn assert_graffiti_forbidden(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let request = UpdateGraffitiRequest {
+            graffiti: GraffitiString::from_str("read only").unwrap(),
+        };
+        let errors = [
+            self.client
+                .post_graffiti(&validator.voting_pubkey, &request)
+                .await
+                .unwrap_err(),
+            self.client
+                .delete_graffiti(&validator.voting_pubkey)
+                .await
+                .unwrap_err(),
+        ];
+        for err in errors {
+            assert_eq!(err.status().unwrap(), 403);
+        }
+
+        self
+    }
+
+    /// Returns the runtime context and beacon nodes of a service using the beacon node at
+    /// `beacon_node_url`.
+    fn service_context(
+        &self,
+        beacon_node_url: &str,
+        spec: &ChainSpec,
+    ) -> (
+        RuntimeContext<E>,
+        Arc<BeaconNodeFallback<TestingSlotClock, E>>,
+    ) {
+        let beacon_node = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(beacon_node_url).unwrap(),
+            Timeouts::set_all(Duration::from_secs(1)),
+        );
+        let beacon_nodes = BeaconNodeFallback::new(
+            vec![CandidateBeaconNode::new(beacon_node)],
+            vec![],
+            spec.clone(),
+            test_logger(),
+        );
+        let context = RuntimeContext {
+            executor: self.test_runtime.task_executor.clone(),
+            eth_spec_instance: E::default(),
+            eth2_config: Eth2Config {
+                eth_spec_id: EthSpecId::Mainnet,
+                spec: spec.clone(),
+            },
+            eth2_network_config: None,
+            sse_logging_components: None,
+            log_filters: None,
+        };
+        (context, Arc::new(beacon_nodes))
+    }
+
+    /// Adds a validator with index `index` whose Web3Signer refuses to sign `refused_type`,
+    /// returning its key and a channel which receives the type of each message its signer is asked
+    /// to sign.
+    ///
+    /// The signer is probed until the refused type is known to be refused.
+    async fn add_refusing_web3signer_validator(
+        &self,
+        index: u64,
+        refused_type: &'static str,
+    ) -> (PublicKeyBytes, tokio::sync::mpsc::UnboundedReceiver<String>) {
+        let keypair = Keypair::random();
+        let (url, mut requested_types_rx) =
+            spawn_mock_web3signer(vec![keypair.clone()], refused_type);
+        self.client
+            .post_lighthouse_validators_web3signer(&[Web3SignerValidatorRequest {
+                enable: true,
+                description: String::new(),
+                graffiti: None,
+                suggested_fee_recipient: None,
+                gas_limit: None,
+                builder_proposals: Some(true),
+                voting_public_key: keypair.pk.clone(),
+                url,
+                root_certificate_path: None,
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            }])
+            .await
+            .unwrap();
+        let pubkey = keypair.pk.compress();
+        self.initialized_validators
+            .write()
+            .set_index(&pubkey, index);
+
+        for _ in 0..REFUSALS_BEFORE_REFUSED {
+            probe_signing_capabilities(&self.validator_store, &test_logger()).await;
+        }
+        drain(&mut requested_types_rx);
+
+        (pubkey, requested_types_rx)
+    }
+
+    /// Checks that a `SyncCommitteeService` neither signs nor subscribes for a sync committee
+    /// member whose signer refuses sync committee messages.
+    pub async fn test_sync_committee_skips_refused_validator(self) -> Self {
+        let (allowed, mut allowed_types_rx) = self
+            .add_refusing_web3signer_validator(0, "VOLUNTARY_EXIT")
+            .await;
+        let (refused, mut refused_types_rx) = self
+            .add_refusing_web3signer_validator(1, "SYNC_COMMITTEE_MESSAGE")
+            .await;
+        assert!(self
+            .validator_store
+            .can_sign(&allowed, MessageType::SyncCommitteeMessage));
+        assert!(!self
+            .validator_store
+            .can_sign(&refused, MessageType::SyncCommitteeMessage));
+
+        // Match the one second slots of the slot clock, starting in Altair.
+        let mut spec = E::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(0));
+        spec.seconds_per_slot = 1;
+
+        let sync_duties = [allowed, refused]
+            .into_iter()
+            .zip(0..)
+            .map(|(pubkey, validator_index)| SyncDuty {
+                pubkey,
+                validator_index,
+                validator_sync_committee_indices: vec![validator_index],
+            })
+            .collect();
+        let (beacon_node_url, mut request_rx) = spawn_mock_duties_beacon_node(&spec, sync_duties);
+        let (context, beacon_nodes) = self.service_context(&beacon_node_url, &spec);
+        let duties_service = Arc::new(DutiesService {
+            attesters: <_>::default(),
+            proposers: <_>::default(),
+            sync_duties: <_>::default(),
+            attestation_selection_proofs: <_>::default(),
+            sync_selection_proofs: <_>::default(),
+            selection_proof_hook: Arc::new(LocalSelectionProofs),
+            distributed: false,
+            slot_clock: self.slot_clock.clone(),
+            slot_timing: Arc::new(
+                SlotTiming::new(Duration::from_secs(1), None, None, test_logger()).unwrap(),
+            ),
+            beacon_nodes: beacon_nodes.clone(),
+            validator_store: self.validator_store.clone(),
+            spec: spec.clone(),
+            context: context.clone(),
+            enable_high_validator_count_metrics: false,
+            proposer_duties_events: false,
+            proposer_duties_events_connected: <_>::default(),
+            proposer_duties_refetch: <_>::default(),
+        });
+        poll_sync_committee_duties(&duties_service).await.unwrap();
+
+        SyncCommitteeService::new(
+            duties_service,
+            self.validator_store.clone(),
+            self.slot_clock.clone(),
+            beacon_nodes,
+            context,
+        )
+        .start_update_service(&spec)
+        .unwrap();
+
+        let mut signers = None;
+        let mut subscribers = None;
+        while signers.is_none() || subscribers.is_none() {
+            let (path, body) = tokio::time::timeout(Duration::from_secs(10), request_rx.recv())
+                .await
+                .expect("sync committee duties should be performed")
+                .unwrap();
+            match path.as_str() {
+                "/eth/v1/beacon/pool/sync_committees" => {
+                    let messages: Vec<SyncCommitteeMessage> = serde_json::from_value(body).unwrap();
+                    signers = Some(
+                        messages
+                            .iter()
+                            .map(|message| message.validator_index)
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                "/eth/v1/validator/sync_committee_subscriptions" => {
+                    let subscriptions: Vec<SyncCommitteeSubscription> =
+                        serde_json::from_value(body).unwrap();
+                    subscribers = Some(
+                        subscriptions
+                            .iter()
+                            .map(|subscription| subscription.validator_index)
+                            .collect::<Vec<_>>(),
+                    );
+                }
+                _ => (),
+            }
+        }
+        assert_eq!(signers, Some(vec![0]));
+        assert_eq!(subscribers, Some(vec![0]));
+
+        let sync_committee_message = "SYNC_COMMITTEE_MESSAGE".to_string();
+        assert!(drain(&mut allowed_types_rx).contains(&sync_committee_message));
+        assert!(!drain(&mut refused_types_rx).contains(&sync_committee_message));
+
+        self
+    }
+
+    /// Checks that a `PreparationService` does not register a validator whose signer refuses
+    /// validator registrations.
+    pub async fn test_preparation_skips_refused_validator(self) -> Self {
+        let (allowed, _) = self
+            .add_refusing_web3signer_validator(0, "VOLUNTARY_EXIT")
+            .await;
+        let (refused, mut refused_types_rx) = self
+            .add_refusing_web3signer_validator(1, "VALIDATOR_REGISTRATION")
+            .await;
+        assert!(self
+            .validator_store
+            .can_sign(&allowed, MessageType::ValidatorRegistration));
+        assert!(!self
+            .validator_store
+            .can_sign(&refused, MessageType::ValidatorRegistration));
+
+        let spec = E::default_spec();
+        let (beacon_node_url, mut request_rx) = spawn_mock_duties_beacon_node(&spec, vec![]);
+        let (context, beacon_nodes) = self.service_context(&beacon_node_url, &spec);
+        PreparationServiceBuilder::new()
+            .slot_clock(self.slot_clock.clone())
+            .validator_store(self.validator_store.clone())
+            .beacon_nodes(beacon_nodes)
+            .runtime_context(context)
+            .validator_registration_batch_size(500)
+            .build()
+            .unwrap()
+            .start_validator_registration_service(&spec)
+            .unwrap();
+
+        let registrations = loop {
+            let (path, body) = tokio::time::timeout(Duration::from_secs(10), request_rx.recv())
+                .await
+                .expect("validators should be registered")
+                .unwrap();
+            if path == "/eth/v1/validator/register_validator" {
+                break serde_json::from_value::<Vec<SignedValidatorRegistrationData>>(body)
+                    .unwrap();
+            }
+        };
+        let registered = registrations
+            .iter()
+            .map(|registration| registration.message.pubkey)
+            .collect::<Vec<_>>();
+        assert_eq!(registered, vec![allowed]);
+        assert!(!drain(&mut refused_types_rx).contains(&"VALIDATOR_REGISTRATION".to_string()));
+
+        self
+    }
+
+    /// Has a `BlockService` propose a block for the validator at `index`, checking the graffiti
+    /// it requests the block with.
+    pub async fn assert_proposal_graffiti(self, index: usize, graffiti: &str) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let spec = E::default_spec();
+
+        let (beacon_node_url, mut graffiti_rx) = spawn_mock_beacon_node(&spec);
+        let (context, beacon_nodes) = self.service_context(&beacon_node_url, &spec);
+        let block_service = BlockServiceBuilder::new()
+            .validator_store(self.validator_store.clone())
+            .slot_clock(self.slot_clock.clone())
+            .beacon_nodes(beacon_nodes)
+            .runtime_context(context)
+            .graffiti(self.graffiti_flag)
+            .graffiti_file(self.graffiti_file.clone())
+            .build()
+            .unwrap();
+        let (notification_tx, notification_rx) = tokio::sync::mpsc::channel(1);
+        block_service.start_update_service(notification_rx).unwrap();
+
+        // Blocks aren't produced at genesis.
+        let slot = Slot::new(1);
+        self.slot_clock.set_slot(slot.as_u64());
+        notification_tx
+            .send(BlockServiceNotification {
+                slot,
+                block_proposers: vec![validator.voting_pubkey],
+            })
+            .await
+            .unwrap();
+
+        let requested_graffiti = tokio::time::timeout(Duration::from_secs(10), graffiti_rx.recv())
+            .await
+            .expect("block should be requested")
+            .unwrap();
+        let expected_graffiti = Graffiti::from(GraffitiString::from_str(graffiti).unwrap());
+        assert_eq!(requested_graffiti, Some(expected_graffiti.to_string()));
+
+        self
+    }
+
+    /// Checks that an `AttestationService` produces attestations and aggregates at the configured
+    /// offsets into the slot, driving its slot clock in step with real time.
+    pub async fn test_attestation_service_timing(self) -> Self {
+        let attestation_offset = Duration::from_millis(200);
+        let aggregate_offset = Duration::from_millis(600);
+
+        // Match the one second slots of the slot clock.
+        let mut spec = E::default_spec();
+        spec.seconds_per_slot = 1;
+
+        let (beacon_node_url, mut request_rx) = spawn_mock_attestation_beacon_node(&spec);
+        let (context, beacon_nodes) = self.service_context(&beacon_node_url, &spec);
+        let slot_timing = Arc::new(
+            SlotTiming::new(
+                Duration::from_secs(1),
+                Some(attestation_offset),
+                Some(aggregate_offset),
+                test_logger(),
+            )
+            .unwrap(),
+        );
+        let duties_service = Arc::new(DutiesService {
+            attesters: <_>::default(),
+            proposers: <_>::default(),
+            sync_duties: <_>::default(),
+            attestation_selection_proofs: <_>::default(),
+            sync_selection_proofs: <_>::default(),
+            selection_proof_hook: Arc::new(LocalSelectionProofs),
+            distributed: false,
+            slot_clock: self.slot_clock.clone(),
+            slot_timing: slot_timing.clone(),
+            beacon_nodes: beacon_nodes.clone(),
+            validator_store: self.validator_store.clone(),
+            spec: spec.clone(),
+            context: context.clone(),
+            enable_high_validator_count_metrics: false,
+            proposer_duties_events: false,
+            proposer_duties_events_connected: <_>::default(),
+            proposer_duties_refetch: <_>::default(),
+        });
+
+        // Give the first validator the only seat in a committee at slot 2, so that it also
+        // aggregates.
+        let pubkey = self.client.get_lighthouse_validators().await.unwrap().data[0].voting_pubkey;
+        let slot = Slot::new(2);
+        let duty = AttesterData {
+            pubkey,
+            validator_index: 0,
+            committees_at_slot: 1,
+            committee_index: 0,
+            committee_length: 1,
+            validator_committee_index: 0,
+            slot,
+        };
+        let duty_and_proof = DutyAndProof::new_with_selection_proof(
+            duty,
+            &self.validator_store,
+            &duties_service.attestation_selection_proofs,
+            &LocalSelectionProofs,
+            &spec,
+        )
+        .await
+        .unwrap();
+        assert!(duty_and_proof.selection_proof.is_some());
+        duties_service
+            .attesters
+            .write()
+            .entry(pubkey)
+            .or_default()
+            .insert(
+                slot.epoch(E::slots_per_epoch()),
+                (Hash256::zero(), duty_and_proof),
+            );
+
+        // Start at the beginning of slot 1 and keep the slot clock in step with real time.
+        let slot_clock = self.slot_clock.clone();
+        let genesis_to_start = Duration::from_secs(1);
+        slot_clock.set_current_time(genesis_to_start);
+        let start = Instant::now();
+        tokio::spawn(async move {
+            loop {
+                slot_clock.set_current_time(genesis_to_start + start.elapsed());
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        });
+
+        AttestationServiceBuilder::new()
+            .duties_service(duties_service)
+            .validator_store(self.validator_store.clone())
+            .slot_clock(self.slot_clock.clone())
+            .slot_timing(slot_timing)
+            .beacon_nodes(beacon_nodes)
+            .runtime_context(context)
+            .build()
+            .unwrap()
+            .start_update_service(&spec)
+            .unwrap();
+
+        // Each request should be made at its offset into slot 2, allowing for some scheduling delay.
+        let slot_start = Duration::from_secs(slot.as_u64());
+        let expected_requests = [
+            (
+                "/eth/v1/validator/attestation_data",
+                slot_start + attestation_offset,
+            ),
+            (
+                "/eth/v1/validator/aggregate_attestation",
+                slot_start + aggregate_offset,
+            ),
+        ];
+        for (expected_path, expected) in expected_requests {
+            let request_instant = loop {
+                let (path, instant) =
+                    tokio::time::timeout(Duration::from_secs(10), request_rx.recv())
+                        .await
+                        .expect("attestation duties should be performed")
+                        .unwrap();
+                if path == expected_path {
+                    break instant;
+                }
+            };
+            let actual = genesis_to_start + request_instant.duration_since(start);
+            assert!(
+                actual >= expected && actual < expected + Duration::from_millis(150),
+                "expected {} to be requested {:?} after genesis, not {:?}",
+                expected_path,
+                expected,
+                actual,
+            );
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_timing(self, expected: SlotTimingOffsets) -> Self {
+        let timing = self.client.get_lighthouse_timing().await.unwrap().data;
+        assert_eq!(timing, expected);
+
+        self
+    }
+
+    pub async fn set_lighthouse_timing(self, request: TimingPatchRequest) -> Self {
+        self.client.patch_lighthouse_timing(&request).await.unwrap();
+
+        self
+    }
+
+    pub async fn test_invalid_lighthouse_timing(self, request: TimingPatchRequest) -> Self {
+        let err = self
+            .client
+            .patch_lighthouse_timing(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 400);
+
+        self
+    }
 }
 
 struct HdValidatorScenario {
@@ -659,6 +1396,211 @@ struct Web3SignerValidatorScenario {
     enabled: bool,
 }
 
+/// The endpoints a `BeaconNodeFallback` checks before sending requests to a beacon node.
+fn mock_beacon_node_status(
+    spec: &ChainSpec,
+) -> impl Filter<Extract = (impl warp::Reply,), Error = warp::Rejection> + Clone {
+    let config = types::Config::from_chain_spec::<E>(spec);
+
+    let get_version = warp::path!("eth" / "v1" / "node" / "version")
+        .and(warp::get())
+        .map(|| warp::reply::json(&serde_json::json!({ "data": { "version": "mock" } })));
+    let get_spec = warp::path!("eth" / "v1" / "config" / "spec")
+        .and(warp::get())
+        .map(move || warp::reply::json(&serde_json::json!({ "data": config })));
+
+    get_version.or(get_spec)
+}
+
+/// Spawns a beacon node which serves just enough of the API for a `BlockService` to request a
+/// block, returning its URL and a channel which receives the graffiti of each block request.
+///
+/// Block requests are refused, so that no block is signed.
+fn spawn_mock_beacon_node(
+    spec: &ChainSpec,
+) -> (String, tokio::sync::mpsc::UnboundedReceiver<Option<String>>) {
+    let (graffiti_tx, graffiti_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let get_block = warp::path!("eth" / "v2" / "validator" / "blocks" / u64)
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |_slot: u64, query: HashMap<String, String>| {
+            let _ = graffiti_tx.send(query.get("graffiti").cloned());
+            warp::reply::with_status(
+                warp::reply::json(&"not producing blocks"),
+                StatusCode::SERVICE_UNAVAILABLE,
+            )
+        });
+
+    let (addr, server) = warp::serve(mock_beacon_node_status(spec).or(get_block))
+        .bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+    (format!("http://{}", addr), graffiti_rx)
+}
+
+/// Spawns a beacon node which serves `sync_duties` and a non-optimistic head block, returning its
+/// URL and a channel which receives the path and body of every other `POST` request.
+fn spawn_mock_duties_beacon_node(
+    spec: &ChainSpec,
+    sync_duties: Vec<SyncDuty>,
+) -> (
+    String,
+    tokio::sync::mpsc::UnboundedReceiver<(String, serde_json::Value)>,
+) {
+    let (request_tx, request_rx) = tokio::sync::mpsc::unbounded_channel();
+
+    let post_sync_duties = warp::path!("eth" / "v1" / "validator" / "duties" / "sync" / u64)
+        .and(warp::post())
+        .map(move |_epoch: u64| {
+            warp::reply::json(&serde_json::json!({
+                "execution_optimistic": false,
+                "data": sync_duties,
+            }))
+        });
+    let get_head_root = warp::path!("eth" / "v1" / "beacon" / "blocks" / "head" / "root")
+        .and(warp::get())
+        .map(|| {
+            warp::reply::json(&serde_json::json!({
+                "execution_optimistic": false,
+                "finalized": false,
+                "data": { "root": Hash256::repeat_byte(1) },
+            }))
+        });
+    let post_other = warp::path::full()
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(move |path: FullPath, body: serde_json::Value| {
+            let _ = request_tx.send((path.as_str().to_string(), body));
+            warp::reply()
+        });
+
+    let routes = mock_beacon_node_status(spec)
+        .or(post_sync_duties)
+        .or(get_head_root)
+        .or(post_other);
+    let (addr, server) =
+        warp::serve(routes).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+    (format!("http://{}", addr), request_rx)
+}
+
+/// Spawns a beacon node which serves attestation data for any slot and committee, returning its URL
+/// and a channel which receives the path and arrival time of every attestation data and aggregate
+/// request.
+///
+/// Published attestations are accepted, and aggregates are never available.
+fn spawn_mock_attestation_beacon_node(
+    spec: &ChainSpec,
+) -> (
+    String,
+    tokio::sync::mpsc::UnboundedReceiver<(String, Instant)>,
+) {
+    let (request_tx, request_rx) = tokio::sync::mpsc::unbounded_channel();
+    let aggregate_request_tx = request_tx.clone();
+
+    let get_attestation_data = warp::path!("eth" / "v1" / "validator" / "attestation_data")
+        .and(warp::get())
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |query: HashMap<String, String>| {
+            let _ = request_tx.send((
+                "/eth/v1/validator/attestation_data".to_string(),
+                Instant::now(),
+            ));
+            let checkpoint = Checkpoint {
+                epoch: Epoch::new(0),
+                root: Hash256::zero(),
+            };
+            let attestation_data = AttestationData {
+                slot: Slot::new(query["slot"].parse().unwrap()),
+                index: query["committee_index"].parse().unwrap(),
+                beacon_block_root: Hash256::repeat_byte(1),
+                source: checkpoint,
+                target: checkpoint,
+            };
+            warp::reply::json(&serde_json::json!({ "data": attestation_data }))
+        });
+    let get_aggregate = warp::path!("eth" / "v1" / "validator" / "aggregate_attestation")
+        .and(warp::get())
+        .map(move || {
+            let _ = aggregate_request_tx.send((
+                "/eth/v1/validator/aggregate_attestation".to_string(),
+                Instant::now(),
+            ));
+            warp::reply::with_status(
+                warp::reply::json(&"no aggregate available"),
+                StatusCode::NOT_FOUND,
+            )
+        });
+    let post_any = warp::post().map(warp::reply);
+
+    let routes = mock_beacon_node_status(spec)
+        .or(get_attestation_data)
+        .or(get_aggregate)
+        .or(post_any);
+    let (addr, server) =
+        warp::serve(routes).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+    (format!("http://{}", addr), request_rx)
+}
+
+/// Spawns a Web3Signer which holds the keys of `keypairs` and refuses to sign messages of
+/// `refused_type`, returning its URL and a channel which receives the type of each message it is
+/// asked to sign.
+fn spawn_mock_web3signer(
+    keypairs: Vec<Keypair>,
+    refused_type: &'static str,
+) -> (String, tokio::sync::mpsc::UnboundedReceiver<String>) {
+    let (requested_types_tx, requested_types_rx) = tokio::sync::mpsc::unbounded_channel();
+    let public_keys = keypairs
+        .iter()
+        .map(|keypair| keypair.pk.compress())
+        .collect::<Vec<_>>();
+    let get_public_keys = warp::path!("api" / "v1" / "eth2" / "publicKeys")
+        .and(warp::get())
+        .map(move || warp::reply::json(&public_keys));
+    let post_sign = warp::path!("api" / "v1" / "eth2" / "sign" / String)
+        .and(warp::post())
+        .and(warp::body::json())
+        .map(move |pubkey: String, request: serde_json::Value| {
+            let Some(keypair) = keypairs
+                .iter()
+                .find(|keypair| keypair.pk.to_string() == pubkey)
+            else {
+                return warp::reply::with_status(
+                    warp::reply::json(&"key not found"),
+                    StatusCode::NOT_FOUND,
+                );
+            };
+            if let Some(message_type) = request["type"].as_str() {
+                let _ = requested_types_tx.send(message_type.to_string());
+            }
+            if request["type"] == refused_type {
+                return warp::reply::with_status(
+                    warp::reply::json(&"refused by policy"),
+                    StatusCode::FORBIDDEN,
+                );
+            }
+            let signing_root: Hash256 =
+                serde_json::from_value(request["signingRoot"].clone()).unwrap();
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "signature": keypair.sk.sign(signing_root),
+                })),
+                StatusCode::OK,
+            )
+        });
+
+    let (addr, server) = warp::serve(get_public_keys.or(post_sign))
+        .bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+    (format!("http://{}", addr), requested_types_rx)
+}
+
+/// Returns everything already sent to `rx`, without waiting.
+fn drain<T>(rx: &mut tokio::sync::mpsc::UnboundedReceiver<T>) -> Vec<T> {
+    std::iter::from_fn(|| rx.try_recv().ok()).collect()
+}
+
 #[tokio::test]
 async fn invalid_pubkey() {
     ApiTester::new()
@@ -688,6 +1630,18 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .get_lighthouse_validators_protection_history(&PublicKeyBytes::empty())
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .get_lighthouse_validators_signing_capabilities()
+                .await
+        })
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .post_lighthouse_validators(vec![ValidatorRequest {
@@ -744,6 +1698,17 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_timing().await })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .patch_lighthouse_timing(&TimingPatchRequest {
+                    attestation_offset_ms: Some(3000),
+                    aggregate_offset_ms: None,
+                })
+                .await
+        })
+        .await
         .test_with_invalid_auth(|client| async move { client.get_keystores().await })
         .await
         .test_with_invalid_auth(|client| async move {
@@ -771,6 +1736,25 @@ async fn routes_with_invalid_auth() {
                 })
                 .await
         })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client.get_graffiti(&PublicKeyBytes::empty()).await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_graffiti(
+                    &PublicKeyBytes::empty(),
+                    &UpdateGraffitiRequest {
+                        graffiti: GraffitiString::from_str("graffiti").unwrap(),
+                    },
+                )
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client.delete_graffiti(&PublicKeyBytes::empty()).await
+        })
         .await;
 }
 
@@ -786,6 +1770,53 @@ async fn simple_getters() {
         .await;
 }
 
+#[tokio::test]
+async fn lighthouse_timing() {
+    ApiTester::new()
+        .await
+        .test_get_lighthouse_timing(SlotTimingOffsets {
+            attestation_offset_ms: 4000,
+            aggregate_offset_ms: 8000,
+        })
+        .await
+        .set_lighthouse_timing(TimingPatchRequest {
+            attestation_offset_ms: Some(3500),
+            aggregate_offset_ms: None,
+        })
+        .await
+        .test_get_lighthouse_timing(SlotTimingOffsets {
+            attestation_offset_ms: 3500,
+            aggregate_offset_ms: 8000,
+        })
+        .await
+        // The aggregate offset must be later than the attestation offset.
+        .test_invalid_lighthouse_timing(TimingPatchRequest {
+            attestation_offset_ms: None,
+            aggregate_offset_ms: Some(3000),
+        })
+        .await
+        .test_get_lighthouse_timing(SlotTimingOffsets {
+            attestation_offset_ms: 3500,
+            aggregate_offset_ms: 8000,
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn attestation_service_timing() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 1,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_attestation_service_timing()
+        .await;
+}
+
 #[tokio::test]
 async fn hd_validator_creation() {
     ApiTester::new()
@@ -821,6 +1852,42 @@ async fn hd_validator_creation() {
         .assert_validators_count(3);
 }
 
+#[tokio::test]
+async fn protection_history() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 1,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_get_protection_history(0)
+        .await;
+}
+
+#[tokio::test]
+async fn signing_capabilities() {
+    ApiTester::new().await.test_signing_capabilities().await;
+}
+
+#[tokio::test]
+async fn sync_committee_skips_refused_validator() {
+    ApiTester::new()
+        .await
+        .test_sync_committee_skips_refused_validator()
+        .await;
+}
+
+#[tokio::test]
+async fn preparation_skips_refused_validator() {
+    ApiTester::new()
+        .await
+        .test_preparation_skips_refused_validator()
+        .await;
+}
+
 #[tokio::test]
 async fn validator_exit() {
     ApiTester::new()
@@ -954,6 +2021,104 @@ async fn validator_graffiti() {
         .await;
 }
 
+#[tokio::test]
+async fn validator_graffiti_keymanager() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_get_graffiti(0, "")
+        .await
+        .assert_graffiti_not_found()
+        .await
+        .post_graffiti(0, "per validator")
+        .await
+        .assert_get_graffiti(0, "per validator")
+        .await
+        .assert_get_graffiti(1, "")
+        .await
+        .assert_graffiti(0, "per validator")
+        .await
+        .assert_proposal_graffiti(0, "per validator")
+        .await
+        // Test overriding a previously set graffiti.
+        .post_graffiti(0, "per validator again")
+        .await
+        .assert_get_graffiti(0, "per validator again")
+        .await
+        .assert_proposal_graffiti(0, "per validator again")
+        .await
+        // Deleting the graffiti reverts to the `--graffiti` flag.
+        .delete_graffiti(0)
+        .await
+        .assert_get_graffiti(0, "")
+        .await
+        .assert_proposal_graffiti(0, "")
+        .await;
+}
+
+#[tokio::test]
+async fn validator_graffiti_keymanager_with_graffiti_file() {
+    let graffiti_dir = tempdir().unwrap();
+    let graffiti_path = graffiti_dir.path().join("graffiti.txt");
+    std::fs::write(&graffiti_path, "default: from file\n").unwrap();
+
+    ApiTester::new_with_graffiti_file(Some(GraffitiFile::new(graffiti_path)))
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 1,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_get_graffiti(0, "from file")
+        .await
+        .assert_proposal_graffiti(0, "from file")
+        .await
+        // A graffiti in the validator definitions takes precedence over the graffiti file.
+        .post_graffiti(0, "from definitions")
+        .await
+        .assert_get_graffiti(0, "from definitions")
+        .await
+        .assert_proposal_graffiti(0, "from definitions")
+        .await
+        // Deleting the graffiti reverts to the graffiti file.
+        .delete_graffiti(0)
+        .await
+        .assert_get_graffiti(0, "from file")
+        .await
+        .assert_proposal_graffiti(0, "from file")
+        .await;
+}
+
+#[tokio::test]
+async fn validator_graffiti_keymanager_read_only() {
+    ApiTester::new()
+        .await
+        .create_web3signer_validators(Web3SignerValidatorScenario {
+            count: 1,
+            enabled: true,
+        })
+        .await
+        .set_definitions_read_only(true)
+        .assert_graffiti_forbidden(0)
+        .await
+        .assert_get_graffiti(0, "")
+        .await
+        // The validator can be modified once the definitions file is writable.
+        .set_definitions_read_only(false)
+        .post_graffiti(0, "writable")
+        .await
+        .assert_get_graffiti(0, "writable")
+        .await;
+}
+
 #[tokio::test]
 async fn keystore_validator_creation() {
     ApiTester::new()
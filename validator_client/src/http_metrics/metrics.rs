@@ -5,6 +5,7 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use types::EthSpec;
 
 pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
@@ -14,6 +15,7 @@ pub const BEACON_BLOCK_HTTP_GET: &str = "beacon_block_http_get";
 pub const BLINDED_BEACON_BLOCK_HTTP_GET: &str = "blinded_beacon_block_http_get";
 pub const BEACON_BLOCK_HTTP_POST: &str = "beacon_block_http_post";
 pub const BLINDED_BEACON_BLOCK_HTTP_POST: &str = "blinded_beacon_block_http_post";
+pub const BLOB_PROPAGATION_HTTP_GET: &str = "blob_propagation_http_get";
 pub const ATTESTATIONS: &str = "attestations";
 pub const ATTESTATIONS_HTTP_GET: &str = "attestations_http_get";
 pub const ATTESTATIONS_HTTP_POST: &str = "attestations_http_post";
@@ -29,9 +31,23 @@ pub const UPDATE_ATTESTERS_FETCH: &str = "update_attesters_fetch";
 pub const UPDATE_ATTESTERS_STORE: &str = "update_attesters_store";
 pub const ATTESTER_DUTIES_HTTP_POST: &str = "attester_duties_http_post";
 pub const PROPOSER_DUTIES_HTTP_GET: &str = "proposer_duties_http_get";
+pub const PROPOSER_DUTIES_EVENTS_HTTP_GET: &str = "proposer_duties_events_http_get";
 pub const VALIDATOR_DUTIES_SYNC_HTTP_POST: &str = "validator_duties_sync_http_post";
 pub const VALIDATOR_ID_HTTP_GET: &str = "validator_id_http_get";
 pub const SUBSCRIPTIONS_HTTP_POST: &str = "subscriptions_http_post";
+pub const SYNC_SUBSCRIPTIONS_HTTP_POST: &str = "sync_subscriptions_http_post";
+pub const SYNC_COMMITTEE_MESSAGES_HTTP_POST: &str = "sync_committee_messages_http_post";
+pub const SYNC_CONTRIBUTION_HTTP_GET: &str = "sync_contribution_http_get";
+pub const SYNC_CONTRIBUTIONS_HTTP_POST: &str = "sync_contributions_http_post";
+pub const HEAD_BLOCK_ROOT_HTTP_GET: &str = "head_block_root_http_get";
+pub const PREPARE_PROPOSER_HTTP_POST: &str = "prepare_proposer_http_post";
+pub const PROPOSER_PREPARATION_HTTP_GET: &str = "proposer_preparation_http_get";
+pub const PROPOSED_PAYLOAD_HTTP_GET: &str = "proposed_payload_http_get";
+pub const REGISTER_VALIDATOR_HTTP_POST: &str = "register_validator_http_post";
+pub const LIVENESS_HTTP_POST: &str = "liveness_http_post";
+pub const GENESIS_HTTP_GET: &str = "genesis_http_get";
+pub const STAKING_HTTP_GET: &str = "staking_http_get";
+pub const VALIDATOR_METRICS_HTTP_POST: &str = "validator_metrics_http_post";
 pub const UPDATE_PROPOSERS: &str = "update_proposers";
 pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
@@ -69,6 +85,11 @@ lazy_static::lazy_static! {
         "Total count of attempted Attestation signings",
         &["status"]
     );
+    pub static ref SLASHING_PROTECTION_REFUSALS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_slashing_protection_refusals_total",
+        "Total count of signings refused by slashing protection, by message type and reason",
+        &["type", "reason"]
+    );
     pub static ref SIGNED_AGGREGATES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "vc_signed_aggregates_total",
         "Total count of attempted SignedAggregateAndProof signings",
@@ -104,6 +125,11 @@ lazy_static::lazy_static! {
         "Total count of ValidatorRegistrationData signings",
         &["status"]
     );
+    pub static ref DOPPELGANGER_LIVENESS_RESPONSES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_doppelganger_liveness_responses_total",
+        "Total count of liveness responses used for doppelganger protection, by BN endpoint",
+        &["endpoint"]
+    );
     pub static ref DUTIES_SERVICE_TIMES: Result<HistogramVec> = try_create_histogram_vec(
         "vc_duties_service_task_times_seconds",
         "Duration to perform duties service tasks",
@@ -150,6 +176,21 @@ lazy_static::lazy_static! {
         "The number of beacon node requests for each endpoint",
         &["endpoint"]
     );
+    pub static ref ENDPOINT_BROADCAST_ERRORS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "bn_endpoint_broadcast_errors",
+        "The number of failed broadcasts to each endpoint which were accepted by another endpoint",
+        &["endpoint"]
+    );
+    pub static ref BEACON_NODE_REQUEST_DURATION: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_beacon_node_request_duration_seconds",
+        "Duration of requests to each beacon node, by endpoint and outcome",
+        &["endpoint", "node", "outcome"]
+    );
+    pub static ref BEACON_NODE_REQUESTS_SERVED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_beacon_node_requests_served_total",
+        "The number of requests for each endpoint whose result was taken from each beacon node",
+        &["endpoint", "node"]
+    );
 
     /*
     * Beacon node availability metrics
@@ -194,6 +235,11 @@ lazy_static::lazy_static! {
         "Attestation duty slot for all managed validators",
         &["validator"]
     );
+    pub static ref FEE_RECIPIENT_DRIFT: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "fee_recipient_drift",
+        "Set to 1 if the fee recipient or gas limit of a validator differs between the VC, the BN and its last proposed payload",
+        &["validator"]
+    );
     /*
      * BN latency
      */
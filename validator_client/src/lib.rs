@@ -5,35 +5,43 @@ mod check_synced;
 mod cli;
 mod config;
 mod duties_service;
+mod fee_recipient_audit;
 mod graffiti_file;
 mod http_metrics;
 mod key_cache;
 mod latency;
 mod notifier;
 mod preparation_service;
+mod signing_capabilities;
 mod signing_method;
 mod sync_committee_service;
 
 mod doppelganger_service;
 pub mod http_api;
 pub mod initialized_validators;
+pub mod selection_proof_hook;
+pub mod slot_timing;
 pub mod validator_store;
 
+pub use beacon_node_fallback::ApiTopic;
 pub use cli::cli_app;
 pub use config::Config;
 use initialized_validators::InitializedValidators;
 use lighthouse_metrics::set_gauge;
-use monitoring_api::{MonitoringHttpClient, ProcessType};
+use monitoring_api::{MonitoringHttpClient, ProcessType, ValidatorEffectivenessSource};
 use sensitive_url::SensitiveUrl;
+use slashing_protection::{NotSafe, SlashingDatabaseConfig};
 pub use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 
 use crate::beacon_node_fallback::{
-    start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode, OfflineOnFailure,
-    RequireSynced,
+    start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode, ChainIdentity,
+    OfflineOnFailure, RequireSynced,
 };
-use crate::doppelganger_service::DoppelgangerService;
+use crate::doppelganger_service::{DoppelgangerService, DoppelgangerStatus};
 use crate::graffiti_file::GraffitiFile;
 use crate::initialized_validators::Error::UnableToOpenVotingKeystore;
+use crate::selection_proof_hook::{LocalSelectionProofs, SelectionProofHook};
+use crate::slot_timing::SlotTiming;
 use account_utils::validator_definitions::ValidatorDefinitions;
 use attestation_service::{AttestationService, AttestationServiceBuilder};
 use block_service::{BlockService, BlockServiceBuilder};
@@ -97,6 +105,7 @@ pub struct ProductionValidatorClient<T: EthSpec> {
     preparation_service: PreparationService<SystemTimeSlotClock, T>,
     validator_store: Arc<ValidatorStore<SystemTimeSlotClock, T>>,
     slot_clock: SystemTimeSlotClock,
+    slot_timing: Arc<SlotTiming>,
     http_api_listen_addr: Option<SocketAddr>,
     config: Config,
     beacon_nodes: Arc<BeaconNodeFallback<SystemTimeSlotClock, T>>,
@@ -118,6 +127,16 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
     /// Instantiates the validator client, _without_ starting the timers to trigger block
     /// and attestation production.
     pub async fn new(context: RuntimeContext<T>, config: Config) -> Result<Self, String> {
+        Self::new_with_selection_proof_hook(context, config, Arc::new(LocalSelectionProofs)).await
+    }
+
+    /// Instantiates the validator client with a custom `SelectionProofHook`, _without_ starting
+    /// the timers to trigger block and attestation production.
+    pub async fn new_with_selection_proof_hook(
+        context: RuntimeContext<T>,
+        config: Config,
+        selection_proof_hook: Arc<dyn SelectionProofHook>,
+    ) -> Result<Self, String> {
         let log = context.log().clone();
 
         info!(
@@ -157,17 +176,6 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             None
         };
 
-        // Start the explorer client which periodically sends validator process
-        // and system metrics to the configured endpoint.
-        if let Some(monitoring_config) = &config.monitoring_api {
-            let monitoring_client =
-                MonitoringHttpClient::new(monitoring_config, context.log().clone())?;
-            monitoring_client.auto_update(
-                context.executor.clone(),
-                vec![ProcessType::Validator, ProcessType::System],
-            );
-        };
-
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.validator_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
@@ -228,15 +236,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // `init_slashing_protection` is not supplied. There is no risk in creating a slashing
         // database without any validators in it.
         let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
+        let slashing_db_config = SlashingDatabaseConfig {
+            busy_timeout: config.slashing_protection_busy_timeout,
+            ..SlashingDatabaseConfig::default()
+        };
         let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
-            SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open or create slashing protection database: {:?}",
-                    e
-                )
-            })
+            SlashingDatabase::open_or_create_with_config(&slashing_db_path, slashing_db_config)
+                .map_err(|e| {
+                    format!(
+                        "Failed to open or create slashing protection database: {:?}",
+                        e
+                    )
+                })
         } else {
-            SlashingDatabase::open(&slashing_db_path).map_err(|e| {
+            SlashingDatabase::open_with_config(&slashing_db_path, slashing_db_config).map_err(|e| {
                 format!(
                     "Failed to open slashing protection database: {:?}.\n\
                      Ensure that `slashing_protection.sqlite` is in {:?} folder",
@@ -317,11 +330,13 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 Timeouts::set_all(slot_duration)
             };
 
-            Ok(BeaconNodeHttpClient::from_components(
+            let mut beacon_node = BeaconNodeHttpClient::from_components(
                 url.clone(),
                 beacon_node_http_client,
                 timeouts,
-            ))
+            );
+            beacon_node.set_compress_ssz_blocks(config.compress_ssz_blocks);
+            Ok(beacon_node)
         };
 
         let beacon_nodes: Vec<BeaconNodeHttpClient> = config
@@ -369,14 +384,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let mut beacon_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
 
         let mut proposer_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             proposer_candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
@@ -401,6 +416,31 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         beacon_nodes.set_slot_clock(slot_clock.clone());
         proposer_nodes.set_slot_clock(slot_clock.clone());
 
+        // Refuse to start unless the beacon nodes and the slashing protection database all agree
+        // on the chain being validated.
+        let chain_identity = tokio::select! {
+            result = init_chain_identity(
+                &beacon_nodes,
+                &proposer_nodes,
+                genesis_time,
+                genesis_validators_root,
+                &slashing_protection,
+            ) => result?,
+            () = context.executor.exit() => return Err("Shutting down".to_string())
+        };
+        beacon_nodes.set_chain_identity(chain_identity.clone());
+        proposer_nodes.set_chain_identity(chain_identity);
+
+        let slot_timing = Arc::new(
+            SlotTiming::new(
+                slot_clock.slot_duration(),
+                config.attestation_offset,
+                config.aggregate_offset,
+                log.clone(),
+            )
+            .map_err(|e| format!("Invalid slot timing offsets: {}", e))?,
+        );
+
         let beacon_nodes = Arc::new(beacon_nodes);
         start_fallback_updater_service(context.clone(), beacon_nodes.clone())?;
 
@@ -439,6 +479,21 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             "voting_validators" => validator_store.num_voting_validators()
         );
 
+        // Start the explorer client which periodically sends validator process
+        // and system metrics to the configured endpoint.
+        if let Some(monitoring_config) = &config.monitoring_api {
+            let monitoring_client =
+                MonitoringHttpClient::new(monitoring_config, context.log().clone())?
+                    .with_validator_effectiveness(validator_effectiveness_source(
+                        beacon_nodes.clone(),
+                        validator_store.clone(),
+                    ));
+            monitoring_client.auto_update(
+                context.executor.clone(),
+                vec![ProcessType::Validator, ProcessType::System],
+            );
+        };
+
         // Perform pruning of the slashing protection database on start-up. In case the database is
         // oversized from having not been pruned (by a prior version) we don't want to prune
         // concurrently, as it will hog the lock and cause the attestation service to spew CRITs.
@@ -451,12 +506,20 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             attesters: <_>::default(),
             proposers: <_>::default(),
             sync_duties: <_>::default(),
+            attestation_selection_proofs: <_>::default(),
+            sync_selection_proofs: <_>::default(),
+            selection_proof_hook,
+            distributed: config.distributed,
             slot_clock: slot_clock.clone(),
+            slot_timing: slot_timing.clone(),
             beacon_nodes: beacon_nodes.clone(),
             validator_store: validator_store.clone(),
             spec: context.eth2_config.spec.clone(),
             context: duties_context,
             enable_high_validator_count_metrics: config.enable_high_validator_count_metrics,
+            proposer_duties_events: config.proposer_duties_events,
+            proposer_duties_events_connected: <_>::default(),
+            proposer_duties_refetch: <_>::default(),
         });
 
         // Update the metrics server.
@@ -472,7 +535,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
             .graffiti_file(config.graffiti_file.clone())
-            .block_delay(config.block_delay);
+            .block_delay(config.block_delay)
+            .broadcast_validation(config.broadcast_validation);
 
         // If we have proposer nodes, add them to the block service builder.
         if proposer_nodes_num > 0 {
@@ -484,6 +548,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let attestation_service = AttestationServiceBuilder::new()
             .duties_service(duties_service.clone())
             .slot_clock(slot_clock.clone())
+            .slot_timing(slot_timing.clone())
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
@@ -517,6 +582,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             validator_store,
             config,
             slot_clock,
+            slot_timing,
             http_api_listen_addr: None,
             genesis_time,
             beacon_nodes,
@@ -546,6 +612,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 config: self.config.http_api.clone(),
                 sse_logging_components: self.context.sse_logging_components.clone(),
                 slot_clock: self.slot_clock.clone(),
+                slot_timing: Some(self.slot_timing.clone()),
+                beacon_nodes: Some(self.beacon_nodes.clone()),
                 log: log.clone(),
                 _phantom: PhantomData,
             });
@@ -607,6 +675,17 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        fee_recipient_audit::start_fee_recipient_audit_service(
+            self.context.clone(),
+            self.duties_service.clone(),
+        );
+
+        signing_capabilities::start_signing_capabilities_service(
+            self.context.clone(),
+            self.validator_store.clone(),
+            self.duties_service.slot_clock.clone(),
+        );
+
         if self.config.enable_latency_measurement_service {
             latency::start_latency_service(
                 self.context.clone(),
@@ -681,6 +760,7 @@ async fn init_from_beacon_node<E: EthSpec>(
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                http_metrics::metrics::GENESIS_HTTP_GET,
                 |node| async move { node.get_beacon_genesis().await },
             )
             .await
@@ -715,6 +795,51 @@ async fn init_from_beacon_node<E: EthSpec>(
     Ok((genesis.genesis_time, genesis.genesis_validators_root))
 }
 
+/// Checks that every reachable beacon and proposer node is following the chain with the given
+/// genesis, and that the slashing protection database is for that chain.
+async fn init_chain_identity<E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    proposer_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    genesis_time: u64,
+    genesis_validators_root: Hash256,
+    slashing_protection: &SlashingDatabase,
+) -> Result<ChainIdentity, String> {
+    let chain_identity = beacon_nodes
+        .fetch_chain_identity(None)
+        .await?
+        .ok_or("Unable to read the chain identity from any beacon node")?;
+    if chain_identity.genesis_time != genesis_time
+        || chain_identity.genesis_validators_root != genesis_validators_root
+    {
+        return Err(format!(
+            "Beacon nodes disagree about genesis: time {} and root {:?} != time {} and root {:?}",
+            genesis_time,
+            genesis_validators_root,
+            chain_identity.genesis_time,
+            chain_identity.genesis_validators_root
+        ));
+    }
+    proposer_nodes
+        .fetch_chain_identity(Some(chain_identity.clone()))
+        .await?;
+
+    slashing_protection
+        .check_or_set_genesis_validators_root(genesis_validators_root)
+        .map_err(|e| match e {
+            NotSafe::GenesisValidatorsRootMismatch { database, provided } => format!(
+                "The slashing protection database is for a different chain. It has genesis \
+                 validators root {:?}, but the beacon nodes have {:?}",
+                database, provided
+            ),
+            e => format!(
+                "Unable to check the slashing protection database genesis validators root: {:?}",
+                e
+            ),
+        })?;
+
+    Ok(chain_identity)
+}
+
 async fn wait_for_genesis<E: EthSpec>(
     beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
     genesis_time: u64,
@@ -772,6 +897,7 @@ async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                http_metrics::metrics::STAKING_HTTP_GET,
                 |beacon_node| async move { beacon_node.get_lighthouse_staking().await },
             )
             .await
@@ -814,6 +940,34 @@ async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
     }
 }
 
+/// Fetches the attestation effectiveness of the validators in `validator_store` from the validator
+/// monitor of the beacon nodes, for inclusion in the monitoring API metrics.
+fn validator_effectiveness_source<E: EthSpec>(
+    beacon_nodes: Arc<BeaconNodeFallback<SystemTimeSlotClock, E>>,
+    validator_store: Arc<ValidatorStore<SystemTimeSlotClock, E>>,
+) -> ValidatorEffectivenessSource {
+    Arc::new(move || {
+        let beacon_nodes = beacon_nodes.clone();
+        let indices = validator_store
+            .voting_pubkeys::<Vec<_>, _>(DoppelgangerStatus::ignored)
+            .iter()
+            .filter_map(|pubkey| validator_store.validator_index(pubkey))
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            beacon_nodes
+                .first_success(
+                    RequireSynced::No,
+                    OfflineOnFailure::No,
+                    http_metrics::metrics::VALIDATOR_METRICS_HTTP_POST,
+                    |beacon_node| beacon_node.post_lighthouse_ui_validator_metrics(&indices),
+                )
+                .await
+                .map(|response| response.data.validators)
+                .map_err(|e| e.to_string())
+        })
+    })
+}
+
 pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate, String> {
     let mut buf = Vec::new();
     File::open(&pem_path)
@@ -825,6 +979,9 @@ pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate,
 
 // Given the various graffiti control methods, determine the graffiti that will be used for
 // the next block produced by the validator with the given public key.
+//
+// The per-validator graffiti from the validator definitions takes precedence, followed by the
+// graffiti file and finally the process-wide `--graffiti` flag.
 pub fn determine_graffiti(
     validator_pubkey: &PublicKeyBytes,
     log: &Logger,
@@ -832,14 +989,15 @@ pub fn determine_graffiti(
     validator_definition_graffiti: Option<Graffiti>,
     graffiti_flag: Option<Graffiti>,
 ) -> Option<Graffiti> {
-    graffiti_file
-        .and_then(|mut g| match g.load_graffiti(validator_pubkey) {
-            Ok(g) => g,
-            Err(e) => {
-                warn!(log, "Failed to read graffiti file"; "error" => ?e);
-                None
-            }
+    validator_definition_graffiti
+        .or_else(|| {
+            graffiti_file.and_then(|mut g| match g.load_graffiti(validator_pubkey) {
+                Ok(g) => g,
+                Err(e) => {
+                    warn!(log, "Failed to read graffiti file"; "error" => ?e);
+                    None
+                }
+            })
         })
-        .or(validator_definition_graffiti)
         .or(graffiti_flag)
 }
@@ -1,4 +1,6 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
+use crate::http_metrics::metrics;
+use crate::signing_method::MessageType;
 use crate::validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, ValidatorStore};
 use crate::OfflineOnFailure;
 use bls::PublicKeyBytes;
@@ -300,6 +302,13 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
             // Ignore fee recipients for keys without indices, they are inactive.
             proposal_data.validator_index?;
 
+            if !self
+                .validator_store
+                .can_sign(&pubkey, MessageType::ValidatorRegistration)
+            {
+                return None;
+            }
+
             // We don't log for missing fee recipients here because this will be logged more
             // frequently in `collect_preparation_data`.
             proposal_data.fee_recipient.and_then(|fee_recipient| {
@@ -342,9 +351,11 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
         let preparation_entries = preparation_data.as_slice();
         match self
             .beacon_nodes
-            .run(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
+                metrics::PREPARE_PROPOSER_HTTP_POST,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_prepare_beacon_proposer(preparation_entries)
@@ -360,7 +371,7 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
             ),
             Err(e) => error!(
                 log,
-                "Unable to publish proposer preparation to all beacon nodes";
+                "Unable to publish proposer preparation";
                 "error" => %e,
             ),
         }
@@ -479,6 +490,7 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
                     .first_success(
                         RequireSynced::No,
                         OfflineOnFailure::No,
+                        metrics::REGISTER_VALIDATOR_HTTP_POST,
                         |beacon_node| async move {
                             beacon_node.post_validator_register_validator(batch).await
                         },
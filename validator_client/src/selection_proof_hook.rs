@@ -0,0 +1,56 @@
+//! Provides the `SelectionProofHook`, through which every selection proof signed by the validator
+//! client passes before it is used to decide whether a validator is an aggregator.
+//!
+//! By default the proof signed by this client is used as-is. In a distributed validator cluster
+//! the proof signed by this client is only a partial signature, which must be combined with those
+//! of the other co-signers before the aggregator selection can be made. Combining the proofs may
+//! take some time and may fail, so in `--distributed` mode attestation subnet subscriptions are
+//! sent with `is_aggregator=true` until the combined proof is known, and aggregation is skipped if
+//! it doesn't win.
+
+use eth2::types::AttesterData;
+use futures::future::{self, BoxFuture};
+use types::{SelectionProof, Slot, SyncDuty, SyncSelectionProof, SyncSubnetId};
+
+pub trait SelectionProofHook: Send + Sync {
+    /// Returns the selection proof for the attestation `duty`, given the `proof` signed by this
+    /// client, or `None` if no proof could be produced.
+    fn attestation_selection_proof<'a>(
+        &'a self,
+        duty: &'a AttesterData,
+        proof: SelectionProof,
+    ) -> BoxFuture<'a, Option<SelectionProof>>;
+
+    /// Returns the selection proof for the sync committee `duty` at `slot` on `subnet_id`, given
+    /// the `proof` signed by this client, or `None` if no proof could be produced.
+    fn sync_selection_proof<'a>(
+        &'a self,
+        duty: &'a SyncDuty,
+        slot: Slot,
+        subnet_id: SyncSubnetId,
+        proof: SyncSelectionProof,
+    ) -> BoxFuture<'a, Option<SyncSelectionProof>>;
+}
+
+/// Uses the selection proofs signed by this client, as a non-distributed validator client does.
+pub struct LocalSelectionProofs;
+
+impl SelectionProofHook for LocalSelectionProofs {
+    fn attestation_selection_proof<'a>(
+        &'a self,
+        _duty: &'a AttesterData,
+        proof: SelectionProof,
+    ) -> BoxFuture<'a, Option<SelectionProof>> {
+        Box::pin(future::ready(Some(proof)))
+    }
+
+    fn sync_selection_proof<'a>(
+        &'a self,
+        _duty: &'a SyncDuty,
+        _slot: Slot,
+        _subnet_id: SyncSubnetId,
+        proof: SyncSelectionProof,
+    ) -> BoxFuture<'a, Option<SyncSelectionProof>> {
+        Box::pin(future::ready(Some(proof)))
+    }
+}
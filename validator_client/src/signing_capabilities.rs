@@ -0,0 +1,146 @@
+//! Periodically probes the remote signers of validators for the types of message they will sign.
+//!
+//! A Web3Signer may be run with a policy which refuses some types of message, which would otherwise
+//! only be discovered when a duty fails to be signed. At startup, and then every
+//! `PROBE_INTERVAL_EPOCHS`, each validator using a Web3Signer is checked:
+//!
+//! - Its key must be listed by the signer's `GET /api/v1/eth2/publicKeys`, otherwise every message
+//!   type is considered refused.
+//! - Each message type which is neither slashable nor harmful is signed as a dry run, see
+//!   `ValidatorStore::probe_signing_capabilities`.
+//!
+//! The outcome of real signing requests is recorded as well. Duties which require a refused message
+//! type are not performed, and the capabilities are served at
+//! `GET lighthouse/validators/signing_capabilities`.
+
+use crate::signing_method::{Error as SigningError, MessageType};
+use crate::validator_store::{DoppelgangerStatus, ValidatorStore};
+use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::SigningCapability;
+use slog::{debug, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::time::sleep;
+use types::{EthSpec, PublicKeyBytes};
+use url::Url;
+
+/// The number of epochs between probes of the remote signers.
+pub const PROBE_INTERVAL_EPOCHS: u64 = 8;
+
+/// Probes the remote signer of every enabled validator.
+pub async fn probe_signing_capabilities<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: &ValidatorStore<T, E>,
+    log: &Logger,
+) {
+    let pubkeys = validator_store.voting_pubkeys::<Vec<_>, _>(DoppelgangerStatus::ignored);
+
+    // Each signer is only asked for its keys once.
+    let mut keys_by_signer: HashMap<Url, Result<HashSet<PublicKeyBytes>, SigningError>> =
+        HashMap::new();
+    let mut num_probed = 0;
+
+    for pubkey in pubkeys {
+        let Some(signing_method) = validator_store
+            .initialized_validators()
+            .read()
+            .signing_method(&pubkey)
+        else {
+            continue;
+        };
+        let Some(url) = signing_method.web3signer_public_keys_url() else {
+            continue;
+        };
+
+        if !keys_by_signer.contains_key(&url) {
+            let keys = signing_method
+                .web3signer_public_keys()
+                .await
+                .map(|keys| keys.unwrap_or_default().into_iter().collect());
+            keys_by_signer.insert(url.clone(), keys);
+        }
+
+        match &keys_by_signer[&url] {
+            Ok(keys) if !keys.contains(&pubkey) => {
+                warn!(
+                    log,
+                    "Remote signer does not hold validator key";
+                    "signer" => %url,
+                    "pubkey" => ?pubkey,
+                );
+                for message_type in MessageType::SIGNED_BY_VALIDATORS {
+                    signing_method.set_capability(message_type, SigningCapability::Refused);
+                }
+                continue;
+            }
+            Ok(_) => (),
+            // The capabilities are left as they are until the signer can be reached.
+            Err(e) => {
+                debug!(
+                    log,
+                    "Unable to list remote signer keys";
+                    "signer" => %url,
+                    "error" => ?e,
+                );
+                continue;
+            }
+        }
+
+        if let Err(e) = validator_store.probe_signing_capabilities(pubkey).await {
+            debug!(
+                log,
+                "Unable to probe remote signer";
+                "pubkey" => ?pubkey,
+                "error" => ?e,
+            );
+            continue;
+        }
+        num_probed += 1;
+
+        let refused = MessageType::SIGNED_BY_VALIDATORS
+            .into_iter()
+            .filter(|message_type| {
+                signing_method.capability(*message_type) == SigningCapability::Refused
+            })
+            .map(|message_type| message_type.as_str())
+            .collect::<Vec<_>>();
+        if !refused.is_empty() {
+            warn!(
+                log,
+                "Remote signer refuses message types";
+                "msg" => "duties requiring these message types will not be performed",
+                "message_types" => ?refused,
+                "pubkey" => ?pubkey,
+            );
+        }
+    }
+
+    if num_probed > 0 {
+        info!(
+            log,
+            "Probed remote signers";
+            "validators" => num_probed,
+            "signers" => keys_by_signer.len(),
+        );
+    }
+}
+
+/// Starts a service which probes the remote signers at startup and every `PROBE_INTERVAL_EPOCHS`.
+pub fn start_signing_capabilities_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    slot_clock: T,
+) {
+    let log = context.log().clone();
+    let interval =
+        slot_clock.slot_duration() * (E::slots_per_epoch() * PROBE_INTERVAL_EPOCHS) as u32;
+
+    let future = async move {
+        loop {
+            probe_signing_capabilities(&validator_store, &log).await;
+            sleep(interval).await;
+        }
+    };
+
+    context.executor.spawn(future, "signing_capabilities");
+}
@@ -4,10 +4,12 @@
 //! - Via a remote signer (Web3Signer)
 
 use crate::http_metrics::metrics;
+use eth2::lighthouse_vc::types::SigningCapability;
 use eth2_keystore::Keystore;
 use lockfile::Lockfile;
-use parking_lot::Mutex;
-use reqwest::Client;
+use parking_lot::{Mutex, RwLock};
+use reqwest::{Client, StatusCode};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
@@ -15,10 +17,14 @@ use types::*;
 use url::Url;
 use web3signer::{ForkInfo, SigningRequest, SigningResponse};
 
-pub use web3signer::Web3SignerObject;
+pub use web3signer::{MessageType, Web3SignerObject};
 
 mod web3signer;
 
+/// The number of consecutive refused signing requests after which a Web3Signer is considered to
+/// refuse a message type.
+pub const REFUSALS_BEFORE_REFUSED: usize = 3;
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     InconsistentDomains {
@@ -26,6 +32,12 @@ pub enum Error {
         domain: Domain,
     },
     Web3SignerRequestFailed(String),
+    /// The Web3Signer responded with a client error, e.g. because its policy forbids signing
+    /// `message_type` or it does not hold the key.
+    Web3SignerRefused {
+        message_type: MessageType,
+        status: u16,
+    },
     Web3SignerJsonParsingFailed(String),
     ShuttingDown,
     TokioJoin(String),
@@ -94,6 +106,10 @@ pub enum SigningMethod {
         signing_url: Url,
         http_client: Client,
         voting_public_key: PublicKey,
+        /// The message types the signer has been seen to sign or refuse.
+        capabilities: RwLock<HashMap<MessageType, SigningCapability>>,
+        /// The number of consecutive signing requests refused for each message type.
+        refusals: RwLock<HashMap<MessageType, usize>>,
     },
 }
 
@@ -119,6 +135,102 @@ impl SigningContext {
 }
 
 impl SigningMethod {
+    /// Returns whether this method is known to sign messages of `message_type`.
+    pub fn capability(&self, message_type: MessageType) -> SigningCapability {
+        match self {
+            SigningMethod::LocalKeystore { .. } => SigningCapability::Allowed,
+            SigningMethod::Web3Signer { capabilities, .. } => capabilities
+                .read()
+                .get(&message_type)
+                .copied()
+                .unwrap_or(SigningCapability::Unknown),
+        }
+    }
+
+    /// Returns the capability of this method for each message type signed by validators, keyed by
+    /// the Web3Signer name of the type.
+    pub fn capabilities(&self) -> BTreeMap<String, SigningCapability> {
+        MessageType::SIGNED_BY_VALIDATORS
+            .iter()
+            .map(|message_type| {
+                (
+                    message_type.as_str().to_string(),
+                    self.capability(*message_type),
+                )
+            })
+            .collect()
+    }
+
+    /// Records the capability of a Web3Signer for `message_type`. Has no effect on local
+    /// keystores.
+    pub fn set_capability(&self, message_type: MessageType, capability: SigningCapability) {
+        if let SigningMethod::Web3Signer {
+            capabilities,
+            refusals,
+            ..
+        } = self
+        {
+            refusals.write().remove(&message_type);
+            capabilities.write().insert(message_type, capability);
+        }
+    }
+
+    /// Records a refused signing request for `message_type`, marking the type as refused once
+    /// `REFUSALS_BEFORE_REFUSED` consecutive requests have been refused.
+    ///
+    /// A single refusal may be transient (e.g. a signer restarting with a partial key set), so it
+    /// is not enough to stop signing the message type.
+    fn record_refusal(&self, message_type: MessageType) {
+        if let SigningMethod::Web3Signer {
+            capabilities,
+            refusals,
+            ..
+        } = self
+        {
+            let mut refusals = refusals.write();
+            let count = refusals.entry(message_type).or_insert(0);
+            *count += 1;
+            if *count >= REFUSALS_BEFORE_REFUSED {
+                capabilities
+                    .write()
+                    .insert(message_type, SigningCapability::Refused);
+            }
+        }
+    }
+
+    /// Returns the URL of the Web3Signer endpoint listing the keys it holds, or `None` for local
+    /// keystores.
+    pub fn web3signer_public_keys_url(&self) -> Option<Url> {
+        match self {
+            SigningMethod::LocalKeystore { .. } => None,
+            // The signing URL is `{base}/api/v1/eth2/sign/{pubkey}`.
+            SigningMethod::Web3Signer { signing_url, .. } => signing_url.join("../publicKeys").ok(),
+        }
+    }
+
+    /// Fetches the public keys held by the Web3Signer from `GET /api/v1/eth2/publicKeys`.
+    ///
+    /// Returns `None` for local keystores.
+    pub async fn web3signer_public_keys(&self) -> Result<Option<Vec<PublicKeyBytes>>, Error> {
+        let (SigningMethod::Web3Signer { http_client, .. }, Some(url)) =
+            (self, self.web3signer_public_keys_url())
+        else {
+            return Ok(None);
+        };
+
+        http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+            .json()
+            .await
+            .map(Some)
+            .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))
+    }
+
     /// Return the signature of `signable_message`, with respect to the `signing_context`.
     pub async fn get_signature<T: EthSpec, Payload: AbstractExecPayload<T>>(
         &self,
@@ -152,6 +264,9 @@ impl SigningMethod {
         executor: &TaskExecutor,
         fork_info: Option<ForkInfo>,
     ) -> Result<Signature, Error> {
+        // Complete the signature if a shutdown is requested whilst it is in progress.
+        let _in_flight = executor.graceful_shutdown().track();
+
         match self {
             SigningMethod::LocalKeystore { voting_keypair, .. } => {
                 let _timer =
@@ -233,17 +348,38 @@ impl SigningMethod {
                 };
 
                 // Request a signature from the Web3Signer instance via HTTP(S).
-                let response: SigningResponse = http_client
+                let response = http_client
                     .post(signing_url.clone())
                     .json(&request)
                     .send()
                     .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?;
+
+                // Slashing protection (412) and rate limiting refuse a single request rather than
+                // the message type.
+                let status = response.status();
+                if status.is_client_error()
+                    && !matches!(
+                        status,
+                        StatusCode::PRECONDITION_FAILED
+                            | StatusCode::REQUEST_TIMEOUT
+                            | StatusCode::TOO_MANY_REQUESTS
+                    )
+                {
+                    self.record_refusal(message_type);
+                    return Err(Error::Web3SignerRefused {
+                        message_type,
+                        status: status.as_u16(),
+                    });
+                }
+
+                let response: SigningResponse = response
                     .error_for_status()
                     .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
                     .json()
                     .await
                     .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+                self.set_capability(message_type, SigningCapability::Allowed);
 
                 Ok(response.signature)
             }
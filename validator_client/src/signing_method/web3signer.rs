@@ -4,7 +4,7 @@ use super::Error;
 use serde::{Deserialize, Serialize};
 use types::*;
 
-#[derive(Debug, PartialEq, Copy, Clone, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MessageType {
     AggregationSlot,
@@ -20,6 +20,41 @@ pub enum MessageType {
     ValidatorRegistration,
 }
 
+impl MessageType {
+    /// The message types signed by the validator client. Deposits are signed elsewhere.
+    pub const SIGNED_BY_VALIDATORS: [MessageType; 10] = [
+        MessageType::AggregationSlot,
+        MessageType::AggregateAndProof,
+        MessageType::Attestation,
+        MessageType::BlockV2,
+        MessageType::RandaoReveal,
+        MessageType::VoluntaryExit,
+        MessageType::SyncCommitteeMessage,
+        MessageType::SyncCommitteeSelectionProof,
+        MessageType::SyncCommitteeContributionAndProof,
+        MessageType::ValidatorRegistration,
+    ];
+
+    /// The name of the message type in Web3Signer requests.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageType::AggregationSlot => "AGGREGATION_SLOT",
+            MessageType::AggregateAndProof => "AGGREGATE_AND_PROOF",
+            MessageType::Attestation => "ATTESTATION",
+            MessageType::BlockV2 => "BLOCK_V2",
+            MessageType::Deposit => "DEPOSIT",
+            MessageType::RandaoReveal => "RANDAO_REVEAL",
+            MessageType::VoluntaryExit => "VOLUNTARY_EXIT",
+            MessageType::SyncCommitteeMessage => "SYNC_COMMITTEE_MESSAGE",
+            MessageType::SyncCommitteeSelectionProof => "SYNC_COMMITTEE_SELECTION_PROOF",
+            MessageType::SyncCommitteeContributionAndProof => {
+                "SYNC_COMMITTEE_CONTRIBUTION_AND_PROOF"
+            }
+            MessageType::ValidatorRegistration => "VALIDATOR_REGISTRATION",
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Copy, Clone, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ForkName {
@@ -0,0 +1,316 @@
+//! Determines when within each slot the validator client produces attestations and aggregates.
+//!
+//! By default attestations are produced 1/3 of the way through the slot and aggregates 2/3 of the
+//! way through, as per the specification. Both offsets may be overridden on the command line and
+//! updated at runtime via the HTTP API.
+
+use eth2::lighthouse_vc::types::SlotTimingOffsets;
+use parking_lot::RwLock;
+use slog::{info, warn, Logger};
+use slot_clock::SlotClock;
+use std::time::Duration;
+
+/// Offsets from the start of the slot at which attestation and aggregate production are triggered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotOffsets {
+    pub attestation: Duration,
+    pub aggregate: Duration,
+}
+
+impl SlotOffsets {
+    /// The offsets given by the specification.
+    pub fn default_for(slot_duration: Duration) -> Self {
+        Self {
+            attestation: slot_duration / 3,
+            aggregate: slot_duration * 2 / 3,
+        }
+    }
+
+    /// Returns `self` with each offset replaced by its override, if any.
+    fn with_overrides(self, attestation: Option<Duration>, aggregate: Option<Duration>) -> Self {
+        Self {
+            attestation: attestation.unwrap_or(self.attestation),
+            aggregate: aggregate.unwrap_or(self.aggregate),
+        }
+    }
+
+    /// The offset at which attestation selection proofs are signed, halfway between attestation
+    /// and aggregate production when nothing else is likely to be getting signed.
+    pub fn selection_proof(&self) -> Duration {
+        self.attestation + (self.aggregate - self.attestation) / 2
+    }
+
+    fn validate(&self, slot_duration: Duration) -> Result<(), String> {
+        if self.attestation >= self.aggregate {
+            return Err(format!(
+                "attestation offset ({}ms) must be earlier than the aggregate offset ({}ms)",
+                self.attestation.as_millis(),
+                self.aggregate.as_millis()
+            ));
+        }
+        if self.aggregate >= slot_duration {
+            return Err(format!(
+                "aggregate offset ({}ms) must be within the slot ({}ms)",
+                self.aggregate.as_millis(),
+                slot_duration.as_millis()
+            ));
+        }
+        Ok(())
+    }
+
+    fn warn_if_aggressive(&self, slot_duration: Duration, log: &Logger) {
+        if self.attestation < slot_duration / 4 {
+            warn!(
+                log,
+                "Aggressive attestation offset";
+                "msg" => "attestations may be produced before the block for the slot arrives",
+                "attestation_offset_ms" => self.attestation.as_millis(),
+            );
+        }
+        if self.aggregate - self.attestation < slot_duration / 4 {
+            warn!(
+                log,
+                "Aggressive aggregate offset";
+                "msg" => "aggregates may miss attestations which have not yet been published",
+                "attestation_offset_ms" => self.attestation.as_millis(),
+                "aggregate_offset_ms" => self.aggregate.as_millis(),
+            );
+        }
+        if self.aggregate > slot_duration * 5 / 6 {
+            warn!(
+                log,
+                "Aggressive aggregate offset";
+                "msg" => "aggregates may not propagate before the next block is produced",
+                "aggregate_offset_ms" => self.aggregate.as_millis(),
+            );
+        }
+    }
+}
+
+impl From<SlotOffsets> for SlotTimingOffsets {
+    fn from(offsets: SlotOffsets) -> Self {
+        Self {
+            attestation_offset_ms: offsets.attestation.as_millis() as u64,
+            aggregate_offset_ms: offsets.aggregate.as_millis() as u64,
+        }
+    }
+}
+
+/// Provides the current `SlotOffsets`, which may be updated at runtime.
+pub struct SlotTiming {
+    slot_duration: Duration,
+    offsets: RwLock<SlotOffsets>,
+    log: Logger,
+}
+
+impl SlotTiming {
+    /// Creates the timing from the offsets supplied on the command line, using the default for
+    /// any that are not supplied.
+    pub fn new(
+        slot_duration: Duration,
+        attestation_offset: Option<Duration>,
+        aggregate_offset: Option<Duration>,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let offsets = SlotOffsets::default_for(slot_duration)
+            .with_overrides(attestation_offset, aggregate_offset);
+        offsets.validate(slot_duration)?;
+        offsets.warn_if_aggressive(slot_duration, &log);
+
+        Ok(Self {
+            slot_duration,
+            offsets: RwLock::new(offsets),
+            log,
+        })
+    }
+
+    pub fn offsets(&self) -> SlotOffsets {
+        *self.offsets.read()
+    }
+
+    /// Updates the offsets, leaving any that are `None` unchanged.
+    ///
+    /// The update takes effect from the next slot.
+    pub fn update(
+        &self,
+        attestation_offset: Option<Duration>,
+        aggregate_offset: Option<Duration>,
+    ) -> Result<SlotOffsets, String> {
+        let mut offsets = self.offsets.write();
+        let new_offsets = offsets.with_overrides(attestation_offset, aggregate_offset);
+        new_offsets.validate(self.slot_duration)?;
+        new_offsets.warn_if_aggressive(self.slot_duration, &self.log);
+
+        info!(
+            self.log,
+            "Updated slot timing offsets";
+            "attestation_offset_ms" => new_offsets.attestation.as_millis(),
+            "aggregate_offset_ms" => new_offsets.aggregate.as_millis(),
+        );
+        *offsets = new_offsets;
+        Ok(new_offsets)
+    }
+
+    /// Returns the duration from now until attestation production in the next slot.
+    pub fn duration_to_next_attestation_production<S: SlotClock>(
+        &self,
+        slot_clock: &S,
+    ) -> Option<Duration> {
+        slot_clock
+            .duration_to_next_slot()
+            .map(|duration| duration + self.offsets().attestation)
+    }
+
+    /// Returns the duration from now until aggregate production in the current slot, or zero if
+    /// that time has already passed.
+    pub fn duration_to_aggregate_production<S: SlotClock>(
+        &self,
+        slot_clock: &S,
+    ) -> Option<Duration> {
+        self.duration_to_offset_in_current_slot(slot_clock, self.offsets().aggregate)
+    }
+
+    /// Returns the duration from now until selection proof signing in the current slot, or zero
+    /// if that time has already passed.
+    pub fn duration_to_selection_proof_signing<S: SlotClock>(
+        &self,
+        slot_clock: &S,
+    ) -> Option<Duration> {
+        self.duration_to_offset_in_current_slot(slot_clock, self.offsets().selection_proof())
+    }
+
+    fn duration_to_offset_in_current_slot<S: SlotClock>(
+        &self,
+        slot_clock: &S,
+        offset: Duration,
+    ) -> Option<Duration> {
+        slot_clock
+            .duration_to_next_slot()
+            .map(|duration| (duration + offset).saturating_sub(self.slot_duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+    use types::Slot;
+
+    const SLOT_DURATION: Duration = Duration::from_secs(12);
+
+    fn test_logger() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Returns a slot clock at `offset` into slot 1.
+    fn slot_clock_at(offset: Duration) -> ManualSlotClock {
+        let slot_clock = ManualSlotClock::new(Slot::new(0), Duration::from_secs(0), SLOT_DURATION);
+        slot_clock.set_current_time(SLOT_DURATION + offset);
+        slot_clock
+    }
+
+    #[test]
+    fn default_offsets() {
+        let timing = SlotTiming::new(SLOT_DURATION, None, None, test_logger()).unwrap();
+        assert_eq!(
+            timing.offsets(),
+            SlotOffsets {
+                attestation: ms(4000),
+                aggregate: ms(8000),
+            }
+        );
+        assert_eq!(timing.offsets().selection_proof(), ms(6000));
+
+        let slot_clock = slot_clock_at(ms(1000));
+        assert_eq!(
+            timing.duration_to_next_attestation_production(&slot_clock),
+            Some(ms(15000))
+        );
+        assert_eq!(
+            timing.duration_to_aggregate_production(&slot_clock),
+            Some(ms(7000))
+        );
+        assert_eq!(
+            timing.duration_to_selection_proof_signing(&slot_clock),
+            Some(ms(5000))
+        );
+    }
+
+    #[test]
+    fn configured_offsets() {
+        let timing =
+            SlotTiming::new(SLOT_DURATION, Some(ms(3500)), Some(ms(7500)), test_logger()).unwrap();
+
+        // Fired by the attestation service at the attestation offset, as in production.
+        let slot_clock = slot_clock_at(ms(3500));
+        assert_eq!(
+            timing.duration_to_next_attestation_production(&slot_clock),
+            Some(SLOT_DURATION)
+        );
+        assert_eq!(
+            timing.duration_to_aggregate_production(&slot_clock),
+            Some(ms(4000))
+        );
+        assert_eq!(
+            timing.duration_to_selection_proof_signing(&slot_clock),
+            Some(ms(2000))
+        );
+
+        // Instants which have already passed trigger immediately.
+        let slot_clock = slot_clock_at(ms(9000));
+        assert_eq!(
+            timing.duration_to_aggregate_production(&slot_clock),
+            Some(ms(0))
+        );
+        assert_eq!(
+            timing.duration_to_selection_proof_signing(&slot_clock),
+            Some(ms(0))
+        );
+    }
+
+    #[test]
+    fn runtime_update() {
+        let timing = SlotTiming::new(SLOT_DURATION, None, None, test_logger()).unwrap();
+        let slot_clock = slot_clock_at(ms(0));
+
+        let offsets = timing.update(Some(ms(3000)), None).unwrap();
+        assert_eq!(
+            offsets,
+            SlotOffsets {
+                attestation: ms(3000),
+                aggregate: ms(8000),
+            }
+        );
+        assert_eq!(
+            timing.duration_to_next_attestation_production(&slot_clock),
+            Some(ms(15000))
+        );
+
+        timing.update(None, Some(ms(9000))).unwrap();
+        assert_eq!(
+            timing.duration_to_aggregate_production(&slot_clock),
+            Some(ms(9000))
+        );
+    }
+
+    #[test]
+    fn invalid_offsets() {
+        // Attestation at or after aggregation.
+        SlotTiming::new(SLOT_DURATION, Some(ms(8000)), None, test_logger())
+            .map(|_| ())
+            .unwrap_err();
+        // Aggregation outside of the slot.
+        SlotTiming::new(SLOT_DURATION, None, Some(SLOT_DURATION), test_logger())
+            .map(|_| ())
+            .unwrap_err();
+
+        // A rejected update leaves the offsets unchanged.
+        let timing = SlotTiming::new(SLOT_DURATION, None, None, test_logger()).unwrap();
+        timing.update(Some(ms(5000)), Some(ms(4000))).unwrap_err();
+        assert_eq!(timing.offsets(), SlotOffsets::default_for(SLOT_DURATION));
+    }
+}
@@ -1,6 +1,8 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::DutiesService,
+    http_metrics::metrics,
+    signing_method::MessageType,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
@@ -158,7 +160,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 .checked_sub(slot_duration / 3)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
-        let slot_duties = if let Some(duties) = self
+        let mut slot_duties = if let Some(duties) = self
             .duties_service
             .sync_duties
             .get_duties_for_slot::<E>(slot, &self.duties_service.spec)
@@ -169,6 +171,19 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             return Ok(());
         };
 
+        // Skip validators whose signer refuses sync committee messages or contributions.
+        slot_duties.duties.retain(|duty| {
+            self.validator_store
+                .can_sign(&duty.pubkey, MessageType::SyncCommitteeMessage)
+        });
+        slot_duties.aggregators.retain(|_, aggregators| {
+            aggregators.retain(|(_, pubkey, _)| {
+                self.validator_store
+                    .can_sign(pubkey, MessageType::SyncCommitteeContributionAndProof)
+            });
+            !aggregators.is_empty()
+        });
+
         if slot_duties.duties.is_empty() {
             debug!(
                 log,
@@ -184,6 +199,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                metrics::HEAD_BLOCK_ROOT_HTTP_GET,
                 |beacon_node| async move {
                     match beacon_node.get_beacon_blocks_root(BlockId::Head).await {
                         Ok(Some(block)) if block.execution_optimistic == Some(false) => {
@@ -301,9 +317,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .collect::<Vec<_>>();
 
         self.beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::SyncCommittee,
+                metrics::SYNC_COMMITTEE_MESSAGES_HTTP_POST,
                 |beacon_node| async move {
                     beacon_node
                         .post_beacon_pool_sync_committee_signatures(committee_signatures)
@@ -375,6 +393,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .first_success(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                metrics::SYNC_CONTRIBUTION_HTTP_GET,
                 |beacon_node| async move {
                     let sync_contribution_data = SyncContributionData {
                         slot,
@@ -454,9 +473,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         // Publish to the beacon node.
         self.beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::SyncCommittee,
+                metrics::SYNC_CONTRIBUTIONS_HTTP_POST,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_contribution_and_proofs(signed_contributions)
@@ -552,7 +573,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 .get_duties_for_slot::<E>(duty_slot, spec)
             {
                 Some(duties) => subscriptions.extend(subscriptions_from_sync_duties(
-                    duties.duties,
+                    duties
+                        .duties
+                        .into_iter()
+                        .filter(|duty| {
+                            self.validator_store
+                                .can_sign(&duty.pubkey, MessageType::SyncCommitteeMessage)
+                        })
+                        .collect(),
                     sync_committee_period,
                     spec,
                 )),
@@ -596,9 +624,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         if let Err(e) = self
             .beacon_nodes
-            .run(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
+                metrics::SYNC_SUBSCRIPTIONS_HTTP_POST,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_sync_committee_subscriptions(subscriptions_slice)
@@ -2,14 +2,18 @@ use crate::{
     doppelganger_service::DoppelgangerService,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
-    signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
+    signing_method::{
+        Error as SigningError, MessageType, SignableMessage, SigningContext, SigningMethod,
+    },
     Config,
 };
 use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use eth2::lighthouse_vc::types::{SigningCapabilities, SigningCapability};
 use eth2::types::VariableList;
+use futures::future::join_all;
 use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
+    interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase, ValidatorSummary,
 };
 use slog::{crit, error, info, warn, Logger};
 use slot_clock::SlotClock;
@@ -21,10 +25,10 @@ use task_executor::TaskExecutor;
 use types::sidecar::Sidecar;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
-    AggregateAndProof, Attestation, BeaconBlock, BlindedPayload, ChainSpec, ContributionAndProof,
-    Domain, Epoch, EthSpec, Fork, ForkName, Graffiti, Hash256, Keypair, PublicKeyBytes,
-    SelectionProof, SidecarList, Signature, SignedAggregateAndProof, SignedBeaconBlock,
-    SignedContributionAndProof, SignedRoot, SignedSidecar, SignedSidecarList,
+    AggregateAndProof, AggregateSignature, Attestation, BeaconBlock, BitVector, BlindedPayload,
+    ChainSpec, ContributionAndProof, Domain, Epoch, EthSpec, Fork, ForkName, Graffiti, Hash256,
+    Keypair, PublicKeyBytes, SelectionProof, SidecarList, Signature, SignedAggregateAndProof,
+    SignedBeaconBlock, SignedContributionAndProof, SignedRoot, SignedSidecar, SignedSidecarList,
     SignedValidatorRegistrationData, SignedVoluntaryExit, Slot, SyncAggregatorSelectionData,
     SyncCommitteeContribution, SyncCommitteeMessage, SyncSelectionProof, SyncSubnetId,
     ValidatorRegistrationData, VoluntaryExit,
@@ -331,6 +335,138 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             })
     }
 
+    /// Returns `false` if `validator_pubkey` is unknown or its signer is known to refuse messages
+    /// of `message_type`.
+    ///
+    /// Optional duties which would require signing a refused message type should not be
+    /// scheduled. Blocks, attestations, aggregates and voluntary exits don't consult this and are
+    /// always attempted.
+    pub fn can_sign(&self, validator_pubkey: &PublicKeyBytes, message_type: MessageType) -> bool {
+        self.validators
+            .read()
+            .signing_method(validator_pubkey)
+            .map_or(false, |signing_method| {
+                signing_method.capability(message_type) != SigningCapability::Refused
+            })
+    }
+
+    /// Returns the signing capabilities of all enabled validators.
+    pub fn signing_capabilities(&self) -> Vec<SigningCapabilities> {
+        let validators = self.validators.read();
+        validators
+            .iter_voting_pubkeys()
+            .filter_map(|pubkey| {
+                let signing_method = validators.signing_method(pubkey)?;
+                Some(SigningCapabilities {
+                    voting_pubkey: *pubkey,
+                    remote_signer: matches!(*signing_method, SigningMethod::Web3Signer { .. }),
+                    capabilities: signing_method.capabilities(),
+                })
+            })
+            .collect()
+    }
+
+    /// Determines which message types the remote signer of `validator_pubkey` will sign, by
+    /// signing a message of each type that is neither slashable nor harmful if it were published.
+    ///
+    /// Blocks, attestations, aggregates and voluntary exits are never signed speculatively. Their
+    /// capability is learned from real signing requests, and any refusal is forgotten here so that
+    /// the next duty tries again.
+    ///
+    /// Has no effect on validators with local keystores.
+    pub async fn probe_signing_capabilities(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+    ) -> Result<(), Error> {
+        let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
+        if !matches!(*signing_method, SigningMethod::Web3Signer { .. }) {
+            return Ok(());
+        }
+
+        for message_type in [
+            MessageType::AggregateAndProof,
+            MessageType::Attestation,
+            MessageType::BlockV2,
+            MessageType::VoluntaryExit,
+        ] {
+            if signing_method.capability(message_type) == SigningCapability::Refused {
+                signing_method.set_capability(message_type, SigningCapability::Unknown);
+            }
+        }
+
+        let slot = self.slot_clock.now().unwrap_or_else(|| Slot::new(0));
+        let epoch = slot.epoch(E::slots_per_epoch());
+        let sync_selection_data = SyncAggregatorSelectionData {
+            slot,
+            subcommittee_index: 0,
+        };
+        let contribution_and_proof = ContributionAndProof {
+            aggregator_index: 0,
+            contribution: SyncCommitteeContribution {
+                slot,
+                beacon_block_root: Hash256::zero(),
+                subcommittee_index: 0,
+                aggregation_bits: BitVector::new(),
+                signature: AggregateSignature::empty(),
+            },
+            selection_proof: Signature::empty(),
+        };
+        let probes = [
+            (SignableMessage::RandaoReveal(epoch), Domain::Randao),
+            (
+                SignableMessage::SelectionProof(slot),
+                Domain::SelectionProof,
+            ),
+            (
+                SignableMessage::SyncCommitteeSignature {
+                    beacon_block_root: Hash256::zero(),
+                    slot,
+                },
+                Domain::SyncCommittee,
+            ),
+            (
+                SignableMessage::SyncSelectionProof(&sync_selection_data),
+                Domain::SyncCommitteeSelectionProof,
+            ),
+            (
+                SignableMessage::SignedContributionAndProof(&contribution_and_proof),
+                Domain::ContributionAndProof,
+            ),
+        ];
+
+        // The outcome of each probe is recorded by the signing method, so a message type is only
+        // refused once `REFUSALS_BEFORE_REFUSED` probes or real signing requests in a row have been
+        // refused.
+        for (message, domain) in probes {
+            let _ = signing_method
+                .get_signature::<E, BlindedPayload<E>>(
+                    message,
+                    self.signing_context(domain, epoch),
+                    &self.spec,
+                    &self.task_executor,
+                )
+                .await;
+        }
+
+        // A registration with a zero timestamp is never preferred over a real one.
+        let registration = ValidatorRegistrationData {
+            fee_recipient: Address::zero(),
+            gas_limit: 0,
+            timestamp: 0,
+            pubkey: validator_pubkey,
+        };
+        let _ = signing_method
+            .get_signature_from_root::<E, BlindedPayload<E>>(
+                SignableMessage::ValidatorRegistration(&registration),
+                registration.signing_root(self.spec.get_builder_domain()),
+                &self.task_executor,
+                None,
+            )
+            .await;
+
+        Ok(())
+    }
+
     pub fn num_voting_validators(&self) -> usize {
         self.validators.read().num_enabled()
     }
@@ -556,12 +692,25 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 Err(Error::Slashable(NotSafe::UnregisteredValidator(pk)))
             }
             Err(e) => {
+                let (attempt, recorded) = match &e {
+                    NotSafe::InvalidBlock { reason, attempt } => (Some(attempt), reason.recorded()),
+                    _ => (None, None),
+                };
                 crit!(
                     self.log,
                     "Not signing slashable block";
+                    "reason" => e.reason().as_str(),
+                    "slot" => attempt.map(|block| block.slot),
+                    "signing_root" => ?attempt.map(|block| block.signing_root),
+                    "recorded_slot" => recorded.map(|block| block.slot),
+                    "recorded_signing_root" => ?recorded.map(|block| block.signing_root),
                     "error" => format!("{:?}", e)
                 );
                 metrics::inc_counter_vec(&metrics::SIGNED_BLOCKS_TOTAL, &[metrics::SLASHABLE]);
+                metrics::inc_counter_vec(
+                    &metrics::SLASHING_PROTECTION_REFUSALS_TOTAL,
+                    &[metrics::BEACON_BLOCK, e.reason().as_str()],
+                );
                 Err(Error::Slashable(e))
             }
         }
@@ -607,6 +756,109 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         attestation: &mut Attestation<E>,
         current_epoch: Epoch,
     ) -> Result<(), Error> {
+        let signing_context = self.attestation_signing_context(attestation, current_epoch)?;
+
+        // Checking for slashing conditions.
+        let domain_hash = signing_context.domain_hash(&self.spec);
+        let slashing_status = self.slashing_protection.check_and_insert_attestation(
+            &validator_pubkey,
+            &attestation.data,
+            domain_hash,
+        );
+
+        self.sign_checked_attestation(
+            validator_pubkey,
+            validator_committee_position,
+            attestation,
+            signing_context,
+            slashing_status,
+        )
+        .await
+    }
+
+    /// Sign a batch of attestations, checking and recording all of them in the slashing
+    /// protection database using a single transaction.
+    ///
+    /// Each entry is a `(validator_pubkey, validator_committee_position, attestation)`, and the
+    /// outcome for each entry is returned in the same order. An unsafe entry does not prevent the
+    /// others from being signed. An error is only returned if the slashing protection transaction
+    /// fails, in which case none of the attestations are signed.
+    pub async fn sign_attestations(
+        &self,
+        attestations: Vec<(PublicKeyBytes, usize, Attestation<E>)>,
+        current_epoch: Epoch,
+    ) -> Result<Vec<Result<Attestation<E>, Error>>, Error> {
+        let signing_contexts = attestations
+            .iter()
+            .map(|(_, _, attestation)| self.attestation_signing_context(attestation, current_epoch))
+            .collect::<Vec<_>>();
+
+        // Checking for slashing conditions. Attestations which failed the checks above are
+        // excluded from the batch, and all others are recorded before any of them are signed.
+        let slashing_batch = attestations
+            .iter()
+            .zip(&signing_contexts)
+            .filter_map(|((validator_pubkey, _, attestation), signing_context)| {
+                let domain_hash = signing_context.as_ref().ok()?.domain_hash(&self.spec);
+                Some((*validator_pubkey, &attestation.data, domain_hash))
+            })
+            .collect::<Vec<_>>();
+        let mut slashing_statuses = self
+            .slashing_protection
+            .check_and_insert_attestations(&slashing_batch)
+            .map_err(|e| {
+                crit!(
+                    self.log,
+                    "Not signing attestations";
+                    "msg" => "unable to check slashing protection",
+                    "count" => attestations.len(),
+                    "error" => format!("{:?}", e)
+                );
+                metrics::inc_counter_vec_by(
+                    &metrics::SIGNED_ATTESTATIONS_TOTAL,
+                    &[metrics::SLASHABLE],
+                    attestations.len() as u64,
+                );
+                Error::Slashable(e)
+            })?
+            .into_iter();
+
+        let checked = signing_contexts
+            .into_iter()
+            .map(|signing_context| {
+                let signing_context = signing_context?;
+                let slashing_status = slashing_statuses
+                    .next()
+                    .ok_or(Error::Slashable(NotSafe::ConsistencyError))?;
+                Ok((signing_context, slashing_status))
+            })
+            .collect::<Vec<Result<_, Error>>>();
+
+        let signing_futures = attestations.into_iter().zip(checked).map(
+            |((validator_pubkey, committee_position, mut attestation), checked)| async move {
+                let (signing_context, slashing_status) = checked?;
+                self.sign_checked_attestation(
+                    validator_pubkey,
+                    committee_position,
+                    &mut attestation,
+                    signing_context,
+                    slashing_status,
+                )
+                .await?;
+                Ok::<_, Error>(attestation)
+            },
+        );
+
+        Ok(join_all(signing_futures).await)
+    }
+
+    /// Returns the signing context for `attestation`, provided its target epoch is not higher than
+    /// the current epoch.
+    fn attestation_signing_context(
+        &self,
+        attestation: &Attestation<E>,
+        current_epoch: Epoch,
+    ) -> Result<SigningContext, Error> {
         // Make sure the target epoch is not higher than the current epoch to avoid potential attacks.
         if attestation.data.target.epoch > current_epoch {
             return Err(Error::GreaterThanCurrentEpoch {
@@ -615,16 +867,20 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             });
         }
 
-        // Checking for slashing conditions.
         let signing_epoch = attestation.data.target.epoch;
-        let signing_context = self.signing_context(Domain::BeaconAttester, signing_epoch);
-        let domain_hash = signing_context.domain_hash(&self.spec);
-        let slashing_status = self.slashing_protection.check_and_insert_attestation(
-            &validator_pubkey,
-            &attestation.data,
-            domain_hash,
-        );
+        Ok(self.signing_context(Domain::BeaconAttester, signing_epoch))
+    }
 
+    /// Sign `attestation` if `slashing_status` indicates that it has been recorded in the slashing
+    /// protection database and is safe to sign.
+    async fn sign_checked_attestation(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        validator_committee_position: usize,
+        attestation: &mut Attestation<E>,
+        signing_context: SigningContext,
+        slashing_status: Result<Safe, NotSafe>,
+    ) -> Result<(), Error> {
         match slashing_status {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
@@ -670,16 +926,31 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
                 Err(Error::Slashable(NotSafe::UnregisteredValidator(pk)))
             }
             Err(e) => {
+                let (attempt, recorded) = match &e {
+                    NotSafe::InvalidAttestation { reason, attempt } => {
+                        (Some(attempt), reason.recorded())
+                    }
+                    _ => (None, None),
+                };
                 crit!(
                     self.log,
                     "Not signing slashable attestation";
+                    "reason" => e.reason().as_str(),
                     "attestation" => format!("{:?}", attestation.data),
+                    "signing_root" => ?attempt.map(|att| att.signing_root),
+                    "recorded_source" => recorded.map(|att| att.source_epoch),
+                    "recorded_target" => recorded.map(|att| att.target_epoch),
+                    "recorded_signing_root" => ?recorded.map(|att| att.signing_root),
                     "error" => format!("{:?}", e)
                 );
                 metrics::inc_counter_vec(
                     &metrics::SIGNED_ATTESTATIONS_TOTAL,
                     &[metrics::SLASHABLE],
                 );
+                metrics::inc_counter_vec(
+                    &metrics::SLASHING_PROTECTION_REFUSALS_TOTAL,
+                    &[metrics::ATTESTATIONS, e.reason().as_str()],
+                );
                 Err(Error::Slashable(e))
             }
         }
@@ -931,6 +1202,15 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(())
     }
 
+    /// Get the minimum and maximum slots and epochs signed by `pubkey`, including the low
+    /// watermarks that slashing protection enforces.
+    pub fn slashing_protection_summary(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<ValidatorSummary, NotSafe> {
+        self.slashing_protection.get_validator_summary(pubkey)
+    }
+
     /// Export slashing protection data while also disabling the given keys in the database.
     ///
     /// If any key is unknown to the slashing protection database it will be silently omitted
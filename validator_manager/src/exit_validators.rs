@@ -0,0 +1,651 @@
+use super::common::*;
+use crate::move_validators::Validators;
+use crate::DumpConfig;
+use account_utils::read_input_from_user;
+use clap::{App, Arg, ArgMatches};
+use eth2::{
+    lighthouse_vc::http_client::ValidatorClientHttpClient,
+    types::{StateId, ValidatorData, ValidatorId, ValidatorStatus},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::{Epoch, PublicKeyBytes};
+
+pub const CMD: &str = "exit";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const SUBMISSION_DELAY_FLAG: &str = "submission-delay";
+pub const POLL_INTERVAL_FLAG: &str = "poll-interval";
+pub const NO_WAIT_FLAG: &str = "no-wait";
+pub const NO_CONFIRMATION_FLAG: &str = "no-confirmation";
+pub const REPORT_FILE_FLAG: &str = "report-file";
+
+pub const CONFIRMATION_PHRASE: &str = "Exit my validators";
+
+const NO_VALIDATORS_MSG: &str = "No validators present on the validator client";
+
+const BEACON_NODE_HTTP_TIMEOUT: Duration = Duration::from_secs(12);
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Exits validators using the HTTP API of a validator client and a beacon node. \
+                The validator client signs a voluntary exit for each validator, which is then \
+                published to the beacon node. Validators which are already exiting are skipped, \
+                so the command can be run again if it is interrupted. By default the command \
+                waits until every validator has exited.",
+        )
+        .arg(
+            Arg::with_name(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a validator client using the keymanager-API. \
+                    This validator client signs the voluntary exits.",
+                )
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "A HTTP(S) address of a beacon node using the beacon-API. The voluntary \
+                    exits are published to this beacon node.",
+                )
+                .default_value("http://localhost:5052")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "The validators to be exited. Either a list of 0x-prefixed \
+                    validator pubkeys or the keyword \"all\".",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SUBMISSION_DELAY_FLAG)
+                .long(SUBMISSION_DELAY_FLAG)
+                .value_name("MILLISECONDS")
+                .help(
+                    "The time to wait between publishing voluntary exits, to avoid \
+                    overloading the validator client and beacon node with large batches.",
+                )
+                .default_value("100")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(POLL_INTERVAL_FLAG)
+                .long(POLL_INTERVAL_FLAG)
+                .value_name("SECONDS")
+                .help("The time to wait between checks of the status of exiting validators.")
+                .default_value("12")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NO_WAIT_FLAG)
+                .long(NO_WAIT_FLAG)
+                .help(
+                    "Exit after publishing the voluntary exits, without waiting for the \
+                    validators to exit.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(NO_CONFIRMATION_FLAG)
+                .long(NO_CONFIRMATION_FLAG)
+                .help(
+                    "Exits without prompting for confirmation that you understand the \
+                    implications of a voluntary exit. This should be used with caution.",
+                )
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name(REPORT_FILE_FLAG)
+                .long(REPORT_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, write a JSON report of the outcome for each validator to \
+                    this path. The file must not already exist.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .takes_value(false)
+                .hidden(cfg!(windows))
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExitConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub bn_url: SensitiveUrl,
+    pub validators: Validators,
+    pub submission_delay: Duration,
+    pub poll_interval: Duration,
+    pub no_wait: bool,
+    pub no_confirmation: bool,
+    pub stdin_inputs: bool,
+    pub report_path: Option<PathBuf>,
+}
+
+impl ExitConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = match matches.value_of(VALIDATORS_FLAG) {
+            Some("all") => Validators::All,
+            Some(pubkeys) => pubkeys
+                .split(',')
+                .map(PublicKeyBytes::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Validators::Specific)?,
+            None => return Err(format!("Must supply --{VALIDATORS_FLAG}.")),
+        };
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            bn_url: clap_utils::parse_required(matches, BEACON_NODE_FLAG)?,
+            validators,
+            submission_delay: Duration::from_millis(clap_utils::parse_required(
+                matches,
+                SUBMISSION_DELAY_FLAG,
+            )?),
+            poll_interval: Duration::from_secs(clap_utils::parse_required(
+                matches,
+                POLL_INTERVAL_FLAG,
+            )?),
+            no_wait: matches.is_present(NO_WAIT_FLAG),
+            no_confirmation: matches.is_present(NO_CONFIRMATION_FLAG),
+            stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
+            report_path: clap_utils::parse_optional(matches, REPORT_FILE_FLAG)?,
+        })
+    }
+}
+
+/// What happened to a validator when it was asked to exit.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitOutcome {
+    /// A voluntary exit was signed and published.
+    Submitted,
+    /// The validator had already initiated an exit, so it was skipped.
+    AlreadyExiting,
+    /// The validator could not be exited.
+    Failed { error: String },
+}
+
+/// The report for a single validator, written to the `--report-file`.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ValidatorExitReport {
+    pub pubkey: PublicKeyBytes,
+    pub index: Option<u64>,
+    pub outcome: ExitOutcome,
+    /// The last status of the validator reported by the beacon node.
+    pub status: Option<ValidatorStatus>,
+    pub exit_epoch: Option<Epoch>,
+}
+
+impl ValidatorExitReport {
+    /// Creates a report for a validator which has not yet been processed.
+    fn new(pubkey: PublicKeyBytes) -> Self {
+        Self {
+            pubkey,
+            index: None,
+            outcome: ExitOutcome::Failed {
+                error: "Not processed".to_string(),
+            },
+            status: None,
+            exit_epoch: None,
+        }
+    }
+
+    fn update(&mut self, data: &ValidatorData) {
+        self.index = Some(data.index);
+        self.status = Some(data.status);
+        if is_exiting(data.status) {
+            self.exit_epoch = Some(data.validator.exit_epoch);
+        }
+    }
+
+    fn has_exited(&self) -> bool {
+        self.status.map_or(false, |status| {
+            matches!(
+                status.superstatus(),
+                ValidatorStatus::Exited | ValidatorStatus::Withdrawal
+            )
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ExitReport {
+    pub validators: Vec<ValidatorExitReport>,
+}
+
+/// Returns `true` if a validator with `status` has initiated an exit, voluntarily or by being
+/// slashed.
+fn is_exiting(status: ValidatorStatus) -> bool {
+    !matches!(
+        status,
+        ValidatorStatus::PendingInitialized
+            | ValidatorStatus::PendingQueued
+            | ValidatorStatus::ActiveOngoing
+            | ValidatorStatus::Pending
+            | ValidatorStatus::Active
+    )
+}
+
+pub async fn cli_run<'a>(
+    matches: &'a ArgMatches<'a>,
+    dump_config: DumpConfig,
+) -> Result<(), String> {
+    let config = ExitConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: ExitConfig) -> Result<(), String> {
+    let ExitConfig {
+        vc_url,
+        vc_token_path,
+        bn_url,
+        validators,
+        submission_delay,
+        poll_interval,
+        no_wait,
+        no_confirmation,
+        stdin_inputs,
+        report_path,
+    } = config;
+
+    // Check the report can be written before exiting any validators.
+    if let Some(report_path) = &report_path {
+        if report_path.exists() {
+            return Err(format!("{:?} already exists", report_path));
+        }
+    }
+
+    let (vc_http_client, vc_keystores) = vc_http_client(vc_url.clone(), &vc_token_path).await?;
+    let bn_http_client =
+        BeaconNodeHttpClient::new(bn_url, Timeouts::set_all(BEACON_NODE_HTTP_TIMEOUT));
+
+    let pubkeys_to_exit = match validators {
+        Validators::All => vc_keystores.iter().map(|v| v.validating_pubkey).collect(),
+        Validators::Specific(request_pubkeys) => {
+            let vc_pubkeys: HashSet<_> = vc_keystores.iter().map(|v| v.validating_pubkey).collect();
+            let missing = request_pubkeys
+                .iter()
+                .filter(|pubkey| !vc_pubkeys.contains(pubkey))
+                .collect::<Vec<_>>();
+            if !missing.is_empty() {
+                for pubkey in &missing {
+                    eprintln!("{:?} is not present on {:?}", pubkey, vc_url);
+                }
+                return Err(format!(
+                    "{} validators not found on {:?}",
+                    missing.len(),
+                    vc_url
+                ));
+            }
+            request_pubkeys
+        }
+        Validators::Count(_) => {
+            return Err("Exiting a count of validators is not supported".to_string());
+        }
+    };
+
+    if pubkeys_to_exit.is_empty() {
+        return Err(NO_VALIDATORS_MSG.to_string());
+    }
+
+    if !no_confirmation {
+        eprintln!(
+            "You are about to exit {} validators. Exiting a validator is irreversible, and \
+            withdrawing its balance is only possible once it has exited and the validator \
+            has withdrawal credentials for an execution address.",
+            pubkeys_to_exit.len()
+        );
+        eprintln!(
+            "Enter the exit phrase from above to confirm the voluntary exits: \n\
+            {}\n",
+            CONFIRMATION_PHRASE
+        );
+        let confirmation = read_input_from_user(stdin_inputs)?;
+        if confirmation != CONFIRMATION_PHRASE {
+            return Err("Incorrect exit phrase, no validators were exited.".to_string());
+        }
+    }
+
+    let count = pubkeys_to_exit.len();
+    let mut reports = Vec::with_capacity(count);
+    for (i, pubkey) in pubkeys_to_exit.into_iter().enumerate() {
+        let mut report = ValidatorExitReport::new(pubkey);
+        report.outcome = match exit_validator(&vc_http_client, &bn_http_client, &mut report).await {
+            Ok(outcome) => outcome,
+            Err(error) => ExitOutcome::Failed { error },
+        };
+        match &report.outcome {
+            ExitOutcome::Submitted => {
+                eprintln!("Published voluntary exit {} of {}", i + 1, count)
+            }
+            ExitOutcome::AlreadyExiting => eprintln!(
+                "Skipped validator {} of {} which is already exiting",
+                i + 1,
+                count
+            ),
+            ExitOutcome::Failed { error } => {
+                eprintln!("Failed to exit validator {} of {}: {}", i + 1, count, error)
+            }
+        }
+        let submitted = report.outcome == ExitOutcome::Submitted;
+        reports.push(report);
+
+        // Only voluntary exits which were published count towards the rate limit.
+        if submitted && i + 1 < count {
+            sleep(submission_delay).await;
+        }
+    }
+
+    if !no_wait {
+        wait_for_exits(&bn_http_client, &mut reports, poll_interval).await;
+    }
+
+    print_summary(&reports);
+
+    let failed = reports
+        .iter()
+        .filter(|report| matches!(report.outcome, ExitOutcome::Failed { .. }))
+        .count();
+
+    if let Some(report_path) = report_path {
+        write_to_json_file(
+            report_path,
+            &ExitReport {
+                validators: reports,
+            },
+        )?;
+    }
+
+    if failed > 0 {
+        Err(format!("Failed to exit {} of {} validators", failed, count))
+    } else {
+        eprintln!("Done.");
+        Ok(())
+    }
+}
+
+/// Fetches the state of a validator at the head of the beacon node.
+async fn get_validator(
+    bn_http_client: &BeaconNodeHttpClient,
+    pubkey: &PublicKeyBytes,
+) -> Result<ValidatorData, String> {
+    bn_http_client
+        .get_beacon_states_validator_id(StateId::Head, &ValidatorId::PublicKey(*pubkey))
+        .await
+        .map_err(|e| format!("Failed to get validator from beacon node: {:?}", e))?
+        .map(|response| response.data)
+        .ok_or_else(|| "Validator is unknown to the beacon node".to_string())
+}
+
+/// Signs a voluntary exit for the validator with the validator client and publishes it to the
+/// beacon node, unless the validator is already exiting.
+async fn exit_validator(
+    vc_http_client: &ValidatorClientHttpClient,
+    bn_http_client: &BeaconNodeHttpClient,
+    report: &mut ValidatorExitReport,
+) -> Result<ExitOutcome, String> {
+    let validator = get_validator(bn_http_client, &report.pubkey).await?;
+    report.update(&validator);
+    if is_exiting(validator.status) {
+        return Ok(ExitOutcome::AlreadyExiting);
+    }
+
+    // The validator client only signs the exit, it does not publish it.
+    let signed_voluntary_exit = vc_http_client
+        .post_validator_voluntary_exit(&report.pubkey, None)
+        .await
+        .map_err(|e| format!("Failed to sign voluntary exit: {:?}", e))?
+        .data;
+    bn_http_client
+        .post_beacon_pool_voluntary_exits(&signed_voluntary_exit)
+        .await
+        .map_err(|e| format!("Failed to publish voluntary exit: {:?}", e))?;
+
+    Ok(ExitOutcome::Submitted)
+}
+
+/// Polls the beacon node until every validator which is exiting has exited.
+async fn wait_for_exits(
+    bn_http_client: &BeaconNodeHttpClient,
+    reports: &mut [ValidatorExitReport],
+    poll_interval: Duration,
+) {
+    loop {
+        let mut remaining = 0;
+        for report in reports.iter_mut() {
+            if matches!(report.outcome, ExitOutcome::Failed { .. }) || report.has_exited() {
+                continue;
+            }
+            match get_validator(bn_http_client, &report.pubkey).await {
+                Ok(validator) => report.update(&validator),
+                Err(e) => eprintln!("Failed to check validator {:?}: {}", report.pubkey, e),
+            }
+            if !report.has_exited() {
+                remaining += 1;
+            }
+        }
+
+        if remaining == 0 {
+            break;
+        }
+        eprintln!(
+            "Waiting for {} validators to exit. It is safe to stop waiting, running this \
+            command again will not publish duplicate exits.",
+            remaining
+        );
+        sleep(poll_interval).await;
+    }
+}
+
+fn print_summary(reports: &[ValidatorExitReport]) {
+    eprintln!(
+        "{:<98} {:>8} {:<16} {:<20} {:>10}",
+        "pubkey", "index", "outcome", "status", "exit_epoch"
+    );
+    for report in reports {
+        let outcome = match &report.outcome {
+            ExitOutcome::Submitted => "submitted",
+            ExitOutcome::AlreadyExiting => "already_exiting",
+            ExitOutcome::Failed { .. } => "failed",
+        };
+        eprintln!(
+            "{:<98} {:>8} {:<16} {:<20} {:>10}",
+            report.pubkey.as_hex_string(),
+            report
+                .index
+                .map_or("-".to_string(), |index| index.to_string()),
+            outcome,
+            report
+                .status
+                .map_or("-".to_string(), |status| status.to_string()),
+            report
+                .exit_epoch
+                .map_or("-".to_string(), |epoch| epoch.to_string()),
+        );
+    }
+}
+
+// The tests use crypto and are too slow in debug.
+#[cfg(not(debug_assertions))]
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth2::lighthouse_vc::std_types::{
+        ImportKeystoreStatus, ImportKeystoresRequest, KeystoreJsonStr,
+    };
+    use eth2_keystore::KeystoreBuilder;
+    use http_api::test_utils::InteractiveTester;
+    use std::fs;
+    use tempfile::tempdir;
+    use types::{EthSpec, MainnetEthSpec};
+    use validator_client::http_api::test_utils::ApiTester;
+
+    type E = MainnetEthSpec;
+
+    const VALIDATOR_COUNT: usize = 32;
+    const VC_TOKEN_FILE_NAME: &str = "vc_token.json";
+    const REPORT_FILE_NAME: &str = "report.json";
+    const PASSWORD: &str = "password";
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn exit_batch_with_one_already_exiting() {
+        let mut spec = E::default_spec();
+        // Allow validators to exit without building out a long chain.
+        spec.shard_committee_period = 2;
+        let bn = InteractiveTester::<E>::new(Some(spec), VALIDATOR_COUNT).await;
+        let harness = &bn.harness;
+        harness
+            .extend_slots(E::slots_per_epoch() as usize * 2)
+            .await;
+
+        // Validator 0 starts exiting before the batch is submitted.
+        let exit = harness.make_voluntary_exit(0, harness.get_current_state().current_epoch());
+        bn.client
+            .post_beacon_pool_voluntary_exits(&exit)
+            .await
+            .unwrap();
+        harness.extend_slots(1).await;
+
+        // Import the validators into a VC which signs for the harness chain.
+        let vc = ApiTester::new_with_genesis_validators_root(
+            ApiTester::default_http_config(),
+            harness.chain.genesis_validators_root,
+        )
+        .await;
+        let indices = [0, 1, 2];
+        let keystores = indices
+            .iter()
+            .map(|&index| {
+                KeystoreBuilder::new(
+                    &harness.validator_keypairs[index],
+                    PASSWORD.as_bytes(),
+                    "".into(),
+                )
+                .unwrap()
+                .build()
+                .map(KeystoreJsonStr)
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let statuses = vc
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores,
+                passwords: vec![PASSWORD.to_string().into(); indices.len()],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap()
+            .data;
+        assert!(statuses
+            .iter()
+            .all(|status| status.status == ImportKeystoreStatus::Imported));
+        // The test VC has no beacon node to learn the validator indices from.
+        let pubkeys = indices
+            .iter()
+            .map(|&index| harness.validator_keypairs[index].pk.compress())
+            .collect::<Vec<_>>();
+        for (&index, pubkey) in indices.iter().zip(&pubkeys) {
+            vc.initialized_validators
+                .write()
+                .set_index(pubkey, index as u64);
+        }
+
+        let dir = tempdir().unwrap();
+        let vc_token_path = dir.path().join(VC_TOKEN_FILE_NAME);
+        fs::write(&vc_token_path, &vc.api_token).unwrap();
+        let report_path = dir.path().join(REPORT_FILE_NAME);
+        let config = ExitConfig {
+            vc_url: vc.url.clone(),
+            vc_token_path,
+            // The redacted URL of the test server is its full URL.
+            bn_url: SensitiveUrl::parse(bn.client.as_ref()).unwrap(),
+            validators: Validators::Specific(pubkeys.clone()),
+            submission_delay: Duration::from_millis(10),
+            poll_interval: Duration::from_millis(100),
+            no_wait: false,
+            no_confirmation: true,
+            stdin_inputs: false,
+            report_path: Some(report_path.clone()),
+        };
+
+        // Keep producing blocks until the exits have been processed.
+        let drive_chain = async {
+            loop {
+                harness.extend_slots(1).await;
+            }
+        };
+        let result = tokio::select! {
+            result = run(config) => result,
+            _ = drive_chain => unreachable!(),
+        };
+        assert_eq!(result, Ok(()));
+
+        let report: ExitReport =
+            serde_json::from_reader(fs::File::open(report_path).unwrap()).unwrap();
+        let outcomes = report
+            .validators
+            .iter()
+            .map(|report| (report.pubkey, report.outcome.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                (pubkeys[0], ExitOutcome::AlreadyExiting),
+                (pubkeys[1], ExitOutcome::Submitted),
+                (pubkeys[2], ExitOutcome::Submitted),
+            ]
+        );
+        for (validator_report, &index) in report.validators.iter().zip(&indices) {
+            assert_eq!(validator_report.index, Some(index as u64));
+            assert_eq!(
+                validator_report.status,
+                Some(ValidatorStatus::ExitedUnslashed)
+            );
+            let validator = get_validator(&bn.client, &validator_report.pubkey)
+                .await
+                .unwrap();
+            assert_eq!(
+                validator_report.exit_epoch,
+                Some(validator.validator.exit_epoch)
+            );
+        }
+    }
+}
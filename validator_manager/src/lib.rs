@@ -8,6 +8,7 @@ use types::EthSpec;
 
 pub mod common;
 pub mod create_validators;
+pub mod exit_validators;
 pub mod import_validators;
 pub mod move_validators;
 
@@ -45,6 +46,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(create_validators::cli_app())
         .subcommand(import_validators::cli_app())
         .subcommand(move_validators::cli_app())
+        .subcommand(exit_validators::cli_app())
 }
 
 /// Run the account manager, returning an error if the operation did not succeed.
@@ -72,6 +74,9 @@ pub fn run<'a, T: EthSpec>(matches: &'a ArgMatches<'a>, env: Environment<T>) ->
                     (move_validators::CMD, Some(matches)) => {
                         move_validators::cli_run(matches, dump_config).await
                     }
+                    (exit_validators::CMD, Some(matches)) => {
+                        exit_validators::cli_run(matches, dump_config).await
+                    }
                     ("", _) => Err("No command supplied. See --help.".to_string()),
                     (unknown, _) => Err(format!(
                         "{} is not a valid {} command. See --help.",
@@ -4,6 +4,7 @@ use account_utils::{read_password_from_user, ZeroizeString};
 use clap::{App, Arg, ArgMatches};
 use eth2::{
     lighthouse_vc::{
+        http_client::ValidatorClientHttpClient,
         std_types::{
             DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus, InterchangeJsonStr,
             Status,
@@ -32,6 +33,8 @@ pub const VALIDATORS_FLAG: &str = "validators";
 pub const GAS_LIMIT_FLAG: &str = "gas-limit";
 pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
 pub const BUILDER_PROPOSALS_FLAG: &str = "builder-proposals";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+pub const STDIN_PASSWORDS_ALIAS: &str = "stdin-passwords";
 
 const NO_VALIDATORS_MSG: &str = "No validators present on source validator client";
 
@@ -168,7 +171,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(false)
                 .hidden(cfg!(windows))
                 .long(STDIN_INPUTS_FLAG)
-                .help("If present, read all user inputs from stdin instead of tty."),
+                .visible_alias(STDIN_PASSWORDS_ALIAS)
+                .help(
+                    "If present, read all user inputs from stdin instead of tty. This \
+                    includes the passwords of keystores which the source validator client \
+                    does not know.",
+                ),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "Print the validators that would be moved and any conflicts with the \
+                    destination validator client, without changing either validator client.",
+                )
+                .takes_value(false),
         )
 }
 
@@ -190,6 +207,7 @@ pub struct MoveConfig {
     pub fee_recipient: Option<Address>,
     pub gas_limit: Option<u64>,
     pub password_source: PasswordSource,
+    pub dry_run: bool,
 }
 
 impl MoveConfig {
@@ -226,6 +244,7 @@ impl MoveConfig {
             password_source: PasswordSource::Interactive {
                 stdin_inputs: cfg!(windows) || matches.is_present(STDIN_INPUTS_FLAG),
             },
+            dry_run: matches.is_present(DRY_RUN_FLAG),
         })
     }
 }
@@ -253,6 +272,7 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
         fee_recipient,
         gas_limit,
         mut password_source,
+        dry_run,
     } = config;
 
     // Moving validators between the same VC is unlikely to be useful and probably indicates a user
@@ -266,7 +286,7 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
 
     let (src_http_client, src_keystores) =
         vc_http_client(src_vc_url.clone(), &src_vc_token_path).await?;
-    let (dest_http_client, _dest_keystores) =
+    let (dest_http_client, dest_keystores) =
         vc_http_client(dest_vc_url.clone(), &dest_vc_token_path).await?;
 
     if src_keystores.is_empty() {
@@ -319,6 +339,60 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
         .iter()
         .map(|k| (k.validating_pubkey, k))
         .collect();
+    let dest_keystores_map: HashMap<_, _> = dest_keystores
+        .iter()
+        .map(|k| (k.validating_pubkey, k))
+        .collect();
+
+    // Check every validator against the destination before touching either VC. A validator that
+    // the destination signs for remotely can't be imported there, so moving it would only delete
+    // it from the source.
+    let mut conflicts = 0;
+    for pubkey in &pubkeys_to_move {
+        let src_readonly = src_keystores_map
+            .get(pubkey)
+            .ok_or("Inconsistent src keystore map")?
+            .readonly
+            .unwrap_or(true);
+        let dest_readonly = dest_keystores_map.get(pubkey).map(|k| k.readonly);
+        match (src_readonly, dest_readonly) {
+            (true, _) => {
+                if dry_run {
+                    eprintln!("{:?}: skip, read-only on the source", pubkey);
+                }
+            }
+            (false, Some(readonly)) if readonly.unwrap_or(true) => {
+                eprintln!(
+                    "{:?}: conflict, read-only (e.g., a remote signer) on the destination",
+                    pubkey
+                );
+                conflicts += 1;
+            }
+            (false, Some(_)) => {
+                if dry_run {
+                    eprintln!(
+                        "{:?}: already present on the destination, remove from the source",
+                        pubkey
+                    );
+                }
+            }
+            (false, None) => {
+                if dry_run {
+                    eprintln!("{:?}: move", pubkey);
+                }
+            }
+        }
+    }
+    if conflicts > 0 {
+        return Err(format!(
+            "{} validators conflict with read-only validators on {:?}",
+            conflicts, dest_vc_url
+        ));
+    }
+    if dry_run {
+        eprintln!("Dry run, no validators were moved.");
+        return Ok(());
+    }
 
     let count = pubkeys_to_move.len();
     for (i, &pubkey_to_move) in pubkeys_to_move.iter().enumerate() {
@@ -331,6 +405,7 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
             .unwrap_or(true)
         {
             eprintln!("Skipping read-only validator {:?}", pubkey_to_move);
+            continue;
         }
 
         let request = DeleteKeystoresRequest {
@@ -500,6 +575,14 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
             // non-standard API.
             enabled: None,
         };
+        // If the destination refuses the validator it is restored on the source, without the
+        // settings intended for the destination.
+        let restore_specification = ValidatorSpecification {
+            fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            ..validator_specification.clone()
+        };
 
         // We might as well just ignore validators that already exist on the destination machine,
         // there doesn't appear to be much harm just adding them again and removing them from the
@@ -529,8 +612,12 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                                 count,
                                 status.message,
                             );
-                            // Retry uploading this validator.
-                            sleep_with_retry_message(
+                            // The destination did not import the validator, so it is not active
+                            // anywhere. Put it back on the source rather than retrying an import
+                            // which is likely to keep failing.
+                            return restore_on_source(
+                                &src_http_client,
+                                restore_specification,
                                 &pubkey_to_move,
                                 keystore_derivation_path.as_deref(),
                             )
@@ -558,6 +645,8 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                         .await;
                 }
                 Err(UploadError::KeyUploadFailed(e)) => {
+                    // It's unknown whether the destination imported the validator, so it can't be
+                    // restored on the source without risking it being active on both.
                     eprintln!(
                         "Failed to upload keystore. Some keys may have been moved whilst \
                         others may not. Error was {:?}",
@@ -609,6 +698,41 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
     Ok(())
 }
 
+/// Imports a validator which was deleted from the source VC back into it, after the destination
+/// VC refused to import it. Always returns an error since the validator was not moved.
+async fn restore_on_source(
+    src_http_client: &ValidatorClientHttpClient,
+    validator_specification: ValidatorSpecification,
+    pubkey: &PublicKeyBytes,
+    path: Option<&str>,
+) -> Result<(), String> {
+    let error = match validator_specification.upload(src_http_client, true).await {
+        Ok(Status {
+            status: ImportKeystoreStatus::Imported | ImportKeystoreStatus::Duplicate,
+            ..
+        }) => {
+            eprintln!(
+                "Validator {:?} was restored on the source validator client",
+                pubkey
+            );
+            return Err(format!(
+                "Destination validator client failed to import {:?}, it was restored on the source",
+                pubkey
+            ));
+        }
+        Ok(Status { status, message }) => format!("{:?} with message {:?}", status, message),
+        Err(e) => format!("{:?}", e),
+    };
+    eprintln!(
+        "Validator {:?} could not be restored on the source validator client and is not active \
+        on either validator client. The keystore needs to be restored from a backup or mnemonic. \
+        The keystore has a derivation path of {}",
+        pubkey,
+        path.unwrap_or("<unspecified>")
+    );
+    Err(format!("Restoring {:?} failed with {}", pubkey, error))
+}
+
 async fn sleep_with_retry_message(pubkey: &PublicKeyBytes, path: Option<&str>) {
     let path = path.unwrap_or("<unspecified>");
     eprintln!(
@@ -628,6 +752,7 @@ mod test {
     use super::*;
     use crate::import_validators::tests::TestBuilder as ImportTestBuilder;
     use account_utils::validator_definitions::SigningDefinition;
+    use eth2::lighthouse_vc::types::Web3SignerValidatorRequest;
     use std::fs;
     use tempfile::{tempdir, TempDir};
     use validator_client::http_api::{test_utils::ApiTester, Config as HttpConfig};
@@ -649,6 +774,8 @@ mod test {
         passwords: HashMap<PublicKeyBytes, Vec<String>>,
         use_password_files: bool,
         reuse_password_files: Option<usize>,
+        dry_run: bool,
+        dest_remote_signers: Option<bool>,
     }
 
     impl TestBuilder {
@@ -666,9 +793,22 @@ mod test {
                 passwords: <_>::default(),
                 use_password_files: false,
                 reuse_password_files: None,
+                dry_run: false,
+                dest_remote_signers: None,
             }
         }
 
+        fn dry_run(mut self) -> Self {
+            self.dry_run = true;
+            self
+        }
+
+        /// Adds a remote signer validator to the destination for each validator on the source.
+        fn with_dest_remote_signers(mut self, enabled: bool) -> Self {
+            self.dest_remote_signers = Some(enabled);
+            self
+        }
+
         fn move_back_again(mut self) -> Self {
             self.move_back_again = true;
             self
@@ -761,11 +901,27 @@ mod test {
                 fee_recipient: None,
                 gas_limit: None,
                 password_source: PasswordSource::Testing(self.passwords.clone()),
+                dry_run: self.dry_run,
             };
 
             let result = run(move_config).await;
 
-            if result.is_ok() {
+            // A dry run or a failed move should leave the validators where they were.
+            if result.is_err() || self.dry_run {
+                let src_vc_final_keystores = src_vc_client.get_keystores().await.unwrap().data;
+                let dest_vc_final_keystores = dest_vc_client.get_keystores().await.unwrap().data;
+
+                assert_eq!(
+                    src_vc_final_keystores.iter().collect::<HashSet<_>>(),
+                    src_vc_initial_keystores.iter().collect::<HashSet<_>>(),
+                    "the source keystores should be unchanged"
+                );
+                assert_eq!(
+                    dest_vc_final_keystores.iter().collect::<HashSet<_>>(),
+                    dest_vc_initial_keystores.iter().collect::<HashSet<_>>(),
+                    "the destination keystores should be unchanged"
+                );
+            } else {
                 let src_vc_final_keystores = src_vc_client.get_keystores().await.unwrap().data;
                 let dest_vc_final_keystores = dest_vc_client.get_keystores().await.unwrap().data;
 
@@ -956,6 +1112,33 @@ mod test {
                 ApiTester::new_with_http_config(self.http_config.clone()).await
             };
 
+            if let Some(enable) = self.dest_remote_signers {
+                let src_keystores = src_vc.client.get_keystores().await.unwrap().data;
+                let request: Vec<_> = src_keystores
+                    .iter()
+                    .enumerate()
+                    .map(|(i, keystore)| Web3SignerValidatorRequest {
+                        enable,
+                        description: format!("{}", i),
+                        graffiti: None,
+                        suggested_fee_recipient: None,
+                        gas_limit: None,
+                        builder_proposals: None,
+                        voting_public_key: keystore.validating_pubkey.decompress().unwrap(),
+                        url: format!("http://signer_{}.com/", i),
+                        root_certificate_path: None,
+                        request_timeout_ms: None,
+                        client_identity_path: None,
+                        client_identity_password: None,
+                    })
+                    .collect();
+                dest_vc
+                    .client
+                    .post_lighthouse_validators_web3signer(&request)
+                    .await
+                    .unwrap();
+            }
+
             if self.remove_passwords_from_src_vc {
                 let passwords = src_vc
                     .initialized_validators
@@ -1250,4 +1433,57 @@ mod test {
             .await
             .assert_ok();
     }
+
+    #[tokio::test]
+    async fn two_validators_dry_run() {
+        TestBuilder::new()
+            .await
+            .with_src_validators(2, 0)
+            .await
+            .with_dest_validators(1, 1)
+            .await
+            .dry_run()
+            .run_test(|_| Validators::All)
+            .await
+            .assert_ok();
+    }
+
+    /// The destination refuses to import a keystore for a validator it has as a (disabled) remote
+    /// signer, so the validator should be restored on the source.
+    #[tokio::test]
+    async fn one_validator_dest_import_failure_is_rolled_back() {
+        TestBuilder::new()
+            .await
+            .with_src_validators(1, 0)
+            .await
+            .with_dest_remote_signers(false)
+            .run_test(|_| Validators::All)
+            .await
+            .assert_err();
+    }
+
+    #[tokio::test]
+    async fn two_validators_conflict_with_dest_remote_signers() {
+        TestBuilder::new()
+            .await
+            .with_src_validators(2, 0)
+            .await
+            .with_dest_remote_signers(true)
+            .run_test(|_| Validators::All)
+            .await
+            .assert_err();
+    }
+
+    #[tokio::test]
+    async fn two_validators_conflict_with_dest_remote_signers_dry_run() {
+        TestBuilder::new()
+            .await
+            .with_src_validators(2, 0)
+            .await
+            .with_dest_remote_signers(true)
+            .dry_run()
+            .run_test(|_| Validators::All)
+            .await
+            .assert_err();
+    }
 }